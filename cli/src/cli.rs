@@ -296,18 +296,35 @@ impl KaspaCli {
                                 Events::UtxoProcError { message } => {
                                     terrorln!(this,"{message}");
                                 },
+                                Events::ResyncRequired => {
+                                    tprintln!(this, "The node UTXO set has been overridden, resyncing accounts...");
+                                },
+                                Events::ResyncComplete => {
+                                    tprintln!(this, "Accounts resynced with the node UTXO set");
+                                },
                                 #[allow(unused_variables)]
-                                Events::Connect{ url, network_id } => {
+                                Events::Connect{ url, network_id, .. } => {
                                     // log_info!("Connected to {url}");
                                 },
                                 #[allow(unused_variables)]
-                                Events::Disconnect{ url, network_id } => {
+                                Events::Disconnect{ url, network_id, .. } => {
                                     tprintln!(this, "Disconnected from {}",url.unwrap_or("N/A".to_string()));
                                     this.term().refresh_prompt();
                                 },
+                                Events::NodeEndpoint { name, url, failover, .. } => {
+                                    if failover {
+                                        tprintln!(this, "Node endpoint unreachable, failing over to '{name}' at {url}");
+                                    } else {
+                                        tprintln!(this, "Using node endpoint '{name}' at {url}");
+                                    }
+                                    this.term().refresh_prompt();
+                                },
                                 Events::UtxoIndexNotEnabled { .. } => {
                                     tprintln!(this, "Error: Kaspa node UTXO index is not enabled...")
                                 },
+                                Events::ParamsDigestMismatch { network_id, .. } => {
+                                    tprintln!(this, "Error: Kaspa node consensus params do not match the {network_id} network...")
+                                },
                                 Events::SyncState { sync_state } => {
 
                                     if sync_state.is_synced() && this.wallet().is_open() {
@@ -373,6 +390,10 @@ impl KaspaCli {
                                 },
                                 Events::AccountCreate { .. } => { },
                                 Events::AccountUpdate { .. } => { },
+                                Events::AccountDiscovery { account_index, is_active } => {
+                                    let status = if is_active { "activity found" } else { "no activity" };
+                                    tprintln!(this, "{NOTIFY} account #{account_index}: {status}");
+                                },
                                 Events::DaaScoreChange { current_daa_score } => {
                                     if this.is_mutted() && this.flags.get(Track::Daa) {
                                         tprintln!(this, "{NOTIFY} DAA: {current_daa_score}");
@@ -414,8 +435,17 @@ impl KaspaCli {
                                         let include_utxos = this.flags.get(Track::Utxo);
                                         let tx = record.format_transaction_with_state(&this.wallet,Some("pending"),include_utxos).await;
                                         tx.iter().for_each(|line|tprintln!(this,"{NOTIFY} {line}"));
+                                        if let Some(estimate) = record.confirmation_estimate() {
+                                            let min = estimate.min_time as f64 / 1000.0;
+                                            let max = estimate.max_time as f64 / 1000.0;
+                                            tprintln!(this,"{NOTIFY} {:>4}likely to confirm in {min:.1}-{max:.1} seconds", "");
+                                        }
                                     }
                                 },
+                                Events::TransactionConflict { transaction_id, conflicting_transaction_id, is_conflicting_in_block, .. } => {
+                                    let location = if is_conflicting_in_block { "a block" } else { "the mempool" };
+                                    twarnln!(this, "Transaction {transaction_id} is double spent by transaction {conflicting_transaction_id} in {location}");
+                                },
                                 Events::Maturity {
                                     record
                                 } => {
@@ -642,6 +672,43 @@ impl KaspaCli {
         Ok(prv_key_data_info)
     }
 
+    /// Prompts for a wallet to open if several are available, listing the most recently opened first.
+    /// Returns `None` if there are no wallets.
+    pub async fn select_wallet(&self) -> Result<Option<String>> {
+        let mut wallets = self.store().wallet_list().await?;
+        if wallets.len() <= 1 {
+            return Ok(wallets.pop().map(|wallet| wallet.filename));
+        }
+
+        let mut selection = None;
+        while selection.is_none() {
+            tprintln!(self);
+
+            wallets.iter().enumerate().for_each(|(seq, wallet)| {
+                tprintln!(self, "    {seq}: {wallet}");
+            });
+
+            tprintln!(self);
+
+            let text = self
+                .term()
+                .ask(false, &format!("Please select wallet [{}..{}] or <enter> to abort: ", 0, wallets.len() - 1))
+                .await?
+                .trim()
+                .to_string();
+            if text.is_empty() {
+                return Err(Error::UserAbort);
+            } else {
+                match text.parse::<usize>() {
+                    Ok(seq) if seq < wallets.len() => selection = wallets.get(seq).map(|wallet| wallet.filename.clone()),
+                    _ => {}
+                };
+            }
+        }
+
+        Ok(selection)
+    }
+
     pub async fn list(&self) -> Result<()> {
         let mut keys = self.wallet.keys().await?;
 
@@ -111,6 +111,9 @@ pub enum Error {
     #[error(transparent)]
     NetworkId(#[from] kaspa_consensus_core::network::NetworkIdError),
 
+    #[error(transparent)]
+    MessageSigning(#[from] kaspa_consensus_core::message::Error),
+
     #[error(transparent)]
     Bip32(#[from] kaspa_bip32::Error),
 
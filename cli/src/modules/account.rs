@@ -41,6 +41,80 @@ impl Account {
                     }
                 }
             }
+            "settings" => {
+                let account = ctx.select_account().await?;
+                if argv.is_empty() {
+                    tprintln!(ctx, "change address policy: {}", account.change_address_policy());
+                    tprintln!(ctx, "");
+                    tprintln!(ctx, "usage: 'account settings change-address <reuse|rotate-per-transaction|match-destination-branch>'");
+                    return Ok(());
+                }
+
+                let setting = argv.remove(0);
+                match setting.as_str() {
+                    "change-address" => {
+                        if argv.len() != 1 {
+                            tprintln!(
+                                ctx,
+                                "usage: 'account settings change-address <reuse|rotate-per-transaction|match-destination-branch>'"
+                            );
+                            return Ok(());
+                        }
+                        let change_address_policy = argv.remove(0).parse::<ChangeAddressPolicy>()?;
+                        let (wallet_secret, _) = ctx.ask_wallet_secret(None).await?;
+                        let _ = ctx.notifier().show(Notification::Processing).await;
+                        account.set_change_address_policy(&wallet_secret, change_address_policy).await?;
+                        tprintln!(ctx, "change address policy: {change_address_policy}");
+                    }
+                    v => {
+                        tprintln!(ctx, "unknown setting: '{v}'");
+                    }
+                }
+            }
+            "policy" => {
+                let account = ctx.select_account().await?;
+                let mut policy = account.spending_policy();
+                if argv.is_empty() {
+                    self.display_spending_policy(&ctx, &policy)?;
+                    return Ok(());
+                }
+
+                let limit = |argv: &[String]| -> Result<Option<u64>> {
+                    match argv.first().map(String::as_str) {
+                        Some("none") => Ok(None),
+                        amount => Ok(Some(try_parse_required_nonzero_kaspa_as_sompi_u64(amount)?)),
+                    }
+                };
+                let rule = argv.remove(0);
+                match rule.as_str() {
+                    "max-payment" => policy.max_payment_sompi = limit(&argv)?,
+                    "daily-limit" => policy.daily_limit_sompi = limit(&argv)?,
+                    "approval-threshold" => policy.approval_threshold_sompi = limit(&argv)?,
+                    "allow" | "disallow" => {
+                        let Some(address) = argv.first() else {
+                            tprintln!(ctx, "usage: 'account policy {rule} <address>'");
+                            return Ok(());
+                        };
+                        let address = Address::try_from(address.as_str())?;
+                        let allowed_destinations = policy.allowed_destinations.get_or_insert_with(Vec::new);
+                        allowed_destinations.retain(|allowed| allowed != &address);
+                        if rule == "allow" {
+                            allowed_destinations.push(address);
+                        }
+                    }
+                    "allow-any" => policy.allowed_destinations = None,
+                    "clear" => policy = SpendingPolicy::default(),
+                    v => {
+                        tprintln!(ctx, "unknown spending policy rule: '{v}'");
+                        return Ok(());
+                    }
+                }
+
+                let (wallet_secret, _) = ctx.ask_wallet_secret(None).await?;
+                let _ = ctx.notifier().show(Notification::Processing).await;
+                account.set_spending_policy(&wallet_secret, policy.clone()).await?;
+                self.display_spending_policy(&ctx, &policy)?;
+            }
             "create" => {
                 let account_kind = if argv.is_empty() {
                     BIP32_ACCOUNT_KIND.into()
@@ -219,6 +293,18 @@ impl Account {
                 (KDX and kaspanet web wallet). Use 'account import' for additional help.",
                 ),
                 ("name <name>", "Name or rename the selected account (use 'remove' to remove the name"),
+                (
+                    "settings [change-address <policy>]",
+                    "Display or change the settings of the selected account. Change address policies: 'reuse' (default), \
+                'rotate-per-transaction' (a fresh change address per transaction), 'match-destination-branch' \
+                (rotate on payments, a single fresh change address per sweep)",
+                ),
+                (
+                    "policy [<rule> [<value>]]",
+                    "Display or change the spending policy of the selected account. Rules: 'max-payment <amount|none>', \
+                'daily-limit <amount|none>' (over the last 24 hours), 'approval-threshold <amount|none>' (payments requiring \
+                an approval), 'allow <address>', 'disallow <address>', 'allow-any' (remove the destination allowlist), 'clear'",
+                ),
                 ("scan [<derivations>] or scan [<start>] [<derivations>]", "Scan extended address derivation chain (legacy accounts)"),
                 (
                     "sweep [<derivations>] or sweep [<start>] [<derivations>]",
@@ -232,6 +318,26 @@ impl Account {
         Ok(())
     }
 
+    fn display_spending_policy(&self, ctx: &Arc<KaspaCli>, policy: &SpendingPolicy) -> Result<()> {
+        let network_type = NetworkType::from(ctx.wallet().network_id()?);
+        let limit = |limit: Option<u64>| match limit {
+            Some(sompi) => sompi_to_kaspa_string_with_suffix(sompi, &network_type),
+            None => "none".to_string(),
+        };
+        tprintln!(ctx, "max payment: {}", limit(policy.max_payment_sompi));
+        tprintln!(ctx, "daily limit: {}", limit(policy.daily_limit_sompi));
+        tprintln!(ctx, "approval threshold: {}", limit(policy.approval_threshold_sompi));
+        match policy.allowed_destinations.as_ref() {
+            None => tprintln!(ctx, "allowed destinations: any"),
+            Some(destinations) if destinations.is_empty() => tprintln!(ctx, "allowed destinations: none"),
+            Some(destinations) => {
+                tprintln!(ctx, "allowed destinations:");
+                destinations.iter().for_each(|address| tprintln!(ctx, "    {address}"));
+            }
+        }
+        Ok(())
+    }
+
     async fn derivation_scan(
         self: &Arc<Self>,
         ctx: &Arc<KaspaCli>,
@@ -21,6 +21,14 @@ impl Address {
                     tprintln!(ctx, "Generating new address for account {}", style(ident).cyan());
                     tprintln!(ctx, "{}", style(new_address).blue());
                 }
+                "qr" => {
+                    let address = ctx.account().await?.receive_address()?;
+                    tprintln!(ctx);
+                    for line in PaymentUri::new(address.clone()).to_qr_terminal()?.lines() {
+                        tprintln!(ctx, "{line}");
+                    }
+                    tprintln!(ctx, "\n{address}\n");
+                }
                 v => {
                     tprintln!(ctx, "unknown command: '{v}'\r\n");
                     return self.display_help(ctx, argv).await;
@@ -32,7 +40,13 @@ impl Address {
     }
 
     async fn display_help(self: Arc<Self>, ctx: Arc<KaspaCli>, _argv: Vec<String>) -> Result<()> {
-        ctx.term().help(&[("address [new]", "Show current or generate a new account address")], None)?;
+        ctx.term().help(
+            &[
+                ("address [new]", "Show current or generate a new account address"),
+                ("address qr", "Show the current account address as a QR code"),
+            ],
+            None,
+        )?;
 
         Ok(())
     }
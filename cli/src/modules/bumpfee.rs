@@ -0,0 +1,38 @@
+use crate::imports::*;
+use kaspa_rpc_core::api::rpc::RpcApi;
+
+#[derive(Default, Handler)]
+#[help("Accelerates a pending transaction of the account with a child transaction paying for it")]
+pub struct BumpFee;
+
+impl BumpFee {
+    async fn main(self: Arc<Self>, ctx: &Arc<dyn Context>, argv: Vec<String>, _cmd: &str) -> Result<()> {
+        let ctx = ctx.clone().downcast_arc::<KaspaCli>()?;
+
+        let account = ctx.wallet().account()?;
+
+        if argv.is_empty() {
+            tprintln!(ctx, "usage: bumpfee <transaction id> [<target feerate in sompi per gram>]");
+            return Ok(());
+        }
+
+        let transaction_id = TransactionId::from_hex(argv[0].as_str())?;
+        let target_feerate = match argv.get(1) {
+            Some(feerate) => feerate.parse::<f64>()?,
+            None => {
+                // double the feerate the transaction currently competes with in the mempool
+                let estimate = ctx.wallet().rpc_api().estimate_transaction_confirmation_time(transaction_id).await?;
+                estimate.estimate.package_feerate * 2.0
+            }
+        };
+
+        let (wallet_secret, payment_secret) = ctx.ask_wallet_secret(Some(&account)).await?;
+        let abortable = Abortable::default();
+        let (summary, ids) = account.bump_fee(transaction_id, target_feerate, wallet_secret, payment_secret, &abortable, None).await?;
+
+        tprintln!(ctx, "Bump fee - {summary}");
+        tprintln!(ctx, "Transactions: {}", ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", "));
+
+        Ok(())
+    }
+}
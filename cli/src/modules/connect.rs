@@ -36,6 +36,13 @@ impl Connect {
                 );
             }
 
+            // connecting to a url outside of the node endpoint profiles disables the failover
+            if let Some(endpoint_monitor) = ctx.wallet().endpoint_monitor() {
+                if endpoint_monitor.active().is_some_and(|endpoint| endpoint.url != url) {
+                    endpoint_monitor.release();
+                }
+            }
+
             let options = ConnectOptions {
                 block_async_connect: true,
                 strategy: ConnectStrategy::Fallback,
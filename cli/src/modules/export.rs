@@ -1,5 +1,6 @@
 use crate::imports::*;
 use kaspa_wallet_core::account::{multisig::MultiSig, Account, MULTISIG_ACCOUNT_KIND};
+use workflow_store::fs;
 
 #[derive(Default, Handler)]
 #[help("Export transactions, a wallet or a private key")]
@@ -10,7 +11,7 @@ impl Export {
         let ctx = ctx.clone().downcast_arc::<KaspaCli>()?;
 
         if argv.is_empty() || argv.first() == Some(&"help".to_string()) {
-            tprintln!(ctx, "usage: export [mnemonic]");
+            tprintln!(ctx, "usage: export [mnemonic|utxo-snapshot <file>]");
             return Ok(());
         }
 
@@ -25,11 +26,44 @@ impl Export {
                     export_single_key_account(ctx, account).await
                 }
             }
+            "utxo-snapshot" => {
+                let Some(filename) = argv.get(1) else {
+                    return Err("usage: export utxo-snapshot <file>".into());
+                };
+                export_utxo_snapshot(ctx, filename).await
+            }
             _ => Err(format!("Invalid argument: {}", what).into()),
         }
     }
 }
 
+async fn export_utxo_snapshot(ctx: Arc<KaspaCli>, filename: &str) -> Result<()> {
+    let snapshot = ctx.wallet().utxo_snapshot(None)?;
+    if snapshot.accounts.is_empty() {
+        return Err("no active accounts to export".into());
+    }
+
+    let snapshot_secret = Secret::new(ctx.term().ask(true, "Enter snapshot password: ").await?.trim().as_bytes().to_vec());
+    if snapshot_secret.as_ref().is_empty() {
+        return Err("snapshot password is required".into());
+    }
+
+    let path = fs::resolve_path(filename)?;
+    fs::write(&path, &snapshot.try_to_encrypted_vec(&snapshot_secret)?).await?;
+
+    let utxo_count = snapshot.accounts.iter().map(|account| account.utxos.len()).sum::<usize>();
+    tprintln!(
+        ctx,
+        "exported {} UTXO entries of {} accounts at DAA score {} to '{}'",
+        utxo_count,
+        snapshot.accounts.len(),
+        snapshot.daa_score,
+        path.display()
+    );
+
+    Ok(())
+}
+
 async fn export_multisig_account(ctx: Arc<KaspaCli>, account: Arc<MultiSig>) -> Result<()> {
     match &account.prv_key_data_ids() {
         None => Err(Error::KeyDataNotFound),
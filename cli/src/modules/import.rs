@@ -1,4 +1,6 @@
 use crate::imports::*;
+use kaspa_wallet_core::utxo::UtxoSnapshot;
+use workflow_store::fs;
 
 #[derive(Default, Handler)]
 #[help("Import a wallet, mnemonic, or a private key")]
@@ -43,6 +45,24 @@ impl Import {
                     return Err("KDX/kaspanet keydata file not found".into());
                 }
             }
+            "utxo-snapshot" => {
+                let Some(filename) = argv.get(1) else {
+                    return self.display_help(ctx).await;
+                };
+                let data = fs::read(&fs::resolve_path(filename)?).await?;
+                let snapshot_secret = Secret::new(ctx.term().ask(true, "Enter snapshot password: ").await?.trim().as_bytes().to_vec());
+                let snapshot = UtxoSnapshot::try_from_encrypted_slice(&data, &snapshot_secret)?;
+                for summary in wallet.import_utxo_snapshot(&snapshot).await? {
+                    tprintln!(
+                        ctx,
+                        "account {}: imported {} UTXO entries, rescanned {} of {} addresses",
+                        summary.account_id.short(),
+                        summary.imported_utxo_count,
+                        summary.rescanned_addresses.len(),
+                        summary.validated_address_count
+                    );
+                }
+            }
             // todo "read-only" => {}
             // "core" => {}
             v => {
@@ -62,6 +82,7 @@ impl Import {
                     "Import a 24 or 12 word mnemonic (types: 'bip32' (default), 'legacy', 'multisig'), ",
                 ),
                 ("legacy", "Import a legacy (local KDX) wallet"),
+                ("utxo-snapshot <file>", "Restore the active accounts from a UTXO snapshot without a full rescan"),
                 // ("purge", "Purge an account from the wallet"),
             ],
             None,
@@ -1,11 +1,13 @@
 use kaspa_addresses::Version;
 use kaspa_bip32::secp256k1::XOnlyPublicKey;
+use kaspa_consensus_core::message::{self as signed_message, MessageSignature};
 use kaspa_wallet_core::{
     account::{BIP32_ACCOUNT_KIND, KEYPAIR_ACCOUNT_KIND},
     message::{sign_message, verify_message, PersonalMessage},
 };
 
 use crate::imports::*;
+use std::str::FromStr;
 
 #[derive(Default)]
 pub struct Message;
@@ -44,6 +46,17 @@ impl Message {
 
                 self.sign(ctx, kaspa_address, message).await?;
             }
+            "sign-raw" => {
+                if argv.len() != 2 {
+                    return self.display_help(ctx, argv).await;
+                }
+
+                let kaspa_address = argv[1].as_str();
+                let asked_message = ctx.term().ask(false, "Message: ").await?;
+                let message = asked_message.as_str();
+
+                self.sign_raw(ctx, kaspa_address, message).await?;
+            }
             "verify" => {
                 if argv.len() != 3 {
                     return self.display_help(ctx, argv).await;
@@ -55,6 +68,16 @@ impl Message {
 
                 self.verify(ctx, kaspa_address, signature, message).await?;
             }
+            "recover" => {
+                if argv.len() != 2 {
+                    return self.display_help(ctx, argv).await;
+                }
+                let signature = argv[1].as_str();
+                let asked_message = ctx.term().ask(false, "Message: ").await?;
+                let message = asked_message.as_str();
+
+                self.recover(ctx, signature, message).await?;
+            }
             v => {
                 tprintln!(ctx, "unknown command: '{v}'\r\n");
                 return self.display_help(ctx, argv).await;
@@ -68,10 +91,15 @@ impl Message {
         ctx.term().help(
             &[
                 ("sign <kaspa_address>", "Sign a message with the private key that matches the given address. Prompts for message."),
+                (
+                    "sign-raw <kaspa_address>",
+                    "Sign a message producing a bare schnorr signature (KIP-5) instead of the encoded one. Prompts for message.",
+                ),
                 (
                     "verify <kaspa_address> <signature>",
-                    "Verify the signature against the message and kaspa_address. Prompts for message.",
+                    "Verify the signature (encoded or bare schnorr) against the message and kaspa_address. Prompts for message.",
                 ),
+                ("recover <signature>", "Recover the address that produced an ECDSA signature of the message. Prompts for message."),
             ],
             None,
         )?;
@@ -80,6 +108,15 @@ impl Message {
     }
 
     async fn sign(self: Arc<Self>, ctx: Arc<KaspaCli>, kaspa_address: &str, message: &str) -> Result<()> {
+        let kaspa_address = Address::try_from(kaspa_address)?;
+        let account = ctx.wallet().account()?;
+        let (wallet_secret, payment_secret) = ctx.ask_wallet_secret(Some(&account)).await?;
+        let signature = account.sign_message(&kaspa_address, message.as_bytes(), wallet_secret, payment_secret).await?;
+        tprintln!(ctx, "Signature: {}", signature);
+        Ok(())
+    }
+
+    async fn sign_raw(self: Arc<Self>, ctx: Arc<KaspaCli>, kaspa_address: &str, message: &str) -> Result<()> {
         let kaspa_address = Address::try_from(kaspa_address)?;
         if kaspa_address.version != Version::PubKey {
             return Err(Error::custom("Address not supported for message signing. Only supports PubKey addresses"));
@@ -102,6 +139,13 @@ impl Message {
 
     async fn verify(self: Arc<Self>, ctx: Arc<KaspaCli>, kaspa_address: &str, signature: &str, message: &str) -> Result<()> {
         let kaspa_address = Address::try_from(kaspa_address)?;
+        if signature.len() == MessageSignature::SIZE * 2 {
+            let signature = MessageSignature::from_str(signature)?;
+            signed_message::verify_message(&kaspa_address, message.as_bytes(), &signature)?;
+            tprintln!(ctx, "Message verified successfully!");
+            return Ok(());
+        }
+
         if kaspa_address.version != Version::PubKey {
             return Err(Error::custom("Address not supported for message signing. Only supports PubKey addresses"));
         }
@@ -126,6 +170,13 @@ impl Message {
         Ok(())
     }
 
+    async fn recover(self: Arc<Self>, ctx: Arc<KaspaCli>, signature: &str, message: &str) -> Result<()> {
+        let signature = MessageSignature::from_str(signature)?;
+        let address = signed_message::recover_address(ctx.wallet().network_id()?.into(), message.as_bytes(), &signature)?;
+        tprintln!(ctx, "Address: {}", address);
+        Ok(())
+    }
+
     async fn get_address_private_key(self: Arc<Self>, ctx: &Arc<KaspaCli>, kaspa_address: Address) -> Result<[u8; 32]> {
         let account = ctx.wallet().account()?;
 
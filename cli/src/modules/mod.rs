@@ -3,6 +3,7 @@ use crate::imports::*;
 pub mod account;
 pub mod address;
 pub mod broadcast;
+pub mod bumpfee;
 pub mod close;
 pub mod connect;
 #[path = "create-unsigned-tx.rs"]
@@ -27,6 +28,7 @@ pub mod node;
 pub mod open;
 pub mod ping;
 pub mod reload;
+pub mod request;
 pub mod rpc;
 pub mod select;
 pub mod send;
@@ -56,9 +58,9 @@ pub fn register_handlers(cli: &Arc<KaspaCli>) -> Result<()> {
         cli,
         cli.handlers(),
         [
-            account, address, close, connect, details, disconnect, estimate, exit, export, guide, help, history, rpc, list, miner,
-            message, monitor, mute, network, node, open, ping, reload, select, send, server, settings, sweep, track, transfer,
-            wallet,
+            account, address, bumpfee, close, connect, details, disconnect, estimate, exit, export, guide, help, history, rpc, list, miner,
+            message, monitor, mute, network, node, open, ping, reload, request, select, send, server, settings, sweep, track,
+            transfer, wallet,
             // halt,
             // theme,  start, stop
         ]
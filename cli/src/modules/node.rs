@@ -1,5 +1,6 @@
 use crate::imports::*;
 use kaspa_daemon::KaspadConfig;
+use kaspa_wallet_core::endpoints::Endpoint;
 use workflow_core::task::sleep;
 use workflow_node::process;
 pub use workflow_node::process::Event;
@@ -48,16 +49,12 @@ impl Default for Node {
 
 #[async_trait]
 impl Handler for Node {
-    fn verb(&self, ctx: &Arc<dyn Context>) -> Option<&'static str> {
-        if let Ok(ctx) = ctx.clone().downcast_arc::<KaspaCli>() {
-            ctx.daemons().clone().kaspad.as_ref().map(|_| "node")
-        } else {
-            None
-        }
+    fn verb(&self, _ctx: &Arc<dyn Context>) -> Option<&'static str> {
+        Some("node")
     }
 
     fn help(&self, _ctx: &Arc<dyn Context>) -> &'static str {
-        "Manage the local Kaspa node instance"
+        "Manage node endpoints and the local Kaspa node instance"
     }
 
     async fn start(self: Arc<Self>, _ctx: &Arc<dyn Context>) -> cli::Result<()> {
@@ -96,8 +93,19 @@ impl Node {
         if argv.is_empty() {
             return self.display_help(ctx, argv).await;
         }
-        let kaspad = ctx.daemons().kaspad();
-        match argv.remove(0).as_str() {
+        let op = argv.remove(0);
+        match op.as_str() {
+            "list" | "add" | "remove" | "switch" => {
+                return self.endpoints(ctx, op.as_str(), argv).await;
+            }
+            _ => {}
+        }
+
+        let Some(kaspad) = ctx.daemons().try_kaspad() else {
+            tprintln!(ctx, "unknown command: '{op}'\r\n");
+            return self.display_help(ctx, argv).await;
+        };
+        match op.as_str() {
             "start" => {
                 let mute = self.mute.load(Ordering::SeqCst);
                 if mute {
@@ -183,8 +191,14 @@ impl Node {
     }
 
     async fn display_help(self: Arc<Self>, ctx: Arc<KaspaCli>, _argv: Vec<String>) -> Result<()> {
-        ctx.term().help(
-            &[
+        let mut help = vec![
+            ("list", "List the node endpoints of the current network"),
+            ("add <name> <url> [<priority>]", "Add a node endpoint to the current network (lower priority values are preferred)"),
+            ("remove <name>", "Remove a node endpoint"),
+            ("switch <name>", "Connect to a node endpoint, failing over to the next healthy endpoint if it goes down"),
+        ];
+        if ctx.daemons().try_kaspad().is_some() {
+            help.extend([
                 ("select", "Select Kaspad executable (binary) location"),
                 ("version", "Display Kaspad executable version"),
                 ("start", "Start the local Kaspa node instance"),
@@ -193,9 +207,84 @@ impl Node {
                 ("kill", "Kill the local Kaspa node instance"),
                 ("status", "Get the status of the local Kaspa node instance"),
                 ("mute", "Toggle log output"),
-            ],
-            None,
-        )?;
+            ]);
+        }
+        ctx.term().help(&help, None)?;
+
+        Ok(())
+    }
+
+    async fn endpoints(self: Arc<Self>, ctx: Arc<KaspaCli>, op: &str, argv: Vec<String>) -> Result<()> {
+        let wallet = ctx.wallet();
+        let wrpc_client = wallet.try_wrpc_client().ok_or(Error::custom("Node endpoints require a wRPC client"))?;
+        let endpoint_monitor = wallet.endpoint_monitor().ok_or(Error::custom("Node endpoints require a wRPC client"))?;
+        let network_id = wallet.network_id()?;
+
+        match op {
+            "list" => {
+                let endpoints = wallet.endpoints()?;
+                if endpoints.is_empty() {
+                    tprintln!(ctx, "No node endpoints for {network_id}, use 'node add <name> <url>' to add one");
+                    return Ok(());
+                }
+                let active = endpoint_monitor.active().map(|endpoint| endpoint.name);
+                let width = endpoints.iter().map(|endpoint| endpoint.name.len()).max().unwrap_or_default();
+                tprintln!(ctx, "Node endpoints of {network_id}:");
+                for endpoint in endpoints {
+                    let health = endpoint_monitor.health(&endpoint);
+                    let health = match health.latency {
+                        Some(latency) => style(format!("{} ms", latency.as_millis())).green().to_string(),
+                        None if health.is_failed() => style("unreachable").red().to_string(),
+                        None => style("not probed").dim().to_string(),
+                    };
+                    let marker = if active.as_deref() == Some(endpoint.name.as_str()) { "*" } else { " " };
+                    tprintln!(
+                        ctx,
+                        "{marker} {}  priority {}  {}  {health}",
+                        endpoint.name.pad_to_width(width),
+                        endpoint.priority,
+                        endpoint.url
+                    );
+                }
+            }
+            "add" => {
+                let (Some(name), Some(url)) = (argv.first(), argv.get(1)) else {
+                    return Err(Error::custom("usage: node add <name> <url> [<priority>]"));
+                };
+                let url = wrpc_client.parse_url_with_network_type(url.clone(), network_id.into()).map_err(|e| e.to_string())?;
+                let priority = match argv.get(2) {
+                    Some(priority) => priority.parse::<u32>().map_err(|_| Error::custom(format!("Invalid priority: {priority}")))?,
+                    None => wallet.endpoints()?.iter().map(|endpoint| endpoint.priority + 1).max().unwrap_or_default(),
+                };
+                wallet.add_endpoint(Endpoint::new(name, &url, priority)).await?;
+                tprintln!(ctx, "Added node endpoint '{name}' at {url} with priority {priority}");
+            }
+            "remove" => {
+                let name = argv.first().ok_or(Error::custom("usage: node remove <name>"))?;
+                if wallet.remove_endpoint(name).await? {
+                    tprintln!(ctx, "Removed node endpoint '{name}'");
+                } else {
+                    twarnln!(ctx, "No node endpoint named '{name}'");
+                }
+            }
+            "switch" => {
+                let name = argv.first().ok_or(Error::custom("usage: node switch <name>"))?;
+                let endpoint = endpoint_monitor.select(name).await?;
+                wallet.settings().set(WalletSettings::Server, endpoint.url.as_str()).await?;
+
+                if wrpc_client.is_connected() {
+                    wrpc_client.disconnect().await?;
+                }
+                let options = ConnectOptions {
+                    block_async_connect: true,
+                    strategy: ConnectStrategy::Fallback,
+                    url: Some(endpoint.url),
+                    ..Default::default()
+                };
+                wrpc_client.connect(Some(options)).await.map_err(|e| e.to_string())?;
+            }
+            _ => unreachable!(),
+        }
 
         Ok(())
     }
@@ -0,0 +1,46 @@
+use crate::imports::*;
+use kaspa_wallet_core::uri::try_parse_amount;
+
+#[derive(Default, Handler)]
+#[help("Create a payment request (URI and QR code) for the current account address")]
+pub struct Request;
+
+impl Request {
+    async fn main(self: Arc<Self>, ctx: &Arc<dyn Context>, argv: Vec<String>, _cmd: &str) -> Result<()> {
+        let ctx = ctx.clone().downcast_arc::<KaspaCli>()?;
+
+        if argv.len() != 1 {
+            return self.display_help(ctx, argv).await;
+        }
+
+        let amount = try_parse_amount(argv[0].as_str())?;
+        if amount == 0 {
+            return Err(Error::custom("Requested amount must be greater than zero"));
+        }
+
+        let address = ctx.account().await?.receive_address()?;
+        let mut payment_uri = PaymentUri::new(address).with_amount(amount);
+        let label = ctx.term().ask(false, "Label (optional): ").await?;
+        if !label.trim().is_empty() {
+            payment_uri = payment_uri.with_label(label.trim());
+        }
+        let message = ctx.term().ask(false, "Message (optional): ").await?;
+        if !message.trim().is_empty() {
+            payment_uri = payment_uri.with_message(message.trim());
+        }
+
+        tprintln!(ctx);
+        for line in payment_uri.to_qr_terminal()?.lines() {
+            tprintln!(ctx, "{line}");
+        }
+        tprintln!(ctx, "\n{payment_uri}\n");
+
+        Ok(())
+    }
+
+    async fn display_help(self: Arc<Self>, ctx: Arc<KaspaCli>, _argv: Vec<String>) -> Result<()> {
+        ctx.term().help(&[("request <amount>", "Create a payment request for the given amount of KAS")], None)?;
+
+        Ok(())
+    }
+}
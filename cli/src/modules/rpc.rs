@@ -38,7 +38,7 @@ impl Rpc {
                 tprintln!(ctx, "ok");
             }
             RpcApiOps::GetMetrics => {
-                let result = rpc.get_metrics(true, true, true, true).await?;
+                let result = rpc.get_metrics(true, true, true, true, true).await?;
                 self.println(&ctx, result);
             }
             RpcApiOps::GetServerInfo => {
@@ -62,7 +62,7 @@ impl Rpc {
             //     self.println(&ctx, result);
             // }
             RpcApiOps::GetPeerAddresses => {
-                let result = rpc.get_peer_addresses_call(GetPeerAddressesRequest {}).await?;
+                let result = rpc.get_peer_addresses_call(GetPeerAddressesRequest::default()).await?;
                 self.println(&ctx, result);
             }
             RpcApiOps::GetSink => {
@@ -75,9 +75,7 @@ impl Rpc {
             // }
             RpcApiOps::GetMempoolEntries => {
                 // TODO
-                let result = rpc
-                    .get_mempool_entries_call(GetMempoolEntriesRequest { include_orphan_pool: true, filter_transaction_pool: true })
-                    .await?;
+                let result = rpc.get_mempool_entries_call(GetMempoolEntriesRequest::new(true, true)).await?;
                 self.println(&ctx, result);
             }
             RpcApiOps::GetConnectedPeerInfo => {
@@ -93,6 +91,22 @@ impl Rpc {
                 let result = rpc.add_peer_call(AddPeerRequest { peer_address, is_permanent }).await?;
                 self.println(&ctx, result);
             }
+            RpcApiOps::RemovePeer => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Usage: rpc removepeer <ip:port>"));
+                }
+                let peer_address = argv.remove(0).parse::<RpcContextualPeerAddress>()?;
+                let result = rpc.remove_peer_call(RemovePeerRequest { peer_address }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::SetProcessingParallelism => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Usage: rpc setprocessingparallelism <limit>"));
+                }
+                let limit = argv.remove(0).parse::<u32>()?;
+                let result = rpc.set_processing_parallelism_call(SetProcessingParallelismRequest { limit }).await?;
+                self.println(&ctx, result);
+            }
             // RpcApiOps::SubmitTransaction => {
             //     let result = rpc.submit_transaction_call(SubmitTransactionRequest {  }).await?;
             //     self.println(&ctx, result);
@@ -103,7 +117,7 @@ impl Rpc {
                 }
                 let hash = argv.remove(0);
                 let hash = RpcHash::from_hex(hash.as_str())?;
-                let result = rpc.get_block_call(GetBlockRequest { hash, include_transactions: true }).await?;
+                let result = rpc.get_block_call(GetBlockRequest::new(hash, true)).await?;
                 self.println(&ctx, result);
             }
             // RpcApiOps::GetSubnetwork => {
@@ -164,10 +178,54 @@ impl Rpc {
                 let result = rpc.get_balances_by_addresses_call(GetBalancesByAddressesRequest { addresses }).await?;
                 self.println(&ctx, result);
             }
+            RpcApiOps::GetAddressActivity => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Please specify at least one address"));
+                }
+                let addresses = argv.iter().map(|s| Address::try_from(s.as_str())).collect::<std::result::Result<Vec<_>, _>>()?;
+                let result = rpc.get_address_activity_call(GetAddressActivityRequest { addresses }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetMissedNotifications => {
+                use kaspa_wrpc_client::prelude::{Scope, UtxosChangedScope, VirtualChainChangedScope};
+                if argv.len() < 2 {
+                    return Err(Error::custom("Please specify a scope (virtual-chain or utxos) and a starting sequence"));
+                }
+                let scope_name = argv.remove(0);
+                let from_sequence =
+                    argv.remove(0).parse::<u64>().map_err(|_| Error::custom("Could not parse starting sequence to u64"))?;
+                let scope: Scope = match scope_name.as_str() {
+                    "virtual-chain" => VirtualChainChangedScope::new(true).into(),
+                    "utxos" => {
+                        let addresses =
+                            argv.iter().map(|s| Address::try_from(s.as_str())).collect::<std::result::Result<Vec<_>, _>>()?;
+                        UtxosChangedScope::new(addresses).into()
+                    }
+                    _ => return Err(Error::custom("Unknown scope, expecting virtual-chain or utxos")),
+                };
+                let result = rpc.get_missed_notifications_call(GetMissedNotificationsRequest { scope, from_sequence }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetUtxoReturnAddress => {
+                if argv.len() < 3 {
+                    return Err(Error::custom("Please specify an accepting block hash, a transaction id and an input index"));
+                }
+                let accepting_block_hash = RpcHash::from_hex(argv.remove(0).as_str())?;
+                let transaction_id = RpcHash::from_hex(argv.remove(0).as_str())?;
+                let input_index = argv.remove(0).parse::<u32>().map_err(|_| Error::custom("Could not parse input index to u32"))?;
+                let result = rpc
+                    .get_utxo_return_address_call(GetUtxoReturnAddressRequest { accepting_block_hash, transaction_id, input_index })
+                    .await?;
+                self.println(&ctx, result);
+            }
             RpcApiOps::GetSinkBlueScore => {
                 let result = rpc.get_sink_blue_score_call(GetSinkBlueScoreRequest {}).await?;
                 self.println(&ctx, result);
             }
+            RpcApiOps::GetVirtualInfo => {
+                let result = rpc.get_virtual_info_call(GetVirtualInfoRequest {}).await?;
+                self.println(&ctx, result);
+            }
             RpcApiOps::Ban => {
                 if argv.is_empty() {
                     return Err(Error::custom("Please specify peer IP address"));
@@ -229,6 +287,69 @@ impl Rpc {
                     }
                 }
             }
+            RpcApiOps::GetChainBlockByDaaScore => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Please specify a daa_score"));
+                }
+                let daa_score = argv.remove(0).parse::<u64>().map_err(|_| Error::custom("Could not parse daa_score to u64"))?;
+                let result = rpc.get_chain_block_by_daa_score_call(GetChainBlockByDaaScoreRequest { daa_score }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::SetLogFilters => {
+                let filters = argv.join(",");
+                let result = rpc.set_log_filters_call(SetLogFiltersRequest { filters }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetOutpointSpendStatus => {
+                if argv.len() < 2 {
+                    return Err(Error::custom("Please specify a transaction id and an output index"));
+                }
+                let transaction_id = RpcHash::from_hex(argv.remove(0).as_str())?;
+                let index = argv.remove(0).parse::<u32>().map_err(|_| Error::custom("Could not parse output index to u32"))?;
+                let search_depth = if argv.is_empty() {
+                    None
+                } else {
+                    Some(argv.remove(0).parse::<u64>().map_err(|_| Error::custom("Could not parse search_depth to u64"))?)
+                };
+                let outpoint = RpcTransactionOutpoint::new(transaction_id, index);
+                let result = rpc.get_outpoint_spend_status_call(GetOutpointSpendStatusRequest { outpoint, search_depth }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::EstimateTransactionConfirmationTime => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Please specify a transaction id"));
+                }
+                let transaction_id = RpcHash::from_hex(argv.remove(0).as_str())?;
+                let result = rpc
+                    .estimate_transaction_confirmation_time_call(EstimateTransactionConfirmationTimeRequest { transaction_id })
+                    .await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetAcceptanceProof => {
+                if argv.len() < 2 {
+                    return Err(Error::custom("Please specify an accepting block hash and a transaction id"));
+                }
+                let accepting_block_hash = RpcHash::from_hex(argv.remove(0).as_str())?;
+                let transaction_id = RpcHash::from_hex(argv.remove(0).as_str())?;
+                let result = rpc.get_acceptance_proof_call(GetAcceptanceProofRequest { accepting_block_hash, transaction_id }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::CreateBackup => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Please specify the absolute path of the backup directory on the node host"));
+                }
+                let output_directory = argv.remove(0);
+                let result = rpc.create_backup_call(CreateBackupRequest { output_directory }).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetVirtualParentSelectionTrace => {
+                let result = rpc.get_virtual_parent_selection_trace_call(GetVirtualParentSelectionTraceRequest {}).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetSystemInfo => {
+                let result = rpc.get_system_info_call(GetSystemInfoRequest {}).await?;
+                self.println(&ctx, result);
+            }
             _ => {
                 tprintln!(ctx, "rpc method exists but is not supported by the cli: '{op_str}'\r\n");
                 return Ok(());
@@ -11,8 +11,24 @@ impl Send {
 
         let account = ctx.wallet().account()?;
 
+        // coin control: `--from-utxo <outpoint>` (repeatable) restricts the transaction to the given UTXOs
+        let mut argv = argv.into_iter();
+        let mut args = vec![];
+        let mut from_utxos = vec![];
+        while let Some(arg) = argv.next() {
+            if let Some(outpoint) = arg.strip_prefix("--from-utxo=") {
+                from_utxos.push(try_parse_outpoint(outpoint)?);
+            } else if arg == "--from-utxo" {
+                let outpoint = argv.next().ok_or_else(|| Error::custom("Missing outpoint after --from-utxo"))?;
+                from_utxos.push(try_parse_outpoint(&outpoint)?);
+            } else {
+                args.push(arg);
+            }
+        }
+        let argv = args;
+
         if argv.len() < 2 {
-            tprintln!(ctx, "usage: send <address> <amount> <priority fee>");
+            tprintln!(ctx, "usage: send <address> <amount> <priority fee> [--from-utxo <txid>-<index> ...]");
             return Ok(());
         }
 
@@ -20,6 +36,7 @@ impl Send {
         let amount_sompi = try_parse_required_nonzero_kaspa_as_sompi_u64(argv.get(1))?;
         let priority_fee_sompi = try_parse_optional_kaspa_as_sompi_i64(argv.get(2))?.unwrap_or(0);
         let outputs = PaymentOutputs::from((address.clone(), amount_sompi));
+        let utxo_selection = if from_utxos.is_empty() { UtxoSelection::Automatic } else { UtxoSelection::Manual(from_utxos) };
         let abortable = Abortable::default();
         let (wallet_secret, payment_secret) = ctx.ask_wallet_secret(Some(&account)).await?;
 
@@ -29,6 +46,7 @@ impl Send {
                 outputs.into(),
                 priority_fee_sompi.into(),
                 None,
+                utxo_selection,
                 wallet_secret,
                 payment_secret,
                 &abortable,
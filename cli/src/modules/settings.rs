@@ -12,6 +12,8 @@ impl Settings {
         let list = WalletSettings::list();
         let list = list
             .iter()
+            // node endpoint profiles are listed by `node list`
+            .filter(|setting| **setting != WalletSettings::Endpoints)
             .map(|setting| {
                 let value: String = ctx.wallet().settings().get(setting.clone()).unwrap_or_else(|| "-".to_string());
                 let descr = setting.descr();
@@ -24,11 +24,7 @@ impl Wallet {
                     tprintln!(ctx, "Wallets:");
                     tprintln!(ctx, "");
                     for wallet in wallets {
-                        if let Some(title) = wallet.title {
-                            tprintln!(ctx, "  {}: {}", wallet.filename, title);
-                        } else {
-                            tprintln!(ctx, "  {}", wallet.filename);
-                        }
+                        tprintln!(ctx, "  {wallet}");
                     }
                     tprintln!(ctx, "");
                 }
@@ -60,6 +56,8 @@ impl Wallet {
                         return Ok(());
                     }
                     Some(name)
+                } else if let Some(name) = ctx.select_wallet().await? {
+                    Some(name)
                 } else {
                     ctx.wallet().settings().get(WalletSettings::Wallet).clone()
                 };
@@ -111,7 +109,10 @@ impl Wallet {
                 Legacy wallets can only be imported as accounts. \
                 \r\n",
                 ),
-                ("open [<name>]", "Open an existing wallet (shorthand: 'open [<name>]')"),
+                (
+                    "open [<name>]",
+                    "Open an existing wallet, selecting among the available wallets if no name is given (shorthand: 'open [<name>]')",
+                ),
                 ("close", "Close an opened wallet (shorthand: 'close')"),
                 ("hint", "Change the wallet phishing hint"),
             ],
@@ -1,6 +1,7 @@
 use crate::error::Error;
 use crate::result::Result;
 use kaspa_consensus_core::constants::SOMPI_PER_KASPA;
+use kaspa_consensus_core::tx::{TransactionId, TransactionOutpoint};
 use std::fmt::Display;
 
 pub fn try_parse_required_nonzero_kaspa_as_sompi_u64<S: ToString + Display>(kaspa_amount: Option<S>) -> Result<u64> {
@@ -58,3 +59,15 @@ pub fn try_parse_optional_kaspa_as_sompi_i64<S: ToString + Display>(kaspa_amount
         Ok(None)
     }
 }
+
+/// Parses a transaction outpoint supplied as `<transaction id>-<index>` (or `<transaction id>:<index>`)
+pub fn try_parse_outpoint(outpoint: &str) -> Result<TransactionOutpoint> {
+    let (transaction_id, index) = outpoint
+        .split_once(['-', ':'])
+        .ok_or_else(|| Error::custom(format!("Supplied outpoint is not valid (expecting <txid>-<index>): '{outpoint}'")))?;
+    let transaction_id = transaction_id
+        .parse::<TransactionId>()
+        .map_err(|_| Error::custom(format!("Supplied outpoint transaction id is not valid: '{outpoint}'")))?;
+    let index = index.parse::<u32>().map_err(|_| Error::custom(format!("Supplied outpoint index is not valid: '{outpoint}'")))?;
+    Ok(TransactionOutpoint::new(transaction_id, index))
+}
@@ -92,8 +92,16 @@ pub(crate) async fn import_with_mnemonic(ctx: &Arc<KaspaCli>, account_kind: Acco
     let mnemonic = mnemonic.join(" ");
     let mnemonic = Mnemonic::new(mnemonic.trim(), Language::English)?;
 
-    let account = if account_kind != MULTISIG_ACCOUNT_KIND {
-        wallet.import_with_mnemonic(&wallet_secret, payment_secret.as_ref(), mnemonic, account_kind).await?
+    let accounts = if account_kind == BIP32_ACCOUNT_KIND && wallet.is_connected() {
+        tprintln!(ctx, "scanning for accounts with on-chain activity...");
+        wallet
+            .import_with_mnemonic_and_discovery(&wallet_secret, payment_secret.as_ref(), mnemonic, AccountDiscoveryArgs::default())
+            .await?
+    } else if account_kind != MULTISIG_ACCOUNT_KIND {
+        if account_kind == BIP32_ACCOUNT_KIND {
+            tprintln!(ctx, "wallet is not connected, skipping account discovery (only account #0 will be imported)");
+        }
+        vec![wallet.import_with_mnemonic(&wallet_secret, payment_secret.as_ref(), mnemonic, account_kind).await?]
     } else {
         let mut mnemonics_secrets = vec![(mnemonic, payment_secret)];
         while matches!(
@@ -123,10 +131,14 @@ pub(crate) async fn import_with_mnemonic(ctx: &Arc<KaspaCli>, account_kind: Acco
         }
         let n_required: u16 = term.ask(false, "Enter the minimum number of signatures required: ").await?.parse()?;
 
-        wallet.import_multisig_with_mnemonic(&wallet_secret, mnemonics_secrets, n_required, additional_xpubs).await?
+        vec![wallet.import_multisig_with_mnemonic(&wallet_secret, mnemonics_secrets, n_required, additional_xpubs).await?]
     };
 
-    tprintln!(ctx, "\naccount imported: {}\n", account.get_list_string()?);
-    wallet.select(Some(&account)).await?;
+    tprintln!(ctx);
+    for account in accounts.iter() {
+        tprintln!(ctx, "account imported: {}", account.get_list_string()?);
+    }
+    tprintln!(ctx);
+    wallet.select(accounts.first()).await?;
     Ok(())
 }
@@ -1,14 +1,16 @@
+pub mod netutils;
 mod port_mapping_extender;
 mod stores;
 extern crate self as address_manager;
 
-use std::{collections::HashSet, iter, net::SocketAddr, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    iter,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
 
 use address_manager::port_mapping_extender::Extender;
-use igd_next::{
-    self as igd, aio::tokio::Tokio, AddAnyPortError, AddPortError, Gateway, GetExternalIpError, GetGenericPortMappingEntryError,
-    SearchError,
-};
 use itertools::{
     Either::{Left, Right},
     Itertools,
@@ -16,92 +18,158 @@ use itertools::{
 use kaspa_consensus_core::config::Config;
 use kaspa_core::{debug, info, task::tick::TickService, time::unix_now, warn};
 use kaspa_database::prelude::{CachePolicy, StoreResultExtensions, DB};
-use kaspa_utils::networking::IpAddress;
+use kaspa_utils::networking::{IpAddress, OnionAddress};
 use local_ip_address::list_afinet_netifas;
+use netutils::{PortMapping, PortMappingError, PORT_MAPPING_RENEWAL_INTERVAL};
 use parking_lot::Mutex;
 use stores::banned_address_store::{BannedAddressesStore, BannedAddressesStoreReader, ConnectionBanTimestamp, DbBannedAddressesStore};
-use thiserror::Error;
+use stores::manual_peer_store::{DbManualPeersStore, ManualPeerEntry, ManualPeersStore};
+use stores::AddressKey;
 
 pub use stores::NetAddress;
 
 const MAX_ADDRESSES: usize = 4096;
 const MAX_CONNECTION_FAILED_COUNT: u64 = 3;
 
-const UPNP_DEADLINE_SEC: u64 = 2 * 60;
-const UPNP_EXTEND_PERIOD: u64 = UPNP_DEADLINE_SEC / 2;
+/// The maximum number of persisted manual peers, see [`AddressManager::add_manual_peer`]
+pub const MAX_MANUAL_PEERS: usize = 64;
+
+/// The network group of onion addresses, see [`AddressInfo::network_group`]
+pub const ONION_NETWORK_GROUP: &str = "onion";
+
+/// Reasons for which a peer is disconnected and never redialed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisconnectReason {
+    /// The peer runs with different consensus params
+    ParamsDigestMismatch,
+    /// The peer runs on a network with a different genesis
+    GenesisMismatch,
+}
+
+/// Origin of a known address
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressSource {
+    /// The address was restored from the database, which does not record its origin
+    Unknown,
+    DnsSeed,
+    PeerGossip,
+    /// The address of an outbound peer which was connected on request (`--connect`, `--addpeer` or the AddPeer RPC)
+    Manual,
+}
+
+/// Outcome of a connection attempt to a known address
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectionOutcome {
+    Success,
+    Failure,
+}
+
+/// The quality data the address manager holds about a known address.
+///
+/// Only the connection failure counter is persisted, so the remaining data of addresses
+/// restored from the database is unknown until they are seen or dialed again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AddressInfo {
+    pub address: NetAddress,
+    pub source: AddressSource,
+    pub connection_failed_count: u64,
+    /// Whether a connection to the address ever succeeded, as opposed to a new address
+    pub is_tried: bool,
+    /// Unix time (in milliseconds) at which the address was last added or advertised
+    pub last_seen: Option<u64>,
+    /// Unix time (in milliseconds) and outcome of the last connection attempt
+    pub last_attempt: Option<(u64, ConnectionOutcome)>,
+}
 
-/// The name used as description when registering the UPnP service
-pub(crate) const UPNP_REGISTRATION_NAME: &str = "rusty-kaspa";
+impl AddressInfo {
+    /// The network group of the address, i.e. the prefix bucket used for weighting the address selection,
+    /// written as the `/16` prefix of IPv4 addresses (`a.b`) or the `/64` prefix of IPv6 ones (`a:b:c:d`).
+    /// Onion addresses all belong to the `onion` group.
+    pub fn network_group(&self) -> String {
+        if self.address.ip.is_onion() {
+            return ONION_NETWORK_GROUP.to_string();
+        }
+        // IPv4 addresses embedded in IPv6 ones fall in the bucket of the IPv4 address, see `PrefixBucket`
+        let ip = match self.address.ip.0 {
+            IpAddr::V6(ip) => ip.to_ipv4().map_or(IpAddr::V6(ip), IpAddr::V4),
+            ip => ip,
+        };
+        match ip {
+            IpAddr::V4(ip) => format!("{}.{}", ip.octets()[0], ip.octets()[1]),
+            IpAddr::V6(ip) => ip.segments()[..4].iter().map(|segment| format!("{segment:x}")).join(":"),
+        }
+    }
+}
 
-struct ExtendHelper {
-    gateway: Gateway,
-    local_addr: SocketAddr,
-    external_port: u16,
+/// Filter of the known addresses returned by [`AddressManager::get_address_infos`]
+#[derive(Clone, Debug, Default)]
+pub struct AddressFilter {
+    /// Only return addresses to which a connection ever succeeded
+    pub only_tried: bool,
+    /// Only return addresses to which a connection never succeeded
+    pub only_new: bool,
+    /// Only return addresses whose network group starts with this prefix
+    pub group_prefix: Option<String>,
+    /// Maximum number of addresses to return
+    pub limit: Option<usize>,
 }
 
-#[derive(Error, Debug)]
-pub enum UpnpError {
-    #[error(transparent)]
-    AddPortError(#[from] AddPortError),
-    #[error(transparent)]
-    AddAnyPortError(#[from] AddAnyPortError),
-    #[error(transparent)]
-    SearchError(#[from] SearchError),
-    #[error(transparent)]
-    GetExternalIpError(#[from] GetExternalIpError),
+impl AddressFilter {
+    fn matches(&self, info: &AddressInfo) -> bool {
+        (!self.only_tried || info.is_tried)
+            && (!self.only_new || !info.is_tried)
+            && self.group_prefix.as_ref().map_or(true, |prefix| info.network_group().starts_with(prefix.as_str()))
+    }
 }
 
 pub struct AddressManager {
     banned_address_store: DbBannedAddressesStore,
     address_store: address_store_with_cache::Store,
+    /// Addresses of peers which can never be connected, kept in memory for the lifetime of the node
+    incompatible_addresses: HashMap<AddressKey, DisconnectReason>,
+    manual_peer_store: DbManualPeersStore,
+    /// Copy of the persisted manual peers
+    manual_peers: HashMap<AddressKey, NetAddress>,
     config: Arc<Config>,
     local_net_addresses: Vec<NetAddress>,
 }
 
 impl AddressManager {
     pub fn new(config: Arc<Config>, db: Arc<DB>, tick_service: Arc<TickService>) -> (Arc<Mutex<Self>>, Option<Extender>) {
-        let mut instance = Self {
+        let manual_peer_store = DbManualPeersStore::new(db.clone(), CachePolicy::Empty);
+        let manual_peers = manual_peer_store.iterator().map(|res| res.unwrap()).map(|(key, entry)| (key, entry.address)).collect();
+        let instance = Self {
             banned_address_store: DbBannedAddressesStore::new(db.clone(), CachePolicy::Count(MAX_ADDRESSES)),
             address_store: address_store_with_cache::new(db),
+            incompatible_addresses: HashMap::new(),
+            manual_peer_store,
+            manual_peers,
             local_net_addresses: Vec::new(),
             config,
         };
 
-        let extender = instance.init_local_addresses(tick_service);
+        let (instance, mapping) = instance.init_local_addresses();
+        let instance = Arc::new(Mutex::new(instance));
+        let extender = mapping.map(|mapping| Extender::new(tick_service, PORT_MAPPING_RENEWAL_INTERVAL, mapping, instance.clone()));
 
-        (Arc::new(Mutex::new(instance)), extender)
+        (instance, extender)
     }
 
-    fn init_local_addresses(&mut self, tick_service: Arc<TickService>) -> Option<Extender> {
+    fn init_local_addresses(mut self) -> (Self, Option<PortMapping>) {
         self.local_net_addresses = self.local_addresses().collect();
 
-        let extender = if self.local_net_addresses.is_empty() && !self.config.disable_upnp {
-            let (net_address, ExtendHelper { gateway, local_addr, external_port }) = match self.upnp() {
+        let mapping = if self.local_net_addresses.is_empty() && !self.config.disable_upnp {
+            match self.port_mapping() {
+                Ok(Some(mapping)) => {
+                    self.local_net_addresses.push(mapping.external_address());
+                    Some(mapping)
+                }
+                Ok(None) => None,
                 Err(err) => {
-                    warn!("[UPnP] Error adding port mapping: {err}");
-                    return None;
+                    warn!("[UPnP] Error adding port mapping, the node will not be reachable from outside the local network unless the port is forwarded manually: {err}");
+                    None
                 }
-                Ok(None) => return None,
-                Ok(Some((net_address, extend_helper))) => (net_address, extend_helper),
-            };
-            self.local_net_addresses.push(net_address);
-
-            let gateway: igd_next::aio::Gateway<Tokio> = igd_next::aio::Gateway {
-                addr: gateway.addr,
-                root_url: gateway.root_url,
-                control_url: gateway.control_url,
-                control_schema_url: gateway.control_schema_url,
-                control_schema: gateway.control_schema,
-                provider: Tokio,
-            };
-            Some(Extender::new(
-                tick_service,
-                Duration::from_secs(UPNP_EXTEND_PERIOD),
-                UPNP_DEADLINE_SEC,
-                gateway,
-                external_port,
-                local_addr,
-            ))
+            }
         } else {
             None
         };
@@ -109,7 +177,7 @@ impl AddressManager {
         self.local_net_addresses.iter().for_each(|net_addr| {
             info!("Publicly routable local address {} added to store", net_addr);
         });
-        extender
+        (self, mapping)
     }
 
     fn local_addresses(&self) -> impl Iterator<Item = NetAddress> + '_ {
@@ -153,91 +221,30 @@ impl AddressManager {
         }
     }
 
-    fn upnp(&self) -> Result<Option<(NetAddress, ExtendHelper)>, UpnpError> {
-        info!("[UPnP] Attempting to register upnp... (to disable run the node with --disable-upnp)");
-        let gateway = igd::search_gateway(Default::default())?;
-        let ip = IpAddress::new(gateway.get_external_ip()?);
-        if !ip.is_publicly_routable() {
-            info!("[UPnP] Non-publicly routable external ip from gateway using upnp {} not added to store", ip);
-            return Ok(None);
-        }
-        info!("[UPnP] Got external ip from gateway using upnp: {ip}");
+    fn port_mapping(&self) -> Result<Option<PortMapping>, PortMappingError> {
+        info!("[UPnP] Attempting to map the P2P port with UPnP or NAT-PMP... (to disable run the node with --disable-upnp)");
+        let gateway = netutils::search_gateway()?;
 
         let normalized_p2p_listen_address = self.config.p2p_listen_address.normalize(self.config.default_p2p_port());
         let local_addr = if normalized_p2p_listen_address.ip.is_unspecified() {
-            SocketAddr::new(local_ip_address::local_ip().unwrap(), normalized_p2p_listen_address.port)
+            let local_ip = local_ip_address::local_ip().map_err(|err| PortMappingError::NoLocalIp(err.to_string()))?;
+            SocketAddr::new(local_ip, normalized_p2p_listen_address.port)
         } else {
             normalized_p2p_listen_address.into()
         };
 
-        // If an operator runs a node and specifies a non-standard local port, it implies that they also wish to use a non-standard public address. The variable 'desired_external_port' is set to the port number from the normalized peer-to-peer listening address.
-        let desired_external_port = normalized_p2p_listen_address.port;
-        // This loop checks for existing port mappings in the UPnP-enabled gateway.
-        //
-        // The goal of this loop is to identify if the desired external port (`desired_external_port`) is
-        // already mapped to any device inside the local network. This is crucial because, in
-        // certain scenarios, gateways might not throw the `PortInUse` error but rather might
-        // silently remap the external port when there's a conflict. By iterating through the
-        // current mappings, we can make an informed decision about whether to attempt using
-        // the default port or request a new random one.
-        //
-        // The loop goes through all existing port mappings one-by-one:
-        // - If a mapping is found that uses the desired external port, the loop breaks with `already_in_use` set to true.
-        // - If the index is not valid (i.e., we've iterated through all the mappings), the loop breaks with `already_in_use` set to false.
-        // - Any other errors during fetching of port mappings are handled accordingly, but the end result is to exit the loop with the `already_in_use` flag set appropriately.
-        let mut index = 0;
-        let already_in_use = loop {
-            match gateway.get_generic_port_mapping_entry(index) {
-                Ok(entry) => {
-                    if entry.enabled && entry.external_port == desired_external_port {
-                        info!("[UPnP] Found existing mapping that uses the same external port. Description: {}, external port: {}, internal port: {}, client: {}, lease duration: {}", entry.port_mapping_description, entry.external_port, entry.internal_port, entry.internal_client, entry.lease_duration);
-                        break true;
-                    }
-                    index += 1;
-                }
-                Err(GetGenericPortMappingEntryError::ActionNotAuthorized) => {
-                    index += 1;
-                    continue;
-                }
-                Err(GetGenericPortMappingEntryError::RequestError(err)) => {
-                    warn!("[UPnP] request existing port mapping err: {:?}", err);
-                    break false;
-                }
-                Err(GetGenericPortMappingEntryError::SpecifiedArrayIndexInvalid) => break false,
-            }
-        };
-        if already_in_use {
-            let port =
-                gateway.add_any_port(igd::PortMappingProtocol::TCP, local_addr, UPNP_DEADLINE_SEC as u32, UPNP_REGISTRATION_NAME)?;
-            info!("[UPnP] Added port mapping to random external port: {ip}:{port}");
-            return Ok(Some((NetAddress { ip, port }, ExtendHelper { gateway, local_addr, external_port: port })));
-        }
-
-        match gateway.add_port(
-            igd::PortMappingProtocol::TCP,
-            desired_external_port,
-            local_addr,
-            UPNP_DEADLINE_SEC as u32,
-            UPNP_REGISTRATION_NAME,
-        ) {
-            Ok(_) => {
-                info!("[UPnP] Added port mapping to default external port: {ip}:{desired_external_port}");
-                Ok(Some((
-                    NetAddress { ip, port: desired_external_port },
-                    ExtendHelper { gateway, local_addr, external_port: desired_external_port },
-                )))
-            }
-            Err(AddPortError::PortInUse {}) => {
-                let port = gateway.add_any_port(
-                    igd::PortMappingProtocol::TCP,
-                    local_addr,
-                    UPNP_DEADLINE_SEC as u32,
-                    UPNP_REGISTRATION_NAME,
-                )?;
-                info!("[UPnP] Added port mapping to random external port: {ip}:{port}");
-                Ok(Some((NetAddress { ip, port }, ExtendHelper { gateway, local_addr, external_port: port })))
-            }
-            Err(err) => Err(err.into()),
+        // If an operator runs a node and specifies a non-standard local port, it implies that they also wish to use a non-standard public address
+        PortMapping::map(gateway, normalized_p2p_listen_address.port, local_addr)
+    }
+
+    /// Replaces a local address previously advertised, e.g. after the external IP of the port mapping gateway changed.
+    /// The new address takes precedence over the other local addresses if it is publicly routable.
+    pub(crate) fn replace_local_address(&mut self, previous: NetAddress, current: NetAddress) {
+        self.local_net_addresses.retain(|&address| address != previous);
+        if current.ip.is_publicly_routable() {
+            self.local_net_addresses.insert(0, current);
+        } else {
+            info!("Non-publicly routable local address {} not added to store", current);
         }
     }
 
@@ -251,18 +258,59 @@ impl AddressManager {
         }
     }
 
-    pub fn add_address(&mut self, address: NetAddress) {
-        if address.ip.is_loopback() || address.ip.is_unspecified() {
+    pub fn add_address(&mut self, address: NetAddress, source: AddressSource) {
+        // Link-local addresses cannot be dialed without the scope id they lose as a `NetAddress`
+        if address.ip.is_loopback() || address.ip.is_unspecified() || address.ip.is_ipv6_link_local() {
             debug!("[Address manager] skipping local address {}", address.ip);
             return;
         }
 
+        if self.incompatible_addresses.contains_key(&address.into()) {
+            return;
+        }
+
         if self.address_store.has(address) {
+            self.address_store.mark_seen(address);
+            return;
+        }
+
+        if address.ip.is_onion() {
+            // The IPv6 encoding of an onion address cannot be dialed, onion addresses are added along with their host
+            debug!("[Address manager] skipping onion address {} of unknown host", address.ip);
             return;
         }
 
         // We mark `connection_failed_count` as 0 only after first success
-        self.address_store.set(address, 1);
+        self.address_store.insert(address, source);
+    }
+
+    /// Adds the onion address `host:port`, which is known by the IPv6 encoding of `host` (see [`OnionAddress::ipv6_encoding`])
+    /// in all other methods
+    pub fn add_onion_address(&mut self, host: OnionAddress, port: u16, source: AddressSource) {
+        let address = NetAddress::new(host.ipv6_encoding().into(), port);
+        if self.incompatible_addresses.contains_key(&address.into()) {
+            return;
+        }
+
+        if self.address_store.has(address) {
+            self.address_store.mark_seen(address);
+            return;
+        }
+
+        self.address_store.insert_onion(host, address, source);
+    }
+
+    /// Returns the onion host of `address` if it encodes a known onion address
+    pub fn onion_host(&self, address: NetAddress) -> Option<OnionAddress> {
+        self.address_store.onion_host(address.into())
+    }
+
+    /// Returns the `host:port` string to dial for reaching `address`, which is its onion host for onion addresses
+    pub fn dial_target(&self, address: NetAddress) -> String {
+        match self.onion_host(address) {
+            Some(host) => format!("{}:{}", host, address.port),
+            None => address.to_string(),
+        }
     }
 
     pub fn mark_connection_failure(&mut self, address: NetAddress) {
@@ -275,6 +323,7 @@ impl AddressManager {
             self.address_store.remove(address);
         } else {
             self.address_store.set(address, new_count);
+            self.address_store.record_attempt(address, ConnectionOutcome::Failure);
         }
     }
 
@@ -284,6 +333,25 @@ impl AddressManager {
         }
 
         self.address_store.set(address, 0);
+        self.address_store.record_attempt(address, ConnectionOutcome::Success);
+    }
+
+    /// Records that the peer at `address` is incompatible with this node, removing it from the
+    /// store and ignoring it when gossiped again
+    pub fn mark_incompatible(&mut self, address: NetAddress, reason: DisconnectReason) {
+        let key = address.into();
+        if self.incompatible_addresses.len() >= MAX_ADDRESSES && !self.incompatible_addresses.contains_key(&key) {
+            let evicted = *self.incompatible_addresses.keys().next().unwrap();
+            self.incompatible_addresses.remove(&evicted);
+        }
+        self.incompatible_addresses.insert(key, reason);
+        if self.address_store.has(address) {
+            self.address_store.remove(address);
+        }
+    }
+
+    pub fn disconnect_reason(&self, address: NetAddress) -> Option<DisconnectReason> {
+        self.incompatible_addresses.get(&address.into()).copied()
     }
 
     pub fn iterate_addresses(&self) -> impl Iterator<Item = NetAddress> + '_ {
@@ -322,9 +390,52 @@ impl AddressManager {
         self.address_store.iterate_addresses().collect_vec()
     }
 
+    pub fn get_address_info(&self, address: NetAddress) -> Option<AddressInfo> {
+        self.address_store.address_info(address.into())
+    }
+
+    /// Returns the known addresses matching `filter`, ordered from the least to the most failing
+    /// and then from the most to the least recently seen
+    pub fn get_address_infos(&self, filter: &AddressFilter) -> Vec<AddressInfo> {
+        let mut infos = self.address_store.iterate_address_infos().filter(|info| filter.matches(info)).collect_vec();
+        infos.sort_by_key(|info| (info.connection_failed_count, std::cmp::Reverse(info.last_seen)));
+        infos.truncate(filter.limit.unwrap_or(usize::MAX));
+        infos
+    }
+
     pub fn get_all_banned_addresses(&self) -> Vec<IpAddress> {
         self.banned_address_store.iterator().map(|x| IpAddress::from(x.unwrap().0)).collect_vec()
     }
+
+    /// Persists `address` as a manual peer, which the connection manager keeps connected across restarts.
+    /// Returns `false` if [`MAX_MANUAL_PEERS`] manual peers are already persisted.
+    pub fn add_manual_peer(&mut self, address: NetAddress) -> bool {
+        let key = address.into();
+        if !self.manual_peers.contains_key(&key) && self.manual_peers.len() >= MAX_MANUAL_PEERS {
+            return false;
+        }
+        self.manual_peer_store.set(key, ManualPeerEntry { address }).unwrap();
+        self.manual_peers.insert(key, address);
+        true
+    }
+
+    /// Removes `address` from the manual peers, returning whether it was one
+    pub fn remove_manual_peer(&mut self, address: NetAddress) -> bool {
+        let key = address.into();
+        if self.manual_peers.remove(&key).is_none() {
+            return false;
+        }
+        self.manual_peer_store.remove(key).unwrap();
+        true
+    }
+
+    pub fn is_manual_peer(&self, address: NetAddress) -> bool {
+        self.manual_peers.contains_key(&address.into())
+    }
+
+    pub fn get_manual_peers(&self) -> Vec<NetAddress> {
+        self.manual_peers.values().copied().collect_vec()
+    }
 }
 
 mod address_store_with_cache {
@@ -344,29 +455,93 @@ mod address_store_with_cache {
         prelude::Distribution,
     };
 
+    use kaspa_core::time::unix_now;
+
     use crate::{
         stores::{
             address_store::{AddressesStore, DbAddressesStore, Entry},
+            onion_address_store::{DbOnionAddressesStore, OnionAddressesStore, OnionEntry},
             AddressKey,
         },
-        NetAddress, MAX_ADDRESSES, MAX_CONNECTION_FAILED_COUNT,
+        AddressInfo, AddressSource, ConnectionOutcome, NetAddress, MAX_ADDRESSES, MAX_CONNECTION_FAILED_COUNT,
     };
+    use kaspa_utils::networking::OnionAddress;
+
+    /// The in-memory part of the quality data of an address, see [`AddressInfo`]
+    #[derive(Clone, Copy)]
+    struct Metadata {
+        source: AddressSource,
+        is_tried: bool,
+        last_seen: Option<u64>,
+        last_attempt: Option<(u64, ConnectionOutcome)>,
+    }
+
+    impl Metadata {
+        fn restored(entry: &Entry) -> Self {
+            Self { source: AddressSource::Unknown, is_tried: entry.connection_failed_count == 0, last_seen: None, last_attempt: None }
+        }
+    }
 
     pub struct Store {
         db_store: DbAddressesStore,
         addresses: HashMap<AddressKey, Entry>,
+        metadata: HashMap<AddressKey, Metadata>,
+        onion_db_store: DbOnionAddressesStore,
+        /// The hosts of the onion addresses, whose entries only hold their IPv6 encoding
+        onion_hosts: HashMap<AddressKey, OnionAddress>,
     }
 
     impl Store {
         fn new(db: Arc<DB>) -> Self {
             // We manage the cache ourselves on this level, so we disable the inner builtin cache
-            let db_store = DbAddressesStore::new(db, CachePolicy::Empty);
+            let mut db_store = DbAddressesStore::new(db.clone(), CachePolicy::Empty);
+            let mut onion_db_store = DbOnionAddressesStore::new(db, CachePolicy::Empty);
             let mut addresses = HashMap::new();
+            let mut metadata = HashMap::new();
             for (key, entry) in db_store.iterator().map(|res| res.unwrap()) {
+                metadata.insert(key, Metadata::restored(&entry));
                 addresses.insert(key, entry);
             }
+            let mut onion_hosts: HashMap<_, _> =
+                onion_db_store.iterator().map(|res| res.unwrap()).map(|(key, OnionEntry { host })| (key, host)).collect();
+
+            // Onion range addresses gossiped to former versions have no host, and an interrupted removal may leave a host behind
+            let hostless_addresses = addresses
+                .iter()
+                .filter(|(key, entry)| entry.address.ip.is_onion() && !onion_hosts.contains_key(*key))
+                .map(|(key, _)| *key)
+                .collect_vec();
+            for key in hostless_addresses {
+                addresses.remove(&key);
+                metadata.remove(&key);
+                db_store.remove(key).unwrap();
+            }
+            let orphan_hosts = onion_hosts.keys().filter(|key| !addresses.contains_key(*key)).copied().collect_vec();
+            for key in orphan_hosts {
+                onion_hosts.remove(&key);
+                onion_db_store.remove(key).unwrap();
+            }
+
+            Self { db_store, addresses, metadata, onion_db_store, onion_hosts }
+        }
 
-            Self { db_store, addresses }
+        /// Adds a new address, expecting it not to be known yet
+        pub fn insert(&mut self, address: NetAddress, source: AddressSource) {
+            let metadata = Metadata { source, is_tried: false, last_seen: Some(unix_now()), last_attempt: None };
+            self.metadata.insert(address.into(), metadata);
+            self.set(address, 1);
+        }
+
+        /// Adds a new onion address, `address` being the IPv6 encoding of `host`
+        pub fn insert_onion(&mut self, host: OnionAddress, address: NetAddress, source: AddressSource) {
+            // The host is written first, so an address entry never lacks its host
+            self.onion_db_store.set(address.into(), OnionEntry { host }).unwrap();
+            self.onion_hosts.insert(address.into(), host);
+            self.insert(address, source);
+        }
+
+        pub fn onion_host(&self, key: AddressKey) -> Option<OnionAddress> {
+            self.onion_hosts.get(&key).copied()
         }
 
         pub fn has(&mut self, address: NetAddress) -> bool {
@@ -380,9 +555,40 @@ mod address_store_with_cache {
             };
             self.db_store.set(address.into(), entry).unwrap();
             self.addresses.insert(address.into(), entry);
+            let metadata = self.metadata.entry(address.into()).or_insert_with(|| Metadata::restored(&entry));
+            metadata.is_tried |= connection_failed_count == 0;
             self.keep_limit();
         }
 
+        pub fn mark_seen(&mut self, address: NetAddress) {
+            if let Some(metadata) = self.metadata.get_mut(&address.into()) {
+                metadata.last_seen = Some(unix_now());
+            }
+        }
+
+        pub fn record_attempt(&mut self, address: NetAddress, outcome: ConnectionOutcome) {
+            if let Some(metadata) = self.metadata.get_mut(&address.into()) {
+                metadata.last_attempt = Some((unix_now(), outcome));
+            }
+        }
+
+        pub fn address_info(&self, key: AddressKey) -> Option<AddressInfo> {
+            let entry = self.addresses.get(&key)?;
+            let metadata = self.metadata.get(&key).copied().unwrap_or_else(|| Metadata::restored(entry));
+            Some(AddressInfo {
+                address: entry.address,
+                source: metadata.source,
+                connection_failed_count: entry.connection_failed_count,
+                is_tried: metadata.is_tried,
+                last_seen: metadata.last_seen,
+                last_attempt: metadata.last_attempt,
+            })
+        }
+
+        pub fn iterate_address_infos(&self) -> impl Iterator<Item = AddressInfo> + '_ {
+            self.addresses.keys().filter_map(|key| self.address_info(*key))
+        }
+
         fn keep_limit(&mut self) {
             while self.addresses.len() > MAX_ADDRESSES {
                 let to_remove =
@@ -401,7 +607,11 @@ mod address_store_with_cache {
 
         fn remove_by_key(&mut self, key: AddressKey) {
             self.addresses.remove(&key);
-            self.db_store.remove(key).unwrap()
+            self.metadata.remove(&key);
+            self.db_store.remove(key).unwrap();
+            if self.onion_hosts.remove(&key).is_some() {
+                self.onion_db_store.remove(key).unwrap();
+            }
         }
 
         pub fn iterate_addresses(&self) -> impl Iterator<Item = NetAddress> + '_ {
@@ -514,7 +724,9 @@ mod address_store_with_cache {
         use std::str::FromStr;
 
         use super::*;
-        use address_manager::AddressManager;
+        use address_manager::{
+            AddressFilter, AddressManager, AddressSource, ConnectionOutcome, DisconnectReason, MAX_MANUAL_PEERS, ONION_NETWORK_GROUP,
+        };
         use kaspa_consensus_core::config::{params::SIMNET_PARAMS, Config};
         use kaspa_core::task::tick::TickService;
         use kaspa_database::create_temp_db;
@@ -536,6 +748,186 @@ mod address_store_with_cache {
             assert_eq!(iter.count(), 0);
         }
 
+        #[test]
+        fn test_incompatible_addresses() {
+            let db = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+            let (am, _) = AddressManager::new(Arc::new(Config::new(SIMNET_PARAMS)), db.1, Arc::new(TickService::default()));
+            let mut am = am.lock();
+            let address = NetAddress::new(IpAddress::from_str("10.0.0.1").unwrap(), 16111);
+            let other = NetAddress::new(IpAddress::from_str("10.0.0.2").unwrap(), 16111);
+            am.add_address(address, AddressSource::PeerGossip);
+            am.add_address(other, AddressSource::PeerGossip);
+
+            am.mark_incompatible(address, DisconnectReason::GenesisMismatch);
+            assert_eq!(am.disconnect_reason(address), Some(DisconnectReason::GenesisMismatch));
+            assert_eq!(am.disconnect_reason(other), None);
+            assert_eq!(am.get_all_addresses(), vec![other]);
+
+            // The address is not redialed when gossiped again
+            am.add_address(address, AddressSource::PeerGossip);
+            assert_eq!(am.get_all_addresses(), vec![other]);
+        }
+
+        #[test]
+        fn test_address_infos() {
+            let db = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+            let config = Arc::new(Config::new(SIMNET_PARAMS));
+            let (am, _) = AddressManager::new(config.clone(), db.1.clone(), Arc::new(TickService::default()));
+            let mut am = am.lock();
+            let seeded = NetAddress::new(IpAddress::from_str("10.0.0.1").unwrap(), 16111);
+            let gossiped = NetAddress::new(IpAddress::from_str("10.1.0.1").unwrap(), 16111);
+            let other_gossiped = NetAddress::new(IpAddress::from_str("10.0.5.5").unwrap(), 16111);
+            let manual = NetAddress::new(IpAddress::from_str("2001:db8::1").unwrap(), 16111);
+            am.add_address(seeded, AddressSource::DnsSeed);
+            am.add_address(gossiped, AddressSource::PeerGossip);
+            am.add_address(other_gossiped, AddressSource::PeerGossip);
+            am.add_address(manual, AddressSource::Manual);
+            am.mark_connection_success(seeded);
+            am.mark_connection_failure(gossiped);
+
+            let info = am.get_address_info(seeded).unwrap();
+            assert_eq!((info.source, info.connection_failed_count, info.is_tried), (AddressSource::DnsSeed, 0, true));
+            assert_eq!(info.last_attempt.map(|(_, outcome)| outcome), Some(ConnectionOutcome::Success));
+            assert_eq!(info.network_group(), "10.0");
+            let info = am.get_address_info(gossiped).unwrap();
+            assert_eq!((info.source, info.connection_failed_count, info.is_tried), (AddressSource::PeerGossip, 2, false));
+            assert_eq!(info.last_attempt.map(|(_, outcome)| outcome), Some(ConnectionOutcome::Failure));
+            let info = am.get_address_info(manual).unwrap();
+            assert_eq!((info.source, info.is_tried, info.last_attempt), (AddressSource::Manual, false, None));
+            assert!(info.last_seen.is_some());
+            assert_eq!(info.network_group(), "2001:db8:0:0");
+
+            let addresses = |filter: AddressFilter| am.get_address_infos(&filter).into_iter().map(|info| info.address).collect_vec();
+            assert_eq!(addresses(AddressFilter { only_tried: true, ..Default::default() }), vec![seeded]);
+            let mut new_addresses = addresses(AddressFilter { only_new: true, ..Default::default() });
+            new_addresses.sort_by_key(|address| address.to_string());
+            assert_eq!(new_addresses, vec![other_gossiped, gossiped, manual]);
+            let mut group = addresses(AddressFilter { group_prefix: Some("10.0".to_string()), ..Default::default() });
+            group.sort_by_key(|address| address.to_string());
+            assert_eq!(group, vec![seeded, other_gossiped]);
+            assert_eq!(addresses(AddressFilter { group_prefix: Some("2001:db8".to_string()), ..Default::default() }), vec![manual]);
+            assert_eq!(
+                addresses(AddressFilter { only_new: true, group_prefix: Some("10.0".to_string()), ..Default::default() }),
+                vec![other_gossiped]
+            );
+            // The most reliable addresses come first
+            assert_eq!(addresses(AddressFilter { limit: Some(1), ..Default::default() }), vec![seeded]);
+            assert_eq!(addresses(AddressFilter::default()).last(), Some(&gossiped));
+            drop(am);
+
+            // Only the connection failure counter survives a restart
+            let (am, _) = AddressManager::new(config, db.1, Arc::new(TickService::default()));
+            let am = am.lock();
+            let info = am.get_address_info(seeded).unwrap();
+            assert_eq!((info.source, info.is_tried, info.last_seen, info.last_attempt), (AddressSource::Unknown, true, None, None));
+            let info = am.get_address_info(gossiped).unwrap();
+            assert_eq!((info.source, info.connection_failed_count, info.is_tried), (AddressSource::Unknown, 2, false));
+        }
+
+        #[test]
+        fn test_onion_addresses() {
+            let db = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+            let config = Arc::new(Config::new(SIMNET_PARAMS));
+            let (am, _) = AddressManager::new(config.clone(), db.1.clone(), Arc::new(TickService::default()));
+            let mut am = am.lock();
+            let host = OnionAddress::from_str("2gzyxa5ihm7nsggfxnu52rck2vv4rvmdlkiu3zzui5du4xyclen53wid.onion").unwrap();
+            let address = NetAddress::new(host.ipv6_encoding().into(), 16111);
+            am.add_onion_address(host, 16111, AddressSource::PeerGossip);
+            assert_eq!(am.get_all_addresses(), vec![address]);
+            assert_eq!(am.onion_host(address), Some(host));
+            assert_eq!(am.dial_target(address), format!("{host}:16111"));
+            assert_eq!(am.get_address_info(address).unwrap().network_group(), ONION_NETWORK_GROUP);
+
+            // Onion range addresses of unknown host and link-local addresses are not added
+            am.add_address(NetAddress::new(IpAddress::from_str("fd87:d87e:eb43::1").unwrap(), 16111), AddressSource::PeerGossip);
+            am.add_address(NetAddress::new(IpAddress::from_str("fe80::1").unwrap(), 16111), AddressSource::PeerGossip);
+            assert_eq!(am.get_all_addresses(), vec![address]);
+            let ip_address = NetAddress::new(IpAddress::from_str("10.0.0.1").unwrap(), 16111);
+            am.add_address(ip_address, AddressSource::PeerGossip);
+            assert_eq!(am.dial_target(ip_address), "10.0.0.1:16111");
+            drop(am);
+
+            // Onion entries survive a restart
+            let (am, _) = AddressManager::new(config.clone(), db.1.clone(), Arc::new(TickService::default()));
+            let mut am = am.lock();
+            assert_eq!(am.onion_host(address), Some(host));
+            assert_eq!(am.get_all_addresses().len(), 2);
+
+            // Removing the address removes its host as well
+            for _ in 0..=MAX_CONNECTION_FAILED_COUNT {
+                am.mark_connection_failure(address);
+            }
+            assert_eq!(am.onion_host(address), None);
+            assert_eq!(am.get_all_addresses(), vec![ip_address]);
+            drop(am);
+
+            let (am, _) = AddressManager::new(config, db.1, Arc::new(TickService::default()));
+            let am = am.lock();
+            assert_eq!(am.onion_host(address), None);
+            assert_eq!(am.get_all_addresses(), vec![ip_address]);
+        }
+
+        #[test]
+        fn test_manual_peers() {
+            let db = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+            let config = Arc::new(Config::new(SIMNET_PARAMS));
+            let (am, _) = AddressManager::new(config.clone(), db.1.clone(), Arc::new(TickService::default()));
+            let mut am = am.lock();
+            let ipv4 = NetAddress::new(IpAddress::from_str("127.0.0.1").unwrap(), 16111);
+            let ipv6 = NetAddress::new(IpAddress::from_str("2001:db8::1").unwrap(), 16112);
+            assert!(am.add_manual_peer(ipv4));
+            assert!(am.add_manual_peer(ipv6));
+            assert!(am.add_manual_peer(ipv4), "adding a manual peer again is a no-op");
+            // Manual peers are kept apart from the known addresses
+            assert!(am.get_all_addresses().is_empty());
+            drop(am);
+
+            // Manual peers survive a restart
+            let (am, _) = AddressManager::new(config.clone(), db.1.clone(), Arc::new(TickService::default()));
+            let mut am = am.lock();
+            let mut manual_peers = am.get_manual_peers();
+            manual_peers.sort_by_key(|address| address.to_string());
+            assert_eq!(manual_peers, vec![ipv4, ipv6]);
+            assert!(am.is_manual_peer(ipv4));
+
+            assert!(am.remove_manual_peer(ipv4));
+            assert!(!am.remove_manual_peer(ipv4));
+            assert!(!am.is_manual_peer(ipv4));
+
+            // The number of manual peers is capped
+            for port in 0..MAX_MANUAL_PEERS as u16 - 1 {
+                assert!(am.add_manual_peer(NetAddress::new(IpAddress::from_str("10.0.0.1").unwrap(), port)));
+            }
+            assert!(!am.add_manual_peer(ipv4));
+            assert!(am.add_manual_peer(ipv6), "an existing manual peer can be added again at the cap");
+            drop(am);
+
+            let (am, _) = AddressManager::new(config, db.1, Arc::new(TickService::default()));
+            let am = am.lock();
+            assert_eq!(am.get_manual_peers().len(), MAX_MANUAL_PEERS);
+            assert!(!am.is_manual_peer(ipv4));
+        }
+
+        #[test]
+        fn test_replace_local_address() {
+            let db = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+            let mut config = Config::new(SIMNET_PARAMS);
+            config.externalip = Some(NetAddress::from_str("1.2.3.4:16111").unwrap());
+            config.disable_upnp = true;
+            let (am, extender) = AddressManager::new(Arc::new(config), db.1, Arc::new(TickService::default()));
+            assert!(extender.is_none());
+            let mut am = am.lock();
+            assert_eq!(am.best_local_address(), Some(NetAddress::from_str("1.2.3.4:16111").unwrap()));
+
+            // The external IP of the gateway changed
+            am.replace_local_address(NetAddress::from_str("1.2.3.4:16111").unwrap(), NetAddress::from_str("5.6.7.8:16111").unwrap());
+            assert_eq!(am.best_local_address(), Some(NetAddress::from_str("5.6.7.8:16111").unwrap()));
+
+            // A non-publicly routable address is not advertised
+            am.replace_local_address(NetAddress::from_str("5.6.7.8:16111").unwrap(), NetAddress::from_str("10.0.0.1:16111").unwrap());
+            assert_eq!(am.best_local_address(), None);
+        }
+
         #[test]
         fn test_network_distribution_weighting() {
             kaspa_core::log::try_init_logger("info");
@@ -562,14 +954,17 @@ mod address_store_with_cache {
                 for current_suffix_bytes in 0..current_bucket_size {
                     let current_ip_bytes =
                         [current_prefix_bytes.to_be_bytes(), current_suffix_bytes.to_be_bytes()].concat().to_owned();
-                    am_guard.add_address(NetAddress::new(
-                        IpAddress::from_str(&format!(
-                            "{0}.{1}.{2}.{3}",
-                            current_ip_bytes[0], current_ip_bytes[1], current_ip_bytes[2], current_ip_bytes[3]
-                        ))
-                        .unwrap(),
-                        12411,
-                    ));
+                    am_guard.add_address(
+                        NetAddress::new(
+                            IpAddress::from_str(&format!(
+                                "{0}.{1}.{2}.{3}",
+                                current_ip_bytes[0], current_ip_bytes[1], current_ip_bytes[2], current_ip_bytes[3]
+                            ))
+                            .unwrap(),
+                            12411,
+                        ),
+                        AddressSource::PeerGossip,
+                    );
                     num_of_addresses += 1;
                 }
 
@@ -0,0 +1,335 @@
+//! Port mapping of the P2P listen port on the NAT gateway of the local network, so that nodes
+//! without a publicly routable address can still accept inbound connections.
+//!
+//! The gateway is first searched with UPnP and, if none answers, the default gateway is queried
+//! with NAT-PMP. Both protocols are abstracted by [`PortMappingGateway`], which lets the mapping
+//! and renewal logic of [`PortMapping`] be tested against a mock gateway.
+
+mod natpmp;
+mod upnp;
+
+pub use natpmp::NatPmpGateway;
+pub use upnp::UpnpGateway;
+
+use igd_next::{AddAnyPortError, AddPortError, GetExternalIpError, RemovePortError, SearchError};
+use kaspa_core::{debug, info};
+use kaspa_utils::networking::{IpAddress, NetAddress};
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+use thiserror::Error;
+
+/// The lease requested for port mappings
+pub const PORT_MAPPING_LEASE: Duration = Duration::from_secs(2 * 60);
+
+/// The interval at which port mappings are renewed, well within their lease
+pub const PORT_MAPPING_RENEWAL_INTERVAL: Duration = Duration::from_secs(PORT_MAPPING_LEASE.as_secs() / 2);
+
+/// The name used as description when registering a port mapping
+pub(crate) const PORT_MAPPING_DESCRIPTION: &str = "rusty-kaspa";
+
+#[derive(Error, Debug)]
+pub enum PortMappingError {
+    #[error("the external port is already mapped to another client")]
+    PortInUse,
+
+    #[error("the local IP address could not be determined: {0}")]
+    NoLocalIp(String),
+
+    #[error(transparent)]
+    UpnpSearch(#[from] SearchError),
+
+    #[error(transparent)]
+    UpnpAddPort(AddPortError),
+
+    #[error(transparent)]
+    UpnpAddAnyPort(#[from] AddAnyPortError),
+
+    #[error(transparent)]
+    UpnpGetExternalIp(#[from] GetExternalIpError),
+
+    #[error(transparent)]
+    UpnpRemovePort(#[from] RemovePortError),
+
+    #[error("NAT-PMP gateway {0} did not respond")]
+    NatPmpTimeout(SocketAddr),
+
+    #[error("NAT-PMP gateway {0} answered with result code {1}")]
+    NatPmpResultCode(SocketAddr, u16),
+
+    #[error("NAT-PMP gateway {0} sent a malformed response")]
+    NatPmpMalformedResponse(SocketAddr),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+impl From<AddPortError> for PortMappingError {
+    fn from(err: AddPortError) -> Self {
+        match err {
+            AddPortError::PortInUse => PortMappingError::PortInUse,
+            err => PortMappingError::UpnpAddPort(err),
+        }
+    }
+}
+
+/// A NAT gateway able to map external TCP ports to local addresses
+pub trait PortMappingGateway: Send + Sync {
+    /// The name of the gateway protocol, used in logs
+    fn protocol(&self) -> &'static str;
+
+    fn external_ip(&self) -> Result<IpAddr, PortMappingError>;
+
+    /// Maps `external_port` to `local_addr`, or renews the mapping if it already exists, and returns the external port
+    /// actually mapped. Fails with [`PortMappingError::PortInUse`] if the port is mapped to another client.
+    fn add_port(&self, external_port: u16, local_addr: SocketAddr, lease: Duration) -> Result<u16, PortMappingError>;
+
+    /// Maps any available external port to `local_addr` and returns it
+    fn add_any_port(&self, local_addr: SocketAddr, lease: Duration) -> Result<u16, PortMappingError>;
+
+    fn remove_port(&self, external_port: u16, local_addr: SocketAddr) -> Result<(), PortMappingError>;
+}
+
+/// Searches the local network for a gateway supporting port mapping, with UPnP first and then with NAT-PMP
+pub fn search_gateway() -> Result<Arc<dyn PortMappingGateway>, PortMappingError> {
+    match UpnpGateway::search() {
+        Ok(gateway) => Ok(Arc::new(gateway)),
+        Err(err) => match NatPmpGateway::default_gateway() {
+            Some(gateway) => {
+                debug!("[UPnP] No gateway found ({err}), falling back to NAT-PMP");
+                Ok(Arc::new(gateway))
+            }
+            None => Err(err),
+        },
+    }
+}
+
+/// A mapping of an external port of a gateway to the local P2P listen address
+pub struct PortMapping {
+    gateway: Arc<dyn PortMappingGateway>,
+    local_addr: SocketAddr,
+    /// The address the node is reachable at from outside the local network
+    external_address: NetAddress,
+}
+
+impl PortMapping {
+    /// Maps `desired_external_port` to `local_addr`, or any external port if the desired one is already in use.
+    ///
+    /// Returns `None` without mapping anything if the external IP of the gateway is not publicly routable, e.g.
+    /// when the gateway is itself behind another NAT.
+    pub fn map(
+        gateway: Arc<dyn PortMappingGateway>,
+        desired_external_port: u16,
+        local_addr: SocketAddr,
+    ) -> Result<Option<Self>, PortMappingError> {
+        let protocol = gateway.protocol();
+        let ip = IpAddress::new(gateway.external_ip()?);
+        if !ip.is_publicly_routable() {
+            info!("[{protocol}] Non-publicly routable external ip from gateway {} not added to store", ip);
+            return Ok(None);
+        }
+        info!("[{protocol}] Got external ip from gateway: {ip}");
+
+        let port = match gateway.add_port(desired_external_port, local_addr, PORT_MAPPING_LEASE) {
+            Ok(port) if port == desired_external_port => {
+                info!("[{protocol}] Added port mapping to default external port: {ip}:{port}");
+                port
+            }
+            Ok(port) => {
+                info!("[{protocol}] Gateway mapped another external port than requested: {ip}:{port}");
+                port
+            }
+            Err(PortMappingError::PortInUse) => {
+                let port = gateway.add_any_port(local_addr, PORT_MAPPING_LEASE)?;
+                info!("[{protocol}] Added port mapping to random external port: {ip}:{port}");
+                port
+            }
+            Err(err) => return Err(err),
+        };
+        Ok(Some(Self { gateway, local_addr, external_address: NetAddress::new(ip, port) }))
+    }
+
+    pub fn protocol(&self) -> &'static str {
+        self.gateway.protocol()
+    }
+
+    pub fn external_address(&self) -> NetAddress {
+        self.external_address
+    }
+
+    /// Renews the lease of the mapping and checks the external IP of the gateway.
+    ///
+    /// Returns the new external address if it changed, either because the gateway was attributed another IP or
+    /// because the external port had to be mapped again, e.g. after a reboot of the gateway dropped the mapping and
+    /// another client took the port over.
+    pub fn renew(&mut self) -> Result<Option<NetAddress>, PortMappingError> {
+        let port = match self.gateway.add_port(self.external_address.port, self.local_addr, PORT_MAPPING_LEASE) {
+            Ok(port) => port,
+            Err(PortMappingError::PortInUse) => self.gateway.add_any_port(self.local_addr, PORT_MAPPING_LEASE)?,
+            Err(err) => return Err(err),
+        };
+        let address = NetAddress::new(IpAddress::new(self.gateway.external_ip()?), port);
+        if address == self.external_address {
+            return Ok(None);
+        }
+        self.external_address = address;
+        Ok(Some(address))
+    }
+
+    pub fn remove(&self) -> Result<(), PortMappingError> {
+        self.gateway.remove_port(self.external_address.port, self.local_addr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use std::{collections::HashMap, str::FromStr};
+
+    struct MockState {
+        external_ip: IpAddr,
+        mappings: HashMap<u16, SocketAddr>,
+        next_any_port: u16,
+        unavailable: bool,
+    }
+
+    /// A gateway keeping its mappings in memory, ignoring leases
+    struct MockGateway {
+        state: Mutex<MockState>,
+    }
+
+    impl MockGateway {
+        fn new(external_ip: &str) -> Arc<Self> {
+            Arc::new(Self {
+                state: Mutex::new(MockState {
+                    external_ip: IpAddr::from_str(external_ip).unwrap(),
+                    mappings: HashMap::new(),
+                    next_any_port: 50000,
+                    unavailable: false,
+                }),
+            })
+        }
+
+        fn check_available(state: &MockState) -> Result<(), PortMappingError> {
+            match state.unavailable {
+                true => Err(std::io::Error::from(std::io::ErrorKind::TimedOut).into()),
+                false => Ok(()),
+            }
+        }
+    }
+
+    impl PortMappingGateway for MockGateway {
+        fn protocol(&self) -> &'static str {
+            "mock"
+        }
+
+        fn external_ip(&self) -> Result<IpAddr, PortMappingError> {
+            let state = self.state.lock();
+            Self::check_available(&state)?;
+            Ok(state.external_ip)
+        }
+
+        fn add_port(&self, external_port: u16, local_addr: SocketAddr, _lease: Duration) -> Result<u16, PortMappingError> {
+            let mut state = self.state.lock();
+            Self::check_available(&state)?;
+            match state.mappings.get(&external_port) {
+                Some(&client) if client != local_addr => Err(PortMappingError::PortInUse),
+                _ => {
+                    state.mappings.insert(external_port, local_addr);
+                    Ok(external_port)
+                }
+            }
+        }
+
+        fn add_any_port(&self, local_addr: SocketAddr, _lease: Duration) -> Result<u16, PortMappingError> {
+            let mut state = self.state.lock();
+            Self::check_available(&state)?;
+            while state.mappings.contains_key(&state.next_any_port) {
+                state.next_any_port += 1;
+            }
+            let port = state.next_any_port;
+            state.mappings.insert(port, local_addr);
+            Ok(port)
+        }
+
+        fn remove_port(&self, external_port: u16, local_addr: SocketAddr) -> Result<(), PortMappingError> {
+            let mut state = self.state.lock();
+            Self::check_available(&state)?;
+            if state.mappings.get(&external_port) == Some(&local_addr) {
+                state.mappings.remove(&external_port);
+            }
+            Ok(())
+        }
+    }
+
+    fn local_addr() -> SocketAddr {
+        SocketAddr::from_str("192.168.1.10:16111").unwrap()
+    }
+
+    #[test]
+    fn test_port_mapping_map() {
+        let gateway = MockGateway::new("1.2.3.4");
+        let mapping = PortMapping::map(gateway.clone(), 16111, local_addr()).unwrap().unwrap();
+        assert_eq!(mapping.external_address(), NetAddress::from_str("1.2.3.4:16111").unwrap());
+        assert_eq!(gateway.state.lock().mappings.get(&16111), Some(&local_addr()));
+
+        // The desired port is mapped to another client of the local network, so any port is mapped instead
+        let other_client = SocketAddr::from_str("192.168.1.11:16111").unwrap();
+        let other_mapping = PortMapping::map(gateway.clone(), 16111, other_client).unwrap().unwrap();
+        assert_eq!(other_mapping.external_address(), NetAddress::from_str("1.2.3.4:50000").unwrap());
+
+        other_mapping.remove().unwrap();
+        mapping.remove().unwrap();
+        assert!(gateway.state.lock().mappings.is_empty());
+
+        // Mapping a port is pointless if the gateway is itself behind a NAT
+        let gateway = MockGateway::new("100.64.0.1");
+        assert!(PortMapping::map(gateway.clone(), 16111, local_addr()).unwrap().is_none());
+        assert!(gateway.state.lock().mappings.is_empty());
+
+        let gateway = MockGateway::new("1.2.3.4");
+        gateway.state.lock().unavailable = true;
+        assert!(PortMapping::map(gateway, 16111, local_addr()).is_err());
+    }
+
+    #[test]
+    fn test_port_mapping_renewal() {
+        let gateway = MockGateway::new("1.2.3.4");
+        let mut mapping = PortMapping::map(gateway.clone(), 16111, local_addr()).unwrap().unwrap();
+        assert!(mapping.renew().unwrap().is_none());
+        assert!(mapping.renew().unwrap().is_none());
+        assert_eq!(gateway.state.lock().mappings.len(), 1);
+
+        // A failed renewal leaves the mapping as is, to be renewed on next attempt
+        gateway.state.lock().unavailable = true;
+        assert!(mapping.renew().is_err());
+        gateway.state.lock().unavailable = false;
+        assert!(mapping.renew().unwrap().is_none());
+
+        // The gateway rebooted, dropping the mapping, and another client took the port over
+        let other_client = SocketAddr::from_str("192.168.1.11:16111").unwrap();
+        gateway.state.lock().mappings.clear();
+        gateway.state.lock().mappings.insert(16111, other_client);
+        assert_eq!(mapping.renew().unwrap(), Some(NetAddress::from_str("1.2.3.4:50000").unwrap()));
+        assert_eq!(mapping.external_address(), NetAddress::from_str("1.2.3.4:50000").unwrap());
+        assert!(mapping.renew().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_port_mapping_external_ip_change() {
+        let gateway = MockGateway::new("1.2.3.4");
+        let mut mapping = PortMapping::map(gateway.clone(), 16111, local_addr()).unwrap().unwrap();
+
+        gateway.state.lock().external_ip = IpAddr::from_str("5.6.7.8").unwrap();
+        assert_eq!(mapping.renew().unwrap(), Some(NetAddress::from_str("5.6.7.8:16111").unwrap()));
+        assert_eq!(mapping.external_address(), NetAddress::from_str("5.6.7.8:16111").unwrap());
+        assert!(mapping.renew().unwrap().is_none());
+
+        // A change to a non-publicly routable IP is reported as well, so that the address stops being advertised
+        gateway.state.lock().external_ip = IpAddr::from_str("10.0.0.1").unwrap();
+        assert_eq!(mapping.renew().unwrap(), Some(NetAddress::from_str("10.0.0.1:16111").unwrap()));
+    }
+}
@@ -0,0 +1,216 @@
+//! A minimal NAT-PMP client, see [RFC 6886](https://datatracker.ietf.org/doc/html/rfc6886)
+
+use super::{PortMappingError, PortMappingGateway};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket},
+    time::Duration,
+};
+
+const NAT_PMP_PORT: u16 = 5351;
+const NAT_PMP_VERSION: u8 = 0;
+const OP_EXTERNAL_ADDRESS: u8 = 0;
+const OP_MAP_TCP: u8 = 2;
+/// Added by the gateway to the opcode of a request to form the opcode of its response
+const OP_RESPONSE: u8 = 128;
+const RESULT_SUCCESS: u16 = 0;
+
+const INITIAL_TIMEOUT: Duration = Duration::from_millis(250);
+/// Number of times a request is sent, the timeout doubling after each attempt
+const MAX_ATTEMPTS: usize = 3;
+
+/// A gateway speaking NAT-PMP, usually the default gateway of the host
+pub struct NatPmpGateway {
+    addr: SocketAddr,
+    initial_timeout: Duration,
+}
+
+impl NatPmpGateway {
+    pub fn new(addr: SocketAddr) -> Self {
+        Self { addr, initial_timeout: INITIAL_TIMEOUT }
+    }
+
+    /// Returns the default IPv4 gateway of the host, if it can be determined on this platform
+    pub fn default_gateway() -> Option<Self> {
+        #[cfg(target_os = "linux")]
+        {
+            let routes = std::fs::read_to_string("/proc/net/route").ok()?;
+            parse_default_gateway(&routes).map(|ip| Self::new(SocketAddr::new(ip.into(), NAT_PMP_PORT)))
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            None
+        }
+    }
+
+    /// Sends `request` until the gateway answers with a response of `response_len` bytes to opcode `op`, and returns
+    /// the response stripped from its header, i.e. starting after the seconds since start of epoch field
+    fn request(&self, op: u8, request: &[u8], response_len: usize) -> Result<Vec<u8>, PortMappingError> {
+        let socket = UdpSocket::bind(SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0))?;
+        socket.connect(self.addr)?;
+        let mut buffer = [0u8; 16];
+        let mut timeout = self.initial_timeout;
+        for _ in 0..MAX_ATTEMPTS {
+            socket.send(request)?;
+            socket.set_read_timeout(Some(timeout))?;
+            match socket.recv(&mut buffer) {
+                Ok(len) => {
+                    if len < 4 || buffer[0] != NAT_PMP_VERSION || buffer[1] != op + OP_RESPONSE {
+                        return Err(PortMappingError::NatPmpMalformedResponse(self.addr));
+                    }
+                    let result_code = u16::from_be_bytes([buffer[2], buffer[3]]);
+                    if result_code != RESULT_SUCCESS {
+                        return Err(PortMappingError::NatPmpResultCode(self.addr, result_code));
+                    }
+                    if len != response_len {
+                        return Err(PortMappingError::NatPmpMalformedResponse(self.addr));
+                    }
+                    return Ok(buffer[8..len].to_vec());
+                }
+                Err(err) if matches!(err.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => timeout *= 2,
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Err(PortMappingError::NatPmpTimeout(self.addr))
+    }
+
+    /// Requests a mapping of `external_port` (or any port if 0) to `internal_port` of the host for `lease`, a lease of
+    /// zero removing the mapping, and returns the external port mapped by the gateway
+    fn map(&self, internal_port: u16, external_port: u16, lease: Duration) -> Result<u16, PortMappingError> {
+        let mut request = [0u8; 12];
+        request[0] = NAT_PMP_VERSION;
+        request[1] = OP_MAP_TCP;
+        request[4..6].copy_from_slice(&internal_port.to_be_bytes());
+        request[6..8].copy_from_slice(&external_port.to_be_bytes());
+        request[8..12].copy_from_slice(&(lease.as_secs() as u32).to_be_bytes());
+        let response = self.request(OP_MAP_TCP, &request, 16)?;
+        if u16::from_be_bytes([response[0], response[1]]) != internal_port {
+            return Err(PortMappingError::NatPmpMalformedResponse(self.addr));
+        }
+        Ok(u16::from_be_bytes([response[2], response[3]]))
+    }
+}
+
+impl PortMappingGateway for NatPmpGateway {
+    fn protocol(&self) -> &'static str {
+        "NAT-PMP"
+    }
+
+    fn external_ip(&self) -> Result<IpAddr, PortMappingError> {
+        let response = self.request(OP_EXTERNAL_ADDRESS, &[NAT_PMP_VERSION, OP_EXTERNAL_ADDRESS], 12)?;
+        Ok(Ipv4Addr::new(response[0], response[1], response[2], response[3]).into())
+    }
+
+    /// NAT-PMP gateways map another external port rather than failing when the requested one is in use, so the
+    /// returned port must be checked by the caller
+    fn add_port(&self, external_port: u16, local_addr: SocketAddr, lease: Duration) -> Result<u16, PortMappingError> {
+        self.map(local_addr.port(), external_port, lease)
+    }
+
+    fn add_any_port(&self, local_addr: SocketAddr, lease: Duration) -> Result<u16, PortMappingError> {
+        self.map(local_addr.port(), 0, lease)
+    }
+
+    fn remove_port(&self, _external_port: u16, local_addr: SocketAddr) -> Result<(), PortMappingError> {
+        // Per RFC 6886, a deletion request has both a zero lifetime and a zero suggested external port
+        self.map(local_addr.port(), 0, Duration::ZERO).map(drop)
+    }
+}
+
+/// Parses the content of `/proc/net/route` and returns the gateway of the default route
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_default_gateway(routes: &str) -> Option<Ipv4Addr> {
+    routes.lines().skip(1).find_map(|line| {
+        let mut fields = line.split_whitespace().skip(1);
+        let (destination, gateway) = (fields.next()?, fields.next()?);
+        if destination != "00000000" {
+            return None;
+        }
+        // Addresses are printed as hex of the u32 in host byte order
+        let gateway = u32::from_str_radix(gateway, 16).ok()?;
+        (gateway != 0).then(|| Ipv4Addr::from(gateway.to_ne_bytes()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{str::FromStr, thread::JoinHandle};
+
+    /// Spawns a gateway answering the given number of requests, the response to each being computed from the request
+    fn spawn_mock_gateway(requests: usize, respond: fn(&[u8]) -> Vec<u8>) -> (NatPmpGateway, JoinHandle<Vec<Vec<u8>>>) {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let gateway = NatPmpGateway { addr: socket.local_addr().unwrap(), initial_timeout: Duration::from_secs(1) };
+        let handle = std::thread::spawn(move || {
+            (0..requests)
+                .map(|_| {
+                    let mut buffer = [0u8; 16];
+                    let (len, client) = socket.recv_from(&mut buffer).unwrap();
+                    socket.send_to(&respond(&buffer[..len]), client).unwrap();
+                    buffer[..len].to_vec()
+                })
+                .collect()
+        });
+        (gateway, handle)
+    }
+
+    fn map_response(request: &[u8], external_port: [u8; 2]) -> Vec<u8> {
+        let mut response = vec![0, request[1] + OP_RESPONSE, 0, 0, 0, 0, 0, 1];
+        response.extend_from_slice(&request[4..6]);
+        response.extend_from_slice(&external_port);
+        response.extend_from_slice(&request[8..12]);
+        response
+    }
+
+    #[test]
+    fn test_natpmp_external_ip() {
+        let (gateway, handle) = spawn_mock_gateway(1, |request| vec![0, request[1] + OP_RESPONSE, 0, 0, 0, 0, 0, 1, 1, 2, 3, 4]);
+        assert_eq!(gateway.external_ip().unwrap(), IpAddr::from_str("1.2.3.4").unwrap());
+        assert_eq!(handle.join().unwrap(), vec![vec![NAT_PMP_VERSION, OP_EXTERNAL_ADDRESS]]);
+    }
+
+    #[test]
+    fn test_natpmp_map() {
+        // The gateway maps another external port than the requested one
+        let (gateway, handle) = spawn_mock_gateway(3, |request| match request[8..12] {
+            [0, 0, 0, 0] => map_response(request, [0, 0]),
+            _ => map_response(request, 50000u16.to_be_bytes()),
+        });
+        let local_addr = SocketAddr::from_str("192.168.1.10:16111").unwrap();
+        assert_eq!(gateway.add_port(16111, local_addr, Duration::from_secs(120)).unwrap(), 50000);
+        assert_eq!(gateway.add_any_port(local_addr, Duration::from_secs(120)).unwrap(), 50000);
+        gateway.remove_port(50000, local_addr).unwrap();
+
+        let requests = handle.join().unwrap();
+        assert_eq!(requests[0], [0, OP_MAP_TCP, 0, 0, 0x3e, 0xef, 0x3e, 0xef, 0, 0, 0, 120]);
+        assert_eq!(requests[1], [0, OP_MAP_TCP, 0, 0, 0x3e, 0xef, 0, 0, 0, 0, 0, 120]);
+        assert_eq!(requests[2], [0, OP_MAP_TCP, 0, 0, 0x3e, 0xef, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_natpmp_errors() {
+        let (gateway, handle) = spawn_mock_gateway(1, |request| vec![0, request[1] + OP_RESPONSE, 0, 2, 0, 0, 0, 1, 0, 0, 0, 0]);
+        assert!(matches!(gateway.external_ip(), Err(PortMappingError::NatPmpResultCode(_, 2))));
+        handle.join().unwrap();
+
+        let (gateway, handle) = spawn_mock_gateway(1, |_| vec![0, OP_RESPONSE, 0, 0]);
+        assert!(matches!(gateway.external_ip(), Err(PortMappingError::NatPmpMalformedResponse(_))));
+        handle.join().unwrap();
+
+        // Nothing answers on this socket
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let gateway = NatPmpGateway { addr: socket.local_addr().unwrap(), initial_timeout: Duration::from_millis(10) };
+        assert!(matches!(gateway.external_ip(), Err(PortMappingError::NatPmpTimeout(_))));
+    }
+
+    #[test]
+    fn test_parse_default_gateway() {
+        let gateway = format!("{:08X}", u32::from_ne_bytes([192, 168, 1, 1]));
+        let routes = format!(
+            "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\n\
+            eth0\t0001A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0\n\
+            eth0\t00000000\t{gateway}\t0003\t0\t0\t100\t00000000\t0\t0\t0\n"
+        );
+        assert_eq!(parse_default_gateway(&routes), Some(Ipv4Addr::new(192, 168, 1, 1)));
+        assert_eq!(parse_default_gateway("Iface\tDestination\tGateway\n"), None);
+    }
+}
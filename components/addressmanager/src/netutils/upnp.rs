@@ -0,0 +1,79 @@
+use super::{PortMappingError, PortMappingGateway, PORT_MAPPING_DESCRIPTION};
+use igd_next::{self as igd, Gateway, GetGenericPortMappingEntryError, PortMappingProtocol};
+use kaspa_core::{info, warn};
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+/// A gateway found with UPnP IGD
+pub struct UpnpGateway {
+    gateway: Gateway,
+}
+
+impl UpnpGateway {
+    pub fn search() -> Result<Self, PortMappingError> {
+        Ok(Self { gateway: igd::search_gateway(Default::default())? })
+    }
+
+    /// Checks the existing port mappings of the gateway for one using `external_port` and targeting another client than
+    /// `local_addr`.
+    ///
+    /// In certain scenarios, gateways do not fail with `PortInUse` on a conflict but rather silently remap the external
+    /// port. By iterating through the current mappings, we can make an informed decision about whether to attempt using
+    /// the desired port or request a new random one.
+    fn is_mapped_to_other_client(&self, external_port: u16, local_addr: SocketAddr) -> bool {
+        let mut index = 0;
+        loop {
+            match self.gateway.get_generic_port_mapping_entry(index) {
+                Ok(entry) => {
+                    let is_own_mapping = entry.internal_client.parse::<IpAddr>().is_ok_and(|ip| ip == local_addr.ip())
+                        && entry.internal_port == local_addr.port();
+                    if entry.enabled && entry.external_port == external_port && !is_own_mapping {
+                        info!("[UPnP] Found existing mapping that uses the same external port. Description: {}, external port: {}, internal port: {}, client: {}, lease duration: {}", entry.port_mapping_description, entry.external_port, entry.internal_port, entry.internal_client, entry.lease_duration);
+                        return true;
+                    }
+                    index += 1;
+                }
+                Err(GetGenericPortMappingEntryError::ActionNotAuthorized) => index += 1,
+                Err(GetGenericPortMappingEntryError::RequestError(err)) => {
+                    warn!("[UPnP] request existing port mapping err: {:?}", err);
+                    return false;
+                }
+                Err(GetGenericPortMappingEntryError::SpecifiedArrayIndexInvalid) => return false,
+            }
+        }
+    }
+}
+
+impl PortMappingGateway for UpnpGateway {
+    fn protocol(&self) -> &'static str {
+        "UPnP"
+    }
+
+    fn external_ip(&self) -> Result<IpAddr, PortMappingError> {
+        Ok(self.gateway.get_external_ip()?)
+    }
+
+    fn add_port(&self, external_port: u16, local_addr: SocketAddr, lease: Duration) -> Result<u16, PortMappingError> {
+        if self.is_mapped_to_other_client(external_port, local_addr) {
+            return Err(PortMappingError::PortInUse);
+        }
+        self.gateway.add_port(
+            PortMappingProtocol::TCP,
+            external_port,
+            local_addr,
+            lease.as_secs() as u32,
+            PORT_MAPPING_DESCRIPTION,
+        )?;
+        Ok(external_port)
+    }
+
+    fn add_any_port(&self, local_addr: SocketAddr, lease: Duration) -> Result<u16, PortMappingError> {
+        Ok(self.gateway.add_any_port(PortMappingProtocol::TCP, local_addr, lease.as_secs() as u32, PORT_MAPPING_DESCRIPTION)?)
+    }
+
+    fn remove_port(&self, external_port: u16, _local_addr: SocketAddr) -> Result<(), PortMappingError> {
+        Ok(self.gateway.remove_port(PortMappingProtocol::TCP, external_port)?)
+    }
+}
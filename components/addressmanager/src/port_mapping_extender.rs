@@ -1,57 +1,58 @@
-use igd_next::{aio::tokio::Tokio, AddPortError};
 use kaspa_core::{
     debug, error, info,
     task::{
-        service::{AsyncService, AsyncServiceFuture},
+        service::{AsyncService, AsyncServiceError, AsyncServiceFuture},
         tick::{TickReason, TickService},
     },
     trace, warn,
 };
-use std::{net::SocketAddr, sync::Arc, time::Duration};
+use parking_lot::Mutex;
+use std::{sync::Arc, time::Duration};
+use tokio::task::JoinError;
 
-use crate::UPNP_REGISTRATION_NAME;
+use crate::{netutils::PortMapping, AddressManager};
 
 pub const SERVICE_NAME: &str = "port-mapping-extender";
 
+/// Periodically renews the lease of the port mapping and keeps the advertised local address in sync with the external
+/// address of the gateway. The mapping is removed when the service stops.
 pub struct Extender {
     tick_service: Arc<TickService>,
-    fetch_interval: Duration,
-    deadline_sec: u64,
-    gateway: igd_next::aio::Gateway<Tokio>,
-    external_port: u16,
-    local_addr: SocketAddr,
+    renewal_interval: Duration,
+    mapping: Arc<Mutex<PortMapping>>,
+    address_manager: Arc<Mutex<AddressManager>>,
 }
 
 impl Extender {
     pub fn new(
         tick_service: Arc<TickService>,
-        fetch_interval: Duration,
-        deadline_sec: u64,
-        gateway: igd_next::aio::Gateway<Tokio>,
-        external_port: u16,
-        local_addr: SocketAddr,
+        renewal_interval: Duration,
+        mapping: PortMapping,
+        address_manager: Arc<Mutex<AddressManager>>,
     ) -> Self {
-        Self { tick_service, fetch_interval, deadline_sec, gateway, external_port, local_addr }
+        Self { tick_service, renewal_interval, mapping: Arc::new(Mutex::new(mapping)), address_manager }
     }
 }
 
 impl Extender {
-    pub async fn worker(&self) -> Result<(), AddPortError> {
-        while let TickReason::Wakeup = self.tick_service.tick(self.fetch_interval).await {
-            if let Err(e) = self
-                .gateway
-                .add_port(
-                    igd_next::PortMappingProtocol::TCP,
-                    self.external_port,
-                    self.local_addr,
-                    self.deadline_sec as u32,
-                    UPNP_REGISTRATION_NAME,
-                )
-                .await
-            {
-                warn!("[UPnP] Extend external ip mapping err: {e:?}");
-            } else {
-                debug!("[UPnP] Extend external ip mapping");
+    pub async fn worker(&self) -> Result<(), JoinError> {
+        let protocol = self.mapping.lock().protocol();
+        while let TickReason::Wakeup = self.tick_service.tick(self.renewal_interval).await {
+            // Gateway requests are blocking
+            let mapping = self.mapping.clone();
+            let renewal = tokio::task::spawn_blocking(move || {
+                let mut mapping = mapping.lock();
+                let previous = mapping.external_address();
+                mapping.renew().map(|current| current.map(|current| (previous, current)))
+            })
+            .await?;
+            match renewal {
+                Ok(None) => debug!("[{protocol}] Extend external ip mapping"),
+                Ok(Some((previous, current))) => {
+                    info!("[{protocol}] External address changed from {previous} to {current}");
+                    self.address_manager.lock().replace_local_address(previous, current);
+                }
+                Err(err) => warn!("[{protocol}] Extend external ip mapping err: {err}"),
             }
         }
         // Let the system print final logs before exiting
@@ -81,10 +82,16 @@ impl AsyncService for Extender {
 
     fn stop(self: Arc<Self>) -> AsyncServiceFuture {
         Box::pin(async move {
-            if let Err(err) = self.gateway.remove_port(igd_next::PortMappingProtocol::TCP, self.external_port).await {
-                warn!("[UPnP] Remove port mapping err: {err:?}");
-            } else {
-                info!("[UPnP] Successfully removed port mapping, external port: {}", self.external_port);
+            let mapping = self.mapping.clone();
+            let (protocol, external_address, removal) = tokio::task::spawn_blocking(move || {
+                let mapping = mapping.lock();
+                (mapping.protocol(), mapping.external_address(), mapping.remove())
+            })
+            .await
+            .map_err(|err| AsyncServiceError::Service(err.to_string()))?;
+            match removal {
+                Ok(()) => info!("[{protocol}] Successfully removed port mapping, external port: {}", external_address.port),
+                Err(err) => warn!("[{protocol}] Remove port mapping err: {err}"),
             }
             trace!("{} stopped", SERVICE_NAME);
             Ok(())
@@ -0,0 +1,60 @@
+use kaspa_database::{
+    prelude::DB,
+    prelude::{CachePolicy, StoreResult},
+    prelude::{CachedDbAccess, DirectDbWriter},
+    registry::DatabaseStorePrefixes,
+};
+use kaspa_utils::mem_size::MemSizeEstimator;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, sync::Arc};
+
+use super::{
+    address_store::{DbAddressKey, ADDRESS_KEY_SIZE},
+    AddressKey,
+};
+use crate::NetAddress;
+
+/// A peer added by the AddPeer RPC which the connection manager keeps connected across restarts
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct ManualPeerEntry {
+    pub address: NetAddress,
+}
+
+impl MemSizeEstimator for ManualPeerEntry {}
+
+pub trait ManualPeersStore {
+    fn set(&mut self, key: AddressKey, entry: ManualPeerEntry) -> StoreResult<()>;
+    fn remove(&mut self, key: AddressKey) -> StoreResult<()>;
+}
+
+#[derive(Clone)]
+pub struct DbManualPeersStore {
+    db: Arc<DB>,
+    access: CachedDbAccess<DbAddressKey, ManualPeerEntry>,
+}
+
+impl DbManualPeersStore {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, DatabaseStorePrefixes::ManualPeers.into()) }
+    }
+
+    pub fn iterator(&self) -> impl Iterator<Item = Result<(AddressKey, ManualPeerEntry), Box<dyn Error>>> + '_ {
+        self.access.iterator().map(|iter_result| match iter_result {
+            Ok((key_bytes, entry)) => match <[u8; ADDRESS_KEY_SIZE]>::try_from(&key_bytes[..]) {
+                Ok(address_key_slice) => Ok((DbAddressKey(address_key_slice).into(), entry)),
+                Err(e) => Err(e.into()),
+            },
+            Err(e) => Err(e),
+        })
+    }
+}
+
+impl ManualPeersStore for DbManualPeersStore {
+    fn set(&mut self, key: AddressKey, entry: ManualPeerEntry) -> StoreResult<()> {
+        self.access.write(DirectDbWriter::new(&self.db), key.into(), entry)
+    }
+
+    fn remove(&mut self, key: AddressKey) -> StoreResult<()> {
+        self.access.delete(DirectDbWriter::new(&self.db), key.into())
+    }
+}
@@ -0,0 +1,59 @@
+use kaspa_database::{
+    prelude::DB,
+    prelude::{CachePolicy, StoreResult},
+    prelude::{CachedDbAccess, DirectDbWriter},
+    registry::DatabaseStorePrefixes,
+};
+use kaspa_utils::{mem_size::MemSizeEstimator, networking::OnionAddress};
+use serde::{Deserialize, Serialize};
+use std::{error::Error, sync::Arc};
+
+use super::{
+    address_store::{DbAddressKey, ADDRESS_KEY_SIZE},
+    AddressKey,
+};
+
+/// The onion host of an address store entry, whose key only holds the IPv6 encoding of the host
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct OnionEntry {
+    pub host: OnionAddress,
+}
+
+impl MemSizeEstimator for OnionEntry {}
+
+pub trait OnionAddressesStore {
+    fn set(&mut self, key: AddressKey, entry: OnionEntry) -> StoreResult<()>;
+    fn remove(&mut self, key: AddressKey) -> StoreResult<()>;
+}
+
+#[derive(Clone)]
+pub struct DbOnionAddressesStore {
+    db: Arc<DB>,
+    access: CachedDbAccess<DbAddressKey, OnionEntry>,
+}
+
+impl DbOnionAddressesStore {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, DatabaseStorePrefixes::OnionAddresses.into()) }
+    }
+
+    pub fn iterator(&self) -> impl Iterator<Item = Result<(AddressKey, OnionEntry), Box<dyn Error>>> + '_ {
+        self.access.iterator().map(|iter_result| match iter_result {
+            Ok((key_bytes, entry)) => match <[u8; ADDRESS_KEY_SIZE]>::try_from(&key_bytes[..]) {
+                Ok(address_key_slice) => Ok((DbAddressKey(address_key_slice).into(), entry)),
+                Err(e) => Err(e.into()),
+            },
+            Err(e) => Err(e),
+        })
+    }
+}
+
+impl OnionAddressesStore for DbOnionAddressesStore {
+    fn set(&mut self, key: AddressKey, entry: OnionEntry) -> StoreResult<()> {
+        self.access.write(DirectDbWriter::new(&self.db), key.into(), entry)
+    }
+
+    fn remove(&mut self, key: AddressKey) -> StoreResult<()> {
+        self.access.delete(DirectDbWriter::new(&self.db), key.into())
+    }
+}
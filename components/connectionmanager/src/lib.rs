@@ -9,9 +9,9 @@ use std::{
 use duration_string::DurationString;
 use futures_util::future::join_all;
 use itertools::Itertools;
-use kaspa_addressmanager::{AddressManager, NetAddress};
+use kaspa_addressmanager::{AddressManager, AddressSource, DisconnectReason, NetAddress};
 use kaspa_core::{debug, info, warn};
-use kaspa_p2p_lib::{common::ProtocolError, ConnectionError, Peer};
+use kaspa_p2p_lib::{common::ProtocolError, ConnectionError, Peer, ProxyConfig};
 use kaspa_utils::triggers::SingleTrigger;
 use parking_lot::Mutex as ParkingLotMutex;
 use rand::{seq::SliceRandom, thread_rng};
@@ -59,12 +59,19 @@ impl ConnectionManager {
         address_manager: Arc<ParkingLotMutex<AddressManager>>,
     ) -> Arc<Self> {
         let (tx, rx) = unbounded_channel::<()>();
+        // Manual peers persisted by former runs are requested again
+        let connection_requests = address_manager
+            .lock()
+            .get_manual_peers()
+            .into_iter()
+            .map(|address| (address.into(), ConnectionRequest::new(true)))
+            .collect();
         let manager = Arc::new(Self {
             p2p_adaptor,
             outbound_target,
             inbound_limit,
             address_manager,
-            connection_requests: Default::default(),
+            connection_requests: TokioMutex::new(connection_requests),
             force_next_iteration: tx,
             shutdown_signal: SingleTrigger::new(),
             dns_seeders,
@@ -109,10 +116,47 @@ impl ConnectionManager {
         self.force_next_iteration.send(()).unwrap(); // We force the next iteration of the connection loop.
     }
 
+    /// Adds a permanent connection request to `address` and persists it as a manual peer, so it is requested
+    /// again after a restart. Like all the connection requests, manual peers are dialed regardless of the
+    /// outbound target. Returns `false` if the maximum number of manual peers is reached.
+    pub async fn add_manual_peer(&self, address: SocketAddr) -> bool {
+        if !self.address_manager.lock().add_manual_peer(address.into()) {
+            return false;
+        }
+        self.add_connection_request(address, true).await;
+        true
+    }
+
+    /// Removes the connection request to `address`, whether persisted as a manual peer or not, and disconnects
+    /// from the peer at `address`. Returns `false`, leaving any connection untouched, if no such request exists.
+    pub async fn remove_peer(&self, address: SocketAddr) -> bool {
+        let is_requested = self.connection_requests.lock().await.remove(&address).is_some();
+        let is_manual = self.address_manager.lock().remove_manual_peer(address.into());
+        if !is_requested && !is_manual {
+            return false;
+        }
+        for peer in self.p2p_adaptor.active_peers() {
+            if peer.net_address() == address {
+                self.p2p_adaptor.terminate(peer.key()).await;
+            }
+        }
+        true
+    }
+
+    /// Returns whether the given address is a persisted manual peer.
+    pub fn is_manual_peer(&self, address: &SocketAddr) -> bool {
+        self.address_manager.lock().is_manual_peer((*address).into())
+    }
+
     pub async fn stop(&self) {
         self.shutdown_signal.trigger.trigger()
     }
 
+    /// The proxy outbound connections are routed through, if any
+    pub fn proxy(&self) -> Option<&ProxyConfig> {
+        self.p2p_adaptor.proxy()
+    }
+
     async fn handle_connection_requests(self: &Arc<Self>, peer_by_address: &HashMap<SocketAddr, Peer>) {
         let mut requests = self.connection_requests.lock().await;
         let mut new_requests = HashMap::with_capacity(requests.len());
@@ -182,10 +226,10 @@ impl ConnectionManager {
                     connecting = false;
                     break;
                 };
-                let socket_addr = SocketAddr::new(net_addr.ip.into(), net_addr.port).to_string();
-                debug!("Connecting to {}", &socket_addr);
+                let dial_target = self.address_manager.lock().dial_target(net_addr);
+                debug!("Connecting to {}", &dial_target);
                 addrs_to_connect.push(net_addr);
-                jobs.push(self.p2p_adaptor.connect_peer(socket_addr.clone()));
+                jobs.push(self.p2p_adaptor.connect_peer(dial_target));
             }
 
             if progressing && !jobs.is_empty() {
@@ -218,6 +262,19 @@ impl ConnectionManager {
                         // We avoid marking the existing connection as connection failure
                         debug!("Failed connecting to {:?}, peer already exists", net_addr);
                     }
+                    Err(ConnectionError::ProxyRequired(_)) => {
+                        // An onion address kept from a run with a proxy, which is not failing as such
+                        debug!("Skipping onion address {:?}, no proxy is configured", net_addr);
+                    }
+                    Err(ConnectionError::ProtocolError(err)) if err.is_incompatible_network_error() => {
+                        // The peer will never be compatible, so we avoid redialing it
+                        info!("Stopping to dial {:?}, err: {}", net_addr, err);
+                        let reason = match err {
+                            ProtocolError::GenesisMismatch(..) => DisconnectReason::GenesisMismatch,
+                            _ => DisconnectReason::ParamsDigestMismatch,
+                        };
+                        self.address_manager.lock().mark_incompatible(net_addr, reason);
+                    }
                     Err(err) => {
                         debug!("Failed connecting to {:?}, err: {}", net_addr, err);
                         self.address_manager.lock().mark_connection_failure(net_addr);
@@ -227,6 +284,11 @@ impl ConnectionManager {
         }
 
         if missing_connections > 0 && !self.dns_seeders.is_empty() {
+            if let Some(proxy) = self.proxy().filter(|proxy| !proxy.onion_only).cloned() {
+                // Resolving the seeders locally would leak them while all the traffic goes through the proxy
+                self.dns_seed_with_proxy(&proxy, missing_connections).await;
+                return;
+            }
             let cmgr = self.clone();
             // DNS lookup is a blocking i/o operation, so we spawn it as a blocking task
             let _ = tokio::task::spawn_blocking(move || {
@@ -268,7 +330,7 @@ impl ConnectionManager {
             info!("Retrieved {} addresses from DNS seeder {}", addrs_len, seeder);
             let mut amgr_lock = self.address_manager.lock();
             for addr in addrs {
-                amgr_lock.add_address(NetAddress::new(addr.ip().into(), addr.port()));
+                amgr_lock.add_address(NetAddress::new(addr.ip().into(), addr.port()), AddressSource::DnsSeed);
             }
 
             if addrs_len >= min_addresses_to_fetch {
@@ -279,6 +341,29 @@ impl ConnectionManager {
         }
     }
 
+    /// Resolves the DNS seeders through `proxy`, which only yields a single address per seeder
+    async fn dns_seed_with_proxy(self: &Arc<Self>, proxy: &ProxyConfig, mut min_addresses_to_fetch: usize) {
+        let shuffled_dns_seeders = self.dns_seeders.choose_multiple(&mut thread_rng(), self.dns_seeders.len()).copied().collect_vec();
+        for seeder in shuffled_dns_seeders {
+            info!("Querying DNS seeder {} through the proxy", seeder);
+            let ip = match proxy.resolve(seeder).await {
+                Ok(ip) => ip,
+                Err(e) => {
+                    warn!("Error resolving DNS seeder {} through the proxy: {}", seeder, e);
+                    continue;
+                }
+            };
+
+            info!("Retrieved address {} from DNS seeder {}", ip, seeder);
+            self.address_manager.lock().add_address(NetAddress::new(ip.into(), self.default_port), AddressSource::DnsSeed);
+
+            min_addresses_to_fetch -= 1;
+            if min_addresses_to_fetch == 0 {
+                break;
+            }
+        }
+    }
+
     /// Bans the given IP and disconnects from all the peers with that IP.
     ///
     /// _GO-KASPAD: BanByIP_
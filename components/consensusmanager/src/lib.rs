@@ -1,8 +1,13 @@
 use itertools::Itertools;
-use kaspa_consensus_core::api::{ConsensusApi, DynConsensus};
+use kaspa_consensus_core::{
+    api::{ConsensusApi, DynConsensus},
+    backup::BackupManifest,
+    errors::backup::BackupResult,
+    multi_consensus::MultiConsensusStatus,
+};
 use kaspa_core::{core::Core, debug, service::Service};
 use parking_lot::RwLock;
-use std::{collections::VecDeque, ops::Deref, sync::Arc, thread::JoinHandle};
+use std::{collections::VecDeque, ops::Deref, path::Path, sync::Arc, thread::JoinHandle};
 
 mod batch;
 mod session;
@@ -21,8 +26,16 @@ pub trait ConsensusCtl: Sync + Send {
     /// Shutdown all workers and clear runtime resources
     fn stop(&self);
 
+    /// Flush the databases and record this staging consensus as prepared for becoming active. From then on an
+    /// interrupted promotion is completed on next startup
+    fn seal(&self);
+
     /// Set as current active consensus
     fn make_active(&self);
+
+    /// Creates a consistent point-in-time copy of the consensus databases at `output_dir` without stopping
+    /// block processing, along with a manifest describing the backed up state
+    fn create_backup(&self, output_dir: &Path) -> BackupResult<BackupManifest>;
 }
 
 pub type DynConsensusCtl = Arc<dyn ConsensusCtl>;
@@ -43,6 +56,9 @@ pub trait ConsensusFactory: Sync + Send {
     /// Delete the staging consensus entry and its database (this is done even if the node is archival
     /// since staging reflects non-final data)
     fn delete_staging_entry(&self);
+
+    /// The status of the active, staging and retired consensus entries
+    fn consensus_status(&self) -> MultiConsensusStatus;
 }
 
 /// Test-only mock factory
@@ -68,6 +84,10 @@ impl ConsensusFactory for MockFactory {
     fn delete_staging_entry(&self) {
         unimplemented!()
     }
+
+    fn consensus_status(&self) -> MultiConsensusStatus {
+        Default::default()
+    }
 }
 
 /// Defines a trait which handles consensus resets for external parts of the system. We avoid using
@@ -142,6 +162,13 @@ impl ConsensusManager {
         StagingConsensus::new(self.clone(), ConsensusInner::new(consensus, ctl))
     }
 
+    /// Backs up the current consensus, see [`ConsensusCtl::create_backup`]. Blocking, hence
+    /// should be called from a blocking context
+    pub fn create_backup(&self, output_dir: &Path) -> BackupResult<BackupManifest> {
+        let ctl = self.inner.read().current.ctl.clone();
+        ctl.create_backup(output_dir)
+    }
+
     pub fn register_consensus_reset_handler(&self, handler: Arc<dyn ConsensusResetHandler>) {
         self.inner.write().consensus_reset_handlers.push(handler);
     }
@@ -170,6 +197,12 @@ impl ConsensusManager {
     pub fn delete_staging_entry(&self) {
         self.factory.delete_staging_entry();
     }
+
+    /// The status of the consensus entries. Reads the disk usage of the retired entries, hence should be called
+    /// from a blocking context
+    pub fn consensus_status(&self) -> MultiConsensusStatus {
+        self.factory.consensus_status()
+    }
 }
 
 impl Service for ConsensusManager {
@@ -198,7 +231,11 @@ impl StagingConsensus {
         Self { manager, staging, handles }
     }
 
+    /// Promotes the staging consensus to active. The staging consensus is first sealed, so that the promotion
+    /// is completed on next startup if interrupted, the active consensus is then swapped, and the inactive
+    /// entries retired for long enough are finally deleted.
     pub fn commit(self) {
+        self.staging.ctl.seal();
         let mut g = self.manager.inner.write();
         let prev = std::mem::replace(&mut g.current, self.staging);
         g.handles.extend(self.handles);
@@ -3,18 +3,24 @@
 //! We use newtypes in order to simplify changing the underlying lock in the future
 
 use kaspa_consensus_core::{
-    acceptance_data::AcceptanceData,
-    api::{BlockCount, BlockValidationFutures, ConsensusApi, ConsensusStats, DynConsensus},
-    block::Block,
+    acceptance_data::{AcceptanceData, AcceptanceProof, OutpointSpendStatus},
+    api::{
+        BlockCount, BlockValidationFutures, ConsensusApi, ConsensusStats, DynConsensus, TrustedBlocksValidationFuture, VirtualInfo,
+    },
+    block::{Block, BlockTemplateValidation},
     blockstatus::BlockStatus,
-    daa_score_timestamp::DaaScoreTimestamp,
-    errors::consensus::ConsensusResult,
+    coinbase::CoinbaseAmounts,
+    daa_score_timestamp::{ChainBlockAtDaaScore, DaaScoreTimestamp},
+    difficulty::BlockDifficultyContext,
+    errors::{block::RuleError, consensus::ConsensusResult},
     header::Header,
     pruning::{PruningPointProof, PruningPointTrustedData, PruningPointsList},
     trusted::{ExternalGhostdagData, TrustedBlock},
-    tx::{MutableTransaction, Transaction, TransactionOutpoint, UtxoEntry},
+    tx::{MutableTransaction, Transaction, TransactionId, TransactionOutpoint, UtxoEntry},
+    virtual_parents::VirtualParentSelectionTrace,
     BlockHashSet, BlueWorkType, ChainPath, Hash,
 };
+use kaspa_core::log::trace_id;
 use kaspa_utils::sync::rwlock::*;
 use std::{ops::Deref, sync::Arc};
 
@@ -107,6 +113,11 @@ impl ConsensusInstance {
         ConsensusSessionOwned::new(g, self.consensus.clone())
     }
 
+    /// The lock acquired by guarded sessions for reading and by data pruning for writing
+    pub fn session_lock(&self) -> SessionLock {
+        self.session_lock.clone()
+    }
+
     /// Returns an unguarded consensus session. There's no guarantee that data will not be pruned between
     /// two sequential consensus calls. This session doesn't hold the consensus pruning lock, so it should
     /// be preferred upon [`session`] when data consistency is not important.
@@ -158,12 +169,15 @@ impl ConsensusSessionOwned {
     /// Uses [`tokio::task::spawn_blocking`] to run the provided consensus closure on a thread where blocking is acceptable.
     /// Note that this function is only available on the *owned* session, and requires cloning the session. In fact this
     /// function is the main motivation for a separate session type.
+    ///
+    /// The trace id of the calling task, if any, is carried over to the blocking thread.
     pub async fn spawn_blocking<F, R>(self, f: F) -> R
     where
         F: FnOnce(&dyn ConsensusApi) -> R + Send + 'static,
         R: Send + 'static,
     {
-        spawn_blocking(move || f(self.consensus.as_ref())).await.unwrap()
+        let trace_id = trace_id::current();
+        spawn_blocking(move || trace_id::sync_scope(trace_id, || f(self.consensus.as_ref()))).await.unwrap()
     }
 }
 
@@ -189,6 +203,10 @@ impl ConsensusSessionOwned {
         self.consensus.validate_and_insert_trusted_block(tb)
     }
 
+    pub fn validate_and_insert_trusted_blocks(&self, batch: Vec<TrustedBlock>) -> TrustedBlocksValidationFuture {
+        self.consensus.validate_and_insert_trusted_blocks(batch)
+    }
+
     pub fn calculate_transaction_compute_mass(&self, transaction: &Transaction) -> u64 {
         // This method performs pure calculations so no need for an async wrapper
         self.consensus.calculate_transaction_compute_mass(transaction)
@@ -214,6 +232,11 @@ impl ConsensusSessionOwned {
         self.consensus.get_virtual_past_median_time()
     }
 
+    pub fn get_virtual_info(&self) -> VirtualInfo {
+        // Accessing cached virtual fields is lock-free and does not require spawn_blocking
+        self.consensus.get_virtual_info()
+    }
+
     pub fn get_virtual_parents(&self) -> BlockHashSet {
         // Accessing cached virtual fields is lock-free and does not require spawn_blocking
         self.consensus.get_virtual_parents()
@@ -284,6 +307,11 @@ impl ConsensusSessionOwned {
         self.clone().spawn_blocking(|c| c.get_tips_len()).await
     }
 
+    /// See [`ConsensusApi::get_virtual_parent_selection_trace`]
+    pub async fn async_get_virtual_parent_selection_trace(&self) -> VirtualParentSelectionTrace {
+        self.clone().spawn_blocking(|c| c.get_virtual_parent_selection_trace()).await
+    }
+
     pub async fn async_is_chain_ancestor_of(&self, low: Hash, high: Hash) -> ConsensusResult<bool> {
         self.clone().spawn_blocking(move |c| c.is_chain_ancestor_of(low, high)).await
     }
@@ -304,6 +332,12 @@ impl ConsensusSessionOwned {
         self.clone().spawn_blocking(|c| c.get_chain_block_samples()).await
     }
 
+    /// Returns the selected chain block whose DAA score is nearest to `daa_score`.
+    /// See [`ConsensusApi::get_chain_block_by_daa_score`] for the exact semantics.
+    pub async fn async_get_chain_block_by_daa_score(&self, daa_score: u64) -> ConsensusResult<ChainBlockAtDaaScore> {
+        self.clone().spawn_blocking(move |c| c.get_chain_block_by_daa_score(daa_score)).await
+    }
+
     /// Returns the antipast of block `hash` from the POV of `context`, i.e. `antipast(hash) ∩ past(context)`.
     /// Since this might be an expensive operation for deep blocks, we allow the caller to specify a limit
     /// `max_traversal_allowed` on the maximum amount of blocks to traverse for obtaining the answer
@@ -380,10 +414,66 @@ impl ConsensusSessionOwned {
         self.clone().spawn_blocking(move |c| c.get_blocks_acceptance_data(&hashes)).await
     }
 
+    /// Returns the ids of the transactions accepted by each of a set of blocks belonging to the selected parent chain,
+    /// loading the acceptance data lazily.
+    pub async fn async_get_blocks_accepted_transaction_ids(&self, hashes: Vec<Hash>) -> ConsensusResult<Vec<Vec<TransactionId>>> {
+        self.clone().spawn_blocking(move |c| c.get_blocks_accepted_transaction_ids(&hashes)).await
+    }
+
     pub async fn async_is_chain_block(&self, hash: Hash) -> ConsensusResult<bool> {
         self.clone().spawn_blocking(move |c| c.is_chain_block(hash)).await
     }
 
+    /// Returns the spend status of `outpoint` as seen by the virtual UTXO set and the acceptance data of
+    /// the `search_depth` most recent chain blocks. See [`ConsensusApi::get_outpoint_spend_status`].
+    pub async fn async_get_outpoint_spend_status(
+        &self,
+        outpoint: TransactionOutpoint,
+        search_depth: u64,
+    ) -> ConsensusResult<OutpointSpendStatus> {
+        self.clone().spawn_blocking(move |c| c.get_outpoint_spend_status(outpoint, search_depth)).await
+    }
+
+    /// Returns a merkle proof of the acceptance of `transaction_id` by the chain block `chain_block`.
+    /// See [`ConsensusApi::get_acceptance_proof`].
+    pub async fn async_get_acceptance_proof(
+        &self,
+        chain_block: Hash,
+        transaction_id: TransactionId,
+    ) -> ConsensusResult<AcceptanceProof> {
+        self.clone().spawn_blocking(move |c| c.get_acceptance_proof(chain_block, transaction_id)).await
+    }
+
+    /// Returns the UTXO entry spent by an input of a transaction accepted by the chain block `chain_block`.
+    /// See [`ConsensusApi::get_spent_utxo_entry`].
+    pub async fn async_get_spent_utxo_entry(
+        &self,
+        chain_block: Hash,
+        transaction_id: TransactionId,
+        input_index: u32,
+    ) -> ConsensusResult<UtxoEntry> {
+        self.clone().spawn_blocking(move |c| c.get_spent_utxo_entry(chain_block, transaction_id, input_index)).await
+    }
+
+    /// Returns the amounts paid by the coinbase of the chain block `chain_block`.
+    /// See [`ConsensusApi::expected_coinbase_amount`].
+    pub async fn async_expected_coinbase_amount(&self, chain_block: Hash) -> ConsensusResult<CoinbaseAmounts> {
+        self.clone().spawn_blocking(move |c| c.expected_coinbase_amount(chain_block)).await
+    }
+
+    /// Returns the amounts a block built on the current virtual parents is expected to pay in its coinbase
+    pub async fn async_expected_virtual_coinbase_amount(&self) -> CoinbaseAmounts {
+        self.clone().spawn_blocking(|c| c.expected_virtual_coinbase_amount()).await
+    }
+
+    pub async fn async_validate_block_template(&self, block: Block) -> Result<BlockTemplateValidation, RuleError> {
+        self.clone().spawn_blocking(move |c| c.validate_block_template(block)).await
+    }
+
+    pub async fn async_get_disqualification_reason(&self, hash: Hash) -> Option<RuleError> {
+        self.clone().spawn_blocking(move |c| c.get_disqualification_reason(hash)).await
+    }
+
     pub async fn async_get_pruning_point_utxos(
         &self,
         expected_pruning_point: Hash,
@@ -420,6 +510,10 @@ impl ConsensusSessionOwned {
         self.clone().spawn_blocking(move |c| c.estimate_network_hashes_per_second(start_hash, window_size)).await
     }
 
+    pub async fn async_get_block_difficulty_context(&self, hash: Hash) -> ConsensusResult<BlockDifficultyContext> {
+        self.clone().spawn_blocking(move |c| c.get_block_difficulty_context(hash)).await
+    }
+
     pub async fn async_validate_pruning_points(&self) -> ConsensusResult<()> {
         self.clone().spawn_blocking(move |c| c.validate_pruning_points()).await
     }
@@ -0,0 +1,344 @@
+//! End-to-end block processing benchmarks.
+//!
+//! Each profile first generates a realistic DAG using a [`TestConsensus`] instance and then replays it through
+//! `validate_and_insert_block` on a fresh instance, measuring the throughput of the headers phase (as done by
+//! IBD) and of the bodies phase separately.
+//!
+//! Run with `cargo bench --bench block_processing_benchmarks [-- <profile name filter>]`. Each profile reports
+//! its results as a single line JSON object on stdout for tracking by CI, while progress is written to stderr.
+
+use futures_util::future::try_join_all;
+use indexmap::IndexSet;
+use itertools::Itertools;
+use kaspa_consensus::{
+    config::{Config, ConfigBuilder},
+    consensus::test_consensus::TestConsensus,
+    params::DEVNET_PARAMS,
+    processes::mass::MassCalculator,
+};
+use kaspa_consensus_core::{
+    api::ConsensusApi,
+    block::Block,
+    blockhash,
+    blockstatus::BlockStatus,
+    coinbase::MinerData,
+    config::bps::calculate_ghostdag_k,
+    sign::sign,
+    subnets::SUBNETWORK_ID_NATIVE,
+    tx::{
+        MutableTransaction, ScriptPublicKey, ScriptVec, Transaction, TransactionInput, TransactionOutpoint, TransactionOutput,
+        UtxoEntry,
+    },
+    utxo::utxo_view::UtxoView,
+    KType,
+};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+use rand_distr::{Distribution, Poisson};
+use serde_json::json;
+use std::{
+    collections::HashSet,
+    iter::once,
+    time::{Duration, Instant},
+};
+
+/// Describes the DAG generated by a benchmark profile
+struct Profile {
+    name: &'static str,
+    /// Blocks per second
+    bps: u64,
+    /// Network delay in seconds. Blocks mined within the same delay window do not see each other,
+    /// so the DAG width is distributed ~Poisson(bps * delay)
+    delay: f64,
+    /// Number of blocks to generate
+    blocks: usize,
+    /// Mean of the ~Poisson distribution of the number of transactions per block (zero for coinbase-only blocks).
+    /// The actual number of transactions is further bounded by the block mass limit and by the spendable UTXOs
+    txs_per_block: f64,
+    /// Probability of a transaction to spend two inputs instead of one
+    two_inputs_ratio: f64,
+}
+
+const PROFILES: [Profile; 2] = [
+    // Mimics the headers phase of IBD over a high bps network: many blocks with small bodies
+    Profile { name: "header-heavy", bps: 10, delay: 1.0, blocks: 10_000, txs_per_block: 0.0, two_inputs_ratio: 0.0 },
+    // Full blocks near the mass limit, filled with standard P2PK transactions
+    Profile { name: "body-heavy", bps: 2, delay: 1.0, blocks: 600, txs_per_block: 400.0, two_inputs_ratio: 0.3 },
+];
+
+/// Coinbase maturity in seconds, kept short so that the generated blocks fill up quickly
+const COINBASE_MATURITY_SECONDS: u64 = 10;
+
+/// The fee paid by each generated transaction
+const FEE: u64 = 10_000;
+
+/// Inputs of transactions below this amount are no longer split into two outputs
+const MIN_SPLIT_AMOUNT: u64 = 100_000_000;
+
+/// Blocks are submitted to consensus in chunks, allowing the processing pipeline to work concurrently
+const CHUNK_SIZE: usize = 1000;
+
+fn build_config(profile: &Profile) -> Config {
+    let bps = profile.bps;
+    let k = calculate_ghostdag_k(2.0 * profile.delay * bps as f64, 0.05).max(DEVNET_PARAMS.ghostdag_k as u64);
+    let k = k.min(KType::MAX as u64) as KType;
+    ConfigBuilder::new(DEVNET_PARAMS)
+        .skip_proof_of_work()
+        .edit_consensus_params(|p| {
+            p.ghostdag_k = k;
+            p.mergeset_size_limit = k as u64 * 10;
+            p.max_block_parents = u8::max((0.66 * k as f64) as u8, 10);
+            p.target_time_per_block = 1000 / bps;
+            p.merge_depth *= bps;
+            p.coinbase_maturity = COINBASE_MATURITY_SECONDS * bps;
+            // Use the sampled DAA and median-time windows
+            p.sampling_activation_daa_score = 0;
+            p.past_median_time_sample_rate = 10 * bps;
+            p.new_timestamp_deviation_tolerance = 600 * bps;
+            p.difficulty_sample_rate = 2 * bps;
+        })
+        .build()
+}
+
+struct GeneratedDag {
+    /// The generated blocks, in topological order
+    blocks: Vec<Block>,
+    txs: usize,
+}
+
+impl GeneratedDag {
+    fn parents_mean(&self) -> f64 {
+        self.blocks.iter().map(|b| b.header.direct_parents().len()).sum::<usize>() as f64 / self.blocks.len() as f64
+    }
+}
+
+struct DagGenerator<'a> {
+    profile: &'a Profile,
+    config: &'a Config,
+    consensus: TestConsensus,
+    miner_data: MinerData,
+    schnorr_key: secp256k1::Keypair,
+    mass_calculator: MassCalculator,
+    /// Outpoints which were created by the generated blocks and were not spent yet. Coinbase outpoints
+    /// are spendable only once the block is merged and matured, so their state is verified against the
+    /// virtual UTXO set before use
+    possible_unspent_outpoints: IndexSet<TransactionOutpoint>,
+    rng: SmallRng,
+}
+
+impl<'a> DagGenerator<'a> {
+    fn new(profile: &'a Profile, config: &'a Config) -> Self {
+        let secret_key = secp256k1::SecretKey::from_slice(&[1u8; 32]).unwrap();
+        let schnorr_key = secp256k1::Keypair::from_secret_key(secp256k1::SECP256K1, &secret_key);
+        let (x_only_public_key, _) = schnorr_key.x_only_public_key();
+        let script = once(0x20).chain(x_only_public_key.serialize()).chain(once(0xac)).collect_vec(); // P2PK
+        Self {
+            profile,
+            config,
+            consensus: TestConsensus::new(config),
+            miner_data: MinerData::new(ScriptPublicKey::new(0, ScriptVec::from_slice(&script)), Vec::new()),
+            schnorr_key,
+            mass_calculator: MassCalculator::new(&config.params),
+            possible_unspent_outpoints: IndexSet::new(),
+            rng: SmallRng::seed_from_u64(42),
+        }
+    }
+
+    async fn generate(mut self) -> GeneratedDag {
+        let wait_handles = self.consensus.init();
+        let width_distribution = Poisson::new(self.profile.bps as f64 * self.profile.delay).unwrap();
+        let txs_distribution = (self.profile.txs_per_block > 0.0).then(|| Poisson::new(self.profile.txs_per_block).unwrap());
+
+        let mut blocks = Vec::with_capacity(self.profile.blocks);
+        let mut tips = vec![self.config.genesis.hash];
+        let mut timestamp = self.config.genesis.timestamp;
+        let mut txs = 0;
+        while blocks.len() < self.profile.blocks {
+            timestamp += (self.profile.delay * 1000.0) as u64;
+            let width = (width_distribution.sample(&mut self.rng) as usize)
+                .min(self.config.max_block_parents as usize)
+                .min(self.profile.blocks - blocks.len());
+            if width == 0 {
+                continue;
+            }
+
+            // Blocks of the same round are mined in parallel over the current tips
+            let virtual_daa_score = self.consensus.get_virtual_daa_score();
+            let round = (0..width)
+                .map(|_| {
+                    let block_txs = match &txs_distribution {
+                        Some(distribution) => {
+                            let target = distribution.sample(&mut self.rng) as usize;
+                            self.build_txs(target, virtual_daa_score)
+                        }
+                        None => vec![],
+                    };
+                    txs += block_txs.len();
+                    let mut block = self.consensus.build_utxo_valid_block_with_parents(
+                        blockhash::new_unique(),
+                        tips.clone(),
+                        self.miner_data.clone(),
+                        block_txs,
+                    );
+                    // Use simulation time rather than real time
+                    block.header.timestamp = timestamp;
+                    block.to_immutable()
+                })
+                .collect_vec();
+
+            let statuses = try_join_all(round.iter().map(|b| self.consensus.validate_and_insert_block(b.clone()).virtual_state_task))
+                .await
+                .unwrap();
+            assert!(statuses.iter().all(|s| s.is_utxo_valid_or_pending()));
+
+            for block in round.iter() {
+                for tx in block.transactions.iter() {
+                    let id = tx.id();
+                    self.possible_unspent_outpoints
+                        .extend((0..tx.outputs.len() as u32).map(|index| TransactionOutpoint::new(id, index)));
+                }
+            }
+            tips = round.iter().map(|b| b.hash()).collect();
+            blocks.extend(round);
+            if blocks.len() % CHUNK_SIZE < width {
+                eprintln!("[{}] Generated {} blocks", self.profile.name, blocks.len());
+            }
+        }
+
+        self.consensus.shutdown(wait_handles);
+        GeneratedDag { blocks, txs }
+    }
+
+    /// Builds up to `target` signed transactions spending from the virtual UTXO set, while keeping the overall
+    /// block mass within the limit
+    fn build_txs(&mut self, target: usize, virtual_daa_score: u64) -> Vec<Transaction> {
+        let virtual_stores = self.consensus.virtual_stores();
+        let virtual_read = virtual_stores.read();
+        let utxo_view = &virtual_read.utxo_set;
+
+        let mut txs = Vec::with_capacity(target);
+        let mut spent = HashSet::new();
+        let mut block_mass = 0;
+        let mut candidates = self.possible_unspent_outpoints.iter().copied();
+        while txs.len() < target {
+            let inputs_count = if self.rng.gen_bool(self.profile.two_inputs_ratio) { 2 } else { 1 };
+            let entries = candidates
+                .by_ref()
+                .filter_map(|outpoint| Some((outpoint, self.get_spendable_entry(utxo_view, outpoint, virtual_daa_score)?)))
+                .take(inputs_count)
+                .collect_vec();
+            if entries.is_empty() {
+                break;
+            }
+
+            let tx = self.build_tx(entries);
+            let mass = self.mass_calculator.calc_tx_compute_mass(&tx);
+            if block_mass + mass > self.config.max_block_mass {
+                break;
+            }
+            block_mass += mass;
+            spent.extend(tx.inputs.iter().map(|input| input.previous_outpoint));
+            txs.push(tx);
+        }
+
+        self.possible_unspent_outpoints.retain(|outpoint| !spent.contains(outpoint));
+        txs
+    }
+
+    fn get_spendable_entry(
+        &self,
+        utxo_view: &impl UtxoView,
+        outpoint: TransactionOutpoint,
+        virtual_daa_score: u64,
+    ) -> Option<UtxoEntry> {
+        let entry = utxo_view.get(&outpoint)?;
+        if entry.amount <= FEE || (entry.is_coinbase && virtual_daa_score <= entry.block_daa_score + self.config.coinbase_maturity) {
+            return None;
+        }
+        Some(entry)
+    }
+
+    /// Builds a signed P2PK transaction paying to two outputs (e.g. payment and change), or to a single output
+    /// if the input amount is small
+    fn build_tx(&self, entries: Vec<(TransactionOutpoint, UtxoEntry)>) -> Transaction {
+        let (outpoints, entries): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+        let amount = entries.iter().map(|entry| entry.amount).sum::<u64>() - FEE;
+        let spk = self.miner_data.script_public_key.clone();
+        let outputs = if amount >= MIN_SPLIT_AMOUNT {
+            vec![TransactionOutput::new(amount / 2, spk.clone()), TransactionOutput::new(amount - amount / 2, spk)]
+        } else {
+            vec![TransactionOutput::new(amount, spk)]
+        };
+        let inputs = outpoints.into_iter().map(|outpoint| TransactionInput::new(outpoint, vec![], 0, 0)).collect();
+        let unsigned_tx = Transaction::new_non_finalized(0, inputs, outputs, 0, SUBNETWORK_ID_NATIVE, 0, vec![]);
+        let mut signed_tx = sign(MutableTransaction::with_entries(unsigned_tx, entries), self.schnorr_key).tx;
+        signed_tx.finalize();
+        signed_tx
+    }
+}
+
+/// Submits `blocks` to consensus and returns the overall processing time, including virtual processing
+async fn process_blocks(
+    consensus: &TestConsensus,
+    blocks: impl Iterator<Item = Block>,
+    expected_status: impl Fn(&BlockStatus) -> bool,
+) -> Duration {
+    let start = Instant::now();
+    let chunks = blocks.chunks(CHUNK_SIZE);
+    for chunk in chunks.into_iter() {
+        let futures = chunk.map(|block| consensus.validate_and_insert_block(block).virtual_state_task).collect_vec();
+        let statuses = try_join_all(futures).await.unwrap();
+        assert!(statuses.iter().all(&expected_status));
+    }
+    start.elapsed()
+}
+
+async fn run_profile(profile: &Profile) {
+    let config = build_config(profile);
+    eprintln!("[{}] Generating a DAG of {} blocks (bps={}, delay={})", profile.name, profile.blocks, profile.bps, profile.delay);
+    let dag = DagGenerator::new(profile, &config).generate().await;
+    eprintln!(
+        "[{}] Generated DAG stats: parents mean: {:.2}, txs mean: {:.2}",
+        profile.name,
+        dag.parents_mean(),
+        dag.txs as f64 / dag.blocks.len() as f64
+    );
+
+    // Replay the DAG on a fresh instance, first the headers and then the full blocks
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+    eprintln!("[{}] Processing {} headers...", profile.name, dag.blocks.len());
+    let headers_elapsed =
+        process_blocks(&consensus, dag.blocks.iter().map(|b| Block::from_header_arc(b.header.clone())), |s| s.is_header_only()).await;
+    // Store reads performed while building the block windows of the headers
+    let header_window_store_reads = consensus.processing_counters().snapshot().window_store_reads;
+    eprintln!("[{}] Processing {} blocks with {} transactions overall...", profile.name, dag.blocks.len(), dag.txs);
+    let blocks_elapsed = process_blocks(&consensus, dag.blocks.iter().cloned(), |s| s.is_utxo_valid_or_pending()).await;
+    assert_eq!(consensus.block_status(consensus.get_sink()), BlockStatus::StatusUTXOValid);
+    consensus.shutdown(wait_handles);
+
+    let blocks = dag.blocks.len() as f64;
+    let result = json!({
+        "bench": "block_processing",
+        "profile": profile.name,
+        "bps": profile.bps,
+        "blocks": dag.blocks.len(),
+        "txs": dag.txs,
+        "headers_elapsed_ms": headers_elapsed.as_millis() as u64,
+        "blocks_elapsed_ms": blocks_elapsed.as_millis() as u64,
+        "headers_per_sec": blocks / headers_elapsed.as_secs_f64(),
+        "header_window_store_reads": header_window_store_reads,
+        "window_store_reads_per_header": header_window_store_reads as f64 / blocks,
+        "blocks_per_sec": blocks / blocks_elapsed.as_secs_f64(),
+        "txs_per_sec": dag.txs as f64 / blocks_elapsed.as_secs_f64(),
+    });
+    println!("{result}");
+}
+
+fn main() {
+    // Cargo passes flags such as `--bench`, any other argument filters the profiles by name
+    let filter = std::env::args().skip(1).find(|arg| !arg.starts_with('-'));
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    for profile in PROFILES.iter().filter(|p| filter.as_ref().map_or(true, |filter| p.name.contains(filter.as_str()))) {
+        runtime.block_on(run_profile(profile));
+    }
+}
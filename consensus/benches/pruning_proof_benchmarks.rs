@@ -0,0 +1,93 @@
+//! Pruning point proof validation benchmark.
+//!
+//! Validates a recorded pruning point proof in the context of a fresh consensus instance, once with the proof levels
+//! processed concurrently on the global rayon pool and once with a single thread, and asserts that both outcomes are
+//! identical.
+//!
+//! Recorded proofs are too large to be kept in the repository. A proof is recorded as a gzipped file holding one JSON
+//! array of headers per proof level, starting from level 0 (i.e., `serde_json::to_string(&proof[level])` for each level
+//! of the proof returned by `ConsensusApi::get_pruning_point_proof` of a node synced with the network).
+//!
+//! Run with `KASPA_PRUNING_PROOF_PATH=<file> [KASPA_PRUNING_PROOF_NETWORK=<network id>] cargo bench --bench pruning_proof_benchmarks`,
+//! where the network defaults to `testnet-10`. Results are reported as a single line JSON object on stdout.
+
+use flate2::read::GzDecoder;
+use itertools::Itertools;
+use kaspa_consensus::{config::Config, consensus::test_consensus::TestConsensus, params::Params};
+use kaspa_consensus_core::{
+    api::ConsensusApi, errors::pruning::PruningImportResult, header::Header, network::NetworkId, pruning::PruningPointProof,
+};
+use serde_json::json;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    str::FromStr,
+    sync::{atomic::AtomicBool, Arc},
+    time::{Duration, Instant},
+};
+
+const PROOF_PATH_ENV: &str = "KASPA_PRUNING_PROOF_PATH";
+const NETWORK_ENV: &str = "KASPA_PRUNING_PROOF_NETWORK";
+const DEFAULT_NETWORK: &str = "testnet-10";
+
+fn load_proof(path: &str) -> PruningPointProof {
+    let file = File::open(path).unwrap_or_else(|err| panic!("cannot open {path}: {err}"));
+    BufReader::new(GzDecoder::new(file))
+        .lines()
+        .map(|line| serde_json::from_str::<Vec<Header>>(&line.unwrap()).unwrap().into_iter().map(Arc::new).collect_vec())
+        .collect_vec()
+}
+
+fn validate(consensus: &TestConsensus, proof: &PruningPointProof) -> (PruningImportResult<()>, Duration) {
+    let start = Instant::now();
+    let result = consensus.validate_pruning_proof_with_progress(
+        proof,
+        &|progress| {
+            if progress.processed_levels == progress.total_levels {
+                eprintln!("Validated {} proof headers", progress.processed_headers);
+            }
+        },
+        &AtomicBool::new(false),
+    );
+    (result, start.elapsed())
+}
+
+fn main() {
+    let Ok(path) = std::env::var(PROOF_PATH_ENV) else {
+        eprintln!("{PROOF_PATH_ENV} is not set, skipping the pruning proof benchmark");
+        return;
+    };
+    let network = std::env::var(NETWORK_ENV).unwrap_or_else(|_| DEFAULT_NETWORK.to_string());
+    let network_id = NetworkId::from_str(&network).unwrap();
+    let config = Config::new(Params::from(network_id));
+
+    eprintln!("Loading the pruning point proof from {path}...");
+    let proof = load_proof(&path);
+    let headers = proof.iter().map(|level| level.len()).sum::<usize>();
+    eprintln!("Loaded a proof with {} levels and {} headers overall", proof.len(), headers);
+
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    eprintln!("Validating the proof on the global pool ({} threads)...", rayon::current_num_threads());
+    let (parallel_result, parallel_elapsed) = validate(&consensus, &proof);
+    eprintln!("Validating the proof on a single thread...");
+    let serial_pool = rayon::ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+    let (serial_result, serial_elapsed) = serial_pool.install(|| validate(&consensus, &proof));
+    assert_eq!(format!("{parallel_result:?}"), format!("{serial_result:?}"), "parallel and serial validation outcomes differ");
+
+    consensus.shutdown(wait_handles);
+
+    let result = json!({
+        "bench": "pruning_proof_validation",
+        "network": network_id.to_string(),
+        "levels": proof.len(),
+        "headers": headers,
+        "valid": parallel_result.is_ok(),
+        "threads": rayon::current_num_threads(),
+        "parallel_elapsed_ms": parallel_elapsed.as_millis() as u64,
+        "serial_elapsed_ms": serial_elapsed.as_millis() as u64,
+        "speedup": serial_elapsed.as_secs_f64() / parallel_elapsed.as_secs_f64(),
+    });
+    println!("{result}");
+}
@@ -0,0 +1,37 @@
+use crate::imports::*;
+use crate::result::Result;
+use kaspa_consensus_core::acceptance_data::{self as native, AcceptanceProof};
+use kaspa_hashes::Hash;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_ACCEPTANCE_PROOF: &'static str = r#"
+/**
+ * Interface defining the structure of a merkle proof of the acceptance
+ * of a transaction, as returned by the `getAcceptanceProof` RPC method.
+ * 
+ * @category Consensus
+ */
+export interface IAcceptanceProof {
+    leafIndex: number;
+    path: HexString[];
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "IAcceptanceProof")]
+    pub type IAcceptanceProof;
+}
+
+/// Verifies that `proof` authenticates the acceptance of `transactionId` against the
+/// `acceptedIdMerkleRoot` of the accepting block header. Does not require node access,
+/// making it suitable for light clients holding the block header alone.
+/// @category Consensus
+#[wasm_bindgen(js_name = verifyAcceptanceProof)]
+#[allow(non_snake_case)]
+pub fn js_verify_acceptance_proof(acceptedIdMerkleRoot: String, transactionId: String, proof: IAcceptanceProof) -> Result<bool> {
+    let accepted_id_merkle_root = Hash::from_hex_lenient(&acceptedIdMerkleRoot)?;
+    let transaction_id = TransactionId::from_hex_lenient(&transactionId)?;
+    let proof: AcceptanceProof = serde_wasm_bindgen::from_value(proof.into())?;
+    Ok(native::verify_acceptance_proof(accepted_id_merkle_root, transaction_id, &proof))
+}
@@ -0,0 +1,123 @@
+//!
+//! Compact binary encoding of the client data structures, allowing to pass
+//! them as transferable buffers between threads (e.g. to and from a Web Worker)
+//! without the loss of fidelity of a JSON round trip.
+//!
+
+use crate::imports::*;
+use crate::result::Result;
+use crate::utxo::{UtxoEntry, UtxoEntryReference};
+use borsh::{BorshDeserialize, BorshSerialize};
+use kaspa_addresses::Address;
+
+/// Version of the binary encoding, prefixing every encoded buffer
+pub const BINARY_ENCODING_VERSION: u8 = 1;
+
+pub(crate) fn to_bytes<T: BorshSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = vec![BINARY_ENCODING_VERSION];
+    value.serialize(&mut bytes).expect("serializing into a vector never fails");
+    bytes
+}
+
+pub(crate) fn from_bytes<T: BorshDeserialize>(bytes: &[u8]) -> Result<T> {
+    match bytes.split_first() {
+        Some((&BINARY_ENCODING_VERSION, payload)) => T::try_from_slice(payload).map_err(|err| Error::BinaryEncoding(err.to_string())),
+        Some((version, _)) => Err(Error::BinaryEncoding(format!("unsupported encoding version {version}"))),
+        None => Err(Error::BinaryEncoding("empty buffer".to_string())),
+    }
+}
+
+/// Binary representation of a [`UtxoEntry`]
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct BinaryUtxoEntry {
+    address: Option<Address>,
+    outpoint: cctx::TransactionOutpoint,
+    entry: cctx::UtxoEntry,
+}
+
+impl From<&UtxoEntry> for BinaryUtxoEntry {
+    fn from(utxo: &UtxoEntry) -> Self {
+        Self { address: utxo.address.clone(), outpoint: utxo.outpoint.clone().into(), entry: utxo.into() }
+    }
+}
+
+impl From<BinaryUtxoEntry> for UtxoEntry {
+    fn from(utxo: BinaryUtxoEntry) -> Self {
+        let BinaryUtxoEntry { address, outpoint, entry } = utxo;
+        UtxoEntry {
+            address,
+            outpoint: outpoint.into(),
+            amount: entry.amount,
+            script_public_key: entry.script_public_key,
+            block_daa_score: entry.block_daa_score,
+            is_coinbase: entry.is_coinbase,
+        }
+    }
+}
+
+impl From<BinaryUtxoEntry> for UtxoEntryReference {
+    fn from(utxo: BinaryUtxoEntry) -> Self {
+        UtxoEntry::from(utxo).into()
+    }
+}
+
+#[cfg(all(test, feature = "wasm32-sdk"))]
+mod tests {
+    use super::*;
+    use crate::{sign_with_multiple_v3, Signed, Transaction, TransactionInput, TransactionOutpoint, TransactionOutput};
+    use kaspa_addresses::{Prefix, Version};
+    use kaspa_consensus_core::subnets::SUBNETWORK_ID_NATIVE;
+    use kaspa_txscript::pay_to_address_script;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    fn sign(transaction: Transaction, private_key: [u8; 32]) -> Transaction {
+        match sign_with_multiple_v3(transaction, &[private_key]).unwrap() {
+            Signed::Fully(transaction) => transaction,
+            Signed::Partially(_) => panic!("the transaction is expected to be fully signed"),
+        }
+    }
+
+    /// Simulates generating a transaction on the main thread, signing it in a Web Worker
+    /// and submitting it back from the main thread, with both threads exchanging buffers
+    #[wasm_bindgen_test]
+    pub fn test_worker_signing_round_trip() {
+        let private_key = [0x11u8; 32];
+        let keypair = secp256k1::Keypair::from_seckey_slice(secp256k1::SECP256K1, &private_key).unwrap();
+        let address = Address::new(Prefix::Testnet, Version::PubKey, &keypair.x_only_public_key().0.serialize());
+        let script_public_key = pay_to_address_script(&address);
+        let outpoint = TransactionOutpoint::new(TransactionId::from_u64_word(7), 1);
+        let utxo = UtxoEntryReference::from(UtxoEntry {
+            address: Some(address.clone()),
+            outpoint: outpoint.clone(),
+            amount: 100_000_000,
+            script_public_key: script_public_key.clone(),
+            block_daa_score: 42,
+            is_coinbase: false,
+        });
+        let input = TransactionInput::new(outpoint, vec![], 0, 1, Some(utxo.clone()));
+        let output = TransactionOutput::new(99_000_000, script_public_key);
+        let transaction = Transaction::new(None, 0, vec![input], vec![output], 0, SUBNETWORK_ID_NATIVE, 0, vec![0, 1, 2], 0).unwrap();
+
+        // Same-thread flow
+        let signed_in_place = sign(Transaction::from_bytes(&transaction.to_bytes()).unwrap(), private_key);
+
+        // Worker flow: the buffers are what gets posted between the threads
+        let request = transaction.to_bytes();
+        let signed_in_worker = sign(Transaction::from_bytes(&request).unwrap(), private_key).to_bytes();
+        let signed = Transaction::from_bytes(&signed_in_worker).unwrap();
+
+        assert_eq!(signed.id(), transaction.id());
+        assert_eq!(signed.id(), signed_in_place.id());
+        assert_eq!(signed.to_bytes(), signed_in_worker);
+        let signed_input = signed.inner().inputs[0].clone();
+        assert_eq!(signed_input.inner().signature_script.len(), 66);
+        let signed_utxo = signed_input.utxo().unwrap();
+        assert_eq!(signed_utxo.as_ref().address, Some(address));
+        assert_eq!(cctx::UtxoEntry::from(signed_utxo.as_ref()), cctx::UtxoEntry::from(utxo.as_ref()));
+        assert_eq!(UtxoEntryReference::from_bytes(&utxo.to_bytes()).unwrap().id(), utxo.id());
+
+        assert!(Transaction::from_bytes(&[]).is_err());
+        assert!(Transaction::from_bytes(&[BINARY_ENCODING_VERSION + 1]).is_err());
+        assert!(Transaction::from_bytes(&request[..request.len() - 1]).is_err());
+    }
+}
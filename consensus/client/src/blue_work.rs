@@ -0,0 +1,66 @@
+use crate::imports::*;
+use crate::result::Result;
+use kaspa_consensus_core::BlueWorkType;
+
+#[wasm_bindgen]
+extern "C" {
+    /// A blue work, as a `BlueWork`, a `bigint` or its hex representation
+    #[wasm_bindgen(typescript_type = "BlueWork | bigint | HexString")]
+    pub type BlueWorkT;
+}
+
+/// The accumulated blue work of a block, a 192-bit unsigned integer. Parsed from hex in any letter
+/// case and optionally prefixed with `0x`, and always emitted as lowercase hex without leading zeros.
+/// @category Consensus
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, CastFromJs)]
+#[wasm_bindgen]
+pub struct BlueWork(BlueWorkType);
+
+#[wasm_bindgen]
+impl BlueWork {
+    #[wasm_bindgen(constructor)]
+    pub fn constructor(value: &BlueWorkT) -> Result<BlueWork> {
+        Self::try_owned_from(value)
+    }
+
+    #[wasm_bindgen(js_name = fromHex)]
+    pub fn from_hex(hex: &str) -> Result<BlueWork> {
+        Ok(Self(BlueWorkType::from_hex_lenient(hex)?))
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn js_to_string(&self) -> String {
+        format!("{:x}", self.0)
+    }
+
+    #[wasm_bindgen(js_name = toBigInt)]
+    pub fn to_bigint(&self) -> Result<js_sys::BigInt> {
+        self.0.try_into().map_err(|err| Error::convert("blueWork", err))
+    }
+
+    /// Returns `true` if `other` is the same amount of blue work
+    pub fn equals(&self, other: &BlueWorkT) -> bool {
+        Self::try_owned_from(other).is_ok_and(|other| other == *self)
+    }
+}
+
+impl From<BlueWorkType> for BlueWork {
+    fn from(blue_work: BlueWorkType) -> Self {
+        Self(blue_work)
+    }
+}
+
+impl From<BlueWork> for BlueWorkType {
+    fn from(blue_work: BlueWork) -> Self {
+        blue_work.0
+    }
+}
+
+impl TryCastFromJs for BlueWork {
+    type Error = Error;
+    fn try_cast_from(value: impl AsRef<JsValue>) -> std::result::Result<Cast<Self>, Self::Error> {
+        Self::resolve(&value, || {
+            Ok(Self(BlueWorkType::try_from(value.as_ref().clone()).map_err(|err| Error::convert("blueWork", err))?))
+        })
+    }
+}
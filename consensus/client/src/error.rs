@@ -48,6 +48,9 @@ pub enum Error {
 
     #[error("Transaction input is missing UTXO entry")]
     MissingUtxoEntry,
+
+    #[error("Error decoding binary data: {0}")]
+    BinaryEncoding(String),
 }
 
 impl Error {
@@ -53,3 +53,64 @@ impl TransactionSigningHashECDSA {
         self.hasher.clone().finalize().to_string()
     }
 }
+
+#[wasm_bindgen]
+extern "C" {
+    /// A transaction id, a hash or their hex representation, in any letter case and optionally prefixed with `0x`
+    #[wasm_bindgen(typescript_type = "TransactionId | Hash | HexString")]
+    pub type TransactionIdT;
+}
+
+/// Identifier of a transaction. Parsed from hex in any letter case and optionally
+/// prefixed with `0x`, and always emitted as lowercase hex without prefix.
+/// @category Consensus
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CastFromJs)]
+#[wasm_bindgen(js_name = TransactionId)]
+pub struct TransactionIdWrapper(TransactionId);
+
+#[wasm_bindgen(js_class = TransactionId)]
+impl TransactionIdWrapper {
+    #[wasm_bindgen(constructor)]
+    pub fn constructor(hex: &str) -> Result<TransactionIdWrapper> {
+        Ok(Self(TransactionId::from_hex_lenient(hex)?))
+    }
+
+    #[wasm_bindgen(js_name = fromHex)]
+    pub fn from_hex(hex: &str) -> Result<TransactionIdWrapper> {
+        Self::constructor(hex)
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn js_to_string(&self) -> String {
+        self.0.to_string()
+    }
+
+    #[wasm_bindgen(js_name = toHash)]
+    pub fn to_hash(&self) -> native::Hash {
+        self.0
+    }
+
+    /// Returns `true` if `other` identifies the same transaction
+    pub fn equals(&self, other: &TransactionIdT) -> bool {
+        Self::try_owned_from(other).is_ok_and(|other| other == *self)
+    }
+}
+
+impl From<TransactionId> for TransactionIdWrapper {
+    fn from(id: TransactionId) -> Self {
+        Self(id)
+    }
+}
+
+impl From<TransactionIdWrapper> for TransactionId {
+    fn from(id: TransactionIdWrapper) -> Self {
+        id.0
+    }
+}
+
+impl TryCastFromJs for TransactionIdWrapper {
+    type Error = Error;
+    fn try_cast_from(value: impl AsRef<JsValue>) -> std::result::Result<Cast<Self>, Self::Error> {
+        Self::resolve(&value, || Ok(Self(native::Hash::try_owned_from(value.as_ref())?)))
+    }
+}
@@ -1,3 +1,4 @@
+use crate::blue_work::BlueWork;
 use crate::error::Error;
 use js_sys::{Array, Object};
 use kaspa_consensus_core::hashing;
@@ -28,7 +29,7 @@ export interface IHeader {
     bits: number;
     nonce: bigint;
     daaScore: bigint;
-    blueWork: bigint | HexString;
+    blueWork: BlueWork | bigint | HexString;
     blueScore: bigint;
     pruningPoint: HexString;
 }
@@ -78,6 +79,15 @@ impl Header {
         inner.hash.to_hex()
     }
 
+    /// Returns the header serialization hashed into the pre-PoW hash, with a zero timestamp and nonce.
+    /// The nonce of the header is kept if `nonceZeroed` is `false`. This serialization is stable and
+    /// can be hashed by external miners as done by the node.
+    /// @return { Uint8Array } pre-PoW header serialization
+    #[wasm_bindgen(js_name = serializePrePow)]
+    pub fn serialize_pre_pow(&self, nonce_zeroed: Option<bool>) -> Vec<u8> {
+        self.inner().serialize_pre_pow(nonce_zeroed.unwrap_or(true))
+    }
+
     /// Obtain `JSON` representation of the header. JSON representation
     /// should be obtained using WASM, to ensure proper serialization of
     /// big integers.
@@ -158,7 +168,7 @@ impl Header {
 
     #[wasm_bindgen(setter = hashMerkleRoot)]
     pub fn set_hash_merkle_root_from_js_value(&mut self, js_value: JsValue) {
-        self.inner_mut().hash_merkle_root = Hash::from_slice(&js_value.try_as_vec_u8().expect("hash merkle root"));
+        self.inner_mut().hash_merkle_root = js_value.try_into_owned().unwrap_or_else(|err| panic!("invalid hash merkle root: {err}"));
     }
 
     #[wasm_bindgen(getter = acceptedIdMerkleRoot)]
@@ -168,7 +178,8 @@ impl Header {
 
     #[wasm_bindgen(setter = acceptedIdMerkleRoot)]
     pub fn set_accepted_id_merkle_root_from_js_value(&mut self, js_value: JsValue) {
-        self.inner_mut().accepted_id_merkle_root = Hash::from_slice(&js_value.try_as_vec_u8().expect("accepted id merkle root"));
+        self.inner_mut().accepted_id_merkle_root =
+            js_value.try_into_owned().unwrap_or_else(|err| panic!("invalid accepted id merkle root: {err}"));
     }
 
     #[wasm_bindgen(getter = utxoCommitment)]
@@ -178,7 +189,7 @@ impl Header {
 
     #[wasm_bindgen(setter = utxoCommitment)]
     pub fn set_utxo_commitment_from_js_value(&mut self, js_value: JsValue) {
-        self.inner_mut().utxo_commitment = Hash::from_slice(&js_value.try_as_vec_u8().expect("utxo commitment"));
+        self.inner_mut().utxo_commitment = js_value.try_into_owned().unwrap_or_else(|err| panic!("invalid utxo commitment: {err}"));
     }
 
     #[wasm_bindgen(getter = pruningPoint)]
@@ -188,7 +199,7 @@ impl Header {
 
     #[wasm_bindgen(setter = pruningPoint)]
     pub fn set_pruning_point_from_js_value(&mut self, js_value: JsValue) {
-        self.inner_mut().pruning_point = Hash::from_slice(&js_value.try_as_vec_u8().expect("pruning point"));
+        self.inner_mut().pruning_point = js_value.try_into_owned().unwrap_or_else(|err| panic!("invalid pruning point: {err}"));
     }
 
     #[wasm_bindgen(getter = parentsByLevel)]
@@ -226,7 +237,8 @@ impl Header {
 
     #[wasm_bindgen(setter = blueWork)]
     pub fn set_blue_work_from_js_value(&mut self, js_value: JsValue) {
-        self.inner_mut().blue_work = js_value.try_into().unwrap_or_else(|err| panic!("invalid blue work: {err}"));
+        self.inner_mut().blue_work =
+            BlueWork::try_owned_from(js_value).unwrap_or_else(|err| panic!("invalid blue work: {err}")).into();
     }
 }
 
@@ -267,7 +279,7 @@ impl TryCastFromJs for Header {
                     timestamp: object.get_u64("timestamp")?,
                     daa_score: object.get_u64("daaScore")?,
                     bits: object.get_u32("bits")?,
-                    blue_work: object.get_value("blueWork")?.try_into().map_err(|err| Error::convert("blueWork", err))?,
+                    blue_work: BlueWork::try_owned_from(object.get_value("blueWork")?)?.into(),
                     blue_score: object.get_u64("blueScore")?,
                     pruning_point: object
                         .get_value("pruningPoint")?
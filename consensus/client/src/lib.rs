@@ -1,3 +1,4 @@
+pub mod binary;
 pub mod error;
 mod imports;
 mod outpoint;
@@ -10,6 +11,8 @@ pub use utxo::*;
 
 cfg_if::cfg_if! {
     if #[cfg(feature = "wasm32-sdk")] {
+        mod acceptance;
+        mod blue_work;
         mod header;
         mod input;
         mod transaction;
@@ -20,6 +23,8 @@ cfg_if::cfg_if! {
         mod serializable;
 
 
+        pub use acceptance::*;
+        pub use blue_work::*;
         pub use header::*;
         pub use input::*;
         pub use transaction::*;
@@ -28,6 +33,6 @@ cfg_if::cfg_if! {
         pub use hash::*;
         // pub use signing::*;
         pub use script::*;
-        pub use sign::sign_with_multiple_v3;
+        pub use sign::{sign_input, sign_with_multiple_v3, SighashType, Signed};
     }
 }
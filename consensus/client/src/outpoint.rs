@@ -51,7 +51,7 @@ impl TryFrom<&JsValue> for TransactionOutpointInner {
         if let Some(string) = js_value.as_string() {
             let vec = string.split('-').collect::<Vec<_>>();
             if vec.len() == 2 {
-                let transaction_id: TransactionId = vec[0].parse()?;
+                let transaction_id = TransactionId::from_hex_lenient(vec[0])?;
                 let id: u32 = vec[1].parse()?;
                 Ok(TransactionOutpointInner::new(transaction_id, id))
             } else {
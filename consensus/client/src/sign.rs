@@ -1,16 +1,50 @@
+use crate::imports::*;
 use crate::transaction::Transaction;
 use core::iter::once;
 use itertools::Itertools;
 use kaspa_consensus_core::{
     hashing::{
         sighash::{calc_schnorr_signature_hash, SigHashReusedValues},
-        sighash_type::SIG_HASH_ALL,
+        sighash_type::{SigHashType, SIG_HASH_ALL, SIG_HASH_ANY_ONE_CAN_PAY, SIG_HASH_NONE, SIG_HASH_SINGLE},
     },
     tx::PopulatedTransaction,
     //sign::Signed,
 };
 use std::collections::BTreeMap;
 
+/// The sighash type used when signing a transaction input.
+/// @see {@link createInputSignature}, {@link signTransactionInput}
+/// @category Wallet SDK
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SighashType {
+    #[default]
+    All,
+    None,
+    Single,
+    AllAnyOneCanPay,
+    NoneAnyOneCanPay,
+    SingleAnyOneCanPay,
+}
+
+impl From<SighashType> for SigHashType {
+    fn from(sighash_type: SighashType) -> SigHashType {
+        let (base, anyone_can_pay) = match sighash_type {
+            SighashType::All => (SIG_HASH_ALL, false),
+            SighashType::None => (SIG_HASH_NONE, false),
+            SighashType::Single => (SIG_HASH_SINGLE, false),
+            SighashType::AllAnyOneCanPay => (SIG_HASH_ALL, true),
+            SighashType::NoneAnyOneCanPay => (SIG_HASH_NONE, true),
+            SighashType::SingleAnyOneCanPay => (SIG_HASH_SINGLE, true),
+        };
+        if anyone_can_pay {
+            SigHashType::from_u8(base.to_u8() | SIG_HASH_ANY_ONE_CAN_PAY.to_u8()).expect("valid sighash type")
+        } else {
+            base
+        }
+    }
+}
+
 /// A wrapper enum that represents the transaction signed state. A transaction
 /// contained by this enum can be either fully signed or partially signed.
 pub enum Signed {
@@ -71,3 +105,16 @@ pub fn sign_with_multiple_v3(tx: Transaction, privkeys: &[[u8; 32]]) -> crate::r
         Ok(Signed::Fully(tx))
     }
 }
+
+/// Sign a single input of a transaction using schnorr and the provided sighash type.
+/// Returns the signature script push `OP_DATA_65 <SIGNATURE+SIGHASH_TYPE>` of the input.
+/// The transaction inputs are expected to be populated with their UTXO entries.
+#[allow(clippy::result_large_err)]
+pub fn sign_input(tx: &Transaction, input_index: usize, privkey: &[u8; 32], hash_type: SigHashType) -> crate::result::Result<Vec<u8>> {
+    if tx.inner().inputs.iter().any(|input| input.utxo().is_none()) {
+        return Err(Error::MissingUtxoEntry);
+    }
+    let (cctx, utxos) = tx.tx_and_utxos();
+    let populated_transaction = PopulatedTransaction::new(&cctx, utxos);
+    Ok(kaspa_consensus_core::sign::sign_input(&populated_transaction, input_index, privkey, hash_type)?)
+}
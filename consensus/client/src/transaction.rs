@@ -1,5 +1,6 @@
 #![allow(non_snake_case)]
 
+use crate::binary::{self, BinaryUtxoEntry};
 use crate::imports::*;
 use crate::input::TransactionInput;
 use crate::outpoint::TransactionOutpoint;
@@ -8,12 +9,23 @@ use crate::result::Result;
 use crate::serializable::{numeric, string};
 use crate::utxo::{UtxoEntryId, UtxoEntryReference};
 use ahash::AHashMap;
+use borsh::{BorshDeserialize, BorshSerialize};
 use kaspa_consensus_core::network::NetworkType;
 use kaspa_consensus_core::network::NetworkTypeT;
 use kaspa_consensus_core::subnets::{self, SubnetworkId};
 use kaspa_consensus_core::tx::UtxoEntry;
 use kaspa_txscript::extract_script_pub_key_address;
 use kaspa_utils::hex::*;
+use kaspa_wasm_core::types::BinaryT;
+
+/// Binary representation of a [`Transaction`], holding the UTXO entry of each input if known
+#[derive(BorshSerialize, BorshDeserialize)]
+struct BinaryTransaction {
+    transaction: cctx::Transaction,
+    // The mass is not part of the transaction encoding
+    mass: u64,
+    utxos: Vec<Option<BinaryUtxoEntry>>,
+}
 
 #[wasm_bindgen(typescript_custom_section)]
 const TS_TRANSACTION: &'static str = r#"
@@ -30,6 +42,8 @@ export interface ITransaction {
     subnetworkId: HexString;
     gas: bigint;
     payload: HexString;
+    /** The mass commitment of the transaction (storage mass activated networks only) */
+    mass?: bigint;
 
     /** Optional verbose data provided by RPC */
     verboseData?: ITransactionVerboseData;
@@ -65,6 +79,8 @@ pub struct TransactionInner {
     pub subnetwork_id: SubnetworkId,
     pub gas: u64,
     pub payload: Vec<u8>,
+    #[serde(default)]
+    pub mass: u64,
 
     // A field that is used to cache the transaction ID.
     // Always use the corresponding self.id() instead of accessing this field directly
@@ -92,6 +108,7 @@ impl Transaction {
         subnetwork_id: SubnetworkId,
         gas: u64,
         payload: Vec<u8>,
+        mass: u64,
     ) -> Result<Self> {
         let finalize = id.is_none();
         let tx = Self {
@@ -104,6 +121,7 @@ impl Transaction {
                 subnetwork_id,
                 gas,
                 payload,
+                mass,
             })),
         };
         if finalize {
@@ -123,6 +141,16 @@ impl Transaction {
     pub fn id(&self) -> TransactionId {
         self.inner().id
     }
+
+    pub fn mass(&self) -> u64 {
+        self.inner().mass
+    }
+
+    /// Set the mass commitment of this transaction. The mass has no effect on the tx ID
+    /// so there is no need to finalize the transaction following this call.
+    pub fn set_mass(&self, mass: u64) {
+        self.inner().mass = mass;
+    }
 }
 
 #[wasm_bindgen]
@@ -234,6 +262,16 @@ impl Transaction {
         self.inner().gas = v;
     }
 
+    #[wasm_bindgen(getter, js_name = mass)]
+    pub fn get_mass(&self) -> u64 {
+        self.inner().mass
+    }
+
+    #[wasm_bindgen(setter, js_name = mass)]
+    pub fn set_mass_js(&self, v: u64) {
+        self.inner().mass = v;
+    }
+
     #[wasm_bindgen(getter = subnetworkId)]
     pub fn get_subnetwork_id_as_hex(&self) -> String {
         self.inner().subnetwork_id.to_hex()
@@ -269,6 +307,7 @@ impl TryCastFromJs for Transaction {
                     let lock_time = object.get_u64("lockTime")?;
                     let gas = object.get_u64("gas")?;
                     let payload = object.get_vec_u8("payload")?;
+                    let mass = if object.try_get_value("mass")?.is_some() { object.get_u64("mass")? } else { 0 };
                     let subnetwork_id = object.get_vec_u8("subnetworkId")?;
                     if subnetwork_id.len() != subnets::SUBNETWORK_ID_SIZE {
                         return Err(Error::Custom("subnetworkId must be 20 bytes long".into()));
@@ -287,7 +326,7 @@ impl TryCastFromJs for Transaction {
                         .iter()
                         .map(|jsv| jsv.try_into())
                         .collect::<std::result::Result<Vec<TransactionOutput>, Error>>()?;
-                    Transaction::new(id, version, inputs, outputs, lock_time, subnetwork_id, gas, payload).map(Into::into)
+                    Transaction::new(id, version, inputs, outputs, lock_time, subnetwork_id, gas, payload, mass).map(Into::into)
                 }
             } else {
                 Err("Transaction must be an object".into())
@@ -300,6 +339,7 @@ impl TryCastFromJs for Transaction {
 impl From<cctx::Transaction> for Transaction {
     fn from(tx: cctx::Transaction) -> Self {
         let id = tx.id();
+        let mass = tx.mass();
         let inputs: Vec<TransactionInput> = tx.inputs.into_iter().map(|input| input.into()).collect::<Vec<TransactionInput>>();
         let outputs: Vec<TransactionOutput> = tx.outputs.into_iter().map(|output| output.into()).collect::<Vec<TransactionOutput>>();
         Self::new_with_inner(TransactionInner {
@@ -310,6 +350,7 @@ impl From<cctx::Transaction> for Transaction {
             gas: tx.gas,
             payload: tx.payload,
             subnetwork_id: tx.subnetwork_id,
+            mass,
             id,
         })
     }
@@ -322,7 +363,7 @@ impl From<&Transaction> for cctx::Transaction {
             inner.inputs.clone().into_iter().map(|input| input.as_ref().into()).collect::<Vec<cctx::TransactionInput>>();
         let outputs: Vec<cctx::TransactionOutput> =
             inner.outputs.clone().into_iter().map(|output| output.as_ref().into()).collect::<Vec<cctx::TransactionOutput>>();
-        cctx::Transaction::new(
+        let tx = cctx::Transaction::new(
             inner.version,
             inputs,
             outputs,
@@ -330,7 +371,9 @@ impl From<&Transaction> for cctx::Transaction {
             inner.subnetwork_id.clone(),
             inner.gas,
             inner.payload.clone(),
-        )
+        );
+        tx.set_mass(inner.mass);
+        tx
     }
 }
 
@@ -356,6 +399,7 @@ impl Transaction {
             gas: tx.gas,
             payload: tx.payload.clone(),
             subnetwork_id: tx.subnetwork_id.clone(),
+            mass: tx.mass(),
         })
     }
 
@@ -382,6 +426,7 @@ impl Transaction {
             inner.gas,
             inner.payload.clone(),
         );
+        tx.set_mass(inner.mass);
 
         (tx, utxos)
     }
@@ -435,4 +480,40 @@ impl Transaction {
     pub fn deserialize_from_safe_json(json: &str) -> Result<Transaction> {
         string::SerializableTransaction::deserialize_from_json(json)?.try_into()
     }
+
+    /// Serializes the transaction, along with the UTXO entries of its inputs, to a compact
+    /// binary buffer. The buffer can be posted to a Web Worker as a transferable, where the
+    /// transaction is deserialized using {@link Transaction.fromBytes}, signed and serialized
+    /// back, so that it can be submitted from the main thread.
+    #[wasm_bindgen(js_name = "toBytes")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let transaction = cctx::Transaction::from(self);
+        let utxos = self.inner().inputs.iter().map(|input| input.utxo().map(|utxo| BinaryUtxoEntry::from(utxo.as_ref()))).collect();
+        binary::to_bytes(&BinaryTransaction { mass: transaction.mass(), transaction, utxos })
+    }
+
+    /// Deserializes the {@link Transaction} from a binary buffer produced by {@link Transaction.toBytes}
+    /// or {@link PendingTransaction.toBytes}.
+    #[wasm_bindgen(js_name = "fromBytes")]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Transaction> {
+        let BinaryTransaction { mut transaction, mass, utxos } = binary::from_bytes(bytes)?;
+        if utxos.len() != transaction.inputs.len() {
+            return Err(Error::BinaryEncoding("the count of UTXO entries does not match the count of inputs".to_string()));
+        }
+        // The encoded transaction id is not trusted
+        transaction.finalize();
+        transaction.set_mass(mass);
+        let utxos = utxos.into_iter().flatten().map(UtxoEntryReference::from).map(|utxo| (utxo.id(), utxo)).collect();
+        Ok(Self::from_cctx_transaction(&transaction, &utxos))
+    }
+
+    /// Sets the signature script of the input at `inputIndex`, e.g. to a signature created by
+    /// {@link createInputSignature} or to a script assembled from several such signatures.
+    /// @see {@link createInputSignature}
+    #[wasm_bindgen(js_name = "fillInput")]
+    pub fn fill_input(&self, input_index: usize, signature_script: BinaryT) -> Result<()> {
+        let signature_script =
+            JsValue::from(signature_script).try_as_vec_u8().map_err(|_| Error::custom("invalid signature script"))?;
+        self.set_signature_script(input_index, signature_script)
+    }
 }
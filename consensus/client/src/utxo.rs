@@ -1,3 +1,4 @@
+use crate::binary::{self, BinaryUtxoEntry};
 use crate::imports::*;
 use crate::outpoint::{TransactionOutpoint, TransactionOutpointInner};
 use crate::result::Result;
@@ -131,6 +132,21 @@ impl UtxoEntryReference {
         Ok(js_sys::JSON::stringify(&object)?)
     }
 
+    /// Serializes the UTXO entry to a compact binary buffer which can be
+    /// transferred to a Web Worker and deserialized there using
+    /// {@link UtxoEntryReference.fromBytes}.
+    #[wasm_bindgen(js_name = "toBytes")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        binary::to_bytes(&BinaryUtxoEntry::from(self.as_ref()))
+    }
+
+    /// Deserializes a UTXO entry from a binary buffer produced by
+    /// {@link UtxoEntryReference.toBytes}.
+    #[wasm_bindgen(js_name = "fromBytes")]
+    pub fn from_bytes(bytes: &[u8]) -> Result<UtxoEntryReference> {
+        Ok(binary::from_bytes::<BinaryUtxoEntry>(bytes)?.into())
+    }
+
     #[wasm_bindgen(getter)]
     pub fn entry(&self) -> UtxoEntry {
         self.as_ref().clone()
@@ -1,7 +1,8 @@
 use kaspa_hashes::Hash;
+use kaspa_merkle::calc_merkle_root_from_path;
 use serde::{Deserialize, Serialize};
 
-use crate::tx::TransactionId;
+use crate::tx::{TransactionId, UtxoEntry};
 
 pub type AcceptanceData = Vec<MergesetBlockAcceptanceData>;
 
@@ -16,3 +17,52 @@ pub struct AcceptedTxEntry {
     pub transaction_id: TransactionId,
     pub index_within_block: u32,
 }
+
+/// The UTXO entries spent by an accepted transaction, ordered as its inputs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcceptedTxSpentEntries {
+    pub transaction_id: TransactionId,
+    pub entries: Vec<UtxoEntry>,
+}
+
+/// The UTXO entries spent by the non-coinbase transactions accepted by a chain block, in acceptance order.
+/// Only recorded when [`crate::config::Config::store_spent_utxos`] is set.
+pub type SpentUtxoEntries = Vec<AcceptedTxSpentEntries>;
+
+/// A transaction spending a given outpoint, as found within the mergeset of a selected chain block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutpointSpender {
+    pub transaction_id: TransactionId,
+    /// The block containing the spending transaction
+    pub block_hash: Hash,
+    /// The selected chain block whose mergeset contains `block_hash`
+    pub merging_block_hash: Hash,
+    /// Whether the spending transaction was accepted by `merging_block_hash`
+    pub is_accepted: bool,
+}
+
+/// The spend status of an outpoint from the POV of the virtual state
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutpointSpendStatus {
+    /// The UTXO entry of the outpoint if it is unspent by the virtual UTXO set
+    pub utxo_entry: Option<UtxoEntry>,
+    /// The spending transactions found within the searched chain blocks, ordered from the sink downwards
+    pub spenders: Vec<OutpointSpender>,
+}
+
+/// Merkle proof of the acceptance of a transaction by a chain block, authenticating the transaction id against
+/// the `accepted_id_merkle_root` of the accepting block header. See [`verify_acceptance_proof`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptanceProof {
+    /// The index of the transaction id within the sorted accepted transaction ids of the accepting block
+    pub leaf_index: u32,
+    /// The sibling hashes from the leaf level up to the root
+    pub path: Vec<Hash>,
+}
+
+/// Verifies that `proof` authenticates `transaction_id` against the `accepted_id_merkle_root` of a block header.
+/// Does not require any consensus data, hence usable by light clients holding the header alone.
+pub fn verify_acceptance_proof(accepted_id_merkle_root: Hash, transaction_id: TransactionId, proof: &AcceptanceProof) -> bool {
+    calc_merkle_root_from_path(transaction_id, proof.leaf_index as usize, &proof.path) == Some(accepted_id_merkle_root)
+}
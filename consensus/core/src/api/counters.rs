@@ -10,6 +10,13 @@ pub struct ProcessingCounters {
     pub txs_counts: AtomicU64,
     pub chain_block_counts: AtomicU64,
     pub mass_counts: AtomicU64,
+    pub reindex_counts: AtomicU64,
+    pub reindexed_blocks_counts: AtomicU64,
+    pub global_reindex_counts: AtomicU64,
+    /// Gauge of the interval capacity left unallocated by the latest reachability reindex
+    pub reindex_slack_remaining: AtomicU64,
+    /// Store reads performed by the window managers while building block windows
+    pub window_store_reads: AtomicU64,
 }
 
 impl ProcessingCounters {
@@ -23,6 +30,11 @@ impl ProcessingCounters {
             txs_counts: self.txs_counts.load(Ordering::Relaxed),
             chain_block_counts: self.chain_block_counts.load(Ordering::Relaxed),
             mass_counts: self.mass_counts.load(Ordering::Relaxed),
+            reindex_counts: self.reindex_counts.load(Ordering::Relaxed),
+            reindexed_blocks_counts: self.reindexed_blocks_counts.load(Ordering::Relaxed),
+            global_reindex_counts: self.global_reindex_counts.load(Ordering::Relaxed),
+            reindex_slack_remaining: self.reindex_slack_remaining.load(Ordering::Relaxed),
+            window_store_reads: self.window_store_reads.load(Ordering::Relaxed),
         }
     }
 }
@@ -37,6 +49,11 @@ pub struct ProcessingCountersSnapshot {
     pub txs_counts: u64,
     pub chain_block_counts: u64,
     pub mass_counts: u64,
+    pub reindex_counts: u64,
+    pub reindexed_blocks_counts: u64,
+    pub global_reindex_counts: u64,
+    pub reindex_slack_remaining: u64,
+    pub window_store_reads: u64,
 }
 
 impl core::ops::Sub for &ProcessingCountersSnapshot {
@@ -52,6 +69,12 @@ impl core::ops::Sub for &ProcessingCountersSnapshot {
             txs_counts: self.txs_counts.saturating_sub(rhs.txs_counts),
             chain_block_counts: self.chain_block_counts.saturating_sub(rhs.chain_block_counts),
             mass_counts: self.mass_counts.saturating_sub(rhs.mass_counts),
+            reindex_counts: self.reindex_counts.saturating_sub(rhs.reindex_counts),
+            reindexed_blocks_counts: self.reindexed_blocks_counts.saturating_sub(rhs.reindexed_blocks_counts),
+            global_reindex_counts: self.global_reindex_counts.saturating_sub(rhs.global_reindex_counts),
+            // A gauge rather than a counter, so the latest value is kept
+            reindex_slack_remaining: self.reindex_slack_remaining,
+            window_store_reads: self.window_store_reads.saturating_sub(rhs.window_store_reads),
         }
     }
 }
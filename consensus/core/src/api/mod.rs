@@ -1,13 +1,14 @@
 use futures_util::future::BoxFuture;
 use kaspa_muhash::MuHash;
-use std::sync::Arc;
+use std::sync::{atomic::AtomicBool, Arc};
 
 use crate::{
-    acceptance_data::AcceptanceData,
-    block::{Block, BlockTemplate, TemplateBuildMode, TemplateTransactionSelector, VirtualStateApproxId},
+    acceptance_data::{AcceptanceData, AcceptanceProof, OutpointSpendStatus},
+    block::{Block, BlockTemplate, BlockTemplateValidation, TemplateBuildMode, TemplateTransactionSelector, VirtualStateApproxId},
     blockstatus::BlockStatus,
-    coinbase::MinerData,
-    daa_score_timestamp::DaaScoreTimestamp,
+    coinbase::{CoinbaseAmounts, MinerData},
+    daa_score_timestamp::{ChainBlockAtDaaScore, DaaScoreTimestamp},
+    difficulty::BlockDifficultyContext,
     errors::{
         block::{BlockProcessResult, RuleError},
         coinbase::CoinbaseResult,
@@ -16,16 +17,18 @@ use crate::{
         tx::TxResult,
     },
     header::Header,
-    pruning::{PruningPointProof, PruningPointTrustedData, PruningPointsList},
+    pruning::{PruningPointProof, PruningPointTrustedData, PruningPointsList, PruningProofValidationProgress},
     trusted::{ExternalGhostdagData, TrustedBlock},
-    tx::{MutableTransaction, Transaction, TransactionOutpoint, UtxoEntry},
+    tx::{MutableTransaction, Transaction, TransactionId, TransactionOutpoint, UtxoEntry},
+    virtual_parents::VirtualParentSelectionTrace,
     BlockHashSet, BlueWorkType, ChainPath,
 };
 use kaspa_hashes::Hash;
 
-pub use self::stats::{BlockCount, ConsensusStats};
+pub use self::stats::{BlockCount, ConsensusStats, VirtualInfo};
 
 pub mod counters;
+pub mod parallelism;
 pub mod stats;
 
 pub type BlockValidationFuture = BoxFuture<'static, BlockProcessResult<BlockStatus>>;
@@ -41,6 +44,31 @@ pub struct BlockValidationFutures {
     pub virtual_state_task: BlockValidationFuture,
 }
 
+/// Summary of the processing of a batch of trusted blocks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TrustedBlocksSummary {
+    /// Number of processed blocks
+    pub blocks: usize,
+
+    /// Number of processed blocks having a body, the others being header-only
+    pub bodies: usize,
+}
+
+impl TrustedBlocksSummary {
+    pub fn from_statuses(statuses: &[BlockStatus]) -> Self {
+        Self { blocks: statuses.len(), bodies: statuses.iter().filter(|status| status.has_block_body()).count() }
+    }
+}
+
+impl std::ops::AddAssign for TrustedBlocksSummary {
+    fn add_assign(&mut self, other: Self) {
+        self.blocks += other.blocks;
+        self.bodies += other.bodies;
+    }
+}
+
+pub type TrustedBlocksValidationFuture = BoxFuture<'static, BlockProcessResult<TrustedBlocksSummary>>;
+
 /// Abstracts the consensus external API
 #[allow(unused_variables)]
 pub trait ConsensusApi: Send + Sync {
@@ -53,6 +81,16 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Validates a block template, possibly modified after being built, against the current virtual state without
+    /// inserting it. The template is expected to build on the current virtual parents. Proof of work is not checked,
+    /// so that the template can be validated before being mined.
+    ///
+    /// Returns the block mass and the total fees of its transactions, or the rule error a submission of the block
+    /// would encounter.
+    fn validate_block_template(&self, block: Block) -> Result<BlockTemplateValidation, RuleError> {
+        unimplemented!()
+    }
+
     fn validate_and_insert_block(&self, block: Block) -> BlockValidationFutures {
         unimplemented!()
     }
@@ -61,6 +99,15 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Submits a batch of trusted blocks at once, the returned future completing once all of them are processed.
+    ///
+    /// The blocks are expected in topological order, as the trusted set built from the pruning point trusted data.
+    /// Independent blocks of the batch are processed concurrently, and no BlockAdded notification is sent for the
+    /// blocks of the batch, which is reported through the returned summary instead.
+    fn validate_and_insert_trusted_blocks(&self, batch: Vec<TrustedBlock>) -> TrustedBlocksValidationFuture {
+        unimplemented!()
+    }
+
     /// Populates the mempool transaction with maximally found UTXO entry data and proceeds to full transaction
     /// validation if all are found. If validation is successful, also [`transaction.calculated_fee`] is expected to be populated.
     fn validate_mempool_transaction(&self, transaction: &mut MutableTransaction) -> TxResult<()> {
@@ -108,6 +155,12 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the DAA score, sink, sink blue score, parents count and past median time of virtual at once.
+    /// Designed to be a fast call reading the cached virtual state only.
+    fn get_virtual_info(&self) -> VirtualInfo {
+        unimplemented!()
+    }
+
     fn get_virtual_merge_depth_root(&self) -> Option<Hash> {
         unimplemented!()
     }
@@ -155,6 +208,13 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the selected chain block whose DAA score is nearest to `daa_score`, preferring the
+    /// earlier block on ties. Scores above the sink resolve to the sink, while scores below the
+    /// pruning point are rejected with a `DaaScoreBelowPruningPoint` error.
+    fn get_chain_block_by_daa_score(&self, daa_score: u64) -> ConsensusResult<ChainBlockAtDaaScore> {
+        unimplemented!()
+    }
+
     fn get_virtual_parents(&self) -> BlockHashSet {
         unimplemented!()
     }
@@ -180,6 +240,12 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Traces the virtual parent selection over the current tips, explaining why each excluded tip was not selected
+    /// as a virtual parent. Diagnostic only, the current virtual parents are unaffected.
+    fn get_virtual_parent_selection_trace(&self) -> VirtualParentSelectionTrace {
+        unimplemented!()
+    }
+
     fn modify_coinbase_payload(&self, payload: Vec<u8>, miner_data: &MinerData) -> CoinbaseResult<Vec<u8>> {
         unimplemented!()
     }
@@ -188,6 +254,17 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Validates the pruning point proof while reporting the validation progress to `progress`.
+    /// The validation is aborted with [`crate::errors::pruning::PruningImportError::PruningValidationCancelled`] once `cancel` is set.
+    fn validate_pruning_proof_with_progress(
+        &self,
+        proof: &PruningPointProof,
+        progress: &(dyn Fn(PruningProofValidationProgress) + Sync),
+        cancel: &AtomicBool,
+    ) -> PruningImportResult<()> {
+        unimplemented!()
+    }
+
     fn apply_pruning_proof(&self, proof: PruningPointProof, trusted_set: &[TrustedBlock]) -> PruningImportResult<()> {
         unimplemented!()
     }
@@ -287,10 +364,58 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the ids of the transactions accepted by each of a set of blocks belonging to the selected parent chain.
+    /// Unlike `self::get_blocks_acceptance_data`, the acceptance data is loaded lazily, one block at a time.
+    fn get_blocks_accepted_transaction_ids(&self, hashes: &[Hash]) -> ConsensusResult<Vec<Vec<TransactionId>>> {
+        unimplemented!()
+    }
+
     fn is_chain_block(&self, hash: Hash) -> ConsensusResult<bool> {
         unimplemented!()
     }
 
+    /// Returns the spend status of `outpoint`: its UTXO entry if unspent by the virtual UTXO set, along with the
+    /// transactions spending it found in the mergesets of the `search_depth` selected chain blocks ending at the
+    /// sink (the search never goes below the pruning point). The transactions are obtained through the acceptance
+    /// data of the chain blocks, so spenders which were not accepted, i.e. conflicting double spends, are reported as well.
+    /// Note that spenders merged by the virtual block alone (e.g. transactions of the sink) are not reported yet.
+    fn get_outpoint_spend_status(&self, outpoint: TransactionOutpoint, search_depth: u64) -> ConsensusResult<OutpointSpendStatus> {
+        unimplemented!()
+    }
+
+    /// Returns a merkle proof of the acceptance of `transaction_id` by the selected chain block `chain_block`, verifiable
+    /// against the `accepted_id_merkle_root` of its header with [`crate::acceptance_data::verify_acceptance_proof`].
+    /// The proof is rebuilt from the acceptance data of the block, hence is unavailable once this data is pruned.
+    fn get_acceptance_proof(&self, chain_block: Hash, transaction_id: TransactionId) -> ConsensusResult<AcceptanceProof> {
+        unimplemented!()
+    }
+
+    /// Returns the UTXO entry spent by input `input_index` of `transaction_id`, as accepted by the selected chain block
+    /// `chain_block`. The entries are only recorded when [`crate::config::Config::store_spent_utxos`] is set, and share
+    /// the lifecycle of the acceptance data of the block.
+    fn get_spent_utxo_entry(&self, chain_block: Hash, transaction_id: TransactionId, input_index: u32) -> ConsensusResult<UtxoEntry> {
+        unimplemented!()
+    }
+
+    /// Returns the amounts paid by the coinbase transaction of the selected chain block `chain_block`. The total is
+    /// read from the coinbase itself, which chain blocks are known to build as expected, while the subsidy is read
+    /// from the coinbase payloads of the rewarded mergeset blocks. Hence unavailable once these bodies are pruned.
+    fn expected_coinbase_amount(&self, chain_block: Hash) -> ConsensusResult<CoinbaseAmounts> {
+        unimplemented!()
+    }
+
+    /// Returns the amounts a block built on the current virtual parents, i.e. a block built from a fresh template,
+    /// is expected to pay in its coinbase transaction
+    fn expected_virtual_coinbase_amount(&self) -> CoinbaseAmounts {
+        unimplemented!()
+    }
+
+    /// Returns the rule violated by `hash` if the block was recently disqualified from the selected chain, i.e. found
+    /// invalid in UTXO context. Reasons are kept in memory for a limited number of blocks and are lost on restart.
+    fn get_disqualification_reason(&self, hash: Hash) -> Option<RuleError> {
+        unimplemented!()
+    }
+
     fn get_pruning_point_utxos(
         &self,
         expected_pruning_point: Hash,
@@ -324,6 +449,11 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the difficulty target, the work and the hashrate estimated over the DAA window of the block `hash`
+    fn get_block_difficulty_context(&self, hash: Hash) -> ConsensusResult<BlockDifficultyContext> {
+        unimplemented!()
+    }
+
     fn validate_pruning_points(&self) -> ConsensusResult<()> {
         unimplemented!()
     }
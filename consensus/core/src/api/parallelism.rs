@@ -0,0 +1,156 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+/// A deferred start of a block processing task
+pub type TaskStarter = Box<dyn FnOnce() + Send>;
+
+/// Bounds the number of block processing tasks (header and body validations) running concurrently.
+///
+/// The limit can be adjusted at runtime. Tasks submitted above the limit are queued rather than blocking
+/// a thread of the processing pool, and are started in submission order as running tasks complete.
+pub struct ProcessingParallelism {
+    state: Mutex<ParallelismState>,
+    peak_running: AtomicUsize,
+}
+
+struct ParallelismState {
+    limit: usize,
+    running: usize,
+    queue: VecDeque<TaskStarter>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProcessingParallelismSnapshot {
+    pub limit: usize,
+    pub running: usize,
+    pub queued: usize,
+}
+
+impl ProcessingParallelism {
+    /// Creates a control allowing `limit` concurrent tasks, a zero limit meaning one task per available CPU
+    pub fn new(limit: usize) -> Self {
+        let limit = if limit == 0 { Self::default_limit() } else { limit };
+        Self { state: Mutex::new(ParallelismState { limit, running: 0, queue: VecDeque::new() }), peak_running: AtomicUsize::new(0) }
+    }
+
+    /// The number of available CPUs
+    pub fn default_limit() -> usize {
+        std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+    }
+
+    pub fn limit(&self) -> usize {
+        self.state.lock().unwrap().limit
+    }
+
+    /// Sets the limit of concurrent tasks, starting queued tasks if it was raised. Running tasks are never
+    /// interrupted, so lowering the limit takes effect as they complete. Returns the previous limit.
+    ///
+    /// Panics if `limit` is zero
+    pub fn set_limit(&self, limit: usize) -> usize {
+        assert!(limit > 0, "the processing parallelism limit must be positive");
+        let mut starters = Vec::new();
+        let previous = {
+            let mut state = self.state.lock().unwrap();
+            let previous = std::mem::replace(&mut state.limit, limit);
+            while state.running < state.limit {
+                let Some(starter) = state.queue.pop_front() else { break };
+                state.running += 1;
+                starters.push(starter);
+            }
+            self.peak_running.fetch_max(state.running, Ordering::Relaxed);
+            previous
+        };
+        starters.into_iter().for_each(|start| start());
+        previous
+    }
+
+    /// Calls `start` now if the limit allows it, or once a slot gets released otherwise. `start` is expected
+    /// to spawn the task, which must call [`Self::complete`] once done.
+    pub fn submit(&self, start: TaskStarter) {
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.running >= state.limit {
+                state.queue.push_back(start);
+                return;
+            }
+            state.running += 1;
+            self.peak_running.fetch_max(state.running, Ordering::Relaxed);
+        }
+        start();
+    }
+
+    /// Releases the slot of a completed task, handing it over to the next queued task if any
+    pub fn complete(&self) {
+        let next = {
+            let mut state = self.state.lock().unwrap();
+            let next = if state.running <= state.limit { state.queue.pop_front() } else { None };
+            if next.is_none() {
+                state.running -= 1;
+            }
+            next
+        };
+        if let Some(start) = next {
+            start();
+        }
+    }
+
+    pub fn snapshot(&self) -> ProcessingParallelismSnapshot {
+        let state = self.state.lock().unwrap();
+        ProcessingParallelismSnapshot { limit: state.limit, running: state.running, queued: state.queue.len() }
+    }
+
+    /// The highest number of tasks observed running concurrently since the last call
+    pub fn take_peak_running(&self) -> usize {
+        let running = self.state.lock().unwrap().running;
+        self.peak_running.swap(running, Ordering::Relaxed)
+    }
+}
+
+impl Default for ProcessingParallelism {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{mpsc, Arc};
+
+    #[test]
+    fn test_processing_parallelism() {
+        let parallelism = Arc::new(ProcessingParallelism::new(2));
+        let (sender, receiver) = mpsc::channel();
+        for i in 0..5 {
+            let sender = sender.clone();
+            parallelism.submit(Box::new(move || sender.send(i).unwrap()));
+        }
+        // Only the first two tasks are started
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(parallelism.snapshot(), ProcessingParallelismSnapshot { limit: 2, running: 2, queued: 3 });
+
+        // A completed task hands its slot over to the next queued one
+        parallelism.complete();
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![2]);
+
+        // Raising the limit starts queued tasks right away
+        assert_eq!(parallelism.set_limit(3), 2);
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![3]);
+        assert_eq!(parallelism.take_peak_running(), 3);
+
+        // Lowering the limit takes effect as running tasks complete
+        parallelism.set_limit(1);
+        parallelism.complete();
+        parallelism.complete();
+        assert!(receiver.try_recv().is_err());
+        parallelism.complete();
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![4]);
+        parallelism.complete();
+        assert_eq!(parallelism.snapshot(), ProcessingParallelismSnapshot { limit: 1, running: 0, queued: 0 });
+    }
+}
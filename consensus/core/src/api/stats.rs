@@ -1,4 +1,5 @@
 use borsh::{BorshDeserialize, BorshSerialize};
+use kaspa_hashes::Hash;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, Default)]
@@ -23,6 +24,19 @@ pub struct VirtualStateStats {
     pub past_median_time: u64,
 }
 
+/// A snapshot of the virtual state commonly tracked by wallets, read from the cached virtual state
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualInfo {
+    pub daa_score: u64,
+    /// The selected parent of virtual
+    pub sink: Hash,
+    pub sink_blue_score: u64,
+    /// Number of direct parents of virtual
+    pub parents_count: u32,
+    pub past_median_time: u64,
+}
+
 pub struct ConsensusStats {
     /// Block and header counts
     pub block_counts: BlockCount,
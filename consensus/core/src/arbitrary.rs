@@ -0,0 +1,380 @@
+//!
+//! Generators of random consensus values for property and fuzz testing.
+//!
+//! Generated values are structurally valid within the consensus [`Limits`] they are built with,
+//! and can be made to break a single rule on demand (see [`TxViolation`] and [`BlockViolation`])
+//! for negative tests. Generation is driven by the provided RNG, so a seeded RNG makes a failing
+//! case reproducible.
+//!
+
+use crate::{
+    block::Block,
+    config::params::Params,
+    constants::{BLOCK_VERSION, MAX_SOMPI, TX_VERSION},
+    header::Header,
+    merkle::calc_hash_merkle_root,
+    subnets::{SUBNETWORK_ID_COINBASE, SUBNETWORK_ID_NATIVE},
+    tx::{ScriptPublicKey, ScriptVec, Transaction, TransactionInput, TransactionOutpoint, TransactionOutput},
+};
+use kaspa_hashes::{Hash, HASH_SIZE};
+use rand::Rng;
+
+/// Bound on the number of generated inputs, outputs and parents when the consensus limits are larger
+const MAX_GENERATED_LEN: usize = 8;
+
+/// Bound on the length of generated scripts and payloads when the consensus limits are larger
+const MAX_GENERATED_SCRIPT_LEN: usize = 128;
+
+/// Bound on generated signature operation counts, keeping blocks of generated transactions within the block mass limit
+const MAX_GENERATED_SIG_OP_COUNT: u8 = 2;
+
+/// Consensus limits honored by generated values
+#[derive(Clone, Debug)]
+pub struct Limits {
+    pub max_tx_inputs: usize,
+    pub max_tx_outputs: usize,
+    pub max_signature_script_len: usize,
+    pub max_script_public_key_len: usize,
+    pub max_coinbase_outputs: usize,
+    pub coinbase_payload_script_public_key_max_len: usize,
+    pub max_coinbase_payload_len: usize,
+    pub max_block_parents: usize,
+    pub max_block_transactions: usize,
+}
+
+impl From<&Params> for Limits {
+    fn from(params: &Params) -> Self {
+        Self {
+            max_tx_inputs: params.max_tx_inputs,
+            max_tx_outputs: params.max_tx_outputs,
+            max_signature_script_len: params.max_signature_script_len,
+            max_script_public_key_len: params.max_script_public_key_len,
+            max_coinbase_outputs: params.ghostdag_k as usize + 2,
+            coinbase_payload_script_public_key_max_len: params.coinbase_payload_script_public_key_max_len as usize,
+            max_coinbase_payload_len: params.max_coinbase_payload_len,
+            max_block_parents: params.max_block_parents as usize,
+            max_block_transactions: params.max_block_transactions,
+        }
+    }
+}
+
+/// A transaction rule checked in isolation, broken by [`Generator::transaction_with_violation`].
+///
+/// Violations of count and size limits generate one element more than the limit, so they are
+/// meant to be used with reduced limits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TxViolation {
+    NoInputs,
+    TooManyInputs,
+    TooManyOutputs,
+    TooBigSignatureScript,
+    TooBigScriptPublicKey,
+    OutputZero,
+    OutputTooHigh,
+    /// Requires a limit of at least 2 outputs
+    TotalOutputTooHigh,
+    /// Requires a limit of at least 2 inputs
+    DuplicateInputs,
+    HasGas,
+    NonCoinbasePayload,
+    UnknownVersion,
+    CoinbaseHasInputs,
+    /// Requires the output limit to exceed the coinbase output limit
+    CoinbaseTooManyOutputs,
+    CoinbaseScriptPublicKeyTooLong,
+}
+
+impl TxViolation {
+    pub const ALL: [TxViolation; 15] = [
+        Self::NoInputs,
+        Self::TooManyInputs,
+        Self::TooManyOutputs,
+        Self::TooBigSignatureScript,
+        Self::TooBigScriptPublicKey,
+        Self::OutputZero,
+        Self::OutputTooHigh,
+        Self::TotalOutputTooHigh,
+        Self::DuplicateInputs,
+        Self::HasGas,
+        Self::NonCoinbasePayload,
+        Self::UnknownVersion,
+        Self::CoinbaseHasInputs,
+        Self::CoinbaseTooManyOutputs,
+        Self::CoinbaseScriptPublicKeyTooLong,
+    ];
+
+    /// Returns whether the violation is produced by a coinbase transaction
+    pub fn is_coinbase(self) -> bool {
+        matches!(self, Self::CoinbaseHasInputs | Self::CoinbaseTooManyOutputs | Self::CoinbaseScriptPublicKeyTooLong)
+    }
+}
+
+/// A block body rule checked in isolation, broken by [`Generator::block_with_violation`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockViolation {
+    NoTransactions,
+    /// Generates one transaction more than the limit, so is meant to be used with a reduced limit
+    TooManyTransactions,
+    BadMerkleRoot,
+    FirstTxNotCoinbase,
+    MultipleCoinbases,
+    DuplicateTransactions,
+    DoubleSpendInSameBlock,
+    ChainedTransaction,
+    /// A transaction of the block breaks a rule checked in isolation
+    InvalidTransaction(TxViolation),
+}
+
+impl BlockViolation {
+    pub const ALL: [BlockViolation; 8] = [
+        Self::NoTransactions,
+        Self::TooManyTransactions,
+        Self::BadMerkleRoot,
+        Self::FirstTxNotCoinbase,
+        Self::MultipleCoinbases,
+        Self::DuplicateTransactions,
+        Self::DoubleSpendInSameBlock,
+        Self::ChainedTransaction,
+    ];
+}
+
+/// Generates random consensus values within the given limits.
+///
+/// Inputs spend random outpoints, so the transactions of a generated block neither
+/// double spend nor spend each other, and their transaction ids are distinct.
+pub struct Generator<R: Rng> {
+    rng: R,
+    limits: Limits,
+}
+
+impl<R: Rng> Generator<R> {
+    pub fn new(rng: R, limits: Limits) -> Self {
+        Self { rng, limits }
+    }
+
+    pub fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    pub fn rng(&mut self) -> &mut R {
+        &mut self.rng
+    }
+
+    pub fn hash(&mut self) -> Hash {
+        Hash::from_bytes(self.rng.gen::<[u8; HASH_SIZE]>())
+    }
+
+    pub fn outpoint(&mut self) -> TransactionOutpoint {
+        TransactionOutpoint::new(self.hash(), self.rng.gen())
+    }
+
+    /// Generates a script public key whose script is at most `max_len` bytes long
+    pub fn script_public_key(&mut self, max_len: usize) -> ScriptPublicKey {
+        let len = self.len(0, max_len, MAX_GENERATED_SCRIPT_LEN);
+        self.script_public_key_of_len(len)
+    }
+
+    pub fn transaction_input(&mut self) -> TransactionInput {
+        let len = self.len(0, self.limits.max_signature_script_len, MAX_GENERATED_SCRIPT_LEN);
+        TransactionInput::new(self.outpoint(), self.bytes(len), self.rng.gen(), self.rng.gen_range(0..=MAX_GENERATED_SIG_OP_COUNT))
+    }
+
+    /// Generates an output of a value in `1..=max_value`
+    pub fn transaction_output(&mut self, max_value: u64) -> TransactionOutput {
+        let script_public_key = self.script_public_key(self.limits.max_script_public_key_len);
+        TransactionOutput::new(self.rng.gen_range(1..=max_value.max(1)), script_public_key)
+    }
+
+    /// Generates a non-coinbase transaction which is valid in isolation
+    pub fn transaction(&mut self) -> Transaction {
+        let inputs = (0..self.len(1, self.limits.max_tx_inputs, MAX_GENERATED_LEN)).map(|_| self.transaction_input()).collect();
+        let outputs_count = self.len(1, self.limits.max_tx_outputs, MAX_GENERATED_LEN);
+        let outputs = (0..outputs_count).map(|_| self.transaction_output(MAX_SOMPI / outputs_count as u64)).collect();
+        Transaction::new(TX_VERSION, inputs, outputs, self.rng.gen(), SUBNETWORK_ID_NATIVE, 0, vec![])
+    }
+
+    /// Generates a coinbase transaction which is valid in isolation
+    pub fn coinbase_transaction(&mut self) -> Transaction {
+        let max_script_len = self.limits.coinbase_payload_script_public_key_max_len.min(self.limits.max_script_public_key_len);
+        let outputs_count = self.len(0, self.limits.max_coinbase_outputs.min(self.limits.max_tx_outputs), MAX_GENERATED_LEN);
+        let outputs = (0..outputs_count)
+            .map(|_| {
+                let script_public_key = self.script_public_key(max_script_len);
+                TransactionOutput::new(self.rng.gen_range(1..=MAX_SOMPI / outputs_count as u64), script_public_key)
+            })
+            .collect();
+        let payload_len = self.len(0, self.limits.max_coinbase_payload_len, MAX_GENERATED_SCRIPT_LEN);
+        Transaction::new(TX_VERSION, vec![], outputs, 0, SUBNETWORK_ID_COINBASE, 0, self.bytes(payload_len))
+    }
+
+    /// Generates a transaction breaking `violation` and no rule checked before it
+    pub fn transaction_with_violation(&mut self, violation: TxViolation) -> Transaction {
+        let limits = self.limits.clone();
+        let mut tx = if violation.is_coinbase() { self.coinbase_transaction() } else { self.transaction() };
+        match violation {
+            TxViolation::NoInputs => tx.inputs.clear(),
+            TxViolation::TooManyInputs => {
+                while tx.inputs.len() <= limits.max_tx_inputs {
+                    tx.inputs.push(self.transaction_input());
+                }
+            }
+            TxViolation::TooManyOutputs => {
+                // Keeping the total value in range is not needed since the count is checked first
+                while tx.outputs.len() <= limits.max_tx_outputs {
+                    tx.outputs.push(self.transaction_output(1));
+                }
+            }
+            TxViolation::TooBigSignatureScript => {
+                let i = self.rng.gen_range(0..tx.inputs.len());
+                tx.inputs[i].signature_script = self.bytes(limits.max_signature_script_len + 1);
+            }
+            TxViolation::TooBigScriptPublicKey => {
+                let i = self.rng.gen_range(0..tx.outputs.len());
+                tx.outputs[i].script_public_key = self.script_public_key_of_len(limits.max_script_public_key_len + 1);
+            }
+            TxViolation::OutputZero => {
+                let i = self.rng.gen_range(0..tx.outputs.len());
+                tx.outputs[i].value = 0;
+            }
+            TxViolation::OutputTooHigh => {
+                let i = self.rng.gen_range(0..tx.outputs.len());
+                tx.outputs[i].value = MAX_SOMPI + 1;
+            }
+            TxViolation::TotalOutputTooHigh => {
+                tx.outputs.truncate(1);
+                tx.outputs[0].value = MAX_SOMPI;
+                tx.outputs.push(self.transaction_output(MAX_SOMPI));
+            }
+            TxViolation::DuplicateInputs => {
+                tx.inputs.truncate(limits.max_tx_inputs - 1);
+                let duplicate = tx.inputs[self.rng.gen_range(0..tx.inputs.len())].clone();
+                tx.inputs.push(duplicate);
+            }
+            TxViolation::HasGas => tx.gas = self.rng.gen_range(1..=u64::MAX),
+            TxViolation::NonCoinbasePayload => {
+                let len = self.len(1, usize::MAX, MAX_GENERATED_SCRIPT_LEN);
+                tx.payload = self.bytes(len);
+            }
+            TxViolation::UnknownVersion => tx.version = self.rng.gen_range(TX_VERSION + 1..=u16::MAX),
+            TxViolation::CoinbaseHasInputs => {
+                let count = self.len(1, limits.max_tx_inputs, MAX_GENERATED_LEN);
+                tx.inputs = (0..count).map(|_| self.transaction_input()).collect();
+            }
+            TxViolation::CoinbaseTooManyOutputs => {
+                let max_script_len = limits.coinbase_payload_script_public_key_max_len.min(limits.max_script_public_key_len);
+                while tx.outputs.len() <= limits.max_coinbase_outputs {
+                    let script_public_key = self.script_public_key(max_script_len);
+                    tx.outputs.push(TransactionOutput::new(1, script_public_key));
+                }
+            }
+            TxViolation::CoinbaseScriptPublicKeyTooLong => {
+                if tx.outputs.is_empty() {
+                    tx.outputs.push(TransactionOutput::new(1, ScriptPublicKey::default()));
+                }
+                let i = self.rng.gen_range(0..tx.outputs.len());
+                tx.outputs[i].script_public_key = self.script_public_key_of_len(limits.coinbase_payload_script_public_key_max_len + 1);
+            }
+        }
+        tx.finalize();
+        tx
+    }
+
+    /// Generates a header committing to `hash_merkle_root`. Other fields are random and
+    /// meaningless beyond the structure of the header.
+    pub fn header(&mut self, hash_merkle_root: Hash) -> Header {
+        let parents = (0..self.len(1, self.limits.max_block_parents, MAX_GENERATED_LEN)).map(|_| self.hash()).collect();
+        Header::new_finalized(
+            BLOCK_VERSION,
+            vec![parents],
+            hash_merkle_root,
+            self.hash(),
+            self.hash(),
+            self.rng.gen(),
+            self.rng.gen(),
+            self.rng.gen(),
+            self.rng.gen(),
+            self.rng.gen::<u64>().into(),
+            self.rng.gen(),
+            self.hash(),
+        )
+    }
+
+    /// Generates a block made of a coinbase and `tx_count` other transactions, valid in isolation.
+    ///
+    /// Transactions commit to no mass, so blocks are valid only if the storage mass is not activated
+    /// at their random DAA score. A few transactions per block keep the compute mass within limits.
+    pub fn block(&mut self, tx_count: usize) -> Block {
+        let mut transactions = vec![self.coinbase_transaction()];
+        transactions.extend((0..tx_count).map(|_| self.transaction()));
+        self.block_with_transactions(transactions)
+    }
+
+    /// Generates a block made of a coinbase and at least one other transaction, breaking `violation`
+    /// and no rule checked before it
+    pub fn block_with_violation(&mut self, tx_count: usize, violation: BlockViolation) -> Block {
+        let mut transactions = vec![self.coinbase_transaction()];
+        transactions.extend((0..tx_count.max(1)).map(|_| self.transaction()));
+        match violation {
+            BlockViolation::NoTransactions => transactions.clear(),
+            BlockViolation::TooManyTransactions => {
+                let missing = (self.limits.max_block_transactions + 1).saturating_sub(transactions.len());
+                transactions.extend((0..missing).map(|_| self.transaction()));
+            }
+            BlockViolation::BadMerkleRoot => {
+                let hash_merkle_root = self.hash();
+                return Block::new(self.header(hash_merkle_root), transactions);
+            }
+            BlockViolation::FirstTxNotCoinbase => {
+                transactions.remove(0);
+            }
+            BlockViolation::MultipleCoinbases => {
+                let i = self.rng.gen_range(1..=transactions.len());
+                let coinbase = self.coinbase_transaction();
+                transactions.insert(i, coinbase);
+            }
+            BlockViolation::DuplicateTransactions => {
+                let duplicate = transactions[self.rng.gen_range(1..transactions.len())].clone();
+                let i = self.rng.gen_range(1..=transactions.len());
+                transactions.insert(i, duplicate);
+            }
+            BlockViolation::DoubleSpendInSameBlock => {
+                let spent = transactions[self.rng.gen_range(1..transactions.len())].inputs[0].previous_outpoint;
+                let mut tx = self.transaction();
+                tx.inputs[0].previous_outpoint = spent;
+                tx.finalize();
+                transactions.push(tx);
+            }
+            BlockViolation::ChainedTransaction => {
+                let spent = TransactionOutpoint::new(transactions[self.rng.gen_range(1..transactions.len())].id(), 0);
+                let mut tx = self.transaction();
+                tx.inputs[0].previous_outpoint = spent;
+                tx.finalize();
+                transactions.push(tx);
+            }
+            BlockViolation::InvalidTransaction(violation) => {
+                let i = if violation.is_coinbase() { 0 } else { self.rng.gen_range(1..transactions.len()) };
+                transactions[i] = self.transaction_with_violation(violation);
+            }
+        }
+        self.block_with_transactions(transactions)
+    }
+
+    fn block_with_transactions(&mut self, transactions: Vec<Transaction>) -> Block {
+        let hash_merkle_root = calc_hash_merkle_root(transactions.iter());
+        Block::new(self.header(hash_merkle_root), transactions)
+    }
+
+    /// Returns a random length in `min..=max`, bounded by `generated_max` unless `min` is larger
+    fn len(&mut self, min: usize, max: usize, generated_max: usize) -> usize {
+        self.rng.gen_range(min..=max.min(generated_max).max(min))
+    }
+
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.rng.gen()).collect()
+    }
+
+    fn script_public_key_of_len(&mut self, len: usize) -> ScriptPublicKey {
+        let script: ScriptVec = (0..len).map(|_| self.rng.gen()).collect();
+        ScriptPublicKey::new(0, script)
+    }
+}
@@ -0,0 +1,46 @@
+//!
+//! Manifest of the consensus database backups. A backup directory mirrors the layout of
+//! the node data directory, so a backup is restored by using it as the data directory.
+//!
+
+use crate::{
+    errors::backup::{BackupError, BackupResult},
+    network::NetworkId,
+};
+use kaspa_hashes::Hash;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+/// Name of the manifest file within a backup directory
+pub const BACKUP_MANIFEST_FILE: &str = "manifest.json";
+
+/// Describes the state of the node at the time a backup was taken
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupManifest {
+    /// Version of the node which took the backup
+    pub node_version: String,
+    pub network_id: NetworkId,
+    /// Digest of the consensus params of the network, see `Params::digest`
+    pub params_digest: Hash,
+    pub pruning_point: Hash,
+    pub sink: Hash,
+    /// Unix time (in milliseconds) at which the backup was taken
+    pub timestamp: u64,
+    /// Path of the consensus database, relative to the backup directory
+    pub consensus_dir: String,
+    /// Path of the meta database (consensus entries and peer addresses), relative to the backup directory
+    pub meta_dir: String,
+}
+
+impl BackupManifest {
+    pub fn load(backup_dir: &Path) -> BackupResult<Self> {
+        let manifest = fs::read(backup_dir.join(BACKUP_MANIFEST_FILE))?;
+        serde_json::from_slice(&manifest).map_err(|err| BackupError::Manifest(err.to_string()))
+    }
+
+    pub fn store(&self, backup_dir: &Path) -> BackupResult<()> {
+        let manifest = serde_json::to_vec_pretty(self).map_err(|err| BackupError::Manifest(err.to_string()))?;
+        Ok(fs::write(backup_dir.join(BACKUP_MANIFEST_FILE), manifest)?)
+    }
+}
@@ -80,8 +80,44 @@ pub trait TemplateTransactionSelector {
     /// track the selection state and discard the rejected tx from internal occupation calculations
     fn reject_selection(&mut self, tx_id: TransactionId);
 
+    /// Sorts the transactions gathered over several `select_transactions` calls (i.e. after rejections)
+    /// into the canonical order of the selector. The default implementation keeps the selection order
+    fn sort_selected_transactions(&self, _transactions: &mut [Transaction]) {}
+
     /// Determine whether this was an overall successful selection episode
     fn is_successful(&self) -> bool;
+
+    /// Statistics of the transactions currently selected, i.e. excluding the rejected ones. Expected to be
+    /// called once the selection episode is over. The default implementation provides none
+    fn selection_stats(&self) -> Option<TemplateSelectionStats> {
+        None
+    }
+}
+
+/// Statistics of the transaction selection of a block template, excluding the coinbase transaction
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TemplateSelectionStats {
+    /// Total fees of the selected transactions
+    pub total_fees: u64,
+    /// Total mass of the selected transactions
+    pub total_mass: u64,
+    /// Maximum mass of the selected transactions allowed by the selection policy
+    pub max_block_mass: u64,
+    /// Number of candidate transactions the selection was made from
+    pub considered_count: u64,
+    /// Number of selected transactions
+    pub selected_count: u64,
+    /// Lowest fee per gram of mass of the selected transactions, `None` if no transaction was selected
+    pub min_feerate: Option<f64>,
+}
+
+/// The outcome of a successful block template validation
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlockTemplateValidation {
+    /// Mass of the block
+    pub mass: u64,
+    /// Total fees of the non-coinbase transactions
+    pub total_fees: u64,
 }
 
 /// Block template build mode
@@ -105,6 +141,8 @@ pub struct BlockTemplate {
     pub selected_parent_timestamp: u64,
     pub selected_parent_daa_score: u64,
     pub selected_parent_hash: Hash,
+    /// Statistics of the transaction selection, if provided by the selector
+    pub selection_stats: Option<TemplateSelectionStats>,
 }
 
 impl BlockTemplate {
@@ -116,7 +154,20 @@ impl BlockTemplate {
         selected_parent_daa_score: u64,
         selected_parent_hash: Hash,
     ) -> Self {
-        Self { block, miner_data, coinbase_has_red_reward, selected_parent_timestamp, selected_parent_daa_score, selected_parent_hash }
+        Self {
+            block,
+            miner_data,
+            coinbase_has_red_reward,
+            selected_parent_timestamp,
+            selected_parent_daa_score,
+            selected_parent_hash,
+            selection_stats: None,
+        }
+    }
+
+    pub fn with_selection_stats(mut self, selection_stats: Option<TemplateSelectionStats>) -> Self {
+        self.selection_stats = selection_stats;
+        self
     }
 
     pub fn to_virtual_state_approx_id(&self) -> VirtualStateApproxId {
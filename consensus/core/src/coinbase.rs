@@ -1,5 +1,6 @@
 use crate::tx::{ScriptPublicKey, Transaction};
 use serde::{Deserialize, Serialize};
+use std::fmt::Display;
 
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct MinerData<T: AsRef<[u8]> = Vec<u8>> {
@@ -33,6 +34,34 @@ impl BlockRewardData {
     }
 }
 
+/// The total amount paid by a coinbase transaction, broken down by component. A coinbase pays out the rewards
+/// of the blocks merged by its block (its mergeset, excluding non-DAA blocks), each reward being the subsidy
+/// committed to by the merged block plus the fees of the transactions accepted from it.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct CoinbaseAmounts {
+    /// Sum of the subsidies of the rewarded mergeset blocks
+    pub subsidy: u64,
+    /// Sum of the fees of the transactions accepted from the rewarded mergeset blocks
+    pub fees: u64,
+}
+
+impl CoinbaseAmounts {
+    pub fn new(subsidy: u64, fees: u64) -> Self {
+        Self { subsidy, fees }
+    }
+
+    /// The total amount paid by the coinbase outputs
+    pub fn total(&self) -> u64 {
+        self.subsidy + self.fees
+    }
+}
+
+impl Display for CoinbaseAmounts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} sompi (subsidy {} + fees {})", self.total(), self.subsidy, self.fees)
+    }
+}
+
 /// Holds a coinbase transaction along with meta-data obtained during creation
 pub struct CoinbaseTransactionTemplate {
     pub tx: Transaction,
@@ -0,0 +1,150 @@
+//!
+//! Hard-coded pruning point checkpoints, protecting fresh nodes syncing through a pruning proof
+//! from being led onto an alternative chain.
+//!
+//! A checkpoint pins the hash of the pruning point of a given DAA score. A pruning points chain
+//! reaching the DAA score of a checkpoint must include a pruning point with that very DAA score and hash.
+//! Recommended checkpoints can be emitted from a synced node with the `kaspad checkpoints` subcommand.
+//!
+
+use super::{
+    genesis::{GENESIS, TESTNET11_GENESIS, TESTNET_GENESIS},
+    params::Params,
+};
+use crate::{
+    errors::pruning::{PruningImportError, PruningImportResult},
+    header::Header,
+    network::NetworkType,
+};
+use kaspa_hashes::Hash;
+use std::{fmt::Display, num::ParseIntError, str::FromStr, sync::Arc};
+use thiserror::Error;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Checkpoint {
+    /// DAA score of the pruning point
+    pub daa_score: u64,
+    /// Hash of the pruning point
+    pub hash: Hash,
+}
+
+impl Checkpoint {
+    pub const fn new(daa_score: u64, hash: Hash) -> Self {
+        Self { daa_score, hash }
+    }
+}
+
+/// Formats the checkpoint as `<DAA score>:<block hash>`, the format expected by the `--checkpoint` argument
+impl Display for Checkpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.daa_score, self.hash)
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CheckpointParseError {
+    #[error("expected a checkpoint of the form <DAA score>:<block hash>, got {0}")]
+    InvalidFormat(String),
+
+    #[error("invalid checkpoint DAA score: {0}")]
+    InvalidDaaScore(#[from] ParseIntError),
+
+    #[error("invalid checkpoint block hash: {0}")]
+    InvalidHash(String),
+}
+
+impl FromStr for Checkpoint {
+    type Err = CheckpointParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (daa_score, hash) = s.split_once(':').ok_or_else(|| CheckpointParseError::InvalidFormat(s.to_owned()))?;
+        let daa_score = daa_score.trim().parse()?;
+        let hash = hash.trim().parse().map_err(|err: faster_hex::Error| CheckpointParseError::InvalidHash(err.to_string()))?;
+        Ok(Self { daa_score, hash })
+    }
+}
+
+pub const MAINNET_CHECKPOINTS: &[Checkpoint] = &[Checkpoint::new(GENESIS.daa_score, GENESIS.hash)];
+
+pub const TESTNET_CHECKPOINTS: &[Checkpoint] = &[Checkpoint::new(TESTNET_GENESIS.daa_score, TESTNET_GENESIS.hash)];
+
+pub const TESTNET11_CHECKPOINTS: &[Checkpoint] = &[Checkpoint::new(TESTNET11_GENESIS.daa_score, TESTNET11_GENESIS.hash)];
+
+/// Returns the hard-coded checkpoints of the network of `params`. Simnet and devnet have none.
+pub fn default_checkpoints(params: &Params) -> Vec<Checkpoint> {
+    match (params.net.network_type(), params.net.suffix()) {
+        (NetworkType::Mainnet, _) => MAINNET_CHECKPOINTS.to_vec(),
+        (NetworkType::Testnet, Some(10)) => TESTNET_CHECKPOINTS.to_vec(),
+        (NetworkType::Testnet, Some(11)) => TESTNET11_CHECKPOINTS.to_vec(),
+        _ => vec![],
+    }
+}
+
+/// Validates a pruning points chain, ordered from genesis up, against `checkpoints`. Only the checkpoints
+/// whose DAA score is not above the DAA score of the last pruning point are enforced.
+pub fn validate_pruning_points_against_checkpoints(
+    checkpoints: &[Checkpoint],
+    pruning_points: &[Arc<Header>],
+) -> PruningImportResult<()> {
+    let Some(last_daa_score) = pruning_points.last().map(|header| header.daa_score) else {
+        return Ok(());
+    };
+    for checkpoint in checkpoints.iter().filter(|checkpoint| checkpoint.daa_score <= last_daa_score) {
+        let index = pruning_points.partition_point(|header| header.daa_score < checkpoint.daa_score);
+        let matching = pruning_points[index..]
+            .iter()
+            .take_while(|header| header.daa_score == checkpoint.daa_score)
+            .any(|header| header.hash == checkpoint.hash);
+        if !matching {
+            return Err(PruningImportError::PruningPointsCheckpointMismatch(checkpoint.daa_score, checkpoint.hash));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::params::{MAINNET_PARAMS, SIMNET_PARAMS, TESTNET11_PARAMS};
+
+    fn header(daa_score: u64, hash: u64) -> Arc<Header> {
+        let mut header = Header::from_precomputed_hash(hash.into(), vec![]);
+        header.daa_score = daa_score;
+        Arc::new(header)
+    }
+
+    #[test]
+    fn test_checkpoint_parsing() {
+        let checkpoint = Checkpoint::new(1312860, GENESIS.hash);
+        assert_eq!(checkpoint.to_string().parse::<Checkpoint>().unwrap(), checkpoint);
+        assert!(matches!("1312860".parse::<Checkpoint>(), Err(CheckpointParseError::InvalidFormat(_))));
+        assert!(matches!(format!("x:{}", GENESIS.hash).parse::<Checkpoint>(), Err(CheckpointParseError::InvalidDaaScore(_))));
+        assert!(matches!("1312860:abcd".parse::<Checkpoint>(), Err(CheckpointParseError::InvalidHash(_))));
+    }
+
+    #[test]
+    fn test_default_checkpoints() {
+        assert_eq!(default_checkpoints(&MAINNET_PARAMS), vec![Checkpoint::new(GENESIS.daa_score, GENESIS.hash)]);
+        assert_eq!(default_checkpoints(&TESTNET11_PARAMS), vec![Checkpoint::new(TESTNET11_GENESIS.daa_score, TESTNET11_GENESIS.hash)]);
+        assert!(default_checkpoints(&SIMNET_PARAMS).is_empty());
+    }
+
+    #[test]
+    fn test_validate_pruning_points_against_checkpoints() {
+        let pruning_points = vec![header(0, 1), header(100, 2), header(200, 3)];
+        let validate = |checkpoints: &[Checkpoint]| validate_pruning_points_against_checkpoints(checkpoints, &pruning_points);
+
+        assert!(validate(&[]).is_ok());
+        assert!(validate(&[Checkpoint::new(0, 1.into()), Checkpoint::new(200, 3.into())]).is_ok());
+        // Checkpoints beyond the last pruning point are not enforced yet
+        assert!(validate(&[Checkpoint::new(300, 9.into())]).is_ok());
+        // A different pruning point at the DAA score of the checkpoint
+        assert!(matches!(
+            validate(&[Checkpoint::new(100, 9.into())]),
+            Err(PruningImportError::PruningPointsCheckpointMismatch(100, hash)) if hash == 9.into()
+        ));
+        // No pruning point at the DAA score of the checkpoint
+        assert!(validate(&[Checkpoint::new(150, 2.into())]).is_err());
+        assert!(validate_pruning_points_against_checkpoints(&[Checkpoint::new(0, 1.into())], &[]).is_ok());
+    }
+}
@@ -102,6 +102,19 @@ pub mod consensus {
 
     /// **Legacy** value of the coinbase maturity parameter for 1 BPS networks
     pub const LEGACY_COINBASE_MATURITY: u64 = 100;
+
+    //
+    // ~~~~~~~~~~~~~~~~~~~ Block body ~~~~~~~~~~~~~~~~~~~
+    //
+
+    /// Default limit on the number of transactions of a block, coinbase included.
+    ///
+    /// The limit must never bind before the block mass limit on mainnet. The smallest transaction a block
+    /// can hold (a single input with an empty signature script, no outputs and no payload) has an estimated
+    /// serialized size of 154 bytes, so with a mass of 1 per byte and a block mass limit of 500,000 a block
+    /// fits at most 1 + 500,000 / 154 = 3,247 transactions. Devnets can lower the limit for load testing the
+    /// components processing block transactions (indexes, notifications).
+    pub const DEFAULT_MAX_BLOCK_TRANSACTIONS: usize = 4_000;
 }
 
 pub mod perf {
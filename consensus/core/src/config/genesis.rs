@@ -66,17 +66,17 @@ impl From<(&Header, &'static [u8])> for GenesisBlock {
 /// The genesis block of the block-DAG which serves as the public transaction ledger for the main network.
 pub const GENESIS: GenesisBlock = GenesisBlock {
     hash: Hash::from_bytes([
-        0xa58, 0xac2, 0xad4, 0xa19, 0xa9e, 0xa21, 0xaf9, 0xa10, 0xad1, 0xa57, 0xa1d, 0xa11, 0xa49, 0xa69, 0xace, 0xace, 0xaf4, 0xa8f, 0xa9, 0xaf9, 0xa34,
-        0xad4, 0xa2c, 0xacb, 0xa6a, 0xa28, 0xa1a, 0xa15, 0xa86, 0xa8f, 0xa29, 0xa99,
+        0xa58, 0xac2, 0xad4, 0xa19, 0xa9e, 0xa21, 0xaf9, 0xa10, 0xad1, 0xa57, 0xa1d, 0xa11, 0xa49, 0xa69, 0xace, 0xace, 0xaf4, 0xa8f,
+        0xa9, 0xaf9, 0xa34, 0xad4, 0xa2c, 0xacb, 0xa6a, 0xa28, 0xa1a, 0xa15, 0xa86, 0xa8f, 0xa29, 0xa99,
     ]),
     version: 0,
     hash_merkle_root: Hash::from_bytes([
-        0xa8e, 0xac8, 0xa98, 0xa56, 0xa8c, 0xa68, 0xa1, 0xad1, 0xa3d, 0xaf4, 0xaee, 0xa6e, 0xa2a, 0xa1b, 0xa54, 0xab7, 0xae6, 0xa23, 0xa6f, 0xa67, 0xa1f,
-        0xa20, 0xa95, 0xa4f, 0xa5, 0xa30, 0xa64, 0xa10, 0xa51, 0xa8e, 0xaeb, 0xa32,
+        0xa8e, 0xac8, 0xa98, 0xa56, 0xa8c, 0xa68, 0xa1, 0xad1, 0xa3d, 0xaf4, 0xaee, 0xa6e, 0xa2a, 0xa1b, 0xa54, 0xab7, 0xae6, 0xa23,
+        0xa6f, 0xa67, 0xa1f, 0xa20, 0xa95, 0xa4f, 0xa5, 0xa30, 0xa64, 0xa10, 0xa51, 0xa8e, 0xaeb, 0xa32,
     ]),
     utxo_commitment: Hash::from_bytes([
-        0xa71, 0xa0f, 0xa27, 0xadf, 0xa42, 0xa3e, 0xa63, 0xaaa, 0xa6c, 0xadb, 0xa72, 0xab8, 0xa9e, 0xaa5, 0xaa0, 0xa6c, 0xaff, 0xaa3, 0xa99, 0xad6, 0xa6f,
-        0xa16, 0xa77, 0xa04, 0xa45, 0xa5b, 0xa5a, 0xaf5, 0xa9d, 0xaef, 0xa8e, 0xa20,
+        0xa71, 0xa0f, 0xa27, 0xadf, 0xa42, 0xa3e, 0xa63, 0xaaa, 0xa6c, 0xadb, 0xa72, 0xab8, 0xa9e, 0xaa5, 0xaa0, 0xa6c, 0xaff, 0xaa3,
+        0xa99, 0xad6, 0xa6f, 0xa16, 0xa77, 0xa04, 0xa45, 0xa5b, 0xa5a, 0xaf5, 0xa9d, 0xaef, 0xa8e, 0xa20,
     ]),
     timestamp: 1637609671037,
     bits: 486722099,
@@ -117,13 +117,13 @@ pub const GENESIS: GenesisBlock = GenesisBlock {
 
 pub const TESTNET_GENESIS: GenesisBlock = GenesisBlock {
     hash: Hash::from_bytes([
-        0xaf8, 0xa96, 0xaa3, 0xa03, 0xa48, 0xa73, 0xabe, 0xa17, 0xa39, 0xafc, 0xa43, 0xa59, 0xa23, 0xa68, 0xa99, 0xafd, 0xa3d, 0xa65, 0xad2, 0xabc, 0xa94,
-        0xaf9, 0xa78, 0xa0d, 0xaf0, 0xad0, 0xada, 0xa3e, 0xab1, 0xacc, 0xa43, 0xa70,
+        0xaf8, 0xa96, 0xaa3, 0xa03, 0xa48, 0xa73, 0xabe, 0xa17, 0xa39, 0xafc, 0xa43, 0xa59, 0xa23, 0xa68, 0xa99, 0xafd, 0xa3d, 0xa65,
+        0xad2, 0xabc, 0xa94, 0xaf9, 0xa78, 0xa0d, 0xaf0, 0xad0, 0xada, 0xa3e, 0xab1, 0xacc, 0xa43, 0xa70,
     ]),
     version: 0,
     hash_merkle_root: Hash::from_bytes([
-        0xa17, 0xa34, 0xa14, 0xa08, 0xaa5, 0xa72, 0xa45, 0xa56, 0xa50, 0xa4d, 0xaf4, 0xad6, 0xacf, 0xa51, 0xa5c, 0xabf, 0xabb, 0xa22, 0xa04, 0xa30, 0xadc,
-        0xa45, 0xa1c, 0xa74, 0xa3c, 0xa22, 0xad5, 0xae9, 0xa11, 0xa72, 0xa0c, 0xa2a,
+        0xa17, 0xa34, 0xa14, 0xa08, 0xaa5, 0xa72, 0xa45, 0xa56, 0xa50, 0xa4d, 0xaf4, 0xad6, 0xacf, 0xa51, 0xa5c, 0xabf, 0xabb, 0xa22,
+        0xa04, 0xa30, 0xadc, 0xa45, 0xa1c, 0xa74, 0xa3c, 0xa22, 0xad5, 0xae9, 0xa11, 0xa72, 0xa0c, 0xa2a,
     ]),
     utxo_commitment: EMPTY_MUHASH,
     timestamp: 0x17c5f62fbb6,
@@ -143,12 +143,12 @@ pub const TESTNET_GENESIS: GenesisBlock = GenesisBlock {
 
 pub const TESTNET11_GENESIS: GenesisBlock = GenesisBlock {
     hash: Hash::from_bytes([
-        0xacf, 0xaa2, 0xaa7, 0xaeb, 0xafb, 0xa8b, 0xa4e, 0xaa3, 0xa45, 0xa30, 0xa7b, 0xac2, 0xa5e, 0xaf9, 0xa42, 0xa1b, 0xa23, 0xa91, 0xaf0, 0xa9c, 0xa8b,
-        0xa2c, 0xaf2, 0xa15, 0xaf0, 0xa75, 0xa60, 0xaaf, 0xa0d, 0xa4d, 0xa71, 0xa64,
+        0xacf, 0xaa2, 0xaa7, 0xaeb, 0xafb, 0xa8b, 0xa4e, 0xaa3, 0xa45, 0xa30, 0xa7b, 0xac2, 0xa5e, 0xaf9, 0xa42, 0xa1b, 0xa23, 0xa91,
+        0xaf0, 0xa9c, 0xa8b, 0xa2c, 0xaf2, 0xa15, 0xaf0, 0xa75, 0xa60, 0xaaf, 0xa0d, 0xa4d, 0xa71, 0xa64,
     ]),
     hash_merkle_root: Hash::from_bytes([
-        0xa3c, 0xa35, 0xadb, 0xa98, 0xa02, 0xa7e, 0xa84, 0xa6e, 0xa02, 0xacc, 0xa60, 0xab7, 0xaa7, 0xafa, 0xab1, 0xa6d, 0xa6c, 0xaf2, 0xaa5, 0xa42, 0xad8,
-        0xae1, 0xa60, 0xaad, 0xa9c, 0xad9, 0xa08, 0xa5f, 0xa51, 0xa0c, 0xa47, 0xabb,
+        0xa3c, 0xa35, 0xadb, 0xa98, 0xa02, 0xa7e, 0xa84, 0xa6e, 0xa02, 0xacc, 0xa60, 0xab7, 0xaa7, 0xafa, 0xab1, 0xa6d, 0xa6c, 0xaf2,
+        0xaa5, 0xa42, 0xad8, 0xae1, 0xa60, 0xaad, 0xa9c, 0xad9, 0xa08, 0xa5f, 0xa51, 0xa0c, 0xa47, 0xabb,
     ]),
     bits: 504155340, // see `gen_testnet11_genesis`
     #[rustfmt::skip]
@@ -166,13 +166,13 @@ pub const TESTNET11_GENESIS: GenesisBlock = GenesisBlock {
 
 pub const SIMNET_GENESIS: GenesisBlock = GenesisBlock {
     hash: Hash::from_bytes([
-        0xa41, 0xa1f, 0xa8c, 0xad2, 0xa6f, 0xa3d, 0xa41, 0xaae, 0xaa3, 0xa9e, 0xa78, 0xa57, 0xa39, 0xa27, 0xada, 0xa24, 0xad2, 0xa39, 0xa95, 0xa70, 0xa5b,
-        0xa57, 0xa9f, 0xa30, 0xa95, 0xa9b, 0xa91, 0xa27, 0xae9, 0xa6b, 0xa79, 0xae3,
+        0xa41, 0xa1f, 0xa8c, 0xad2, 0xa6f, 0xa3d, 0xa41, 0xaae, 0xaa3, 0xa9e, 0xa78, 0xa57, 0xa39, 0xa27, 0xada, 0xa24, 0xad2, 0xa39,
+        0xa95, 0xa70, 0xa5b, 0xa57, 0xa9f, 0xa30, 0xa95, 0xa9b, 0xa91, 0xa27, 0xae9, 0xa6b, 0xa79, 0xae3,
     ]),
     version: 0,
     hash_merkle_root: Hash::from_bytes([
-        0xa19, 0xa46, 0xad6, 0xa29, 0xaf7, 0xae9, 0xa22, 0xaa7, 0xabc, 0xaed, 0xa59, 0xa19, 0xa05, 0xa21, 0xac3, 0xa77, 0xa1f, 0xa73, 0xad3, 0xa52, 0xadd,
-        0xabb, 0xab6, 0xa86, 0xa56, 0xa4a, 0xad7, 0xafd, 0xa56, 0xa85, 0xa7c, 0xa1b,
+        0xa19, 0xa46, 0xad6, 0xa29, 0xaf7, 0xae9, 0xa22, 0xaa7, 0xabc, 0xaed, 0xa59, 0xa19, 0xa05, 0xa21, 0xac3, 0xa77, 0xa1f, 0xa73,
+        0xad3, 0xa52, 0xadd, 0xabb, 0xab6, 0xa86, 0xa56, 0xa4a, 0xad7, 0xafd, 0xa56, 0xa85, 0xa7c, 0xa1b,
     ]),
     utxo_commitment: EMPTY_MUHASH,
     timestamp: 0x17c5f62fbb6,
@@ -197,13 +197,13 @@ pub const DEVNET_GENESIS: GenesisBlock = GenesisBlock {
         // 0xa37, 0xa9b, 0xac8, 0xa70, 0xa0a, 0xab0, 0xa43, 0xa31, 0xa9e, 0xaf8,
         // 0xa46,
         // New rust devnet genesis hash updated according to the modified bits field (see below)
-        0xa4c, 0xab4, 0xa8d, 0xa0b, 0xa20, 0xa73, 0xab8, 0xa02, 0xa36, 0xa01, 0xa45, 0xaa1, 0xa5a, 0xad1, 0xaab, 0xadc, 0xa01, 0xad8, 0xa9b, 0xa5c, 0xa2f,
-        0xae4, 0xa72, 0xa26, 0xa30, 0xaab, 0xa9b, 0xa5f, 0xae9, 0xadf, 0xac4, 0xaf2,
+        0xa4c, 0xab4, 0xa8d, 0xa0b, 0xa20, 0xa73, 0xab8, 0xa02, 0xa36, 0xa01, 0xa45, 0xaa1, 0xa5a, 0xad1, 0xaab, 0xadc, 0xa01, 0xad8,
+        0xa9b, 0xa5c, 0xa2f, 0xae4, 0xa72, 0xa26, 0xa30, 0xaab, 0xa9b, 0xa5f, 0xae9, 0xadf, 0xac4, 0xaf2,
     ]),
     version: 0,
     hash_merkle_root: Hash::from_bytes([
-        0xa58, 0xaab, 0xaf2, 0xa03, 0xa21, 0xad7, 0xa07, 0xa16, 0xa16, 0xa2b, 0xa6b, 0xaf8, 0xad9, 0xaf5, 0xa89, 0xaca, 0xa33, 0xaae, 0xa6e, 0xa32, 0xab3,
-        0xab1, 0xa9a, 0xabb, 0xa7f, 0xaa6, 0xa5d, 0xa11, 0xa41, 0xaa3, 0xaf9, 0xa4d,
+        0xa58, 0xaab, 0xaf2, 0xa03, 0xa21, 0xad7, 0xa07, 0xa16, 0xa16, 0xa2b, 0xa6b, 0xaf8, 0xad9, 0xaf5, 0xa89, 0xaca, 0xa33, 0xaae,
+        0xa6e, 0xa32, 0xab3, 0xab1, 0xa9a, 0xabb, 0xa7f, 0xaa6, 0xa5d, 0xa11, 0xa41, 0xaa3, 0xaf9, 0xa4d,
     ]),
     utxo_commitment: EMPTY_MUHASH,
     timestamp: 0x11e9db49828,
@@ -0,0 +1,95 @@
+use crate::constants::BLOCK_VERSION;
+
+/// A block header version along with the DAA score from which it is accepted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HeaderVersionActivation {
+    pub version: u16,
+    pub activation_daa_score: u64,
+}
+
+impl HeaderVersionActivation {
+    pub const fn new(version: u16, activation_daa_score: u64) -> Self {
+        Self { version, activation_daa_score }
+    }
+
+    #[inline]
+    pub fn is_active(&self, daa_score: u64) -> bool {
+        daa_score >= self.activation_daa_score
+    }
+}
+
+/// Defines the block header versions accepted at a given DAA score.
+///
+/// Known versions are accepted from their activation score on. Once the optional unknown version tolerance
+/// is active, any version above its threshold is accepted as well, which allows staging new header versions
+/// on test networks before they are known to all nodes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HeaderVersionPolicy {
+    /// The known header versions, ordered by activation score
+    pub versions: &'static [HeaderVersionActivation],
+
+    /// Versions above `version` are tolerated from `activation_daa_score` on
+    pub unknown_version_tolerance: Option<HeaderVersionActivation>,
+}
+
+const DEFAULT_HEADER_VERSIONS: &[HeaderVersionActivation] = &[HeaderVersionActivation::new(BLOCK_VERSION, 0)];
+
+impl HeaderVersionPolicy {
+    /// The policy accepting [`BLOCK_VERSION`] only, at any DAA score
+    pub const DEFAULT: Self = Self::new(DEFAULT_HEADER_VERSIONS);
+
+    pub const fn new(versions: &'static [HeaderVersionActivation]) -> Self {
+        Self { versions, unknown_version_tolerance: None }
+    }
+
+    /// Returns a policy tolerating any version above `version` from `activation_daa_score` on
+    pub const fn with_unknown_version_tolerance(self, version: u16, activation_daa_score: u64) -> Self {
+        Self { versions: self.versions, unknown_version_tolerance: Some(HeaderVersionActivation::new(version, activation_daa_score)) }
+    }
+
+    /// Returns the known versions active at `daa_score`
+    pub fn active_versions(&self, daa_score: u64) -> Vec<u16> {
+        self.versions.iter().filter(|x| x.is_active(daa_score)).map(|x| x.version).collect()
+    }
+
+    pub fn is_accepted(&self, version: u16, daa_score: u64) -> bool {
+        self.versions.iter().any(|x| x.version == version && x.is_active(daa_score))
+            || self.unknown_version_tolerance.is_some_and(|x| x.is_active(daa_score) && version > x.version)
+    }
+
+    /// Returns the highest known version active at `daa_score`, which is the version of the headers built by this node
+    pub fn highest_active_version(&self, daa_score: u64) -> u16 {
+        self.versions.iter().filter(|x| x.is_active(daa_score)).map(|x| x.version).max().unwrap_or(BLOCK_VERSION)
+    }
+}
+
+impl Default for HeaderVersionPolicy {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_version_policy() {
+        const VERSIONS: &[HeaderVersionActivation] = &[HeaderVersionActivation::new(1, 0), HeaderVersionActivation::new(2, 100)];
+        let policy = HeaderVersionPolicy::new(VERSIONS);
+        assert!(policy.is_accepted(1, 0) && policy.is_accepted(1, 100));
+        assert!(!policy.is_accepted(2, 99) && policy.is_accepted(2, 100));
+        assert!(!policy.is_accepted(0, 100) && !policy.is_accepted(3, 100));
+        assert_eq!(policy.active_versions(99), vec![1]);
+        assert_eq!(policy.active_versions(100), vec![1, 2]);
+        assert_eq!(policy.highest_active_version(99), 1);
+        assert_eq!(policy.highest_active_version(100), 2);
+
+        let policy = policy.with_unknown_version_tolerance(2, 200);
+        assert!(!policy.is_accepted(3, 199) && policy.is_accepted(3, 200) && policy.is_accepted(u16::MAX, 200));
+        assert!(!policy.is_accepted(0, 200));
+        assert_eq!(policy.highest_active_version(200), 2, "unknown versions are never built");
+
+        assert_eq!(HeaderVersionPolicy::DEFAULT.highest_active_version(0), BLOCK_VERSION);
+    }
+}
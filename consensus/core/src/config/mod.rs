@@ -1,8 +1,11 @@
 pub mod bps;
+pub mod checkpoints;
 pub mod constants;
 pub mod genesis;
+pub mod header_version;
 pub mod params;
 
+use kaspa_core::time::{DynClock, SystemClock};
 use kaspa_utils::networking::{ContextualNetAddress, NetAddress};
 
 #[cfg(feature = "devnet-prealloc")]
@@ -13,6 +16,7 @@ use std::sync::Arc;
 use std::ops::Deref;
 
 use {
+    checkpoints::{default_checkpoints, Checkpoint},
     constants::perf::{PerfParams, PERF_PARAMS},
     params::Params,
 };
@@ -42,9 +46,24 @@ pub struct Config {
     /// Enable the UTXO index
     pub utxoindex: bool,
 
+    /// Record the UTXO entries spent by the accepted transactions along with the acceptance data of chain blocks,
+    /// allowing to resolve the address the coins of a transaction input came from
+    pub store_spent_utxos: bool,
+
     /// Enable RPC commands which affect the state of the node
     pub unsafe_rpc: bool,
 
+    /// Hold transactions submitted by RPC whose lock time is not reached yet until they become valid
+    pub hold_time_locked_txs: bool,
+
+    /// Reject peers not advertising their consensus params digest and genesis hash during the handshake.
+    /// Such legacy peers are tolerated by default, until the network has upgraded.
+    pub reject_legacy_peers: bool,
+
+    /// Skip verifying the block bodies received during IBD against the hash merkle roots of their headers
+    /// before queuing them for processing. Corrupted bodies are then only detected by the body processor.
+    pub skip_ibd_merkle_check: bool,
+
     /// Allow the node to accept blocks from RPC while not synced
     /// (required when initiating a new network from genesis)
     pub enable_unsynced_mining: bool,
@@ -61,6 +80,9 @@ pub struct Config {
 
     pub block_template_cache_lifetime: Option<u64>,
 
+    /// Number of most recent notifications retained per journaled notification scope, 0 disabling the journal
+    pub notification_journal_size: usize,
+
     #[cfg(feature = "devnet-prealloc")]
     pub initial_utxo_set: Arc<UtxoCollection>,
 
@@ -68,6 +90,12 @@ pub struct Config {
 
     /// A scale factor to apply to memory allocation bounds
     pub ram_scale: f64,
+
+    /// Pruning point checkpoints the pruning points chain of a syncer peer is validated against
+    pub checkpoints: Vec<Checkpoint>,
+
+    /// Clock providing the wall time to consensus, the system clock unless simulated by tests
+    pub clock: DynClock,
 }
 
 impl Config {
@@ -76,6 +104,7 @@ impl Config {
     }
 
     pub fn with_perf(params: Params, perf: PerfParams) -> Self {
+        let checkpoints = default_checkpoints(&params);
         Self {
             params,
             perf,
@@ -83,18 +112,25 @@ impl Config {
             is_archival: false,
             enable_sanity_checks: false,
             utxoindex: false,
+            store_spent_utxos: false,
             unsafe_rpc: false,
+            hold_time_locked_txs: false,
+            reject_legacy_peers: false,
+            skip_ibd_merkle_check: false,
             enable_unsynced_mining: false,
             enable_mainnet_mining: false,
             user_agent_comments: Default::default(),
             externalip: None,
             p2p_listen_address: ContextualNetAddress::unspecified(),
             block_template_cache_lifetime: None,
+            notification_journal_size: 0,
 
             #[cfg(feature = "devnet-prealloc")]
             initial_utxo_set: Default::default(),
             disable_upnp: false,
             ram_scale: 1.0,
+            checkpoints,
+            clock: SystemClock::new_dyn(),
         }
     }
 
@@ -167,6 +203,12 @@ impl ConfigBuilder {
         self
     }
 
+    /// Sets the clock providing the wall time to consensus, typically a simulated clock in tests
+    pub fn set_clock(mut self, clock: DynClock) -> Self {
+        self.config.clock = clock;
+        self
+    }
+
     pub fn skip_adding_genesis(mut self) -> Self {
         self.config.process_genesis = false;
         self
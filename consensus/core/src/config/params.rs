@@ -2,6 +2,7 @@ pub use super::{
     bps::{Bps, Testnet11Bps},
     constants::consensus::*,
     genesis::{GenesisBlock, DEVNET_GENESIS, GENESIS, SIMNET_GENESIS, TESTNET11_GENESIS, TESTNET_GENESIS},
+    header_version::{HeaderVersionActivation, HeaderVersionPolicy},
 };
 use crate::{
     constants::STORAGE_MASS_PARAMETER,
@@ -9,11 +10,10 @@ use crate::{
     BlockLevel, KType,
 };
 use kaspa_addresses::Prefix;
+use kaspa_core::time::hybrid_unix_now;
+use kaspa_hashes::Hash;
 use kaspa_math::Uint256;
-use std::{
-    cmp::min,
-    time::{SystemTime, UNIX_EPOCH},
-};
+use std::cmp::min;
 
 /// Consensus parameters. Contains settings and configurations which are consensus-sensitive.
 /// Changing one of these on a network node would exclude and prevent it from reaching consensus
@@ -77,6 +77,9 @@ pub struct Params {
     pub mass_per_sig_op: u64,
     pub max_block_mass: u64,
 
+    /// Maximum number of transactions of a block, coinbase included (see [`DEFAULT_MAX_BLOCK_TRANSACTIONS`])
+    pub max_block_transactions: usize,
+
     /// The parameter for scaling inverse KAS value to mass units (unpublished KIP-0009)
     pub storage_mass_parameter: u64,
 
@@ -91,10 +94,9 @@ pub struct Params {
     pub skip_proof_of_work: bool,
     pub max_block_level: BlockLevel,
     pub pruning_proof_m: u64,
-}
 
-fn unix_now() -> u64 {
-    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
+    /// Block header versions accepted by consensus and their activation DAA scores
+    pub header_version_policy: HeaderVersionPolicy,
 }
 
 impl Params {
@@ -223,11 +225,17 @@ impl Params {
 
     /// Returns whether the sink timestamp is recent enough and the node is considered synced or nearly synced.
     pub fn is_nearly_synced(&self, sink_timestamp: u64, sink_daa_score: u64) -> bool {
+        self.is_nearly_synced_at(hybrid_unix_now(), sink_timestamp, sink_daa_score)
+    }
+
+    /// Returns whether the sink timestamp is recent enough at time `now` for the node to be considered synced or
+    /// nearly synced, see [`Self::is_nearly_synced`]
+    pub fn is_nearly_synced_at(&self, now: u64, sink_timestamp: u64, sink_daa_score: u64) -> bool {
         if self.net.is_mainnet() {
             // We consider the node close to being synced if the sink (virtual selected parent) block
             // timestamp is within DAA window duration far in the past. Blocks mined over such DAG state would
             // enter the DAA window of fully-synced nodes and thus contribute to overall network difficulty
-            unix_now() < sink_timestamp + self.expected_daa_window_duration_in_milliseconds(sink_daa_score)
+            now < sink_timestamp + self.expected_daa_window_duration_in_milliseconds(sink_daa_score)
         } else {
             // For testnets we consider the node to be synced if the sink timestamp is within a time range which
             // is overwhelmingly unlikely to pass without mined blocks even if net hashrate decreased dramatically.
@@ -237,7 +245,7 @@ impl Params {
             //
             // We use DAA duration as baseline and scale it down with BPS (and divide by 3 for mining only when very close to current time on TN11)
             let max_expected_duration_without_blocks_in_milliseconds = self.target_time_per_block * NEW_DIFFICULTY_WINDOW_DURATION / 3; // = DAA duration in milliseconds / bps / 3
-            unix_now() < sink_timestamp + max_expected_duration_without_blocks_in_milliseconds
+            now < sink_timestamp + max_expected_duration_without_blocks_in_milliseconds
         }
     }
 
@@ -260,6 +268,12 @@ impl Params {
     pub fn finality_duration(&self) -> u64 {
         self.target_time_per_block * self.finality_depth
     }
+
+    /// Returns a canonical digest of the consensus-relevant params. Nodes and clients
+    /// can compare digests in order to detect a network or params misconfiguration.
+    pub fn digest(&self) -> Hash {
+        crate::hashing::params::digest(self)
+    }
 }
 
 impl From<NetworkType> for Params {
@@ -346,6 +360,7 @@ pub const MAINNET_PARAMS: Params = Params {
     mass_per_script_pub_key_byte: 10,
     mass_per_sig_op: 1000,
     max_block_mass: 500_000,
+    max_block_transactions: DEFAULT_MAX_BLOCK_TRANSACTIONS,
 
     storage_mass_parameter: STORAGE_MASS_PARAMETER,
     storage_mass_activation_daa_score: u64::MAX,
@@ -362,6 +377,7 @@ pub const MAINNET_PARAMS: Params = Params {
     skip_proof_of_work: false,
     max_block_level: 225,
     pruning_proof_m: 1000,
+    header_version_policy: HeaderVersionPolicy::DEFAULT,
 };
 
 pub const TESTNET_PARAMS: Params = Params {
@@ -405,6 +421,7 @@ pub const TESTNET_PARAMS: Params = Params {
     mass_per_script_pub_key_byte: 10,
     mass_per_sig_op: 1000,
     max_block_mass: 500_000,
+    max_block_transactions: DEFAULT_MAX_BLOCK_TRANSACTIONS,
 
     storage_mass_parameter: STORAGE_MASS_PARAMETER,
     storage_mass_activation_daa_score: u64::MAX,
@@ -421,6 +438,7 @@ pub const TESTNET_PARAMS: Params = Params {
     skip_proof_of_work: false,
     max_block_level: 250,
     pruning_proof_m: 1000,
+    header_version_policy: HeaderVersionPolicy::DEFAULT,
 };
 
 pub const TESTNET11_PARAMS: Params = Params {
@@ -455,6 +473,7 @@ pub const TESTNET11_PARAMS: Params = Params {
     finality_depth: Testnet11Bps::finality_depth(),
     pruning_depth: Testnet11Bps::pruning_depth(),
     pruning_proof_m: Testnet11Bps::pruning_proof_m(),
+    header_version_policy: HeaderVersionPolicy::DEFAULT,
     deflationary_phase_daa_score: Testnet11Bps::deflationary_phase_daa_score(),
     pre_deflationary_phase_base_subsidy: Testnet11Bps::pre_deflationary_phase_base_subsidy(),
     coinbase_maturity: Testnet11Bps::coinbase_maturity(),
@@ -471,6 +490,7 @@ pub const TESTNET11_PARAMS: Params = Params {
     mass_per_script_pub_key_byte: 10,
     mass_per_sig_op: 1000,
     max_block_mass: 500_000,
+    max_block_transactions: DEFAULT_MAX_BLOCK_TRANSACTIONS,
 
     storage_mass_parameter: STORAGE_MASS_PARAMETER,
     storage_mass_activation_daa_score: 0,
@@ -507,6 +527,7 @@ pub const SIMNET_PARAMS: Params = Params {
     finality_depth: Testnet11Bps::finality_depth(),
     pruning_depth: Testnet11Bps::pruning_depth(),
     pruning_proof_m: Testnet11Bps::pruning_proof_m(),
+    header_version_policy: HeaderVersionPolicy::DEFAULT,
     deflationary_phase_daa_score: Testnet11Bps::deflationary_phase_daa_score(),
     pre_deflationary_phase_base_subsidy: Testnet11Bps::pre_deflationary_phase_base_subsidy(),
     coinbase_maturity: Testnet11Bps::coinbase_maturity(),
@@ -523,6 +544,7 @@ pub const SIMNET_PARAMS: Params = Params {
     mass_per_script_pub_key_byte: 10,
     mass_per_sig_op: 1000,
     max_block_mass: 500_000,
+    max_block_transactions: DEFAULT_MAX_BLOCK_TRANSACTIONS,
 
     storage_mass_parameter: STORAGE_MASS_PARAMETER,
     storage_mass_activation_daa_score: 0,
@@ -569,6 +591,7 @@ pub const DEVNET_PARAMS: Params = Params {
     mass_per_script_pub_key_byte: 10,
     mass_per_sig_op: 1000,
     max_block_mass: 500_000,
+    max_block_transactions: DEFAULT_MAX_BLOCK_TRANSACTIONS,
 
     storage_mass_parameter: STORAGE_MASS_PARAMETER,
     storage_mass_activation_daa_score: u64::MAX,
@@ -585,4 +608,5 @@ pub const DEVNET_PARAMS: Params = Params {
     skip_proof_of_work: false,
     max_block_level: 250,
     pruning_proof_m: 1000,
+    header_version_policy: HeaderVersionPolicy::DEFAULT,
 };
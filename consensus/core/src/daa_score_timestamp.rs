@@ -2,6 +2,8 @@ use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
+use kaspa_hashes::Hash;
+
 use crate::header::Header;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,3 +23,17 @@ impl From<Arc<Header>> for DaaScoreTimestamp {
         DaaScoreTimestamp { daa_score: header.daa_score, timestamp: header.timestamp }
     }
 }
+
+/// A selected chain block along with its exact DAA score and timestamp
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainBlockAtDaaScore {
+    pub hash: Hash,
+    pub daa_score: u64,
+    pub timestamp: u64,
+}
+
+impl From<ChainBlockAtDaaScore> for DaaScoreTimestamp {
+    fn from(block: ChainBlockAtDaaScore) -> DaaScoreTimestamp {
+        DaaScoreTimestamp { daa_score: block.daa_score, timestamp: block.timestamp }
+    }
+}
@@ -0,0 +1,15 @@
+use crate::BlueWorkType;
+use kaspa_math::Uint256;
+
+/// The difficulty context a block was mined in, as needed for verifying the network conditions of mined blocks
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockDifficultyContext {
+    /// Target decoded from the header bits
+    pub target: Uint256,
+
+    /// Expected number of hashes needed to mine a block with this target
+    pub work: BlueWorkType,
+
+    /// Network hashrate estimated over the DAA window of the block, or `None` if the window is too short
+    pub network_hashes_per_second: Option<u64>,
+}
@@ -0,0 +1,38 @@
+use crate::network::NetworkId;
+use kaspa_hashes::Hash;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone)]
+pub enum BackupError {
+    #[error("the backup directory {0} already exists")]
+    OutputExists(String),
+
+    #[error("only the active consensus can be backed up")]
+    NoActiveConsensus,
+
+    #[error("backup manifest: {0}")]
+    Manifest(String),
+
+    #[error("the backup was taken on network {0} while the node is configured for {1}")]
+    NetworkMismatch(NetworkId, NetworkId),
+
+    #[error("the backup consensus params digest {0} does not match the configured params digest {1}")]
+    ParamsDigestMismatch(Hash, Hash),
+
+    #[error("backup I/O error: {0}")]
+    Io(String),
+
+    #[error("backup database error: {0}")]
+    Store(String),
+
+    #[error("the backup is inconsistent: {0}")]
+    Inconsistent(String),
+}
+
+impl From<std::io::Error> for BackupError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err.to_string())
+    }
+}
+
+pub type BackupResult<T> = std::result::Result<T, BackupError>;
@@ -1,7 +1,7 @@
 use std::{collections::HashMap, fmt::Display};
 
 use crate::{
-    constants,
+    coinbase::CoinbaseAmounts,
     errors::{coinbase::CoinbaseError, tx::TxRuleError},
     tx::{TransactionId, TransactionOutpoint},
     BlueWorkType,
@@ -28,8 +28,8 @@ impl<T: Display + Clone> Display for TwoDimVecDisplay<T> {
 
 #[derive(Error, Debug, Clone)]
 pub enum RuleError {
-    #[error("wrong block version: got {0} but expected {}", constants::BLOCK_VERSION)]
-    WrongBlockVersion(u16),
+    #[error("block version {0} is not accepted at DAA score {2}, accepted versions are {1}")]
+    UnacceptedBlockVersion(u16, VecDisplay<u16>, u64),
 
     #[error("the block timestamp is too far into the future: block timestamp is {0} but maximum timestamp allowed is {1}")]
     TimeTooFarIntoTheFuture(u64, u64),
@@ -85,6 +85,9 @@ pub enum RuleError {
     #[error("block has no transactions")]
     NoTransactions,
 
+    #[error("block has {0} transactions, exceeding the limit of {1}")]
+    TooManyTransactions(usize, usize),
+
     #[error("block first transaction is not coinbase")]
     FirstTxNotCoinbase,
 
@@ -139,14 +142,31 @@ pub enum RuleError {
     #[error("coinbase transaction is not built as expected")]
     BadCoinbaseTransaction,
 
+    #[error("coinbase transaction pays a total of {1} sompi but is expected to pay {0}")]
+    BadCoinbasePayment(CoinbaseAmounts, u64),
+
     #[error("{0} non-coinbase transactions (out of {1}) are invalid in UTXO context")]
     InvalidTransactionsInUtxoContext(usize, usize),
 
     #[error("invalid transactions in new block template")]
     InvalidTransactionsInNewBlock(HashMap<TransactionId, TxRuleError>),
 
+    #[error("block template parents {1} are not the current virtual parents {0}")]
+    UnexpectedTemplateParents(VecDisplay<Hash>, VecDisplay<Hash>),
+
     #[error("DAA window data has only {0} entries")]
     InsufficientDaaWindowSize(usize),
+
+    /// A store read failed for a reason other than a missing key (e.g. a corrupted record or a DB error).
+    /// The block is left unprocessed rather than marked invalid since its validity is unknown
+    #[error("failed reading {1} from the {0} store: {2}")]
+    StoreFailure(String, String, String),
+}
+
+impl RuleError {
+    pub fn is_store_failure(&self) -> bool {
+        matches!(self, RuleError::StoreFailure(..))
+    }
 }
 
 pub type BlockProcessResult<T> = std::result::Result<T, RuleError>;
@@ -17,6 +17,33 @@ pub enum ConfigError {
     #[error("Configuration: --max-tracked-addresses cannot be set above {0}")]
     MaxTrackedAddressesTooHigh(usize),
 
+    #[error("Configuration: --p2p-serving-window-sec cannot be set to 0")]
+    P2pServingWindowTooLow,
+
+    #[error("Configuration: --p2p-serving-disconnect-factor cannot be set below 1")]
+    P2pServingDisconnectFactorTooLow,
+
+    #[error("Configuration: --p2p-ping-interval-sec, --p2p-idle-timeout-sec and --p2p-pong-timeout-sec cannot be set to 0")]
+    P2pKeepaliveTimeoutTooLow,
+
+    #[error("Configuration: --p2p-max-missed-pongs cannot be set below 1")]
+    P2pMaxMissedPongsTooLow,
+
+    #[error("Configuration: --processing-parallelism cannot be set to 0")]
+    ProcessingParallelismTooLow,
+
+    #[error("Configuration: --proxy-onion-only requires --proxy")]
+    ProxyOnionOnlyWithoutProxy,
+
+    #[error("Configuration: dust-relay-threshold-by-class has an unknown script class `{0}`")]
+    InvalidDustRelayScriptClass(String),
+
+    #[error("Configuration: --template-origin-mass-quota must be above 0 and at most 1")]
+    TemplateOriginMassQuotaOutOfRange,
+
+    #[error("Configuration: --template-quota-fee-tolerance must be between 0 and 1")]
+    TemplateQuotaFeeToleranceOutOfRange,
+
     #[cfg(feature = "devnet-prealloc")]
     #[error("Cannot preallocate UTXOs on any network except devnet")]
     PreallocUtxosOnNonDevnet,
@@ -17,9 +17,24 @@ pub enum ConsensusError {
     #[error("some data is missing for block {0}")]
     MissingData(Hash),
 
+    #[error("block {0} is not a selected chain block")]
+    NotChainBlock(Hash),
+
+    #[error("transaction {0} is not accepted by chain block {1}")]
+    TransactionNotAccepted(Hash, Hash),
+
+    #[error("transaction {0} has no input at index {1}")]
+    InputIndexOutOfRange(Hash, u32),
+
+    #[error("spent UTXO entries are not stored for chain block {0}")]
+    SpentUtxoEntriesNotStored(Hash),
+
     #[error("got unexpected pruning point")]
     UnexpectedPruningPoint,
 
+    #[error("DAA score {0} is below the pruning point DAA score {1}")]
+    DaaScoreBelowPruningPoint(u64, u64),
+
     #[error("pruning point is not at sufficient depth from virtual, cannot obtain its final anticone at this stage")]
     PruningPointInsufficientDepth,
 
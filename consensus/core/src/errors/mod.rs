@@ -1,3 +1,4 @@
+pub mod backup;
 pub mod block;
 pub mod coinbase;
 pub mod config;
@@ -59,6 +59,12 @@ pub enum PruningImportError {
 
     #[error("process exit was initiated while validating pruning point proof")]
     PruningValidationInterrupted,
+
+    #[error("pruning point proof validation was cancelled")]
+    PruningValidationCancelled,
+
+    #[error("the pruning points chain contradicts the checkpoint {1} at DAA score {0}")]
+    PruningPointsCheckpointMismatch(u64, Hash),
 }
 
 pub type PruningImportResult<T> = std::result::Result<T, PruningImportError>;
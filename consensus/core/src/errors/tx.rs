@@ -44,9 +44,13 @@ pub enum TxRuleError {
     #[error("script public key of coinbase output #{0} is too long")]
     CoinbaseScriptPublicKeyTooLong(usize),
 
+    /// Input index, spent outpoint, UTXO DAA score, DAA score of the spend and coinbase maturity.
+    ///
+    /// The message format is parsed by [`CoinbaseMaturityShortfall::from_error_message`].
     #[error(
-        "transaction input #{0} tried to spend coinbase outpoint {1} with daa score of {2} 
-    while the merging block daa score is {3} and the coinbase maturity period of {4} hasn't passed yet"
+        "transaction input #{0} tried to spend coinbase outpoint {1} with daa score of {2} while the daa score is {3} \
+        and the coinbase maturity period of {4} hasn't passed yet ({} daa score remaining)",
+        CoinbaseMaturityShortfall::new(*.2, *.3, *.4).remaining()
     )]
     ImmatureCoinbaseSpend(usize, TransactionOutpoint, u64, u64, u64),
 
@@ -91,3 +95,77 @@ pub enum TxRuleError {
 }
 
 pub type TxResult<T> = std::result::Result<T, TxRuleError>;
+
+impl TxRuleError {
+    /// Returns the maturity shortfall of an [`TxRuleError::ImmatureCoinbaseSpend`] error
+    pub fn coinbase_maturity_shortfall(&self) -> Option<CoinbaseMaturityShortfall> {
+        match *self {
+            TxRuleError::ImmatureCoinbaseSpend(_, _, utxo_daa_score, daa_score, coinbase_maturity) => {
+                Some(CoinbaseMaturityShortfall::new(utxo_daa_score, daa_score, coinbase_maturity))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The DAA scores of a spend of an immature coinbase UTXO
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CoinbaseMaturityShortfall {
+    /// DAA score of the block which created the coinbase UTXO
+    pub utxo_daa_score: u64,
+    /// DAA score the spend was validated at, i.e. the virtual DAA score for mempool transactions
+    pub daa_score: u64,
+    pub coinbase_maturity: u64,
+}
+
+impl CoinbaseMaturityShortfall {
+    pub fn new(utxo_daa_score: u64, daa_score: u64, coinbase_maturity: u64) -> Self {
+        Self { utxo_daa_score, daa_score, coinbase_maturity }
+    }
+
+    /// DAA score from which the coinbase UTXO can be spent
+    pub fn mature_daa_score(&self) -> u64 {
+        self.utxo_daa_score.saturating_add(self.coinbase_maturity)
+    }
+
+    /// DAA score remaining before the coinbase UTXO can be spent
+    pub fn remaining(&self) -> u64 {
+        self.mature_daa_score().saturating_sub(self.daa_score)
+    }
+
+    /// Parses the shortfall out of an error message embedding the message of an [`TxRuleError::ImmatureCoinbaseSpend`]
+    /// error, as do the errors relayed by RPC when a transaction submission gets rejected
+    pub fn from_error_message(message: &str) -> Option<Self> {
+        fn number_after<'a>(message: &'a str, marker: &str) -> Option<(u64, &'a str)> {
+            let (_, tail) = message.split_once(marker)?;
+            let end = tail.find(|c: char| !c.is_ascii_digit()).unwrap_or(tail.len());
+            Some((tail[..end].parse().ok()?, &tail[end..]))
+        }
+        let (_, message) = message.split_once("tried to spend coinbase outpoint")?;
+        let (utxo_daa_score, message) = number_after(message, " with daa score of ")?;
+        let (daa_score, message) = number_after(message, " while the daa score is ")?;
+        let (coinbase_maturity, _) = number_after(message, " and the coinbase maturity period of ")?;
+        Some(Self::new(utxo_daa_score, daa_score, coinbase_maturity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_hashes::Hash;
+
+    #[test]
+    fn test_coinbase_maturity_shortfall_from_error_message() {
+        let err = TxRuleError::ImmatureCoinbaseSpend(1, TransactionOutpoint::new(Default::default(), 2), 10, 50, 100);
+        let shortfall = err.coinbase_maturity_shortfall().unwrap();
+        assert_eq!(shortfall.mature_daa_score(), 110);
+        assert_eq!(shortfall.remaining(), 60);
+        assert!(err.to_string().ends_with("(60 daa score remaining)"));
+
+        // The shortfall survives the wrapping of the message by the mempool and RPC errors
+        let message =
+            format!("Rejected transaction {}: one of the transaction inputs spends an immature UTXO: {err}", Hash::default());
+        assert_eq!(CoinbaseMaturityShortfall::from_error_message(&message), Some(shortfall));
+        assert_eq!(CoinbaseMaturityShortfall::from_error_message(&TxRuleError::NoTxInputs.to_string()), None);
+    }
+}
@@ -6,6 +6,40 @@ use kaspa_hashes::{Hash, HasherBase};
 #[inline]
 pub fn hash_override_nonce_time(header: &Header, nonce: u64, timestamp: u64) -> Hash {
     let mut hasher = kaspa_hashes::BlockHash::new();
+    write_header(&mut hasher, header, nonce, timestamp);
+    hasher.finalize()
+}
+
+/// Returns the bytes hashed by [`hash_override_nonce_time`], that is the header serialization using the provided
+/// nonce+timestamp. Integers are little endian and this serialization is a stable API. In order:
+///
+/// - `version`: u16
+/// - the number of parent levels: u64, then for each level the number of parents: u64, followed by the 32 bytes of each parent
+/// - `hash_merkle_root`, `accepted_id_merkle_root` and `utxo_commitment`: 32 bytes each
+/// - `timestamp`: u64, `bits`: u32, `nonce`: u64, `daa_score`: u64 and `blue_score`: u64
+/// - `blue_work`: the number of bytes of its big endian representation without leading zeros: u64, followed by these bytes
+/// - `pruning_point`: 32 bytes
+///
+/// The test vectors at `test-data/pre_pow_header_vectors.json` can be used for verifying reimplementations.
+pub fn serialize_override_nonce_time(header: &Header, nonce: u64, timestamp: u64) -> Vec<u8> {
+    let mut writer = ByteWriter::default();
+    write_header(&mut writer, header, nonce, timestamp);
+    writer.0
+}
+
+/// Collects the bytes written through the [`HasherBase`] interface instead of hashing them
+#[derive(Default)]
+struct ByteWriter(Vec<u8>);
+
+impl HasherBase for ByteWriter {
+    fn update<A: AsRef<[u8]>>(&mut self, data: A) -> &mut Self {
+        self.0.extend_from_slice(data.as_ref());
+        self
+    }
+}
+
+#[inline]
+fn write_header(hasher: &mut impl HasherBase, header: &Header, nonce: u64, timestamp: u64) {
     hasher.update(header.version.to_le_bytes()).write_len(header.parents_by_level.len()); // Write the number of parent levels
 
     // Write parents at each level
@@ -25,8 +59,6 @@ pub fn hash_override_nonce_time(header: &Header, nonce: u64, timestamp: u64) ->
         .update(header.blue_score.to_le_bytes())
         .write_blue_work(header.blue_work)
         .update(header.pruning_point);
-
-    hasher.finalize()
 }
 
 /// Returns the header hash.
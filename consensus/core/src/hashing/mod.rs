@@ -2,6 +2,7 @@ use crate::BlueWorkType;
 use kaspa_hashes::HasherBase;
 
 pub mod header;
+pub mod params;
 pub mod sighash;
 pub mod sighash_type;
 pub mod tx;
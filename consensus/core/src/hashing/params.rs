@@ -0,0 +1,81 @@
+use super::HasherExtensions;
+use crate::config::params::Params;
+use kaspa_hashes::{Hash, HasherBase};
+
+/// Returns a digest of the consensus-relevant fields of `params`.
+///
+/// Fields are written in declaration order using fixed width little endian encoding, so the digest
+/// is canonical across platforms. Fields which do not affect consensus (DNS seeders) or which are
+/// derived from other fields (the `f64` form of the max difficulty target) are excluded.
+pub fn digest(params: &Params) -> Hash {
+    let mut hasher = kaspa_hashes::ParamsDigestHash::new();
+    hasher
+        .write_var_bytes(params.net.to_string().as_bytes())
+        .update(params.genesis.hash)
+        .write_u16(params.ghostdag_k)
+        .write_u64(params.legacy_timestamp_deviation_tolerance)
+        .write_u64(params.new_timestamp_deviation_tolerance)
+        .write_u64(params.past_median_time_sample_rate)
+        .write_u64(params.past_median_time_sampled_window_size)
+        .write_u64(params.target_time_per_block)
+        .write_u64(params.sampling_activation_daa_score)
+        .update(params.max_difficulty_target.to_le_bytes())
+        .write_u64(params.difficulty_sample_rate)
+        .write_len(params.sampled_difficulty_window_size)
+        .write_len(params.legacy_difficulty_window_size)
+        .write_len(params.min_difficulty_window_len)
+        .write_u8(params.max_block_parents)
+        .write_u64(params.mergeset_size_limit)
+        .write_u64(params.merge_depth)
+        .write_u64(params.finality_depth)
+        .write_u64(params.pruning_depth)
+        .write_u8(params.coinbase_payload_script_public_key_max_len)
+        .write_len(params.max_coinbase_payload_len)
+        .write_len(params.max_tx_inputs)
+        .write_len(params.max_tx_outputs)
+        .write_len(params.max_signature_script_len)
+        .write_len(params.max_script_public_key_len)
+        .write_u64(params.mass_per_tx_byte)
+        .write_u64(params.mass_per_script_pub_key_byte)
+        .write_u64(params.mass_per_sig_op)
+        .write_u64(params.max_block_mass)
+        .write_len(params.max_block_transactions)
+        .write_u64(params.storage_mass_parameter)
+        .write_u64(params.storage_mass_activation_daa_score)
+        .write_u64(params.deflationary_phase_daa_score)
+        .write_u64(params.pre_deflationary_phase_base_subsidy)
+        .write_u64(params.coinbase_maturity)
+        .write_bool(params.skip_proof_of_work)
+        .write_u8(params.max_block_level)
+        .write_u64(params.pruning_proof_m);
+    hasher.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::params::{DEVNET_PARAMS, MAINNET_PARAMS, SIMNET_PARAMS, TESTNET11_PARAMS, TESTNET_PARAMS};
+
+    #[test]
+    fn test_params_digest() {
+        let all = [MAINNET_PARAMS, TESTNET_PARAMS, TESTNET11_PARAMS, SIMNET_PARAMS, DEVNET_PARAMS];
+        let digests = all.iter().map(digest).collect::<Vec<_>>();
+
+        // Each network has a distinct digest
+        for (i, a) in digests.iter().enumerate() {
+            for b in digests.iter().skip(i + 1) {
+                assert_ne!(a, b);
+            }
+        }
+
+        // The digest is deterministic and ignores non consensus-relevant fields
+        let mut params = MAINNET_PARAMS;
+        assert_eq!(digest(&params), digests[0]);
+        params.dns_seeders = &[];
+        assert_eq!(digest(&params), digests[0]);
+
+        // Overriding a consensus-relevant field changes the digest
+        params.coinbase_maturity += 1;
+        assert_ne!(digest(&params), digests[0]);
+    }
+}
@@ -17,6 +17,7 @@ pub struct Header {
     /// Timestamp is in milliseconds
     pub timestamp: u64,
     pub bits: u32,
+    #[serde(with = "kaspa_utils::serde_u64_string")]
     pub nonce: u64,
     pub daa_score: u64,
     pub blue_work: BlueWorkType,
@@ -64,6 +65,19 @@ impl Header {
         self.hash = hashing::header::hash(self);
     }
 
+    /// Returns the serialization hashed into the pre-PoW hash of the header, namely the header serialization with a zero
+    /// timestamp, and a zero nonce as well if `nonce_zeroed`. The proof of work is computed over the pre-PoW hash with
+    /// both zeroed, the timestamp and the nonce. This serialization is a stable API for external miners, see
+    /// [`hashing::header::serialize_override_nonce_time`] for its layout.
+    pub fn serialize_pre_pow(&self, nonce_zeroed: bool) -> Vec<u8> {
+        hashing::header::serialize_override_nonce_time(self, if nonce_zeroed { 0 } else { self.nonce }, 0)
+    }
+
+    /// Returns the hash of [`Self::serialize_pre_pow`]
+    pub fn pre_pow_hash(&self, nonce_zeroed: bool) -> Hash {
+        hashing::header::hash_override_nonce_time(self, if nonce_zeroed { 0 } else { self.nonce }, 0)
+    }
+
     pub fn direct_parents(&self) -> &[Hash] {
         if self.parents_by_level.is_empty() {
             &[]
@@ -95,8 +109,95 @@ impl Header {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::genesis::GENESIS;
+    use kaspa_hashes::Hasher;
     use kaspa_math::Uint192;
+    use kaspa_utils::hex::{FromHex, ToHex};
     use serde_json::Value;
+    use std::{fs::File, io::BufReader, path::Path};
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct VectorHeader {
+        version: u16,
+        parents_by_level: Vec<Vec<Hash>>,
+        hash_merkle_root: Hash,
+        accepted_id_merkle_root: Hash,
+        utxo_commitment: Hash,
+        timestamp: String,
+        bits: u32,
+        nonce: String,
+        daa_score: String,
+        blue_work: String,
+        blue_score: String,
+        pruning_point: Hash,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct PrePowVector {
+        name: String,
+        header: VectorHeader,
+        hash: Hash,
+        pre_pow_bytes: String,
+        pre_pow_hash: Hash,
+        pre_pow_bytes_with_nonce: String,
+        pre_pow_hash_with_nonce: Hash,
+    }
+
+    #[derive(Deserialize)]
+    struct PrePowVectors {
+        vectors: Vec<PrePowVector>,
+    }
+
+    #[test]
+    fn test_pre_pow_header_vectors() {
+        let file = File::open(Path::new(env!("CARGO_MANIFEST_DIR")).join("test-data").join("pre_pow_header_vectors.json")).unwrap();
+        let vectors: PrePowVectors = serde_json::from_reader(BufReader::new(file)).unwrap();
+
+        for vector in vectors.vectors {
+            let name = &vector.name;
+            let fields = vector.header;
+            let header = Header::new_finalized(
+                fields.version,
+                fields.parents_by_level,
+                fields.hash_merkle_root,
+                fields.accepted_id_merkle_root,
+                fields.utxo_commitment,
+                fields.timestamp.parse().unwrap(),
+                fields.bits,
+                fields.nonce.parse().unwrap(),
+                fields.daa_score.parse().unwrap(),
+                BlueWorkType::from_hex(&fields.blue_work).unwrap(),
+                fields.blue_score.parse().unwrap(),
+                fields.pruning_point,
+            );
+
+            assert_eq!(header.hash, vector.hash, "{name}");
+            assert_eq!(header.serialize_pre_pow(true).to_hex(), vector.pre_pow_bytes, "{name}");
+            assert_eq!(header.pre_pow_hash(true), vector.pre_pow_hash, "{name}");
+            assert_eq!(header.serialize_pre_pow(false).to_hex(), vector.pre_pow_bytes_with_nonce, "{name}");
+            assert_eq!(header.pre_pow_hash(false), vector.pre_pow_hash_with_nonce, "{name}");
+
+            // The hashes are those of the serializations
+            let pre_pow_bytes = Vec::<u8>::from_hex(&vector.pre_pow_bytes).unwrap();
+            assert_eq!(kaspa_hashes::BlockHash::hash(pre_pow_bytes), vector.pre_pow_hash, "{name}");
+            let full_bytes = hashing::header::serialize_override_nonce_time(&header, header.nonce, header.timestamp);
+            assert_eq!(kaspa_hashes::BlockHash::hash(full_bytes), header.hash, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_genesis_pre_pow_vector() {
+        let file = File::open(Path::new(env!("CARGO_MANIFEST_DIR")).join("test-data").join("pre_pow_header_vectors.json")).unwrap();
+        let vectors: PrePowVectors = serde_json::from_reader(BufReader::new(file)).unwrap();
+        let vector = vectors.vectors.iter().find(|vector| vector.name == "mainnet genesis").unwrap();
+
+        let genesis = Header::from(&GENESIS);
+        assert_eq!(genesis.hash, vector.hash);
+        assert_eq!(genesis.pre_pow_hash(true), vector.pre_pow_hash);
+        assert_eq!(genesis.serialize_pre_pow(true).to_hex(), vector.pre_pow_bytes);
+    }
 
     #[test]
     fn test_header_ser() {
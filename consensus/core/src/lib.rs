@@ -9,6 +9,9 @@ pub use kaspa_hashes::Hash;
 
 pub mod acceptance_data;
 pub mod api;
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+pub mod backup;
 pub mod block;
 pub mod blockhash;
 pub mod blockstatus;
@@ -16,12 +19,17 @@ pub mod coinbase;
 pub mod config;
 pub mod constants;
 pub mod daa_score_timestamp;
+pub mod difficulty;
+pub mod emission;
 pub mod errors;
 pub mod hashing;
 pub mod header;
+pub mod locktime;
 pub mod mass;
 pub mod merkle;
+pub mod message;
 pub mod muhash;
+pub mod multi_consensus;
 pub mod network;
 pub mod pruning;
 pub mod sign;
@@ -29,6 +37,7 @@ pub mod subnets;
 pub mod trusted;
 pub mod tx;
 pub mod utxo;
+pub mod virtual_parents;
 
 /// Integer type for accumulated PoW of blue blocks. We expect no more than
 /// 2^128 work in a single block (btc has ~2^80), and no more than 2^64
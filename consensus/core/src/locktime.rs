@@ -0,0 +1,163 @@
+//!
+//! Lock time finality and sequence lock calculations shared by the consensus validation,
+//! the mempool and transaction builders which need to predict when a transaction becomes valid.
+//!
+
+use crate::{
+    constants::{LOCK_TIME_THRESHOLD, MAX_TX_IN_SEQUENCE_NUM, SEQUENCE_LOCK_TIME_DISABLED, SEQUENCE_LOCK_TIME_MASK},
+    tx::Transaction,
+};
+
+/// Returns whether `lock_time` has passed in the context of the provided DAA score and past median time.
+///
+/// The lock time is either a DAA score or a timestamp (in milliseconds) depending on whether it is
+/// below [`LOCK_TIME_THRESHOLD`]. A zero lock time is always reached.
+pub fn is_lock_time_reached(lock_time: u64, daa_score: u64, median_time: u64) -> bool {
+    if lock_time == 0 {
+        return true;
+    }
+    let block_time_or_daa_score = if lock_time < LOCK_TIME_THRESHOLD { daa_score } else { median_time };
+    lock_time < block_time_or_daa_score
+}
+
+/// Returns the index of the first input preventing the finality of `tx` in the context of the provided
+/// DAA score and past median time, or `None` if the transaction is final.
+///
+/// A transaction whose lock time has not passed yet is still final if the sequence numbers of all its
+/// inputs are maxed out.
+pub fn first_non_final_input(tx: &Transaction, daa_score: u64, median_time: u64) -> Option<usize> {
+    if is_lock_time_reached(tx.lock_time, daa_score, median_time) {
+        return None;
+    }
+    tx.inputs.iter().position(|input| input.sequence != MAX_TX_IN_SEQUENCE_NUM)
+}
+
+/// Returns whether `tx` is final in the context of the provided DAA score and past median time
+pub fn is_final(tx: &Transaction, daa_score: u64, median_time: u64) -> bool {
+    first_non_final_input(tx, daa_score, median_time).is_none()
+}
+
+/// The earliest DAA score and past median time at which a transaction satisfies both its lock time
+/// and the relative locks of its inputs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SequenceLock {
+    pub daa_score: u64,
+    pub median_time: u64,
+}
+
+impl SequenceLock {
+    /// Returns whether the lock is satisfied in the context of the provided DAA score and past median time
+    pub fn is_met(&self, daa_score: u64, median_time: u64) -> bool {
+        daa_score >= self.daa_score && median_time >= self.median_time
+    }
+}
+
+/// Calculates the earliest DAA score and past median time, at or after the current ones, at which `tx`
+/// is final and the relative locks of all its inputs are satisfied, so that the transaction can be
+/// accepted by a block (or the mempool) at these values.
+///
+/// `utxo_daa_scores` holds the DAA scores of the UTXO entries spent by the transaction inputs, in the
+/// input order. Entries which are not accepted yet (see [`crate::constants::UNACCEPTED_DAA_SCORE`]) cannot
+/// satisfy a relative lock, in which case the returned DAA score is `u64::MAX`.
+///
+/// Relative locks are expressed in DAA score units only, hence the past median time only reflects a
+/// timestamp-based lock time.
+pub fn calc_sequence_lock(
+    tx: &Transaction,
+    utxo_daa_scores: &[u64],
+    current_daa_score: u64,
+    current_median_time: u64,
+) -> SequenceLock {
+    assert_eq!(tx.inputs.len(), utxo_daa_scores.len(), "a DAA score is expected for the UTXO entry of each input");
+    let mut lock = SequenceLock { daa_score: current_daa_score, median_time: current_median_time };
+
+    // The lock time must have passed, i.e., the context value must be strictly greater than the lock time
+    if tx.lock_time != 0 && tx.inputs.iter().any(|input| input.sequence != MAX_TX_IN_SEQUENCE_NUM) {
+        let required = tx.lock_time.saturating_add(1);
+        if tx.lock_time < LOCK_TIME_THRESHOLD {
+            lock.daa_score = lock.daa_score.max(required);
+        } else {
+            lock.median_time = lock.median_time.max(required);
+        }
+    }
+
+    // An input with a relative lock can be spent once the DAA score reaches the DAA score of its
+    // UTXO entry plus the relative lock
+    for (input, &utxo_daa_score) in tx.inputs.iter().zip(utxo_daa_scores) {
+        if input.sequence & SEQUENCE_LOCK_TIME_DISABLED == SEQUENCE_LOCK_TIME_DISABLED {
+            continue;
+        }
+        let required = utxo_daa_score.saturating_add(input.sequence & SEQUENCE_LOCK_TIME_MASK);
+        lock.daa_score = lock.daa_score.max(required);
+    }
+
+    lock
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constants::UNACCEPTED_DAA_SCORE,
+        subnets::SUBNETWORK_ID_NATIVE,
+        tx::{TransactionInput, TransactionOutpoint},
+    };
+
+    fn tx_with(lock_time: u64, sequences: &[u64]) -> Transaction {
+        let inputs = sequences
+            .iter()
+            .enumerate()
+            .map(|(i, &sequence)| TransactionInput::new(TransactionOutpoint::new(1.into(), i as u32), vec![], sequence, 1))
+            .collect();
+        Transaction::new(0, inputs, vec![], lock_time, SUBNETWORK_ID_NATIVE, 0, vec![])
+    }
+
+    #[test]
+    fn test_is_final() {
+        let timestamp = LOCK_TIME_THRESHOLD + 1000;
+        let tests = [
+            // (lock time, sequences, DAA score, median time, expected first non final input)
+            (0, vec![0], 0, 0, None),
+            (100, vec![MAX_TX_IN_SEQUENCE_NUM, 0], 100, 0, Some(1)),
+            (100, vec![MAX_TX_IN_SEQUENCE_NUM, 0], 101, 0, None),
+            (100, vec![MAX_TX_IN_SEQUENCE_NUM, MAX_TX_IN_SEQUENCE_NUM], 0, 0, None),
+            // Timestamp lock times are compared against the median time only
+            (timestamp, vec![0], u64::MAX, timestamp, Some(0)),
+            (timestamp, vec![0], 0, timestamp + 1, None),
+            (LOCK_TIME_THRESHOLD - 1, vec![0], LOCK_TIME_THRESHOLD, 0, None),
+        ];
+        for (lock_time, sequences, daa_score, median_time, expected) in tests {
+            let tx = tx_with(lock_time, &sequences);
+            assert_eq!(
+                first_non_final_input(&tx, daa_score, median_time),
+                expected,
+                "lock time {lock_time} at ({daa_score}, {median_time})"
+            );
+            assert_eq!(is_final(&tx, daa_score, median_time), expected.is_none());
+        }
+    }
+
+    #[test]
+    fn test_calc_sequence_lock() {
+        let timestamp = LOCK_TIME_THRESHOLD + 1000;
+        let tests = [
+            // (lock time, sequences, UTXO DAA scores, current DAA score, current median time, expected lock)
+            (0, vec![SEQUENCE_LOCK_TIME_DISABLED], vec![10], 50, 70, (50, 70)),
+            (0, vec![MAX_TX_IN_SEQUENCE_NUM], vec![10], 50, 70, (50, 70)),
+            (0, vec![30, 5], vec![40, 60], 50, 70, (70, 70)),
+            (0, vec![30 | (1 << 32)], vec![40], 50, 70, (70, 70)),
+            (0, vec![10], vec![20], 50, 70, (50, 70)),
+            (100, vec![SEQUENCE_LOCK_TIME_DISABLED], vec![10], 50, 70, (101, 70)),
+            (100, vec![MAX_TX_IN_SEQUENCE_NUM], vec![10], 50, 70, (50, 70)),
+            (100, vec![500], vec![10], 50, 70, (510, 70)),
+            (timestamp, vec![20], vec![10], 50, 70, (50, timestamp + 1)),
+            (0, vec![20], vec![UNACCEPTED_DAA_SCORE], 50, 70, (u64::MAX, 70)),
+        ];
+        for (lock_time, sequences, utxo_daa_scores, daa_score, median_time, (expected_daa_score, expected_median_time)) in tests {
+            let tx = tx_with(lock_time, &sequences);
+            let lock = calc_sequence_lock(&tx, &utxo_daa_scores, daa_score, median_time);
+            assert_eq!(lock, SequenceLock { daa_score: expected_daa_score, median_time: expected_median_time });
+            assert!(lock.daa_score == u64::MAX || is_final(&tx, lock.daa_score, lock.median_time));
+        }
+    }
+}
@@ -0,0 +1,328 @@
+//!
+//! Signing of arbitrary messages with the key of an address, allowing to prove the ownership of the address.
+//!
+//! The signed digest is the message length (as u64 little endian) followed by the message bytes, hashed with
+//! blake2b-256 keyed with the `KaspaSignedMessage:` domain. It is signed deterministically, with a schnorr signature
+//! (BIP340, no auxiliary randomness) for [`Version::PubKey`] addresses and a recoverable ECDSA signature (RFC6979)
+//! for [`Version::PubKeyECDSA`] addresses.
+//!
+//! A signature is encoded as 65 bytes: a header byte identifying the scheme followed by the 64 signature bytes.
+//! The header is `0x00` for schnorr and `0x01 + recovery id` for ECDSA.
+//!
+
+use crate::hashing::HasherExtensions;
+use kaspa_addresses::{Address, Prefix, Version};
+use kaspa_hashes::{Hash, SignedMessageHash};
+use secp256k1::{
+    ecdsa::{RecoverableSignature, RecoveryId},
+    Keypair, PublicKey, SecretKey, XOnlyPublicKey, SECP256K1,
+};
+use std::{fmt::Display, str::FromStr};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    #[error("{0} addresses are not supported for message signing")]
+    UnsupportedAddress(Version),
+
+    #[error("a {signature} signature cannot be verified against a {address} address")]
+    SchemeMismatch { signature: SignatureScheme, address: Version },
+
+    #[error("invalid message signature encoding: {0}")]
+    InvalidEncoding(String),
+
+    #[error("the signature does not match the message and the address")]
+    InvalidSignature,
+
+    #[error("the address cannot be recovered from a schnorr signature")]
+    NotRecoverable,
+
+    #[error("Secp256k1 -> {0}")]
+    Secp256k1Error(#[from] secp256k1::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureScheme {
+    Schnorr,
+    Ecdsa,
+}
+
+impl Display for SignatureScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignatureScheme::Schnorr => write!(f, "schnorr"),
+            SignatureScheme::Ecdsa => write!(f, "ECDSA"),
+        }
+    }
+}
+
+/// A message signature in its compact encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageSignature {
+    Schnorr([u8; 64]),
+    Ecdsa { recovery_id: u8, signature: [u8; 64] },
+}
+
+impl MessageSignature {
+    /// Size of the encoded signature
+    pub const SIZE: usize = 65;
+
+    const SCHNORR_HEADER: u8 = 0x00;
+    const ECDSA_HEADER: u8 = 0x01;
+
+    pub fn scheme(&self) -> SignatureScheme {
+        match self {
+            MessageSignature::Schnorr(_) => SignatureScheme::Schnorr,
+            MessageSignature::Ecdsa { .. } => SignatureScheme::Ecdsa,
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0u8; Self::SIZE];
+        let (header, signature) = match self {
+            MessageSignature::Schnorr(signature) => (Self::SCHNORR_HEADER, signature),
+            MessageSignature::Ecdsa { recovery_id, signature } => (Self::ECDSA_HEADER + recovery_id, signature),
+        };
+        bytes[0] = header;
+        bytes[1..].copy_from_slice(signature);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != Self::SIZE {
+            return Err(Error::InvalidEncoding(format!("expected {} bytes, got {}", Self::SIZE, bytes.len())));
+        }
+        let signature: [u8; 64] = bytes[1..].try_into().unwrap();
+        match bytes[0] {
+            Self::SCHNORR_HEADER => Ok(MessageSignature::Schnorr(signature)),
+            header @ 0x01..=0x04 => Ok(MessageSignature::Ecdsa { recovery_id: header - Self::ECDSA_HEADER, signature }),
+            header => Err(Error::InvalidEncoding(format!("unknown header {header:#04x}"))),
+        }
+    }
+}
+
+impl Display for MessageSignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", faster_hex::hex_string(&self.to_bytes()))
+    }
+}
+
+impl FromStr for MessageSignature {
+    type Err = Error;
+
+    fn from_str(hex_str: &str) -> Result<Self, Self::Err> {
+        if hex_str.len() != Self::SIZE * 2 {
+            return Err(Error::InvalidEncoding(format!("expected {} hex characters, got {}", Self::SIZE * 2, hex_str.len())));
+        }
+        let mut bytes = [0u8; Self::SIZE];
+        faster_hex::hex_decode(hex_str.as_bytes(), &mut bytes).map_err(|err| Error::InvalidEncoding(err.to_string()))?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+/// The digest signed for `message`
+pub fn calc_signed_message_hash(message: &[u8]) -> Hash {
+    let mut hasher = SignedMessageHash::new();
+    hasher.write_var_bytes(message);
+    hasher.finalize()
+}
+
+fn signed_message_digest(message: &[u8]) -> secp256k1::Message {
+    secp256k1::Message::from_digest(calc_signed_message_hash(message).as_bytes())
+}
+
+/// Signs `message` with `private_key`, using the signature scheme of the addresses of `version`
+pub fn sign_message(message: &[u8], private_key: &[u8; 32], version: Version) -> Result<MessageSignature, Error> {
+    let digest = signed_message_digest(message);
+    match version {
+        Version::PubKey => {
+            let keypair = Keypair::from_seckey_slice(SECP256K1, private_key)?;
+            Ok(MessageSignature::Schnorr(*SECP256K1.sign_schnorr_no_aux_rand(&digest, &keypair).as_ref()))
+        }
+        Version::PubKeyECDSA => {
+            let secret_key = SecretKey::from_slice(private_key)?;
+            let (recovery_id, signature) = SECP256K1.sign_ecdsa_recoverable(&digest, &secret_key).serialize_compact();
+            Ok(MessageSignature::Ecdsa { recovery_id: recovery_id.to_i32() as u8, signature })
+        }
+        Version::ScriptHash => Err(Error::UnsupportedAddress(version)),
+    }
+}
+
+/// Verifies that `signature` was produced over `message` by the key of `address`. Requires neither a wallet nor a node.
+pub fn verify_message(address: &Address, message: &[u8], signature: &MessageSignature) -> Result<(), Error> {
+    let digest = signed_message_digest(message);
+    match (address.version, signature) {
+        (Version::PubKey, MessageSignature::Schnorr(signature)) => {
+            let public_key = XOnlyPublicKey::from_slice(&address.payload)?;
+            let signature = secp256k1::schnorr::Signature::from_slice(signature)?;
+            SECP256K1.verify_schnorr(&signature, &digest, &public_key).map_err(|_| Error::InvalidSignature)
+        }
+        (Version::PubKeyECDSA, MessageSignature::Ecdsa { signature, .. }) => {
+            let public_key = PublicKey::from_slice(&address.payload)?;
+            let signature = secp256k1::ecdsa::Signature::from_compact(signature)?;
+            SECP256K1.verify_ecdsa(&digest, &signature, &public_key).map_err(|_| Error::InvalidSignature)
+        }
+        (Version::ScriptHash, _) => Err(Error::UnsupportedAddress(address.version)),
+        (version, signature) => Err(Error::SchemeMismatch { signature: signature.scheme(), address: version }),
+    }
+}
+
+/// Recovers the address of `prefix` whose key produced `signature` over `message`.
+///
+/// Only ECDSA signatures allow recovering the signing key, schnorr signatures must instead be verified against
+/// a known address with [`verify_message`].
+pub fn recover_address(prefix: Prefix, message: &[u8], signature: &MessageSignature) -> Result<Address, Error> {
+    match signature {
+        MessageSignature::Schnorr(_) => Err(Error::NotRecoverable),
+        MessageSignature::Ecdsa { recovery_id, signature } => {
+            let recovery_id = RecoveryId::from_i32(*recovery_id as i32)?;
+            let signature = RecoverableSignature::from_compact(signature, recovery_id)?;
+            let public_key = SECP256K1.recover_ecdsa(&signed_message_digest(message), &signature)?;
+            Ok(Address::new(prefix, Version::PubKeyECDSA, &public_key.serialize()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_3: [u8; 32] = [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03,
+    ];
+    const KEY_B7: [u8; 32] = [
+        0xB7, 0xE1, 0x51, 0x62, 0x8A, 0xED, 0x2A, 0x6A, 0xBF, 0x71, 0x58, 0x80, 0x9C, 0xF4, 0xF3, 0xC7, 0x62, 0xE7, 0x16, 0x0F, 0x38,
+        0xB4, 0xDA, 0x56, 0xA7, 0x84, 0xD9, 0x04, 0x51, 0x90, 0xCF, 0xEF,
+    ];
+
+    struct Vector {
+        private_key: [u8; 32],
+        message: &'static str,
+        address: &'static str,
+        signature: &'static str,
+    }
+
+    /// Vectors produced by an independent implementation of BIP340, RFC6979 and of the address encoding
+    fn vectors() -> Vec<Vector> {
+        // cspell:disable
+        vec![
+            Vector {
+                private_key: KEY_3,
+                message: "Hello Kaspa!",
+                address: "kaspa:qrunpzspjfvvxyzfx38ct7ya2g5m2vwggkpklxdsscqlzyauuqm0ju6q2fjpa",
+                signature: "00500ecd94588d16fafc621d5897ef4e1eb96f548cd8238b287d2f1e50aba0a4be8c429ed58edbc91a66c06b55487f661164a0d3036b2a9cb511a36bdcb73b9526",
+            },
+            Vector {
+                private_key: KEY_3,
+                message: "Hello Kaspa!",
+                address: "kaspa:qyp0jvy2qxf93scsfy6ylp0cn4fzndf3epzcxmuekzrqrugnhnsrd7gz2htx7e5",
+                signature: "02674d1d3570438a2945b00bee92c6db25bed0a6cd3e903b482665abafa760b86a4b063d0bf29fffe0109df9877ac240956e021198e32ebd2fafebcaaa932c6c3e",
+            },
+            Vector {
+                private_key: KEY_3,
+                message: "こんにちは世界",
+                address: "kaspa:qrunpzspjfvvxyzfx38ct7ya2g5m2vwggkpklxdsscqlzyauuqm0ju6q2fjpa",
+                signature: "009848ecaa754177d535b97b0e35d004c8b949066336c54764bc2d414e4f2cc99b96d9545872d129b58d178ae164261c44c7f1c826320b4c3ca7ed839eb3552b6c",
+            },
+            Vector {
+                private_key: KEY_3,
+                message: "こんにちは世界",
+                address: "kaspa:qyp0jvy2qxf93scsfy6ylp0cn4fzndf3epzcxmuekzrqrugnhnsrd7gz2htx7e5",
+                signature: "01a70cd05a7cacf5d0d7cdc5f028ea5749141f9a7a13ec7d6a90061140372461df06b2db4d0582f26446a411bc3fc3a187e389cf428301f65f926ff7720cdee276",
+            },
+            Vector {
+                private_key: KEY_B7,
+                message: "Hello Kaspa!",
+                address: "kaspa:qr0lr4ml9fn3chekrqmjdkergxl93l4wrk3dankcgvjq776s9wn9jkdskewva",
+                signature: "00b59d4a49e8ced23ad30860c9292c304b61516b9e9ec0e46cce843c7fa570955529adaa235e3966315880d1b5690478ffd9363f38d91296d33a90e31abbb1edd2",
+            },
+            Vector {
+                private_key: KEY_B7,
+                message: "Hello Kaspa!",
+                address: "kaspa:qypdluwh0u4xw8zlxcvrwfkmydqmuk874cw69hkwmppjgrmm2q46vkgl9zsrch2",
+                signature: "01310596314c76b2f4e22f8dc5a3b7762f6910282c917dc563b548209edabda24866be931f8b60b5f9d119b875836068f40f669b011d467f24922dca971cea8c74",
+            },
+            Vector {
+                private_key: KEY_B7,
+                message: "こんにちは世界",
+                address: "kaspa:qr0lr4ml9fn3chekrqmjdkergxl93l4wrk3dankcgvjq776s9wn9jkdskewva",
+                signature: "00500553a7431bf0daf184a8b9a9bb257b7bbaa64dfdd703b4fe0af0268c7ba946bd1d2eaf15026fdd4e22c7c5dd6dbf277a2501be268780256cf981dbbbf0b848",
+            },
+            Vector {
+                private_key: KEY_B7,
+                message: "こんにちは世界",
+                address: "kaspa:qypdluwh0u4xw8zlxcvrwfkmydqmuk874cw69hkwmppjgrmm2q46vkgl9zsrch2",
+                signature: "01bd7d9271cbaae65b4086fc1afcb1c867894d4bdce32dc59e1753c5f03d10990b1be3e5c30c2786e1c4b8306e7cdfea16be2225cd2afb1d65ad02f16055db38cb",
+            },
+        ]
+        // cspell:enable
+    }
+
+    #[test]
+    fn test_signed_message_hash() {
+        assert_eq!(
+            calc_signed_message_hash(b"Hello Kaspa!"),
+            Hash::from_str("e0dd2ee5eef9fcc1304b87fc0bd2e49cbbd9a9f6a11f89f5f406e07096e72f2d").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_message_signing_vectors() {
+        for vector in vectors() {
+            let address = Address::try_from(vector.address).unwrap();
+            let signature = sign_message(vector.message.as_bytes(), &vector.private_key, address.version).unwrap();
+            assert_eq!(signature.to_string(), vector.signature, "signature of {:?} by {}", vector.message, vector.address);
+            assert_eq!(MessageSignature::from_str(vector.signature).unwrap(), signature);
+            verify_message(&address, vector.message.as_bytes(), &signature).unwrap();
+            match signature.scheme() {
+                SignatureScheme::Schnorr => {
+                    assert_eq!(recover_address(Prefix::Mainnet, vector.message.as_bytes(), &signature), Err(Error::NotRecoverable))
+                }
+                SignatureScheme::Ecdsa => {
+                    assert_eq!(recover_address(Prefix::Mainnet, vector.message.as_bytes(), &signature).unwrap(), address)
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_message_verification_failures() {
+        let schnorr_address = Address::try_from("kaspa:qrunpzspjfvvxyzfx38ct7ya2g5m2vwggkpklxdsscqlzyauuqm0ju6q2fjpa").unwrap();
+        let ecdsa_address = Address::try_from("kaspa:qyp0jvy2qxf93scsfy6ylp0cn4fzndf3epzcxmuekzrqrugnhnsrd7gz2htx7e5").unwrap();
+        let other_address = Address::try_from("kaspa:qr0lr4ml9fn3chekrqmjdkergxl93l4wrk3dankcgvjq776s9wn9jkdskewva").unwrap();
+        let message = b"Hello Kaspa!";
+        let schnorr = sign_message(message, &KEY_3, Version::PubKey).unwrap();
+        let ecdsa = sign_message(message, &KEY_3, Version::PubKeyECDSA).unwrap();
+
+        // Wrong address
+        assert_eq!(verify_message(&other_address, message, &schnorr), Err(Error::InvalidSignature));
+
+        // Tampered message
+        assert_eq!(verify_message(&schnorr_address, b"Hello Kaspa?", &schnorr), Err(Error::InvalidSignature));
+        assert_eq!(verify_message(&ecdsa_address, b"Hello Kaspa?", &ecdsa), Err(Error::InvalidSignature));
+        assert_ne!(recover_address(Prefix::Mainnet, b"Hello Kaspa?", &ecdsa).ok(), Some(ecdsa_address.clone()));
+
+        // Signature of a scheme not matching the address, even though the key is the same
+        assert_eq!(
+            verify_message(&ecdsa_address, message, &schnorr),
+            Err(Error::SchemeMismatch { signature: SignatureScheme::Schnorr, address: Version::PubKeyECDSA })
+        );
+        assert_eq!(
+            verify_message(&schnorr_address, message, &ecdsa),
+            Err(Error::SchemeMismatch { signature: SignatureScheme::Ecdsa, address: Version::PubKey })
+        );
+
+        // Script addresses have no key to sign with
+        let script_address = Address::new(Prefix::Mainnet, Version::ScriptHash, &[0u8; 32]);
+        assert_eq!(sign_message(message, &KEY_3, Version::ScriptHash), Err(Error::UnsupportedAddress(Version::ScriptHash)));
+        assert_eq!(verify_message(&script_address, message, &schnorr), Err(Error::UnsupportedAddress(Version::ScriptHash)));
+
+        // Malformed encodings
+        let mut bytes = schnorr.to_bytes();
+        bytes[0] = 0x05;
+        assert!(matches!(MessageSignature::from_bytes(&bytes), Err(Error::InvalidEncoding(_))));
+        assert!(matches!(MessageSignature::from_bytes(&bytes[1..]), Err(Error::InvalidEncoding(_))));
+        assert!(matches!(MessageSignature::from_str("zz"), Err(Error::InvalidEncoding(_))));
+    }
+}
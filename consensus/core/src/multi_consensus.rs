@@ -0,0 +1,72 @@
+//!
+//! Status of the consensus entries of the node, each entry owning a consensus database directory.
+//!
+//! A staging consensus, used for syncing from a pruning point, is promoted to active in three phases:
+//! it is first sealed and recorded as prepared, the active consensus pointer is then swapped, and the
+//! previously active entry is finally retired and its directory deleted once a grace period elapsed.
+//!
+
+use serde::{Deserialize, Serialize};
+
+/// Why a consensus entry was created
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConsensusCreationReason {
+    /// A brand new consensus processing the network from genesis
+    Genesis,
+    /// A staging consensus syncing from the pruning point of a peer
+    PruningPointSync,
+    /// The entry was created by a node version not recording the reason
+    Unknown,
+}
+
+impl ConsensusCreationReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConsensusCreationReason::Genesis => "genesis",
+            ConsensusCreationReason::PruningPointSync => "pruning-point-sync",
+            ConsensusCreationReason::Unknown => "unknown",
+        }
+    }
+}
+
+impl std::fmt::Display for ConsensusCreationReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsensusEntryInfo {
+    pub key: u64,
+    /// Name of the database directory of the entry, relative to the consensus databases directory
+    pub directory_name: String,
+    /// Unix time (in milliseconds) at which the entry was created
+    pub creation_timestamp: u64,
+    pub creation_reason: ConsensusCreationReason,
+}
+
+/// A consensus entry which is no longer active and has a database directory awaiting deletion
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetiredConsensusEntryInfo {
+    pub entry: ConsensusEntryInfo,
+    /// Unix time (in milliseconds) at which the entry was replaced as active, `None` if retired by a node
+    /// version not recording it
+    pub retirement_timestamp: Option<u64>,
+    /// Size in bytes of the database directory of the entry
+    pub disk_usage: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MultiConsensusStatus {
+    pub current: Option<ConsensusEntryInfo>,
+    pub staging: Option<ConsensusEntryInfo>,
+    /// Whether the staging consensus is sealed and its promotion to active is decided
+    pub is_staging_prepared: bool,
+    pub retired: Vec<RetiredConsensusEntryInfo>,
+}
+
+impl MultiConsensusStatus {
+    pub fn retired_disk_usage(&self) -> u64 {
+        self.retired.iter().map(|entry| entry.disk_usage).sum()
+    }
+}
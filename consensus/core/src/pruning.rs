@@ -19,3 +19,16 @@ pub struct PruningPointTrustedData {
     /// Union of GHOSTDAG data required to verify blocks in the future of the pruning point
     pub ghostdag_blocks: Vec<TrustedGhostdagData>,
 }
+
+/// Progress of an ongoing pruning point proof validation
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PruningProofValidationProgress {
+    /// The number of proof headers processed so far over all levels
+    pub processed_headers: u64,
+    /// The total number of proof headers over all levels. Headers appearing in several levels are counted once per level
+    pub total_headers: u64,
+    /// The number of fully processed proof levels. Levels are processed concurrently, so they do not complete in order
+    pub processed_levels: usize,
+    /// The total number of proof levels
+    pub total_levels: usize,
+}
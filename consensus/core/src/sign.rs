@@ -1,9 +1,9 @@
 use crate::{
     hashing::{
         sighash::{calc_schnorr_signature_hash, SigHashReusedValues},
-        sighash_type::SIG_HASH_ALL,
+        sighash_type::{SigHashType, SIG_HASH_ALL},
     },
-    tx::SignableTransaction,
+    tx::{SignableTransaction, VerifiableTransaction},
 };
 use itertools::Itertools;
 use std::collections::BTreeMap;
@@ -153,6 +153,39 @@ pub fn sign_with_multiple_v2(mut mutable_tx: SignableTransaction, privkeys: &[[u
     }
 }
 
+/// Sign a single input of a transaction using schnorr and the provided sighash type.
+/// Returns the signature script push `OP_DATA_65 <SIGNATURE+SIGHASH_TYPE>` for the input, leaving
+/// the assembly of the final signature script (e.g. for P2SH or multisig inputs) to the caller.
+pub fn sign_input(
+    tx: &impl VerifiableTransaction,
+    input_index: usize,
+    privkey: &[u8; 32],
+    hash_type: SigHashType,
+) -> Result<Vec<u8>, Error> {
+    let mut reused_values = SigHashReusedValues::new();
+    sign_input_with_reused_values(tx, input_index, privkey, hash_type, &mut reused_values)
+}
+
+/// Same as [`sign_input`] but allows sharing the sighash reused values when signing several
+/// inputs of the same transaction separately
+pub fn sign_input_with_reused_values(
+    tx: &impl VerifiableTransaction,
+    input_index: usize,
+    privkey: &[u8; 32],
+    hash_type: SigHashType,
+    reused_values: &mut SigHashReusedValues,
+) -> Result<Vec<u8>, Error> {
+    if input_index >= tx.tx().inputs.len() {
+        return Err(Error::Message(format!("Input index {input_index} is out of range")));
+    }
+    let schnorr_key = secp256k1::Keypair::from_seckey_slice(secp256k1::SECP256K1, privkey)?;
+    let sig_hash = calc_schnorr_signature_hash(tx, input_index, hash_type, reused_values);
+    let msg = secp256k1::Message::from_digest_slice(sig_hash.as_bytes().as_slice())?;
+    let sig: [u8; 64] = *schnorr_key.sign_schnorr(msg).as_ref();
+    // This represents OP_DATA_65 <SIGNATURE+SIGHASH_TYPE> (since signature length is 64 bytes and SIGHASH_TYPE is one byte)
+    Ok(std::iter::once(65u8).chain(sig).chain([hash_type.to_u8()]).collect())
+}
+
 pub fn verify(tx: &impl crate::tx::VerifiableTransaction) -> Result<(), Error> {
     let mut reused_values = SigHashReusedValues::new();
     for (i, (input, entry)) in tx.populated_inputs().enumerate() {
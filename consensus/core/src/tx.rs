@@ -33,6 +33,7 @@ pub type TransactionId = kaspa_hashes::Hash;
 #[serde(rename_all = "camelCase")]
 #[wasm_bindgen(inspectable, js_name = TransactionUtxoEntry)]
 pub struct UtxoEntry {
+    #[serde(with = "kaspa_utils::serde_u64_string")]
     pub amount: u64,
     #[wasm_bindgen(js_name = scriptPublicKey, getter_with_clone)]
     pub script_public_key: ScriptPublicKey,
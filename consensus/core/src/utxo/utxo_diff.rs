@@ -12,6 +12,20 @@ pub trait ImmutableUtxoDiff {
     fn removed(&self) -> &UtxoCollection;
 }
 
+/// The difference between two UTXO sets: the entries added to and the entries removed from the first set
+/// in order to get the second. An outpoint both added and removed had its entry replaced. Entries of the
+/// same outpoint are told apart by their DAA score.
+///
+/// Denoting [`UtxoDiff::apply_to`] over a UTXO collection `u` by `u + d` and [`UtxoDiff::compose`] by `a ∘ b`,
+/// the diff algebra satisfies the following laws:
+/// - identity: `d ∘ ∅ = ∅ ∘ d = d`
+/// - composition: if `(u + a) + b` is defined, then so is `a ∘ b`, and `u + (a ∘ b) = (u + a) + b`
+/// - associativity: if `((u + a) + b) + c` is defined, then `(a ∘ b) ∘ c = a ∘ (b ∘ c)`
+/// - reversal: `d.reverse().reverse() = d`, and if `u + d` is defined, then `(u + d) + d.reverse() = u`
+/// - reversal of a composition: if `(u + a) + b` is defined, then `(a ∘ b).reverse() = b.reverse() ∘ a.reverse()`
+/// - difference: if `a` and `b` are diffs from the same UTXO collection, then `a ∘ a.diff_from(b) = b`
+///
+/// Composition does not depend on any UTXO collection, so `u + (a ∘ b)` might be defined while `(u + a) + b` is not.
 #[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct UtxoDiff {
     pub add: UtxoCollection,
@@ -76,6 +90,35 @@ impl UtxoDiff {
         Self::new(self.remove, self.add)
     }
 
+    /// Returns the diff undoing this diff
+    pub fn reverse(&self) -> UtxoDiff {
+        Self::new(self.remove.clone(), self.add.clone())
+    }
+
+    /// Returns the diff equal to applying this diff and then `other` to the same UTXO collection,
+    /// failing if both diffs add, or both remove, the same entry. See the laws documented on [`UtxoDiff`]
+    pub fn compose(&self, other: &impl ImmutableUtxoDiff) -> UtxoResult<UtxoDiff> {
+        self.with_diff(other)
+    }
+
+    /// Applies this diff to `collection`. Fails, leaving `collection` untouched, if a removed entry is missing
+    /// from `collection` or if an added outpoint is already held by `collection` and not removed by this diff
+    pub fn apply_to(&self, collection: &mut UtxoCollection) -> UtxoResult<()> {
+        for (outpoint, entry) in self.remove.iter() {
+            if !collection.contains_with_daa_score(outpoint, entry.block_daa_score) {
+                return Err(UtxoAlgebraError::MissingRemovePoint(*outpoint));
+            }
+        }
+        for outpoint in self.add.keys() {
+            if collection.contains_key(outpoint) && !self.remove.contains_key(outpoint) {
+                return Err(UtxoAlgebraError::ExistingAddPoint(*outpoint));
+            }
+        }
+        collection.remove_collection(&self.remove);
+        collection.add_collection(&self.add);
+        Ok(())
+    }
+
     pub fn with_diff(&self, other: &impl ImmutableUtxoDiff) -> UtxoResult<UtxoDiff> {
         let mut clone = self.clone();
         clone.with_diff_in_place(other)?;
@@ -264,6 +307,7 @@ impl UtxoDiff {
 mod tests {
     use super::*;
     use crate::tx::{ScriptPublicKey, TransactionId};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
     use std::str::FromStr;
 
     #[test]
@@ -567,4 +611,154 @@ mod tests {
         drop(utxo_entry1);
         drop(utxo_entry2);
     }
+
+    /// Number of distinct outpoints the random collections and diffs are drawn from, kept small so that
+    /// the diffs frequently touch the same outpoints
+    const OUTPOINTS: u64 = 12;
+
+    fn outpoint(index: u64) -> TransactionOutpoint {
+        TransactionOutpoint::new(TransactionId::from_u64_word(index + 1), 0)
+    }
+
+    /// The entry of an outpoint is fully determined by its DAA score, as assumed by the diff algebra
+    fn entry(daa_score: u64) -> UtxoEntry {
+        UtxoEntry::new(daa_score * 1000, ScriptPublicKey::default(), daa_score, false)
+    }
+
+    fn random_collection(rng: &mut StdRng) -> UtxoCollection {
+        (0..OUTPOINTS).filter(|_| rng.gen_bool(0.5)).map(|i| (outpoint(i), entry(rng.gen_range(0..4)))).collect()
+    }
+
+    /// Returns a random diff applicable to `collection`, removing, replacing and adding entries
+    fn random_diff(rng: &mut StdRng, collection: &UtxoCollection) -> UtxoDiff {
+        let mut diff = UtxoDiff::default();
+        for outpoint in (0..OUTPOINTS).map(outpoint) {
+            match collection.get(&outpoint) {
+                Some(existing) => match rng.gen_range(0..10) {
+                    0..=2 => {
+                        diff.remove.insert(outpoint, existing.clone());
+                    }
+                    3..=4 => {
+                        diff.remove.insert(outpoint, existing.clone());
+                        diff.add.insert(outpoint, entry(existing.block_daa_score + rng.gen_range(1..3)));
+                    }
+                    _ => {}
+                },
+                None if rng.gen_bool(0.3) => {
+                    diff.add.insert(outpoint, entry(rng.gen_range(0..6)));
+                }
+                None => {}
+            }
+        }
+        diff
+    }
+
+    fn applied(collection: &UtxoCollection, diff: &UtxoDiff) -> UtxoResult<UtxoCollection> {
+        let mut collection = collection.clone();
+        diff.apply_to(&mut collection)?;
+        Ok(collection)
+    }
+
+    #[test]
+    fn test_utxo_diff_algebra_laws() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..1000 {
+            let u0 = random_collection(&mut rng);
+            let a = random_diff(&mut rng, &u0);
+            let u1 = applied(&u0, &a).unwrap();
+            let b = random_diff(&mut rng, &u1);
+            let u2 = applied(&u1, &b).unwrap();
+            let c = random_diff(&mut rng, &u2);
+            let u3 = applied(&u2, &c).unwrap();
+
+            // Identity
+            assert_eq!(a.compose(&UtxoDiff::default()).unwrap(), a);
+            assert_eq!(UtxoDiff::default().compose(&a).unwrap(), a);
+
+            // Composition
+            let ab = a.compose(&b).unwrap();
+            assert_eq!(applied(&u0, &ab).unwrap(), u2);
+            let bc = b.compose(&c).unwrap();
+            assert_eq!(applied(&u1, &bc).unwrap(), u3);
+
+            // Associativity
+            let ab_c = ab.compose(&c).unwrap();
+            assert_eq!(ab_c, a.compose(&bc).unwrap());
+            assert_eq!(applied(&u0, &ab_c).unwrap(), u3);
+
+            // Reversal
+            assert_eq!(a.reverse().reverse(), a);
+            assert_eq!(applied(&u1, &a.reverse()).unwrap(), u0);
+            assert_eq!(applied(&u3, &ab_c.reverse()).unwrap(), u0);
+            assert_eq!(ab.reverse(), b.reverse().compose(&a.reverse()).unwrap());
+            assert_eq!(a.compose(&a.as_reversed()).unwrap(), UtxoDiff::default());
+
+            // Difference between the diffs leading from the same collection to two collections
+            let other = random_diff(&mut rng, &u0);
+            let diff = ab.diff_from(&other).unwrap();
+            assert_eq!(ab.compose(&diff).unwrap(), other);
+            assert_eq!(applied(&u2, &diff).unwrap(), applied(&u0, &other).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_utxo_diff_conflict_detection() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..1000 {
+            let u0 = random_collection(&mut rng);
+            let a = random_diff(&mut rng, &u0);
+            let u1 = applied(&u0, &a).unwrap();
+
+            // A diff applied twice either conflicts or changes nothing
+            match a.apply_to(&mut u1.clone()) {
+                Ok(()) => assert!(a.add.is_empty() && a.remove.is_empty()),
+                Err(err) => {
+                    assert!(matches!(err, UtxoAlgebraError::MissingRemovePoint(_) | UtxoAlgebraError::ExistingAddPoint(_)))
+                }
+            }
+
+            // A failing application leaves the collection untouched
+            let mut collection = u1.clone();
+            if a.apply_to(&mut collection).is_err() {
+                assert_eq!(collection, u1);
+            }
+
+            // Adding an entry twice, or removing it twice, cannot be composed
+            if let Some((&outpoint, added)) = a.add.iter().find(|(outpoint, _)| !a.remove.contains_key(outpoint)) {
+                let double_add = UtxoDiff::new([(outpoint, added.clone())].into(), Default::default());
+                assert_eq!(a.compose(&double_add), Err(UtxoAlgebraError::DuplicateAddPoint(outpoint)));
+                assert_eq!(applied(&u1, &double_add), Err(UtxoAlgebraError::ExistingAddPoint(outpoint)));
+            }
+            if let Some((&outpoint, removed)) = a.remove.iter().find(|(outpoint, _)| !a.add.contains_key(outpoint)) {
+                let double_remove = UtxoDiff::new(Default::default(), [(outpoint, removed.clone())].into());
+                assert_eq!(a.compose(&double_remove), Err(UtxoAlgebraError::DuplicateRemovePoint(outpoint)));
+                assert_eq!(applied(&u1, &double_remove), Err(UtxoAlgebraError::MissingRemovePoint(outpoint)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_utxo_diff_add_then_remove_across_compositions() {
+        let x = outpoint(0);
+        let added = UtxoDiff::new([(x, entry(5))].into(), Default::default());
+        let removed = UtxoDiff::new(Default::default(), [(x, entry(5))].into());
+        let replaced = UtxoDiff::new([(x, entry(5))].into(), [(x, entry(1))].into());
+
+        // Adding and then removing the same entry cancels out, also when the entry replaced a previous one
+        assert_eq!(added.compose(&removed).unwrap(), UtxoDiff::default());
+        assert_eq!(replaced.compose(&removed).unwrap(), UtxoDiff::new(Default::default(), [(x, entry(1))].into()));
+        assert_eq!(removed.compose(&added).unwrap(), UtxoDiff::default());
+
+        // Removing an entry and adding it back with another DAA score is a replacement
+        let re_added = UtxoDiff::new([(x, entry(7))].into(), Default::default());
+        assert_eq!(removed.compose(&re_added).unwrap(), UtxoDiff::new([(x, entry(7))].into(), [(x, entry(5))].into()));
+        assert_eq!(
+            added.compose(&removed).unwrap().compose(&re_added).unwrap(),
+            added.compose(&removed.compose(&re_added).unwrap()).unwrap()
+        );
+
+        let base: UtxoCollection = [(x, entry(1))].into();
+        assert_eq!(applied(&base, &replaced.compose(&removed).unwrap()).unwrap(), UtxoCollection::new());
+        assert_eq!(applied(&base, &removed), Err(UtxoAlgebraError::MissingRemovePoint(x)));
+    }
 }
@@ -16,6 +16,12 @@ pub enum UtxoAlgebraError {
     #[error("cannot add outpoint {0} twice")]
     DoubleAddCall(TransactionOutpoint),
 
+    #[error("outpoint {0} cannot be removed since the UTXO collection misses its entry")]
+    MissingRemovePoint(TransactionOutpoint),
+
+    #[error("outpoint {0} cannot be added since the UTXO collection already holds it")]
+    ExistingAddPoint(TransactionOutpoint),
+
     #[error("outpoint {0} {1}")]
     DiffIntersectionPoint(TransactionOutpoint, &'static str),
 
@@ -31,6 +37,8 @@ impl PartialEq for UtxoAlgebraError {
             (Self::DuplicateAddPoint(l0), Self::DuplicateAddPoint(r0)) => l0 == r0,
             (Self::DoubleRemoveCall(l0), Self::DoubleRemoveCall(r0)) => l0 == r0,
             (Self::DoubleAddCall(l0), Self::DoubleAddCall(r0)) => l0 == r0,
+            (Self::MissingRemovePoint(l0), Self::MissingRemovePoint(r0)) => l0 == r0,
+            (Self::ExistingAddPoint(l0), Self::ExistingAddPoint(r0)) => l0 == r0,
             (Self::DiffIntersectionPoint(l0, _), Self::DiffIntersectionPoint(r0, _)) => l0 == r0, // Ignore the description string
             (Self::General(_), Self::General(_)) => true,
             (_, _) => false,
@@ -0,0 +1,81 @@
+//!
+//! Decision trace of the virtual parent selection, reporting why each excluded tip was not selected as a virtual parent.
+//!
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use kaspa_hashes::Hash;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, str::FromStr};
+
+/// The reason for which a virtual parent candidate was excluded from the virtual parents
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VirtualParentExclusionReason {
+    /// Merging the candidate would exceed the mergeset size limit
+    MergesetSizeLimit,
+    /// Merging the candidate would merge a red block below the merge depth root which is not kosherized by any blue.
+    /// Candidates violating finality are reported as such as well, since the merge depth is bounded by the finality depth
+    MergeDepthViolation,
+    /// The candidate, or a block on its selected chain, has an invalid UTXO state
+    Disqualified,
+    /// The maximum number of block parents was reached, or the candidate was left out of the randomly picked candidates
+    ParentLimit,
+}
+
+impl VirtualParentExclusionReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VirtualParentExclusionReason::MergesetSizeLimit => "mergeset-size-limit",
+            VirtualParentExclusionReason::MergeDepthViolation => "merge-depth-violation",
+            VirtualParentExclusionReason::Disqualified => "disqualified",
+            VirtualParentExclusionReason::ParentLimit => "parent-limit",
+        }
+    }
+}
+
+impl Display for VirtualParentExclusionReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for VirtualParentExclusionReason {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mergeset-size-limit" => Ok(VirtualParentExclusionReason::MergesetSizeLimit),
+            "merge-depth-violation" => Ok(VirtualParentExclusionReason::MergeDepthViolation),
+            "disqualified" => Ok(VirtualParentExclusionReason::Disqualified),
+            "parent-limit" => Ok(VirtualParentExclusionReason::ParentLimit),
+            _ => Err(format!("unknown virtual parent exclusion reason: {s}")),
+        }
+    }
+}
+
+/// A tip which was not selected as a virtual parent
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExcludedVirtualParent {
+    pub hash: Hash,
+    pub reason: VirtualParentExclusionReason,
+}
+
+impl ExcludedVirtualParent {
+    pub fn new(hash: Hash, reason: VirtualParentExclusionReason) -> Self {
+        Self { hash, reason }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualParentSelectionTrace {
+    /// The current DAG tips
+    pub tips: Vec<Hash>,
+    /// The selected parent of virtual, i.e. the sink
+    pub selected_parent: Hash,
+    /// The selected virtual parents, which might include blocks in the past of tips excluded due to the mergeset size limit
+    pub virtual_parents: Vec<Hash>,
+    /// The tips which are neither the selected parent nor a virtual parent, along with the reason of their exclusion
+    pub excluded_tips: Vec<ExcludedVirtualParent>,
+}
@@ -1,5 +1,5 @@
 use derive_more::Display;
-use kaspa_consensus_core::{acceptance_data::AcceptanceData, block::Block, utxo::utxo_diff::UtxoDiff};
+use kaspa_consensus_core::{acceptance_data::AcceptanceData, api::VirtualInfo, block::Block, utxo::utxo_diff::UtxoDiff};
 use kaspa_hashes::Hash;
 use kaspa_notify::{
     events::EventType,
@@ -7,7 +7,7 @@ use kaspa_notify::{
     notification::Notification as NotificationTrait,
     subscription::{
         context::SubscriptionContext,
-        single::{OverallSubscription, UtxosChangedSubscription, VirtualChainChangedSubscription},
+        single::{OverallSubscription, TransactionConflictSubscription, UtxosChangedSubscription, VirtualChainChangedSubscription},
         Subscription,
     },
 };
@@ -87,6 +87,14 @@ impl NotificationTrait for Notification {
         Some(self.clone())
     }
 
+    fn apply_transaction_conflict_subscription(
+        &self,
+        _subscription: &TransactionConflictSubscription,
+        _context: &SubscriptionContext,
+    ) -> Option<Self> {
+        Some(self.clone())
+    }
+
     fn event_type(&self) -> EventType {
         self.into()
     }
@@ -168,11 +176,12 @@ impl SinkBlueScoreChangedNotification {
 #[derive(Debug, Clone)]
 pub struct VirtualDaaScoreChangedNotification {
     pub virtual_daa_score: u64,
+    pub virtual_info: VirtualInfo,
 }
 
 impl VirtualDaaScoreChangedNotification {
-    pub fn new(virtual_daa_score: u64) -> Self {
-        Self { virtual_daa_score }
+    pub fn new(virtual_info: VirtualInfo) -> Self {
+        Self { virtual_daa_score: virtual_info.daa_score, virtual_info }
     }
 }
 
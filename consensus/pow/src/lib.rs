@@ -9,7 +9,7 @@ pub mod xoshiro;
 use std::cmp::max;
 
 use crate::matrix::Matrix;
-use kaspa_consensus_core::{hashing, header::Header, BlockLevel};
+use kaspa_consensus_core::{header::Header, BlockLevel};
 use kaspa_hashes::PowHash;
 use kaspa_math::Uint256;
 
@@ -26,7 +26,7 @@ impl State {
     pub fn new(header: &Header) -> Self {
         let target = Uint256::from_compact_target_bits(header.bits);
         // Zero out the time and nonce.
-        let pre_pow_hash = hashing::header::hash_override_nonce_time(header, 0, 0);
+        let pre_pow_hash = header.pre_pow_hash(true);
         // PRE_POW_HASH || TIME || 32 zero byte padding || NONCE
         let hasher = PowHash::new(pre_pow_hash, header.timestamp);
         let matrix = Matrix::generate(pre_pow_hash);
@@ -1,7 +1,6 @@
 use crate::matrix::Matrix;
 use js_sys::BigInt;
 use kaspa_consensus_client::Header;
-use kaspa_consensus_core::hashing;
 use kaspa_hashes::Hash;
 use kaspa_hashes::PowHash;
 use kaspa_math::Uint256;
@@ -32,7 +31,7 @@ impl State {
 
         let target = Uint256::from_compact_target_bits(header.bits);
         // Zero out the time and nonce.
-        let pre_pow_hash = hashing::header::hash_override_nonce_time(header, 0, 0);
+        let pre_pow_hash = header.pre_pow_hash(true);
         // PRE_POW_HASH || TIME || 32 zero byte padding || NONCE
         let hasher = PowHash::new(pre_pow_hash, header.timestamp);
         let matrix = Matrix::generate(pre_pow_hash);
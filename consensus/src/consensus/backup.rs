@@ -0,0 +1,208 @@
+//!
+//! Consistent backups of the consensus databases of a running node, relying on RocksDB checkpoints,
+//! and verification of such backups.
+//!
+
+use super::{
+    factory::MultiConsensusManagementStore,
+    recovery::{check_stores, CheckDepth},
+    services::ConsensusServices,
+    storage::ConsensusStorage,
+};
+use crate::model::{
+    services::reachability::ReachabilityService,
+    stores::{
+        ghostdag::GhostdagStoreReader, headers_selected_tip::HeadersSelectedTipStoreReader, pruning::PruningStoreReader,
+        quarantine::StoreClass, statuses::StatusesStoreReader, virtual_state::VirtualStateStoreReader,
+    },
+};
+use kaspa_consensus_core::{
+    backup::BackupManifest,
+    blockstatus::BlockStatus,
+    config::Config,
+    errors::backup::{BackupError, BackupResult},
+    muhash::MuHashExtensions,
+};
+use kaspa_core::{info, kaspad_env::version, time::unix_now, warn};
+use kaspa_database::prelude::{ConnBuilder, StoreError, DB};
+use kaspa_hashes::Hash;
+use kaspa_muhash::MuHash;
+use parking_lot::RwLock;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Files limit of the databases opened for reading a backup
+const BACKUP_DB_FILES_LIMIT: i32 = 64;
+
+fn store_error(err: StoreError) -> BackupError {
+    BackupError::Store(err.to_string())
+}
+
+fn inconsistent(message: String) -> BackupError {
+    BackupError::Inconsistent(message)
+}
+
+/// Returns the last component of `path`
+fn dir_name(path: &Path) -> PathBuf {
+    path.file_name().map(PathBuf::from).unwrap_or_default()
+}
+
+fn open_read_only(path: &Path) -> BackupResult<Arc<DB>> {
+    if !path.is_dir() {
+        return Err(inconsistent(format!("the database {} is missing", path.display())));
+    }
+    ConnBuilder::default()
+        .with_db_path(path.to_owned())
+        .with_files_limit(BACKUP_DB_FILES_LIMIT)
+        .build_read_only()
+        .map_err(|err| BackupError::Store(err.to_string()))
+}
+
+/// Backs up the consensus database `consensus_db` along with the management (meta) database to `output_dir`,
+/// mirroring the layout of the data directory.
+///
+/// Each database is copied by a RocksDB checkpoint, which is consistent on its own. The management store lock
+/// is held during both checkpoints so that the meta copy designates the backed up consensus as the active one.
+/// The pruning point and sink of the manifest are read from the copy itself, hence match it exactly.
+pub(super) fn create_backup(
+    management_store: &RwLock<MultiConsensusManagementStore>,
+    consensus_db: &DB,
+    config: Arc<Config>,
+    output_dir: &Path,
+) -> BackupResult<BackupManifest> {
+    if output_dir.exists() {
+        return Err(BackupError::OutputExists(output_dir.display().to_string()));
+    }
+
+    let consensus_dir = consensus_db.path().parent().map(dir_name).unwrap_or_default().join(dir_name(consensus_db.path()));
+    let management_store = management_store.read();
+    let meta_dir = dir_name(management_store.db().path());
+    let active_consensus_dir_name = management_store.active_consensus_dir_name().map_err(store_error)?;
+    if active_consensus_dir_name.as_deref() != dir_name(consensus_db.path()).to_str() {
+        return Err(BackupError::NoActiveConsensus);
+    }
+
+    info!("Creating a backup of the consensus databases at {}", output_dir.display());
+    fs::create_dir_all(output_dir.join(&consensus_dir).parent().unwrap())?;
+    management_store.db().create_checkpoint(&output_dir.join(&meta_dir)).map_err(store_error)?;
+    consensus_db.create_checkpoint(&output_dir.join(&consensus_dir)).map_err(store_error)?;
+    drop(management_store);
+
+    let storage = ConsensusStorage::new(open_read_only(&output_dir.join(&consensus_dir))?, config.clone());
+    let pruning_point = storage.pruning_point_store.read().pruning_point().map_err(store_error)?;
+    let sink = storage.virtual_stores.read().state.get().map_err(store_error)?.ghostdag_data.selected_parent;
+
+    let manifest = BackupManifest {
+        node_version: version().to_string(),
+        network_id: config.net,
+        params_digest: config.params.digest(),
+        pruning_point,
+        sink,
+        timestamp: unix_now(),
+        consensus_dir: consensus_dir.to_string_lossy().into_owned(),
+        meta_dir: meta_dir.to_string_lossy().into_owned(),
+    };
+    manifest.store(output_dir)?;
+    info!("Backup created at {} (pruning point {}, sink {})", output_dir.display(), pruning_point, sink);
+    Ok(manifest)
+}
+
+/// Opens the backup at `backup_dir` read-only and verifies it matches both its manifest and the network
+/// of `config`, then runs the consistency checker over its consensus database.
+pub fn verify_backup(backup_dir: &Path, config: Arc<Config>) -> BackupResult<BackupManifest> {
+    let manifest = BackupManifest::load(backup_dir)?;
+    if manifest.network_id != config.net {
+        return Err(BackupError::NetworkMismatch(manifest.network_id, config.net));
+    }
+    let params_digest = config.params.digest();
+    if manifest.params_digest != params_digest {
+        return Err(BackupError::ParamsDigestMismatch(manifest.params_digest, params_digest));
+    }
+
+    let management_store = MultiConsensusManagementStore::new(open_read_only(&backup_dir.join(&manifest.meta_dir))?);
+    let active_consensus_dir_name = management_store.active_consensus_dir_name().map_err(store_error)?;
+    if active_consensus_dir_name.as_deref() != dir_name(Path::new(&manifest.consensus_dir)).to_str() {
+        return Err(inconsistent(format!("the meta database does not designate {} as the active consensus", manifest.consensus_dir)));
+    }
+
+    let (pruning_point, sink) = check_consistency(open_read_only(&backup_dir.join(&manifest.consensus_dir))?, config)?;
+    if pruning_point != manifest.pruning_point || sink != manifest.sink {
+        return Err(inconsistent(format!(
+            "the database holds pruning point {pruning_point} and sink {sink} while the manifest records {} and {}",
+            manifest.pruning_point, manifest.sink
+        )));
+    }
+    Ok(manifest)
+}
+
+/// Checks the consistency of the consensus database `db`, returning the pruning point and sink it holds:
+/// - the pruning point, sink, headers selected tip and virtual parents are known blocks, the sink being UTXO valid
+/// - the sink is in the selected chain future of the pruning point
+/// - the pruning point is valid from the POV of the headers selected tip, and past pruning points form a valid chain
+/// - the virtual UTXO set matches the UTXO commitment of the virtual state
+///
+/// Verifying the UTXO commitment iterates the whole UTXO set, so checking a mainnet database can take a while.
+pub fn check_consistency(db: Arc<DB>, config: Arc<Config>) -> BackupResult<(Hash, Hash)> {
+    // Damaged derivable stores are rebuilt when the backup gets restored, so only damaged source of truth stores are fatal
+    for finding in check_stores(db.clone(), &config, CheckDepth::Full) {
+        match finding.store.class() {
+            StoreClass::SourceOfTruth => return Err(inconsistent(finding.to_string())),
+            StoreClass::Derivable => warn!("{}, it will be rebuilt on startup", finding),
+        }
+    }
+
+    let storage = ConsensusStorage::new(db.clone(), config.clone());
+    let services = ConsensusServices::new(
+        db,
+        storage.clone(),
+        config,
+        Default::default(),
+        Default::default(),
+        Default::default(),
+        Default::default(),
+    );
+
+    let pruning_info = storage.pruning_point_store.read().get().map_err(store_error)?;
+    let pruning_point = pruning_info.pruning_point;
+    let virtual_state = storage.virtual_stores.read().state.get().map_err(store_error)?;
+    let sink = virtual_state.ghostdag_data.selected_parent;
+    let headers_selected_tip = storage.headers_selected_tip_store.read().get().map_err(store_error)?.hash;
+
+    let blocks = [("pruning point", pruning_point), ("sink", sink), ("headers selected tip", headers_selected_tip)];
+    for (name, hash) in blocks.into_iter().chain(virtual_state.parents.iter().map(|&parent| ("virtual parent", parent))) {
+        if !storage.headers_store.has(hash).map_err(store_error)? || !storage.ghostdag_primary_store.has(hash).map_err(store_error)? {
+            return Err(inconsistent(format!("the {name} {hash} has no header or GHOSTDAG data")));
+        }
+    }
+    let sink_status = storage.statuses_store.read().get(sink).map_err(store_error)?;
+    if sink_status != BlockStatus::StatusUTXOValid {
+        return Err(inconsistent(format!("the sink {sink} has status {sink_status:?}")));
+    }
+
+    if !services.reachability_service.is_chain_ancestor_of(pruning_point, sink) {
+        return Err(inconsistent(format!("the sink {sink} is not in the selected chain future of the pruning point {pruning_point}")));
+    }
+    if !services.pruning_point_manager.is_valid_pruning_point(pruning_point, headers_selected_tip) {
+        return Err(inconsistent(format!("the pruning point {pruning_point} is invalid")));
+    }
+    if !services.pruning_point_manager.are_pruning_points_in_valid_chain(pruning_info, headers_selected_tip) {
+        return Err(inconsistent("the past pruning points do not form a valid chain".to_string()));
+    }
+
+    let mut multiset = MuHash::new();
+    for item in storage.virtual_stores.read().utxo_set.iterator() {
+        let (outpoint, entry) = item.map_err(|err| BackupError::Store(err.to_string()))?;
+        multiset.add_utxo(&outpoint, &entry);
+    }
+    let (utxo_commitment, expected_utxo_commitment) = (multiset.finalize(), virtual_state.multiset.clone().finalize());
+    if utxo_commitment != expected_utxo_commitment {
+        return Err(inconsistent(format!(
+            "the virtual UTXO set commitment {utxo_commitment} does not match the virtual state commitment {expected_utxo_commitment}"
+        )));
+    }
+
+    Ok((pruning_point, sink))
+}
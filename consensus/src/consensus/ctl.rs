@@ -1,9 +1,13 @@
-use super::{factory::MultiConsensusManagementStore, Consensus};
+use super::{backup, factory::MultiConsensusManagementStore, Consensus};
+use kaspa_consensus_core::{
+    backup::BackupManifest,
+    errors::backup::{BackupError, BackupResult},
+};
 use kaspa_consensusmanager::ConsensusCtl;
 use kaspa_database::prelude::DB;
 use parking_lot::RwLock;
 use std::{
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, Weak},
     thread::JoinHandle,
 };
@@ -36,10 +40,22 @@ impl ConsensusCtl for Ctl {
         self.consensus.signal_exit()
     }
 
+    fn seal(&self) {
+        if let Some(consensus_db) = self.consensus_db_ref.upgrade() {
+            consensus_db.flush_all().unwrap();
+        }
+        self.management_store.write().prepare_staging_consensus().unwrap();
+    }
+
     fn make_active(&self) {
         // TODO: pass a value to make sure the correct consensus is committed
         self.management_store.write().commit_staging_consensus().unwrap();
     }
+
+    fn create_backup(&self, output_dir: &Path) -> BackupResult<BackupManifest> {
+        let consensus_db = self.consensus_db_ref.upgrade().ok_or(BackupError::NoActiveConsensus)?;
+        backup::create_backup(&self.management_store, &consensus_db, self.consensus.config.clone(), output_dir)
+    }
 }
 
 /// Impl for test purposes
@@ -52,7 +68,15 @@ impl ConsensusCtl for Consensus {
         self.signal_exit()
     }
 
+    fn seal(&self) {
+        unimplemented!()
+    }
+
     fn make_active(&self) {
         unimplemented!()
     }
+
+    fn create_backup(&self, _output_dir: &Path) -> BackupResult<BackupManifest> {
+        unimplemented!()
+    }
 }
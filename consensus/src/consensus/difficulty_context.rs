@@ -0,0 +1,71 @@
+use indexmap::IndexMap;
+use kaspa_consensus_core::{difficulty::BlockDifficultyContext, BlockHasher};
+use kaspa_hashes::Hash;
+use parking_lot::Mutex;
+
+/// Number of block difficulty contexts kept in cache
+pub(super) const DIFFICULTY_CONTEXT_CACHE_SIZE: usize = 512;
+
+/// A least recently used cache of block difficulty contexts. Explorers tend to repeatedly request the
+/// most recent blocks, for which computing the DAA window hashrate means reading the whole window.
+pub(super) struct DifficultyContextCache {
+    capacity: usize,
+    // Entries are kept ordered from the least to the most recently used
+    map: Mutex<IndexMap<Hash, BlockDifficultyContext, BlockHasher>>,
+}
+
+impl DifficultyContextCache {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, map: Mutex::new(IndexMap::with_capacity_and_hasher(capacity, BlockHasher::new())) }
+    }
+
+    pub fn get(&self, hash: &Hash) -> Option<BlockDifficultyContext> {
+        let mut map = self.map.lock();
+        let index = map.get_index_of(hash)?;
+        let last = map.len() - 1;
+        map.move_index(index, last);
+        map.get_index(last).map(|(_, context)| *context)
+    }
+
+    pub fn insert(&self, hash: Hash, context: BlockDifficultyContext) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut map = self.map.lock();
+        if let Some(index) = map.get_index_of(&hash) {
+            let last = map.len() - 1;
+            map.move_index(index, last);
+        } else if map.len() == self.capacity {
+            map.shift_remove_index(0);
+        }
+        map.insert(hash, context);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_math::Uint256;
+
+    #[test]
+    fn test_difficulty_context_cache_eviction() {
+        let context = |n: u64| BlockDifficultyContext {
+            target: Uint256::from_u64(n),
+            work: Default::default(),
+            network_hashes_per_second: Some(n),
+        };
+        let cache = DifficultyContextCache::new(2);
+        cache.insert(1.into(), context(1));
+        cache.insert(2.into(), context(2));
+        // Using the first entry makes the second one the least recently used
+        assert_eq!(cache.get(&1.into()), Some(context(1)));
+        cache.insert(3.into(), context(3));
+        assert_eq!(cache.get(&2.into()), None);
+        assert_eq!(cache.get(&1.into()), Some(context(1)));
+        assert_eq!(cache.get(&3.into()), Some(context(3)));
+
+        let cache = DifficultyContextCache::new(0);
+        cache.insert(1.into(), context(1));
+        assert_eq!(cache.get(&1.into()), None);
+    }
+}
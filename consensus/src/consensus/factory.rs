@@ -3,23 +3,38 @@ use super::utxo_set_override::{set_genesis_utxo_commitment_from_config, set_init
 use super::{ctl::Ctl, Consensus};
 use crate::{model::stores::U64Key, pipeline::ProcessingCounters};
 use itertools::Itertools;
-use kaspa_consensus_core::config::Config;
-use kaspa_consensus_notify::root::ConsensusNotificationRoot;
-use kaspa_consensusmanager::{ConsensusFactory, ConsensusInstance, DynConsensusCtl, SessionLock};
-use kaspa_core::{debug, time::unix_now, warn};
+use kaspa_consensus_core::{
+    api::parallelism::ProcessingParallelism,
+    config::Config,
+    multi_consensus::{ConsensusCreationReason, ConsensusEntryInfo, MultiConsensusStatus, RetiredConsensusEntryInfo},
+};
+use kaspa_consensus_notify::{
+    notification::{Notification, PruningPointUtxoSetOverrideNotification},
+    root::ConsensusNotificationRoot,
+};
+use kaspa_consensusmanager::{ConsensusFactory, ConsensusInstance, ConsensusResetHandler, DynConsensusCtl, SessionLock};
+use kaspa_core::{debug, info, panic::register_emergency_action, time::unix_now, warn};
 use kaspa_database::{
     prelude::{
-        BatchDbWriter, CachePolicy, CachedDbAccess, CachedDbItem, DirectDbWriter, StoreError, StoreResult, StoreResultExtensions, DB,
+        BatchDbWriter, CachePolicy, CachedDbAccess, CachedDbItem, DirectDbWriter, StoreError, StoreResult, StoreResultExtensions,
+        WalSyncPolicy, DB,
     },
     registry::DatabaseStorePrefixes,
 };
+use kaspa_notify::notifier::Notify;
 
-use kaspa_txscript::caches::TxScriptCacheCounters;
+use kaspa_txscript::{caches::TxScriptCacheCounters, metrics::ScriptMetrics};
 use kaspa_utils::mem_size::MemSizeEstimator;
 use parking_lot::RwLock;
 use rocksdb::WriteBatch;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, error::Error, fs, path::PathBuf, sync::Arc};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ConsensusEntry {
@@ -73,11 +88,28 @@ impl Default for MultiConsensusMetadata {
     }
 }
 
+/// Minimum time (in milliseconds) a retired consensus entry is kept before its directory is deleted on startup or
+/// on the next staging consensus promotion
+pub const RETIREMENT_GRACE_PERIOD: u64 = 24 * 60 * 60 * 1000;
+
+/// Records of the promotion of staging consensus entries. Kept apart from [`MultiConsensusMetadata`] so that nodes
+/// predating them need no DB version upgrade.
+#[derive(Serialize, Deserialize, Clone, Default)]
+struct ConsensusLifecycle {
+    /// Key of the staging entry sealed and prepared for promotion, the promotion being completed on next startup
+    /// if interrupted
+    prepared_staging_key: Option<u64>,
+    creation_reasons: HashMap<u64, ConsensusCreationReason>,
+    /// Unix time (in milliseconds) at which each retired entry was replaced as active
+    retirement_timestamps: HashMap<u64, u64>,
+}
+
 #[derive(Clone)]
 pub struct MultiConsensusManagementStore {
     db: Arc<DB>,
     entries: CachedDbAccess<U64Key, ConsensusEntry>,
     metadata: CachedDbItem<MultiConsensusMetadata>,
+    lifecycle: CachedDbItem<ConsensusLifecycle>,
 }
 
 impl MultiConsensusManagementStore {
@@ -85,14 +117,16 @@ impl MultiConsensusManagementStore {
         let mut store = Self {
             db: db.clone(),
             entries: CachedDbAccess::new(db.clone(), CachePolicy::Count(16), DatabaseStorePrefixes::ConsensusEntries.into()),
-            metadata: CachedDbItem::new(db, DatabaseStorePrefixes::MultiConsensusMetadata.into()),
+            metadata: CachedDbItem::new(db.clone(), DatabaseStorePrefixes::MultiConsensusMetadata.into()),
+            lifecycle: CachedDbItem::new(db, DatabaseStorePrefixes::ConsensusLifecycle.into()),
         };
         store.init();
         store
     }
 
     fn init(&mut self) {
-        if self.metadata.read().unwrap_option().is_none() {
+        // Read-only instances (e.g. of a backup) are expected to be initialized already
+        if self.db.check_writable().is_ok() && self.metadata.read().unwrap_option().is_none() {
             let mut batch = WriteBatch::default();
             let metadata = MultiConsensusMetadata::default();
             self.metadata.write(BatchDbWriter::new(&mut batch), &metadata).unwrap();
@@ -100,6 +134,14 @@ impl MultiConsensusManagementStore {
         }
     }
 
+    pub fn db(&self) -> &Arc<DB> {
+        &self.db
+    }
+
+    fn read_lifecycle(&self) -> StoreResult<ConsensusLifecycle> {
+        self.lifecycle.read().unwrap_option().map(Option::unwrap_or_default)
+    }
+
     /// The directory name of the active consensus, if one exists. None otherwise
     pub fn active_consensus_dir_name(&self) -> StoreResult<Option<String>> {
         let metadata = self.metadata.read()?;
@@ -137,12 +179,15 @@ impl MultiConsensusManagementStore {
         if self.entries.has(key.into())? {
             return Err(StoreError::KeyAlreadyExists(format!("{key}")));
         }
+        let mut lifecycle = self.read_lifecycle()?;
+        lifecycle.creation_reasons.insert(key, ConsensusCreationReason::Genesis);
         let mut batch = WriteBatch::default();
         self.entries.write(BatchDbWriter::new(&mut batch), key.into(), entry)?;
         self.metadata.update(BatchDbWriter::new(&mut batch), |mut data| {
             data.current_consensus_key = Some(key);
             data
         })?;
+        self.lifecycle.write(BatchDbWriter::new(&mut batch), &lifecycle)?;
         self.db.write(batch)?;
         Ok(())
     }
@@ -154,32 +199,79 @@ impl MultiConsensusManagementStore {
         let new_key = metadata.max_key_used;
         metadata.staging_consensus_key = Some(new_key);
         let new_entry = ConsensusEntry::from_key(new_key);
+        let mut lifecycle = self.read_lifecycle()?;
+        lifecycle.creation_reasons.insert(new_key, ConsensusCreationReason::PruningPointSync);
 
         let mut batch = WriteBatch::default();
         self.metadata.write(BatchDbWriter::new(&mut batch), &metadata)?;
         self.entries.write(BatchDbWriter::new(&mut batch), new_key.into(), new_entry.clone())?;
+        self.lifecycle.write(BatchDbWriter::new(&mut batch), &lifecycle)?;
         self.db.write(batch)?;
 
         Ok(new_entry)
     }
 
+    /// First phase of the promotion of the staging consensus: records the staging entry, whose database is expected
+    /// to be validated and flushed, as prepared. From then on the promotion is decided, and is completed on next
+    /// startup if interrupted.
+    pub fn prepare_staging_consensus(&mut self) -> StoreResult<()> {
+        let staging_key = self.metadata.read()?.staging_consensus_key;
+        assert!(staging_key.is_some());
+        let mut lifecycle = self.read_lifecycle()?;
+        lifecycle.prepared_staging_key = staging_key;
+        self.lifecycle.write(DirectDbWriter::new(&self.db), &lifecycle)
+    }
+
+    /// Second phase of the promotion of the staging consensus: atomically swaps the active consensus pointer to the
+    /// staging entry and retires the previously active entry
     pub fn commit_staging_consensus(&mut self) -> StoreResult<()> {
-        self.metadata.update(DirectDbWriter::new(&self.db), |mut data| {
-            assert!(data.staging_consensus_key.is_some());
-            data.current_consensus_key = data.staging_consensus_key.take();
-            data
-        })?;
+        let mut metadata = self.metadata.read()?;
+        assert!(metadata.staging_consensus_key.is_some());
+        let mut lifecycle = self.read_lifecycle()?;
+        if let Some(previous_key) = metadata.current_consensus_key {
+            lifecycle.retirement_timestamps.insert(previous_key, unix_now());
+        }
+        lifecycle.prepared_staging_key = None;
+        metadata.current_consensus_key = metadata.staging_consensus_key.take();
+
+        let mut batch = WriteBatch::default();
+        self.metadata.write(BatchDbWriter::new(&mut batch), &metadata)?;
+        self.lifecycle.write(BatchDbWriter::new(&mut batch), &lifecycle)?;
+        self.db.write(batch)?;
         Ok(())
     }
 
     pub fn cancel_staging_consensus(&mut self) -> StoreResult<()> {
-        self.metadata.update(DirectDbWriter::new(&self.db), |mut data| {
+        let mut lifecycle = self.read_lifecycle()?;
+        lifecycle.prepared_staging_key = None;
+        let mut batch = WriteBatch::default();
+        self.metadata.update(BatchDbWriter::new(&mut batch), |mut data| {
             data.staging_consensus_key = None;
             data
         })?;
+        self.lifecycle.write(BatchDbWriter::new(&mut batch), &lifecycle)?;
+        self.db.write(batch)?;
         Ok(())
     }
 
+    /// Completes a promotion of the staging consensus interrupted after its first phase, returning the promoted entry.
+    /// A staging entry which was not prepared is left as is, holding non-final data to be deleted.
+    pub fn recover_interrupted_promotion(&mut self) -> StoreResult<Option<ConsensusEntry>> {
+        let Some(prepared_key) = self.read_lifecycle()?.prepared_staging_key else {
+            return Ok(None);
+        };
+        if self.metadata.read()?.staging_consensus_key != Some(prepared_key) {
+            // Not expected since the commit clears the record atomically, but the staging entry is gone either way
+            warn!("Discarding the promotion record of the missing staging consensus entry {prepared_key}");
+            let mut lifecycle = self.read_lifecycle()?;
+            lifecycle.prepared_staging_key = None;
+            self.lifecycle.write(DirectDbWriter::new(&self.db), &lifecycle)?;
+            return Ok(None);
+        }
+        self.commit_staging_consensus()?;
+        Ok(Some(self.entries.read(prepared_key.into())?))
+    }
+
     fn iterator(&self) -> impl Iterator<Item = Result<ConsensusEntry, Box<dyn Error>>> + '_ {
         self.entries.iterator().map(|iter_result| match iter_result {
             Ok((_, entry)) => Ok(entry),
@@ -187,11 +279,13 @@ impl MultiConsensusManagementStore {
         })
     }
 
-    fn iterate_inactive_entries(&self) -> impl Iterator<Item = Result<ConsensusEntry, Box<dyn Error>>> + '_ {
-        let current_consensus_key = self.metadata.read().unwrap().current_consensus_key;
+    /// Iterates the entries neither active nor staging, i.e. the entries replaced as active
+    fn iterate_retired_entries(&self) -> impl Iterator<Item = Result<ConsensusEntry, Box<dyn Error>>> + '_ {
+        let metadata = self.metadata.read().unwrap();
+        let (current_consensus_key, staging_consensus_key) = (metadata.current_consensus_key, metadata.staging_consensus_key);
         self.iterator().filter(move |entry_result| {
             if let Ok(entry) = entry_result {
-                return Some(entry.key) != current_consensus_key;
+                return Some(entry.key) != current_consensus_key && Some(entry.key) != staging_consensus_key;
             }
 
             true
@@ -199,7 +293,102 @@ impl MultiConsensusManagementStore {
     }
 
     fn delete_entry(&mut self, entry: ConsensusEntry) -> StoreResult<()> {
-        self.entries.delete(DirectDbWriter::new(&self.db), entry.key.into())
+        let mut lifecycle = self.read_lifecycle()?;
+        lifecycle.creation_reasons.remove(&entry.key);
+        lifecycle.retirement_timestamps.remove(&entry.key);
+        let mut batch = WriteBatch::default();
+        self.entries.delete(BatchDbWriter::new(&mut batch), entry.key.into())?;
+        self.lifecycle.write(BatchDbWriter::new(&mut batch), &lifecycle)?;
+        self.db.write(batch)?;
+        Ok(())
+    }
+
+    /// Deletes the staging entry and its database directory under `db_root_dir`
+    pub fn delete_staging_entry(&mut self, db_root_dir: &Path) {
+        if let Some(entry) = self.staging_consensus_entry() {
+            let dir = db_root_dir.join(entry.directory_name.clone());
+            match fs::remove_dir_all(dir) {
+                Ok(_) => {
+                    self.delete_entry(entry).unwrap();
+                }
+                Err(e) => {
+                    warn!("Error deleting staging consensus entry {}: {}", entry.key, e);
+                }
+            };
+            self.cancel_staging_consensus().unwrap();
+        }
+    }
+
+    /// Deletes the retired entries, and their database directories under `db_root_dir`, replaced as active at or
+    /// before `retired_before` (a Unix time in milliseconds). Entries whose retirement time is unknown are deleted
+    /// regardless. Returns the deleted entries.
+    pub fn delete_retired_entries(&mut self, db_root_dir: &Path, retired_before: u64) -> Vec<RetiredConsensusEntryInfo> {
+        let lifecycle = self.read_lifecycle().unwrap();
+        let entries_to_delete = self
+            .iterate_retired_entries()
+            .map(|entry_result| entry_result.unwrap())
+            .filter(|entry| lifecycle.retirement_timestamps.get(&entry.key).map_or(true, |&timestamp| timestamp <= retired_before))
+            .filter_map(|entry| {
+                let retired = Self::retired_entry_info(&entry, &lifecycle, db_root_dir);
+                let dir = db_root_dir.join(entry.directory_name.clone());
+                if dir.exists() {
+                    match fs::remove_dir_all(dir) {
+                        Ok(_) => Some((entry, retired)),
+                        Err(e) => {
+                            warn!("Error deleting consensus entry {}: {}", entry.key, e);
+                            None
+                        }
+                    }
+                } else {
+                    Some((entry, retired))
+                }
+            })
+            .collect_vec();
+
+        entries_to_delete
+            .into_iter()
+            .map(|(entry, retired)| {
+                self.delete_entry(entry).unwrap();
+                retired
+            })
+            .collect()
+    }
+
+    fn entry_info(entry: &ConsensusEntry, lifecycle: &ConsensusLifecycle) -> ConsensusEntryInfo {
+        ConsensusEntryInfo {
+            key: entry.key,
+            directory_name: entry.directory_name.clone(),
+            creation_timestamp: entry.creation_timestamp,
+            creation_reason: lifecycle.creation_reasons.get(&entry.key).copied().unwrap_or(ConsensusCreationReason::Unknown),
+        }
+    }
+
+    fn retired_entry_info(entry: &ConsensusEntry, lifecycle: &ConsensusLifecycle, db_root_dir: &Path) -> RetiredConsensusEntryInfo {
+        RetiredConsensusEntryInfo {
+            entry: Self::entry_info(entry, lifecycle),
+            retirement_timestamp: lifecycle.retirement_timestamps.get(&entry.key).copied(),
+            disk_usage: disk_usage(&db_root_dir.join(&entry.directory_name)),
+        }
+    }
+
+    /// The status of the consensus entries, the disk usage of the retired entries being read under `db_root_dir`
+    pub fn status(&self, db_root_dir: &Path) -> StoreResult<MultiConsensusStatus> {
+        let metadata = self.metadata.read()?;
+        let lifecycle = self.read_lifecycle()?;
+        let entry_info = |key: Option<u64>| -> StoreResult<Option<ConsensusEntryInfo>> {
+            key.map(|key| self.entries.read(key.into()).map(|entry| Self::entry_info(&entry, &lifecycle))).transpose()
+        };
+        Ok(MultiConsensusStatus {
+            current: entry_info(metadata.current_consensus_key)?,
+            staging: entry_info(metadata.staging_consensus_key)?,
+            is_staging_prepared: metadata.staging_consensus_key.is_some()
+                && lifecycle.prepared_staging_key == metadata.staging_consensus_key,
+            retired: self
+                .iterate_retired_entries()
+                .map(|entry_result| entry_result.map(|entry| Self::retired_entry_info(&entry, &lifecycle, db_root_dir)))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| StoreError::DataInconsistency(err.to_string()))?,
+        })
     }
 
     pub fn is_archival_node(&self) -> StoreResult<bool> {
@@ -233,9 +422,12 @@ pub struct Factory {
     config: Config,
     db_root_dir: PathBuf,
     db_parallelism: usize,
+    db_wal_sync: WalSyncPolicy,
     notification_root: Arc<ConsensusNotificationRoot>,
     counters: Arc<ProcessingCounters>,
+    parallelism: Arc<ProcessingParallelism>,
     tx_script_cache_counters: Arc<TxScriptCacheCounters>,
+    script_metrics: Arc<ScriptMetrics>,
     fd_budget: i32,
 }
 
@@ -245,9 +437,12 @@ impl Factory {
         config: &Config,
         db_root_dir: PathBuf,
         db_parallelism: usize,
+        db_wal_sync: WalSyncPolicy,
         notification_root: Arc<ConsensusNotificationRoot>,
         counters: Arc<ProcessingCounters>,
+        parallelism: Arc<ProcessingParallelism>,
         tx_script_cache_counters: Arc<TxScriptCacheCounters>,
+        script_metrics: Arc<ScriptMetrics>,
         fd_budget: i32,
     ) -> Self {
         assert!(fd_budget > 0, "fd_budget has to be positive");
@@ -257,14 +452,20 @@ impl Factory {
         config.process_genesis = false;
         let management_store = Arc::new(RwLock::new(MultiConsensusManagementStore::new(management_db)));
         management_store.write().set_is_archival_node(config.is_archival);
+        if let Some(entry) = management_store.write().recover_interrupted_promotion().unwrap() {
+            info!("Completed the interrupted promotion of the staging consensus {} to active", entry.directory_name);
+        }
         let factory = Self {
             management_store,
             config,
             db_root_dir,
             db_parallelism,
+            db_wal_sync,
             notification_root,
             counters,
+            parallelism,
             tx_script_cache_counters,
+            script_metrics,
             fd_budget,
         };
         factory.delete_inactive_consensus_entries();
@@ -293,11 +494,14 @@ impl ConsensusFactory for Factory {
 
         let dir = self.db_root_dir.join(entry.directory_name.clone());
         let db = kaspa_database::prelude::ConnBuilder::default()
-            .with_db_path(dir)
+            .with_db_path(dir.clone())
             .with_parallelism(self.db_parallelism)
+            .with_wal_sync(self.db_wal_sync)
             .with_files_limit(self.fd_budget / 2) // active and staging consensuses should have equal budgets
             .build()
             .unwrap();
+        // Named after the full path, which remains unique when several nodes run in the process
+        register_emergency_action(format!("consensus-db:{}", dir.display()), db.emergency_flush_action());
 
         let session_lock = SessionLock::new();
         let consensus = Arc::new(Consensus::new(
@@ -306,7 +510,9 @@ impl ConsensusFactory for Factory {
             session_lock.clone(),
             self.notification_root.clone(),
             self.counters.clone(),
+            self.parallelism.clone(),
             self.tx_script_cache_counters.clone(),
+            self.script_metrics.clone(),
             entry.creation_timestamp,
         ));
 
@@ -325,13 +531,15 @@ impl ConsensusFactory for Factory {
         assert!(!self.notification_root.is_closed());
 
         let entry = self.management_store.write().new_staging_consensus_entry().unwrap();
-        let dir = self.db_root_dir.join(entry.directory_name);
+        let dir = self.db_root_dir.join(entry.directory_name.clone());
         let db = kaspa_database::prelude::ConnBuilder::default()
-            .with_db_path(dir)
+            .with_db_path(dir.clone())
             .with_parallelism(self.db_parallelism)
+            .with_wal_sync(self.db_wal_sync)
             .with_files_limit(self.fd_budget / 2) // active and staging consensuses should have equal budgets
             .build()
             .unwrap();
+        register_emergency_action(format!("consensus-db:{}", dir.display()), db.emergency_flush_action());
 
         let session_lock = SessionLock::new();
         let consensus = Arc::new(Consensus::new(
@@ -340,7 +548,9 @@ impl ConsensusFactory for Factory {
             session_lock.clone(),
             self.notification_root.clone(),
             self.counters.clone(),
+            self.parallelism.clone(),
             self.tx_script_cache_counters.clone(),
+            self.script_metrics.clone(),
             entry.creation_timestamp,
         ));
 
@@ -360,44 +570,145 @@ impl ConsensusFactory for Factory {
             return;
         }
 
-        let mut write_guard = self.management_store.write();
-        let entries_to_delete = write_guard
-            .iterate_inactive_entries()
-            .filter_map(|entry_result| {
-                let entry = entry_result.unwrap();
-                let dir = self.db_root_dir.join(entry.directory_name.clone());
-                if dir.exists() {
-                    match fs::remove_dir_all(dir) {
-                        Ok(_) => Some(entry),
-                        Err(e) => {
-                            warn!("Error deleting consensus entry {}: {}", entry.key, e);
-                            None
-                        }
-                    }
-                } else {
-                    Some(entry)
-                }
-            })
-            .collect_vec();
-
-        for entry in entries_to_delete {
-            write_guard.delete_entry(entry).unwrap();
+        let retired_before = unix_now().saturating_sub(RETIREMENT_GRACE_PERIOD);
+        for retired in self.management_store.write().delete_retired_entries(&self.db_root_dir, retired_before) {
+            info!("Deleted the retired consensus {}, reclaiming {} bytes", retired.entry.directory_name, retired.disk_usage);
         }
     }
 
     fn delete_staging_entry(&self) {
-        let mut write_guard = self.management_store.write();
-        if let Some(entry) = write_guard.staging_consensus_entry() {
-            let dir = self.db_root_dir.join(entry.directory_name.clone());
-            match fs::remove_dir_all(dir) {
-                Ok(_) => {
-                    write_guard.delete_entry(entry).unwrap();
-                }
-                Err(e) => {
-                    warn!("Error deleting staging consensus entry {}: {}", entry.key, e);
-                }
-            };
-            write_guard.cancel_staging_consensus().unwrap();
+        self.management_store.write().delete_staging_entry(&self.db_root_dir);
+    }
+
+    fn consensus_status(&self) -> MultiConsensusStatus {
+        self.management_store.read().status(&self.db_root_dir).unwrap()
+    }
+}
+
+/// The total size in bytes of the files under `path`, errors being ignored
+fn disk_usage(path: &Path) -> u64 {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => fs::read_dir(path)
+            .map(|entries| entries.filter_map(Result::ok).map(|entry| disk_usage(&entry.path())).sum())
+            .unwrap_or_default(),
+        Ok(metadata) => metadata.len(),
+        Err(_) => 0,
+    }
+}
+
+/// Notifies a [`PruningPointUtxoSetOverrideNotification`] when a staging consensus, holding a newly
+/// imported pruning point UTXO set, is committed as the active consensus.
+///
+/// Reset handlers are called in registration order, so this one should be registered after those
+/// rebuilding state derived from the UTXO set (e.g. the UTXO index), letting consumers of the
+/// notification observe the rebuilt state.
+pub struct PruningPointUtxoSetOverrideNotifier {
+    notification_root: Arc<ConsensusNotificationRoot>,
+}
+
+impl PruningPointUtxoSetOverrideNotifier {
+    pub fn new(notification_root: Arc<ConsensusNotificationRoot>) -> Self {
+        Self { notification_root }
+    }
+}
+
+impl ConsensusResetHandler for PruningPointUtxoSetOverrideNotifier {
+    fn handle_consensus_reset(&self) {
+        info!("The pruning point UTXO set was overridden, notifying consumers of the UTXO set");
+        let notification = Notification::PruningPointUtxoSetOverride(PruningPointUtxoSetOverrideNotification {});
+        if let Err(err) = self.notification_root.notify(notification) {
+            warn!("Error notifying the pruning point UTXO set override: {err}");
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_database::{create_temp_db, prelude::ConnBuilder, utils::get_kaspa_tempdir};
+
+    /// Creates the database directory of `entry` under `db_root_dir`, holding a file of `size` bytes
+    fn create_entry_dir(db_root_dir: &Path, entry: &ConsensusEntry, size: usize) {
+        let dir = db_root_dir.join(&entry.directory_name);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("data"), vec![0u8; size]).unwrap();
+    }
+
+    #[test]
+    fn test_staging_promotion_crash_recovery() {
+        let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        let db_root_dir = get_kaspa_tempdir();
+        let db_root_dir = db_root_dir.path();
+
+        let mut store = MultiConsensusManagementStore::new(db.clone());
+        let ConsensusEntryType::New(genesis_entry) = store.active_consensus_entry().unwrap() else { panic!("expected a new entry") };
+        create_entry_dir(db_root_dir, &genesis_entry, 100);
+        store.save_new_active_consensus(genesis_entry.clone()).unwrap();
+
+        // Crash while the staging consensus syncs, before it is prepared: the staging entry is discarded on recovery
+        let staging_entry = store.new_staging_consensus_entry().unwrap();
+        create_entry_dir(db_root_dir, &staging_entry, 10);
+        drop(store);
+        let mut store = MultiConsensusManagementStore::new(db.clone());
+        let status = store.status(db_root_dir).unwrap();
+        assert_eq!(status.staging.unwrap().key, staging_entry.key);
+        assert!(!status.is_staging_prepared);
+        assert!(store.recover_interrupted_promotion().unwrap().is_none());
+        store.delete_staging_entry(db_root_dir);
+        assert!(!db_root_dir.join(&staging_entry.directory_name).exists());
+        let status = store.status(db_root_dir).unwrap();
+        assert_eq!(status.current.unwrap().key, genesis_entry.key);
+        assert!(status.staging.is_none());
+        assert!(status.retired.is_empty());
+
+        // Crash once the staging consensus is prepared, before the active pointer is swapped: the promotion is completed
+        let staging_entry = store.new_staging_consensus_entry().unwrap();
+        create_entry_dir(db_root_dir, &staging_entry, 10);
+        store.prepare_staging_consensus().unwrap();
+        drop(store);
+        let mut store = MultiConsensusManagementStore::new(db.clone());
+        assert!(store.status(db_root_dir).unwrap().is_staging_prepared);
+        assert_eq!(store.recover_interrupted_promotion().unwrap().unwrap().key, staging_entry.key);
+        assert!(store.recover_interrupted_promotion().unwrap().is_none());
+        let status = store.status(db_root_dir).unwrap();
+        let current = status.current.unwrap();
+        assert_eq!(current.key, staging_entry.key);
+        assert_eq!(current.creation_reason, ConsensusCreationReason::PruningPointSync);
+        assert!(status.staging.is_none());
+        assert!(!status.is_staging_prepared);
+        assert_eq!(status.retired.len(), 1);
+        assert_eq!(status.retired[0].entry.key, genesis_entry.key);
+        assert_eq!(status.retired[0].entry.creation_reason, ConsensusCreationReason::Genesis);
+        assert!(status.retired[0].retirement_timestamp.is_some());
+        assert_eq!(status.retired[0].disk_usage, 100);
+        assert_eq!(store.active_consensus_dir_name().unwrap(), Some(staging_entry.directory_name.clone()));
+
+        // Crash once the pointer is swapped, before the cleanup: the retired entry is kept for the grace period
+        drop(store);
+        let mut store = MultiConsensusManagementStore::new(db.clone());
+        assert!(store.delete_retired_entries(db_root_dir, unix_now().saturating_sub(RETIREMENT_GRACE_PERIOD)).is_empty());
+        assert!(db_root_dir.join(&genesis_entry.directory_name).exists());
+        let deleted = store.delete_retired_entries(db_root_dir, u64::MAX);
+        assert_eq!(deleted.len(), 1);
+        assert_eq!(deleted[0].disk_usage, 100);
+        assert!(!db_root_dir.join(&genesis_entry.directory_name).exists());
+        assert!(store.status(db_root_dir).unwrap().retired.is_empty());
+
+        // Crash during the cleanup, after the directory is deleted but before the entry is: the entry is deleted on recovery
+        let previous_entry = staging_entry;
+        let staging_entry = store.new_staging_consensus_entry().unwrap();
+        create_entry_dir(db_root_dir, &staging_entry, 10);
+        store.prepare_staging_consensus().unwrap();
+        store.commit_staging_consensus().unwrap();
+        fs::remove_dir_all(db_root_dir.join(&previous_entry.directory_name)).unwrap();
+        drop(store);
+        let mut store = MultiConsensusManagementStore::new(db);
+        assert!(store.recover_interrupted_promotion().unwrap().is_none());
+        let status = store.status(db_root_dir).unwrap();
+        assert_eq!(status.current.unwrap().key, staging_entry.key);
+        assert_eq!(status.retired.len(), 1);
+        assert_eq!(status.retired[0].disk_usage, 0);
+        assert_eq!(store.delete_retired_entries(db_root_dir, u64::MAX).len(), 1);
+        assert!(store.status(db_root_dir).unwrap().retired.is_empty());
+    }
+}
@@ -1,6 +1,9 @@
+pub mod backup;
 pub mod cache_policy_builder;
 pub mod ctl;
+mod difficulty_context;
 pub mod factory;
+pub mod recovery;
 pub mod services;
 pub mod storage;
 pub mod test_consensus;
@@ -16,12 +19,14 @@ use crate::{
         stores::{
             acceptance_data::AcceptanceDataStoreReader,
             block_transactions::BlockTransactionsStoreReader,
+            daa::DaaStoreReader,
             ghostdag::{GhostdagData, GhostdagStoreReader},
             headers::{CompactHeaderData, HeaderStoreReader},
             headers_selected_tip::HeadersSelectedTipStoreReader,
             past_pruning_points::PastPruningPointsStoreReader,
             pruning::PruningStoreReader,
             relations::RelationsStoreReader,
+            spent_utxo_entries::SpentUtxoEntriesStoreReader,
             statuses::StatusesStoreReader,
             tips::TipsStoreReader,
             utxo_set::{UtxoSetStore, UtxoSetStoreReader},
@@ -36,16 +41,24 @@ use crate::{
         virtual_processor::{errors::PruningImportResult, VirtualStateProcessor},
         ProcessingCounters,
     },
-    processes::window::{WindowManager, WindowType},
+    processes::{
+        difficulty::calc_work,
+        window::{WindowManager, WindowType},
+    },
 };
 use kaspa_consensus_core::{
-    acceptance_data::AcceptanceData,
-    api::{stats::BlockCount, BlockValidationFutures, ConsensusApi, ConsensusStats},
-    block::{Block, BlockTemplate, TemplateBuildMode, TemplateTransactionSelector, VirtualStateApproxId},
+    acceptance_data::{AcceptanceData, AcceptanceProof, OutpointSpendStatus, OutpointSpender},
+    api::{
+        parallelism::ProcessingParallelism, stats::BlockCount, BlockValidationFutures, ConsensusApi, ConsensusStats,
+        TrustedBlocksSummary, TrustedBlocksValidationFuture, VirtualInfo,
+    },
+    block::{Block, BlockTemplate, BlockTemplateValidation, TemplateBuildMode, TemplateTransactionSelector, VirtualStateApproxId},
     blockhash::BlockHashExtensions,
     blockstatus::BlockStatus,
-    coinbase::MinerData,
-    daa_score_timestamp::DaaScoreTimestamp,
+    coinbase::{CoinbaseAmounts, MinerData},
+    config::checkpoints::validate_pruning_points_against_checkpoints,
+    daa_score_timestamp::{ChainBlockAtDaaScore, DaaScoreTimestamp},
+    difficulty::BlockDifficultyContext,
     errors::{
         coinbase::CoinbaseResult,
         consensus::{ConsensusError, ConsensusResult},
@@ -55,9 +68,10 @@ use kaspa_consensus_core::{
     header::Header,
     muhash::MuHashExtensions,
     network::NetworkType,
-    pruning::{PruningPointProof, PruningPointTrustedData, PruningPointsList},
+    pruning::{PruningPointProof, PruningPointTrustedData, PruningPointsList, PruningProofValidationProgress},
     trusted::{ExternalGhostdagData, TrustedBlock},
-    tx::{MutableTransaction, Transaction, TransactionOutpoint, UtxoEntry},
+    tx::{MutableTransaction, Transaction, TransactionId, TransactionOutpoint, UtxoEntry},
+    virtual_parents::VirtualParentSelectionTrace,
     BlockHashSet, BlueWorkType, ChainPath,
 };
 use kaspa_consensus_notify::root::ConsensusNotificationRoot;
@@ -65,13 +79,15 @@ use kaspa_consensus_notify::root::ConsensusNotificationRoot;
 use crossbeam_channel::{
     bounded as bounded_crossbeam, unbounded as unbounded_crossbeam, Receiver as CrossbeamReceiver, Sender as CrossbeamSender,
 };
+use futures_util::future::try_join_all;
 use itertools::Itertools;
 use kaspa_consensusmanager::{SessionLock, SessionReadGuard};
 
 use kaspa_database::prelude::StoreResultExtensions;
 use kaspa_hashes::Hash;
+use kaspa_math::Uint256;
 use kaspa_muhash::MuHash;
-use kaspa_txscript::caches::TxScriptCacheCounters;
+use kaspa_txscript::{caches::TxScriptCacheCounters, metrics::ScriptMetrics};
 
 use std::{
     future::Future,
@@ -85,7 +101,11 @@ use std::{
 };
 use tokio::sync::oneshot;
 
-use self::{services::ConsensusServices, storage::ConsensusStorage};
+use self::{
+    difficulty_context::{DifficultyContextCache, DIFFICULTY_CONTEXT_CACHE_SIZE},
+    services::ConsensusServices,
+    storage::ConsensusStorage,
+};
 
 use crate::model::stores::selected_chain::SelectedChainStoreReader;
 
@@ -119,11 +139,15 @@ pub struct Consensus {
     // Counters
     counters: Arc<ProcessingCounters>,
 
+    // Bound of the concurrently processed blocks
+    parallelism: Arc<ProcessingParallelism>,
+
     // Config
     config: Arc<Config>,
 
     // Other
     creation_timestamp: u64,
+    difficulty_context_cache: DifficultyContextCache,
 
     // Signals
     is_consensus_exiting: Arc<AtomicBool>,
@@ -144,7 +168,9 @@ impl Consensus {
         pruning_lock: SessionLock,
         notification_root: Arc<ConsensusNotificationRoot>,
         counters: Arc<ProcessingCounters>,
+        parallelism: Arc<ProcessingParallelism>,
         tx_script_cache_counters: Arc<TxScriptCacheCounters>,
+        script_metrics: Arc<ScriptMetrics>,
         creation_timestamp: u64,
     ) -> Self {
         let params = &config.params;
@@ -165,7 +191,9 @@ impl Consensus {
             db.clone(),
             storage.clone(),
             config.clone(),
+            counters.clone(),
             tx_script_cache_counters,
+            script_metrics,
             is_consensus_exiting.clone(),
         );
 
@@ -218,11 +246,13 @@ impl Consensus {
             body_sender,
             block_processors_pool.clone(),
             params,
+            config.clock.clone(),
             db.clone(),
             &storage,
             &services,
             pruning_lock.clone(),
             counters.clone(),
+            parallelism.clone(),
         ));
 
         let body_processor = Arc::new(BlockBodyProcessor::new(
@@ -241,10 +271,12 @@ impl Consensus {
             services.transaction_validator.clone(),
             services.window_manager.clone(),
             params.max_block_mass,
+            params.max_block_transactions,
             params.genesis.clone(),
             pruning_lock.clone(),
             notification_root.clone(),
             counters.clone(),
+            parallelism.clone(),
             params.storage_mass_activation_daa_score,
         ));
 
@@ -254,6 +286,8 @@ impl Consensus {
             pruning_receiver.clone(),
             virtual_pool,
             params,
+            config.store_spent_utxos,
+            config.clock.clone(),
             db.clone(),
             &storage,
             &services,
@@ -296,8 +330,10 @@ impl Consensus {
             pruning_lock,
             notification_root,
             counters,
+            parallelism,
             config,
             creation_timestamp,
+            difficulty_context_cache: DifficultyContextCache::new(DIFFICULTY_CONTEXT_CACHE_SIZE),
             is_consensus_exiting,
         }
     }
@@ -353,6 +389,10 @@ impl Consensus {
         &self.counters
     }
 
+    pub fn processing_parallelism(&self) -> &Arc<ProcessingParallelism> {
+        &self.parallelism
+    }
+
     pub fn signal_exit(&self) {
         self.is_consensus_exiting.store(true, Ordering::Relaxed);
         self.block_sender.send(BlockProcessingMessage::Exit).unwrap();
@@ -387,6 +427,20 @@ impl Consensus {
         Ok(self.services.window_manager.estimate_network_hashes_per_second(window)?)
     }
 
+    /// Estimates the hashrate over the DAA window of a block, returning `None` if the window is unavailable or too short
+    fn estimate_daa_window_hashes_per_second(&self, ghostdag_data: &GhostdagData) -> Option<u64> {
+        // The selected parent header is used within to check for sampling activation, so we verify its existence first
+        if !self.headers_store.has(ghostdag_data.selected_parent).unwrap() {
+            return None;
+        }
+        let daa_window = match self.services.window_manager.block_daa_window(ghostdag_data) {
+            Ok(w) => w,
+            Err(RuleError::InsufficientDaaWindowSize(_)) => return None,
+            Err(e) => panic!("unexpected error: {e}"),
+        };
+        self.services.window_manager.estimate_daa_window_hashes_per_second(ghostdag_data, &daa_window).ok()
+    }
+
     fn pruning_point_compact_headers(&self) -> Vec<(Hash, CompactHeaderData)> {
         // PRUNE SAFETY: index is monotonic and past pruning point headers are expected permanently
         let current_pp_info = self.pruning_point_store.read().get().unwrap();
@@ -408,16 +462,39 @@ impl ConsensusApi for Consensus {
         self.virtual_processor.build_block_template(miner_data, tx_selector, build_mode)
     }
 
+    fn validate_block_template(&self, block: Block) -> Result<BlockTemplateValidation, RuleError> {
+        self.header_processor.validate_template_header_in_isolation(&block.header)?;
+        self.virtual_processor.validate_block_template(&block, |past_median_time| {
+            let mass = self.body_processor.validate_body_in_isolation(&block)?;
+            self.body_processor.validate_template_body_in_context(&block, past_median_time)?;
+            Ok(mass)
+        })
+    }
+
     fn validate_and_insert_block(&self, block: Block) -> BlockValidationFutures {
         let (block_task, virtual_state_task) = self.validate_and_insert_block_impl(BlockTask::Ordinary { block });
         BlockValidationFutures { block_task: Box::pin(block_task), virtual_state_task: Box::pin(virtual_state_task) }
     }
 
     fn validate_and_insert_trusted_block(&self, tb: TrustedBlock) -> BlockValidationFutures {
-        let (block_task, virtual_state_task) = self.validate_and_insert_block_impl(BlockTask::Trusted { block: tb.block });
+        let (block_task, virtual_state_task) =
+            self.validate_and_insert_block_impl(BlockTask::Trusted { block: tb.block, notify: true });
         BlockValidationFutures { block_task: Box::pin(block_task), virtual_state_task: Box::pin(virtual_state_task) }
     }
 
+    fn validate_and_insert_trusted_blocks(&self, batch: Vec<TrustedBlock>) -> TrustedBlocksValidationFuture {
+        // Submitting the whole batch lets the pipeline process independent blocks concurrently, the dependency
+        // managers holding back the blocks of the batch until their parents are processed
+        let virtual_state_tasks = batch
+            .into_iter()
+            .map(|tb| self.validate_and_insert_block_impl(BlockTask::Trusted { block: tb.block, notify: false }).1)
+            .collect_vec();
+        Box::pin(async move {
+            let statuses = try_join_all(virtual_state_tasks).await?;
+            Ok(TrustedBlocksSummary::from_statuses(&statuses))
+        })
+    }
+
     fn validate_mempool_transaction(&self, transaction: &mut MutableTransaction) -> TxResult<()> {
         self.virtual_processor.validate_mempool_transaction(transaction)?;
         Ok(())
@@ -469,6 +546,10 @@ impl ConsensusApi for Consensus {
         self.lkg_virtual_state.load().past_median_time
     }
 
+    fn get_virtual_info(&self) -> VirtualInfo {
+        self.lkg_virtual_state.load().as_ref().into()
+    }
+
     fn get_virtual_merge_depth_root(&self) -> Option<Hash> {
         // TODO: consider saving the merge depth root as part of virtual state
         let pruning_point = self.pruning_point_store.read().pruning_point().unwrap();
@@ -531,7 +612,7 @@ impl ConsensusApi for Consensus {
         // See comment within `config.is_nearly_synced`
         let sink = self.get_sink();
         let compact = self.headers_store.get_compact_header_data(sink).unwrap();
-        self.config.is_nearly_synced(compact.timestamp, compact.daa_score)
+        self.config.is_nearly_synced_at(self.config.clock.unix_now(), compact.timestamp, compact.daa_score)
     }
 
     fn get_virtual_chain_from_block(&self, hash: Hash) -> ConsensusResult<ChainPath> {
@@ -611,6 +692,52 @@ impl ConsensusApi for Consensus {
         sample_headers
     }
 
+    fn get_chain_block_by_daa_score(&self, daa_score: u64) -> ConsensusResult<ChainBlockAtDaaScore> {
+        // We need consistency between the pruning point, selected chain and header store reads
+        let _guard = self.pruning_lock.blocking_read();
+
+        let pruning_point = self.pruning_point_store.read().pruning_point().unwrap();
+        let sc_read = self.storage.selected_chain_store.read();
+        let chain_block_at = |index: u64| {
+            let hash = sc_read.get_by_index(index).expect("store lock is acquired");
+            let compact = self.storage.headers_store.get_compact_header_data(hash).unwrap();
+            ChainBlockAtDaaScore { hash, daa_score: compact.daa_score, timestamp: compact.timestamp }
+        };
+
+        let high_index = sc_read.get_tip().unwrap().0;
+        // Same as in `get_chain_block_samples`, we prefer not crashing if the pruning point is missing from the selected chain store
+        let low_index = sc_read.get_by_hash(pruning_point).unwrap_option().unwrap_or(high_index);
+
+        let mut low = chain_block_at(low_index);
+        if daa_score < low.daa_score {
+            return Err(ConsensusError::DaaScoreBelowPruningPoint(daa_score, low.daa_score));
+        }
+        let mut high = chain_block_at(high_index);
+        if daa_score >= high.daa_score {
+            return Ok(high);
+        }
+
+        // DAA scores are strictly increasing over the selected chain, so we binary search
+        // while maintaining the invariant `low.daa_score <= daa_score < high.daa_score`
+        let (mut low_index, mut high_index) = (low_index, high_index);
+        while high_index - low_index > 1 {
+            let mid_index = low_index + (high_index - low_index) / 2;
+            let mid = chain_block_at(mid_index);
+            if mid.daa_score <= daa_score {
+                (low_index, low) = (mid_index, mid);
+            } else {
+                (high_index, high) = (mid_index, mid);
+            }
+        }
+
+        // Return the nearest of the two neighbors, preferring the earlier one on ties
+        if daa_score - low.daa_score <= high.daa_score - daa_score {
+            Ok(low)
+        } else {
+            Ok(high)
+        }
+    }
+
     fn get_virtual_parents(&self) -> BlockHashSet {
         self.lkg_virtual_state.load().parents.iter().copied().collect()
     }
@@ -638,6 +765,10 @@ impl ConsensusApi for Consensus {
         self.body_tips_store.read().get().unwrap().read().len()
     }
 
+    fn get_virtual_parent_selection_trace(&self) -> VirtualParentSelectionTrace {
+        self.virtual_processor.virtual_parent_selection_trace()
+    }
+
     fn get_pruning_point_utxos(
         &self,
         expected_pruning_point: Hash,
@@ -670,6 +801,15 @@ impl ConsensusApi for Consensus {
         self.services.pruning_proof_manager.validate_pruning_point_proof(proof)
     }
 
+    fn validate_pruning_proof_with_progress(
+        &self,
+        proof: &PruningPointProof,
+        progress: &(dyn Fn(PruningProofValidationProgress) + Sync),
+        cancel: &AtomicBool,
+    ) -> PruningImportResult<()> {
+        self.services.pruning_proof_manager.validate_pruning_point_proof_with_progress(proof, progress, cancel)
+    }
+
     fn apply_pruning_proof(&self, proof: PruningPointProof, trusted_set: &[TrustedBlock]) -> PruningImportResult<()> {
         self.services.pruning_proof_manager.apply_proof(proof, trusted_set)
     }
@@ -701,6 +841,10 @@ impl ConsensusApi for Consensus {
             return Err(ConsensusError::General("past pruning points do not form a valid chain"));
         }
 
+        if validate_pruning_points_against_checkpoints(&self.config.checkpoints, &self.pruning_point_headers()).is_err() {
+            return Err(ConsensusError::General("past pruning points contradict the configured checkpoints"));
+        }
+
         Ok(())
     }
 
@@ -844,10 +988,158 @@ impl ConsensusApi for Consensus {
             .collect::<ConsensusResult<Vec<_>>>()
     }
 
+    fn get_blocks_accepted_transaction_ids(&self, hashes: &[Hash]) -> ConsensusResult<Vec<Vec<TransactionId>>> {
+        hashes
+            .iter()
+            .copied()
+            .zip(self.acceptance_data_store.iter_uncached(hashes.iter().copied()))
+            .map(|(hash, acceptance_data)| {
+                let acceptance_data = acceptance_data.unwrap_option().ok_or(ConsensusError::MissingData(hash))?;
+                Ok(acceptance_data.iter().flat_map(|x| x.accepted_transactions.iter().map(|tx| tx.transaction_id)).collect())
+            })
+            .collect()
+    }
+
     fn is_chain_block(&self, hash: Hash) -> ConsensusResult<bool> {
         self.is_chain_ancestor_of(hash, self.get_sink())
     }
 
+    fn get_outpoint_spend_status(&self, outpoint: TransactionOutpoint, search_depth: u64) -> ConsensusResult<OutpointSpendStatus> {
+        // We need consistency between the pruning point, selected chain and block data reads
+        let _guard = self.pruning_lock.blocking_read();
+
+        // Note: the virtual stores lock is released before acquiring the selected chain lock in order to avoid nesting
+        // the two, hence the UTXO entry might reflect a slightly more recent virtual state than the searched chain
+        let utxo_entry = self.virtual_stores.read().utxo_set.get(&outpoint).unwrap_option().map(|entry| entry.as_ref().clone());
+        let mut spenders = Vec::new();
+        if search_depth == 0 {
+            return Ok(OutpointSpendStatus { utxo_entry, spenders });
+        }
+
+        let pruning_point = self.pruning_point_store.read().pruning_point().unwrap();
+        let sc_read = self.storage.selected_chain_store.read();
+        let high_index = sc_read.get_tip().unwrap().0;
+        // Same as in `get_chain_block_samples`, we prefer not crashing if the pruning point is missing from the selected chain store
+        let low_index =
+            sc_read.get_by_hash(pruning_point).unwrap_option().unwrap_or(high_index).max(high_index.saturating_sub(search_depth - 1));
+
+        for index in (low_index..=high_index).rev() {
+            let chain_block = sc_read.get_by_index(index).expect("store lock is acquired");
+            // The acceptance data of the pruning point might be missing when syncing from a pruning point
+            let Some(acceptance_data) = self.acceptance_data_store.get(chain_block).unwrap_option() else {
+                continue;
+            };
+            for mergeset_block_data in acceptance_data.iter() {
+                let Some(transactions) = self.block_transactions_store.get(mergeset_block_data.block_hash).unwrap_option() else {
+                    continue;
+                };
+                // Skip the coinbase transaction which has no inputs
+                for (index_within_block, tx) in transactions.iter().enumerate().skip(1) {
+                    if tx.inputs.iter().any(|input| input.previous_outpoint == outpoint) {
+                        let is_accepted = mergeset_block_data
+                            .accepted_transactions
+                            .iter()
+                            .any(|entry| entry.index_within_block as usize == index_within_block);
+                        spenders.push(OutpointSpender {
+                            transaction_id: tx.id(),
+                            block_hash: mergeset_block_data.block_hash,
+                            merging_block_hash: chain_block,
+                            is_accepted,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(OutpointSpendStatus { utxo_entry, spenders })
+    }
+
+    fn get_acceptance_proof(&self, chain_block: Hash, transaction_id: TransactionId) -> ConsensusResult<AcceptanceProof> {
+        // We need consistency between the chain membership check and the acceptance data read
+        let _guard = self.pruning_lock.blocking_read();
+        self.validate_block_exists(chain_block)?;
+        if !self.services.reachability_service.is_chain_ancestor_of(chain_block, self.get_sink()) {
+            return Err(ConsensusError::NotChainBlock(chain_block));
+        }
+        let acceptance_data = self.get_block_acceptance_data(chain_block)?;
+
+        // The acceptance data of the selected parent includes its coinbase transaction, so the accepted
+        // ids form the very same sorted leaf set committed to by `accepted_id_merkle_root`
+        let mut accepted_ids = acceptance_data
+            .iter()
+            .flat_map(|mergeset_block_data| mergeset_block_data.accepted_transactions.iter().map(|entry| entry.transaction_id))
+            .collect_vec();
+        accepted_ids.sort();
+        let leaf_index = accepted_ids
+            .binary_search(&transaction_id)
+            .map_err(|_| ConsensusError::TransactionNotAccepted(transaction_id, chain_block))?;
+        let path = kaspa_merkle::calc_merkle_path(accepted_ids.iter().copied(), leaf_index).expect("leaf index is in range");
+        Ok(AcceptanceProof { leaf_index: leaf_index as u32, path })
+    }
+
+    fn get_spent_utxo_entry(&self, chain_block: Hash, transaction_id: TransactionId, input_index: u32) -> ConsensusResult<UtxoEntry> {
+        // We need consistency between the chain membership check and the spent entries read
+        let _guard = self.pruning_lock.blocking_read();
+        self.validate_block_exists(chain_block)?;
+        if !self.services.reachability_service.is_chain_ancestor_of(chain_block, self.get_sink()) {
+            return Err(ConsensusError::NotChainBlock(chain_block));
+        }
+        let spent_utxo_entries = self
+            .storage
+            .spent_utxo_entries_store
+            .get(chain_block)
+            .unwrap_option()
+            .ok_or(ConsensusError::SpentUtxoEntriesNotStored(chain_block))?;
+        let accepted_tx = spent_utxo_entries
+            .iter()
+            .find(|accepted_tx| accepted_tx.transaction_id == transaction_id)
+            .ok_or(ConsensusError::TransactionNotAccepted(transaction_id, chain_block))?;
+        accepted_tx.entries.get(input_index as usize).cloned().ok_or(ConsensusError::InputIndexOutOfRange(transaction_id, input_index))
+    }
+
+    fn expected_coinbase_amount(&self, chain_block: Hash) -> ConsensusResult<CoinbaseAmounts> {
+        // We need consistency between the chain membership check and the block data reads
+        let _guard = self.pruning_lock.blocking_read();
+        self.validate_block_exists(chain_block)?;
+        if !self.services.reachability_service.is_chain_ancestor_of(chain_block, self.get_sink()) {
+            return Err(ConsensusError::NotChainBlock(chain_block));
+        }
+        let coinbase_total = |hash: Hash| -> ConsensusResult<(u64, u64)> {
+            let transactions = self.block_transactions_store.get(hash).unwrap_option().ok_or(ConsensusError::BlockNotFound(hash))?;
+            let coinbase = transactions.first().ok_or(ConsensusError::MissingData(hash))?;
+            let subsidy = self
+                .services
+                .coinbase_manager
+                .deserialize_coinbase_payload(&coinbase.payload)
+                .map_err(|_| ConsensusError::InvalidBlock(hash))?
+                .subsidy;
+            Ok((subsidy, coinbase.outputs.iter().map(|output| output.value).sum()))
+        };
+
+        // Chain blocks are UTXO valid, so their coinbase pays exactly the expected total
+        let (_, total) = coinbase_total(chain_block)?;
+        let ghostdag_data = self.ghostdag_primary_store.get_data(chain_block).unwrap();
+        let mergeset_non_daa = self.daa_excluded_store.get_mergeset_non_daa(chain_block).unwrap_option().unwrap_or_default();
+        let mut subsidy = 0;
+        for merged in ghostdag_data.unordered_mergeset().filter(|h| !mergeset_non_daa.contains(h)) {
+            subsidy += coinbase_total(merged)?.0;
+        }
+        Ok(CoinbaseAmounts::new(subsidy, total - subsidy))
+    }
+
+    fn expected_virtual_coinbase_amount(&self) -> CoinbaseAmounts {
+        let virtual_state = self.lkg_virtual_state.load();
+        self.services.coinbase_manager.expected_coinbase_amounts(
+            &virtual_state.ghostdag_data,
+            &virtual_state.mergeset_rewards,
+            &virtual_state.mergeset_non_daa,
+        )
+    }
+
+    fn get_disqualification_reason(&self, hash: Hash) -> Option<RuleError> {
+        self.virtual_processor.disqualification_reason(hash)
+    }
+
     fn get_missing_block_body_hashes(&self, high: Hash) -> ConsensusResult<Vec<Hash>> {
         let _guard = self.pruning_lock.blocking_read();
         self.validate_block_exists(high)?;
@@ -917,6 +1209,23 @@ impl ConsensusApi for Consensus {
         }
     }
 
+    fn get_block_difficulty_context(&self, hash: Hash) -> ConsensusResult<BlockDifficultyContext> {
+        if let Some(context) = self.difficulty_context_cache.get(&hash) {
+            return Ok(context);
+        }
+        let _guard = self.pruning_lock.blocking_read();
+        self.validate_block_exists(hash)?;
+        let bits = self.headers_store.get_bits(hash).unwrap();
+        let ghostdag_data = self.ghostdag_primary_store.get_data(hash).unwrap_option().ok_or(ConsensusError::MissingData(hash))?;
+        let context = BlockDifficultyContext {
+            target: Uint256::from_compact_target_bits(bits),
+            work: calc_work(bits),
+            network_hashes_per_second: self.estimate_daa_window_hashes_per_second(&ghostdag_data),
+        };
+        self.difficulty_context_cache.insert(hash, context);
+        Ok(context)
+    }
+
     fn are_pruning_points_violating_finality(&self, pp_list: PruningPointsList) -> bool {
         self.virtual_processor.are_pruning_points_violating_finality(pp_list)
     }
@@ -0,0 +1,678 @@
+//!
+//! Detection of damaged consensus stores and recovery of the derivable ones.
+//!
+//! Every store of the consensus database is classified as either a source of truth or derivable (see
+//! [`ConsensusStoreKind::class`]). A derivable store found damaged is quarantined, then dropped and re-derived
+//! from its source stores, while damage to a source of truth store can only be fixed by a resync.
+//!
+
+use crate::{
+    config::Config,
+    model::stores::{
+        acceptance_data::{AcceptanceDataStoreReader, DbAcceptanceDataStore},
+        block_transactions::{BlockTransactionsStoreReader, DbBlockTransactionsStore},
+        children::DbChildrenStore,
+        daa::{DaaStoreReader, DbDaaStore},
+        depth::{DbDepthStore, DepthStoreReader},
+        ghostdag::{CompactGhostdagData, DbGhostdagStore, GhostdagData, GhostdagStoreReader},
+        headers::{CompactHeaderData, DbHeadersStore, HeaderStoreReader, HeaderWithBlockLevel},
+        headers_selected_tip::{DbHeadersSelectedTipStore, HeadersSelectedTipStoreReader},
+        past_pruning_points::{DbPastPruningPointsStore, PastPruningPointsStoreReader},
+        pruning::{DbPruningStore, PruningStoreReader},
+        pruning_utxoset::PruningUtxosetStores,
+        quarantine::{ConsensusStoreKind, DbQuarantineStore, StoreClass},
+        reachability::{DbReachabilityStore, ReachabilityData, ReachabilityStoreReader},
+        relations::{DbRelationsStore, RelationsStoreReader},
+        selected_chain::{DbSelectedChainStore, SelectedChainStore, SelectedChainStoreReader},
+        statuses::{DbStatusesStore, StatusesStoreReader},
+        tips::{DbTipsStore, TipsStoreReader},
+        utxo_diffs::{DbUtxoDiffsStore, UtxoDiffsStoreReader},
+        utxo_multisets::{DbUtxoMultisetsStore, UtxoMultisetsStoreReader},
+        virtual_state::VirtualState,
+        DB,
+    },
+    processes::ghostdag::ordering::SortableBlock,
+};
+use itertools::Itertools;
+use kaspa_consensus_core::{
+    acceptance_data::AcceptanceData, blockhash::ORIGIN, blockstatus::BlockStatus, tx::Transaction, tx::UtxoEntry,
+    utxo::utxo_diff::UtxoDiff, BlockHashSet, BlockLevel, ChainPath,
+};
+use kaspa_core::{info, warn};
+use kaspa_database::prelude::{CachePolicy, CachedDbItem, StoreError, StoreResult};
+use kaspa_database::registry::DatabaseStorePrefixes;
+use kaspa_hashes::{Hash, HASH_SIZE};
+use kaspa_math::Uint3072;
+use rocksdb::{Direction, IterateBounds, IteratorMode, ReadOptions, WriteBatch};
+use serde::de::DeserializeOwned;
+use std::{collections::HashMap, fmt::Display, sync::Arc};
+use thiserror::Error;
+
+/// Count of entries written per batch when rebuilding a store
+const REBUILD_BATCH_SIZE: usize = 10_000;
+
+/// Count of entries rebuilt between two progress logs
+const REBUILD_PROGRESS_INTERVAL: usize = 500_000;
+
+/// A damaged store, along with the reason it is considered damaged
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoreFinding {
+    pub store: ConsensusStoreKind,
+    pub reason: String,
+}
+
+impl StoreFinding {
+    pub fn new(store: ConsensusStoreKind, reason: impl Display) -> Self {
+        Self { store, reason: reason.to_string() }
+    }
+}
+
+impl Display for StoreFinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the {} store is damaged: {}", self.store, self.reason)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum RecoveryError {
+    #[error("source of truth consensus stores are damaged, the node must be resynced ({})", .0.iter().join("; "))]
+    SourceOfTruthDamaged(Vec<StoreFinding>),
+
+    #[error("failed rebuilding the {0} store: {1}")]
+    Rebuild(ConsensusStoreKind, String),
+
+    #[error(transparent)]
+    Store(#[from] StoreError),
+}
+
+pub type RecoveryResult<T> = std::result::Result<T, RecoveryError>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckDepth {
+    /// Checks the entries of the pruning point, the headers selected tip and the sink, which is cheap enough
+    /// to be done on every startup
+    Quick,
+    /// Decodes every entry of every store and cross-checks the derivable stores with their sources
+    Full,
+}
+
+/// Checks the stores of the consensus database `db`, returning the damaged ones
+pub fn check_stores(db: Arc<DB>, config: &Config, depth: CheckDepth) -> Vec<StoreFinding> {
+    let mut checker = StoresChecker::new(db, config);
+    checker.check_anchors();
+    if depth == CheckDepth::Full {
+        checker.check_entries();
+    }
+    checker.findings.into_iter().unique().collect()
+}
+
+/// Recovers the damaged derivable stores of the consensus database `db`, to be called before the consensus is
+/// instantiated. The stores found damaged by a quick check are quarantined, then all quarantined stores are rebuilt.
+/// Returns the rebuilt stores, or an error if a source of truth store is damaged, in which case nothing is rebuilt.
+pub fn recover_consensus_stores(db: Arc<DB>, config: &Config) -> RecoveryResult<Vec<ConsensusStoreKind>> {
+    let (derivable, source_of_truth): (Vec<_>, Vec<_>) = check_stores(db.clone(), config, CheckDepth::Quick)
+        .into_iter()
+        .partition(|finding| finding.store.class() == StoreClass::Derivable);
+    if !source_of_truth.is_empty() {
+        return Err(RecoveryError::SourceOfTruthDamaged(source_of_truth));
+    }
+
+    let mut quarantine_store = DbQuarantineStore::new(db.clone());
+    for finding in derivable {
+        warn!("{}, quarantining it for a rebuild", finding);
+        quarantine_store.insert(finding.store)?;
+    }
+
+    let quarantined = quarantine_store.get()?;
+    for store in quarantined.iter().copied() {
+        info!("Rebuilding the {} store", store);
+        rebuild_store(&db, store)?;
+        quarantine_store.remove(store)?;
+        info!("The {} store was rebuilt", store);
+    }
+    Ok(quarantined)
+}
+
+/// Marks `store` as damaged so that it gets rebuilt on next startup, failing if the store is a source of truth
+pub fn quarantine_store(db: Arc<DB>, store: ConsensusStoreKind) -> RecoveryResult<()> {
+    if store.class() == StoreClass::SourceOfTruth {
+        return Err(RecoveryError::SourceOfTruthDamaged(vec![StoreFinding::new(store, "reported damaged at runtime")]));
+    }
+    DbQuarantineStore::new(db).insert(store)?;
+    Ok(())
+}
+
+/// Iterates the raw entries under `prefix`, returning the keys stripped from the prefix
+fn prefix_iterator<'a>(db: &'a DB, prefix: &'a [u8]) -> impl Iterator<Item = Result<(Box<[u8]>, Box<[u8]>), rocksdb::Error>> + 'a {
+    let mut read_opts = ReadOptions::default();
+    read_opts.set_iterate_range(rocksdb::PrefixRange(prefix));
+    db.iterator_opt(IteratorMode::From(prefix, Direction::Forward), read_opts)
+        .map(move |result| result.map(|(key, value)| (key[prefix.len()..].into(), value)))
+}
+
+fn prefix_key(prefix: &[u8], suffix: &[u8]) -> Vec<u8> {
+    prefix.iter().chain(suffix).copied().collect()
+}
+
+/// Reads the virtual state without going through [`DbVirtualStateStore`], which panics on a damaged entry
+fn read_virtual_state(db: Arc<DB>) -> StoreResult<Arc<VirtualState>> {
+    CachedDbItem::new(db, DatabaseStorePrefixes::VirtualState.into()).read()
+}
+
+fn not_found_as_none<T>(result: StoreResult<T>) -> StoreResult<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(StoreError::KeyNotFound(_)) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+struct StoresChecker {
+    db: Arc<DB>,
+    max_block_level: BlockLevel,
+    findings: Vec<StoreFinding>,
+}
+
+impl StoresChecker {
+    fn new(db: Arc<DB>, config: &Config) -> Self {
+        Self { db, max_block_level: config.params.max_block_level, findings: vec![] }
+    }
+
+    fn report(&mut self, store: ConsensusStoreKind, reason: impl Display) {
+        self.findings.push(StoreFinding::new(store, reason));
+    }
+
+    /// Returns the value of `result`, reporting `store` as damaged on failure
+    fn check<T>(&mut self, store: ConsensusStoreKind, entry: impl Display, result: StoreResult<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.report(store, format!("failed reading {entry}: {err}"));
+                None
+            }
+        }
+    }
+
+    /// Like [`Self::check`], for entries which might not exist
+    fn check_optional<T>(&mut self, store: ConsensusStoreKind, entry: impl Display, result: StoreResult<T>) -> Option<T> {
+        let result = not_found_as_none(result);
+        self.check(store, entry, result).flatten()
+    }
+
+    /// Checks the entries related to the pruning point, the headers selected tip and the sink
+    fn check_anchors(&mut self) {
+        use ConsensusStoreKind as S;
+        let db = self.db.clone();
+
+        let pruning_store = DbPruningStore::new(db.clone());
+        // With no pruning point the consensus was never initialized, so there is nothing to check
+        let Some(pruning_info) = self.check_optional(S::Pruning, "the pruning point", pruning_store.get()) else {
+            return;
+        };
+        let pruning_point = pruning_info.pruning_point;
+        self.check_optional(S::Pruning, "the history root", pruning_store.history_root());
+        let past_pruning_points_store = DbPastPruningPointsStore::new(db.clone(), CachePolicy::Empty);
+        self.check(S::PastPruningPoints, "the current pruning point", past_pruning_points_store.get(pruning_info.index));
+        self.check_optional(
+            S::PruningUtxoset,
+            "the pruning UTXO set position",
+            PruningUtxosetStores::new(db.clone(), CachePolicy::Empty).utxoset_position(),
+        );
+
+        let headers_selected_tip = self
+            .check_optional(S::HeadersSelectedTip, "the headers selected tip", DbHeadersSelectedTipStore::new(db.clone()).get())
+            .map(|block| block.hash);
+        let sink = self
+            .check_optional(S::VirtualState, "the virtual state", read_virtual_state(db.clone()))
+            .map(|virtual_state| virtual_state.ghostdag_data.selected_parent);
+
+        let headers_store = DbHeadersStore::new(db.clone(), CachePolicy::Empty, CachePolicy::Empty);
+        let ghostdag_store = DbGhostdagStore::new(db.clone(), 0, CachePolicy::Empty, CachePolicy::Empty);
+        let statuses_store = DbStatusesStore::new(db.clone(), CachePolicy::Empty);
+        let reachability_store = DbReachabilityStore::new(db.clone(), CachePolicy::Empty, CachePolicy::Empty);
+        let relations_store = DbRelationsStore::new(db.clone(), 0, CachePolicy::Empty, CachePolicy::Empty);
+        // The headers selected tip is origin until the genesis is processed
+        for block in [Some(pruning_point), headers_selected_tip, sink].into_iter().flatten().filter(|&block| block != ORIGIN).unique()
+        {
+            if let Some(header) = self.check(S::Headers, format_args!("the header of {block}"), headers_store.get_header(block)) {
+                let compact = headers_store.get_compact_header_data(block);
+                if let Some(compact) = self.check(S::HeadersCompact, format_args!("the compact header of {block}"), compact) {
+                    if compact != CompactHeaderData::from(header.as_ref()) {
+                        self.report(S::HeadersCompact, format!("the compact header of {block} does not match its header"));
+                    }
+                }
+            }
+            if let Some(data) = self.check(S::Ghostdag, format_args!("the GHOSTDAG data of {block}"), ghostdag_store.get_data(block)) {
+                let compact = ghostdag_store.get_compact_data(block);
+                if let Some(compact) = self.check(S::GhostdagCompact, format_args!("the compact GHOSTDAG data of {block}"), compact) {
+                    if compact != CompactGhostdagData::from(data.as_ref()) {
+                        self.report(
+                            S::GhostdagCompact,
+                            format!("the compact GHOSTDAG data of {block} does not match its GHOSTDAG data"),
+                        );
+                    }
+                }
+            }
+            self.check(S::Statuses, format_args!("the status of {block}"), statuses_store.get(block));
+            self.check(S::Reachability, format_args!("the reachability data of {block}"), reachability_store.get_interval(block));
+            if let Some(parents) =
+                self.check(S::RelationsParents, format_args!("the parents of {block}"), relations_store.get_parents(block))
+            {
+                self.check_children(&relations_store, block, &parents);
+            }
+        }
+        self.check(S::Reachability, "the reindex root", reachability_store.get_reindex_root());
+
+        let Some(sink) = sink else {
+            return;
+        };
+        self.check(S::Tips, "the tips", DbTipsStore::new(db.clone()).get());
+        self.check(
+            S::BlockTransactions,
+            format_args!("the transactions of {sink}"),
+            DbBlockTransactionsStore::new(db.clone(), CachePolicy::Empty).get(sink),
+        );
+        let selected_chain_store = DbSelectedChainStore::new(db.clone(), CachePolicy::Empty);
+        if let Some((_, tip)) = self.check(S::SelectedChain, "the selected chain tip", selected_chain_store.get_tip()) {
+            if tip != sink {
+                self.report(S::SelectedChain, format!("the selected chain tip {tip} is not the sink {sink}"));
+            }
+        }
+        let pruning_point_index = selected_chain_store.get_by_hash(pruning_point);
+        if let Some(index) = self.check(S::SelectedChain, "the selected chain index of the pruning point", pruning_point_index) {
+            if self.check(
+                S::SelectedChain,
+                format_args!("the selected chain block at index {index}"),
+                selected_chain_store.get_by_index(index),
+            ) != Some(pruning_point)
+            {
+                self.report(S::SelectedChain, "the selected chain indexes of the pruning point do not match");
+            }
+        }
+
+        // The UTXO related data of the pruning point might not be held when the sink is the pruning point
+        if sink != pruning_point {
+            let entry = format!("the data of {sink}");
+            self.check(S::AcceptanceData, &entry, DbAcceptanceDataStore::new(db.clone(), CachePolicy::Empty).get(sink));
+            self.check(S::UtxoDiffs, &entry, DbUtxoDiffsStore::new(db.clone(), CachePolicy::Empty).get(sink));
+            self.check(S::UtxoMultisets, &entry, DbUtxoMultisetsStore::new(db.clone(), CachePolicy::Empty).get(sink));
+            self.check(S::DaaExcluded, &entry, DbDaaStore::new(db.clone(), CachePolicy::Empty).get_mergeset_non_daa(sink));
+            self.check(S::BlockDepth, &entry, DbDepthStore::new(db, CachePolicy::Empty).merge_depth_root(sink));
+        }
+    }
+
+    /// Checks `block` is a child of each of its `parents` still held in the store
+    fn check_children(&mut self, relations_store: &DbRelationsStore, block: Hash, parents: &[Hash]) {
+        if let Some(finding) = find_missing_child(relations_store, block, parents) {
+            self.findings.push(finding);
+        }
+    }
+
+    /// Decodes every entry under `prefix` as a `T`, passing it to `visit` along with its key stripped from the prefix.
+    /// Stops at the first entry failing to decode, reporting `store` as damaged, or for which `visit` returns a finding.
+    fn for_each_entry<T: DeserializeOwned>(
+        &mut self,
+        store: ConsensusStoreKind,
+        prefix: &[u8],
+        mut visit: impl FnMut(&[u8], T) -> Option<StoreFinding>,
+    ) -> usize {
+        let db = self.db.clone();
+        let mut count = 0;
+        for entry in prefix_iterator(&db, prefix) {
+            let (key, value) = match entry {
+                Ok(entry) => entry,
+                Err(err) => {
+                    self.report(store, format!("failed iterating the entries: {err}"));
+                    break;
+                }
+            };
+            let value = match bincode::deserialize::<T>(&value) {
+                Ok(value) => value,
+                Err(err) => {
+                    self.report(store, format!("failed decoding entry {}: {err}", faster_hex::hex_string(&key)));
+                    break;
+                }
+            };
+            count += 1;
+            if let Some(finding) = visit(&key, value) {
+                self.findings.push(finding);
+                break;
+            }
+        }
+        count
+    }
+
+    fn decode_entries<T: DeserializeOwned>(&mut self, store: ConsensusStoreKind, prefix: DatabaseStorePrefixes) {
+        self.for_each_entry::<T>(store, prefix.as_ref(), |_, _| None);
+    }
+
+    fn decode_item<T: DeserializeOwned>(&mut self, store: ConsensusStoreKind, prefix: DatabaseStorePrefixes) {
+        match self.db.get_pinned(prefix) {
+            Ok(Some(value)) => {
+                if let Err(err) = bincode::deserialize::<T>(&value) {
+                    self.report(store, format!("failed decoding its item: {err}"));
+                }
+            }
+            Ok(None) => {}
+            Err(err) => self.report(store, format!("failed reading its item: {err}")),
+        }
+    }
+
+    /// Decodes the compact entry matching each entry of a source store, checking it matches `derive(entry)`
+    fn check_compact_entries<T: DeserializeOwned, C: DeserializeOwned + PartialEq>(
+        &mut self,
+        source: ConsensusStoreKind,
+        compact: ConsensusStoreKind,
+        derive: impl Fn(&T) -> C,
+    ) {
+        let db = self.db.clone();
+        let compact_prefix = compact.prefixes()[0];
+        let count = self.for_each_entry::<T>(source, source.prefixes()[0].as_ref(), |key, entry| {
+            let reason = match db.get_pinned(prefix_key(compact_prefix.as_ref(), key)) {
+                Ok(Some(value)) => match bincode::deserialize::<C>(&value) {
+                    Ok(value) if value == derive(&entry) => return None,
+                    Ok(_) => format!("entry {} does not match its source", faster_hex::hex_string(key)),
+                    Err(err) => format!("failed decoding entry {}: {err}", faster_hex::hex_string(key)),
+                },
+                Ok(None) => format!("entry {} is missing", faster_hex::hex_string(key)),
+                Err(err) => format!("failed reading entry {}: {err}", faster_hex::hex_string(key)),
+            };
+            Some(StoreFinding::new(compact, reason))
+        });
+        let compact_count = prefix_iterator(&db, compact_prefix.as_ref()).count();
+        if compact_count != count && !self.findings.iter().any(|finding| finding.store == source || finding.store == compact) {
+            self.report(compact, format!("it holds {compact_count} entries while its source holds {count}"));
+        }
+    }
+
+    /// Decodes every entry of every store and cross-checks the derivable stores with their sources
+    fn check_entries(&mut self) {
+        use ConsensusStoreKind as S;
+        use DatabaseStorePrefixes as P;
+
+        self.check_compact_entries::<HeaderWithBlockLevel, CompactHeaderData>(S::Headers, S::HeadersCompact, |header| {
+            header.header.as_ref().into()
+        });
+        self.check_compact_entries::<GhostdagData, CompactGhostdagData>(S::Ghostdag, S::GhostdagCompact, |data| data.into());
+
+        // The children relations must be exactly the inverse of the parent relations
+        let db = self.db.clone();
+        let relations_stores = (0..=self.max_block_level)
+            .map(|level| DbRelationsStore::new(db.clone(), level, CachePolicy::Empty, CachePolicy::Empty))
+            .collect_vec();
+        self.for_each_entry::<Vec<Hash>>(S::RelationsParents, P::RelationsParents.as_ref(), |key, parents| {
+            let (level, block) = (key[0], Hash::from_slice(&key[1..]));
+            find_missing_child(relations_stores.get(level as usize)?, block, &parents)
+        });
+        // Children entries are keyed by level, parent and child, with no value
+        for entry in prefix_iterator(&db, P::RelationsChildren.as_ref()) {
+            let reason = match entry {
+                Ok((key, _)) if key.len() == 1 + 2 * HASH_SIZE => {
+                    let (level, parent, child) =
+                        (key[0], Hash::from_slice(&key[1..=HASH_SIZE]), Hash::from_slice(&key[1 + HASH_SIZE..]));
+                    match relations_stores.get(level as usize).map(|relations_store| relations_store.get_parents(child)) {
+                        Some(Ok(parents)) if parents.contains(&parent) => continue,
+                        Some(Err(err)) if !matches!(err, StoreError::KeyNotFound(_)) => {
+                            self.report(S::RelationsParents, format!("failed reading the parents of {child}: {err}"));
+                            break;
+                        }
+                        _ => format!("{child} is a child of {parent} while not having it as parent"),
+                    }
+                }
+                Ok((key, _)) => format!("entry {} has an invalid key", faster_hex::hex_string(&key)),
+                Err(err) => format!("failed iterating the entries: {err}"),
+            };
+            self.report(S::RelationsChildren, reason);
+            break;
+        }
+
+        self.check_selected_chain_entries();
+
+        self.decode_entries::<ReachabilityData>(S::Reachability, P::Reachability);
+        self.for_each_entry::<Vec<Hash>>(
+            S::Reachability,
+            &prefix_key(P::ReachabilityRelations.as_ref(), P::RelationsParents.as_ref()),
+            |_, _| None,
+        );
+        self.decode_entries::<BlockStatus>(S::Statuses, P::Statuses);
+        self.decode_entries::<Vec<Transaction>>(S::BlockTransactions, P::BlockTransactions);
+        self.decode_entries::<Hash>(S::PastPruningPoints, P::PastPruningPoints);
+        self.decode_entries::<UtxoEntry>(S::PruningUtxoset, P::PruningUtxoset);
+        self.decode_entries::<UtxoEntry>(S::VirtualState, P::VirtualUtxoset);
+        self.decode_entries::<AcceptanceData>(S::AcceptanceData, P::AcceptanceData);
+        self.decode_entries::<UtxoDiff>(S::UtxoDiffs, P::UtxoDiffs);
+        self.decode_entries::<Uint3072>(S::UtxoMultisets, P::UtxoMultisets);
+        self.decode_entries::<BlockHashSet>(S::DaaExcluded, P::NonDaaMergeset);
+        // The depth store entries are pairs of merge depth root and finality point
+        self.decode_entries::<(Hash, Hash)>(S::BlockDepth, P::BlockDepth);
+        self.decode_item::<VirtualState>(S::VirtualState, P::VirtualState);
+        self.decode_item::<SortableBlock>(S::HeadersSelectedTip, P::HeadersSelectedTip);
+    }
+
+    /// Walks the selected chain down from its tip, checking both indexes match and consecutive blocks are linked
+    /// through their selected parent
+    fn check_selected_chain_entries(&mut self) {
+        use ConsensusStoreKind as S;
+        let selected_chain_store = DbSelectedChainStore::new(self.db.clone(), CachePolicy::Empty);
+        let ghostdag_store = DbGhostdagStore::new(self.db.clone(), 0, CachePolicy::Empty, CachePolicy::Empty);
+        let Some((mut index, mut block)) =
+            self.check_optional(S::SelectedChain, "the selected chain tip", selected_chain_store.get_tip())
+        else {
+            return;
+        };
+        while index > 0 {
+            match selected_chain_store.get_by_hash(block) {
+                Ok(block_index) if block_index == index => {}
+                Ok(block_index) => {
+                    return self.report(S::SelectedChain, format!("{block} is at index {index} but indexed at {block_index}"))
+                }
+                Err(err) => return self.report(S::SelectedChain, format!("failed reading the index of {block}: {err}")),
+            }
+            let previous = match not_found_as_none(selected_chain_store.get_by_index(index - 1)) {
+                Ok(Some(previous)) => previous,
+                Ok(None) => return, // The chain was pruned below this point
+                Err(err) => return self.report(S::SelectedChain, format!("failed reading the block at index {}: {err}", index - 1)),
+            };
+            match ghostdag_store.get_selected_parent(block) {
+                Ok(selected_parent) if selected_parent == previous => {}
+                Ok(_) => return self.report(S::SelectedChain, format!("the block preceding {block} is not its selected parent")),
+                Err(err) => return self.report(S::Ghostdag, format!("failed reading the selected parent of {block}: {err}")),
+            }
+            (index, block) = (index - 1, previous);
+        }
+    }
+}
+
+/// Returns a finding if `block` is missing from the children of one of its `parents` held in the store
+fn find_missing_child(relations_store: &DbRelationsStore, block: Hash, parents: &[Hash]) -> Option<StoreFinding> {
+    parents.iter().copied().find_map(|parent| {
+        let reason = match relations_store.has(parent) {
+            Ok(false) => return None, // The parent was pruned
+            Ok(true) => match relations_store.get_children(parent) {
+                Ok(children) if children.read().contains(&block) => return None,
+                Ok(_) => format!("{block} is missing from the children of {parent}"),
+                Err(err) => format!("failed reading the children of {parent}: {err}"),
+            },
+            Err(err) => format!("failed reading the parents of {parent}: {err}"),
+        };
+        Some(StoreFinding::new(ConsensusStoreKind::RelationsChildren, reason))
+    })
+}
+
+/// Flushes `batch` once it holds enough entries, logging the progress of the rebuild
+fn flush_rebuild_batch(db: &DB, store: ConsensusStoreKind, batch: &mut WriteBatch, count: usize, force: bool) -> RecoveryResult<()> {
+    if force || batch.len() >= REBUILD_BATCH_SIZE {
+        db.write(std::mem::take(batch)).map_err(|err| RecoveryError::Rebuild(store, err.to_string()))?;
+    }
+    if count > 0 && count % REBUILD_PROGRESS_INTERVAL == 0 {
+        info!("Rebuilding the {} store: {} entries processed", store, count);
+    }
+    Ok(())
+}
+
+/// Deletes every entry of `store`
+fn clear_store(db: &DB, store: ConsensusStoreKind) -> RecoveryResult<()> {
+    let mut batch = WriteBatch::default();
+    for prefix in store.prefixes() {
+        let (from, to) = rocksdb::PrefixRange(prefix.as_ref()).into_bounds();
+        batch.delete_range(from.unwrap(), to.unwrap());
+    }
+    db.write(batch).map_err(|err| RecoveryError::Rebuild(store, err.to_string()))
+}
+
+fn rebuild_store(db: &Arc<DB>, store: ConsensusStoreKind) -> RecoveryResult<()> {
+    clear_store(db, store)?;
+    match store {
+        ConsensusStoreKind::HeadersCompact => {
+            rebuild_compact_store::<HeaderWithBlockLevel, CompactHeaderData>(db, ConsensusStoreKind::Headers, store, |header| {
+                header.header.as_ref().into()
+            })
+        }
+        ConsensusStoreKind::GhostdagCompact => {
+            rebuild_compact_store::<GhostdagData, CompactGhostdagData>(db, ConsensusStoreKind::Ghostdag, store, |data| data.into())
+        }
+        ConsensusStoreKind::RelationsChildren => rebuild_children_store(db),
+        ConsensusStoreKind::SelectedChain => rebuild_selected_chain_store(db),
+        _ => Err(RecoveryError::Rebuild(store, "the store is not derivable".to_string())),
+    }
+}
+
+/// Rebuilds `compact` by writing `derive(entry)` for each entry of `source`, keeping the keys
+fn rebuild_compact_store<T: DeserializeOwned, C: serde::Serialize>(
+    db: &DB,
+    source: ConsensusStoreKind,
+    compact: ConsensusStoreKind,
+    derive: impl Fn(&T) -> C,
+) -> RecoveryResult<()> {
+    let compact_prefix = compact.prefixes()[0];
+    let mut batch = WriteBatch::default();
+    let mut count = 0;
+    for entry in prefix_iterator(db, source.prefixes()[0].as_ref()) {
+        let (key, value) = entry.map_err(|err| RecoveryError::Rebuild(compact, err.to_string()))?;
+        let entry = bincode::deserialize::<T>(&value).map_err(|err| RecoveryError::Rebuild(compact, err.to_string()))?;
+        let compact_value = bincode::serialize(&derive(&entry)).map_err(|err| RecoveryError::Rebuild(compact, err.to_string()))?;
+        batch.put(prefix_key(compact_prefix.as_ref(), &key), compact_value);
+        count += 1;
+        flush_rebuild_batch(db, compact, &mut batch, count, false)?;
+    }
+    flush_rebuild_batch(db, compact, &mut batch, count, true)
+}
+
+/// Rebuilds the children of each level by inverting the parent relations
+fn rebuild_children_store(db: &Arc<DB>) -> RecoveryResult<()> {
+    let store = ConsensusStoreKind::RelationsChildren;
+    let mut relations_stores = HashMap::<BlockLevel, DbRelationsStore>::new();
+    let mut children_stores = HashMap::<BlockLevel, DbChildrenStore>::new();
+    let mut batch = WriteBatch::default();
+    let mut count = 0;
+    for entry in prefix_iterator(db, DatabaseStorePrefixes::RelationsParents.as_ref()) {
+        let (key, value) = entry.map_err(|err| RecoveryError::Rebuild(store, err.to_string()))?;
+        let (level, block) = (key[0], Hash::from_slice(&key[1..]));
+        let parents = bincode::deserialize::<Vec<Hash>>(&value).map_err(|err| RecoveryError::Rebuild(store, err.to_string()))?;
+        let relations_store = relations_stores
+            .entry(level)
+            .or_insert_with(|| DbRelationsStore::new(db.clone(), level, CachePolicy::Empty, CachePolicy::Empty));
+        let children_store =
+            children_stores.entry(level).or_insert_with(|| DbChildrenStore::new(db.clone(), level, CachePolicy::Empty));
+        // Like when inserting a block, only parents held in the store are linked to their children
+        for parent in parents {
+            if relations_store.has(parent)? {
+                children_store.insert_batch(&mut batch, parent, block)?;
+            }
+        }
+        count += 1;
+        flush_rebuild_batch(db, store, &mut batch, count, false)?;
+    }
+    flush_rebuild_batch(db, store, &mut batch, count, true)
+}
+
+/// Rebuilds the selected chain by walking the selected parents from the sink down to the pruning point, which
+/// gets index 0
+fn rebuild_selected_chain_store(db: &Arc<DB>) -> RecoveryResult<()> {
+    let store = ConsensusStoreKind::SelectedChain;
+    let Some(pruning_point) = not_found_as_none(DbPruningStore::new(db.clone()).pruning_point())? else {
+        return Ok(());
+    };
+    let Some(virtual_state) = not_found_as_none(read_virtual_state(db.clone()))? else {
+        return Ok(());
+    };
+
+    let ghostdag_store = DbGhostdagStore::new(db.clone(), 0, CachePolicy::Empty, CachePolicy::Empty);
+    let mut chain = vec![];
+    let mut block = virtual_state.ghostdag_data.selected_parent;
+    while block != pruning_point {
+        if block == ORIGIN {
+            return Err(RecoveryError::Rebuild(store, format!("the sink is not in the selected chain future of {pruning_point}")));
+        }
+        chain.push(block);
+        block = ghostdag_store.get_selected_parent(block)?;
+        if chain.len() % REBUILD_PROGRESS_INTERVAL == 0 {
+            info!("Rebuilding the {} store: {} chain blocks processed", store, chain.len());
+        }
+    }
+    chain.reverse();
+
+    let mut selected_chain_store = DbSelectedChainStore::new(db.clone(), CachePolicy::Empty);
+    let mut batch = WriteBatch::default();
+    selected_chain_store.init_with_pruning_point(&mut batch, pruning_point)?;
+    flush_rebuild_batch(db, store, &mut batch, 0, true)?;
+    selected_chain_store.apply_changes(&mut batch, &ChainPath { added: chain, removed: vec![] })?;
+    flush_rebuild_batch(db, store, &mut batch, 0, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::test_consensus::TestConsensus;
+    use kaspa_consensus_core::{
+        api::ConsensusApi,
+        config::{params::MAINNET_PARAMS, ConfigBuilder},
+    };
+    use kaspa_database::{create_temp_db, prelude::ConnBuilder};
+
+    #[tokio::test]
+    async fn test_store_recovery() {
+        let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+        let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        let (sender, _receiver) = async_channel::unbounded();
+        let consensus = TestConsensus::with_db(db.clone(), &config, sender);
+        let wait_handles = consensus.init();
+        let mut parents = vec![config.genesis.hash];
+        for i in 1..=10 {
+            let hash = Hash::from_u64_word(i);
+            consensus.add_utxo_valid_block_with_parents(hash, parents, vec![]).await.unwrap();
+            parents = vec![hash];
+        }
+        let sink = consensus.get_sink();
+        consensus.shutdown(wait_handles);
+        drop(consensus);
+        assert_eq!(check_stores(db.clone(), &config, CheckDepth::Full), vec![]);
+        assert_eq!(recover_consensus_stores(db.clone(), &config).unwrap(), vec![]);
+
+        // Corrupt derivable stores: garbage in the compact header of the sink, and a missing selected chain tip
+        db.put(prefix_key(DatabaseStorePrefixes::HeadersCompact.as_ref(), sink.as_bytes().as_ref()), [1, 2, 3]).unwrap();
+        db.delete(DatabaseStorePrefixes::ChainHighestIndex).unwrap();
+        let findings = check_stores(db.clone(), &config, CheckDepth::Quick);
+        assert!(findings.iter().all(|finding| finding.store.class() == StoreClass::Derivable), "{findings:?}");
+        let mut rebuilt = recover_consensus_stores(db.clone(), &config).unwrap();
+        rebuilt.sort_by_key(|store| store.name());
+        assert_eq!(rebuilt, vec![ConsensusStoreKind::HeadersCompact, ConsensusStoreKind::SelectedChain]);
+        assert_eq!(check_stores(db.clone(), &config, CheckDepth::Full), vec![]);
+        assert_eq!(DbSelectedChainStore::new(db.clone(), CachePolicy::Empty).get_tip().unwrap(), (10, sink));
+
+        // A quarantined store is rebuilt even if no damage is detected, e.g. when resuming an interrupted rebuild
+        quarantine_store(db.clone(), ConsensusStoreKind::RelationsChildren).unwrap();
+        clear_store(&db, ConsensusStoreKind::RelationsChildren).unwrap();
+        assert_eq!(recover_consensus_stores(db.clone(), &config).unwrap(), vec![ConsensusStoreKind::RelationsChildren]);
+        assert_eq!(check_stores(db.clone(), &config, CheckDepth::Full), vec![]);
+
+        // Damage to a source of truth store refuses the recovery and names the store
+        db.put(DatabaseStorePrefixes::VirtualState, [1, 2, 3]).unwrap();
+        let err = recover_consensus_stores(db.clone(), &config).unwrap_err();
+        assert!(
+            matches!(&err, RecoveryError::SourceOfTruthDamaged(findings) if findings[0].store == ConsensusStoreKind::VirtualState)
+        );
+        assert!(err.to_string().contains("the virtual state store is damaged"), "{err}");
+        assert!(quarantine_store(db, ConsensusStoreKind::Headers).is_err());
+    }
+}
@@ -10,6 +10,7 @@ use crate::{
             selected_chain::DbSelectedChainStore, statuses::DbStatusesStore, DB,
         },
     },
+    pipeline::ProcessingCounters,
     processes::{
         block_depth::BlockDepthManager, coinbase::CoinbaseManager, ghostdag::protocol::GhostdagManager, mass::MassCalculator,
         parents_builder::ParentsManager, pruning::PruningPointManager, pruning_proof::PruningProofManager, sync::SyncManager,
@@ -18,7 +19,7 @@ use crate::{
 };
 
 use itertools::Itertools;
-use kaspa_txscript::caches::TxScriptCacheCounters;
+use kaspa_txscript::{caches::TxScriptCacheCounters, metrics::ScriptMetrics};
 use std::sync::{atomic::AtomicBool, Arc};
 
 pub type DbGhostdagManager =
@@ -70,7 +71,9 @@ impl ConsensusServices {
         db: Arc<DB>,
         storage: Arc<ConsensusStorage>,
         config: Arc<Config>,
+        counters: Arc<ProcessingCounters>,
         tx_script_cache_counters: Arc<TxScriptCacheCounters>,
+        script_metrics: Arc<ScriptMetrics>,
         is_consensus_exiting: Arc<AtomicBool>,
     ) -> Arc<Self> {
         let params = &config.params;
@@ -103,6 +106,7 @@ impl ConsensusServices {
             params.legacy_past_median_time_window_size(),
             params.sampled_past_median_time_window_size(),
             params.past_median_time_sample_rate,
+            counters,
         );
         let depth_manager = BlockDepthManager::new(
             params.merge_depth,
@@ -140,12 +144,7 @@ impl ConsensusServices {
             params.target_time_per_block,
         );
 
-        let mass_calculator = MassCalculator::new(
-            params.mass_per_tx_byte,
-            params.mass_per_script_pub_key_byte,
-            params.mass_per_sig_op,
-            params.storage_mass_parameter,
-        );
+        let mass_calculator = MassCalculator::new(params);
 
         let transaction_validator = TransactionValidator::new(
             params.max_tx_inputs,
@@ -156,6 +155,7 @@ impl ConsensusServices {
             params.coinbase_payload_script_public_key_max_len,
             params.coinbase_maturity,
             tx_script_cache_counters,
+            script_metrics,
             mass_calculator.clone(),
             params.storage_mass_activation_daa_score,
         );
@@ -2,8 +2,14 @@ use async_channel::Sender;
 use kaspa_consensus_core::coinbase::MinerData;
 use kaspa_consensus_core::tx::ScriptPublicKey;
 use kaspa_consensus_core::{
-    api::ConsensusApi, block::MutableBlock, blockstatus::BlockStatus, header::Header, merkle::calc_hash_merkle_root,
-    subnets::SUBNETWORK_ID_COINBASE, tx::Transaction,
+    api::ConsensusApi,
+    block::{Block, MutableBlock},
+    blockstatus::BlockStatus,
+    header::Header,
+    merkle::calc_hash_merkle_root,
+    multi_consensus::MultiConsensusStatus,
+    subnets::SUBNETWORK_ID_COINBASE,
+    tx::Transaction,
 };
 use kaspa_consensus_notify::{notification::Notification, root::ConsensusNotificationRoot};
 use kaspa_consensusmanager::{ConsensusFactory, ConsensusInstance, DynConsensusCtl};
@@ -27,12 +33,16 @@ use crate::{
     model::{
         services::reachability::MTReachabilityService,
         stores::{
-            ghostdag::DbGhostdagStore, headers::HeaderStoreReader, pruning::PruningStoreReader, reachability::DbReachabilityStore,
-            virtual_state::VirtualStores, DB,
+            daa::DaaStoreReader, ghostdag::DbGhostdagStore, headers::HeaderStoreReader, pruning::PruningStoreReader,
+            reachability::DbReachabilityStore, virtual_state::VirtualStores, DB,
         },
     },
     params::Params,
-    pipeline::{body_processor::BlockBodyProcessor, virtual_processor::VirtualStateProcessor, ProcessingCounters},
+    pipeline::{
+        body_processor::{BlockBodyProcessor, BodyValidationReport},
+        virtual_processor::VirtualStateProcessor,
+        ProcessingCounters,
+    },
     test_helpers::header_from_precomputed_hash,
 };
 
@@ -58,7 +68,9 @@ impl TestConsensus {
             Default::default(),
             notification_root,
             counters,
+            Default::default(),
             tx_script_cache_counters,
+            Default::default(),
             0,
         ));
         let block_builder = TestBlockBuilder::new(consensus.virtual_processor.clone());
@@ -78,7 +90,9 @@ impl TestConsensus {
             Default::default(),
             notification_root,
             counters,
+            Default::default(),
             tx_script_cache_counters,
+            Default::default(),
             0,
         ));
         let block_builder = TestBlockBuilder::new(consensus.virtual_processor.clone());
@@ -99,7 +113,9 @@ impl TestConsensus {
             Default::default(),
             notification_root,
             counters,
+            Default::default(),
             tx_script_cache_counters,
+            Default::default(),
             0,
         ));
         let block_builder = TestBlockBuilder::new(consensus.virtual_processor.clone());
@@ -184,6 +200,12 @@ impl TestConsensus {
         MutableBlock::from_header(self.build_header_with_parents(hash, parents))
     }
 
+    /// Validates the body of `block` in isolation against all the rules, reporting every violation rather than only
+    /// the first one
+    pub fn validate_body_in_isolation_full(&self, block: &Block) -> BodyValidationReport {
+        self.consensus.body_processor.validate_body_in_isolation_full(block)
+    }
+
     pub fn init(&self) -> Vec<JoinHandle<()>> {
         self.consensus.run_processors()
     }
@@ -216,6 +238,10 @@ impl TestConsensus {
         self.consensus.headers_store.clone()
     }
 
+    pub fn daa_excluded_store(&self) -> Arc<impl DaaStoreReader> {
+        self.consensus.daa_excluded_store.clone()
+    }
+
     pub fn virtual_stores(&self) -> Arc<RwLock<VirtualStores>> {
         self.consensus.virtual_stores.clone()
     }
@@ -291,4 +317,8 @@ impl ConsensusFactory for TestConsensusFactory {
     fn delete_staging_entry(&self) {
         unimplemented!()
     }
+
+    fn consensus_status(&self) -> MultiConsensusStatus {
+        Default::default()
+    }
 }
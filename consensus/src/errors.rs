@@ -1,2 +1,28 @@
 //! Re-exports rule-related errors from consensus core for internal crate usage
 pub(crate) use kaspa_consensus_core::errors::block::*;
+use kaspa_database::prelude::{StoreError, StoreResult};
+use std::fmt::Display;
+
+/// Conversions of store read results into rule results, used by the block processors for propagating
+/// store failures of a specific block instead of panicking
+pub(crate) trait StoreResultRuleExtensions<T> {
+    /// Converts any store error into [`RuleError::StoreFailure`]
+    fn or_store_failure(self, store: &str, key: impl Display) -> BlockProcessResult<T>;
+
+    /// Like [`StoreResultRuleExtensions::or_store_failure`], but a missing key is returned as `None`
+    fn optional_or_store_failure(self, store: &str, key: impl Display) -> BlockProcessResult<Option<T>>;
+}
+
+impl<T> StoreResultRuleExtensions<T> for StoreResult<T> {
+    fn or_store_failure(self, store: &str, key: impl Display) -> BlockProcessResult<T> {
+        self.map_err(|err| RuleError::StoreFailure(store.to_owned(), key.to_string(), err.to_string()))
+    }
+
+    fn optional_or_store_failure(self, store: &str, key: impl Display) -> BlockProcessResult<Option<T>> {
+        match self {
+            Ok(value) => Ok(Some(value)),
+            Err(StoreError::KeyNotFound(_)) => Ok(None),
+            Err(err) => Err(RuleError::StoreFailure(store.to_owned(), key.to_string(), err.to_string())),
+        }
+    }
+}
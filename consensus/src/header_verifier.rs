@@ -0,0 +1,145 @@
+//!
+//! Batch validation of headers for services maintaining header-only mirrors of the DAG, without running a
+//! full consensus instance.
+//!
+//! The [`HeaderChainVerifier`] runs the header rules of the header processor which do not require the
+//! GHOSTDAG data of the headers: the rules checked in isolation, including the proof of work, the
+//! existence of the parents, the difficulty bits and the past median time. The difficulty and past median
+//! time windows are provided by the caller through a [`HeaderParentDataProvider`].
+//!
+//! The DAA score, blue score and blue work of the headers, their pruning point, their indirect parents
+//! and the bounded merge depth all depend on the GHOSTDAG data and are not validated.
+//!
+
+use crate::{
+    errors::{BlockProcessResult, RuleError},
+    params::Params,
+    pipeline::header_processor::header_rules::{
+        check_block_timestamp_in_isolation, check_difficulty_bits, check_header_version, check_median_timestamp, check_parents_limit,
+        check_parents_not_origin, check_pow_and_calc_block_level,
+    },
+    processes::{
+        difficulty::{calc_difficulty_bits, DifficultyBlock},
+        ghostdag::ordering::SortableBlock,
+        past_median_time::{calc_full_past_median_time, calc_sampled_past_median_time},
+    },
+};
+use kaspa_consensus_core::{header::Header, BlockLevel};
+use kaspa_core::time::{DynClock, SystemClock};
+use kaspa_hashes::Hash;
+use std::sync::Arc;
+
+/// Provides the verifier with the ancestors of the headers being verified.
+///
+/// The provider is expected to know the headers of the batch being verified as well, a header sync service
+/// typically storing the headers it received before verifying them.
+pub trait HeaderParentDataProvider {
+    /// Returns the header of `hash`, or `None` if unknown
+    fn get_header(&self, hash: Hash) -> Option<Arc<Header>>;
+
+    /// Returns the headers of the DAA window of `header`, in any order
+    fn difficulty_window(&self, header: &Header) -> BlockProcessResult<Vec<Arc<Header>>>;
+
+    /// Returns the headers of the past median time window of `header`, in any order
+    fn past_median_time_window(&self, header: &Header) -> BlockProcessResult<Vec<Arc<Header>>>;
+}
+
+/// Validates batches of headers against the consensus rules of `Params`, following the same code paths as
+/// the header processor.
+pub struct HeaderChainVerifier<P: HeaderParentDataProvider> {
+    params: Params,
+    provider: P,
+    clock: DynClock,
+}
+
+impl<P: HeaderParentDataProvider> HeaderChainVerifier<P> {
+    pub fn new(params: &Params, provider: P) -> Self {
+        Self { params: params.clone(), provider, clock: SystemClock::new_dyn() }
+    }
+
+    /// Sets the clock the header timestamps are checked against, the system clock by default
+    pub fn with_clock(mut self, clock: DynClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Verifies `headers` in order, returning the index of the first invalid header along with the rule it
+    /// fails
+    pub fn verify_batch(&self, headers: &[Arc<Header>]) -> Result<(), (usize, RuleError)> {
+        headers.iter().enumerate().try_for_each(|(index, header)| self.verify_header(header).map(|_| ()).map_err(|err| (index, err)))
+    }
+
+    /// Verifies a single header and returns its block level
+    pub fn verify_header(&self, header: &Header) -> BlockProcessResult<BlockLevel> {
+        let block_level = self.validate_header_in_isolation(header)?;
+        let selected_parent_daa_score = self.selected_parent(header)?.daa_score;
+        self.check_difficulty(header, selected_parent_daa_score)?;
+        self.check_median_timestamp(header, selected_parent_daa_score)?;
+        Ok(block_level)
+    }
+
+    fn validate_header_in_isolation(&self, header: &Header) -> BlockProcessResult<BlockLevel> {
+        // Matching the header processor, which does not use the tolerance of the sampled windows yet
+        check_header_version(&self.params.header_version_policy, header)?;
+        check_block_timestamp_in_isolation(self.clock.unix_now(), self.params.timestamp_deviation_tolerance(0), header)?;
+        check_parents_limit(self.params.max_block_parents, header)?;
+        check_parents_not_origin(header)?;
+        check_pow_and_calc_block_level(self.params.max_block_level, self.params.skip_proof_of_work, header)
+    }
+
+    /// Returns the header of the selected parent, being the parent with the highest blue work. All the
+    /// parents are required to be known.
+    fn selected_parent(&self, header: &Header) -> BlockProcessResult<Arc<Header>> {
+        let mut missing_parents = Vec::new();
+        let mut selected_parent: Option<Arc<Header>> = None;
+        for &parent in header.direct_parents() {
+            match self.provider.get_header(parent) {
+                None => missing_parents.push(parent),
+                Some(parent) => {
+                    let is_selected = selected_parent.as_ref().map_or(true, |selected| {
+                        SortableBlock::new(parent.hash, parent.blue_work) > SortableBlock::new(selected.hash, selected.blue_work)
+                    });
+                    if is_selected {
+                        selected_parent = Some(parent);
+                    }
+                }
+            }
+        }
+        if !missing_parents.is_empty() {
+            return Err(RuleError::MissingParents(missing_parents));
+        }
+        // The parents limit was checked so there is at least one parent
+        Ok(selected_parent.unwrap())
+    }
+
+    fn sampling(&self, selected_parent_daa_score: u64) -> bool {
+        selected_parent_daa_score >= self.params.sampling_activation_daa_score
+    }
+
+    fn check_difficulty(&self, header: &Header, selected_parent_daa_score: u64) -> BlockProcessResult<()> {
+        let window = self.provider.difficulty_window(header)?;
+        let difficulty_blocks = window
+            .iter()
+            .map(|block| DifficultyBlock::new(block.timestamp, block.bits, SortableBlock::new(block.hash, block.blue_work)))
+            .collect();
+        let window_size = self.params.difficulty_window_size(selected_parent_daa_score);
+        let expected_bits = calc_difficulty_bits(
+            difficulty_blocks,
+            self.params.genesis.bits,
+            self.params.min_difficulty_window_len.min(window_size),
+            self.params.max_difficulty_target.into(),
+            self.params.target_time_per_block(selected_parent_daa_score)
+                * self.params.difficulty_sample_rate(selected_parent_daa_score),
+        );
+        check_difficulty_bits(header, expected_bits)
+    }
+
+    fn check_median_timestamp(&self, header: &Header, selected_parent_daa_score: u64) -> BlockProcessResult<()> {
+        let window_timestamps = self.provider.past_median_time_window(header)?.iter().map(|block| block.timestamp).collect();
+        let past_median_time = match self.sampling(selected_parent_daa_score) {
+            true => calc_sampled_past_median_time(window_timestamps, self.params.genesis.timestamp),
+            false => calc_full_past_median_time(window_timestamps, self.params.genesis.timestamp),
+        };
+        check_median_timestamp(header, past_median_time)
+    }
+}
@@ -6,6 +6,8 @@ pub mod config;
 pub mod consensus;
 pub mod constants;
 pub mod errors;
+#[cfg(feature = "header-verifier")]
+pub mod header_verifier;
 pub mod model;
 pub mod params;
 pub mod pipeline;
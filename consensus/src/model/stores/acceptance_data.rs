@@ -64,6 +64,15 @@ impl DbAcceptanceDataStore {
     pub fn delete_batch(&self, batch: &mut WriteBatch, hash: Hash) -> Result<(), StoreError> {
         self.access.delete(BatchDbWriter::new(batch), hash)
     }
+
+    /// Lazily reads the acceptance data of `hashes`, one block at a time and bypassing the cache, so that bulk
+    /// reads neither hold all the entries in memory nor evict the entries cached for virtual processing
+    pub fn iter_uncached<'a>(
+        &'a self,
+        hashes: impl IntoIterator<Item = Hash> + 'a,
+    ) -> impl Iterator<Item = Result<Arc<AcceptanceData>, StoreError>> + 'a {
+        hashes.into_iter().map(|hash| Ok(self.access.read_uncached(hash)?.0))
+    }
 }
 
 impl AcceptanceDataStoreReader for DbAcceptanceDataStore {
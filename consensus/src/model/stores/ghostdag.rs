@@ -30,7 +30,7 @@ pub struct GhostdagData {
     pub blues_anticone_sizes: HashKTypeMap,
 }
 
-#[derive(Clone, Serialize, Deserialize, Copy)]
+#[derive(Clone, Serialize, Deserialize, Copy, Debug, PartialEq, Eq)]
 pub struct CompactGhostdagData {
     pub blue_score: u64,
     pub blue_work: BlueWorkType,
@@ -41,7 +41,7 @@ pub trait HeaderStore: HeaderStoreReader {
     fn delete(&self, hash: Hash) -> Result<(), StoreError>;
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CompactHeaderData {
     pub daa_score: u64,
     pub timestamp: u64,
@@ -163,7 +163,7 @@ impl HeaderStore for DbHeadersStore {
         let mut batch = WriteBatch::default();
         self.compact_headers_access.write(BatchDbWriter::new(&mut batch), hash, header.as_ref().into())?;
         self.headers_access.write(BatchDbWriter::new(&mut batch), hash, HeaderWithBlockLevel { header, block_level })?;
-        self.db.write(batch)?;
+        self.db.write_critical(batch)?;
         Ok(())
     }
 
@@ -14,8 +14,10 @@ pub mod headers_selected_tip;
 pub mod past_pruning_points;
 pub mod pruning;
 pub mod pruning_utxoset;
+pub mod quarantine;
 pub mod reachability;
 pub mod relations;
+pub mod spent_utxo_entries;
 pub mod statuses;
 pub mod tips;
 pub mod utxo_diffs;
@@ -0,0 +1,185 @@
+use kaspa_database::prelude::{CachedDbItem, DirectDbWriter, StoreError, StoreResult, DB};
+use kaspa_database::registry::DatabaseStorePrefixes;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, sync::Arc};
+
+/// Whether the content of a store can be derived from other stores
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreClass {
+    /// The store holds data which cannot be recovered locally, so damage to it requires a resync
+    SourceOfTruth,
+    /// The store can be dropped and re-derived from source of truth stores
+    Derivable,
+}
+
+/// The stores of the consensus database.
+///
+/// Stores which could only be re-derived by reprocessing blocks (e.g. acceptance data or UTXO diffs) are
+/// classified as source of truth, since rebuilding them amounts to a resync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ConsensusStoreKind {
+    Headers,
+    HeadersCompact,
+    Ghostdag,
+    GhostdagCompact,
+    RelationsParents,
+    RelationsChildren,
+    Reachability,
+    SelectedChain,
+    Statuses,
+    BlockTransactions,
+    Pruning,
+    PastPruningPoints,
+    PruningUtxoset,
+    VirtualState,
+    Tips,
+    HeadersSelectedTip,
+    AcceptanceData,
+    UtxoDiffs,
+    UtxoMultisets,
+    DaaExcluded,
+    BlockDepth,
+    SpentUtxoEntries,
+}
+
+impl ConsensusStoreKind {
+    pub const ALL: [Self; 22] = [
+        Self::Headers,
+        Self::HeadersCompact,
+        Self::Ghostdag,
+        Self::GhostdagCompact,
+        Self::RelationsParents,
+        Self::RelationsChildren,
+        Self::Reachability,
+        Self::SelectedChain,
+        Self::Statuses,
+        Self::BlockTransactions,
+        Self::Pruning,
+        Self::PastPruningPoints,
+        Self::PruningUtxoset,
+        Self::VirtualState,
+        Self::Tips,
+        Self::HeadersSelectedTip,
+        Self::AcceptanceData,
+        Self::UtxoDiffs,
+        Self::UtxoMultisets,
+        Self::DaaExcluded,
+        Self::BlockDepth,
+        Self::SpentUtxoEntries,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Headers => "headers",
+            Self::HeadersCompact => "compact headers",
+            Self::Ghostdag => "GHOSTDAG",
+            Self::GhostdagCompact => "compact GHOSTDAG",
+            Self::RelationsParents => "relations parents",
+            Self::RelationsChildren => "relations children",
+            Self::Reachability => "reachability",
+            Self::SelectedChain => "selected chain",
+            Self::Statuses => "statuses",
+            Self::BlockTransactions => "block transactions",
+            Self::Pruning => "pruning point",
+            Self::PastPruningPoints => "past pruning points",
+            Self::PruningUtxoset => "pruning UTXO set",
+            Self::VirtualState => "virtual state",
+            Self::Tips => "tips",
+            Self::HeadersSelectedTip => "headers selected tip",
+            Self::AcceptanceData => "acceptance data",
+            Self::UtxoDiffs => "UTXO diffs",
+            Self::UtxoMultisets => "UTXO multisets",
+            Self::DaaExcluded => "DAA excluded",
+            Self::BlockDepth => "block depth",
+            Self::SpentUtxoEntries => "spent UTXO entries",
+        }
+    }
+
+    pub fn class(&self) -> StoreClass {
+        match self {
+            Self::HeadersCompact | Self::GhostdagCompact | Self::RelationsChildren | Self::SelectedChain => StoreClass::Derivable,
+            _ => StoreClass::SourceOfTruth,
+        }
+    }
+
+    /// The DB prefixes holding the entries of the store
+    pub fn prefixes(&self) -> &'static [DatabaseStorePrefixes] {
+        use DatabaseStorePrefixes as P;
+        match self {
+            Self::Headers => &[P::Headers],
+            Self::HeadersCompact => &[P::HeadersCompact],
+            Self::Ghostdag => &[P::Ghostdag],
+            Self::GhostdagCompact => &[P::GhostdagCompact],
+            Self::RelationsParents => &[P::RelationsParents],
+            Self::RelationsChildren => &[P::RelationsChildren],
+            Self::Reachability => &[
+                P::Reachability,
+                P::ReachabilityReindexRoot,
+                P::ReachabilityRelations,
+                P::ReachabilityTreeChildren,
+                P::ReachabilityFutureCoveringSet,
+            ],
+            Self::SelectedChain => &[P::ChainHashByIndex, P::ChainIndexByHash, P::ChainHighestIndex],
+            Self::Statuses => &[P::Statuses],
+            Self::BlockTransactions => &[P::BlockTransactions],
+            Self::Pruning => &[P::PruningPoint, P::HistoryRoot],
+            Self::PastPruningPoints => &[P::PastPruningPoints],
+            Self::PruningUtxoset => &[P::PruningUtxoset, P::PruningUtxosetPosition],
+            Self::VirtualState => &[P::VirtualState, P::VirtualUtxoset],
+            Self::Tips => &[P::Tips],
+            Self::HeadersSelectedTip => &[P::HeadersSelectedTip],
+            Self::AcceptanceData => &[P::AcceptanceData],
+            Self::UtxoDiffs => &[P::UtxoDiffs],
+            Self::UtxoMultisets => &[P::UtxoMultisets],
+            Self::DaaExcluded => &[P::NonDaaMergeset],
+            Self::BlockDepth => &[P::BlockDepth],
+            Self::SpentUtxoEntries => &[P::SpentUtxoEntries],
+        }
+    }
+}
+
+impl Display for ConsensusStoreKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A DB implementation of the set of quarantined stores, i.e. the derivable stores found damaged and
+/// waiting to be rebuilt. Persisting the set guarantees an interrupted rebuild is resumed on next startup.
+#[derive(Clone)]
+pub struct DbQuarantineStore {
+    db: Arc<DB>,
+    access: CachedDbItem<Vec<ConsensusStoreKind>>,
+}
+
+impl DbQuarantineStore {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbItem::new(db, DatabaseStorePrefixes::QuarantinedStores.into()) }
+    }
+
+    pub fn get(&self) -> StoreResult<Vec<ConsensusStoreKind>> {
+        match self.access.read() {
+            Ok(stores) => Ok(stores),
+            Err(StoreError::KeyNotFound(_)) => Ok(vec![]),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn insert(&mut self, store: ConsensusStoreKind) -> StoreResult<()> {
+        let mut stores = self.get()?;
+        if !stores.contains(&store) {
+            stores.push(store);
+            self.access.write(DirectDbWriter::new(&self.db), &stores)?;
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, store: ConsensusStoreKind) -> StoreResult<()> {
+        let stores = self.get()?.into_iter().filter(|&s| s != store).collect::<Vec<_>>();
+        if stores.is_empty() {
+            self.access.remove(DirectDbWriter::new(&self.db))
+        } else {
+            self.access.write(DirectDbWriter::new(&self.db), &stores)
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use kaspa_consensus_core::acceptance_data::{AcceptedTxSpentEntries, SpentUtxoEntries};
+use kaspa_consensus_core::tx::UtxoEntry;
+use kaspa_consensus_core::BlockHasher;
+use kaspa_database::prelude::CachePolicy;
+use kaspa_database::prelude::StoreError;
+use kaspa_database::prelude::DB;
+use kaspa_database::prelude::{BatchDbWriter, CachedDbAccess};
+use kaspa_database::registry::DatabaseStorePrefixes;
+use kaspa_hashes::Hash;
+use kaspa_utils::mem_size::MemSizeEstimator;
+use rocksdb::WriteBatch;
+use serde::Deserialize;
+use serde::Serialize;
+use std::mem::size_of;
+use std::sync::Arc;
+
+/// Store for holding the UTXO entries spent by the transactions accepted by a chain block. The entries are written
+/// along with the acceptance data of the block and pruned with it, hence share its lifecycle.
+pub trait SpentUtxoEntriesStoreReader {
+    fn get(&self, hash: Hash) -> Result<Arc<SpentUtxoEntries>, StoreError>;
+}
+
+/// Simple wrapper for implementing `MemSizeEstimator`
+#[derive(Clone, Serialize, Deserialize)]
+struct SpentUtxoEntriesEntry(Arc<SpentUtxoEntries>);
+
+impl MemSizeEstimator for SpentUtxoEntriesEntry {
+    fn estimate_mem_bytes(&self) -> usize {
+        self.0.iter().map(|tx| tx.entries.len()).sum::<usize>() * size_of::<UtxoEntry>()
+            + self.0.len() * size_of::<AcceptedTxSpentEntries>()
+            + size_of::<SpentUtxoEntries>()
+            + size_of::<Self>()
+    }
+}
+
+/// A DB + cache implementation of `SpentUtxoEntriesStoreReader` trait, with concurrency support.
+#[derive(Clone)]
+pub struct DbSpentUtxoEntriesStore {
+    db: Arc<DB>,
+    access: CachedDbAccess<Hash, SpentUtxoEntriesEntry, BlockHasher>,
+}
+
+impl DbSpentUtxoEntriesStore {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, DatabaseStorePrefixes::SpentUtxoEntries.into()) }
+    }
+
+    pub fn clone_with_new_cache(&self, cache_policy: CachePolicy) -> Self {
+        Self::new(Arc::clone(&self.db), cache_policy)
+    }
+
+    pub fn insert_batch(
+        &self,
+        batch: &mut WriteBatch,
+        hash: Hash,
+        spent_utxo_entries: Arc<SpentUtxoEntries>,
+    ) -> Result<(), StoreError> {
+        if self.access.has(hash)? {
+            return Err(StoreError::HashAlreadyExists(hash));
+        }
+        self.access.write(BatchDbWriter::new(batch), hash, SpentUtxoEntriesEntry(spent_utxo_entries))?;
+        Ok(())
+    }
+
+    pub fn delete_batch(&self, batch: &mut WriteBatch, hash: Hash) -> Result<(), StoreError> {
+        self.access.delete(BatchDbWriter::new(batch), hash)
+    }
+}
+
+impl SpentUtxoEntriesStoreReader for DbSpentUtxoEntriesStore {
+    fn get(&self, hash: Hash) -> Result<Arc<SpentUtxoEntries>, StoreError> {
+        Ok(self.access.read(hash)?.0)
+    }
+}
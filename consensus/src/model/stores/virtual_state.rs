@@ -2,7 +2,7 @@ use std::ops::Deref;
 use std::sync::Arc;
 
 use arc_swap::ArcSwap;
-use kaspa_consensus_core::api::stats::VirtualStateStats;
+use kaspa_consensus_core::api::stats::{VirtualInfo, VirtualStateStats};
 use kaspa_consensus_core::{
     block::VirtualStateApproxId, coinbase::BlockRewardData, config::genesis::GenesisBlock, tx::TransactionId,
     utxo::utxo_diff::UtxoDiff, BlockHashMap, BlockHashSet, HashMapCustomHasher,
@@ -91,6 +91,20 @@ impl From<&VirtualState> for VirtualStateStats {
     }
 }
 
+impl From<&VirtualState> for VirtualInfo {
+    fn from(state: &VirtualState) -> Self {
+        Self {
+            daa_score: state.daa_score,
+            sink: state.ghostdag_data.selected_parent,
+            // The blue score of virtual is the blue score of its selected parent (the sink) plus the size
+            // of its mergeset blues, which saves reading the GHOSTDAG data of the sink
+            sink_blue_score: state.ghostdag_data.blue_score.saturating_sub(state.ghostdag_data.mergeset_blues.len() as u64),
+            parents_count: state.parents.len() as u32,
+            past_median_time: state.past_median_time,
+        }
+    }
+}
+
 /// Represents the "last known good" virtual state. To be used by any logic which does not want to wait
 /// for a possible virtual state write to complete but can rather settle with the last known state
 #[derive(Clone, Default)]
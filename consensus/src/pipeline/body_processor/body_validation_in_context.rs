@@ -1,11 +1,10 @@
 use super::BlockBodyProcessor;
 use crate::{
-    errors::{BlockProcessResult, RuleError},
+    errors::{BlockProcessResult, RuleError, StoreResultRuleExtensions},
     model::stores::{ghostdag::GhostdagStoreReader, statuses::StatusesStoreReader},
     processes::window::WindowManager,
 };
 use kaspa_consensus_core::block::Block;
-use kaspa_database::prelude::StoreResultExtensions;
 use kaspa_hashes::Hash;
 use kaspa_utils::option::OptionExtensions;
 use std::sync::Arc;
@@ -18,6 +17,14 @@ impl BlockBodyProcessor {
         self.check_block_is_not_pruned(block)
     }
 
+    /// Validates the body of a block template in context. The template is not part of the DAG, hence
+    /// its past median time is provided by the caller.
+    pub fn validate_template_body_in_context(self: &Arc<Self>, block: &Block, past_median_time: u64) -> BlockProcessResult<()> {
+        self.check_parent_bodies_exist(block)?;
+        self.check_coinbase_blue_score_and_subsidy(block)?;
+        self.check_block_transactions_at_past_median_time(block, past_median_time)
+    }
+
     fn check_block_is_not_pruned(self: &Arc<Self>, _block: &Block) -> BlockProcessResult<()> {
         // TODO: In kaspad code it checks that the block is not in the past of the current tips.
         // We should decide what's the best indication that a block was pruned.
@@ -25,9 +32,14 @@ impl BlockBodyProcessor {
     }
 
     fn check_block_transactions_in_context(self: &Arc<Self>, block: &Block) -> BlockProcessResult<()> {
-        let (pmt, _) = self.window_manager.calc_past_median_time(&self.ghostdag_store.get_data(block.hash()).unwrap())?;
+        let ghostdag_data = self.ghostdag_store.get_data(block.hash()).or_store_failure("ghostdag", block.hash())?;
+        let (pmt, _) = self.window_manager.calc_past_median_time(&ghostdag_data)?;
+        self.check_block_transactions_at_past_median_time(block, pmt)
+    }
+
+    fn check_block_transactions_at_past_median_time(self: &Arc<Self>, block: &Block, past_median_time: u64) -> BlockProcessResult<()> {
         for tx in block.transactions.iter() {
-            if let Err(e) = self.transaction_validator.utxo_free_tx_validation(tx, block.header.daa_score, pmt) {
+            if let Err(e) = self.transaction_validator.utxo_free_tx_validation(tx, block.header.daa_score, past_median_time) {
                 return Err(RuleError::TxInContextFailed(tx.id(), e));
             }
         }
@@ -43,16 +55,13 @@ impl BlockBodyProcessor {
         }
 
         let statuses_read_guard = self.statuses_store.read();
-        let missing: Vec<Hash> = block
-            .header
-            .direct_parents()
-            .iter()
-            .copied()
-            .filter(|parent| {
-                let status_option = statuses_read_guard.get(*parent).unwrap_option();
-                status_option.is_none_or(|s| !s.has_block_body())
-            })
-            .collect();
+        let mut missing: Vec<Hash> = Vec::new();
+        for &parent in block.header.direct_parents() {
+            let status_option = statuses_read_guard.get(parent).optional_or_store_failure("statuses", parent)?;
+            if status_option.is_none_or(|s| !s.has_block_body()) {
+                missing.push(parent);
+            }
+        }
         if !missing.is_empty() {
             return Err(RuleError::MissingParents(missing));
         }
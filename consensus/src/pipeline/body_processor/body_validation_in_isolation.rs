@@ -4,11 +4,27 @@ use super::BlockBodyProcessor;
 use crate::errors::{BlockProcessResult, RuleError};
 use kaspa_consensus_core::{block::Block, merkle::calc_hash_merkle_root_with_options, tx::TransactionOutpoint};
 
+/// The outcome of [`BlockBodyProcessor::validate_body_in_isolation_full`]
+#[derive(Debug, Default)]
+pub struct BodyValidationReport {
+    /// Mass of the block, `None` if the mass check failed
+    pub mass: Option<u64>,
+    /// The errors of all the failing rules, in the order the rules are checked
+    pub errors: Vec<RuleError>,
+}
+
+impl BodyValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
 impl BlockBodyProcessor {
     pub fn validate_body_in_isolation(self: &Arc<Self>, block: &Block) -> BlockProcessResult<u64> {
         let storage_mass_activated = block.header.daa_score > self.storage_mass_activation_daa_score;
 
         Self::check_has_transactions(block)?;
+        self.check_transaction_count(block)?;
         Self::check_hash_merkle_root(block, storage_mass_activated)?;
         Self::check_only_one_coinbase(block)?;
         self.check_transactions_in_isolation(block)?;
@@ -19,6 +35,33 @@ impl BlockBodyProcessor {
         Ok(mass)
     }
 
+    /// Validates the body in isolation against the same rules as [`Self::validate_body_in_isolation`], but
+    /// runs through all of them instead of failing on the first violation. Meant for tooling reporting all
+    /// the issues of a handcrafted block, the pipeline keeps using the fail-fast variant.
+    pub fn validate_body_in_isolation_full(self: &Arc<Self>, block: &Block) -> BodyValidationReport {
+        let mut report = BodyValidationReport::default();
+
+        // All the other rules expect a coinbase transaction
+        if let Err(err) = Self::check_has_transactions(block) {
+            report.errors.push(err);
+            return report;
+        }
+
+        let storage_mass_activated = block.header.daa_score > self.storage_mass_activation_daa_score;
+        let results = [
+            self.check_transaction_count(block),
+            Self::check_hash_merkle_root(block, storage_mass_activated),
+            Self::check_only_one_coinbase(block),
+            self.check_transactions_in_isolation(block),
+            self.check_block_mass(block, storage_mass_activated).map(|mass| report.mass = Some(mass)),
+            self.check_duplicate_transactions(block),
+            self.check_block_double_spends(block),
+            self.check_no_chained_transactions(block),
+        ];
+        report.errors.extend(results.into_iter().filter_map(Result::err));
+        report
+    }
+
     fn check_has_transactions(block: &Block) -> BlockProcessResult<()> {
         // We expect the outer flow to not queue blocks with no transactions for body validation,
         // but we still check it in case the outer flow changes.
@@ -28,6 +71,14 @@ impl BlockBodyProcessor {
         Ok(())
     }
 
+    fn check_transaction_count(self: &Arc<Self>, block: &Block) -> BlockProcessResult<()> {
+        // Checked before any per-transaction work, so blocks stuffed with tiny transactions are rejected cheaply
+        if block.transactions.len() > self.max_block_transactions {
+            return Err(RuleError::TooManyTransactions(block.transactions.len(), self.max_block_transactions));
+        }
+        Ok(())
+    }
+
     fn check_hash_merkle_root(block: &Block, storage_mass_activated: bool) -> BlockProcessResult<()> {
         let calculated = calc_hash_merkle_root_with_options(block.transactions.iter(), storage_mass_activated);
         if calculated != block.header.hash_merkle_root {
@@ -132,9 +183,11 @@ mod tests {
         consensus::test_consensus::TestConsensus,
         errors::RuleError,
         params::MAINNET_PARAMS,
+        processes::mass::MassCalculator,
     };
     use kaspa_consensus_core::{
         api::{BlockValidationFutures, ConsensusApi},
+        arbitrary::{BlockViolation, Generator, Limits, TxViolation},
         block::MutableBlock,
         header::Header,
         merkle::calc_hash_merkle_root,
@@ -143,6 +196,7 @@ mod tests {
     };
     use kaspa_core::assert_match;
     use kaspa_hashes::Hash;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
 
     #[test]
     fn validate_body_in_isolation_test() {
@@ -455,6 +509,84 @@ mod tests {
         consensus.shutdown(wait_handles);
     }
 
+    #[test]
+    fn validate_generated_body_in_isolation_test() {
+        // Reduced transaction limits, so that generated violations of count and size limits remain cheap
+        let config = ConfigBuilder::new(MAINNET_PARAMS)
+            .edit_consensus_params(|p| {
+                p.max_tx_inputs = 10;
+                p.max_tx_outputs = 32;
+                p.max_signature_script_len = 256;
+                p.max_script_public_key_len = 512;
+                p.max_block_transactions = 8;
+            })
+            .build();
+        let consensus = TestConsensus::new(&config);
+        let wait_handles = consensus.init();
+        let body_processor = consensus.block_body_processor();
+
+        let mut generator = Generator::new(StdRng::seed_from_u64(42), Limits::from(&config.params));
+        for _ in 0..50 {
+            let tx_count = generator.rng().gen_range(0..4);
+            let block = generator.block(tx_count);
+            assert_match!(body_processor.validate_body_in_isolation(&block), Ok(_), "{block:?}");
+
+            for violation in BlockViolation::ALL {
+                let tx_count = generator.rng().gen_range(1..4);
+                let block = generator.block_with_violation(tx_count, violation);
+                let result = body_processor.validate_body_in_isolation(&block);
+                let expected = match violation {
+                    BlockViolation::NoTransactions => matches!(result, Err(RuleError::NoTransactions)),
+                    BlockViolation::TooManyTransactions => matches!(result, Err(RuleError::TooManyTransactions(9, 8))),
+                    BlockViolation::BadMerkleRoot => matches!(result, Err(RuleError::BadMerkleRoot(_, _))),
+                    BlockViolation::FirstTxNotCoinbase => matches!(result, Err(RuleError::FirstTxNotCoinbase)),
+                    BlockViolation::MultipleCoinbases => matches!(result, Err(RuleError::MultipleCoinbases(_))),
+                    BlockViolation::DuplicateTransactions => matches!(result, Err(RuleError::DuplicateTransactions(_))),
+                    BlockViolation::DoubleSpendInSameBlock => matches!(result, Err(RuleError::DoubleSpendInSameBlock(_))),
+                    BlockViolation::ChainedTransaction => matches!(result, Err(RuleError::ChainedTransaction(_))),
+                    BlockViolation::InvalidTransaction(_) => unreachable!(),
+                };
+                assert!(expected, "{violation:?} resulted in {result:?}");
+            }
+
+            for violation in TxViolation::ALL.map(BlockViolation::InvalidTransaction) {
+                let tx_count = generator.rng().gen_range(1..4);
+                let block = generator.block_with_violation(tx_count, violation);
+                assert_match!(
+                    body_processor.validate_body_in_isolation(&block),
+                    Err(RuleError::TxInIsolationValidationFailed(_, _)),
+                    "{violation:?}"
+                );
+            }
+        }
+
+        consensus.shutdown(wait_handles);
+    }
+
+    #[test]
+    fn validate_block_transaction_count_test() {
+        // On mainnet, the mass limit binds before the count limit even for blocks of the smallest transactions
+        let params = &MAINNET_PARAMS;
+        let mass_calculator = MassCalculator::new(params);
+        let input = TransactionInput::new(TransactionOutpoint::new(1.into(), 0), vec![], 0, 0);
+        let smallest_tx = Transaction::new(0, vec![input], vec![], 0, SUBNETWORK_ID_NATIVE, 0, vec![]);
+        let smallest_tx_mass = mass_calculator.calc_tx_compute_mass(&smallest_tx);
+        assert_eq!(smallest_tx_mass, 154);
+        assert!(params.max_block_transactions as u64 > 1 + params.max_block_mass / smallest_tx_mass);
+
+        let config = ConfigBuilder::new(MAINNET_PARAMS).edit_consensus_params(|p| p.max_block_transactions = 4).build();
+        let consensus = TestConsensus::new(&config);
+        let wait_handles = consensus.init();
+        let body_processor = consensus.block_body_processor();
+
+        // A coinbase and 3 transactions reach the limit, a fourth one exceeds it
+        let mut generator = Generator::new(StdRng::seed_from_u64(7), Limits::from(&config.params));
+        assert_match!(body_processor.validate_body_in_isolation(&generator.block(3)), Ok(_));
+        assert_match!(body_processor.validate_body_in_isolation(&generator.block(4)), Err(RuleError::TooManyTransactions(5, 4)));
+
+        consensus.shutdown(wait_handles);
+    }
+
     #[tokio::test]
     async fn merkle_root_missing_parents_known_invalid_test() {
         let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
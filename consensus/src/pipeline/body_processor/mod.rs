@@ -1,4 +1,5 @@
 mod body_validation_in_context;
 mod body_validation_in_isolation;
 mod processor;
+pub use body_validation_in_isolation::BodyValidationReport;
 pub use processor::*;
@@ -1,6 +1,6 @@
 use crate::{
     consensus::services::DbWindowManager,
-    errors::{BlockProcessResult, RuleError},
+    errors::{BlockProcessResult, RuleError, StoreResultRuleExtensions},
     model::{
         services::reachability::MTReachabilityService,
         stores::{
@@ -15,12 +15,13 @@ use crate::{
     },
     pipeline::{
         deps_manager::{BlockProcessingMessage, BlockTaskDependencyManager, TaskId, VirtualStateProcessingMessage},
-        ProcessingCounters,
+        spawn_limited, ProcessingCounters,
     },
     processes::{coinbase::CoinbaseManager, mass::MassCalculator, transaction_validator::TransactionValidator},
 };
 use crossbeam_channel::{Receiver, Sender};
 use kaspa_consensus_core::{
+    api::parallelism::ProcessingParallelism,
     block::Block,
     blockstatus::BlockStatus::{self, StatusHeaderOnly, StatusInvalid},
     config::genesis::GenesisBlock,
@@ -51,6 +52,7 @@ pub struct BlockBodyProcessor {
 
     // Config
     pub(super) max_block_mass: u64,
+    pub(super) max_block_transactions: usize,
     pub(super) genesis: GenesisBlock,
 
     // Stores
@@ -79,6 +81,9 @@ pub struct BlockBodyProcessor {
     // Counters
     counters: Arc<ProcessingCounters>,
 
+    // Bound of the concurrently processed blocks
+    parallelism: Arc<ProcessingParallelism>,
+
     /// Storage mass hardfork DAA score
     pub(crate) storage_mass_activation_daa_score: u64,
 }
@@ -103,10 +108,12 @@ impl BlockBodyProcessor {
         transaction_validator: TransactionValidator,
         window_manager: DbWindowManager,
         max_block_mass: u64,
+        max_block_transactions: usize,
         genesis: GenesisBlock,
         pruning_lock: SessionLock,
         notification_root: Arc<ConsensusNotificationRoot>,
         counters: Arc<ProcessingCounters>,
+        parallelism: Arc<ProcessingParallelism>,
         storage_mass_activation_daa_score: u64,
     ) -> Self {
         Self {
@@ -125,11 +132,13 @@ impl BlockBodyProcessor {
             transaction_validator,
             window_manager,
             max_block_mass,
+            max_block_transactions,
             genesis,
             pruning_lock,
             task_manager: BlockTaskDependencyManager::new(),
             notification_root,
             counters,
+            parallelism,
             storage_mass_activation_daa_score,
         }
     }
@@ -141,7 +150,7 @@ impl BlockBodyProcessor {
                 BlockProcessingMessage::Process(task, block_result_transmitter, virtual_result_transmitter) => {
                     if let Some(task_id) = self.task_manager.register(task, block_result_transmitter, virtual_result_transmitter) {
                         let processor = self.clone();
-                        self.thread_pool.spawn(move || {
+                        spawn_limited(&self.parallelism, &self.thread_pool, move || {
                             processor.queue_block(task_id);
                         });
                     }
@@ -158,7 +167,7 @@ impl BlockBodyProcessor {
 
     fn queue_block(self: &Arc<BlockBodyProcessor>, task_id: TaskId) {
         if let Some(task) = self.task_manager.try_begin(task_id) {
-            let res = self.process_body(task.block(), task.is_trusted());
+            let res = self.process_body(task.block(), task.is_trusted(), task.requires_notification());
 
             let dependent_tasks = self.task_manager.end(task, |task, block_result_transmitter, virtual_state_result_transmitter| {
                 let _ = block_result_transmitter.send(res.clone());
@@ -172,14 +181,14 @@ impl BlockBodyProcessor {
 
             for dep in dependent_tasks {
                 let processor = self.clone();
-                self.thread_pool.spawn(move || processor.queue_block(dep));
+                spawn_limited(&self.parallelism, &self.thread_pool, move || processor.queue_block(dep));
             }
         }
     }
 
-    fn process_body(self: &Arc<BlockBodyProcessor>, block: &Block, is_trusted: bool) -> BlockProcessResult<BlockStatus> {
+    fn process_body(self: &Arc<BlockBodyProcessor>, block: &Block, is_trusted: bool, notify: bool) -> BlockProcessResult<BlockStatus> {
         let _prune_guard = self.pruning_lock.blocking_read();
-        let status = self.statuses_store.read().get(block.hash()).unwrap();
+        let status = self.statuses_store.read().get(block.hash()).or_store_failure("statuses", block.hash())?;
         match status {
             StatusInvalid => return Err(RuleError::KnownInvalid),
             StatusHeaderOnly => {} // Proceed to body processing
@@ -201,7 +210,9 @@ impl BlockBodyProcessor {
                 // PrunedBlock - PrunedBlock is an error that rejects a block body and
                 // not the block as a whole, so we shouldn't mark it as invalid.
                 // TODO: implement the last part.
-                if !matches!(e, RuleError::BadMerkleRoot(_, _) | RuleError::MissingParents(_)) {
+                // StoreFailure - a failed store read says nothing about the validity of
+                // the block, which can be processed again once the store is repaired.
+                if !matches!(e, RuleError::BadMerkleRoot(_, _) | RuleError::MissingParents(_) | RuleError::StoreFailure(..)) {
                     self.statuses_store.write().set(block.hash(), BlockStatus::StatusInvalid).unwrap();
                 }
                 return Err(e);
@@ -211,9 +222,11 @@ impl BlockBodyProcessor {
         self.commit_body(block.hash(), block.header.direct_parents(), block.transactions.clone());
 
         // Send a BlockAdded notification
-        self.notification_root
-            .notify(Notification::BlockAdded(BlockAddedNotification::new(block.to_owned())))
-            .expect("expecting an open unbounded channel");
+        if notify {
+            self.notification_root
+                .notify(Notification::BlockAdded(BlockAddedNotification::new(block.to_owned())))
+                .expect("expecting an open unbounded channel");
+        }
 
         // Report counters
         self.counters.body_counts.fetch_add(1, Ordering::Relaxed);
@@ -242,7 +255,7 @@ impl BlockBodyProcessor {
         let statuses_write_guard =
             self.statuses_store.set_batch(&mut batch, hash, BlockStatus::StatusUTXOPendingVerification).unwrap();
 
-        self.db.write(batch).unwrap();
+        self.db.write_critical(batch).unwrap();
 
         // Calling the drops explicitly after the batch is written in order to avoid possible errors.
         drop(statuses_write_guard);
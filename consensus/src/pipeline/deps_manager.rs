@@ -46,14 +46,16 @@ pub enum BlockTask {
 
     /// Trusted block processing task, only requiring partial validation.
     /// Trusted blocks arrive as part of the pruning proof; the block might be header-only.
-    Trusted { block: Block },
+    /// Trusted blocks processed as part of a batch set `notify` to false, the batch being
+    /// reported as a whole rather than through per-block notifications.
+    Trusted { block: Block, notify: bool },
 }
 
 impl BlockTask {
     pub fn block(&self) -> &Block {
         match self {
             BlockTask::Ordinary { block } => block,
-            BlockTask::Trusted { block } => block,
+            BlockTask::Trusted { block, .. } => block,
         }
     }
 
@@ -65,6 +67,14 @@ impl BlockTask {
         matches!(self, BlockTask::Trusted { .. })
     }
 
+    /// Returns whether a BlockAdded notification should be sent once the block body is processed
+    pub fn requires_notification(&self) -> bool {
+        match self {
+            BlockTask::Ordinary { .. } => true,
+            BlockTask::Trusted { notify, .. } => *notify,
+        }
+    }
+
     pub fn requires_virtual_processing(&self) -> bool {
         // Trusted blocks should not trigger virtual processing
         self.is_ordinary()
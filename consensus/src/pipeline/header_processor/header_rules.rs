@@ -0,0 +1,87 @@
+//!
+//! Header rules which only depend on consensus parameters and on data readily provided by the caller,
+//! shared by the header processor and the header chain verifier.
+//!
+
+use crate::errors::{BlockProcessResult, RuleError};
+use kaspa_consensus_core::{
+    blockhash::BlockHashExtensions, config::header_version::HeaderVersionPolicy, errors::block::VecDisplay, header::Header, BlockLevel,
+};
+use std::cmp::max;
+
+pub(crate) fn check_header_version(header_version_policy: &HeaderVersionPolicy, header: &Header) -> BlockProcessResult<()> {
+    // The header DAA score is not verified yet at this stage, however a header with a wrong DAA score
+    // is rejected by the in-context validation anyway
+    if !header_version_policy.is_accepted(header.version, header.daa_score) {
+        return Err(RuleError::UnacceptedBlockVersion(
+            header.version,
+            VecDisplay(header_version_policy.active_versions(header.daa_score)),
+            header.daa_score,
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the header timestamp is not ahead of the current time `now` by more than the deviation tolerance
+pub(crate) fn check_block_timestamp_in_isolation(
+    now: u64,
+    timestamp_deviation_tolerance: u64,
+    header: &Header,
+) -> BlockProcessResult<()> {
+    // Timestamp deviation tolerance is in seconds so we multiply by 1000 to get milliseconds (without BPS dependency)
+    let max_block_time = now + timestamp_deviation_tolerance * 1000;
+    if header.timestamp > max_block_time {
+        return Err(RuleError::TimeTooFarIntoTheFuture(header.timestamp, max_block_time));
+    }
+    Ok(())
+}
+
+pub(crate) fn check_parents_limit(max_block_parents: u8, header: &Header) -> BlockProcessResult<()> {
+    if header.direct_parents().is_empty() {
+        return Err(RuleError::NoParents);
+    }
+
+    if header.direct_parents().len() > max_block_parents as usize {
+        return Err(RuleError::TooManyParents(header.direct_parents().len(), max_block_parents as usize));
+    }
+
+    Ok(())
+}
+
+pub(crate) fn check_parents_not_origin(header: &Header) -> BlockProcessResult<()> {
+    if header.direct_parents().iter().any(|&parent| parent.is_origin()) {
+        return Err(RuleError::OriginParent);
+    }
+
+    Ok(())
+}
+
+/// Checks the proof of work against the header declared bits and returns the block level computed from it
+pub(crate) fn check_pow_and_calc_block_level(
+    max_block_level: BlockLevel,
+    skip_proof_of_work: bool,
+    header: &Header,
+) -> BlockProcessResult<BlockLevel> {
+    let state = kaspa_pow::State::new(header);
+    let (passed, pow) = state.check_pow(header.nonce);
+    if passed || skip_proof_of_work {
+        let signed_block_level = max_block_level as i64 - pow.bits() as i64;
+        Ok(max(signed_block_level, 0) as BlockLevel)
+    } else {
+        Err(RuleError::InvalidPoW)
+    }
+}
+
+pub(crate) fn check_difficulty_bits(header: &Header, expected_bits: u32) -> BlockProcessResult<()> {
+    if header.bits != expected_bits {
+        return Err(RuleError::UnexpectedDifficulty(header.bits, expected_bits));
+    }
+    Ok(())
+}
+
+pub(crate) fn check_median_timestamp(header: &Header, past_median_time: u64) -> BlockProcessResult<()> {
+    if header.timestamp <= past_median_time {
+        return Err(RuleError::TimeTooOld(header.timestamp, past_median_time));
+    }
+    Ok(())
+}
@@ -1,3 +1,4 @@
+pub(crate) mod header_rules;
 pub mod post_pow_validation;
 mod pre_ghostdag_validation;
 mod pre_pow_validation;
@@ -1,4 +1,4 @@
-use super::{HeaderProcessingContext, HeaderProcessor};
+use super::{header_rules::check_median_timestamp, HeaderProcessingContext, HeaderProcessor};
 use crate::errors::{BlockProcessResult, RuleError, TwoDimVecDisplay};
 use crate::model::services::reachability::ReachabilityService;
 use crate::processes::window::WindowManager;
@@ -18,14 +18,12 @@ impl HeaderProcessor {
     }
 
     pub fn check_median_timestamp(&self, ctx: &mut HeaderProcessingContext, header: &Header) -> BlockProcessResult<()> {
-        let (past_median_time, window) = self.window_manager.calc_past_median_time(ctx.ghostdag_data())?;
+        let (past_median_time, window) = match ctx.past_median_time.take() {
+            Some(past_median_time) => past_median_time?,
+            None => self.window_manager.calc_past_median_time(ctx.ghostdag_data())?,
+        };
         ctx.block_window_for_past_median_time = Some(window);
-
-        if header.timestamp <= past_median_time {
-            return Err(RuleError::TimeTooOld(header.timestamp, past_median_time));
-        }
-
-        Ok(())
+        check_median_timestamp(header, past_median_time)
     }
 
     pub fn check_merge_size_limit(&self, ctx: &mut HeaderProcessingContext) -> BlockProcessResult<()> {
@@ -1,7 +1,8 @@
+use super::header_rules::check_difficulty_bits;
 use super::*;
 use crate::errors::{BlockProcessResult, RuleError};
 use crate::model::services::reachability::ReachabilityService;
-use crate::processes::window::WindowManager;
+use crate::processes::window::{BlockWindows, WindowManager};
 use kaspa_consensus_core::header::Header;
 
 impl HeaderProcessor {
@@ -25,7 +26,8 @@ impl HeaderProcessor {
 
     fn check_difficulty_and_daa_score(&self, ctx: &mut HeaderProcessingContext, header: &Header) -> BlockProcessResult<()> {
         let ghostdag_data = ctx.ghostdag_data();
-        let daa_window = self.window_manager.block_daa_window(ghostdag_data)?;
+        let BlockWindows { daa_window, past_median_time } = self.window_manager.block_windows(ghostdag_data)?;
+        ctx.past_median_time = Some(past_median_time);
 
         if daa_window.daa_score != header.daa_score {
             return Err(RuleError::UnexpectedHeaderDaaScore(daa_window.daa_score, header.daa_score));
@@ -34,9 +36,7 @@ impl HeaderProcessor {
         let expected_bits = self.window_manager.calculate_difficulty_bits(ghostdag_data, &daa_window);
         ctx.mergeset_non_daa = Some(daa_window.mergeset_non_daa);
 
-        if header.bits != expected_bits {
-            return Err(RuleError::UnexpectedDifficulty(header.bits, expected_bits));
-        }
+        check_difficulty_bits(header, expected_bits)?;
 
         ctx.block_window_for_difficulty = Some(daa_window.window);
         Ok(())
@@ -6,7 +6,7 @@ use crate::{
         },
         storage::ConsensusStorage,
     },
-    errors::{BlockProcessResult, RuleError},
+    errors::{BlockProcessResult, RuleError, StoreResultRuleExtensions},
     model::{
         services::reachability::MTReachabilityService,
         stores::{
@@ -24,20 +24,24 @@ use crate::{
         },
     },
     params::Params,
-    pipeline::deps_manager::{BlockProcessingMessage, BlockTask, BlockTaskDependencyManager, TaskId},
+    pipeline::{
+        deps_manager::{BlockProcessingMessage, BlockTask, BlockTaskDependencyManager, TaskId},
+        spawn_limited,
+    },
     processes::{ghostdag::ordering::SortableBlock, reachability::inquirer as reachability, relations::RelationsStoreExtensions},
 };
 use crossbeam_channel::{Receiver, Sender};
-use itertools::Itertools;
 use kaspa_consensus_core::{
+    api::parallelism::ProcessingParallelism,
     blockhash::{BlockHashes, ORIGIN},
     blockstatus::BlockStatus::{self, StatusHeaderOnly, StatusInvalid},
-    config::genesis::GenesisBlock,
+    config::{genesis::GenesisBlock, header_version::HeaderVersionPolicy},
     header::Header,
     BlockHashSet, BlockLevel,
 };
 use kaspa_consensusmanager::SessionLock;
-use kaspa_database::prelude::{StoreResultEmptyTuple, StoreResultExtensions};
+use kaspa_core::time::DynClock;
+use kaspa_database::prelude::StoreResultEmptyTuple;
 use kaspa_hashes::Hash;
 use kaspa_utils::vec::VecExtensions;
 use parking_lot::RwLock;
@@ -58,6 +62,8 @@ pub struct HeaderProcessingContext {
     pub ghostdag_data: Option<Vec<Arc<GhostdagData>>>,
     pub block_window_for_difficulty: Option<Arc<BlockWindowHeap>>,
     pub block_window_for_past_median_time: Option<Arc<BlockWindowHeap>>,
+    /// The past median time built along with the DAA window, validated after the proof of work
+    pub past_median_time: Option<Result<(u64, Arc<BlockWindowHeap>), RuleError>>,
     pub mergeset_non_daa: Option<BlockHashSet>,
     pub merge_depth_root: Option<Hash>,
     pub finality_point: Option<Hash>,
@@ -81,6 +87,7 @@ impl HeaderProcessingContext {
             block_window_for_difficulty: None,
             mergeset_non_daa: None,
             block_window_for_past_median_time: None,
+            past_median_time: None,
             merge_depth_root: None,
             finality_point: None,
         }
@@ -119,6 +126,8 @@ pub struct HeaderProcessor {
     pub(super) mergeset_size_limit: u64,
     pub(super) skip_proof_of_work: bool,
     pub(super) max_block_level: BlockLevel,
+    pub(super) header_version_policy: HeaderVersionPolicy,
+    pub(super) clock: DynClock,
 
     // DB
     db: Arc<DB>,
@@ -154,6 +163,9 @@ pub struct HeaderProcessor {
 
     // Counters
     counters: Arc<ProcessingCounters>,
+
+    // Bound of the concurrently processed blocks
+    parallelism: Arc<ProcessingParallelism>,
 }
 
 impl HeaderProcessor {
@@ -162,11 +174,13 @@ impl HeaderProcessor {
         body_sender: Sender<BlockProcessingMessage>,
         thread_pool: Arc<ThreadPool>,
         params: &Params,
+        clock: DynClock,
         db: Arc<DB>,
         storage: &Arc<ConsensusStorage>,
         services: &Arc<ConsensusServices>,
         pruning_lock: SessionLock,
         counters: Arc<ProcessingCounters>,
+        parallelism: Arc<ProcessingParallelism>,
     ) -> Self {
         Self {
             receiver,
@@ -199,6 +213,7 @@ impl HeaderProcessor {
             task_manager: BlockTaskDependencyManager::new(),
             pruning_lock,
             counters,
+            parallelism,
             // TODO (HF): make sure to also pass `new_timestamp_deviation_tolerance` and use according to HF activation score
             timestamp_deviation_tolerance: params.timestamp_deviation_tolerance(0),
             target_time_per_block: params.target_time_per_block,
@@ -206,6 +221,8 @@ impl HeaderProcessor {
             mergeset_size_limit: params.mergeset_size_limit,
             skip_proof_of_work: params.skip_proof_of_work,
             max_block_level: params.max_block_level,
+            header_version_policy: params.header_version_policy,
+            clock,
         }
     }
 
@@ -219,7 +236,7 @@ impl HeaderProcessor {
                     if let Some(task_id) = self.task_manager.register(task, block_result_transmitter, virtual_state_result_transmitter)
                     {
                         let processor = self.clone();
-                        self.thread_pool.spawn(move || {
+                        spawn_limited(&self.parallelism, &self.thread_pool, move || {
                             processor.queue_block(task_id);
                         });
                     }
@@ -257,7 +274,7 @@ impl HeaderProcessor {
 
             for dep in dependent_tasks {
                 let processor = self.clone();
-                self.thread_pool.spawn(move || processor.queue_block(dep));
+                spawn_limited(&self.parallelism, &self.thread_pool, move || processor.queue_block(dep));
             }
         }
     }
@@ -265,7 +282,7 @@ impl HeaderProcessor {
     fn process_header(&self, task: &BlockTask) -> BlockProcessResult<BlockStatus> {
         let _prune_guard = self.pruning_lock.blocking_read();
         let header = &task.block().header;
-        let status_option = self.statuses_store.read().get(header.hash).unwrap_option();
+        let status_option = self.statuses_store.read().get(header.hash).optional_or_store_failure("statuses", header.hash)?;
 
         match status_option {
             Some(StatusInvalid) => return Err(RuleError::KnownInvalid),
@@ -277,7 +294,7 @@ impl HeaderProcessor {
         match task {
             BlockTask::Ordinary { .. } => {
                 let ctx = self.validate_header(header)?;
-                self.commit_header(ctx, header);
+                self.commit_header(ctx, header)?;
             }
             BlockTask::Trusted { .. } => {
                 let ctx = self.validate_trusted_header(header)?;
@@ -296,11 +313,14 @@ impl HeaderProcessor {
     fn validate_header(&self, header: &Arc<Header>) -> BlockProcessResult<HeaderProcessingContext> {
         let block_level = self.validate_header_in_isolation(header)?;
         self.validate_parent_relations(header)?;
-        let mut ctx = self.build_processing_context(header, block_level);
-        self.ghostdag(&mut ctx);
+        let mut ctx = self.build_processing_context(header, block_level)?;
+        self.ghostdag(&mut ctx)?;
         self.pre_pow_validation(&mut ctx, header)?;
         if let Err(e) = self.post_pow_validation(&mut ctx, header) {
-            self.statuses_store.write().set(ctx.hash, StatusInvalid).unwrap();
+            // A store failure says nothing about the validity of the header
+            if !e.is_store_failure() {
+                self.statuses_store.write().set(ctx.hash, StatusInvalid).unwrap();
+            }
             return Err(e);
         }
         Ok(ctx)
@@ -311,57 +331,56 @@ impl HeaderProcessor {
         // TODO: For now we skip most validations for trusted blocks, but in the future we should
         // employ some validations to avoid spam etc.
         let block_level = self.validate_header_in_isolation(header)?;
-        let mut ctx = self.build_processing_context(header, block_level);
-        self.ghostdag(&mut ctx);
+        let mut ctx = self.build_processing_context(header, block_level)?;
+        self.ghostdag(&mut ctx)?;
         Ok(ctx)
     }
 
-    fn build_processing_context(&self, header: &Arc<Header>, block_level: u8) -> HeaderProcessingContext {
-        HeaderProcessingContext::new(
+    fn build_processing_context(&self, header: &Arc<Header>, block_level: u8) -> BlockProcessResult<HeaderProcessingContext> {
+        Ok(HeaderProcessingContext::new(
             header.hash,
             header.clone(),
             block_level,
-            self.pruning_point_store.read().get().unwrap(),
-            self.collect_known_parents(header, block_level),
-        )
+            self.pruning_point_store.read().get().or_store_failure("pruning", "pruning point")?,
+            self.collect_known_parents(header, block_level)?,
+        ))
     }
 
     /// Collects the known parents for all block levels
-    fn collect_known_parents(&self, header: &Header, block_level: BlockLevel) -> Vec<Arc<Vec<Hash>>> {
+    fn collect_known_parents(&self, header: &Header, block_level: BlockLevel) -> BlockProcessResult<Vec<Arc<Vec<Hash>>>> {
         let relations_read = self.relations_stores.read();
         (0..=block_level)
-            .map(|level| {
-                Arc::new(
-                    self.parents_manager
-                        .parents_at_level(header, level)
-                        .iter()
-                        .copied()
-                        .filter(|parent| relations_read[level as usize].has(*parent).unwrap())
-                        .collect_vec()
-                        // This kicks-in only for trusted blocks or for level > 0. If an ordinary block is 
-                        // missing direct parents it will fail validation.
-                        .push_if_empty(ORIGIN),
-                )
+            .map(|level| -> BlockProcessResult<_> {
+                let mut known_parents = Vec::new();
+                for &parent in self.parents_manager.parents_at_level(header, level).iter() {
+                    if relations_read[level as usize].has(parent).or_store_failure("relations", parent)? {
+                        known_parents.push(parent);
+                    }
+                }
+                // This kicks-in only for trusted blocks or for level > 0. If an ordinary block is
+                // missing direct parents it will fail validation.
+                Ok(Arc::new(known_parents.push_if_empty(ORIGIN)))
             })
-            .collect_vec()
+            .collect()
     }
 
     /// Runs the GHOSTDAG algorithm for all block levels and writes the data into the context (if hasn't run already)
-    fn ghostdag(&self, ctx: &mut HeaderProcessingContext) {
+    fn ghostdag(&self, ctx: &mut HeaderProcessingContext) -> BlockProcessResult<()> {
         let ghostdag_data = (0..=ctx.block_level as usize)
-            .map(|level| {
-                self.ghostdag_stores[level]
+            .map(|level| -> BlockProcessResult<_> {
+                Ok(self.ghostdag_stores[level]
                     .get_data(ctx.hash)
-                    .unwrap_option()
-                    .unwrap_or_else(|| Arc::new(self.ghostdag_managers[level].ghostdag(&ctx.known_parents[level])))
+                    .optional_or_store_failure("ghostdag", ctx.hash)?
+                    .unwrap_or_else(|| Arc::new(self.ghostdag_managers[level].ghostdag(&ctx.known_parents[level]))))
             })
-            .collect_vec();
+            .collect::<BlockProcessResult<Vec<_>>>()?;
 
         self.counters.mergeset_counts.fetch_add(ghostdag_data[0].mergeset_size() as u64, Ordering::Relaxed);
         ctx.ghostdag_data = Some(ghostdag_data);
+        Ok(())
     }
 
-    fn commit_header(&self, ctx: HeaderProcessingContext, header: &Header) {
+    fn commit_header(&self, ctx: HeaderProcessingContext, header: &Header) -> BlockProcessResult<()> {
         let ghostdag_data = ctx.ghostdag_data.as_ref().unwrap();
         let pp = ctx.pruning_point();
 
@@ -397,20 +416,28 @@ impl HeaderProcessor {
         let mut staging = StagingReachabilityStore::new(self.reachability_store.upgradable_read());
         let selected_parent = ghostdag_data[0].selected_parent;
         let mut reachability_mergeset = ghostdag_data[0].unordered_mergeset_without_selected_parent();
-        reachability::add_block(&mut staging, ctx.hash, selected_parent, &mut reachability_mergeset).unwrap();
+        let mut reindex_stats = reachability::add_block(&mut staging, ctx.hash, selected_parent, &mut reachability_mergeset).unwrap();
 
         // Non-append only stores need to use write locks.
         // Note we need to keep the lock write guards until the batch is written.
         let mut hst_write = self.headers_selected_tip_store.write();
-        let prev_hst = hst_write.get().unwrap();
+        let prev_hst = hst_write.get().or_store_failure("headers selected tip", "selected tip")?;
         if SortableBlock::new(ctx.hash, header.blue_work) > prev_hst
             && reachability::is_chain_ancestor_of(&staging, pp, ctx.hash).unwrap()
         {
             // Hint reachability about the new tip.
             // TODO: identify a disqualified hst and make sure to use sink instead
-            reachability::hint_virtual_selected_parent(&mut staging, ctx.hash).unwrap();
+            reindex_stats.merge(&reachability::hint_virtual_selected_parent(&mut staging, ctx.hash).unwrap());
             hst_write.set_batch(&mut batch, SortableBlock::new(ctx.hash, header.blue_work)).unwrap();
         }
+        if !reindex_stats.is_empty() {
+            self.counters.reindex_counts.fetch_add(reindex_stats.operations, Ordering::Relaxed);
+            self.counters.reindexed_blocks_counts.fetch_add(reindex_stats.reindexed_blocks, Ordering::Relaxed);
+            self.counters.global_reindex_counts.fetch_add(reindex_stats.global_reindexes, Ordering::Relaxed);
+            if let Some(slack_remaining) = reindex_stats.slack_remaining {
+                self.counters.reindex_slack_remaining.store(slack_remaining, Ordering::Relaxed);
+            }
+        }
 
         //
         // Relations and statuses
@@ -435,7 +462,7 @@ impl HeaderProcessor {
         let reachability_write = staging.commit(&mut batch).unwrap();
 
         // Flush the batch to the DB
-        self.db.write(batch).unwrap();
+        self.db.write_critical(batch).unwrap();
 
         // Calling the drops explicitly after the batch is written in order to avoid possible errors.
         drop(reachability_write);
@@ -443,6 +470,7 @@ impl HeaderProcessor {
         drop(reachability_relations_write);
         drop(relations_write);
         drop(hst_write);
+        Ok(())
     }
 
     fn commit_trusted_header(&self, ctx: HeaderProcessingContext, _header: &Header) {
@@ -465,7 +493,7 @@ impl HeaderProcessor {
         let statuses_write = self.statuses_store.set_batch(&mut batch, ctx.hash, StatusHeaderOnly).unwrap();
 
         // Flush the batch to the DB
-        self.db.write(batch).unwrap();
+        self.db.write_critical(batch).unwrap();
 
         // Calling the drops explicitly after the batch is written in order to avoid possible errors.
         drop(statuses_write);
@@ -5,5 +5,24 @@ pub mod monitor;
 pub mod pruning_processor;
 pub mod virtual_processor;
 
+use kaspa_consensus_core::api::parallelism::ProcessingParallelism;
+use rayon::ThreadPool;
+use std::sync::Arc;
+
 /// Re-export from consensus core
 pub use kaspa_consensus_core::api::counters::{ProcessingCounters, ProcessingCountersSnapshot};
+
+/// Spawns the block processing `job` on `thread_pool` as soon as the `parallelism` limit allows it
+pub(crate) fn spawn_limited(
+    parallelism: &Arc<ProcessingParallelism>,
+    thread_pool: &Arc<ThreadPool>,
+    job: impl FnOnce() + Send + 'static,
+) {
+    let (thread_pool, parallelism_clone) = (thread_pool.clone(), parallelism.clone());
+    parallelism.submit(Box::new(move || {
+        thread_pool.spawn(move || {
+            job();
+            parallelism_clone.complete();
+        })
+    }));
+}
@@ -62,6 +62,13 @@ impl ConsensusMonitor {
                 if delta.body_counts != 0 { delta.mass_counts as f64 / delta.body_counts as f64 } else{ 0f64 },
             );
 
+            if delta.reindex_counts != 0 {
+                info!(
+                    "Reachability reindexed {} blocks in {} operations ({} global; {} slack remaining)",
+                    delta.reindexed_blocks_counts, delta.reindex_counts, delta.global_reindex_counts, snapshot.reindex_slack_remaining,
+                );
+            }
+
             last_snapshot = snapshot;
             last_log_time = now;
         }
@@ -188,7 +188,10 @@ impl PruningProcessor {
             drop(pruning_point_write);
 
             // Inform the user
-            info!("Periodic pruning point movement: advancing from {} to {}", current_pruning_info.pruning_point, new_pruning_point);
+            info!(
+                from:% = current_pruning_info.pruning_point, hash:% = new_pruning_point;
+                "Periodic pruning point movement: advancing from {} to {}", current_pruning_info.pruning_point, new_pruning_point
+            );
 
             // Advance the pruning point utxoset to the state of the new pruning point using chain-block UTXO diffs
             if !self.advance_pruning_utxoset(current_pruning_info.pruning_point, new_pruning_point) {
@@ -386,6 +389,7 @@ impl PruningProcessor {
                 self.utxo_multisets_store.delete_batch(&mut batch, current).unwrap();
                 self.utxo_diffs_store.delete_batch(&mut batch, current).unwrap();
                 self.acceptance_data_store.delete_batch(&mut batch, current).unwrap();
+                self.spent_utxo_entries_store.delete_batch(&mut batch, current).unwrap();
                 self.block_transactions_store.delete_batch(&mut batch, current).unwrap();
 
                 if keep_relations.contains(&current) {
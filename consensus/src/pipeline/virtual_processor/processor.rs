@@ -6,8 +6,7 @@ use crate::{
         },
         storage::ConsensusStorage,
     },
-    constants::BLOCK_VERSION,
-    errors::RuleError,
+    errors::{BlockProcessResult, RuleError, StoreResultRuleExtensions, TwoDimVecDisplay, VecDisplay},
     model::{
         services::{
             reachability::{MTReachabilityService, ReachabilityService},
@@ -26,6 +25,7 @@ use crate::{
             reachability::DbReachabilityStore,
             relations::{DbRelationsStore, RelationsStoreReader},
             selected_chain::{DbSelectedChainStore, SelectedChainStore},
+            spent_utxo_entries::DbSpentUtxoEntriesStore,
             statuses::{DbStatusesStore, StatusesStore, StatusesStoreBatchExtensions, StatusesStoreReader},
             tips::{DbTipsStore, TipsStoreReader},
             utxo_diffs::{DbUtxoDiffsStore, UtxoDiffsStoreReader},
@@ -43,15 +43,15 @@ use crate::{
         coinbase::CoinbaseManager,
         ghostdag::ordering::SortableBlock,
         transaction_validator::{errors::TxResult, transaction_validator_populated::TxValidationFlags, TransactionValidator},
-        window::WindowManager,
+        window::{BlockWindows, WindowManager},
     },
 };
 use kaspa_consensus_core::{
-    acceptance_data::AcceptanceData,
-    block::{BlockTemplate, MutableBlock, TemplateBuildMode, TemplateTransactionSelector},
+    acceptance_data::{AcceptanceData, SpentUtxoEntries},
+    block::{Block, BlockTemplate, BlockTemplateValidation, MutableBlock, TemplateBuildMode, TemplateTransactionSelector},
     blockstatus::BlockStatus::{StatusDisqualifiedFromChain, StatusUTXOValid},
     coinbase::MinerData,
-    config::genesis::GenesisBlock,
+    config::{genesis::GenesisBlock, header_version::HeaderVersionPolicy},
     header::Header,
     merkle::calc_hash_merkle_root_with_options,
     pruning::PruningPointsList,
@@ -60,6 +60,7 @@ use kaspa_consensus_core::{
         utxo_diff::UtxoDiff,
         utxo_view::{UtxoView, UtxoViewComposition},
     },
+    virtual_parents::{ExcludedVirtualParent, VirtualParentExclusionReason, VirtualParentSelectionTrace},
     BlockHashSet, ChainPath,
 };
 use kaspa_consensus_notify::{
@@ -70,7 +71,7 @@ use kaspa_consensus_notify::{
     root::ConsensusNotificationRoot,
 };
 use kaspa_consensusmanager::SessionLock;
-use kaspa_core::{debug, info, time::unix_now, trace, warn};
+use kaspa_core::{debug, error, info, time::DynClock, trace, warn};
 use kaspa_database::prelude::{StoreError, StoreResultEmptyTuple, StoreResultExtensions};
 use kaspa_hashes::Hash;
 use kaspa_muhash::MuHash;
@@ -79,7 +80,7 @@ use kaspa_notify::{events::EventType, notifier::Notify};
 use crossbeam_channel::{Receiver as CrossbeamReceiver, Sender as CrossbeamSender};
 use itertools::Itertools;
 use kaspa_utils::binary_heap::BinaryHeapExtensions;
-use parking_lot::{RwLock, RwLockUpgradableReadGuard};
+use parking_lot::{Mutex, RwLock, RwLockUpgradableReadGuard};
 use rand::{seq::SliceRandom, Rng};
 use rayon::{
     prelude::{IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator},
@@ -88,7 +89,7 @@ use rayon::{
 use rocksdb::WriteBatch;
 use std::{
     cmp::min,
-    collections::{BinaryHeap, HashMap, VecDeque},
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
     ops::Deref,
     sync::{atomic::Ordering, Arc},
 };
@@ -112,6 +113,8 @@ pub struct VirtualStateProcessor {
     pub(super) max_block_parents: u8,
     pub(super) mergeset_size_limit: u64,
     pub(super) pruning_depth: u64,
+    pub(super) store_spent_utxos: bool,
+    pub(super) clock: DynClock,
 
     // Stores
     pub(super) statuses_store: Arc<RwLock<DbStatusesStore>>,
@@ -129,6 +132,7 @@ pub struct VirtualStateProcessor {
     pub(super) utxo_diffs_store: Arc<DbUtxoDiffsStore>,
     pub(super) utxo_multisets_store: Arc<DbUtxoMultisetsStore>,
     pub(super) acceptance_data_store: Arc<DbAcceptanceDataStore>,
+    pub(super) spent_utxo_entries_store: Arc<DbSpentUtxoEntriesStore>,
     pub(super) virtual_stores: Arc<RwLock<VirtualStores>>,
     pub(super) pruning_utxoset_stores: Arc<RwLock<PruningUtxosetStores>>,
 
@@ -159,8 +163,17 @@ pub struct VirtualStateProcessor {
 
     // Storage mass hardfork DAA score
     pub(crate) storage_mass_activation_daa_score: u64,
+
+    // Block header versions accepted by consensus
+    header_version_policy: HeaderVersionPolicy,
+
+    // The rule violated by each of the most recently disqualified chain blocks, oldest first
+    disqualification_reasons: Mutex<VecDeque<(Hash, RuleError)>>,
 }
 
+/// The number of recently disqualified blocks whose reason is kept in memory
+const DISQUALIFICATION_REASONS_CAPACITY: usize = 256;
+
 impl VirtualStateProcessor {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
@@ -169,6 +182,8 @@ impl VirtualStateProcessor {
         pruning_receiver: CrossbeamReceiver<PruningProcessingMessage>,
         thread_pool: Arc<ThreadPool>,
         params: &Params,
+        store_spent_utxos: bool,
+        clock: DynClock,
         db: Arc<DB>,
         storage: &Arc<ConsensusStorage>,
         services: &Arc<ConsensusServices>,
@@ -186,6 +201,8 @@ impl VirtualStateProcessor {
             max_block_parents: params.max_block_parents,
             mergeset_size_limit: params.mergeset_size_limit,
             pruning_depth: params.pruning_depth,
+            store_spent_utxos,
+            clock,
 
             db,
             statuses_store: storage.statuses_store.clone(),
@@ -201,6 +218,7 @@ impl VirtualStateProcessor {
             utxo_diffs_store: storage.utxo_diffs_store.clone(),
             utxo_multisets_store: storage.utxo_multisets_store.clone(),
             acceptance_data_store: storage.acceptance_data_store.clone(),
+            spent_utxo_entries_store: storage.spent_utxo_entries_store.clone(),
             virtual_stores: storage.virtual_stores.clone(),
             pruning_utxoset_stores: storage.pruning_utxoset_stores.clone(),
             lkg_virtual_state: storage.lkg_virtual_state.clone(),
@@ -220,9 +238,24 @@ impl VirtualStateProcessor {
             notification_root,
             counters,
             storage_mass_activation_daa_score: params.storage_mass_activation_daa_score,
+            header_version_policy: params.header_version_policy,
+            disqualification_reasons: Mutex::new(VecDeque::with_capacity(DISQUALIFICATION_REASONS_CAPACITY)),
         }
     }
 
+    /// Returns the rule violated by `hash` if the block is among the most recently disqualified chain blocks
+    pub fn disqualification_reason(&self, hash: Hash) -> Option<RuleError> {
+        self.disqualification_reasons.lock().iter().rev().find(|(disqualified, _)| *disqualified == hash).map(|(_, err)| err.clone())
+    }
+
+    fn record_disqualification_reason(&self, hash: Hash, reason: RuleError) {
+        let mut reasons = self.disqualification_reasons.lock();
+        if reasons.len() == DISQUALIFICATION_REASONS_CAPACITY {
+            reasons.pop_front();
+        }
+        reasons.push_back((hash, reason));
+    }
+
     pub fn worker(self: &Arc<Self>) {
         'outer: while let Ok(msg) = self.receiver.recv() {
             if msg.is_exit_message() {
@@ -236,15 +269,22 @@ impl VirtualStateProcessor {
             let messages: Vec<VirtualStateProcessingMessage> = std::iter::once(msg).chain(self.receiver.try_iter()).collect();
             trace!("virtual processor received {} tasks", messages.len());
 
-            self.resolve_virtual();
+            // A store failure leaves the virtual state as is, so resolving is retried with the next arriving block.
+            // The pending blocks are not marked invalid since their validity is unknown
+            let res = self.resolve_virtual();
+            if let Err(err) = &res {
+                error!("Virtual resolving failed, the virtual state of {} pending blocks is left unchanged: {}", messages.len(), err);
+            }
 
             let statuses_read = self.statuses_store.read();
             for msg in messages {
                 match msg {
                     VirtualStateProcessingMessage::Exit => break 'outer,
                     VirtualStateProcessingMessage::Process(task, virtual_state_result_transmitter) => {
+                        let hash = task.block().hash();
+                        let status = res.clone().and_then(|_| statuses_read.get(hash).or_store_failure("statuses", hash));
                         // We don't care if receivers were dropped
-                        let _ = virtual_state_result_transmitter.send(Ok(statuses_read.get(task.block().hash()).unwrap()));
+                        let _ = virtual_state_result_transmitter.send(status);
                     }
                 };
             }
@@ -254,10 +294,10 @@ impl VirtualStateProcessor {
         self.pruning_sender.send(PruningProcessingMessage::Exit).unwrap();
     }
 
-    fn resolve_virtual(self: &Arc<Self>) {
-        let pruning_point = self.pruning_point_store.read().pruning_point().unwrap();
+    fn resolve_virtual(self: &Arc<Self>) -> BlockProcessResult<()> {
+        let pruning_point = self.pruning_point_store.read().pruning_point().or_store_failure("pruning", "pruning point")?;
         let virtual_read = self.virtual_stores.upgradable_read();
-        let prev_state = virtual_read.state.get().unwrap();
+        let prev_state = virtual_read.state.get().or_store_failure("virtual state", "virtual")?;
         let finality_point = self.virtual_finality_point(&prev_state.ghostdag_data, pruning_point);
 
         // PRUNE SAFETY: in order to avoid locking the prune lock throughout virtual resolving we make sure
@@ -273,7 +313,7 @@ impl VirtualStateProcessor {
             .body_tips_store
             .read()
             .get()
-            .unwrap()
+            .or_store_failure("body tips", "tips")?
             .read()
             .iter()
             .copied()
@@ -284,11 +324,11 @@ impl VirtualStateProcessor {
         let mut accumulated_diff = prev_state.utxo_diff.clone().to_reversed();
 
         let (new_sink, virtual_parent_candidates) =
-            self.sink_search_algorithm(&virtual_read, &mut accumulated_diff, prev_sink, tips, finality_point, pruning_point);
+            self.sink_search_algorithm(&virtual_read, &mut accumulated_diff, prev_sink, tips, finality_point, pruning_point)?;
         let (virtual_parents, virtual_ghostdag_data) = self.pick_virtual_parents(new_sink, virtual_parent_candidates, pruning_point);
         assert_eq!(virtual_ghostdag_data.selected_parent, new_sink);
 
-        let sink_multiset = self.utxo_multisets_store.get(new_sink).unwrap();
+        let sink_multiset = self.utxo_multisets_store.get(new_sink).or_store_failure("utxo multisets", new_sink)?;
         let chain_path = self.dag_traversal_manager.calculate_chain_path(prev_sink, new_sink);
         let new_virtual_state = self
             .calculate_and_commit_virtual_state(
@@ -302,7 +342,7 @@ impl VirtualStateProcessor {
             .expect("all possible rule errors are unexpected here");
 
         // Update the pruning processor about the virtual state change
-        let sink_ghostdag_data = self.ghostdag_primary_store.get_compact_data(new_sink).unwrap();
+        let sink_ghostdag_data = self.ghostdag_primary_store.get_compact_data(new_sink).or_store_failure("ghostdag", new_sink)?;
         // Empty the channel before sending the new message. If pruning processor is busy, this step makes sure
         // the internal channel does not grow with no need (since we only care about the most recent message)
         let _consume = self.pruning_receiver.try_iter().count();
@@ -321,12 +361,16 @@ impl VirtualStateProcessor {
             .notify(Notification::SinkBlueScoreChanged(SinkBlueScoreChangedNotification::new(sink_ghostdag_data.blue_score)))
             .expect("expecting an open unbounded channel");
         self.notification_root
-            .notify(Notification::VirtualDaaScoreChanged(VirtualDaaScoreChangedNotification::new(new_virtual_state.daa_score)))
+            .notify(Notification::VirtualDaaScoreChanged(VirtualDaaScoreChangedNotification::new(new_virtual_state.as_ref().into())))
             .expect("expecting an open unbounded channel");
         if self.notification_root.has_subscription(EventType::VirtualChainChanged) {
             // check for subscriptions before the heavy lifting
-            let added_chain_blocks_acceptance_data =
-                chain_path.added.iter().copied().map(|added| self.acceptance_data_store.get(added).unwrap()).collect_vec();
+            let added_chain_blocks_acceptance_data = chain_path
+                .added
+                .iter()
+                .copied()
+                .map(|added| self.acceptance_data_store.get(added).or_store_failure("acceptance data", added))
+                .collect::<BlockProcessResult<Vec<_>>>()?;
             self.notification_root
                 .notify(Notification::VirtualChainChanged(VirtualChainChangedNotification::new(
                     chain_path.added.into(),
@@ -335,6 +379,7 @@ impl VirtualStateProcessor {
                 )))
                 .expect("expecting an open unbounded channel");
         }
+        Ok(())
     }
 
     pub(crate) fn virtual_finality_point(&self, virtual_ghostdag_data: &GhostdagData, pruning_point: Hash) -> Hash {
@@ -352,11 +397,18 @@ impl VirtualStateProcessor {
     /// The provided `diff` is assumed to initially hold the UTXO diff of `from` from virtual.
     /// The function returns the top-most UTXO-valid block on `chain(to)` which is ideally
     /// `to` itself (with the exception of returning `from` if `to` is already known to be UTXO disqualified).
-    /// When returning it is guaranteed that `diff` holds the diff of the returned block from virtual
-    fn calculate_utxo_state_relatively(&self, stores: &VirtualStores, diff: &mut UtxoDiff, from: Hash, to: Hash) -> Hash {
+    /// When returning it is guaranteed that `diff` holds the diff of the returned block from virtual.
+    /// On a store failure `diff` is left partially updated and must be discarded
+    fn calculate_utxo_state_relatively(
+        &self,
+        stores: &VirtualStores,
+        diff: &mut UtxoDiff,
+        from: Hash,
+        to: Hash,
+    ) -> BlockProcessResult<Hash> {
         // Avoid reorging if disqualified status is already known
-        if self.statuses_store.read().get(to).unwrap() == StatusDisqualifiedFromChain {
-            return from;
+        if self.statuses_store.read().get(to).or_store_failure("statuses", to)? == StatusDisqualifiedFromChain {
+            return Ok(from);
         }
 
         let mut split_point: Option<Hash> = None;
@@ -368,13 +420,13 @@ impl VirtualStateProcessor {
                 break;
             }
 
-            let mergeset_diff = self.utxo_diffs_store.get(current).unwrap();
+            let mergeset_diff = self.utxo_diffs_store.get(current).or_store_failure("utxo diffs", current)?;
             // Apply the diff in reverse
             diff.with_diff_in_place(&mergeset_diff.as_reversed()).unwrap();
         }
 
         let split_point = split_point.expect("chain iterator was expected to reach the reorg split point");
-        debug!("VIRTUAL PROCESSOR, found split point: {split_point}");
+        debug!(hash:% = split_point; "VIRTUAL PROCESSOR, found split point: {split_point}");
 
         // A variable holding the most recent UTXO-valid block on `chain(to)` (note that it's maintained such
         // that 'diff' is always its UTXO diff from virtual)
@@ -395,55 +447,79 @@ impl VirtualStateProcessor {
                     diff_point = current;
                 }
                 Err(StoreError::KeyNotFound(_)) => {
-                    if self.statuses_store.read().get(current).unwrap() == StatusDisqualifiedFromChain {
+                    if self.statuses_store.read().get(current).or_store_failure("statuses", current)? == StatusDisqualifiedFromChain {
                         // Current block is already known to be disqualified
                         continue;
                     }
 
-                    let header = self.headers_store.get_header(current).unwrap();
-                    let mergeset_data = self.ghostdag_primary_store.get_data(current).unwrap();
+                    let header = self.headers_store.get_header(current).or_store_failure("headers", current)?;
+                    let mergeset_data = self.ghostdag_primary_store.get_data(current).or_store_failure("ghostdag", current)?;
                     let pov_daa_score = header.daa_score;
 
-                    let selected_parent_multiset_hash = self.utxo_multisets_store.get(selected_parent).unwrap();
+                    let selected_parent_multiset_hash =
+                        self.utxo_multisets_store.get(selected_parent).or_store_failure("utxo multisets", selected_parent)?;
                     let selected_parent_utxo_view = (&stores.utxo_set).compose(&*diff);
 
                     let mut ctx = UtxoProcessingContext::new(mergeset_data.into(), selected_parent_multiset_hash);
+                    if self.store_spent_utxos {
+                        ctx = ctx.with_spent_utxo_entries();
+                    }
 
                     self.calculate_utxo_state(&mut ctx, &selected_parent_utxo_view, pov_daa_score);
                     let res = self.verify_expected_utxo_state(&mut ctx, &selected_parent_utxo_view, &header);
 
                     if let Err(rule_error) = res {
-                        info!("Block {} is disqualified from virtual chain: {}", current, rule_error);
+                        info!(
+                            hash:% = current, error:% = rule_error;
+                            "Block {} is disqualified from virtual chain: {}", current, rule_error
+                        );
                         self.statuses_store.write().set(current, StatusDisqualifiedFromChain).unwrap();
+                        self.record_disqualification_reason(current, rule_error);
                     } else {
-                        debug!("VIRTUAL PROCESSOR, UTXO validated for {current}");
+                        debug!(hash:% = current; "VIRTUAL PROCESSOR, UTXO validated for {current}");
 
                         // Accumulate the diff
                         diff.with_diff_in_place(&ctx.mergeset_diff).unwrap();
                         // Update the diff point
                         diff_point = current;
                         // Commit UTXO data for current chain block
-                        self.commit_utxo_state(current, ctx.mergeset_diff, ctx.multiset_hash, ctx.mergeset_acceptance_data);
+                        self.commit_utxo_state(
+                            current,
+                            ctx.mergeset_diff,
+                            ctx.multiset_hash,
+                            ctx.mergeset_acceptance_data,
+                            ctx.spent_utxo_entries,
+                        );
                         // Count the number of UTXO-processed chain blocks
                         chain_block_counter += 1;
                     }
                 }
-                Err(err) => panic!("unexpected error {err}"),
+                Err(err) => return Err(RuleError::StoreFailure("utxo diffs".to_owned(), current.to_string(), err.to_string())),
             }
         }
         // Report counters
         self.counters.chain_block_counts.fetch_add(chain_block_counter, Ordering::Relaxed);
 
-        diff_point
+        Ok(diff_point)
     }
 
-    fn commit_utxo_state(&self, current: Hash, mergeset_diff: UtxoDiff, multiset: MuHash, acceptance_data: AcceptanceData) {
+    fn commit_utxo_state(
+        &self,
+        current: Hash,
+        mergeset_diff: UtxoDiff,
+        multiset: MuHash,
+        acceptance_data: AcceptanceData,
+        spent_utxo_entries: Option<SpentUtxoEntries>,
+    ) {
         let mut batch = WriteBatch::default();
         self.utxo_diffs_store.insert_batch(&mut batch, current, Arc::new(mergeset_diff)).unwrap();
         self.utxo_multisets_store.insert_batch(&mut batch, current, multiset).unwrap();
         self.acceptance_data_store.insert_batch(&mut batch, current, Arc::new(acceptance_data)).unwrap();
+        if let Some(spent_utxo_entries) = spent_utxo_entries {
+            self.spent_utxo_entries_store.insert_batch(&mut batch, current, Arc::new(spent_utxo_entries)).unwrap();
+        }
         let write_guard = self.statuses_store.set_batch(&mut batch, current, StatusUTXOValid).unwrap();
-        self.db.write(batch).unwrap();
+        self.db.write_critical(batch).unwrap();
         // Calling the drops explicitly after the batch is written in order to avoid possible errors.
         drop(write_guard);
     }
@@ -480,9 +556,10 @@ impl VirtualStateProcessor {
         let mut ctx = UtxoProcessingContext::new((&virtual_ghostdag_data).into(), selected_parent_multiset);
 
         // Calc virtual DAA score, difficulty bits and past median time
-        let virtual_daa_window = self.window_manager.block_daa_window(&virtual_ghostdag_data)?;
+        let BlockWindows { daa_window: virtual_daa_window, past_median_time } =
+            self.window_manager.block_windows(&virtual_ghostdag_data)?;
         let virtual_bits = self.window_manager.calculate_difficulty_bits(&virtual_ghostdag_data, &virtual_daa_window);
-        let virtual_past_median_time = self.window_manager.calc_past_median_time(&virtual_ghostdag_data)?.0;
+        let virtual_past_median_time = past_median_time?.0;
 
         // Calc virtual UTXO state relative to selected parent
         self.calculate_utxo_state(&mut ctx, &selected_parent_utxo_view, virtual_daa_window.daa_score);
@@ -526,7 +603,7 @@ impl VirtualStateProcessor {
         selected_chain_write.apply_changes(&mut batch, chain_path).unwrap();
 
         // Flush the batch changes
-        self.db.write(batch).unwrap();
+        self.db.write_critical(batch).unwrap();
 
         // Calling the drops explicitly after the batch is written in order to avoid possible errors.
         drop(virtual_write);
@@ -557,16 +634,37 @@ impl VirtualStateProcessor {
         tips: Vec<Hash>,
         finality_point: Hash,
         pruning_point: Hash,
-    ) -> (Hash, VecDeque<Hash>) {
+    ) -> BlockProcessResult<(Hash, VecDeque<Hash>)> {
+        // The initial diff point is the previous sink
+        let mut diff_point = prev_sink;
+        let (sink, candidates, _) = self.search_sink(tips, finality_point, pruning_point, |candidate| {
+            diff_point = self.calculate_utxo_state_relatively(stores, diff, diff_point, candidate)?;
+            // `diff_point == candidate` indicates that candidate has valid UTXO state and that `diff` represents its diff from virtual
+            Ok(diff_point == candidate)
+        })?;
+        Ok((sink, candidates))
+    }
+
+    /// Searches for the sink among `tips` and their past, in descending blue work order, where `is_valid_sink` decides whether
+    /// a candidate within the chain future of the finality point is a valid sink. Returns the sink, the remaining virtual parent
+    /// candidates and the candidates which were excluded along the way.
+    fn search_sink(
+        &self,
+        tips: Vec<Hash>,
+        finality_point: Hash,
+        pruning_point: Hash,
+        mut is_valid_sink: impl FnMut(Hash) -> BlockProcessResult<bool>,
+    ) -> BlockProcessResult<(Hash, VecDeque<Hash>, Vec<ExcludedVirtualParent>)> {
         // TODO: tests
 
         let mut heap = tips
             .into_iter()
-            .map(|block| SortableBlock { hash: block, blue_work: self.ghostdag_primary_store.get_blue_work(block).unwrap() })
-            .collect::<BinaryHeap<_>>();
-
-        // The initial diff point is the previous sink
-        let mut diff_point = prev_sink;
+            .map(|block| -> BlockProcessResult<_> {
+                let blue_work = self.ghostdag_primary_store.get_blue_work(block).or_store_failure("ghostdag", block)?;
+                Ok(SortableBlock { hash: block, blue_work })
+            })
+            .collect::<BlockProcessResult<BinaryHeap<_>>>()?;
+        let mut exclusions = Vec::new();
 
         // We maintain the following invariant: `heap` is an antichain.
         // It holds at step 0 since tips are an antichain, and remains through the loop
@@ -575,34 +673,46 @@ impl VirtualStateProcessor {
         loop {
             let candidate = heap.pop().expect("valid sink must exist").hash;
             if self.reachability_service.is_chain_ancestor_of(finality_point, candidate) {
-                diff_point = self.calculate_utxo_state_relatively(stores, diff, diff_point, candidate);
-                if diff_point == candidate {
-                    // This indicates that candidate has valid UTXO state and that `diff` represents its diff from virtual
-
+                if is_valid_sink(candidate)? {
                     // All blocks with lower blue work than filtering_root are:
                     // 1. not in its future (bcs blue work is monotonic),
                     // 2. will be removed eventually by the bounded merge check.
                     // Hence as an optimization we prefer removing such blocks in advance to allow valid tips to be considered.
-                    let filtering_root = self.depth_store.merge_depth_root(candidate).unwrap();
+                    let filtering_root = self.depth_store.merge_depth_root(candidate).or_store_failure("depth", candidate)?;
                     let filtering_blue_work = self.ghostdag_primary_store.get_blue_work(filtering_root).unwrap_or_default();
-                    return (
-                        candidate,
-                        heap.into_sorted_iter().take_while(|s| s.blue_work >= filtering_blue_work).map(|s| s.hash).collect(),
+                    let (candidates, filtered): (Vec<_>, Vec<_>) =
+                        heap.into_sorted_iter().partition(|s| s.blue_work >= filtering_blue_work);
+                    exclusions.extend(
+                        filtered
+                            .into_iter()
+                            .map(|s| ExcludedVirtualParent::new(s.hash, VirtualParentExclusionReason::MergeDepthViolation)),
                     );
+                    return Ok((candidate, candidates.into_iter().map(|s| s.hash).collect(), exclusions));
                 } else {
-                    debug!("Block candidate {} has invalid UTXO state and is ignored from Virtual chain.", candidate)
+                    debug!(
+                        hash:% = candidate;
+                        "Block candidate {} has invalid UTXO state and is ignored from Virtual chain.", candidate
+                    );
+                    exclusions.push(ExcludedVirtualParent::new(candidate, VirtualParentExclusionReason::Disqualified));
+                }
+            } else {
+                if finality_point != pruning_point {
+                    // `finality_point == pruning_point` indicates we are at IBD start hence no warning required
+                    warn!(
+                        hash:% = candidate;
+                        "Finality Violation Detected. Block {} violates finality and is ignored from Virtual chain.", candidate
+                    );
                 }
-            } else if finality_point != pruning_point {
-                // `finality_point == pruning_point` indicates we are at IBD start hence no warning required
-                warn!("Finality Violation Detected. Block {} violates finality and is ignored from Virtual chain.", candidate);
+                exclusions.push(ExcludedVirtualParent::new(candidate, VirtualParentExclusionReason::MergeDepthViolation));
             }
             // PRUNE SAFETY: see comment within [`resolve_virtual`]
             let prune_guard = self.pruning_lock.blocking_read();
-            for parent in self.relations_service.get_parents(candidate).unwrap().iter().copied() {
+            for parent in self.relations_service.get_parents(candidate).or_store_failure("relations", candidate)?.iter().copied() {
                 if self.reachability_service.is_dag_ancestor_of(finality_point, parent)
                     && !self.reachability_service.is_dag_ancestor_of_any(parent, &mut heap.iter().map(|sb| sb.hash))
                 {
-                    heap.push(SortableBlock { hash: parent, blue_work: self.ghostdag_primary_store.get_blue_work(parent).unwrap() });
+                    let blue_work = self.ghostdag_primary_store.get_blue_work(parent).or_store_failure("ghostdag", parent)?;
+                    heap.push(SortableBlock { hash: parent, blue_work });
                 }
             }
             drop(prune_guard);
@@ -617,9 +727,21 @@ impl VirtualStateProcessor {
     pub(super) fn pick_virtual_parents(
         &self,
         selected_parent: Hash,
-        mut candidates: VecDeque<Hash>,
+        candidates: VecDeque<Hash>,
         pruning_point: Hash,
     ) -> (Vec<Hash>, GhostdagData) {
+        let (virtual_parents, ghostdag_data, _) = self.select_virtual_parents(selected_parent, candidates, pruning_point);
+        (virtual_parents, ghostdag_data)
+    }
+
+    /// Same as [`Self::pick_virtual_parents`], additionally returning the candidates which were not selected along with the
+    /// reason of their exclusion
+    fn select_virtual_parents(
+        &self,
+        selected_parent: Hash,
+        mut candidates: VecDeque<Hash>,
+        pruning_point: Hash,
+    ) -> (Vec<Hash>, GhostdagData, Vec<ExcludedVirtualParent>) {
         // TODO: tests
 
         // Mergeset increasing might traverse DAG areas which are below the finality point and which theoretically
@@ -630,6 +752,7 @@ impl VirtualStateProcessor {
         let _prune_guard = self.pruning_lock.blocking_read();
         let max_block_parents = self.max_block_parents as usize;
         let max_candidates = self.max_virtual_parent_candidates();
+        let mut exclusions = Vec::new();
 
         // Prioritize half the blocks with highest blue work and pick the rest randomly to ensure diversity between nodes
         if candidates.len() > max_candidates {
@@ -646,7 +769,9 @@ impl VirtualStateProcessor {
             }
 
             // Truncate the unchosen elements
-            candidates.truncate(max_candidates);
+            exclusions.extend(
+                candidates.drain(max_candidates..).map(|h| ExcludedVirtualParent::new(h, VirtualParentExclusionReason::ParentLimit)),
+            );
         } else if candidates.len() > max_block_parents / 2 {
             // Fallback to a simpler algo in this case
             candidates.make_contiguous()[max_block_parents / 2..].shuffle(&mut rand::thread_rng());
@@ -659,6 +784,13 @@ impl VirtualStateProcessor {
         // Try adding parents as long as mergeset size and number of parents limits are not reached
         while let Some(candidate) = candidates.pop_front() {
             if mergeset_size >= self.mergeset_size_limit || virtual_parents.len() >= max_block_parents {
+                let reason = if mergeset_size >= self.mergeset_size_limit {
+                    VirtualParentExclusionReason::MergesetSizeLimit
+                } else {
+                    VirtualParentExclusionReason::ParentLimit
+                };
+                exclusions
+                    .extend(std::iter::once(candidate).chain(candidates.drain(..)).map(|h| ExcludedVirtualParent::new(h, reason)));
                 break;
             }
             match self.mergeset_increase(&virtual_parents, candidate, self.mergeset_size_limit - mergeset_size) {
@@ -667,19 +799,68 @@ impl VirtualStateProcessor {
                     virtual_parents.push(candidate);
                 }
                 MergesetIncreaseResult::Rejected { new_candidate } => {
+                    exclusions.push(ExcludedVirtualParent::new(candidate, VirtualParentExclusionReason::MergesetSizeLimit));
                     // If we already have a candidate in the past of new candidate then skip.
                     if self.reachability_service.is_any_dag_ancestor(&mut candidates.iter().copied(), new_candidate) {
                         continue; // TODO: not sure this test is needed if candidates invariant as antichain is kept
                     }
                     // Remove all candidates which are in the future of the new candidate
-                    candidates.retain(|&h| !self.reachability_service.is_dag_ancestor_of(new_candidate, h));
+                    candidates.retain(|&h| {
+                        let retain = !self.reachability_service.is_dag_ancestor_of(new_candidate, h);
+                        if !retain {
+                            exclusions.push(ExcludedVirtualParent::new(h, VirtualParentExclusionReason::MergesetSizeLimit));
+                        }
+                        retain
+                    });
                     candidates.push_back(new_candidate);
                 }
             }
         }
         assert!(mergeset_size <= self.mergeset_size_limit);
         assert!(virtual_parents.len() <= max_block_parents);
-        self.remove_bounded_merge_breaking_parents(virtual_parents, pruning_point)
+        let (virtual_parents, ghostdag_data, merge_breaking_parents) =
+            self.remove_bounded_merge_breaking_parents(virtual_parents, pruning_point);
+        exclusions.extend(
+            merge_breaking_parents
+                .into_iter()
+                .map(|h| ExcludedVirtualParent::new(h, VirtualParentExclusionReason::MergeDepthViolation)),
+        );
+        (virtual_parents, ghostdag_data, exclusions)
+    }
+
+    /// Traces the virtual parent selection over the current tips, reporting why each tip was excluded from the virtual parents.
+    ///
+    /// The trace follows the code path of virtual resolving, except that the sink is taken from the current virtual state rather
+    /// than searched for by computing UTXO states, so that tracing has no side effects. Candidates are randomly picked as in the
+    /// actual selection, hence the virtual parents of the trace might differ from the current ones when there are more candidates
+    /// than can be considered.
+    pub fn virtual_parent_selection_trace(&self) -> VirtualParentSelectionTrace {
+        let pruning_point = self.pruning_point_store.read().pruning_point().unwrap();
+        let virtual_state = self.virtual_stores.read().state.get().unwrap();
+        let sink = virtual_state.ghostdag_data.selected_parent;
+        let finality_point = self.virtual_finality_point(&virtual_state.ghostdag_data, pruning_point);
+
+        // PRUNE SAFETY: see comment within [`resolve_virtual`]
+        let prune_guard = self.pruning_lock.blocking_read();
+        let tips = self.body_tips_store.read().get().unwrap().read().iter().copied().collect_vec();
+        let (chain_tips, finality_violating_tips): (Vec<_>, Vec<_>) =
+            tips.iter().copied().partition(|&h| self.reachability_service.is_dag_ancestor_of(finality_point, h));
+        drop(prune_guard);
+
+        let (selected_parent, candidates, mut exclusions) =
+            self.search_sink(chain_tips, finality_point, pruning_point, |candidate| Ok(candidate == sink)).unwrap();
+        let (virtual_parents, _, parent_exclusions) = self.select_virtual_parents(selected_parent, candidates, pruning_point);
+        exclusions.extend(parent_exclusions);
+        exclusions.extend(
+            finality_violating_tips
+                .into_iter()
+                .map(|h| ExcludedVirtualParent::new(h, VirtualParentExclusionReason::MergeDepthViolation)),
+        );
+
+        // Exclusions also cover blocks in the past of tips which were considered along the way
+        let tip_set: BlockHashSet = tips.iter().copied().collect();
+        exclusions.retain(|excluded| tip_set.contains(&excluded.hash));
+        VirtualParentSelectionTrace { tips, selected_parent, virtual_parents, excluded_tips: exclusions }
     }
 
     fn mergeset_increase(&self, selected_parents: &[Hash], candidate: Hash, budget: u64) -> MergesetIncreaseResult {
@@ -717,7 +898,7 @@ impl VirtualStateProcessor {
         &self,
         mut virtual_parents: Vec<Hash>,
         current_pruning_point: Hash,
-    ) -> (Vec<Hash>, GhostdagData) {
+    ) -> (Vec<Hash>, GhostdagData, Vec<Hash>) {
         let mut ghostdag_data = self.ghostdag_manager.ghostdag(&virtual_parents);
         let merge_depth_root = self.depth_manager.calc_merge_depth_root(&ghostdag_data, current_pruning_point);
         let mut kosherizing_blues: Option<Vec<Hash>> = None;
@@ -741,14 +922,17 @@ impl VirtualStateProcessor {
             }
         }
 
+        let mut merge_breaking_parents = Vec::new();
         if !bad_reds.is_empty() {
             // Remove all parents which lead to merging a bad red
-            virtual_parents.retain(|&h| !self.reachability_service.is_any_dag_ancestor(&mut bad_reds.iter().copied(), h));
+            (virtual_parents, merge_breaking_parents) = virtual_parents
+                .into_iter()
+                .partition(|&h| !self.reachability_service.is_any_dag_ancestor(&mut bad_reds.iter().copied(), h));
             // Recompute ghostdag data since parents changed
             ghostdag_data = self.ghostdag_manager.ghostdag(&virtual_parents);
         }
 
-        (virtual_parents, ghostdag_data)
+        (virtual_parents, ghostdag_data, merge_breaking_parents)
     }
 
     fn validate_mempool_transaction_impl(
@@ -892,6 +1076,11 @@ impl VirtualStateProcessor {
             }
         }
 
+        // Transactions of the later batches were appended, so the selector restores its canonical order
+        if !invalid_transactions.is_empty() {
+            tx_selector.sort_selected_transactions(&mut txs);
+        }
+
         // Check whether this was an overall successful selection episode. We pass this decision
         // to the selector implementation which has the broadest picture and can use mempool config
         // and context
@@ -904,7 +1093,8 @@ impl VirtualStateProcessor {
         drop(virtual_read);
 
         // Build the template
-        self.build_block_template_from_virtual_state(virtual_state, miner_data, txs)
+        let selection_stats = tx_selector.selection_stats();
+        Ok(self.build_block_template_from_virtual_state(virtual_state, miner_data, txs)?.with_selection_stats(selection_stats))
     }
 
     pub(crate) fn validate_block_template_transactions(
@@ -927,6 +1117,126 @@ impl VirtualStateProcessor {
         }
     }
 
+    /// Validates a block template against the current virtual state, which the template is expected to build on.
+    ///
+    /// The header is assumed to be validated in isolation. The checks in context are those of a submission of the
+    /// template, the virtual GHOSTDAG data standing for the GHOSTDAG data of the template. `validate_body` is called
+    /// with the past median time of the template and is expected to validate its body and return its mass.
+    pub fn validate_block_template(
+        &self,
+        block: &Block,
+        validate_body: impl FnOnce(u64) -> BlockProcessResult<u64>,
+    ) -> BlockProcessResult<BlockTemplateValidation> {
+        // Holding the read lock throughout guarantees that the body is validated against the same UTXO set as the header
+        let virtual_read = self.virtual_stores.read();
+        let virtual_state = virtual_read.state.get().unwrap();
+        let header = &block.header;
+
+        let mut expected_parents = virtual_state.parents.clone();
+        let mut parents = header.direct_parents().to_vec();
+        expected_parents.sort();
+        parents.sort();
+        if parents != expected_parents {
+            return Err(RuleError::UnexpectedTemplateParents(
+                VecDisplay(virtual_state.parents.clone()),
+                VecDisplay(header.direct_parents().to_vec()),
+            ));
+        }
+        if header.daa_score != virtual_state.daa_score {
+            return Err(RuleError::UnexpectedHeaderDaaScore(virtual_state.daa_score, header.daa_score));
+        }
+        if header.bits != virtual_state.bits {
+            return Err(RuleError::UnexpectedDifficulty(header.bits, virtual_state.bits));
+        }
+        if header.blue_score != virtual_state.ghostdag_data.blue_score {
+            return Err(RuleError::UnexpectedHeaderBlueScore(virtual_state.ghostdag_data.blue_score, header.blue_score));
+        }
+        if header.blue_work != virtual_state.ghostdag_data.blue_work {
+            return Err(RuleError::UnexpectedHeaderBlueWork(virtual_state.ghostdag_data.blue_work, header.blue_work));
+        }
+        if header.timestamp <= virtual_state.past_median_time {
+            return Err(RuleError::TimeTooOld(header.timestamp, virtual_state.past_median_time));
+        }
+
+        {
+            // [`calc_block_parents`] can use deep blocks below the pruning point for this calculation, so we
+            // need to hold the pruning lock.
+            let _prune_guard = self.pruning_lock.blocking_read();
+            let pruning_info = self.pruning_point_store.read().get().unwrap();
+            let expected_pruning_point =
+                self.pruning_point_manager.expected_header_pruning_point(virtual_state.ghostdag_data.to_compact(), pruning_info);
+            if header.pruning_point != expected_pruning_point {
+                return Err(RuleError::WrongHeaderPruningPoint(expected_pruning_point, header.pruning_point));
+            }
+            let expected_parents_by_level =
+                self.parents_manager.calc_block_parents(pruning_info.pruning_point, &virtual_state.parents);
+            if header.parents_by_level.len() != expected_parents_by_level.len()
+                || !expected_parents_by_level.iter().zip(header.parents_by_level.iter()).all(
+                    |(expected_level_parents, level_parents)| {
+                        let expected_set = HashSet::<&Hash>::from_iter(expected_level_parents);
+                        level_parents.len() == expected_level_parents.len()
+                            && level_parents.iter().all(|parent| expected_set.contains(parent))
+                    },
+                )
+            {
+                return Err(RuleError::UnexpectedIndirectParents(
+                    TwoDimVecDisplay(expected_parents_by_level),
+                    TwoDimVecDisplay(header.parents_by_level.clone()),
+                ));
+            }
+        }
+
+        let mass = validate_body(virtual_state.past_median_time)?;
+
+        // The UTXO state of a block merging exactly the virtual mergeset is the virtual UTXO state
+        let expected_commitment = virtual_state.multiset.clone().finalize();
+        if header.utxo_commitment != expected_commitment {
+            return Err(RuleError::BadUTXOCommitment(block.hash(), header.utxo_commitment, expected_commitment));
+        }
+        let expected_accepted_id_merkle_root = kaspa_merkle::calc_merkle_root(virtual_state.accepted_tx_ids.iter().copied());
+        if header.accepted_id_merkle_root != expected_accepted_id_merkle_root {
+            return Err(RuleError::BadAcceptedIDMerkleRoot(
+                block.hash(),
+                header.accepted_id_merkle_root,
+                expected_accepted_id_merkle_root,
+            ));
+        }
+        self.verify_coinbase_transaction(
+            &block.transactions[0],
+            header.daa_score,
+            &virtual_state.ghostdag_data,
+            &virtual_state.mergeset_rewards,
+            &virtual_state.mergeset_non_daa,
+        )?;
+
+        // Unlike a submission which only reports how many transactions are invalid, report the error of each
+        let virtual_utxo_view = &virtual_read.utxo_set;
+        let results = self.thread_pool.install(|| {
+            block.transactions[1..]
+                .par_iter()
+                .map(|tx| {
+                    self.validate_transaction_in_utxo_context(tx, &virtual_utxo_view, header.daa_score, TxValidationFlags::Full)
+                        .map(|validated_tx| validated_tx.calculated_fee)
+                })
+                .collect::<Vec<TxResult<u64>>>()
+        });
+        let mut total_fees = 0u64;
+        let mut invalid_transactions = HashMap::new();
+        for (tx, res) in block.transactions[1..].iter().zip(results) {
+            match res {
+                Ok(fee) => total_fees += fee,
+                Err(e) => {
+                    invalid_transactions.insert(tx.id(), e);
+                }
+            }
+        }
+        if !invalid_transactions.is_empty() {
+            return Err(RuleError::InvalidTransactionsInNewBlock(invalid_transactions));
+        }
+
+        Ok(BlockTemplateValidation { mass, total_fees })
+    }
+
     pub(crate) fn build_block_template_from_virtual_state(
         &self,
         virtual_state: Arc<VirtualState>,
@@ -950,7 +1260,7 @@ impl VirtualStateProcessor {
             )
             .unwrap();
         txs.insert(0, coinbase.tx);
-        let version = BLOCK_VERSION;
+        let version = self.header_version_policy.highest_active_version(virtual_state.daa_score);
         let parents_by_level = self.parents_manager.calc_block_parents(pruning_info.pruning_point, &virtual_state.parents);
 
         // Hash according to hardfork activation
@@ -967,7 +1277,7 @@ impl VirtualStateProcessor {
             hash_merkle_root,
             accepted_id_merkle_root,
             utxo_commitment,
-            u64::max(min_block_time, unix_now()),
+            u64::max(min_block_time, self.clock.unix_now()),
             virtual_state.bits,
             0,
             virtual_state.daa_score,
@@ -1009,7 +1319,13 @@ impl VirtualStateProcessor {
     /// Note that pruning point-related stores are initialized by `init`
     pub fn process_genesis(self: &Arc<Self>) {
         // Write the UTXO state of genesis
-        self.commit_utxo_state(self.genesis.hash, UtxoDiff::default(), MuHash::new(), AcceptanceData::default());
+        self.commit_utxo_state(
+            self.genesis.hash,
+            UtxoDiff::default(),
+            MuHash::new(),
+            AcceptanceData::default(),
+            self.store_spent_utxos.then(SpentUtxoEntries::new),
+        );
 
         // Init the virtual selected chain store
         let mut batch = WriteBatch::default();
@@ -1,16 +1,27 @@
-use crate::{consensus::test_consensus::TestConsensus, model::services::reachability::ReachabilityService};
+use crate::{
+    consensus::test_consensus::TestConsensus, constants::TX_VERSION, errors::RuleError,
+    model::services::reachability::ReachabilityService,
+};
 use kaspa_consensus_core::{
     api::ConsensusApi,
     block::{Block, BlockTemplate, MutableBlock, TemplateBuildMode, TemplateTransactionSelector},
     blockhash,
     blockstatus::BlockStatus,
     coinbase::MinerData,
-    config::{params::MAINNET_PARAMS, ConfigBuilder},
-    tx::{ScriptPublicKey, ScriptVec, Transaction},
+    config::{params::MAINNET_PARAMS, Config, ConfigBuilder},
+    merkle::calc_hash_merkle_root,
+    subnets::SUBNETWORK_ID_NATIVE,
+    tx::{ScriptPublicKey, ScriptVec, Transaction, TransactionInput, TransactionOutput},
+    virtual_parents::{VirtualParentExclusionReason, VirtualParentSelectionTrace},
     BlockHashSet,
 };
+use kaspa_core::{
+    assert_match,
+    time::{Clock, SimulatedClock},
+};
 use kaspa_hashes::Hash;
-use std::{collections::VecDeque, thread::JoinHandle};
+use kaspa_txscript::opcodes::codes::OpTrue;
+use std::{collections::VecDeque, thread::JoinHandle, time::Duration};
 
 struct OnetimeTxSelector {
     txs: Option<Vec<Transaction>>,
@@ -40,7 +51,8 @@ struct TestContext {
     consensus: TestConsensus,
     join_handles: Vec<JoinHandle<()>>,
     miner_data: MinerData,
-    simulated_time: u64,
+    /// Clock of consensus, advanced by the target time per block with every block built by the test
+    clock: SimulatedClock,
     current_templates: VecDeque<BlockTemplate>,
     current_tips: BlockHashSet,
 }
@@ -52,15 +64,16 @@ impl Drop for TestContext {
 }
 
 impl TestContext {
-    fn new(consensus: TestConsensus) -> Self {
+    fn new(config: &Config) -> Self {
+        let clock = SimulatedClock::new(config.genesis.timestamp);
+        let consensus = TestConsensus::new(&config.to_builder().set_clock(clock.as_dyn()).build());
         let join_handles = consensus.init();
         let genesis_hash = consensus.params().genesis.hash;
-        let simulated_time = consensus.params().genesis.timestamp;
         Self {
             consensus,
             join_handles,
             miner_data: new_miner_data(),
-            simulated_time,
+            clock,
             current_templates: Default::default(),
             current_tips: BlockHashSet::from_iter([genesis_hash]),
         }
@@ -68,8 +81,8 @@ impl TestContext {
 
     pub fn build_block_template_row(&mut self, nonces: impl Iterator<Item = usize>) -> &mut Self {
         for nonce in nonces {
-            self.simulated_time += self.consensus.params().target_time_per_block;
-            self.current_templates.push_back(self.build_block_template(nonce as u64, self.simulated_time));
+            let timestamp = self.clock.advance_millis(self.consensus.params().target_time_per_block);
+            self.current_templates.push_back(self.build_block_template(nonce as u64, timestamp));
         }
         self
     }
@@ -93,8 +106,8 @@ impl TestContext {
     pub async fn build_and_insert_disqualified_chain(&mut self, mut parents: Vec<Hash>, len: usize) -> Hash {
         // The chain will be disqualified since build_block_with_parents builds utxo-invalid blocks
         for _ in 0..len {
-            self.simulated_time += self.consensus.params().target_time_per_block;
-            let b = self.build_block_with_parents(parents, 0, self.simulated_time);
+            let timestamp = self.clock.advance_millis(self.consensus.params().target_time_per_block);
+            let b = self.build_block_with_parents(parents, 0, timestamp);
             parents = vec![b.header.hash];
             self.validate_and_insert_block(b.to_immutable()).await;
         }
@@ -150,12 +163,25 @@ impl TestContext {
         assert!(self.consensus.body_tips().iter().copied().any(|h| self.consensus.block_status(h) == BlockStatus::StatusUTXOValid));
         self
     }
+
+    /// Returns the virtual parent selection trace after asserting it is consistent with the current tips and sink
+    pub fn virtual_parent_selection_trace(&self) -> VirtualParentSelectionTrace {
+        let trace = self.consensus.get_virtual_parent_selection_trace();
+        assert_eq!(BlockHashSet::from_iter(trace.tips.iter().copied()), BlockHashSet::from_iter(self.consensus.get_tips()));
+        assert_eq!(trace.selected_parent, self.consensus.get_sink());
+        assert_eq!(trace.virtual_parents.first(), Some(&trace.selected_parent));
+        for tip in trace.tips.iter() {
+            let excluded = trace.excluded_tips.iter().filter(|excluded| excluded.hash == *tip).count();
+            assert_eq!(excluded + trace.virtual_parents.contains(tip) as usize, 1, "tip {tip} should be either selected or excluded");
+        }
+        trace
+    }
 }
 
 #[tokio::test]
 async fn template_mining_sanity_test() {
     let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
-    let mut ctx = TestContext::new(TestConsensus::new(&config));
+    let mut ctx = TestContext::new(&config);
     let rounds = 10;
     let width = 3;
     for _ in 0..rounds {
@@ -179,7 +205,7 @@ async fn antichain_merge_test() {
         })
         .build();
 
-    let mut ctx = TestContext::new(TestConsensus::new(&config));
+    let mut ctx = TestContext::new(&config);
 
     // Build a large 32-wide antichain
     ctx.build_block_template_row(0..32)
@@ -207,7 +233,7 @@ async fn basic_utxo_disqualified_test() {
         })
         .build();
 
-    let mut ctx = TestContext::new(TestConsensus::new(&config));
+    let mut ctx = TestContext::new(&config);
 
     // Mine a valid chain
     for _ in 0..10 {
@@ -239,7 +265,7 @@ async fn double_search_disqualified_test() {
             p.min_difficulty_window_len = p.legacy_difficulty_window_size;
         })
         .build();
-    let mut ctx = TestContext::new(TestConsensus::new(&config));
+    let mut ctx = TestContext::new(&config);
 
     // Mine 3 valid blocks over genesis
     ctx.build_block_template_row(0..3)
@@ -296,6 +322,226 @@ async fn double_search_disqualified_test() {
     ctx.assert_tips_num(1);
 }
 
+#[tokio::test]
+async fn virtual_parent_selection_trace_parent_limit_test() {
+    let config = ConfigBuilder::new(MAINNET_PARAMS)
+        .skip_proof_of_work()
+        .edit_consensus_params(|p| {
+            p.max_block_parents = 4;
+            p.mergeset_size_limit = 100;
+        })
+        .build();
+    let mut ctx = TestContext::new(&config);
+
+    // Build a 32-wide antichain, of which only 4 blocks can be virtual parents
+    ctx.build_block_template_row(0..32).validate_and_insert_row().await.assert_tips();
+
+    let trace = ctx.virtual_parent_selection_trace();
+    assert_eq!(trace.virtual_parents.len(), 4);
+    assert_eq!(trace.excluded_tips.len(), 28);
+    assert!(trace.excluded_tips.iter().all(|excluded| excluded.reason == VirtualParentExclusionReason::ParentLimit));
+}
+
+#[tokio::test]
+async fn virtual_parent_selection_trace_mergeset_size_limit_test() {
+    let config = ConfigBuilder::new(MAINNET_PARAMS)
+        .skip_proof_of_work()
+        .edit_consensus_params(|p| {
+            p.max_block_parents = 4;
+            p.mergeset_size_limit = 10;
+        })
+        .build();
+    let mut ctx = TestContext::new(&config);
+
+    // Mine a valid chain
+    for _ in 0..20 {
+        ctx.build_block_template_row(0..1).validate_and_insert_row().await.assert_valid_utxo_tip();
+    }
+    let sink = ctx.consensus.get_sink();
+
+    // Mine a shorter side chain, too large to be merged as a whole
+    let side_tip = ctx.build_and_insert_disqualified_chain(vec![config.genesis.hash], 15).await;
+
+    let trace = ctx.virtual_parent_selection_trace();
+    assert_eq!(trace.selected_parent, sink);
+    assert_eq!(trace.excluded_tips.len(), 1);
+    assert_eq!(trace.excluded_tips[0].hash, side_tip);
+    assert_eq!(trace.excluded_tips[0].reason, VirtualParentExclusionReason::MergesetSizeLimit);
+    // A block in the past of the side tip is merged instead
+    assert_eq!(trace.virtual_parents.len(), 2);
+    assert!(ctx.consensus.reachability_service().is_dag_ancestor_of(trace.virtual_parents[1], side_tip));
+}
+
+#[tokio::test]
+async fn virtual_parent_selection_trace_merge_depth_test() {
+    let config = ConfigBuilder::new(MAINNET_PARAMS)
+        .skip_proof_of_work()
+        .edit_consensus_params(|p| {
+            p.max_block_parents = 4;
+            p.mergeset_size_limit = 10;
+            p.merge_depth = 7;
+        })
+        .build();
+    let mut ctx = TestContext::new(&config);
+
+    // Mine a valid chain
+    for _ in 0..20 {
+        ctx.build_block_template_row(0..1).validate_and_insert_row().await.assert_valid_utxo_tip();
+    }
+    let sink = ctx.consensus.get_sink();
+
+    // Mine a short side chain from genesis, which lies below the merge depth root of the sink
+    let side_tip = ctx.build_and_insert_disqualified_chain(vec![config.genesis.hash], 3).await;
+
+    let trace = ctx.virtual_parent_selection_trace();
+    assert_eq!(trace.selected_parent, sink);
+    assert_eq!(trace.virtual_parents, vec![sink]);
+    assert_eq!(trace.excluded_tips.len(), 1);
+    assert_eq!(trace.excluded_tips[0].hash, side_tip);
+    assert_eq!(trace.excluded_tips[0].reason, VirtualParentExclusionReason::MergeDepthViolation);
+}
+
+#[tokio::test]
+async fn virtual_parent_selection_trace_disqualified_test() {
+    let config = ConfigBuilder::new(MAINNET_PARAMS)
+        .skip_proof_of_work()
+        .edit_consensus_params(|p| {
+            p.max_block_parents = 4;
+            p.mergeset_size_limit = 10;
+        })
+        .build();
+    let mut ctx = TestContext::new(&config);
+
+    // Mine a valid chain
+    for _ in 0..10 {
+        ctx.build_block_template_row(0..1).validate_and_insert_row().await.assert_valid_utxo_tip();
+    }
+    let sink = ctx.consensus.get_sink();
+
+    // Mine a longer disqualified chain
+    let disqualified_tip = ctx.build_and_insert_disqualified_chain(vec![config.genesis.hash], 20).await;
+
+    let trace = ctx.virtual_parent_selection_trace();
+    assert_eq!(trace.selected_parent, sink);
+    assert_eq!(trace.excluded_tips.len(), 1);
+    assert_eq!(trace.excluded_tips[0].hash, disqualified_tip);
+    assert_eq!(trace.excluded_tips[0].reason, VirtualParentExclusionReason::Disqualified);
+}
+
+#[tokio::test]
+async fn validate_modified_block_template_test() {
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().edit_consensus_params(|p| p.coinbase_maturity = 0).build();
+    let mut ctx = TestContext::new(&config);
+    // Coinbase outputs paying to OpTrue can be spent with an empty signature script
+    let any_one_can_spend = ScriptPublicKey::new(0, ScriptVec::from_slice(&[OpTrue]));
+    ctx.miner_data = MinerData::new(any_one_can_spend.clone(), vec![]);
+    for _ in 0..5 {
+        ctx.build_block_template_row(0..1).validate_and_insert_row().await.assert_valid_utxo_tip();
+    }
+
+    let template = ctx.build_block_template(0, ctx.clock.unix_now() + config.target_time_per_block);
+    // The template as built is valid, its only transaction being the coinbase
+    let template_validation = ctx.consensus.validate_block_template(template.block.clone().to_immutable()).unwrap();
+    assert_eq!(template_validation.total_fees, 0);
+
+    // Inject transactions into the template as a pool would
+    let utxos = ctx.consensus.get_virtual_utxos(None, usize::MAX, false);
+    let mut spendable = utxos.into_iter().filter(|(_, entry)| entry.amount > 10_000);
+    let mut block = template.block.clone();
+    for fee in [3_000, 2_000] {
+        let (outpoint, entry) = spendable.next().unwrap();
+        let tx = Transaction::new(
+            TX_VERSION,
+            vec![TransactionInput::new(outpoint, vec![], 0, 0)],
+            vec![TransactionOutput::new(entry.amount - fee, any_one_can_spend.clone())],
+            0,
+            SUBNETWORK_ID_NATIVE,
+            0,
+            vec![],
+        );
+        block.transactions.push(tx);
+    }
+    block.header.hash_merkle_root = calc_hash_merkle_root(block.transactions.iter());
+    block.header.finalize();
+
+    let validation = ctx.consensus.validate_block_template(block.clone().to_immutable()).unwrap();
+    assert_eq!(validation.total_fees, 5_000);
+    assert!(validation.mass > template_validation.mass);
+    assert_eq!(
+        validation.mass,
+        ctx.consensus.block_body_processor().validate_body_in_isolation(&block.clone().to_immutable()).unwrap()
+    );
+
+    // The modified template is accepted as the new sink once submitted, making the template stale
+    ctx.validate_and_insert_block(block.clone().to_immutable()).await.assert_valid_utxo_tip();
+    assert_eq!(ctx.consensus.get_sink(), block.header.hash);
+    assert_match!(ctx.consensus.validate_block_template(block.to_immutable()), Err(RuleError::UnexpectedTemplateParents(_, _)));
+}
+
+#[tokio::test]
+async fn validate_block_template_wrong_coinbase_amount_test() {
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+    let mut ctx = TestContext::new(&config);
+    for _ in 0..3 {
+        ctx.build_block_template_row(0..1).validate_and_insert_row().await.assert_valid_utxo_tip();
+    }
+
+    let mut block = ctx.build_block_template(0, ctx.clock.unix_now() + config.target_time_per_block).block;
+    let coinbase = &mut block.transactions[0];
+    coinbase.outputs[0].value += 1;
+    coinbase.finalize();
+    block.header.hash_merkle_root = calc_hash_merkle_root(block.transactions.iter());
+    block.header.finalize();
+
+    let err = ctx.consensus.validate_block_template(block.clone().to_immutable()).unwrap_err();
+    let RuleError::BadCoinbasePayment(expected_amounts, actual_total) = &err else { panic!("unexpected error: {err}") };
+    assert_eq!(*actual_total, expected_amounts.total() + 1);
+
+    // A submission of the template disqualifies it from the chain for the very same reason
+    let sink = ctx.consensus.get_sink();
+    let status = ctx.consensus.validate_and_insert_block(block.clone().to_immutable()).virtual_state_task.await.unwrap();
+    assert_eq!(status, BlockStatus::StatusDisqualifiedFromChain);
+    assert_eq!(ctx.consensus.get_sink(), sink);
+    assert_eq!(ctx.consensus.get_disqualification_reason(block.header.hash).map(|reason| reason.to_string()), Some(err.to_string()));
+}
+
+#[tokio::test]
+async fn simulated_clock_test() {
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+    let mut ctx = TestContext::new(&config);
+    for _ in 0..5 {
+        ctx.build_block_template_row(0..1).validate_and_insert_row().await.assert_valid_utxo_tip();
+    }
+    assert!(ctx.consensus.is_nearly_synced());
+
+    // A block stamped a day ahead of the clock is too far into the future
+    let day = Duration::from_secs(86_400);
+    let future_block = ctx.build_block_template(0, ctx.clock.unix_now() + day.as_millis() as u64).block;
+    let max_block_time = ctx.clock.unix_now() + config.timestamp_deviation_tolerance(0) * 1000;
+    assert_match!(
+        ctx.consensus.validate_and_insert_block(future_block.clone().to_immutable()).virtual_state_task.await,
+        Err(RuleError::TimeTooFarIntoTheFuture(timestamp, max)) if timestamp == future_block.header.timestamp && max == max_block_time
+    );
+
+    // Once a day passed without blocks, the node is no longer synced and the templates are stamped with the new time
+    let now = ctx.clock.advance(day);
+    assert!(!ctx.consensus.is_nearly_synced());
+    let template = ctx
+        .consensus
+        .build_block_template(
+            ctx.miner_data.clone(),
+            Box::new(OnetimeTxSelector::new(Default::default())),
+            TemplateBuildMode::Standard,
+        )
+        .unwrap();
+    assert_eq!(template.block.header.timestamp, now);
+
+    // The block stamped a day ahead is now valid and syncs the node again
+    ctx.validate_and_insert_block(future_block.clone().to_immutable()).await.assert_valid_utxo_tip();
+    assert_eq!(ctx.consensus.get_sink(), future_block.header.hash);
+    assert!(ctx.consensus.is_nearly_synced());
+}
+
 fn new_miner_data() -> MinerData {
     let secp = secp256k1::Secp256k1::new();
     let mut rng = rand::thread_rng();
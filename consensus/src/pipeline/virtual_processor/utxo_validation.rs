@@ -2,7 +2,9 @@ use super::VirtualStateProcessor;
 use crate::{
     errors::{
         BlockProcessResult,
-        RuleError::{BadAcceptedIDMerkleRoot, BadCoinbaseTransaction, BadUTXOCommitment, InvalidTransactionsInUtxoContext},
+        RuleError::{
+            BadAcceptedIDMerkleRoot, BadCoinbasePayment, BadCoinbaseTransaction, BadUTXOCommitment, InvalidTransactionsInUtxoContext,
+        },
     },
     model::stores::{block_transactions::BlockTransactionsStoreReader, daa::DaaStoreReader, ghostdag::GhostdagData},
     processes::{
@@ -14,7 +16,7 @@ use crate::{
     },
 };
 use kaspa_consensus_core::{
-    acceptance_data::{AcceptedTxEntry, MergesetBlockAcceptanceData},
+    acceptance_data::{AcceptedTxEntry, AcceptedTxSpentEntries, MergesetBlockAcceptanceData, SpentUtxoEntries},
     coinbase::*,
     hashing,
     header::Header,
@@ -43,6 +45,8 @@ pub(super) struct UtxoProcessingContext<'a> {
     pub accepted_tx_ids: Vec<TransactionId>,
     pub mergeset_acceptance_data: Vec<MergesetBlockAcceptanceData>,
     pub mergeset_rewards: BlockHashMap<BlockRewardData>,
+    /// The UTXO entries spent by the accepted transactions, only collected if requested
+    pub spent_utxo_entries: Option<SpentUtxoEntries>,
 }
 
 impl<'a> UtxoProcessingContext<'a> {
@@ -55,9 +59,16 @@ impl<'a> UtxoProcessingContext<'a> {
             accepted_tx_ids: Vec::with_capacity(1), // We expect at least the selected parent coinbase tx
             mergeset_rewards: BlockHashMap::with_capacity(mergeset_size),
             mergeset_acceptance_data: Vec::with_capacity(mergeset_size),
+            spent_utxo_entries: None,
         }
     }
 
+    /// Requests the UTXO entries spent by the accepted transactions to be collected along the UTXO state
+    pub fn with_spent_utxo_entries(mut self) -> Self {
+        self.spent_utxo_entries = Some(SpentUtxoEntries::new());
+        self
+    }
+
     pub fn selected_parent(&self) -> Hash {
         self.ghostdag_data.selected_parent
     }
@@ -103,6 +114,10 @@ impl VirtualStateProcessor {
                 ctx.mergeset_diff.add_transaction(validated_tx, pov_daa_score).unwrap();
                 ctx.multiset_hash.add_transaction(validated_tx, pov_daa_score);
                 ctx.accepted_tx_ids.push(validated_tx.id());
+                if let Some(spent_utxo_entries) = ctx.spent_utxo_entries.as_mut() {
+                    spent_utxo_entries
+                        .push(AcceptedTxSpentEntries { transaction_id: validated_tx.id(), entries: validated_tx.entries.clone() });
+                }
                 block_fee += validated_tx.calculated_fee;
             }
 
@@ -188,7 +203,7 @@ impl VirtualStateProcessor {
         Ok(())
     }
 
-    fn verify_coinbase_transaction(
+    pub(super) fn verify_coinbase_transaction(
         &self,
         coinbase: &Transaction,
         daa_score: u64,
@@ -196,6 +211,13 @@ impl VirtualStateProcessor {
         mergeset_rewards: &BlockHashMap<BlockRewardData>,
         mergeset_non_daa: &BlockHashSet,
     ) -> BlockProcessResult<()> {
+        // Report a wrong total payment along with the expected amounts, so that miners can tell what they missed
+        let expected_amounts = self.coinbase_manager.expected_coinbase_amounts(ghostdag_data, mergeset_rewards, mergeset_non_daa);
+        let actual_total = coinbase.outputs.iter().fold(0u64, |total, output| total.saturating_add(output.value));
+        if actual_total != expected_amounts.total() {
+            return Err(BadCoinbasePayment(expected_amounts, actual_total));
+        }
+
         // Extract only miner data from the provided coinbase
         let miner_data = self.coinbase_manager.deserialize_coinbase_payload(&coinbase.payload).unwrap().miner_data;
         let expected_coinbase = self
@@ -252,7 +274,10 @@ impl VirtualStateProcessor {
         match res {
             Ok(calculated_fee) => Ok(ValidatedTransaction::new(populated_tx, calculated_fee)),
             Err(tx_rule_error) => {
-                info!("Rejecting transaction {} due to transaction rule error: {}", transaction.id(), tx_rule_error);
+                info!(
+                    tx_id:% = transaction.id(), error:% = tx_rule_error;
+                    "Rejecting transaction {} due to transaction rule error: {}", transaction.id(), tx_rule_error
+                );
                 Err(tx_rule_error)
             }
         }
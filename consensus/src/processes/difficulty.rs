@@ -46,6 +46,11 @@ trait DifficultyManagerExtension {
         if window_size < MIN_WINDOW_SIZE {
             return Err(DifficultyError::UnderMinWindowSizeAllowed(window_size, MIN_WINDOW_SIZE));
         }
+        self.internal_window_hashes_per_second(window)
+    }
+
+    /// Estimates the hashrate from the blue work accumulated over the time span of the window
+    fn internal_window_hashes_per_second(&self, window: &BlockWindowHeap) -> DifficultyResult<u64> {
         let difficulty_blocks = self.get_difficulty_blocks(window);
         let (min_ts, max_ts) = difficulty_blocks.iter().map(|x| x.timestamp).minmax().into_option().unwrap();
         if min_ts == max_ts {
@@ -129,33 +134,26 @@ impl<T: HeaderStoreReader> FullDifficultyManager<T> {
     }
 
     pub fn calculate_difficulty_bits(&self, window: &BlockWindowHeap) -> u32 {
-        let mut difficulty_blocks = self.get_difficulty_blocks(window);
-
-        // Until there are enough blocks for a valid calculation the difficulty should remain constant.
-        if difficulty_blocks.len() < self.min_difficulty_window_len {
-            return self.genesis_bits;
-        }
-
-        let (min_ts_index, max_ts_index) = difficulty_blocks.iter().position_minmax().into_option().unwrap();
-
-        let min_ts = difficulty_blocks[min_ts_index].timestamp;
-        let max_ts = difficulty_blocks[max_ts_index].timestamp;
-
-        // We remove the minimal block because we want the average target for the internal window.
-        difficulty_blocks.swap_remove(min_ts_index);
-
-        // We need Uint320 to avoid overflow when summing and multiplying by the window size.
-        let difficulty_blocks_len = difficulty_blocks.len() as u64;
-        let targets_sum: Uint320 =
-            difficulty_blocks.into_iter().map(|diff_block| Uint320::from(Uint256::from_compact_target_bits(diff_block.bits))).sum();
-        let average_target = targets_sum / (difficulty_blocks_len);
-        let new_target = average_target * max(max_ts - min_ts, 1) / (self.target_time_per_block * difficulty_blocks_len);
-        Uint256::try_from(new_target.min(self.max_difficulty_target)).expect("max target < Uint256::MAX").compact_target_bits()
+        calc_difficulty_bits(
+            self.get_difficulty_blocks(window),
+            self.genesis_bits,
+            self.min_difficulty_window_len,
+            self.max_difficulty_target,
+            self.target_time_per_block,
+        )
     }
 
     pub fn estimate_network_hashes_per_second(&self, window: &BlockWindowHeap) -> DifficultyResult<u64> {
         self.internal_estimate_network_hashes_per_second(window)
     }
+
+    /// Estimates the hashrate over a DAA window, which only has to be long enough for a valid difficulty calculation
+    pub fn estimate_daa_window_hashes_per_second(&self, window: &BlockWindowHeap) -> DifficultyResult<u64> {
+        if window.len() < self.min_difficulty_window_len {
+            return Err(DifficultyError::UnderMinWindowSizeAllowed(window.len(), self.min_difficulty_window_len));
+        }
+        self.internal_window_hashes_per_second(window)
+    }
 }
 
 impl<T: HeaderStoreReader> DifficultyManagerExtension for FullDifficultyManager<T> {
@@ -231,37 +229,26 @@ impl<T: HeaderStoreReader> SampledDifficultyManager<T> {
     }
 
     pub fn calculate_difficulty_bits(&self, window: &BlockWindowHeap) -> u32 {
-        // Note: this fn is duplicated (almost, see `* self.difficulty_sample_rate`) in Full and Sampled structs
-        // so some alternate calculation can be investigated here.
-        let mut difficulty_blocks = self.get_difficulty_blocks(window);
-
-        // Until there are enough blocks for a valid calculation the difficulty should remain constant.
-        if difficulty_blocks.len() < self.min_difficulty_window_len {
-            return self.genesis_bits;
-        }
-
-        let (min_ts_index, max_ts_index) = difficulty_blocks.iter().position_minmax().into_option().unwrap();
-
-        let min_ts = difficulty_blocks[min_ts_index].timestamp;
-        let max_ts = difficulty_blocks[max_ts_index].timestamp;
-
-        // We remove the minimal block because we want the average target for the internal window.
-        difficulty_blocks.swap_remove(min_ts_index);
-
-        // We need Uint320 to avoid overflow when summing and multiplying by the window size.
-        let difficulty_blocks_len = difficulty_blocks.len() as u64;
-        let targets_sum: Uint320 =
-            difficulty_blocks.into_iter().map(|diff_block| Uint320::from(Uint256::from_compact_target_bits(diff_block.bits))).sum();
-        let average_target = targets_sum / difficulty_blocks_len;
-        let measured_duration = max(max_ts - min_ts, 1);
-        let expected_duration = self.target_time_per_block * self.difficulty_sample_rate * difficulty_blocks_len; // This does differ from FullDifficultyManager version
-        let new_target = average_target * measured_duration / expected_duration;
-        Uint256::try_from(new_target.min(self.max_difficulty_target)).expect("max target < Uint256::MAX").compact_target_bits()
+        calc_difficulty_bits(
+            self.get_difficulty_blocks(window),
+            self.genesis_bits,
+            self.min_difficulty_window_len,
+            self.max_difficulty_target,
+            self.target_time_per_block * self.difficulty_sample_rate,
+        )
     }
 
     pub fn estimate_network_hashes_per_second(&self, window: &BlockWindowHeap) -> DifficultyResult<u64> {
         self.internal_estimate_network_hashes_per_second(window)
     }
+
+    /// Estimates the hashrate over a DAA window, which only has to be long enough for a valid difficulty calculation
+    pub fn estimate_daa_window_hashes_per_second(&self, window: &BlockWindowHeap) -> DifficultyResult<u64> {
+        if window.len() < self.min_difficulty_window_len {
+            return Err(DifficultyError::UnderMinWindowSizeAllowed(window.len(), self.min_difficulty_window_len));
+        }
+        self.internal_window_hashes_per_second(window)
+    }
 }
 
 impl<T: HeaderStoreReader> DifficultyManagerExtension for SampledDifficultyManager<T> {
@@ -282,13 +269,55 @@ pub fn calc_work(bits: u32) -> BlueWorkType {
     res.try_into().expect("Work should not exceed 2**192")
 }
 
+/// Calculates the difficulty bits expected after a window of difficulty blocks, each block of the window
+/// being expected to stand for `target_time_per_sample` milliseconds (the target time per block, multiplied
+/// by the sample rate of sampled windows).
+///
+/// Shared by the difficulty managers and the header chain verifier, so both follow the exact same rules.
+pub(crate) fn calc_difficulty_bits(
+    mut difficulty_blocks: Vec<DifficultyBlock>,
+    genesis_bits: u32,
+    min_difficulty_window_len: usize,
+    max_difficulty_target: Uint320,
+    target_time_per_sample: u64,
+) -> u32 {
+    // Until there are enough blocks for a valid calculation the difficulty should remain constant.
+    if difficulty_blocks.len() < min_difficulty_window_len {
+        return genesis_bits;
+    }
+
+    let (min_ts_index, max_ts_index) = difficulty_blocks.iter().position_minmax().into_option().unwrap();
+
+    let min_ts = difficulty_blocks[min_ts_index].timestamp;
+    let max_ts = difficulty_blocks[max_ts_index].timestamp;
+
+    // We remove the minimal block because we want the average target for the internal window.
+    difficulty_blocks.swap_remove(min_ts_index);
+
+    // We need Uint320 to avoid overflow when summing and multiplying by the window size.
+    let difficulty_blocks_len = difficulty_blocks.len() as u64;
+    let targets_sum: Uint320 =
+        difficulty_blocks.into_iter().map(|diff_block| Uint320::from(Uint256::from_compact_target_bits(diff_block.bits))).sum();
+    let average_target = targets_sum / difficulty_blocks_len;
+    let measured_duration = max(max_ts - min_ts, 1);
+    let expected_duration = target_time_per_sample * difficulty_blocks_len;
+    let new_target = average_target * measured_duration / expected_duration;
+    Uint256::try_from(new_target.min(max_difficulty_target)).expect("max target < Uint256::MAX").compact_target_bits()
+}
+
 #[derive(Eq)]
-struct DifficultyBlock {
+pub(crate) struct DifficultyBlock {
     timestamp: u64,
     bits: u32,
     sortable_block: SortableBlock,
 }
 
+impl DifficultyBlock {
+    pub(crate) fn new(timestamp: u64, bits: u32, sortable_block: SortableBlock) -> Self {
+        Self { timestamp, bits, sortable_block }
+    }
+}
+
 impl PartialEq for DifficultyBlock {
     fn eq(&self, other: &Self) -> bool {
         // If the sortable blocks are equal the timestamps and bits that are associated with the block are equal for sure.
@@ -16,14 +16,8 @@ impl<T: HeaderStoreReader> FullPastMedianTimeManager<T> {
     }
 
     pub fn calc_past_median_time(&self, window: &BlockWindowHeap) -> Result<u64, RuleError> {
-        if window.is_empty() {
-            return Ok(self.genesis_timestamp);
-        }
-
-        let mut window_timestamps: Vec<u64> =
-            window.iter().map(|item| self.headers_store.get_timestamp(item.0.hash).unwrap()).collect();
-        window_timestamps.sort_unstable(); // This is deterministic because we sort u64
-        Ok(window_timestamps[window_timestamps.len() / 2])
+        let window_timestamps = window.iter().map(|item| self.headers_store.get_timestamp(item.0.hash).unwrap()).collect();
+        Ok(calc_full_past_median_time(window_timestamps, self.genesis_timestamp))
     }
 }
 
@@ -41,23 +35,35 @@ impl<T: HeaderStoreReader> SampledPastMedianTimeManager<T> {
     }
 
     pub fn calc_past_median_time(&self, window: &BlockWindowHeap) -> Result<u64, RuleError> {
-        // The past median time is actually calculated taking the average of the 11 values closest to the center
-        // of the sorted timestamps
-        const AVERAGE_FRAME_SIZE: usize = 11;
-
-        if window.is_empty() {
-            return Ok(self.genesis_timestamp);
-        }
-
-        let mut window_timestamps: Vec<u64> =
-            window.iter().map(|item| self.headers_store.get_timestamp(item.0.hash).unwrap()).collect();
-        window_timestamps.sort_unstable(); // This is deterministic because we sort u64
-        let avg_frame_size = window_timestamps.len().min(AVERAGE_FRAME_SIZE);
-        // Define the slice so that the average is the highest among the 2 possible solutions in case of an even frame size
-        let ending_index = (window_timestamps.len() + avg_frame_size + 1) / 2;
-        let timestamp = (window_timestamps[ending_index - avg_frame_size..ending_index].iter().sum::<u64>()
-            + avg_frame_size as u64 / 2)
-            / avg_frame_size as u64;
-        Ok(timestamp)
+        let window_timestamps = window.iter().map(|item| self.headers_store.get_timestamp(item.0.hash).unwrap()).collect();
+        Ok(calc_sampled_past_median_time(window_timestamps, self.genesis_timestamp))
     }
 }
+
+/// Calculates the past median time of a full window given the timestamps of its blocks
+pub(crate) fn calc_full_past_median_time(mut window_timestamps: Vec<u64>, genesis_timestamp: u64) -> u64 {
+    if window_timestamps.is_empty() {
+        return genesis_timestamp;
+    }
+
+    window_timestamps.sort_unstable(); // This is deterministic because we sort u64
+    window_timestamps[window_timestamps.len() / 2]
+}
+
+/// Calculates the past median time of a sampled window given the timestamps of its blocks
+pub(crate) fn calc_sampled_past_median_time(mut window_timestamps: Vec<u64>, genesis_timestamp: u64) -> u64 {
+    // The past median time is actually calculated taking the average of the 11 values closest to the center
+    // of the sorted timestamps
+    const AVERAGE_FRAME_SIZE: usize = 11;
+
+    if window_timestamps.is_empty() {
+        return genesis_timestamp;
+    }
+
+    window_timestamps.sort_unstable(); // This is deterministic because we sort u64
+    let avg_frame_size = window_timestamps.len().min(AVERAGE_FRAME_SIZE);
+    // Define the slice so that the average is the highest among the 2 possible solutions in case of an even frame size
+    let ending_index = (window_timestamps.len() + avg_frame_size + 1) / 2;
+    (window_timestamps[ending_index - avg_frame_size..ending_index].iter().sum::<u64>() + avg_frame_size as u64 / 2)
+        / avg_frame_size as u64
+}
@@ -4,7 +4,7 @@ use std::{
     collections::{hash_map::Entry::Vacant, VecDeque},
     ops::{Deref, DerefMut},
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
         Arc,
     },
 };
@@ -12,6 +12,7 @@ use std::{
 use itertools::Itertools;
 use kaspa_math::int::SignedInteger;
 use parking_lot::{Mutex, RwLock};
+use rayon::prelude::*;
 use rocksdb::WriteBatch;
 
 use kaspa_consensus_core::{
@@ -21,7 +22,7 @@ use kaspa_consensus_core::{
         pruning::{PruningImportError, PruningImportResult},
     },
     header::Header,
-    pruning::{PruningPointProof, PruningPointTrustedData},
+    pruning::{PruningPointProof, PruningPointTrustedData, PruningProofValidationProgress},
     trusted::{TrustedBlock, TrustedGhostdagData, TrustedHeader},
     BlockHashMap, BlockHashSet, BlockLevel, HashMapCustomHasher, KType,
 };
@@ -88,6 +89,55 @@ impl<T> Clone for CachedPruningPointData<T> {
     }
 }
 
+/// GHOSTDAG manager operating over the temporary stores used for validating a pruning point proof
+type TempGhostdagManager =
+    GhostdagManager<DbGhostdagStore, DbRelationsStore, MTReachabilityService<DbReachabilityStore>, DbHeadersStore>;
+
+/// The number of processed proof headers between consecutive progress reports
+const PROOF_VALIDATION_PROGRESS_INTERVAL: u64 = 1000;
+
+/// Tracks the progress of a pruning point proof validation over all levels and reports it to the validation caller
+struct ProofValidationProgressTracker<'a> {
+    callback: &'a (dyn Fn(PruningProofValidationProgress) + Sync),
+    processed_headers: AtomicU64,
+    processed_levels: AtomicUsize,
+    total_headers: u64,
+    total_levels: usize,
+}
+
+impl<'a> ProofValidationProgressTracker<'a> {
+    fn new(proof: &PruningPointProof, callback: &'a (dyn Fn(PruningProofValidationProgress) + Sync)) -> Self {
+        Self {
+            callback,
+            processed_headers: AtomicU64::new(0),
+            processed_levels: AtomicUsize::new(0),
+            total_headers: proof.iter().map(|level| level.len() as u64).sum(),
+            total_levels: proof.len(),
+        }
+    }
+
+    fn header_processed(&self) {
+        let processed_headers = self.processed_headers.fetch_add(1, Ordering::Relaxed) + 1;
+        if processed_headers % PROOF_VALIDATION_PROGRESS_INTERVAL == 0 {
+            self.report(processed_headers, self.processed_levels.load(Ordering::Relaxed));
+        }
+    }
+
+    fn level_processed(&self) {
+        let processed_levels = self.processed_levels.fetch_add(1, Ordering::Relaxed) + 1;
+        self.report(self.processed_headers.load(Ordering::Relaxed), processed_levels);
+    }
+
+    fn report(&self, processed_headers: u64, processed_levels: usize) {
+        (self.callback)(PruningProofValidationProgress {
+            processed_headers,
+            total_headers: self.total_headers,
+            processed_levels,
+            total_levels: self.total_levels,
+        });
+    }
+}
+
 pub struct PruningProofManager {
     db: Arc<DB>,
 
@@ -388,6 +438,21 @@ impl PruningProofManager {
     }
 
     pub fn validate_pruning_point_proof(&self, proof: &PruningPointProof) -> PruningImportResult<()> {
+        self.validate_pruning_point_proof_with_progress(proof, &|_| {}, &AtomicBool::new(false))
+    }
+
+    /// Validates the pruning point proof while reporting the validation progress to `progress`. Setting `cancel`
+    /// aborts the validation with [`PruningImportError::PruningValidationCancelled`].
+    ///
+    /// Proof levels are independent of each other and are hence processed concurrently on the rayon pool. The checks
+    /// relating consecutive levels are only applied once all levels were processed, going from the top level down,
+    /// so the outcome is identical to the one of processing the levels one after the other.
+    pub fn validate_pruning_point_proof_with_progress(
+        &self,
+        proof: &PruningPointProof,
+        progress: &(dyn Fn(PruningProofValidationProgress) + Sync),
+        cancel: &AtomicBool,
+    ) -> PruningImportResult<()> {
         if proof.len() != self.max_block_level as usize + 1 {
             return Err(PruningImportError::ProofNotEnoughLevels(self.max_block_level as usize + 1));
         }
@@ -444,72 +509,48 @@ impl PruningProofManager {
             db.write(batch).unwrap();
         }
 
-        let mut selected_tip_by_level = vec![None; self.max_block_level as usize + 1];
-        for level in (0..=self.max_block_level).rev() {
-            // Before processing this level, check if the process is exiting so we can end early
-            if self.is_consensus_exiting.load(Ordering::Relaxed) {
-                return Err(PruningImportError::PruningValidationInterrupted);
-            }
-
-            info!("Validating level {level} from the pruning point proof ({} headers)", proof[level as usize].len());
-            let level_idx = level as usize;
-            let mut selected_tip = None;
-            for (i, header) in proof[level as usize].iter().enumerate() {
-                let header_level = calc_block_level(header, self.max_block_level);
-                if header_level < level {
-                    return Err(PruningImportError::PruningProofWrongBlockLevel(header.hash, header_level, level));
-                }
-
-                headers_store.insert(header.hash, header.clone(), header_level).unwrap_or_exists();
-
-                let parents = self
-                    .parents_manager
-                    .parents_at_level(header, level)
-                    .iter()
-                    .copied()
-                    .filter(|parent| ghostdag_stores[level_idx].has(*parent).unwrap())
-                    .collect_vec();
-
-                // Only the first block at each level is allowed to have no known parents
-                if parents.is_empty() && i != 0 {
-                    return Err(PruningImportError::PruningProofHeaderWithNoKnownParents(header.hash, level));
-                }
-
-                let parents: BlockHashes = parents.push_if_empty(ORIGIN).into();
-
-                if relations_stores[level_idx].has(header.hash).unwrap() {
-                    return Err(PruningImportError::PruningProofDuplicateHeaderAtLevel(header.hash, level));
+        let progress = ProofValidationProgressTracker::new(proof, progress);
+        // The highest level which failed validation plus one, or zero if no level failed so far. Validating the levels
+        // one by one from the top down would have stopped at this level, so there is no point in processing lower ones
+        let failed_above = AtomicUsize::new(0);
+        // The headers store is shared by all levels, so inserts are serialized in order to avoid racing over headers
+        // appearing in several levels
+        let headers_insert_lock = Mutex::new(());
+        let level_results = relations_stores
+            .par_iter_mut()
+            .enumerate()
+            .map(|(level_idx, relations_store)| {
+                let result = self.validate_proof_level(
+                    &proof[level_idx],
+                    level_idx as BlockLevel,
+                    &headers_store,
+                    &headers_insert_lock,
+                    &ghostdag_stores[level_idx],
+                    relations_store,
+                    &reachability_stores[level_idx],
+                    &ghostdag_managers[level_idx],
+                    || cancel.load(Ordering::Relaxed) || failed_above.load(Ordering::Relaxed) > level_idx + 1,
+                    &progress,
+                );
+                if result.is_err() {
+                    failed_above.fetch_max(level_idx + 1, Ordering::Relaxed);
                 }
+                result
+            })
+            .collect::<Vec<_>>();
 
-                relations_stores[level_idx].insert(header.hash, parents.clone()).unwrap();
-                let ghostdag_data = Arc::new(ghostdag_managers[level_idx].ghostdag(&parents));
-                ghostdag_stores[level_idx].insert(header.hash, ghostdag_data.clone()).unwrap();
-                selected_tip = Some(match selected_tip {
-                    Some(tip) => ghostdag_managers[level_idx].find_selected_parent([tip, header.hash]),
-                    None => header.hash,
-                });
-
-                let mut reachability_mergeset = {
-                    let reachability_read = reachability_stores[level_idx].read();
-                    ghostdag_data
-                        .unordered_mergeset_without_selected_parent()
-                        .filter(|hash| reachability_read.has(*hash).unwrap())
-                        .collect_vec() // We collect to vector so reachability_read can be released and let `reachability::add_block` use a write lock.
-                        .into_iter()
-                };
-                reachability::add_block(
-                    reachability_stores[level_idx].write().deref_mut(),
-                    header.hash,
-                    ghostdag_data.selected_parent,
-                    &mut reachability_mergeset,
-                )
-                .unwrap();
+        // Before applying the remaining checks, check if the process is exiting or the validation was cancelled so we can end early
+        if self.is_consensus_exiting.load(Ordering::Relaxed) {
+            return Err(PruningImportError::PruningValidationInterrupted);
+        }
+        if cancel.load(Ordering::Relaxed) {
+            return Err(PruningImportError::PruningValidationCancelled);
+        }
 
-                if selected_tip.unwrap() == header.hash {
-                    reachability::hint_virtual_selected_parent(reachability_stores[level_idx].write().deref_mut(), header.hash)
-                        .unwrap();
-                }
-            }
+        let mut selected_tip_by_level = vec![None; self.max_block_level as usize + 1];
+        for (level_idx, level_result) in level_results.into_iter().enumerate().rev() {
+            let level = level_idx as BlockLevel;
+            let selected_tip = level_result?;
 
             if level < self.max_block_level {
                 let block_at_depth_m_at_next_level = self
@@ -619,6 +660,97 @@ impl PruningProofManager {
         Err(PruningImportError::PruningProofNotEnoughHeaders)
     }
 
+    /// Populates the temporary stores of `level` with the headers of the corresponding proof level, in order, and
+    /// returns the selected tip of the level (or `None` if the level is empty).
+    ///
+    /// Returns early with an interruption error once `should_stop` returns true. Such a result is never surfaced
+    /// if the stop was caused by a failure at a higher level, since level results are inspected from the top down.
+    #[allow(clippy::too_many_arguments)]
+    fn validate_proof_level(
+        &self,
+        level_headers: &[Arc<Header>],
+        level: BlockLevel,
+        headers_store: &DbHeadersStore,
+        headers_insert_lock: &Mutex<()>,
+        ghostdag_store: &DbGhostdagStore,
+        relations_store: &mut DbRelationsStore,
+        reachability_store: &RwLock<DbReachabilityStore>,
+        ghostdag_manager: &TempGhostdagManager,
+        should_stop: impl Fn() -> bool,
+        progress: &ProofValidationProgressTracker,
+    ) -> PruningImportResult<Option<Hash>> {
+        debug!("Validating level {level} from the pruning point proof ({} headers)", level_headers.len());
+        let mut selected_tip = None;
+        for (i, header) in level_headers.iter().enumerate() {
+            // Check if the process is exiting or if the validation should stop so we can end early
+            if self.is_consensus_exiting.load(Ordering::Relaxed) || should_stop() {
+                return Err(PruningImportError::PruningValidationInterrupted);
+            }
+
+            let header_level = calc_block_level(header, self.max_block_level);
+            if header_level < level {
+                return Err(PruningImportError::PruningProofWrongBlockLevel(header.hash, header_level, level));
+            }
+
+            {
+                let _guard = headers_insert_lock.lock();
+                headers_store.insert(header.hash, header.clone(), header_level).unwrap_or_exists();
+            }
+
+            let parents = self
+                .parents_manager
+                .parents_at_level(header, level)
+                .iter()
+                .copied()
+                .filter(|parent| ghostdag_store.has(*parent).unwrap())
+                .collect_vec();
+
+            // Only the first block at each level is allowed to have no known parents
+            if parents.is_empty() && i != 0 {
+                return Err(PruningImportError::PruningProofHeaderWithNoKnownParents(header.hash, level));
+            }
+
+            let parents: BlockHashes = parents.push_if_empty(ORIGIN).into();
+
+            if relations_store.has(header.hash).unwrap() {
+                return Err(PruningImportError::PruningProofDuplicateHeaderAtLevel(header.hash, level));
+            }
+
+            relations_store.insert(header.hash, parents.clone()).unwrap();
+            let ghostdag_data = Arc::new(ghostdag_manager.ghostdag(&parents));
+            ghostdag_store.insert(header.hash, ghostdag_data.clone()).unwrap();
+            selected_tip = Some(match selected_tip {
+                Some(tip) => ghostdag_manager.find_selected_parent([tip, header.hash]),
+                None => header.hash,
+            });
+
+            let mut reachability_mergeset = {
+                let reachability_read = reachability_store.read();
+                ghostdag_data
+                    .unordered_mergeset_without_selected_parent()
+                    .filter(|hash| reachability_read.has(*hash).unwrap())
+                    .collect_vec() // We collect to vector so reachability_read can be released and let `reachability::add_block` use a write lock.
+                    .into_iter()
+            };
+            reachability::add_block(
+                reachability_store.write().deref_mut(),
+                header.hash,
+                ghostdag_data.selected_parent,
+                &mut reachability_mergeset,
+            )
+            .unwrap();
+
+            if selected_tip.unwrap() == header.hash {
+                reachability::hint_virtual_selected_parent(reachability_store.write().deref_mut(), header.hash).unwrap();
+            }
+
+            progress.header_processed();
+        }
+
+        progress.level_processed();
+        Ok(selected_tip)
+    }
+
     pub(crate) fn build_pruning_point_proof(&self, pp: Hash) -> PruningPointProof {
         if pp == self.genesis_hash {
             return vec![];
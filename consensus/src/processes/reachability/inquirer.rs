@@ -21,12 +21,13 @@ pub(super) fn init_with_params(store: &mut (impl ReachabilityStore + ?Sized), or
 type HashIterator<'a> = &'a mut dyn Iterator<Item = Hash>;
 
 /// Add a block to the DAG reachability data structures and persist using the provided `store`.
+/// Returns statistics of the interval reindexing triggered by the addition, if any.
 pub fn add_block(
     store: &mut (impl ReachabilityStore + ?Sized),
     new_block: Hash,
     selected_parent: Hash,
     mergeset_iterator: HashIterator,
-) -> Result<()> {
+) -> Result<ReindexStats> {
     add_block_with_params(store, new_block, selected_parent, mergeset_iterator, None, None)
 }
 
@@ -37,8 +38,8 @@ fn add_block_with_params(
     mergeset_iterator: HashIterator,
     reindex_depth: Option<u64>,
     reindex_slack: Option<u64>,
-) -> Result<()> {
-    add_tree_block(
+) -> Result<ReindexStats> {
+    let stats = add_tree_block(
         store,
         new_block,
         selected_parent,
@@ -46,7 +47,7 @@ fn add_block_with_params(
         reindex_slack.unwrap_or(crate::constants::perf::DEFAULT_REINDEX_SLACK),
     )?;
     add_dag_block(store, new_block, mergeset_iterator)?;
-    Ok(())
+    Ok(stats)
 }
 
 fn add_dag_block(store: &mut (impl ReachabilityStore + ?Sized), new_block: Hash, mergeset_iterator: HashIterator) -> Result<()> {
@@ -147,7 +148,8 @@ fn insert_to_future_covering_set(store: &mut (impl ReachabilityStore + ?Sized),
 /// the `virtual selected parent` (`sink`). This might affect internal reachability heuristics such
 /// as moving the reindex point. The consensus runtime is expected to call this function
 /// for a new header selected tip which is `header only` / `pending UTXO verification`, or for a completely resolved `sink`.
-pub fn hint_virtual_selected_parent(store: &mut (impl ReachabilityStore + ?Sized), hint: Hash) -> Result<()> {
+/// Returns statistics of the interval reindexing triggered by moving the reindex root, if any.
+pub fn hint_virtual_selected_parent(store: &mut (impl ReachabilityStore + ?Sized), hint: Hash) -> Result<ReindexStats> {
     try_advancing_reindex_root(
         store,
         hint,
@@ -31,6 +31,38 @@ impl ReachabilityError {
 
 pub type Result<T> = std::result::Result<T, ReachabilityError>;
 
+/// Summary of the interval reindexing work performed by a reachability operation
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReindexStats {
+    /// The number of reindex operations performed
+    pub operations: u64,
+    /// The number of blocks whose intervals were reallocated
+    pub reindexed_blocks: u64,
+    /// The interval capacity which was left unallocated at the block the last reindex operation
+    /// allocated from. A value approaching zero indicates that interval space is degrading.
+    /// Not measured by operations concentrating intervals towards a new reindex root
+    pub slack_remaining: Option<u64>,
+    /// The number of operations which exhausted the capacity of the reindex root and fell back
+    /// to reindexing the whole tree
+    pub global_reindexes: u64,
+}
+
+impl ReindexStats {
+    pub fn is_empty(&self) -> bool {
+        self.operations == 0
+    }
+
+    pub fn merge(&mut self, other: &ReindexStats) {
+        if other.is_empty() {
+            return;
+        }
+        self.operations += other.operations;
+        self.reindexed_blocks += other.reindexed_blocks;
+        self.slack_remaining = other.slack_remaining.or(self.slack_remaining);
+        self.global_reindexes += other.global_reindexes;
+    }
+}
+
 pub trait ReachabilityResultExtensions<T> {
     /// Unwraps the error into `None` if the internal error is `StoreError::KeyNotFound` or panics otherwise
     fn unwrap_option(self) -> Option<T>;
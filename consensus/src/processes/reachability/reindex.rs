@@ -1,8 +1,28 @@
 use super::{extensions::ReachabilityStoreIntervalExtensions, inquirer::get_next_chain_ancestor_unchecked, interval::Interval, *};
 use crate::model::stores::reachability::ReachabilityStore;
 use kaspa_consensus_core::{blockhash::BlockHashExtensions, BlockHashMap, HashMapCustomHasher};
+use kaspa_core::{info, time::hybrid_unix_now, warn};
 use kaspa_hashes::Hash;
-use std::collections::VecDeque;
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+/// The number of reallocated blocks between two consecutive checks for reporting the progress of a long reindex operation
+const PROGRESS_CHECK_BLOCKS: u64 = 4096;
+
+/// The minimal time between two consecutive progress reports of a single long reindex operation
+const PROGRESS_REPORT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Reindex operations reallocating at least this many blocks are summarized in the log
+const SUMMARY_LOG_MIN_BLOCKS: u64 = 100_000;
+
+/// The minimal time (in milliseconds) between two consecutive reindex summary logs
+const SUMMARY_LOG_INTERVAL_MILLIS: u64 = 10_000;
+
+/// Unix time (in milliseconds) of the last reindex summary log
+static LAST_SUMMARY_LOG: AtomicU64 = AtomicU64::new(0);
 
 /// A struct used during reindex operations. It represents a temporary context
 /// for caching subtree information during the *current* reindex operation only
@@ -11,11 +31,27 @@ pub(super) struct ReindexOperationContext<'a, T: ReachabilityStore + ?Sized> {
     subtree_sizes: BlockHashMap<u64>, // Cache for subtree sizes computed during this operation
     depth: u64,
     slack: u64,
+    stats: ReindexStats,
+    last_report: Instant,
+    next_progress_check: u64,
 }
 
 impl<'a, T: ReachabilityStore + ?Sized> ReindexOperationContext<'a, T> {
     pub(super) fn new(store: &'a mut T, depth: u64, slack: u64) -> Self {
-        Self { store, subtree_sizes: BlockHashMap::new(), depth, slack }
+        Self {
+            store,
+            subtree_sizes: BlockHashMap::new(),
+            depth,
+            slack,
+            stats: ReindexStats { operations: 1, ..Default::default() },
+            last_report: Instant::now(),
+            next_progress_check: PROGRESS_CHECK_BLOCKS,
+        }
+    }
+
+    /// Returns the statistics of the reindex work performed by this operation so far
+    pub(super) fn stats(&self) -> ReindexStats {
+        self.stats
     }
 
     /// Traverses the reachability subtree that's defined by the new child
@@ -34,6 +70,7 @@ impl<'a, T: ReachabilityStore + ?Sized> ReindexOperationContext<'a, T> {
 
             // `current` has sufficient space, break and propagate
             if current_interval.size() >= self.subtree_sizes[&current] {
+                self.stats.slack_remaining = Some(current_interval.size() - self.subtree_sizes[&current]);
                 break;
             }
 
@@ -56,10 +93,9 @@ impl<'a, T: ReachabilityStore + ?Sized> ReindexOperationContext<'a, T> {
                 // than ~2^52 blocks in the DAG, which should never happen in our lifetimes
                 // even if block rate per second is above 100. The calculation follows from the allocation of
                 // 2^12 (which equals 2^64/2^52) for slack per chain block below the reindex root.
-                return Err(ReachabilityError::DataOverflow(format!(
-                    "unexpected behavior: reindex root {reindex_root} is out of capacity during reindexing.
-                    Theoretically, this should only ever happen if there are more than ~2^52 blocks in the DAG."
-                )));
+                // However, adversarial DAG shapes can degrade the allocation, so we fall back to
+                // reclaiming all the slack in the tree rather than failing
+                return self.reindex_globally(reindex_root);
             }
 
             if inquirer::is_strict_chain_ancestor_of(self.store, parent, reindex_root)? {
@@ -158,6 +194,8 @@ impl<'a, T: ReachabilityStore + ?Sized> ReindexOperationContext<'a, T> {
         // Make sure subtrees are counted before propagating
         self.count_subtrees(block)?;
 
+        let subtree_size = self.subtree_sizes[&block];
+        let mut propagated = 0u64;
         let mut queue = VecDeque::<Hash>::from([block]);
         while let Some(current) = queue.pop_front() {
             let children = self.store.get_children(current)?;
@@ -169,11 +207,68 @@ impl<'a, T: ReachabilityStore + ?Sized> ReindexOperationContext<'a, T> {
                     self.store.set_interval(c, ci)?;
                 }
                 queue.extend(children.iter());
+                propagated += children.len() as u64;
+                self.stats.reindexed_blocks += children.len() as u64;
+                self.report_progress(block, propagated, subtree_size);
             }
         }
         Ok(())
     }
 
+    /// Reports the progress of a long propagation of the subtree of `block`. Time is only
+    /// sampled once every `PROGRESS_CHECK_BLOCKS` reallocated blocks.
+    fn report_progress(&mut self, block: Hash, propagated: u64, subtree_size: u64) {
+        if self.stats.reindexed_blocks < self.next_progress_check {
+            return;
+        }
+        self.next_progress_check = self.stats.reindexed_blocks + PROGRESS_CHECK_BLOCKS;
+        if self.last_report.elapsed() >= PROGRESS_REPORT_INTERVAL {
+            self.last_report = Instant::now();
+            info!(
+                "Reachability reindex in progress: {}/{} blocks of the {} subtree reallocated ({} blocks during this operation)",
+                propagated, subtree_size, block, self.stats.reindexed_blocks
+            );
+        }
+    }
+
+    /// Fallback for the case where the reindex root is out of capacity. Reallocates the intervals of the
+    /// entire tree, thereby reclaiming all slack reserved along the chain and by early siblings.
+    /// Fails only if the tree root itself does not hold enough capacity for all blocks.
+    fn reindex_globally(&mut self, reindex_root: Hash) -> Result<()> {
+        let mut root = reindex_root;
+        loop {
+            let parent = self.store.get_parent(root)?;
+            if parent.is_none() {
+                break;
+            }
+            root = parent;
+        }
+
+        self.count_subtrees(root)?;
+        let tree_size = self.subtree_sizes[&root];
+        let root_interval = self.store.get_interval(root)?;
+        if root_interval.size() < tree_size {
+            return Err(ReachabilityError::DataOverflow(format!(
+                "tree root {root} with interval {root_interval} cannot hold {tree_size} blocks during a global reindex"
+            )));
+        }
+
+        warn!(
+            "Reachability reindex root {} is out of interval capacity, reindexing the entire tree ({} blocks)",
+            reindex_root, tree_size
+        );
+        self.stats.global_reindexes += 1;
+        self.stats.slack_remaining = Some(root_interval.size() - tree_size);
+        self.propagate_interval(root)
+    }
+
+    /// Returns whether `block` can give away `offset` of its interval while still holding its entire subtree
+    fn can_shrink_interval(&mut self, block: Hash, offset: u64) -> Result<bool> {
+        self.count_subtrees(block)?;
+        let subtree_size = self.subtree_sizes[&block];
+        Ok(self.store.get_interval(block)?.size().checked_sub(offset).is_some_and(|size| size >= subtree_size))
+    }
+
     /// This method implements the reindex algorithm for the case where the
     /// new child node is not in reindex root's subtree. The function is expected to allocate
     /// `required_allocation` to be added to interval of `allocation_block`. `common_ancestor` is
@@ -219,6 +314,11 @@ impl<'a, T: ReachabilityStore + ?Sized> ReindexOperationContext<'a, T> {
                 // Reached reindex root. In this case, since we reached (the unlimited) root,
                 // we also re-allocate new slack for the chain we just traversed
                 let offset = required_allocation + self.slack * path_len - slack_sum;
+                if !self.can_shrink_interval(current, offset)? {
+                    // Nothing was modified yet, so the whole tree can be safely reindexed instead
+                    return self.reindex_globally(reindex_root);
+                }
+                self.stats.slack_remaining = Some(self.store.get_interval(current)?.size() - offset - self.subtree_sizes[&current]);
                 self.apply_interval_op_and_propagate(current, offset, Interval::increase_start)?;
                 self.offset_siblings_before(allocation_block, current, offset)?;
 
@@ -231,6 +331,7 @@ impl<'a, T: ReachabilityStore + ?Sized> ReindexOperationContext<'a, T> {
             slack_sum += slack_before_current;
 
             if slack_sum >= required_allocation {
+                self.stats.slack_remaining = Some(slack_sum - required_allocation);
                 // Set offset to be just enough to satisfy required allocation
                 let offset = slack_before_current - (slack_sum - required_allocation);
                 self.apply_interval_op(current, offset, Interval::increase_start)?;
@@ -281,6 +382,11 @@ impl<'a, T: ReachabilityStore + ?Sized> ReindexOperationContext<'a, T> {
                 // Reached reindex root. In this case, since we reached (the unlimited) root,
                 // we also re-allocate new slack for the chain we just traversed
                 let offset = required_allocation + self.slack * path_len - slack_sum;
+                if !self.can_shrink_interval(current, offset)? {
+                    // Nothing was modified yet, so the whole tree can be safely reindexed instead
+                    return self.reindex_globally(reindex_root);
+                }
+                self.stats.slack_remaining = Some(self.store.get_interval(current)?.size() - offset - self.subtree_sizes[&current]);
                 self.apply_interval_op_and_propagate(current, offset, Interval::decrease_end)?;
                 self.offset_siblings_after(allocation_block, current, offset)?;
 
@@ -293,6 +399,7 @@ impl<'a, T: ReachabilityStore + ?Sized> ReindexOperationContext<'a, T> {
             slack_sum += slack_after_current;
 
             if slack_sum >= required_allocation {
+                self.stats.slack_remaining = Some(slack_sum - required_allocation);
                 // Set offset to be just enough to satisfy required allocation
                 let offset = slack_after_current - (slack_sum - required_allocation);
                 self.apply_interval_op(current, offset, Interval::decrease_end)?;
@@ -408,6 +515,7 @@ impl<'a, T: ReachabilityStore + ?Sized> ReindexOperationContext<'a, T> {
 
         for (c, ci) in children_before.iter().cloned().zip(interval_before.split_exact(sizes.as_slice())) {
             self.store.set_interval(c, ci)?;
+            self.stats.reindexed_blocks += 1;
             self.propagate_interval(c)?;
         }
 
@@ -430,6 +538,7 @@ impl<'a, T: ReachabilityStore + ?Sized> ReindexOperationContext<'a, T> {
 
         for (c, ci) in children_after.iter().cloned().zip(interval_after.split_exact(sizes.as_slice())) {
             self.store.set_interval(c, ci)?;
+            self.stats.reindexed_blocks += 1;
             self.propagate_interval(c)?;
         }
 
@@ -471,6 +580,29 @@ impl<'a, T: ReachabilityStore + ?Sized> ReindexOperationContext<'a, T> {
     }
 }
 
+/// Logs a summary of a long reindex operation. Logging is throttled so that a burst of
+/// consecutive reindex operations does not flood the log
+pub(super) fn log_reindex_summary(stats: &ReindexStats, elapsed: Duration) {
+    if stats.reindexed_blocks < SUMMARY_LOG_MIN_BLOCKS && elapsed < PROGRESS_REPORT_INTERVAL {
+        return;
+    }
+    let now = hybrid_unix_now();
+    let last = LAST_SUMMARY_LOG.load(Ordering::Relaxed);
+    if now.saturating_sub(last) < SUMMARY_LOG_INTERVAL_MILLIS
+        || LAST_SUMMARY_LOG.compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed).is_err()
+    {
+        return;
+    }
+    info!(
+        "Reachability reindex reallocated {} blocks in {:.2}s ({} operations, {} global, {} slack remaining)",
+        stats.reindexed_blocks,
+        elapsed.as_secs_f64(),
+        stats.operations,
+        stats.global_reindexes,
+        stats.slack_remaining.map_or("unknown".to_string(), |slack| slack.to_string())
+    );
+}
+
 /// Splits `children` into two slices: the blocks that are before `pivot` and the blocks that are after.
 fn split_children(children: &std::sync::Arc<Vec<Hash>>, pivot: Hash) -> Result<(&[Hash], &[Hash])> {
     if let Some(index) = children.iter().cloned().position(|c| c == pivot) {
@@ -482,7 +614,7 @@ fn split_children(children: &std::sync::Arc<Vec<Hash>>, pivot: Hash) -> Result<(
 
 #[cfg(test)]
 mod tests {
-    use super::super::tests::*;
+    use super::super::{tests::*, tree::add_tree_block};
     use super::*;
     use crate::{
         model::stores::reachability::{MemoryReachabilityStore, ReachabilityStoreReader},
@@ -533,4 +665,57 @@ mod tests {
         // Assert intervals follow the general rules
         store.validate_intervals(root).unwrap();
     }
+
+    #[test]
+    fn test_reindex_root_exhaustion_falls_back_to_global_reindex() {
+        let mut store = MemoryReachabilityStore::new();
+        let (depth, slack) = (2, 5);
+
+        // Arrange: a chain with a few forks
+        let root: Hash = 1.into();
+        let mut builder = TreeBuilder::new_with_params(&mut store, depth, slack);
+        builder.init_with_params(root, Interval::maximal());
+        for i in 2u64..=20 {
+            builder.add_block(i.into(), (i - 1).into());
+        }
+        for i in 21u64..=25 {
+            builder.add_block(i.into(), (i - 18).into());
+        }
+        let tip: Hash = 20.into();
+        let reindex_root = store.get_reindex_root().unwrap();
+        assert_ne!(reindex_root, root);
+        assert_ne!(reindex_root, tip);
+
+        // Exhaust the interval capacity of the reindex root by shrinking it to its exact subtree size
+        {
+            let mut ctx = ReindexOperationContext::new(&mut store, depth, slack);
+            ctx.count_subtrees(reindex_root).unwrap();
+            let interval = ctx.store.get_interval(reindex_root).unwrap();
+            let exact = Interval::new(interval.start, interval.start + ctx.subtree_sizes[&reindex_root] - 1);
+            ctx.store.set_interval(reindex_root, exact).unwrap();
+            ctx.propagate_interval(reindex_root).unwrap();
+        }
+        store.validate_intervals(root).unwrap();
+
+        // Act: no block up to the reindex root can hold a new child of the tip
+        let new_block: Hash = 26.into();
+        let stats = add_tree_block(&mut store, new_block, tip, depth, slack).unwrap();
+
+        // Assert
+        assert_eq!(stats.operations, 1);
+        assert_eq!(stats.global_reindexes, 1);
+        assert_eq!(stats.reindexed_blocks, 25);
+        store.validate_intervals(root).unwrap();
+        for i in 1u64..=20 {
+            assert!(inquirer::is_chain_ancestor_of(&store, i.into(), new_block).unwrap());
+        }
+        for i in 21u64..=25 {
+            assert!(!inquirer::is_chain_ancestor_of(&store, i.into(), new_block).unwrap());
+            assert!(inquirer::is_chain_ancestor_of(&store, (i - 18).into(), i.into()).unwrap());
+        }
+
+        // Following blocks are added regularly
+        TreeBuilder::new_with_params(&mut store, depth, slack).add_block(27.into(), new_block);
+        store.validate_intervals(root).unwrap();
+    }
 }
@@ -1,20 +1,27 @@
 //!
 //! Tree-related functions internal to the module
 //!
-use super::{extensions::ReachabilityStoreIntervalExtensions, inquirer::*, reindex::ReindexOperationContext, *};
+use super::{
+    extensions::ReachabilityStoreIntervalExtensions,
+    inquirer::*,
+    reindex::{log_reindex_summary, ReindexOperationContext},
+    *,
+};
 use crate::model::stores::reachability::ReachabilityStore;
 use kaspa_hashes::Hash;
+use std::time::Instant;
 
 /// Adds `new_block` as a child of `parent` in the tree structure. If this block
 /// has no remaining interval to allocate, a reindexing is triggered. When a reindexing
-/// is triggered, the reindex root point is used within the reindex algorithm's logic
+/// is triggered, the reindex root point is used within the reindex algorithm's logic.
+/// Returns statistics of the reindex work performed, if any
 pub fn add_tree_block(
     store: &mut (impl ReachabilityStore + ?Sized),
     new_block: Hash,
     parent: Hash,
     reindex_depth: u64,
     reindex_slack: u64,
-) -> Result<()> {
+) -> Result<ReindexStats> {
     // Get the remaining interval capacity
     let remaining = store.interval_remaining_after(parent)?;
     // Append the new child to `parent.children`
@@ -26,15 +33,19 @@ pub fn add_tree_block(
         //       which comes exactly at the end of current capacity
         store.insert(new_block, parent, remaining, parent_height + 1)?;
 
-        // Start a reindex operation (TODO: add timing)
+        // Start a reindex operation
+        let start = Instant::now();
         let reindex_root = store.get_reindex_root()?;
         let mut ctx = ReindexOperationContext::new(store, reindex_depth, reindex_slack);
         ctx.reindex_intervals(new_block, reindex_root)?;
+        let stats = ctx.stats();
+        log_reindex_summary(&stats, start.elapsed());
+        Ok(stats)
     } else {
         let allocated = remaining.split_half().0;
         store.insert(new_block, parent, allocated, parent_height + 1)?;
-    };
-    Ok(())
+        Ok(Default::default())
+    }
 }
 
 /// Finds the most recent tree ancestor common to both `block` and the given `reindex root`.
@@ -108,13 +119,14 @@ pub fn find_next_reindex_root(
 /// since this way it can benefit from chain-robustness which is implied by the security
 /// of the ordering protocol. That is, it enjoys from the fact that all future blocks are
 /// expected to elect the root subtree (by converging to the agreement to have it on the
-/// selected chain). See also the reachability algorithms overview (TODO)
+/// selected chain). See also the reachability algorithms overview (TODO).
+/// Returns statistics of the reindex work performed while concentrating intervals, if any
 pub fn try_advancing_reindex_root(
     store: &mut (impl ReachabilityStore + ?Sized),
     hint: Hash,
     reindex_depth: u64,
     reindex_slack: u64,
-) -> Result<()> {
+) -> Result<ReindexStats> {
     // Get current root from the store
     let current = store.get_reindex_root()?;
 
@@ -123,20 +135,26 @@ pub fn try_advancing_reindex_root(
 
     // No update to root, return
     if current == next {
-        return Ok(());
+        return Ok(Default::default());
     }
 
     // if ancestor == next {
     //     trace!("next reindex root is an ancestor of current one, skipping concentration.")
     // }
+    let start = Instant::now();
+    let mut stats = ReindexStats::default();
     while ancestor != next {
         let child = get_next_chain_ancestor_unchecked(store, next, ancestor)?;
         let mut ctx = ReindexOperationContext::new(store, reindex_depth, reindex_slack);
         ctx.concentrate_interval(ancestor, child, child == next)?;
+        if ctx.stats().reindexed_blocks > 0 {
+            stats.merge(&ctx.stats());
+        }
         ancestor = child;
     }
+    log_reindex_summary(&stats, start.elapsed());
 
     // Update reindex root in the data store
     store.set_reindex_root(next)?;
-    Ok(())
+    Ok(stats)
 }
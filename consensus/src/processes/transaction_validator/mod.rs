@@ -8,6 +8,7 @@ use crate::model::stores::ghostdag;
 
 use kaspa_txscript::{
     caches::{Cache, TxScriptCacheCounters},
+    metrics::ScriptMetrics,
     SigCacheKey,
 };
 
@@ -23,6 +24,7 @@ pub struct TransactionValidator {
     coinbase_payload_script_public_key_max_len: u8,
     coinbase_maturity: u64,
     sig_cache: Cache<SigCacheKey, bool>,
+    script_metrics: Arc<ScriptMetrics>,
 
     pub(crate) mass_calculator: MassCalculator,
 
@@ -40,6 +42,7 @@ impl TransactionValidator {
         coinbase_payload_script_public_key_max_len: u8,
         coinbase_maturity: u64,
         counters: Arc<TxScriptCacheCounters>,
+        script_metrics: Arc<ScriptMetrics>,
         mass_calculator: MassCalculator,
         storage_mass_activation_daa_score: u64,
     ) -> Self {
@@ -52,6 +55,7 @@ impl TransactionValidator {
             coinbase_payload_script_public_key_max_len,
             coinbase_maturity,
             sig_cache: Cache::with_counters(10_000, counters),
+            script_metrics,
             mass_calculator,
             storage_mass_activation_daa_score,
         }
@@ -76,7 +80,8 @@ impl TransactionValidator {
             coinbase_payload_script_public_key_max_len,
             coinbase_maturity,
             sig_cache: Cache::with_counters(10_000, counters),
-            mass_calculator: MassCalculator::new(0, 0, 0, 0),
+            script_metrics: Default::default(),
+            mass_calculator: MassCalculator::new_with_parameters(0, 0, 0, 0),
             storage_mass_activation_daa_score: u64::MAX,
         }
     }
@@ -145,14 +145,19 @@ impl TransactionValidator {
     }
 
     pub fn check_scripts(&self, tx: &impl VerifiableTransaction) -> TxResult<()> {
-        let mut reused_values = SigHashReusedValues::new();
-        for (i, (input, entry)) in tx.populated_inputs().enumerate() {
-            let mut engine = TxScriptEngine::from_transaction_input(tx, input, i, entry, &mut reused_values, &self.sig_cache)
-                .map_err(TxRuleError::SignatureInvalid)?;
-            engine.execute().map_err(TxRuleError::SignatureInvalid)?;
-        }
-
-        Ok(())
+        self.script_metrics
+            .record_verification(|mut opcode_counts| {
+                let mut reused_values = SigHashReusedValues::new();
+                for (i, (input, entry)) in tx.populated_inputs().enumerate() {
+                    let mut engine = TxScriptEngine::from_transaction_input(tx, input, i, entry, &mut reused_values, &self.sig_cache)?;
+                    if let Some(counts) = opcode_counts.as_deref_mut() {
+                        engine = engine.with_opcode_counts(counts);
+                    }
+                    engine.execute()?;
+                }
+                Ok(())
+            })
+            .map_err(TxRuleError::SignatureInvalid)
     }
 }
 
@@ -161,14 +166,19 @@ mod tests {
     use super::super::errors::TxRuleError;
     use core::str::FromStr;
     use itertools::Itertools;
+    use kaspa_consensus_core::constants::{LOCK_TIME_THRESHOLD, SEQUENCE_LOCK_TIME_DISABLED};
+    use kaspa_consensus_core::locktime::calc_sequence_lock;
     use kaspa_consensus_core::sign::sign;
-    use kaspa_consensus_core::subnets::SubnetworkId;
+    use kaspa_consensus_core::subnets::{SubnetworkId, SUBNETWORK_ID_NATIVE};
     use kaspa_consensus_core::tx::{MutableTransaction, PopulatedTransaction, ScriptVec, TransactionId, UtxoEntry};
     use kaspa_consensus_core::tx::{ScriptPublicKey, Transaction, TransactionInput, TransactionOutpoint, TransactionOutput};
+    use kaspa_txscript::metrics::ScriptMetrics;
+    use kaspa_txscript::opcodes::codes;
     use kaspa_txscript_errors::TxScriptError;
     use secp256k1::Secp256k1;
     use smallvec::SmallVec;
     use std::iter::once;
+    use std::sync::Arc;
 
     use crate::{params::MAINNET_PARAMS, processes::transaction_validator::TransactionValidator};
 
@@ -681,4 +691,134 @@ mod tests {
         assert_eq!(tv.check_scripts(&populated_tx), Ok(()));
         assert_eq!(TransactionValidator::check_sig_op_counts(&populated_tx), Ok(()));
     }
+
+    #[test]
+    fn test_calc_sequence_lock_matches_validation() {
+        let params = MAINNET_PARAMS.clone();
+        let tv = TransactionValidator::new_for_tests(
+            params.max_tx_inputs,
+            params.max_tx_outputs,
+            params.max_signature_script_len,
+            params.max_script_public_key_len,
+            params.ghostdag_k,
+            params.coinbase_payload_script_public_key_max_len,
+            params.coinbase_maturity,
+            Default::default(),
+        );
+
+        let timestamp = LOCK_TIME_THRESHOLD + 5000;
+        let tests = [
+            // (lock time, input sequences, UTXO DAA scores)
+            (0, vec![10, SEQUENCE_LOCK_TIME_DISABLED], vec![100, 200]),
+            (0, vec![0, 25], vec![100, 90]),
+            (150, vec![10], vec![100]),
+            (105, vec![10, 0], vec![100, 80]),
+            (timestamp, vec![10], vec![100]),
+            (timestamp, vec![SEQUENCE_LOCK_TIME_DISABLED], vec![100]),
+        ];
+        for (lock_time, sequences, utxo_daa_scores) in tests {
+            let inputs = sequences
+                .iter()
+                .enumerate()
+                .map(|(i, &sequence)| {
+                    TransactionInput::new(TransactionOutpoint::new(TransactionId::from_u64_word(1), i as u32), vec![], sequence, 1)
+                })
+                .collect();
+            let tx = Transaction::new(0, inputs, vec![], lock_time, SUBNETWORK_ID_NATIVE, 0, vec![]);
+            let entries =
+                utxo_daa_scores.iter().map(|&daa_score| UtxoEntry::new(1000, Default::default(), daa_score, false)).collect();
+            let populated_tx = PopulatedTransaction::new(&tx, entries);
+            let accepts = |daa_score: u64, median_time: u64| {
+                TransactionValidator::check_sequence_lock(&populated_tx, daa_score).is_ok()
+                    && tv.utxo_free_tx_validation(&tx, daa_score, median_time).is_ok()
+            };
+
+            // The validation accepts the transaction at the calculated lock but neither at a lower DAA score nor at an
+            // earlier median time, unless these are not bound by the lock
+            let lock = calc_sequence_lock(&tx, &utxo_daa_scores, 0, 0);
+            assert!(accepts(lock.daa_score, lock.median_time), "lock time {lock_time}, sequences {sequences:?}");
+            assert!(accepts(lock.daa_score + 1, lock.median_time + 1));
+            assert_eq!(accepts(lock.daa_score.saturating_sub(1), lock.median_time), lock.daa_score == 0);
+            assert_eq!(accepts(lock.daa_score, lock.median_time.saturating_sub(1)), lock.median_time == 0);
+            assert!(lock.is_met(lock.daa_score, lock.median_time));
+        }
+    }
+
+    #[test]
+    fn check_script_metrics_test() {
+        let params = MAINNET_PARAMS.clone();
+        let mut tv = TransactionValidator::new_for_tests(
+            params.max_tx_inputs,
+            params.max_tx_outputs,
+            params.max_signature_script_len,
+            params.max_script_public_key_len,
+            params.ghostdag_k,
+            params.coinbase_payload_script_public_key_max_len,
+            params.coinbase_maturity,
+            Default::default(),
+        );
+        // Count the opcodes of every transaction
+        tv.script_metrics = Arc::new(ScriptMetrics::new(1));
+
+        let prev_tx_id = TransactionId::from_str("1111111111111111111111111111111111111111111111111111111111111111").unwrap();
+        let check = |tv: &TransactionValidator, signature_script: &[u8], script_public_key: &[u8]| {
+            let tx = Transaction::new(
+                0,
+                vec![TransactionInput {
+                    previous_outpoint: TransactionOutpoint { transaction_id: prev_tx_id, index: 0 },
+                    signature_script: signature_script.to_vec(),
+                    sequence: 0,
+                    sig_op_count: 0,
+                }],
+                vec![],
+                0,
+                SUBNETWORK_ID_NATIVE,
+                0,
+                vec![],
+            );
+            let entry = UtxoEntry {
+                amount: 1000,
+                script_public_key: ScriptPublicKey::new(0, ScriptVec::from_slice(script_public_key)),
+                block_daa_score: 0,
+                is_coinbase: false,
+            };
+            tv.check_scripts(&PopulatedTransaction::new(&tx, vec![entry]))
+        };
+
+        assert_eq!(check(&tv, &[], &[codes::OpTrue]), Ok(()));
+        assert_eq!(check(&tv, &[], &[codes::OpTrue]), Ok(()));
+        assert_eq!(check(&tv, &[], &[codes::OpFalse]), Err(TxRuleError::SignatureInvalid(TxScriptError::EvalFalse)));
+        assert_eq!(check(&tv, &[], &[codes::OpFalse]), Err(TxRuleError::SignatureInvalid(TxScriptError::EvalFalse)));
+        assert_eq!(
+            check(&tv, &[codes::OpTrue, codes::OpDrop], &[codes::OpTrue]),
+            Err(TxRuleError::SignatureInvalid(TxScriptError::SignatureScriptNotPushOnly))
+        );
+        assert_eq!(check(&tv, &[codes::OpTrue], &[codes::OpTrue]), Err(TxRuleError::SignatureInvalid(TxScriptError::CleanStack(1))));
+        assert_eq!(check(&tv, &[], &[codes::OpReturn]), Err(TxRuleError::SignatureInvalid(TxScriptError::EarlyReturn)));
+
+        tv.script_metrics.merge_local();
+
+        let snapshot = tv.script_metrics.snapshot();
+        assert_eq!(snapshot.checked_counts, 7);
+        assert_eq!(snapshot.sampled_counts, 7);
+        assert_eq!(snapshot.rejected_counts(), 5);
+        assert_eq!(
+            snapshot.rejection_counts,
+            vec![("CleanStack", 1), ("EvalFalse", 2), ("EarlyReturn", 1), ("SignatureScriptNotPushOnly", 1)]
+        );
+        // The opcodes failing the push only check of the signature script are not executed
+        assert_eq!(snapshot.opcode_counts, vec![(codes::OpFalse, 2), (codes::OpTrue, 5), (codes::OpReturn, 1)]);
+
+        // Sampling 1 in 3 transactions counts the opcodes of the 1st, 4th and 7th ones
+        tv.script_metrics = Arc::new(ScriptMetrics::new(3));
+        for _ in 0..7 {
+            assert_eq!(check(&tv, &[], &[codes::OpTrue]), Ok(()));
+        }
+        tv.script_metrics.merge_local();
+        let snapshot = tv.script_metrics.snapshot();
+        assert_eq!(snapshot.checked_counts, 7);
+        assert_eq!(snapshot.sampled_counts, 3);
+        assert_eq!(snapshot.rejected_counts(), 0);
+        assert_eq!(snapshot.opcode_counts, vec![(codes::OpTrue, 3)]);
+    }
 }
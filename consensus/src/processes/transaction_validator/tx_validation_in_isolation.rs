@@ -149,10 +149,12 @@ fn check_transaction_output_value_ranges(tx: &Transaction) -> TxResult<()> {
 #[cfg(test)]
 mod tests {
     use kaspa_consensus_core::{
+        arbitrary::{Generator, Limits, TxViolation},
         subnets::{SUBNETWORK_ID_COINBASE, SUBNETWORK_ID_NATIVE},
         tx::{scriptvec, ScriptPublicKey, Transaction, TransactionId, TransactionInput, TransactionOutpoint, TransactionOutput},
     };
     use kaspa_core::assert_match;
+    use rand::{rngs::StdRng, SeedableRng};
 
     use crate::{
         constants::TX_VERSION,
@@ -297,4 +299,57 @@ mod tests {
         tx.version = TX_VERSION + 1;
         assert_match!(tv.validate_tx_in_isolation(&tx), Err(TxRuleError::UnknownTxVersion(_)));
     }
+
+    #[test]
+    fn validate_generated_tx_in_isolation_test() {
+        // Reduced limits, so that generated violations of count and size limits remain cheap
+        let mut params = MAINNET_PARAMS.clone();
+        params.max_tx_inputs = 10;
+        params.max_tx_outputs = 32;
+        params.max_signature_script_len = 256;
+        params.max_script_public_key_len = 512;
+        let tv = TransactionValidator::new_for_tests(
+            params.max_tx_inputs,
+            params.max_tx_outputs,
+            params.max_signature_script_len,
+            params.max_script_public_key_len,
+            params.ghostdag_k,
+            params.coinbase_payload_script_public_key_max_len,
+            params.coinbase_maturity,
+            Default::default(),
+        );
+
+        let mut generator = Generator::new(StdRng::seed_from_u64(42), Limits::from(&params));
+        for _ in 0..200 {
+            let tx = generator.transaction();
+            assert_match!(tv.validate_tx_in_isolation(&tx), Ok(()), "{tx:?}");
+            let tx = generator.coinbase_transaction();
+            assert_match!(tv.validate_tx_in_isolation(&tx), Ok(()), "{tx:?}");
+
+            for violation in TxViolation::ALL {
+                let tx = generator.transaction_with_violation(violation);
+                let result = tv.validate_tx_in_isolation(&tx);
+                let expected = match violation {
+                    TxViolation::NoInputs => matches!(result, Err(TxRuleError::NoTxInputs)),
+                    TxViolation::TooManyInputs => matches!(result, Err(TxRuleError::TooManyInputs(_, _))),
+                    TxViolation::TooManyOutputs => matches!(result, Err(TxRuleError::TooManyOutputs(_, _))),
+                    TxViolation::TooBigSignatureScript => matches!(result, Err(TxRuleError::TooBigSignatureScript(_, _))),
+                    TxViolation::TooBigScriptPublicKey => matches!(result, Err(TxRuleError::TooBigScriptPublicKey(_, _))),
+                    TxViolation::OutputZero => matches!(result, Err(TxRuleError::TxOutZero(_))),
+                    TxViolation::OutputTooHigh => matches!(result, Err(TxRuleError::TxOutTooHigh(_))),
+                    TxViolation::TotalOutputTooHigh => matches!(result, Err(TxRuleError::TotalTxOutTooHigh)),
+                    TxViolation::DuplicateInputs => matches!(result, Err(TxRuleError::TxDuplicateInputs)),
+                    TxViolation::HasGas => matches!(result, Err(TxRuleError::TxHasGas)),
+                    TxViolation::NonCoinbasePayload => matches!(result, Err(TxRuleError::NonCoinbaseTxHasPayload)),
+                    TxViolation::UnknownVersion => matches!(result, Err(TxRuleError::UnknownTxVersion(_))),
+                    TxViolation::CoinbaseHasInputs => matches!(result, Err(TxRuleError::CoinbaseHasInputs(_))),
+                    TxViolation::CoinbaseTooManyOutputs => matches!(result, Err(TxRuleError::CoinbaseTooManyOutputs(_, _))),
+                    TxViolation::CoinbaseScriptPublicKeyTooLong => {
+                        matches!(result, Err(TxRuleError::CoinbaseScriptPublicKeyTooLong(_)))
+                    }
+                };
+                assert!(expected, "{violation:?} resulted in {result:?} for {tx:?}");
+            }
+        }
+    }
 }
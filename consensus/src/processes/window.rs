@@ -2,22 +2,33 @@ use crate::{
     model::stores::{
         block_window_cache::{BlockWindowCacheReader, BlockWindowHeap, WindowOrigin},
         daa::DaaStoreReader,
-        ghostdag::{GhostdagData, GhostdagStoreReader},
+        ghostdag::{CompactGhostdagData, GhostdagData, GhostdagStoreReader},
         headers::HeaderStoreReader,
     },
     processes::ghostdag::ordering::SortableBlock,
 };
 use kaspa_consensus_core::{
-    blockhash::BlockHashExtensions,
+    api::counters::ProcessingCounters,
+    blockhash::{BlockHashExtensions, BlockHashes},
     config::genesis::GenesisBlock,
     errors::{block::RuleError, difficulty::DifficultyResult},
-    BlockHashSet, BlueWorkType,
+    BlockHashSet, BlueWorkType, HashKTypeMap,
 };
+use kaspa_database::prelude::StoreError;
 use kaspa_hashes::Hash;
 use kaspa_math::Uint256;
 use kaspa_utils::refs::Refs;
 use once_cell::unsync::Lazy;
-use std::{cmp::Reverse, iter::once, ops::Deref, sync::Arc};
+use std::{
+    cell::Cell,
+    cmp::Reverse,
+    iter::once,
+    ops::Deref,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
 use super::{
     difficulty::{FullDifficultyManager, SampledDifficultyManager},
@@ -44,13 +55,28 @@ impl DaaWindow {
     }
 }
 
+/// The DAA window and the past median time of a block, built together in a single pass over the past of the block
+pub struct BlockWindows {
+    pub daa_window: DaaWindow,
+
+    /// The past median time along with its window, or the error computing it, which is left to the
+    /// caller since the past median time is validated independently of the DAA window
+    pub past_median_time: Result<(u64, Arc<BlockWindowHeap>), RuleError>,
+}
+
 pub trait WindowManager {
     fn block_window(&self, ghostdag_data: &GhostdagData, window_type: WindowType) -> Result<Arc<BlockWindowHeap>, RuleError>;
     fn calc_daa_window(&self, ghostdag_data: &GhostdagData, window: Arc<BlockWindowHeap>) -> DaaWindow;
     fn block_daa_window(&self, ghostdag_data: &GhostdagData) -> Result<DaaWindow, RuleError>;
     fn calculate_difficulty_bits(&self, ghostdag_data: &GhostdagData, daa_window: &DaaWindow) -> u32;
     fn calc_past_median_time(&self, ghostdag_data: &GhostdagData) -> Result<(u64, Arc<BlockWindowHeap>), RuleError>;
+
+    /// Returns the same DAA window as [`Self::block_daa_window`] and the same past median time as [`Self::calc_past_median_time`],
+    /// sharing the store reads of the blocks entering both windows
+    fn block_windows(&self, ghostdag_data: &GhostdagData) -> Result<BlockWindows, RuleError>;
+
     fn estimate_network_hashes_per_second(&self, window: Arc<BlockWindowHeap>) -> DifficultyResult<u64>;
+    fn estimate_daa_window_hashes_per_second(&self, ghostdag_data: &GhostdagData, daa_window: &DaaWindow) -> DifficultyResult<u64>;
     fn window_size(&self, ghostdag_data: &GhostdagData, window_type: WindowType) -> usize;
     fn sample_rate(&self, ghostdag_data: &GhostdagData, window_type: WindowType) -> u64;
 }
@@ -67,6 +93,7 @@ pub struct FullWindowManager<T: GhostdagStoreReader, U: BlockWindowCacheReader,
     past_median_time_window_size: usize,
     difficulty_manager: FullDifficultyManager<V>,
     past_median_time_manager: FullPastMedianTimeManager<V>,
+    counters: Arc<ProcessingCounters>,
 }
 
 impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader> FullWindowManager<T, U, V> {
@@ -81,6 +108,7 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader> Fu
         difficulty_window_size: usize,
         min_difficulty_window_len: usize,
         past_median_time_window_size: usize,
+        counters: Arc<ProcessingCounters>,
     ) -> Self {
         let difficulty_manager = FullDifficultyManager::new(
             headers_store.clone(),
@@ -100,33 +128,38 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader> Fu
             past_median_time_window_size,
             difficulty_manager,
             past_median_time_manager,
+            counters,
         }
     }
 
-    fn build_block_window(&self, ghostdag_data: &GhostdagData, window_type: WindowType) -> Result<Arc<BlockWindowHeap>, RuleError> {
-        let window_size = self.window_size(ghostdag_data, window_type);
-        if window_size == 0 {
-            return Ok(Arc::new(BlockWindowHeap::new(WindowOrigin::Full)));
-        }
-
-        let cache = if window_size == self.difficulty_window_size {
+    fn window_cache(&self, window_size: usize) -> Option<&Arc<U>> {
+        if window_size == self.difficulty_window_size {
             Some(&self.block_window_cache_for_difficulty)
         } else if window_size == self.past_median_time_window_size {
             Some(&self.block_window_cache_for_past_median_time)
         } else {
             None
-        };
+        }
+    }
+
+    fn build_block_window(&self, ghostdag_data: &GhostdagData, window_type: WindowType) -> Result<Arc<BlockWindowHeap>, RuleError> {
+        let window_size = self.window_size(ghostdag_data, window_type);
+        if window_size == 0 {
+            return Ok(Arc::new(BlockWindowHeap::new(WindowOrigin::Full)));
+        }
 
-        if let Some(cache) = cache {
+        let store = CountingGhostdagReader::new(self.ghostdag_store.deref(), &self.counters.window_store_reads);
+        if let Some(cache) = self.window_cache(window_size) {
             if let Some(selected_parent_binary_heap) = cache.get(&ghostdag_data.selected_parent) {
                 // Only use the cached window if it originates from here
                 if let WindowOrigin::Full = selected_parent_binary_heap.origin() {
                     let mut window_heap = BoundedSizeBlockHeap::from_binary_heap(window_size, (*selected_parent_binary_heap).clone());
                     if ghostdag_data.selected_parent != self.genesis_hash {
                         self.try_push_mergeset(
+                            &store,
                             &mut window_heap,
                             ghostdag_data,
-                            self.ghostdag_store.get_blue_work(ghostdag_data.selected_parent).unwrap(),
+                            store.get_blue_work(ghostdag_data.selected_parent).unwrap(),
                         );
                     }
 
@@ -155,9 +188,9 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader> Fu
                 break;
             }
 
-            let parent_ghostdag = self.ghostdag_store.get_data(current_ghostdag.selected_parent).unwrap();
+            let parent_ghostdag = store.get_data(current_ghostdag.selected_parent).unwrap();
             let selected_parent_blue_work_too_low =
-                self.try_push_mergeset(&mut window_heap, &current_ghostdag, parent_ghostdag.blue_work);
+                self.try_push_mergeset(&store, &mut window_heap, &current_ghostdag, parent_ghostdag.blue_work);
             // No need to further iterate since past of selected parent has even lower blue work
             if selected_parent_blue_work_too_low {
                 break;
@@ -170,6 +203,7 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader> Fu
 
     fn try_push_mergeset(
         &self,
+        store: &CountingGhostdagReader<T>,
         heap: &mut BoundedSizeBlockHeap,
         ghostdag_data: &GhostdagData,
         selected_parent_blue_work: BlueWorkType,
@@ -179,7 +213,7 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader> Fu
         if !heap.try_push(ghostdag_data.selected_parent, selected_parent_blue_work) {
             return true;
         }
-        for block in ghostdag_data.descending_mergeset_without_selected_parent(self.ghostdag_store.deref()) {
+        for block in ghostdag_data.descending_mergeset_without_selected_parent(store) {
             // If it's smaller than minimum then we won't be able to add the rest because we iterate in descending blue work order.
             if !heap.try_push(block.hash, block.blue_work) {
                 break;
@@ -187,6 +221,80 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader> Fu
         }
         false
     }
+
+    /// Builds the difficulty and the past median time windows of a block at once. Since both windows hold the blocks
+    /// with the highest blue work in the past of the block, they are built by a single walk down the selected chain,
+    /// lasting until the larger window is complete.
+    ///
+    /// The result is identical to building each window by [`Self::build_block_window`]
+    fn build_daa_and_median_time_windows(&self, ghostdag_data: &GhostdagData) -> [Result<Arc<BlockWindowHeap>, RuleError>; 2] {
+        let store = CountingGhostdagReader::new(self.ghostdag_store.deref(), &self.counters.window_store_reads);
+        let window_types = [WindowType::SampledDifficultyWindow, WindowType::SampledMedianTimeWindow];
+        let mut windows = PendingWindows::new(
+            WindowOrigin::Full,
+            window_types.map(|window_type| {
+                let window_size = self.window_size(ghostdag_data, window_type);
+                if window_size == 0 {
+                    return None;
+                }
+                // Only use the cached window if it originates from here
+                match self.window_cache(window_size).and_then(|cache| cache.get(&ghostdag_data.selected_parent)) {
+                    Some(selected_parent_binary_heap) if matches!(selected_parent_binary_heap.origin(), WindowOrigin::Full) => {
+                        Some(PendingWindow::Extend(ExtendedBlockHeap::new(window_size, selected_parent_binary_heap)))
+                    }
+                    _ => Some(PendingWindow::Walk(BoundedSizeBlockHeap::new(WindowOrigin::Full, window_size))),
+                }
+            }),
+        );
+
+        let mut current_ghostdag: Refs<GhostdagData> = ghostdag_data.into();
+        loop {
+            if current_ghostdag.selected_parent == self.genesis_hash {
+                break;
+            }
+            if current_ghostdag.selected_parent.is_origin() {
+                windows.complete_walks_at_origin();
+            }
+            if windows.all_completed() {
+                break;
+            }
+
+            let parent_ghostdag = store.get_data(current_ghostdag.selected_parent).unwrap();
+
+            // If a window is full and the selected parent is less than its minimum, then no more blocks in the past
+            // can enter the window
+            let mut accepting = [false; 2];
+            for (i, accepting) in accepting.iter_mut().enumerate() {
+                if let Some(window) = windows.get_mut(i) {
+                    *accepting = window.heap_mut().try_push(current_ghostdag.selected_parent, parent_ghostdag.blue_work);
+                    if !*accepting && window.is_walk() {
+                        windows.complete(i);
+                    }
+                }
+            }
+
+            // Mergeset blocks are iterated in descending blue work order, so once a block failed to enter a window
+            // the rest of the mergeset cannot enter it either
+            if accepting.contains(&true) {
+                for block in current_ghostdag.descending_mergeset_without_selected_parent(&store) {
+                    for (i, accepting) in accepting.iter_mut().enumerate() {
+                        if *accepting {
+                            *accepting = windows.get_mut(i).unwrap().heap_mut().try_push(block.hash, block.blue_work);
+                        }
+                    }
+                    if !accepting.contains(&true) {
+                        break;
+                    }
+                }
+            }
+
+            // A cached window is only extended by the mergeset of the block itself
+            windows.complete_extended();
+            current_ghostdag = parent_ghostdag.into();
+        }
+
+        windows.into_results()
+    }
 }
 
 impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader> WindowManager for FullWindowManager<T, U, V> {
@@ -215,10 +323,22 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader> Wi
         Ok((past_median_time, window))
     }
 
+    fn block_windows(&self, ghostdag_data: &GhostdagData) -> Result<BlockWindows, RuleError> {
+        let [daa_window, past_median_time_window] = self.build_daa_and_median_time_windows(ghostdag_data);
+        let daa_window = self.calc_daa_window(ghostdag_data, daa_window?);
+        let past_median_time =
+            past_median_time_window.and_then(|window| Ok((self.past_median_time_manager.calc_past_median_time(&window)?, window)));
+        Ok(BlockWindows { daa_window, past_median_time })
+    }
+
     fn estimate_network_hashes_per_second(&self, window: Arc<BlockWindowHeap>) -> DifficultyResult<u64> {
         self.difficulty_manager.estimate_network_hashes_per_second(&window)
     }
 
+    fn estimate_daa_window_hashes_per_second(&self, _ghostdag_data: &GhostdagData, daa_window: &DaaWindow) -> DifficultyResult<u64> {
+        self.difficulty_manager.estimate_daa_window_hashes_per_second(&daa_window.window)
+    }
+
     fn window_size(&self, _ghostdag_data: &GhostdagData, window_type: WindowType) -> usize {
         match window_type {
             WindowType::SampledDifficultyWindow | WindowType::FullDifficultyWindow => self.difficulty_window_size,
@@ -239,6 +359,12 @@ enum SampledBlock {
     NonDaa(Hash),
 }
 
+enum MergesetBlock {
+    /// A block of the DAA window along with the DAA score it is counted at
+    Daa(SortableBlock, u64),
+    NonDaa(Hash),
+}
+
 /// A sampled window manager implementing [KIP-0004](https://github.com/kaspanet/kips/blob/master/kip-0004.md)
 #[derive(Clone)]
 pub struct SampledWindowManager<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader, W: DaaStoreReader> {
@@ -256,6 +382,7 @@ pub struct SampledWindowManager<T: GhostdagStoreReader, U: BlockWindowCacheReade
     past_median_time_sample_rate: u64,
     difficulty_manager: SampledDifficultyManager<V>,
     past_median_time_manager: SampledPastMedianTimeManager<V>,
+    counters: Arc<ProcessingCounters>,
 }
 
 impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader, W: DaaStoreReader> SampledWindowManager<T, U, V, W> {
@@ -275,6 +402,7 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader, W:
         difficulty_sample_rate: u64,
         past_median_time_window_size: usize,
         past_median_time_sample_rate: u64,
+        counters: Arc<ProcessingCounters>,
     ) -> Self {
         let difficulty_manager = SampledDifficultyManager::new(
             headers_store.clone(),
@@ -301,6 +429,15 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader, W:
             past_median_time_sample_rate,
             difficulty_manager,
             past_median_time_manager,
+            counters,
+        }
+    }
+
+    fn window_cache(&self, window_type: WindowType) -> Option<&Arc<U>> {
+        match window_type {
+            WindowType::SampledDifficultyWindow => Some(&self.block_window_cache_for_difficulty),
+            WindowType::SampledMedianTimeWindow => Some(&self.block_window_cache_for_past_median_time),
+            WindowType::FullDifficultyWindow | WindowType::VaryingWindow(_) => None,
         }
     }
 
@@ -326,21 +463,16 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader, W:
             return Err(RuleError::InsufficientDaaWindowSize(0));
         }
 
-        let cache = match window_type {
-            WindowType::SampledDifficultyWindow => Some(&self.block_window_cache_for_difficulty),
-            WindowType::SampledMedianTimeWindow => Some(&self.block_window_cache_for_past_median_time),
-            WindowType::FullDifficultyWindow | WindowType::VaryingWindow(_) => None,
-        };
-
-        if let Some(cache) = cache {
+        let store = CountingGhostdagReader::new(self.ghostdag_store.deref(), &self.counters.window_store_reads);
+        if let Some(cache) = self.window_cache(window_type) {
             if let Some(selected_parent_binary_heap) = cache.get(&ghostdag_data.selected_parent) {
                 // Only use the cached window if it originates from here
                 if let WindowOrigin::Sampled = selected_parent_binary_heap.origin() {
-                    let selected_parent_blue_work = self.ghostdag_store.get_blue_work(ghostdag_data.selected_parent).unwrap();
+                    let selected_parent_blue_work = store.get_blue_work(ghostdag_data.selected_parent).unwrap();
 
                     let mut heap =
                         Lazy::new(|| BoundedSizeBlockHeap::from_binary_heap(window_size, (*selected_parent_binary_heap).clone()));
-                    for block in self.sampled_mergeset_iterator(sample_rate, ghostdag_data, selected_parent_blue_work) {
+                    for block in self.sampled_mergeset_iterator(&store, sample_rate, ghostdag_data, selected_parent_blue_work) {
                         match block {
                             SampledBlock::Sampled(block) => {
                                 heap.try_push(block.hash, block.blue_work);
@@ -361,9 +493,9 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader, W:
         }
 
         let mut window_heap = BoundedSizeBlockHeap::new(WindowOrigin::Sampled, window_size);
-        let parent_ghostdag = self.ghostdag_store.get_data(ghostdag_data.selected_parent).unwrap();
+        let parent_ghostdag = store.get_data(ghostdag_data.selected_parent).unwrap();
 
-        for block in self.sampled_mergeset_iterator(sample_rate, ghostdag_data, parent_ghostdag.blue_work) {
+        for block in self.sampled_mergeset_iterator(&store, sample_rate, ghostdag_data, parent_ghostdag.blue_work) {
             match block {
                 SampledBlock::Sampled(block) => {
                     window_heap.try_push(block.hash, block.blue_work);
@@ -393,9 +525,9 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader, W:
                 break;
             }
 
-            let parent_ghostdag = self.ghostdag_store.get_data(current_ghostdag.selected_parent).unwrap();
+            let parent_ghostdag = store.get_data(current_ghostdag.selected_parent).unwrap();
             let selected_parent_blue_work_too_low =
-                self.try_push_mergeset(&mut window_heap, sample_rate, &current_ghostdag, parent_ghostdag.blue_work);
+                self.try_push_mergeset(&store, &mut window_heap, sample_rate, &current_ghostdag, parent_ghostdag.blue_work);
             // No need to further iterate since past of selected parent has even lower blue work
             if selected_parent_blue_work_too_low {
                 break;
@@ -409,6 +541,7 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader, W:
 
     fn try_push_mergeset(
         &self,
+        store: &CountingGhostdagReader<T>,
         heap: &mut BoundedSizeBlockHeap,
         sample_rate: u64,
         ghostdag_data: &GhostdagData,
@@ -420,7 +553,7 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader, W:
             return true;
         }
 
-        for block in self.sampled_mergeset_iterator(sample_rate, ghostdag_data, selected_parent_blue_work) {
+        for block in self.sampled_mergeset_iterator(store, sample_rate, ghostdag_data, selected_parent_blue_work) {
             match block {
                 SampledBlock::Sampled(block) => {
                     if !heap.try_push(block.hash, block.blue_work) {
@@ -435,29 +568,160 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader, W:
 
     fn sampled_mergeset_iterator<'a>(
         &'a self,
+        store: &'a CountingGhostdagReader<'a, T>,
         sample_rate: u64,
         ghostdag_data: &'a GhostdagData,
         selected_parent_blue_work: BlueWorkType,
     ) -> impl Iterator<Item = SampledBlock> + 'a {
+        self.mergeset_daa_iterator(store, ghostdag_data, selected_parent_blue_work).filter_map(move |block| match block {
+            MergesetBlock::Daa(block, daa_score) if daa_score % sample_rate == 0 => Some(SampledBlock::Sampled(block)),
+            MergesetBlock::Daa(..) => None,
+            MergesetBlock::NonDaa(hash) => Some(SampledBlock::NonDaa(hash)),
+        })
+    }
+
+    /// Iterates the selected parent and the mergeset of a block in descending blue work order, along with the DAA score
+    /// each DAA block is counted at, which decides whether the block is sampled
+    fn mergeset_daa_iterator<'a>(
+        &'a self,
+        store: &'a CountingGhostdagReader<'a, T>,
+        ghostdag_data: &'a GhostdagData,
+        selected_parent_blue_work: BlueWorkType,
+    ) -> impl Iterator<Item = MergesetBlock> + 'a {
         let selected_parent_block = SortableBlock::new(ghostdag_data.selected_parent, selected_parent_blue_work);
         let selected_parent_daa_score = self.headers_store.get_daa_score(ghostdag_data.selected_parent).unwrap();
+        store.count_read();
         let blue_score_threshold = self.difficulty_manager.lowest_daa_blue_score(ghostdag_data);
         let mut index: u64 = 0;
 
-        once(selected_parent_block)
-            .chain(ghostdag_data.descending_mergeset_without_selected_parent(self.ghostdag_store.deref()))
-            .filter_map(move |block| {
-                if self.ghostdag_store.get_blue_score(block.hash).unwrap() < blue_score_threshold {
-                    Some(SampledBlock::NonDaa(block.hash))
-                } else {
-                    index += 1;
-                    if (selected_parent_daa_score + index) % sample_rate == 0 {
-                        Some(SampledBlock::Sampled(block))
-                    } else {
-                        None
+        once(selected_parent_block).chain(ghostdag_data.descending_mergeset_without_selected_parent(store)).map(move |block| {
+            if store.get_blue_score(block.hash).unwrap() < blue_score_threshold {
+                MergesetBlock::NonDaa(block.hash)
+            } else {
+                index += 1;
+                MergesetBlock::Daa(block, selected_parent_daa_score + index)
+            }
+        })
+    }
+
+    /// Builds the difficulty and the past median time windows of a block at once, sharing the store reads of the blocks
+    /// visited by both. The windows are sampled at different rates, so each block is pushed into the windows sampling it.
+    ///
+    /// The result is identical to building each window by [`Self::build_block_window`], with the mergeset blocks
+    /// outside of the DAA window reported to `mergeset_non_daa_inserter` as done when building the difficulty window
+    fn build_daa_and_median_time_windows(
+        &self,
+        ghostdag_data: &GhostdagData,
+        mut mergeset_non_daa_inserter: impl FnMut(Hash),
+    ) -> [Result<Arc<BlockWindowHeap>, RuleError>; 2] {
+        let window_types = [WindowType::SampledDifficultyWindow, WindowType::SampledMedianTimeWindow];
+        let window_sizes = window_types.map(|window_type| self.window_size(ghostdag_data, window_type));
+        let sample_rates = window_types.map(|window_type| self.sample_rate(ghostdag_data, window_type));
+        // The mergeset blocks outside of the DAA window are reported only by a non-empty difficulty window
+        let report_non_daa = window_sizes[0] != 0;
+
+        // First, we handle all edge cases
+        if ghostdag_data.selected_parent == self.genesis_hash {
+            // Special case: Genesis does not enter the DAA window due to having a fixed timestamp
+            if report_non_daa {
+                mergeset_non_daa_inserter(self.genesis_hash);
+            }
+            return window_sizes.map(|_| Ok(Arc::new(BlockWindowHeap::new(WindowOrigin::Sampled))));
+        }
+        if ghostdag_data.selected_parent.is_origin() {
+            return window_sizes.map(|window_size| match window_size {
+                0 => Ok(Arc::new(BlockWindowHeap::new(WindowOrigin::Sampled))),
+                _ => Err(RuleError::InsufficientDaaWindowSize(0)),
+            });
+        }
+
+        let mut windows = PendingWindows::new(
+            WindowOrigin::Sampled,
+            window_types.map(|window_type| {
+                let window_size = self.window_size(ghostdag_data, window_type);
+                if window_size == 0 {
+                    return None;
+                }
+                // Only use the cached window if it originates from here
+                match self.window_cache(window_type).and_then(|cache| cache.get(&ghostdag_data.selected_parent)) {
+                    Some(selected_parent_binary_heap) if matches!(selected_parent_binary_heap.origin(), WindowOrigin::Sampled) => {
+                        Some(PendingWindow::Extend(ExtendedBlockHeap::new(window_size, selected_parent_binary_heap)))
+                    }
+                    _ => Some(PendingWindow::Walk(BoundedSizeBlockHeap::new(WindowOrigin::Sampled, window_size))),
+                }
+            }),
+        );
+        if windows.all_completed() {
+            return windows.into_results();
+        }
+        let store = CountingGhostdagReader::new(self.ghostdag_store.deref(), &self.counters.window_store_reads);
+
+        // The whole mergeset of the block itself is visited, since it holds the non-DAA blocks
+        let parent_ghostdag = store.get_data(ghostdag_data.selected_parent).unwrap();
+        for block in self.mergeset_daa_iterator(&store, ghostdag_data, parent_ghostdag.blue_work) {
+            match block {
+                MergesetBlock::Daa(block, daa_score) => {
+                    for (i, sample_rate) in sample_rates.iter().enumerate() {
+                        if daa_score % sample_rate == 0 {
+                            if let Some(window) = windows.get_mut(i) {
+                                window.heap_mut().try_push(block.hash, block.blue_work);
+                            }
+                        }
+                    }
+                }
+                MergesetBlock::NonDaa(hash) => {
+                    if report_non_daa {
+                        mergeset_non_daa_inserter(hash);
                     }
                 }
-            })
+            }
+        }
+        windows.complete_extended();
+
+        let mut current_ghostdag = parent_ghostdag;
+
+        // Walk down the chain until we cross the window boundaries
+        loop {
+            if current_ghostdag.selected_parent.is_origin() {
+                windows.complete_walks_at_origin();
+            }
+            if windows.all_completed() || current_ghostdag.selected_parent == self.genesis_hash {
+                break;
+            }
+
+            let parent_ghostdag = store.get_data(current_ghostdag.selected_parent).unwrap();
+
+            // If a window is full and the selected parent is less than its minimum, then no more blocks in the past
+            // can enter the window
+            let mut accepting = [false; 2];
+            for (i, accepting) in accepting.iter_mut().enumerate() {
+                if let Some(window) = windows.get_mut(i) {
+                    *accepting = window.heap_mut().can_push(current_ghostdag.selected_parent, parent_ghostdag.blue_work);
+                    if !*accepting {
+                        windows.complete(i);
+                    }
+                }
+            }
+
+            // Once a sampled block failed to enter a window, the rest of the mergeset cannot enter it either
+            if accepting.contains(&true) {
+                for block in self.mergeset_daa_iterator(&store, &current_ghostdag, parent_ghostdag.blue_work) {
+                    let MergesetBlock::Daa(block, daa_score) = block else { continue };
+                    for (i, accepting) in accepting.iter_mut().enumerate() {
+                        if *accepting && daa_score % sample_rates[i] == 0 {
+                            *accepting = windows.get_mut(i).unwrap().heap_mut().try_push(block.hash, block.blue_work);
+                        }
+                    }
+                    if !accepting.contains(&true) {
+                        break;
+                    }
+                }
+            }
+
+            current_ghostdag = parent_ghostdag;
+        }
+
+        windows.into_results()
     }
 }
 
@@ -493,10 +757,27 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader, W:
         Ok((past_median_time, window))
     }
 
+    fn block_windows(&self, ghostdag_data: &GhostdagData) -> Result<BlockWindows, RuleError> {
+        let mut mergeset_non_daa = BlockHashSet::default();
+        let [daa_window, past_median_time_window] = self.build_daa_and_median_time_windows(ghostdag_data, |hash| {
+            mergeset_non_daa.insert(hash);
+        });
+        let daa_window = daa_window?;
+        let daa_score = self.difficulty_manager.calc_daa_score(ghostdag_data, &mergeset_non_daa);
+        let daa_window = DaaWindow::new(daa_window, daa_score, mergeset_non_daa);
+        let past_median_time =
+            past_median_time_window.and_then(|window| Ok((self.past_median_time_manager.calc_past_median_time(&window)?, window)));
+        Ok(BlockWindows { daa_window, past_median_time })
+    }
+
     fn estimate_network_hashes_per_second(&self, window: Arc<BlockWindowHeap>) -> DifficultyResult<u64> {
         self.difficulty_manager.estimate_network_hashes_per_second(&window)
     }
 
+    fn estimate_daa_window_hashes_per_second(&self, _ghostdag_data: &GhostdagData, daa_window: &DaaWindow) -> DifficultyResult<u64> {
+        self.difficulty_manager.estimate_daa_window_hashes_per_second(&daa_window.window)
+    }
+
     fn window_size(&self, _ghostdag_data: &GhostdagData, window_type: WindowType) -> usize {
         match window_type {
             WindowType::SampledDifficultyWindow => self.difficulty_window_size,
@@ -549,6 +830,7 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader, W:
         full_past_median_time_window_size: usize,
         sampled_past_median_time_window_size: usize,
         past_median_time_sample_rate: u64,
+        counters: Arc<ProcessingCounters>,
     ) -> Self {
         let full_window_manager = FullWindowManager::new(
             genesis,
@@ -561,6 +843,7 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader, W:
             full_difficulty_window_size,
             min_difficulty_window_len.min(full_difficulty_window_size),
             full_past_median_time_window_size,
+            counters.clone(),
         );
         let sampled_window_manager = SampledWindowManager::new(
             genesis,
@@ -577,6 +860,7 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader, W:
             difficulty_sample_rate,
             sampled_past_median_time_window_size,
             past_median_time_sample_rate,
+            counters,
         );
         Self { ghostdag_store, headers_store, sampled_window_manager, full_window_manager, sampling_activation_daa_score }
     }
@@ -625,10 +909,24 @@ impl<T: GhostdagStoreReader, U: BlockWindowCacheReader, V: HeaderStoreReader, W:
         }
     }
 
+    fn block_windows(&self, ghostdag_data: &GhostdagData) -> Result<BlockWindows, RuleError> {
+        match self.sampling(ghostdag_data) {
+            true => self.sampled_window_manager.block_windows(ghostdag_data),
+            false => self.full_window_manager.block_windows(ghostdag_data),
+        }
+    }
+
     fn estimate_network_hashes_per_second(&self, window: Arc<BlockWindowHeap>) -> DifficultyResult<u64> {
         self.sampled_window_manager.estimate_network_hashes_per_second(window)
     }
 
+    fn estimate_daa_window_hashes_per_second(&self, ghostdag_data: &GhostdagData, daa_window: &DaaWindow) -> DifficultyResult<u64> {
+        match self.sampling(ghostdag_data) {
+            true => self.sampled_window_manager.estimate_daa_window_hashes_per_second(ghostdag_data, daa_window),
+            false => self.full_window_manager.estimate_daa_window_hashes_per_second(ghostdag_data, daa_window),
+        }
+    }
+
     fn window_size(&self, ghostdag_data: &GhostdagData, window_type: WindowType) -> usize {
         match self.sampling(ghostdag_data) {
             true => self.sampled_window_manager.window_size(ghostdag_data, window_type),
@@ -687,3 +985,189 @@ impl BoundedSizeBlockHeap {
         true
     }
 }
+
+/// A cached window of the selected parent extended by the mergeset of a block, cloned only once a block is pushed into it
+struct ExtendedBlockHeap {
+    selected_parent_binary_heap: Arc<BlockWindowHeap>,
+    size_bound: usize,
+    heap: Option<BoundedSizeBlockHeap>,
+}
+
+impl ExtendedBlockHeap {
+    fn new(size_bound: usize, selected_parent_binary_heap: Arc<BlockWindowHeap>) -> Self {
+        Self { selected_parent_binary_heap, size_bound, heap: None }
+    }
+
+    fn heap_mut(&mut self) -> &mut BoundedSizeBlockHeap {
+        self.heap.get_or_insert_with(|| {
+            BoundedSizeBlockHeap::from_binary_heap(self.size_bound, (*self.selected_parent_binary_heap).clone())
+        })
+    }
+
+    fn into_window(self) -> Arc<BlockWindowHeap> {
+        match self.heap {
+            Some(heap) => Arc::new(heap.binary_heap),
+            None => self.selected_parent_binary_heap,
+        }
+    }
+}
+
+/// A window built along with other windows, see `build_daa_and_median_time_windows`
+enum PendingWindow {
+    /// Extends the cached window of the selected parent by the mergeset of the block
+    Extend(ExtendedBlockHeap),
+
+    /// Walks down the selected chain until crossing the window boundaries
+    Walk(BoundedSizeBlockHeap),
+}
+
+impl PendingWindow {
+    fn heap_mut(&mut self) -> &mut BoundedSizeBlockHeap {
+        match self {
+            PendingWindow::Extend(heap) => heap.heap_mut(),
+            PendingWindow::Walk(heap) => heap,
+        }
+    }
+
+    fn is_walk(&self) -> bool {
+        matches!(self, PendingWindow::Walk(_))
+    }
+
+    fn into_window(self) -> Arc<BlockWindowHeap> {
+        match self {
+            PendingWindow::Extend(heap) => heap.into_window(),
+            PendingWindow::Walk(heap) => Arc::new(heap.binary_heap),
+        }
+    }
+}
+
+/// Windows built together, each of them either pending or completed
+struct PendingWindows<const N: usize> {
+    pending: [Option<PendingWindow>; N],
+    results: [Option<Result<Arc<BlockWindowHeap>, RuleError>>; N],
+}
+
+impl<const N: usize> PendingWindows<N> {
+    /// Creates the pending windows, where `None` stands for an empty window of `origin`
+    fn new(origin: WindowOrigin, pending: [Option<PendingWindow>; N]) -> Self {
+        let results =
+            std::array::from_fn(|i| if pending[i].is_none() { Some(Ok(Arc::new(BlockWindowHeap::new(origin)))) } else { None });
+        Self { pending, results }
+    }
+
+    fn get_mut(&mut self, i: usize) -> Option<&mut PendingWindow> {
+        self.pending[i].as_mut()
+    }
+
+    fn all_completed(&self) -> bool {
+        self.pending.iter().all(Option::is_none)
+    }
+
+    fn complete(&mut self, i: usize) {
+        if let Some(window) = self.pending[i].take() {
+            self.results[i] = Some(Ok(window.into_window()));
+        }
+    }
+
+    fn complete_extended(&mut self) {
+        for i in 0..N {
+            if let Some(PendingWindow::Extend(_)) = self.pending[i] {
+                self.complete(i);
+            }
+        }
+    }
+
+    /// Completes the windows walking down the chain once reaching origin. Since there's no more data below origin,
+    /// a window which is not full yet fails (see `build_block_window`)
+    fn complete_walks_at_origin(&mut self) {
+        for i in 0..N {
+            let Some(PendingWindow::Walk(heap)) = &self.pending[i] else { continue };
+            if heap.reached_size_bound() {
+                self.complete(i);
+            } else {
+                let len = heap.binary_heap.len();
+                self.pending[i] = None;
+                self.results[i] = Some(Err(RuleError::InsufficientDaaWindowSize(len)));
+            }
+        }
+    }
+
+    fn into_results(mut self) -> [Result<Arc<BlockWindowHeap>, RuleError>; N] {
+        for i in 0..N {
+            self.complete(i);
+        }
+        self.results.map(Option::unwrap)
+    }
+}
+
+/// A ghostdag store reader counting the reads performed while building a window, adding them to the
+/// shared counter once dropped, so that the hot path does not contend on the counter
+struct CountingGhostdagReader<'a, T: GhostdagStoreReader> {
+    store: &'a T,
+    reads: Cell<u64>,
+    counter: &'a AtomicU64,
+}
+
+impl<'a, T: GhostdagStoreReader> CountingGhostdagReader<'a, T> {
+    fn new(store: &'a T, counter: &'a AtomicU64) -> Self {
+        Self { store, reads: Cell::new(0), counter }
+    }
+
+    /// Counts a read performed on some other store
+    fn count_read(&self) {
+        self.reads.set(self.reads.get() + 1);
+    }
+}
+
+impl<T: GhostdagStoreReader> Drop for CountingGhostdagReader<'_, T> {
+    fn drop(&mut self) {
+        self.counter.fetch_add(self.reads.get(), Ordering::Relaxed);
+    }
+}
+
+impl<T: GhostdagStoreReader> GhostdagStoreReader for CountingGhostdagReader<'_, T> {
+    fn get_blue_score(&self, hash: Hash) -> Result<u64, StoreError> {
+        self.count_read();
+        self.store.get_blue_score(hash)
+    }
+
+    fn get_blue_work(&self, hash: Hash) -> Result<BlueWorkType, StoreError> {
+        self.count_read();
+        self.store.get_blue_work(hash)
+    }
+
+    fn get_selected_parent(&self, hash: Hash) -> Result<Hash, StoreError> {
+        self.count_read();
+        self.store.get_selected_parent(hash)
+    }
+
+    fn get_mergeset_blues(&self, hash: Hash) -> Result<BlockHashes, StoreError> {
+        self.count_read();
+        self.store.get_mergeset_blues(hash)
+    }
+
+    fn get_mergeset_reds(&self, hash: Hash) -> Result<BlockHashes, StoreError> {
+        self.count_read();
+        self.store.get_mergeset_reds(hash)
+    }
+
+    fn get_blues_anticone_sizes(&self, hash: Hash) -> Result<HashKTypeMap, StoreError> {
+        self.count_read();
+        self.store.get_blues_anticone_sizes(hash)
+    }
+
+    fn get_data(&self, hash: Hash) -> Result<Arc<GhostdagData>, StoreError> {
+        self.count_read();
+        self.store.get_data(hash)
+    }
+
+    fn get_compact_data(&self, hash: Hash) -> Result<CompactGhostdagData, StoreError> {
+        self.count_read();
+        self.store.get_compact_data(hash)
+    }
+
+    fn has(&self, hash: Hash) -> Result<bool, StoreError> {
+        self.count_read();
+        self.store.has(hash)
+    }
+}
@@ -0,0 +1,114 @@
+//!
+//! Peak allocation test of the lazy acceptance data reads serving deep virtual chain queries. The test lives in
+//! its own binary since it replaces the global allocator with one counting the allocations of each thread.
+//!
+
+use kaspa_consensus::model::stores::acceptance_data::{AcceptanceDataStore, AcceptanceDataStoreReader, DbAcceptanceDataStore};
+use kaspa_consensus_core::acceptance_data::{AcceptedTxEntry, MergesetBlockAcceptanceData};
+use kaspa_database::{
+    create_temp_db,
+    prelude::{CachePolicy, ConnBuilder},
+};
+use kaspa_hashes::Hash;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    mem::size_of,
+    sync::Arc,
+};
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATED: Cell<usize> = const { Cell::new(0) };
+    static PEAK: Cell<usize> = const { Cell::new(0) };
+}
+
+fn record_alloc(size: usize) {
+    let allocated = ALLOCATED.get() + size;
+    ALLOCATED.set(allocated);
+    PEAK.set(PEAK.get().max(allocated));
+}
+
+fn record_dealloc(size: usize) {
+    // Memory allocated by another thread might be released by this one
+    ALLOCATED.set(ALLOCATED.get().saturating_sub(size));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record_alloc(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        record_dealloc(layout.size());
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record_dealloc(layout.size());
+        record_alloc(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Returns the peak of the bytes allocated by the current thread while running `f`, on top of the bytes
+/// allocated when `f` is called
+fn peak_allocation<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let base = ALLOCATED.get();
+    PEAK.set(base);
+    let result = f();
+    (result, PEAK.get() - base)
+}
+
+#[test]
+fn test_lazy_acceptance_data_peak_allocation() {
+    const CHAIN_BLOCKS: u64 = 50_000;
+    const MERGESET_SIZE: u64 = 2;
+    const ACCEPTED_TXS: u64 = 4;
+    const BATCH_SIZE: usize = 1024;
+
+    let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+    let store = DbAcceptanceDataStore::new(db, CachePolicy::Count(16));
+    let chain = (1..=CHAIN_BLOCKS).map(Hash::from_u64_word).collect::<Vec<_>>();
+    for (i, &hash) in chain.iter().enumerate() {
+        let acceptance_data = (0..MERGESET_SIZE)
+            .map(|j| MergesetBlockAcceptanceData {
+                block_hash: Hash::from_u64_word(i as u64 * MERGESET_SIZE + j),
+                accepted_transactions: (0..ACCEPTED_TXS)
+                    .map(|k| AcceptedTxEntry { transaction_id: Hash::from_u64_word(k), index_within_block: k as u32 })
+                    .collect(),
+            })
+            .collect();
+        store.insert(hash, Arc::new(acceptance_data)).unwrap();
+    }
+
+    // Loading the acceptance data of the whole chain at once grows with the chain length
+    let (loaded, eager_peak) = peak_allocation(|| chain.iter().map(|&hash| store.get(hash).unwrap()).collect::<Vec<_>>());
+    assert_eq!(loaded.len(), CHAIN_BLOCKS as usize);
+    drop(loaded);
+
+    // Streaming batches of accepted transaction ids only holds the acceptance data of a single block at a time
+    let (accepted_count, lazy_peak) = peak_allocation(|| {
+        let mut accepted_count = 0;
+        for hashes in chain.chunks(BATCH_SIZE) {
+            let batch = store
+                .iter_uncached(hashes.iter().copied())
+                .map(|acceptance_data| {
+                    acceptance_data.unwrap().iter().flat_map(|x| x.accepted_transactions.iter().map(|tx| tx.transaction_id)).count()
+                })
+                .collect::<Vec<_>>();
+            accepted_count += batch.into_iter().sum::<usize>();
+        }
+        accepted_count
+    });
+    assert_eq!(accepted_count, (CHAIN_BLOCKS * MERGESET_SIZE * ACCEPTED_TXS) as usize);
+
+    let block_bytes = (MERGESET_SIZE * (ACCEPTED_TXS * 40 + 64)) as usize;
+    assert!(lazy_peak < BATCH_SIZE * size_of::<usize>() + 16 * block_bytes, "lazy reads peaked at {lazy_peak} bytes");
+    assert!(eager_peak > CHAIN_BLOCKS as usize * block_bytes / 2, "eager reads peaked at {eager_peak} bytes");
+}
@@ -0,0 +1,80 @@
+//!
+//! Test of the emergency flush run by the panic hook. The test lives in its own binary since it replaces
+//! the global panic hook.
+//!
+
+use kaspa_consensus::model::stores::headers::{DbHeadersStore, HeaderStore, HeaderStoreReader};
+use kaspa_consensus_core::header::Header;
+use kaspa_core::panic::{configure_panic_with_exit, register_emergency_action};
+use kaspa_database::{
+    prelude::{CachePolicy, ConnBuilder, WalSyncPolicy, DB},
+    utils::{get_kaspa_tempdir, DbLifetime},
+};
+use kaspa_hashes::Hash;
+use std::{
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+fn open_db(path: &Path) -> Arc<DB> {
+    ConnBuilder::default().with_db_path(path.to_owned()).with_wal_sync(WalSyncPolicy::Critical).with_files_limit(10).build().unwrap()
+}
+
+#[test]
+fn test_emergency_flush_on_panic() {
+    let db_dir = get_kaspa_tempdir();
+    let db = open_db(db_dir.path());
+
+    let flushed = Arc::new(AtomicBool::new(false));
+    let flush = db.emergency_flush_action();
+    let flushed_clone = flushed.clone();
+    register_emergency_action("test-db", move || {
+        flush()?;
+        flushed_clone.store(true, Ordering::SeqCst);
+        Ok(())
+    });
+    // The process is kept alive rather than exited by the hook
+    let exits = Arc::new(AtomicUsize::new(0));
+    let exits_clone = exits.clone();
+    configure_panic_with_exit(move || {
+        exits_clone.fetch_add(1, Ordering::SeqCst);
+    });
+
+    let headers = (1..=10)
+        .map(|i| {
+            let mut header = Header::from_precomputed_hash(Hash::from_u64_word(i), vec![]);
+            header.daa_score = i;
+            Arc::new(header)
+        })
+        .collect::<Vec<_>>();
+    let last_hash = headers.last().unwrap().hash;
+
+    // A processor thread panics right after writing its last header
+    let store = DbHeadersStore::new(db.clone(), CachePolicy::Empty, CachePolicy::Empty);
+    let processor = thread::Builder::new()
+        .name("header-processor".to_string())
+        .spawn(move || {
+            for header in headers {
+                store.insert(header.hash, header, 0).unwrap();
+            }
+            panic!("simulated processing failure");
+        })
+        .unwrap();
+    assert!(processor.join().is_err());
+    assert_eq!(exits.load(Ordering::SeqCst), 1, "the panic hook is expected to run once");
+    assert!(flushed.load(Ordering::SeqCst), "the emergency flush is expected to complete before exiting");
+
+    // The DB reopens cleanly with the last written header
+    drop(db);
+    let db = open_db(db_dir.path());
+    let store = DbHeadersStore::new(db.clone(), CachePolicy::Empty, CachePolicy::Empty);
+    assert_eq!(store.get_header(last_hash).unwrap().daa_score, 10);
+
+    drop(store);
+    let _lifetime = DbLifetime::new(db_dir, Arc::downgrade(&db));
+    drop(db);
+}
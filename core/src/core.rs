@@ -1,12 +1,26 @@
-use crate::service::Service;
+use crate::panic::{run_emergency_actions, EMERGENCY_ACTIONS_TIMEOUT};
+use crate::service::{qualified_ident, Service};
 use crate::signals::Shutdown;
-use crate::trace;
+use crate::{error, trace, warn};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
 
 pub struct Core {
     pub keep_running: AtomicBool,
+    namespace: Option<String>,
     services: Mutex<Vec<Arc<dyn Service>>>,
+    shutdown_deadline: Option<Duration>,
+    join_progress: Mutex<JoinProgress>,
+    joined: Condvar,
+}
+
+#[derive(Default)]
+struct JoinProgress {
+    /// Name of the worker thread being joined
+    current: Option<String>,
+    remaining: usize,
+    done: bool,
 }
 
 impl Default for Core {
@@ -17,7 +31,36 @@ impl Default for Core {
 
 impl Core {
     pub fn new() -> Core {
-        Core { keep_running: AtomicBool::new(true), services: Mutex::new(Vec::new()) }
+        Core {
+            keep_running: AtomicBool::new(true),
+            namespace: None,
+            services: Mutex::new(Vec::new()),
+            shutdown_deadline: None,
+            join_progress: Default::default(),
+            joined: Condvar::new(),
+        }
+    }
+
+    /// Creates a core whose shutdown is expected to complete within `deadline`. A shutdown exceeding it is logged
+    /// along with the worker being waited for, and a shutdown exceeding twice the deadline is escalated to running
+    /// the emergency actions and exiting the process.
+    pub fn with_shutdown_deadline(deadline: Duration) -> Core {
+        Core { shutdown_deadline: Some(deadline), ..Self::new() }
+    }
+
+    /// Namespaces the core, distinguishing its services and workers from the ones of the other cores of the process
+    /// when several nodes run in a single process
+    pub fn with_namespace(self, namespace: impl Into<String>) -> Core {
+        Core { namespace: Some(namespace.into()), ..self }
+    }
+
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
+    /// The identity of a service or worker of this core, qualified by the namespace of the core if any
+    pub fn qualified_ident(&self, ident: &str) -> String {
+        qualified_ident(self.namespace(), ident)
     }
 
     pub fn bind<T>(&self, service: Arc<T>)
@@ -43,13 +86,19 @@ impl Core {
         for service in self.services.lock().unwrap().iter() {
             workers.append(&mut service.clone().start(self.clone()));
         }
-        trace!("core is starting {} workers", workers.len());
+        trace!("{} is starting {} workers", self.qualified_ident("core"), workers.len());
         workers
     }
 
     /// Join workers previously returned from `start`
     pub fn join(&self, workers: Vec<std::thread::JoinHandle<()>>) {
-        for worker in workers {
+        let count = workers.len();
+        for (i, worker) in workers.into_iter().enumerate() {
+            {
+                let mut progress = self.join_progress.lock().unwrap();
+                progress.current = Some(self.qualified_ident(worker.thread().name().unwrap_or("<unnamed>")));
+                progress.remaining = count - i;
+            }
             match worker.join() {
                 Ok(()) => {}
                 Err(err) => {
@@ -61,7 +110,45 @@ impl Core {
         // Drop all services and cleanup
         self.services.lock().unwrap().clear();
 
-        trace!("... core is shut down");
+        *self.join_progress.lock().unwrap() = JoinProgress { current: None, remaining: 0, done: true };
+        self.joined.notify_all();
+
+        trace!("... {} is shut down", self.qualified_ident("core"));
+    }
+
+    /// Watches a shutdown in progress, escalating if it exceeds the deadline
+    fn watch_shutdown(&self, deadline: Duration) {
+        let start = Instant::now();
+        let mut progress = self.join_progress.lock().unwrap();
+        for escalation in 0.. {
+            let (guard, result) = self.joined.wait_timeout_while(progress, deadline, |progress| !progress.done).unwrap();
+            progress = guard;
+            if !result.timed_out() {
+                return;
+            }
+            let waiting = match progress.current.as_ref() {
+                Some(name) => format!("worker '{}' ({} of the workers remaining)", name, progress.remaining),
+                None => "the workers to start joining".to_string(),
+            };
+            if escalation == 0 {
+                warn!(
+                    "{} shutdown exceeded its deadline of {:?}, still waiting for {}",
+                    self.qualified_ident("Core"),
+                    deadline,
+                    waiting
+                );
+            } else {
+                error!(
+                    "{} shutdown is stuck for {:?} waiting for {}, forcing exit",
+                    self.qualified_ident("Core"),
+                    start.elapsed(),
+                    waiting
+                );
+                drop(progress);
+                run_emergency_actions(EMERGENCY_ACTIONS_TIMEOUT);
+                std::process::exit(1);
+            }
+        }
     }
 }
 
@@ -71,16 +158,25 @@ impl Shutdown for Core {
             return;
         }
 
-        trace!("signaling core shutdown...");
+        trace!("signaling {} shutdown...", self.qualified_ident("core"));
 
         {
             for service in self.services.lock().unwrap().iter() {
-                let ident = service.clone().ident();
+                let ident = self.qualified_ident(service.clone().ident());
                 trace!("shutting down: {}", ident);
                 service.clone().stop();
             }
         }
 
-        trace!("core is shutting down...");
+        if let Some(deadline) = self.shutdown_deadline {
+            let core = self.clone();
+            let spawned =
+                std::thread::Builder::new().name("shutdown-watchdog".to_string()).spawn(move || core.watch_shutdown(deadline));
+            if let Err(err) = spawned {
+                warn!("shutdown watchdog could not be started: {}", err);
+            }
+        }
+
+        trace!("{} is shutting down...", self.qualified_ident("core"));
     }
 }
@@ -0,0 +1,208 @@
+use super::{logger::LogError, trace_id};
+use chrono::{SecondsFormat, Utc};
+use log::{
+    kv::{self, Key, Value, VisitSource},
+    Record,
+};
+use log4rs::encode::{pattern::PatternEncoder, Encode, Write};
+use serde_json::{Map, Number, Value as JsonValue};
+use std::{fmt::Display, str::FromStr};
+
+/// Output format of the log lines
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human readable lines following the log line patterns
+    #[default]
+    Text,
+    /// One JSON object per line holding the `ts`, `level`, `target` and `message` fields, the `trace_id`
+    /// field within the scope of a trace id, along with the structured key-values supplied by the call site
+    Json,
+}
+
+impl LogFormat {
+    pub(super) fn encoder(self, pattern: &str) -> Box<dyn Encode> {
+        match self {
+            LogFormat::Text => Box::new(TextEncoder(PatternEncoder::new(pattern))),
+            LogFormat::Json => Box::new(JsonEncoder),
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = LogError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(LogError::ParseLogFormatError(s.to_string())),
+        }
+    }
+}
+
+impl Display for LogFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogFormat::Text => write!(f, "text"),
+            LogFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// Encodes each log record following a line pattern, prefixing the message with the current trace id, if any
+#[derive(Debug)]
+pub(super) struct TextEncoder(PatternEncoder);
+
+impl Encode for TextEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        if let Some(trace_id) = trace_id::current() {
+            return self.0.encode(w, &record.to_builder().args(format_args!("[{trace_id}] {}", record.args())).build());
+        }
+        self.0.encode(w, record)
+    }
+}
+
+/// Encodes each log record as a single line JSON object.
+///
+/// The object holds the `ts` (UTC timestamp in RFC 3339 format with millisecond precision), `level`,
+/// `target` and `message` fields, the `trace_id` field if the record is emitted within the scope of a trace
+/// id, along with the key-values supplied by the call site, if any (e.g. `info!(hash:% = block_hash;
+/// "Accepted block")`). Key-values never override the above fields.
+#[derive(Debug, Default)]
+pub(super) struct JsonEncoder;
+
+impl Encode for JsonEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        let mut object = Map::new();
+        object.insert("ts".to_string(), Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true).into());
+        object.insert("level".to_string(), record.level().as_str().into());
+        object.insert("target".to_string(), record.target().into());
+        object.insert("message".to_string(), record.args().to_string().into());
+        if let Some(trace_id) = trace_id::current() {
+            object.insert("trace_id".to_string(), trace_id.as_str().into());
+        }
+        record.key_values().visit(&mut KeyValuesVisitor(&mut object)).map_err(|err| anyhow::anyhow!("{err}"))?;
+
+        serde_json::to_writer(&mut *w, &object)?;
+        w.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+struct KeyValuesVisitor<'a>(&'a mut Map<String, JsonValue>);
+
+impl<'kvs> VisitSource<'kvs> for KeyValuesVisitor<'_> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), kv::Error> {
+        if !self.0.contains_key(key.as_str()) {
+            self.0.insert(key.to_string(), to_json_value(&value));
+        }
+        Ok(())
+    }
+}
+
+fn to_json_value(value: &Value) -> JsonValue {
+    if let Some(value) = value.to_bool() {
+        value.into()
+    } else if let Some(value) = value.to_u64() {
+        value.into()
+    } else if let Some(value) = value.to_i64() {
+        value.into()
+    } else if let Some(number) = value.to_f64().and_then(Number::from_f64) {
+        JsonValue::Number(number)
+    } else {
+        value.to_string().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+    use log::Level;
+    use log4rs::encode::writer::simple::SimpleWriter;
+
+    fn encode(record: &Record) -> JsonValue {
+        let mut writer = SimpleWriter(Vec::new());
+        JsonEncoder.encode(&mut writer, record).unwrap();
+        let line = String::from_utf8(writer.0).unwrap();
+        assert!(line.ends_with('\n'));
+        assert_eq!(line.matches('\n').count(), 1, "a record must be encoded as a single line");
+        serde_json::from_str(&line).unwrap()
+    }
+
+    #[test]
+    fn test_json_encoder_schema() {
+        let key_values: [(&str, Value); 5] = [
+            ("hash", Value::from("8a1bd3e6")),
+            ("peer", Value::from_display(&"127.0.0.1:16111")),
+            ("count", Value::from(3u64)),
+            ("synced", Value::from(true)),
+            ("level", Value::from("overridden")),
+        ];
+        let record = Record::builder()
+            .level(Level::Info)
+            .target("kaspa_p2p_flows::flow_context")
+            .args(format_args!("Accepted block 8a1bd3e6\nvia relay"))
+            .key_values(&key_values)
+            .build();
+        let json = encode(&record);
+        let object = json.as_object().unwrap();
+
+        assert!(DateTime::parse_from_rfc3339(object["ts"].as_str().unwrap()).is_ok());
+        assert_eq!(object["level"], "INFO");
+        assert_eq!(object["target"], "kaspa_p2p_flows::flow_context");
+        assert_eq!(object["message"], "Accepted block 8a1bd3e6\nvia relay");
+        assert_eq!(object["hash"], "8a1bd3e6");
+        assert_eq!(object["peer"], "127.0.0.1:16111");
+        assert_eq!(object["count"], 3);
+        assert_eq!(object["synced"], true);
+        assert_eq!(object.len(), 8);
+
+        // A record without key-values holds the fixed fields only
+        let record = Record::builder().level(Level::Warn).target("kaspad").args(format_args!("warning")).build();
+        let json = encode(&record);
+        let object = json.as_object().unwrap();
+        let mut keys = object.keys().map(|x| x.as_str()).collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(keys, vec!["level", "message", "target", "ts"]);
+        assert_eq!(object["level"], "WARN");
+    }
+
+    #[test]
+    fn test_trace_id_field() {
+        let trace_id = trace_id::TraceId::from_client("rpc-call-1").unwrap();
+        let key_values: [(&str, Value); 1] = [("trace_id", Value::from("call-site"))];
+        let record = Record::builder()
+            .level(Level::Info)
+            .target("kaspa_rpc_service")
+            .args(format_args!("handled"))
+            .key_values(&key_values)
+            .build();
+
+        let object = trace_id::sync_scope(Some(trace_id), || encode(&record));
+        assert_eq!(object["trace_id"], "rpc-call-1");
+        assert_eq!(object["message"], "handled");
+        // Outside of any scope, a trace id supplied by the call site is kept
+        assert_eq!(encode(&record)["trace_id"], "call-site");
+
+        let encode_text = |record: &Record| {
+            let mut writer = SimpleWriter(Vec::new());
+            TextEncoder(PatternEncoder::new("[{l}] {m}{n}")).encode(&mut writer, record).unwrap();
+            String::from_utf8(writer.0).unwrap()
+        };
+        let record = Record::builder().level(Level::Info).args(format_args!("handled")).build();
+        assert_eq!(
+            trace_id::sync_scope(trace_id::TraceId::from_client("rpc-call-2"), || encode_text(&record)),
+            "[INFO] [rpc-call-2] handled\n"
+        );
+        assert_eq!(encode_text(&record), "[INFO] handled\n");
+    }
+
+    #[test]
+    fn test_log_format_parsing() {
+        assert_eq!("text".parse::<LogFormat>().unwrap(), LogFormat::Text);
+        assert_eq!("JSON".parse::<LogFormat>().unwrap(), LogFormat::Json);
+        assert!("xml".parse::<LogFormat>().is_err());
+        assert_eq!(LogFormat::Json.to_string().parse::<LogFormat>().unwrap(), LogFormat::Json);
+    }
+}
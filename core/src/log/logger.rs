@@ -7,6 +7,12 @@ use thiserror::Error;
 pub enum LogError {
     #[error("Logger spec parsing error: {0}")]
     ParseLoggerSpecError(String),
+
+    #[error("Log format parsing error: {0} (expected text or json)")]
+    ParseLogFormatError(String),
+
+    #[error("Logger is not initialized")]
+    LoggerNotInitialized,
 }
 
 #[derive(Clone)]
@@ -39,8 +45,16 @@ impl Loggers {
     pub fn items(&self) -> impl IntoIterator<Item = Logger> + '_ {
         self.loggers.iter().map(|x| x.logger())
     }
+
+    /// Returns the specs expression equivalent to these loggers, listing the root level first
+    pub fn expression(&self) -> String {
+        let mut loggers = self.loggers.iter().map(|x| format!("{}={}", x.name, x.level.as_str().to_lowercase())).collect::<Vec<_>>();
+        loggers.sort();
+        std::iter::once(self.root_level.as_str().to_lowercase()).chain(loggers).collect::<Vec<_>>().join(",")
+    }
 }
 
+#[derive(Clone)]
 pub(super) struct Builder {
     appenders: Vec<&'static str>,
     loggers: HashMap<String, (Vec<&'static str>, LevelFilter)>,
@@ -76,44 +90,58 @@ impl Builder {
         self.parse_specs(expression)
     }
 
+    /// Applies a specs expression, failing without applying any spec if one of them is invalid.
+    pub fn try_parse_expression(&mut self, expression: &str) -> Result<&mut Self, LogError> {
+        let specs = Self::specs(expression).collect::<Result<Vec<_>, _>>()?;
+        for (name, log_level) in specs {
+            self.apply_spec(name, log_level);
+        }
+        Ok(self)
+    }
+
     fn parse_specs(&mut self, expression: &str) -> &mut Self {
-        for spec in expression.split(',').map(|x| x.trim()) {
-            if spec.is_empty() {
-                continue;
+        for spec in Self::specs(expression) {
+            match spec {
+                Ok((name, log_level)) => self.apply_spec(name, log_level),
+                Err(err) => println!("Ignoring invalid logging spec '{}'", err),
             }
-            let mut parts = spec.split('=');
-            let (log_level, name) = match (parts.next(), parts.next().map(|x| x.trim()), parts.next()) {
-                (Some(part0), None, None) => {
-                    // if the single argument is a log-level string or number,
-                    // it defines the root level
-                    match part0.parse() {
-                        Ok(lvl) => (lvl, None),
-                        Err(_) => (LevelFilter::max(), Some(part0)),
-                    }
-                }
-                (Some(part0), Some(""), None) => (LevelFilter::max(), Some(part0)),
-                (Some(part0), Some(part1), None) => match part1.parse() {
-                    Ok(lvl) => (lvl, Some(part0)),
-                    _ => {
-                        println!("Ignoring invalid logging spec '{}'", LogError::ParseLoggerSpecError(part1.to_string()));
-                        continue;
-                    }
-                },
-                _ => {
-                    println!("Ignoring invalid logging spec '{}'", LogError::ParseLoggerSpecError(spec.to_string()));
-                    continue;
-                }
-            };
-            match name {
-                Some(name) => {
-                    self.logger(name.to_string(), log_level);
-                }
-                None => {
-                    self.root_level(log_level);
+        }
+        self
+    }
+
+    fn specs(expression: &str) -> impl Iterator<Item = Result<(Option<&str>, LevelFilter), LogError>> {
+        expression.split(',').map(|x| x.trim()).filter(|x| !x.is_empty()).map(Self::parse_spec)
+    }
+
+    fn parse_spec(spec: &str) -> Result<(Option<&str>, LevelFilter), LogError> {
+        let mut parts = spec.split('=');
+        match (parts.next(), parts.next().map(|x| x.trim()), parts.next()) {
+            (Some(part0), None, None) => {
+                // if the single argument is a log-level string or number,
+                // it defines the root level
+                match part0.parse() {
+                    Ok(lvl) => Ok((None, lvl)),
+                    Err(_) => Ok((Some(part0), LevelFilter::max())),
                 }
             }
+            (Some(part0), Some(""), None) => Ok((Some(part0), LevelFilter::max())),
+            (Some(part0), Some(part1), None) => match part1.parse() {
+                Ok(lvl) => Ok((Some(part0), lvl)),
+                _ => Err(LogError::ParseLoggerSpecError(part1.to_string())),
+            },
+            _ => Err(LogError::ParseLoggerSpecError(spec.to_string())),
+        }
+    }
+
+    fn apply_spec(&mut self, name: Option<&str>, log_level: LevelFilter) {
+        match name {
+            Some(name) => {
+                self.logger(name.to_string(), log_level);
+            }
+            None => {
+                self.root_level(log_level);
+            }
         }
-        self
     }
 
     #[allow(dead_code)]
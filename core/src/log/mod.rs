@@ -10,10 +10,16 @@ pub use workflow_log;
 cfg_if::cfg_if! {
     if #[cfg(not(target_arch = "wasm32"))] {
         use consts::*;
+        use std::sync::Mutex;
 
         mod appender;
         mod consts;
+        mod encoder;
         mod logger;
+        pub mod trace_id;
+
+        pub use encoder::LogFormat;
+        pub use logger::LogError;
     }
 }
 
@@ -21,8 +27,20 @@ pub fn set_log_level(level: LevelFilter) {
     workflow_log::set_log_level(level);
 }
 
+/// State of the global logger, kept for adjusting its filters at runtime
 #[cfg(not(target_arch = "wasm32"))]
-pub fn init_logger(log_dir: Option<&str>, filters: &str) {
+struct LoggerState {
+    handle: log4rs::Handle,
+    log_dir: Option<String>,
+    format: LogFormat,
+    builder: logger::Builder,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+static LOGGER_STATE: Mutex<Option<LoggerState>> = Mutex::new(None);
+
+#[cfg(not(target_arch = "wasm32"))]
+fn build_config(log_dir: Option<&str>, format: LogFormat, loggers: &logger::Loggers) -> log4rs::Config {
     use crate::log::appender::AppenderSpec;
     use log4rs::{config::Root, Config};
     use std::iter::once;
@@ -31,16 +49,13 @@ pub fn init_logger(log_dir: Option<&str>, filters: &str) {
     const LOG_FILE_APPENDER: &str = "log_file";
     const ERR_LOG_FILE_APPENDER: &str = "err_log_file";
 
-    let level = LevelFilter::Info;
-    let loggers = logger::Builder::new().root_level(level).parse_env(DEFAULT_LOGGER_ENV).parse_expression(filters).build();
-
-    let mut stdout_appender = AppenderSpec::console(CONSOLE_APPENDER, None);
-    let mut file_appender = log_dir.map(|x| AppenderSpec::roller(LOG_FILE_APPENDER, None, x, LOG_FILE_NAME));
+    let mut stdout_appender = AppenderSpec::console(CONSOLE_APPENDER, None, format);
+    let mut file_appender = log_dir.map(|x| AppenderSpec::roller(LOG_FILE_APPENDER, None, x, LOG_FILE_NAME, format));
     let mut err_file_appender =
-        log_dir.map(|x| AppenderSpec::roller(ERR_LOG_FILE_APPENDER, Some(LevelFilter::Warn), x, ERR_LOG_FILE_NAME));
+        log_dir.map(|x| AppenderSpec::roller(ERR_LOG_FILE_APPENDER, Some(LevelFilter::Warn), x, ERR_LOG_FILE_NAME, format));
     let appenders = once(&mut stdout_appender).chain(&mut file_appender).chain(&mut err_file_appender).map(|x| x.appender());
 
-    let config = Config::builder()
+    Config::builder()
         .appenders(appenders)
         .loggers(loggers.items())
         .build(
@@ -48,30 +63,65 @@ pub fn init_logger(log_dir: Option<&str>, filters: &str) {
                 .appenders(once(&stdout_appender).chain(&file_appender).chain(&err_file_appender).map(|x| x.name))
                 .build(loggers.root_level()),
         )
-        .unwrap();
+        .unwrap()
+}
 
-    let _handle = log4rs::init_config(config).unwrap();
+#[cfg(not(target_arch = "wasm32"))]
+pub fn init_logger(log_dir: Option<&str>, filters: &str) {
+    init_logger_with_format(log_dir, filters, LogFormat::Text)
+}
+
+/// Inits the global logger writing log lines in the given `format`.
+/// The filters of the logger can later be adjusted at runtime with [`set_log_filters`].
+#[cfg(not(target_arch = "wasm32"))]
+pub fn init_logger_with_format(log_dir: Option<&str>, filters: &str, format: LogFormat) {
+    let level = LevelFilter::Info;
+    let mut builder = logger::Builder::new();
+    builder.root_level(level).parse_env(DEFAULT_LOGGER_ENV).parse_expression(filters);
+    let loggers = builder.clone().build();
+
+    let handle = log4rs::init_config(build_config(log_dir, format, &loggers)).unwrap();
+    LOGGER_STATE.lock().unwrap().replace(LoggerState { handle, log_dir: log_dir.map(|x| x.to_string()), format, builder });
 
     set_log_level(level);
 }
 
+/// Adjusts the filters of the global logger at runtime.
+///
+/// `filters` follows the syntax of the filters the logger was initialized with and is applied on top of
+/// them, so `kaspa_p2p_flows=debug` raises the verbosity of a single module while `warn` sets the root level.
+/// Returns the resulting filters, or an error (without applying any filter) if one of them is invalid.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn set_log_filters(filters: &str) -> Result<String, LogError> {
+    let mut state = LOGGER_STATE.lock().unwrap();
+    let state = state.as_mut().ok_or(LogError::LoggerNotInitialized)?;
+
+    let mut builder = state.builder.clone();
+    builder.try_parse_expression(filters)?;
+    let loggers = builder.clone().build();
+    state.handle.set_config(build_config(state.log_dir.as_deref(), state.format, &loggers));
+    state.builder = builder;
+
+    set_log_level(loggers.root_level());
+    Ok(loggers.expression())
+}
+
+/// Returns the current filters of the global logger, if initialized by this module
+#[cfg(not(target_arch = "wasm32"))]
+pub fn log_filters() -> Option<String> {
+    LOGGER_STATE.lock().unwrap().as_ref().map(|state| state.builder.clone().build().expression())
+}
+
 /// Tries to init the global logger, but does not panic if it was already setup.
 /// Should be used for tests.
 #[cfg(not(target_arch = "wasm32"))]
 pub fn try_init_logger(filters: &str) {
-    use crate::log::appender::AppenderSpec;
-    use log4rs::{config::Root, Config};
-
-    const CONSOLE_APPENDER: &str = "stdout";
-
-    let loggers = logger::Builder::new().root_level(LevelFilter::Info).parse_env(DEFAULT_LOGGER_ENV).parse_expression(filters).build();
-    let mut stdout_appender = AppenderSpec::console(CONSOLE_APPENDER, None);
-    let config = Config::builder()
-        .appender(stdout_appender.appender())
-        .loggers(loggers.items())
-        .build(Root::builder().appender(CONSOLE_APPENDER).build(loggers.root_level()))
-        .unwrap();
-    let _ = log4rs::init_config(config);
+    let mut builder = logger::Builder::new();
+    builder.root_level(LevelFilter::Info).parse_env(DEFAULT_LOGGER_ENV).parse_expression(filters);
+    let loggers = builder.clone().build();
+    if let Ok(handle) = log4rs::init_config(build_config(None, LogFormat::Text, &loggers)) {
+        LOGGER_STATE.lock().unwrap().replace(LoggerState { handle, log_dir: None, format: LogFormat::Text, builder });
+    }
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -163,3 +213,65 @@ macro_rules! error {
         log::error!($($t)*);
     )
 }
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    const TARGET: &str = "kaspa_core::log::tests";
+
+    fn logged_lines(log_dir: &std::path::Path) -> Vec<Value> {
+        let content = std::fs::read_to_string(log_dir.join(LOG_FILE_NAME)).unwrap();
+        content.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_runtime_log_filters() {
+        // This is the only test initializing the global logger of this crate
+        let log_dir = tempfile::tempdir().unwrap();
+        init_logger_with_format(log_dir.path().to_str(), "info", LogFormat::Json);
+
+        log::debug!(target: TARGET, step = 1; "debug before raising the level");
+        log::info!(target: TARGET, step = 1; "info before raising the level");
+
+        let filters = set_log_filters(&format!("{TARGET}=debug")).unwrap();
+        assert_eq!(filters, format!("info,{TARGET}=debug"));
+        assert_eq!(log_filters(), Some(filters));
+        log::debug!(target: TARGET, step = 2, hash = "8a1bd3e6"; "debug after raising the level");
+        log::debug!(target: "kaspa_core::other", "debug of another target");
+
+        // An invalid spec leaves the filters untouched
+        assert!(set_log_filters(&format!("warn,{TARGET}=loud")).is_err());
+        log::debug!(target: TARGET, step = 3; "debug after an invalid update");
+
+        assert_eq!(set_log_filters(&format!("{TARGET}=warn")).unwrap(), format!("info,{TARGET}=warn"));
+        log::info!(target: TARGET, step = 4; "info after lowering the level");
+        log::warn!(target: TARGET, step = 4; "warn after lowering the level");
+
+        let lines = logged_lines(log_dir.path());
+        // Every line follows the schema
+        for line in lines.iter() {
+            let object = line.as_object().unwrap();
+            for field in ["ts", "level", "target", "message"] {
+                assert!(object[field].is_string(), "missing {field} in {line}");
+            }
+        }
+        // Only the records allowed by the filters in effect at the time of logging were emitted
+        let ours = lines.iter().filter(|x| x["target"] == TARGET).collect::<Vec<_>>();
+        let messages = ours.iter().map(|x| x["message"].as_str().unwrap()).collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                "info before raising the level",
+                "debug after raising the level",
+                "debug after an invalid update",
+                "warn after lowering the level"
+            ]
+        );
+        assert_eq!(ours.iter().map(|x| x["step"].as_u64().unwrap()).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!(ours[1]["level"], "DEBUG");
+        assert_eq!(ours[1]["hash"], "8a1bd3e6");
+        assert!(!lines.iter().any(|x| x["target"] == "kaspa_core::other"));
+    }
+}
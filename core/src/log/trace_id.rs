@@ -0,0 +1,214 @@
+//!
+//! Trace ids correlating the log lines emitted while serving a single request, such as an RPC call.
+//!
+//! A trace id is bound to a future with [`scope`] and is then available through [`current`] to any code
+//! polled by that future. Blocking work spawned on another thread carries the trace id over with
+//! [`sync_scope`]. The log encoders include the current trace id, if any, in every log line.
+//!
+
+use std::{
+    cell::RefCell,
+    collections::hash_map::RandomState,
+    fmt::Display,
+    future::Future,
+    hash::{BuildHasher, Hasher},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TraceId(String);
+
+impl TraceId {
+    /// Maximum length of a client provided trace id
+    pub const MAX_LEN: usize = 64;
+
+    /// Generates a new random trace id
+    pub fn generate() -> Self {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+        Self(format!("{:016x}", hasher.finish()))
+    }
+
+    /// Returns a trace id provided by a client, or `None` if it is empty, longer than [`Self::MAX_LEN`] or
+    /// holds characters other than ASCII alphanumerics, `-`, `_`, `.` and `:`
+    pub fn from_client(id: &str) -> Option<Self> {
+        let is_valid = !id.is_empty()
+            && id.len() <= Self::MAX_LEN
+            && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'));
+        is_valid.then(|| Self(id.to_string()))
+    }
+
+    /// Returns the trace id provided by a client if valid, or a newly generated one otherwise
+    pub fn from_client_or_generate(id: &str) -> Self {
+        Self::from_client(id).unwrap_or_else(Self::generate)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for TraceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+tokio::task_local! {
+    static TASK_TRACE_ID: TraceId;
+}
+
+thread_local! {
+    static THREAD_TRACE_ID: RefCell<Option<TraceId>> = const { RefCell::new(None) };
+}
+
+/// Returns the trace id of the current task or, outside of any task scope, of the current thread
+pub fn current() -> Option<TraceId> {
+    TASK_TRACE_ID.try_with(|trace_id| trace_id.clone()).ok().or_else(|| THREAD_TRACE_ID.with(|trace_id| trace_id.borrow().clone()))
+}
+
+/// Runs `future` with `trace_id` as the current trace id
+pub async fn scope<F: Future>(trace_id: TraceId, future: F) -> F::Output {
+    TASK_TRACE_ID.scope(trace_id, future).await
+}
+
+/// Runs `f` on the current thread with `trace_id` as the current trace id, typically used to carry the
+/// trace id of a task over to a blocking thread
+pub fn sync_scope<R>(trace_id: Option<TraceId>, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<TraceId>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            THREAD_TRACE_ID.with(|trace_id| *trace_id.borrow_mut() = self.0.take());
+        }
+    }
+
+    let _restore = Restore(THREAD_TRACE_ID.with(|current| current.replace(trace_id)));
+    f()
+}
+
+/// Threshold in milliseconds above which calls are logged as slow, `0` disabling the log
+static SLOW_CALL_THRESHOLD: AtomicU64 = AtomicU64::new(0);
+
+/// Sets the latency above which the calls run with [`timed_scope`] are logged as slow, `None` disabling
+/// the log
+pub fn set_slow_call_threshold(threshold: Option<Duration>) {
+    let millis = threshold.map_or(0, |threshold| (threshold.as_millis() as u64).max(1));
+    SLOW_CALL_THRESHOLD.store(millis, Ordering::Relaxed);
+}
+
+pub fn slow_call_threshold() -> Option<Duration> {
+    match SLOW_CALL_THRESHOLD.load(Ordering::Relaxed) {
+        0 => None,
+        millis => Some(Duration::from_millis(millis)),
+    }
+}
+
+/// Runs the call `future` within the scope of `trace_id`, logging it as slow along with its trace id if
+/// it exceeds the slow call threshold
+pub async fn timed_scope<F: Future>(call: impl Display, trace_id: TraceId, future: F) -> F::Output {
+    scope(trace_id.clone(), async move {
+        let start = Instant::now();
+        let output = future.await;
+        if let Some(threshold) = slow_call_threshold() {
+            let elapsed = start.elapsed();
+            if elapsed >= threshold {
+                let elapsed_ms = elapsed.as_millis() as u64;
+                log::warn!(trace_id:% = trace_id, elapsed_ms = elapsed_ms; "Slow call {call} took {elapsed_ms} ms (trace id {trace_id})");
+            }
+        }
+        output
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::{kv::Key, Log, Metadata, Record};
+    use std::sync::Mutex;
+
+    /// Keeps the message and `trace_id` key-value of the logged records
+    struct CaptureLogger(Mutex<Vec<(String, Option<String>)>>);
+
+    impl Log for CaptureLogger {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            let trace_id = record.key_values().get(Key::from_str("trace_id")).map(|value| value.to_string());
+            self.0.lock().unwrap().push((record.args().to_string(), trace_id));
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CaptureLogger = CaptureLogger(Mutex::new(Vec::new()));
+
+    #[test]
+    fn test_client_trace_ids() {
+        assert_eq!(TraceId::from_client("wallet-42:get_info.1_a").unwrap().as_str(), "wallet-42:get_info.1_a");
+        assert!(TraceId::from_client("").is_none());
+        assert!(TraceId::from_client("with space").is_none());
+        assert!(TraceId::from_client("line\nbreak").is_none());
+        assert!(TraceId::from_client(&"a".repeat(TraceId::MAX_LEN)).is_some());
+        assert!(TraceId::from_client(&"a".repeat(TraceId::MAX_LEN + 1)).is_none());
+        assert_eq!(TraceId::from_client_or_generate("abc").as_str(), "abc");
+        assert_eq!(TraceId::from_client_or_generate("").as_str().len(), 16);
+        assert_ne!(TraceId::generate(), TraceId::generate());
+    }
+
+    #[tokio::test]
+    async fn test_trace_id_propagation() {
+        assert_eq!(current(), None);
+        let trace_id = TraceId::generate();
+        let inner = scope(trace_id.clone(), async {
+            let in_task = current();
+            // A blocking thread only sees the trace id when carried over
+            let carried = current();
+            let (carried, not_carried) = tokio::task::spawn_blocking(move || (sync_scope(carried, current), current())).await.unwrap();
+            (in_task, carried, not_carried)
+        })
+        .await;
+        assert_eq!(inner, (Some(trace_id.clone()), Some(trace_id.clone()), None));
+        assert_eq!(current(), None);
+
+        // The thread trace id is restored when leaving a sync scope
+        let other = TraceId::generate();
+        sync_scope(Some(trace_id.clone()), || {
+            sync_scope(Some(other.clone()), || assert_eq!(current(), Some(other.clone())));
+            assert_eq!(current(), Some(trace_id.clone()));
+        });
+        assert_eq!(current(), None);
+    }
+
+    #[tokio::test]
+    async fn test_slow_call_log() {
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+
+        set_slow_call_threshold(Some(Duration::from_millis(20)));
+        let fast = TraceId::from_client("fast-call").unwrap();
+        timed_scope("fast", fast, async {}).await;
+        let slow = TraceId::from_client("slow-call").unwrap();
+        let output = timed_scope("slow", slow.clone(), async {
+            // The handler is artificially slowed down
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            log::info!("handling the slow call");
+            current()
+        })
+        .await;
+        assert_eq!(output, Some(slow.clone()));
+        set_slow_call_threshold(None);
+        timed_scope("slow with log disabled", TraceId::generate(), tokio::time::sleep(Duration::from_millis(30))).await;
+
+        let records = LOGGER.0.lock().unwrap();
+        let slow_calls = records.iter().filter(|(message, _)| message.starts_with("Slow call")).collect::<Vec<_>>();
+        assert_eq!(slow_calls.len(), 1, "{records:?}");
+        assert!(slow_calls[0].0.starts_with("Slow call slow took") && slow_calls[0].0.contains("slow-call"), "{}", slow_calls[0].0);
+        assert_eq!(slow_calls[0].1.as_deref(), Some("slow-call"));
+    }
+}
@@ -1,8 +1,101 @@
-use kaspa_core::error;
-use std::{panic, process, thread};
+use kaspa_core::{error, info, warn};
+use std::{
+    panic, process,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Time given to the emergency actions to complete before the process exits abnormally
+pub const EMERGENCY_ACTIONS_TIMEOUT: Duration = Duration::from_secs(5);
+
+type EmergencyAction = Arc<dyn Fn() -> Result<(), String> + Send + Sync>;
+
+static EMERGENCY_ACTIONS: Mutex<Vec<(String, EmergencyAction)>> = Mutex::new(Vec::new());
+static EMERGENCY_ACTIONS_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Registers a best-effort action, such as flushing a database, to be run by [`run_emergency_actions`] when the process
+/// is about to exit abnormally, i.e. on panic or on a forced termination. An action replaces any previous one registered
+/// under the same name.
+pub fn register_emergency_action(name: impl Into<String>, action: impl Fn() -> Result<(), String> + Send + Sync + 'static) {
+    let name = name.into();
+    let mut actions = EMERGENCY_ACTIONS.lock().unwrap_or_else(|err| err.into_inner());
+    actions.retain(|(registered, _)| *registered != name);
+    actions.push((name, Arc::new(action)));
+}
+
+/// Runs the registered emergency actions concurrently and waits up to `timeout` for them to complete, logging the
+/// outcome of each. Returns `true` if all the actions completed successfully.
+///
+/// Actions still running at the deadline are left behind, the caller being expected to exit the process. Calls
+/// made while the actions are already running, e.g. by a panic within an action, return `false` immediately.
+pub fn run_emergency_actions(timeout: Duration) -> bool {
+    if EMERGENCY_ACTIONS_RUNNING.swap(true, Ordering::SeqCst) {
+        return false;
+    }
+    let actions = EMERGENCY_ACTIONS.lock().unwrap_or_else(|err| err.into_inner()).clone();
+    let mut pending = actions.iter().map(|(name, _)| name.clone()).collect::<Vec<_>>();
+    let (sender, receiver) = mpsc::channel();
+    for (name, action) in actions {
+        let sender = sender.clone();
+        let spawned = thread::Builder::new().name("emergency-action".to_string()).spawn(move || {
+            let result = action();
+            let _ = sender.send((name, result));
+        });
+        if let Err(err) = spawned {
+            error!("Emergency action could not be started: {}", err);
+        }
+    }
+    drop(sender);
+
+    let deadline = Instant::now() + timeout;
+    let mut succeeded = true;
+    while !pending.is_empty() {
+        match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok((name, result)) => {
+                pending.retain(|pending_name| *pending_name != name);
+                match result {
+                    Ok(()) => info!("Emergency action {} completed", name),
+                    Err(err) => {
+                        error!("Emergency action {} failed: {}", name, err);
+                        succeeded = false;
+                    }
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                error!("Emergency actions did not complete within {:?}, still running: {}", timeout, pending.join(", "));
+                succeeded = false;
+                break;
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                error!("Emergency actions were interrupted, not completed: {}", pending.join(", "));
+                succeeded = false;
+                break;
+            }
+        }
+    }
+    if succeeded {
+        info!("Emergency flush completed");
+    } else {
+        warn!("Emergency flush did not complete, recent writes may be lost");
+    }
+    EMERGENCY_ACTIONS_RUNNING.store(false, Ordering::SeqCst);
+    succeeded
+}
 
 /// Configures the panic hook to exit the program on every panic
 pub fn configure_panic() {
+    configure_panic_with_exit(|| {
+        println!("Exiting...");
+        process::exit(1);
+    })
+}
+
+/// Configures the panic hook to run the emergency actions and then call `exit` on every panic
+pub fn configure_panic_with_exit(exit: impl Fn() + Send + Sync + 'static) {
     let default_hook = panic::take_hook();
     panic::set_hook(Box::new(move |panic_info| {
         // Get the panic location details
@@ -25,7 +118,8 @@ pub fn configure_panic() {
         error!("thread '{}' panicked at {}:{}:{}: {}", thread_name, file, line, column, message);
         // Invoke the default hook as well, since it might include additional info such as the full backtrace
         default_hook(panic_info);
-        println!("Exiting...");
-        process::exit(1);
+        // Attempt to make recent writes durable before exiting
+        run_emergency_actions(EMERGENCY_ACTIONS_TIMEOUT);
+        exit();
     }));
 }
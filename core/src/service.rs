@@ -7,3 +7,11 @@ pub trait Service: CastFromSync {
     fn start(self: Arc<Self>, core: Arc<Core>) -> Vec<JoinHandle<()>>;
     fn stop(self: Arc<Self>);
 }
+
+/// Qualifies the identity of a service with the namespace of the node it belongs to, as `namespace/ident`
+pub fn qualified_ident(namespace: Option<&str>, ident: &str) -> String {
+    match namespace {
+        Some(namespace) => format!("{namespace}/{ident}"),
+        None => ident.to_string(),
+    }
+}
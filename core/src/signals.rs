@@ -22,6 +22,7 @@ impl<T: Shutdown + Send + Sync> Signals<T> {
             let v = signals.iterations.fetch_add(1, Ordering::SeqCst);
             if v > 1 {
                 println!("^SIGTERM - halting");
+                crate::panic::run_emergency_actions(crate::panic::EMERGENCY_ACTIONS_TIMEOUT);
                 std::process::exit(1);
             }
 
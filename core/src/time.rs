@@ -1,4 +1,11 @@
-use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
 /// Returns the number of milliseconds since UNIX EPOCH
 #[inline]
@@ -6,6 +13,189 @@ pub fn unix_now() -> u64 {
     SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
 }
 
+/// Returns the number of milliseconds since UNIX EPOCH according to the process-wide [`HybridClock`],
+/// which unlike [`unix_now`] does not go backwards when the system clock is stepped back by a small amount
+pub fn hybrid_unix_now() -> u64 {
+    static CLOCK: OnceLock<HybridClock> = OnceLock::new();
+    CLOCK.get_or_init(HybridClock::default).unix_now()
+}
+
+/// Interval in milliseconds after which the [`HybridClock`] is re-anchored to the wall clock
+pub const CLOCK_REANCHOR_INTERVAL_MILLIS: u64 = 1_000;
+
+/// Largest backward step of the wall clock in milliseconds absorbed by slewing the [`HybridClock`].
+/// It is kept well below the timestamp deviation tolerance of the block headers, so that the block
+/// template timestamps never run ahead of real time by more than the peers accept.
+pub const CLOCK_MAX_SLEW_MILLIS: u64 = 30_000;
+
+/// While slewing, the [`HybridClock`] advances at `1 - 1 / CLOCK_SLEW_RATE_DIVISOR` of the monotonic rate
+const CLOCK_SLEW_RATE_DIVISOR: u64 = 2;
+
+/// Source of the wall and monotonic times driving a [`HybridClock`]
+pub trait TimeSource: Send + Sync {
+    /// Milliseconds since UNIX EPOCH according to the system clock, which may step in both directions
+    fn wall_millis(&self) -> u64;
+
+    /// Milliseconds elapsed since a fixed origin, never going backwards
+    fn monotonic_millis(&self) -> u64;
+}
+
+pub struct SystemTimeSource {
+    origin: Instant,
+}
+
+impl Default for SystemTimeSource {
+    fn default() -> Self {
+        Self { origin: Instant::now() }
+    }
+}
+
+impl TimeSource for SystemTimeSource {
+    fn wall_millis(&self) -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_millis() as u64).unwrap_or_default()
+    }
+
+    fn monotonic_millis(&self) -> u64 {
+        self.origin.elapsed().as_millis() as u64
+    }
+}
+
+struct ClockState {
+    /// Monotonic time of the last anchoring
+    anchor_monotonic: u64,
+    /// Clock time of the last anchoring
+    anchor_time: u64,
+    /// Milliseconds by which the clock is ahead of the wall clock, absorbed by slowing the clock down
+    slew: u64,
+}
+
+/// Clock reporting the wall time, anchored to the wall clock at creation and advanced by monotonic deltas.
+///
+/// The clock is re-anchored to the wall clock every [`CLOCK_REANCHOR_INTERVAL_MILLIS`]. Forward steps of
+/// the wall clock are followed at once, while backward steps up to [`CLOCK_MAX_SLEW_MILLIS`] are absorbed
+/// by slowing the clock down until it meets the wall clock again, so that the clock never goes backwards.
+/// Larger backward steps are considered as a reset of the system clock and followed at once.
+pub struct HybridClock<S: TimeSource = SystemTimeSource> {
+    source: S,
+    state: Mutex<ClockState>,
+}
+
+impl Default for HybridClock {
+    fn default() -> Self {
+        Self::new(SystemTimeSource::default())
+    }
+}
+
+impl<S: TimeSource> HybridClock<S> {
+    pub fn new(source: S) -> Self {
+        let state = ClockState { anchor_monotonic: source.monotonic_millis(), anchor_time: source.wall_millis(), slew: 0 };
+        Self { source, state: Mutex::new(state) }
+    }
+
+    /// Returns the number of milliseconds since UNIX EPOCH
+    pub fn unix_now(&self) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        let monotonic = self.source.monotonic_millis();
+        let elapsed = monotonic.saturating_sub(state.anchor_monotonic);
+        let now = state.anchor_time + elapsed - u64::min(state.slew, elapsed / CLOCK_SLEW_RATE_DIVISOR);
+        if elapsed < CLOCK_REANCHOR_INTERVAL_MILLIS {
+            return now;
+        }
+
+        let wall = self.source.wall_millis();
+        let now = if wall >= now {
+            // Either the wall clock stepped forward or the slewing is over
+            state.slew = 0;
+            wall
+        } else if now - wall <= CLOCK_MAX_SLEW_MILLIS {
+            state.slew = now - wall;
+            now
+        } else {
+            kaspa_core::warn!(
+                "The system clock went back by {} ms, more than the {} ms absorbed by slewing, resetting the clock",
+                now - wall,
+                CLOCK_MAX_SLEW_MILLIS
+            );
+            state.slew = 0;
+            wall
+        };
+        state.anchor_monotonic = monotonic;
+        state.anchor_time = now;
+        now
+    }
+}
+
+/// Clock read by the components depending on the wall time, such as the header timestamp rules, the block
+/// template timestamps and the mempool expiration scans, allowing tests to run them on simulated time
+pub trait Clock: Debug + Send + Sync {
+    /// Returns the number of milliseconds since UNIX EPOCH
+    fn unix_now(&self) -> u64;
+}
+
+pub type DynClock = Arc<dyn Clock>;
+
+/// The wall clock, as reported by [`hybrid_unix_now`]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl SystemClock {
+    pub fn new_dyn() -> DynClock {
+        Arc::new(Self)
+    }
+}
+
+impl Clock for SystemClock {
+    fn unix_now(&self) -> u64 {
+        hybrid_unix_now()
+    }
+}
+
+/// Clock which only advances when told to, for deterministic tests of time dependent behavior.
+///
+/// Clones share the same time, so that a test can keep a handle on the clock injected into the tested
+/// components and advance it programmatically.
+#[derive(Clone, Debug, Default)]
+pub struct SimulatedClock {
+    now: Arc<AtomicU64>,
+}
+
+impl SimulatedClock {
+    /// Creates a clock set to `unix_now` milliseconds since UNIX EPOCH
+    pub fn new(unix_now: u64) -> Self {
+        Self { now: Arc::new(AtomicU64::new(unix_now)) }
+    }
+
+    /// Creates a clock set to the current wall time
+    pub fn from_system_time() -> Self {
+        Self::new(unix_now())
+    }
+
+    pub fn as_dyn(&self) -> DynClock {
+        Arc::new(self.clone())
+    }
+
+    /// Advances the clock by `duration` and returns the new time
+    pub fn advance(&self, duration: Duration) -> u64 {
+        self.advance_millis(duration.as_millis() as u64)
+    }
+
+    /// Advances the clock by `millis` milliseconds and returns the new time
+    pub fn advance_millis(&self, millis: u64) -> u64 {
+        self.now.fetch_add(millis, Ordering::SeqCst) + millis
+    }
+
+    /// Sets the clock to `unix_now` milliseconds since UNIX EPOCH, possibly moving it backwards
+    pub fn set(&self, unix_now: u64) {
+        self.now.store(unix_now, Ordering::SeqCst);
+    }
+}
+
+impl Clock for SimulatedClock {
+    fn unix_now(&self) -> u64 {
+        self.now.load(Ordering::SeqCst)
+    }
+}
+
 /// Stopwatch which reports on drop if the timed operation passed the threshold `TR` in milliseconds
 pub struct Stopwatch<const TR: u64 = 1000> {
     name: &'static str,
@@ -36,3 +226,122 @@ impl<const TR: u64> Drop for Stopwatch<TR> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: u64 = 1_700_000_000_000;
+
+    /// Time source whose wall clock can be stepped independently of its monotonic clock
+    #[derive(Clone, Default)]
+    struct MockTimeSource {
+        wall: Arc<AtomicU64>,
+        monotonic: Arc<AtomicU64>,
+    }
+
+    impl MockTimeSource {
+        fn new(wall: u64) -> Self {
+            let source = Self::default();
+            source.wall.store(wall, Ordering::SeqCst);
+            source
+        }
+
+        fn advance(&self, millis: u64) {
+            self.wall.fetch_add(millis, Ordering::SeqCst);
+            self.monotonic.fetch_add(millis, Ordering::SeqCst);
+        }
+
+        fn step_back(&self, millis: u64) {
+            self.wall.fetch_sub(millis, Ordering::SeqCst);
+        }
+
+        fn wall(&self) -> u64 {
+            self.wall.load(Ordering::SeqCst)
+        }
+    }
+
+    impl TimeSource for MockTimeSource {
+        fn wall_millis(&self) -> u64 {
+            self.wall.load(Ordering::SeqCst)
+        }
+
+        fn monotonic_millis(&self) -> u64 {
+            self.monotonic.load(Ordering::SeqCst)
+        }
+    }
+
+    #[test]
+    fn test_hybrid_clock_tracks_wall_time() {
+        let source = MockTimeSource::new(START);
+        let clock = HybridClock::new(source.clone());
+        assert_eq!(clock.unix_now(), START);
+        for _ in 0..50 {
+            source.advance(300);
+            assert_eq!(clock.unix_now(), source.wall());
+        }
+
+        // A forward step is followed at the next anchoring
+        source.wall.fetch_add(60_000, Ordering::SeqCst);
+        source.advance(CLOCK_REANCHOR_INTERVAL_MILLIS);
+        assert_eq!(clock.unix_now(), source.wall());
+    }
+
+    #[test]
+    fn test_hybrid_clock_slews_backward_steps() {
+        let source = MockTimeSource::new(START);
+        let clock = HybridClock::new(source.clone());
+        source.advance(5_000);
+        let mut last = clock.unix_now();
+
+        const STEP: u64 = 10_000;
+        source.step_back(STEP);
+        let mut converged = false;
+        for _ in 0..1_000 {
+            source.advance(100);
+            let now = clock.unix_now();
+            // Derived values never go backwards, nor run ahead of the wall clock by more than the step
+            assert!(now >= last);
+            assert!(now <= source.wall() + STEP);
+            last = now;
+            if now == source.wall() {
+                converged = true;
+                break;
+            }
+        }
+        assert!(converged, "the clock did not converge back to the wall clock");
+
+        // Once converged, the clock tracks the wall clock again
+        source.advance(CLOCK_REANCHOR_INTERVAL_MILLIS);
+        assert_eq!(clock.unix_now(), source.wall());
+    }
+
+    #[test]
+    fn test_hybrid_clock_resets_on_large_backward_steps() {
+        let source = MockTimeSource::new(START);
+        let clock = HybridClock::new(source.clone());
+        source.advance(5_000);
+        let before = clock.unix_now();
+
+        source.step_back(CLOCK_MAX_SLEW_MILLIS + 60_000);
+        // Within the anchoring interval the clock keeps advancing monotonically
+        source.advance(CLOCK_REANCHOR_INTERVAL_MILLIS - 1);
+        assert_eq!(clock.unix_now(), before + CLOCK_REANCHOR_INTERVAL_MILLIS - 1);
+        // The system clock reset is then followed in order to keep tracking real time
+        source.advance(1);
+        assert_eq!(clock.unix_now(), source.wall());
+    }
+
+    #[test]
+    fn test_simulated_clock() {
+        let clock = SimulatedClock::new(START);
+        let injected = clock.as_dyn();
+        assert_eq!(injected.unix_now(), START);
+        assert_eq!(clock.advance(Duration::from_secs(86_400)), START + 86_400_000);
+        assert_eq!(clock.advance_millis(1), START + 86_400_001);
+        // The clones share the time of the clock
+        assert_eq!(injected.unix_now(), START + 86_400_001);
+        clock.set(START);
+        assert_eq!(injected.unix_now(), START);
+    }
+}
@@ -30,6 +30,8 @@ blake2b_hasher! {
     struct MuHashElementHash => b"MuHashElement",
     struct MuHashFinalizeHash => b"MuHashFinalize",
     struct PersonalMessageSigningHash => b"PersonalMessageSigningHash",
+    struct SignedMessageHash => b"KaspaSignedMessage:",
+    struct ParamsDigestHash => b"ParamsDigest",
 }
 
 sha256_hasher! {
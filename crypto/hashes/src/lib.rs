@@ -89,6 +89,14 @@ impl Hash {
     pub fn from_u64_word(word: u64) -> Self {
         Self::from_le_u64([0, 0, 0, word])
     }
+
+    /// Parses a hash from its hex representation, in any letter case and optionally prefixed with `0x`.
+    /// Unlike [`FromStr`], which expects the exact form emitted by [`Display`] (lowercase without prefix),
+    /// this accepts the forms commonly supplied by external callers.
+    pub fn from_hex_lenient(hex_str: &str) -> Result<Self, faster_hex::Error> {
+        let hex_str = hex_str.strip_prefix("0x").or_else(|| hex_str.strip_prefix("0X")).unwrap_or(hex_str);
+        Self::from_str(hex_str)
+    }
 }
 
 // Override the default Hash implementation, to: A. improve perf a bit (siphash works over u64s), B. allow a hasher to just take the first u64.
@@ -162,26 +170,48 @@ impl ToHex for Hash {
     }
 }
 
+/// Used by the human readable deserialization of hashes, hence accepting any letter case and a `0x` prefix
 impl FromHex for Hash {
     type Error = faster_hex::Error;
     fn from_hex(hex_str: &str) -> Result<Self, Self::Error> {
-        Self::from_str(hex_str)
+        Self::from_hex_lenient(hex_str)
     }
 }
 
 impl MemSizeEstimator for Hash {}
 
+#[wasm_bindgen]
+extern "C" {
+    /// A hash or its hex representation, in any letter case and optionally prefixed with `0x`
+    #[wasm_bindgen(typescript_type = "Hash | HexString")]
+    pub type HashT;
+}
+
 #[wasm_bindgen]
 impl Hash {
+    /// Parses a hash from its hex representation, in any letter case and optionally prefixed with `0x`
     #[wasm_bindgen(constructor)]
-    pub fn constructor(hex_str: &str) -> Self {
-        Hash::from_str(hex_str).expect("invalid hash value")
+    pub fn constructor(hex_str: &str) -> Result<Hash, JsError> {
+        Hash::from_hex_lenient(hex_str).map_err(|err| JsError::new(&format!("invalid hash `{hex_str}`: {err:?}")))
+    }
+
+    /// Parses a hash from its hex representation, in any letter case and optionally prefixed with `0x`
+    #[wasm_bindgen(js_name = fromHex)]
+    pub fn js_from_hex(hex_str: &str) -> Result<Hash, JsError> {
+        Hash::from_hex_lenient(hex_str).map_err(|err| JsError::new(&format!("invalid hash `{hex_str}`: {err:?}")))
     }
 
+    /// Returns the lowercase hex representation of the hash, without prefix
     #[wasm_bindgen(js_name = toString)]
     pub fn js_to_string(&self) -> String {
         self.to_string()
     }
+
+    /// Returns `true` if `other` is the same hash, `other` being either a `Hash` or its hex representation
+    #[wasm_bindgen(js_name = equals)]
+    pub fn js_equals(&self, other: &HashT) -> bool {
+        Hash::try_owned_from(other).is_ok_and(|other| other == *self)
+    }
 }
 
 type TryFromError = workflow_wasm::error::Error;
@@ -189,6 +219,10 @@ impl TryCastFromJs for Hash {
     type Error = TryFromError;
     fn try_cast_from(value: impl AsRef<JsValue>) -> Result<Cast<Self>, Self::Error> {
         Self::resolve(&value, || {
+            if let Some(hex_str) = value.as_ref().as_string() {
+                return Hash::from_hex_lenient(&hex_str)
+                    .map_err(|err| TryFromError::Custom(format!("invalid hash `{hex_str}`: {err:?}")));
+            }
             let bytes = value.as_ref().try_as_vec_u8()?;
             Ok(Hash(
                 <[u8; HASH_SIZE]>::try_from(bytes)
@@ -203,6 +237,7 @@ pub const ZERO_HASH: Hash = Hash([0; HASH_SIZE]);
 #[cfg(test)]
 mod tests {
     use super::Hash;
+    use kaspa_utils::hex::FromHex;
     use std::str::FromStr;
 
     #[test]
@@ -222,4 +257,38 @@ mod tests {
         assert!(matches!(dbg!(Hash::from_str(odd_str)), Err(faster_hex::Error::InvalidLength(len)) if len == 64));
         assert!(matches!(dbg!(Hash::from_str(short_str)), Err(faster_hex::Error::InvalidLength(len)) if len == 64));
     }
+
+    #[test]
+    fn test_hash_from_hex_lenient() {
+        let hash_str = "8e40af02265360d59f4ecf9ae9ebf8f00a3118408f5a9cdcbcc9c0f93642f3af";
+        let hash = Hash::from_str(hash_str).unwrap();
+        for input in [
+            hash_str.to_string(),
+            hash_str.to_uppercase(),
+            "8E40af02265360D59f4ecf9ae9ebf8f00a3118408f5a9cdcbcc9c0f93642F3aF".to_string(),
+            format!("0x{hash_str}"),
+            format!("0X{}", hash_str.to_uppercase()),
+        ] {
+            let parsed = Hash::from_hex_lenient(&input).unwrap();
+            assert_eq!(parsed, hash);
+            // Hashes are always emitted in lowercase without prefix
+            assert_eq!(parsed.to_string(), hash_str);
+            assert_eq!(<Hash as FromHex>::from_hex(&input).unwrap(), hash);
+        }
+
+        // The length is validated after removing the prefix
+        assert!(Hash::from_hex_lenient(&format!("0x{}", &hash_str[2..])).is_err());
+        assert!(Hash::from_hex_lenient(&format!("0x{hash_str}00")).is_err());
+        assert!(Hash::from_hex_lenient(&hash_str[1..]).is_err());
+        assert!(Hash::from_hex_lenient("0x").is_err());
+        assert!(Hash::from_hex_lenient(&format!("00{hash_str}")).is_err());
+        assert!(Hash::from_hex_lenient(&hash_str.replace('e', "g")).is_err());
+        // The prefix is accepted once only, and only at the start
+        assert!(Hash::from_hex_lenient(&format!("0x0x{hash_str}")).is_err());
+
+        // Deserialization from human readable formats goes through `FromHex`
+        let json = format!("\"0x{}\"", hash_str.to_uppercase());
+        assert_eq!(serde_json::from_str::<Hash>(&json).unwrap(), hash);
+        assert_eq!(serde_json::to_string(&hash).unwrap(), format!("\"{hash_str}\""));
+    }
 }
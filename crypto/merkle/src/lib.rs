@@ -22,8 +22,72 @@ pub fn calc_merkle_root(hashes: impl ExactSizeIterator<Item = Hash>) -> Hash {
     merkles.last().unwrap().unwrap()
 }
 
+/// Returns the authentication path of the leaf at `index` in the tree built by [`calc_merkle_root`], i.e. the
+/// sibling hashes from the leaf level up to (but excluding) the root, or `None` if `index` is out of range.
+pub fn calc_merkle_path(hashes: impl ExactSizeIterator<Item = Hash>, mut index: usize) -> Option<Vec<Hash>> {
+    if index >= hashes.len() {
+        return None;
+    }
+    let mut level = hashes.collect::<Vec<_>>();
+    let mut path = Vec::new();
+    // Missing right nodes are hashed as ZERO_HASH, same as in `calc_merkle_root`. Since leaves are filled from
+    // the left, nodes of the path to an existing leaf always have an existing left sibling.
+    while level.len() > 1 {
+        path.push(level.get(index ^ 1).copied().unwrap_or(ZERO_HASH));
+        level = level.chunks(2).map(|pair| merkle_hash(pair[0], pair.get(1).copied().unwrap_or(ZERO_HASH))).collect();
+        index /= 2;
+    }
+    Some(path)
+}
+
+/// Computes the merkle root committing to `leaf` at `index` given its authentication `path`
+/// (see [`calc_merkle_path`]), or `None` if `index` does not fit within a tree of the path depth.
+pub fn calc_merkle_root_from_path(leaf: Hash, index: usize, path: &[Hash]) -> Option<Hash> {
+    if index.checked_shr(path.len() as u32).unwrap_or_default() != 0 {
+        return None;
+    }
+    let (root, _) = path.iter().fold((leaf, index), |(node, index), &sibling| {
+        let parent = if index % 2 == 0 { merkle_hash(node, sibling) } else { merkle_hash(sibling, node) };
+        (parent, index / 2)
+    });
+    Some(root)
+}
+
 fn merkle_hash(left: Hash, right: Hash) -> Hash {
     let mut hasher = MerkleBranchHash::new();
     hasher.update(left).update(right);
     hasher.finalize()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merkle_path() {
+        for len in 1..=17u64 {
+            let leaves = (0..len).map(Hash::from_u64_word).collect::<Vec<_>>();
+            let root = calc_merkle_root(leaves.iter().copied());
+            for (index, &leaf) in leaves.iter().enumerate() {
+                let path = calc_merkle_path(leaves.iter().copied(), index).unwrap();
+                assert_eq!(path.len(), len.next_power_of_two().trailing_zeros() as usize);
+                assert_eq!(calc_merkle_root_from_path(leaf, index, &path), Some(root), "leaf {index} of {len}");
+
+                // A different leaf index or tampered path do not lead to the root
+                let other_index = if index == 0 { 1 } else { index - 1 };
+                assert_ne!(calc_merkle_root_from_path(leaf, other_index, &path), Some(root));
+                if let Some(sibling) = path.first() {
+                    let mut tampered = path.clone();
+                    tampered[0] = Hash::from_u64_word(!sibling.to_le_u64()[0]);
+                    assert_ne!(calc_merkle_root_from_path(leaf, index, &tampered), Some(root));
+                }
+            }
+            assert!(calc_merkle_path(leaves.iter().copied(), len as usize).is_none());
+        }
+
+        // The index must fit within the path depth
+        assert!(calc_merkle_root_from_path(ZERO_HASH, 2, &[ZERO_HASH]).is_none());
+        assert!(calc_merkle_root_from_path(ZERO_HASH, 1, &[]).is_none());
+        assert_eq!(calc_merkle_root_from_path(ZERO_HASH, 0, &[]), Some(ZERO_HASH));
+    }
+}
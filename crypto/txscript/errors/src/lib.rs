@@ -70,3 +70,90 @@ pub enum TxScriptError {
     #[error("script of size {0} exceeded maximum allowed size of {1}")]
     ScriptSize(usize, usize),
 }
+
+impl TxScriptError {
+    /// Number of variants of the error, see [`TxScriptError::variant_index`]
+    pub const VARIANT_COUNT: usize = 33;
+
+    /// Names of the variants of the error, indexed by [`TxScriptError::variant_index`]
+    pub const VARIANT_NAMES: [&'static str; Self::VARIANT_COUNT] = [
+        "MalformedPushSize",
+        "MalformedPush",
+        "InvalidIndex",
+        "StackSizeExceeded",
+        "InvalidOpcode",
+        "OpcodeReserved",
+        "OpcodeDisabled",
+        "EmptyStack",
+        "CleanStack",
+        "EvalFalse",
+        "EarlyReturn",
+        "VerifyError",
+        "InvalidState",
+        "InvalidSignature",
+        "SigcacheSignatureInvalid",
+        "TooManyOperations",
+        "NotATransactionInput",
+        "ElementTooBig",
+        "NotMinimalData",
+        "InvalidSource",
+        "UnsatisfiedLockTime",
+        "NumberTooBig",
+        "NullFail",
+        "InvalidSignatureCount",
+        "InvalidPubKeyCount",
+        "InvalidSigHashType",
+        "PubKeyFormat",
+        "SigLength",
+        "NoScripts",
+        "SignatureScriptNotPushOnly",
+        "ErrUnbalancedConditional",
+        "InvalidStackOperation",
+        "ScriptSize",
+    ];
+
+    /// Index of the variant of the error, ignoring its payload. Allows bucketing errors by kind,
+    /// e.g. for statistics. New variants must be appended so existing indices remain stable.
+    pub fn variant_index(&self) -> usize {
+        match self {
+            Self::MalformedPushSize(..) => 0,
+            Self::MalformedPush(..) => 1,
+            Self::InvalidIndex(..) => 2,
+            Self::StackSizeExceeded(..) => 3,
+            Self::InvalidOpcode(..) => 4,
+            Self::OpcodeReserved(..) => 5,
+            Self::OpcodeDisabled(..) => 6,
+            Self::EmptyStack => 7,
+            Self::CleanStack(..) => 8,
+            Self::EvalFalse => 9,
+            Self::EarlyReturn => 10,
+            Self::VerifyError => 11,
+            Self::InvalidState(..) => 12,
+            Self::InvalidSignature(..) => 13,
+            Self::SigcacheSignatureInvalid => 14,
+            Self::TooManyOperations(..) => 15,
+            Self::NotATransactionInput => 16,
+            Self::ElementTooBig(..) => 17,
+            Self::NotMinimalData(..) => 18,
+            Self::InvalidSource(..) => 19,
+            Self::UnsatisfiedLockTime(..) => 20,
+            Self::NumberTooBig(..) => 21,
+            Self::NullFail => 22,
+            Self::InvalidSignatureCount(..) => 23,
+            Self::InvalidPubKeyCount(..) => 24,
+            Self::InvalidSigHashType(..) => 25,
+            Self::PubKeyFormat => 26,
+            Self::SigLength(..) => 27,
+            Self::NoScripts => 28,
+            Self::SignatureScriptNotPushOnly => 29,
+            Self::ErrUnbalancedConditional => 30,
+            Self::InvalidStackOperation(..) => 31,
+            Self::ScriptSize(..) => 32,
+        }
+    }
+
+    /// Name of the variant of the error, ignoring its payload
+    pub fn variant_name(&self) -> &'static str {
+        Self::VARIANT_NAMES[self.variant_index()]
+    }
+}
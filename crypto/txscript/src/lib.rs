@@ -3,6 +3,7 @@ extern crate core;
 
 pub mod caches;
 mod data_stack;
+pub mod metrics;
 pub mod opcodes;
 pub mod script_builder;
 pub mod script_class;
@@ -10,6 +11,7 @@ pub mod standard;
 
 use crate::caches::Cache;
 use crate::data_stack::{DataStack, Stack};
+use crate::metrics::OpcodeCounts;
 use crate::opcodes::{deserialize_next_opcode, OpCodeImplementation};
 use itertools::Itertools;
 use kaspa_consensus_core::hashing::sighash::{calc_ecdsa_signature_hash, calc_schnorr_signature_hash, SigHashReusedValues};
@@ -80,6 +82,9 @@ pub struct TxScriptEngine<'a, T: VerifiableTransaction> {
     cond_stack: Vec<OpCond>, // Following if stacks, and whether it is running
 
     num_ops: i32,
+
+    // Execution counts of the opcodes, when the script is sampled for metrics
+    opcode_counts: Option<&'a mut OpcodeCounts>,
 }
 
 fn parse_script<T: VerifiableTransaction>(
@@ -152,6 +157,7 @@ impl<'a, T: VerifiableTransaction> TxScriptEngine<'a, T> {
             sig_cache,
             cond_stack: vec![],
             num_ops: 0,
+            opcode_counts: None,
         }
     }
 
@@ -176,6 +182,7 @@ impl<'a, T: VerifiableTransaction> TxScriptEngine<'a, T> {
                 sig_cache,
                 cond_stack: Default::default(),
                 num_ops: 0,
+                opcode_counts: None,
             }),
             false => Err(TxScriptError::InvalidIndex(input_idx, tx.tx().inputs.len())),
         }
@@ -190,9 +197,16 @@ impl<'a, T: VerifiableTransaction> TxScriptEngine<'a, T> {
             sig_cache,
             cond_stack: Default::default(),
             num_ops: 0,
+            opcode_counts: None,
         }
     }
 
+    /// Counts the opcodes executed by the engine into `counts`
+    pub fn with_opcode_counts(mut self, counts: &'a mut OpcodeCounts) -> Self {
+        self.opcode_counts = Some(counts);
+        self
+    }
+
     #[inline]
     pub fn is_executing(&self) -> bool {
         return self.cond_stack.is_empty() || *self.cond_stack.last().expect("Checked not empty") == OpCond::True;
@@ -235,6 +249,9 @@ impl<'a, T: VerifiableTransaction> TxScriptEngine<'a, T> {
                 return Err(TxScriptError::SignatureScriptNotPushOnly);
             }
 
+            if let Some(counts) = self.opcode_counts.as_deref_mut() {
+                counts[opcode.value() as usize] += 1;
+            }
             self.execute_opcode(opcode)?;
 
             let combined_size = self.astack.len() + self.dstack.len();
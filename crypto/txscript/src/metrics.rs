@@ -0,0 +1,258 @@
+//! Node-wide statistics of script verification.
+//!
+//! Script verification runs concurrently on many threads, so the counts are first aggregated in a
+//! thread-local buffer and only merged into the shared atomic counters once enough transactions were
+//! recorded or enough time elapsed since the previous merge, and when the thread exits. The hot path
+//! thus never contends on shared cache lines.
+//!
+//! Counting the executed opcodes costs more than counting the verification results, so opcodes are
+//! only counted for a sample of 1 in [`ScriptMetrics::opcode_sample_rate`] transactions.
+
+use kaspa_txscript_errors::TxScriptError;
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Weak,
+    },
+    time::{Duration, Instant},
+};
+
+/// Per opcode value execution counts
+pub type OpcodeCounts = [u64; 256];
+
+pub const DEFAULT_OPCODE_SAMPLE_RATE: u64 = 100;
+
+/// Max number of transactions recorded by a thread before its counts get merged
+const MERGE_THRESHOLD: u64 = 256;
+
+/// Max time elapsed since the previous merge of a thread before its counts get merged on next record
+const MERGE_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct ScriptMetrics {
+    opcode_sample_rate: u64,
+    checked_counts: AtomicU64,
+    sampled_counts: AtomicU64,
+    rejection_counts: [AtomicU64; TxScriptError::VARIANT_COUNT],
+    opcode_counts: [AtomicU64; 256],
+}
+
+impl ScriptMetrics {
+    /// Creates metrics counting the executed opcodes of 1 in `opcode_sample_rate` transactions,
+    /// a rate of 0 disabling opcode counting
+    pub fn new(opcode_sample_rate: u64) -> Self {
+        Self {
+            opcode_sample_rate,
+            checked_counts: Default::default(),
+            sampled_counts: Default::default(),
+            rejection_counts: std::array::from_fn(|_| Default::default()),
+            opcode_counts: std::array::from_fn(|_| Default::default()),
+        }
+    }
+
+    pub fn opcode_sample_rate(&self) -> u64 {
+        self.opcode_sample_rate
+    }
+
+    /// Records the script verification of a transaction.
+    ///
+    /// `verify` runs the verification and receives the buffer counting the executed opcodes if
+    /// the transaction is sampled.
+    pub fn record_verification<T>(
+        self: &Arc<Self>,
+        verify: impl FnOnce(Option<&mut OpcodeCounts>) -> Result<T, TxScriptError>,
+    ) -> Result<T, TxScriptError> {
+        LOCAL_METRICS.with_borrow_mut(|buffers| {
+            let buffer = LocalBuffer::of(buffers, self);
+            let sampled = self.opcode_sample_rate > 0 && buffer.sequence % self.opcode_sample_rate == 0;
+            let result = verify(sampled.then_some(&mut buffer.opcode_counts));
+            buffer.sequence += 1;
+            buffer.checked_counts += 1;
+            buffer.sampled_counts += sampled as u64;
+            if let Err(err) = &result {
+                buffer.rejection_counts[err.variant_index()] += 1;
+            }
+            if buffer.checked_counts >= MERGE_THRESHOLD || buffer.last_merge.elapsed() >= MERGE_INTERVAL {
+                buffer.merge_into(self);
+            }
+            result
+        })
+    }
+
+    /// Merges the counts buffered by the current thread, making them visible to [`ScriptMetrics::snapshot`]
+    pub fn merge_local(self: &Arc<Self>) {
+        LOCAL_METRICS.with_borrow_mut(|buffers| {
+            if let Some(buffer) = buffers.iter_mut().find(|buffer| buffer.target.as_ptr() == Arc::as_ptr(self)) {
+                buffer.merge_into(self);
+            }
+        })
+    }
+
+    /// Returns the merged counts. The counts still buffered by the threads are not included.
+    pub fn snapshot(&self) -> ScriptMetricsSnapshot {
+        ScriptMetricsSnapshot {
+            opcode_sample_rate: self.opcode_sample_rate,
+            checked_counts: self.checked_counts.load(Ordering::Relaxed),
+            sampled_counts: self.sampled_counts.load(Ordering::Relaxed),
+            rejection_counts: self
+                .rejection_counts
+                .iter()
+                .enumerate()
+                .filter_map(|(index, count)| {
+                    let count = count.load(Ordering::Relaxed);
+                    (count > 0).then_some((TxScriptError::VARIANT_NAMES[index], count))
+                })
+                .collect(),
+            opcode_counts: self
+                .opcode_counts
+                .iter()
+                .enumerate()
+                .filter_map(|(opcode, count)| {
+                    let count = count.load(Ordering::Relaxed);
+                    (count > 0).then_some((opcode as u8, count))
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Default for ScriptMetrics {
+    fn default() -> Self {
+        Self::new(DEFAULT_OPCODE_SAMPLE_RATE)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScriptMetricsSnapshot {
+    pub opcode_sample_rate: u64,
+    /// Number of transactions whose scripts were verified
+    pub checked_counts: u64,
+    /// Number of verified transactions whose executed opcodes were counted
+    pub sampled_counts: u64,
+    /// Number of rejected transactions per [`TxScriptError`] variant name, omitting variants never encountered
+    pub rejection_counts: Vec<(&'static str, u64)>,
+    /// Number of executions per opcode value in the sampled transactions, omitting opcodes never executed
+    pub opcode_counts: Vec<(u8, u64)>,
+}
+
+impl ScriptMetricsSnapshot {
+    pub fn rejected_counts(&self) -> u64 {
+        self.rejection_counts.iter().map(|(_, count)| count).sum()
+    }
+
+    pub fn rejection_count(&self, variant_name: &str) -> u64 {
+        self.rejection_counts.iter().find(|(name, _)| *name == variant_name).map_or(0, |(_, count)| *count)
+    }
+
+    pub fn opcode_count(&self, opcode: u8) -> u64 {
+        self.opcode_counts.iter().find(|(value, _)| *value == opcode).map_or(0, |(_, count)| *count)
+    }
+}
+
+thread_local! {
+    static LOCAL_METRICS: RefCell<Vec<LocalBuffer>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The counts recorded by a thread for some [`ScriptMetrics`] and not merged yet
+struct LocalBuffer {
+    target: Weak<ScriptMetrics>,
+    /// Number of transactions ever recorded by the thread, driving the sampling
+    sequence: u64,
+    checked_counts: u64,
+    sampled_counts: u64,
+    rejection_counts: [u64; TxScriptError::VARIANT_COUNT],
+    opcode_counts: OpcodeCounts,
+    last_merge: Instant,
+}
+
+impl LocalBuffer {
+    fn new(target: &Arc<ScriptMetrics>) -> Self {
+        Self {
+            target: Arc::downgrade(target),
+            sequence: 0,
+            checked_counts: 0,
+            sampled_counts: 0,
+            rejection_counts: [0; TxScriptError::VARIANT_COUNT],
+            opcode_counts: [0; 256],
+            last_merge: Instant::now(),
+        }
+    }
+
+    /// Returns the buffer of the current thread for `target`, creating it if needed.
+    /// The buffers of dropped metrics are discarded along the way.
+    fn of<'a>(buffers: &'a mut Vec<LocalBuffer>, target: &Arc<ScriptMetrics>) -> &'a mut LocalBuffer {
+        match buffers.iter().position(|buffer| buffer.target.as_ptr() == Arc::as_ptr(target)) {
+            Some(index) => &mut buffers[index],
+            None => {
+                buffers.retain(|buffer| buffer.target.strong_count() > 0);
+                buffers.push(LocalBuffer::new(target));
+                buffers.last_mut().unwrap()
+            }
+        }
+    }
+
+    /// Adds the buffered counts to `target` and resets them
+    fn merge_into(&mut self, target: &ScriptMetrics) {
+        target.checked_counts.fetch_add(std::mem::take(&mut self.checked_counts), Ordering::Relaxed);
+        if self.sampled_counts > 0 {
+            target.sampled_counts.fetch_add(self.sampled_counts, Ordering::Relaxed);
+            for (count, buffered) in target.opcode_counts.iter().zip(self.opcode_counts.iter_mut()) {
+                if *buffered > 0 {
+                    count.fetch_add(std::mem::take(buffered), Ordering::Relaxed);
+                }
+            }
+            self.sampled_counts = 0;
+        }
+        for (count, buffered) in target.rejection_counts.iter().zip(self.rejection_counts.iter_mut()) {
+            if *buffered > 0 {
+                count.fetch_add(std::mem::take(buffered), Ordering::Relaxed);
+            }
+        }
+        self.last_merge = Instant::now();
+    }
+}
+
+impl Drop for LocalBuffer {
+    fn drop(&mut self) {
+        if let Some(target) = self.target.upgrade() {
+            self.merge_into(&target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_metrics_merge_across_threads() {
+        let metrics = Arc::new(ScriptMetrics::new(2));
+        let handles = (0..4)
+            .map(|_| {
+                let metrics = metrics.clone();
+                std::thread::spawn(move || {
+                    for i in 0..10u8 {
+                        let _ = metrics.record_verification(|counts| {
+                            if let Some(counts) = counts {
+                                counts[i as usize] += 1;
+                            }
+                            match i % 5 {
+                                0 => Err(TxScriptError::EvalFalse),
+                                1 => Err(TxScriptError::NullFail),
+                                _ => Ok(()),
+                            }
+                        });
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        // The buffers of the threads get merged as they exit
+        handles.into_iter().for_each(|handle| handle.join().unwrap());
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.checked_counts, 40);
+        assert_eq!(snapshot.sampled_counts, 20);
+        assert_eq!(snapshot.rejection_counts, vec![("EvalFalse", 8), ("NullFail", 8)]);
+        assert_eq!(snapshot.opcode_counts, vec![(0, 4), (2, 4), (4, 4), (6, 4), (8, 4)]);
+    }
+}
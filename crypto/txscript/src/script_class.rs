@@ -16,7 +16,7 @@ pub enum Error {
 }
 
 /// Standard classes of script payment in the blockDAG
-#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[repr(u8)]
 pub enum ScriptClass {
     /// None of the recognized forms
@@ -80,7 +80,7 @@ impl ScriptClass {
         (script_public_key[34] == opcodes::codes::OpEqual)
     }
 
-    fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &'static str {
         match self {
             ScriptClass::NonStandard => NON_STANDARD,
             ScriptClass::PubKey => PUB_KEY,
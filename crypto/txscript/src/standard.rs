@@ -116,6 +116,20 @@ pub mod test_helpers {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{caches::Cache, TxScriptEngine};
+    use kaspa_consensus_core::{
+        hashing::{
+            sighash::SigHashReusedValues,
+            sighash_type::{SIG_HASH_ALL, SIG_HASH_SINGLE},
+        },
+        sign::sign_input,
+        subnets::SUBNETWORK_ID_NATIVE,
+        tx::{
+            MutableTransaction, Transaction, TransactionId, TransactionInput, TransactionOutpoint, TransactionOutput, UtxoEntry,
+            VerifiableTransaction,
+        },
+    };
+    use secp256k1::Keypair;
 
     #[test]
     fn test_extract_address_and_encode_script() {
@@ -184,4 +198,46 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_sign_inputs_separately() {
+        let keys =
+            [Keypair::new(secp256k1::SECP256K1, &mut rand::thread_rng()), Keypair::new(secp256k1::SECP256K1, &mut rand::thread_rng())];
+        let prefix = Prefix::Testnet;
+        let addresses = keys.map(|key| Address::new(prefix, Version::PubKey, &key.x_only_public_key().0.serialize()));
+
+        let prev_tx_id = TransactionId::from_bytes([1; 32]);
+        let inputs = (0..2).map(|i| TransactionInput::new(TransactionOutpoint::new(prev_tx_id, i), vec![], 0, 1)).collect();
+        let outputs = vec![TransactionOutput::new(2_000, pay_to_address_script(&addresses[0]))];
+        let tx = Transaction::new(0, inputs, outputs, 0, SUBNETWORK_ID_NATIVE, 0, vec![]);
+        let entries = addresses.iter().map(|address| UtxoEntry::new(1_500, pay_to_address_script(address), 0, false)).collect();
+        let mut tx = MutableTransaction::with_entries(tx, entries);
+
+        // Each party signs its own input only, possibly with a different sighash type
+        let signatures = [
+            sign_input(&tx.as_verifiable(), 0, &keys[0].secret_bytes(), SIG_HASH_ALL).unwrap(),
+            sign_input(&tx.as_verifiable(), 1, &keys[1].secret_bytes(), SIG_HASH_SINGLE).unwrap(),
+        ];
+        assert!(sign_input(&tx.as_verifiable(), 2, &keys[0].secret_bytes(), SIG_HASH_ALL).is_err());
+
+        // Assemble the signatures into the transaction
+        for (input, signature) in tx.tx.inputs.iter_mut().zip(signatures) {
+            input.signature_script = signature;
+        }
+
+        let tx = tx.as_verifiable();
+        let cache = Cache::new(10_000);
+        let mut reused_values = SigHashReusedValues::new();
+        for (i, (input, entry)) in tx.populated_inputs().enumerate() {
+            let mut engine = TxScriptEngine::from_transaction_input(&tx, input, i, entry, &mut reused_values, &cache).unwrap();
+            engine.execute().unwrap();
+        }
+
+        // A signature created for one input does not satisfy another input
+        let (input, entry) = tx.populated_input(1);
+        let mut swapped = input.clone();
+        swapped.signature_script = tx.tx().inputs[0].signature_script.clone();
+        let mut engine = TxScriptEngine::from_transaction_input(&tx, &swapped, 1, entry, &mut reused_values, &cache).unwrap();
+        assert!(engine.execute().is_err());
+    }
 }
@@ -7,6 +7,9 @@ use serde::{de::DeserializeOwned, Serialize};
 use std::{collections::hash_map::RandomState, error::Error, hash::BuildHasher, sync::Arc};
 
 /// A concurrent DB store access with typed caching.
+///
+/// Over a secondary DB instance the access is read-only: caching is disabled, since the primary might
+/// modify entries behind its back, and write operations fail with [`StoreError::ReadOnlyDb`].
 #[derive(Clone)]
 pub struct CachedDbAccess<TKey, TData, S = RandomState>
 where
@@ -29,6 +32,7 @@ where
     S: BuildHasher + Default,
 {
     pub fn new(db: Arc<DB>, cache_policy: CachePolicy, prefix: Vec<u8>) -> Self {
+        let cache_policy = if db.is_secondary() { CachePolicy::Empty } else { cache_policy };
         Self { db, cache: Cache::new(cache_policy), prefix }
     }
 
@@ -65,6 +69,25 @@ where
         }
     }
 
+    /// Reads the data of `key` without populating the cache on a cache miss. Intended for bulk reads of
+    /// entries which are not expected to be read again soon, and which would otherwise evict the hot ones.
+    pub fn read_uncached(&self, key: TKey) -> Result<TData, StoreError>
+    where
+        TKey: Clone + AsRef<[u8]> + ToString,
+        TData: DeserializeOwned,
+    {
+        if let Some(data) = self.cache.get(&key) {
+            Ok(data)
+        } else {
+            let db_key = DbKey::new(&self.prefix, key.clone());
+            if let Some(slice) = self.db.get_pinned(&db_key)? {
+                Ok(bincode::deserialize(&slice)?)
+            } else {
+                Err(StoreError::KeyNotFound(db_key))
+            }
+        }
+    }
+
     pub fn iterator(&self) -> impl Iterator<Item = Result<(Box<[u8]>, TData), Box<dyn Error>>> + '_
     where
         TKey: Clone + AsRef<[u8]>,
@@ -89,6 +112,7 @@ where
         TKey: Clone + AsRef<[u8]>,
         TData: Serialize,
     {
+        self.db.check_writable()?;
         let bin_data = bincode::serialize(&data)?;
         self.cache.insert(key.clone(), data);
         writer.put(DbKey::new(&self.prefix, key), bin_data)?;
@@ -104,6 +128,7 @@ where
         TKey: Clone + AsRef<[u8]>,
         TData: Serialize,
     {
+        self.db.check_writable()?;
         let iter_clone = iter.clone();
         self.cache.insert_many(iter);
         for (key, data) in iter_clone {
@@ -123,6 +148,7 @@ where
         TKey: Clone + AsRef<[u8]>,
         TData: Serialize,
     {
+        self.db.check_writable()?;
         for (key, data) in iter {
             let bin_data = bincode::serialize(&data)?;
             writer.put(DbKey::new(&self.prefix, key), bin_data)?;
@@ -136,6 +162,7 @@ where
     where
         TKey: Clone + AsRef<[u8]>,
     {
+        self.db.check_writable()?;
         self.cache.remove(&key);
         writer.delete(DbKey::new(&self.prefix, key))?;
         Ok(())
@@ -145,6 +172,7 @@ where
     where
         TKey: Clone + AsRef<[u8]>,
     {
+        self.db.check_writable()?;
         let key_iter_clone = key_iter.clone();
         self.cache.remove_many(key_iter);
         for key in key_iter_clone {
@@ -158,6 +186,7 @@ where
     where
         TKey: Clone + AsRef<[u8]>,
     {
+        self.db.check_writable()?;
         self.cache.remove_all();
         let db_key = DbKey::prefix_only(&self.prefix);
         let (from, to) = rocksdb::PrefixRange(db_key.as_ref()).into_bounds();
@@ -221,6 +250,7 @@ mod tests {
     use crate::{
         create_temp_db,
         prelude::{BatchDbWriter, ConnBuilder, DirectDbWriter},
+        utils::get_kaspa_tempdir,
     };
     use kaspa_hashes::Hash;
     use rocksdb::WriteBatch;
@@ -243,4 +273,55 @@ mod tests {
         db.write(batch).unwrap();
         assert_eq!(0, access.iterator().count());
     }
+
+    #[test]
+    fn test_secondary_catch_up() {
+        let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        let access = CachedDbAccess::<Hash, u64>::new(db.clone(), CachePolicy::Count(16), vec![1, 2]);
+        access.write_many(DirectDbWriter::new(&db), &mut (0..8).map(|i| (i.into(), i))).unwrap();
+
+        let secondary_dir = get_kaspa_tempdir();
+        let secondary = ConnBuilder::default()
+            .with_db_path(db.path().to_owned())
+            .with_files_limit(10)
+            .build_secondary(secondary_dir.path().to_owned())
+            .unwrap();
+        let secondary_access = CachedDbAccess::<Hash, u64>::new(secondary.clone(), CachePolicy::Count(16), vec![1, 2]);
+        assert_eq!(secondary_access.read(7.into()).unwrap(), 7);
+
+        // New entries and updates of the primary are only seen after catching up
+        access.write_many(DirectDbWriter::new(&db), &mut (8..16).map(|i| (i.into(), i))).unwrap();
+        access.write(DirectDbWriter::new(&db), 7.into(), 70).unwrap();
+        assert!(matches!(secondary_access.read(15.into()), Err(StoreError::KeyNotFound(_))));
+        secondary.try_catch_up_with_primary().unwrap();
+        assert_eq!(16, secondary_access.iterator().count());
+        assert_eq!(secondary_access.read(15.into()).unwrap(), 15);
+        assert_eq!(secondary_access.read(7.into()).unwrap(), 70);
+
+        // Writes are rejected before reaching the DB
+        assert!(matches!(secondary_access.write(DirectDbWriter::new(&secondary), 16.into(), 16), Err(StoreError::ReadOnlyDb)));
+        assert!(matches!(secondary_access.delete_all(DirectDbWriter::new(&secondary)), Err(StoreError::ReadOnlyDb)));
+        assert!(matches!(db.try_catch_up_with_primary(), Err(StoreError::NotSecondaryDb)));
+    }
+
+    #[test]
+    fn test_checkpoint_read_only() {
+        let (_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        let access = CachedDbAccess::<Hash, u64>::new(db.clone(), CachePolicy::Count(16), vec![1, 2]);
+        access.write_many(DirectDbWriter::new(&db), &mut (0..8).map(|i| (i.into(), i))).unwrap();
+
+        let checkpoint_dir = get_kaspa_tempdir();
+        let checkpoint_path = checkpoint_dir.path().join("checkpoint");
+        db.create_checkpoint(&checkpoint_path).unwrap();
+        assert!(db.create_checkpoint(&checkpoint_path).is_err(), "checkpoints cannot overwrite an existing directory");
+
+        // Later writes of the DB are not part of the checkpoint
+        access.write(DirectDbWriter::new(&db), 8.into(), 8).unwrap();
+
+        let checkpoint = ConnBuilder::default().with_db_path(checkpoint_path).with_files_limit(10).build_read_only().unwrap();
+        let checkpoint_access = CachedDbAccess::<Hash, u64>::new(checkpoint.clone(), CachePolicy::Count(16), vec![1, 2]);
+        assert_eq!(8, checkpoint_access.iterator().count());
+        assert_eq!(checkpoint_access.read(7.into()).unwrap(), 7);
+        assert!(matches!(checkpoint_access.write(DirectDbWriter::new(&checkpoint), 9.into(), 9), Err(StoreError::ReadOnlyDb)));
+    }
 }
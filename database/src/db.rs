@@ -1,21 +1,159 @@
-use rocksdb::{DBWithThreadMode, MultiThreaded};
+use crate::errors::{StoreError, StoreResult};
+use rocksdb::{DBWithThreadMode, MultiThreaded, WriteBatch, WriteOptions};
 use std::ops::{Deref, DerefMut};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 pub use conn_builder::ConnBuilder;
 use kaspa_utils::fd_budget::FDGuard;
 
 mod conn_builder;
 
+/// The criticality of the data written by a store, which determines whether its writes are synced
+/// to disk under a given [`WalSyncPolicy`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreCriticality {
+    /// Data which can be rebuilt or refetched at a low cost
+    Regular,
+    /// Data whose loss costs a resync, such as block headers, statuses and the virtual state
+    Critical,
+}
+
+/// Which writes wait for the RocksDB write-ahead log to be synced to disk before returning.
+///
+/// Unsynced writes survive a crash of the process but may be lost on a crash of the host.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WalSyncPolicy {
+    /// No write is synced, favoring throughput
+    #[default]
+    Never,
+    /// Only writes of [critical](StoreCriticality::Critical) data are synced
+    Critical,
+    /// Every write is synced
+    Always,
+}
+
+impl WalSyncPolicy {
+    pub fn syncs(&self, criticality: StoreCriticality) -> bool {
+        match self {
+            WalSyncPolicy::Never => false,
+            WalSyncPolicy::Critical => criticality == StoreCriticality::Critical,
+            WalSyncPolicy::Always => true,
+        }
+    }
+}
+
+impl std::str::FromStr for WalSyncPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "never" => Ok(WalSyncPolicy::Never),
+            "critical" => Ok(WalSyncPolicy::Critical),
+            "always" => Ok(WalSyncPolicy::Always),
+            _ => Err(format!("unknown WAL sync policy '{s}', expected one of: never, critical, always")),
+        }
+    }
+}
+
+impl std::fmt::Display for WalSyncPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WalSyncPolicy::Never => write!(f, "never"),
+            WalSyncPolicy::Critical => write!(f, "critical"),
+            WalSyncPolicy::Always => write!(f, "always"),
+        }
+    }
+}
+
 /// The DB type used for Kaspad stores
 pub struct DB {
     inner: DBWithThreadMode<MultiThreaded>,
+    secondary: bool,
+    read_only: bool,
+    wal_sync: WalSyncPolicy,
     _fd_guard: FDGuard,
 }
 
 impl DB {
-    pub fn new(inner: DBWithThreadMode<MultiThreaded>, fd_guard: FDGuard) -> Self {
-        Self { inner, _fd_guard: fd_guard }
+    pub fn new(inner: DBWithThreadMode<MultiThreaded>, wal_sync: WalSyncPolicy, fd_guard: FDGuard) -> Self {
+        Self { inner, secondary: false, read_only: false, wal_sync, _fd_guard: fd_guard }
+    }
+
+    /// Wraps a DB opened as a RocksDB secondary instance, see [`ConnBuilder::build_secondary`]
+    pub fn new_secondary(inner: DBWithThreadMode<MultiThreaded>, fd_guard: FDGuard) -> Self {
+        Self { inner, secondary: true, read_only: true, wal_sync: Default::default(), _fd_guard: fd_guard }
+    }
+
+    /// Wraps a DB opened in read-only mode, see [`ConnBuilder::build_read_only`]
+    pub fn new_read_only(inner: DBWithThreadMode<MultiThreaded>, fd_guard: FDGuard) -> Self {
+        Self { inner, secondary: false, read_only: true, wal_sync: Default::default(), _fd_guard: fd_guard }
+    }
+
+    pub fn wal_sync_policy(&self) -> WalSyncPolicy {
+        self.wal_sync
+    }
+
+    /// Writes `batch`, syncing the write-ahead log first if the [`WalSyncPolicy`] of the DB requires it for `criticality`
+    pub fn write_with_criticality(&self, batch: WriteBatch, criticality: StoreCriticality) -> Result<(), rocksdb::Error> {
+        let mut opts = WriteOptions::default();
+        opts.set_sync(self.wal_sync.syncs(criticality));
+        self.inner.write_opt(batch, &opts)
+    }
+
+    /// Writes a batch holding [critical](StoreCriticality::Critical) data
+    pub fn write_critical(&self, batch: WriteBatch) -> Result<(), rocksdb::Error> {
+        self.write_with_criticality(batch, StoreCriticality::Critical)
+    }
+
+    /// Syncs the write-ahead log to disk and flushes the memtables, so that all the writes so far are durable
+    /// and the DB reopens without replaying its log
+    pub fn flush_all(&self) -> StoreResult<()> {
+        self.check_writable()?;
+        self.inner.flush_wal(true)?;
+        self.inner.flush()?;
+        Ok(())
+    }
+
+    /// Returns a best-effort flush of this DB for running when the process is about to exit abnormally, e.g.
+    /// by registering it with `kaspa_core::panic::register_emergency_action`. The action holds no strong
+    /// reference to the DB and succeeds trivially once the DB is closed.
+    pub fn emergency_flush_action(self: &Arc<Self>) -> impl Fn() -> Result<(), String> + Send + Sync + 'static {
+        let db = Arc::downgrade(self);
+        move || match db.upgrade() {
+            Some(db) => db.flush_all().map_err(|err| err.to_string()),
+            None => Ok(()),
+        }
+    }
+
+    /// Indicates whether this is a read-only secondary instance following a primary DB
+    pub fn is_secondary(&self) -> bool {
+        self.secondary
+    }
+
+    /// Returns [`StoreError::ReadOnlyDb`] if this is a secondary or read-only instance
+    pub fn check_writable(&self) -> StoreResult<()> {
+        match self.read_only {
+            true => Err(StoreError::ReadOnlyDb),
+            false => Ok(()),
+        }
+    }
+
+    /// Makes a secondary instance catch up with all the writes the primary DB flushed to its logs so far.
+    /// Data read before this call is not refreshed by it, so readers should not hold onto it.
+    pub fn try_catch_up_with_primary(&self) -> StoreResult<()> {
+        if !self.secondary {
+            return Err(StoreError::NotSecondaryDb);
+        }
+        Ok(self.inner.try_catch_up_with_primary()?)
+    }
+
+    /// Creates a RocksDB checkpoint of the DB at `checkpoint_dir`, which must not exist yet. The checkpoint is a
+    /// consistent point-in-time copy of all column families, taken without blocking concurrent writes. SST files
+    /// are hard linked when `checkpoint_dir` is on the same filesystem as the DB, and copied otherwise.
+    pub fn create_checkpoint(&self, checkpoint_dir: &Path) -> StoreResult<()> {
+        let checkpoint = rocksdb::checkpoint::Checkpoint::new(&self.inner)?;
+        Ok(checkpoint.create_checkpoint(checkpoint_dir)?)
     }
 }
 
@@ -1,4 +1,4 @@
-use crate::db::DB;
+use crate::db::{WalSyncPolicy, DB};
 use rocksdb::{DBWithThreadMode, MultiThreaded};
 use std::{path::PathBuf, sync::Arc};
 
@@ -12,6 +12,7 @@ pub struct ConnBuilder<Path, const STATS_ENABLED: bool, StatsPeriod, FDLimit> {
     parallelism: usize,
     files_limit: FDLimit,
     mem_budget: usize,
+    wal_sync: WalSyncPolicy,
     stats_period: StatsPeriod,
 }
 
@@ -22,6 +23,7 @@ impl Default for ConnBuilder<Unspecified, false, Unspecified, Unspecified> {
             create_if_missing: true,
             parallelism: 1,
             mem_budget: 64 * 1024 * 1024,
+            wal_sync: WalSyncPolicy::Never,
             stats_period: Unspecified,
             files_limit: Unspecified,
         }
@@ -36,6 +38,7 @@ impl<Path, const STATS_ENABLED: bool, StatsPeriod, FDLimit> ConnBuilder<Path, ST
             create_if_missing: self.create_if_missing,
             parallelism: self.parallelism,
             mem_budget: self.mem_budget,
+            wal_sync: self.wal_sync,
             stats_period: self.stats_period,
         }
     }
@@ -48,6 +51,9 @@ impl<Path, const STATS_ENABLED: bool, StatsPeriod, FDLimit> ConnBuilder<Path, ST
     pub fn with_mem_budget(self, mem_budget: impl Into<usize>) -> ConnBuilder<Path, STATS_ENABLED, StatsPeriod, FDLimit> {
         ConnBuilder { mem_budget: mem_budget.into(), ..self }
     }
+    pub fn with_wal_sync(self, wal_sync: WalSyncPolicy) -> ConnBuilder<Path, STATS_ENABLED, StatsPeriod, FDLimit> {
+        ConnBuilder { wal_sync, ..self }
+    }
     pub fn with_files_limit(self, files_limit: impl Into<i32>) -> ConnBuilder<Path, STATS_ENABLED, StatsPeriod, i32> {
         ConnBuilder {
             db_path: self.db_path,
@@ -55,6 +61,7 @@ impl<Path, const STATS_ENABLED: bool, StatsPeriod, FDLimit> ConnBuilder<Path, ST
             create_if_missing: self.create_if_missing,
             parallelism: self.parallelism,
             mem_budget: self.mem_budget,
+            wal_sync: self.wal_sync,
             stats_period: self.stats_period,
         }
     }
@@ -68,6 +75,7 @@ impl<Path, FDLimit> ConnBuilder<Path, false, Unspecified, FDLimit> {
             parallelism: self.parallelism,
             files_limit: self.files_limit,
             mem_budget: self.mem_budget,
+            wal_sync: self.wal_sync,
             stats_period: self.stats_period,
         }
     }
@@ -81,6 +89,7 @@ impl<Path, StatsPeriod, FDLimit> ConnBuilder<Path, true, StatsPeriod, FDLimit> {
             parallelism: self.parallelism,
             files_limit: self.files_limit,
             mem_budget: self.mem_budget,
+            wal_sync: self.wal_sync,
             stats_period: Unspecified,
         }
     }
@@ -91,6 +100,7 @@ impl<Path, StatsPeriod, FDLimit> ConnBuilder<Path, true, StatsPeriod, FDLimit> {
             parallelism: self.parallelism,
             files_limit: self.files_limit,
             mem_budget: self.mem_budget,
+            wal_sync: self.wal_sync,
             stats_period: stats_period.into(),
         }
     }
@@ -114,16 +124,49 @@ macro_rules! default_opts {
 impl ConnBuilder<PathBuf, false, Unspecified, i32> {
     pub fn build(self) -> Result<Arc<DB>, kaspa_utils::fd_budget::Error> {
         let (opts, guard) = default_opts!(self)?;
-        let db = Arc::new(DB::new(<DBWithThreadMode<MultiThreaded>>::open(&opts, self.db_path.to_str().unwrap()).unwrap(), guard));
+        let db = Arc::new(DB::new(
+            <DBWithThreadMode<MultiThreaded>>::open(&opts, self.db_path.to_str().unwrap()).unwrap(),
+            self.wal_sync,
+            guard,
+        ));
         Ok(db)
     }
+
+    /// Opens the DB at the configured path as a RocksDB secondary instance, which can be used concurrently with
+    /// a running primary instance (e.g. by an analytics process reading the DB of a live node). The secondary
+    /// instance is read-only and needs `secondary_path` for storing its own info logs. It only sees the data
+    /// written by the primary up to the time of opening, or of its last call to [`DB::try_catch_up_with_primary`].
+    pub fn build_secondary(self, secondary_path: PathBuf) -> Result<Arc<DB>, kaspa_utils::fd_budget::Error> {
+        let (mut opts, guard) = default_opts!(self)?;
+        opts.create_if_missing(false);
+        let inner = <DBWithThreadMode<MultiThreaded>>::open_as_secondary(
+            &opts,
+            self.db_path.to_str().unwrap(),
+            secondary_path.to_str().unwrap(),
+        )
+        .unwrap();
+        Ok(Arc::new(DB::new_secondary(inner, guard)))
+    }
+
+    /// Opens the existing DB at the configured path in read-only mode, e.g. for inspecting a backup. Unlike
+    /// [`Self::build_secondary`], this requires no process to have the DB opened for writing.
+    pub fn build_read_only(self) -> Result<Arc<DB>, kaspa_utils::fd_budget::Error> {
+        let (mut opts, guard) = default_opts!(self)?;
+        opts.create_if_missing(false);
+        let inner = <DBWithThreadMode<MultiThreaded>>::open_for_read_only(&opts, self.db_path.to_str().unwrap(), false).unwrap();
+        Ok(Arc::new(DB::new_read_only(inner, guard)))
+    }
 }
 
 impl ConnBuilder<PathBuf, true, Unspecified, i32> {
     pub fn build(self) -> Result<Arc<DB>, kaspa_utils::fd_budget::Error> {
         let (mut opts, guard) = default_opts!(self)?;
         opts.enable_statistics();
-        let db = Arc::new(DB::new(<DBWithThreadMode<MultiThreaded>>::open(&opts, self.db_path.to_str().unwrap()).unwrap(), guard));
+        let db = Arc::new(DB::new(
+            <DBWithThreadMode<MultiThreaded>>::open(&opts, self.db_path.to_str().unwrap()).unwrap(),
+            self.wal_sync,
+            guard,
+        ));
         Ok(db)
     }
 }
@@ -134,7 +177,11 @@ impl ConnBuilder<PathBuf, true, u32, i32> {
         opts.enable_statistics();
         opts.set_report_bg_io_stats(true);
         opts.set_stats_dump_period_sec(self.stats_period);
-        let db = Arc::new(DB::new(<DBWithThreadMode<MultiThreaded>>::open(&opts, self.db_path.to_str().unwrap()).unwrap(), guard));
+        let db = Arc::new(DB::new(
+            <DBWithThreadMode<MultiThreaded>>::open(&opts, self.db_path.to_str().unwrap()).unwrap(),
+            self.wal_sync,
+            guard,
+        ));
         Ok(db)
     }
 }
@@ -21,6 +21,12 @@ pub enum StoreError {
     #[error("rocksdb error {0}")]
     DbError(#[from] rocksdb::Error),
 
+    #[error("attempted to write to a read-only DB instance")]
+    ReadOnlyDb,
+
+    #[error("catching up with the primary DB is only supported by secondary DB instances")]
+    NotSecondaryDb,
+
     #[error("bincode error {0}")]
     DeserializationError(#[from] Box<bincode::ErrorKind>),
 }
@@ -13,7 +13,8 @@ use std::{
     sync::Arc,
 };
 
-/// A cached DB item with concurrency support
+/// A cached DB item with concurrency support. Over a secondary DB instance the item is never
+/// cached and writes fail with [`StoreError::ReadOnlyDb`]
 #[derive(Clone)]
 pub struct CachedDbItem<T> {
     db: Arc<DB>,
@@ -35,7 +36,9 @@ impl<T> CachedDbItem<T> {
         }
         if let Some(slice) = self.db.get_pinned(&self.key)? {
             let item: T = bincode::deserialize(&slice)?;
-            *self.cached_item.write() = Some(item.clone());
+            if !self.db.is_secondary() {
+                *self.cached_item.write() = Some(item.clone());
+            }
             Ok(item)
         } else {
             Err(StoreError::KeyNotFound(DbKey::prefix_only(&self.key)))
@@ -46,6 +49,7 @@ impl<T> CachedDbItem<T> {
     where
         T: Clone + Serialize,
     {
+        self.db.check_writable()?;
         *self.cached_item.write() = Some(item.clone());
         let bin_data = bincode::serialize(item)?;
         writer.put(&self.key, bin_data)?;
@@ -54,6 +58,7 @@ impl<T> CachedDbItem<T> {
 
     pub fn remove(&mut self, mut writer: impl DbWriter) -> Result<(), StoreError>
 where {
+        self.db.check_writable()?;
         *self.cached_item.write() = None;
         writer.delete(&self.key)?;
         Ok(())
@@ -64,6 +69,7 @@ where {
         T: Clone + Serialize + DeserializeOwned,
         F: Fn(T) -> T,
     {
+        self.db.check_writable()?;
         let mut guard = self.cached_item.write();
         let mut item = if let Some(item) = guard.take() {
             item
@@ -117,7 +123,9 @@ where
         }
         let set = self.access.bucket_iterator(EmptyKey).collect::<Result<HashSet<_, _>, _>>()?;
         let set = Arc::new(RwLock::new(set));
-        self.cached_set.write().replace(set.clone());
+        if !self.access.db().is_secondary() {
+            self.cached_set.write().replace(set.clone());
+        }
         Ok(set)
     }
 
@@ -36,6 +36,8 @@ pub enum DatabaseStorePrefixes {
     UtxoMultisets = 26,
     VirtualUtxoset = 27,
     VirtualState = 28,
+    QuarantinedStores = 29,
+    SpentUtxoEntries = 32,
 
     // ---- Decomposed reachability stores ----
     ReachabilityTreeChildren = 30,
@@ -44,15 +46,19 @@ pub enum DatabaseStorePrefixes {
     // ---- Metadata ----
     MultiConsensusMetadata = 124,
     ConsensusEntries = 125,
+    ConsensusLifecycle = 126,
 
     // ---- Components ----
     Addresses = 128,
     BannedAddresses = 129,
+    OnionAddresses = 130,
+    ManualPeers = 131,
 
     // ---- Indexes ----
     UtxoIndex = 192,
     UtxoIndexTips = 193,
     CirculatingSupply = 194,
+    AddressActivity = 195,
 
     // ---- Separator ----
     /// Reserved as a separator
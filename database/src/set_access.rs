@@ -32,7 +32,8 @@ impl<T> From<T> for ReadLock<T> {
     }
 }
 
-/// A concurrent DB store for **set** access with typed caching.
+/// A concurrent DB store for **set** access with typed caching. Like [`crate::prelude::CachedDbAccess`],
+/// it does not cache and fails writing over a secondary DB instance.
 #[derive(Clone)]
 pub struct CachedDbSetAccess<TKey, TData, S = RandomState, W = RandomState>
 where
@@ -55,6 +56,7 @@ where
     W: BuildHasher + Default + Send + Sync,
 {
     pub fn new(db: Arc<DB>, cache_policy: CachePolicy, prefix: Vec<u8>) -> Self {
+        let cache_policy = if db.is_secondary() { CachePolicy::Empty } else { cache_policy };
         Self { inner: DbSetAccess::new(db, prefix), cache: Cache::new(cache_policy) }
     }
 
@@ -130,6 +132,7 @@ where
     }
 
     pub fn write(&self, mut writer: impl DbWriter, key: TKey, data: TData) -> Result<(), StoreError> {
+        self.db.check_writable()?;
         writer.put(self.get_db_key(&key, &data)?, [])?;
         Ok(())
     }
@@ -140,6 +143,7 @@ where
     }
 
     pub fn delete_bucket(&self, mut writer: impl DbWriter, key: TKey) -> Result<(), StoreError> {
+        self.db.check_writable()?;
         let db_key = DbKey::new_with_bucket(&self.prefix, &key, []);
         let (from, to) = rocksdb::PrefixRange(db_key.as_ref()).into_bounds();
         writer.delete_range(from.unwrap(), to.unwrap())?;
@@ -147,6 +151,7 @@ where
     }
 
     pub fn delete(&self, mut writer: impl DbWriter, key: TKey, data: TData) -> Result<(), StoreError> {
+        self.db.check_writable()?;
         writer.delete(self.get_db_key(&key, &data)?)?;
         Ok(())
     }
@@ -181,6 +186,10 @@ where
         &self.prefix
     }
 
+    pub(crate) fn db(&self) -> &DB {
+        &self.db
+    }
+
     pub fn bucket_iterator(&self, key: TKey) -> impl Iterator<Item = Result<TData, StoreError>> + '_
     where
         TKey: Clone + AsRef<[u8]>,
@@ -52,3 +52,35 @@ impl UtxoChanges {
         Self { added, removed }
     }
 }
+
+/// The on-chain activity of a [`ScriptPublicKey`], as observed by the utxoindex: the lowest and highest
+/// DAA scores of the UTXOs it received, also accounting for the DAA scores at which its UTXOs were spent.
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct AddressActivity {
+    pub first_seen_daa_score: u64,
+    pub last_activity_daa_score: u64,
+}
+
+impl AddressActivity {
+    /// Creates a new [`AddressActivity`] from a single activity at `daa_score`
+    pub fn new(daa_score: u64) -> Self {
+        Self { first_seen_daa_score: daa_score, last_activity_daa_score: daa_score }
+    }
+
+    /// Widens the activity range so it covers `daa_score`
+    pub fn observe(&mut self, daa_score: u64) {
+        self.first_seen_daa_score = self.first_seen_daa_score.min(daa_score);
+        self.last_activity_daa_score = self.last_activity_daa_score.max(daa_score);
+    }
+
+    /// Widens the activity range so it covers `other`
+    pub fn merge(&mut self, other: &AddressActivity) {
+        self.observe(other.first_seen_daa_score);
+        self.observe(other.last_activity_daa_score);
+    }
+}
+
+impl MemSizeEstimator for AddressActivity {}
+
+/// A map of on-chain activity by script public key
+pub type ActivityByScriptPublicKey = HashMap<ScriptPublicKey, AddressActivity>;
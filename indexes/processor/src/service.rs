@@ -50,7 +50,7 @@ impl IndexService {
 
         // Manually subscribe to index-processor related event types
         consensus_notifier
-            .try_start_notify(consensus_notify_listener_id, UtxosChangedScope::default().into())
+            .try_start_notify(consensus_notify_listener_id, UtxosChangedScope::wildcard().into())
             .expect("the subscription always succeeds");
         consensus_notifier
             .try_start_notify(consensus_notify_listener_id, PruningPointUtxoSetOverrideScope::default().into())
@@ -6,7 +6,7 @@ use kaspa_consensus_core::{
 use kaspa_consensusmanager::spawn_blocking;
 use kaspa_database::prelude::StoreResult;
 use kaspa_hashes::Hash;
-use kaspa_index_core::indexed_utxos::BalanceByScriptPublicKey;
+use kaspa_index_core::indexed_utxos::{ActivityByScriptPublicKey, BalanceByScriptPublicKey};
 use parking_lot::RwLock;
 use std::{collections::HashSet, fmt::Debug, sync::Arc};
 
@@ -29,6 +29,12 @@ pub trait UtxoIndexApi: Send + Sync + Debug {
 
     fn get_balance_by_script_public_keys(&self, script_public_keys: ScriptPublicKeys) -> StoreResult<BalanceByScriptPublicKey>;
 
+    /// Retrieve the activity records of script public keys from the utxoindex db. Script public keys
+    /// which never received a utxo since the utxoindex was first synced are left out.
+    ///
+    /// Note: Use a read lock when accessing this method
+    fn get_activity_by_script_public_keys(&self, script_public_keys: ScriptPublicKeys) -> StoreResult<ActivityByScriptPublicKey>;
+
     // This can have a big memory footprint, so it should be used only for tests.
     fn get_all_outpoints(&self) -> StoreResult<HashSet<TransactionOutpoint>>;
 
@@ -81,6 +87,13 @@ impl UtxoIndexProxy {
         spawn_blocking(move || self.inner.read().get_balance_by_script_public_keys(script_public_keys)).await.unwrap()
     }
 
+    pub async fn get_activity_by_script_public_keys(
+        self,
+        script_public_keys: ScriptPublicKeys,
+    ) -> StoreResult<ActivityByScriptPublicKey> {
+        spawn_blocking(move || self.inner.read().get_activity_by_script_public_keys(script_public_keys)).await.unwrap()
+    }
+
     pub async fn update(self, utxo_diff: Arc<UtxoDiff>, tips: Arc<Vec<Hash>>) -> UtxoIndexResult<UtxoChanges> {
         spawn_blocking(move || self.inner.write().update(utxo_diff, tips)).await.unwrap()
     }
@@ -11,7 +11,7 @@ use kaspa_consensusmanager::{ConsensusManager, ConsensusResetHandler};
 use kaspa_core::{info, trace};
 use kaspa_database::prelude::{StoreError, StoreResult, DB};
 use kaspa_hashes::Hash;
-use kaspa_index_core::indexed_utxos::BalanceByScriptPublicKey;
+use kaspa_index_core::indexed_utxos::{ActivityByScriptPublicKey, BalanceByScriptPublicKey};
 use kaspa_utils::arc::ArcExtensions;
 use parking_lot::RwLock;
 use std::{
@@ -64,6 +64,13 @@ impl UtxoIndexApi for UtxoIndex {
         self.store.get_balance_by_script_public_key(script_public_keys)
     }
 
+    /// Retrieve the activity records of script public keys from the utxoindex db.
+    fn get_activity_by_script_public_keys(&self, script_public_keys: ScriptPublicKeys) -> StoreResult<ActivityByScriptPublicKey> {
+        trace!("[{0}] retrieving activity of {1} script public keys", IDENT, script_public_keys.len());
+
+        self.store.get_activity_by_script_public_key(script_public_keys)
+    }
+
     /// Retrieve the stored tips of the utxoindex.
     fn get_utxo_index_tips(&self) -> StoreResult<Arc<BlockHashSet>> {
         trace!("[{0}] retrieving tips", IDENT);
@@ -87,6 +94,9 @@ impl UtxoIndexApi for UtxoIndex {
         // Commit changed utxo state to db
         self.store.update_utxo_state(&utxoindex_changes.utxo_changes.added, &utxoindex_changes.utxo_changes.removed, false)?;
 
+        // Commit the activity of the affected script public keys to db
+        self.store.update_activity(&utxoindex_changes.activity)?;
+
         // Commit circulating supply change (if monotonic) to db.
         if utxoindex_changes.supply_change > 0 {
             //we force monotonic here
@@ -160,6 +170,7 @@ impl UtxoIndexApi for UtxoIndex {
             circulating_supply += utxoindex_changes.supply_change as CirculatingSupply;
 
             self.store.update_utxo_state(&utxoindex_changes.utxo_changes.added, &utxoindex_changes.utxo_changes.removed, true)?;
+            self.store.update_activity(&utxoindex_changes.activity)?;
 
             if current_chunk_size < RESYNC_CHUNK_SIZE {
                 break;
@@ -213,7 +224,12 @@ impl ConsensusResetHandler for UtxoIndexConsensusResetHandler {
 
 #[cfg(test)]
 mod tests {
-    use crate::{api::UtxoIndexApi, model::CirculatingSupply, testutils::virtual_change_emulator::VirtualChangeEmulator, UtxoIndex};
+    use crate::{
+        api::UtxoIndexApi,
+        model::{ActivityByScriptPublicKey, AddressActivity, CirculatingSupply},
+        testutils::virtual_change_emulator::VirtualChangeEmulator,
+        UtxoIndex,
+    };
     use kaspa_consensus::{
         config::Config,
         consensus::test_consensus::TestConsensus,
@@ -225,6 +241,7 @@ mod tests {
     };
     use kaspa_consensus_core::{
         api::ConsensusApi,
+        tx::{ScriptPublicKey, TransactionOutpoint, UtxoEntry},
         utxo::{utxo_collection::UtxoCollection, utxo_diff::UtxoDiff},
     };
     use kaspa_consensusmanager::ConsensusManager;
@@ -381,4 +398,51 @@ mod tests {
         drop(utxoindex);
         drop(tc);
     }
+
+    #[test]
+    fn test_utxoindex_address_activity() {
+        let (_utxoindex_db_lifetime, utxoindex_db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        let config = Config::new(DEVNET_PARAMS);
+        let tc = Arc::new(TestConsensus::new(&config));
+        let consensus_manager = Arc::new(ConsensusManager::from_consensus(tc.consensus_clone()));
+        let utxoindex = UtxoIndex::new(consensus_manager, utxoindex_db).unwrap();
+
+        let script_public_key = |i: u8| ScriptPublicKey::from_vec(0, vec![i; 34]);
+        let (funded, other, unused) = (script_public_key(1), script_public_key(2), script_public_key(3));
+        let outpoint = |i: u64| TransactionOutpoint::new(i.into(), 0);
+        let tips = Arc::new(tc.get_virtual_parents().into_iter().collect::<Vec<_>>());
+        let update = |add: &[(TransactionOutpoint, UtxoEntry)], remove: &[(TransactionOutpoint, UtxoEntry)]| {
+            let utxo_diff =
+                UtxoDiff::new(UtxoCollection::from_iter(add.iter().cloned()), UtxoCollection::from_iter(remove.iter().cloned()));
+            utxoindex.write().update(Arc::new(utxo_diff), tips.clone()).unwrap();
+        };
+        let activity = || {
+            utxoindex
+                .read()
+                .get_activity_by_script_public_keys(HashSet::from_iter([funded.clone(), other.clone(), unused.clone()]))
+                .unwrap()
+        };
+
+        // Fund an address at DAA scores 10 and 15, then fully spend it at DAA score 20
+        let funding = [
+            (outpoint(1), UtxoEntry::new(100, funded.clone(), 10, false)),
+            (outpoint(2), UtxoEntry::new(200, funded.clone(), 15, false)),
+        ];
+        update(&funding, &[]);
+        update(&[(outpoint(3), UtxoEntry::new(300, other.clone(), 20, false))], &funding);
+
+        let utxos = utxoindex.read().get_utxos_by_script_public_keys(HashSet::from_iter([funded.clone()])).unwrap();
+        assert!(utxos.values().all(|utxos| utxos.is_empty()));
+
+        // The spent address is still reported as used, along with the recipient of the spending transaction
+        let expected = ActivityByScriptPublicKey::from_iter([
+            (funded.clone(), AddressActivity { first_seen_daa_score: 10, last_activity_daa_score: 20 }),
+            (other.clone(), AddressActivity::new(20)),
+        ]);
+        assert_eq!(activity(), expected);
+
+        // The activity records survive a resync, although the utxos they relate to are gone from the consensus utxo set
+        utxoindex.write().resync().unwrap();
+        assert_eq!(activity(), expected);
+    }
 }
@@ -0,0 +1,83 @@
+use crate::core::model::{ActivityByScriptPublicKey, AddressActivity};
+use crate::stores::indexed_utxos::ScriptPublicKeyBucket;
+
+use kaspa_consensus_core::tx::ScriptPublicKeys;
+use kaspa_database::prelude::{CachePolicy, CachedDbAccess, DirectDbWriter, StoreError, StoreResult, DB};
+use kaspa_database::registry::DatabaseStorePrefixes;
+use std::sync::Arc;
+
+// Traits:
+
+pub trait AddressActivityStoreReader {
+    /// Get the [AddressActivity] of the queried [ScriptPublicKeys], leaving out the ones without activity.
+    fn get_activity_from_script_public_keys(&self, script_public_keys: ScriptPublicKeys) -> StoreResult<ActivityByScriptPublicKey>;
+}
+
+pub trait AddressActivityStore: AddressActivityStoreReader {
+    /// Merges `activity` into the stored activity of the script public keys.
+    fn update_activity(&mut self, activity: &ActivityByScriptPublicKey) -> StoreResult<()>;
+}
+
+// Implementations:
+
+/// Activity records keyed by [ScriptPublicKeyBucket].
+///
+/// Unlike the utxo set, the records only ever widen, so they keep reporting script public keys whose
+/// utxos were all spent. For the same reason they are preserved when the utxoindex is resynced.
+#[derive(Clone)]
+pub struct DbAddressActivityStore {
+    db: Arc<DB>,
+    access: CachedDbAccess<ScriptPublicKeyBucket, AddressActivity>,
+}
+
+impl DbAddressActivityStore {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, DatabaseStorePrefixes::AddressActivity.into()) }
+    }
+
+    fn get(&self, bucket: ScriptPublicKeyBucket) -> StoreResult<Option<AddressActivity>> {
+        match self.access.read(bucket) {
+            Ok(activity) => Ok(Some(activity)),
+            Err(StoreError::KeyNotFound(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl AddressActivityStoreReader for DbAddressActivityStore {
+    fn get_activity_from_script_public_keys(&self, script_public_keys: ScriptPublicKeys) -> StoreResult<ActivityByScriptPublicKey> {
+        let mut activity_by_script_public_key = ActivityByScriptPublicKey::new();
+        for script_public_key in script_public_keys.into_iter() {
+            if let Some(activity) = self.get(ScriptPublicKeyBucket::from(&script_public_key))? {
+                activity_by_script_public_key.insert(script_public_key, activity);
+            }
+        }
+        Ok(activity_by_script_public_key)
+    }
+}
+
+impl AddressActivityStore for DbAddressActivityStore {
+    fn update_activity(&mut self, activity: &ActivityByScriptPublicKey) -> StoreResult<()> {
+        if activity.is_empty() {
+            return Ok(());
+        }
+
+        let mut to_write = Vec::with_capacity(activity.len());
+        for (script_public_key, activity) in activity.iter() {
+            let bucket = ScriptPublicKeyBucket::from(script_public_key);
+            let mut merged = *activity;
+            if let Some(stored) = self.get(bucket.clone())? {
+                if stored.first_seen_daa_score <= merged.first_seen_daa_score
+                    && stored.last_activity_daa_score >= merged.last_activity_daa_score
+                {
+                    continue;
+                }
+                merged.merge(&stored);
+            }
+            to_write.push((bucket, merged));
+        }
+
+        let mut writer = DirectDbWriter::new(&self.db);
+        self.access.write_many(&mut writer, &mut to_write.into_iter())
+    }
+}
@@ -1,3 +1,4 @@
+mod activity;
 mod indexed_utxos;
 pub mod store_manager;
 mod supply;
@@ -6,11 +6,12 @@ use kaspa_consensus_core::{
 };
 use kaspa_core::trace;
 use kaspa_database::prelude::{CachePolicy, StoreResult, DB};
-use kaspa_index_core::indexed_utxos::BalanceByScriptPublicKey;
+use kaspa_index_core::indexed_utxos::{ActivityByScriptPublicKey, BalanceByScriptPublicKey};
 
 use crate::{
     model::UtxoSetByScriptPublicKey,
     stores::{
+        activity::{AddressActivityStore, AddressActivityStoreReader, DbAddressActivityStore},
         indexed_utxos::{DbUtxoSetByScriptPublicKeyStore, UtxoSetByScriptPublicKeyStore, UtxoSetByScriptPublicKeyStoreReader},
         supply::{CirculatingSupplyStore, CirculatingSupplyStoreReader, DbCirculatingSupplyStore},
         tips::{DbUtxoIndexTipsStore, UtxoIndexTipsStore, UtxoIndexTipsStoreReader},
@@ -18,11 +19,14 @@ use crate::{
     IDENT,
 };
 
+const ADDRESS_ACTIVITY_CACHE_SIZE: usize = 10_000;
+
 #[derive(Clone)]
 pub struct Store {
     utxoindex_tips_store: DbUtxoIndexTipsStore,
     circulating_supply_store: DbCirculatingSupplyStore,
     utxos_by_script_public_key_store: DbUtxoSetByScriptPublicKeyStore,
+    address_activity_store: DbAddressActivityStore,
 }
 
 impl Store {
@@ -30,7 +34,8 @@ impl Store {
         Self {
             utxoindex_tips_store: DbUtxoIndexTipsStore::new(db.clone()),
             circulating_supply_store: DbCirculatingSupplyStore::new(db.clone()),
-            utxos_by_script_public_key_store: DbUtxoSetByScriptPublicKeyStore::new(db, CachePolicy::Empty),
+            utxos_by_script_public_key_store: DbUtxoSetByScriptPublicKeyStore::new(db.clone(), CachePolicy::Empty),
+            address_activity_store: DbAddressActivityStore::new(db, CachePolicy::Count(ADDRESS_ACTIVITY_CACHE_SIZE)),
         }
     }
 
@@ -42,6 +47,15 @@ impl Store {
         self.utxos_by_script_public_key_store.get_balance_from_script_public_keys(script_public_keys)
     }
 
+    pub fn get_activity_by_script_public_key(&self, script_public_keys: ScriptPublicKeys) -> StoreResult<ActivityByScriptPublicKey> {
+        self.address_activity_store.get_activity_from_script_public_keys(script_public_keys)
+    }
+
+    /// Merges `activity` into the activity records, which are preserved by [`Self::delete_all`]
+    pub fn update_activity(&mut self, activity: &ActivityByScriptPublicKey) -> StoreResult<()> {
+        self.address_activity_store.update_activity(activity)
+    }
+
     // This can have a big memory footprint, so it should be used only for tests.
     pub fn get_all_outpoints(&self) -> StoreResult<HashSet<TransactionOutpoint>> {
         self.utxos_by_script_public_key_store.get_all_outpoints()
@@ -103,7 +117,7 @@ impl Store {
         res
     }
 
-    /// Resets the utxoindex database:
+    /// Resets the utxoindex database, except for the activity records:
     pub fn delete_all(&mut self) -> StoreResult<()> {
         // TODO: explore possibility of deleting and replacing whole db, currently there is an issue because of file lock and db being in an arc.
         trace!("[{0}] attempting to clear utxoindex database...", IDENT);
@@ -1,17 +1,20 @@
 use kaspa_consensus_core::{
-    tx::{TransactionOutpoint, UtxoEntry},
+    tx::{ScriptPublicKey, TransactionOutpoint, UtxoEntry},
     utxo::utxo_diff::UtxoDiff,
     BlockHashSet, HashMapCustomHasher,
 };
 use kaspa_hashes::Hash;
 use kaspa_utils::hashmap::NestedHashMapExtensions;
 
-use crate::model::{CirculatingSupplyDiff, CompactUtxoEntry, UtxoChanges, UtxoSetByScriptPublicKey};
+use crate::model::{
+    ActivityByScriptPublicKey, AddressActivity, CirculatingSupplyDiff, CompactUtxoEntry, UtxoChanges, UtxoSetByScriptPublicKey,
+};
 
 /// A struct holding all changes to the utxoindex with on-the-fly conversions and processing.
 pub struct UtxoIndexChanges {
     pub utxo_changes: UtxoChanges,
     pub supply_change: CirculatingSupplyDiff,
+    pub activity: ActivityByScriptPublicKey,
     pub tips: BlockHashSet,
 }
 
@@ -21,6 +24,7 @@ impl UtxoIndexChanges {
         Self {
             utxo_changes: UtxoChanges::new(UtxoSetByScriptPublicKey::new(), UtxoSetByScriptPublicKey::new()),
             supply_change: 0,
+            activity: ActivityByScriptPublicKey::new(),
             tips: BlockHashSet::new(),
         }
     }
@@ -29,6 +33,18 @@ impl UtxoIndexChanges {
     pub fn update_utxo_diff(&mut self, utxo_diff: UtxoDiff) {
         let (to_add, mut to_remove) = (utxo_diff.add, utxo_diff.remove);
 
+        // The spending transactions of the removed utxos are accepted along with the creation of the added ones,
+        // so the latest DAA score of the added utxos stands for the DAA score at which the removed ones were spent.
+        let spending_daa_score = to_add.values().map(|utxo_entry| utxo_entry.block_daa_score).max();
+        for utxo_entry in to_add.values().chain(to_remove.values()) {
+            self.observe_activity(&utxo_entry.script_public_key, utxo_entry.block_daa_score);
+        }
+        if let Some(spending_daa_score) = spending_daa_score {
+            for utxo_entry in to_remove.values() {
+                self.observe_activity(&utxo_entry.script_public_key, spending_daa_score);
+            }
+        }
+
         for (transaction_outpoint, utxo_entry) in to_add.into_iter() {
             if to_remove.remove(&transaction_outpoint).is_some() {
                 continue;
@@ -59,6 +75,7 @@ impl UtxoIndexChanges {
     pub fn add_utxos_from_vector(&mut self, utxo_vector: Vec<(TransactionOutpoint, UtxoEntry)>) {
         for (transaction_outpoint, utxo_entry) in utxo_vector.into_iter() {
             self.supply_change += utxo_entry.amount as CirculatingSupplyDiff;
+            self.observe_activity(&utxo_entry.script_public_key, utxo_entry.block_daa_score);
 
             self.utxo_changes.added.insert_into_nested(
                 utxo_entry.script_public_key,
@@ -68,6 +85,15 @@ impl UtxoIndexChanges {
         }
     }
 
+    fn observe_activity(&mut self, script_public_key: &ScriptPublicKey, daa_score: u64) {
+        match self.activity.get_mut(script_public_key) {
+            Some(activity) => activity.observe(daa_score),
+            None => {
+                self.activity.insert(script_public_key.clone(), AddressActivity::new(daa_score));
+            }
+        }
+    }
+
     pub fn set_tips(&mut self, tips: Vec<Hash>) {
         self.tips = BlockHashSet::from_iter(tips);
     }
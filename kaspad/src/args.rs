@@ -1,15 +1,21 @@
+use crate::devcluster::Topology;
 use clap::{arg, Arg, ArgAction, Command};
 use kaspa_consensus_core::{
-    config::Config,
+    config::{checkpoints::Checkpoint, Config},
+    errors::config::{ConfigError, ConfigResult},
     network::{NetworkId, NetworkType},
 };
-use kaspa_core::kaspad_env::version;
+use kaspa_core::{kaspad_env::version, log::LogFormat};
+use kaspa_database::prelude::WalSyncPolicy;
+use kaspa_mining::mempool::config::{DustRelayThresholds, TemplateMassQuota};
 use kaspa_notify::address::tracker::Tracker;
+use kaspa_p2p_lib::{KeepaliveConfig, RequestCostLimits};
+use kaspa_txscript::script_class::ScriptClass;
 use kaspa_utils::networking::ContextualNetAddress;
-use kaspa_wrpc_server::address::WrpcNetAddress;
+use kaspa_wrpc_server::{address::WrpcNetAddress, service::DEFAULT_MAX_WRPC_MESSAGE_SIZE};
 use serde::Deserialize;
 use serde_with::{serde_as, DisplayFromStr};
-use std::{ffi::OsString, fs};
+use std::{collections::BTreeMap, ffi::OsString, fs, time::Duration};
 use toml::from_str;
 
 #[cfg(feature = "devnet-prealloc")]
@@ -39,9 +45,23 @@ pub struct Args {
     #[serde(rename = "unsaferpc")]
     pub unsafe_rpc: bool,
     pub wrpc_verbose: bool,
+    pub wrpc_max_inbound_message_size: usize,
+    pub wrpc_max_outbound_message_size: Option<usize>,
+    /// Latency in milliseconds above which RPC calls are logged as slow along with their trace id
+    pub rpc_slow_call_threshold: Option<u64>,
     #[serde(rename = "loglevel")]
     pub log_level: String,
+    #[serde(rename = "logformat")]
+    #[serde_as(as = "DisplayFromStr")]
+    pub log_format: LogFormat,
     pub async_threads: usize,
+    /// Maximum number of blocks validated concurrently by the header and body processors
+    pub processing_parallelism: usize,
+    /// Executed opcodes are counted for 1 in this many transactions whose scripts get verified, 0 disabling the counting
+    pub opcode_metrics_sample_rate: u64,
+    /// Which database writes wait for the write-ahead log to be synced to disk
+    #[serde_as(as = "DisplayFromStr")]
+    pub db_wal_sync: WalSyncPolicy,
     #[serde(rename = "connect")]
     #[serde_as(as = "Vec<DisplayFromStr>")]
     pub connect_peers: Vec<ContextualNetAddress>,
@@ -53,6 +73,21 @@ pub struct Args {
     #[serde(rename = "uacomment")]
     pub user_agent_comments: Vec<String>,
     pub utxoindex: bool,
+    pub store_spent_utxos: bool,
+    pub hold_time_locked_txs: bool,
+    /// Minimum value (in sompi) of relayed transaction outputs, replacing the fee-based dust rule
+    pub dust_relay_threshold: Option<u64>,
+    /// Minimum value (in sompi) of relayed transaction outputs by script class (e.g. `scripthash = 0`),
+    /// taking precedence over `dust_relay_threshold`. Only settable through the config file.
+    pub dust_relay_threshold_by_class: BTreeMap<String, u64>,
+    /// Maximum fraction of the block template mass spent from a single script public key, uncapped if unset
+    pub template_origin_mass_quota: Option<f64>,
+    /// Maximum fraction of the block template fees given up to honor `template_origin_mass_quota`
+    pub template_quota_fee_tolerance: f64,
+    /// Flag submitted blocks whose transactions are not in the canonical block template order
+    pub check_template_tx_order: bool,
+    pub reject_legacy_peers: bool,
+    pub skip_ibd_merkle_check: bool,
     pub reset_db: bool,
     #[serde(rename = "outpeers")]
     pub outbound_target: usize,
@@ -73,9 +108,14 @@ pub struct Args {
     pub yes: bool,
     #[serde_as(as = "Option<DisplayFromStr>")]
     pub externalip: Option<ContextualNetAddress>,
+    /// SOCKS5 proxy (`host:port`) outbound connections are routed through
+    pub proxy: Option<String>,
+    /// Only route onion addresses through the proxy
+    pub proxy_onion_only: bool,
     pub perf_metrics: bool,
     pub perf_metrics_interval_sec: u64,
     pub block_template_cache_lifetime: Option<u64>,
+    pub notification_journal_size: usize,
 
     #[cfg(feature = "devnet-prealloc")]
     pub num_prealloc_utxos: Option<u64>,
@@ -84,12 +124,85 @@ pub struct Args {
     #[cfg(feature = "devnet-prealloc")]
     pub prealloc_amount: u64,
 
+    /// Address the built-in stratum server listens on, if enabled
+    #[cfg(feature = "stratum")]
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub stratum_listen: Option<ContextualNetAddress>,
+
     pub disable_upnp: bool,
     #[serde(rename = "nodnsseed")]
     pub disable_dns_seeding: bool,
     #[serde(rename = "nogrpc")]
     pub disable_grpc: bool,
     pub ram_scale: f64,
+    pub p2p_max_anticone_size: usize,
+    pub p2p_max_locator_steps: usize,
+    pub p2p_serving_window_sec: u64,
+    pub p2p_max_served_bytes: u64,
+    pub p2p_max_served_cost: u64,
+    pub p2p_serving_disconnect_factor: u64,
+    pub p2p_ping_interval_sec: u64,
+    /// Defaults to a timeout scaled down by the block rate of the network
+    pub p2p_idle_timeout_sec: Option<u64>,
+    pub p2p_pong_timeout_sec: u64,
+    pub p2p_max_missed_pongs: u32,
+    /// Run despite a soft file descriptor limit below the minimum
+    pub ignore_fd_limit: bool,
+    /// Run despite the free disk space of the data directory being below the minimum
+    pub ignore_low_disk_space: bool,
+    /// Run despite the local clock being too far off the clocks of the connected peers
+    pub ignore_clock_skew: bool,
+    /// Pruning point checkpoints (`<DAA score>:<block hash>`) replacing the hard-coded ones of the network
+    #[serde(rename = "checkpoint")]
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    pub checkpoints: Vec<Checkpoint>,
+    /// Do not validate the pruning points chain of syncer peers against any checkpoint
+    #[serde(rename = "nocheckpoints")]
+    pub disable_checkpoints: bool,
+    /// Set by the `backup` subcommand, which backs up a running node (or verifies a backup) instead of running the node
+    #[serde(skip)]
+    pub backup: Option<BackupCommand>,
+    /// Set by the `checkpoints` subcommand, which prints the recommended checkpoints of a running node instead of running the node
+    #[serde(skip)]
+    pub checkpoints_command: Option<CheckpointsCommand>,
+    /// Set by the `devcluster` subcommand, which runs several connected nodes in the process instead of a single node
+    #[serde(skip)]
+    pub devcluster: Option<DevClusterCommand>,
+    /// Set by the `consensus` subcommand, which manages the consensus databases of a stopped node instead of running the node
+    #[serde(skip)]
+    pub consensus_command: Option<ConsensusCommand>,
+}
+
+/// Arguments of the `backup` subcommand
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupCommand {
+    /// Directory of the backup
+    pub output: String,
+    /// Verify the existing backup at `output` rather than creating one
+    pub verify: bool,
+}
+
+/// Arguments of the `checkpoints` subcommand
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckpointsCommand {
+    /// Maximum number of checkpoints to print
+    pub count: usize,
+}
+
+/// Subcommands of the `consensus` subcommand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsensusCommand {
+    /// Delete the staging and retired consensus entries, reclaiming their disk space
+    Cleanup,
+}
+
+/// Arguments of the `devcluster` subcommand
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DevClusterCommand {
+    /// Number of nodes of the cluster
+    pub nodes: usize,
+    /// How the nodes are connected to each other
+    pub topology: Topology,
 }
 
 impl Default for Args {
@@ -101,7 +214,19 @@ impl Default for Args {
             rpclisten_json: None,
             unsafe_rpc: false,
             async_threads: num_cpus::get(),
+            processing_parallelism: num_cpus::get(),
+            opcode_metrics_sample_rate: kaspa_txscript::metrics::DEFAULT_OPCODE_SAMPLE_RATE,
+            db_wal_sync: WalSyncPolicy::Never,
             utxoindex: false,
+            store_spent_utxos: false,
+            hold_time_locked_txs: false,
+            dust_relay_threshold: None,
+            dust_relay_threshold_by_class: BTreeMap::new(),
+            template_origin_mass_quota: None,
+            template_quota_fee_tolerance: 0.05,
+            check_template_tx_order: false,
+            reject_legacy_peers: false,
+            skip_ibd_merkle_check: false,
             reset_db: false,
             outbound_target: 8,
             inbound_limit: 128,
@@ -118,7 +243,11 @@ impl Default for Args {
             logdir: None,
             rpclisten: None,
             wrpc_verbose: false,
+            wrpc_max_inbound_message_size: DEFAULT_MAX_WRPC_MESSAGE_SIZE,
+            wrpc_max_outbound_message_size: None,
+            rpc_slow_call_threshold: None,
             log_level: "INFO".into(),
+            log_format: LogFormat::Text,
             connect_peers: vec![],
             add_peers: vec![],
             listen: None,
@@ -127,7 +256,10 @@ impl Default for Args {
             perf_metrics: false,
             perf_metrics_interval_sec: 10,
             externalip: None,
+            proxy: None,
+            proxy_onion_only: false,
             block_template_cache_lifetime: None,
+            notification_journal_size: 0,
 
             #[cfg(feature = "devnet-prealloc")]
             num_prealloc_utxos: None,
@@ -136,10 +268,32 @@ impl Default for Args {
             #[cfg(feature = "devnet-prealloc")]
             prealloc_amount: 1_000_000,
 
+            #[cfg(feature = "stratum")]
+            stratum_listen: None,
+
             disable_upnp: false,
             disable_dns_seeding: false,
             disable_grpc: false,
             ram_scale: 1.0,
+            p2p_max_anticone_size: RequestCostLimits::DEFAULT_MAX_ANTICONE_SIZE,
+            p2p_max_locator_steps: RequestCostLimits::DEFAULT_MAX_LOCATOR_STEPS,
+            p2p_serving_window_sec: RequestCostLimits::DEFAULT_WINDOW.as_secs(),
+            p2p_max_served_bytes: RequestCostLimits::DEFAULT_MAX_SERVED_BYTES,
+            p2p_max_served_cost: RequestCostLimits::DEFAULT_MAX_SERVED_COST,
+            p2p_serving_disconnect_factor: RequestCostLimits::DEFAULT_DISCONNECT_FACTOR,
+            p2p_ping_interval_sec: KeepaliveConfig::DEFAULT_PING_INTERVAL.as_secs(),
+            p2p_idle_timeout_sec: None,
+            p2p_pong_timeout_sec: KeepaliveConfig::DEFAULT_PONG_TIMEOUT.as_secs(),
+            p2p_max_missed_pongs: KeepaliveConfig::DEFAULT_MAX_MISSED_PONGS,
+            ignore_fd_limit: false,
+            ignore_low_disk_space: false,
+            ignore_clock_skew: false,
+            checkpoints: vec![],
+            disable_checkpoints: false,
+            backup: None,
+            checkpoints_command: None,
+            devcluster: None,
+            consensus_command: None,
         }
     }
 }
@@ -147,8 +301,12 @@ impl Default for Args {
 impl Args {
     pub fn apply_to_config(&self, config: &mut Config) {
         config.utxoindex = self.utxoindex;
+        config.store_spent_utxos = self.store_spent_utxos;
         config.disable_upnp = self.disable_upnp;
         config.unsafe_rpc = self.unsafe_rpc;
+        config.hold_time_locked_txs = self.hold_time_locked_txs;
+        config.reject_legacy_peers = self.reject_legacy_peers;
+        config.skip_ibd_merkle_check = self.skip_ibd_merkle_check;
         config.enable_unsynced_mining = self.enable_unsynced_mining;
         config.enable_mainnet_mining = self.enable_mainnet_mining;
         config.is_archival = self.archival;
@@ -156,9 +314,15 @@ impl Args {
         config.enable_sanity_checks = true;
         config.user_agent_comments.clone_from(&self.user_agent_comments);
         config.block_template_cache_lifetime = self.block_template_cache_lifetime;
+        config.notification_journal_size = self.notification_journal_size;
         config.p2p_listen_address = self.listen.unwrap_or(ContextualNetAddress::unspecified());
         config.externalip = self.externalip.map(|v| v.normalize(config.default_p2p_port()));
         config.ram_scale = self.ram_scale;
+        if self.disable_checkpoints {
+            config.checkpoints.clear();
+        } else if !self.checkpoints.is_empty() {
+            config.checkpoints.clone_from(&self.checkpoints);
+        }
 
         #[cfg(feature = "devnet-prealloc")]
         if let Some(num_prealloc_utxos) = self.num_prealloc_utxos {
@@ -180,6 +344,51 @@ impl Args {
             .collect()
     }
 
+    pub fn request_cost_limits(&self) -> RequestCostLimits {
+        RequestCostLimits {
+            max_anticone_size: self.p2p_max_anticone_size,
+            max_locator_steps: self.p2p_max_locator_steps,
+            window: Duration::from_secs(self.p2p_serving_window_sec),
+            max_served_bytes: self.p2p_max_served_bytes,
+            max_served_cost: self.p2p_max_served_cost,
+            disconnect_factor: self.p2p_serving_disconnect_factor,
+        }
+    }
+
+    /// Returns the keepalive settings of the connections to peers on a network producing `bps` blocks per second
+    pub fn keepalive_config(&self, bps: u64) -> KeepaliveConfig {
+        KeepaliveConfig {
+            ping_interval: Duration::from_secs(self.p2p_ping_interval_sec),
+            idle_timeout: self.p2p_idle_timeout_sec.map_or_else(|| KeepaliveConfig::default_idle_timeout(bps), Duration::from_secs),
+            pong_timeout: Duration::from_secs(self.p2p_pong_timeout_sec),
+            max_missed_pongs: self.p2p_max_missed_pongs,
+        }
+    }
+
+    /// Returns the node-level overrides of the dust rule, failing on unknown script classes
+    pub fn dust_relay_thresholds(&self) -> ConfigResult<DustRelayThresholds> {
+        let by_class = self
+            .dust_relay_threshold_by_class
+            .iter()
+            .map(|(class, threshold)| {
+                let class = class.parse::<ScriptClass>().map_err(|_| ConfigError::InvalidDustRelayScriptClass(class.clone()))?;
+                Ok((class, *threshold))
+            })
+            .collect::<ConfigResult<_>>()?;
+        Ok(DustRelayThresholds::new(self.dust_relay_threshold, by_class))
+    }
+
+    /// Returns the block template mass quota policy if enabled, failing on out of range ratios
+    pub fn template_mass_quota(&self) -> ConfigResult<Option<TemplateMassQuota>> {
+        if !(0.0..=1.0).contains(&self.template_quota_fee_tolerance) {
+            return Err(ConfigError::TemplateQuotaFeeToleranceOutOfRange);
+        }
+        match self.template_origin_mass_quota {
+            Some(ratio) if ratio <= 0.0 || ratio > 1.0 => Err(ConfigError::TemplateOriginMassQuotaOutOfRange),
+            ratio => Ok(ratio.map(|ratio| TemplateMassQuota::new(ratio, self.template_quota_fee_tolerance))),
+        }
+    }
+
     pub fn network(&self) -> NetworkId {
         match (self.testnet, self.devnet, self.simnet) {
             (false, false, false) => NetworkId::new(NetworkType::Mainnet),
@@ -211,6 +420,38 @@ pub fn cli() -> Command {
                 .value_parser(clap::value_parser!(usize))
                 .help(format!("Specify number of async threads (default: {}).", defaults.async_threads)),
         )
+        .arg(
+            Arg::new("processing-parallelism")
+                .long("processing-parallelism")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(usize))
+                .help(format!(
+                    "Maximum number of blocks validated concurrently, adjustable at runtime with the SetProcessingParallelism RPC (default: {}).",
+                    defaults.processing_parallelism
+                )),
+        )
+        .arg(
+            Arg::new("opcode-metrics-sample-rate")
+                .long("opcode-metrics-sample-rate")
+                .value_name("N")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help(format!(
+                    "Count the executed script opcodes of 1 in N verified transactions, 0 disabling the counting (default: {}).",
+                    defaults.opcode_metrics_sample_rate
+                )),
+        )
+        .arg(
+            Arg::new("db-wal-sync")
+                .long("db-wal-sync")
+                .value_name("POLICY")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(WalSyncPolicy))
+                .help(format!(
+                    "Database writes waiting for the write-ahead log to be synced to disk {{never, critical, always}} (default: {}).\n-- critical syncs the writes of block headers, statuses and the virtual state, trading some throughput for durability on host crashes.",
+                    defaults.db_wal_sync
+                )),
+        )
         .arg(
             Arg::new("log_level")
                 .short('d')
@@ -220,6 +461,14 @@ pub fn cli() -> Command {
                 .require_equals(true)
                 .help("Logging level for all subsystems {off, error, warn, info, debug, trace}\n-- You may also specify <subsystem>=<level>,<subsystem2>=<level>,... to set the log level for individual subsystems.".to_string()),
         )
+        .arg(
+            Arg::new("log_format")
+                .long("logformat")
+                .value_name("FORMAT")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(LogFormat))
+                .help("Format of the log lines {text, json} (default: text).\n-- The json format emits one object per line with the ts, level, target and message fields and structured key-values."),
+        )
         .arg(
             Arg::new("rpclisten")
                 .long("rpclisten")
@@ -250,6 +499,27 @@ pub fn cli() -> Command {
                 .value_parser(clap::value_parser!(WrpcNetAddress))
                 .help("Interface:port to listen for wRPC JSON connections (default port: 14412, testnet: 14512)."),
         )
+        .arg(
+            Arg::new("wrpc-max-inbound-message-size")
+                .long("wrpc-max-inbound-message-size")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(usize))
+                .help(format!("Max size in bytes of a message received from a wRPC client. Clients sending larger messages get disconnected (default: {}).", defaults.wrpc_max_inbound_message_size)),
+        )
+        .arg(
+            Arg::new("wrpc-max-outbound-message-size")
+                .long("wrpc-max-outbound-message-size")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(usize))
+                .help("Max size in bytes of a wRPC response. Requests with larger responses fail with an error suggesting to paginate them (default: unlimited)."),
+        )
+        .arg(
+            Arg::new("rpc-slow-call-threshold")
+                .long("rpc-slow-call-threshold")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help("Latency in milliseconds above which gRPC and wRPC calls are logged as slow along with their trace id (default: disabled)."),
+        )
         .arg(arg!(--unsaferpc "Enable RPC commands which affect the state of the node"))
         .arg(
             Arg::new("connect-peers")
@@ -301,6 +571,13 @@ pub fn cli() -> Command {
                 .value_parser(clap::value_parser!(usize))
                 .help("Max number of RPC clients for standard connections (default: 128)."),
         )
+        .arg(
+            Arg::new("notification-journal-size")
+                .long("notification-journal-size")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(usize))
+                .help("Number of recent VirtualChainChanged and UtxosChanged notifications retained for clients to backfill the ones they missed, each notification being stamped with a sequence number. Journaling keeps the node emitting these notifications in full, and should be sized to cover the expected client reconnection delays (default: 0, disabled)."),
+        )
         .arg(arg!(--"reset-db" "Reset database before starting node. It's needed when switching between subnetworks."))
         .arg(arg!(--"enable-unsynced-mining" "Allow the node to accept blocks from RPC while not synced (this flag is mainly used for testing)"))
         .arg(
@@ -311,6 +588,37 @@ pub fn cli() -> Command {
                 .help("Allow mainnet mining (currently enabled by default while the flag is kept for backwards compatibility)"),
         )
         .arg(arg!(--utxoindex "Enable the UTXO index"))
+        .arg(arg!(--"store-spent-utxos" "Store the UTXO entries spent by accepted transactions, enabling the GetUtxoReturnAddress RPC for the chain blocks processed since (increases disk usage)"))
+        .arg(arg!(--"hold-time-locked-txs" "Hold transactions submitted by RPC with allowNotYetValid whose lock time is not reached yet until they become valid"))
+        .arg(
+            Arg::new("dust-relay-threshold")
+                .long("dust-relay-threshold")
+                .value_name("SOMPI")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help("Minimum value of relayed transaction outputs, replacing the default dust rule derived from the minimum relay fee. \
+Per script class thresholds can be set in the config file (eg. `[dust-relay-threshold-by-class]` with `scripthash = 0`)."),
+        )
+        .arg(
+            Arg::new("template-origin-mass-quota")
+                .long("template-origin-mass-quota")
+                .value_name("RATIO")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(f64))
+                .help("Cap the fraction (in (0, 1]) of the block template mass spent from a single script public key, \
+filling the remainder with transactions of other origins. Disabled by default."),
+        )
+        .arg(
+            Arg::new("template-quota-fee-tolerance")
+                .long("template-quota-fee-tolerance")
+                .value_name("RATIO")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(f64))
+                .help("Maximum fraction (in [0, 1]) of the block template fees given up to honor --template-origin-mass-quota (default: 0.05)."),
+        )
+        .arg(arg!(--"check-template-tx-order" "Flag submitted blocks whose transactions are not in the canonical block template order (policy check only, such blocks are still processed)"))
+        .arg(arg!(--"reject-legacy-peers" "Reject peers which do not advertise their consensus params digest and genesis hash during the handshake"))
+        .arg(arg!(--"skip-ibd-merkle-check" "Skip verifying the block bodies received during IBD against their merkle roots before queuing them (only for syncing from trusted peers)"))
         .arg(
             Arg::new("max-tracked-addresses")
                 .long("max-tracked-addresses")
@@ -350,6 +658,14 @@ Setting to 0 prevents the preallocation and sets the maximum to {}, leading to 0
                 .value_parser(clap::value_parser!(ContextualNetAddress))
                 .help("Add a socket address(ip:port) to the list of local addresses we claim to listen on to peers"),
         )
+        .arg(
+            Arg::new("proxy")
+                .long("proxy")
+                .value_name("HOST:PORT")
+                .require_equals(true)
+                .help("Connect to peers via a SOCKS5 proxy (eg. 127.0.0.1:9050 for Tor), resolving DNS seeders through it as well."),
+        )
+        .arg(arg!(--"proxy-onion-only" "Only connect to onion addresses via the proxy, connecting to other peers directly"))
         .arg(arg!(--"perf-metrics" "Enable performance metrics: cpu, memory, disk io usage"))
         .arg(
             Arg::new("perf-metrics-interval-sec")
@@ -358,7 +674,7 @@ Setting to 0 prevents the preallocation and sets the maximum to {}, leading to 0
                 .value_parser(clap::value_parser!(u64))
                 .help("Interval in seconds for performance metrics collection."),
         )
-        .arg(arg!(--"disable-upnp" "Disable upnp"))
+        .arg(arg!(--"disable-upnp" "Disable UPnP and NAT-PMP port mapping"))
         .arg(arg!(--"nodnsseed" "Disable DNS seeding for peers"))
         .arg(arg!(--"nogrpc" "Disable gRPC server"))
         .arg(
@@ -369,6 +685,141 @@ Setting to 0 prevents the preallocation and sets the maximum to {}, leading to 0
                 .help("Apply a scale factor to memory allocation bounds. Nodes with limited RAM (~4-8GB) should set this to ~0.3-0.5 respectively. Nodes with
 a large RAM (~64GB) can set this value to ~3.0-4.0 and gain superior performance especially for syncing peers faster"),
         )
+        .arg(
+            Arg::new("p2p-max-anticone-size")
+                .long("p2p-max-anticone-size")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(usize))
+                .help(format!("Max number of headers served to a peer in response to a single anticone request (default: {}).", defaults.p2p_max_anticone_size)),
+        )
+        .arg(
+            Arg::new("p2p-max-locator-steps")
+                .long("p2p-max-locator-steps")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(usize))
+                .help(format!("Max number of hashes served to a peer in response to a single block locator request (default: {}).", defaults.p2p_max_locator_steps)),
+        )
+        .arg(
+            Arg::new("p2p-serving-window-sec")
+                .long("p2p-serving-window-sec")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help(format!("Length in seconds of the sliding window over which request serving costs are accounted per peer (default: {}).", defaults.p2p_serving_window_sec)),
+        )
+        .arg(
+            Arg::new("p2p-max-served-bytes")
+                .long("p2p-max-served-bytes")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help(format!("Max bytes served to a single peer within the serving window before its requests are throttled (default: {}).", defaults.p2p_max_served_bytes)),
+        )
+        .arg(
+            Arg::new("p2p-max-served-cost")
+                .long("p2p-max-served-cost")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help(format!("Max cost units (roughly store reads) served to a single peer within the serving window before its requests are throttled (default: {}).", defaults.p2p_max_served_cost)),
+        )
+        .arg(
+            Arg::new("p2p-serving-disconnect-factor")
+                .long("p2p-serving-disconnect-factor")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help(format!("Peers exceeding their serving budget by this factor are disconnected rather than throttled (default: {}).", defaults.p2p_serving_disconnect_factor)),
+        )
+        .arg(
+            Arg::new("p2p-ping-interval-sec")
+                .long("p2p-ping-interval-sec")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help(format!("Interval in seconds between two pings to a peer (default: {}).", defaults.p2p_ping_interval_sec)),
+        )
+        .arg(
+            Arg::new("p2p-idle-timeout-sec")
+                .long("p2p-idle-timeout-sec")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help(format!("Time in seconds without any message from a peer after which it is pinged right away (default: {} divided by the network BPS, at least {}).", KeepaliveConfig::BASELINE_IDLE_TIMEOUT.as_secs(), KeepaliveConfig::MIN_IDLE_TIMEOUT.as_secs())),
+        )
+        .arg(
+            Arg::new("p2p-pong-timeout-sec")
+                .long("p2p-pong-timeout-sec")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64))
+                .help(format!("Time in seconds waited for a peer to answer a ping before the ping is considered missed (default: {}).", defaults.p2p_pong_timeout_sec)),
+        )
+        .arg(
+            Arg::new("p2p-max-missed-pongs")
+                .long("p2p-max-missed-pongs")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u32))
+                .help(format!("Number of consecutive unanswered pings after which a peer is disconnected as dead (default: {}).", defaults.p2p_max_missed_pongs)),
+        )
+        .arg(arg!(--"ignore-fd-limit" "Run even if the soft file descriptor limit is below the minimum required by the node"))
+        .arg(arg!(--"ignore-low-disk-space" "Run even if the free disk space of the data directory is below the minimum required by the node"))
+        .arg(arg!(--"ignore-clock-skew" "Keep running even if the local clock is too far off the clocks of the connected peers"))
+        .arg(
+            Arg::new("checkpoints")
+                .long("checkpoint")
+                .value_name("DAA_SCORE:HASH")
+                .action(ArgAction::Append)
+                .require_equals(true)
+                .value_parser(clap::value_parser!(Checkpoint))
+                .help("Pruning point checkpoint the pruning points chain of syncer peers must agree with. Replaces the hard-coded checkpoints of the network when set."),
+        )
+        .arg(arg!(--nocheckpoints "Do not validate the pruning points chain of syncer peers against any checkpoint"))
+        .subcommand(
+            Command::new("backup")
+                .about("Back up the consensus databases of a running node through its gRPC interface (the node must run with --unsaferpc), or verify an existing backup. Node arguments such as the network or --rpclisten go before the subcommand.")
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .value_name("DIR")
+                        .require_equals(true)
+                        .required(true)
+                        .value_parser(clap::value_parser!(String))
+                        .help("Directory of the backup. It must not exist when creating a backup, and must be reachable by the node."),
+                )
+                .arg(arg!(--"verify-backup" "Open the backup at --output read-only and run the consistency checker, instead of creating a backup.")),
+        )
+        .subcommand(
+            Command::new("checkpoints")
+                .about("Print the recommended checkpoints of a synced running node, read from its pruning points through its gRPC interface. Node arguments such as the network or --rpclisten go before the subcommand.")
+                .arg(
+                    Arg::new("count")
+                        .long("count")
+                        .require_equals(true)
+                        .default_value("10")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("Maximum number of checkpoints to print."),
+                ),
+        )
+        .subcommand(
+            Command::new("devcluster")
+                .about("Run several connected simnet or devnet nodes in the process, with isolated app dirs under a temporary directory deleted on exit. Node i listens on the gRPC, wRPC and P2P base ports (set by --rpclisten, --rpclisten-borsh, --rpclisten-json and --listen) plus i. Node arguments go before the subcommand and apply to all the nodes.")
+                .arg(
+                    Arg::new("nodes")
+                        .long("nodes")
+                        .require_equals(true)
+                        .default_value("2")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("Number of nodes of the cluster."),
+                )
+                .arg(
+                    Arg::new("topology")
+                        .long("topology")
+                        .require_equals(true)
+                        .default_value("chain")
+                        .value_parser(clap::value_parser!(Topology))
+                        .help("How the nodes are connected: chain (each node to the previous one), ring (a chain closed on the first node), star (each node to the first one) or mesh (each node to all the others)."),
+                ),
+        )
+        .subcommand(
+            Command::new("consensus")
+                .about("Manage the consensus databases of a stopped node. Node arguments such as the network or --appdir go before the subcommand.")
+                .subcommand_required(true)
+                .subcommand(Command::new("cleanup").about("Complete an interrupted promotion of the staging consensus, then delete the staging consensus and the retired consensus databases awaiting deletion, reclaiming their disk space.")),
+        )
         ;
 
     #[cfg(feature = "devnet-prealloc")]
@@ -377,6 +828,16 @@ a large RAM (~64GB) can set this value to ~3.0-4.0 and gain superior performance
         .arg(Arg::new("prealloc-address").long("prealloc-address").require_equals(true).value_parser(clap::value_parser!(String)))
         .arg(Arg::new("prealloc-amount").long("prealloc-amount").require_equals(true).value_parser(clap::value_parser!(u64)));
 
+    #[cfg(feature = "stratum")]
+    let cmd = cmd.arg(
+        Arg::new("stratum-listen")
+            .long("stratum-listen")
+            .value_name("IP[:PORT]")
+            .require_equals(true)
+            .value_parser(clap::value_parser!(ContextualNetAddress))
+            .help("Interface:port to serve stratum miners on, built-in stratum server is disabled if not set (default port: 5555)."),
+    );
+
     cmd
 }
 
@@ -418,8 +879,26 @@ impl Args {
             rpclisten_json: m.get_one::<WrpcNetAddress>("rpclisten-json").cloned().or(defaults.rpclisten_json),
             unsafe_rpc: arg_match_unwrap_or::<bool>(&m, "unsaferpc", defaults.unsafe_rpc),
             wrpc_verbose: false,
+            wrpc_max_inbound_message_size: arg_match_unwrap_or::<usize>(
+                &m,
+                "wrpc-max-inbound-message-size",
+                defaults.wrpc_max_inbound_message_size,
+            ),
+            wrpc_max_outbound_message_size: m
+                .get_one::<usize>("wrpc-max-outbound-message-size")
+                .cloned()
+                .or(defaults.wrpc_max_outbound_message_size),
+            rpc_slow_call_threshold: m.get_one::<u64>("rpc-slow-call-threshold").cloned().or(defaults.rpc_slow_call_threshold),
             log_level: arg_match_unwrap_or::<String>(&m, "log_level", defaults.log_level),
+            log_format: arg_match_unwrap_or::<LogFormat>(&m, "log_format", defaults.log_format),
             async_threads: arg_match_unwrap_or::<usize>(&m, "async_threads", defaults.async_threads),
+            processing_parallelism: arg_match_unwrap_or::<usize>(&m, "processing-parallelism", defaults.processing_parallelism),
+            opcode_metrics_sample_rate: arg_match_unwrap_or::<u64>(
+                &m,
+                "opcode-metrics-sample-rate",
+                defaults.opcode_metrics_sample_rate,
+            ),
+            db_wal_sync: arg_match_unwrap_or::<WalSyncPolicy>(&m, "db-wal-sync", defaults.db_wal_sync),
             connect_peers: arg_match_many_unwrap_or::<ContextualNetAddress>(&m, "connect-peers", defaults.connect_peers),
             add_peers: arg_match_many_unwrap_or::<ContextualNetAddress>(&m, "add-peers", defaults.add_peers),
             listen: m.get_one::<ContextualNetAddress>("listen").cloned().or(defaults.listen),
@@ -431,6 +910,22 @@ impl Args {
             enable_unsynced_mining: arg_match_unwrap_or::<bool>(&m, "enable-unsynced-mining", defaults.enable_unsynced_mining),
             enable_mainnet_mining: arg_match_unwrap_or::<bool>(&m, "enable-mainnet-mining", defaults.enable_mainnet_mining),
             utxoindex: arg_match_unwrap_or::<bool>(&m, "utxoindex", defaults.utxoindex),
+            store_spent_utxos: arg_match_unwrap_or::<bool>(&m, "store-spent-utxos", defaults.store_spent_utxos),
+            hold_time_locked_txs: arg_match_unwrap_or::<bool>(&m, "hold-time-locked-txs", defaults.hold_time_locked_txs),
+            dust_relay_threshold: m.get_one::<u64>("dust-relay-threshold").cloned().or(defaults.dust_relay_threshold),
+            dust_relay_threshold_by_class: defaults.dust_relay_threshold_by_class,
+            template_origin_mass_quota: m
+                .get_one::<f64>("template-origin-mass-quota")
+                .cloned()
+                .or(defaults.template_origin_mass_quota),
+            template_quota_fee_tolerance: arg_match_unwrap_or::<f64>(
+                &m,
+                "template-quota-fee-tolerance",
+                defaults.template_quota_fee_tolerance,
+            ),
+            check_template_tx_order: arg_match_unwrap_or::<bool>(&m, "check-template-tx-order", defaults.check_template_tx_order),
+            reject_legacy_peers: arg_match_unwrap_or::<bool>(&m, "reject-legacy-peers", defaults.reject_legacy_peers),
+            skip_ibd_merkle_check: arg_match_unwrap_or::<bool>(&m, "skip-ibd-merkle-check", defaults.skip_ibd_merkle_check),
             testnet: arg_match_unwrap_or::<bool>(&m, "testnet", defaults.testnet),
             testnet_suffix: arg_match_unwrap_or::<u32>(&m, "netsuffix", defaults.testnet_suffix),
             devnet: arg_match_unwrap_or::<bool>(&m, "devnet", defaults.devnet),
@@ -440,14 +935,55 @@ impl Args {
             yes: arg_match_unwrap_or::<bool>(&m, "yes", defaults.yes),
             user_agent_comments: arg_match_many_unwrap_or::<String>(&m, "user_agent_comments", defaults.user_agent_comments),
             externalip: m.get_one::<ContextualNetAddress>("externalip").cloned(),
+            proxy: m.get_one::<String>("proxy").cloned(),
+            proxy_onion_only: arg_match_unwrap_or::<bool>(&m, "proxy-onion-only", defaults.proxy_onion_only),
             perf_metrics: arg_match_unwrap_or::<bool>(&m, "perf-metrics", defaults.perf_metrics),
             perf_metrics_interval_sec: arg_match_unwrap_or::<u64>(&m, "perf-metrics-interval-sec", defaults.perf_metrics_interval_sec),
             // Note: currently used programmatically by benchmarks and not exposed to CLI users
             block_template_cache_lifetime: defaults.block_template_cache_lifetime,
+            notification_journal_size: arg_match_unwrap_or::<usize>(
+                &m,
+                "notification-journal-size",
+                defaults.notification_journal_size,
+            ),
             disable_upnp: arg_match_unwrap_or::<bool>(&m, "disable-upnp", defaults.disable_upnp),
             disable_dns_seeding: arg_match_unwrap_or::<bool>(&m, "nodnsseed", defaults.disable_dns_seeding),
             disable_grpc: arg_match_unwrap_or::<bool>(&m, "nogrpc", defaults.disable_grpc),
             ram_scale: arg_match_unwrap_or::<f64>(&m, "ram-scale", defaults.ram_scale),
+            p2p_max_anticone_size: arg_match_unwrap_or::<usize>(&m, "p2p-max-anticone-size", defaults.p2p_max_anticone_size),
+            p2p_max_locator_steps: arg_match_unwrap_or::<usize>(&m, "p2p-max-locator-steps", defaults.p2p_max_locator_steps),
+            p2p_serving_window_sec: arg_match_unwrap_or::<u64>(&m, "p2p-serving-window-sec", defaults.p2p_serving_window_sec),
+            p2p_max_served_bytes: arg_match_unwrap_or::<u64>(&m, "p2p-max-served-bytes", defaults.p2p_max_served_bytes),
+            p2p_max_served_cost: arg_match_unwrap_or::<u64>(&m, "p2p-max-served-cost", defaults.p2p_max_served_cost),
+            p2p_serving_disconnect_factor: arg_match_unwrap_or::<u64>(
+                &m,
+                "p2p-serving-disconnect-factor",
+                defaults.p2p_serving_disconnect_factor,
+            ),
+            p2p_ping_interval_sec: arg_match_unwrap_or::<u64>(&m, "p2p-ping-interval-sec", defaults.p2p_ping_interval_sec),
+            p2p_idle_timeout_sec: m.get_one::<u64>("p2p-idle-timeout-sec").cloned().or(defaults.p2p_idle_timeout_sec),
+            p2p_pong_timeout_sec: arg_match_unwrap_or::<u64>(&m, "p2p-pong-timeout-sec", defaults.p2p_pong_timeout_sec),
+            p2p_max_missed_pongs: arg_match_unwrap_or::<u32>(&m, "p2p-max-missed-pongs", defaults.p2p_max_missed_pongs),
+            ignore_fd_limit: arg_match_unwrap_or::<bool>(&m, "ignore-fd-limit", defaults.ignore_fd_limit),
+            ignore_low_disk_space: arg_match_unwrap_or::<bool>(&m, "ignore-low-disk-space", defaults.ignore_low_disk_space),
+            ignore_clock_skew: arg_match_unwrap_or::<bool>(&m, "ignore-clock-skew", defaults.ignore_clock_skew),
+            backup: m.subcommand_matches("backup").map(|m| BackupCommand {
+                output: m.get_one::<String>("output").cloned().unwrap_or_default(),
+                verify: m.get_flag("verify-backup"),
+            }),
+            checkpoints: arg_match_many_unwrap_or::<Checkpoint>(&m, "checkpoints", defaults.checkpoints),
+            disable_checkpoints: arg_match_unwrap_or::<bool>(&m, "nocheckpoints", defaults.disable_checkpoints),
+            checkpoints_command: m
+                .subcommand_matches("checkpoints")
+                .map(|m| CheckpointsCommand { count: m.get_one::<usize>("count").cloned().unwrap_or(10) }),
+            devcluster: m.subcommand_matches("devcluster").map(|m| DevClusterCommand {
+                nodes: m.get_one::<usize>("nodes").cloned().unwrap_or(2),
+                topology: m.get_one::<Topology>("topology").cloned().unwrap_or_default(),
+            }),
+            consensus_command: m.subcommand_matches("consensus").and_then(|m| match m.subcommand_name() {
+                Some("cleanup") => Some(ConsensusCommand::Cleanup),
+                _ => None,
+            }),
 
             #[cfg(feature = "devnet-prealloc")]
             num_prealloc_utxos: m.get_one::<u64>("num-prealloc-utxos").cloned(),
@@ -455,6 +991,9 @@ impl Args {
             prealloc_address: m.get_one::<String>("prealloc-address").cloned(),
             #[cfg(feature = "devnet-prealloc")]
             prealloc_amount: arg_match_unwrap_or::<u64>(&m, "prealloc-amount", defaults.prealloc_amount),
+
+            #[cfg(feature = "stratum")]
+            stratum_listen: m.get_one::<ContextualNetAddress>("stratum-listen").cloned().or(defaults.stratum_listen),
         };
 
         if arg_match_unwrap_or::<bool>(&m, "enable-mainnet-mining", false) {
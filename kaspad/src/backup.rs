@@ -0,0 +1,75 @@
+//!
+//! The `backup` subcommand, creating a backup of the consensus databases of a running node
+//! through its gRPC interface, or verifying an existing backup.
+//!
+
+use crate::args::{Args, BackupCommand};
+use kaspa_consensus::consensus::backup::verify_backup;
+use kaspa_consensus_core::config::{Config, ConfigBuilder};
+use kaspa_core::info;
+use kaspa_grpc_client::GrpcClient;
+use kaspa_rpc_core::{api::rpc::RpcApi, CreateBackupResponse};
+use kaspa_utils::networking::{ContextualNetAddress, IpAddress, NetAddress};
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    path::PathBuf,
+    process::exit,
+    sync::Arc,
+};
+
+/// The gRPC address of the running node, the loopback interface standing in for an unspecified listening address
+pub(crate) fn node_rpc_address(args: &Args, config: &Config) -> NetAddress {
+    let mut address = args.rpclisten.unwrap_or(ContextualNetAddress::loopback()).normalize(config.default_rpc_port());
+    if address.ip.is_unspecified() {
+        address.ip = IpAddress::new(IpAddr::V4(Ipv4Addr::LOCALHOST));
+    }
+    address
+}
+
+/// Runs the `backup` subcommand, exiting the process on failure
+pub fn run_backup_command(args: &Args, command: &BackupCommand) {
+    kaspa_core::log::init_logger(None, &args.log_level);
+
+    let network = args.network();
+    let config = Arc::new(
+        ConfigBuilder::new(network.into())
+            .adjust_perf_params_to_consensus_params()
+            .apply_args(|config| args.apply_to_config(config))
+            .build(),
+    );
+
+    // The node resolves the output directory on its side, so a relative one is made absolute here
+    let output = PathBuf::from(&command.output);
+    let output = if output.is_absolute() { output } else { std::env::current_dir().unwrap().join(output) };
+
+    let result = if command.verify {
+        info!("Verifying the backup at {}", output.display());
+        verify_backup(&output, config).map(CreateBackupResponse::from).map_err(|err| err.to_string())
+    } else {
+        let address = node_rpc_address(args, &config);
+        info!("Requesting a backup to {} from the node at {}", output.display(), address);
+        let output_directory = output.to_string_lossy().into_owned();
+        tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async move {
+            let client = GrpcClient::connect(format!("grpc://{address}")).await.map_err(|err| err.to_string())?;
+            let response = client.create_backup(output_directory).await.map_err(|err| err.to_string());
+            client.disconnect().await.ok();
+            response
+        })
+    };
+
+    match result {
+        Ok(backup) => {
+            let action = if command.verify { "verified" } else { "created" };
+            println!("Backup {action} at {}", output.display());
+            println!("    node version: {}", backup.node_version);
+            println!("    network: {}", backup.network_id);
+            println!("    pruning point: {}", backup.pruning_point);
+            println!("    sink: {}", backup.sink);
+            println!("    timestamp: {}", backup.timestamp);
+        }
+        Err(err) => {
+            println!("Backup failed: {err}");
+            exit(1);
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//!
+//! The `checkpoints` subcommand, printing the recommended pruning point checkpoints of a synced running node
+//! through its gRPC interface.
+//!
+
+use crate::{
+    args::{Args, CheckpointsCommand},
+    backup::node_rpc_address,
+};
+use kaspa_consensus_core::config::{checkpoints::Checkpoint, ConfigBuilder};
+use kaspa_core::info;
+use kaspa_grpc_client::GrpcClient;
+use kaspa_hashes::Hash;
+use kaspa_rpc_core::{api::rpc::RpcApi, RpcResult};
+use std::process::exit;
+
+/// Runs the `checkpoints` subcommand, exiting the process on failure
+pub fn run_checkpoints_command(args: &Args, command: &CheckpointsCommand) {
+    kaspa_core::log::init_logger(None, &args.log_level);
+
+    let network = args.network();
+    let config = ConfigBuilder::new(network.into()).apply_args(|config| args.apply_to_config(config)).build();
+    let address = node_rpc_address(args, &config);
+    let genesis = config.genesis.hash;
+    let count = command.count;
+
+    info!("Requesting the pruning points of the node at {}", address);
+    let result = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async move {
+        let client = GrpcClient::connect(format!("grpc://{address}")).await.map_err(|err| err.to_string())?;
+        let checkpoints = pruning_point_checkpoints(&client, genesis, count).await.map_err(|err| err.to_string());
+        client.disconnect().await.ok();
+        checkpoints
+    });
+
+    match result {
+        Ok(checkpoints) => {
+            println!("Recommended checkpoints of {network}, from the current pruning point down:");
+            for checkpoint in checkpoints {
+                println!("    --checkpoint={checkpoint}");
+            }
+        }
+        Err(err) => {
+            println!("Fetching the checkpoints failed: {err}");
+            exit(1);
+        }
+    }
+}
+
+/// Walks the pruning points chain down from the current pruning point through the pruning point
+/// committed to by each pruning point header, returning at most `count` checkpoints
+async fn pruning_point_checkpoints(client: &GrpcClient, genesis: Hash, count: usize) -> RpcResult<Vec<Checkpoint>> {
+    let mut hash = client.get_block_dag_info().await?.pruning_point_hash;
+    let mut checkpoints = Vec::with_capacity(count);
+    while checkpoints.len() < count {
+        let header = client.get_block(hash, false).await?.header;
+        checkpoints.push(Checkpoint::new(header.daa_score, hash));
+        if hash == genesis {
+            break;
+        }
+        hash = header.pruning_point;
+    }
+    Ok(checkpoints)
+}
@@ -0,0 +1,76 @@
+//!
+//! The `consensus` subcommand, managing the consensus databases of a stopped node.
+//!
+//! `consensus cleanup` completes an interrupted promotion of the staging consensus as the node would on
+//! startup, then deletes the staging consensus and the retired consensus entries regardless of their
+//! grace period, reclaiming their disk space.
+//!
+
+use crate::{
+    args::{Args, ConsensusCommand},
+    daemon::{get_app_dir_from_args, get_user_approval_or_exit, CONSENSUS_DB, DEFAULT_DATA_DIR, META_DB, META_DB_FILE_LIMIT},
+};
+use kaspa_consensus::consensus::factory::MultiConsensusManagementStore;
+use kaspa_consensus_core::multi_consensus::ConsensusEntryInfo;
+use kaspa_database::prelude::ConnBuilder;
+use std::process::exit;
+
+/// Runs the `consensus` subcommand, exiting the process on failure
+pub fn run_consensus_command(args: &Args, command: &ConsensusCommand) {
+    kaspa_core::log::init_logger(None, &args.log_level);
+
+    let db_dir = get_app_dir_from_args(args).join(args.network().to_prefixed()).join(DEFAULT_DATA_DIR);
+    let (meta_db_dir, consensus_db_dir) = (db_dir.join(META_DB), db_dir.join(CONSENSUS_DB));
+    if !meta_db_dir.exists() {
+        println!("No node database found at {}", db_dir.display());
+        exit(1);
+    }
+    // The open fails on the lock of the DB if the node is running
+    let meta_db =
+        ConnBuilder::default().with_db_path(meta_db_dir).with_files_limit(META_DB_FILE_LIMIT).build().unwrap_or_else(|err| {
+            println!("Opening the node database failed, make sure the node is stopped: {err}");
+            exit(1);
+        });
+    let mut store = MultiConsensusManagementStore::new(meta_db);
+
+    match command {
+        ConsensusCommand::Cleanup => {
+            if store.is_archival_node().unwrap() {
+                get_user_approval_or_exit(
+                    "The node is archival, cleaning up deletes its retired consensus databases. Do you confirm? (y/n)",
+                    args.yes,
+                );
+            }
+            if let Some(entry) = store.recover_interrupted_promotion().unwrap() {
+                println!("Completed the interrupted promotion of the staging consensus {} to active", entry.directory_name);
+            }
+            let status = store.status(&consensus_db_dir).unwrap();
+            if let Some(staging) = status.staging {
+                println!("Deleting the staging consensus {}", describe(&staging));
+                store.delete_staging_entry(&consensus_db_dir);
+            }
+            let deleted = store.delete_retired_entries(&consensus_db_dir, u64::MAX);
+            for retired in deleted.iter() {
+                println!("Deleted the retired consensus {}, reclaiming {} bytes", describe(&retired.entry), retired.disk_usage);
+            }
+            let remaining = store.status(&consensus_db_dir).unwrap();
+            match remaining.current {
+                Some(current) => println!("Active consensus: {}", describe(&current)),
+                None => println!("No active consensus"),
+            }
+            println!(
+                "Reclaimed {} bytes from {} retired consensus entries",
+                deleted.iter().map(|r| r.disk_usage).sum::<u64>(),
+                deleted.len()
+            );
+            if !remaining.retired.is_empty() {
+                println!("{} retired consensus entries could not be deleted, see the logs above", remaining.retired.len());
+                exit(1);
+            }
+        }
+    }
+}
+
+fn describe(entry: &ConsensusEntryInfo) -> String {
+    format!("{} (reason: {}, created at: {})", entry.directory_name, entry.creation_reason, entry.creation_timestamp)
+}
@@ -2,38 +2,49 @@ use std::{fs, path::PathBuf, process::exit, sync::Arc, time::Duration};
 
 use async_channel::unbounded;
 use kaspa_consensus_core::{
-    config::ConfigBuilder,
+    api::parallelism::ProcessingParallelism,
+    config::{Config, ConfigBuilder},
     errors::config::{ConfigError, ConfigResult},
 };
 use kaspa_consensus_notify::{root::ConsensusNotificationRoot, service::NotifyService};
-use kaspa_core::{core::Core, info, trace};
+use kaspa_core::{core::Core, error, info, service::qualified_ident, trace, warn};
 use kaspa_core::{kaspad_env::version, task::tick::TickService};
 use kaspa_database::prelude::CachePolicy;
 use kaspa_grpc_server::service::GrpcService;
 use kaspa_notify::{address::tracker::Tracker, subscription::context::SubscriptionContext};
+use kaspa_rpc_core::RpcPreflightReport;
 use kaspa_rpc_service::service::RpcCoreService;
-use kaspa_txscript::caches::TxScriptCacheCounters;
-use kaspa_utils::networking::ContextualNetAddress;
+use kaspa_txscript::{caches::TxScriptCacheCounters, metrics::ScriptMetrics};
+use kaspa_utils::networking::{ContextualNetAddress, NetAddress};
 use kaspa_utils_tower::counters::TowerConnectionCounters;
 
 use kaspa_addressmanager::AddressManager;
 use kaspa_consensus::{consensus::factory::Factory as ConsensusFactory, pipeline::ProcessingCounters};
 use kaspa_consensus::{
-    consensus::factory::MultiConsensusManagementStore, model::stores::headers::DbHeadersStore, pipeline::monitor::ConsensusMonitor,
+    consensus::factory::{MultiConsensusManagementStore, PruningPointUtxoSetOverrideNotifier},
+    consensus::recovery::recover_consensus_stores,
+    model::stores::headers::DbHeadersStore,
+    pipeline::monitor::ConsensusMonitor,
 };
 use kaspa_consensusmanager::ConsensusManager;
 use kaspa_core::task::runtime::AsyncRuntime;
 use kaspa_index_processor::service::IndexService;
 use kaspa_mining::{
     manager::{MiningManager, MiningManagerProxy},
+    mempool::config::DustRelayThresholds,
     monitor::MiningMonitor,
+    virtual_change::MempoolVirtualChangeService,
     MiningCounters,
 };
 use kaspa_p2p_flows::{flow_context::FlowContext, service::P2pService};
+use kaspa_p2p_lib::ProxyConfig;
 
 use kaspa_perf_monitor::{builder::Builder as PerfMonitorBuilder, counters::CountersSnapshot};
 use kaspa_utxoindex::{api::UtxoIndexProxy, UtxoIndex};
-use kaspa_wrpc_server::service::{Options as WrpcServerOptions, WebSocketCounters as WrpcServerCounters, WrpcEncoding, WrpcService};
+use kaspa_wrpc_server::{
+    address::WrpcNetAddress,
+    service::{Options as WrpcServerOptions, WebSocketCounters as WrpcServerCounters, WrpcEncoding, WrpcService},
+};
 
 /// Desired soft FD limit that needs to be configured
 /// for the kaspad process.
@@ -44,14 +55,19 @@ pub const DESIRED_DAEMON_SOFT_FD_LIMIT: u64 = 8 * 1024;
 /// this value may impact the database performance).
 pub const MINIMUM_DAEMON_SOFT_FD_LIMIT: u64 = 4 * 1024;
 
-use crate::args::Args;
+use crate::{
+    args::Args,
+    preflight::{run_startup_checks, ClockSkewMonitor},
+};
 
-const DEFAULT_DATA_DIR: &str = "datadir";
-const CONSENSUS_DB: &str = "consensus";
+pub(crate) const DEFAULT_DATA_DIR: &str = "datadir";
+pub(crate) const CONSENSUS_DB: &str = "consensus";
 const UTXOINDEX_DB: &str = "utxoindex";
-const META_DB: &str = "meta";
-const META_DB_FILE_LIMIT: i32 = 5;
+pub(crate) const META_DB: &str = "meta";
+pub(crate) const META_DB_FILE_LIMIT: i32 = 5;
 const DEFAULT_LOG_DIR: &str = "logs";
+/// Time given to the services to stop on shutdown before it gets escalated
+const SHUTDOWN_DEADLINE: Duration = Duration::from_secs(60);
 
 fn get_home_dir() -> PathBuf {
     #[cfg(target_os = "windows")]
@@ -95,10 +111,30 @@ pub fn validate_args(args: &Args) -> ConfigResult<()> {
     if args.max_tracked_addresses > Tracker::MAX_ADDRESS_UPPER_BOUND {
         return Err(ConfigError::MaxTrackedAddressesTooHigh(Tracker::MAX_ADDRESS_UPPER_BOUND));
     }
+    if args.p2p_serving_window_sec == 0 {
+        return Err(ConfigError::P2pServingWindowTooLow);
+    }
+    if args.p2p_serving_disconnect_factor < 1 {
+        return Err(ConfigError::P2pServingDisconnectFactorTooLow);
+    }
+    if args.p2p_ping_interval_sec == 0 || args.p2p_idle_timeout_sec == Some(0) || args.p2p_pong_timeout_sec == 0 {
+        return Err(ConfigError::P2pKeepaliveTimeoutTooLow);
+    }
+    if args.p2p_max_missed_pongs < 1 {
+        return Err(ConfigError::P2pMaxMissedPongsTooLow);
+    }
+    if args.processing_parallelism == 0 {
+        return Err(ConfigError::ProcessingParallelismTooLow);
+    }
+    if args.proxy_onion_only && args.proxy.is_none() {
+        return Err(ConfigError::ProxyOnionOnlyWithoutProxy);
+    }
+    args.dust_relay_thresholds()?;
+    args.template_mass_quota()?;
     Ok(())
 }
 
-fn get_user_approval_or_exit(message: &str, approve: bool) {
+pub(crate) fn get_user_approval_or_exit(message: &str, approve: bool) {
     if approve {
         return;
     }
@@ -122,10 +158,75 @@ fn get_user_approval_or_exit(message: &str, approve: bool) {
     }
 }
 
+/// Logs a summary of the effective node configuration and warns about
+/// setting combinations which are likely to be a misconfiguration.
+fn log_effective_config(args: &Args, config: &Config, grpc_server_addr: NetAddress) {
+    let network = args.network();
+    let wrpc_listen_address = |listen_address: &Option<WrpcNetAddress>, encoding: &WrpcEncoding| {
+        listen_address
+            .as_ref()
+            .map(|address| address.to_address(&network.network_type, encoding).to_string())
+            .unwrap_or_else(|| "disabled".to_string())
+    };
+
+    info!("Effective configuration:");
+    info!("    network: {}", network);
+    info!("    consensus params digest: {}", config.params.digest());
+    info!("    utxoindex: {}", if config.utxoindex { "enabled" } else { "disabled" });
+    info!(
+        "    pruning mode: {}",
+        if config.is_archival { "archival (block data is retained past the pruning point)" } else { "pruned" }
+    );
+    info!("    gRPC: {}", if args.disable_grpc { "disabled".to_string() } else { grpc_server_addr.to_string() });
+    info!("    wRPC borsh: {}", wrpc_listen_address(&args.rpclisten_borsh, &WrpcEncoding::Borsh));
+    info!("    wRPC json: {}", wrpc_listen_address(&args.rpclisten_json, &WrpcEncoding::SerdeJson));
+    info!(
+        "    wRPC message size limits: inbound {} bytes, outbound {}",
+        args.wrpc_max_inbound_message_size,
+        args.wrpc_max_outbound_message_size.map_or("unlimited".to_string(), |size| format!("{size} bytes"))
+    );
+    #[cfg(feature = "stratum")]
+    info!(
+        "    stratum: {}",
+        args.stratum_listen
+            .map(|listen| listen.normalize(kaspa_mining::stratum::StratumConfig::DEFAULT_PORT).to_string())
+            .unwrap_or_else(|| "disabled".to_string())
+    );
+    info!("    unsafe RPC: {}", if config.unsafe_rpc { "enabled" } else { "disabled" });
+    info!("    hold time-locked txs: {}", if config.hold_time_locked_txs { "enabled" } else { "disabled" });
+    info!("    legacy peers (without a params digest): {}", if config.reject_legacy_peers { "rejected" } else { "tolerated" });
+    if config.skip_ibd_merkle_check {
+        info!("    IBD merkle root checks: skipped");
+    }
+    let dust_relay_thresholds = args.dust_relay_thresholds().unwrap_or_default();
+    if dust_relay_thresholds != DustRelayThresholds::default() {
+        let default = dust_relay_thresholds.default.map_or("fee-based".to_string(), |threshold| format!("{threshold} sompi"));
+        let by_class = dust_relay_thresholds.by_class.iter().map(|(class, threshold)| format!(", {class}: {threshold} sompi"));
+        info!("    dust relay thresholds: default: {default}{}", by_class.collect::<String>());
+    }
+    if let Ok(Some(quota)) = args.template_mass_quota() {
+        info!(
+            "    template mass quota: {}% per origin, fee tolerance: {}%",
+            quota.max_origin_mass_ratio * 100.0,
+            quota.max_fee_loss_ratio * 100.0
+        );
+    }
+
+    if !config.utxoindex && (args.rpclisten_borsh.is_some() || args.rpclisten_json.is_some()) {
+        warn!("wRPC is enabled while utxoindex is disabled: wallets connecting to this node require --utxoindex");
+    }
+    if config.unsafe_rpc && !grpc_server_addr.ip.is_loopback() {
+        warn!("--unsaferpc is enabled on a non-loopback gRPC binding ({})", grpc_server_addr);
+    }
+}
+
 /// Runtime configuration struct for the application.
 #[derive(Default)]
 pub struct Runtime {
     log_dir: Option<String>,
+    preflight_report: RpcPreflightReport,
+    /// Namespace of the node, set when several nodes run in the process
+    namespace: Option<String>,
 }
 
 /// Get the application directory from the supplied [`Args`].
@@ -161,13 +262,28 @@ impl Runtime {
         let log_dir = get_log_dir(args);
 
         // Initialize the logger
-        kaspa_core::log::init_logger(log_dir.as_deref(), &args.log_level);
+        kaspa_core::log::init_logger_with_format(log_dir.as_deref(), &args.log_level, args.log_format);
+        kaspa_core::log::trace_id::set_slow_call_threshold(args.rpc_slow_call_threshold.map(Duration::from_millis));
 
         // Configure the panic behavior
         // As we log the panic, we want to set it up after the logger
         kaspa_core::panic::configure_panic();
 
-        Self { log_dir: log_dir.map(|log_dir| log_dir.to_owned()) }
+        Self { log_dir: log_dir.map(|log_dir| log_dir.to_owned()), preflight_report: Default::default(), namespace: None }
+    }
+
+    /// Derives the runtime of one of several nodes running in the process, sharing the logger initialized by
+    /// this runtime and starting with a copy of its preflight report. The services of the node are identified
+    /// under `namespace`.
+    pub fn namespaced(&self, namespace: impl Into<String>) -> Self {
+        let preflight_report = RpcPreflightReport::new();
+        self.preflight_report.checks().into_iter().for_each(|check| preflight_report.update(check));
+        Self { log_dir: self.log_dir.clone(), preflight_report, namespace: Some(namespace.into()) }
+    }
+
+    /// The report of the preflight checks, to which checks run before creating the [`Core`] are added
+    pub fn preflight_report(&self) -> &RpcPreflightReport {
+        &self.preflight_report
     }
 }
 
@@ -342,6 +458,35 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
             .unwrap();
     }
 
+    kaspa_core::panic::register_emergency_action(
+        qualified_ident(runtime.namespace.as_deref(), "meta-db"),
+        meta_db.emergency_flush_action(),
+    );
+
+    // Rebuild the damaged derivable stores of the active consensus, refusing to start if a source of truth store is damaged
+    if let Some(dir_name) = MultiConsensusManagementStore::new(meta_db.clone()).active_consensus_dir_name().unwrap() {
+        let consensus_db = kaspa_database::prelude::ConnBuilder::default()
+            .with_db_path(consensus_db_dir.join(dir_name))
+            .with_files_limit(10)
+            .build()
+            .unwrap();
+        match recover_consensus_stores(consensus_db, &config) {
+            Ok(rebuilt) if !rebuilt.is_empty() => info!(
+                "Rebuilt the damaged consensus stores: {}",
+                rebuilt.iter().map(|store| store.name()).collect::<Vec<_>>().join(", ")
+            ),
+            Ok(_) => {}
+            Err(err) => {
+                error!("{err}");
+                error!("Restore a backup of the node database or restart with --reset-db");
+                exit(1);
+            }
+        }
+    }
+
+    let preflight_report = runtime.preflight_report.clone();
+    run_startup_checks(args, &config, &db_dir, &preflight_report);
+
     if !args.archival && MultiConsensusManagementStore::new(meta_db.clone()).is_archival_node().unwrap() {
         get_user_approval_or_exit("--archival is set to false although the node was previously archival. Proceeding may delete archived data. Do you confirm? (y/n)", args.yes);
     }
@@ -355,7 +500,13 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
 
     let grpc_server_addr = args.rpclisten.unwrap_or(ContextualNetAddress::loopback()).normalize(config.default_rpc_port());
 
-    let core = Arc::new(Core::new());
+    log_effective_config(args, &config, grpc_server_addr);
+
+    let core = Core::with_shutdown_deadline(SHUTDOWN_DEADLINE);
+    let core = Arc::new(match runtime.namespace.as_ref() {
+        Some(namespace) => core.with_namespace(namespace),
+        None => core,
+    });
 
     // ---
 
@@ -365,10 +516,12 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
     let subscription_context = SubscriptionContext::with_options(max_tracked_addresses);
     let notification_root = Arc::new(ConsensusNotificationRoot::with_context(notification_send, subscription_context.clone()));
     let processing_counters = Arc::new(ProcessingCounters::default());
+    let processing_parallelism = Arc::new(ProcessingParallelism::new(args.processing_parallelism));
     let mining_counters = Arc::new(MiningCounters::default());
     let wrpc_borsh_counters = Arc::new(WrpcServerCounters::default());
     let wrpc_json_counters = Arc::new(WrpcServerCounters::default());
     let tx_script_cache_counters = Arc::new(TxScriptCacheCounters::default());
+    let script_metrics = Arc::new(ScriptMetrics::new(args.opcode_metrics_sample_rate));
     let p2p_tower_counters = Arc::new(TowerConnectionCounters::default());
     let grpc_tower_counters = Arc::new(TowerConnectionCounters::default());
 
@@ -379,9 +532,12 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
         &config,
         consensus_db_dir,
         consensus_db_parallelism,
+        args.db_wal_sync,
         notification_root.clone(),
         processing_counters.clone(),
+        processing_parallelism.clone(),
         tx_script_cache_counters.clone(),
+        script_metrics.clone(),
         fd_remaining,
     ));
     let consensus_manager = Arc::new(ConsensusManager::new(consensus_factory));
@@ -416,6 +572,8 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
     } else {
         None
     };
+    // Registered after the UTXO index reset handler, so that the index is rebuilt by the time the override is notified
+    consensus_manager.register_consensus_reset_handler(Arc::new(PruningPointUtxoSetOverrideNotifier::new(notification_root.clone())));
 
     let (address_manager, port_mapping_extender_svc) = AddressManager::new(config.clone(), meta_db, tick_service.clone());
 
@@ -424,10 +582,17 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
         config.target_time_per_block,
         false,
         config.max_block_mass,
+        config.max_block_transactions,
         config.ram_scale,
+        config.hold_time_locked_txs,
+        args.dust_relay_thresholds().expect("validated by validate_args"),
+        args.template_mass_quota().expect("validated by validate_args"),
+        args.check_template_tx_order,
         config.block_template_cache_lifetime,
         mining_counters,
     )));
+    let mempool_virtual_change_service =
+        Arc::new(MempoolVirtualChangeService::new(&notify_service.notifier(), consensus_manager.clone(), mining_manager.clone()));
 
     let flow_context = Arc::new(FlowContext::new(
         consensus_manager.clone(),
@@ -436,6 +601,15 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
         mining_manager.clone(),
         tick_service.clone(),
         notification_root,
+        args.request_cost_limits(),
+        args.keepalive_config(config.bps()),
+    ));
+    let clock_skew_monitor = Arc::new(ClockSkewMonitor::new(
+        flow_context.clone(),
+        tick_service.clone(),
+        core.clone(),
+        preflight_report.clone(),
+        args.ignore_clock_skew,
     ));
     let p2p_service = Arc::new(P2pService::new(
         flow_context.clone(),
@@ -447,6 +621,7 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
         dns_seeders,
         config.default_p2p_port(),
         p2p_tower_counters.clone(),
+        args.proxy.clone().map(|address| ProxyConfig::new(address, args.proxy_onion_only)),
     ));
 
     let rpc_core_service = Arc::new(RpcCoreService::new(
@@ -460,12 +635,24 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
         config.clone(),
         core.clone(),
         processing_counters,
+        processing_parallelism,
+        script_metrics,
         wrpc_borsh_counters.clone(),
         wrpc_json_counters.clone(),
         perf_monitor.clone(),
         p2p_tower_counters.clone(),
         grpc_tower_counters.clone(),
+        preflight_report,
     ));
+    #[cfg(feature = "stratum")]
+    let stratum_server = args.stratum_listen.map(|listen| {
+        let listen = listen.normalize(kaspa_mining::stratum::StratumConfig::DEFAULT_PORT);
+        let stratum_config = kaspa_mining::stratum::StratumConfig::new(listen.into(), config.prefix());
+        Arc::new(kaspa_mining::stratum::StratumServer::new(
+            stratum_config,
+            Arc::new(crate::stratum::RpcStratumBackend::new(rpc_core_service.clone())),
+        ))
+    });
     let grpc_service_broadcasters: usize = 3; // TODO: add a command line argument or derive from other arg/config/host-related fields
     let grpc_service = if !args.disable_grpc {
         Some(Arc::new(GrpcService::new(
@@ -494,10 +681,16 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
     if let Some(grpc_service) = grpc_service {
         async_runtime.register(grpc_service)
     }
+    #[cfg(feature = "stratum")]
+    if let Some(stratum_server) = stratum_server {
+        async_runtime.register(stratum_server)
+    }
     async_runtime.register(p2p_service);
     async_runtime.register(consensus_monitor);
     async_runtime.register(mining_monitor);
+    async_runtime.register(mempool_virtual_change_service);
     async_runtime.register(perf_monitor);
+    async_runtime.register(clock_skew_monitor);
     let wrpc_service_tasks: usize = 2; // num_cpus::get() / 2;
                                        // Register wRPC servers based on command line arguments
     [
@@ -515,6 +708,8 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
                 WrpcServerOptions {
                     listen_address: listen_address.to_address(&network.network_type, &encoding).to_string(), // TODO: use a normalized ContextualNetAddress instead of a String
                     verbose: args.wrpc_verbose,
+                    max_inbound_message_size: args.wrpc_max_inbound_message_size,
+                    max_outbound_message_size: args.wrpc_max_outbound_message_size,
                     ..WrpcServerOptions::default()
                 },
             ))
@@ -0,0 +1,369 @@
+//!
+//! The `devcluster` subcommand, running several connected simnet or devnet nodes in a single process for
+//! integration testing and local development.
+//!
+
+use crate::{
+    args::{Args, DevClusterCommand},
+    daemon::{create_core_with_runtime, Runtime},
+};
+use kaspa_consensus_core::network::{NetworkId, NetworkType};
+use kaspa_core::{core::Core, info, signals::Shutdown};
+use kaspa_rpc_core::RpcPreflightCheck;
+use kaspa_utils::networking::{ContextualNetAddress, NetAddress};
+use kaspa_wrpc_server::{address::WrpcNetAddress, service::WrpcEncoding};
+use std::{
+    fmt::Display,
+    path::Path,
+    process::exit,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    thread::JoinHandle,
+};
+use tempfile::TempDir;
+use thiserror::Error;
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum DevClusterError {
+    #[error("a dev cluster runs on simnet or devnet only, not on {0}")]
+    UnsupportedNetwork(NetworkId),
+
+    #[error("a dev cluster requires at least one node")]
+    NoNodes,
+
+    #[error("--{0} cannot be used with a dev cluster, which manages it for each node")]
+    ManagedArgument(&'static str),
+
+    #[error("the {0} ports of the nodes exceed the port range")]
+    PortOverflow(&'static str),
+
+    #[error("the {0} and {1} ports of the nodes overlap, set distinct base ports with --rpclisten and --listen")]
+    PortRangesOverlap(&'static str, &'static str),
+
+    #[error("the temporary root directory could not be created: {0}")]
+    RootDirectory(String),
+}
+
+pub type DevClusterResult<T> = std::result::Result<T, DevClusterError>;
+
+/// How the nodes of a dev cluster are connected to each other
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Topology {
+    /// Every node is connected to the previous one
+    #[default]
+    Chain,
+    /// A chain whose last node is also connected to the first one
+    Ring,
+    /// Every node is connected to the first one
+    Star,
+    /// Every node is connected to all the others
+    Mesh,
+}
+
+impl Topology {
+    /// The nodes to which `node` connects among the `nodes` nodes of the cluster. Each connection is initiated by
+    /// the node of higher index, so that every pair of nodes is connected once.
+    pub fn peers(&self, node: usize, nodes: usize) -> Vec<usize> {
+        match self {
+            Topology::Chain => node.checked_sub(1).into_iter().collect(),
+            Topology::Ring => {
+                let mut peers = Topology::Chain.peers(node, nodes);
+                if nodes > 2 && node == nodes - 1 {
+                    peers.push(0);
+                }
+                peers
+            }
+            Topology::Star => (node > 0).then_some(0).into_iter().collect(),
+            Topology::Mesh => (0..node).collect(),
+        }
+    }
+}
+
+impl FromStr for Topology {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "chain" => Ok(Topology::Chain),
+            "ring" => Ok(Topology::Ring),
+            "star" => Ok(Topology::Star),
+            "mesh" => Ok(Topology::Mesh),
+            _ => Err(format!("unknown topology '{s}', expected one of chain, ring, star or mesh")),
+        }
+    }
+}
+
+impl Display for Topology {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Topology::Chain => "chain",
+            Topology::Ring => "ring",
+            Topology::Star => "star",
+            Topology::Mesh => "mesh",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A node of a dev cluster
+struct ClusterNode {
+    args: Args,
+    core: Arc<Core>,
+    workers: Mutex<Option<Vec<JoinHandle<()>>>>,
+}
+
+impl ClusterNode {
+    fn join(&self) {
+        if let Some(workers) = self.workers.lock().unwrap().take() {
+            self.core.join(workers);
+        }
+    }
+}
+
+/// Several full nodes running in the process, each with its own core and an isolated app dir under a temporary
+/// root directory, which is deleted when the cluster is dropped.
+///
+/// Node `i` is namespaced as `node-i` and listens on the base gRPC, wRPC and P2P ports offset by `i`.
+pub struct DevCluster {
+    nodes: Vec<ClusterNode>,
+    root: TempDir,
+}
+
+impl DevCluster {
+    /// Derives the arguments of every node of the cluster from the common arguments `args`, the app dir of node `i`
+    /// being `root/node-i`
+    pub fn node_args(args: &Args, command: &DevClusterCommand, root: &Path) -> DevClusterResult<Vec<Args>> {
+        let network = args.network();
+        if !matches!(network.network_type, NetworkType::Simnet | NetworkType::Devnet) {
+            return Err(DevClusterError::UnsupportedNetwork(network));
+        }
+        if command.nodes == 0 {
+            return Err(DevClusterError::NoNodes);
+        }
+        if !args.connect_peers.is_empty() {
+            return Err(DevClusterError::ManagedArgument("connect"));
+        }
+        if !args.add_peers.is_empty() {
+            return Err(DevClusterError::ManagedArgument("addpeer"));
+        }
+        if args.appdir.is_some() {
+            return Err(DevClusterError::ManagedArgument("appdir"));
+        }
+
+        let rpclisten = args.rpclisten.unwrap_or(ContextualNetAddress::loopback()).normalize(network.default_rpc_port());
+        let listen = args.listen.unwrap_or(ContextualNetAddress::unspecified()).normalize(network.default_p2p_port());
+        let wrpc_address = |address: &Option<WrpcNetAddress>, encoding: WrpcEncoding| {
+            address.as_ref().map(|address| address.to_address(&network.network_type, &encoding).normalize(0))
+        };
+        let wrpclisten_borsh = wrpc_address(&args.rpclisten_borsh, WrpcEncoding::Borsh);
+        let wrpclisten_json = wrpc_address(&args.rpclisten_json, WrpcEncoding::SerdeJson);
+
+        // The ports of each service are successive, starting at its base port
+        let mut ranges = vec![("gRPC", rpclisten.port), ("P2P", listen.port)];
+        ranges.extend(wrpclisten_borsh.map(|address| ("Borsh wRPC", address.port)));
+        ranges.extend(wrpclisten_json.map(|address| ("JSON wRPC", address.port)));
+        let nodes = u16::try_from(command.nodes).map_err(|_| DevClusterError::PortOverflow(ranges[0].0))?;
+        if let Some(&(name, _)) = ranges.iter().find(|(_, base)| base.checked_add(nodes - 1).is_none()) {
+            return Err(DevClusterError::PortOverflow(name));
+        }
+        let end = |base: u16| base as u32 + nodes as u32;
+        for (i, &(name, base)) in ranges.iter().enumerate() {
+            if let Some(&(other, _)) =
+                ranges[i + 1..].iter().find(|(_, other)| (base as u32) < end(*other) && (*other as u32) < end(base))
+            {
+                return Err(DevClusterError::PortRangesOverlap(name, other));
+            }
+        }
+
+        let node_args = (0..command.nodes)
+            .map(|node| {
+                let offset = node as u16;
+                let wrpc_listen = |address: Option<NetAddress>| {
+                    address.map(|address| WrpcNetAddress::Custom(ContextualNetAddress::new(address.ip, Some(address.port + offset))))
+                };
+                Args {
+                    appdir: Some(root.join(format!("node-{node}")).to_str().unwrap().to_string()),
+                    rpclisten: Some(ContextualNetAddress::new(rpclisten.ip, Some(rpclisten.port + offset))),
+                    rpclisten_borsh: wrpc_listen(wrpclisten_borsh),
+                    rpclisten_json: wrpc_listen(wrpclisten_json),
+                    listen: Some(ContextualNetAddress::new(listen.ip, Some(listen.port + offset))),
+                    add_peers: command
+                        .topology
+                        .peers(node, command.nodes)
+                        .into_iter()
+                        .map(|peer| ContextualNetAddress::loopback().with_port(listen.port + peer as u16))
+                        .collect(),
+                    outbound_target: 0,
+                    disable_dns_seeding: true,
+                    disable_upnp: true,
+                    devcluster: None,
+                    ..args.clone()
+                }
+            })
+            .collect();
+        Ok(node_args)
+    }
+
+    /// Creates the nodes of the cluster, sharing the logger and the preflight checks already concluded by `runtime`.
+    /// The file descriptors budget is divided evenly between the nodes.
+    pub fn new(runtime: &Runtime, args: &Args, command: &DevClusterCommand, fd_total_budget: i32) -> DevClusterResult<Self> {
+        let root = tempfile::Builder::new()
+            .prefix("kaspad-devcluster-")
+            .tempdir()
+            .map_err(|err| DevClusterError::RootDirectory(err.to_string()))?;
+        let node_args = Self::node_args(args, command, root.path())?;
+        let fd_node_budget = fd_total_budget / command.nodes as i32;
+        let nodes = node_args
+            .into_iter()
+            .enumerate()
+            .map(|(node, args)| {
+                let runtime = runtime.namespaced(format!("node-{node}"));
+                let (core, _) = create_core_with_runtime(&runtime, &args, fd_node_budget);
+                ClusterNode { args, core, workers: Mutex::new(None) }
+            })
+            .collect();
+        Ok(Self { nodes, root })
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The temporary root directory holding the app dirs of the nodes
+    pub fn root(&self) -> &Path {
+        self.root.path()
+    }
+
+    /// The arguments `node` runs with
+    pub fn args(&self, node: usize) -> &Args {
+        &self.nodes[node].args
+    }
+
+    /// The core of `node`
+    pub fn core(&self, node: usize) -> Arc<Core> {
+        self.nodes[node].core.clone()
+    }
+
+    /// Starts all the nodes
+    pub fn start(&self) {
+        for node in self.nodes.iter() {
+            let mut workers = node.workers.lock().unwrap();
+            if workers.is_none() {
+                *workers = Some(node.core.start());
+            }
+        }
+    }
+
+    /// Waits for all the nodes to shut down
+    pub fn join(&self) {
+        self.nodes.iter().for_each(ClusterNode::join);
+    }
+
+    /// Starts all the nodes and blocks until they are all shut down
+    pub fn run(&self) {
+        self.start();
+        self.join();
+    }
+
+    /// Shuts `node` down and waits for it to stop, leaving the other nodes running
+    pub fn shutdown_node(&self, node: usize) {
+        let node = &self.nodes[node];
+        node.core.shutdown();
+        node.join();
+    }
+}
+
+impl Shutdown for DevCluster {
+    fn shutdown(self: &Arc<Self>) {
+        self.nodes.iter().for_each(|node| node.core.shutdown());
+    }
+}
+
+impl Drop for DevCluster {
+    fn drop(&mut self) {
+        // The nodes must be stopped before their app dirs get deleted along with the root
+        for node in self.nodes.iter() {
+            node.core.shutdown();
+            node.join();
+        }
+    }
+}
+
+/// Runs the `devcluster` subcommand, exiting the process on failure
+pub fn run_devcluster_command(args: &Args, command: &DevClusterCommand, fd_limit_check: RpcPreflightCheck, fd_total_budget: i32) {
+    let runtime = Runtime::from_args(args);
+    runtime.preflight_report().update(fd_limit_check);
+    let cluster = match DevCluster::new(&runtime, args, command, fd_total_budget) {
+        Ok(cluster) => Arc::new(cluster),
+        Err(err) => {
+            println!("{err}");
+            exit(1);
+        }
+    };
+    info!("Running a dev cluster of {} nodes in a {} topology under {}", cluster.len(), command.topology, cluster.root().display());
+    for node in 0..cluster.len() {
+        let args = cluster.args(node);
+        info!(
+            "node-{node}: gRPC port {}, P2P port {}",
+            args.rpclisten.unwrap().normalize(0).port,
+            args.listen.unwrap().normalize(0).port
+        );
+    }
+
+    // Bind the keyboard signal to all the nodes
+    Arc::new(kaspa_core::signals::Signals::new(&cluster)).init();
+
+    cluster.run();
+    info!("The dev cluster has stopped...");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topology_peers() {
+        let peers = |topology: Topology, nodes: usize| (0..nodes).map(|node| topology.peers(node, nodes)).collect::<Vec<_>>();
+        assert_eq!(peers(Topology::Chain, 3), vec![vec![], vec![0], vec![1]]);
+        assert_eq!(peers(Topology::Ring, 2), vec![vec![], vec![0]]);
+        assert_eq!(peers(Topology::Ring, 4), vec![vec![], vec![0], vec![1], vec![2, 0]]);
+        assert_eq!(peers(Topology::Star, 3), vec![vec![], vec![0], vec![0]]);
+        assert_eq!(peers(Topology::Mesh, 3), vec![vec![], vec![0], vec![0, 1]]);
+        for topology in ["chain", "ring", "star", "mesh"] {
+            assert_eq!(topology.parse::<Topology>().unwrap().to_string(), topology);
+        }
+        assert!("tree".parse::<Topology>().is_err());
+    }
+
+    #[test]
+    fn test_node_args() {
+        let root = Path::new("/tmp/cluster");
+        let command = DevClusterCommand { nodes: 3, topology: Topology::Chain };
+        let args = Args { simnet: true, rpclisten: Some("127.0.0.1:20000".parse().unwrap()), ..Default::default() };
+        let node_args = DevCluster::node_args(&args, &command, root).unwrap();
+        assert_eq!(node_args.len(), 3);
+        for (node, args) in node_args.iter().enumerate() {
+            assert_eq!(args.appdir.as_deref(), root.join(format!("node-{node}")).to_str());
+            assert_eq!(args.rpclisten.unwrap().normalize(0).port, 20000 + node as u16);
+            assert!(args.devcluster.is_none() && args.disable_upnp && args.outbound_target == 0);
+        }
+        let p2p_port = node_args[0].listen.unwrap().normalize(0).port;
+        assert_eq!(node_args[2].listen.unwrap().normalize(0).port, p2p_port + 2);
+        assert!(node_args[0].add_peers.is_empty());
+        assert_eq!(node_args[2].add_peers, vec![ContextualNetAddress::loopback().with_port(p2p_port + 1)]);
+
+        let run = |args: Args, nodes: usize| DevCluster::node_args(&args, &DevClusterCommand { nodes, ..command }, root);
+        assert_eq!(run(Args::default(), 3), Err(DevClusterError::UnsupportedNetwork(Args::default().network())));
+        assert_eq!(run(args.clone(), 0), Err(DevClusterError::NoNodes));
+        let overlapping = Args { listen: Some("0.0.0.0:20002".parse().unwrap()), ..args.clone() };
+        assert_eq!(run(overlapping, 3), Err(DevClusterError::PortRangesOverlap("gRPC", "P2P")));
+        let overflowing = Args { rpclisten: Some("127.0.0.1:65535".parse().unwrap()), ..args.clone() };
+        assert_eq!(run(overflowing, 2), Err(DevClusterError::PortOverflow("gRPC")));
+        let add_peer = Args { add_peers: vec!["127.0.0.1:30000".parse().unwrap()], ..args };
+        assert_eq!(run(add_peer, 2), Err(DevClusterError::ManagedArgument("addpeer")));
+    }
+}
@@ -1,2 +1,9 @@
 pub mod args;
+pub mod backup;
+pub mod checkpoints;
+pub mod consensus;
 pub mod daemon;
+pub mod devcluster;
+pub mod preflight;
+#[cfg(feature = "stratum")]
+pub mod stratum;
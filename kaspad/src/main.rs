@@ -2,14 +2,19 @@ extern crate kaspa_consensus;
 extern crate kaspa_core;
 extern crate kaspa_hashes;
 
-use std::sync::Arc;
+use std::{process::exit, sync::Arc};
 
 use kaspa_alloc::init_allocator_with_default_settings;
 use kaspa_core::{info, signals::Signals};
 use kaspa_utils::fd_budget;
 use kaspad_lib::{
     args::parse_args,
-    daemon::{create_core, DESIRED_DAEMON_SOFT_FD_LIMIT, MINIMUM_DAEMON_SOFT_FD_LIMIT},
+    backup::run_backup_command,
+    checkpoints::run_checkpoints_command,
+    consensus::run_consensus_command,
+    daemon::{create_core_with_runtime, Runtime},
+    devcluster::run_devcluster_command,
+    preflight::{check_fd_limit, HostProbe},
 };
 
 #[cfg(feature = "heap")]
@@ -24,23 +29,39 @@ pub fn main() {
 
     let args = parse_args();
 
-    match fd_budget::try_set_fd_limit(DESIRED_DAEMON_SOFT_FD_LIMIT) {
-        Ok(limit) => {
-            if limit < MINIMUM_DAEMON_SOFT_FD_LIMIT {
-                println!("Current OS file descriptor limit (soft FD limit) is set to {limit}");
-                println!("The kaspad node requires a setting of at least {DESIRED_DAEMON_SOFT_FD_LIMIT} to operate properly.");
-                println!("Please increase the limits using the following command:");
-                println!("ulimit -n {DESIRED_DAEMON_SOFT_FD_LIMIT}");
-            }
-        }
-        Err(err) => {
-            println!("Unable to initialize the necessary OS file descriptor limit (soft FD limit) to: {}", err);
-            println!("The kaspad node requires a setting of at least {DESIRED_DAEMON_SOFT_FD_LIMIT} to operate properly.");
-        }
+    if let Some(command) = args.backup.as_ref() {
+        run_backup_command(&args, command);
+        return;
+    }
+
+    if let Some(command) = args.checkpoints_command.as_ref() {
+        run_checkpoints_command(&args, command);
+        return;
+    }
+
+    if let Some(command) = args.consensus_command.as_ref() {
+        run_consensus_command(&args, command);
+        return;
+    }
+
+    // Checked before initializing the node, whose file descriptor budget depends on the limit
+    let fd_limit_check = check_fd_limit(&HostProbe, args.ignore_fd_limit).unwrap_or_else(|err| {
+        println!("Preflight check {} failed: {err}", err.check());
+        println!("Pass {} to run anyway", err.override_flag());
+        exit(1);
+    });
+
+    if let Some(command) = args.devcluster.as_ref() {
+        // Every node of the cluster reserves its own connections budget
+        let fd_total_budget = fd_budget::limit() - (command.nodes as i32) * (args.rpc_max_clients as i32 + args.inbound_limit as i32);
+        run_devcluster_command(&args, command, fd_limit_check, fd_total_budget);
+        return;
     }
 
     let fd_total_budget = fd_budget::limit() - args.rpc_max_clients as i32 - args.inbound_limit as i32 - args.outbound_target as i32;
-    let (core, _) = create_core(args, fd_total_budget);
+    let runtime = Runtime::from_args(&args);
+    runtime.preflight_report().update(fd_limit_check);
+    let (core, _) = create_core_with_runtime(&runtime, &args, fd_total_budget);
 
     // Bind the keyboard signal to the core
     Arc::new(Signals::new(&core)).init();
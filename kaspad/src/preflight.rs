@@ -0,0 +1,514 @@
+//!
+//! Startup preflight checks of the node host: the soft file descriptor limit, the free disk space of the
+//! data directory and the offset of the local clock against the connected peers.
+//!
+//! A check passes, warns or fails. The node refuses to run on a failed check unless the check is overridden
+//! by its command line flag, then being reported as overridden. Results are logged and recorded in the
+//! [`RpcPreflightReport`] served by the `GetSystemInfo` RPC.
+//!
+
+use crate::{
+    args::Args,
+    daemon::{DESIRED_DAEMON_SOFT_FD_LIMIT, MINIMUM_DAEMON_SOFT_FD_LIMIT},
+};
+use kaspa_consensus_core::{config::Config, network::NetworkType};
+use kaspa_core::{
+    core::Core,
+    error, info,
+    signals::Shutdown,
+    task::{
+        service::{AsyncService, AsyncServiceFuture},
+        tick::{TickReason, TickService},
+    },
+    trace, warn,
+};
+use kaspa_p2p_flows::flow_context::FlowContext;
+use kaspa_rpc_core::{RpcPreflightCheck, RpcPreflightCheckStatus, RpcPreflightReport};
+use kaspa_utils::fd_budget;
+use std::{fmt::Display, fs, io, path::Path, process::exit, sync::Arc, time::Duration};
+use thiserror::Error;
+
+pub const FD_LIMIT_CHECK: &str = "fd-limit";
+pub const DISK_SPACE_CHECK: &str = "disk-space";
+pub const CLOCK_SKEW_CHECK: &str = "clock-skew";
+
+/// Offset of the local clock against the peers above which a warning is logged
+pub const CLOCK_SKEW_WARNING_THRESHOLD: Duration = Duration::from_secs(30);
+/// Offset of the local clock against the peers above which the node refuses to run. Kept below the
+/// mainnet timestamp deviation tolerance (132 seconds), past which valid blocks are seen as coming from the future.
+pub const CLOCK_SKEW_FAILURE_THRESHOLD: Duration = Duration::from_secs(120);
+/// Number of connected peers required for estimating the clock offset
+pub const CLOCK_SKEW_MIN_PEERS: usize = 3;
+const CLOCK_SKEW_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+const GB: u64 = 1_000_000_000;
+
+/// A byte count, displayed in gigabytes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bytes(pub u64);
+
+impl Display for Bytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.1} GB", self.0 as f64 / GB as f64)
+    }
+}
+
+/// An offset of the local clock in milliseconds, positive when the local clock is ahead
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClockOffset(pub i64);
+
+impl Display for ClockOffset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let direction = if self.0 < 0 { "behind" } else { "ahead of" };
+        write!(f, "{:.1}s {direction}", self.0.unsigned_abs() as f64 / 1000.0)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum PreflightError {
+    #[error(
+        "the soft file descriptor limit is {0}, below the minimum of {} (raise it with `ulimit -n {}`)",
+        MINIMUM_DAEMON_SOFT_FD_LIMIT,
+        DESIRED_DAEMON_SOFT_FD_LIMIT
+    )]
+    FdLimit(u64),
+
+    #[error("{available} of free disk space is left at {path}, below the minimum of {floor}")]
+    DiskSpace { path: String, available: Bytes, floor: Bytes },
+
+    #[error("the local clock is {offset} the median of {peers} peers, above the tolerance of {}s", CLOCK_SKEW_FAILURE_THRESHOLD.as_secs())]
+    ClockSkew { offset: ClockOffset, peers: usize },
+}
+
+impl PreflightError {
+    pub fn check(&self) -> &'static str {
+        match self {
+            PreflightError::FdLimit(_) => FD_LIMIT_CHECK,
+            PreflightError::DiskSpace { .. } => DISK_SPACE_CHECK,
+            PreflightError::ClockSkew { .. } => CLOCK_SKEW_CHECK,
+        }
+    }
+
+    /// The command line flag overriding the failed check
+    pub fn override_flag(&self) -> &'static str {
+        match self {
+            PreflightError::FdLimit(_) => "--ignore-fd-limit",
+            PreflightError::DiskSpace { .. } => "--ignore-low-disk-space",
+            PreflightError::ClockSkew { .. } => "--ignore-clock-skew",
+        }
+    }
+}
+
+pub type PreflightResult = Result<RpcPreflightCheck, PreflightError>;
+
+enum Verdict {
+    Passed(String),
+    Warning(String),
+    Failed(PreflightError),
+}
+
+fn conclude(name: &str, verdict: Verdict, ignore: bool) -> PreflightResult {
+    match verdict {
+        Verdict::Passed(message) => Ok(RpcPreflightCheck::new(name, RpcPreflightCheckStatus::Passed, message)),
+        Verdict::Warning(message) => Ok(RpcPreflightCheck::new(name, RpcPreflightCheckStatus::Warning, message)),
+        Verdict::Failed(err) if ignore => Ok(RpcPreflightCheck::new(name, RpcPreflightCheckStatus::Overridden, err.to_string())),
+        Verdict::Failed(err) => Err(err),
+    }
+}
+
+/// Access to the soft file descriptor limit of the process
+pub trait FdLimitProbe {
+    /// Tries raising the soft limit to `limit`, returning the resulting limit
+    fn raise(&self, limit: u64) -> io::Result<u64>;
+    fn current(&self) -> u64;
+}
+
+pub trait DiskProbe {
+    /// The disk space available to the node on the file system holding `path`
+    fn available_space(&self, path: &Path) -> io::Result<u64>;
+    /// The disk space used by the files under `path`
+    fn used_space(&self, path: &Path) -> io::Result<u64>;
+}
+
+pub trait ClockProbe {
+    /// The offsets in milliseconds of the local clock against each connected peer, positive when the local clock is ahead
+    fn peer_time_offsets(&self) -> Vec<i64>;
+}
+
+/// The probes of the host the node runs on
+pub struct HostProbe;
+
+impl FdLimitProbe for HostProbe {
+    fn raise(&self, limit: u64) -> io::Result<u64> {
+        fd_budget::try_set_fd_limit(limit)
+    }
+
+    fn current(&self) -> u64 {
+        fd_budget::limit() as u64
+    }
+}
+
+impl DiskProbe for HostProbe {
+    fn available_space(&self, path: &Path) -> io::Result<u64> {
+        fs2::available_space(path)
+    }
+
+    fn used_space(&self, path: &Path) -> io::Result<u64> {
+        let mut size = 0;
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            size += if metadata.is_dir() { self.used_space(&entry.path())? } else { metadata.len() };
+        }
+        Ok(size)
+    }
+}
+
+impl ClockProbe for FlowContext {
+    fn peer_time_offsets(&self) -> Vec<i64> {
+        self.hub().active_peers().iter().map(|peer| peer.properties().time_offset).collect()
+    }
+}
+
+/// Raises the soft file descriptor limit to [`DESIRED_DAEMON_SOFT_FD_LIMIT`] where possible, then checks the
+/// resulting limit, which must be at least [`MINIMUM_DAEMON_SOFT_FD_LIMIT`]
+pub fn check_fd_limit(probe: &impl FdLimitProbe, ignore: bool) -> PreflightResult {
+    let (limit, raise_error) = match probe.raise(DESIRED_DAEMON_SOFT_FD_LIMIT) {
+        Ok(limit) => (limit, None),
+        Err(err) => (probe.current(), Some(err)),
+    };
+    let verdict = if limit < MINIMUM_DAEMON_SOFT_FD_LIMIT {
+        Verdict::Failed(PreflightError::FdLimit(limit))
+    } else if limit < DESIRED_DAEMON_SOFT_FD_LIMIT {
+        let raise_error = raise_error.map(|err| format!(", raising it failed: {err}")).unwrap_or_default();
+        Verdict::Warning(format!(
+            "the soft file descriptor limit is {limit}, below the recommended {DESIRED_DAEMON_SOFT_FD_LIMIT}{raise_error} \
+            (raise it with `ulimit -n {DESIRED_DAEMON_SOFT_FD_LIMIT}`)"
+        ))
+    } else {
+        Verdict::Passed(format!("the soft file descriptor limit is {limit}"))
+    };
+    conclude(FD_LIMIT_CHECK, verdict, ignore)
+}
+
+/// Estimate of the disk space required by the data directory
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiskSpaceEstimate {
+    /// The expected size of the data directory
+    pub expected: u64,
+    /// The free disk space under which the node refuses to run
+    pub floor: u64,
+}
+
+impl DiskSpaceEstimate {
+    pub fn new(network_type: NetworkType, is_archival: bool) -> Self {
+        let (expected, floor) = match (network_type, is_archival) {
+            (NetworkType::Mainnet, false) => (50 * GB, 10 * GB),
+            (NetworkType::Mainnet, true) => (1000 * GB, 50 * GB),
+            (NetworkType::Testnet, false) => (50 * GB, 10 * GB),
+            (NetworkType::Testnet, true) => (500 * GB, 50 * GB),
+            (NetworkType::Devnet | NetworkType::Simnet, _) => (2 * GB, GB / 2),
+        };
+        Self { expected, floor }
+    }
+}
+
+/// Checks the free disk space at the data directory `path`, which must be at least the floor of `estimate`.
+/// A warning is issued when the data directory cannot grow to its expected size.
+pub fn check_disk_space(probe: &impl DiskProbe, path: &Path, estimate: DiskSpaceEstimate, ignore: bool) -> PreflightResult {
+    let available = match probe.available_space(path) {
+        Ok(available) => available,
+        Err(err) => {
+            let verdict = Verdict::Warning(format!("unable to determine the free disk space at {}: {err}", path.display()));
+            return conclude(DISK_SPACE_CHECK, verdict, ignore);
+        }
+    };
+    let verdict = if available < estimate.floor {
+        let path = path.display().to_string();
+        Verdict::Failed(PreflightError::DiskSpace { path, available: Bytes(available), floor: Bytes(estimate.floor) })
+    } else {
+        // The data directory of a synced node already holds most of its expected size
+        let needed = estimate.expected.saturating_sub(probe.used_space(path).unwrap_or_default());
+        if available < needed {
+            Verdict::Warning(format!(
+                "{} of free disk space is left at {} while the data directory is expected to grow by {} (up to about {})",
+                Bytes(available),
+                path.display(),
+                Bytes(needed),
+                Bytes(estimate.expected)
+            ))
+        } else {
+            Verdict::Passed(format!("{} of free disk space is left at {}", Bytes(available), path.display()))
+        }
+    };
+    conclude(DISK_SPACE_CHECK, verdict, ignore)
+}
+
+/// Estimates the offset of the local clock as the median of its offsets against the peers, which is robust to
+/// a minority of peers having a wrong clock. Returns `None` when less than [`CLOCK_SKEW_MIN_PEERS`] offsets are given.
+pub fn estimate_clock_offset(offsets: &[i64]) -> Option<i64> {
+    if offsets.len() < CLOCK_SKEW_MIN_PEERS {
+        return None;
+    }
+    let mut offsets = offsets.to_vec();
+    offsets.sort_unstable();
+    let mid = offsets.len() / 2;
+    if offsets.len() % 2 == 1 {
+        Some(offsets[mid])
+    } else {
+        Some(((offsets[mid - 1] as i128 + offsets[mid] as i128) / 2) as i64)
+    }
+}
+
+/// Checks the offset of the local clock against the connected peers. Returns `None` when too few peers are connected.
+pub fn check_clock_skew(probe: &impl ClockProbe, ignore: bool) -> Option<PreflightResult> {
+    let offsets = probe.peer_time_offsets();
+    let (offset, peers) = (ClockOffset(estimate_clock_offset(&offsets)?), offsets.len());
+    let skew = offset.0.unsigned_abs();
+    let verdict = if skew > CLOCK_SKEW_FAILURE_THRESHOLD.as_millis() as u64 {
+        Verdict::Failed(PreflightError::ClockSkew { offset, peers })
+    } else if skew > CLOCK_SKEW_WARNING_THRESHOLD.as_millis() as u64 {
+        Verdict::Warning(format!(
+            "the local clock is {offset} the median of {peers} peers, above the recommended {}s (synchronize it, e.g. with NTP)",
+            CLOCK_SKEW_WARNING_THRESHOLD.as_secs()
+        ))
+    } else {
+        Verdict::Passed(format!("the local clock is {offset} the median of {peers} peers"))
+    };
+    Some(conclude(CLOCK_SKEW_CHECK, verdict, ignore))
+}
+
+pub fn log_check(check: &RpcPreflightCheck) {
+    match check.status {
+        RpcPreflightCheckStatus::Passed | RpcPreflightCheckStatus::Pending => {
+            info!("Preflight check {}: {}", check.name, check.message)
+        }
+        status => warn!("Preflight check {} {}: {}", check.name, status, check.message),
+    }
+}
+
+pub fn log_failure(err: &PreflightError) {
+    error!("Preflight check {} failed: {err}. Pass {} to run anyway", err.check(), err.override_flag());
+}
+
+/// Runs the checks due once the data directory `db_dir` exists, exiting the process on failure. The checks
+/// already recorded in `report`, i.e. the fd limit check run before initializing the node, are logged as well.
+/// The clock skew check is recorded as pending, being concluded by the [`ClockSkewMonitor`].
+pub fn run_startup_checks(args: &Args, config: &Config, db_dir: &Path, report: &RpcPreflightReport) {
+    report.checks().iter().for_each(log_check);
+
+    let estimate = DiskSpaceEstimate::new(config.net.network_type, config.is_archival);
+    match check_disk_space(&HostProbe, db_dir, estimate, args.ignore_low_disk_space) {
+        Ok(check) => {
+            log_check(&check);
+            report.update(check);
+        }
+        Err(err) => {
+            log_failure(&err);
+            exit(1);
+        }
+    }
+
+    let message = format!("waiting for {CLOCK_SKEW_MIN_PEERS} connected peers to estimate the clock offset");
+    report.update(RpcPreflightCheck::new(CLOCK_SKEW_CHECK, RpcPreflightCheckStatus::Pending, message));
+}
+
+const CLOCK_SKEW_MONITOR: &str = "clock-skew-monitor";
+
+/// Concludes the clock skew check once enough peers are connected, shutting the node down on failure
+pub struct ClockSkewMonitor {
+    flow_context: Arc<FlowContext>,
+    tick_service: Arc<TickService>,
+    core: Arc<Core>,
+    report: RpcPreflightReport,
+    ignore: bool,
+}
+
+impl ClockSkewMonitor {
+    pub fn new(
+        flow_context: Arc<FlowContext>,
+        tick_service: Arc<TickService>,
+        core: Arc<Core>,
+        report: RpcPreflightReport,
+        ignore: bool,
+    ) -> Self {
+        Self { flow_context, tick_service, core, report, ignore }
+    }
+
+    async fn worker(self: &Arc<Self>) {
+        loop {
+            if let TickReason::Shutdown = self.tick_service.tick(CLOCK_SKEW_POLL_INTERVAL).await {
+                break;
+            }
+            let Some(result) = check_clock_skew(self.flow_context.as_ref(), self.ignore) else {
+                continue;
+            };
+            match result {
+                Ok(check) => {
+                    log_check(&check);
+                    self.report.update(check);
+                }
+                Err(err) => {
+                    log_failure(&err);
+                    self.report.update(RpcPreflightCheck::new(CLOCK_SKEW_CHECK, RpcPreflightCheckStatus::Failed, err.to_string()));
+                    self.core.shutdown();
+                }
+            }
+            break;
+        }
+        trace!("{} exiting", CLOCK_SKEW_MONITOR);
+    }
+}
+
+impl AsyncService for ClockSkewMonitor {
+    fn ident(self: Arc<Self>) -> &'static str {
+        CLOCK_SKEW_MONITOR
+    }
+
+    fn start(self: Arc<Self>) -> AsyncServiceFuture {
+        Box::pin(async move {
+            self.worker().await;
+            Ok(())
+        })
+    }
+
+    fn signal_exit(self: Arc<Self>) {
+        trace!("sending an exit signal to {}", CLOCK_SKEW_MONITOR);
+    }
+
+    fn stop(self: Arc<Self>) -> AsyncServiceFuture {
+        Box::pin(async move {
+            trace!("{} stopped", CLOCK_SKEW_MONITOR);
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Raises the limit up to `hard`, or fails raising it when `hard` is `None`
+    struct MockFdLimit {
+        hard: Option<u64>,
+        current: u64,
+    }
+
+    impl FdLimitProbe for MockFdLimit {
+        fn raise(&self, limit: u64) -> io::Result<u64> {
+            self.hard.map(|hard| limit.min(hard)).ok_or_else(|| io::Error::other("operation not permitted"))
+        }
+
+        fn current(&self) -> u64 {
+            self.current
+        }
+    }
+
+    struct MockDisk {
+        available: Option<u64>,
+        used: u64,
+    }
+
+    impl DiskProbe for MockDisk {
+        fn available_space(&self, _: &Path) -> io::Result<u64> {
+            self.available.ok_or_else(|| io::Error::other("unsupported file system"))
+        }
+
+        fn used_space(&self, _: &Path) -> io::Result<u64> {
+            Ok(self.used)
+        }
+    }
+
+    struct MockClock(Vec<i64>);
+
+    impl ClockProbe for MockClock {
+        fn peer_time_offsets(&self) -> Vec<i64> {
+            self.0.clone()
+        }
+    }
+
+    fn status(result: &PreflightResult) -> Option<RpcPreflightCheckStatus> {
+        result.as_ref().ok().map(|check| check.status)
+    }
+
+    #[test]
+    fn test_fd_limit_check() {
+        use RpcPreflightCheckStatus::*;
+        let fd_limit = |hard, current| MockFdLimit { hard, current };
+
+        assert_eq!(status(&check_fd_limit(&fd_limit(Some(u64::MAX), 1024), false)), Some(Passed));
+        assert_eq!(status(&check_fd_limit(&fd_limit(Some(DESIRED_DAEMON_SOFT_FD_LIMIT - 1), 1024), false)), Some(Warning));
+        assert_eq!(status(&check_fd_limit(&fd_limit(Some(MINIMUM_DAEMON_SOFT_FD_LIMIT), 1024), false)), Some(Warning));
+        assert_eq!(
+            check_fd_limit(&fd_limit(Some(MINIMUM_DAEMON_SOFT_FD_LIMIT - 1), 1024), false),
+            Err(PreflightError::FdLimit(MINIMUM_DAEMON_SOFT_FD_LIMIT - 1))
+        );
+        assert_eq!(status(&check_fd_limit(&fd_limit(Some(MINIMUM_DAEMON_SOFT_FD_LIMIT - 1), 1024), true)), Some(Overridden));
+
+        // The current limit is checked when raising it fails
+        assert_eq!(status(&check_fd_limit(&fd_limit(None, DESIRED_DAEMON_SOFT_FD_LIMIT), false)), Some(Passed));
+        let check = check_fd_limit(&fd_limit(None, MINIMUM_DAEMON_SOFT_FD_LIMIT), false).unwrap();
+        assert_eq!(check.status, Warning);
+        assert!(check.message.contains("operation not permitted"), "{}", check.message);
+        assert_eq!(check_fd_limit(&fd_limit(None, 1024), false), Err(PreflightError::FdLimit(1024)));
+    }
+
+    #[test]
+    fn test_disk_space_check() {
+        use RpcPreflightCheckStatus::*;
+        let estimate = DiskSpaceEstimate::new(NetworkType::Mainnet, false);
+        let check = |available, used, ignore| check_disk_space(&MockDisk { available, used }, Path::new("/data"), estimate, ignore);
+
+        assert_eq!(status(&check(Some(estimate.expected), 0, false)), Some(Passed));
+        assert_eq!(status(&check(Some(estimate.expected - 1), 0, false)), Some(Warning));
+        assert_eq!(status(&check(Some(estimate.floor), 0, false)), Some(Warning));
+        assert!(matches!(check(Some(estimate.floor - 1), 0, false), Err(PreflightError::DiskSpace { .. })));
+        assert_eq!(status(&check(Some(estimate.floor - 1), 0, true)), Some(Overridden));
+
+        // The space already used by the data directory counts toward its expected size, not toward the floor
+        assert_eq!(status(&check(Some(estimate.floor), estimate.expected - estimate.floor, false)), Some(Passed));
+        assert!(check(Some(estimate.floor - 1), estimate.expected, false).is_err());
+
+        // Failing to probe the free space only warns
+        assert_eq!(status(&check(None, 0, false)), Some(Warning));
+    }
+
+    #[test]
+    fn test_disk_space_estimate() {
+        for network_type in [NetworkType::Mainnet, NetworkType::Testnet, NetworkType::Devnet, NetworkType::Simnet] {
+            let (pruned, archival) = (DiskSpaceEstimate::new(network_type, false), DiskSpaceEstimate::new(network_type, true));
+            assert!(pruned.floor < pruned.expected && archival.floor < archival.expected);
+            assert!(pruned.expected <= archival.expected, "{network_type}");
+        }
+    }
+
+    #[test]
+    fn test_clock_offset_estimate() {
+        assert_eq!(estimate_clock_offset(&[]), None);
+        assert_eq!(estimate_clock_offset(&[1000, 2000]), None);
+        // A peer with a wrong clock does not move the median
+        assert_eq!(estimate_clock_offset(&[1000, -500_000, 2000]), Some(1000));
+        assert_eq!(estimate_clock_offset(&[4000, 1000, 2000, 3000]), Some(2500));
+        assert_eq!(estimate_clock_offset(&[i64::MAX, i64::MAX, i64::MAX, i64::MAX]), Some(i64::MAX));
+    }
+
+    #[test]
+    fn test_clock_skew_check() {
+        use RpcPreflightCheckStatus::*;
+        let warning = CLOCK_SKEW_WARNING_THRESHOLD.as_millis() as i64;
+        let failure = CLOCK_SKEW_FAILURE_THRESHOLD.as_millis() as i64;
+        let check = |offset: i64, ignore| check_clock_skew(&MockClock(vec![offset; CLOCK_SKEW_MIN_PEERS]), ignore).unwrap();
+
+        assert!(check_clock_skew(&MockClock(vec![failure * 2; CLOCK_SKEW_MIN_PEERS - 1]), false).is_none());
+        assert_eq!(status(&check(0, false)), Some(Passed));
+        assert_eq!(status(&check(warning, false)), Some(Passed));
+        assert_eq!(status(&check(-warning - 1, false)), Some(Warning));
+        assert_eq!(status(&check(failure, false)), Some(Warning));
+        assert_eq!(check(failure + 1, false), Err(PreflightError::ClockSkew { offset: ClockOffset(failure + 1), peers: 3 }));
+        assert!(matches!(check(-failure - 1, false), Err(PreflightError::ClockSkew { .. })));
+        assert_eq!(status(&check(-failure - 1, true)), Some(Overridden));
+
+        let err = check(-failure - 1000, false).unwrap_err();
+        assert_eq!(err.to_string(), "the local clock is 121.0s behind the median of 3 peers, above the tolerance of 120s");
+    }
+}
@@ -0,0 +1,35 @@
+use async_trait::async_trait;
+use kaspa_addresses::Address;
+use kaspa_consensus_core::block::Block;
+use kaspa_mining::stratum::{StratumBackend, StratumBackendResult};
+use kaspa_rpc_core::{api::rpc::RpcApi, RpcBlock, SubmitBlockReport};
+use kaspa_rpc_service::service::RpcCoreService;
+use std::sync::Arc;
+
+/// Backend of the built-in stratum server, going through the RPC service so that templates are served from the
+/// template cache and solved blocks are validated and relayed like blocks submitted by RPC miners
+pub struct RpcStratumBackend {
+    rpc: Arc<RpcCoreService>,
+}
+
+impl RpcStratumBackend {
+    pub fn new(rpc: Arc<RpcCoreService>) -> Self {
+        Self { rpc }
+    }
+}
+
+#[async_trait]
+impl StratumBackend for RpcStratumBackend {
+    async fn get_block_template(&self, pay_address: Address, extra_data: Vec<u8>) -> StratumBackendResult<Block> {
+        let response = self.rpc.get_block_template(pay_address, extra_data).await.map_err(|err| err.to_string())?;
+        Block::try_from(&response.block).map_err(|err| err.to_string())
+    }
+
+    async fn submit_block(&self, block: Block) -> StratumBackendResult<()> {
+        let response = self.rpc.submit_block(RpcBlock::from(&block), false).await.map_err(|err| err.to_string())?;
+        match response.report {
+            SubmitBlockReport::Success => Ok(()),
+            SubmitBlockReport::Reject(reason) => Err(reason.to_string()),
+        }
+    }
+}
@@ -7,7 +7,7 @@ pub mod uint;
 pub mod wasm;
 
 construct_uint!(Uint192, 3, BorshSerialize, BorshDeserialize);
-construct_uint!(Uint256, 4);
+construct_uint!(Uint256, 4, BorshSerialize, BorshDeserialize);
 construct_uint!(Uint320, 5);
 construct_uint!(Uint3072, 48);
 
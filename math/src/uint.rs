@@ -408,6 +408,16 @@ macro_rules! construct_uint {
                 Ok(Self::from_be_bytes(out))
             }
 
+            /// Like [`Self::from_hex`], additionally accepting a `0x` prefix as emitted by JS `BigInt` and other external tools
+            #[inline]
+            pub fn from_hex_lenient(hex: &str) -> Result<Self, $crate::uint::faster_hex::Error> {
+                let hex = hex.strip_prefix("0x").or_else(|| hex.strip_prefix("0X")).unwrap_or(hex);
+                if hex.is_empty() {
+                    return Err($crate::uint::faster_hex::Error::InvalidLength(0));
+                }
+                Self::from_hex(hex)
+            }
+
             #[inline]
             pub fn from_be_bytes_var(bytes: &[u8]) -> Result<Self, $crate::uint::TryFromSliceError> {
                 if bytes.len() > Self::BYTES {
@@ -971,7 +981,9 @@ macro_rules! construct_uint {
             fn try_from(js_value: wasm_bindgen::JsValue) -> Result<Self, Self::Error> {
                 use $crate::wasm::*;
 
-                if js_value.is_string() || js_value.is_array() {
+                if let Some(hex) = js_value.as_string() {
+                    Ok(Self::from_hex_lenient(&hex)?)
+                } else if js_value.is_array() {
                     let bytes = js_value.try_as_vec_u8()?;
                     Ok(Self::from_be_bytes_var(&bytes)?)
                 } else if js_value.is_bigint() {
@@ -1150,6 +1162,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_from_hex_lenient() {
+        let value = Uint128::from_u128(0xabcdef0123456789abcdef);
+        for hex in ["abcdef0123456789abcdef", "ABCDEF0123456789ABCDEF", "0xAbCdEf0123456789abcdef", "0X00abcdef0123456789abcdef"] {
+            assert_eq!(Uint128::from_hex_lenient(hex).unwrap(), value);
+        }
+        // Odd lengths are padded, as done by `from_hex`
+        assert_eq!(Uint128::from_hex_lenient("0xf").unwrap(), Uint128::from_u64(15));
+        assert!(Uint128::from_hex_lenient("0x").is_err());
+        assert!(Uint128::from_hex_lenient("").is_err());
+        assert!(Uint128::from_hex_lenient(&format!("0x1{}", "0".repeat(32))).is_err());
+        assert!(Uint128::from_hex_lenient("0xabcg").is_err());
+        // Hex is always emitted in lowercase
+        assert_eq!(format!("{value:x}"), "abcdef0123456789abcdef");
+    }
+
     #[test]
     fn test_mod_inv() {
         use core::cmp::Ordering;
@@ -63,6 +63,13 @@ pub enum RuleError {
     #[error("number of high-priority transactions in orphan pool ({0}) has reached the maximum allowed ({1})")]
     RejectOrphanPoolIsFull(usize, u64),
 
+    #[error("transaction {0} is double spending an input from already held transaction {1}")]
+    RejectDoubleSpendHeld(TransactionId, TransactionId),
+
+    /// A transaction is rejected if the pool of transactions held until valid is full
+    #[error("number of transactions held until valid ({0}) has reached the maximum allowed ({1})")]
+    RejectHeldPoolIsFull(usize, u64),
+
     #[error("transactions in mempool form a cycle")]
     RejectCycleInMempoolTransactions,
 
@@ -115,8 +122,8 @@ pub enum NonStandardError {
     #[error("transaction output #{1}: non-standard script form")]
     RejectOutputScriptClass(TransactionId, usize),
 
-    #[error("transaction output #{1}: payment of {2} is dust")]
-    RejectDust(TransactionId, usize, u64),
+    #[error("transaction output #{1}: payment of {2} is dust (minimum {3})")]
+    RejectDust(TransactionId, usize, u64, u64),
 
     #[error("transaction input {1}: non-standard script form")]
     RejectInputScriptClass(TransactionId, usize),
@@ -137,7 +144,7 @@ impl NonStandardError {
             NonStandardError::RejectSignatureScriptSize(id, _, _, _) => id,
             NonStandardError::RejectScriptPublicKeyVersion(id, _) => id,
             NonStandardError::RejectOutputScriptClass(id, _) => id,
-            NonStandardError::RejectDust(id, _, _) => id,
+            NonStandardError::RejectDust(id, _, _, _) => id,
             NonStandardError::RejectInputScriptClass(id, _) => id,
             NonStandardError::RejectInsufficientFee(id, _, _) => id,
             NonStandardError::RejectSignatureCount(id, _, _, _) => id,
@@ -1,5 +1,7 @@
 use super::{errors::BuilderResult, policy::Policy};
-use crate::{block_template::selector::TransactionsSelector, model::candidate_tx::CandidateTransaction};
+use crate::{
+    block_template::selector::TransactionsSelector, mempool::config::TemplateMassQuota, model::candidate_tx::CandidateTransaction,
+};
 use kaspa_consensus_core::{
     api::ConsensusApi,
     block::{BlockTemplate, TemplateBuildMode},
@@ -7,18 +9,15 @@ use kaspa_consensus_core::{
     merkle::calc_hash_merkle_root,
     tx::COINBASE_TRANSACTION_INDEX,
 };
-use kaspa_core::{
-    debug,
-    time::{unix_now, Stopwatch},
-};
+use kaspa_core::{debug, time::Stopwatch};
 
 pub(crate) struct BlockTemplateBuilder {
     policy: Policy,
 }
 
 impl BlockTemplateBuilder {
-    pub(crate) fn new(max_block_mass: u64) -> Self {
-        let policy = Policy::new(max_block_mass);
+    pub(crate) fn new(max_block_mass: u64, max_block_transactions: usize, mass_quota: Option<TemplateMassQuota>) -> Self {
+        let policy = Policy::new(max_block_mass, max_block_transactions, mass_quota);
         Self { policy }
     }
 
@@ -99,10 +98,12 @@ impl BlockTemplateBuilder {
     }
 
     /// modify_block_template clones an existing block template, modifies it to the requested coinbase data and updates the timestamp
+    /// to `now` when later than the template timestamp
     pub(crate) fn modify_block_template(
         consensus: &dyn ConsensusApi,
         new_miner_data: &MinerData,
         block_template_to_modify: &BlockTemplate,
+        now: u64,
     ) -> BuilderResult<BlockTemplate> {
         let mut block_template = block_template_to_modify.clone();
 
@@ -116,11 +117,10 @@ impl BlockTemplateBuilder {
         }
         // Update the hash merkle root according to the modified transactions
         block_template.block.header.hash_merkle_root = calc_hash_merkle_root(block_template.block.transactions.iter());
-        let new_timestamp = unix_now();
-        if new_timestamp > block_template.block.header.timestamp {
+        if now > block_template.block.header.timestamp {
             // Only if new time stamp is later than current, update the header. Otherwise,
             // we keep the previous time as built by internal consensus median time logic
-            block_template.block.header.timestamp = new_timestamp;
+            block_template.block.header.timestamp = now;
         }
         block_template.block.header.finalize();
         block_template.miner_data = new_miner_data.clone();
@@ -1,3 +1,5 @@
+use crate::mempool::config::TemplateMassQuota;
+
 /// Policy houses the policy (configuration parameters) which is used to control
 /// the generation of block templates. See the documentation for
 /// NewBlockTemplate for more details on each of these parameters are used.
@@ -5,10 +7,16 @@
 pub(crate) struct Policy {
     /// max_block_mass is the maximum block mass to be used when generating a block template.
     pub(crate) max_block_mass: u64,
+
+    /// max_block_transactions is the maximum number of transactions of a block template, coinbase included.
+    pub(crate) max_block_transactions: usize,
+
+    /// mass_quota optionally caps the template mass of a single transaction origin.
+    pub(crate) mass_quota: Option<TemplateMassQuota>,
 }
 
 impl Policy {
-    pub(crate) fn new(max_block_mass: u64) -> Self {
-        Self { max_block_mass }
+    pub(crate) fn new(max_block_mass: u64, max_block_transactions: usize, mass_quota: Option<TemplateMassQuota>) -> Self {
+        Self { max_block_mass, max_block_transactions, mass_quota }
     }
 }
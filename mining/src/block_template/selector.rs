@@ -2,16 +2,16 @@ use kaspa_core::{time::Stopwatch, trace};
 use rand::Rng;
 use std::collections::HashMap;
 
-use crate::model::candidate_tx::CandidateTransaction;
+use crate::{mempool::config::TemplateMassQuota, model::candidate_tx::CandidateTransaction};
 
 use super::{
     model::tx::{CandidateList, SelectableTransaction, SelectableTransactions, TransactionIndex},
     policy::Policy,
 };
 use kaspa_consensus_core::{
-    block::TemplateTransactionSelector,
+    block::{TemplateSelectionStats, TemplateTransactionSelector},
     subnets::SubnetworkId,
-    tx::{Transaction, TransactionId},
+    tx::{ScriptPublicKey, Transaction, TransactionId},
 };
 
 /// ALPHA is a coefficient that defines how uniform the distribution of
@@ -42,6 +42,9 @@ pub(crate) struct TransactionsSelector {
     /// to the index of the tx in the `transactions` vec
     selected_txs_map: Option<HashMap<TransactionId, TransactionIndex>>,
 
+    /// Marks the transactions selected over all calls and not rejected since
+    included: Vec<bool>,
+
     // Inner state of the selection process
     candidate_list: CandidateList,
     overall_rejections: usize,
@@ -49,7 +52,80 @@ pub(crate) struct TransactionsSelector {
     used_p: f64,
     total_mass: u64,
     total_fees: u64,
+    total_count: usize,
+    gas_usage_map: HashMap<SubnetworkId, u64>,
+
+    /// Inner state of the deterministic selection honoring the [`TemplateMassQuota`] policy, if enabled
+    quota: Option<QuotaSelection>,
+}
+
+/// Inner state of the selection honoring a [`TemplateMassQuota`]
+struct QuotaSelection {
+    quota: TemplateMassQuota,
+    /// Indexes of the transactions by descending feerate
+    order: Vec<TransactionIndex>,
+    /// Marks the transactions selected by a previous call, which are never selected again (even if rejected)
+    used: Vec<bool>,
+    /// The mass of the currently selected transactions by origin
+    origin_mass: HashMap<ScriptPublicKey, u64>,
+}
+
+/// Outcome of trying to add a transaction to a [`TemplateFill`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Admission {
+    Added,
+    /// The transaction origin would exceed its mass quota
+    OverQuota,
+    /// The transaction does not fit the template mass or gas limits
+    NoRoom,
+    /// The template reached its maximum transaction count
+    Full,
+}
+
+/// A tentative fill of the template room, on top of the transactions already selected
+#[derive(Clone)]
+struct TemplateFill {
+    selected: Vec<TransactionIndex>,
+    total_mass: u64,
+    total_fees: u64,
+    total_count: usize,
     gas_usage_map: HashMap<SubnetworkId, u64>,
+    origin_mass: HashMap<ScriptPublicKey, u64>,
+}
+
+impl TemplateFill {
+    fn try_add(&mut self, selector: &TransactionsSelector, index: TransactionIndex, origin_cap: Option<u64>) -> Admission {
+        let tx = &selector.transactions[index];
+        if self.total_count + 1 >= selector.policy.max_block_transactions {
+            return Admission::Full;
+        }
+        match self.total_mass.checked_add(tx.calculated_mass) {
+            Some(mass) if mass <= selector.policy.max_block_mass => {}
+            _ => return Admission::NoRoom,
+        }
+        if let (Some(cap), Some(origin)) = (origin_cap, tx.origin.as_ref()) {
+            if self.origin_mass.get(origin).copied().unwrap_or_default() + tx.calculated_mass > cap {
+                return Admission::OverQuota;
+            }
+        }
+        if !tx.tx.subnetwork_id.is_builtin_or_native() {
+            let gas_usage = self.gas_usage_map.get(&tx.tx.subnetwork_id).copied().unwrap_or_default();
+            match gas_usage.checked_add(tx.tx.gas) {
+                Some(gas_usage) if gas_usage <= selector.selectable_txs[index].gas_limit => {
+                    self.gas_usage_map.insert(tx.tx.subnetwork_id.clone(), gas_usage);
+                }
+                _ => return Admission::NoRoom,
+            }
+        }
+        if let Some(origin) = tx.origin.as_ref() {
+            *self.origin_mass.entry(origin.clone()).or_default() += tx.calculated_mass;
+        }
+        self.selected.push(index);
+        self.total_mass += tx.calculated_mass;
+        self.total_fees += tx.calculated_fee;
+        self.total_count += 1;
+        Admission::Added
+    }
 }
 
 impl TransactionsSelector {
@@ -65,13 +141,16 @@ impl TransactionsSelector {
             selectable_txs: Default::default(),
             selected_txs: Default::default(),
             selected_txs_map: None,
+            included: Default::default(),
             candidate_list: Default::default(),
             overall_rejections: 0,
             used_count: 0,
             used_p: 0.0,
             total_mass: 0,
             total_fees: 0,
+            total_count: 0,
             gas_usage_map: Default::default(),
+            quota: None,
         };
 
         // Create the selectable transactions
@@ -79,6 +158,16 @@ impl TransactionsSelector {
             selector.transactions.iter().map(|x| SelectableTransaction::new(selector.calc_tx_value(x), 0, ALPHA)).collect();
         // Prepare the initial candidate list
         selector.candidate_list = CandidateList::new(&selector.selectable_txs);
+        selector.included = vec![false; selector.transactions.len()];
+
+        // Order the transactions by feerate for the quota-honoring selection
+        if let Some(quota) = selector.policy.mass_quota {
+            let feerate = |x: &CandidateTransaction| x.calculated_fee as f64 / x.calculated_mass as f64;
+            let mut order = (0..selector.transactions.len()).collect::<Vec<_>>();
+            order.sort_by(|&a, &b| feerate(&selector.transactions[b]).total_cmp(&feerate(&selector.transactions[a])).then(a.cmp(&b)));
+            let used = vec![false; selector.transactions.len()];
+            selector.quota = Some(QuotaSelection { quota, order, used, origin_mass: Default::default() });
+        }
 
         selector
     }
@@ -109,6 +198,10 @@ impl TransactionsSelector {
 
         self.reset_selection();
 
+        if self.quota.is_some() {
+            return self.select_transactions_with_quota();
+        }
+
         while self.candidate_list.candidates.len() - self.used_count > 0 {
             // Rebalance the candidates if it's required
             if self.used_p >= REBALANCE_THRESHOLD * self.candidate_list.total_p {
@@ -142,6 +235,12 @@ impl TransactionsSelector {
                 break;
             }
 
+            // Enforce maximum transaction count per block, leaving room for the coinbase
+            if self.total_count + 1 >= self.policy.max_block_transactions {
+                trace!("Tx {0} would exceed the max block transaction count. As such, stopping.", selected_tx.tx.id());
+                break;
+            }
+
             // Enforce maximum gas per subnetwork per block.
             // Also check for overflow.
             if !selected_tx.tx.subnetwork_id.is_builtin_or_native() {
@@ -181,6 +280,7 @@ impl TransactionsSelector {
             self.selected_txs.push(selected_candidate.index);
             self.total_mass += selected_tx.calculated_mass;
             self.total_fees += selected_tx.calculated_fee;
+            self.total_count += 1;
 
             trace!(
                 "Adding tx {0} (fee per megagram: {1})",
@@ -194,11 +294,95 @@ impl TransactionsSelector {
             self.used_p += self.selectable_txs[selected_candidate.index].p;
         }
 
-        self.selected_txs.sort();
+        self.sort_selected();
+        self.mark_included();
 
         self.get_transactions()
     }
 
+    /// select_transactions_with_quota implements a deterministic transaction selection honoring the
+    /// [`TemplateMassQuota`] policy. Since candidate transactions only spend confirmed outputs, the
+    /// feerate of a transaction is also the feerate of its package. The algorithm is as follows:
+    /// 1. Fill the template by descending feerate, ignoring the quota. The fees of this reference fill
+    ///    are the best achievable ones.
+    /// 2. Fill the template by descending feerate again, deferring the transactions whose origin would
+    ///    exceed its mass quota, so the remaining room goes to the next-best transactions of other origins.
+    /// 3. While the fees of the capped fill are below the tolerance of the reference fees, admit the
+    ///    deferred transactions best first, ignoring the quota.
+    /// 4. If the fees are still below tolerance, fall back to the reference fill.
+    fn select_transactions_with_quota(&mut self) -> Vec<Transaction> {
+        let mut quota = self.quota.take().expect("the quota selection is enabled");
+        let candidates = quota.order.iter().copied().filter(|&x| !quota.used[x]).collect::<Vec<_>>();
+        let initial = TemplateFill {
+            selected: Vec::new(),
+            total_mass: self.total_mass,
+            total_fees: self.total_fees,
+            total_count: self.total_count,
+            gas_usage_map: self.gas_usage_map.clone(),
+            origin_mass: std::mem::take(&mut quota.origin_mass),
+        };
+
+        let mut reference = initial.clone();
+        for &index in candidates.iter() {
+            if reference.try_add(self, index, None) == Admission::Full {
+                break;
+            }
+        }
+
+        let origin_cap = (quota.quota.max_origin_mass_ratio * self.policy.max_block_mass as f64) as u64;
+        let mut capped = initial.clone();
+        let mut deferred = Vec::new();
+        for &index in candidates.iter() {
+            match capped.try_add(self, index, Some(origin_cap)) {
+                Admission::OverQuota => deferred.push(index),
+                Admission::Full => break,
+                Admission::Added | Admission::NoRoom => {}
+            }
+        }
+
+        let min_fees = (reference.total_fees - initial.total_fees) as f64 * (1.0 - quota.quota.max_fee_loss_ratio);
+        let within_tolerance = |fill: &TemplateFill| (fill.total_fees - initial.total_fees) as f64 >= min_fees;
+        for index in deferred {
+            if within_tolerance(&capped) || capped.try_add(self, index, None) == Admission::Full {
+                break;
+            }
+        }
+        let fill = if within_tolerance(&capped) {
+            capped
+        } else {
+            trace!("The template mass quota would reduce the template fees beyond tolerance. As such, ignoring it.");
+            reference
+        };
+
+        for &index in fill.selected.iter() {
+            quota.used[index] = true;
+        }
+        self.selected_txs = fill.selected;
+        self.total_mass = fill.total_mass;
+        self.total_fees = fill.total_fees;
+        self.total_count = fill.total_count;
+        self.gas_usage_map = fill.gas_usage_map;
+        quota.origin_mass = fill.origin_mass;
+        self.quota = Some(quota);
+
+        self.sort_selected();
+        self.mark_included();
+
+        self.get_transactions()
+    }
+
+    /// Sorts the selected transactions by the canonical template order, see [`CandidateTransaction::cmp_template_order`]
+    fn sort_selected(&mut self) {
+        let transactions = &self.transactions;
+        self.selected_txs.sort_unstable_by(|&a, &b| transactions[a].cmp_template_order(&transactions[b]));
+    }
+
+    fn mark_included(&mut self) {
+        for &index in self.selected_txs.iter() {
+            self.included[index] = true;
+        }
+    }
+
     fn get_transactions(&self) -> Vec<Transaction> {
         // These transactions leave the selector so we clone
         self.selected_txs.iter().map(|x| self.transactions[*x].tx.as_ref().clone()).collect()
@@ -240,15 +424,27 @@ impl TemplateTransactionSelector for TransactionsSelector {
             // We lazy-create the map only when there are actual rejections
             .get_or_insert_with(|| self.selected_txs.iter().map(|&x| (self.transactions[x].tx.id(), x)).collect());
         let tx_index = selected_txs_map.remove(&tx_id).expect("only previously selected txs can be rejected (and only once)");
+        self.included[tx_index] = false;
         let tx = &self.transactions[tx_index];
         self.total_mass -= tx.calculated_mass;
         self.total_fees -= tx.calculated_fee;
+        self.total_count -= 1;
         if !tx.tx.subnetwork_id.is_builtin_or_native() {
             *self.gas_usage_map.get_mut(&tx.tx.subnetwork_id).expect("previously selected txs have an entry") -= tx.tx.gas;
         }
+        if let (Some(quota), Some(origin)) = (self.quota.as_mut(), tx.origin.as_ref()) {
+            *quota.origin_mass.get_mut(origin).expect("previously selected txs have an entry") -= tx.calculated_mass;
+        }
         self.overall_rejections += 1;
     }
 
+    fn sort_selected_transactions(&self, transactions: &mut [Transaction]) {
+        let indexes: HashMap<TransactionId, TransactionIndex> =
+            self.transactions.iter().enumerate().map(|(index, x)| (x.tx.id(), index)).collect();
+        transactions
+            .sort_unstable_by(|a, b| self.transactions[indexes[&a.id()]].cmp_template_order(&self.transactions[indexes[&b.id()]]));
+    }
+
     fn is_successful(&self) -> bool {
         const SUFFICIENT_MASS_THRESHOLD: f64 = 0.8;
         const LOW_REJECTION_FRACTION: f64 = 0.2;
@@ -258,6 +454,25 @@ impl TemplateTransactionSelector for TransactionsSelector {
             || (self.total_mass as f64) > self.policy.max_block_mass as f64 * SUFFICIENT_MASS_THRESHOLD
             || (self.overall_rejections as f64) < self.transactions.len() as f64 * LOW_REJECTION_FRACTION
     }
+
+    fn selection_stats(&self) -> Option<TemplateSelectionStats> {
+        // The totals are maintained along the selection, so only the feerate requires a pass over the transactions
+        let min_feerate = self
+            .transactions
+            .iter()
+            .zip(self.included.iter())
+            .filter(|(_, &included)| included)
+            .map(|(tx, _)| tx.calculated_fee as f64 / tx.calculated_mass as f64)
+            .min_by(f64::total_cmp);
+        Some(TemplateSelectionStats {
+            total_fees: self.total_fees,
+            total_mass: self.total_mass,
+            max_block_mass: self.policy.max_block_mass,
+            considered_count: self.transactions.len() as u64,
+            selected_count: self.total_count as u64,
+            min_feerate,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -265,6 +480,7 @@ mod tests {
     use super::*;
     use itertools::Itertools;
     use kaspa_consensus_core::{
+        config::constants::consensus::DEFAULT_MAX_BLOCK_TRANSACTIONS,
         constants::{MAX_TX_IN_SEQUENCE_NUM, SOMPI_PER_KASPA, TX_VERSION},
         mass::transaction_estimated_serialized_size,
         subnets::SUBNETWORK_ID_NATIVE,
@@ -281,7 +497,7 @@ mod tests {
 
         // Create a vector of transactions differing by output value so they have unique ids
         let transactions = (0..TX_INITIAL_COUNT).map(|i| create_transaction(SOMPI_PER_KASPA * (i + 1) as u64)).collect_vec();
-        let policy = Policy::new(100_000);
+        let policy = Policy::new(100_000, DEFAULT_MAX_BLOCK_TRANSACTIONS, None);
         let mut selector = TransactionsSelector::new(policy, transactions);
         let (mut kept, mut rejected) = (HashSet::new(), HashSet::new());
         let mut reject_count = 32;
@@ -307,17 +523,159 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_max_block_transactions() {
+        const MAX_BLOCK_TRANSACTIONS: usize = 10;
+
+        // The mass limit fits all transactions, so only the count limit binds
+        let transactions = (0..100).map(|i| create_transaction(SOMPI_PER_KASPA * (i + 1) as u64)).collect_vec();
+        let policy = Policy::new(100_000_000, MAX_BLOCK_TRANSACTIONS, None);
+        let mut selector = TransactionsSelector::new(policy, transactions);
+
+        // The coinbase takes one of the block transactions
+        let selected_txs = selector.select_transactions();
+        assert_eq!(selected_txs.len(), MAX_BLOCK_TRANSACTIONS - 1);
+
+        // Rejected transactions free room for as many replacements
+        selected_txs.iter().take(3).for_each(|tx| selector.reject_selection(tx.id()));
+        assert_eq!(selector.select_transactions().len(), 3);
+        assert!(selector.select_transactions().is_empty());
+    }
+
+    #[test]
+    fn test_template_mass_quota() {
+        const TOLERANCE: f64 = 0.1;
+
+        // 90% of the mempool is spent from a single key with a slightly higher feerate than the other origins
+        let dominant = ScriptPublicKey::from_vec(0, vec![0]);
+        let transactions = (0..1_000)
+            .map(|i| {
+                let value = SOMPI_PER_KASPA * (i + 1) as u64;
+                match i % 10 {
+                    0 => create_transaction_from(value, 1_000, ScriptPublicKey::from_vec(0, vec![1, (i / 10) as u8])),
+                    _ => create_transaction_from(value, 1_100, dominant.clone()),
+                }
+            })
+            .collect_vec();
+        // The template fits 100 of the transactions, which all have the same mass
+        let mass = transactions[0].calculated_mass;
+        let max_block_mass = mass * 100;
+        let reference_fees = 100 * 1_100;
+        let dominant_mass = |selected_txs: &[Transaction]| {
+            let ids = selected_txs.iter().map(|tx| tx.id()).collect::<HashSet<_>>();
+            transactions.iter().filter(|x| ids.contains(&x.tx.id()) && x.origin.as_ref() == Some(&dominant)).count() as u64 * mass
+        };
+
+        // Without quota, the dominant key fills the template
+        let policy = Policy::new(max_block_mass, DEFAULT_MAX_BLOCK_TRANSACTIONS, None);
+        let selected_txs = TransactionsSelector::new(policy, transactions.clone()).select_transactions();
+        assert_eq!(dominant_mass(&selected_txs), max_block_mass);
+
+        // The quota caps the dominant key, filling the remainder with the other origins within the fee tolerance
+        let policy = Policy::new(max_block_mass, DEFAULT_MAX_BLOCK_TRANSACTIONS, Some(TemplateMassQuota::new(0.5, TOLERANCE)));
+        let mut selector = TransactionsSelector::new(policy, transactions.clone());
+        let selected_txs = selector.select_transactions();
+        assert_eq!(selected_txs.len(), 100);
+        assert!(dominant_mass(&selected_txs) <= max_block_mass / 2);
+        assert!(selector.total_fees as f64 >= reference_fees as f64 * (1.0 - TOLERANCE));
+
+        // Rejections are refilled with respect to the quota
+        selected_txs.iter().take(10).for_each(|tx| selector.reject_selection(tx.id()));
+        let refilled_txs = selector.select_transactions();
+        assert_eq!(refilled_txs.len(), 10);
+        assert!(refilled_txs.iter().all(|tx| !selected_txs.contains(tx)));
+        assert!(selector.quota.as_ref().unwrap().origin_mass[&dominant] <= max_block_mass / 2);
+
+        // The quota is lifted when it would reduce the fees beyond tolerance
+        let policy = Policy::new(max_block_mass, DEFAULT_MAX_BLOCK_TRANSACTIONS, Some(TemplateMassQuota::new(0.5, 0.01)));
+        let mut selector = TransactionsSelector::new(policy, transactions.clone());
+        let selected_txs = selector.select_transactions();
+        assert_eq!(dominant_mass(&selected_txs), max_block_mass);
+        assert_eq!(selector.total_fees, reference_fees);
+    }
+
+    #[test]
+    fn test_canonical_template_order() {
+        // Transactions of equal mass with distinct feerates, unrelated to their order of creation
+        let transactions = (0..50u64)
+            .map(|i| create_transaction_from(SOMPI_PER_KASPA * (i + 1), 1_000 + (i * 37 % 50) * 10, op_true_script().0))
+            .collect_vec();
+        let candidates = transactions.iter().map(|x| (x.tx.id(), x)).collect::<HashMap<_, _>>();
+        let is_canonical = |txs: &[Transaction]| {
+            txs.iter().tuple_windows().all(|(a, b)| candidates[&a.id()].cmp_template_order(candidates[&b.id()]).is_lt())
+        };
+
+        let policy = Policy::new(transactions[0].calculated_mass * 20, DEFAULT_MAX_BLOCK_TRANSACTIONS, None);
+        let mut selector = TransactionsSelector::new(policy, transactions.clone());
+        let selected_txs = selector.select_transactions();
+        assert_eq!(selected_txs.len(), 20);
+        assert!(is_canonical(&selected_txs), "a selection batch is expected to be in canonical order");
+        // Higher feerates come first
+        let fees = selected_txs.iter().map(|tx| candidates[&tx.id()].calculated_fee).collect_vec();
+        assert!(fees.iter().tuple_windows().all(|(a, b)| a > b));
+
+        // The refill of rejections is appended to the kept transactions, the selector then restores the canonical order
+        let rejected = selected_txs.iter().skip(5).step_by(3).map(|tx| tx.id()).collect::<HashSet<_>>();
+        rejected.iter().for_each(|&id| selector.reject_selection(id));
+        let mut template_txs = selected_txs.into_iter().filter(|tx| !rejected.contains(&tx.id())).collect_vec();
+        template_txs.extend(selector.select_transactions());
+        assert_eq!(template_txs.len(), 20);
+        selector.sort_selected_transactions(&mut template_txs);
+        assert!(is_canonical(&template_txs));
+    }
+
+    #[test]
+    fn test_selection_stats() {
+        // Transactions of equal mass paying known fees, of which the template fits 10
+        let transactions =
+            (0..20u64).map(|i| create_transaction_from(SOMPI_PER_KASPA * (i + 1), 1_000 + i * 100, op_true_script().0)).collect_vec();
+        let mass = transactions[0].calculated_mass;
+        assert!(transactions.iter().all(|x| x.calculated_mass == mass));
+        let fees = transactions.iter().map(|x| (x.tx.id(), x.calculated_fee)).collect::<HashMap<_, _>>();
+        let policy = Policy::new(mass * 10, DEFAULT_MAX_BLOCK_TRANSACTIONS, None);
+        let mut selector = TransactionsSelector::new(policy, transactions);
+
+        let expected_stats = |selected_txs: &[Transaction]| TemplateSelectionStats {
+            total_fees: selected_txs.iter().map(|tx| fees[&tx.id()]).sum(),
+            total_mass: selected_txs.len() as u64 * mass,
+            max_block_mass: mass * 10,
+            considered_count: 20,
+            selected_count: selected_txs.len() as u64,
+            min_feerate: selected_txs.iter().map(|tx| fees[&tx.id()]).min().map(|fee| fee as f64 / mass as f64),
+        };
+
+        let selected_txs = selector.select_transactions();
+        assert_eq!(selected_txs.len(), 10);
+        assert_eq!(selector.selection_stats(), Some(expected_stats(&selected_txs)));
+
+        // Rejected transactions are excluded, including from the min feerate
+        let lowest = selected_txs.iter().min_by_key(|tx| fees[&tx.id()]).unwrap().id();
+        let rejected = selected_txs.iter().filter(|tx| tx.id() == lowest).chain(selected_txs.iter().take(2)).map(|tx| tx.id());
+        let rejected = rejected.collect::<HashSet<_>>();
+        rejected.iter().for_each(|&id| selector.reject_selection(id));
+        let mut template_txs = selected_txs.into_iter().filter(|tx| !rejected.contains(&tx.id())).collect_vec();
+        assert_eq!(selector.selection_stats(), Some(expected_stats(&template_txs)));
+
+        // The refill is accounted along the kept transactions
+        template_txs.extend(selector.select_transactions());
+        assert_eq!(template_txs.len(), 10);
+        assert_eq!(selector.selection_stats(), Some(expected_stats(&template_txs)));
+    }
+
     fn create_transaction(value: u64) -> CandidateTransaction {
+        create_transaction_from(value, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE, op_true_script().0)
+    }
+
+    fn create_transaction_from(value: u64, calculated_fee: u64, origin: ScriptPublicKey) -> CandidateTransaction {
         let previous_outpoint = TransactionOutpoint::new(TransactionId::default(), 0);
         let (script_public_key, redeem_script) = op_true_script();
         let signature_script = pay_to_script_hash_signature_script(redeem_script, vec![]).expect("the redeem script is canonical");
 
         let input = TransactionInput::new(previous_outpoint, signature_script, MAX_TX_IN_SEQUENCE_NUM, 1);
-        let output = TransactionOutput::new(value - DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE, script_public_key);
+        let output = TransactionOutput::new(value - calculated_fee, script_public_key);
         let tx = Arc::new(Transaction::new(TX_VERSION, vec![input], vec![output], 0, SUBNETWORK_ID_NATIVE, 0, vec![]));
         let calculated_mass = transaction_estimated_serialized_size(&tx);
-        let calculated_fee = DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE;
 
-        CandidateTransaction { tx, calculated_fee, calculated_mass }
+        CandidateTransaction { tx, calculated_fee, calculated_mass, origin: Some(origin) }
     }
 }
@@ -1,5 +1,5 @@
 use kaspa_consensus_core::block::{BlockTemplate, VirtualStateApproxId};
-use kaspa_core::time::unix_now;
+use kaspa_core::time::DynClock;
 use parking_lot::{Mutex, MutexGuard};
 use std::sync::Arc;
 
@@ -15,12 +15,14 @@ pub(crate) struct Inner {
 
     /// Duration in milliseconds after which the cached data expires
     cache_lifetime: u64,
+
+    clock: DynClock,
 }
 
 impl Inner {
-    pub(crate) fn new(cache_lifetime: Option<u64>) -> Self {
+    pub(crate) fn new(cache_lifetime: Option<u64>, clock: DynClock) -> Self {
         let cache_lifetime = cache_lifetime.unwrap_or(DEFAULT_CACHE_LIFETIME);
-        Self { last_update_time: 0, block_template: None, cache_lifetime }
+        Self { last_update_time: 0, block_template: None, cache_lifetime, clock }
     }
 
     fn clear(&mut self) {
@@ -28,7 +30,7 @@ impl Inner {
     }
 
     pub(crate) fn get_immutable_cached_template(&self) -> Option<Arc<BlockTemplate>> {
-        let now = unix_now();
+        let now = self.clock.unix_now();
         // We verify that `now > last update` in order to avoid theoretic clock change bugs
         if now > self.last_update_time + self.cache_lifetime || now < self.last_update_time {
             None
@@ -38,7 +40,7 @@ impl Inner {
     }
 
     pub(crate) fn set_immutable_cached_template(&mut self, block_template: BlockTemplate) -> Arc<BlockTemplate> {
-        self.last_update_time = unix_now();
+        self.last_update_time = self.clock.unix_now();
         let block_template = Arc::new(block_template);
         self.block_template = Some(block_template.clone());
         block_template
@@ -50,8 +52,8 @@ pub(crate) struct BlockTemplateCache {
 }
 
 impl BlockTemplateCache {
-    pub(crate) fn new(cache_lifetime: Option<u64>) -> Self {
-        Self { inner: Mutex::new(Inner::new(cache_lifetime)) }
+    pub(crate) fn new(cache_lifetime: Option<u64>, clock: DynClock) -> Self {
+        Self { inner: Mutex::new(Inner::new(cache_lifetime, clock)) }
     }
 
     #[cfg(test)]
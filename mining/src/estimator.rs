@@ -0,0 +1,386 @@
+//! Confirmation time estimation of mempool transactions.
+//!
+//! The estimation combines the position of a transaction in package-feerate order within the
+//! mempool with the recent block mass utilization and block rate observed by the node.
+
+use kaspa_consensus_core::tx::TransactionId;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Number of recent blocks kept by a [`BlockUtilizationHistory`]
+pub const DEFAULT_BLOCK_UTILIZATION_HISTORY_SIZE: usize = 600;
+
+/// Lower bound of the block capacity ratio assumed to be left to the transactions already in the
+/// mempool, so a saturated network still yields a bounded estimate
+pub const MIN_AVAILABLE_CAPACITY_RATIO: f64 = 0.1;
+
+/// A mempool transaction as seen by the estimator
+#[derive(Clone, Debug)]
+pub struct MempoolSnapshotEntry {
+    pub id: TransactionId,
+    pub fee: u64,
+    pub mass: u64,
+    /// Ids of the parent transactions present in the mempool
+    pub parents: Vec<TransactionId>,
+}
+
+impl MempoolSnapshotEntry {
+    pub fn new(id: TransactionId, fee: u64, mass: u64, parents: Vec<TransactionId>) -> Self {
+        Self { id, fee, mass, parents }
+    }
+}
+
+/// Position of a transaction in the mempool when ordered by descending package feerate
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MempoolPosition {
+    /// Feerate of the package formed by the transaction and all its in-mempool ancestors
+    pub package_feerate: f64,
+    /// Number of transactions expected to be mined before the transaction
+    pub transactions_ahead: u64,
+    /// Cumulative mass of the transactions expected to be mined before the transaction
+    pub mass_ahead: u64,
+    /// Mass of the transaction itself
+    pub mass: u64,
+}
+
+/// A point in time view of the mempool transactions
+#[derive(Clone, Debug, Default)]
+pub struct MempoolSnapshot {
+    entries: HashMap<TransactionId, MempoolSnapshotEntry>,
+}
+
+impl MempoolSnapshot {
+    pub fn new(entries: impl IntoIterator<Item = MempoolSnapshotEntry>) -> Self {
+        Self { entries: entries.into_iter().map(|entry| (entry.id, entry)).collect() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the in-mempool ancestors of `transaction_id`, the transaction itself excluded
+    fn ancestors(&self, transaction_id: &TransactionId) -> HashSet<TransactionId> {
+        let mut ancestors = HashSet::new();
+        let mut stack = self.entries.get(transaction_id).map(|entry| entry.parents.clone()).unwrap_or_default();
+        while let Some(id) = stack.pop() {
+            if let Some(entry) = self.entries.get(&id) {
+                if ancestors.insert(id) {
+                    stack.extend(entry.parents.iter().copied());
+                }
+            }
+        }
+        ancestors
+    }
+
+    /// Returns the feerate of the package formed by `transaction_id` and all its in-mempool ancestors.
+    ///
+    /// A transaction can only be mined along with or after its ancestors, so the package feerate is the
+    /// rate at which a miner collects fees when including it.
+    pub fn package_feerate(&self, transaction_id: &TransactionId) -> Option<f64> {
+        let entry = self.entries.get(transaction_id)?;
+        let (fee, mass) = self
+            .ancestors(transaction_id)
+            .iter()
+            .map(|id| &self.entries[id])
+            .fold((entry.fee, entry.mass), |(fee, mass), x| (fee.saturating_add(x.fee), mass.saturating_add(x.mass)));
+        Some(if mass > 0 { fee as f64 / mass as f64 } else { 0.0 })
+    }
+
+    /// Returns the position of `transaction_id` in package-feerate order.
+    ///
+    /// Transactions with a higher package feerate are ahead, ties being broken by transaction id, and so
+    /// are their in-mempool ancestors since those must be mined first. The in-mempool ancestors of the
+    /// transaction are always ahead whatever their own package feerate.
+    pub fn position(&self, transaction_id: &TransactionId) -> Option<MempoolPosition> {
+        let entry = self.entries.get(transaction_id)?;
+        let package_feerate = self.package_feerate(transaction_id)?;
+        let mut ahead = self.ancestors(transaction_id);
+        for x in self.entries.values().filter(|x| x.id != *transaction_id && !ahead.contains(&x.id)) {
+            let feerate = self.package_feerate(&x.id).unwrap();
+            if feerate > package_feerate || (feerate == package_feerate && x.id < *transaction_id) {
+                ahead.insert(x.id);
+                ahead.extend(self.ancestors(&x.id));
+            }
+        }
+        ahead.remove(transaction_id);
+        let mass_ahead = ahead.iter().map(|id| self.entries[id].mass).fold(0u64, |mass, x| mass.saturating_add(x));
+        Some(MempoolPosition { package_feerate, transactions_ahead: ahead.len() as u64, mass_ahead, mass: entry.mass })
+    }
+}
+
+/// Mass of a block observed by the node at a given time
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockUtilizationSample {
+    /// Unix time in milliseconds
+    pub timestamp: u64,
+    pub mass: u64,
+}
+
+/// Bounded history of the mass of the recently handled blocks
+#[derive(Clone, Debug)]
+pub struct BlockUtilizationHistory {
+    samples: VecDeque<BlockUtilizationSample>,
+    capacity: usize,
+    max_block_mass: u64,
+}
+
+impl BlockUtilizationHistory {
+    pub fn new(capacity: usize, max_block_mass: u64) -> Self {
+        assert!(capacity > 0, "the history must hold at least one sample");
+        Self { samples: VecDeque::with_capacity(capacity), capacity, max_block_mass }
+    }
+
+    pub fn record(&mut self, timestamp: u64, mass: u64) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(BlockUtilizationSample { timestamp, mass });
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Returns the average ratio of the block mass used by the recent blocks, in the `[0, 1]` range.
+    /// An empty history yields `0`.
+    pub fn utilization(&self) -> f64 {
+        if self.samples.is_empty() || self.max_block_mass == 0 {
+            return 0.0;
+        }
+        let total_mass = self.samples.iter().map(|x| x.mass as f64).sum::<f64>();
+        (total_mass / (self.samples.len() as f64 * self.max_block_mass as f64)).clamp(0.0, 1.0)
+    }
+
+    /// Returns the average interval in milliseconds between the recent blocks or `default_interval`
+    /// if the history is too short to observe one.
+    pub fn block_interval(&self, default_interval: f64) -> f64 {
+        match (self.samples.front(), self.samples.back()) {
+            (Some(first), Some(last)) if self.samples.len() > 1 && last.timestamp > first.timestamp => {
+                (last.timestamp - first.timestamp) as f64 / (self.samples.len() - 1) as f64
+            }
+            _ => default_interval,
+        }
+    }
+}
+
+/// Estimated confirmation time range of a mempool transaction
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConfirmationTimeEstimate {
+    pub package_feerate: f64,
+    pub transactions_ahead: u64,
+    pub mass_ahead: u64,
+    /// Average ratio of the block mass used by the recent blocks
+    pub block_utilization: f64,
+    /// Average interval between blocks in milliseconds
+    pub block_interval: f64,
+    pub min_blocks: u64,
+    pub max_blocks: u64,
+    /// Lower bound of the estimated confirmation time in milliseconds
+    pub min_time: u64,
+    /// Upper bound of the estimated confirmation time in milliseconds
+    pub max_time: u64,
+}
+
+/// Estimates the confirmation time range of a transaction at `position` in the mempool.
+///
+/// The lower bound assumes the whole mass of every coming block is dedicated to the transactions
+/// at and ahead of `position`. The upper bound assumes competing transactions keep arriving with
+/// higher feerates and take a share of every block equal to the recent `block_utilization`, the
+/// capacity left being bounded below by [`MIN_AVAILABLE_CAPACITY_RATIO`].
+pub fn estimate_confirmation_time(
+    position: &MempoolPosition,
+    block_utilization: f64,
+    block_interval: f64,
+    max_block_mass: u64,
+) -> ConfirmationTimeEstimate {
+    let block_utilization = block_utilization.clamp(0.0, 1.0);
+    let mass_to_clear = position.mass_ahead.saturating_add(position.mass) as f64;
+    let max_block_mass = max_block_mass.max(1) as f64;
+    let min_blocks = ((mass_to_clear / max_block_mass).ceil() as u64).max(1);
+    let available_capacity = max_block_mass * (1.0 - block_utilization).max(MIN_AVAILABLE_CAPACITY_RATIO);
+    let max_blocks = ((mass_to_clear / available_capacity).ceil() as u64).max(min_blocks);
+    ConfirmationTimeEstimate {
+        package_feerate: position.package_feerate,
+        transactions_ahead: position.transactions_ahead,
+        mass_ahead: position.mass_ahead,
+        block_utilization,
+        block_interval,
+        min_blocks,
+        max_blocks,
+        min_time: (min_blocks as f64 * block_interval).round() as u64,
+        max_time: (max_blocks as f64 * block_interval).round() as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(i: u64) -> TransactionId {
+        TransactionId::from_u64_word(i)
+    }
+
+    fn entry(i: u64, fee: u64, mass: u64, parents: &[u64]) -> MempoolSnapshotEntry {
+        MempoolSnapshotEntry::new(id(i), fee, mass, parents.iter().copied().map(id).collect())
+    }
+
+    #[test]
+    fn test_package_feerate_ordering() {
+        // 1: independent, feerate 10
+        // 2: independent, feerate 2
+        // 3 -> 2: child paying for its parent, package feerate (2_000 + 38_000) / 2_000 = 20
+        // 4 -> 3 -> 2: grandchild with a tiny fee, package feerate (40_000 + 1_000) / 3_000
+        // 5: independent, feerate 5
+        let snapshot = MempoolSnapshot::new(vec![
+            entry(1, 10_000, 1_000, &[]),
+            entry(2, 2_000, 1_000, &[]),
+            entry(3, 38_000, 1_000, &[2]),
+            entry(4, 1_000, 1_000, &[3]),
+            entry(5, 5_000, 1_000, &[]),
+        ]);
+        assert_eq!(snapshot.len(), 5);
+        assert_eq!(snapshot.package_feerate(&id(2)), Some(2.0));
+        assert_eq!(snapshot.package_feerate(&id(3)), Some(20.0));
+        assert_eq!(snapshot.package_feerate(&id(4)), Some(41_000.0 / 3_000.0));
+        assert_eq!(snapshot.package_feerate(&id(6)), None);
+
+        // The CPFP package is ahead of everything
+        let position = snapshot.position(&id(3)).unwrap();
+        assert_eq!(position.transactions_ahead, 1, "only the parent is ahead");
+        assert_eq!(position.mass_ahead, 1_000);
+
+        // Ahead of 1: 3 (20) and 4 (13.67) by package feerate, along with 2 which must be mined before them
+        let position = snapshot.position(&id(1)).unwrap();
+        assert_eq!(position.package_feerate, 10.0);
+        assert_eq!(position.transactions_ahead, 3);
+        assert_eq!(position.mass_ahead, 3_000);
+
+        // 2 has the lowest package feerate so everything else is ahead
+        let position = snapshot.position(&id(2)).unwrap();
+        assert_eq!(position.transactions_ahead, 4);
+        assert_eq!(position.mass_ahead, 4_000);
+
+        // 4 follows its ancestors, 1 and 5 having a lower package feerate
+        let position = snapshot.position(&id(4)).unwrap();
+        assert_eq!(position.transactions_ahead, 2);
+        assert_eq!(position.mass_ahead, 2_000);
+
+        let position = snapshot.position(&id(5)).unwrap();
+        assert_eq!(position.transactions_ahead, 4);
+
+        assert!(snapshot.position(&id(6)).is_none());
+    }
+
+    #[test]
+    fn test_package_feerate_diamond_ancestors() {
+        // 4 -> {2, 3} -> 1: the common ancestor must be accounted for once
+        let snapshot = MempoolSnapshot::new(vec![
+            entry(1, 1_000, 1_000, &[]),
+            entry(2, 1_000, 1_000, &[1]),
+            entry(3, 1_000, 1_000, &[1]),
+            entry(4, 5_000, 1_000, &[2, 3]),
+        ]);
+        assert_eq!(snapshot.package_feerate(&id(4)), Some(8_000.0 / 4_000.0));
+        let position = snapshot.position(&id(4)).unwrap();
+        assert_eq!(position.transactions_ahead, 3);
+        assert_eq!(position.mass_ahead, 3_000);
+    }
+
+    #[test]
+    fn test_equal_feerates_tie_break() {
+        let snapshot = MempoolSnapshot::new((1..=4).map(|i| entry(i, 1_000, 1_000, &[])));
+        for i in 1..=4 {
+            assert_eq!(snapshot.position(&id(i)).unwrap().transactions_ahead, i - 1);
+        }
+    }
+
+    #[test]
+    fn test_block_utilization_history() {
+        let mut history = BlockUtilizationHistory::new(4, 1_000);
+        assert!(history.is_empty());
+        assert_eq!(history.utilization(), 0.0);
+        assert_eq!(history.block_interval(1_000.0), 1_000.0);
+
+        history.record(10_000, 500);
+        assert_eq!(history.utilization(), 0.5);
+        assert_eq!(history.block_interval(1_000.0), 1_000.0, "a single sample shows no interval");
+
+        history.record(10_100, 1_000);
+        history.record(10_200, 0);
+        history.record(10_300, 500);
+        assert_eq!(history.len(), 4);
+        assert_eq!(history.utilization(), 0.5);
+        assert_eq!(history.block_interval(1_000.0), 100.0);
+
+        // The oldest sample is evicted
+        history.record(10_700, 1_000);
+        assert_eq!(history.len(), 4);
+        assert_eq!(history.utilization(), 2_500.0 / 4_000.0);
+        assert_eq!(history.block_interval(1_000.0), 200.0);
+
+        // Masses above the block mass limit are clamped
+        let mut history = BlockUtilizationHistory::new(2, 1_000);
+        history.record(0, 5_000);
+        assert_eq!(history.utilization(), 1.0);
+    }
+
+    #[test]
+    fn test_estimate_confirmation_time() {
+        let max_block_mass = 500_000;
+        let position = |mass_ahead| MempoolPosition { package_feerate: 1.0, transactions_ahead: 1, mass_ahead, mass: 2_000 };
+
+        // Empty network: next block
+        let estimate = estimate_confirmation_time(&position(0), 0.0, 1_000.0, max_block_mass);
+        assert_eq!((estimate.min_blocks, estimate.max_blocks), (1, 1));
+        assert_eq!((estimate.min_time, estimate.max_time), (1_000, 1_000));
+
+        // 2M mass to clear: 4 full blocks at best, 8 when half of the block space is taken by competitors
+        let estimate = estimate_confirmation_time(&position(1_998_000), 0.5, 1_000.0, max_block_mass);
+        assert_eq!((estimate.min_blocks, estimate.max_blocks), (4, 8));
+        assert_eq!((estimate.min_time, estimate.max_time), (4_000, 8_000));
+
+        // A saturated network is bounded by the minimal available capacity
+        let estimate = estimate_confirmation_time(&position(998_000), 1.0, 100.0, max_block_mass);
+        assert_eq!((estimate.min_blocks, estimate.max_blocks), (2, 20));
+        assert_eq!((estimate.min_time, estimate.max_time), (200, 2_000));
+
+        // The upper bound grows with the utilization
+        let mut previous = 0;
+        for utilization in [0.0, 0.2, 0.4, 0.6, 0.8, 0.95] {
+            let estimate = estimate_confirmation_time(&position(5_000_000), utilization, 1_000.0, max_block_mass);
+            assert!(estimate.max_blocks >= previous);
+            assert!(estimate.max_blocks >= estimate.min_blocks);
+            assert_eq!(estimate.min_blocks, 11);
+            previous = estimate.max_blocks;
+        }
+    }
+
+    #[test]
+    fn test_estimate_over_synthetic_mempool() {
+        // 100 transactions of mass 10_000 with increasing feerates and blocks of 100_000 mass
+        let snapshot = MempoolSnapshot::new((1..=100).map(|i| entry(i, i * 10_000, 10_000, &[])));
+        let mut history = BlockUtilizationHistory::new(10, 100_000);
+        (0..10).for_each(|i| history.record(i * 250, 75_000));
+        assert_eq!(history.utilization(), 0.75);
+        assert_eq!(history.block_interval(1_000.0), 250.0);
+
+        // The top feerate transaction goes with the next block
+        let position = snapshot.position(&id(100)).unwrap();
+        let estimate = estimate_confirmation_time(&position, history.utilization(), history.block_interval(1_000.0), 100_000);
+        assert_eq!((estimate.min_blocks, estimate.max_blocks), (1, 1));
+
+        // The lowest feerate transaction waits for the whole mempool: 10 full blocks, 40 at 25% available capacity
+        let position = snapshot.position(&id(1)).unwrap();
+        assert_eq!(position.transactions_ahead, 99);
+        let estimate = estimate_confirmation_time(&position, history.utilization(), history.block_interval(1_000.0), 100_000);
+        assert_eq!((estimate.min_blocks, estimate.max_blocks), (10, 40));
+        assert_eq!((estimate.min_time, estimate.max_time), (2_500, 10_000));
+    }
+}
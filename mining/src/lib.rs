@@ -3,16 +3,22 @@ use std::{
     time::{Duration, Instant},
 };
 
+use kaspa_consensus_core::block::TemplateSelectionStats;
 use mempool::tx::Priority;
+use parking_lot::Mutex;
 
 mod block_template;
 pub(crate) mod cache;
 pub mod errors;
+pub mod estimator;
 pub mod manager;
 mod manager_tests;
 pub mod mempool;
 pub mod model;
 pub mod monitor;
+#[cfg(feature = "stratum")]
+pub mod stratum;
+pub mod virtual_change;
 
 #[cfg(test)]
 pub mod testutils;
@@ -27,12 +33,18 @@ pub struct MiningCounters {
     pub tx_accepted_counts: AtomicU64,
     pub input_counts: AtomicU64,
     pub output_counts: AtomicU64,
+    pub stale_block_submission_counts: AtomicU64,
+    pub duplicate_block_submission_counts: AtomicU64,
+    pub non_canonical_block_submission_counts: AtomicU64,
+    pub block_template_counts: AtomicU64,
 
     // Samples
     pub ready_txs_sample: AtomicU64,
     pub txs_sample: AtomicU64,
     pub orphans_sample: AtomicU64,
     pub accepted_sample: AtomicU64,
+    /// Selection statistics of the last built block template
+    pub last_template_stats: Mutex<Option<TemplateSelectionStats>>,
 }
 
 impl Default for MiningCounters {
@@ -45,10 +57,15 @@ impl Default for MiningCounters {
             tx_accepted_counts: Default::default(),
             input_counts: Default::default(),
             output_counts: Default::default(),
+            stale_block_submission_counts: Default::default(),
+            duplicate_block_submission_counts: Default::default(),
+            non_canonical_block_submission_counts: Default::default(),
+            block_template_counts: Default::default(),
             ready_txs_sample: Default::default(),
             txs_sample: Default::default(),
             orphans_sample: Default::default(),
             accepted_sample: Default::default(),
+            last_template_stats: Default::default(),
         }
     }
 }
@@ -63,6 +80,10 @@ impl MiningCounters {
             tx_accepted_counts: self.tx_accepted_counts.load(Ordering::Relaxed),
             input_counts: self.input_counts.load(Ordering::Relaxed),
             output_counts: self.output_counts.load(Ordering::Relaxed),
+            stale_block_submission_counts: self.stale_block_submission_counts.load(Ordering::Relaxed),
+            duplicate_block_submission_counts: self.duplicate_block_submission_counts.load(Ordering::Relaxed),
+            non_canonical_block_submission_counts: self.non_canonical_block_submission_counts.load(Ordering::Relaxed),
+            block_template_counts: self.block_template_counts.load(Ordering::Relaxed),
             ready_txs_sample: self.ready_txs_sample.load(Ordering::Relaxed),
             txs_sample: self.txs_sample.load(Ordering::Relaxed),
             orphans_sample: self.orphans_sample.load(Ordering::Relaxed),
@@ -87,6 +108,18 @@ impl MiningCounters {
             }
         }
     }
+
+    /// Records a newly built block template (cached template reuses excluded)
+    pub fn record_block_template(&self, selection_stats: Option<TemplateSelectionStats>) {
+        self.block_template_counts.fetch_add(1, Ordering::Relaxed);
+        if selection_stats.is_some() {
+            *self.last_template_stats.lock() = selection_stats;
+        }
+    }
+
+    pub fn last_template_stats(&self) -> Option<TemplateSelectionStats> {
+        *self.last_template_stats.lock()
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -98,6 +131,10 @@ pub struct MempoolCountersSnapshot {
     pub tx_accepted_counts: u64,
     pub input_counts: u64,
     pub output_counts: u64,
+    pub stale_block_submission_counts: u64,
+    pub duplicate_block_submission_counts: u64,
+    pub non_canonical_block_submission_counts: u64,
+    pub block_template_counts: u64,
     pub ready_txs_sample: u64,
     pub txs_sample: u64,
     pub orphans_sample: u64,
@@ -153,6 +190,19 @@ impl core::ops::Sub for &MempoolCountersSnapshot {
             tx_accepted_counts: self.tx_accepted_counts.checked_sub(rhs.tx_accepted_counts).unwrap_or_default(),
             input_counts: self.input_counts.checked_sub(rhs.input_counts).unwrap_or_default(),
             output_counts: self.output_counts.checked_sub(rhs.output_counts).unwrap_or_default(),
+            stale_block_submission_counts: self
+                .stale_block_submission_counts
+                .checked_sub(rhs.stale_block_submission_counts)
+                .unwrap_or_default(),
+            duplicate_block_submission_counts: self
+                .duplicate_block_submission_counts
+                .checked_sub(rhs.duplicate_block_submission_counts)
+                .unwrap_or_default(),
+            non_canonical_block_submission_counts: self
+                .non_canonical_block_submission_counts
+                .checked_sub(rhs.non_canonical_block_submission_counts)
+                .unwrap_or_default(),
+            block_template_counts: self.block_template_counts.checked_sub(rhs.block_template_counts).unwrap_or_default(),
             ready_txs_sample: (self.ready_txs_sample + rhs.ready_txs_sample) / 2,
             txs_sample: (self.txs_sample + rhs.txs_sample) / 2,
             orphans_sample: (self.orphans_sample + rhs.orphans_sample) / 2,
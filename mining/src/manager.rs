@@ -2,36 +2,46 @@ use crate::{
     block_template::{builder::BlockTemplateBuilder, errors::BuilderError},
     cache::BlockTemplateCache,
     errors::MiningManagerResult,
+    estimator::ConfirmationTimeEstimate,
     mempool::{
-        config::Config,
+        config::{Config, DustRelayThresholds, TemplateMassQuota, DEFAULT_MAXIMUM_HELD_TRANSACTION_COUNT},
         model::tx::{MempoolTransaction, TxRemovalReason},
         populate_entries_and_try_validate::{
             populate_mempool_transactions_in_parallel, validate_mempool_transaction, validate_mempool_transactions_in_parallel,
         },
-        tx::{Orphan, Priority},
+        tx::{Hold, Orphan, Priority},
         Mempool,
     },
     model::{
+        acceptance_trace::TransactionAcceptanceTrace,
+        block_submission::{BlockSubmissionClassification, SubmittedTransactionOrder},
         candidate_tx::CandidateTransaction,
+        mempool_entries::MempoolEntries,
         owner_txs::{GroupedOwnerTransactions, ScriptPublicKeySet},
         topological_sort::IntoIterTopologically,
+        tx_conflict::TransactionConflict,
         tx_query::TransactionQuery,
     },
     MempoolCountersSnapshot, MiningCounters, P2pTxCountSample,
 };
 use itertools::Itertools;
 use kaspa_consensus_core::{
+    acceptance_data::AcceptanceData,
     api::ConsensusApi,
-    block::{BlockTemplate, TemplateBuildMode},
+    block::{Block, BlockTemplate, TemplateBuildMode},
     coinbase::MinerData,
     errors::{block::RuleError as BlockRuleError, tx::TxRuleError},
-    tx::{MutableTransaction, Transaction, TransactionId, TransactionOutput},
+    tx::{MutableTransaction, Transaction, TransactionId, TransactionOutpoint, TransactionOutput},
+    utxo::utxo_diff::UtxoDiff,
 };
 use kaspa_consensusmanager::{spawn_blocking, ConsensusProxy};
 use kaspa_core::{debug, error, info, time::Stopwatch, warn};
+use kaspa_hashes::Hash;
 use kaspa_mining_errors::{manager::MiningManagerError, mempool::RuleError};
+use kaspa_notify::collector::CollectorNotificationSender;
+use kaspa_txscript::script_class::ScriptClass;
 use parking_lot::RwLock;
-use std::sync::Arc;
+use std::sync::{atomic::Ordering, Arc};
 use tokio::sync::mpsc::UnboundedSender;
 
 pub struct MiningManager {
@@ -39,6 +49,8 @@ pub struct MiningManager {
     block_template_cache: BlockTemplateCache,
     mempool: RwLock<Mempool>,
     counters: Arc<MiningCounters>,
+    /// Sender of the detected transaction conflicts, if anyone listens to them
+    transaction_conflict_sender: RwLock<Option<CollectorNotificationSender<TransactionConflict>>>,
 }
 
 impl MiningManager {
@@ -53,24 +65,56 @@ impl MiningManager {
         Self::with_config(config, cache_lifetime, counters)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new_with_extended_config(
         target_time_per_block: u64,
         relay_non_std_transactions: bool,
         max_block_mass: u64,
+        max_block_transactions: usize,
         ram_scale: f64,
+        hold_time_locked_transactions: bool,
+        dust_relay_thresholds: DustRelayThresholds,
+        template_mass_quota: Option<TemplateMassQuota>,
+        check_submitted_transaction_order: bool,
         cache_lifetime: Option<u64>,
         counters: Arc<MiningCounters>,
     ) -> Self {
-        let config =
-            Config::build_default(target_time_per_block, relay_non_std_transactions, max_block_mass).apply_ram_scale(ram_scale);
+        let mut config = Config::build_default(target_time_per_block, relay_non_std_transactions, max_block_mass)
+            .apply_max_block_transactions(max_block_transactions)
+            .apply_ram_scale(ram_scale)
+            .apply_dust_relay_thresholds(dust_relay_thresholds)
+            .apply_template_mass_quota(template_mass_quota)
+            .apply_check_submitted_transaction_order(check_submitted_transaction_order);
+        if hold_time_locked_transactions {
+            config = config.apply_held_transaction_limit(DEFAULT_MAXIMUM_HELD_TRANSACTION_COUNT);
+        }
         Self::with_config(config, cache_lifetime, counters)
     }
 
     pub(crate) fn with_config(config: Config, cache_lifetime: Option<u64>, counters: Arc<MiningCounters>) -> Self {
         let config = Arc::new(config);
         let mempool = RwLock::new(Mempool::new(config.clone(), counters.clone()));
-        let block_template_cache = BlockTemplateCache::new(cache_lifetime);
-        Self { config, block_template_cache, mempool, counters }
+        let block_template_cache = BlockTemplateCache::new(cache_lifetime, config.clock.clone());
+        Self { config, block_template_cache, mempool, counters, transaction_conflict_sender: Default::default() }
+    }
+
+    /// Sets the sender of the transaction conflicts detected by the mempool, namely the double spends of mempool
+    /// transactions rejected at admission and the mempool or recently rejected transactions double spending the
+    /// transactions of incoming blocks.
+    pub fn set_transaction_conflict_sender(&self, sender: CollectorNotificationSender<TransactionConflict>) {
+        *self.transaction_conflict_sender.write() = Some(sender);
+    }
+
+    fn notify_transaction_conflicts(&self, conflicts: impl IntoIterator<Item = TransactionConflict>) {
+        let sender = self.transaction_conflict_sender.read();
+        let Some(sender) = sender.as_ref() else {
+            return;
+        };
+        for conflict in conflicts {
+            if let Err(err) = sender.try_send(conflict) {
+                debug!("Dropping a transaction conflict notification: {}", err);
+            }
+        }
     }
 
     pub fn get_block_template(&self, consensus: &dyn ConsensusApi, miner_data: &MinerData) -> MiningManagerResult<BlockTemplate> {
@@ -86,7 +130,8 @@ impl MiningManager {
             }
             // Miner data is new -- make the minimum changes required
             // Note the call returns a modified clone of the cached block template
-            let block_template = BlockTemplateBuilder::modify_block_template(consensus, miner_data, &immutable_template)?;
+            let block_template =
+                BlockTemplateBuilder::modify_block_template(consensus, miner_data, &immutable_template, self.config.clock.unix_now())?;
 
             // No point in updating cache since we have no reason to believe this coinbase will be used more
             // than the previous one, and we want to maintain the original template caching time
@@ -104,7 +149,11 @@ impl MiningManager {
             attempts += 1;
 
             let transactions = self.block_candidate_transactions();
-            let block_template_builder = BlockTemplateBuilder::new(self.config.maximum_mass_per_block);
+            let block_template_builder = BlockTemplateBuilder::new(
+                self.config.maximum_mass_per_block,
+                self.config.maximum_transactions_per_block,
+                self.config.template_mass_quota,
+            );
             let build_mode = if attempts < self.config.maximum_build_block_template_attempts {
                 TemplateBuildMode::Standard
             } else {
@@ -112,6 +161,7 @@ impl MiningManager {
             };
             match block_template_builder.build_block_template(consensus, miner_data, transactions, build_mode) {
                 Ok(block_template) => {
+                    self.counters.record_block_template(block_template.selection_stats);
                     let block_template = cache_lock.set_immutable_cached_template(block_template);
                     match attempts {
                         1 => {
@@ -197,6 +247,57 @@ impl MiningManager {
         self.mempool.read().block_candidate_transactions()
     }
 
+    /// Classifies a block submitted by a miner before it gets processed by consensus, counting
+    /// the stale and already processed submissions.
+    pub fn classify_submitted_block(&self, consensus: &dyn ConsensusApi, block: &Block) -> BlockSubmissionClassification {
+        if consensus.get_block_status(block.hash()).is_some_and(|status| status.has_block_body()) {
+            self.counters.duplicate_block_submission_counts.fetch_add(1, Ordering::Relaxed);
+            return BlockSubmissionClassification::AlreadyProcessed;
+        }
+        // The template of the block was built on the virtual parents of that time
+        let virtual_parents = consensus.get_virtual_parents();
+        let parents = block.header.direct_parents();
+        if parents.len() != virtual_parents.len() || parents.iter().any(|parent| !virtual_parents.contains(parent)) {
+            self.counters.stale_block_submission_counts.fetch_add(1, Ordering::Relaxed);
+            return BlockSubmissionClassification::Stale;
+        }
+        BlockSubmissionClassification::Fresh
+    }
+
+    /// Checks, if enabled by the config, whether the transactions of a submitted block follow the canonical block
+    /// template order (see [`CandidateTransaction::cmp_template_order`]), counting the non-canonical submissions.
+    /// This is a policy check only: blocks with another order are valid and processed. Returns `None` if disabled.
+    pub fn check_submitted_transaction_order(&self, block: &Block) -> Option<SubmittedTransactionOrder> {
+        if !self.config.check_submitted_transaction_order {
+            return None;
+        }
+        let order = match block.transactions.split_first() {
+            Some((coinbase, transactions)) if coinbase.is_coinbase() => {
+                // The fees establishing the canonical order are only known for transactions still in the mempool
+                let candidates = {
+                    let mempool = self.mempool.read();
+                    transactions
+                        .iter()
+                        .map(|tx| mempool.get_transaction(&tx.id(), TransactionQuery::TransactionsOnly))
+                        .map(|mtx| mtx.map(|mtx| CandidateTransaction::from_mutable(&mtx)))
+                        .collect::<Option<Vec<_>>>()
+                };
+                match candidates {
+                    Some(candidates) if candidates.iter().tuple_windows().all(|(a, b)| a.cmp_template_order(b).is_lt()) => {
+                        SubmittedTransactionOrder::Canonical
+                    }
+                    Some(_) => SubmittedTransactionOrder::NonCanonical,
+                    None => SubmittedTransactionOrder::Unverified,
+                }
+            }
+            _ => SubmittedTransactionOrder::NonCanonical,
+        };
+        if order == SubmittedTransactionOrder::NonCanonical {
+            self.counters.non_canonical_block_submission_counts.fetch_add(1, Ordering::Relaxed);
+        }
+        Some(order)
+    }
+
     /// Clears the block template cache, forcing the next call to get_block_template to build a new block template.
     #[cfg(test)]
     pub(crate) fn clear_block_template(&self) {
@@ -205,7 +306,11 @@ impl MiningManager {
 
     #[cfg(test)]
     pub(crate) fn block_template_builder(&self) -> BlockTemplateBuilder {
-        BlockTemplateBuilder::new(self.config.maximum_mass_per_block)
+        BlockTemplateBuilder::new(
+            self.config.maximum_mass_per_block,
+            self.config.maximum_transactions_per_block,
+            self.config.template_mass_quota,
+        )
     }
 
     /// validate_and_insert_transaction validates the given transaction, and
@@ -220,26 +325,62 @@ impl MiningManager {
         priority: Priority,
         orphan: Orphan,
     ) -> MiningManagerResult<Vec<Arc<Transaction>>> {
-        self.validate_and_insert_mutable_transaction(consensus, MutableTransaction::from_tx(transaction), priority, orphan)
+        self.validate_and_insert_mutable_transaction(
+            consensus,
+            MutableTransaction::from_tx(transaction),
+            priority,
+            orphan,
+            Hold::Forbidden,
+        )
     }
 
-    /// Exposed only for tests. Ordinary users should call `validate_and_insert_transaction` instead
+    /// Same as `validate_and_insert_transaction` but, if `hold` is allowed and the mempool is configured
+    /// accordingly, a transaction whose only invalidity is a lock time not reached yet gets held until
+    /// it becomes valid instead of being rejected.
+    ///
+    /// Ordinary users should call `validate_and_insert_transaction` instead
     pub fn validate_and_insert_mutable_transaction(
         &self,
         consensus: &dyn ConsensusApi,
         transaction: MutableTransaction,
         priority: Priority,
         orphan: Orphan,
+        hold: Hold,
     ) -> MiningManagerResult<Vec<Arc<Transaction>>> {
+        let tx = transaction.tx.clone();
         // read lock on mempool
-        let mut transaction = self.mempool.read().pre_validate_and_populate_transaction(consensus, transaction)?;
+        let pre_validation_result = self.mempool.read().pre_validate_and_populate_transaction(consensus, transaction);
+        let mut transaction = match pre_validation_result {
+            Ok(transaction) => transaction,
+            Err(err) => {
+                if let RuleError::RejectDoubleSpendInMempool(_, conflicting_id) = err {
+                    // write lock on mempool
+                    let conflict = self.mempool.write().register_double_spend(&tx, &conflicting_id);
+                    self.notify_transaction_conflicts(conflict);
+                }
+                return Err(err.into());
+            }
+        };
         // no lock on mempool
         let validation_result = validate_mempool_transaction(consensus, &mut transaction);
         // write lock on mempool
         let mut mempool = self.mempool.write();
-        if let Some(accepted_transaction) =
-            mempool.post_validate_and_insert_transaction(consensus, validation_result, transaction, priority, orphan)?
-        {
+        let insert_result =
+            match mempool.post_validate_and_insert_transaction(consensus, validation_result, transaction, priority, orphan, hold) {
+                Ok(insert_result) => insert_result,
+                Err(err) => {
+                    let conflict = match err {
+                        RuleError::RejectDoubleSpendInMempool(_, conflicting_id) => {
+                            mempool.register_double_spend(&tx, &conflicting_id)
+                        }
+                        _ => None,
+                    };
+                    drop(mempool);
+                    self.notify_transaction_conflicts(conflict);
+                    return Err(err.into());
+                }
+            };
+        if let Some(accepted_transaction) = insert_result {
             let unorphaned_transactions = mempool.get_unorphaned_transactions_after_accepted_transaction(&accepted_transaction);
             drop(mempool);
 
@@ -256,6 +397,15 @@ impl MiningManager {
         }
     }
 
+    /// Replays the mempool validation of a transaction rule by rule without inserting it and
+    /// returns the trace of the checks performed up to the first failing one.
+    ///
+    /// This is meant for explaining the rejection of a transaction and is much more expensive
+    /// than the ordinary validation, which it does not affect.
+    pub fn trace_transaction_acceptance(&self, consensus: &dyn ConsensusApi, transaction: Transaction) -> TransactionAcceptanceTrace {
+        self.mempool.read().trace_transaction_acceptance(consensus, MutableTransaction::from_tx(transaction))
+    }
+
     fn validate_and_insert_unorphaned_transactions(
         &self,
         consensus: &dyn ConsensusApi,
@@ -297,6 +447,7 @@ impl MiningManager {
                         transaction,
                         priority,
                         Orphan::Forbidden,
+                        Hold::Forbidden,
                     ) {
                         Ok(Some(accepted_transaction)) => {
                             accepted_transactions.push(accepted_transaction.clone());
@@ -316,6 +467,16 @@ impl MiningManager {
         accepted_transactions
     }
 
+    /// Replays the mempool validation of a transaction rule by rule without inserting it and
+    /// returns the trace of the checks performed up to the first failing one.
+    pub async fn trace_transaction_acceptance(
+        self,
+        consensus: &ConsensusProxy,
+        transaction: Transaction,
+    ) -> TransactionAcceptanceTrace {
+        consensus.clone().spawn_blocking(move |c| self.inner.trace_transaction_acceptance(c, transaction)).await
+    }
+
     /// Validates a batch of transactions, handling iteratively only the independent ones, and
     /// adds those to the set of known transactions that have not yet been added to any block.
     ///
@@ -340,10 +501,12 @@ impl MiningManager {
         // read lock on mempool
         // Here, we simply log and drop all erroneous transactions since the caller doesn't care about those anyway
         let mut transactions = Vec::with_capacity(sorted_transactions.len());
+        let mut double_spends = vec![];
         for chunk in &sorted_transactions.chunks(TRANSACTION_CHUNK_SIZE) {
             let mempool = self.mempool.read();
             let txs = chunk.filter_map(|tx| {
                 let transaction_id = tx.id();
+                let inner_tx = tx.tx.clone();
                 match mempool.pre_validate_and_populate_transaction(consensus, tx) {
                     Ok(tx) => Some(tx),
                     Err(RuleError::RejectAlreadyAccepted(transaction_id)) => {
@@ -360,6 +523,9 @@ impl MiningManager {
                     }
                     Err(err) => {
                         debug!("Failed to pre validate transaction {0} due to rule error: {1}", transaction_id, err);
+                        if let RuleError::RejectDoubleSpendInMempool(_, conflicting_id) = err {
+                            double_spends.push((inner_tx, conflicting_id));
+                        }
                         insert_results.push(Err(MiningManagerError::MempoolError(err)));
                         None
                     }
@@ -367,6 +533,13 @@ impl MiningManager {
             });
             transactions.extend(txs);
         }
+        let mut conflicts = vec![];
+        if !double_spends.is_empty() {
+            // write lock on mempool
+            let mut mempool = self.mempool.write();
+            conflicts
+                .extend(double_spends.iter().filter_map(|(tx, conflicting_id)| mempool.register_double_spend(tx, conflicting_id)));
+        }
 
         // no lock on mempool
         // We process the transactions by chunks of max block mass to prevent locking the virtual processor for too long.
@@ -386,7 +559,15 @@ impl MiningManager {
             let mut mempool = self.mempool.write();
             let txs = chunk.flat_map(|(transaction, validation_result)| {
                 let transaction_id = transaction.id();
-                match mempool.post_validate_and_insert_transaction(consensus, validation_result, transaction, priority, orphan) {
+                let inner_tx = transaction.tx.clone();
+                match mempool.post_validate_and_insert_transaction(
+                    consensus,
+                    validation_result,
+                    transaction,
+                    priority,
+                    orphan,
+                    Hold::Forbidden,
+                ) {
                     Ok(Some(accepted_transaction)) => {
                         insert_results.push(Ok(accepted_transaction.clone()));
                         self.counters.increase_tx_counts(1, priority);
@@ -398,6 +579,9 @@ impl MiningManager {
                     }
                     Err(err) => {
                         debug!("Failed to post validate transaction {0} due to rule error: {1}", transaction_id, err);
+                        if let RuleError::RejectDoubleSpendInMempool(_, conflicting_id) = err {
+                            conflicts.extend(mempool.register_double_spend(&inner_tx, &conflicting_id));
+                        }
                         insert_results.push(Err(MiningManagerError::MempoolError(err)));
                         vec![]
                     }
@@ -405,6 +589,7 @@ impl MiningManager {
             });
             unorphaned_transactions.extend(txs);
         }
+        self.notify_transaction_conflicts(conflicts);
 
         insert_results
             .extend(self.validate_and_insert_unorphaned_transactions(consensus, unorphaned_transactions).into_iter().map(Ok));
@@ -464,6 +649,36 @@ impl MiningManager {
         (transactions, orphans)
     }
 
+    /// Returns the transactions of the pools selected by `query`, the held transactions being returned along the
+    /// transaction pool, with a summary of these pools taken under the read lock the transaction ids are read from.
+    /// Every returned transaction is therefore accounted in the summary.
+    ///
+    /// The transaction pool is read by chunks of transactions under distinct read locks, so that large queries do not
+    /// stall the mempool, the transactions removed meanwhile missing from the result. With `consistent_snapshot`, all
+    /// the transactions are cloned under the read lock of the summary instead, so the result matches its summary.
+    pub fn get_mempool_entries(&self, query: TransactionQuery, consistent_snapshot: bool) -> MempoolEntries {
+        const TRANSACTION_CHUNK_SIZE: usize = 1000;
+        // read lock on mempool
+        let mempool = self.mempool.read();
+        let summary = mempool.summary(query);
+        let held_transactions = if query.include_transaction_pool() { mempool.get_all_held_transactions() } else { vec![] };
+        if consistent_snapshot {
+            let (transactions, orphans) = mempool.get_all_transactions(query);
+            return MempoolEntries { transactions, orphans, held_transactions, summary };
+        }
+        let (transaction_ids, _) = mempool.get_all_transaction_ids(query);
+        let orphans = if query.include_orphan_pool() { mempool.get_all_transactions(TransactionQuery::OrphansOnly).1 } else { vec![] };
+        drop(mempool);
+
+        // read lock on mempool by transaction chunks
+        let mut transactions = Vec::with_capacity(transaction_ids.len());
+        for chunk in transaction_ids.chunks(TRANSACTION_CHUNK_SIZE) {
+            let mempool = self.mempool.read();
+            transactions.extend(chunk.iter().filter_map(|x| mempool.get_transaction(x, TransactionQuery::TransactionsOnly)));
+        }
+        MempoolEntries { transactions, orphans, held_transactions, summary }
+    }
+
     /// get_transactions_by_addresses returns the sending and receiving transactions for
     /// a set of addresses.
     ///
@@ -481,6 +696,33 @@ impl MiningManager {
         self.mempool.read().transaction_count(query)
     }
 
+    /// Try to return a transaction held until its lock time is reached by its id.
+    pub fn get_held_transaction(&self, transaction_id: &TransactionId) -> Option<MutableTransaction> {
+        self.mempool.read().get_held_transaction(transaction_id)
+    }
+
+    /// Returns all the transactions held until their lock time is reached.
+    pub fn get_all_held_transactions(&self) -> Vec<MutableTransaction> {
+        self.mempool.read().get_all_held_transactions()
+    }
+
+    pub fn held_transaction_count(&self) -> usize {
+        self.mempool.read().held_transaction_count()
+    }
+
+    /// Returns the id of the mempool transaction spending `outpoint`, be it a regular, orphan or held transaction.
+    pub fn get_outpoint_spender(&self, outpoint: &TransactionOutpoint) -> Option<TransactionId> {
+        self.mempool.read().get_outpoint_spender(outpoint)
+    }
+
+    /// Estimates the confirmation time range of a transaction of the transaction pool from its position in
+    /// package-feerate order, the recent block mass utilization and the observed block rate.
+    ///
+    /// Returns `None` if the transaction is not in the transaction pool.
+    pub fn estimate_transaction_confirmation_time(&self, transaction_id: &TransactionId) -> Option<ConfirmationTimeEstimate> {
+        self.mempool.read().estimate_transaction_confirmation_time(transaction_id)
+    }
+
     pub fn handle_new_block_transactions(
         &self,
         consensus: &dyn ConsensusApi,
@@ -492,14 +734,91 @@ impl MiningManager {
         // problem of the internal implementation and unrelated to the caller
 
         // write lock on mempool
-        let unorphaned_transactions = self.mempool.write().handle_new_block_transactions(block_daa_score, block_transactions)?;
+        let (unorphaned_transactions, conflicts) =
+            self.mempool.write().handle_new_block_transactions(block_daa_score, block_transactions)?;
+        self.notify_transaction_conflicts(conflicts);
 
         // alternate no & write lock on mempool
-        let accepted_transactions = self.validate_and_insert_unorphaned_transactions(consensus, unorphaned_transactions);
+        let mut accepted_transactions = self.validate_and_insert_unorphaned_transactions(consensus, unorphaned_transactions);
+
+        // alternate read, no & write lock on mempool
+        accepted_transactions.extend(self.promote_held_transactions(consensus));
 
         Ok(accepted_transactions)
     }
 
+    /// Revalidates the held transactions whose lock time is reached in the current virtual state
+    /// and promotes the valid ones into the transaction pool. Transactions still not finalized are
+    /// held again while transactions failing on any other rule are dropped.
+    ///
+    /// The returned transactions are clones of objects owned by the mempool.
+    fn promote_held_transactions(&self, consensus: &dyn ConsensusApi) -> Vec<Arc<Transaction>> {
+        // read lock on mempool
+        let mut transactions = self
+            .mempool
+            .read()
+            .get_held_transactions_ready_for_promotion(consensus.get_virtual_daa_score(), consensus.get_virtual_past_median_time());
+        if transactions.is_empty() {
+            return vec![];
+        }
+
+        // no lock on mempool
+        // We process the transactions by chunks of max block mass to prevent locking the virtual processor for too long.
+        let mut lower_bound: usize = 0;
+        let mut validation_results = Vec::with_capacity(transactions.len());
+        while let Some(upper_bound) = self.next_transaction_chunk_upper_bound(&transactions, lower_bound) {
+            assert!(lower_bound < upper_bound, "the chunk is never empty");
+            validation_results
+                .extend(validate_mempool_transactions_in_parallel(consensus, &mut transactions[lower_bound..upper_bound]));
+            lower_bound = upper_bound;
+        }
+        assert_eq!(transactions.len(), validation_results.len(), "every transaction should have a matching validation result");
+
+        // write lock on mempool
+        let mut promoted_transactions = Vec::with_capacity(transactions.len());
+        let mut unorphaned_transactions = vec![];
+        let mut mempool = self.mempool.write();
+        for (transaction, validation_result) in transactions.into_iter().zip(validation_results) {
+            let transaction_id = transaction.id();
+            // Only consider transactions still being held since during the validation some might have been removed.
+            let Some(priority) = mempool.take_held_transaction(&transaction_id) else {
+                continue;
+            };
+            match mempool.post_validate_and_insert_transaction(
+                consensus,
+                validation_result,
+                transaction,
+                priority,
+                Orphan::Forbidden,
+                Hold::Allowed,
+            ) {
+                Ok(Some(accepted_transaction)) => {
+                    self.counters.increase_tx_counts(1, priority);
+                    unorphaned_transactions
+                        .extend(mempool.get_unorphaned_transactions_after_accepted_transaction(&accepted_transaction));
+                    promoted_transactions.push(accepted_transaction);
+                }
+                Ok(None) => {
+                    // Either held again or already existing in the mempool
+                }
+                Err(err) => {
+                    info!("Dropping held transaction {0} since it failed revalidation with {1}", transaction_id, err);
+                }
+            }
+        }
+        drop(mempool);
+
+        match promoted_transactions.len() {
+            0 => {}
+            1 => info!("Promoted held transaction {} into the mempool", promoted_transactions[0].id()),
+            n => info!("Promoted {} held transactions into the mempool", n),
+        }
+
+        // alternate no & write lock on mempool
+        promoted_transactions.extend(self.validate_and_insert_unorphaned_transactions(consensus, unorphaned_transactions));
+        promoted_transactions
+    }
+
     pub fn expire_low_priority_transactions(&self, consensus: &dyn ConsensusApi) {
         // very fine-grained write locks on mempool
         debug!("<> Expiring low priority transactions...");
@@ -534,8 +853,6 @@ impl MiningManager {
         consensus: &dyn ConsensusApi,
         transaction_ids_sender: UnboundedSender<Vec<TransactionId>>,
     ) {
-        const TRANSACTION_CHUNK_SIZE: usize = 1000;
-
         // read lock on mempool
         // Prepare a vector with clones of high priority transactions found in the mempool
         let mempool = self.mempool.read();
@@ -547,6 +864,93 @@ impl MiningManager {
             debug!("<> Revalidating {} high priority transactions...", transaction_ids.len());
         }
         drop(mempool);
+
+        let stats = self.revalidate_transactions(consensus, transaction_ids, Some(&transaction_ids_sender), false);
+        match stats.removed() {
+            0 => {
+                info!("Revalidated {} high priority transactions", stats.valid);
+            }
+            removed => {
+                info!(
+                    "Revalidated {} and removed {} high priority transactions (removals: {} accepted, {} missing outpoint, {} invalid)",
+                    stats.valid, removed, stats.accepted, stats.missing_outpoint, stats.invalid,
+                );
+            }
+        }
+    }
+
+    /// Updates the mempool after a change of the virtual UTXO set described by `utxo_diff`.
+    ///
+    /// Instead of rescanning the whole mempool, only the transactions spending an outpoint of the diff are
+    /// revalidated, along with their redeemers. Those are the transactions affected by the virtual change:
+    /// their inputs were either spent by a newly accepted transaction or created by a transaction which is
+    /// no longer accepted after a reorg (removed outpoints), or they spend outpoints restored by a reorg or
+    /// created by a newly accepted transaction (added outpoints). Transactions having their outputs in the
+    /// added outpoints are accepted by the virtual and left over to [`Self::handle_new_block_transactions`].
+    ///
+    /// Orphans spending added outpoints are unorphaned when all their inputs become available.
+    ///
+    /// The returned transactions are the unorphaned transactions inserted into the mempool.
+    pub fn handle_virtual_utxo_diff(&self, consensus: &dyn ConsensusApi, utxo_diff: &UtxoDiff) -> Vec<Arc<Transaction>> {
+        // write lock on mempool
+        let mut mempool = self.mempool.write();
+        let transaction_ids = mempool.transaction_ids_affected_by_utxo_diff(utxo_diff);
+        let unorphaned_transactions = mempool.get_unorphaned_transactions_after_utxo_diff(utxo_diff);
+        drop(mempool);
+
+        if !transaction_ids.is_empty() {
+            debug!("<> Revalidating {} transactions affected by a virtual UTXO change...", transaction_ids.len());
+            let stats = self.revalidate_transactions(consensus, transaction_ids, None, true);
+            if stats.removed() > 0 {
+                debug!(
+                    "Revalidated {} and removed {} transactions after a virtual UTXO change (removals: {} accepted, {} missing outpoint, {} invalid)",
+                    stats.valid,
+                    stats.removed(),
+                    stats.accepted,
+                    stats.missing_outpoint,
+                    stats.invalid,
+                );
+            }
+        }
+
+        // alternate no & write lock on mempool
+        self.validate_and_insert_unorphaned_transactions(consensus, unorphaned_transactions)
+    }
+
+    /// Registers the selected chain blocks accepting the transactions of a virtual chain change, so that resubmissions
+    /// of recently accepted transactions can be answered with their accepting block
+    pub fn handle_virtual_chain_changed(
+        &self,
+        consensus: &dyn ConsensusApi,
+        added_chain_block_hashes: &[Hash],
+        removed_chain_block_hashes: &[Hash],
+        added_chain_blocks_acceptance_data: &[Arc<AcceptanceData>],
+    ) {
+        let virtual_daa_score = consensus.get_virtual_daa_score();
+        self.mempool.write().handle_virtual_chain_changed(
+            virtual_daa_score,
+            added_chain_block_hashes,
+            removed_chain_block_hashes,
+            added_chain_blocks_acceptance_data,
+        );
+    }
+
+    /// Revalidates the transactions of the transaction pool identified by `transaction_ids` against the
+    /// current virtual UTXO set, removing the invalid ones from the mempool.
+    ///
+    /// The ids of the valid transactions are sent by chunks to `transaction_ids_sender` when provided.
+    ///
+    /// A transaction with missing outpoints is removed along with its redeemers only if `remove_redeemers_on_missing_outpoints`
+    /// is set, which callers do when the outpoints are known to be missing from the virtual UTXO set.
+    fn revalidate_transactions(
+        &self,
+        consensus: &dyn ConsensusApi,
+        transaction_ids: Vec<TransactionId>,
+        transaction_ids_sender: Option<&UnboundedSender<Vec<TransactionId>>>,
+        remove_redeemers_on_missing_outpoints: bool,
+    ) -> RevalidationStats {
+        const TRANSACTION_CHUNK_SIZE: usize = 1000;
+
         // read lock on mempool by transaction chunks
         let mut transactions = Vec::with_capacity(transaction_ids.len());
         for chunk in &transaction_ids.iter().chunks(TRANSACTION_CHUNK_SIZE) {
@@ -554,11 +958,7 @@ impl MiningManager {
             transactions.extend(chunk.filter_map(|x| mempool.get_transaction(x, TransactionQuery::TransactionsOnly)));
         }
 
-        let mut valid: usize = 0;
-        let mut accepted: usize = 0;
-        let mut other: usize = 0;
-        let mut missing_outpoint: usize = 0;
-        let mut invalid: usize = 0;
+        let mut stats = RevalidationStats::default();
 
         // We process the transactions by level of dependency inside the batch.
         // Doing so allows to remove all chained dependencies of rejected transactions.
@@ -576,7 +976,7 @@ impl MiningManager {
             let txs = chunk.filter_map(|mut x| {
                 let transaction_id = x.id();
                 if mempool.has_accepted_transaction(&transaction_id) {
-                    accepted += 1;
+                    stats.accepted += 1;
                     None
                 } else if mempool.has_transaction(&transaction_id, TransactionQuery::TransactionsOnly) {
                     x.clear_entries();
@@ -585,12 +985,12 @@ impl MiningManager {
                         false => Some(x),
                         true => {
                             // If all entries are populated with mempool UTXOs, we already know the transaction is valid
-                            valid += 1;
+                            stats.valid += 1;
                             None
                         }
                     }
                 } else {
-                    other += 1;
+                    stats.other += 1;
                     None
                 }
             });
@@ -631,7 +1031,7 @@ impl MiningManager {
                             // However, as only consequence, said transaction would then be advertised to registered peers and not be
                             // provided upon request.
                             valid_ids.push(transaction_id);
-                            valid += 1;
+                            stats.valid += 1;
                         }
                         Err(RuleError::RejectMissingOutpoint) => {
                             let transaction = mempool.get_transaction(&transaction_id, TransactionQuery::TransactionsOnly).unwrap();
@@ -655,7 +1055,8 @@ impl MiningManager {
                             // the consensus and this ongoing call to revalidate.
                             //
                             // So we only remove the transaction and keep its redeemers in the mempool because we cannot be sure they are invalid, in
-                            // fact in the race condition case they are valid regarding outpoints.
+                            // fact in the race condition case they are valid regarding outpoints. The redeemers are only removed when the caller knows
+                            // the outpoints are actually missing from the virtual UTXO set.
                             let extra_info = match missing_txs.len() {
                                 0 => " but no missing tx!".to_string(), // this is never supposed to happen
                                 1 => format!(" missing tx {}", missing_txs[0]),
@@ -665,56 +1066,41 @@ impl MiningManager {
                             // This call cleanly removes the invalid transaction.
                             let result = mempool.remove_transaction(
                                 &transaction_id,
-                                false,
+                                remove_redeemers_on_missing_outpoints,
                                 TxRemovalReason::RevalidationWithMissingOutpoints,
                                 extra_info.as_str(),
                             );
                             if let Err(err) = result {
                                 warn!("Failed to remove transaction {} from mempool: {}", transaction_id, err);
                             }
-                            missing_outpoint += 1;
+                            stats.missing_outpoint += 1;
                         }
                         Err(err) => {
                             // Rust rewrite note:
                             // The behavior changes here compared to the golang version.
                             // The failed revalidation is simply logged and the process continues.
-                            warn!(
-                                "Removing high priority transaction {0} and its redeemers, it failed revalidation with {1}",
-                                transaction_id, err
-                            );
+                            warn!("Removing transaction {0} and its redeemers, it failed revalidation with {1}", transaction_id, err);
                             // This call cleanly removes the invalid transaction and its redeemers.
                             let result = mempool.remove_transaction(&transaction_id, true, TxRemovalReason::Muted, "");
                             if let Err(err) = result {
                                 warn!("Failed to remove transaction {} from mempool: {}", transaction_id, err);
                             }
-                            invalid += 1;
+                            stats.invalid += 1;
                         }
                     }
                 } else {
-                    other += 1;
+                    stats.other += 1;
                 }
             }
-            if !valid_ids.is_empty() {
-                let _ = transaction_ids_sender.send(valid_ids);
+            if let Some(transaction_ids_sender) = transaction_ids_sender {
+                if !valid_ids.is_empty() {
+                    let _ = transaction_ids_sender.send(valid_ids);
+                }
             }
             drop(_swo);
             drop(mempool);
         }
-        match accepted + missing_outpoint + invalid {
-            0 => {
-                info!("Revalidated {} high priority transactions", valid);
-            }
-            _ => {
-                info!(
-                    "Revalidated {} and removed {} high priority transactions (removals: {} accepted, {} missing outpoint, {} invalid)",
-                    valid,
-                    accepted + missing_outpoint + invalid,
-                    accepted,
-                    missing_outpoint,
-                    invalid,
-                );
-            }
-        }
+        stats
     }
 
     /// is_transaction_output_dust returns whether or not the passed transaction output
@@ -723,15 +1109,26 @@ impl MiningManager {
     ///
     /// Dust is defined in terms of the minimum transaction relay fee. In particular,
     /// if the cost to the network to spend coins is more than 1/3 of the minimum
-    /// transaction relay fee, it is considered dust.
+    /// transaction relay fee, it is considered dust. The node-level [`DustRelayThresholds`]
+    /// override this rule.
     pub fn is_transaction_output_dust(&self, transaction_output: &TransactionOutput) -> bool {
         self.mempool.read().is_transaction_output_dust(transaction_output)
     }
 
+    /// Returns the minimum value of an output of each standard script class, accounting for
+    /// the node-level [`DustRelayThresholds`]
+    pub fn standard_dust_thresholds(&self) -> Vec<(ScriptClass, u64)> {
+        self.mempool.read().standard_dust_thresholds()
+    }
+
     pub fn has_accepted_transaction(&self, transaction_id: &TransactionId) -> bool {
         self.mempool.read().has_accepted_transaction(transaction_id)
     }
 
+    pub fn accepting_block(&self, transaction_id: &TransactionId) -> Option<Hash> {
+        self.mempool.read().accepting_block(transaction_id)
+    }
+
     pub fn unaccepted_transactions(&self, transactions: Vec<TransactionId>) -> Vec<TransactionId> {
         self.mempool.read().unaccepted_transactions(transactions)
     }
@@ -741,6 +1138,22 @@ impl MiningManager {
     }
 }
 
+/// Outcome of a revalidation of mempool transactions
+#[derive(Default)]
+struct RevalidationStats {
+    valid: usize,
+    accepted: usize,
+    other: usize,
+    missing_outpoint: usize,
+    invalid: usize,
+}
+
+impl RevalidationStats {
+    fn removed(&self) -> usize {
+        self.accepted + self.missing_outpoint + self.invalid
+    }
+}
+
 /// Async proxy for the mining manager
 #[derive(Clone)]
 pub struct MiningManagerProxy {
@@ -756,6 +1169,18 @@ impl MiningManagerProxy {
         consensus.clone().spawn_blocking(move |c| self.inner.get_block_template(c, &miner_data)).await
     }
 
+    /// Classifies a block submitted by a miner before it gets processed by consensus, counting
+    /// the stale and already processed submissions.
+    pub async fn classify_submitted_block(self, consensus: &ConsensusProxy, block: Block) -> BlockSubmissionClassification {
+        consensus.clone().spawn_blocking(move |c| self.inner.classify_submitted_block(c, &block)).await
+    }
+
+    /// Checks, if enabled, whether the transactions of a submitted block follow the canonical block template order,
+    /// counting the non-canonical submissions. Blocks with another order are valid and processed.
+    pub async fn check_submitted_transaction_order(self, block: Block) -> Option<SubmittedTransactionOrder> {
+        spawn_blocking(move || self.inner.check_submitted_transaction_order(&block)).await.unwrap()
+    }
+
     /// Validates a transaction and adds it to the set of known transactions that have not yet been
     /// added to any block.
     ///
@@ -770,6 +1195,27 @@ impl MiningManagerProxy {
         consensus.clone().spawn_blocking(move |c| self.inner.validate_and_insert_transaction(c, transaction, priority, orphan)).await
     }
 
+    /// Validates a transaction and adds it to the set of known transactions that have not yet been
+    /// added to any block. If `hold` is allowed and the mempool is configured accordingly, a transaction
+    /// whose lock time is not reached yet is held until it becomes valid.
+    ///
+    /// The returned transactions are clones of objects owned by the mempool.
+    pub async fn validate_and_insert_transaction_with_hold(
+        self,
+        consensus: &ConsensusProxy,
+        transaction: Transaction,
+        priority: Priority,
+        orphan: Orphan,
+        hold: Hold,
+    ) -> MiningManagerResult<Vec<Arc<Transaction>>> {
+        consensus
+            .clone()
+            .spawn_blocking(move |c| {
+                self.inner.validate_and_insert_mutable_transaction(c, MutableTransaction::from_tx(transaction), priority, orphan, hold)
+            })
+            .await
+    }
+
     /// Validates a batch of transactions, handling iteratively only the independent ones, and
     /// adds those to the set of known transactions that have not yet been added to any block.
     ///
@@ -812,6 +1258,37 @@ impl MiningManagerProxy {
         consensus.clone().spawn_blocking(move |c| self.inner.revalidate_high_priority_transactions(c, transaction_ids_sender)).await;
     }
 
+    /// Revalidates the mempool transactions affected by a change of the virtual UTXO set and unorphans the
+    /// orphans spending added outpoints.
+    ///
+    /// See [`MiningManager::handle_virtual_utxo_diff`]
+    pub async fn handle_virtual_utxo_diff(self, consensus: &ConsensusProxy, utxo_diff: Arc<UtxoDiff>) -> Vec<Arc<Transaction>> {
+        consensus.clone().spawn_blocking(move |c| self.inner.handle_virtual_utxo_diff(c, &utxo_diff)).await
+    }
+
+    /// Registers the selected chain blocks accepting the transactions of a virtual chain change.
+    ///
+    /// See [`MiningManager::handle_virtual_chain_changed`]
+    pub async fn handle_virtual_chain_changed(
+        self,
+        consensus: &ConsensusProxy,
+        added_chain_block_hashes: Arc<Vec<Hash>>,
+        removed_chain_block_hashes: Arc<Vec<Hash>>,
+        added_chain_blocks_acceptance_data: Arc<Vec<Arc<AcceptanceData>>>,
+    ) {
+        consensus
+            .clone()
+            .spawn_blocking(move |c| {
+                self.inner.handle_virtual_chain_changed(
+                    c,
+                    &added_chain_block_hashes,
+                    &removed_chain_block_hashes,
+                    &added_chain_blocks_acceptance_data,
+                )
+            })
+            .await
+    }
+
     /// Try to return a mempool transaction by its id.
     ///
     /// Note: the transaction is an orphan if tx.is_fully_populated() returns false.
@@ -828,10 +1305,37 @@ impl MiningManagerProxy {
         spawn_blocking(move || self.inner.transaction_count(query)).await.unwrap()
     }
 
+    /// Try to return a transaction held until its lock time is reached by its id.
+    pub async fn get_held_transaction(self, transaction_id: TransactionId) -> Option<MutableTransaction> {
+        spawn_blocking(move || self.inner.get_held_transaction(&transaction_id)).await.unwrap()
+    }
+
+    /// Returns all the transactions held until their lock time is reached.
+    pub async fn get_all_held_transactions(self) -> Vec<MutableTransaction> {
+        spawn_blocking(move || self.inner.get_all_held_transactions()).await.unwrap()
+    }
+
+    /// Returns the id of the mempool transaction spending `outpoint`, be it a regular, orphan or held transaction.
+    pub async fn get_outpoint_spender(self, outpoint: TransactionOutpoint) -> Option<TransactionId> {
+        spawn_blocking(move || self.inner.get_outpoint_spender(&outpoint)).await.unwrap()
+    }
+
+    /// Estimates the confirmation time range of a transaction of the transaction pool.
+    /// For more details, see [`MiningManager::estimate_transaction_confirmation_time()`].
+    pub async fn estimate_transaction_confirmation_time(self, transaction_id: TransactionId) -> Option<ConfirmationTimeEstimate> {
+        spawn_blocking(move || self.inner.estimate_transaction_confirmation_time(&transaction_id)).await.unwrap()
+    }
+
     pub async fn get_all_transactions(self, query: TransactionQuery) -> (Vec<MutableTransaction>, Vec<MutableTransaction>) {
         spawn_blocking(move || self.inner.get_all_transactions(query)).await.unwrap()
     }
 
+    /// Returns the transactions of the pools selected by `query` with a summary of these pools.
+    /// For more details, see [`MiningManager::get_mempool_entries()`].
+    pub async fn get_mempool_entries(self, query: TransactionQuery, consistent_snapshot: bool) -> MempoolEntries {
+        spawn_blocking(move || self.inner.get_mempool_entries(query, consistent_snapshot)).await.unwrap()
+    }
+
     /// get_transactions_by_addresses returns the sending and receiving transactions for
     /// a set of addresses.
     ///
@@ -856,6 +1360,12 @@ impl MiningManagerProxy {
         spawn_blocking(move || self.inner.has_accepted_transaction(&transaction_id)).await.unwrap()
     }
 
+    /// Returns the selected chain block which accepted the transaction, if the transaction was accepted within
+    /// the expiration delay of [`Self::has_accepted_transaction()`].
+    pub async fn accepting_block(self, transaction_id: TransactionId) -> Option<Hash> {
+        spawn_blocking(move || self.inner.accepting_block(&transaction_id)).await.unwrap()
+    }
+
     /// Returns a vector of unaccepted transactions.
     /// For more details, see [`Self::has_accepted_transaction()`].
     pub async fn unaccepted_transactions(self, transactions: Vec<TransactionId>) -> Vec<TransactionId> {
@@ -868,10 +1378,21 @@ impl MiningManagerProxy {
         spawn_blocking(move || self.inner.unknown_transactions(transactions)).await.unwrap()
     }
 
+    /// Returns the minimum value of an output of each standard script class.
+    /// For more details, see [`MiningManager::standard_dust_thresholds()`].
+    pub async fn standard_dust_thresholds(self) -> Vec<(ScriptClass, u64)> {
+        spawn_blocking(move || self.inner.standard_dust_thresholds()).await.unwrap()
+    }
+
     pub fn snapshot(&self) -> MempoolCountersSnapshot {
         self.inner.counters.snapshot()
     }
 
+    /// For more details, see [`MiningManager::set_transaction_conflict_sender()`].
+    pub fn set_transaction_conflict_sender(&self, sender: CollectorNotificationSender<TransactionConflict>) {
+        self.inner.set_transaction_conflict_sender(sender)
+    }
+
     pub fn p2p_tx_count_sample(&self) -> P2pTxCountSample {
         self.inner.counters.p2p_tx_count_sample()
     }
@@ -5,11 +5,18 @@ mod tests {
         errors::{MiningManagerError, MiningManagerResult},
         manager::MiningManager,
         mempool::{
-            config::{Config, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE},
+            config::{Config, DEFAULT_MAXIMUM_HELD_TRANSACTION_COUNT, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE},
             errors::RuleError,
-            tx::{Orphan, Priority},
+            tx::{Hold, Orphan, Priority},
+        },
+        model::{
+            acceptance_trace::{AcceptanceDetail, AcceptanceOutcome, AcceptanceRule, TransactionAcceptanceTrace},
+            block_submission::{BlockSubmissionClassification, SubmittedTransactionOrder},
+            candidate_tx::CandidateTransaction,
+            mempool_entries::MempoolEntries,
+            tx_conflict::{TransactionConflict, TransactionConflictSource},
+            tx_query::TransactionQuery,
         },
-        model::{candidate_tx::CandidateTransaction, tx_query::TransactionQuery},
         testutils::consensus_mock::ConsensusMock,
         MiningCounters,
     };
@@ -26,13 +33,24 @@ mod tests {
             scriptvec, MutableTransaction, ScriptPublicKey, Transaction, TransactionId, TransactionInput, TransactionOutpoint,
             TransactionOutput, UtxoEntry,
         },
+        utxo::utxo_diff::UtxoDiff,
     };
+    use kaspa_core::time::{unix_now, SimulatedClock};
     use kaspa_hashes::Hash;
     use kaspa_txscript::{
         pay_to_address_script, pay_to_script_hash_signature_script,
         test_helpers::{create_transaction, op_true_script},
     };
-    use std::sync::Arc;
+    use kaspa_utils::channel::Channel;
+    use parking_lot::Mutex;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::{
+        collections::{HashMap, HashSet},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+    };
     use tokio::sync::mpsc::{error::TryRecvError, unbounded_channel};
 
     const TARGET_TIME_PER_BLOCK: u64 = 1_000;
@@ -52,6 +70,7 @@ mod tests {
                 transaction.clone(),
                 Priority::Low,
                 Orphan::Allowed,
+                Hold::Forbidden,
             );
             assert!(result.is_ok(), "inserting a valid transaction failed");
         }
@@ -158,6 +177,7 @@ mod tests {
             transaction.clone(),
             Priority::Low,
             Orphan::Allowed,
+            Hold::Forbidden,
         );
         assert!(result.is_ok(), "mempool should have accepted a valid transaction but did not");
 
@@ -235,6 +255,188 @@ mod tests {
         }
     }
 
+    // test_get_outpoint_spender verifies that the mempool reports the transactions spending an outpoint,
+    // be they in the transaction pool or in the orphan pool.
+    #[test]
+    fn test_get_outpoint_spender() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+
+        let (parent_tx, child_tx) = create_parent_and_children_transactions(&consensus, vec![500 * SOMPI_PER_KASPA]);
+        let parent_outpoint = parent_tx.inputs[0].previous_outpoint;
+        let child_outpoint = child_tx.inputs[0].previous_outpoint;
+        assert_eq!(mining_manager.get_outpoint_spender(&parent_outpoint), None);
+
+        // The child is inserted first, hence as an orphan
+        let result =
+            mining_manager.validate_and_insert_transaction(consensus.as_ref(), child_tx.clone(), Priority::Low, Orphan::Allowed);
+        assert!(result.is_ok(), "the mempool should accept the valid orphan transaction {}", child_tx.id());
+        assert_eq!(mining_manager.get_outpoint_spender(&child_outpoint), Some(child_tx.id()));
+        assert_eq!(mining_manager.get_outpoint_spender(&parent_outpoint), None);
+
+        let result =
+            mining_manager.validate_and_insert_transaction(consensus.as_ref(), parent_tx.clone(), Priority::Low, Orphan::Allowed);
+        assert!(result.is_ok(), "the mempool should accept the valid transaction {}", parent_tx.id());
+        assert_eq!(mining_manager.get_outpoint_spender(&parent_outpoint), Some(parent_tx.id()));
+        assert_eq!(mining_manager.get_outpoint_spender(&child_outpoint), Some(child_tx.id()));
+
+        // An unrelated outpoint has no spender
+        assert_eq!(mining_manager.get_outpoint_spender(&TransactionOutpoint::new(child_tx.id(), 0)), None);
+    }
+
+    // test_get_mempool_entries verifies that the mempool entries returned while the mempool is concurrently mutated
+    // all belong to the snapshot the summary was taken from, and that the summary matches a consistent snapshot.
+    #[test]
+    fn test_get_mempool_entries() {
+        const INITIAL_TX_COUNT: u32 = 3_000;
+        const BATCH_SIZE: usize = 100;
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+        let insert = |transaction: &MutableTransaction| {
+            let result = mining_manager.validate_and_insert_mutable_transaction(
+                consensus.as_ref(),
+                transaction.clone(),
+                Priority::Low,
+                Orphan::Allowed,
+                Hold::Forbidden,
+            );
+            assert!(result.is_ok(), "inserting a valid transaction failed");
+        };
+        let assert_summary_matches = |entries: &MempoolEntries| {
+            let transactions = entries.transactions.iter().chain(entries.orphans.iter()).chain(entries.held_transactions.iter());
+            let (count, mass, fees) = transactions.fold((0, 0, 0), |(count, mass, fees), x| {
+                (count + 1, mass + x.tx.mass(), fees + x.calculated_fee.unwrap_or_default())
+            });
+            assert_eq!(entries.summary.transaction_count, count);
+            assert_eq!(entries.summary.total_mass, mass);
+            assert_eq!(entries.summary.total_fees, fees);
+        };
+
+        let initial_transactions = (0..INITIAL_TX_COUNT).map(|i| create_transaction_with_utxo_entry(i, 0)).collect::<Vec<_>>();
+        initial_transactions.iter().for_each(insert);
+        for consistent_snapshot in [false, true] {
+            let entries = mining_manager.get_mempool_entries(TransactionQuery::All, consistent_snapshot);
+            assert_eq!(entries.transactions.len(), initial_transactions.len());
+            assert_eq!(entries.summary.total_fees, INITIAL_TX_COUNT as u64 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+            assert_summary_matches(&entries);
+        }
+
+        // Batches of new transactions are inserted while batches of the initial transactions get mined. The unix time
+        // before the insertion and after the removal of each transaction is recorded.
+        let added_transactions =
+            (INITIAL_TX_COUNT..2 * INITIAL_TX_COUNT).map(|i| create_transaction_with_utxo_entry(i, 0)).collect::<Vec<_>>();
+        let inserted_at = Mutex::new(HashMap::<TransactionId, u64>::new());
+        let removed_at = Mutex::new(HashMap::<TransactionId, u64>::new());
+        let mutated = AtomicBool::new(false);
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                let batches = added_transactions.chunks(BATCH_SIZE).zip(initial_transactions.chunks(BATCH_SIZE));
+                for (i, (added, mined)) in batches.enumerate() {
+                    let now = unix_now();
+                    inserted_at.lock().extend(added.iter().map(|x| (x.id(), now)));
+                    added.iter().for_each(insert);
+                    let block_transactions = build_block_transactions(mined.iter().map(|x| x.tx.as_ref()));
+                    let result = mining_manager.handle_new_block_transactions(consensus.as_ref(), i as u64 + 1, &block_transactions);
+                    assert!(result.is_ok(), "handling the transactions of a block failed");
+                    let now = unix_now();
+                    removed_at.lock().extend(mined.iter().map(|x| (x.id(), now)));
+                }
+                mutated.store(true, Ordering::SeqCst);
+            });
+
+            // Query in both modes alternately until the mutations are over
+            let mut consistent_snapshot = false;
+            loop {
+                let done = mutated.load(Ordering::SeqCst);
+                let entries = mining_manager.get_mempool_entries(TransactionQuery::All, consistent_snapshot);
+                let snapshot_time = entries.summary.timestamp;
+                let (inserted_at, removed_at) = (inserted_at.lock(), removed_at.lock());
+                for transaction in entries.transactions.iter() {
+                    let id = transaction.id();
+                    if let Some(&inserted_at) = inserted_at.get(&id) {
+                        assert!(inserted_at <= snapshot_time, "the transaction {id} was inserted after the snapshot");
+                    }
+                    if let Some(&removed_at) = removed_at.get(&id) {
+                        assert!(removed_at >= snapshot_time, "the transaction {id} was removed before the snapshot");
+                    }
+                }
+                if consistent_snapshot {
+                    assert_summary_matches(&entries);
+                } else {
+                    assert!(entries.transactions.len() as u64 <= entries.summary.transaction_count);
+                }
+                if done {
+                    break;
+                }
+                consistent_snapshot = !consistent_snapshot;
+            }
+        });
+
+        // Once the mempool is no longer mutated both modes match the summary again
+        for consistent_snapshot in [false, true] {
+            let entries = mining_manager.get_mempool_entries(TransactionQuery::All, consistent_snapshot);
+            assert_eq!(entries.transactions.len(), added_transactions.len());
+            assert_summary_matches(&entries);
+        }
+    }
+
+    // test_estimate_transaction_confirmation_time verifies that the confirmation time estimate of a mempool transaction
+    // accounts for the transactions with a higher feerate and for the blocks handled by the mempool.
+    #[test]
+    fn test_estimate_transaction_confirmation_time() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+
+        let low_fee_tx = create_transaction_with_utxo_entry(0, 0);
+        let mut high_fee_tx = create_transaction_with_utxo_entry(1, 0);
+        Arc::make_mut(&mut high_fee_tx.tx).outputs[0].value -= 10 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE;
+        for transaction in [&low_fee_tx, &high_fee_tx] {
+            let result = mining_manager.validate_and_insert_transaction(
+                consensus.as_ref(),
+                transaction.tx.as_ref().clone(),
+                Priority::Low,
+                Orphan::Allowed,
+            );
+            assert!(result.is_ok(), "the mempool should accept the valid transaction {}", transaction.id());
+        }
+
+        let estimate = mining_manager.estimate_transaction_confirmation_time(&high_fee_tx.id()).unwrap();
+        assert_eq!(estimate.transactions_ahead, 0);
+        assert_eq!(estimate.mass_ahead, 0);
+        assert_eq!((estimate.min_blocks, estimate.max_blocks), (1, 1));
+        assert_eq!(estimate.block_utilization, 0.0);
+        assert_eq!(estimate.block_interval, TARGET_TIME_PER_BLOCK as f64, "no block was observed yet");
+        assert_eq!(estimate.min_time, TARGET_TIME_PER_BLOCK);
+
+        let high_fee_tx_mass =
+            mining_manager.get_transaction(&high_fee_tx.id(), TransactionQuery::TransactionsOnly).unwrap().tx.mass();
+        let estimate = mining_manager.estimate_transaction_confirmation_time(&low_fee_tx.id()).unwrap();
+        assert_eq!(estimate.transactions_ahead, 1);
+        assert_eq!(estimate.mass_ahead, high_fee_tx_mass);
+        assert!(
+            estimate.package_feerate
+                < mining_manager.estimate_transaction_confirmation_time(&high_fee_tx.id()).unwrap().package_feerate
+        );
+
+        // A transaction unknown to the mempool has no estimate
+        assert!(mining_manager.estimate_transaction_confirmation_time(&TransactionId::from_u64_word(1)).is_none());
+
+        // Once mined, the high fee transaction leaves the mempool and the block mass is accounted for
+        let mined_tx =
+            mining_manager.get_transaction(&high_fee_tx.id(), TransactionQuery::TransactionsOnly).unwrap().tx.as_ref().clone();
+        mined_tx.set_mass(MAX_BLOCK_MASS / 2);
+        let block_transactions = build_block_transactions(std::iter::once(&mined_tx));
+        let result = mining_manager.handle_new_block_transactions(consensus.as_ref(), 2, &block_transactions);
+        assert!(result.is_ok());
+        assert!(mining_manager.estimate_transaction_confirmation_time(&high_fee_tx.id()).is_none());
+        let estimate = mining_manager.estimate_transaction_confirmation_time(&low_fee_tx.id()).unwrap();
+        assert_eq!(estimate.transactions_ahead, 0);
+        assert!(estimate.block_utilization >= 0.5);
+    }
+
     // test_handle_new_block_transactions verifies that all the transactions in the block were successfully removed from the mempool.
     #[test]
     fn test_handle_new_block_transactions() {
@@ -326,6 +528,66 @@ mod tests {
         );
     }
 
+    // test_transaction_conflict_notifications verifies that the double spends of a mempool transaction are reported,
+    // both when rejected at admission and when the mempool transaction and its rejected double spend lose against a
+    // block transaction.
+    #[test]
+    fn test_transaction_conflict_notifications() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+        let channel = Channel::<TransactionConflict>::default();
+        mining_manager.set_transaction_conflict_sender(channel.sender());
+
+        let transaction = create_child_and_parent_txs_and_add_parent_to_consensus(&consensus);
+        let outpoint = transaction.inputs[0].previous_outpoint;
+        let double_spend = |transaction: &Transaction, delta: u64| {
+            let mut double_spend = transaction.clone();
+            double_spend.outputs[0].value -= delta;
+            double_spend.finalize();
+            double_spend
+        };
+        let received = || std::iter::from_fn(|| channel.try_recv().ok()).collect::<Vec<_>>();
+
+        let result =
+            mining_manager.validate_and_insert_transaction(consensus.as_ref(), transaction.clone(), Priority::Low, Orphan::Allowed);
+        assert!(result.is_ok());
+        assert!(received().is_empty(), "a transaction without conflict should not be reported");
+
+        // A rejected double spend is reported once only
+        let rejected_transaction = double_spend(&transaction, 1);
+        for _ in 0..2 {
+            let result = mining_manager.validate_and_insert_transaction(
+                consensus.as_ref(),
+                rejected_transaction.clone(),
+                Priority::Low,
+                Orphan::Allowed,
+            );
+            assert!(result.is_err(), "mempool should refuse a double spend transaction but accepts it");
+        }
+        let conflicts = received();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].transaction_id, rejected_transaction.id());
+        assert_eq!(conflicts[0].conflicting_transaction_id, transaction.id());
+        assert_eq!(conflicts[0].outpoints, vec![outpoint]);
+        assert_eq!(conflicts[0].source, TransactionConflictSource::Mempool);
+        assert!(conflicts[0].script_public_keys.contains(&transaction.outputs[0].script_public_key));
+
+        // Both the mempool transaction and the rejected one lose against the block transaction
+        let block_transaction = double_spend(&transaction, 2);
+        let block_transactions = build_block_transactions(std::iter::once(&block_transaction));
+        let result = mining_manager.handle_new_block_transactions(consensus.as_ref(), 2, &block_transactions);
+        assert!(result.is_ok());
+        let conflicts = received();
+        let losing_ids = conflicts.iter().map(|conflict| conflict.transaction_id).collect::<HashSet<_>>();
+        assert_eq!(losing_ids, HashSet::from([transaction.id(), rejected_transaction.id()]));
+        for conflict in conflicts.iter() {
+            assert_eq!(conflict.conflicting_transaction_id, block_transaction.id());
+            assert_eq!(conflict.outpoints, vec![outpoint]);
+            assert_eq!(conflict.source, TransactionConflictSource::Block);
+        }
+    }
+
     // test_orphan_transactions verifies that a transaction could be a part of a new block template only if it's not an orphan.
     #[test]
     fn test_orphan_transactions() {
@@ -725,6 +987,587 @@ mod tests {
         assert!(orphan_txs.is_empty(), "orphan pool should be empty");
     }
 
+    /// test_handle_virtual_utxo_diff verifies that the incremental revalidation of the transactions affected by virtual UTXO
+    /// changes leaves the mempool in the same state as full revalidations of all high priority transactions do, over randomized
+    /// virtual chain changes confirming, double spending, reorging out and restoring the funding of chains of transactions.
+    #[test]
+    fn test_handle_virtual_utxo_diff() {
+        const SCENARIO_COUNT: u64 = 20;
+        const CHAIN_COUNT: usize = 12;
+        const ROUND_COUNT: u64 = 5;
+
+        struct Chain {
+            // The transaction funding the first transaction of the chain
+            funding_tx: Transaction,
+            transactions: Vec<Transaction>,
+        }
+
+        for seed in 0..SCENARIO_COUNT {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let consensus = Arc::new(ConsensusMock::new());
+            let full = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, Arc::new(MiningCounters::default()));
+            let incremental =
+                MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, Arc::new(MiningCounters::default()));
+
+            // Insert in both mempools chains of high priority transactions of random length
+            let mut chains = (0..CHAIN_COUNT)
+                .map(|i| {
+                    let funding_tx = create_transaction_without_input(vec![1_000 * SOMPI_PER_KASPA + i as u64]);
+                    consensus.add_transaction(funding_tx.clone(), 1);
+                    let mut transactions = vec![create_transaction(&funding_tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE)];
+                    for _ in 1..rng.gen_range(1..=3) {
+                        transactions.push(create_transaction(transactions.last().unwrap(), DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE));
+                    }
+                    for transaction in transactions.iter() {
+                        for mining_manager in [&full, &incremental] {
+                            let result = mining_manager.validate_and_insert_transaction(
+                                consensus.as_ref(),
+                                transaction.clone(),
+                                Priority::High,
+                                Orphan::Forbidden,
+                            );
+                            assert!(result.is_ok(), "the insertion of a new valid transaction in the mempool failed");
+                        }
+                    }
+                    Chain { funding_tx, transactions }
+                })
+                .collect::<Vec<_>>();
+
+            for round in 0..ROUND_COUNT {
+                let block_daa_score = 2 + round;
+                let mut utxo_diff = UtxoDiff::default();
+                let mut block_transactions = vec![];
+                for chain in chains.iter_mut() {
+                    let funding_utxo = utxo_of(&chain.funding_tx, 1);
+                    let is_funded = consensus.has_utxo(&funding_utxo.0);
+                    match rng.gen_range(0..8) {
+                        // Confirm the first transaction of the chain
+                        0 if is_funded && !chain.transactions.is_empty() => {
+                            let transaction = chain.transactions.remove(0);
+                            utxo_diff.remove.insert(funding_utxo.0, funding_utxo.1);
+                            utxo_diff.add.extend([utxo_of(&transaction, block_daa_score)]);
+                            block_transactions.push(transaction.clone());
+                            chain.funding_tx = transaction;
+                        }
+                        // Confirm a transaction double spending the first transaction of the chain
+                        1 if is_funded && !chain.transactions.is_empty() => {
+                            let mut double_spend = chain.transactions[0].clone();
+                            double_spend.outputs[0].value -= 1;
+                            double_spend.finalize();
+                            utxo_diff.remove.insert(funding_utxo.0, funding_utxo.1);
+                            utxo_diff.add.extend([utxo_of(&double_spend, block_daa_score)]);
+                            block_transactions.push(double_spend);
+                            chain.transactions.clear();
+                        }
+                        // Reorg out the funding of the chain
+                        2 if is_funded => {
+                            utxo_diff.remove.insert(funding_utxo.0, funding_utxo.1);
+                        }
+                        // Restore the funding of the chain
+                        3 if !is_funded && !chain.transactions.is_empty() => {
+                            utxo_diff.add.insert(funding_utxo.0, funding_utxo.1);
+                        }
+                        _ => {}
+                    }
+                }
+
+                consensus.apply_utxo_diff(&utxo_diff);
+                let block_transactions = build_block_transactions(block_transactions.iter());
+                for mining_manager in [&full, &incremental] {
+                    let result =
+                        mining_manager.handle_new_block_transactions(consensus.as_ref(), block_daa_score, &block_transactions);
+                    assert!(result.is_ok(), "the handling of the block transactions should succeed but returned {result:?}");
+                }
+
+                // A full revalidation keeps the redeemers of transactions with missing outpoints, so repeat it until the mempool is stable
+                loop {
+                    let transaction_count = full.transaction_count(TransactionQuery::TransactionsOnly);
+                    full.revalidate_high_priority_transactions(consensus.as_ref(), unbounded_channel().0);
+                    if full.transaction_count(TransactionQuery::TransactionsOnly) == transaction_count {
+                        break;
+                    }
+                }
+                incremental.handle_virtual_utxo_diff(consensus.as_ref(), &utxo_diff);
+
+                let transaction_ids = |mining_manager: &MiningManager| {
+                    mining_manager
+                        .get_all_transactions(TransactionQuery::TransactionsOnly)
+                        .0
+                        .iter()
+                        .map(|x| x.id())
+                        .collect::<HashSet<_>>()
+                };
+                assert_eq!(
+                    transaction_ids(&full),
+                    transaction_ids(&incremental),
+                    "scenario {seed}, round {round}: the incremental and full revalidations should keep the same transactions"
+                );
+            }
+        }
+    }
+
+    /// test_handle_virtual_utxo_diff_unorphans_transactions verifies that an orphan transaction is unorphaned when a virtual
+    /// UTXO change restores the outpoints it spends.
+    #[test]
+    fn test_handle_virtual_utxo_diff_unorphans_transactions() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+
+        let parent_tx = create_transaction_without_input(vec![500 * SOMPI_PER_KASPA]);
+        let child_tx = create_transaction(&parent_tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let result =
+            mining_manager.validate_and_insert_transaction(consensus.as_ref(), child_tx.clone(), Priority::Low, Orphan::Allowed);
+        assert!(result.is_ok(), "the mempool should accept the valid orphan transaction");
+        assert!(mining_manager.has_transaction(&child_tx.id(), TransactionQuery::OrphansOnly));
+
+        // Simulate a reorg restoring the output of the parent transaction
+        let utxo_diff = UtxoDiff::new([utxo_of(&parent_tx, 5)].into_iter().collect(), Default::default());
+        consensus.apply_utxo_diff(&utxo_diff);
+        let unorphaned_txs = mining_manager.handle_virtual_utxo_diff(consensus.as_ref(), &utxo_diff);
+        assert_eq!(1, unorphaned_txs.len(), "the child transaction should be unorphaned");
+        assert_eq!(child_tx.id(), unorphaned_txs[0].id());
+        assert!(!mining_manager.has_transaction(&child_tx.id(), TransactionQuery::OrphansOnly));
+
+        let transaction = mining_manager.get_transaction(&child_tx.id(), TransactionQuery::TransactionsOnly);
+        assert!(transaction.is_some(), "the unorphaned transaction should be in the transaction pool");
+        assert_eq!(
+            5,
+            transaction.unwrap().entries[0].as_ref().unwrap().block_daa_score,
+            "the unorphaned transaction should spend the restored UTXO entry"
+        );
+    }
+
+    /// test_max_block_transactions verifies that block templates never hold more transactions, coinbase included,
+    /// than the configured per-block limit.
+    #[test]
+    fn test_max_block_transactions() {
+        const MAX_BLOCK_TRANSACTIONS: usize = 3;
+
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let config =
+            Config::build_default(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS).apply_max_block_transactions(MAX_BLOCK_TRANSACTIONS);
+        let mining_manager = MiningManager::with_config(config, None, counters);
+
+        for i in 0..5 {
+            let funding_tx = create_transaction_without_input(vec![(500 + i) * SOMPI_PER_KASPA]);
+            consensus.add_transaction(funding_tx.clone(), 1);
+            let tx = create_transaction(&funding_tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+            let result = mining_manager.validate_and_insert_transaction(consensus.as_ref(), tx, Priority::High, Orphan::Forbidden);
+            assert!(result.is_ok(), "inserting a valid transaction into the mempool failed: {result:?}");
+        }
+
+        let result = mining_manager.get_block_template(consensus.as_ref(), &get_miner_data(Prefix::Testnet));
+        assert!(result.is_ok(), "failed at getting a block template");
+        let template = result.unwrap();
+        assert_eq!(
+            template.block.transactions.len(),
+            MAX_BLOCK_TRANSACTIONS,
+            "the block template should be filled up to the transaction count limit"
+        );
+    }
+
+    /// test_expire_low_priority_transactions verifies that the expiration scans of low priority transactions are
+    /// paced by the mempool clock, running on simulated time without waiting for the scan interval.
+    #[test]
+    fn test_expire_low_priority_transactions() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let clock = SimulatedClock::from_system_time();
+        let config = Config::build_default(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS).apply_clock(clock.as_dyn());
+        let expire_scan_interval_milliseconds = config.transaction_expire_scan_interval_milliseconds;
+        let expired_daa_score = config.transaction_expire_interval_daa_score + config.transaction_expire_scan_interval_daa_score + 1;
+        let mining_manager = MiningManager::with_config(config, None, counters);
+
+        let transactions = [Priority::Low, Priority::High].map(|priority| {
+            let funding_tx = create_transaction_without_input(vec![500 * SOMPI_PER_KASPA]);
+            consensus.add_transaction(funding_tx.clone(), 1);
+            let tx = create_transaction(&funding_tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+            let result = mining_manager.validate_and_insert_transaction(consensus.as_ref(), tx.clone(), priority, Orphan::Forbidden);
+            assert!(result.is_ok(), "inserting a valid transaction into the mempool failed: {result:?}");
+            tx
+        });
+        consensus.set_virtual_daa_score(expired_daa_score);
+
+        // The scan interval did not elapse yet so nothing gets expired
+        mining_manager.expire_low_priority_transactions(consensus.as_ref());
+        assert_eq!(mining_manager.transaction_count(TransactionQuery::TransactionsOnly), 2);
+
+        clock.advance_millis(expire_scan_interval_milliseconds);
+        mining_manager.expire_low_priority_transactions(consensus.as_ref());
+        assert!(
+            !mining_manager.has_transaction(&transactions[0].id(), TransactionQuery::TransactionsOnly),
+            "the low priority transaction should have expired"
+        );
+        assert!(
+            mining_manager.has_transaction(&transactions[1].id(), TransactionQuery::TransactionsOnly),
+            "a high priority transaction should never expire"
+        );
+    }
+
+    /// test_held_transactions verifies that a transaction locked until a future DAA score is held apart from the
+    /// mempool when allowed, then automatically promoted once the virtual DAA score passes its lock time and
+    /// eventually included in a block template.
+    #[test]
+    fn test_held_transactions() {
+        const VIRTUAL_DAA_SCORE: u64 = 1_000;
+        const LOCK_TIME: u64 = VIRTUAL_DAA_SCORE + 100;
+
+        let consensus = Arc::new(ConsensusMock::new());
+        consensus.set_virtual_daa_score(VIRTUAL_DAA_SCORE);
+        let counters = Arc::new(MiningCounters::default());
+        let config = Config::build_default(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS)
+            .apply_held_transaction_limit(DEFAULT_MAXIMUM_HELD_TRANSACTION_COUNT);
+        let mining_manager = MiningManager::with_config(config, None, counters);
+
+        // Create a transaction spending a UTXO known by consensus and locked 100 DAA in the future
+        let funding_tx = create_transaction_without_input(vec![500 * SOMPI_PER_KASPA]);
+        consensus.add_transaction(funding_tx.clone(), 1);
+        let mut locked_tx = create_transaction(&funding_tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        locked_tx.lock_time = LOCK_TIME;
+        locked_tx.inputs[0].sequence = 0;
+        locked_tx.finalize();
+
+        // Without hold, the transaction is rejected
+        let result =
+            mining_manager.validate_and_insert_transaction(consensus.as_ref(), locked_tx.clone(), Priority::High, Orphan::Forbidden);
+        assert_eq!(Err(TxRuleError::NotFinalized(0)), into_status(result), "a not finalized transaction should be rejected");
+        assert!(mining_manager.get_held_transaction(&locked_tx.id()).is_none(), "the rejected transaction should not be held");
+
+        // With hold, the transaction is held apart from the mempool
+        let result = mining_manager.validate_and_insert_mutable_transaction(
+            consensus.as_ref(),
+            MutableTransaction::from_tx(locked_tx.clone()),
+            Priority::High,
+            Orphan::Forbidden,
+            Hold::Allowed,
+        );
+        assert!(result.is_ok(), "a not finalized transaction should be held but got {result:?}");
+        assert!(result.unwrap().is_empty(), "a held transaction should not be reported as accepted");
+        assert!(mining_manager.get_held_transaction(&locked_tx.id()).is_some(), "the transaction should be held");
+        assert!(
+            mining_manager.get_transaction(&locked_tx.id(), TransactionQuery::All).is_none(),
+            "a held transaction should not be in the mempool"
+        );
+        assert!(mining_manager.block_candidate_transactions().is_empty(), "a held transaction should not be a block candidate");
+
+        // Submitting the same transaction again is rejected as a duplicate
+        let result = mining_manager.validate_and_insert_mutable_transaction(
+            consensus.as_ref(),
+            MutableTransaction::from_tx(locked_tx.clone()),
+            Priority::High,
+            Orphan::Forbidden,
+            Hold::Allowed,
+        );
+        assert!(
+            matches!(result, Err(MiningManagerError::MempoolError(RuleError::RejectDuplicate(id))) if id == locked_tx.id()),
+            "a held transaction submitted again should be rejected as a duplicate"
+        );
+
+        // Mine up to the lock time, the transaction is still not finalized and remains held
+        consensus.set_virtual_daa_score(LOCK_TIME);
+        let result =
+            mining_manager.handle_new_block_transactions(consensus.as_ref(), LOCK_TIME, &build_block_transactions(std::iter::empty()));
+        assert!(result.is_ok());
+        assert!(result.unwrap().is_empty(), "a not finalized held transaction should not be promoted");
+        assert!(mining_manager.get_held_transaction(&locked_tx.id()).is_some(), "the transaction should still be held");
+
+        // Mine past the lock time, the transaction gets promoted into the mempool
+        consensus.set_virtual_daa_score(LOCK_TIME + 1);
+        let result = mining_manager.handle_new_block_transactions(
+            consensus.as_ref(),
+            LOCK_TIME + 1,
+            &build_block_transactions(std::iter::empty()),
+        );
+        assert!(result.is_ok());
+        let promoted_txs = result.unwrap();
+        assert_eq!(1, promoted_txs.len(), "the held transaction should be promoted");
+        assert_eq!(locked_tx.id(), promoted_txs[0].id(), "the promoted transaction is not the right one");
+        assert!(mining_manager.get_held_transaction(&locked_tx.id()).is_none(), "a promoted transaction should no longer be held");
+        assert!(
+            mining_manager.get_transaction(&locked_tx.id(), TransactionQuery::TransactionsOnly).is_some(),
+            "a promoted transaction should be in the mempool"
+        );
+
+        // The promoted transaction is included in the next block template
+        let result = mining_manager.get_block_template(consensus.as_ref(), &get_miner_data(Prefix::Testnet));
+        assert!(result.is_ok(), "failed at getting a block template");
+        let template = result.unwrap();
+        assert!(
+            contained_by(locked_tx.id(), &template.block.transactions),
+            "the promoted transaction should be found in a built block template"
+        );
+
+        // Once mined, the transaction leaves the mempool
+        consensus.add_transaction(locked_tx.clone(), LOCK_TIME + 2);
+        let result = mining_manager.handle_new_block_transactions(
+            consensus.as_ref(),
+            LOCK_TIME + 2,
+            &build_block_transactions(std::iter::once(&locked_tx)),
+        );
+        assert!(result.is_ok());
+        assert!(
+            mining_manager.get_transaction(&locked_tx.id(), TransactionQuery::All).is_none(),
+            "the mined transaction should no longer be in the mempool"
+        );
+    }
+
+    /// test_transaction_acceptance_trace verifies that the acceptance trace of a rejected transaction
+    /// reports the rules passed and the failing one along with its inputs, for several rejection categories.
+    #[test]
+    fn test_transaction_acceptance_trace() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+
+        let assert_trace = |trace: &TransactionAcceptanceTrace, passed: &[AcceptanceRule], failed: AcceptanceRule| {
+            let rules = trace.checks.iter().map(|check| check.rule).collect::<Vec<_>>();
+            assert_eq!(rules, passed.iter().copied().chain(std::iter::once(failed)).collect::<Vec<_>>(), "unexpected checked rules");
+            assert!(trace.checks[..passed.len()].iter().all(|check| matches!(check.outcome, AcceptanceOutcome::Passed)));
+            assert_eq!(trace.failed_check().map(|check| check.rule), Some(failed), "unexpected failing rule");
+        };
+        let isolation_rules = [
+            AcceptanceRule::NotAlreadyAccepted,
+            AcceptanceRule::NotDuplicate,
+            AcceptanceRule::StandardInIsolation,
+            AcceptanceRule::NoDoubleSpend,
+        ];
+
+        // A valid transaction passes all the rules
+        let funding_tx = create_transaction_without_input(vec![500 * SOMPI_PER_KASPA, 600 * SOMPI_PER_KASPA, 700 * SOMPI_PER_KASPA]);
+        consensus.add_transaction(funding_tx.clone(), 1);
+        let valid_tx = create_transaction(&funding_tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let trace = mining_manager.trace_transaction_acceptance(consensus.as_ref(), valid_tx.clone());
+        assert!(trace.failed_check().is_none(), "a valid transaction should pass all rules but got {trace:?}");
+        assert_eq!(trace.checks.last().unwrap().rule, AcceptanceRule::PoolCapacity);
+        assert!(!AcceptanceRule::NoDoubleSpend.is_contextual() && AcceptanceRule::InputsAvailable.is_contextual());
+
+        // Missing outpoints
+        let unknown_funding_tx = create_transaction_without_input(vec![800 * SOMPI_PER_KASPA]);
+        let orphan_tx = create_transaction(&unknown_funding_tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let result =
+            mining_manager.validate_and_insert_transaction(consensus.as_ref(), orphan_tx.clone(), Priority::High, Orphan::Forbidden);
+        assert!(matches!(result, Err(MiningManagerError::MempoolError(RuleError::RejectDisallowedOrphan(_)))));
+        let trace = mining_manager.trace_transaction_acceptance(consensus.as_ref(), orphan_tx.clone());
+        assert_trace(&trace, &isolation_rules, AcceptanceRule::InputsAvailable);
+        assert_eq!(
+            trace.failed_check().unwrap().detail,
+            Some(AcceptanceDetail::MissingOutpoints(vec![TransactionOutpoint::new(unknown_funding_tx.id(), 0)]))
+        );
+
+        // Insufficient fee
+        let no_fee_tx = create_transaction(&funding_tx, 0);
+        let result =
+            mining_manager.validate_and_insert_transaction(consensus.as_ref(), no_fee_tx.clone(), Priority::High, Orphan::Forbidden);
+        assert!(matches!(result, Err(MiningManagerError::MempoolError(RuleError::RejectNonStandard(..)))));
+        let trace = mining_manager.trace_transaction_acceptance(consensus.as_ref(), no_fee_tx.clone());
+        let contextual_rules = [
+            AcceptanceRule::InputsAvailable,
+            AcceptanceRule::ConsensusContext,
+            AcceptanceRule::NotSpam,
+            AcceptanceRule::StandardContextualMass,
+            AcceptanceRule::StandardInputScripts,
+        ];
+        assert_trace(&trace, &[&isolation_rules[..], &contextual_rules[..]].concat(), AcceptanceRule::MinimumFee);
+        let required = transaction_estimated_serialized_size(&no_fee_tx) * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE / 1000;
+        assert_eq!(trace.failed_check().unwrap().detail, Some(AcceptanceDetail::Fee { required, provided: 0 }));
+        let mass = transaction_estimated_serialized_size(&no_fee_tx);
+        assert_eq!(
+            trace.check(AcceptanceRule::StandardContextualMass).unwrap().detail,
+            Some(AcceptanceDetail::Mass { mass, limit: 100_000 }),
+            "the inputs of the passed rules should be reported as well"
+        );
+
+        // Mass above the standard limit
+        let mut heavy_tx = create_transaction(&funding_tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        heavy_tx.payload = vec![0; 100_000];
+        heavy_tx.finalize();
+        let result =
+            mining_manager.validate_and_insert_transaction(consensus.as_ref(), heavy_tx.clone(), Priority::High, Orphan::Forbidden);
+        assert!(matches!(result, Err(MiningManagerError::MempoolError(RuleError::RejectNonStandard(..)))));
+        let trace = mining_manager.trace_transaction_acceptance(consensus.as_ref(), heavy_tx.clone());
+        assert_trace(&trace, &isolation_rules[..2], AcceptanceRule::StandardInIsolation);
+        assert_eq!(
+            trace.failed_check().unwrap().detail,
+            Some(AcceptanceDetail::Mass { mass: transaction_estimated_serialized_size(&heavy_tx), limit: 100_000 })
+        );
+
+        // Dust output, reported along with the threshold applied to its pay-to-script-hash class
+        let mut dust_tx = create_transaction(&funding_tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        dust_tx.outputs[0].value = 1;
+        dust_tx.finalize();
+        let trace = mining_manager.trace_transaction_acceptance(consensus.as_ref(), dust_tx.clone());
+        assert_trace(&trace, &isolation_rules[..2], AcceptanceRule::StandardInIsolation);
+        let threshold = 3 * (53 + 148) * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE / 1000;
+        assert_eq!(trace.failed_check().unwrap().detail, Some(AcceptanceDetail::Dust { output_index: 0, value: 1, threshold }));
+
+        // Immature coinbase spend
+        let immature_tx = create_transaction(&funding_tx, 2 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let immature_spend = TxRuleError::ImmatureCoinbaseSpend(0, immature_tx.inputs[0].previous_outpoint, 10, 50, 100);
+        consensus.set_status(immature_tx.id(), Err(immature_spend));
+        let result =
+            mining_manager.validate_and_insert_transaction(consensus.as_ref(), immature_tx.clone(), Priority::High, Orphan::Forbidden);
+        assert!(matches!(result, Err(MiningManagerError::MempoolError(RuleError::RejectImmatureSpend(_)))));
+        let trace = mining_manager.trace_transaction_acceptance(consensus.as_ref(), immature_tx.clone());
+        assert_trace(
+            &trace,
+            &[&isolation_rules[..], &[AcceptanceRule::InputsAvailable][..]].concat(),
+            AcceptanceRule::ConsensusContext,
+        );
+        assert_eq!(trace.failed_check().unwrap().detail, Some(AcceptanceDetail::MaturityShortfall { input_index: 0, shortfall: 60 }));
+
+        // Double spend of a mempool transaction, and duplicate of the latter
+        let result =
+            mining_manager.validate_and_insert_transaction(consensus.as_ref(), valid_tx.clone(), Priority::High, Orphan::Forbidden);
+        assert!(result.is_ok());
+        let double_spend_tx = create_transaction(&funding_tx, 3 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let trace = mining_manager.trace_transaction_acceptance(consensus.as_ref(), double_spend_tx.clone());
+        assert_trace(&trace, &isolation_rules[..3], AcceptanceRule::NoDoubleSpend);
+        assert!(matches!(
+            trace.failed_check().unwrap().outcome,
+            AcceptanceOutcome::Failed(RuleError::RejectDoubleSpendInMempool(_, id)) if id == valid_tx.id()
+        ));
+        let trace = mining_manager.trace_transaction_acceptance(consensus.as_ref(), valid_tx.clone());
+        assert_trace(&trace, &isolation_rules[..1], AcceptanceRule::NotDuplicate);
+
+        // Tracing never inserts a transaction
+        for transaction in [&orphan_tx, &no_fee_tx, &heavy_tx, &immature_tx, &double_spend_tx] {
+            assert!(mining_manager.get_transaction(&transaction.id(), TransactionQuery::All).is_none());
+        }
+    }
+
+    // test_classify_submitted_blocks verifies that resubmitted blocks and blocks built on outdated templates are
+    // classified and counted as such.
+    #[test]
+    fn test_classify_submitted_blocks() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters.clone());
+        consensus.add_block(Hash::from_u64_word(1));
+        let miner_data = get_miner_data(Prefix::Testnet);
+
+        let old_template = mining_manager.get_block_template(consensus.as_ref(), &miner_data).unwrap();
+        let old_block = old_template.block.clone().to_immutable();
+        assert_eq!(mining_manager.classify_submitted_block(consensus.as_ref(), &old_block), BlockSubmissionClassification::Fresh);
+
+        // A new tip is mined from a newer template
+        mining_manager.clear_block_template();
+        let mut template = mining_manager.get_block_template(consensus.as_ref(), &miner_data).unwrap();
+        template.block.header.nonce = 1;
+        template.block.header.finalize();
+        let block = template.block.to_immutable();
+        assert_eq!(mining_manager.classify_submitted_block(consensus.as_ref(), &block), BlockSubmissionClassification::Fresh);
+        consensus.add_block(block.hash());
+
+        // Submitting the same solved block again
+        assert_eq!(
+            mining_manager.classify_submitted_block(consensus.as_ref(), &block),
+            BlockSubmissionClassification::AlreadyProcessed
+        );
+
+        // Submitting a block built on the old template, whose parents are no longer the virtual parents
+        assert_eq!(mining_manager.classify_submitted_block(consensus.as_ref(), &old_block), BlockSubmissionClassification::Stale);
+
+        let snapshot = counters.snapshot();
+        assert_eq!(snapshot.duplicate_block_submission_counts, 1);
+        assert_eq!(snapshot.stale_block_submission_counts, 1);
+    }
+
+    // test_check_submitted_transaction_order verifies that blocks whose transactions deviate from the canonical template
+    // order are flagged, and that the check is disabled by default.
+    #[test]
+    fn test_check_submitted_transaction_order() {
+        const TX_COUNT: u64 = 5;
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let config = Config::build_default(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS).apply_check_submitted_transaction_order(true);
+        let mining_manager = MiningManager::with_config(config, None, counters.clone());
+
+        // Insert transactions paying distinct fees
+        for i in 0..TX_COUNT {
+            let funding_tx = create_transaction_without_input(vec![500 * SOMPI_PER_KASPA + i]);
+            consensus.add_transaction(funding_tx.clone(), 1);
+            let tx = create_transaction(&funding_tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE * (i + 1));
+            let result = mining_manager.validate_and_insert_transaction(consensus.as_ref(), tx, Priority::Low, Orphan::Forbidden);
+            assert!(result.is_ok(), "the mempool should accept the transaction but got {result:?}");
+        }
+
+        // The template transactions are in canonical order
+        let template = mining_manager.get_block_template(consensus.as_ref(), &get_miner_data(Prefix::Testnet)).unwrap();
+        assert_eq!(template.block.transactions.len(), TX_COUNT as usize + 1);
+        let block = template.block.clone().to_immutable();
+        assert_eq!(mining_manager.check_submitted_transaction_order(&block), Some(SubmittedTransactionOrder::Canonical));
+
+        // Reordering the non-coinbase transactions breaks the canonical order
+        let mut reordered = template.block.clone();
+        reordered.transactions[1..].reverse();
+        assert_eq!(
+            mining_manager.check_submitted_transaction_order(&reordered.to_immutable()),
+            Some(SubmittedTransactionOrder::NonCanonical)
+        );
+
+        // The order of a block including a transaction unknown to the mempool cannot be verified
+        let mut unknown = template.block.clone();
+        unknown.transactions.push(create_transaction_without_input(vec![SOMPI_PER_KASPA]));
+        assert_eq!(
+            mining_manager.check_submitted_transaction_order(&unknown.to_immutable()),
+            Some(SubmittedTransactionOrder::Unverified)
+        );
+        assert_eq!(counters.snapshot().non_canonical_block_submission_counts, 1);
+
+        // The check is disabled by default
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters);
+        assert_eq!(mining_manager.check_submitted_transaction_order(&block), None);
+    }
+
+    // test_block_template_selection_stats verifies that a block template reports the exact fee and mass totals
+    // of its selected transactions, also when served from the cache.
+    #[test]
+    fn test_block_template_selection_stats() {
+        const FEES: [u64; 5] = [1_000, 2_500, 10_000, 4_200, 3_333];
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters.clone());
+
+        let mut fees = HashMap::new();
+        for (i, fee) in FEES.into_iter().enumerate() {
+            let funding_tx = create_transaction_without_input(vec![500 * SOMPI_PER_KASPA + i as u64]);
+            consensus.add_transaction(funding_tx.clone(), 1);
+            let tx = create_transaction(&funding_tx, fee);
+            fees.insert(tx.id(), fee);
+            let result = mining_manager.validate_and_insert_transaction(consensus.as_ref(), tx, Priority::Low, Orphan::Forbidden);
+            assert!(result.is_ok(), "the mempool should accept the transaction but got {result:?}");
+        }
+
+        let template = mining_manager.get_block_template(consensus.as_ref(), &get_miner_data(Prefix::Testnet)).unwrap();
+        assert_eq!(template.block.transactions.len(), FEES.len() + 1);
+        let selected_txs = &template.block.transactions[1..];
+        let total_mass = selected_txs.iter().map(|tx| tx.mass()).sum::<u64>();
+        let min_feerate = selected_txs.iter().map(|tx| fees[&tx.id()] as f64 / tx.mass() as f64).min_by(f64::total_cmp).unwrap();
+        let stats = template.selection_stats.expect("the selector provides stats");
+        assert_eq!(stats.total_fees, 21_033);
+        assert_eq!(stats.total_mass, total_mass);
+        assert_eq!(stats.max_block_mass, MAX_BLOCK_MASS);
+        assert_eq!(stats.considered_count, FEES.len() as u64);
+        assert_eq!(stats.selected_count, FEES.len() as u64);
+        assert_eq!(stats.min_feerate, Some(min_feerate));
+        assert_eq!(counters.last_template_stats(), Some(stats));
+
+        // A cached template modified for other miner data keeps its stats, without counting as a new template
+        let modified_template = mining_manager.get_block_template(consensus.as_ref(), &get_miner_data(Prefix::Testnet)).unwrap();
+        assert_eq!(modified_template.selection_stats, Some(stats));
+        assert_eq!(counters.snapshot().block_template_counts, 1);
+
+        // An empty mempool selects nothing
+        let mining_manager = MiningManager::new(TARGET_TIME_PER_BLOCK, false, MAX_BLOCK_MASS, None, counters.clone());
+        let template = mining_manager.get_block_template(consensus.as_ref(), &get_miner_data(Prefix::Testnet)).unwrap();
+        let stats = template.selection_stats.unwrap();
+        assert_eq!((stats.total_fees, stats.total_mass, stats.considered_count, stats.selected_count), (0, 0, 0, 0));
+        assert_eq!(stats.min_feerate, None);
+        assert_eq!(counters.snapshot().block_template_counts, 2);
+    }
+
     // test_modify_block_template verifies that modifying a block template changes coinbase data correctly.
     #[test]
     fn test_modify_block_template() {
@@ -853,7 +1696,7 @@ mod tests {
         let expected_template = result.unwrap();
 
         // Modify to miner_data_1
-        let result = BlockTemplateBuilder::modify_block_template(consensus, &miner_data_1, &expected_template);
+        let result = BlockTemplateBuilder::modify_block_template(consensus, &miner_data_1, &expected_template, unix_now());
         assert!(result.is_ok(), "modify block template failed for miner data 1");
         let mut modified_template = result.unwrap();
         // Make sure timestamps are equal before comparing the hash
@@ -872,7 +1715,7 @@ mod tests {
         assert_ne!(expected_block.hash(), modified_block.hash(), "built and modified blocks should have different hashes");
 
         // And modify back to miner_data_2
-        let result = BlockTemplateBuilder::modify_block_template(consensus, &miner_data_2, &modified_template);
+        let result = BlockTemplateBuilder::modify_block_template(consensus, &miner_data_2, &modified_template, unix_now());
         assert!(result.is_ok(), "modify block template failed for miner data 2");
         let mut modified_template_2 = result.unwrap();
         // Make sure timestamps are equal before comparing the hash
@@ -970,6 +1813,12 @@ mod tests {
         Transaction::new(TX_VERSION, vec![], outputs, 0, SUBNETWORK_ID_NATIVE, 0, vec![])
     }
 
+    fn utxo_of(transaction: &Transaction, block_daa_score: u64) -> (TransactionOutpoint, UtxoEntry) {
+        let output = &transaction.outputs[0];
+        let entry = UtxoEntry::new(output.value, output.script_public_key.clone(), block_daa_score, false);
+        (TransactionOutpoint::new(transaction.id(), 0), entry)
+    }
+
     fn contained_by<T: AsRef<Transaction>>(transaction_id: TransactionId, transactions: &[T]) -> bool {
         transactions.iter().any(|x| x.as_ref().id() == transaction_id)
     }
@@ -2,12 +2,13 @@ use crate::mempool::{
     errors::{NonStandardError, NonStandardResult},
     Mempool,
 };
+use kaspa_addresses::{Address, Prefix, Version};
 use kaspa_consensus_core::{
     constants::{MAX_SCRIPT_PUBLIC_KEY_VERSION, MAX_SOMPI},
     mass,
     tx::{MutableTransaction, PopulatedTransaction, TransactionOutput},
 };
-use kaspa_txscript::{get_sig_op_count, is_unspendable, script_class::ScriptClass};
+use kaspa_txscript::{get_sig_op_count, is_unspendable, pay_to_address_script, script_class::ScriptClass};
 
 /// MAX_STANDARD_P2SH_SIG_OPS is the maximum number of signature operations
 /// that are considered standard in a pay-to-script-hash script.
@@ -34,7 +35,7 @@ const MAXIMUM_STANDARD_SIGNATURE_SCRIPT_SIZE: u64 = 1650;
 
 /// MAXIMUM_STANDARD_TRANSACTION_MASS is the maximum mass allowed for transactions that
 /// are considered standard and will therefore be relayed and considered for mining.
-const MAXIMUM_STANDARD_TRANSACTION_MASS: u64 = 100_000;
+pub(crate) const MAXIMUM_STANDARD_TRANSACTION_MASS: u64 = 100_000;
 
 impl Mempool {
     pub(crate) fn check_transaction_standard_in_isolation(&self, transaction: &MutableTransaction) -> NonStandardResult<()> {
@@ -96,7 +97,7 @@ impl Mempool {
             }
 
             if self.is_transaction_output_dust(output) {
-                return Err(NonStandardError::RejectDust(transaction_id, i, output.value));
+                return Err(NonStandardError::RejectDust(transaction_id, i, output.value, self.dust_threshold(output)));
             }
         }
 
@@ -104,18 +105,25 @@ impl Mempool {
     }
 
     /// is_transaction_output_dust returns whether or not the passed transaction output
-    /// amount is considered dust or not based on the configured minimum transaction
-    /// relay fee.
-    ///
-    /// Dust is defined in terms of the minimum transaction relay fee. In particular,
-    /// if the cost to the network to spend coins is more than 1/3 of the minimum
-    /// transaction relay fee, it is considered dust.
+    /// amount is considered dust or not, see [`Self::dust_threshold`].
     ///
     /// It is exposed by [MiningManager] for use by transaction generators and wallets.
     pub(crate) fn is_transaction_output_dust(&self, transaction_output: &TransactionOutput) -> bool {
         // Unspendable outputs are considered dust.
-        if is_unspendable::<PopulatedTransaction>(transaction_output.script_public_key.script()) {
-            return true;
+        is_unspendable::<PopulatedTransaction>(transaction_output.script_public_key.script())
+            || transaction_output.value < self.dust_threshold(transaction_output)
+    }
+
+    /// dust_threshold returns the minimum value of a spendable (standard) transaction output.
+    ///
+    /// The node configuration may set the threshold of the output script class, see
+    /// [`crate::mempool::config::DustRelayThresholds`]. Otherwise, dust is defined in terms
+    /// of the minimum transaction relay fee. In particular, if the cost to the network to
+    /// spend coins is more than 1/3 of the minimum transaction relay fee, it is considered dust.
+    pub(crate) fn dust_threshold(&self, transaction_output: &TransactionOutput) -> u64 {
+        let script_class = ScriptClass::from_script(&transaction_output.script_public_key);
+        if let Some(threshold) = self.config.dust_relay_thresholds.get(script_class) {
+            return threshold;
         }
 
         // The total serialized size consists of the output and the associated
@@ -147,18 +155,23 @@ impl Mempool {
         // fee of 1000, this equates to values less than 546 sompi being
         // considered dust.
         //
-        // The following is equivalent to (value/total_serialized_size) * (1/3) * 1000
-        // without needing to do floating point math.
-        //
-        // Since the multiplication may overflow a u64, 2 separate calculation paths
-        // are considered to avoid overflowing.
-        match transaction_output.value.checked_mul(1000) {
-            Some(value_1000) => value_1000 / (3 * total_serialized_size) < self.config.minimum_relay_transaction_fee,
-            None => {
-                (transaction_output.value as u128 * 1000 / (3 * total_serialized_size as u128))
-                    < self.config.minimum_relay_transaction_fee as u128
-            }
-        }
+        // The following is equivalent to (value/total_serialized_size) * (1/3) * 1000 < fee,
+        // solved for the lowest value which is not dust, without needing to do floating
+        // point math. u128 arithmetic avoids overflowing.
+        let threshold = (3 * total_serialized_size as u128 * self.config.minimum_relay_transaction_fee as u128).div_ceil(1000);
+        threshold.try_into().unwrap_or(u64::MAX)
+    }
+
+    /// Returns the dust threshold applied to outputs of each standard script class,
+    /// as advertised to wallets so they avoid creating outputs which aren't relayed
+    pub(crate) fn standard_dust_thresholds(&self) -> Vec<(ScriptClass, u64)> {
+        [Version::PubKey, Version::PubKeyECDSA, Version::ScriptHash]
+            .into_iter()
+            .map(|version| {
+                let address = Address::new(Prefix::Mainnet, version, &vec![0u8; version.public_key_len()]);
+                (ScriptClass::from(version), self.dust_threshold(&TransactionOutput::new(0, pay_to_address_script(&address))))
+            })
+            .collect()
     }
 
     /// check_transaction_standard_in_context performs a series of checks on a transaction's
@@ -208,7 +221,7 @@ impl Mempool {
 
     /// minimum_required_transaction_relay_fee returns the minimum transaction fee required
     /// for a transaction with the passed mass to be accepted into the mempool and relayed.
-    fn minimum_required_transaction_relay_fee(&self, mass: u64) -> u64 {
+    pub(crate) fn minimum_required_transaction_relay_fee(&self, mass: u64) -> u64 {
         // Calculate the minimum fee for a transaction to be allowed into the
         // mempool and relayed by scaling the base fee. MinimumRelayTransactionFee is in
         // sompi/kg so multiply by mass (which is in grams) and divide by 1000 to get
@@ -231,10 +244,9 @@ impl Mempool {
 mod tests {
     use super::*;
     use crate::{
-        mempool::config::{Config, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE},
+        mempool::config::{Config, DustRelayThresholds, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE},
         MiningCounters,
     };
-    use kaspa_addresses::{Address, Prefix, Version};
     use kaspa_consensus_core::{
         config::params::Params,
         constants::{MAX_TX_IN_SEQUENCE_NUM, SOMPI_PER_KASPA, TX_VERSION},
@@ -389,6 +401,61 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_dust_relay_thresholds() {
+        let pay_to_pubkey = kaspa_txscript::pay_to_address_script(&Address::new(Prefix::Testnet, Version::PubKey, &[1u8; 32]));
+        let pay_to_script_hash =
+            kaspa_txscript::pay_to_address_script(&Address::new(Prefix::Testnet, Version::ScriptHash, &[1u8; 32]));
+        let unspendable = ScriptPublicKey::new(0, ScriptBuilder::new().add_op(OpReturn).unwrap().script().into());
+        let params: Params = NetworkType::Mainnet.into();
+        let new_mempool = |dust_relay_thresholds: DustRelayThresholds| {
+            let config = Config::build_default(params.target_time_per_block, false, params.max_block_mass)
+                .apply_dust_relay_thresholds(dust_relay_thresholds);
+            Mempool::new(Arc::new(config), Arc::new(MiningCounters::default()))
+        };
+
+        // By default, the threshold is derived from the minimum relay fee
+        let mempool = new_mempool(DustRelayThresholds::default());
+        let fee_based_threshold = mempool.dust_threshold(&TransactionOutput::new(0, pay_to_pubkey.clone()));
+        // 52 bytes of a pay-to-pubkey output (value, script version and length, 34 script bytes) and 148 bytes of a typical input
+        assert_eq!(fee_based_threshold, 3 * (52 + 148) * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE / 1000);
+        assert!(mempool.is_transaction_output_dust(&TransactionOutput::new(fee_based_threshold - 1, pay_to_pubkey.clone())));
+        assert!(!mempool.is_transaction_output_dust(&TransactionOutput::new(fee_based_threshold, pay_to_pubkey.clone())));
+
+        // The threshold of the script class takes precedence over the default one
+        let mempool = new_mempool(DustRelayThresholds::new(Some(10_000), vec![(ScriptClass::ScriptHash, 0)]));
+        assert_eq!(mempool.dust_threshold(&TransactionOutput::new(0, pay_to_pubkey.clone())), 10_000);
+        assert!(mempool.is_transaction_output_dust(&TransactionOutput::new(fee_based_threshold, pay_to_pubkey.clone())));
+        assert!(!mempool.is_transaction_output_dust(&TransactionOutput::new(10_000, pay_to_pubkey.clone())));
+        assert_eq!(mempool.dust_threshold(&TransactionOutput::new(0, pay_to_script_hash.clone())), 0);
+        assert!(!mempool.is_transaction_output_dust(&TransactionOutput::new(0, pay_to_script_hash.clone())));
+        assert_eq!(
+            mempool.standard_dust_thresholds(),
+            vec![(ScriptClass::PubKey, 10_000), (ScriptClass::PubKeyECDSA, 10_000), (ScriptClass::ScriptHash, 0)]
+        );
+
+        // Classes without a threshold fall back to the fee-based rule
+        let mempool = new_mempool(DustRelayThresholds::new(None, vec![(ScriptClass::ScriptHash, 1)]));
+        assert_eq!(mempool.dust_threshold(&TransactionOutput::new(0, pay_to_pubkey.clone())), fee_based_threshold);
+        assert!(!mempool.is_transaction_output_dust(&TransactionOutput::new(1, pay_to_script_hash)));
+
+        // Unspendable outputs are dust regardless of the thresholds
+        let mempool = new_mempool(DustRelayThresholds::new(Some(0), vec![]));
+        assert!(mempool.is_transaction_output_dust(&TransactionOutput::new(SOMPI_PER_KASPA, unspendable)));
+
+        // The rejection reports the applied threshold
+        let mempool = new_mempool(DustRelayThresholds::new(Some(10_000), vec![]));
+        let input = TransactionInput::new(TransactionOutpoint::new(1.into(), 0), vec![0u8; 65], MAX_TX_IN_SEQUENCE_NUM, 1);
+        let output = TransactionOutput::new(9_999, pay_to_pubkey);
+        let mut mtx =
+            MutableTransaction::from_tx(Transaction::new(TX_VERSION, vec![input], vec![output], 0, SUBNETWORK_ID_NATIVE, 0, vec![]));
+        mtx.calculated_compute_mass = Some(1000);
+        assert!(matches!(
+            mempool.check_transaction_standard_in_isolation(&mtx),
+            Err(NonStandardError::RejectDust(_, 0, 9_999, 10_000))
+        ));
+    }
+
     #[test]
     fn test_check_transaction_standard_in_isolation() {
         // Create some dummy, but otherwise standard, data for transactions.
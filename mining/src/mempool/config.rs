@@ -1,4 +1,6 @@
-use kaspa_consensus_core::constants::TX_VERSION;
+use kaspa_consensus_core::{config::constants::consensus::DEFAULT_MAX_BLOCK_TRANSACTIONS, constants::TX_VERSION};
+use kaspa_core::time::{DynClock, SystemClock};
+use kaspa_txscript::script_class::ScriptClass;
 
 pub(crate) const DEFAULT_MAXIMUM_TRANSACTION_COUNT: u64 = 1_000_000;
 pub(crate) const DEFAULT_MAXIMUM_READY_TRANSACTION_COUNT: u64 = 50_000;
@@ -16,6 +18,13 @@ pub(crate) const DEFAULT_MAXIMUM_ORPHAN_TRANSACTION_MASS: u64 = 100_000;
 // TODO: when rusty-kaspa nodes run most of the network, consider increasing this value
 pub(crate) const DEFAULT_MAXIMUM_ORPHAN_TRANSACTION_COUNT: u64 = 50;
 
+/// Maximum number of not yet valid (time-locked) transactions held by the mempool when holding is enabled
+pub(crate) const DEFAULT_MAXIMUM_HELD_TRANSACTION_COUNT: u64 = 1_000;
+
+/// Maximum number of transactions recently rejected for double spending a mempool transaction which are kept for
+/// detecting their conflicts with block transactions
+pub(crate) const DEFAULT_MAXIMUM_CONFLICT_CACHE_COUNT: u64 = 1_000;
+
 /// DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE specifies the minimum transaction fee for a transaction to be accepted to
 /// the mempool and relayed. It is specified in sompi per 1kg (or 1000 grams) of transaction mass.
 pub(crate) const DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE: u64 = 1000;
@@ -27,6 +36,48 @@ pub(crate) const DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE: u64 = 1000;
 pub(crate) const DEFAULT_MINIMUM_STANDARD_TRANSACTION_VERSION: u16 = TX_VERSION;
 pub(crate) const DEFAULT_MAXIMUM_STANDARD_TRANSACTION_VERSION: u16 = TX_VERSION;
 
+/// Node-level overrides of the minimum value (in sompi) of a standard transaction output.
+///
+/// By default, an output is dust if spending it costs more than a third of the minimum relay
+/// fee (see `Mempool::is_transaction_output_dust`). A threshold set here replaces this rule,
+/// the threshold of the output script class taking precedence over the `default` one.
+/// Unspendable outputs are always dust.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DustRelayThresholds {
+    pub default: Option<u64>,
+    pub by_class: Vec<(ScriptClass, u64)>,
+}
+
+impl DustRelayThresholds {
+    pub const fn new(default: Option<u64>, by_class: Vec<(ScriptClass, u64)>) -> Self {
+        Self { default, by_class }
+    }
+
+    /// Returns the threshold overriding the fee-based dust rule for outputs of `script_class`, if any
+    pub fn get(&self, script_class: ScriptClass) -> Option<u64> {
+        self.by_class.iter().find(|(class, _)| *class == script_class).map(|(_, threshold)| *threshold).or(self.default)
+    }
+}
+
+/// Block template policy capping the template mass attributable to a single origin, i.e. to the script
+/// public key spent by the first input of the transactions, so that a single spender cannot fill templates.
+///
+/// The template mass left over by a capped origin is filled with the next-best transactions of other origins.
+/// The cap is lifted whenever it would reduce the template fees by more than `max_fee_loss_ratio`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TemplateMassQuota {
+    /// Maximum fraction, in `(0, 1]`, of the template mass of a single origin
+    pub max_origin_mass_ratio: f64,
+    /// Maximum fraction, in `[0, 1]`, of the template fees which may be given up to honor the quota
+    pub max_fee_loss_ratio: f64,
+}
+
+impl TemplateMassQuota {
+    pub const fn new(max_origin_mass_ratio: f64, max_fee_loss_ratio: f64) -> Self {
+        Self { max_origin_mass_ratio, max_fee_loss_ratio }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Config {
     pub maximum_transaction_count: u64,
@@ -42,11 +93,25 @@ pub struct Config {
     pub orphan_expire_scan_interval_daa_score: u64,
     pub maximum_orphan_transaction_mass: u64,
     pub maximum_orphan_transaction_count: u64,
+    /// Maximum number of transactions held until their lock time is reached; 0 disables holding
+    pub maximum_held_transaction_count: u64,
+    /// Maximum number of recently rejected double spending transactions kept for conflict detection
+    pub maximum_conflict_cache_count: u64,
     pub accept_non_standard: bool,
     pub maximum_mass_per_block: u64,
+    /// Maximum number of transactions of a block template, coinbase included
+    pub maximum_transactions_per_block: usize,
+    pub target_milliseconds_per_block: u64,
     pub minimum_relay_transaction_fee: u64,
     pub minimum_standard_transaction_version: u16,
     pub maximum_standard_transaction_version: u16,
+    pub dust_relay_thresholds: DustRelayThresholds,
+    /// Caps the block template mass by transaction origin when set, see [`TemplateMassQuota`]
+    pub template_mass_quota: Option<TemplateMassQuota>,
+    /// Checks that submitted blocks keep the canonical transaction order of the block templates
+    pub check_submitted_transaction_order: bool,
+    /// Clock driving the expiration scans and the block template timestamps, the system clock unless simulated by tests
+    pub clock: DynClock,
 }
 
 impl Config {
@@ -65,8 +130,11 @@ impl Config {
         orphan_expire_scan_interval_daa_score: u64,
         maximum_orphan_transaction_mass: u64,
         maximum_orphan_transaction_count: u64,
+        maximum_held_transaction_count: u64,
         accept_non_standard: bool,
         maximum_mass_per_block: u64,
+        maximum_transactions_per_block: usize,
+        target_milliseconds_per_block: u64,
         minimum_relay_transaction_fee: u64,
         minimum_standard_transaction_version: u16,
         maximum_standard_transaction_version: u16,
@@ -85,17 +153,25 @@ impl Config {
             orphan_expire_scan_interval_daa_score,
             maximum_orphan_transaction_mass,
             maximum_orphan_transaction_count,
+            maximum_held_transaction_count,
+            maximum_conflict_cache_count: DEFAULT_MAXIMUM_CONFLICT_CACHE_COUNT,
             accept_non_standard,
             maximum_mass_per_block,
+            maximum_transactions_per_block,
+            target_milliseconds_per_block,
             minimum_relay_transaction_fee,
             minimum_standard_transaction_version,
             maximum_standard_transaction_version,
+            dust_relay_thresholds: DustRelayThresholds::new(None, Vec::new()),
+            template_mass_quota: None,
+            check_submitted_transaction_order: false,
+            clock: SystemClock::new_dyn(),
         }
     }
 
     /// Build a default config.
     /// The arguments should be obtained from the current consensus [`kaspa_consensus_core::config::params::Params`] instance.
-    pub const fn build_default(target_milliseconds_per_block: u64, relay_non_std_transactions: bool, max_block_mass: u64) -> Self {
+    pub fn build_default(target_milliseconds_per_block: u64, relay_non_std_transactions: bool, max_block_mass: u64) -> Self {
         Self {
             maximum_transaction_count: DEFAULT_MAXIMUM_TRANSACTION_COUNT,
             maximum_ready_transaction_count: DEFAULT_MAXIMUM_READY_TRANSACTION_COUNT,
@@ -113,11 +189,19 @@ impl Config {
             orphan_expire_scan_interval_daa_score: DEFAULT_ORPHAN_EXPIRE_SCAN_INTERVAL_SECONDS * 1000 / target_milliseconds_per_block,
             maximum_orphan_transaction_mass: DEFAULT_MAXIMUM_ORPHAN_TRANSACTION_MASS,
             maximum_orphan_transaction_count: DEFAULT_MAXIMUM_ORPHAN_TRANSACTION_COUNT,
+            maximum_held_transaction_count: 0,
+            maximum_conflict_cache_count: DEFAULT_MAXIMUM_CONFLICT_CACHE_COUNT,
             accept_non_standard: relay_non_std_transactions,
             maximum_mass_per_block: max_block_mass,
+            maximum_transactions_per_block: DEFAULT_MAX_BLOCK_TRANSACTIONS,
+            target_milliseconds_per_block,
             minimum_relay_transaction_fee: DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE,
             minimum_standard_transaction_version: DEFAULT_MINIMUM_STANDARD_TRANSACTION_VERSION,
             maximum_standard_transaction_version: DEFAULT_MAXIMUM_STANDARD_TRANSACTION_VERSION,
+            dust_relay_thresholds: DustRelayThresholds::new(None, Vec::new()),
+            template_mass_quota: None,
+            check_submitted_transaction_order: false,
+            clock: SystemClock::new_dyn(),
         }
     }
 
@@ -125,4 +209,41 @@ impl Config {
         self.maximum_transaction_count = (self.maximum_transaction_count as f64 * ram_scale.min(1.0)) as u64; // Allow only scaling down
         self
     }
+
+    /// Limits block templates to `maximum_transactions_per_block` transactions, which should be obtained from the
+    /// current consensus [`kaspa_consensus_core::config::params::Params`] when it overrides the default limit
+    pub fn apply_max_block_transactions(mut self, maximum_transactions_per_block: usize) -> Self {
+        self.maximum_transactions_per_block = maximum_transactions_per_block;
+        self
+    }
+
+    /// Enables holding of not yet valid (time-locked) transactions, bounded to `maximum_held_transaction_count` entries
+    pub fn apply_held_transaction_limit(mut self, maximum_held_transaction_count: u64) -> Self {
+        self.maximum_held_transaction_count = maximum_held_transaction_count;
+        self
+    }
+
+    /// Overrides the fee-based dust rule of standard transaction outputs, see [`DustRelayThresholds`]
+    pub fn apply_dust_relay_thresholds(mut self, dust_relay_thresholds: DustRelayThresholds) -> Self {
+        self.dust_relay_thresholds = dust_relay_thresholds;
+        self
+    }
+
+    /// Enables the [`TemplateMassQuota`] block template policy
+    pub fn apply_template_mass_quota(mut self, template_mass_quota: Option<TemplateMassQuota>) -> Self {
+        self.template_mass_quota = template_mass_quota;
+        self
+    }
+
+    /// Enables the check of the transaction order of submitted blocks against the canonical block template order
+    pub fn apply_check_submitted_transaction_order(mut self, check_submitted_transaction_order: bool) -> Self {
+        self.check_submitted_transaction_order = check_submitted_transaction_order;
+        self
+    }
+
+    /// Replaces the system clock, typically by a simulated clock in tests
+    pub fn apply_clock(mut self, clock: DynClock) -> Self {
+        self.clock = clock;
+        self
+    }
 }
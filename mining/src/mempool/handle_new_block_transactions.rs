@@ -1,26 +1,36 @@
-use crate::mempool::{
-    errors::RuleResult,
-    model::{
-        pool::Pool,
-        tx::{MempoolTransaction, TxRemovalReason},
+use crate::{
+    mempool::{
+        errors::RuleResult,
+        model::{
+            pool::Pool,
+            tx::{MempoolTransaction, TxRemovalReason},
+        },
+        Mempool,
     },
-    Mempool,
+    model::tx_conflict::{TransactionConflict, TransactionConflictSource},
 };
 use kaspa_consensus_core::{
+    acceptance_data::AcceptanceData,
     api::ConsensusApi,
     tx::{Transaction, TransactionId},
+    utxo::utxo_diff::UtxoDiff,
 };
 use kaspa_core::time::Stopwatch;
-use std::{collections::HashSet, sync::atomic::Ordering};
+use kaspa_hashes::Hash;
+use std::{
+    collections::HashSet,
+    sync::{atomic::Ordering, Arc},
+};
 
 impl Mempool {
     pub(crate) fn handle_new_block_transactions(
         &mut self,
         block_daa_score: u64,
         block_transactions: &[Transaction],
-    ) -> RuleResult<Vec<MempoolTransaction>> {
+    ) -> RuleResult<(Vec<MempoolTransaction>, Vec<TransactionConflict>)> {
         let _sw = Stopwatch::<400>::with_threshold("handle_new_block_transactions op");
         let mut unorphaned_transactions = vec![];
+        let mut conflicts = vec![];
         let mut tx_accepted_counts = 0;
         let mut input_counts = 0;
         let mut output_counts = 0;
@@ -33,7 +43,8 @@ impl Mempool {
             if !self.orphan_pool.has(&transaction_id) {
                 self.remove_transaction(&transaction_id, false, TxRemovalReason::Accepted, "")?;
             }
-            self.remove_double_spends(transaction)?;
+            self.held_pool.remove_held(&transaction_id, TxRemovalReason::Accepted);
+            self.remove_double_spends(transaction, &mut conflicts)?;
             self.orphan_pool.remove_orphan(&transaction_id, false, TxRemovalReason::Accepted, "")?;
             if self.accepted_transactions.add(transaction_id, block_daa_score) {
                 tx_accepted_counts += 1;
@@ -42,6 +53,7 @@ impl Mempool {
             }
             unorphaned_transactions.extend(self.get_unorphaned_transactions_after_accepted_transaction(transaction));
         }
+        self.block_utilization.record(self.config.clock.unix_now(), block_transactions.iter().map(|x| x.mass()).sum());
         self.counters.block_tx_counts.fetch_add(block_transactions.len() as u64 - 1, Ordering::Relaxed);
         self.counters.tx_accepted_counts.fetch_add(tx_accepted_counts, Ordering::Relaxed);
         self.counters.input_counts.fetch_add(input_counts as u64, Ordering::Relaxed);
@@ -51,7 +63,7 @@ impl Mempool {
         self.counters.orphans_sample.store(self.orphan_pool.len() as u64, Ordering::Relaxed);
         self.counters.accepted_sample.store(self.accepted_transactions.len() as u64, Ordering::Relaxed);
 
-        Ok(unorphaned_transactions)
+        Ok((unorphaned_transactions, conflicts))
     }
 
     pub(crate) fn expire_orphan_low_priority_transactions(&mut self, consensus: &dyn ConsensusApi) -> RuleResult<()> {
@@ -62,17 +74,84 @@ impl Mempool {
         self.accepted_transactions.expire(consensus.get_virtual_daa_score());
     }
 
+    /// Registers the accepting blocks of the transactions accepted by the selected chain blocks added by a virtual chain change,
+    /// forgetting the accepting blocks removed from the chain
+    pub(crate) fn handle_virtual_chain_changed(
+        &mut self,
+        virtual_daa_score: u64,
+        added_chain_block_hashes: &[Hash],
+        removed_chain_block_hashes: &[Hash],
+        added_chain_blocks_acceptance_data: &[Arc<AcceptanceData>],
+    ) {
+        if !removed_chain_block_hashes.is_empty() {
+            self.accepted_transactions.unset_accepting_blocks(&removed_chain_block_hashes.iter().copied().collect());
+        }
+        for (accepting_block_hash, acceptance_data) in added_chain_block_hashes.iter().zip(added_chain_blocks_acceptance_data.iter()) {
+            for entry in acceptance_data.iter().flat_map(|mergeset_block| mergeset_block.accepted_transactions.iter()) {
+                self.accepted_transactions.set_accepting_block(entry.transaction_id, *accepting_block_hash, virtual_daa_score);
+            }
+        }
+        self.counters.accepted_sample.store(self.accepted_transactions.len() as u64, Ordering::Relaxed);
+    }
+
     pub(crate) fn collect_expired_low_priority_transactions(&mut self, consensus: &dyn ConsensusApi) -> Vec<TransactionId> {
         self.transaction_pool.collect_expired_low_priority_transactions(consensus.get_virtual_daa_score())
     }
 
-    fn remove_double_spends(&mut self, transaction: &Transaction) -> RuleResult<()> {
+    /// Returns the ids of the transaction pool transactions affected by a change of the virtual UTXO set, along with
+    /// the ids of their redeemers.
+    ///
+    /// A transaction is affected if it spends an outpoint added to or removed from the UTXO set by `utxo_diff`,
+    /// unless the transaction itself is accepted by the virtual, i.e. its outputs belong to the added outpoints.
+    pub(crate) fn transaction_ids_affected_by_utxo_diff(&self, utxo_diff: &UtxoDiff) -> Vec<TransactionId> {
+        let accepted_ids: HashSet<TransactionId> = utxo_diff.add.keys().map(|outpoint| outpoint.transaction_id).collect();
+        let mut affected_ids = HashSet::new();
+        for outpoint in utxo_diff.add.keys().chain(utxo_diff.remove.keys()) {
+            if let Some(transaction_id) = self.transaction_pool.get_outpoint_owner_id(outpoint) {
+                if !accepted_ids.contains(transaction_id) && affected_ids.insert(*transaction_id) {
+                    affected_ids.extend(self.transaction_pool.get_redeemer_ids_in_pool(transaction_id));
+                }
+            }
+        }
+        affected_ids.into_iter().collect()
+    }
+
+    /// Removes the mempool transactions double spending the block `transaction`, collecting into `conflicts` the
+    /// conflicts of the block transaction with these transactions and with the recently rejected ones.
+    fn remove_double_spends(&mut self, transaction: &Transaction, conflicts: &mut Vec<TransactionConflict>) -> RuleResult<()> {
         let mut transactions_to_remove = HashSet::new();
         for input in transaction.inputs.iter() {
             if let Some(redeemer_id) = self.transaction_pool.get_outpoint_owner_id(&input.previous_outpoint) {
                 transactions_to_remove.insert(*redeemer_id);
             }
         }
+        for double_spend in transactions_to_remove.iter().filter_map(|x| self.transaction_pool.get(x)) {
+            let spent_script_public_keys = double_spend.mtx.entries.iter().flatten().map(|entry| &entry.script_public_key);
+            conflicts.push(TransactionConflict::new(
+                &double_spend.mtx.tx,
+                transaction,
+                spent_script_public_keys,
+                TransactionConflictSource::Block,
+            ));
+        }
+        for rejected_id in self.conflict_cache.get_conflicting_ids(transaction) {
+            if let Some(rejected) = self.conflict_cache.remove(&rejected_id) {
+                conflicts.push(TransactionConflict::new(
+                    &rejected.transaction,
+                    transaction,
+                    rejected.spent_script_public_keys.iter(),
+                    TransactionConflictSource::Block,
+                ));
+            }
+        }
+        // A rejected transaction making it into a block can no longer lose a conflict
+        self.conflict_cache.remove(&transaction.id());
+        // Held transactions have no redeemers so they are simply dropped
+        for input in transaction.inputs.iter() {
+            if let Some(held_id) = self.held_pool.outpoint_owner_id(&input.previous_outpoint).copied() {
+                self.held_pool.remove_held(&held_id, TxRemovalReason::DoubleSpend);
+            }
+        }
         transactions_to_remove.iter().try_for_each(|x| {
             self.remove_transaction(x, true, TxRemovalReason::DoubleSpend, format!(" favouring {}", transaction.id()).as_str())
         })
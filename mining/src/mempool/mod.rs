@@ -1,6 +1,10 @@
 use crate::{
+    estimator::{
+        estimate_confirmation_time, BlockUtilizationHistory, ConfirmationTimeEstimate, DEFAULT_BLOCK_UTILIZATION_HISTORY_SIZE,
+    },
     model::{
         candidate_tx::CandidateTransaction,
+        mempool_entries::MempoolSummary,
         owner_txs::{GroupedOwnerTransactions, ScriptPublicKeySet},
         tx_query::TransactionQuery,
     },
@@ -9,11 +13,15 @@ use crate::{
 
 use self::{
     config::Config,
-    model::{accepted_transactions::AcceptedTransactions, orphan_pool::OrphanPool, pool::Pool, transactions_pool::TransactionsPool},
+    model::{
+        accepted_transactions::AcceptedTransactions, conflict_cache::ConflictCache, held_pool::HeldPool, orphan_pool::OrphanPool,
+        pool::Pool, transactions_pool::TransactionsPool,
+    },
     tx::Priority,
 };
-use kaspa_consensus_core::tx::{MutableTransaction, TransactionId};
-use kaspa_core::time::Stopwatch;
+use kaspa_consensus_core::tx::{MutableTransaction, TransactionId, TransactionOutpoint};
+use kaspa_core::time::{unix_now, Stopwatch};
+use kaspa_hashes::Hash;
 use std::sync::Arc;
 
 pub(crate) mod check_transaction_standard;
@@ -23,6 +31,7 @@ pub(crate) mod handle_new_block_transactions;
 pub(crate) mod model;
 pub(crate) mod populate_entries_and_try_validate;
 pub(crate) mod remove_transaction;
+pub(crate) mod trace_transaction_acceptance;
 pub(crate) mod validate_and_insert_transaction;
 
 /// Mempool contains transactions intended to be inserted into a block and mined.
@@ -41,11 +50,19 @@ pub(crate) mod validate_and_insert_transaction;
 ///   rebroadcasts them once in a while.
 /// - Transactions received through P2P have **low-priority**. They expire after
 ///   60 seconds and are removed if not inserted in a block for mining.
+/// - When enabled, RPC transactions whose lock time is not reached yet can be held
+///   apart from the transaction pool until they become valid. Held transactions are
+///   neither relayed nor considered for block templates until they are promoted.
 pub(crate) struct Mempool {
     config: Arc<Config>,
     transaction_pool: TransactionsPool,
     orphan_pool: OrphanPool,
+    held_pool: HeldPool,
     accepted_transactions: AcceptedTransactions,
+    /// Transactions recently rejected for double spending a mempool transaction
+    conflict_cache: ConflictCache,
+    /// Mass of the recently handled blocks
+    block_utilization: BlockUtilizationHistory,
     counters: Arc<MiningCounters>,
 }
 
@@ -53,8 +70,11 @@ impl Mempool {
     pub(crate) fn new(config: Arc<Config>, counters: Arc<MiningCounters>) -> Self {
         let transaction_pool = TransactionsPool::new(config.clone());
         let orphan_pool = OrphanPool::new(config.clone());
+        let held_pool = HeldPool::new(config.clone());
         let accepted_transactions = AcceptedTransactions::new(config.clone());
-        Self { config, transaction_pool, orphan_pool, accepted_transactions, counters }
+        let conflict_cache = ConflictCache::new(config.clone());
+        let block_utilization = BlockUtilizationHistory::new(DEFAULT_BLOCK_UTILIZATION_HISTORY_SIZE, config.maximum_mass_per_block);
+        Self { config, transaction_pool, orphan_pool, held_pool, accepted_transactions, conflict_cache, block_utilization, counters }
     }
 
     pub(crate) fn get_transaction(&self, transaction_id: &TransactionId, query: TransactionQuery) -> Option<MutableTransaction> {
@@ -111,6 +131,56 @@ impl Mempool {
         count
     }
 
+    /// Returns the totals of the pools selected by `query`, the held transactions being accounted along the transaction pool
+    pub(crate) fn summary(&self, query: TransactionQuery) -> MempoolSummary {
+        let mut summary = MempoolSummary::new(unix_now());
+        if query.include_transaction_pool() {
+            self.transaction_pool.all().values().chain(self.held_pool.all().values()).for_each(|x| summary.add(&x.mtx));
+        }
+        if query.include_orphan_pool() {
+            self.orphan_pool.all().values().for_each(|x| summary.add(&x.mtx));
+        }
+        summary
+    }
+
+    pub(crate) fn get_held_transaction(&self, transaction_id: &TransactionId) -> Option<MutableTransaction> {
+        self.held_pool.get(transaction_id).map(|x| x.mtx.clone())
+    }
+
+    pub(crate) fn get_all_held_transactions(&self) -> Vec<MutableTransaction> {
+        self.held_pool.get_all_transactions()
+    }
+
+    pub(crate) fn held_transaction_count(&self) -> usize {
+        self.held_pool.len()
+    }
+
+    /// Returns the id of the transaction spending `outpoint`, looking in the transaction pool first,
+    /// then in the orphan pool and finally among the held transactions.
+    pub(crate) fn get_outpoint_spender(&self, outpoint: &TransactionOutpoint) -> Option<TransactionId> {
+        self.transaction_pool
+            .get_outpoint_owner_id(outpoint)
+            .copied()
+            .or_else(|| self.orphan_pool.outpoint_orphan(outpoint).map(|orphan| orphan.id()))
+            .or_else(|| self.held_pool.outpoint_owner_id(outpoint).copied())
+    }
+
+    /// Estimates the confirmation time of a transaction of the transaction pool. Orphan and held
+    /// transactions are not eligible for mining so no estimate is returned for them.
+    pub(crate) fn estimate_transaction_confirmation_time(&self, transaction_id: &TransactionId) -> Option<ConfirmationTimeEstimate> {
+        let _sw = Stopwatch::<50>::with_threshold("estimate_transaction_confirmation_time op");
+        if !self.transaction_pool.has(transaction_id) {
+            return None;
+        }
+        let position = self.transaction_pool.snapshot().position(transaction_id)?;
+        Some(estimate_confirmation_time(
+            &position,
+            self.block_utilization.utilization(),
+            self.block_utilization.block_interval(self.config.target_milliseconds_per_block as f64),
+            self.config.maximum_mass_per_block,
+        ))
+    }
+
     pub(crate) fn block_candidate_transactions(&self) -> Vec<CandidateTransaction> {
         let _sw = Stopwatch::<10>::with_threshold("block_candidate_transactions op");
         self.transaction_pool.all_ready_transactions()
@@ -134,14 +204,18 @@ impl Mempool {
         self.accepted_transactions.has(transaction_id)
     }
 
+    pub(crate) fn accepting_block(&self, transaction_id: &TransactionId) -> Option<Hash> {
+        self.accepted_transactions.accepting_block(transaction_id)
+    }
+
     pub(crate) fn unaccepted_transactions(&self, transactions: Vec<TransactionId>) -> Vec<TransactionId> {
         self.accepted_transactions.unaccepted(&mut transactions.into_iter())
     }
 
     pub(crate) fn unknown_transactions(&self, transactions: Vec<TransactionId>) -> Vec<TransactionId> {
-        let mut not_in_pools_txs = transactions
-            .into_iter()
-            .filter(|transaction_id| !(self.transaction_pool.has(transaction_id) || self.orphan_pool.has(transaction_id)));
+        let mut not_in_pools_txs = transactions.into_iter().filter(|transaction_id| {
+            !(self.transaction_pool.has(transaction_id) || self.orphan_pool.has(transaction_id) || self.held_pool.has(transaction_id))
+        });
         self.accepted_transactions.unaccepted(&mut not_in_pools_txs)
     }
 }
@@ -158,4 +232,12 @@ pub mod tx {
         Forbidden,
         Allowed,
     }
+
+    /// Indicates whether a transaction whose lock time is not reached yet may be held
+    /// by the mempool until it becomes valid
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Hold {
+        Forbidden,
+        Allowed,
+    }
 }
@@ -1,14 +1,24 @@
 use crate::mempool::config::Config;
 use kaspa_consensus_core::tx::TransactionId;
-use kaspa_core::{debug, time::unix_now};
-use std::{collections::HashMap, sync::Arc};
+use kaspa_core::debug;
+use kaspa_hashes::Hash;
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    sync::Arc,
+};
+
+struct AcceptedTransaction {
+    daa_score: u64,
+    /// The selected chain block accepting the transaction, if known
+    accepting_block_hash: Option<Hash>,
+}
 
 pub(crate) struct AcceptedTransactions {
     /// Mempool config
     config: Arc<Config>,
 
-    /// A map of Transaction IDs to DAA scores
-    transactions: HashMap<TransactionId, u64>,
+    /// A map of Transaction IDs to DAA scores and accepting blocks
+    transactions: HashMap<TransactionId, AcceptedTransaction>,
 
     /// Last expire scan DAA score
     last_expire_scan_daa_score: u64,
@@ -18,11 +28,42 @@ pub(crate) struct AcceptedTransactions {
 
 impl AcceptedTransactions {
     pub(crate) fn new(config: Arc<Config>) -> Self {
-        Self { config, transactions: Default::default(), last_expire_scan_daa_score: 0, last_expire_scan_time: unix_now() }
+        let last_expire_scan_time = config.clock.unix_now();
+        Self { config, transactions: Default::default(), last_expire_scan_daa_score: 0, last_expire_scan_time }
     }
 
     pub(crate) fn add(&mut self, transaction_id: TransactionId, daa_score: u64) -> bool {
-        self.transactions.insert(transaction_id, daa_score).is_none()
+        match self.transactions.entry(transaction_id) {
+            Entry::Occupied(mut entry) => {
+                entry.get_mut().daa_score = daa_score;
+                false
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(AcceptedTransaction { daa_score, accepting_block_hash: None });
+                true
+            }
+        }
+    }
+
+    /// Registers the selected chain block accepting the transaction, adding the transaction if not registered yet
+    pub(crate) fn set_accepting_block(&mut self, transaction_id: TransactionId, accepting_block_hash: Hash, daa_score: u64) {
+        self.transactions
+            .entry(transaction_id)
+            .and_modify(|entry| entry.accepting_block_hash = Some(accepting_block_hash))
+            .or_insert(AcceptedTransaction { daa_score, accepting_block_hash: Some(accepting_block_hash) });
+    }
+
+    /// Forgets the accepting blocks removed from the selected chain by a reorg
+    pub(crate) fn unset_accepting_blocks(&mut self, removed_chain_block_hashes: &HashSet<Hash>) {
+        for entry in self.transactions.values_mut() {
+            if entry.accepting_block_hash.is_some_and(|hash| removed_chain_block_hashes.contains(&hash)) {
+                entry.accepting_block_hash = None;
+            }
+        }
+    }
+
+    pub(crate) fn accepting_block(&self, transaction_id: &TransactionId) -> Option<Hash> {
+        self.transactions.get(transaction_id).and_then(|entry| entry.accepting_block_hash)
     }
 
     pub(crate) fn remove(&mut self, transaction_id: &TransactionId) -> bool {
@@ -42,7 +83,7 @@ impl AcceptedTransactions {
     }
 
     pub(crate) fn expire(&mut self, virtual_daa_score: u64) {
-        let now = unix_now();
+        let now = self.config.clock.unix_now();
         if virtual_daa_score < self.last_expire_scan_daa_score + self.config.accepted_transaction_expire_scan_interval_daa_score
             || now < self.last_expire_scan_time + self.config.accepted_transaction_expire_scan_interval_milliseconds
         {
@@ -52,8 +93,8 @@ impl AcceptedTransactions {
         let expired_transactions: Vec<TransactionId> = self
             .transactions
             .iter()
-            .filter_map(|(transaction_id, daa_score)| {
-                if virtual_daa_score > daa_score + self.config.accepted_transaction_expire_interval_daa_score {
+            .filter_map(|(transaction_id, entry)| {
+                if virtual_daa_score > entry.daa_score + self.config.accepted_transaction_expire_interval_daa_score {
                     Some(*transaction_id)
                 } else {
                     None
@@ -0,0 +1,95 @@
+use crate::{
+    mempool::config::Config,
+    model::{owner_txs::ScriptPublicKeySet, TransactionIdSet},
+};
+use kaspa_consensus_core::tx::{Transaction, TransactionId, TransactionOutpoint};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+};
+
+pub(crate) struct CachedConflict {
+    pub(crate) transaction: Arc<Transaction>,
+    /// Script public keys of the UTXO entries spent by the transaction, as far as known when it got rejected
+    pub(crate) spent_script_public_keys: ScriptPublicKeySet,
+}
+
+/// Bounded cache of the transactions recently rejected for double spending a mempool transaction, indexed
+/// by the outpoints they spend.
+///
+/// The cache allows to detect the conflicts of these transactions with the transactions of incoming blocks,
+/// even once the mempool transaction they were initially conflicting with is gone. When full, the oldest
+/// transactions get evicted first.
+pub(crate) struct ConflictCache {
+    /// Mempool config
+    config: Arc<Config>,
+
+    transactions: HashMap<TransactionId, CachedConflict>,
+    outpoint_owner_ids: HashMap<TransactionOutpoint, TransactionIdSet>,
+    /// Insertion order of the transactions, for evicting the oldest ones
+    order: VecDeque<TransactionId>,
+}
+
+impl ConflictCache {
+    pub(crate) fn new(config: Arc<Config>) -> Self {
+        Self { config, transactions: Default::default(), outpoint_owner_ids: Default::default(), order: Default::default() }
+    }
+
+    pub(crate) fn contains(&self, transaction_id: &TransactionId) -> bool {
+        self.transactions.contains_key(transaction_id)
+    }
+
+    /// Inserts a rejected transaction, evicting the oldest ones if the cache is full.
+    ///
+    /// Returns false if the transaction is already cached or if caching is disabled.
+    pub(crate) fn insert(&mut self, transaction: Arc<Transaction>, spent_script_public_keys: ScriptPublicKeySet) -> bool {
+        let capacity = self.config.maximum_conflict_cache_count as usize;
+        let transaction_id = transaction.id();
+        if capacity == 0 || self.contains(&transaction_id) {
+            return false;
+        }
+        while self.transactions.len() >= capacity {
+            match self.order.pop_front() {
+                Some(oldest_id) => {
+                    self.remove(&oldest_id);
+                }
+                None => break,
+            }
+        }
+        for input in transaction.inputs.iter() {
+            self.outpoint_owner_ids.entry(input.previous_outpoint).or_default().insert(transaction_id);
+        }
+        self.transactions.insert(transaction_id, CachedConflict { transaction, spent_script_public_keys });
+        self.order.push_back(transaction_id);
+        true
+    }
+
+    pub(crate) fn remove(&mut self, transaction_id: &TransactionId) -> Option<CachedConflict> {
+        let cached = self.transactions.remove(transaction_id)?;
+        for input in cached.transaction.inputs.iter() {
+            if let Some(owner_ids) = self.outpoint_owner_ids.get_mut(&input.previous_outpoint) {
+                owner_ids.remove(transaction_id);
+                if owner_ids.is_empty() {
+                    self.outpoint_owner_ids.remove(&input.previous_outpoint);
+                }
+            }
+        }
+        if let Some(position) = self.order.iter().position(|x| x == transaction_id) {
+            self.order.remove(position);
+        }
+        Some(cached)
+    }
+
+    /// Returns the ids of the cached transactions spending any outpoint spent by `transaction`, excluding itself
+    pub(crate) fn get_conflicting_ids(&self, transaction: &Transaction) -> TransactionIdSet {
+        let transaction_id = transaction.id();
+        transaction
+            .inputs
+            .iter()
+            .filter_map(|input| self.outpoint_owner_ids.get(&input.previous_outpoint))
+            .flatten()
+            .filter(|id| **id != transaction_id)
+            .copied()
+            .collect()
+    }
+}
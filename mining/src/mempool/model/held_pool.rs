@@ -0,0 +1,116 @@
+use crate::mempool::{
+    config::Config,
+    errors::{RuleError, RuleResult},
+    model::{
+        map::{MempoolTransactionCollection, OutpointIndex},
+        pool::{Pool, TransactionsEdges},
+        tx::{MempoolTransaction, TxRemovalReason},
+    },
+    tx::Priority,
+};
+use kaspa_consensus_core::{
+    locktime,
+    tx::{MutableTransaction, TransactionId, TransactionOutpoint},
+};
+use kaspa_core::{debug, warn};
+use std::sync::Arc;
+
+/// Pool of transactions whose only invalidity is a lock time which is not reached yet
+///
+/// Held transactions are not part of the transaction pool: they are neither relayed nor
+/// selected as block template candidates. They get revalidated as the virtual DAA score
+/// and past median time advance and are then promoted into the transaction pool.
+///
+/// Since a held transaction is rejected by consensus before any UTXO context validation
+/// occurs, its UTXO entries are not populated and no chained relations are tracked.
+pub(crate) struct HeldPool {
+    config: Arc<Config>,
+    all_held: MempoolTransactionCollection,
+    /// Always empty, held transactions are never chained
+    chained_held: TransactionsEdges,
+    outpoint_owner_id: OutpointIndex,
+}
+
+impl HeldPool {
+    pub(crate) fn new(config: Arc<Config>) -> Self {
+        Self {
+            config,
+            all_held: MempoolTransactionCollection::default(),
+            chained_held: TransactionsEdges::default(),
+            outpoint_owner_id: OutpointIndex::default(),
+        }
+    }
+
+    /// Returns whether holding not yet valid transactions is enabled
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.config.maximum_held_transaction_count > 0
+    }
+
+    pub(crate) fn outpoint_owner_id(&self, outpoint: &TransactionOutpoint) -> Option<&TransactionId> {
+        self.outpoint_owner_id.get(outpoint)
+    }
+
+    pub(crate) fn try_add_held(
+        &mut self,
+        virtual_daa_score: u64,
+        transaction: MutableTransaction,
+        priority: Priority,
+    ) -> RuleResult<()> {
+        let id = transaction.id();
+        if self.all_held.contains_key(&id) {
+            return Err(RuleError::RejectDuplicate(id));
+        }
+        for input in transaction.tx.inputs.iter() {
+            if let Some(double_spend_id) = self.outpoint_owner_id(&input.previous_outpoint) {
+                return Err(RuleError::RejectDoubleSpendHeld(id, *double_spend_id));
+            }
+        }
+        // Held transactions are all submitted by RPC, so none gets evicted to make room
+        if self.all_held.len() >= self.config.maximum_held_transaction_count as usize {
+            let err = RuleError::RejectHeldPoolIsFull(self.all_held.len(), self.config.maximum_held_transaction_count);
+            warn!("{}", err.to_string());
+            return Err(err);
+        }
+
+        for input in transaction.tx.inputs.iter() {
+            self.outpoint_owner_id.insert(input.previous_outpoint, id);
+        }
+        self.all_held.insert(id, MempoolTransaction::new(transaction, priority, virtual_daa_score));
+        debug!("Added transaction to held pool: {}", id);
+        Ok(())
+    }
+
+    pub(crate) fn remove_held(&mut self, transaction_id: &TransactionId, reason: TxRemovalReason) -> Option<MempoolTransaction> {
+        let transaction = self.all_held.remove(transaction_id)?;
+        for input in transaction.mtx.tx.inputs.iter() {
+            self.outpoint_owner_id.remove(&input.previous_outpoint);
+        }
+        if reason.verbose() {
+            debug!("Removed held transaction ({}): {}", reason, transaction_id);
+        }
+        Some(transaction)
+    }
+
+    /// Returns the ids of all held transactions whose lock time is reached in the context
+    /// of the provided virtual DAA score and past median time.
+    pub(crate) fn get_ready_transaction_ids(&self, virtual_daa_score: u64, virtual_past_median_time: u64) -> Vec<TransactionId> {
+        self.all_held
+            .values()
+            .filter_map(|x| locktime::is_final(&x.mtx.tx, virtual_daa_score, virtual_past_median_time).then(|| x.id()))
+            .collect()
+    }
+}
+
+impl Pool for HeldPool {
+    fn all(&self) -> &MempoolTransactionCollection {
+        &self.all_held
+    }
+
+    fn chained(&self) -> &TransactionsEdges {
+        &self.chained_held
+    }
+
+    fn get_mut(&mut self, transaction_id: &TransactionId) -> Option<&mut MempoolTransaction> {
+        self.all_held.get_mut(transaction_id)
+    }
+}
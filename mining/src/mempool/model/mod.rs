@@ -1,4 +1,6 @@
 pub(crate) mod accepted_transactions;
+pub(crate) mod conflict_cache;
+pub(crate) mod held_pool;
 pub(crate) mod map;
 pub(crate) mod orphan_pool;
 pub(crate) mod pool;
@@ -1,4 +1,5 @@
 use crate::{
+    estimator::{MempoolSnapshot, MempoolSnapshotEntry},
     mempool::{
         config::Config,
         errors::{RuleError, RuleResult},
@@ -16,7 +17,7 @@ use kaspa_consensus_core::{
     tx::TransactionId,
     tx::{MutableTransaction, TransactionOutpoint},
 };
-use kaspa_core::{time::unix_now, trace, warn};
+use kaspa_core::{trace, warn};
 use std::{
     collections::{hash_map::Keys, hash_set::Iter, HashSet},
     sync::Arc,
@@ -66,6 +67,7 @@ pub(crate) struct TransactionsPool {
 
 impl TransactionsPool {
     pub(crate) fn new(config: Arc<Config>) -> Self {
+        let last_expire_scan_time = config.clock.unix_now();
         Self {
             config,
             all_transactions: MempoolTransactionCollection::default(),
@@ -73,7 +75,7 @@ impl TransactionsPool {
             chained_transactions: TransactionsEdges::default(),
             ready_transactions: Default::default(),
             last_expire_scan_daa_score: 0,
-            last_expire_scan_time: unix_now(),
+            last_expire_scan_time,
             utxo_set: MempoolUtxoSet::new(),
         }
     }
@@ -237,6 +239,15 @@ impl TransactionsPool {
         Ok(transactions_to_remove.iter().map(|x| x.id()).collect())
     }
 
+    /// Returns a view of all the transactions of the pool suited for confirmation time estimation
+    pub(crate) fn snapshot(&self) -> MempoolSnapshot {
+        MempoolSnapshot::new(self.all_transactions.values().map(|x| {
+            let id = x.id();
+            let parents = self.parent_transactions.get(&id).map(|parents| parents.iter().copied().collect()).unwrap_or_default();
+            MempoolSnapshotEntry::new(id, x.mtx.calculated_fee.unwrap_or_default(), x.mtx.tx.mass(), parents)
+        }))
+    }
+
     pub(crate) fn all_transaction_ids_with_priority(&self, priority: Priority) -> Vec<TransactionId> {
         self.all().values().filter_map(|x| if x.priority == priority { Some(x.id()) } else { None }).collect()
     }
@@ -250,7 +261,7 @@ impl TransactionsPool {
     }
 
     pub(crate) fn collect_expired_low_priority_transactions(&mut self, virtual_daa_score: u64) -> Vec<TransactionId> {
-        let now = unix_now();
+        let now = self.config.clock.unix_now();
         if virtual_daa_score < self.last_expire_scan_daa_score + self.config.transaction_expire_scan_interval_daa_score
             || now < self.last_expire_scan_time + self.config.transaction_expire_scan_interval_milliseconds
         {
@@ -59,6 +59,7 @@ pub(crate) enum TxRemovalReason {
     Accepted,
     MakingRoom,
     Unorphaned,
+    Promoted,
     Expired,
     DoubleSpend,
     InvalidInBlockTemplate,
@@ -72,6 +73,7 @@ impl TxRemovalReason {
             TxRemovalReason::Accepted => "accepted",
             TxRemovalReason::MakingRoom => "making room",
             TxRemovalReason::Unorphaned => "unorphaned",
+            TxRemovalReason::Promoted => "promoted",
             TxRemovalReason::Expired => "expired",
             TxRemovalReason::DoubleSpend => "double spend",
             TxRemovalReason::InvalidInBlockTemplate => "invalid in block template",
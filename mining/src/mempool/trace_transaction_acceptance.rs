@@ -0,0 +1,121 @@
+use crate::{
+    mempool::{
+        check_transaction_standard::MAXIMUM_STANDARD_TRANSACTION_MASS,
+        errors::{NonStandardError, RuleError},
+        populate_entries_and_try_validate::validate_mempool_transaction,
+        Mempool,
+    },
+    model::acceptance_trace::{AcceptanceDetail, AcceptanceRule, TransactionAcceptanceTrace},
+};
+use kaspa_consensus_core::{
+    api::ConsensusApi,
+    errors::tx::{CoinbaseMaturityShortfall, TxRuleError},
+    tx::MutableTransaction,
+};
+
+impl Mempool {
+    /// Replays the validation of a transaction rule by rule, in the order applied by
+    /// `validate_and_insert_transaction`, and returns the trace of the checks performed
+    /// up to the first failing one. The transaction is never inserted into the mempool.
+    ///
+    /// This is a diagnostic path meant to explain the rejection of a transaction, so it
+    /// is run on demand only and is kept apart from the ordinary validation path.
+    pub(crate) fn trace_transaction_acceptance(
+        &self,
+        consensus: &dyn ConsensusApi,
+        mut transaction: MutableTransaction,
+    ) -> TransactionAcceptanceTrace {
+        let transaction_id = transaction.id();
+        let mut trace = TransactionAcceptanceTrace::new(transaction_id);
+
+        // Rules checked in isolation
+        if !trace.record(AcceptanceRule::NotAlreadyAccepted, self.validate_transaction_unacceptance(&transaction), None) {
+            return trace;
+        }
+        let duplicate = match self.transaction_pool.has(&transaction_id) || self.held_pool.has(&transaction_id) {
+            true => Err(RuleError::RejectDuplicate(transaction_id)),
+            false => Ok(()),
+        };
+        if !trace.record(AcceptanceRule::NotDuplicate, duplicate, None) {
+            return trace;
+        }
+        let compute_mass = consensus.calculate_transaction_compute_mass(&transaction.tx);
+        transaction.calculated_compute_mass = Some(compute_mass);
+        if self.config.accept_non_standard {
+            trace.skip(AcceptanceRule::StandardInIsolation);
+        } else {
+            let result = self.check_transaction_standard_in_isolation(&transaction);
+            let detail = match result {
+                Err(NonStandardError::RejectDust(_, output_index, value, threshold)) => {
+                    AcceptanceDetail::Dust { output_index, value, threshold }
+                }
+                _ => AcceptanceDetail::Mass { mass: compute_mass, limit: MAXIMUM_STANDARD_TRANSACTION_MASS },
+            };
+            if !trace.record(AcceptanceRule::StandardInIsolation, result.map_err(RuleError::from), Some(detail)) {
+                return trace;
+            }
+        }
+        if !trace.record(AcceptanceRule::NoDoubleSpend, self.transaction_pool.check_double_spends(&transaction), None) {
+            return trace;
+        }
+
+        // Rules checked in the context of the UTXO set and the mempool
+        self.populate_mempool_entries(&mut transaction);
+        match validate_mempool_transaction(consensus, &mut transaction) {
+            Err(RuleError::RejectMissingOutpoint) => {
+                let detail = AcceptanceDetail::MissingOutpoints(transaction.missing_outpoints().collect());
+                trace.record(AcceptanceRule::InputsAvailable, Err(RuleError::RejectMissingOutpoint), Some(detail));
+                return trace;
+            }
+            result => {
+                trace.record(AcceptanceRule::InputsAvailable, Ok(()), None);
+                let detail = match result {
+                    Err(RuleError::RejectImmatureSpend(TxRuleError::ImmatureCoinbaseSpend(
+                        input_index,
+                        _,
+                        utxo_daa_score,
+                        daa_score,
+                        coinbase_maturity,
+                    ))) => Some(AcceptanceDetail::MaturityShortfall {
+                        input_index,
+                        shortfall: CoinbaseMaturityShortfall::new(utxo_daa_score, daa_score, coinbase_maturity).remaining(),
+                    }),
+                    _ => None,
+                };
+                if !trace.record(AcceptanceRule::ConsensusContext, result, detail) {
+                    return trace;
+                }
+            }
+        }
+        if !trace.record(AcceptanceRule::NotSpam, self.check_transaction_not_spam(&transaction), None) {
+            return trace;
+        }
+        if self.config.accept_non_standard {
+            trace.skip(AcceptanceRule::StandardContextualMass);
+            trace.skip(AcceptanceRule::StandardInputScripts);
+            trace.skip(AcceptanceRule::MinimumFee);
+        } else {
+            let mass_detail = Some(AcceptanceDetail::Mass { mass: transaction.tx.mass(), limit: MAXIMUM_STANDARD_TRANSACTION_MASS });
+            let fee_detail = Some(AcceptanceDetail::Fee {
+                required: self.minimum_required_transaction_relay_fee(compute_mass),
+                provided: transaction.calculated_fee.unwrap_or_default(),
+            });
+            // The standard contextual rules are checked in a single pass, which returns the first failing rule
+            let (mass, scripts, fee) = match self.check_transaction_standard_in_context(&transaction) {
+                Ok(()) => (Ok(()), Ok(()), Ok(())),
+                Err(err @ NonStandardError::RejectContextualMass(..)) => (Err(err.into()), Ok(()), Ok(())),
+                Err(err @ NonStandardError::RejectInsufficientFee(..)) => (Ok(()), Ok(()), Err(err.into())),
+                Err(err) => (Ok(()), Err(err.into()), Ok(())),
+            };
+            if !trace.record(AcceptanceRule::StandardContextualMass, mass, mass_detail)
+                || !trace.record(AcceptanceRule::StandardInputScripts, scripts, None)
+                || !trace.record(AcceptanceRule::MinimumFee, fee, fee_detail)
+            {
+                return trace;
+            }
+        }
+        trace.record(AcceptanceRule::PoolCapacity, self.transaction_pool.limit_transaction_count(1, &transaction).map(|_| ()), None);
+
+        trace
+    }
+}
@@ -1,19 +1,27 @@
-use crate::mempool::{
-    errors::{RuleError, RuleResult},
+use crate::{
+    mempool::{
+        errors::{RuleError, RuleResult},
+        model::{
+            pool::Pool,
+            tx::{MempoolTransaction, TxRemovalReason},
+        },
+        tx::{Hold, Orphan, Priority},
+        Mempool,
+    },
     model::{
-        pool::Pool,
-        tx::{MempoolTransaction, TxRemovalReason},
+        owner_txs::ScriptPublicKeySet,
+        tx_conflict::{TransactionConflict, TransactionConflictSource},
     },
-    tx::{Orphan, Priority},
-    Mempool,
 };
 use kaspa_consensus_core::{
     api::ConsensusApi,
     constants::UNACCEPTED_DAA_SCORE,
+    errors::tx::TxRuleError,
     tx::{MutableTransaction, Transaction, TransactionId, TransactionOutpoint, UtxoEntry},
+    utxo::utxo_diff::UtxoDiff,
 };
 use kaspa_core::{debug, info};
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 impl Mempool {
     pub(crate) fn pre_validate_and_populate_transaction(
@@ -37,6 +45,7 @@ impl Mempool {
         transaction: MutableTransaction,
         priority: Priority,
         orphan: Orphan,
+        hold: Hold,
     ) -> RuleResult<Option<Arc<Transaction>>> {
         let transaction_id = transaction.id();
 
@@ -63,6 +72,10 @@ impl Mempool {
                 self.orphan_pool.try_add_orphan(consensus.get_virtual_daa_score(), transaction, priority)?;
                 return Ok(None);
             }
+            Err(RuleError::RejectTxRule(TxRuleError::NotFinalized(_))) if hold == Hold::Allowed && self.held_pool.is_enabled() => {
+                self.held_pool.try_add_held(consensus.get_virtual_daa_score(), transaction, priority)?;
+                return Ok(None);
+            }
             Err(err) => {
                 return Err(err);
             }
@@ -78,11 +91,47 @@ impl Mempool {
         // Add the transaction to the mempool as a MempoolTransaction and return a clone of the embedded Arc<Transaction>
         let accepted_transaction =
             self.transaction_pool.add_transaction(transaction, consensus.get_virtual_daa_score(), priority)?.mtx.tx.clone();
+        // A previously rejected transaction now in the pool is reported as such on a conflict with a block transaction
+        self.conflict_cache.remove(&transaction_id);
         Ok(Some(accepted_transaction))
     }
 
+    /// Records `transaction` as rejected for double spending the mempool transaction `conflicting_id`, so its
+    /// conflicts with incoming block transactions can be reported later, and returns the resulting conflict.
+    ///
+    /// Returns `None` if the rejection was already recorded or if the conflicting transaction left the mempool.
+    pub(crate) fn register_double_spend(
+        &mut self,
+        transaction: &Arc<Transaction>,
+        conflicting_id: &TransactionId,
+    ) -> Option<TransactionConflict> {
+        if self.conflict_cache.contains(&transaction.id()) {
+            return None;
+        }
+        let conflicting = self.transaction_pool.get(conflicting_id)?;
+        let outpoints: HashSet<_> = transaction.inputs.iter().map(|input| input.previous_outpoint).collect();
+        // The entries spent by both transactions are known from the mempool transaction
+        let spent_script_public_keys: ScriptPublicKeySet = conflicting
+            .mtx
+            .tx
+            .inputs
+            .iter()
+            .zip(conflicting.mtx.entries.iter())
+            .filter(|(input, _)| outpoints.contains(&input.previous_outpoint))
+            .filter_map(|(_, entry)| entry.as_ref().map(|entry| entry.script_public_key.clone()))
+            .collect();
+        let conflict = TransactionConflict::new(
+            transaction,
+            &conflicting.mtx.tx,
+            spent_script_public_keys.iter(),
+            TransactionConflictSource::Mempool,
+        );
+        self.conflict_cache.insert(transaction.clone(), spent_script_public_keys);
+        Some(conflict)
+    }
+
     /// Validates that the transaction wasn't already accepted into the DAG
-    fn validate_transaction_unacceptance(&self, transaction: &MutableTransaction) -> RuleResult<()> {
+    pub(crate) fn validate_transaction_unacceptance(&self, transaction: &MutableTransaction) -> RuleResult<()> {
         // Reject if the transaction is registered as an accepted transaction
         let transaction_id = transaction.id();
         match self.accepted_transactions.has(&transaction_id) {
@@ -93,7 +142,7 @@ impl Mempool {
 
     fn validate_transaction_in_isolation(&self, transaction: &MutableTransaction) -> RuleResult<()> {
         let transaction_id = transaction.id();
-        if self.transaction_pool.has(&transaction_id) {
+        if self.transaction_pool.has(&transaction_id) || self.held_pool.has(&transaction_id) {
             return Err(RuleError::RejectDuplicate(transaction_id));
         }
         if !self.config.accept_non_standard {
@@ -103,6 +152,14 @@ impl Mempool {
     }
 
     fn validate_transaction_in_context(&self, transaction: &MutableTransaction) -> RuleResult<()> {
+        self.check_transaction_not_spam(transaction)?;
+        if !self.config.accept_non_standard {
+            self.check_transaction_standard_in_context(transaction)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_transaction_not_spam(&self, transaction: &MutableTransaction) -> RuleResult<()> {
         // TEMP: apply parts of go-kaspad mempool dust prevention patch
         let has_coinbase_input = transaction.entries.iter().any(|e| e.as_ref().unwrap().is_coinbase);
         let num_extra_outs = transaction.tx.outputs.len() as i64 - transaction.tx.inputs.len() as i64;
@@ -113,10 +170,6 @@ impl Mempool {
             kaspa_core::trace!("Rejected spam tx {} from mempool ({} outputs)", transaction.id(), transaction.tx.outputs.len());
             return Err(RuleError::RejectSpamTransaction(transaction.id()));
         }
-
-        if !self.config.accept_non_standard {
-            self.check_transaction_standard_in_context(transaction)?;
-        }
         Ok(())
     }
 
@@ -126,47 +179,63 @@ impl Mempool {
         &mut self,
         transaction: &Transaction,
     ) -> Vec<MempoolTransaction> {
-        let mut unorphaned_transactions = Vec::new();
         let transaction_id = transaction.id();
-        let mut outpoint = TransactionOutpoint::new(transaction_id, 0);
-        for (i, output) in transaction.outputs.iter().enumerate() {
-            outpoint.index = i as u32;
-            let mut orphan_id = None;
-            if let Some(orphan) = self.orphan_pool.outpoint_orphan_mut(&outpoint) {
-                for (i, input) in orphan.mtx.tx.inputs.iter().enumerate() {
-                    if input.previous_outpoint == outpoint {
-                        if orphan.mtx.entries[i].is_none() {
-                            let entry = UtxoEntry::new(output.value, output.script_public_key.clone(), UNACCEPTED_DAA_SCORE, false);
-                            orphan.mtx.entries[i] = Some(entry);
-                            if orphan.mtx.is_verifiable() {
-                                orphan_id = Some(orphan.id());
-                            }
-                        }
-                        break;
-                    }
-                }
-            } else {
-                continue;
+        transaction
+            .outputs
+            .iter()
+            .enumerate()
+            .filter_map(|(i, output)| {
+                let outpoint = TransactionOutpoint::new(transaction_id, i as u32);
+                self.try_unorphan_after_outpoint_added(&outpoint, || {
+                    UtxoEntry::new(output.value, output.script_public_key.clone(), UNACCEPTED_DAA_SCORE, false)
+                })
+            })
+            .collect()
+    }
+
+    /// Returns a list with all successfully unorphaned transactions after the
+    /// outpoints added by `utxo_diff` became available in the virtual UTXO set.
+    pub(crate) fn get_unorphaned_transactions_after_utxo_diff(&mut self, utxo_diff: &UtxoDiff) -> Vec<MempoolTransaction> {
+        utxo_diff
+            .add
+            .iter()
+            .filter_map(|(outpoint, entry)| self.try_unorphan_after_outpoint_added(outpoint, || entry.clone()))
+            .collect()
+    }
+
+    /// Fills the missing entry of the orphan spending `outpoint`, if any, and unorphans
+    /// the orphan if all its entries are then populated.
+    fn try_unorphan_after_outpoint_added(
+        &mut self,
+        outpoint: &TransactionOutpoint,
+        entry: impl FnOnce() -> UtxoEntry,
+    ) -> Option<MempoolTransaction> {
+        let orphan = self.orphan_pool.outpoint_orphan_mut(outpoint)?;
+        let i = orphan.mtx.tx.inputs.iter().position(|input| input.previous_outpoint == *outpoint)?;
+        if orphan.mtx.entries[i].is_some() {
+            return None;
+        }
+        orphan.mtx.entries[i] = Some(entry());
+        if !orphan.mtx.is_verifiable() {
+            return None;
+        }
+        let orphan_id = orphan.id();
+        match self.unorphan_transaction(&orphan_id) {
+            Ok(unorphaned_tx) => {
+                debug!("Transaction {0} unorphaned", orphan_id);
+                Some(unorphaned_tx)
             }
-            if let Some(orphan_id) = orphan_id {
-                match self.unorphan_transaction(&orphan_id) {
-                    Ok(unorphaned_tx) => {
-                        unorphaned_transactions.push(unorphaned_tx);
-                        debug!("Transaction {0} unorphaned", transaction_id);
-                    }
-                    Err(RuleError::RejectAlreadyAccepted(transaction_id)) => {
-                        debug!("Ignoring already accepted transaction {}", transaction_id);
-                    }
-                    Err(err) => {
-                        // In case of validation error, we log the problem and drop the
-                        // erroneous transaction.
-                        info!("Failed to unorphan transaction {0} due to rule error: {1}", orphan_id, err.to_string());
-                    }
-                }
+            Err(RuleError::RejectAlreadyAccepted(transaction_id)) => {
+                debug!("Ignoring already accepted transaction {}", transaction_id);
+                None
+            }
+            Err(err) => {
+                // In case of validation error, we log the problem and drop the
+                // erroneous transaction.
+                info!("Failed to unorphan transaction {0} due to rule error: {1}", orphan_id, err.to_string());
+                None
             }
         }
-
-        unorphaned_transactions
     }
 
     fn unorphan_transaction(&mut self, transaction_id: &TransactionId) -> RuleResult<MempoolTransaction> {
@@ -189,4 +258,29 @@ impl Mempool {
         self.transaction_pool.check_double_spends(&transaction.mtx)?;
         Ok(transaction)
     }
+    /// Returns clones of all held transactions whose lock time is reached in the context of the
+    /// provided virtual DAA score and past median time, ready for being revalidated.
+    ///
+    /// The transactions are kept in the held pool until [`Self::take_held_transaction`] is called.
+    pub(crate) fn get_held_transactions_ready_for_promotion(
+        &self,
+        virtual_daa_score: u64,
+        virtual_past_median_time: u64,
+    ) -> Vec<MutableTransaction> {
+        self.held_pool
+            .get_ready_transaction_ids(virtual_daa_score, virtual_past_median_time)
+            .iter()
+            .filter_map(|transaction_id| {
+                let mut transaction = self.held_pool.get(transaction_id)?.mtx.clone();
+                transaction.clear_entries();
+                self.populate_mempool_entries(&mut transaction);
+                Some(transaction)
+            })
+            .collect()
+    }
+
+    /// Removes a transaction from the held pool, returning its priority if it was held.
+    pub(crate) fn take_held_transaction(&mut self, transaction_id: &TransactionId) -> Option<Priority> {
+        self.held_pool.remove_held(transaction_id, TxRemovalReason::Promoted).map(|x| x.priority)
+    }
 }
@@ -0,0 +1,139 @@
+use crate::mempool::errors::RuleError;
+use kaspa_consensus_core::tx::{TransactionId, TransactionOutpoint};
+use std::fmt::Display;
+
+/// A mempool acceptance rule checked against a submitted transaction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AcceptanceRule {
+    /// The transaction was not already accepted by the consensus
+    NotAlreadyAccepted,
+    /// The transaction is not already in the mempool
+    NotDuplicate,
+    /// The transaction is standard regardless of the UTXO set (version, mass, scripts, dust)
+    StandardInIsolation,
+    /// No input is already spent by another mempool transaction
+    NoDoubleSpend,
+    /// All the outpoints spent by the transaction are found in the UTXO set or the mempool
+    InputsAvailable,
+    /// The transaction is valid in the context of the virtual UTXO set (maturity, lock time, amounts, signatures)
+    ConsensusContext,
+    /// The transaction does not match the mempool spam pattern
+    NotSpam,
+    /// The contextual mass (including the storage mass) is below the standard limit
+    StandardContextualMass,
+    /// The scripts spent by the transaction inputs are standard
+    StandardInputScripts,
+    /// The transaction pays at least the minimum relay fee
+    MinimumFee,
+    /// The mempool has room for the transaction, possibly by evicting lower priority ones
+    PoolCapacity,
+}
+
+impl AcceptanceRule {
+    /// Returns `true` if the rule is checked once the UTXO entries of the transaction are populated
+    pub fn is_contextual(&self) -> bool {
+        !matches!(
+            self,
+            AcceptanceRule::NotAlreadyAccepted
+                | AcceptanceRule::NotDuplicate
+                | AcceptanceRule::StandardInIsolation
+                | AcceptanceRule::NoDoubleSpend
+        )
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AcceptanceRule::NotAlreadyAccepted => "not-already-accepted",
+            AcceptanceRule::NotDuplicate => "not-duplicate",
+            AcceptanceRule::StandardInIsolation => "standard-in-isolation",
+            AcceptanceRule::NoDoubleSpend => "no-double-spend",
+            AcceptanceRule::InputsAvailable => "inputs-available",
+            AcceptanceRule::ConsensusContext => "consensus-context",
+            AcceptanceRule::NotSpam => "not-spam",
+            AcceptanceRule::StandardContextualMass => "standard-contextual-mass",
+            AcceptanceRule::StandardInputScripts => "standard-input-scripts",
+            AcceptanceRule::MinimumFee => "minimum-fee",
+            AcceptanceRule::PoolCapacity => "pool-capacity",
+        }
+    }
+}
+
+impl Display for AcceptanceRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Outcome of an acceptance rule check
+#[derive(Clone, Debug)]
+pub enum AcceptanceOutcome {
+    Passed,
+    Failed(RuleError),
+    /// The rule is disabled by the mempool configuration
+    Skipped,
+}
+
+/// The inputs an acceptance rule was checked against
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AcceptanceDetail {
+    /// Transaction mass and the maximum allowed by the rule
+    Mass { mass: u64, limit: u64 },
+    /// Fee required by the rule and the fee provided by the transaction
+    Fee { required: u64, provided: u64 },
+    /// Outpoints found neither in the UTXO set nor in the mempool
+    MissingOutpoints(Vec<TransactionOutpoint>),
+    /// Number of DAA scores left before the coinbase UTXO spent by input `input_index` reaches maturity
+    MaturityShortfall { input_index: usize, shortfall: u64 },
+    /// Value of the dust output `output_index` and the minimum value applied to its script class
+    Dust { output_index: usize, value: u64, threshold: u64 },
+}
+
+#[derive(Clone, Debug)]
+pub struct AcceptanceCheck {
+    pub rule: AcceptanceRule,
+    pub outcome: AcceptanceOutcome,
+    pub detail: Option<AcceptanceDetail>,
+}
+
+impl AcceptanceCheck {
+    pub fn is_failed(&self) -> bool {
+        matches!(self.outcome, AcceptanceOutcome::Failed(_))
+    }
+}
+
+/// The ordered list of the acceptance rules checked against a transaction, up to and
+/// including the first failing one, if any
+#[derive(Clone, Debug)]
+pub struct TransactionAcceptanceTrace {
+    pub transaction_id: TransactionId,
+    pub checks: Vec<AcceptanceCheck>,
+}
+
+impl TransactionAcceptanceTrace {
+    pub fn new(transaction_id: TransactionId) -> Self {
+        Self { transaction_id, checks: vec![] }
+    }
+
+    /// Records the outcome of a rule, returning `true` if the rule did not fail
+    pub(crate) fn record(&mut self, rule: AcceptanceRule, result: Result<(), RuleError>, detail: Option<AcceptanceDetail>) -> bool {
+        let outcome = match result {
+            Ok(()) => AcceptanceOutcome::Passed,
+            Err(err) => AcceptanceOutcome::Failed(err),
+        };
+        self.checks.push(AcceptanceCheck { rule, outcome, detail });
+        !self.checks.last().unwrap().is_failed()
+    }
+
+    pub(crate) fn skip(&mut self, rule: AcceptanceRule) {
+        self.checks.push(AcceptanceCheck { rule, outcome: AcceptanceOutcome::Skipped, detail: None });
+    }
+
+    /// Returns the failing check, if any
+    pub fn failed_check(&self) -> Option<&AcceptanceCheck> {
+        self.checks.iter().find(|check| check.is_failed())
+    }
+
+    pub fn check(&self, rule: AcceptanceRule) -> Option<&AcceptanceCheck> {
+        self.checks.iter().find(|check| check.rule == rule)
+    }
+}
@@ -0,0 +1,23 @@
+/// Classification of a block submitted by a miner, done before its processing by consensus
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockSubmissionClassification {
+    /// The block is built on the current virtual parents
+    Fresh,
+    /// The block is built on a template whose parents are no longer the virtual parents. Stale blocks
+    /// are still valid and processed, but are less likely to end up in the selected chain.
+    Stale,
+    /// The block is already known to consensus, so it needs no processing
+    AlreadyProcessed,
+}
+
+/// Outcome of the policy check of the transaction order of a submitted block against the canonical block
+/// template order. Blocks with another order are valid and processed as usual.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubmittedTransactionOrder {
+    /// The coinbase comes first, followed by the other transactions in canonical template order
+    Canonical,
+    /// The transactions were reordered, as for instance when a pool reassembles its templates
+    NonCanonical,
+    /// Some transactions are unknown to the mempool, so their canonical order cannot be established
+    Unverified,
+}
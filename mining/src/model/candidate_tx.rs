@@ -1,5 +1,5 @@
-use kaspa_consensus_core::tx::{MutableTransaction, Transaction};
-use std::sync::Arc;
+use kaspa_consensus_core::tx::{MutableTransaction, ScriptPublicKey, Transaction};
+use std::{cmp::Ordering, sync::Arc};
 
 /// Transaction with additional metadata needed in order to be a candidate
 /// in the transaction selection algorithm
@@ -11,12 +11,33 @@ pub(crate) struct CandidateTransaction {
     pub calculated_fee: u64,
     /// Populated mass
     pub calculated_mass: u64,
+    /// The script public key spent by the first input, if populated
+    pub origin: Option<ScriptPublicKey>,
 }
 
 impl CandidateTransaction {
     pub(crate) fn from_mutable(tx: &MutableTransaction) -> Self {
         let mass = tx.tx.mass();
         assert_ne!(mass, 0, "mass field is expected to be set when inserting to the mempool");
-        Self { tx: tx.tx.clone(), calculated_fee: tx.calculated_fee.expect("fee is expected to be populated"), calculated_mass: mass }
+        let origin = tx.entries.first().and_then(|entry| entry.as_ref()).map(|entry| entry.script_public_key.clone());
+        Self {
+            tx: tx.tx.clone(),
+            calculated_fee: tx.calculated_fee.expect("fee is expected to be populated"),
+            calculated_mass: mass,
+            origin,
+        }
+    }
+
+    /// Compares by the canonical order of the block template transactions following the coinbase: by subnetwork id,
+    /// then by descending feerate and finally by transaction id. Candidate transactions only spend confirmed outputs,
+    /// so this order is also a topological one.
+    pub(crate) fn cmp_template_order(&self, other: &Self) -> Ordering {
+        // Feerates are compared exactly, by cross-multiplying fees and masses
+        let feerate_cmp = || {
+            let feerate = self.calculated_fee as u128 * other.calculated_mass as u128;
+            let other_feerate = other.calculated_fee as u128 * self.calculated_mass as u128;
+            other_feerate.cmp(&feerate)
+        };
+        self.tx.subnetwork_id.cmp(&other.tx.subnetwork_id).then_with(feerate_cmp).then_with(|| self.tx.id().cmp(&other.tx.id()))
     }
 }
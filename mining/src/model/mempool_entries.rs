@@ -0,0 +1,32 @@
+use kaspa_consensus_core::tx::MutableTransaction;
+
+/// Totals of the mempool transactions matching a query, taken at `timestamp`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MempoolSummary {
+    pub transaction_count: u64,
+    pub total_mass: u64,
+    pub total_fees: u64,
+    /// Unix time in milliseconds of the snapshot
+    pub timestamp: u64,
+}
+
+impl MempoolSummary {
+    pub(crate) fn new(timestamp: u64) -> Self {
+        Self { timestamp, ..Default::default() }
+    }
+
+    pub(crate) fn add(&mut self, transaction: &MutableTransaction) {
+        self.transaction_count += 1;
+        self.total_mass += transaction.tx.mass();
+        self.total_fees += transaction.calculated_fee.unwrap_or_default();
+    }
+}
+
+/// The mempool transactions matching a query, along with the summary of the snapshot they were read from
+pub struct MempoolEntries {
+    pub transactions: Vec<MutableTransaction>,
+    pub orphans: Vec<MutableTransaction>,
+    /// Transactions held until their lock time is reached, returned along the transaction pool
+    pub held_transactions: Vec<MutableTransaction>,
+    pub summary: MempoolSummary,
+}
@@ -1,10 +1,14 @@
 use kaspa_consensus_core::tx::TransactionId;
 use std::collections::HashSet;
 
+pub mod acceptance_trace;
+pub mod block_submission;
 pub(crate) mod candidate_tx;
+pub mod mempool_entries;
 pub mod owner_txs;
 pub mod topological_index;
 pub mod topological_sort;
+pub mod tx_conflict;
 pub mod tx_query;
 
 /// A set of unique transaction ids
@@ -0,0 +1,60 @@
+use super::owner_txs::ScriptPublicKeySet;
+use kaspa_consensus_core::tx::{ScriptPublicKey, Transaction, TransactionId, TransactionOutpoint};
+use std::collections::HashSet;
+
+/// Where the transaction winning a conflict was found
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransactionConflictSource {
+    /// The winning transaction is in the mempool, the losing one being rejected at its admission
+    Mempool,
+    /// The winning transaction is in a block, the losing one being removed from the mempool or having been
+    /// recently rejected by it
+    Block,
+}
+
+/// Two transactions spending some same outpoints, so that at most one of them can ever get accepted
+#[derive(Clone, Debug)]
+pub struct TransactionConflict {
+    /// The transaction losing the conflict
+    pub transaction_id: TransactionId,
+    /// The transaction winning the conflict
+    pub conflicting_transaction_id: TransactionId,
+    /// The outpoints spent by both transactions
+    pub outpoints: Vec<TransactionOutpoint>,
+    pub source: TransactionConflictSource,
+    /// The script public keys paid to by both transactions along with the ones of the spent UTXO entries known
+    /// to the mempool, allowing a selection of the conflicts by address
+    pub script_public_keys: ScriptPublicKeySet,
+}
+
+impl TransactionConflict {
+    pub(crate) fn new<'a>(
+        transaction: &Transaction,
+        conflicting_transaction: &Transaction,
+        spent_script_public_keys: impl Iterator<Item = &'a ScriptPublicKey>,
+        source: TransactionConflictSource,
+    ) -> Self {
+        let conflicting_outpoints: HashSet<_> = conflicting_transaction.inputs.iter().map(|x| x.previous_outpoint).collect();
+        let outpoints = transaction
+            .inputs
+            .iter()
+            .map(|x| x.previous_outpoint)
+            .filter(|outpoint| conflicting_outpoints.contains(outpoint))
+            .collect();
+        let script_public_keys = transaction
+            .outputs
+            .iter()
+            .chain(conflicting_transaction.outputs.iter())
+            .map(|output| &output.script_public_key)
+            .chain(spent_script_public_keys)
+            .cloned()
+            .collect();
+        Self {
+            transaction_id: transaction.id(),
+            conflicting_transaction_id: conflicting_transaction.id(),
+            outpoints,
+            source,
+            script_public_keys,
+        }
+    }
+}
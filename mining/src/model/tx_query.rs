@@ -1,4 +1,5 @@
 /// Indicates whether the mempool query result should include transactions/orphans or both
+#[derive(Clone, Copy)]
 pub enum TransactionQuery {
     /// Include only non-orphan transactions from the ordinary mempool tx pool
     TransactionsOnly,
@@ -73,6 +73,32 @@ impl MiningMonitor {
                     tx_script_cache_delta.hit_ratio()
                 );
             }
+            if delta.stale_block_submission_counts
+                + delta.duplicate_block_submission_counts
+                + delta.non_canonical_block_submission_counts
+                > 0
+            {
+                info!(
+                    "Block submission stats: {} stale, {} already processed, {} with non-canonical transaction order",
+                    delta.stale_block_submission_counts,
+                    delta.duplicate_block_submission_counts,
+                    delta.non_canonical_block_submission_counts
+                );
+            }
+            if delta.block_template_counts > 0 {
+                if let Some(stats) = self.counters.last_template_stats() {
+                    info!(
+                        "Block template stats: {} built, last one selecting {} out of {} txs ({} fees, {}/{} mass, {:.2} min feerate)",
+                        delta.block_template_counts,
+                        stats.selected_count,
+                        stats.considered_count,
+                        stats.total_fees,
+                        stats.total_mass,
+                        stats.max_block_mass,
+                        stats.min_feerate.unwrap_or_default(),
+                    );
+                }
+            }
             if delta.txs_sample + delta.orphans_sample > 0 {
                 debug!(
                     "Mempool sample: {} ready out of {} txs, {} orphans, {} cached as accepted",
@@ -0,0 +1,156 @@
+use super::vardiff::difficulty_to_target;
+use kaspa_consensus_core::block::Block;
+use kaspa_hashes::Hash;
+use kaspa_math::Uint256;
+use kaspa_pow::State;
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::Arc,
+};
+
+/// A block template notified to a connection, together with the difficulty of its shares
+pub struct Job {
+    pub id: String,
+    pub block: Block,
+    pub pre_pow_hash: Hash,
+    pub difficulty: f64,
+    share_target: Uint256,
+    pow_state: State,
+    nonces: HashSet<u64>,
+}
+
+impl Job {
+    fn new(id: String, block: Block, difficulty: f64) -> Self {
+        let pre_pow_hash = block.header.pre_pow_hash(true);
+        let pow_state = State::new(&block.header);
+        Self { id, block, pre_pow_hash, difficulty, share_target: difficulty_to_target(difficulty), pow_state, nonces: HashSet::new() }
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.block.header.timestamp
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareOutcome {
+    /// The share meets the difficulty of its job
+    Accepted,
+    /// The share meets the target of the block, which must be submitted
+    Block,
+    /// The nonce was already submitted for the job
+    Duplicate,
+    /// The job is unknown or no longer retained
+    Stale,
+    /// The share does not meet the difficulty of its job
+    LowDifficulty,
+}
+
+pub struct SubmittedShare {
+    pub outcome: ShareOutcome,
+    /// The solved block, if the share meets its target
+    pub block: Option<Block>,
+}
+
+impl SubmittedShare {
+    fn new(outcome: ShareOutcome) -> Self {
+        Self { outcome, block: None }
+    }
+}
+
+/// The jobs of a connection, from the oldest to the current one
+#[derive(Default)]
+pub struct Jobs {
+    jobs: VecDeque<Job>,
+    next_id: u64,
+}
+
+impl Jobs {
+    /// Count of jobs whose shares are accepted
+    pub const RETAINED_JOBS: usize = 8;
+
+    /// Makes `block` the current job, solved at `difficulty`
+    pub fn push(&mut self, block: Block, difficulty: f64) -> &Job {
+        if self.jobs.len() == Self::RETAINED_JOBS {
+            self.jobs.pop_front();
+        }
+        self.next_id += 1;
+        self.jobs.push_back(Job::new(self.next_id.to_string(), block, difficulty));
+        self.jobs.back().unwrap()
+    }
+
+    pub fn current(&self) -> Option<&Job> {
+        self.jobs.back()
+    }
+
+    /// Validates the share of `nonce` for job `job_id`. A nonce solving the block is reported as a block even if it
+    /// does not meet the difficulty of the job, so that no solution gets lost.
+    pub fn submit(&mut self, job_id: &str, nonce: u64) -> SubmittedShare {
+        let Some(job) = self.jobs.iter_mut().find(|job| job.id == job_id) else {
+            return SubmittedShare::new(ShareOutcome::Stale);
+        };
+        if !job.nonces.insert(nonce) {
+            return SubmittedShare::new(ShareOutcome::Duplicate);
+        }
+
+        let (meets_block_target, pow) = job.pow_state.check_pow(nonce);
+        if meets_block_target {
+            let mut header = (*job.block.header).clone();
+            header.nonce = nonce;
+            header.finalize();
+            let block = Block::from_arcs(Arc::new(header), job.block.transactions.clone());
+            return SubmittedShare { outcome: ShareOutcome::Block, block: Some(block) };
+        }
+        if pow <= job.share_target {
+            SubmittedShare::new(ShareOutcome::Accepted)
+        } else {
+            SubmittedShare::new(ShareOutcome::LowDifficulty)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::header::Header;
+
+    fn block(bits: u32, timestamp: u64) -> Block {
+        let mut header = Header::from_precomputed_hash(Hash::from_u64_word(timestamp), vec![Hash::from_u64_word(1)]);
+        header.bits = bits;
+        header.timestamp = timestamp;
+        header.finalize();
+        Block::from_header(header)
+    }
+
+    #[test]
+    fn test_share_classification() {
+        let mut jobs = Jobs::default();
+
+        // With the easiest block target, about half of the nonces solve the block
+        let job_id = jobs.push(block(0x207fffff, 1000), 1.0).id.clone();
+        let solution = (0..).find(|nonce| jobs.submit(&job_id, *nonce).outcome == ShareOutcome::Block).unwrap();
+        let share = jobs.submit(&job_id, solution + 1000);
+        if share.outcome == ShareOutcome::Block {
+            let block = share.block.unwrap();
+            assert_eq!(block.header.nonce, solution + 1000);
+            assert_eq!(block.header.pre_pow_hash(true), jobs.current().unwrap().pre_pow_hash);
+        }
+        assert_eq!(jobs.submit(&job_id, solution).outcome, ShareOutcome::Duplicate);
+
+        // With a hard block target, nonces meeting the share difficulty are accepted as shares
+        let job_id = jobs.push(block(0x1d00ffff, 2000), 1.0 / (1 << 24) as f64).id.clone();
+        let outcomes = (0..100_000u64).map(|nonce| jobs.submit(&job_id, nonce).outcome).collect::<Vec<_>>();
+        assert!(outcomes.contains(&ShareOutcome::Accepted));
+        assert!(outcomes.contains(&ShareOutcome::LowDifficulty));
+        assert!(!outcomes.contains(&ShareOutcome::Block));
+
+        // Jobs beyond the retained count are stale
+        let first_job_id = jobs.push(block(0x1d00ffff, 3000), 1.0).id.clone();
+        for timestamp in 0..Jobs::RETAINED_JOBS as u64 - 1 {
+            jobs.push(block(0x1d00ffff, 4000 + timestamp), 1.0);
+        }
+        assert_ne!(jobs.submit(&first_job_id, 0).outcome, ShareOutcome::Stale);
+        jobs.push(block(0x1d00ffff, 5000), 1.0);
+        assert_eq!(jobs.submit(&first_job_id, 1).outcome, ShareOutcome::Stale);
+        assert_eq!(jobs.submit("unknown", 0).outcome, ShareOutcome::Stale);
+    }
+}
@@ -0,0 +1,102 @@
+//!
+//! Built-in stratum server, allowing stratum speaking miners to mine directly against the node.
+//!
+//! The server speaks the stratum v1 dialect of the Kaspa mining ecosystem, over newline delimited JSON-RPC:
+//!
+//! - `mining.subscribe [agent, ...]` → `[true, "EthereumStratum/1.0.0"]`, followed by a
+//!   `mining.set_extranonce [extranonce, nonce_size]` notification. The extranonce (hex) is the prefix of the 8 bytes
+//!   nonce reserved to the connection, so that miners sharing a payout address never duplicate work, and `nonce_size`
+//!   is the count of nonce bytes left to the miner.
+//! - `mining.extranonce.subscribe []` → `true`.
+//! - `mining.authorize ["<address>[.<worker>]", password]` → `true`. The address receives the coinbase rewards of
+//!   the blocks found by the connection. It is followed by a `mining.set_difficulty [difficulty]` notification and
+//!   by the first job.
+//! - `mining.notify [job_id, [w0, w1, w2, w3], timestamp]` notifications, where `w0..w3` are the little endian u64
+//!   words of the pre-PoW hash of the block template and `timestamp` its timestamp in milliseconds. A new job is
+//!   notified whenever the template changes and whenever the difficulty of the connection is adjusted.
+//! - `mining.submit [worker, job_id, nonce]` → `true`, where `nonce` is the hex encoding (optionally 0x prefixed)
+//!   of either the full 8 bytes nonce, which must start with the extranonce, or of the `nonce_size` bytes left to
+//!   the miner.
+//!
+//! Errors are reported as `[code, message, null]`, with the codes of [`StratumErrorCode`].
+//!
+//! A share is valid if its PoW meets the target of the difficulty the job was issued with, difficulty 1 matching
+//! the target `2^224 - 1`. The difficulty of each connection starts at [`StratumConfig::initial_difficulty`] and is
+//! adjusted so that the connection submits a share every [`StratumConfig::target_share_interval`] on average.
+//! The last [`Jobs::RETAINED_JOBS`] jobs of a connection are accepted, shares of older jobs being stale. Since a block
+//! built on a recent template is still valid in the DAG, a solution of any retained job is submitted as a block.
+//!
+//! Block templates are obtained, and solutions submitted, through a [`StratumBackend`]. The node backend goes through
+//! the usual RPC paths, so templates are served from the mining manager template cache and solutions are validated
+//! and relayed like any submitted block.
+//!
+
+mod jobs;
+mod protocol;
+mod server;
+mod vardiff;
+
+pub use jobs::{Job, Jobs, ShareOutcome, SubmittedShare};
+pub use protocol::{parse_nonce, StratumErrorCode};
+pub use server::{ConnectionStats, StratumServer};
+pub use vardiff::{difficulty_to_target, VarDiff};
+
+use async_trait::async_trait;
+use kaspa_addresses::{Address, Prefix};
+use kaspa_consensus_core::block::Block;
+use std::{net::SocketAddr, time::Duration};
+
+pub type StratumBackendResult<T> = std::result::Result<T, String>;
+
+/// Source of block templates and sink of solved blocks of the stratum server
+#[async_trait]
+pub trait StratumBackend: Send + Sync + 'static {
+    /// A block template paying the coinbase reward to `pay_address`
+    async fn get_block_template(&self, pay_address: Address, extra_data: Vec<u8>) -> StratumBackendResult<Block>;
+
+    /// Submits a solved block, returning an error if the node rejected it
+    async fn submit_block(&self, block: Block) -> StratumBackendResult<()>;
+}
+
+#[derive(Debug, Clone)]
+pub struct StratumConfig {
+    /// Address the server listens on
+    pub listen: SocketAddr,
+
+    /// Network prefix the payout addresses of the miners must match
+    pub prefix: Prefix,
+
+    pub initial_difficulty: f64,
+    pub min_difficulty: f64,
+    pub max_difficulty: f64,
+
+    /// Average interval between two shares of a connection the difficulty is adjusted for
+    pub target_share_interval: Duration,
+
+    /// Minimum interval between two difficulty adjustments of a connection
+    pub retarget_interval: Duration,
+
+    /// Interval at which the template of each connection is checked for changes
+    pub template_poll_interval: Duration,
+
+    /// Count of leading nonce bytes reserved to each connection, from 0 to 3
+    pub extranonce_size: usize,
+}
+
+impl StratumConfig {
+    pub const DEFAULT_PORT: u16 = 5555;
+
+    pub fn new(listen: SocketAddr, prefix: Prefix) -> Self {
+        Self {
+            listen,
+            prefix,
+            initial_difficulty: 4096.0,
+            min_difficulty: 1.0 / 65536.0,
+            max_difficulty: 1e10,
+            target_share_interval: Duration::from_secs(5),
+            retarget_interval: Duration::from_secs(60),
+            template_poll_interval: Duration::from_millis(250),
+            extranonce_size: 2,
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use kaspa_hashes::Hash;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+pub(super) const SUBSCRIBE: &str = "mining.subscribe";
+pub(super) const EXTRANONCE_SUBSCRIBE: &str = "mining.extranonce.subscribe";
+pub(super) const AUTHORIZE: &str = "mining.authorize";
+pub(super) const SUBMIT: &str = "mining.submit";
+pub(super) const SET_EXTRANONCE: &str = "mining.set_extranonce";
+pub(super) const SET_DIFFICULTY: &str = "mining.set_difficulty";
+pub(super) const NOTIFY: &str = "mining.notify";
+
+pub(super) const PROTOCOL_VERSION: &str = "EthereumStratum/1.0.0";
+
+/// Error codes reported in the `[code, message, null]` errors of the responses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StratumErrorCode {
+    Other = 20,
+    StaleJob = 21,
+    DuplicateShare = 22,
+    LowDifficulty = 23,
+    Unauthorized = 24,
+    NotSubscribed = 25,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(super) struct Request {
+    #[serde(default)]
+    pub id: Value,
+    pub method: String,
+    #[serde(default)]
+    pub params: Vec<Value>,
+}
+
+pub(super) fn response(id: &Value, result: Value) -> Value {
+    json!({ "id": id, "result": result, "error": null })
+}
+
+pub(super) fn error_response(id: &Value, code: StratumErrorCode, message: &str) -> Value {
+    json!({ "id": id, "result": null, "error": [code as i32, message, null] })
+}
+
+pub(super) fn notification(method: &str, params: Value) -> Value {
+    json!({ "id": null, "method": method, "params": params })
+}
+
+/// The params of the `mining.notify` notification of a job
+pub(super) fn job_params(job_id: &str, pre_pow_hash: Hash, timestamp: u64) -> Value {
+    json!([job_id, pre_pow_hash.to_le_u64(), timestamp])
+}
+
+/// Parses the hex nonce of a `mining.submit` request into the full nonce. `nonce` can either be the full 8 bytes
+/// nonce, which must start with `extranonce`, or only the bytes following the `extranonce_size` bytes extranonce.
+pub fn parse_nonce(nonce: &str, extranonce: u64, extranonce_size: usize) -> Result<u64, String> {
+    let nonce = nonce.strip_prefix("0x").unwrap_or(nonce);
+    let miner_nonce_size = 8 - extranonce_size;
+    if nonce.is_empty() || nonce.len() > 16 || nonce.len() % 2 != 0 {
+        return Err(format!("invalid nonce length {}", nonce.len()));
+    }
+    let value = u64::from_str_radix(nonce, 16).map_err(|err| format!("invalid nonce: {err}"))?;
+    let extranonce_shift = 8 * miner_nonce_size as u32;
+    match nonce.len() / 2 {
+        8 => {
+            if extranonce_size > 0 && value >> extranonce_shift != extranonce {
+                return Err("the nonce does not start with the extranonce of the connection".to_string());
+            }
+            Ok(value)
+        }
+        size if size == miner_nonce_size => Ok(extranonce.checked_shl(extranonce_shift).unwrap_or_default() | value),
+        size => Err(format!("expected a nonce of 8 or {miner_nonce_size} bytes, got {size}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nonce() {
+        assert_eq!(parse_nonce("0x12340000000000ab", 0x1234, 2), Ok(0x12340000000000ab));
+        assert_eq!(parse_nonce("0000000000ab", 0x1234, 2), Ok(0x12340000000000ab));
+        assert_eq!(parse_nonce("ffffffffffffffff", 0, 0), Ok(u64::MAX));
+        assert!(parse_nonce("43210000000000ab", 0x1234, 2).is_err());
+        assert!(parse_nonce("00ab", 0x1234, 2).is_err());
+        assert!(parse_nonce("0x123", 0x1234, 2).is_err());
+        assert!(parse_nonce("zz00000000ab", 0x1234, 2).is_err());
+    }
+}
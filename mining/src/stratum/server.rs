@@ -0,0 +1,424 @@
+use super::{
+    jobs::{Jobs, ShareOutcome},
+    protocol::{self, error_response, job_params, notification, parse_nonce, response, Request, StratumErrorCode},
+    vardiff::VarDiff,
+    StratumBackend, StratumConfig,
+};
+use kaspa_addresses::Address;
+use kaspa_core::{
+    debug, info,
+    task::service::{AsyncService, AsyncServiceError, AsyncServiceFuture},
+    trace, warn,
+};
+use kaspa_utils::triggers::SingleTrigger;
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    io,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{tcp::OwnedWriteHalf, TcpListener, TcpStream},
+    time::{interval, MissedTickBehavior},
+};
+use triggered::Listener;
+
+/// Extra data of the coinbase of the templates mined through the stratum server
+const EXTRA_DATA: &[u8] = b"stratum";
+
+/// Interval between two logs of the aggregated connection statistics
+const STATS_LOG_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Statistics of a stratum connection
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectionStats {
+    pub id: u64,
+    pub peer: SocketAddr,
+    /// The `mining.authorize` user, if authorized
+    pub worker: Option<String>,
+    pub difficulty: f64,
+    pub accepted_shares: u64,
+    pub stale_shares: u64,
+    pub duplicate_shares: u64,
+    pub low_difficulty_shares: u64,
+    /// Malformed submissions
+    pub invalid_shares: u64,
+    pub blocks_found: u64,
+    /// Solved blocks the node rejected
+    pub blocks_rejected: u64,
+}
+
+impl ConnectionStats {
+    fn new(id: u64, peer: SocketAddr, difficulty: f64) -> Self {
+        Self {
+            id,
+            peer,
+            worker: None,
+            difficulty,
+            accepted_shares: 0,
+            stale_shares: 0,
+            duplicate_shares: 0,
+            low_difficulty_shares: 0,
+            invalid_shares: 0,
+            blocks_found: 0,
+            blocks_rejected: 0,
+        }
+    }
+}
+
+/// Stratum server, serving block templates to stratum miners and submitting their solutions. See the
+/// [module documentation](super) for the dialect spoken by the server.
+pub struct StratumServer {
+    config: StratumConfig,
+    backend: Arc<dyn StratumBackend>,
+    connections: Mutex<HashMap<u64, ConnectionStats>>,
+    next_connection_id: AtomicU64,
+    started: SingleTrigger,
+    shutdown: SingleTrigger,
+}
+
+impl StratumServer {
+    pub const IDENT: &'static str = "stratum-server";
+
+    pub fn new(config: StratumConfig, backend: Arc<dyn StratumBackend>) -> Self {
+        assert!(config.extranonce_size <= 3, "the extranonce cannot exceed 3 bytes");
+        Self {
+            config,
+            backend,
+            connections: Default::default(),
+            next_connection_id: AtomicU64::new(0),
+            started: Default::default(),
+            shutdown: Default::default(),
+        }
+    }
+
+    /// Triggered once the server listens for connections
+    pub fn started(&self) -> Listener {
+        self.started.listener.clone()
+    }
+
+    /// Statistics of the open connections
+    pub fn connection_stats(&self) -> Vec<ConnectionStats> {
+        let mut stats = self.connections.lock().values().cloned().collect::<Vec<_>>();
+        stats.sort_by_key(|stats| stats.id);
+        stats
+    }
+
+    fn update_stats(&self, id: u64, update: impl FnOnce(&mut ConnectionStats)) {
+        if let Some(stats) = self.connections.lock().get_mut(&id) {
+            update(stats);
+        }
+    }
+
+    fn log_stats(&self) {
+        let stats = self.connection_stats();
+        if stats.is_empty() {
+            return;
+        }
+        let sum = |field: fn(&ConnectionStats) -> u64| stats.iter().map(field).sum::<u64>();
+        info!(
+            "Stratum stats: {} connections, {} shares accepted ({} stale, {} duplicate, {} low difficulty, {} invalid), {} blocks found, {} rejected",
+            stats.len(),
+            sum(|s| s.accepted_shares),
+            sum(|s| s.stale_shares),
+            sum(|s| s.duplicate_shares),
+            sum(|s| s.low_difficulty_shares),
+            sum(|s| s.invalid_shares),
+            sum(|s| s.blocks_found),
+            sum(|s| s.blocks_rejected),
+        );
+    }
+
+    async fn serve(self: Arc<Self>, stream: TcpStream, peer: SocketAddr) {
+        let id = self.next_connection_id.fetch_add(1, Ordering::Relaxed);
+        let extranonce_bits = 8 * self.config.extranonce_size as u32;
+        let extranonce = if extranonce_bits == 0 { 0 } else { id % (1 << extranonce_bits) };
+        let vardiff = VarDiff::new(
+            self.config.initial_difficulty,
+            self.config.min_difficulty,
+            self.config.max_difficulty,
+            self.config.target_share_interval,
+            self.config.retarget_interval,
+            Instant::now(),
+        );
+        self.connections.lock().insert(id, ConnectionStats::new(id, peer, vardiff.difficulty()));
+        debug!("Stratum connection {} from {}", id, peer);
+
+        let (reader, writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        let mut connection = Connection {
+            server: self.clone(),
+            id,
+            writer,
+            extranonce,
+            subscribed: false,
+            pay_address: None,
+            worker: String::new(),
+            vardiff,
+            jobs: Jobs::default(),
+            template_error: false,
+        };
+        let mut poll = interval(self.config.template_poll_interval);
+        poll.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let shutdown = self.shutdown.listener.clone();
+        loop {
+            let result = tokio::select! {
+                line = lines.next_line() => match line {
+                    Ok(Some(line)) => connection.handle_line(&line).await,
+                    Ok(None) => break,
+                    Err(err) => Err(err),
+                },
+                _ = poll.tick() => connection.refresh_job(false).await,
+                _ = shutdown.clone() => break,
+            };
+            if let Err(err) = result {
+                debug!("Stratum connection {} from {} failed: {}", id, peer, err);
+                break;
+            }
+        }
+
+        self.connections.lock().remove(&id);
+        debug!("Stratum connection {} from {} closed", id, peer);
+    }
+}
+
+struct Connection {
+    server: Arc<StratumServer>,
+    id: u64,
+    writer: OwnedWriteHalf,
+    extranonce: u64,
+    subscribed: bool,
+    pay_address: Option<Address>,
+    worker: String,
+    vardiff: VarDiff,
+    jobs: Jobs,
+    /// Whether the last template request failed, so that failures are logged once
+    template_error: bool,
+}
+
+impl Connection {
+    async fn send(&mut self, message: Value) -> io::Result<()> {
+        let mut line = message.to_string();
+        line.push('\n');
+        self.writer.write_all(line.as_bytes()).await
+    }
+
+    async fn send_error(&mut self, id: &Value, code: StratumErrorCode, message: &str) -> io::Result<()> {
+        self.send(error_response(id, code, message)).await
+    }
+
+    async fn handle_line(&mut self, line: &str) -> io::Result<()> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(());
+        }
+        let request = match serde_json::from_str::<Request>(line) {
+            Ok(request) => request,
+            Err(err) => return self.send_error(&Value::Null, StratumErrorCode::Other, &format!("invalid request: {err}")).await,
+        };
+        trace!("Stratum connection {} request: {}", self.id, line);
+
+        match request.method.as_str() {
+            protocol::SUBSCRIBE => {
+                self.subscribed = true;
+                self.send(response(&request.id, json!([true, protocol::PROTOCOL_VERSION]))).await?;
+                let extranonce_size = self.server.config.extranonce_size;
+                let extranonce = if extranonce_size == 0 {
+                    String::new()
+                } else {
+                    format!("{:0width$x}", self.extranonce, width = 2 * extranonce_size)
+                };
+                self.send(notification(protocol::SET_EXTRANONCE, json!([extranonce, 8 - extranonce_size]))).await
+            }
+            protocol::EXTRANONCE_SUBSCRIBE => self.send(response(&request.id, json!(true))).await,
+            protocol::AUTHORIZE => self.authorize(request).await,
+            protocol::SUBMIT => self.submit(request).await,
+            method => self.send_error(&request.id, StratumErrorCode::Other, &format!("unknown method {method}")).await,
+        }
+    }
+
+    async fn authorize(&mut self, request: Request) -> io::Result<()> {
+        if !self.subscribed {
+            return self.send_error(&request.id, StratumErrorCode::NotSubscribed, "not subscribed").await;
+        }
+        let Some(user) = request.params.first().and_then(Value::as_str) else {
+            return self.send_error(&request.id, StratumErrorCode::Other, "missing user").await;
+        };
+        let address = user.split_once('.').map_or(user, |(address, _)| address);
+        let pay_address = match Address::try_from(address) {
+            Ok(address) if address.prefix == self.server.config.prefix => address,
+            _ => return self.send_error(&request.id, StratumErrorCode::Unauthorized, "invalid payout address").await,
+        };
+
+        self.pay_address = Some(pay_address);
+        self.worker = user.to_string();
+        let worker = self.worker.clone();
+        self.server.update_stats(self.id, |stats| stats.worker = Some(worker));
+        debug!("Stratum connection {} authorized as {}", self.id, user);
+
+        self.send(response(&request.id, json!(true))).await?;
+        self.send(notification(protocol::SET_DIFFICULTY, json!([self.vardiff.difficulty()]))).await?;
+        self.refresh_job(true).await
+    }
+
+    async fn submit(&mut self, request: Request) -> io::Result<()> {
+        if self.pay_address.is_none() {
+            return self.send_error(&request.id, StratumErrorCode::Unauthorized, "not authorized").await;
+        }
+        let (Some(job_id), Some(nonce)) =
+            (request.params.get(1).and_then(Value::as_str), request.params.get(2).and_then(Value::as_str))
+        else {
+            self.server.update_stats(self.id, |stats| stats.invalid_shares += 1);
+            return self.send_error(&request.id, StratumErrorCode::Other, "expected [worker, job_id, nonce] params").await;
+        };
+        let nonce = match parse_nonce(nonce, self.extranonce, self.server.config.extranonce_size) {
+            Ok(nonce) => nonce,
+            Err(err) => {
+                self.server.update_stats(self.id, |stats| stats.invalid_shares += 1);
+                return self.send_error(&request.id, StratumErrorCode::Other, &err).await;
+            }
+        };
+
+        let share = self.jobs.submit(job_id, nonce);
+        match share.outcome {
+            ShareOutcome::Accepted => self.server.update_stats(self.id, |stats| stats.accepted_shares += 1),
+            ShareOutcome::Block => {
+                let block = share.block.expect("a solved share carries its block");
+                let hash = block.hash();
+                match self.server.backend.submit_block(block).await {
+                    Ok(()) => {
+                        info!("Stratum: block {} found by {}", hash, self.worker);
+                        self.server.update_stats(self.id, |stats| {
+                            stats.accepted_shares += 1;
+                            stats.blocks_found += 1;
+                        });
+                    }
+                    Err(err) => {
+                        warn!("Stratum: block {} found by {} was rejected: {}", hash, self.worker, err);
+                        self.server.update_stats(self.id, |stats| {
+                            stats.accepted_shares += 1;
+                            stats.blocks_rejected += 1;
+                        });
+                    }
+                }
+            }
+            ShareOutcome::Duplicate => {
+                self.server.update_stats(self.id, |stats| stats.duplicate_shares += 1);
+                return self.send_error(&request.id, StratumErrorCode::DuplicateShare, "duplicate share").await;
+            }
+            ShareOutcome::Stale => {
+                self.server.update_stats(self.id, |stats| stats.stale_shares += 1);
+                return self.send_error(&request.id, StratumErrorCode::StaleJob, "job not found").await;
+            }
+            ShareOutcome::LowDifficulty => {
+                self.server.update_stats(self.id, |stats| stats.low_difficulty_shares += 1);
+                return self.send_error(&request.id, StratumErrorCode::LowDifficulty, "low difficulty share").await;
+            }
+        }
+
+        self.send(response(&request.id, json!(true))).await?;
+        if let Some(difficulty) = self.vardiff.record_share(Instant::now()) {
+            self.set_difficulty(difficulty).await?;
+        }
+        Ok(())
+    }
+
+    /// Notifies a new difficulty, together with a job of the current template at this difficulty
+    async fn set_difficulty(&mut self, difficulty: f64) -> io::Result<()> {
+        self.server.update_stats(self.id, |stats| stats.difficulty = difficulty);
+        self.send(notification(protocol::SET_DIFFICULTY, json!([difficulty]))).await?;
+        let Some(block) = self.jobs.current().map(|job| job.block.clone()) else {
+            return Ok(());
+        };
+        self.notify_job(block).await
+    }
+
+    async fn notify_job(&mut self, block: kaspa_consensus_core::block::Block) -> io::Result<()> {
+        let job = self.jobs.push(block, self.vardiff.difficulty());
+        let params = job_params(&job.id, job.pre_pow_hash, job.timestamp());
+        self.send(notification(protocol::NOTIFY, params)).await
+    }
+
+    /// Notifies a new job if the template changed, or unconditionally if `force` is set. Also lowers the difficulty
+    /// of a connection submitting too few shares.
+    async fn refresh_job(&mut self, force: bool) -> io::Result<()> {
+        let Some(pay_address) = self.pay_address.clone() else {
+            return Ok(());
+        };
+        if let Some(difficulty) = self.vardiff.retarget(Instant::now()) {
+            return self.set_difficulty(difficulty).await;
+        }
+
+        let block = match self.server.backend.get_block_template(pay_address, EXTRA_DATA.to_vec()).await {
+            Ok(block) => {
+                self.template_error = false;
+                block
+            }
+            Err(err) => {
+                if !self.template_error {
+                    warn!("Stratum: no block template for {}: {}", self.worker, err);
+                    self.template_error = true;
+                }
+                return Ok(());
+            }
+        };
+        let changed = self.jobs.current().map_or(true, |job| job.pre_pow_hash != block.header.pre_pow_hash(true));
+        if changed || force {
+            self.notify_job(block).await?;
+        }
+        Ok(())
+    }
+}
+
+impl AsyncService for StratumServer {
+    fn ident(self: Arc<Self>) -> &'static str {
+        Self::IDENT
+    }
+
+    fn start(self: Arc<Self>) -> AsyncServiceFuture {
+        trace!("{} starting", Self::IDENT);
+        Box::pin(async move {
+            let listener = TcpListener::bind(self.config.listen)
+                .await
+                .map_err(|err| AsyncServiceError::Service(format!("stratum server failed to bind {}: {}", self.config.listen, err)))?;
+            info!("Stratum server listening on {}", self.config.listen);
+            self.started.trigger.trigger();
+
+            let shutdown = self.shutdown.listener.clone();
+            let mut stats_log = interval(STATS_LOG_INTERVAL);
+            stats_log.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                tokio::select! {
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, peer)) => {
+                            tokio::spawn(self.clone().serve(stream, peer));
+                        }
+                        Err(err) => warn!("Stratum server failed to accept a connection: {}", err),
+                    },
+                    _ = stats_log.tick() => self.log_stats(),
+                    _ = shutdown.clone() => break,
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn signal_exit(self: Arc<Self>) {
+        trace!("sending an exit signal to {}", Self::IDENT);
+        self.shutdown.trigger.trigger();
+    }
+
+    fn stop(self: Arc<Self>) -> AsyncServiceFuture {
+        Box::pin(async move {
+            trace!("{} stopped", Self::IDENT);
+            Ok(())
+        })
+    }
+}
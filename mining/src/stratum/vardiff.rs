@@ -0,0 +1,150 @@
+use kaspa_math::Uint256;
+use std::time::{Duration, Instant};
+
+/// Bound of the factor applied to the difficulty by a single adjustment
+const MAX_ADJUSTMENT_FACTOR: f64 = 4.0;
+
+/// Relative change of the difficulty below which no adjustment is made
+const MIN_ADJUSTMENT: f64 = 0.1;
+
+/// Fixed point scale of the difficulty when converted to a target
+const DIFFICULTY_SCALE_BITS: u32 = 30;
+
+/// The share target of `difficulty`, difficulty 1 matching the target `2^224 - 1`
+pub fn difficulty_to_target(difficulty: f64) -> Uint256 {
+    let diff1_target = Uint256::MAX >> 32;
+    let scaled_difficulty = (difficulty * (1u64 << DIFFICULTY_SCALE_BITS) as f64).round().clamp(1.0, u64::MAX as f64) as u64;
+    // The quotient is at most 2^224, so scaling it back cannot overflow
+    (diff1_target / scaled_difficulty) << DIFFICULTY_SCALE_BITS
+}
+
+/// Variable difficulty of a connection, adjusted after the rate of its shares
+#[derive(Debug, Clone)]
+pub struct VarDiff {
+    difficulty: f64,
+    min_difficulty: f64,
+    max_difficulty: f64,
+    target_share_interval: Duration,
+    retarget_interval: Duration,
+    window_start: Instant,
+    window_shares: u32,
+}
+
+impl VarDiff {
+    pub fn new(
+        initial_difficulty: f64,
+        min_difficulty: f64,
+        max_difficulty: f64,
+        target_share_interval: Duration,
+        retarget_interval: Duration,
+        now: Instant,
+    ) -> Self {
+        Self {
+            difficulty: initial_difficulty.clamp(min_difficulty, max_difficulty),
+            min_difficulty,
+            max_difficulty,
+            target_share_interval,
+            retarget_interval,
+            window_start: now,
+            window_shares: 0,
+        }
+    }
+
+    pub fn difficulty(&self) -> f64 {
+        self.difficulty
+    }
+
+    /// Records a valid share, returning the new difficulty if it got adjusted
+    pub fn record_share(&mut self, now: Instant) -> Option<f64> {
+        self.window_shares += 1;
+        self.retarget(now)
+    }
+
+    /// Adjusts the difficulty if the retarget interval elapsed, returning the new difficulty if it got adjusted.
+    /// Expected to be called periodically, so that the difficulty of connections finding no shares gets lowered.
+    pub fn retarget(&mut self, now: Instant) -> Option<f64> {
+        let elapsed = now.saturating_duration_since(self.window_start);
+        if elapsed < self.retarget_interval {
+            return None;
+        }
+
+        // With no share in the window, the actual rate is only known to be lower than one share per window
+        let observed_interval = elapsed.as_secs_f64() / self.window_shares.max(1) as f64;
+        let factor = if self.window_shares == 0 {
+            (self.target_share_interval.as_secs_f64() / observed_interval).min(0.5)
+        } else {
+            self.target_share_interval.as_secs_f64() / observed_interval
+        };
+        let factor = factor.clamp(1.0 / MAX_ADJUSTMENT_FACTOR, MAX_ADJUSTMENT_FACTOR);
+
+        self.window_start = now;
+        self.window_shares = 0;
+
+        let difficulty = (self.difficulty * factor).clamp(self.min_difficulty, self.max_difficulty);
+        if (difficulty - self.difficulty).abs() <= self.difficulty * MIN_ADJUSTMENT {
+            return None;
+        }
+        self.difficulty = difficulty;
+        Some(difficulty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_difficulty_to_target() {
+        assert_eq!(difficulty_to_target(1.0), ((Uint256::MAX >> 32) / (1 << 30)) << 30);
+        assert!(difficulty_to_target(2.0) < difficulty_to_target(1.0));
+        assert!(difficulty_to_target(0.5) > difficulty_to_target(1.0));
+        // Difficulties below the fixed point precision are clamped
+        assert_eq!(difficulty_to_target(1e-10), difficulty_to_target(1.0 / (1u64 << 30) as f64));
+        assert!(difficulty_to_target(1e10) > Uint256::from_u64(0));
+    }
+
+    #[test]
+    fn test_vardiff_retarget() {
+        let start = Instant::now();
+        let interval = Duration::from_secs(5);
+        let retarget_interval = Duration::from_secs(60);
+        let mut vardiff = VarDiff::new(100.0, 1.0, 1000.0, interval, retarget_interval, start);
+
+        // Shares twice as frequent as the target double the difficulty at the end of the window
+        for i in 1..24 {
+            assert_eq!(vardiff.record_share(start + Duration::from_millis(2500 * i)), None);
+        }
+        assert_eq!(vardiff.record_share(start + retarget_interval), Some(200.0));
+
+        // Shares at the target rate keep the difficulty
+        let window_start = start + retarget_interval;
+        for i in 1..12 {
+            assert_eq!(vardiff.record_share(window_start + interval * i), None);
+        }
+        assert_eq!(vardiff.record_share(window_start + retarget_interval), None);
+        assert_eq!(vardiff.difficulty(), 200.0);
+
+        // A window without shares at least halves the difficulty
+        let window_start = window_start + retarget_interval;
+        assert_eq!(vardiff.retarget(window_start + retarget_interval / 2), None);
+        assert_eq!(vardiff.retarget(window_start + retarget_interval), Some(50.0));
+
+        // A single adjustment is bounded, and so is the difficulty
+        let window_start = window_start + retarget_interval;
+        for _ in 0..1000 {
+            vardiff.record_share(window_start);
+        }
+        assert_eq!(vardiff.record_share(window_start + retarget_interval), Some(200.0));
+        let window_start = window_start + retarget_interval;
+        for _ in 0..1000 {
+            vardiff.record_share(window_start);
+        }
+        vardiff.record_share(window_start + retarget_interval);
+        assert_eq!(vardiff.difficulty(), 800.0);
+        let window_start = window_start + retarget_interval;
+        for _ in 0..1000 {
+            vardiff.record_share(window_start);
+        }
+        assert_eq!(vardiff.record_share(window_start + retarget_interval), Some(1000.0));
+    }
+}
@@ -2,6 +2,7 @@ use super::coinbase_mock::CoinbaseManagerMock;
 use kaspa_consensus_core::{
     api::ConsensusApi,
     block::{BlockTemplate, MutableBlock, TemplateBuildMode, TemplateTransactionSelector, VirtualStateApproxId},
+    blockstatus::BlockStatus,
     coinbase::MinerData,
     constants::BLOCK_VERSION,
     errors::{
@@ -10,21 +11,32 @@ use kaspa_consensus_core::{
         tx::{TxResult, TxRuleError},
     },
     header::Header,
+    locktime,
     mass::transaction_estimated_serialized_size,
     merkle::calc_hash_merkle_root,
     tx::{MutableTransaction, Transaction, TransactionId, TransactionOutpoint, UtxoEntry},
-    utxo::utxo_collection::UtxoCollection,
+    utxo::{utxo_collection::UtxoCollection, utxo_diff::UtxoDiff},
+    BlockHashSet,
 };
 use kaspa_core::time::unix_now;
-use kaspa_hashes::ZERO_HASH;
+use kaspa_hashes::{Hash, ZERO_HASH};
 
 use parking_lot::RwLock;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
 pub(crate) struct ConsensusMock {
     transactions: RwLock<HashMap<TransactionId, Arc<Transaction>>>,
     statuses: RwLock<HashMap<TransactionId, TxResult<()>>>,
     utxos: RwLock<UtxoCollection>,
+    virtual_daa_score: AtomicU64,
+    virtual_parents: RwLock<Vec<Hash>>,
+    blocks: RwLock<BlockHashSet>,
 }
 
 impl ConsensusMock {
@@ -33,9 +45,22 @@ impl ConsensusMock {
             transactions: RwLock::new(HashMap::default()),
             statuses: RwLock::new(HashMap::default()),
             utxos: RwLock::new(HashMap::default()),
+            virtual_daa_score: AtomicU64::new(0),
+            virtual_parents: RwLock::new(vec![]),
+            blocks: RwLock::new(BlockHashSet::default()),
         }
     }
 
+    pub(crate) fn set_virtual_daa_score(&self, virtual_daa_score: u64) {
+        self.virtual_daa_score.store(virtual_daa_score, Ordering::SeqCst);
+    }
+
+    /// Registers a processed block, making it the single virtual parent
+    pub(crate) fn add_block(&self, hash: Hash) {
+        self.blocks.write().insert(hash);
+        *self.virtual_parents.write() = vec![hash];
+    }
+
     pub(crate) fn set_status(&self, transaction_id: TransactionId, status: TxResult<()>) {
         self.statuses.write().insert(transaction_id, status);
     }
@@ -60,6 +85,19 @@ impl ConsensusMock {
         transactions.insert(transaction.id(), transaction.tx);
     }
 
+    /// Applies a virtual UTXO change to the UTXO set, as a virtual chain change, possibly including a reorg, would
+    pub(crate) fn apply_utxo_diff(&self, utxo_diff: &UtxoDiff) {
+        let mut utxos = self.utxos.write();
+        utxo_diff.remove.keys().for_each(|outpoint| {
+            utxos.remove(outpoint);
+        });
+        utxos.extend(utxo_diff.add.iter().map(|(outpoint, entry)| (*outpoint, entry.clone())));
+    }
+
+    pub(crate) fn has_utxo(&self, outpoint: &TransactionOutpoint) -> bool {
+        self.utxos.read().contains_key(outpoint)
+    }
+
     pub(crate) fn can_finance_transaction(&self, transaction: &MutableTransaction) -> bool {
         let utxos = self.utxos.read();
         for outpoint in transaction.missing_outpoints() {
@@ -84,9 +122,11 @@ impl ConsensusApi for ConsensusMock {
         txs.insert(0, coinbase.tx);
         let now = unix_now();
         let hash_merkle_root = calc_hash_merkle_root(txs.iter());
+        let virtual_parents = self.virtual_parents.read().clone();
+        let parents_by_level = if virtual_parents.is_empty() { vec![] } else { vec![virtual_parents] };
         let header = Header::new_finalized(
             BLOCK_VERSION,
-            vec![],
+            parents_by_level,
             hash_merkle_root,
             ZERO_HASH,
             ZERO_HASH,
@@ -100,7 +140,8 @@ impl ConsensusApi for ConsensusMock {
         );
         let mutable_block = MutableBlock::new(header, txs);
 
-        Ok(BlockTemplate::new(mutable_block, miner_data, coinbase.has_red_reward, now, 0, ZERO_HASH))
+        Ok(BlockTemplate::new(mutable_block, miner_data, coinbase.has_red_reward, now, 0, ZERO_HASH)
+            .with_selection_stats(tx_selector.selection_stats()))
     }
 
     fn validate_mempool_transaction(&self, mutable_tx: &mut MutableTransaction) -> TxResult<()> {
@@ -110,6 +151,12 @@ impl ConsensusApi for ConsensusMock {
                 return status.clone();
             }
         }
+        // Simulate the consensus finality rule in the context of the mocked virtual state
+        if let Some(i) =
+            locktime::first_non_final_input(&mutable_tx.tx, self.get_virtual_daa_score(), self.get_virtual_past_median_time())
+        {
+            return Err(TxRuleError::NotFinalized(i));
+        }
         let utxos = self.utxos.read();
         let mut has_missing_outpoints = false;
         for i in 0..mutable_tx.tx.inputs.len() {
@@ -159,13 +206,25 @@ impl ConsensusApi for ConsensusMock {
     }
 
     fn get_virtual_daa_score(&self) -> u64 {
-        0
+        self.virtual_daa_score.load(Ordering::SeqCst)
+    }
+
+    fn get_virtual_past_median_time(&self) -> u64 {
+        unix_now()
     }
 
     fn get_virtual_state_approx_id(&self) -> VirtualStateApproxId {
         VirtualStateApproxId::new(self.get_virtual_daa_score(), 0.into(), ZERO_HASH)
     }
 
+    fn get_virtual_parents(&self) -> BlockHashSet {
+        self.virtual_parents.read().iter().copied().collect()
+    }
+
+    fn get_block_status(&self, hash: Hash) -> Option<BlockStatus> {
+        self.blocks.read().contains(&hash).then_some(BlockStatus::StatusUTXOValid)
+    }
+
     fn modify_coinbase_payload(&self, payload: Vec<u8>, miner_data: &MinerData) -> CoinbaseResult<Vec<u8>> {
         let coinbase_manager = CoinbaseManagerMock::new();
         Ok(coinbase_manager.modify_coinbase_payload(payload, miner_data))
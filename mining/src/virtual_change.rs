@@ -0,0 +1,111 @@
+use crate::manager::MiningManagerProxy;
+use kaspa_consensus_notify::{
+    connection::ConsensusChannelConnection, notification::Notification as ConsensusNotification, notifier::ConsensusNotifier,
+};
+use kaspa_consensusmanager::ConsensusManager;
+use kaspa_core::{
+    task::service::{AsyncService, AsyncServiceFuture},
+    trace,
+};
+use kaspa_notify::{
+    connection::ChannelType,
+    listener::ListenerLifespan,
+    scope::{UtxosChangedScope, VirtualChainChangedScope},
+    subscription::{MutationPolicies, UtxosChangedMutationPolicy},
+};
+use kaspa_utils::{channel::Channel, triggers::SingleTrigger};
+use std::sync::Arc;
+
+const SERVICE: &str = "mempool-virtual-change";
+
+/// Service keeping the mempool in line with the virtual UTXO set by revalidating, on every virtual change,
+/// the transactions spending outpoints of the accumulated UTXO diff, and by registering, on every virtual
+/// chain change, the chain blocks accepting the recently accepted transactions.
+///
+/// See [`crate::manager::MiningManager::handle_virtual_utxo_diff`] and
+/// [`crate::manager::MiningManager::handle_virtual_chain_changed`]
+pub struct MempoolVirtualChangeService {
+    mining_manager: MiningManagerProxy,
+    consensus_manager: Arc<ConsensusManager>,
+    consensus_notify_channel: Channel<ConsensusNotification>,
+    shutdown: SingleTrigger,
+}
+
+impl MempoolVirtualChangeService {
+    pub fn new(
+        consensus_notifier: &Arc<ConsensusNotifier>,
+        consensus_manager: Arc<ConsensusManager>,
+        mining_manager: MiningManagerProxy,
+    ) -> Self {
+        let policies = MutationPolicies::new(UtxosChangedMutationPolicy::Wildcard);
+        let consensus_notify_channel = Channel::<ConsensusNotification>::default();
+        let consensus_notify_listener_id = consensus_notifier.register_new_listener(
+            ConsensusChannelConnection::new(SERVICE, consensus_notify_channel.sender(), ChannelType::Closable),
+            ListenerLifespan::Static(policies),
+        );
+        consensus_notifier
+            .try_start_notify(consensus_notify_listener_id, UtxosChangedScope::wildcard().into())
+            .expect("the subscription always succeeds");
+        // The acceptance data is only retained by the notifier when accepted transaction ids are requested
+        consensus_notifier
+            .try_start_notify(consensus_notify_listener_id, VirtualChainChangedScope::new(true).into())
+            .expect("the subscription always succeeds");
+
+        Self { mining_manager, consensus_manager, consensus_notify_channel, shutdown: SingleTrigger::default() }
+    }
+}
+
+impl AsyncService for MempoolVirtualChangeService {
+    fn ident(self: Arc<Self>) -> &'static str {
+        SERVICE
+    }
+
+    fn start(self: Arc<Self>) -> AsyncServiceFuture {
+        trace!("{} starting", SERVICE);
+        let shutdown_signal = self.shutdown.listener.clone();
+        let receiver = self.consensus_notify_channel.receiver();
+
+        Box::pin(async move {
+            tokio::pin!(shutdown_signal);
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown_signal => break,
+                    notification = receiver.recv() => match notification {
+                        Ok(ConsensusNotification::UtxosChanged(notification)) => {
+                            let consensus = self.consensus_manager.consensus().session().await;
+                            self.mining_manager.clone().handle_virtual_utxo_diff(&consensus, notification.accumulated_utxo_diff).await;
+                        }
+                        Ok(ConsensusNotification::VirtualChainChanged(notification)) => {
+                            let consensus = self.consensus_manager.consensus().session().await;
+                            self.mining_manager
+                                .clone()
+                                .handle_virtual_chain_changed(
+                                    &consensus,
+                                    notification.added_chain_block_hashes,
+                                    notification.removed_chain_block_hashes,
+                                    notification.added_chain_blocks_acceptance_data,
+                                )
+                                .await;
+                        }
+                        Ok(_) => {}
+                        Err(_) => break,
+                    },
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn signal_exit(self: Arc<Self>) {
+        trace!("sending an exit signal to {}", SERVICE);
+        self.shutdown.trigger.trigger();
+    }
+
+    fn stop(self: Arc<Self>) -> AsyncServiceFuture {
+        Box::pin(async move {
+            self.consensus_notify_channel.close();
+            trace!("{} stopped", SERVICE);
+            Ok(())
+        })
+    }
+}
@@ -3,7 +3,7 @@ use crate::subscription::context::SubscriptionContext;
 use super::{
     events::EventType,
     subscription::{
-        single::{OverallSubscription, UtxosChangedSubscription, VirtualChainChangedSubscription},
+        single::{OverallSubscription, TransactionConflictSubscription, UtxosChangedSubscription, VirtualChainChangedSubscription},
         Single,
     },
 };
@@ -22,6 +22,12 @@ pub trait Notification: Clone + Debug + Display + Send + Sync + 'static {
     fn apply_utxos_changed_subscription(&self, subscription: &UtxosChangedSubscription, context: &SubscriptionContext)
         -> Option<Self>;
 
+    fn apply_transaction_conflict_subscription(
+        &self,
+        subscription: &TransactionConflictSubscription,
+        context: &SubscriptionContext,
+    ) -> Option<Self>;
+
     fn apply_subscription(&self, subscription: &dyn Single, context: &SubscriptionContext) -> Option<Self> {
         match subscription.event_type() {
             EventType::VirtualChainChanged => self.apply_virtual_chain_changed_subscription(
@@ -30,6 +36,10 @@ pub trait Notification: Clone + Debug + Display + Send + Sync + 'static {
             ),
             EventType::UtxosChanged => self
                 .apply_utxos_changed_subscription(subscription.as_any().downcast_ref::<UtxosChangedSubscription>().unwrap(), context),
+            EventType::TransactionConflict => self.apply_transaction_conflict_subscription(
+                subscription.as_any().downcast_ref::<TransactionConflictSubscription>().unwrap(),
+                context,
+            ),
             _ => self.apply_overall_subscription(subscription.as_any().downcast_ref::<OverallSubscription>().unwrap(), context),
         }
     }
@@ -178,6 +188,17 @@ pub mod test_helpers {
             }
         }
 
+        fn apply_transaction_conflict_subscription(
+            &self,
+            subscription: &TransactionConflictSubscription,
+            _: &SubscriptionContext,
+        ) -> Option<Self> {
+            match subscription.active() {
+                true => Some(self.clone()),
+                false => None,
+            }
+        }
+
         fn event_type(&self) -> EventType {
             self.into()
         }
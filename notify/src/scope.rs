@@ -44,6 +44,7 @@ pub enum Scope {
     VirtualDaaScoreChanged,
     PruningPointUtxoSetOverride,
     NewBlockTemplate,
+    TransactionConflict,
 }
 }
 
@@ -79,6 +80,15 @@ pub struct FinalityConflictScope {}
 #[derive(Clone, Display, Debug, PartialEq, Eq, Default, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct FinalityConflictResolvedScope {}
 
+/// Scope of UtxosChanged notifications, selecting the addresses whose UTXO changes get notified.
+///
+/// An empty address list is a wildcard, matching the UTXO changes of all addresses, including
+/// addresses never seen before. Starting a wildcard subscription replaces any address selection
+/// and stopping it cancels the whole subscription, whatever its current selection.
+///
+/// Wildcard subscriptions yield the full UTXO changes of every block, so a listener consuming them
+/// slower than they are produced may see its connection queue overflow, in which case the
+/// connection gets closed and removed from the notification broadcasting.
 #[derive(Clone, Debug, Default, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct UtxosChangedScope {
     pub addresses: Vec<Address>,
@@ -107,6 +117,15 @@ impl UtxosChangedScope {
     pub fn new(addresses: Vec<Address>) -> Self {
         Self { addresses }
     }
+
+    /// Creates a wildcard scope, matching all addresses
+    pub fn wildcard() -> Self {
+        Self { addresses: vec![] }
+    }
+
+    pub fn is_wildcard(&self) -> bool {
+        self.addresses.is_empty()
+    }
 }
 
 #[derive(Clone, Display, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
@@ -120,3 +139,46 @@ pub struct PruningPointUtxoSetOverrideScope {}
 
 #[derive(Clone, Display, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct NewBlockTemplateScope {}
+
+/// Scope of TransactionConflict notifications, selecting the addresses whose conflicting transactions get notified.
+///
+/// A conflict involves an address if any of the two conflicting transactions pays to it or spends from it. An empty
+/// address list is a wildcard, matching the conflicts of all addresses.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct TransactionConflictScope {
+    pub addresses: Vec<Address>,
+}
+
+impl std::fmt::Display for TransactionConflictScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let addresses = match self.addresses.len() {
+            0 => "all".to_string(),
+            1 => format!("{}", self.addresses[0]),
+            n => format!("{} addresses", n),
+        };
+        write!(f, "TransactionConflictScope ({})", addresses)
+    }
+}
+
+impl PartialEq for TransactionConflictScope {
+    fn eq(&self, other: &Self) -> bool {
+        self.addresses.len() == other.addresses.len() && self.addresses.iter().all(|x| other.addresses.contains(x))
+    }
+}
+
+impl Eq for TransactionConflictScope {}
+
+impl TransactionConflictScope {
+    pub fn new(addresses: Vec<Address>) -> Self {
+        Self { addresses }
+    }
+
+    /// Creates a wildcard scope, matching all addresses
+    pub fn wildcard() -> Self {
+        Self { addresses: vec![] }
+    }
+
+    pub fn is_wildcard(&self) -> bool {
+        self.addresses.is_empty()
+    }
+}
@@ -18,6 +18,9 @@ impl ArrayBuilder {
                     listener_id,
                     utxos_changed_capacity.unwrap_or_default(),
                 )),
+                EventType::TransactionConflict => {
+                    Arc::new(single::TransactionConflictSubscription::new(single::UtxosChangedState::None, listener_id))
+                }
                 _ => Arc::new(single::OverallSubscription::new(event_type, false)),
             };
             subscription
@@ -188,11 +188,11 @@ impl Compounded for UtxosChangedSubscription {
         if let Scope::UtxosChanged(scope) = mutation.scope {
             match mutation.command {
                 Command::Start => {
-                    if scope.addresses.is_empty() {
+                    if scope.is_wildcard() {
                         // Add All
                         self.all += 1;
                         if self.all == 1 {
-                            return Some(Mutation::new(Command::Start, UtxosChangedScope::default().into()));
+                            return Some(Mutation::new(Command::Start, UtxosChangedScope::wildcard().into()));
                         }
                     } else {
                         // Add(A)
@@ -203,7 +203,7 @@ impl Compounded for UtxosChangedSubscription {
                     }
                 }
                 Command::Stop => {
-                    if !scope.addresses.is_empty() {
+                    if !scope.is_wildcard() {
                         // Remove(R)
                         let removed = self.unregister(scope.addresses, context);
                         if !removed.is_empty() && self.all == 0 {
@@ -218,7 +218,7 @@ impl Compounded for UtxosChangedSubscription {
                             if !addresses.is_empty() {
                                 return Some(Mutation::new(Command::Start, UtxosChangedScope::new(addresses).into()));
                             } else {
-                                return Some(Mutation::new(Command::Stop, UtxosChangedScope::default().into()));
+                                return Some(Mutation::new(Command::Stop, UtxosChangedScope::wildcard().into()));
                             }
                         }
                     }
@@ -3,7 +3,7 @@ use crate::{
     error::Result,
     events::EventType,
     listener::ListenerId,
-    scope::{Scope, UtxosChangedScope, VirtualChainChangedScope},
+    scope::{Scope, TransactionConflictScope, UtxosChangedScope, VirtualChainChangedScope},
     subscription::{
         context::SubscriptionContext, BroadcastingSingle, Command, DynSubscription, Mutation, MutationOutcome, MutationPolicies,
         Single, Subscription, UtxosChangedMutationPolicy,
@@ -184,7 +184,7 @@ enum UtxosChangedMutation {
 
 impl From<(Command, &UtxosChangedScope)> for UtxosChangedMutation {
     fn from((command, scope): (Command, &UtxosChangedScope)) -> Self {
-        match (command, scope.addresses.is_empty()) {
+        match (command, scope.is_wildcard()) {
             (Command::Stop, true) => Self::None,
             (Command::Stop, false) => Self::Remove,
             (Command::Start, false) => Self::Add,
@@ -433,7 +433,7 @@ impl Single for UtxosChangedSubscription {
                             vec![Mutation::new(mutation.command, UtxosChangedScope::new(addresses).into())]
                         }
                         UtxosChangedMutationPolicy::Wildcard => {
-                            vec![Mutation::new(mutation.command, UtxosChangedScope::default().into())]
+                            vec![Mutation::new(mutation.command, UtxosChangedScope::wildcard().into())]
                         }
                     };
                     MutationOutcome::with_mutated(current.clone(), mutations)
@@ -441,7 +441,7 @@ impl Single for UtxosChangedSubscription {
                 (UtxosChangedState::None, UtxosChangedMutation::All) => {
                     // State None + Mutation All => Mutated new state All
                     data.update_state(UtxosChangedState::All);
-                    let mutations = vec![Mutation::new(mutation.command, UtxosChangedScope::default().into())];
+                    let mutations = vec![Mutation::new(mutation.command, UtxosChangedScope::wildcard().into())];
                     MutationOutcome::with_mutated(current.clone(), mutations)
                 }
                 (UtxosChangedState::Selected, UtxosChangedMutation::None) => {
@@ -454,7 +454,7 @@ impl Single for UtxosChangedSubscription {
                             vec![Mutation::new(Command::Stop, UtxosChangedScope::new(removed).into())]
                         }
                         UtxosChangedMutationPolicy::Wildcard => {
-                            vec![Mutation::new(Command::Stop, UtxosChangedScope::default().into())]
+                            vec![Mutation::new(Command::Stop, UtxosChangedScope::wildcard().into())]
                         }
                     };
                     MutationOutcome::with_mutated(current.clone(), mutations)
@@ -479,7 +479,7 @@ impl Single for UtxosChangedSubscription {
                                     vec![Mutation::new(Command::Stop, UtxosChangedScope::new(removed).into())]
                                 }
                                 UtxosChangedMutationPolicy::Wildcard => {
-                                    vec![Mutation::new(Command::Stop, UtxosChangedScope::default().into())]
+                                    vec![Mutation::new(Command::Stop, UtxosChangedScope::wildcard().into())]
                                 }
                             };
                             MutationOutcome::with_mutated(current.clone(), mutations)
@@ -511,7 +511,7 @@ impl Single for UtxosChangedSubscription {
                     let mutations = match policies.utxo_changed {
                         UtxosChangedMutationPolicy::AddressSet => vec![
                             Mutation::new(Command::Stop, UtxosChangedScope::new(removed).into()),
-                            Mutation::new(Command::Start, UtxosChangedScope::default().into()),
+                            Mutation::new(Command::Start, UtxosChangedScope::wildcard().into()),
                         ],
                         UtxosChangedMutationPolicy::Wildcard => vec![],
                     };
@@ -520,7 +520,7 @@ impl Single for UtxosChangedSubscription {
                 (UtxosChangedState::All, UtxosChangedMutation::None) => {
                     // State All + Mutation None => Mutated new state None
                     data.update_state(UtxosChangedState::None);
-                    let mutations = vec![Mutation::new(Command::Stop, UtxosChangedScope::default().into())];
+                    let mutations = vec![Mutation::new(Command::Stop, UtxosChangedScope::wildcard().into())];
                     MutationOutcome::with_mutated(current.clone(), mutations)
                 }
                 (UtxosChangedState::All, UtxosChangedMutation::Remove) => {
@@ -534,7 +534,7 @@ impl Single for UtxosChangedSubscription {
                     let mutations = match policies.utxo_changed {
                         UtxosChangedMutationPolicy::AddressSet => vec![
                             Mutation::new(Command::Start, UtxosChangedScope::new(added).into()),
-                            Mutation::new(Command::Stop, UtxosChangedScope::default().into()),
+                            Mutation::new(Command::Stop, UtxosChangedScope::wildcard().into()),
                         ],
                         UtxosChangedMutationPolicy::Wildcard => vec![],
                     };
@@ -567,6 +567,172 @@ impl Subscription for UtxosChangedSubscription {
     }
 }
 
+/// Subscription to TransactionConflict notifications, selecting the conflicts involving a set of addresses or all of them.
+///
+/// The addresses are registered in the address tracker of the [`SubscriptionContext`], just like the ones of a
+/// [`UtxosChangedSubscription`], sharing its inner data structure.
+#[derive(Debug)]
+pub struct TransactionConflictSubscription {
+    /// Mutable inner data
+    data: RwLock<UtxosChangedSubscriptionData>,
+
+    /// ID of the listener owning this subscription
+    ///
+    /// This fully determines both equality and hash.
+    listener_id: ListenerId,
+}
+
+impl TransactionConflictSubscription {
+    pub fn new(state: UtxosChangedState, listener_id: ListenerId) -> Self {
+        Self::with_capacity(state, listener_id, 0)
+    }
+
+    pub fn with_capacity(state: UtxosChangedState, listener_id: ListenerId, capacity: usize) -> Self {
+        Self { data: RwLock::new(UtxosChangedSubscriptionData::with_capacity(state, capacity)), listener_id }
+    }
+
+    #[cfg(test)]
+    pub fn with_addresses(active: bool, addresses: Vec<Address>, listener_id: ListenerId, context: &SubscriptionContext) -> Self {
+        let state = match (active, addresses.is_empty()) {
+            (false, _) => UtxosChangedState::None,
+            (true, false) => UtxosChangedState::Selected,
+            (true, true) => UtxosChangedState::All,
+        };
+        let subscription = Self::with_capacity(state, listener_id, addresses.len());
+        let _ = subscription.data_mut().register(addresses, context);
+        subscription
+    }
+
+    pub fn data(&self) -> RwLockReadGuard<UtxosChangedSubscriptionData> {
+        self.data.read()
+    }
+
+    pub fn data_mut(&self) -> RwLockWriteGuard<UtxosChangedSubscriptionData> {
+        self.data.write()
+    }
+
+    #[inline(always)]
+    pub fn state(&self) -> UtxosChangedState {
+        self.data().state
+    }
+
+    pub fn to_all(&self) -> bool {
+        matches!(self.data().state, UtxosChangedState::All)
+    }
+}
+
+impl Clone for TransactionConflictSubscription {
+    fn clone(&self) -> Self {
+        Self { data: RwLock::new(self.data().clone()), listener_id: self.listener_id }
+    }
+}
+
+impl Display for TransactionConflictSubscription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.data())
+    }
+}
+
+impl PartialEq for TransactionConflictSubscription {
+    /// Equality is specifically bound to the listener ID
+    fn eq(&self, other: &Self) -> bool {
+        self.listener_id == other.listener_id
+    }
+}
+impl Eq for TransactionConflictSubscription {}
+
+impl Hash for TransactionConflictSubscription {
+    /// Hash is specifically bound to the listener ID
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.listener_id.hash(state);
+    }
+}
+
+impl Single for TransactionConflictSubscription {
+    /// Applies the mutation to the address selection of the subscription.
+    ///
+    /// No parent notifier is selecting conflicts by address, so the outcome mutations only reflect the activation
+    /// and deactivation of the subscription, always with a wildcard scope.
+    fn apply_mutation(
+        &self,
+        current: &Arc<dyn Single>,
+        mutation: Mutation,
+        _policies: MutationPolicies,
+        context: &SubscriptionContext,
+    ) -> Result<MutationOutcome> {
+        assert_eq!(self.event_type(), mutation.event_type());
+        let outcome = if let Scope::TransactionConflict(scope) = mutation.scope {
+            let mut data = self.data_mut();
+            let start = || vec![Mutation::new(Command::Start, TransactionConflictScope::wildcard().into())];
+            let stop = || vec![Mutation::new(Command::Stop, TransactionConflictScope::wildcard().into())];
+            match (data.state, mutation.command, scope.is_wildcard()) {
+                (UtxosChangedState::None, Command::Stop, _) | (UtxosChangedState::All, Command::Stop, false) => MutationOutcome::new(),
+                (UtxosChangedState::None, Command::Start, false) => {
+                    data.register(scope.addresses, context)?;
+                    data.update_state(UtxosChangedState::Selected);
+                    MutationOutcome::with_mutated(current.clone(), start())
+                }
+                (UtxosChangedState::None, Command::Start, true) => {
+                    data.update_state(UtxosChangedState::All);
+                    MutationOutcome::with_mutated(current.clone(), start())
+                }
+                (UtxosChangedState::Selected, Command::Stop, true) => {
+                    data.unregister_indexes(context);
+                    data.update_state(UtxosChangedState::None);
+                    MutationOutcome::with_mutated(current.clone(), stop())
+                }
+                (UtxosChangedState::Selected, Command::Stop, false) => {
+                    data.unregister(scope.addresses, context);
+                    match data.is_empty() {
+                        true => {
+                            data.update_state(UtxosChangedState::None);
+                            MutationOutcome::with_mutated(current.clone(), stop())
+                        }
+                        false => MutationOutcome::new(),
+                    }
+                }
+                (UtxosChangedState::Selected, Command::Start, false) => {
+                    data.register(scope.addresses, context)?;
+                    MutationOutcome::new()
+                }
+                (UtxosChangedState::Selected, Command::Start, true) => {
+                    data.unregister_indexes(context);
+                    data.update_state(UtxosChangedState::All);
+                    MutationOutcome::new()
+                }
+                (UtxosChangedState::All, Command::Stop, true) => {
+                    data.update_state(UtxosChangedState::None);
+                    MutationOutcome::with_mutated(current.clone(), stop())
+                }
+                (UtxosChangedState::All, Command::Start, false) => {
+                    data.register(scope.addresses, context)?;
+                    data.update_state(UtxosChangedState::Selected);
+                    MutationOutcome::new()
+                }
+                (UtxosChangedState::All, Command::Start, true) => MutationOutcome::new(),
+            }
+        } else {
+            MutationOutcome::new()
+        };
+        Ok(outcome)
+    }
+}
+
+impl Subscription for TransactionConflictSubscription {
+    fn event_type(&self) -> EventType {
+        EventType::TransactionConflict
+    }
+
+    fn active(&self) -> bool {
+        self.state().active()
+    }
+
+    fn scope(&self, context: &SubscriptionContext) -> Scope {
+        // TODO: consider using a provided prefix
+        TransactionConflictScope::new(self.data().to_addresses(Prefix::Mainnet, context)).into()
+    }
+}
+
 impl BroadcastingSingle for DynSubscription {
     fn broadcasting(self, context: &SubscriptionContext) -> DynSubscription {
         match self.event_type() {
@@ -1076,4 +1242,86 @@ mod tests {
         ]);
         tests.run(&context)
     }
+
+    #[test]
+    fn test_transaction_conflict_mutation() {
+        let context = SubscriptionContext::new();
+        let a_stock = get_3_addresses(true);
+
+        let a = |indexes: &[usize]| indexes.iter().map(|idx| (a_stock[*idx]).clone()).collect::<Vec<_>>();
+        let s = |active: bool, indexes: &[usize]| {
+            Arc::new(TransactionConflictSubscription::with_addresses(active, a(indexes), MutationTests::LISTENER_ID, &context))
+                as DynSubscription
+        };
+        let m = |command: Command, indexes: &[usize]| -> Mutation {
+            Mutation { command, scope: Scope::TransactionConflict(TransactionConflictScope::new(a(indexes))) }
+        };
+
+        // Subscriptions
+        let none = || s(false, &[]);
+        let selected_0 = || s(true, &[0]);
+        let selected_01 = || s(true, &[0, 1]);
+        let all = || s(true, &[]);
+
+        // Mutations
+        let start_all = || m(Command::Start, &[]);
+        let stop_all = || m(Command::Stop, &[]);
+        let start_0 = || m(Command::Start, &[0]);
+        let start_1 = || m(Command::Start, &[1]);
+        let stop_0 = || m(Command::Stop, &[0]);
+
+        // Tests
+        let tests = MutationTests::new(vec![
+            MutationTest {
+                name: "TransactionConflictSubscription None to All (add all)",
+                state: none(),
+                mutation: start_all(),
+                new_state: all(),
+                outcome: MutationOutcome::with_mutated(all(), vec![start_all()]),
+            },
+            MutationTest {
+                name: "TransactionConflictSubscription None to Selected 0 (add set)",
+                state: none(),
+                mutation: start_0(),
+                new_state: selected_0(),
+                outcome: MutationOutcome::with_mutated(selected_0(), vec![start_all()]),
+            },
+            MutationTest {
+                name: "TransactionConflictSubscription None to None (stop set)",
+                state: none(),
+                mutation: stop_0(),
+                new_state: none(),
+                outcome: MutationOutcome::new(),
+            },
+            MutationTest {
+                name: "TransactionConflictSubscription Selected 0 to Selected 01 (add set)",
+                state: selected_0(),
+                mutation: start_1(),
+                new_state: selected_01(),
+                outcome: MutationOutcome::new(),
+            },
+            MutationTest {
+                name: "TransactionConflictSubscription Selected 0 to None (remove set)",
+                state: selected_0(),
+                mutation: stop_0(),
+                new_state: none(),
+                outcome: MutationOutcome::with_mutated(none(), vec![stop_all()]),
+            },
+            MutationTest {
+                name: "TransactionConflictSubscription Selected 01 to Selected 1 (remove set)",
+                state: selected_01(),
+                mutation: stop_0(),
+                new_state: s(true, &[1]),
+                outcome: MutationOutcome::new(),
+            },
+            MutationTest {
+                name: "TransactionConflictSubscription All to None (remove all)",
+                state: all(),
+                mutation: stop_all(),
+                new_state: none(),
+                outcome: MutationOutcome::with_mutated(none(), vec![stop_all()]),
+            },
+        ]);
+        tests.run(&context)
+    }
 }
@@ -1,17 +1,18 @@
 use crate::flowcontext::{
-    orphans::{OrphanBlocksPool, OrphanOutput},
+    orphans::{self, OrphanBlocksPool, OrphanOutput},
     process_queue::ProcessQueue,
+    propagation::{BlockPropagationTracker, RECENT_BLOCKS_WINDOW_SECS},
     transactions::TransactionsSpread,
 };
 use crate::{v5, v6};
 use async_trait::async_trait;
-use futures::future::join_all;
-use kaspa_addressmanager::AddressManager;
+use kaspa_addressmanager::{AddressManager, AddressSource};
 use kaspa_connectionmanager::ConnectionManager;
 use kaspa_consensus_core::api::{BlockValidationFuture, BlockValidationFutures};
 use kaspa_consensus_core::block::Block;
 use kaspa_consensus_core::config::Config;
 use kaspa_consensus_core::errors::block::RuleError;
+use kaspa_consensus_core::pruning::PruningProofValidationProgress;
 use kaspa_consensus_core::tx::{Transaction, TransactionId};
 use kaspa_consensus_notify::{
     notification::{Notification, PruningPointUtxoSetOverrideNotification},
@@ -23,17 +24,17 @@ use kaspa_core::{
     kaspad_env::{name, version},
     task::tick::TickService,
 };
-use kaspa_core::{time::unix_now, warn};
+use kaspa_core::{time::hybrid_unix_now, warn};
 use kaspa_hashes::Hash;
 use kaspa_mining::manager::MiningManagerProxy;
-use kaspa_mining::mempool::tx::{Orphan, Priority};
+use kaspa_mining::mempool::tx::{Hold, Orphan, Priority};
 use kaspa_notify::notifier::Notify;
 use kaspa_p2p_lib::{
     common::ProtocolError,
     convert::model::version::Version,
     make_message,
     pb::{kaspad_message::Payload, InvRelayBlockMessage},
-    ConnectionInitializer, Hub, KaspadHandshake, PeerKey, PeerProperties, Router,
+    ConnectionInitializer, Hub, KaspadHandshake, KeepaliveConfig, PeerKey, PeerProperties, RequestCostLimits, Router,
 };
 use kaspa_utils::iter::IterExtensions;
 use kaspa_utils::networking::PeerId;
@@ -118,6 +119,8 @@ impl BlockEventLogger {
                     submit_count: usize,
                     orphan_count: usize,
                     unorphan_count: usize,
+                    unorphan_events: usize,
+                    max_unorphan_per_event: usize,
                     orphan_roots_count: usize,
                 }
 
@@ -176,6 +179,8 @@ impl BlockEventLogger {
                         }
                         BlockLogEvent::Unorphaned(hash, count) => {
                             summary.unorphan_count += count;
+                            summary.unorphan_events += 1;
+                            summary.max_unorphan_per_event = summary.max_unorphan_per_event.max(count);
                             summary.unorphan_rep = Some(hash)
                         }
                     }
@@ -184,24 +189,44 @@ impl BlockEventLogger {
 
                 match (summary.submit_count, summary.relay_count) {
                     (0, 0) => {}
-                    (1, 0) => info!("Accepted block {} via submit block", summary.submit()),
-                    (n, 0) => info!("Accepted {} blocks ...{} via submit block", n, summary.submit()),
-                    (0, 1) => info!("Accepted block {} via relay", summary.relay()),
-                    (0, m) => info!("Accepted {} blocks ...{} via relay", m, summary.relay()),
+                    (1, 0) => {
+                        info!(hash:% = summary.submit(), source = "submit"; "Accepted block {} via submit block", summary.submit())
+                    }
+                    (n, 0) => info!(
+                        hash:% = summary.submit(), count = n, source = "submit";
+                        "Accepted {} blocks ...{} via submit block", n, summary.submit()
+                    ),
+                    (0, 1) => info!(hash:% = summary.relay(), source = "relay"; "Accepted block {} via relay", summary.relay()),
+                    (0, m) => {
+                        info!(
+                            hash:% = summary.relay(), count = m, source = "relay";
+                            "Accepted {} blocks ...{} via relay", m, summary.relay()
+                        )
+                    }
                     (n, m) => {
-                        info!("Accepted {} blocks ...{}, {} via relay and {} via submit block", n + m, summary.submit(), m, n)
+                        info!(
+                            hash:% = summary.submit(), count = n + m, relay_count = m, submit_count = n;
+                            "Accepted {} blocks ...{}, {} via relay and {} via submit block", n + m, summary.submit(), m, n
+                        )
                     }
                 }
 
                 match (summary.orphan_count, summary.orphan_roots_count) {
                     (0, 0) => {}
-                    (n, m) => info!("Orphaned {} block(s) ...{} and queued {} missing roots", n, summary.orphan(), m),
+                    (n, m) => info!(
+                        hash:% = summary.orphan(), count = n, roots_count = m;
+                        "Orphaned {} block(s) ...{} and queued {} missing roots", n, summary.orphan(), m
+                    ),
                 }
 
                 match summary.unorphan_count {
                     0 => {}
-                    1 => info!("Unorphaned block {}", summary.unorphan()),
-                    n => info!("Unorphaned {} block(s) ...{}", n, summary.unorphan()),
+                    1 => info!(hash:% = summary.unorphan(), count = 1, events = 1; "Unorphaned block {}", summary.unorphan()),
+                    n => info!(
+                        hash:% = summary.unorphan(), count = n, events = summary.unorphan_events,
+                        max_per_event = summary.max_unorphan_per_event;
+                        "Unorphaned {} block(s) ...{}", n, summary.unorphan()
+                    ),
                 }
             }
         });
@@ -231,6 +256,15 @@ pub struct FlowContextInner {
     // Orphan parameters
     orphan_resolution_range: u32,
     max_orphans: usize,
+
+    // Limits applied by request-serving flows to each peer
+    request_cost_limits: RequestCostLimits,
+
+    // Keepalive settings of the connections to peers
+    keepalive_config: KeepaliveConfig,
+
+    // Propagation timestamps of recent blocks
+    block_propagation: Arc<BlockPropagationTracker>,
 }
 
 #[derive(Clone)]
@@ -255,6 +289,8 @@ struct IbdMetadata {
     peer: PeerKey,
     /// The DAA score of the relay block which triggered the current IBD
     daa_score: u64,
+    /// The latest progress of the pruning point proof validation, if the current IBD validated a proof
+    proof_validation_progress: Option<PruningProofValidationProgress>,
 }
 
 pub struct RequestScopeMetadata {
@@ -303,6 +339,8 @@ impl FlowContext {
         mining_manager: MiningManagerProxy,
         tick_service: Arc<TickService>,
         notification_root: Arc<ConsensusNotificationRoot>,
+        request_cost_limits: RequestCostLimits,
+        keepalive_config: KeepaliveConfig,
     ) -> Self {
         let hub = Hub::new();
 
@@ -330,6 +368,9 @@ impl FlowContext {
                 block_event_logger: if config.bps() > 1 { Some(BlockEventLogger::new(config.bps() as usize)) } else { None },
                 orphan_resolution_range,
                 max_orphans,
+                request_cost_limits,
+                keepalive_config,
+                block_propagation: Arc::new(BlockPropagationTracker::new(config.bps() as usize * RECENT_BLOCKS_WINDOW_SECS)),
                 config,
             }),
         }
@@ -347,6 +388,18 @@ impl FlowContext {
         self.max_orphans
     }
 
+    pub fn request_cost_limits(&self) -> &RequestCostLimits {
+        &self.request_cost_limits
+    }
+
+    pub fn keepalive_config(&self) -> &KeepaliveConfig {
+        &self.keepalive_config
+    }
+
+    pub fn block_propagation(&self) -> &Arc<BlockPropagationTracker> {
+        &self.block_propagation
+    }
+
     pub fn start_async_services(&self) {
         if let Some(logger) = self.block_event_logger.as_ref() {
             logger.start();
@@ -379,7 +432,7 @@ impl FlowContext {
 
     pub fn try_set_ibd_running(&self, peer: PeerKey, relay_daa_score: u64) -> Option<IbdRunningGuard> {
         if self.is_ibd_running.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
-            self.ibd_metadata.write().replace(IbdMetadata { peer, daa_score: relay_daa_score });
+            self.ibd_metadata.write().replace(IbdMetadata { peer, daa_score: relay_daa_score, proof_validation_progress: None });
             Some(IbdRunningGuard { indicator: self.is_ibd_running.clone() })
         } else {
             None
@@ -408,6 +461,22 @@ impl FlowContext {
         }
     }
 
+    /// If IBD is running and validated a pruning point proof, returns the latest progress of the validation
+    pub fn ibd_proof_validation_progress(&self) -> Option<PruningProofValidationProgress> {
+        if self.is_ibd_running() {
+            self.ibd_metadata.read().and_then(|md| md.proof_validation_progress)
+        } else {
+            None
+        }
+    }
+
+    /// Updates the pruning point proof validation progress of the current IBD
+    pub fn set_ibd_proof_validation_progress(&self, progress: PruningProofValidationProgress) {
+        if let Some(md) = self.ibd_metadata.write().as_mut() {
+            md.proof_validation_progress = Some(progress);
+        }
+    }
+
     fn try_adding_request_impl(req: Hash, map: &Arc<Mutex<HashMap<Hash, RequestScopeMetadata>>>) -> Option<RequestScope<Hash>> {
         match map.lock().entry(req) {
             Entry::Occupied(mut e) => {
@@ -451,17 +520,8 @@ impl FlowContext {
     }
 
     pub async fn unorphan_blocks(&self, consensus: &ConsensusProxy, root: Hash) -> Vec<(Block, BlockValidationFuture)> {
-        let (blocks, block_tasks, virtual_state_tasks) = self.orphans_pool.write().await.unorphan_blocks(consensus, root).await;
-        let mut unorphaned_blocks = Vec::with_capacity(blocks.len());
-        let results = join_all(block_tasks).await;
-        for ((block, result), virtual_state_task) in blocks.into_iter().zip(results).zip(virtual_state_tasks) {
-            match result {
-                Ok(_) => {
-                    unorphaned_blocks.push((block, virtual_state_task));
-                }
-                Err(e) => warn!("Validation failed for orphan block {}: {}", block.hash(), e),
-            }
-        }
+        let queue = self.orphans_pool.write().await.take_root_children(root);
+        let unorphaned_blocks = orphans::unorphan_blocks(&self.orphans_pool, consensus, queue).await;
 
         // Log or send to event logger
         if !unorphaned_blocks.is_empty() {
@@ -469,8 +529,13 @@ impl FlowContext {
                 logger.log(BlockLogEvent::Unorphaned(unorphaned_blocks[0].0.hash(), unorphaned_blocks.len()));
             } else {
                 match unorphaned_blocks.len() {
-                    1 => info!("Unorphaned block {}", unorphaned_blocks[0].0.hash()),
-                    n => info!("Unorphaned {} blocks: {}", n, unorphaned_blocks.iter().map(|b| b.0.hash()).reusable_format(", ")),
+                    1 => {
+                        info!(hash:% = unorphaned_blocks[0].0.hash(), count = 1; "Unorphaned block {}", unorphaned_blocks[0].0.hash())
+                    }
+                    n => info!(
+                        hash:% = unorphaned_blocks[0].0.hash(), count = n;
+                        "Unorphaned {} blocks: {}", n, unorphaned_blocks.iter().map(|b| b.0.hash()).reusable_format(", ")
+                    ),
                 }
             }
         }
@@ -478,7 +543,12 @@ impl FlowContext {
     }
 
     pub async fn revalidate_orphans(&self, consensus: &ConsensusProxy) -> (Vec<Hash>, Vec<BlockValidationFuture>) {
-        self.orphans_pool.write().await.revalidate_orphans(consensus).await
+        let queue = self.orphans_pool.write().await.revalidate_orphans(consensus).await;
+        orphans::unorphan_blocks(&self.orphans_pool, consensus, queue)
+            .await
+            .into_iter()
+            .map(|(block, task)| (block.hash(), task))
+            .unzip()
     }
 
     /// Adds the rpc-submitted block to the DAG and propagates it to peers.
@@ -492,6 +562,7 @@ impl FlowContext {
             warn!("Validation failed for block {}: {}", hash, err);
             return Err(err)?;
         }
+        self.block_propagation.record_validated(hash);
         // Broadcast as soon as the block has been validated and inserted into the DAG
         self.hub.broadcast(make_message!(Payload::InvRelayBlock, InvRelayBlockMessage { hash: Some(hash.into()) })).await;
 
@@ -506,10 +577,13 @@ impl FlowContext {
             logger.log(event)
         } else {
             match event {
-                BlockLogEvent::Relay(hash) => info!("Accepted block {} via relay", hash),
-                BlockLogEvent::Submit(hash) => info!("Accepted block {} via submit block", hash),
+                BlockLogEvent::Relay(hash) => info!(hash:% = hash, source = "relay"; "Accepted block {} via relay", hash),
+                BlockLogEvent::Submit(hash) => info!(hash:% = hash, source = "submit"; "Accepted block {} via submit block", hash),
                 BlockLogEvent::Orphaned(orphan, roots_count) => {
-                    info!("Received a block with {} missing ancestors, adding to orphan pool: {}", roots_count, orphan)
+                    info!(
+                        hash:% = orphan, roots_count = roots_count;
+                        "Received a block with {} missing ancestors, adding to orphan pool: {}", roots_count, orphan
+                    )
                 }
                 _ => {}
             }
@@ -617,9 +691,13 @@ impl FlowContext {
         consensus: &ConsensusProxy,
         transaction: Transaction,
         orphan: Orphan,
+        hold: Hold,
     ) -> Result<(), ProtocolError> {
-        let accepted_transactions =
-            self.mining_manager().clone().validate_and_insert_transaction(consensus, transaction, Priority::High, orphan).await?;
+        let accepted_transactions = self
+            .mining_manager()
+            .clone()
+            .validate_and_insert_transaction_with_hold(consensus, transaction, Priority::High, orphan, hold)
+            .await?;
         self.broadcast_transactions(
             accepted_transactions.iter().map(|x| x.id()),
             false, // RPC transactions are considered high priority, so we don't want to throttle them
@@ -673,13 +751,13 @@ impl ConnectionInitializer for FlowContext {
         // Subnets are not currently supported
         let mut self_version_message = Version::new(local_address, self.node_id, network_name.clone(), None, PROTOCOL_VERSION);
         self_version_message.add_user_agent(name(), version(), &self.config.user_agent_comments);
-        // TODO: get number of live services
+        self_version_message.set_network_identity(self.config.params.digest(), self.config.params.genesis.hash);
         // TODO: disable_relay_tx from config/cmd
 
         // Perform the handshake
         let peer_version_message = handshake.handshake(self_version_message.into()).await?;
         // Get time_offset as accurate as possible by computing right after the handshake
-        let time_offset = unix_now() as i64 - peer_version_message.timestamp;
+        let time_offset = hybrid_unix_now() as i64 - peer_version_message.timestamp;
 
         let peer_version: Version = peer_version_message.try_into()?;
         router.set_identity(peer_version.id);
@@ -696,6 +774,13 @@ impl ConnectionInitializer for FlowContext {
             return Err(ProtocolError::WrongNetwork(network_name, peer_version.network));
         }
 
+        // Peers running on other consensus params or genesis would only ever send invalid data
+        peer_version.verify_network_identity(
+            self.config.params.digest(),
+            self.config.params.genesis.hash,
+            !self.config.reject_legacy_peers,
+        )?;
+
         debug!("protocol versions - self: {}, peer: {}", PROTOCOL_VERSION, peer_version.protocol_version);
 
         // Register all flows according to version
@@ -708,6 +793,7 @@ impl ConnectionInitializer for FlowContext {
         // Build and register the peer properties
         let peer_properties = Arc::new(PeerProperties {
             user_agent: peer_version.user_agent.to_owned(),
+            services: peer_version.services,
             advertised_protocol_version: peer_version.protocol_version,
             protocol_version: applied_protocol_version,
             disable_relay_tx: peer_version.disable_relay_tx,
@@ -715,11 +801,15 @@ impl ConnectionInitializer for FlowContext {
             time_offset,
         });
         router.set_properties(peer_properties);
+        router.set_request_cost_limits(self.request_cost_limits);
 
         // Send and receive the ready signal
         handshake.exchange_ready_messages().await?;
 
-        info!("Registering p2p flows for peer {} for protocol version {}", router, applied_protocol_version);
+        info!(
+            peer_id:% = router.identity(), peer:% = router, protocol_version = applied_protocol_version;
+            "Registering p2p flows for peer {} for protocol version {}", router, applied_protocol_version
+        );
 
         // Launch all flows. Note we launch only after the ready signal was exchanged
         for flow in flows {
@@ -729,12 +819,13 @@ impl ConnectionInitializer for FlowContext {
         if router.is_outbound() || peer_version.address.is_some() {
             let mut address_manager = self.address_manager.lock();
 
+            // Outbound peers which are not known yet were dialed on a connection request
             if router.is_outbound() {
-                address_manager.add_address(router.net_address().into());
+                address_manager.add_address(router.net_address().into(), AddressSource::Manual);
             }
 
             if let Some(peer_ip_address) = peer_version.address {
-                address_manager.add_address(peer_ip_address);
+                address_manager.add_address(peer_ip_address, AddressSource::PeerGossip);
             }
         }
 
@@ -1,20 +1,25 @@
+use futures::future::join_all;
 use indexmap::{map::Entry::Occupied, IndexMap};
 use kaspa_consensus_core::{
     api::{BlockValidationFuture, BlockValidationFutures},
     block::Block,
 };
 use kaspa_consensusmanager::{BlockProcessingBatch, ConsensusProxy};
-use kaspa_core::debug;
+use kaspa_core::{debug, warn};
 use kaspa_hashes::Hash;
 use kaspa_utils::option::OptionExtensions;
 use rand::Rng;
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
+    collections::{HashSet, VecDeque},
     iter::once,
 };
+use tokio::sync::RwLock as AsyncRwLock;
 
 use super::process_queue::ProcessQueue;
 
+/// The maximum number of ready orphans submitted to consensus at once while unorphaning
+pub const MAX_UNORPHAN_BATCH_SIZE: usize = 64;
+
 /// The output of an orphan pool block query
 #[derive(Debug)]
 pub enum OrphanOutput {
@@ -180,35 +185,46 @@ impl OrphanBlocksPool {
         }
     }
 
-    pub async fn unorphan_blocks(
+    /// Removes the provided root from the pool (in case it was previously an orphan) and returns
+    /// a process queue holding its child orphans
+    pub fn take_root_children(&mut self, root: Hash) -> ProcessQueue<Hash> {
+        match self.orphans.swap_remove(&root) {
+            Some(root_entry) => ProcessQueue::from(root_entry.children),
+            None => ProcessQueue::from(self.iterate_child_orphans(root).collect::<HashSet<_>>()),
+        }
+    }
+
+    /// Performs a breadth-first readiness pass over the queued orphans. An orphan is ready once
+    /// all of its parents have a known block body consensus-wise. Up to `max_batch_size` ready
+    /// orphans are removed from the pool and returned along with their child orphans. Orphans
+    /// which are not ready yet are dequeued as well, since they are queued again as children of
+    /// their missing orphan parent once it is processed.
+    pub async fn take_ready_orphans(
         &mut self,
         consensus: &ConsensusProxy,
-        root: Hash,
-    ) -> (Vec<Block>, Vec<BlockValidationFuture>, Vec<BlockValidationFuture>) {
-        let root_entry = self.orphans.swap_remove(&root); // Try removing the root just in case it was previously an orphan
-        let mut process_queue =
-            ProcessQueue::from(root_entry.map(|e| e.children).unwrap_or_else(|| self.iterate_child_orphans(root).collect()));
-        let mut processing = HashMap::new();
-        while let Some(orphan_hash) = process_queue.dequeue() {
+        queue: &mut ProcessQueue<Hash>,
+        max_batch_size: usize,
+    ) -> Vec<(Block, HashSet<Hash>)> {
+        let mut ready = Vec::new();
+        while ready.len() < max_batch_size {
+            let Some(orphan_hash) = queue.dequeue() else {
+                break;
+            };
             if let Occupied(entry) = self.orphans.entry(orphan_hash) {
                 let mut processable = true;
                 for p in entry.get().block.header.direct_parents().iter().copied() {
-                    if !processing.contains_key(&p) && consensus.async_get_block_status(p).await.is_none_or(|s| s.is_header_only()) {
+                    if consensus.async_get_block_status(p).await.is_none_or(|s| s.is_header_only()) {
                         processable = false;
                         break;
                     }
                 }
                 if processable {
                     let orphan_block = entry.swap_remove();
-                    let BlockValidationFutures { block_task, virtual_state_task } =
-                        consensus.validate_and_insert_block(orphan_block.block.clone());
-                    processing.insert(orphan_hash, (orphan_block.block, block_task, virtual_state_task));
-                    process_queue.enqueue_chunk(orphan_block.children);
+                    ready.push((orphan_block.block, orphan_block.children));
                 }
             }
         }
-        // We deliberately want all processing tasks to be awaited out of the orphan pool lock
-        itertools::multiunzip(processing.into_values())
+        ready
     }
 
     fn iterate_child_orphans(&self, hash: Hash) -> impl Iterator<Item = Hash> + '_ {
@@ -224,8 +240,10 @@ impl OrphanBlocksPool {
     /// Iterate all orphans and remove blocks which are no longer orphans.
     /// This is important for the overall health of the pool and for ensuring that
     /// orphan blocks don't evict due to pool size limit while already processed
-    /// blocks remain in it. Should be called following IBD.  
-    pub async fn revalidate_orphans(&mut self, consensus: &ConsensusProxy) -> (Vec<Hash>, Vec<BlockValidationFuture>) {
+    /// blocks remain in it. Should be called following IBD. Returns a process
+    /// queue holding the orphans which are now processable, i.e., all of their
+    /// parents are known to consensus with valid body state.
+    pub async fn revalidate_orphans(&mut self, consensus: &ConsensusProxy) -> ProcessQueue<Hash> {
         // First, cleanup blocks already processed by consensus
         let mut i = 0;
         while i < self.orphans.len() {
@@ -244,8 +262,8 @@ impl OrphanBlocksPool {
 
         // Next, search for root blocks which are processable. A processable block is a block
         // which all of its parents are known to consensus with valid body state
-        let mut roots = Vec::new();
-        for block in self.orphans.values() {
+        let mut roots = ProcessQueue::new();
+        for (&hash, block) in self.orphans.iter() {
             let mut processable = true;
             for parent in block.block.header.direct_parents().iter().copied() {
                 if self.orphans.contains_key(&parent)
@@ -256,56 +274,99 @@ impl OrphanBlocksPool {
                 }
             }
             if processable {
-                roots.push(block.block.clone());
+                roots.enqueue_chunk(once(hash));
             }
         }
+        roots
+    }
+}
 
-        // Now process the roots and unorphan their descendents
-        let mut virtual_processing_tasks = Vec::with_capacity(roots.len());
-        let mut queued_hashes = Vec::with_capacity(roots.len());
-        for root in roots {
-            let root_hash = root.hash();
-            // Queue the root for processing
-            let BlockValidationFutures { block_task: _, virtual_state_task: root_task } = consensus.validate_and_insert_block(root);
-            // Queue its descendents which are processable
-            let (descendent_blocks, _, descendents_tasks) = self.unorphan_blocks(consensus, root_hash).await;
-            // Keep track of all hashes and tasks
-            virtual_processing_tasks.extend(once(root_task).chain(descendents_tasks));
-            queued_hashes.extend(once(root_hash).chain(descendent_blocks.into_iter().map(|block| block.hash())));
+/// Unorphans the queued orphans and all of their orphan descendants by repeatedly performing readiness
+/// passes over the orphan pool (see [`OrphanBlocksPool::take_ready_orphans`]) and submitting the ready
+/// orphans to consensus in batches of up to [`MAX_UNORPHAN_BATCH_SIZE`] blocks. The `block_task` of all
+/// blocks in a batch is awaited before their child orphans are released, so a block is never submitted
+/// prior to its parents. The orphan pool lock is only held during the readiness passes.
+///
+/// Returns the successfully validated blocks along with their virtual state processing tasks.
+pub async fn unorphan_blocks(
+    orphans_pool: &AsyncRwLock<OrphanBlocksPool>,
+    consensus: &ConsensusProxy,
+    mut queue: ProcessQueue<Hash>,
+) -> Vec<(Block, BlockValidationFuture)> {
+    let mut unorphaned_blocks = Vec::new();
+    loop {
+        let ready = orphans_pool.write().await.take_ready_orphans(consensus, &mut queue, MAX_UNORPHAN_BATCH_SIZE).await;
+        if ready.is_empty() {
+            break;
+        }
+        let mut block_tasks = Vec::with_capacity(ready.len());
+        let mut batch = Vec::with_capacity(ready.len());
+        for (block, children) in ready {
+            let BlockValidationFutures { block_task, virtual_state_task } = consensus.validate_and_insert_block(block.clone());
+            block_tasks.push(block_task);
+            batch.push((block, children, virtual_state_task));
+        }
+        // Release the child orphans only once their parents have been validated and inserted into the DAG
+        let results = join_all(block_tasks).await;
+        for ((block, children, virtual_state_task), result) in batch.into_iter().zip(results) {
+            match result {
+                Ok(_) => {
+                    queue.enqueue_chunk(children);
+                    unorphaned_blocks.push((block, virtual_state_task));
+                }
+                Err(e) => warn!("Validation failed for orphan block {}: {}", block.hash(), e),
+            }
         }
-
-        // We deliberately want the processing tasks to be awaited out of the orphan pool lock
-        (queued_hashes, virtual_processing_tasks)
     }
+    unorphaned_blocks
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use futures::future::try_join_all;
+    use itertools::Itertools;
     use kaspa_consensus_core::{
         api::{BlockValidationFutures, ConsensusApi},
         blockstatus::BlockStatus,
-        errors::block::BlockProcessResult,
+        errors::block::{BlockProcessResult, RuleError},
     };
     use kaspa_consensusmanager::{ConsensusInstance, SessionLock};
     use kaspa_core::assert_match;
     use parking_lot::RwLock;
-    use std::sync::Arc;
+    use std::{collections::HashMap, sync::Arc};
 
     #[derive(Default)]
     struct MockProcessor {
         processed: Arc<RwLock<HashSet<Hash>>>,
+        submissions: Arc<RwLock<HashMap<Hash, usize>>>,
     }
 
-    async fn block_process_mock() -> BlockProcessResult<BlockStatus> {
+    /// A block becomes known to the mock processor only once one of its processing tasks completes.
+    /// Blocks submitted before all of their parents are known fail with a missing parents error
+    async fn block_process_mock(
+        processed: Arc<RwLock<HashSet<Hash>>>,
+        hash: Hash,
+        missing_parents: Vec<Hash>,
+    ) -> BlockProcessResult<BlockStatus> {
+        if !missing_parents.is_empty() {
+            return Err(RuleError::MissingParents(missing_parents));
+        }
+        tokio::task::yield_now().await;
+        processed.write().insert(hash);
         Ok(BlockStatus::StatusUTXOPendingVerification)
     }
 
     impl ConsensusApi for MockProcessor {
         fn validate_and_insert_block(&self, block: Block) -> BlockValidationFutures {
-            self.processed.write().insert(block.hash());
-            BlockValidationFutures { block_task: Box::pin(block_process_mock()), virtual_state_task: Box::pin(block_process_mock()) }
+            let hash = block.hash();
+            *self.submissions.write().entry(hash).or_default() += 1;
+            let missing_parents =
+                block.header.direct_parents().iter().copied().filter(|parent| !self.processed.read().contains(parent)).collect_vec();
+            BlockValidationFutures {
+                block_task: Box::pin(block_process_mock(self.processed.clone(), hash, missing_parents.clone())),
+                virtual_state_task: Box::pin(block_process_mock(self.processed.clone(), hash, missing_parents)),
+            }
         }
 
         fn get_block_status(&self, hash: Hash) -> Option<BlockStatus> {
@@ -318,7 +379,7 @@ mod tests {
         let max_orphans = 10;
         let ci = ConsensusInstance::new(SessionLock::new(), Arc::new(MockProcessor::default()));
         let consensus = ci.session().await;
-        let mut pool = OrphanBlocksPool::new(max_orphans);
+        let pool = AsyncRwLock::new(OrphanBlocksPool::new(max_orphans));
 
         let roots = vec![8.into(), 9.into()];
         let a = Block::from_precomputed_hash(8.into(), vec![]);
@@ -332,33 +393,69 @@ mod tests {
         let h = Block::from_precomputed_hash(15.into(), vec![14.into()]);
         let k = Block::from_precomputed_hash(16.into(), vec![15.into()]);
 
-        pool.add_orphan(&consensus, c.clone()).await.unwrap();
-        pool.add_orphan(&consensus, d.clone()).await.unwrap();
+        pool.write().await.add_orphan(&consensus, c.clone()).await.unwrap();
+        pool.write().await.add_orphan(&consensus, d.clone()).await.unwrap();
 
-        assert_match!(pool.get_orphan_roots_if_known(&consensus, d.hash()).await, OrphanOutput::Roots(recv_roots) if recv_roots == roots);
+        assert_match!(pool.read().await.get_orphan_roots_if_known(&consensus, d.hash()).await, OrphanOutput::Roots(recv_roots) if recv_roots == roots);
 
         consensus.validate_and_insert_block(a.clone()).virtual_state_task.await.unwrap();
         consensus.validate_and_insert_block(b.clone()).virtual_state_task.await.unwrap();
 
         // Test unorphaning
-        let (blocks, _, virtual_state_tasks) = pool.unorphan_blocks(&consensus, 8.into()).await;
+        let queue = pool.write().await.take_root_children(8.into());
+        let (blocks, virtual_state_tasks): (Vec<_>, Vec<_>) = unorphan_blocks(&pool, &consensus, queue).await.into_iter().unzip();
         try_join_all(virtual_state_tasks).await.unwrap();
         assert_eq!(blocks.into_iter().map(|b| b.hash()).collect::<HashSet<_>>(), HashSet::from([10.into(), 11.into()]));
-        assert!(pool.orphans.is_empty());
+        assert!(pool.read().await.orphans.is_empty());
 
         // Test revalidation
-        pool.add_orphan(&consensus, f.clone()).await.unwrap();
-        pool.add_orphan(&consensus, g.clone()).await.unwrap();
-        pool.add_orphan(&consensus, k.clone()).await.unwrap();
-        assert_eq!(pool.orphans.len(), 3);
+        pool.write().await.add_orphan(&consensus, f.clone()).await.unwrap();
+        pool.write().await.add_orphan(&consensus, g.clone()).await.unwrap();
+        pool.write().await.add_orphan(&consensus, k.clone()).await.unwrap();
+        assert_eq!(pool.read().await.orphans.len(), 3);
         consensus.validate_and_insert_block(e.clone()).virtual_state_task.await.unwrap();
-        pool.revalidate_orphans(&consensus).await;
-        assert_eq!(pool.orphans.len(), 1);
-        assert!(pool.orphans.contains_key(&k.hash())); // k's parent, h, was never inserted to the pool
+        let queue = pool.write().await.revalidate_orphans(&consensus).await;
+        unorphan_blocks(&pool, &consensus, queue).await;
+        assert_eq!(pool.read().await.orphans.len(), 1);
+        assert!(pool.read().await.orphans.contains_key(&k.hash())); // k's parent, h, was never inserted to the pool
         consensus.validate_and_insert_block(h.clone()).virtual_state_task.await.unwrap();
-        pool.revalidate_orphans(&consensus).await;
-        assert!(pool.orphans.is_empty());
+        let queue = pool.write().await.revalidate_orphans(&consensus).await;
+        unorphan_blocks(&pool, &consensus, queue).await;
+        assert!(pool.read().await.orphans.is_empty());
 
         drop((a, b, c, d, e, f, g, h, k));
     }
+
+    #[tokio::test]
+    async fn test_unorphan_reversed_chain() {
+        let chain_depth = 50;
+        let processor = Arc::new(MockProcessor::default());
+        let ci = ConsensusInstance::new(SessionLock::new(), processor.clone());
+        let consensus = ci.session().await;
+        let pool = AsyncRwLock::new(OrphanBlocksPool::new(100));
+
+        // A chain of orphans on top of a root yet unknown to consensus
+        let root = Block::from_precomputed_hash(1.into(), vec![]);
+        let chain = (2..chain_depth + 2).map(|i: u64| Block::from_precomputed_hash(i.into(), vec![(i - 1).into()])).collect_vec();
+
+        // Deliver the chain in reverse order, so each block misses its direct parent upon arrival
+        for block in chain.iter().rev() {
+            let output = pool.write().await.add_orphan(&consensus, block.clone()).await.unwrap();
+            assert_match!(output, OrphanOutput::Roots(roots) if roots == block.header.direct_parents().to_vec());
+        }
+        assert_eq!(pool.read().await.orphans.len(), chain.len());
+
+        // The missing root finally arrives
+        consensus.validate_and_insert_block(root.clone()).block_task.await.unwrap();
+        let queue = pool.write().await.take_root_children(root.hash());
+        let (blocks, virtual_state_tasks): (Vec<_>, Vec<_>) = unorphan_blocks(&pool, &consensus, queue).await.into_iter().unzip();
+        try_join_all(virtual_state_tasks).await.unwrap();
+
+        // All blocks are accepted in topological order and none of them was submitted more than once
+        assert_eq!(blocks.iter().map(|b| b.hash()).collect_vec(), chain.iter().map(|b| b.hash()).collect_vec());
+        assert!(pool.read().await.orphans.is_empty());
+        let submissions = processor.submissions.read();
+        assert_eq!(submissions.len(), chain.len() + 1);
+        assert!(submissions.values().all(|&count| count == 1), "no block should be submitted more than once");
+    }
 }
@@ -0,0 +1,208 @@
+use kaspa_core::time::unix_now;
+use kaspa_hashes::Hash;
+use parking_lot::Mutex;
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Instant,
+};
+
+/// The time span (in seconds) of recent blocks whose propagation timestamps are retained
+pub const RECENT_BLOCKS_WINDOW_SECS: usize = 600;
+
+/// A unix clock in milliseconds which is sampled once and then advanced by the monotonic clock,
+/// so that timestamps taken within the node are consistently ordered even if the system clock
+/// is adjusted meanwhile
+struct MonotonicClock {
+    origin: Instant,
+    origin_unix_millis: u64,
+}
+
+impl MonotonicClock {
+    fn new() -> Self {
+        Self { origin: Instant::now(), origin_unix_millis: unix_now() }
+    }
+
+    fn now(&self) -> u64 {
+        self.origin_unix_millis + self.origin.elapsed().as_millis() as u64
+    }
+}
+
+/// The times (in unix milliseconds of the node clock) at which the node learned of a block
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlockPropagationTimes {
+    /// The first announcement of the block hash by a peer (inv)
+    pub inv: Option<u64>,
+    /// The arrival of the block header (relay blocks are received with their body)
+    pub header: Option<u64>,
+    /// The completion of the block body validation
+    pub validated: Option<u64>,
+}
+
+/// Percentiles (in milliseconds) of the delay between the first announcement of recent relay blocks
+/// and the completion of their validation
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlockPropagationPercentiles {
+    pub samples: usize,
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+}
+
+struct Inner {
+    times: HashMap<Hash, BlockPropagationTimes>,
+    /// Tracked hashes by insertion order, for evicting the oldest
+    order: VecDeque<Hash>,
+    /// Announcement to validation delays of the recent relay blocks
+    delays: VecDeque<u64>,
+}
+
+/// Tracks the propagation timestamps of a bounded set of recent blocks
+pub struct BlockPropagationTracker {
+    clock: MonotonicClock,
+    capacity: usize,
+    inner: Mutex<Inner>,
+}
+
+impl BlockPropagationTracker {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            clock: MonotonicClock::new(),
+            capacity,
+            inner: Mutex::new(Inner {
+                times: HashMap::with_capacity(capacity),
+                order: VecDeque::with_capacity(capacity),
+                delays: VecDeque::with_capacity(capacity),
+            }),
+        }
+    }
+
+    /// Records the announcement of `hash` by a peer. Only the first announcement of a block
+    /// which is not yet tracked is recorded.
+    pub fn record_inv(&self, hash: Hash) {
+        let now = self.clock.now();
+        let mut inner = self.inner.lock();
+        if !inner.times.contains_key(&hash) {
+            self.insert(&mut inner, hash, BlockPropagationTimes { inv: Some(now), ..Default::default() });
+        }
+    }
+
+    /// Records the arrival of the header of `hash`
+    pub fn record_header(&self, hash: Hash) {
+        let now = self.clock.now();
+        let mut inner = self.inner.lock();
+        match inner.times.get_mut(&hash) {
+            Some(times) => {
+                times.header.get_or_insert(now);
+            }
+            None => self.insert(&mut inner, hash, BlockPropagationTimes { header: Some(now), ..Default::default() }),
+        }
+    }
+
+    /// Records the completion of the validation of `hash`
+    pub fn record_validated(&self, hash: Hash) {
+        let now = self.clock.now();
+        let mut inner = self.inner.lock();
+        let inv = match inner.times.get_mut(&hash) {
+            Some(times) if times.validated.is_none() => {
+                times.validated = Some(now);
+                times.inv
+            }
+            Some(_) => return,
+            None => {
+                self.insert(&mut inner, hash, BlockPropagationTimes { validated: Some(now), ..Default::default() });
+                None
+            }
+        };
+        if let Some(inv) = inv {
+            if inner.delays.len() == self.capacity {
+                inner.delays.pop_front();
+            }
+            inner.delays.push_back(now - inv);
+        }
+    }
+
+    /// Returns the propagation timestamps of `hash` if it is a recent block
+    pub fn get(&self, hash: Hash) -> Option<BlockPropagationTimes> {
+        self.inner.lock().times.get(&hash).copied()
+    }
+
+    pub fn percentiles(&self) -> BlockPropagationPercentiles {
+        let mut delays = self.inner.lock().delays.iter().copied().collect::<Vec<_>>();
+        if delays.is_empty() {
+            return Default::default();
+        }
+        delays.sort_unstable();
+        let percentile = |p: usize| delays[(delays.len() * p).div_ceil(100).saturating_sub(1)];
+        BlockPropagationPercentiles { samples: delays.len(), p50: percentile(50), p90: percentile(90), p99: percentile(99) }
+    }
+
+    fn insert(&self, inner: &mut Inner, hash: Hash, times: BlockPropagationTimes) {
+        if inner.order.len() == self.capacity {
+            if let Some(evicted) = inner.order.pop_front() {
+                inner.times.remove(&evicted);
+            }
+        }
+        inner.order.push_back(hash);
+        inner.times.insert(hash, times);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relay_and_submitted_blocks() {
+        let tracker = BlockPropagationTracker::new(16);
+        let (relayed, mined) = (Hash::from_u64_word(1), Hash::from_u64_word(2));
+
+        // A relay block is announced, received and then validated
+        tracker.record_inv(relayed);
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        tracker.record_inv(relayed);
+        tracker.record_header(relayed);
+        tracker.record_validated(relayed);
+        let times = tracker.get(relayed).unwrap();
+        let (inv, header, validated) = (times.inv.unwrap(), times.header.unwrap(), times.validated.unwrap());
+        assert!(inv < header, "only the first announcement is recorded");
+        assert!(header <= validated);
+
+        // A block mined by the node only has a validation time
+        tracker.record_validated(mined);
+        let times = tracker.get(mined).unwrap();
+        assert_eq!((times.inv, times.header), (None, None));
+        assert!(times.validated.is_some());
+
+        // Only relay blocks are sampled
+        assert_eq!(tracker.percentiles().samples, 1);
+        assert_eq!(tracker.percentiles().p50, validated - inv);
+    }
+
+    #[test]
+    fn test_bounded_tracking() {
+        let tracker = BlockPropagationTracker::new(4);
+        for i in 0..10 {
+            let hash = Hash::from_u64_word(i);
+            tracker.record_inv(hash);
+            tracker.record_header(hash);
+            tracker.record_validated(hash);
+        }
+        assert!((0..6).all(|i| tracker.get(Hash::from_u64_word(i)).is_none()));
+        assert!((6..10).all(|i| tracker.get(Hash::from_u64_word(i)).is_some()));
+        assert_eq!(tracker.inner.lock().order.len(), 4);
+        assert_eq!(tracker.percentiles().samples, 4);
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let tracker = BlockPropagationTracker::new(128);
+        {
+            let mut inner = tracker.inner.lock();
+            inner.delays.extend((1..=100).rev());
+        }
+        let percentiles = tracker.percentiles();
+        assert_eq!(percentiles, BlockPropagationPercentiles { samples: 100, p50: 50, p90: 90, p99: 99 });
+        assert_eq!(BlockPropagationTracker::new(1).percentiles(), Default::default());
+    }
+}
@@ -1,13 +1,12 @@
 use crate::{flow_context::FlowContext, flow_trait::Flow};
-use itertools::Itertools;
-use kaspa_addressmanager::NetAddress;
+use kaspa_addressmanager::{AddressSource, NetAddress};
 use kaspa_p2p_lib::{
     common::ProtocolError,
     dequeue, dequeue_with_timeout, make_message,
     pb::{kaspad_message::Payload, AddressesMessage, RequestAddressesMessage},
     IncomingRoute, Router,
 };
-use kaspa_utils::networking::IpAddress;
+use kaspa_utils::networking::{IpAddress, OnionAddress};
 use rand::seq::SliceRandom;
 use std::sync::Arc;
 
@@ -49,13 +48,21 @@ impl ReceiveAddressesFlow {
             .await?;
 
         let msg = dequeue_with_timeout!(self.incoming_route, Payload::Addresses)?;
-        let address_list: Vec<(IpAddress, u16)> = msg.try_into()?;
-        if address_list.len() > MAX_ADDRESSES_RECEIVE {
-            return Err(ProtocolError::OtherOwned(format!("address count {} exceeded {}", address_list.len(), MAX_ADDRESSES_RECEIVE)));
+        let (address_list, onion_address_list): (Vec<(IpAddress, u16)>, Vec<(OnionAddress, u16)>) = msg.try_into()?;
+        let address_count = address_list.len() + onion_address_list.len();
+        if address_count > MAX_ADDRESSES_RECEIVE {
+            return Err(ProtocolError::OtherOwned(format!("address count {} exceeded {}", address_count, MAX_ADDRESSES_RECEIVE)));
         }
+        // Onion addresses are only reachable through a proxy
+        let accepts_onion = self.ctx.connection_manager().is_some_and(|connection_manager| connection_manager.proxy().is_some());
         let mut amgr_lock = self.ctx.address_manager.lock();
         for (ip, port) in address_list {
-            amgr_lock.add_address(NetAddress::new(ip, port))
+            amgr_lock.add_address(NetAddress::new(ip, port), AddressSource::PeerGossip)
+        }
+        if accepts_onion {
+            for (host, port) in onion_address_list {
+                amgr_lock.add_onion_address(host, port, AddressSource::PeerGossip)
+            }
         }
 
         Ok(())
@@ -87,12 +94,21 @@ impl SendAddressesFlow {
     async fn start_impl(&mut self) -> Result<(), ProtocolError> {
         loop {
             dequeue!(self.incoming_route, Payload::RequestAddresses)?;
-            let addresses = self.ctx.address_manager.lock().iterate_addresses().collect_vec();
-            let address_list = addresses
-                .choose_multiple(&mut rand::thread_rng(), MAX_ADDRESSES_SEND)
-                .map(|addr| (addr.ip, addr.port).into())
-                .collect();
-            self.router.enqueue(make_message!(Payload::Addresses, AddressesMessage { address_list })).await?;
+            let supports_onion = self.router.properties().supports_onion_addresses();
+            let (mut address_list, mut onion_address_list) = (Vec::new(), Vec::new());
+            {
+                let amgr_lock = self.ctx.address_manager.lock();
+                let addresses: Vec<NetAddress> = amgr_lock.iterate_addresses().collect();
+                for addr in addresses.choose_multiple(&mut rand::thread_rng(), MAX_ADDRESSES_SEND) {
+                    match amgr_lock.onion_host(*addr) {
+                        Some(host) if supports_onion => onion_address_list.push((host, addr.port).into()),
+                        // Other peers would take the IPv6 encoding of the onion address for a reachable IP
+                        Some(_) => {}
+                        None => address_list.push((addr.ip, addr.port).into()),
+                    }
+                }
+            }
+            self.router.enqueue(make_message!(Payload::Addresses, AddressesMessage { address_list, onion_address_list })).await?;
         }
     }
 }
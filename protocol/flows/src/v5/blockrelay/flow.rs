@@ -101,11 +101,19 @@ impl HandleRelayInvsFlow {
                 }
                 _ => {
                     // Block is already known, skip to next inv
-                    debug!("Relay block {} already exists, continuing...", inv.hash);
+                    debug!(
+                        hash:% = inv.hash, peer_id:% = self.router.identity();
+                        "Relay block {} already exists, continuing...", inv.hash
+                    );
                     continue;
                 }
             }
 
+            // Indirect invs are orphan roots requested by the node rather than announcements
+            if !inv.is_orphan_root {
+                self.ctx.block_propagation().record_inv(inv.hash);
+            }
+
             match self.ctx.get_orphan_roots_if_known(&session, inv.hash).await {
                 OrphanOutput::Unknown => {}           // Keep processing this inv
                 OrphanOutput::NoRoots(_) => continue, // Existing orphan w/o missing roots
@@ -119,16 +127,23 @@ impl HandleRelayInvsFlow {
             if self.ctx.is_ibd_running() && !session.async_is_nearly_synced().await {
                 // Note: If the node is considered nearly synced we continue processing relay blocks even though an IBD is in progress.
                 // For instance this means that downloading a side-chain from a delayed node does not interop the normal flow of live blocks.
-                debug!("Got relay block {} while in IBD and the node is out of sync, continuing...", inv.hash);
+                debug!(
+                    hash:% = inv.hash, peer_id:% = self.router.identity();
+                    "Got relay block {} while in IBD and the node is out of sync, continuing...", inv.hash
+                );
                 continue;
             }
 
             // We keep the request scope alive until consensus processes the block
             let Some((block, request_scope)) = self.request_block(inv.hash, self.msg_route.id()).await? else {
-                debug!("Relay block {} was already requested from another peer, continuing...", inv.hash);
+                debug!(
+                    hash:% = inv.hash, peer_id:% = self.router.identity();
+                    "Relay block {} was already requested from another peer, continuing...", inv.hash
+                );
                 continue;
             };
             request_scope.report_obtained();
+            self.ctx.block_propagation().record_header(inv.hash);
 
             if block.is_header_only() {
                 return Err(ProtocolError::OtherOwned(format!("sent header of {} where expected block with body", block.hash())));
@@ -144,6 +159,7 @@ impl HandleRelayInvsFlow {
             // that means the process started by a proper and relevant relay block
             if !inv.is_orphan_root && !broadcast {
                 debug!(
+                    hash:% = inv.hash, peer_id:% = self.router.identity();
                     "Relay block {} has lower blue work than virtual's merge depth root ({} <= {}), hence we are skipping it",
                     inv.hash, block.header.blue_work, blue_work_threshold
                 );
@@ -153,9 +169,15 @@ impl HandleRelayInvsFlow {
             let BlockValidationFutures { block_task, mut virtual_state_task } = session.validate_and_insert_block(block.clone());
 
             let ancestor_batch = match block_task.await {
-                Ok(_) => Default::default(),
+                Ok(_) => {
+                    self.ctx.block_propagation().record_validated(inv.hash);
+                    Default::default()
+                }
                 Err(RuleError::MissingParents(missing_parents)) => {
-                    debug!("Block {} is orphan and has missing parents: {:?}", block.hash(), missing_parents);
+                    debug!(
+                        hash:% = block.hash(), peer_id:% = self.router.identity();
+                        "Block {} is orphan and has missing parents: {:?}", block.hash(), missing_parents
+                    );
                     if let Some(mut ancestor_batch) = self.process_orphan(&session, block.clone(), inv.known_within_range).await? {
                         // Block is not an orphan, retrying
                         let BlockValidationFutures { block_task: block_task_inner, virtual_state_task: virtual_state_task_inner } =
@@ -171,13 +193,16 @@ impl HandleRelayInvsFlow {
                         }
 
                         match block_task_inner.await {
-                            Ok(_) => match ancestor_batch.blocks.len() {
-                                0 => debug!("Retried orphan block {} successfully", block.hash()),
-                                n => {
-                                    self.ctx.log_block_event(BlockLogEvent::Unorphaned(ancestor_batch.blocks[0].hash(), n));
-                                    debug!("Unorphaned {} ancestors and retried orphan block {} successfully", n, block.hash())
+                            Ok(_) => {
+                                self.ctx.block_propagation().record_validated(inv.hash);
+                                match ancestor_batch.blocks.len() {
+                                    0 => debug!("Retried orphan block {} successfully", block.hash()),
+                                    n => {
+                                        self.ctx.log_block_event(BlockLogEvent::Unorphaned(ancestor_batch.blocks[0].hash(), n));
+                                        debug!("Unorphaned {} ancestors and retried orphan block {} successfully", n, block.hash())
+                                    }
                                 }
-                            },
+                            }
                             Err(rule_error) => return Err(rule_error.into()),
                         }
                         ancestor_batch
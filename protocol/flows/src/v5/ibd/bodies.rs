@@ -0,0 +1,197 @@
+use kaspa_consensus_core::{block::Block, merkle::calc_hash_merkle_root_with_options};
+use kaspa_consensusmanager::spawn_blocking;
+use kaspa_hashes::Hash;
+use kaspa_p2p_lib::common::ProtocolError;
+use rayon::prelude::*;
+
+/// The messaging used for downloading the block bodies of an IBD chunk from the syncer
+#[async_trait::async_trait]
+pub(super) trait IbdBlocksSyncer: Send {
+    async fn request_ibd_blocks(&mut self, hashes: &[Hash]) -> Result<(), ProtocolError>;
+
+    async fn recv_ibd_block(&mut self) -> Result<Block, ProtocolError>;
+}
+
+/// Returns the hash of the first block whose body does not match the hash merkle root of its header.
+/// The merkle roots are recomputed in parallel, including the mass commitment of the transactions
+/// for blocks above `storage_mass_activation_daa_score`, as done by the body processor.
+pub(super) fn find_corrupted_body(blocks: &[Block], storage_mass_activation_daa_score: u64) -> Option<Hash> {
+    blocks
+        .par_iter()
+        .find_first(|block| {
+            let storage_mass_activated = block.header.daa_score > storage_mass_activation_daa_score;
+            calc_hash_merkle_root_with_options(block.transactions.iter(), storage_mass_activated) != block.header.hash_merkle_root
+        })
+        .map(|block| block.hash())
+}
+
+/// Downloads the blocks of `chunk` from the syncer. Unless `merkle_check` is `None`, the bodies are checked against
+/// the hash merkle roots of their headers before being returned, so that a corrupted body fails the whole chunk with
+/// [`ProtocolError::MisbehavingPeer`] rather than being detected deep in the block processing pipeline, where it can
+/// hardly be attributed to the syncer. `merkle_check` holds the storage mass activation DAA score.
+///
+/// Note that the header of a received block is bound to the requested hash, hence to the header validated
+/// during the headers phase of the IBD.
+pub(super) async fn download_bodies_chunk(
+    syncer: &mut impl IbdBlocksSyncer,
+    chunk: &[Hash],
+    merkle_check: Option<u64>,
+) -> Result<Vec<Block>, ProtocolError> {
+    syncer.request_ibd_blocks(chunk).await?;
+    let mut blocks = Vec::with_capacity(chunk.len());
+    for &expected_hash in chunk {
+        let block = syncer.recv_ibd_block().await?;
+        if block.hash() != expected_hash {
+            return Err(ProtocolError::OtherOwned(format!("expected block {} but got {}", expected_hash, block.hash())));
+        }
+        if block.is_header_only() {
+            return Err(ProtocolError::OtherOwned(format!("sent header of {} where expected block with body", block.hash())));
+        }
+        blocks.push(block);
+    }
+
+    let Some(storage_mass_activation_daa_score) = merkle_check else {
+        return Ok(blocks);
+    };
+    let (blocks, corrupted) = spawn_blocking(move || {
+        let corrupted = find_corrupted_body(&blocks, storage_mass_activation_daa_score);
+        (blocks, corrupted)
+    })
+    .await
+    .unwrap();
+    match corrupted {
+        Some(hash) => Err(ProtocolError::MisbehavingPeer(format!("the body of block {hash} does not match its hash merkle root"))),
+        None => Ok(blocks),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::{
+        header::Header,
+        merkle::calc_hash_merkle_root,
+        subnets::SUBNETWORK_ID_NATIVE,
+        tx::{Transaction, TransactionOutput},
+    };
+    use std::collections::{HashMap, HashSet, VecDeque};
+
+    fn create_block(daa_score: u64) -> Block {
+        let transactions = (0..3)
+            .map(|i| {
+                let tx = Transaction::new(
+                    0,
+                    vec![],
+                    vec![TransactionOutput::new(daa_score + i, Default::default())],
+                    0,
+                    SUBNETWORK_ID_NATIVE,
+                    0,
+                    vec![],
+                );
+                tx.set_mass(1000 + i);
+                tx
+            })
+            .collect::<Vec<_>>();
+        let mut header = Header::from_precomputed_hash(Hash::from_u64_word(daa_score), vec![]);
+        header.daa_score = daa_score;
+        header.hash_merkle_root = calc_hash_merkle_root(transactions.iter());
+        header.finalize();
+        Block::new(header, transactions)
+    }
+
+    /// Replaces the transactions of the block keeping its header
+    fn corrupt(block: &Block) -> Block {
+        let mut transactions = (*block.transactions).clone();
+        transactions.pop();
+        Block::from_arcs(block.header.clone(), transactions.into())
+    }
+
+    /// A syncer serving the bodies it holds, some of which may be corrupted
+    struct MockSyncer {
+        blocks: HashMap<Hash, Block>,
+        pending: VecDeque<Hash>,
+    }
+
+    impl MockSyncer {
+        fn new(blocks: impl Iterator<Item = Block>) -> Self {
+            Self { blocks: blocks.map(|block| (block.hash(), block)).collect(), pending: Default::default() }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl IbdBlocksSyncer for MockSyncer {
+        async fn request_ibd_blocks(&mut self, hashes: &[Hash]) -> Result<(), ProtocolError> {
+            self.pending.extend(hashes);
+            Ok(())
+        }
+
+        async fn recv_ibd_block(&mut self) -> Result<Block, ProtocolError> {
+            let hash = self.pending.pop_front().ok_or(ProtocolError::Other("no pending request"))?;
+            self.blocks.get(&hash).cloned().ok_or(ProtocolError::Other("unknown block"))
+        }
+    }
+
+    #[test]
+    fn test_find_corrupted_body() {
+        let blocks = (1..=100).map(create_block).collect::<Vec<_>>();
+        assert_eq!(find_corrupted_body(&blocks, u64::MAX), None);
+
+        // The mass is committed to only once storage mass is activated
+        assert_eq!(find_corrupted_body(&blocks, 0), Some(blocks[0].hash()));
+
+        let mut corrupted = blocks.clone();
+        corrupted[40] = corrupt(&blocks[40]);
+        corrupted[70] = corrupt(&blocks[70]);
+        assert_eq!(find_corrupted_body(&corrupted, u64::MAX), Some(blocks[40].hash()));
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_body_failover() {
+        let blocks = (1..=250).map(create_block).collect::<Vec<_>>();
+        let hashes = blocks.iter().map(|block| block.hash()).collect::<Vec<_>>();
+        let corrupted_hash = hashes[150];
+
+        let corrupted_syncer =
+            MockSyncer::new(blocks.iter().map(|block| if block.hash() == corrupted_hash { corrupt(block) } else { block.clone() }));
+        let honest_syncer = MockSyncer::new(blocks.iter().cloned());
+
+        // Without the check the corrupted body is handed over for processing
+        let mut syncer = MockSyncer::new(corrupted_syncer.blocks.values().cloned());
+        let chunk = download_bodies_chunk(&mut syncer, &hashes[100..200], None).await.unwrap();
+        assert!(chunk.iter().any(|block| block.hash() == corrupted_hash));
+
+        // Bodies are synced chunk by chunk from the first syncer, the IBD moving on to the next syncer
+        // with the remaining missing bodies once a syncer fails
+        let mut syncers = VecDeque::from([corrupted_syncer, honest_syncer]);
+        let mut missing = hashes.clone();
+        let mut queued = HashSet::new();
+        let mut failures = 0;
+        while let Some(mut syncer) = syncers.pop_front() {
+            let mut failed = false;
+            for chunk in missing.clone().chunks(99) {
+                match download_bodies_chunk(&mut syncer, chunk, Some(u64::MAX)).await {
+                    Ok(chunk_blocks) => {
+                        assert!(chunk_blocks.iter().all(|block| block.transactions.len() == 3));
+                        queued.extend(chunk_blocks.iter().map(|block| block.hash()));
+                        missing.retain(|hash| !queued.contains(hash));
+                    }
+                    Err(ProtocolError::MisbehavingPeer(_)) => {
+                        // No block of the failed chunk was queued
+                        assert!(chunk.iter().all(|hash| !queued.contains(hash)));
+                        failures += 1;
+                        failed = true;
+                        break;
+                    }
+                    Err(err) => panic!("unexpected error {err}"),
+                }
+            }
+            if !failed {
+                break;
+            }
+        }
+
+        assert_eq!(failures, 1);
+        assert!(missing.is_empty());
+        assert_eq!(queued.len(), hashes.len());
+    }
+}
@@ -6,9 +6,11 @@ use crate::{
     },
 };
 use futures::future::{join_all, select, try_join_all, Either};
+use itertools::Itertools;
 use kaspa_consensus_core::{
-    api::BlockValidationFuture,
+    api::{BlockValidationFuture, TrustedBlocksSummary},
     block::Block,
+    config::checkpoints::validate_pruning_points_against_checkpoints,
     header::Header,
     pruning::{PruningPointProof, PruningPointsList},
     BlockHashSet,
@@ -29,12 +31,22 @@ use kaspa_p2p_lib::{
 };
 use kaspa_utils::channel::JobReceiver;
 use std::{
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     time::{Duration, Instant},
 };
 use tokio::time::sleep;
 
-use super::{progress::ProgressReporter, HeadersChunk, PruningPointUtxosetChunkStream, IBD_BATCH_SIZE};
+use super::{
+    bodies::{download_bodies_chunk, IbdBlocksSyncer},
+    progress::{ProgressReporter, ProofValidationProgressReporter},
+    HeadersChunk, PruningPointUtxosetChunkStream, IBD_BATCH_SIZE,
+};
+
+/// Number of trusted blocks submitted to consensus at once. Progress is reported between batches
+const TRUSTED_BLOCKS_BATCH_SIZE: usize = 1000;
 
 /// Flow for managing IBD - Initial Block Download
 pub struct IbdFlow {
@@ -78,12 +90,21 @@ impl IbdFlow {
     async fn start_impl(&mut self) -> Result<(), ProtocolError> {
         while let Ok(relay_block) = self.relay_receiver.recv().await {
             if let Some(_guard) = self.ctx.try_set_ibd_running(self.router.key(), relay_block.header.daa_score) {
-                info!("IBD started with peer {}", self.router);
+                info!(
+                    peer_id:% = self.router.identity(), peer:% = self.router, hash:% = relay_block.hash();
+                    "IBD started with peer {}", self.router
+                );
 
                 match self.ibd(relay_block).await {
-                    Ok(_) => info!("IBD with peer {} completed successfully", self.router),
+                    Ok(_) => info!(
+                        peer_id:% = self.router.identity(), peer:% = self.router;
+                        "IBD with peer {} completed successfully", self.router
+                    ),
                     Err(e) => {
-                        info!("IBD with peer {} completed with error: {}", self.router, e);
+                        info!(
+                            peer_id:% = self.router.identity(), peer:% = self.router, error:% = e;
+                            "IBD with peer {} completed with error: {}", self.router, e
+                        );
                         return Err(e);
                     }
                 }
@@ -127,7 +148,10 @@ impl IbdFlow {
                         session = self.ctx.consensus().session().await;
                     }
                     Err(e) => {
-                        info!("IBD with headers proof from {} was unsuccessful ({})", self.router, e);
+                        info!(
+                            peer_id:% = self.router.identity(), peer:% = self.router, error:% = e;
+                            "IBD with headers proof from {} was unsuccessful ({})", self.router, e
+                        );
                         staging.cancel();
                         return Err(e);
                     }
@@ -214,7 +238,7 @@ impl IbdFlow {
         syncer_virtual_selected_parent: Hash,
         relay_block: &Block,
     ) -> Result<(), ProtocolError> {
-        info!("Starting IBD with headers proof with peer {}", self.router);
+        info!(peer_id:% = self.router.identity(), peer:% = self.router; "Starting IBD with headers proof with peer {}", self.router);
 
         let staging_session = staging.session().await;
 
@@ -238,7 +262,23 @@ impl IbdFlow {
         let consensus = self.ctx.consensus().session().await;
 
         // The proof is validated in the context of current consensus
-        let proof = consensus.clone().spawn_blocking(move |c| c.validate_pruning_proof(&proof).map(|()| proof)).await?;
+        let (ctx, router) = (self.ctx.clone(), self.router.clone());
+        let proof = consensus
+            .clone()
+            .spawn_blocking(move |c| {
+                let cancel = AtomicBool::new(false);
+                let reporter = ProofValidationProgressReporter::new();
+                let progress = |progress| {
+                    // Validation might take several minutes, so we stop validating if the syncer peer disconnected meanwhile
+                    if router.is_closed() {
+                        cancel.store(true, Ordering::Relaxed);
+                    }
+                    ctx.set_ibd_proof_validation_progress(progress);
+                    reporter.report(progress);
+                };
+                c.validate_pruning_proof_with_progress(&proof, &progress, &cancel).map(|()| proof)
+            })
+            .await?;
 
         let proof_pruning_point = proof[0].last().expect("was just ensured by validation").hash;
 
@@ -267,6 +307,15 @@ impl IbdFlow {
             return Err(ProtocolError::Other("the first pruning point in the list is expected to be genesis"));
         }
 
+        // A pruning points chain contradicting the hard-coded checkpoints leads to an alternative chain, so the peer is banned
+        if let Err(err) = validate_pruning_points_against_checkpoints(&self.ctx.config.checkpoints, &pruning_points) {
+            warn!("Banning peer {} since its pruning points chain contradicts the checkpoints: {}", self.router, err);
+            if let Some(connection_manager) = self.ctx.connection_manager() {
+                connection_manager.ban(self.router.net_address().ip()).await;
+            }
+            return Err(ProtocolError::MisbehavingPeer(err.to_string()));
+        }
+
         // Check if past pruning points violate finality of current consensus
         if self.ctx.consensus().session().await.async_are_pruning_points_violating_finality(pruning_points.clone()).await {
             // TODO: consider performing additional actions on finality conflicts in addition to disconnecting from the peer (e.g., banning, rpc notification)
@@ -339,21 +388,40 @@ impl IbdFlow {
 
         // TODO: add logs to staging commit process
 
-        info!("Starting to process {} trusted blocks", trusted_set.len());
-        let mut last_time = Instant::now();
-        let mut last_index: usize = 0;
-        for (i, tb) in trusted_set.into_iter().enumerate() {
+        let total = trusted_set.len();
+        info!("Starting to process {} trusted blocks", total);
+        let start_time = Instant::now();
+        let mut last_time = start_time;
+        let mut last_processed: usize = 0;
+        let mut summary = TrustedBlocksSummary::default();
+        let mut trusted_set = trusted_set.into_iter();
+        loop {
+            // The trusted set is topologically sorted, so a batch only depends on its own blocks and on previous batches
+            let batch = trusted_set.by_ref().take(TRUSTED_BLOCKS_BATCH_SIZE).collect_vec();
+            if batch.is_empty() {
+                break;
+            }
+            summary += staging.validate_and_insert_trusted_blocks(batch).await?;
             let now = Instant::now();
             let passed = now.duration_since(last_time);
             if passed > Duration::from_secs(1) {
-                info!("Processed {} trusted blocks in the last {:.2}s (total {})", i - last_index, passed.as_secs_f64(), i);
+                info!(
+                    "Processed {} trusted blocks in the last {:.2}s (total {}/{})",
+                    summary.blocks - last_processed,
+                    passed.as_secs_f64(),
+                    summary.blocks,
+                    total
+                );
                 last_time = now;
-                last_index = i;
+                last_processed = summary.blocks;
             }
-            // TODO: queue and join in batches
-            staging.validate_and_insert_trusted_block(tb).virtual_state_task.await?;
         }
-        info!("Done processing trusted blocks");
+        info!(
+            "Done processing {} trusted blocks ({} with body) in {:.2}s",
+            summary.blocks,
+            summary.bodies,
+            start_time.elapsed().as_secs_f64()
+        );
         Ok(proof_pruning_point)
     }
 
@@ -555,29 +623,40 @@ staging selected tip ({}) is too small or negative. Aborting IBD...",
         consensus: &ConsensusProxy,
         chunk: &[Hash],
     ) -> Result<QueueChunkOutput, ProtocolError> {
-        let mut jobs = Vec::with_capacity(chunk.len());
-        let mut current_daa_score = 0;
-        let mut current_timestamp = 0;
+        let merkle_check = (!self.ctx.config.skip_ibd_merkle_check).then_some(self.ctx.config.storage_mass_activation_daa_score);
+        let blocks = match download_bodies_chunk(self, chunk, merkle_check).await {
+            Ok(blocks) => blocks,
+            Err(ProtocolError::MisbehavingPeer(err)) => {
+                // The chunk is dropped as a whole, leaving its bodies to be requested again from another syncer
+                warn!("Banning peer {} since it sent a corrupted block body: {}", self.router, err);
+                if let Some(connection_manager) = self.ctx.connection_manager() {
+                    connection_manager.ban(self.router.net_address().ip()).await;
+                }
+                return Err(ProtocolError::MisbehavingPeer(err));
+            }
+            Err(err) => return Err(err),
+        };
+
+        let (current_daa_score, current_timestamp) =
+            blocks.last().map_or((0, 0), |block| (block.header.daa_score, block.header.timestamp));
+        let jobs = blocks.into_iter().map(|block| consensus.validate_and_insert_block(block).virtual_state_task).collect();
+        Ok(QueueChunkOutput { jobs, daa_score: current_daa_score, timestamp: current_timestamp })
+    }
+}
+
+#[async_trait::async_trait]
+impl IbdBlocksSyncer for IbdFlow {
+    async fn request_ibd_blocks(&mut self, hashes: &[Hash]) -> Result<(), ProtocolError> {
         self.router
             .enqueue(make_message!(
                 Payload::RequestIbdBlocks,
-                RequestIbdBlocksMessage { hashes: chunk.iter().map(|h| h.into()).collect() }
+                RequestIbdBlocksMessage { hashes: hashes.iter().map(|h| h.into()).collect() }
             ))
-            .await?;
-        for &expected_hash in chunk {
-            let msg = dequeue_with_timeout!(self.incoming_route, Payload::IbdBlock)?;
-            let block: Block = msg.try_into()?;
-            if block.hash() != expected_hash {
-                return Err(ProtocolError::OtherOwned(format!("expected block {} but got {}", expected_hash, block.hash())));
-            }
-            if block.is_header_only() {
-                return Err(ProtocolError::OtherOwned(format!("sent header of {} where expected block with body", block.hash())));
-            }
-            current_daa_score = block.header.daa_score;
-            current_timestamp = block.header.timestamp;
-            jobs.push(consensus.validate_and_insert_block(block).virtual_state_task);
-        }
+            .await
+    }
 
-        Ok(QueueChunkOutput { jobs, daa_score: current_daa_score, timestamp: current_timestamp })
+    async fn recv_ibd_block(&mut self) -> Result<Block, ProtocolError> {
+        let msg = dequeue_with_timeout!(self.incoming_route, Payload::IbdBlock)?;
+        Ok(msg.try_into()?)
     }
 }
@@ -1,7 +1,9 @@
 use std::time::{Duration, Instant};
 
 use chrono::{Local, LocalResult, TimeZone};
+use kaspa_consensus_core::pruning::PruningProofValidationProgress;
 use kaspa_core::info;
+use parking_lot::Mutex;
 
 /// Minimum number of items to report
 const REPORT_BATCH_GRANULARITY: usize = 500;
@@ -64,3 +66,36 @@ impl ProgressReporter {
         info!("IBD: Processed {} {} (100%)", self.processed, self.object_name);
     }
 }
+
+/// Logs the progress of a pruning point proof validation. Progress is reported concurrently by the consensus
+/// threads validating the proof levels, hence the interior mutability
+pub struct ProofValidationProgressReporter {
+    last_log_time: Mutex<Instant>,
+}
+
+impl ProofValidationProgressReporter {
+    pub fn new() -> Self {
+        Self { last_log_time: Mutex::new(Instant::now()) }
+    }
+
+    pub fn report(&self, progress: PruningProofValidationProgress) {
+        let is_complete = progress.processed_levels == progress.total_levels;
+        let mut last_log_time = self.last_log_time.lock();
+        let now = Instant::now();
+        if now - *last_log_time < REPORT_TIME_GRANULARITY && !is_complete {
+            return;
+        }
+        let percent = (progress.processed_headers as f64 / progress.total_headers.max(1) as f64 * 100.0) as i32;
+        info!(
+            "IBD: Validated {} pruning proof headers ({}%), {} out of {} levels complete",
+            progress.processed_headers, percent, progress.processed_levels, progress.total_levels
+        );
+        *last_log_time = now;
+    }
+}
+
+impl Default for ProofValidationProgressReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,15 +1,14 @@
 use crate::{flow_context::FlowContext, flow_trait::Flow};
-use kaspa_core::{debug, task::tick::TickReason};
+use kaspa_core::{debug, task::tick::TickReason, warn};
 use kaspa_p2p_lib::{
     common::ProtocolError,
     dequeue, dequeue_with_timeout, make_message,
     pb::{kaspad_message::Payload, PingMessage, PongMessage},
-    IncomingRoute, Router,
+    run_keepalive, IncomingRoute, PingTransport, Router,
 };
-use rand::Rng;
 use std::{
     sync::{Arc, Weak},
-    time::{Duration, Instant},
+    time::Duration,
 };
 
 /// Flow for managing a loop receiving pings and responding with pongs
@@ -46,9 +45,8 @@ impl ReceivePingsFlow {
     }
 }
 
-pub const PING_INTERVAL: Duration = Duration::from_secs(120); // 2 minutes
-
-/// Flow for managing a loop sending pings and waiting for pongs
+/// Flow for managing a loop sending pings and waiting for pongs, disconnecting the peer once it stops answering.
+/// See [`run_keepalive`] for the keepalive logic and [`FlowContext::keepalive_config`] for its settings.
 pub struct SendPingsFlow {
     ctx: FlowContext,
 
@@ -76,27 +74,45 @@ impl SendPingsFlow {
     }
 
     async fn start_impl(&mut self) -> Result<(), ProtocolError> {
-        loop {
-            // Wait `PING_INTERVAL` between pings
-            if let TickReason::Shutdown = self.ctx.tick_service.tick(PING_INTERVAL).await {
-                return Ok(());
+        let config = *self.ctx.keepalive_config();
+        let Some(tracker) = self.router.upgrade().map(|router| router.keepalive().clone()) else {
+            return Err(ProtocolError::ConnectionClosed);
+        };
+        let result = run_keepalive(&config, &tracker, self).await;
+        if let Err(ProtocolError::DeadPeer(missed)) = result {
+            warn!("Peer {} left {} consecutive pings unanswered, disconnecting it as dead", self.peer, missed);
+            if let Some(router) = self.router.upgrade().filter(|router| router.is_outbound()) {
+                self.ctx.address_manager.lock().mark_connection_failure(router.net_address().into());
             }
+        }
+        result
+    }
+}
 
-            // Create a fresh random nonce for each ping
-            let nonce = rand::thread_rng().gen::<u64>();
-            let ping = make_message!(Payload::Ping, PingMessage { nonce });
-            let ping_time = Instant::now();
-            let Some(router) = self.router.upgrade() else {
-                return Err(ProtocolError::ConnectionClosed);
-            };
-            router.enqueue(ping).await?;
-            let pong = dequeue_with_timeout!(self.incoming_route, Payload::Pong)?;
-            if pong.nonce != nonce {
-                return Err(ProtocolError::Other("nonce mismatch between ping and pong"));
-            } else {
+#[async_trait::async_trait]
+impl PingTransport for SendPingsFlow {
+    async fn send_ping(&mut self, nonce: u64) -> Result<(), ProtocolError> {
+        let Some(router) = self.router.upgrade() else {
+            return Err(ProtocolError::ConnectionClosed);
+        };
+        router.enqueue(make_message!(Payload::Ping, PingMessage { nonce })).await
+    }
+
+    async fn recv_pong(&mut self, timeout: Duration) -> Result<Option<u64>, ProtocolError> {
+        match dequeue_with_timeout!(self.incoming_route, Payload::Pong, timeout) {
+            Ok(pong) => {
                 debug!("Successful ping with peer {} (nonce: {})", self.peer, pong.nonce);
+                Ok(Some(pong.nonce))
+            }
+            Err(ProtocolError::Timeout(_)) => {
+                debug!("Ping to peer {} was left unanswered for {:?}", self.peer, timeout);
+                Ok(None)
             }
-            router.set_last_ping_duration(ping_time.elapsed().as_millis() as u64);
+            Err(err) => Err(err),
         }
     }
+
+    async fn wait(&mut self, duration: Duration) -> bool {
+        !matches!(self.ctx.tick_service.tick(duration).await, TickReason::Shutdown)
+    }
 }
@@ -36,6 +36,7 @@ impl HandleAntipastRequests {
         loop {
             let (msg, request_id) = dequeue_with_request_id!(self.incoming_route, Payload::RequestAntipast)?;
             let (block, context): (Hash, Hash) = msg.try_into()?;
+            self.router.apply_request_cost_budget().await?;
 
             debug!("received anticone request with block hash: {}, context hash: {} for peer {}", block, context, self.router);
 
@@ -45,8 +46,9 @@ impl HandleAntipastRequests {
             // `RequestAntipast` is expected to be called by the syncee for getting the antipast of `sink`
             // intersected by past of the relayed block. We do not expect the relay block to be too much after
             // the sink (in fact usually it should be in its past or anticone), hence we bound the expected traversal to be
-            // in the order of `mergeset_size_limit`.
-            let hashes = session.async_get_antipast_from_pov(block, context, Some(self.ctx.config.mergeset_size_limit * 2)).await?;
+            // in the order of `mergeset_size_limit`. The bound is further capped by the configured max anticone size.
+            let max_traversal = (self.ctx.config.mergeset_size_limit * 2).min(self.ctx.request_cost_limits().max_anticone_size as u64);
+            let hashes = session.async_get_antipast_from_pov(block, context, Some(max_traversal)).await?;
             let mut headers = session
                 .spawn_blocking(|c| hashes.into_iter().map(|h| c.get_header(h)).collect::<Result<Vec<_>, ConsensusError>>())
                 .await?;
@@ -55,13 +57,15 @@ impl HandleAntipastRequests {
             // Sort the headers in bottom-up topological order before sending
             headers.sort_by(|a, b| a.blue_work.cmp(&b.blue_work));
 
-            self.router
-                .enqueue(make_response!(
-                    Payload::BlockHeaders,
-                    BlockHeadersMessage { block_headers: headers.into_iter().map(|header| header.as_ref().into()).collect() },
-                    request_id
-                ))
-                .await?;
+            // Each served header costs roughly a single store read
+            let cost = headers.len() as u64;
+            let msg = make_response!(
+                Payload::BlockHeaders,
+                BlockHeadersMessage { block_headers: headers.into_iter().map(|header| header.as_ref().into()).collect() },
+                request_id
+            );
+            self.router.record_served(&msg, cost);
+            self.router.enqueue(msg).await?;
             self.router.enqueue(make_response!(Payload::DoneHeaders, DoneHeadersMessage {}, request_id)).await?;
         }
     }
@@ -35,17 +35,20 @@ impl RequestBlockLocatorFlow {
         loop {
             let (msg, request_id) = dequeue_with_request_id!(self.incoming_route, Payload::RequestBlockLocator)?;
             let (high, limit) = msg.try_into()?;
-
-            let locator =
-                self.ctx.consensus().session().await.async_create_block_locator_from_pruning_point(high, limit as usize).await?;
-
-            self.router
-                .enqueue(make_response!(
-                    Payload::BlockLocator,
-                    BlockLocatorMessage { hashes: locator.into_iter().map(|hash| hash.into()).collect() },
-                    request_id
-                ))
-                .await?;
+            self.router.apply_request_cost_budget().await?;
+
+            // Cap the number of locator steps the peer can ask for
+            let limit = (limit as usize).min(self.ctx.request_cost_limits().max_locator_steps);
+            let locator = self.ctx.consensus().session().await.async_create_block_locator_from_pruning_point(high, limit).await?;
+
+            let cost = locator.len() as u64;
+            let msg = make_response!(
+                Payload::BlockLocator,
+                BlockLocatorMessage { hashes: locator.into_iter().map(|hash| hash.into()).collect() },
+                request_id
+            );
+            self.router.record_served(&msg, cost);
+            self.router.enqueue(msg).await?;
         }
     }
 }
@@ -36,6 +36,7 @@ impl RequestIbdChainBlockLocatorFlow {
         loop {
             let (msg, request_id) = dequeue_with_request_id!(self.incoming_route, Payload::RequestIbdChainBlockLocator)?;
             let (low, high) = msg.try_into()?;
+            self.router.apply_request_cost_budget().await?;
 
             let locator =
                 match (self.ctx.consensus().session().await).async_create_virtual_selected_chain_block_locator(low, high).await {
@@ -51,13 +52,17 @@ impl RequestIbdChainBlockLocatorFlow {
                     }
                 }?;
 
-            self.router
-                .enqueue(make_response!(
-                    Payload::IbdChainBlockLocator,
-                    IbdChainBlockLocatorMessage { block_locator_hashes: locator.into_iter().map(|hash| hash.into()).collect() },
-                    request_id
-                ))
-                .await?;
+            let cost = locator.len() as u64;
+            let max_locator_steps = self.ctx.request_cost_limits().max_locator_steps;
+            let msg = make_response!(
+                Payload::IbdChainBlockLocator,
+                IbdChainBlockLocatorMessage {
+                    block_locator_hashes: locator.into_iter().take(max_locator_steps).map(|hash| hash.into()).collect()
+                },
+                request_id
+            );
+            self.router.record_served(&msg, cost);
+            self.router.enqueue(msg).await?;
         }
     }
 }
@@ -36,14 +36,17 @@ impl RequestPruningPointProofFlow {
         loop {
             let (_, request_id) = dequeue_with_request_id!(self.incoming_route, Payload::RequestPruningPointProof)?;
             debug!("Got pruning point proof request");
+            self.router.apply_request_cost_budget().await?;
             let proof = self.ctx.consensus().unguarded_session().async_get_pruning_point_proof().await;
-            self.router
-                .enqueue(make_response!(
-                    Payload::PruningPointProof,
-                    PruningPointProofMessage { headers: proof.iter().map(|headers| headers.into()).collect() },
-                    request_id
-                ))
-                .await?;
+            // The proof is cached by consensus, so serving it costs roughly its size in header conversions
+            let cost = proof.iter().map(|headers| headers.len() as u64).sum();
+            let msg = make_response!(
+                Payload::PruningPointProof,
+                PruningPointProofMessage { headers: proof.iter().map(|headers| headers.into()).collect() },
+                request_id
+            );
+            self.router.record_served(&msg, cost);
+            self.router.enqueue(msg).await?;
             debug!("Sent pruning point proof");
         }
     }
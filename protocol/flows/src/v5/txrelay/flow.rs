@@ -18,14 +18,18 @@ use kaspa_mining::{
 use kaspa_p2p_lib::{
     common::{ProtocolError, DEFAULT_TIMEOUT},
     dequeue, make_message,
-    pb::{kaspad_message::Payload, RequestTransactionsMessage, TransactionNotFoundMessage},
+    pb::{kaspad_message::Payload, KaspadMessage, RequestTransactionsMessage, TransactionNotFoundMessage, TransactionsBatchMessage},
     IncomingRoute, Router,
 };
-use std::sync::Arc;
+use kaspa_utils::rate_limit::TokenBucket;
+use std::{collections::HashSet, sync::Arc, time::Duration};
 use tokio::time::timeout;
 
 pub(crate) const MAX_TPS_THRESHOLD: u64 = 3000;
 
+/// Maximum number of transactions carried by a single `TransactionsBatchMessage`
+pub(crate) const MAX_TRANSACTIONS_PER_BATCH: usize = 512;
+
 enum Response {
     Transaction(Transaction),
     NotFound(TransactionId),
@@ -47,11 +51,14 @@ pub struct RelayTransactionsFlow {
     router: Arc<Router>,
     /// A route specific for invs messages
     invs_route: IncomingRoute,
-    /// A route for other messages such as Transaction and TransactionNotFound
+    /// A route for other messages such as Transaction, TransactionNotFound and TransactionsBatch
     msg_route: IncomingRoute,
 
     /// Track the number of spam txs coming from this peer
     spam_counter: u64,
+
+    /// Bounds the rate of the transactions requested from this peer while the relay is throttled
+    tx_request_limiter: TokenBucket,
 }
 
 /// Holds the state information for whether we will throttle tx relay or not
@@ -74,7 +81,8 @@ impl Flow for RelayTransactionsFlow {
 
 impl RelayTransactionsFlow {
     pub fn new(ctx: FlowContext, router: Arc<Router>, invs_route: IncomingRoute, msg_route: IncomingRoute) -> Self {
-        Self { ctx, router, invs_route, msg_route, spam_counter: 0 }
+        let tx_request_limiter = TokenBucket::new(MAX_TPS_THRESHOLD, Duration::from_secs(1), MAX_TPS_THRESHOLD);
+        Self { ctx, router, invs_route, msg_route, spam_counter: 0, tx_request_limiter }
     }
 
     pub fn invs_channel_size() -> usize {
@@ -139,20 +147,23 @@ impl RelayTransactionsFlow {
 
         // To reduce the P2P TPS to below the threshold, we need to request up to a max of
         // whatever the balances overage. If MAX_TPS_THRESHOLD is 3000 and the current TPS is 4000,
-        // then we can only request up to 2000 (MAX - (4000 - 3000)) to average out into the threshold.
+        // then we can only request up to 2000 (MAX - (4000 - 3000)) per second to average out into the threshold.
         let curr_p2p_tps = 1000 * snapshot_delta.low_priority_tx_counts / (snapshot_delta.elapsed_time.as_millis().max(1) as u64);
         let overage = if should_throttle && curr_p2p_tps > MAX_TPS_THRESHOLD { curr_p2p_tps - MAX_TPS_THRESHOLD } else { 0 };
 
-        let limit = MAX_TPS_THRESHOLD.saturating_sub(overage);
+        if should_throttle {
+            let limit = MAX_TPS_THRESHOLD.saturating_sub(overage);
+            self.tx_request_limiter.set_rate(limit, Duration::from_secs(1));
+        }
 
         for transaction_id in transaction_ids {
             if let Some(req) = self.ctx.try_adding_transaction_request(transaction_id) {
+                // Dropping the request scope releases the transaction for other peers to request
+                if should_throttle && !self.tx_request_limiter.try_acquire() {
+                    break;
+                }
                 requests.push(req);
             }
-
-            if should_throttle && requests.len() >= limit as usize {
-                break;
-            }
         }
 
         // Request the transactions
@@ -172,21 +183,30 @@ impl RelayTransactionsFlow {
     /// Returns the next Transaction or TransactionNotFound message in msg_route,
     /// returning only one of the message types at a time.
     async fn read_response(&mut self) -> Result<Response, ProtocolError> {
+        let msg = self.read_message().await?;
+        match msg.payload {
+            Some(Payload::Transaction(payload)) => Ok(Response::Transaction(payload.try_into()?)),
+            Some(Payload::TransactionNotFound(payload)) => Ok(Response::NotFound(payload.try_into()?)),
+            _ => Err(ProtocolError::UnexpectedMessage(
+                stringify!(Payload::Transaction | Payload::TransactionNotFound),
+                msg.payload.as_ref().map(|v| v.into()),
+            )),
+        }
+    }
+
+    /// Returns the transactions and not found ids of the next TransactionsBatch message in msg_route
+    async fn read_batch_response(&mut self) -> Result<(Vec<Transaction>, Vec<TransactionId>), ProtocolError> {
+        let msg = self.read_message().await?;
+        match msg.payload {
+            Some(Payload::TransactionsBatch(payload)) => Ok(payload.try_into()?),
+            _ => Err(ProtocolError::UnexpectedMessage(stringify!(Payload::TransactionsBatch), msg.payload.as_ref().map(|v| v.into()))),
+        }
+    }
+
+    async fn read_message(&mut self) -> Result<KaspadMessage, ProtocolError> {
         match timeout(DEFAULT_TIMEOUT, self.msg_route.recv()).await {
-            Ok(op) => {
-                if let Some(msg) = op {
-                    match msg.payload {
-                        Some(Payload::Transaction(payload)) => Ok(Response::Transaction(payload.try_into()?)),
-                        Some(Payload::TransactionNotFound(payload)) => Ok(Response::NotFound(payload.try_into()?)),
-                        _ => Err(ProtocolError::UnexpectedMessage(
-                            stringify!(Payload::Transaction | Payload::TransactionNotFound),
-                            msg.payload.as_ref().map(|v| v.into()),
-                        )),
-                    }
-                } else {
-                    Err(ProtocolError::ConnectionClosed)
-                }
-            }
+            Ok(Some(msg)) => Ok(msg),
+            Ok(None) => Err(ProtocolError::ConnectionClosed),
             Err(_) => {
                 // One reason this may happen is the invs_route being full and preventing
                 // the router from routing other incoming messages
@@ -202,17 +222,27 @@ impl RelayTransactionsFlow {
         should_throttle: bool,
     ) -> Result<(), ProtocolError> {
         let mut transactions: Vec<Transaction> = Vec::with_capacity(requests.len());
-        for request in requests {
-            let response = self.read_response().await?;
-            let transaction_id = response.transaction_id();
-            if transaction_id != request.req {
-                return Err(ProtocolError::OtherOwned(format!(
-                    "requested transaction id {} but got transaction {}",
-                    request.req, transaction_id
-                )));
+        if self.router.properties().supports_transactions_batch() {
+            // Every requested id is explicitly acknowledged by the batches, so the requests are
+            // complete once all of them are accounted for
+            let mut pending: HashSet<TransactionId> = requests.iter().map(|request| request.req).collect();
+            while !pending.is_empty() {
+                let (batch, not_found) = self.read_batch_response().await?;
+                transactions.extend(resolve_batch(&mut pending, batch, not_found)?);
             }
-            if let Response::Transaction(transaction) = response {
-                transactions.push(transaction);
+        } else {
+            for request in requests {
+                let response = self.read_response().await?;
+                let transaction_id = response.transaction_id();
+                if transaction_id != request.req {
+                    return Err(ProtocolError::OtherOwned(format!(
+                        "requested transaction id {} but got transaction {}",
+                        request.req, transaction_id
+                    )));
+                }
+                if let Response::Transaction(transaction) = response {
+                    transactions.push(transaction);
+                }
             }
         }
         let insert_results = self
@@ -254,9 +284,55 @@ impl RelayTransactionsFlow {
     }
 }
 
+/// Accounts for the transactions and not found ids of a TransactionsBatch response in the `pending` requested ids,
+/// returning the transactions
+fn resolve_batch(
+    pending: &mut HashSet<TransactionId>,
+    transactions: Vec<Transaction>,
+    not_found: Vec<TransactionId>,
+) -> Result<Vec<Transaction>, ProtocolError> {
+    if transactions.len() > MAX_TRANSACTIONS_PER_BATCH {
+        return Err(ProtocolError::OtherOwned(format!(
+            "transactions batch holds {} transactions while the limit is {}",
+            transactions.len(),
+            MAX_TRANSACTIONS_PER_BATCH
+        )));
+    }
+    for transaction_id in transactions.iter().map(|tx| tx.id()).chain(not_found) {
+        if !pending.remove(&transaction_id) {
+            return Err(ProtocolError::OtherOwned(format!("got unrequested or duplicate transaction {} in batch", transaction_id)));
+        }
+    }
+    Ok(transactions)
+}
+
+/// Accumulates the responses to a transactions request into TransactionsBatch messages
+#[derive(Default)]
+struct TransactionsBatchBuilder {
+    batch: TransactionsBatchMessage,
+}
+
+impl TransactionsBatchBuilder {
+    /// Adds a found transaction, returning the batch to send if it is full
+    fn push_transaction(&mut self, transaction: &Transaction) -> Option<TransactionsBatchMessage> {
+        self.batch.transactions.push(transaction.into());
+        (self.batch.transactions.len() == MAX_TRANSACTIONS_PER_BATCH).then(|| std::mem::take(&mut self.batch))
+    }
+
+    fn push_not_found(&mut self, transaction_id: TransactionId) {
+        self.batch.not_found.push(transaction_id.into());
+    }
+
+    /// Returns the last batch, if holding anything
+    fn finish(self) -> Option<TransactionsBatchMessage> {
+        (!self.batch.transactions.is_empty() || !self.batch.not_found.is_empty()).then_some(self.batch)
+    }
+}
+
 // Flow listening to RequestTransactions messages, responding with the requested
 // transactions if those are in the mempool.
-// Missing transactions would be ignored
+// Missing transactions are reported by TransactionNotFound messages, or in the not
+// found ids of the batches for peers supporting TransactionsBatch responses
 pub struct RequestTransactionsFlow {
     ctx: FlowContext,
     router: Arc<Router>,
@@ -283,6 +359,10 @@ impl RequestTransactionsFlow {
         loop {
             let msg = dequeue!(self.incoming_route, Payload::RequestTransactions)?;
             let tx_ids: Vec<_> = msg.try_into()?;
+            if self.router.properties().supports_transactions_batch() {
+                self.respond_with_batches(tx_ids).await?;
+                continue;
+            }
             for transaction_id in tx_ids {
                 if let Some(mutable_tx) =
                     self.ctx.mining_manager().clone().get_transaction(transaction_id, TransactionQuery::TransactionsOnly).await
@@ -301,6 +381,25 @@ impl RequestTransactionsFlow {
             }
         }
     }
+
+    async fn respond_with_batches(&self, tx_ids: Vec<TransactionId>) -> Result<(), ProtocolError> {
+        let mut builder = TransactionsBatchBuilder::default();
+        for transaction_id in tx_ids {
+            if let Some(mutable_tx) =
+                self.ctx.mining_manager().clone().get_transaction(transaction_id, TransactionQuery::TransactionsOnly).await
+            {
+                if let Some(batch) = builder.push_transaction(&mutable_tx.tx) {
+                    self.router.enqueue(make_message!(Payload::TransactionsBatch, batch)).await?;
+                }
+            } else {
+                builder.push_not_found(transaction_id);
+            }
+        }
+        if let Some(batch) = builder.finish() {
+            self.router.enqueue(make_message!(Payload::TransactionsBatch, batch)).await?;
+        }
+        Ok(())
+    }
 }
 
 /// If in the last 10 seconds we exceeded the TPS threshold, we will throttle tx relay
@@ -326,6 +425,91 @@ mod tests {
     use std::time::Duration;
 
     use super::*;
+    use kaspa_consensus_core::subnets::SUBNETWORK_ID_NATIVE;
+    use kaspa_p2p_lib::{
+        convert::model::version::{Version, SERVICE_ONION_ADDRESSES},
+        PeerProperties,
+    };
+    use kaspa_utils::networking::PeerId;
+
+    fn create_transaction(nonce: u64) -> Transaction {
+        Transaction::new(0, vec![], vec![], 0, SUBNETWORK_ID_NATIVE, 0, nonce.to_le_bytes().to_vec())
+    }
+
+    /// Builds the batches responding to a request for `transactions`, the ones at `missing` indices not being found
+    fn build_batches(transactions: &[Transaction], missing: &[usize]) -> Vec<(Vec<Transaction>, Vec<TransactionId>)> {
+        let mut builder = TransactionsBatchBuilder::default();
+        let mut batches = vec![];
+        for (i, transaction) in transactions.iter().enumerate() {
+            if missing.contains(&i) {
+                builder.push_not_found(transaction.id());
+            } else {
+                batches.extend(builder.push_transaction(transaction));
+            }
+        }
+        batches.extend(builder.finish());
+        // Going through the wire format, as received batches do
+        batches.into_iter().map(|batch| batch.try_into().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_full_transactions_batch() {
+        let transactions = (0..MAX_TRANSACTIONS_PER_BATCH as u64 + 10).map(create_transaction).collect::<Vec<_>>();
+        let batches = build_batches(&transactions, &[]);
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].0.len(), MAX_TRANSACTIONS_PER_BATCH);
+        assert_eq!(batches[1].0.len(), 10);
+
+        let mut pending = transactions.iter().map(|tx| tx.id()).collect::<HashSet<_>>();
+        let mut received = vec![];
+        for (batch, not_found) in batches {
+            assert!(not_found.is_empty());
+            received.extend(resolve_batch(&mut pending, batch, not_found).unwrap());
+        }
+        assert!(pending.is_empty());
+        assert_eq!(received.iter().map(|tx| tx.id()).collect::<Vec<_>>(), transactions.iter().map(|tx| tx.id()).collect::<Vec<_>>());
+
+        // A single request fitting exactly in a batch is responded by a single batch
+        assert_eq!(build_batches(&transactions[..MAX_TRANSACTIONS_PER_BATCH], &[]).len(), 1);
+    }
+
+    #[test]
+    fn test_partial_not_found_transactions_batch() {
+        let transactions = (0..5).map(create_transaction).collect::<Vec<_>>();
+        let batches = build_batches(&transactions, &[1, 3]);
+        assert_eq!(batches.len(), 1);
+        let (batch, not_found) = batches.into_iter().next().unwrap();
+        assert_eq!(not_found, vec![transactions[1].id(), transactions[3].id()]);
+
+        // The requests are complete with a single batch, without waiting on the not found ids
+        let mut pending = transactions.iter().map(|tx| tx.id()).collect::<HashSet<_>>();
+        let received = resolve_batch(&mut pending, batch.clone(), not_found).unwrap();
+        assert!(pending.is_empty());
+        assert_eq!(received.len(), 3);
+
+        // Unrequested or duplicate ids are rejected
+        let mut pending = transactions[..4].iter().map(|tx| tx.id()).collect::<HashSet<_>>();
+        assert!(resolve_batch(&mut pending, batch.clone(), vec![]).is_err());
+        let mut pending = transactions.iter().map(|tx| tx.id()).collect::<HashSet<_>>();
+        assert!(resolve_batch(&mut pending, batch, vec![transactions[0].id()]).is_err());
+
+        // A batch may hold not found ids only
+        let all_not_found = build_batches(&transactions[..2], &[0, 1]);
+        assert_eq!(all_not_found, vec![(vec![], vec![transactions[0].id(), transactions[1].id()])]);
+    }
+
+    #[test]
+    fn test_legacy_peer_transactions_batch_fallback() {
+        // Peers built from a legacy version message lack the service flag, and are thus requested and
+        // responded with individual Transaction and TransactionNotFound messages
+        let legacy = PeerProperties { services: SERVICE_ONION_ADDRESSES, ..Default::default() };
+        assert!(!legacy.supports_transactions_batch());
+        assert!(!PeerProperties::default().supports_transactions_batch());
+
+        let version = Version::new(None, PeerId::new(uuid::Uuid::new_v4()), "kaspa-simnet".to_string(), None, 6);
+        let current = PeerProperties { services: version.services, ..Default::default() };
+        assert!(current.supports_transactions_batch());
+    }
 
     fn create_snapshot(low_priority_tx_counts: u64, elapsed_time: u64) -> P2pTxCountSample {
         P2pTxCountSample { low_priority_tx_counts, elapsed_time: Duration::from_millis(elapsed_time) }
@@ -103,7 +103,11 @@ pub fn register(ctx: FlowContext, router: Arc<Router>) -> Vec<Box<dyn Flow>> {
             router
                 .subscribe_with_capacity(vec![KaspadMessagePayloadType::InvTransactions], RelayTransactionsFlow::invs_channel_size()),
             router.subscribe_with_capacity(
-                vec![KaspadMessagePayloadType::Transaction, KaspadMessagePayloadType::TransactionNotFound],
+                vec![
+                    KaspadMessagePayloadType::Transaction,
+                    KaspadMessagePayloadType::TransactionNotFound,
+                    KaspadMessagePayloadType::TransactionsBatch,
+                ],
                 RelayTransactionsFlow::txs_channel_size(),
             ),
         )),
@@ -1,5 +1,6 @@
 use crate::{convert::error::ConversionError, core::peer::PeerKey, KaspadMessagePayloadType};
 use kaspa_consensus_core::errors::{block::RuleError, consensus::ConsensusError, pruning::PruningImportError};
+use kaspa_hashes::Hash;
 use kaspa_mining_errors::manager::MiningManagerError;
 use std::time::Duration;
 use thiserror::Error;
@@ -18,6 +19,15 @@ pub enum ProtocolError {
     #[error("Network mismatch - local: {0}, remote: {1}")]
     WrongNetwork(String, String),
 
+    #[error("Consensus params mismatch - local digest: {0}, remote digest: {1}")]
+    ParamsDigestMismatch(Hash, Hash),
+
+    #[error("Genesis mismatch - local: {0}, remote: {1}")]
+    GenesisMismatch(Hash, Hash),
+
+    #[error("peer did not advertise its consensus params digest and genesis hash")]
+    MissingParamsDigest,
+
     #[error("expected message type/s {0} but got {1:?}")]
     UnexpectedMessage(&'static str, Option<KaspadMessagePayloadType>),
 
@@ -52,6 +62,9 @@ pub enum ProtocolError {
     #[error("peer connection is closed")]
     ConnectionClosed,
 
+    #[error("peer is unresponsive, {0} consecutive pings were left unanswered")]
+    DeadPeer(u32),
+
     #[error("incoming route capacity for message type {0:?} has been reached (peer: {1})")]
     IncomingRouteCapacityReached(KaspadMessagePayloadType, String),
 
@@ -85,6 +98,12 @@ impl ProtocolError {
         matches!(self, Self::ConnectionClosed)
     }
 
+    /// Returns whether the peer runs on consensus params or a genesis different from ours, meaning
+    /// it will never be a valid peer
+    pub fn is_incompatible_network_error(&self) -> bool {
+        matches!(self, Self::ParamsDigestMismatch(..) | Self::GenesisMismatch(..))
+    }
+
     pub fn can_send_outgoing_message(&self) -> bool {
         !matches!(self, Self::ConnectionClosed | Self::OutgoingRouteCapacityReached(_))
     }
@@ -1,4 +1,4 @@
-use kaspa_consensus_core::subnets::SubnetworkConversionError;
+use kaspa_consensus_core::subnets::SubnetworkConversionError;
 use thiserror::Error;
 
 #[derive(Clone, Debug, Error)]
@@ -21,12 +21,15 @@ pub enum ConversionError {
     #[error("Integer parsing error: {0}")]
     IntCastingError(#[from] std::num::TryFromIntError),
 
-    #[error(transparent)]
+    #[error(transparent)]
     AddressParsingError(#[from] std::net::AddrParseError),
 
-    #[error(transparent)]
+    #[error(transparent)]
     IdentityError(#[from] uuid::Error),
-
-    #[error(transparent)]
-    SubnetParsingError(#[from] SubnetworkConversionError),
+
+    #[error(transparent)]
+    SubnetParsingError(#[from] SubnetworkConversionError),
+
+    #[error(transparent)]
+    OnionAddressError(#[from] kaspa_utils::networking::OnionAddressError),
 }
@@ -10,10 +10,10 @@ use crate::pb as protowire;
 use kaspa_consensus_core::{
     header::Header,
     pruning::{PruningPointProof, PruningPointsList},
-    tx::{TransactionId, TransactionOutpoint, UtxoEntry},
+    tx::{Transaction, TransactionId, TransactionOutpoint, UtxoEntry},
 };
 use kaspa_hashes::Hash;
-use kaspa_utils::networking::{IpAddress, PeerId};
+use kaspa_utils::networking::{IpAddress, OnionAddress, PeerId};
 
 use std::sync::Arc;
 
@@ -33,6 +33,8 @@ impl From<Version> for protowire::VersionMessage {
             disable_relay_tx: item.disable_relay_tx,
             subnetwork_id: item.subnetwork_id.map(|x| x.into()),
             network: item.network.clone(),
+            params_digest: item.params_digest.map(|x| x.into()),
+            genesis_hash: item.genesis_hash.map(|x| x.into()),
         }
     }
 }
@@ -54,6 +56,8 @@ impl TryFrom<protowire::VersionMessage> for Version {
             disable_relay_tx: msg.disable_relay_tx,
             subnetwork_id: if msg.subnetwork_id.is_none() { None } else { Some(msg.subnetwork_id.unwrap().try_into()?) },
             network: msg.network.clone(),
+            params_digest: msg.params_digest.map(|x| x.try_into()).transpose()?,
+            genesis_hash: msg.genesis_hash.map(|x| x.try_into()).transpose()?,
         })
     }
 }
@@ -175,11 +179,14 @@ impl TryFrom<protowire::BlockLocatorMessage> for Vec<Hash> {
     }
 }
 
-impl TryFrom<protowire::AddressesMessage> for Vec<(IpAddress, u16)> {
+impl TryFrom<protowire::AddressesMessage> for (Vec<(IpAddress, u16)>, Vec<(OnionAddress, u16)>) {
     type Error = ConversionError;
 
     fn try_from(msg: protowire::AddressesMessage) -> Result<Self, Self::Error> {
-        msg.address_list.into_iter().map(|addr| addr.try_into()).collect::<Result<_, _>>()
+        Ok((
+            msg.address_list.into_iter().map(|addr| addr.try_into()).collect::<Result<_, _>>()?,
+            msg.onion_address_list.into_iter().map(|addr| addr.try_into()).collect::<Result<_, _>>()?,
+        ))
     }
 }
 
@@ -207,6 +214,17 @@ impl TryFrom<protowire::TransactionNotFoundMessage> for TransactionId {
     }
 }
 
+impl TryFrom<protowire::TransactionsBatchMessage> for (Vec<Transaction>, Vec<TransactionId>) {
+    type Error = ConversionError;
+
+    fn try_from(msg: protowire::TransactionsBatchMessage) -> Result<Self, Self::Error> {
+        Ok((
+            msg.transactions.into_iter().map(|tx| tx.try_into()).collect::<Result<_, _>>()?,
+            msg.not_found.into_iter().map(|id| id.try_into()).collect::<Result<_, _>>()?,
+        ))
+    }
+}
+
 impl TryFrom<protowire::RequestBlockLocatorMessage> for (Hash, u32) {
     type Error = ConversionError;
     fn try_from(msg: protowire::RequestBlockLocatorMessage) -> Result<Self, Self::Error> {
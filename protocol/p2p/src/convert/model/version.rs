@@ -1,23 +1,36 @@
+use crate::common::ProtocolError;
 use kaspa_consensus_core::subnets::SubnetworkId;
 use kaspa_core::{
     kaspad_env::{name, version},
     time::unix_now,
 };
+use kaspa_hashes::Hash;
 use kaspa_utils::networking::{NetAddress, PeerId};
 
 /// Maximum allowed length for the user agent field in a version message `VersionMessage`.
 pub const MAX_USER_AGENT_LEN: usize = 256;
 
+/// Service flag advertising the support of onion addresses in `AddressesMessage`
+pub const SERVICE_ONION_ADDRESSES: u64 = 1 << 0;
+
+/// Service flag advertising the support of `TransactionsBatchMessage` responses to `RequestTransactionsMessage`
+pub const SERVICE_TRANSACTIONS_BATCH: u64 = 1 << 1;
+
 pub struct Version {
     pub protocol_version: u32,
     pub network: String,
-    pub services: u64, // TODO
+    /// Bit flags of the services supported by the node, see `SERVICE_ONION_ADDRESSES` and `SERVICE_TRANSACTIONS_BATCH`
+    pub services: u64,
     pub timestamp: u64,
     pub address: Option<NetAddress>,
     pub id: PeerId,
     pub user_agent: String,
     pub disable_relay_tx: bool,
     pub subnetwork_id: Option<SubnetworkId>,
+    /// Digest of the consensus params the node runs with. Not advertised by legacy nodes.
+    pub params_digest: Option<Hash>,
+    /// Genesis hash of the network the node runs on. Not advertised by legacy nodes.
+    pub genesis_hash: Option<Hash>,
 }
 
 impl Version {
@@ -31,14 +44,45 @@ impl Version {
         Self {
             protocol_version,
             network,
-            services: 0, // TODO: get number of live services
+            services: SERVICE_ONION_ADDRESSES | SERVICE_TRANSACTIONS_BATCH,
             timestamp: unix_now(),
             address,
             id,
             user_agent: format!("/{}:{}/", name(), version()),
             disable_relay_tx: false,
             subnetwork_id,
+            params_digest: None,
+            genesis_hash: None,
+        }
+    }
+
+    pub fn set_network_identity(&mut self, params_digest: Hash, genesis_hash: Hash) {
+        self.params_digest = Some(params_digest);
+        self.genesis_hash = Some(genesis_hash);
+    }
+
+    /// Verifies the peer advertising this version runs with the local consensus params digest and genesis hash.
+    /// A peer not advertising them is accepted only if `tolerate_missing` is set.
+    pub fn verify_network_identity(
+        &self,
+        params_digest: Hash,
+        genesis_hash: Hash,
+        tolerate_missing: bool,
+    ) -> Result<(), ProtocolError> {
+        if let Some(peer_genesis_hash) = self.genesis_hash {
+            if peer_genesis_hash != genesis_hash {
+                return Err(ProtocolError::GenesisMismatch(genesis_hash, peer_genesis_hash));
+            }
+        }
+        if let Some(peer_params_digest) = self.params_digest {
+            if peer_params_digest != params_digest {
+                return Err(ProtocolError::ParamsDigestMismatch(params_digest, peer_params_digest));
+            }
         }
+        if (self.params_digest.is_none() || self.genesis_hash.is_none()) && !tolerate_missing {
+            return Err(ProtocolError::MissingParamsDigest);
+        }
+        Ok(())
     }
 
     pub fn add_user_agent(&mut self, name: &str, version: &str, comments: &[String]) {
@@ -48,3 +92,41 @@ impl Version {
         self.user_agent.truncate(MAX_USER_AGENT_LEN);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pb;
+
+    fn version(network_identity: Option<(Hash, Hash)>) -> Version {
+        // Going through the wire format, as peer versions do
+        let mut version = Version::new(None, PeerId::new(uuid::Uuid::new_v4()), "kaspa-simnet".to_string(), None, 6);
+        if let Some((params_digest, genesis_hash)) = network_identity {
+            version.set_network_identity(params_digest, genesis_hash);
+        }
+        pb::VersionMessage::from(version).try_into().unwrap()
+    }
+
+    #[test]
+    fn test_verify_network_identity() {
+        let (digest, genesis) = (Hash::from_u64_word(1), Hash::from_u64_word(2));
+        let other = Hash::from_u64_word(3);
+
+        // Matching peer
+        assert!(version(Some((digest, genesis))).verify_network_identity(digest, genesis, false).is_ok());
+
+        // Mismatching peers are rejected regardless of the transition window
+        for tolerate_missing in [false, true] {
+            let result = version(Some((other, genesis))).verify_network_identity(digest, genesis, tolerate_missing);
+            assert!(matches!(result, Err(ProtocolError::ParamsDigestMismatch(local, remote)) if local == digest && remote == other));
+            let result = version(Some((digest, other))).verify_network_identity(digest, genesis, tolerate_missing);
+            assert!(matches!(result, Err(ProtocolError::GenesisMismatch(local, remote)) if local == genesis && remote == other));
+        }
+
+        // Legacy peer, tolerated only during the transition window
+        let legacy = version(None);
+        assert!(legacy.params_digest.is_none() && legacy.genesis_hash.is_none());
+        assert!(legacy.verify_network_identity(digest, genesis, true).is_ok());
+        assert!(matches!(legacy.verify_network_identity(digest, genesis, false), Err(ProtocolError::MissingParamsDigest)));
+    }
+}
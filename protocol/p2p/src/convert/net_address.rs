@@ -7,7 +7,7 @@ use super::error::ConversionError;
 use crate::pb as protowire;
 
 use itertools::Itertools;
-use kaspa_utils::networking::{IpAddress, NetAddress};
+use kaspa_utils::networking::{IpAddress, NetAddress, OnionAddress};
 
 // ----------------------------------------------------------------------------
 // consensus_core to protowire
@@ -33,6 +33,12 @@ impl From<NetAddress> for protowire::NetAddress {
     }
 }
 
+impl From<(OnionAddress, u16)> for protowire::OnionNetAddress {
+    fn from((host, port): (OnionAddress, u16)) -> Self {
+        Self { host: host.as_bytes().to_vec(), port: port as u32 }
+    }
+}
+
 // ----------------------------------------------------------------------------
 // protowire to consensus_core
 // ----------------------------------------------------------------------------
@@ -68,9 +74,17 @@ impl TryFrom<protowire::NetAddress> for NetAddress {
     }
 }
 
+impl TryFrom<protowire::OnionNetAddress> for (OnionAddress, u16) {
+    type Error = ConversionError;
+
+    fn try_from(addr: protowire::OnionNetAddress) -> Result<Self, Self::Error> {
+        Ok((OnionAddress::from_bytes(&addr.host)?, addr.port.try_into()?))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use kaspa_utils::networking::IpAddress;
+    use kaspa_utils::networking::{IpAddress, OnionAddress};
 
     use crate::pb;
     use std::{
@@ -90,4 +104,15 @@ mod tests {
         assert_eq!(<(IpAddress, u16)>::try_from(net_addr_ipv6.clone()).unwrap(), (ipv6, 456u16));
         assert_eq!(pb::NetAddress::from((ipv6, 456u16)), net_addr_ipv6);
     }
+
+    #[test]
+    fn test_onion_netaddress() {
+        let onion = OnionAddress::from_str("2gzyxa5ihm7nsggfxnu52rck2vv4rvmdlkiu3zzui5du4xyclen53wid.onion").unwrap();
+        let net_addr = pb::OnionNetAddress::from((onion, 16111u16));
+        assert_eq!(net_addr.host.len(), 35);
+        assert_eq!(<(OnionAddress, u16)>::try_from(net_addr).unwrap(), (onion, 16111u16));
+
+        let truncated = pb::OnionNetAddress { host: onion.as_bytes()[..32].to_vec(), port: 16111 };
+        assert!(<(OnionAddress, u16)>::try_from(truncated).is_err());
+    }
 }
@@ -1,5 +1,6 @@
 use crate::common::ProtocolError;
 use crate::core::hub::Hub;
+use crate::core::proxy::ProxyConfig;
 use crate::ConnectionError;
 use crate::{core::connection_handler::ConnectionHandler, Router};
 use kaspa_utils::networking::NetAddress;
@@ -46,29 +47,41 @@ impl Adaptor {
     }
 
     /// Creates a P2P adaptor with only client-side support. Typical Kaspa nodes should use `Adaptor::bidirectional`
-    pub fn client_only(hub: Hub, initializer: Arc<dyn ConnectionInitializer>, counters: Arc<TowerConnectionCounters>) -> Arc<Self> {
+    pub fn client_only(
+        hub: Hub,
+        initializer: Arc<dyn ConnectionInitializer>,
+        counters: Arc<TowerConnectionCounters>,
+        proxy: Option<ProxyConfig>,
+    ) -> Arc<Self> {
         let (hub_sender, hub_receiver) = mpsc_channel(Self::hub_channel_size());
-        let connection_handler = ConnectionHandler::new(hub_sender, initializer.clone(), counters);
+        let connection_handler = ConnectionHandler::new(hub_sender, initializer.clone(), counters, proxy);
         let adaptor = Arc::new(Adaptor::new(None, connection_handler, hub));
         adaptor.hub.clone().start_event_loop(hub_receiver, initializer);
         adaptor
     }
 
-    /// Creates a bidirectional P2P adaptor with a server serving at `serve_address` and with client support
+    /// Creates a bidirectional P2P adaptor with a server serving at `serve_address` and with client support.
+    /// Outbound connections are routed through `proxy` if set.
     pub fn bidirectional(
         serve_address: NetAddress,
         hub: Hub,
         initializer: Arc<dyn ConnectionInitializer>,
         counters: Arc<TowerConnectionCounters>,
+        proxy: Option<ProxyConfig>,
     ) -> Result<Arc<Self>, ConnectionError> {
         let (hub_sender, hub_receiver) = mpsc_channel(Self::hub_channel_size());
-        let connection_handler = ConnectionHandler::new(hub_sender, initializer.clone(), counters);
+        let connection_handler = ConnectionHandler::new(hub_sender, initializer.clone(), counters, proxy);
         let server_termination = connection_handler.serve(serve_address)?;
         let adaptor = Arc::new(Adaptor::new(Some(server_termination), connection_handler, hub));
         adaptor.hub.clone().start_event_loop(hub_receiver, initializer);
         Ok(adaptor)
     }
 
+    /// The proxy outbound connections are routed through, if any
+    pub fn proxy(&self) -> Option<&ProxyConfig> {
+        self.connection_handler.proxy()
+    }
+
     /// Connect to a new peer (no retries)
     pub async fn connect_peer(&self, peer_address: String) -> Result<PeerKey, ConnectionError> {
         self.connection_handler.connect_with_retry(peer_address, 1, Default::default()).await.map(|r| r.key())
@@ -1,18 +1,20 @@
 use crate::common::ProtocolError;
 use crate::core::hub::HubEvent;
+use crate::core::proxy::ProxyConfig;
 use crate::pb::{
     p2p_client::P2pClient as ProtoP2pClient, p2p_server::P2p as ProtoP2p, p2p_server::P2pServer as ProtoP2pServer, KaspadMessage,
 };
 use crate::{ConnectionInitializer, Router};
 use futures::FutureExt;
 use kaspa_core::{debug, info};
-use kaspa_utils::networking::NetAddress;
+use kaspa_utils::networking::{NetAddress, OnionAddress, OnionAddressError};
 use kaspa_utils_tower::{
     counters::TowerConnectionCounters,
     middleware::{measure_request_body_size_layer, CountBytesBody, MapResponseBodyLayer, ServiceBuilder},
 };
-use std::net::ToSocketAddrs;
+use std::net::{SocketAddr, ToSocketAddrs};
 use std::pin::Pin;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 use thiserror::Error;
@@ -21,7 +23,7 @@ use tokio::sync::oneshot::{channel as oneshot_channel, Sender as OneshotSender};
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_stream::StreamExt;
 use tonic::codegen::Body;
-use tonic::transport::{Error as TonicError, Server as TonicServer};
+use tonic::transport::{Error as TonicError, Server as TonicServer, Uri};
 use tonic::{Request, Response, Status as TonicStatus, Streaming};
 
 #[derive(Error, Debug)]
@@ -40,6 +42,12 @@ pub enum ConnectionError {
 
     #[error("{0}")]
     ProtocolError(#[from] ProtocolError),
+
+    #[error("no proxy is configured for reaching onion address {0}")]
+    ProxyRequired(String),
+
+    #[error("{0}")]
+    OnionAddressError(#[from] OnionAddressError),
 }
 
 /// Maximum P2P decoded gRPC message size to send and receive
@@ -52,6 +60,8 @@ pub struct ConnectionHandler {
     hub_sender: MpscSender<HubEvent>,
     initializer: Arc<dyn ConnectionInitializer>,
     counters: Arc<TowerConnectionCounters>,
+    /// The proxy outbound connections are routed through, if any
+    proxy: Option<ProxyConfig>,
 }
 
 impl ConnectionHandler {
@@ -59,8 +69,13 @@ impl ConnectionHandler {
         hub_sender: MpscSender<HubEvent>,
         initializer: Arc<dyn ConnectionInitializer>,
         counters: Arc<TowerConnectionCounters>,
+        proxy: Option<ProxyConfig>,
     ) -> Self {
-        Self { hub_sender, initializer, counters }
+        Self { hub_sender, initializer, counters, proxy }
+    }
+
+    pub(crate) fn proxy(&self) -> Option<&ProxyConfig> {
+        self.proxy.as_ref()
     }
 
     /// Launches a P2P server listener loop
@@ -96,17 +111,46 @@ impl ConnectionHandler {
 
     /// Connect to a new peer
     pub(crate) async fn connect(&self, peer_address: String) -> Result<Arc<Router>, ConnectionError> {
-        let Some(socket_address) = peer_address.to_socket_addrs()?.next() else {
+        let Some((host, port)) = peer_address.rsplit_once(':').and_then(|(host, port)| Some((host, port.parse::<u16>().ok()?))) else {
             return Err(ConnectionError::NoAddress);
         };
+        let socket_address = if OnionAddress::is_onion_host(host) {
+            if self.proxy.is_none() {
+                return Err(ConnectionError::ProxyRequired(peer_address));
+            }
+            // Onion peers are identified by the IPv6 encoding of their address
+            SocketAddr::new(OnionAddress::from_str(host)?.ipv6_encoding().into(), port)
+        } else {
+            let Some(socket_address) = peer_address.to_socket_addrs()?.next() else {
+                return Err(ConnectionError::NoAddress);
+            };
+            socket_address
+        };
+        let proxy = self.proxy.clone().filter(|proxy| proxy.is_proxied(host));
+        let host = host.to_owned();
         let peer_address = format!("http://{}", peer_address); // Add scheme prefix as required by Tonic
 
-        let channel = tonic::transport::Endpoint::new(peer_address)?
+        let endpoint = tonic::transport::Endpoint::new(peer_address)?
             .timeout(Duration::from_millis(Self::communication_timeout()))
             .connect_timeout(Duration::from_millis(Self::connect_timeout()))
-            .tcp_keepalive(Some(Duration::from_millis(Self::keep_alive())))
-            .connect()
-            .await?;
+            .tcp_keepalive(Some(Duration::from_millis(Self::keep_alive())));
+        let channel = match proxy {
+            Some(proxy) => {
+                // The endpoint connect timeout only applies to its own connector
+                let connect_timeout = Duration::from_millis(Self::proxied_connect_timeout());
+                let connector = tower::service_fn(move |_: Uri| {
+                    let (proxy, host) = (proxy.clone(), host.clone());
+                    async move {
+                        match tokio::time::timeout(connect_timeout, proxy.connect(&host, port)).await {
+                            Ok(stream) => stream,
+                            Err(_) => Err(std::io::ErrorKind::TimedOut.into()),
+                        }
+                    }
+                });
+                endpoint.connect_with_connector(connector).await?
+            }
+            None => endpoint.connect().await?,
+        };
 
         let channel = ServiceBuilder::new()
             .layer(MapResponseBodyLayer::new(move |body| CountBytesBody::new(body, self.counters.bytes_rx.clone())))
@@ -195,6 +239,11 @@ impl ConnectionHandler {
     fn connect_timeout() -> u64 {
         1_000
     }
+
+    /// Connecting through a proxy, and notably Tor circuits, takes longer than direct connections
+    fn proxied_connect_timeout() -> u64 {
+        10_000
+    }
 }
 
 #[tonic::async_trait]
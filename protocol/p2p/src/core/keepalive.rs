@@ -0,0 +1,307 @@
+use crate::common::ProtocolError;
+use parking_lot::Mutex;
+use rand::Rng;
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+use tonic::async_trait;
+
+/// Keepalive settings of the connections to peers, used for detecting dead peers whose connection
+/// was not closed (e.g. half-open TCP connections)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeepaliveConfig {
+    /// Interval between two consecutive pings to a peer
+    pub ping_interval: Duration,
+
+    /// Time without any incoming message from a peer after which a ping is sent without waiting for the ping interval
+    pub idle_timeout: Duration,
+
+    /// Time waited for the pong answering a ping before the ping is considered missed
+    pub pong_timeout: Duration,
+
+    /// Number of consecutive missed pongs after which the peer is declared dead
+    pub max_missed_pongs: u32,
+}
+
+impl KeepaliveConfig {
+    pub const DEFAULT_PING_INTERVAL: Duration = Duration::from_secs(120);
+    pub const DEFAULT_PONG_TIMEOUT: Duration = Duration::from_secs(20);
+    pub const DEFAULT_MAX_MISSED_PONGS: u32 = 3;
+
+    /// The idle timeout of a 1 BPS network, scaled down by the block rate of faster networks
+    pub const BASELINE_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+    pub const MIN_IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Returns the default settings for a network producing `bps` blocks per second. Blocks are relayed
+    /// by every synced peer, so the faster the network, the sooner a silent peer becomes suspicious.
+    pub fn for_bps(bps: u64) -> Self {
+        Self {
+            ping_interval: Self::DEFAULT_PING_INTERVAL,
+            idle_timeout: Self::default_idle_timeout(bps),
+            pong_timeout: Self::DEFAULT_PONG_TIMEOUT,
+            max_missed_pongs: Self::DEFAULT_MAX_MISSED_PONGS,
+        }
+    }
+
+    pub fn default_idle_timeout(bps: u64) -> Duration {
+        (Self::BASELINE_IDLE_TIMEOUT / bps.max(1) as u32).max(Self::MIN_IDLE_TIMEOUT)
+    }
+
+    /// Upper bound of the time from a peer going silent until it is declared dead
+    pub fn detection_bound(&self) -> Duration {
+        self.ping_interval.min(self.idle_timeout) + self.pong_timeout * self.max_missed_pongs
+    }
+}
+
+impl Default for KeepaliveConfig {
+    fn default() -> Self {
+        Self::for_bps(1)
+    }
+}
+
+/// Round-trip time statistics of the pings to a peer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RttStats {
+    /// Round-trip time of the last answered ping
+    pub last: Option<Duration>,
+
+    /// Exponentially smoothed round-trip time of the answered pings
+    pub smoothed: Option<Duration>,
+
+    /// Number of consecutive pings left unanswered since the last answered one
+    pub missed_pongs: u32,
+
+    /// Total number of pings left unanswered during the connection
+    pub total_missed_pongs: u64,
+}
+
+impl RttStats {
+    /// Weight of a new sample in the smoothed round-trip time, as in TCP (RFC 6298)
+    const SMOOTHING_DIVISOR: u32 = 8;
+
+    fn record_pong(&mut self, rtt: Duration) {
+        self.last = Some(rtt);
+        self.smoothed = Some(match self.smoothed {
+            Some(smoothed) => (smoothed * (Self::SMOOTHING_DIVISOR - 1) + rtt) / Self::SMOOTHING_DIVISOR,
+            None => rtt,
+        });
+        self.missed_pongs = 0;
+    }
+
+    fn record_missed_pong(&mut self) {
+        self.missed_pongs += 1;
+        self.total_missed_pongs += 1;
+    }
+}
+
+/// Tracks the liveness of the connection to a peer: the time of the last incoming message and the
+/// round-trip times of the pings
+#[derive(Debug)]
+pub struct KeepaliveTracker {
+    origin: Instant,
+
+    /// Milliseconds elapsed from `origin` until the last incoming message
+    last_activity: AtomicU64,
+
+    rtt: Mutex<RttStats>,
+}
+
+impl KeepaliveTracker {
+    pub fn new(origin: Instant) -> Self {
+        Self { origin, last_activity: AtomicU64::new(0), rtt: Default::default() }
+    }
+
+    /// Records an incoming message from the peer
+    pub fn record_activity(&self) {
+        self.last_activity.fetch_max(self.origin.elapsed().as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// The time of the last incoming message, or the time the tracking started if none was received yet
+    pub fn last_activity(&self) -> Instant {
+        self.origin + Duration::from_millis(self.last_activity.load(Ordering::Relaxed))
+    }
+
+    pub fn record_pong(&self, rtt: Duration) {
+        self.rtt.lock().record_pong(rtt);
+    }
+
+    /// Records an unanswered ping, returning the number of consecutive unanswered pings
+    pub fn record_missed_pong(&self) -> u32 {
+        let mut rtt = self.rtt.lock();
+        rtt.record_missed_pong();
+        rtt.missed_pongs
+    }
+
+    pub fn rtt_stats(&self) -> RttStats {
+        *self.rtt.lock()
+    }
+}
+
+/// The messaging used by [`run_keepalive`] for pinging a peer
+#[async_trait]
+pub trait PingTransport: Send {
+    async fn send_ping(&mut self, nonce: u64) -> Result<(), ProtocolError>;
+
+    /// Waits up to `timeout` for the next pong, returning its nonce or `None` if the timeout expired
+    async fn recv_pong(&mut self, timeout: Duration) -> Result<Option<u64>, ProtocolError>;
+
+    /// Waits for `duration`, returning `false` if the wait was interrupted by a shutdown
+    async fn wait(&mut self, duration: Duration) -> bool;
+}
+
+/// Pings the peer every `config.ping_interval`, or as soon as it stays silent for `config.idle_timeout`,
+/// until a shutdown. Returns [`ProtocolError::DeadPeer`] once `config.max_missed_pongs` consecutive pings
+/// were left unanswered. An unanswered ping is retried immediately, so a silent peer is declared dead
+/// within [`KeepaliveConfig::detection_bound`].
+pub async fn run_keepalive(
+    config: &KeepaliveConfig,
+    tracker: &KeepaliveTracker,
+    transport: &mut impl PingTransport,
+) -> Result<(), ProtocolError> {
+    let mut last_ping = Instant::now();
+    // Late pongs answering a missed ping still prove the peer is alive
+    let mut outstanding: Vec<(u64, Instant)> = Vec::with_capacity(config.max_missed_pongs as usize);
+    loop {
+        if outstanding.is_empty() {
+            let now = Instant::now();
+            let next_ping = (last_ping + config.ping_interval).min(tracker.last_activity() + config.idle_timeout);
+            if next_ping > now {
+                if !transport.wait(next_ping - now).await {
+                    return Ok(());
+                }
+                // Incoming messages received meanwhile postpone the ping
+                continue;
+            }
+        }
+
+        // Create a fresh random nonce for each ping
+        let nonce = rand::thread_rng().gen::<u64>();
+        transport.send_ping(nonce).await?;
+        last_ping = Instant::now();
+        outstanding.push((nonce, last_ping));
+
+        match transport.recv_pong(config.pong_timeout).await? {
+            Some(pong_nonce) => {
+                let Some(&(_, ping_time)) = outstanding.iter().find(|(nonce, _)| *nonce == pong_nonce) else {
+                    return Err(ProtocolError::Other("nonce mismatch between ping and pong"));
+                };
+                tracker.record_pong(ping_time.elapsed());
+                outstanding.clear();
+            }
+            None => {
+                let missed = tracker.record_missed_pong();
+                if missed >= config.max_missed_pongs {
+                    return Err(ProtocolError::DeadPeer(missed));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tokio::time::sleep;
+
+    /// A peer answering pings after `rtt` until `responsive_until`, then going silent without closing the connection
+    struct MockPeer {
+        tracker: Arc<KeepaliveTracker>,
+        rtt: Duration,
+        responsive_until: Instant,
+        pending_pong: Option<u64>,
+        pings: usize,
+    }
+
+    impl MockPeer {
+        fn new(tracker: Arc<KeepaliveTracker>, rtt: Duration, responsive_until: Instant) -> Self {
+            Self { tracker, rtt, responsive_until, pending_pong: None, pings: 0 }
+        }
+    }
+
+    #[async_trait]
+    impl PingTransport for MockPeer {
+        async fn send_ping(&mut self, nonce: u64) -> Result<(), ProtocolError> {
+            self.pings += 1;
+            if Instant::now() < self.responsive_until {
+                self.pending_pong = Some(nonce);
+            }
+            Ok(())
+        }
+
+        async fn recv_pong(&mut self, timeout: Duration) -> Result<Option<u64>, ProtocolError> {
+            match self.pending_pong.take() {
+                Some(nonce) if self.rtt <= timeout => {
+                    sleep(self.rtt).await;
+                    self.tracker.record_activity();
+                    Ok(Some(nonce))
+                }
+                _ => {
+                    sleep(timeout).await;
+                    Ok(None)
+                }
+            }
+        }
+
+        async fn wait(&mut self, duration: Duration) -> bool {
+            sleep(duration).await;
+            true
+        }
+    }
+
+    fn test_config() -> KeepaliveConfig {
+        KeepaliveConfig {
+            ping_interval: Duration::from_millis(40),
+            idle_timeout: Duration::from_secs(10),
+            pong_timeout: Duration::from_millis(50),
+            max_missed_pongs: 3,
+        }
+    }
+
+    #[test]
+    fn test_default_idle_timeout() {
+        assert_eq!(KeepaliveConfig::for_bps(1).idle_timeout, Duration::from_secs(30));
+        assert_eq!(KeepaliveConfig::for_bps(2).idle_timeout, Duration::from_secs(15));
+        assert_eq!(KeepaliveConfig::for_bps(10).idle_timeout, KeepaliveConfig::MIN_IDLE_TIMEOUT);
+        assert_eq!(KeepaliveConfig::for_bps(0), KeepaliveConfig::default());
+    }
+
+    #[tokio::test]
+    async fn test_responsive_peer_kept_alive() {
+        let config = test_config();
+        let tracker = Arc::new(KeepaliveTracker::new(Instant::now()));
+        let mut peer = MockPeer::new(tracker.clone(), Duration::from_millis(5), Instant::now() + Duration::from_secs(60));
+
+        let result = tokio::time::timeout(Duration::from_millis(400), run_keepalive(&config, &tracker, &mut peer)).await;
+        assert!(result.is_err(), "the keepalive of a responsive peer should run until shutdown");
+        assert!(peer.pings >= 4);
+        let rtt = tracker.rtt_stats();
+        assert!(rtt.last.unwrap() >= Duration::from_millis(5));
+        assert!(rtt.smoothed.is_some());
+        assert_eq!(rtt.total_missed_pongs, 0);
+    }
+
+    #[tokio::test]
+    async fn test_silent_peer_declared_dead() {
+        // Pings are only forced by the idle timeout since the ping interval is far beyond the test duration
+        let config =
+            KeepaliveConfig { ping_interval: Duration::from_secs(60), idle_timeout: Duration::from_millis(100), ..test_config() };
+        let start = Instant::now();
+        let tracker = Arc::new(KeepaliveTracker::new(start));
+        let silent_from = start + Duration::from_millis(250);
+        let mut peer = MockPeer::new(tracker.clone(), Duration::from_millis(5), silent_from);
+
+        let result = run_keepalive(&config, &tracker, &mut peer).await;
+        assert!(matches!(result, Err(ProtocolError::DeadPeer(3))), "unexpected result {result:?}");
+
+        // The idle timeout forced pings while the peer was responsive
+        assert!(peer.pings >= 2 + config.max_missed_pongs as usize);
+        assert_eq!(tracker.rtt_stats().missed_pongs, config.max_missed_pongs);
+
+        // The detection latency is bounded by the idle timeout plus the timeouts of the missed pongs
+        let latency = Instant::now() - silent_from;
+        assert!(latency >= config.pong_timeout * config.max_missed_pongs);
+        assert!(latency <= config.detection_bound() + Duration::from_millis(100), "detection took {latency:?}");
+    }
+}
@@ -1,6 +1,9 @@
 pub mod adaptor;
 pub mod connection_handler;
 pub mod hub;
+pub mod keepalive;
 pub mod payload_type;
 pub mod peer;
+pub mod proxy;
+pub mod request_cost;
 pub mod router;
@@ -1,3 +1,5 @@
+use crate::convert::model::version::{SERVICE_ONION_ADDRESSES, SERVICE_TRANSACTIONS_BATCH};
+use crate::core::{keepalive::RttStats, request_cost::ServedCostCounters};
 use kaspa_consensus_core::subnets::SubnetworkId;
 use kaspa_utils::networking::{IpAddress, PeerId};
 use std::{fmt::Display, net::SocketAddr, sync::Arc, time::Instant};
@@ -5,7 +7,8 @@ use std::{fmt::Display, net::SocketAddr, sync::Arc, time::Instant};
 #[derive(Debug, Clone, Default)]
 pub struct PeerProperties {
     pub user_agent: String,
-    // TODO: add services
+    /// Bit flags of the services advertised by the peer
+    pub services: u64,
     pub advertised_protocol_version: u32,
     pub protocol_version: u32,
     pub disable_relay_tx: bool,
@@ -13,6 +16,18 @@ pub struct PeerProperties {
     pub time_offset: i64,
 }
 
+impl PeerProperties {
+    /// Whether the peer accepts onion addresses in addresses messages
+    pub fn supports_onion_addresses(&self) -> bool {
+        self.services & SERVICE_ONION_ADDRESSES != 0
+    }
+
+    /// Whether the peer responds to transaction requests, and expects to be responded, with transactions batches
+    pub fn supports_transactions_batch(&self) -> bool {
+        self.services & SERVICE_TRANSACTIONS_BATCH != 0
+    }
+}
+
 #[derive(Debug)]
 pub struct Peer {
     identity: PeerId,
@@ -20,7 +35,8 @@ pub struct Peer {
     is_outbound: bool,
     connection_started: Instant,
     properties: Arc<PeerProperties>,
-    last_ping_duration: u64,
+    rtt: RttStats,
+    served_counters: ServedCostCounters,
 }
 
 impl Peer {
@@ -30,9 +46,10 @@ impl Peer {
         is_outbound: bool,
         connection_started: Instant,
         properties: Arc<PeerProperties>,
-        last_ping_duration: u64,
+        rtt: RttStats,
+        served_counters: ServedCostCounters,
     ) -> Self {
-        Self { identity, net_address, is_outbound, connection_started, properties, last_ping_duration }
+        Self { identity, net_address, is_outbound, connection_started, properties, rtt, served_counters }
     }
 
     /// Internal identity of this peer
@@ -62,8 +79,19 @@ impl Peer {
         self.properties.clone()
     }
 
+    /// Round-trip time in milliseconds of the last answered ping
     pub fn last_ping_duration(&self) -> u64 {
-        self.last_ping_duration
+        self.rtt.last.map_or(0, |rtt| rtt.as_millis() as u64)
+    }
+
+    /// Round-trip time statistics of the pings to this peer
+    pub fn rtt(&self) -> RttStats {
+        self.rtt
+    }
+
+    /// The bytes and cost units served to this peer by request-serving flows
+    pub fn served_counters(&self) -> ServedCostCounters {
+        self.served_counters
     }
 }
 
@@ -0,0 +1,244 @@
+//!
+//! SOCKS5 (RFC 1928) client used for routing outbound connections through a proxy such as Tor,
+//! along with the Tor `RESOLVE` extension used for resolving DNS seeders through the proxy.
+//!
+
+use kaspa_utils::networking::OnionAddress;
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+const SOCKS_VERSION: u8 = 5;
+const NO_AUTHENTICATION: u8 = 0;
+const NO_ACCEPTABLE_METHOD: u8 = 0xff;
+
+const COMMAND_CONNECT: u8 = 1;
+/// Tor extension resolving a host name through the proxy, see Tor's `socks-extensions.txt`
+const COMMAND_RESOLVE: u8 = 0xf0;
+
+const ADDRESS_TYPE_IPV4: u8 = 1;
+const ADDRESS_TYPE_DOMAIN_NAME: u8 = 3;
+const ADDRESS_TYPE_IPV6: u8 = 4;
+
+const REPLY_SUCCEEDED: u8 = 0;
+
+/// Routing of outbound connections through a SOCKS5 proxy
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProxyConfig {
+    /// The `host:port` address of the proxy
+    pub address: String,
+    /// Whether only onion addresses are reached through the proxy, other peers being connected directly
+    pub onion_only: bool,
+}
+
+impl ProxyConfig {
+    pub fn new(address: String, onion_only: bool) -> Self {
+        Self { address, onion_only }
+    }
+
+    /// Whether connections to `host` go through the proxy. Onion hosts are only reachable through it.
+    pub fn is_proxied(&self, host: &str) -> bool {
+        !self.onion_only || OnionAddress::is_onion_host(host)
+    }
+
+    /// Opens a TCP stream to `host:port` through the proxy. Host names are passed to the proxy unresolved.
+    pub async fn connect(&self, host: &str, port: u16) -> io::Result<TcpStream> {
+        let mut stream = self.open().await?;
+        stream.write_all(&request(COMMAND_CONNECT, host, port)?).await?;
+        read_reply(&mut stream).await?;
+        Ok(stream)
+    }
+
+    /// Resolves `host` through the proxy with the Tor `RESOLVE` extension, which yields a single address per request
+    pub async fn resolve(&self, host: &str) -> io::Result<IpAddr> {
+        let mut stream = self.open().await?;
+        stream.write_all(&request(COMMAND_RESOLVE, host, 0)?).await?;
+        read_reply(&mut stream).await
+    }
+
+    /// Connects to the proxy and negotiates the (lack of) authentication method
+    async fn open(&self) -> io::Result<TcpStream> {
+        let mut stream = TcpStream::connect(&self.address).await?;
+        stream.write_all(&[SOCKS_VERSION, 1, NO_AUTHENTICATION]).await?;
+        let mut reply = [0u8; 2];
+        stream.read_exact(&mut reply).await?;
+        match reply {
+            [SOCKS_VERSION, NO_AUTHENTICATION] => Ok(stream),
+            [SOCKS_VERSION, NO_ACCEPTABLE_METHOD] => Err(io::Error::other("the proxy requires authentication")),
+            [version, method] => {
+                Err(io::Error::other(format!("unexpected proxy greeting reply (version {version}, method {method})")))
+            }
+        }
+    }
+}
+
+fn request(command: u8, host: &str, port: u16) -> io::Result<Vec<u8>> {
+    let mut request = vec![SOCKS_VERSION, command, 0];
+    match host.strip_prefix('[').and_then(|host| host.strip_suffix(']')).unwrap_or(host).parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            request.push(ADDRESS_TYPE_IPV4);
+            request.extend(ip.octets());
+        }
+        Ok(IpAddr::V6(ip)) => {
+            request.push(ADDRESS_TYPE_IPV6);
+            request.extend(ip.octets());
+        }
+        Err(_) => {
+            let len = u8::try_from(host.len()).map_err(|_| io::Error::other(format!("host name {host} is too long for the proxy")))?;
+            request.push(ADDRESS_TYPE_DOMAIN_NAME);
+            request.push(len);
+            request.extend(host.as_bytes());
+        }
+    }
+    request.extend(port.to_be_bytes());
+    Ok(request)
+}
+
+/// Reads the reply to a request, returning the address it carries
+async fn read_reply(stream: &mut TcpStream) -> io::Result<IpAddr> {
+    let mut header = [0u8; 4];
+    stream.read_exact(&mut header).await?;
+    let [version, reply, _, address_type] = header;
+    if version != SOCKS_VERSION {
+        return Err(io::Error::other(format!("unexpected proxy reply version {version}")));
+    }
+    if reply != REPLY_SUCCEEDED {
+        return Err(io::Error::other(format!("proxy request failed: {}", reply_message(reply))));
+    }
+    let ip = match address_type {
+        ADDRESS_TYPE_IPV4 => {
+            let mut octets = [0u8; 4];
+            stream.read_exact(&mut octets).await?;
+            Ipv4Addr::from(octets).into()
+        }
+        ADDRESS_TYPE_IPV6 => {
+            let mut octets = [0u8; 16];
+            stream.read_exact(&mut octets).await?;
+            Ipv6Addr::from(octets).into()
+        }
+        ADDRESS_TYPE_DOMAIN_NAME => {
+            // Only meaningful as the bound address of a connection, which is ignored anyway
+            let mut name = vec![0u8; stream.read_u8().await? as usize];
+            stream.read_exact(&mut name).await?;
+            Ipv4Addr::UNSPECIFIED.into()
+        }
+        address_type => return Err(io::Error::other(format!("unexpected proxy reply address type {address_type}"))),
+    };
+    let mut port = [0u8; 2];
+    stream.read_exact(&mut port).await?;
+    Ok(ip)
+}
+
+fn reply_message(reply: u8) -> &'static str {
+    match reply {
+        1 => "general failure",
+        2 => "connection not allowed by ruleset",
+        3 => "network unreachable",
+        4 => "host unreachable",
+        5 => "connection refused",
+        6 => "TTL expired",
+        7 => "command not supported",
+        8 => "address type not supported",
+        _ => "unknown error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{net::TcpListener, task::JoinHandle};
+
+    const ONION_HOST: &str = "2gzyxa5ihm7nsggfxnu52rck2vv4rvmdlkiu3zzui5du4xyclen53wid.onion";
+
+    /// Spawns a mock proxy serving a single request with `reply`, then echoing 4 bytes.
+    /// Resolves to the received greeting and request.
+    async fn mock_proxy(reply: Vec<u8>) -> (ProxyConfig, JoinHandle<(Vec<u8>, Vec<u8>)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy = ProxyConfig::new(listener.local_addr().unwrap().to_string(), false);
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut greeting = vec![0u8; 3];
+            stream.read_exact(&mut greeting).await.unwrap();
+            stream.write_all(&[SOCKS_VERSION, NO_AUTHENTICATION]).await.unwrap();
+
+            let mut request = vec![0u8; 4];
+            stream.read_exact(&mut request).await.unwrap();
+            let address_len = match request[3] {
+                ADDRESS_TYPE_IPV4 => 4,
+                ADDRESS_TYPE_IPV6 => 16,
+                _ => {
+                    request.push(stream.read_u8().await.unwrap());
+                    request[4] as usize
+                }
+            };
+            let mut address_and_port = vec![0u8; address_len + 2];
+            stream.read_exact(&mut address_and_port).await.unwrap();
+            request.extend(address_and_port);
+            stream.write_all(&reply).await.unwrap();
+
+            let mut payload = [0u8; 4];
+            if reply[1] == REPLY_SUCCEEDED && stream.read_exact(&mut payload).await.is_ok() {
+                stream.write_all(&payload).await.unwrap();
+            }
+            (greeting, request)
+        });
+        (proxy, server)
+    }
+
+    #[tokio::test]
+    async fn test_connect_negotiation() {
+        let (proxy, server) = mock_proxy(vec![5, 0, 0, 1, 127, 0, 0, 1, 0x1f, 0x90]).await;
+        let mut stream = proxy.connect(ONION_HOST, 16111).await.unwrap();
+        stream.write_all(b"ping").await.unwrap();
+        let mut payload = [0u8; 4];
+        stream.read_exact(&mut payload).await.unwrap();
+        assert_eq!(&payload, b"ping");
+
+        let (greeting, request) = server.await.unwrap();
+        assert_eq!(greeting, vec![SOCKS_VERSION, 1, NO_AUTHENTICATION]);
+        // The onion host is passed as a domain name, to be resolved by the proxy
+        let mut expected = vec![SOCKS_VERSION, COMMAND_CONNECT, 0, ADDRESS_TYPE_DOMAIN_NAME, ONION_HOST.len() as u8];
+        expected.extend(ONION_HOST.as_bytes());
+        expected.extend(16111u16.to_be_bytes());
+        assert_eq!(request, expected);
+
+        // IP targets are passed as such
+        let (proxy, server) = mock_proxy(vec![5, 0, 0, 1, 127, 0, 0, 1, 0x1f, 0x90]).await;
+        proxy.connect("[2a01:4f8:191:1143::2]", 16111).await.unwrap();
+        let (_, request) = server.await.unwrap();
+        let mut expected = vec![SOCKS_VERSION, COMMAND_CONNECT, 0, ADDRESS_TYPE_IPV6];
+        expected.extend("2a01:4f8:191:1143::2".parse::<Ipv6Addr>().unwrap().octets());
+        expected.extend(16111u16.to_be_bytes());
+        assert_eq!(request, expected);
+    }
+
+    #[tokio::test]
+    async fn test_connect_failure() {
+        let (proxy, server) = mock_proxy(vec![5, 5, 0, 1, 0, 0, 0, 0, 0, 0]).await;
+        let err = proxy.connect("1.2.3.4", 16111).await.unwrap_err();
+        assert!(err.to_string().contains("connection refused"), "{err}");
+        let (_, request) = server.await.unwrap();
+        assert_eq!(request, vec![SOCKS_VERSION, COMMAND_CONNECT, 0, ADDRESS_TYPE_IPV4, 1, 2, 3, 4, 0x3e, 0xef]);
+    }
+
+    #[tokio::test]
+    async fn test_resolve() {
+        let (proxy, server) = mock_proxy(vec![5, 0, 0, 1, 1, 2, 3, 4, 0, 0]).await;
+        assert_eq!(proxy.resolve("seeder.example.org").await.unwrap(), IpAddr::from([1, 2, 3, 4]));
+        let (_, request) = server.await.unwrap();
+        assert_eq!(&request[..5], &[SOCKS_VERSION, COMMAND_RESOLVE, 0, ADDRESS_TYPE_DOMAIN_NAME, 18]);
+    }
+
+    #[test]
+    fn test_is_proxied() {
+        let proxy = ProxyConfig::new("127.0.0.1:9050".to_string(), true);
+        assert!(proxy.is_proxied(ONION_HOST));
+        assert!(!proxy.is_proxied("1.2.3.4"));
+        assert!(ProxyConfig::new("127.0.0.1:9050".to_string(), false).is_proxied("1.2.3.4"));
+    }
+}
@@ -0,0 +1,234 @@
+use parking_lot::Mutex;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Limits applied by the request-serving flows in order to bound the amount of store work
+/// a single (possibly hostile) peer can make us do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestCostLimits {
+    /// Max number of headers served in response to a single anticone (antipast) request
+    pub max_anticone_size: usize,
+
+    /// Max number of hashes served in response to a single block locator request
+    pub max_locator_steps: usize,
+
+    /// The sliding window over which served costs are accumulated per peer
+    pub window: Duration,
+
+    /// Max number of bytes served to a single peer within the window before throttling engages
+    pub max_served_bytes: u64,
+
+    /// Max number of cost units (a CPU proxy roughly corresponding to store reads) served to a single
+    /// peer within the window before throttling engages
+    pub max_served_cost: u64,
+
+    /// A peer exceeding either budget by this factor is disconnected rather than throttled
+    pub disconnect_factor: u64,
+}
+
+impl RequestCostLimits {
+    pub const DEFAULT_MAX_ANTICONE_SIZE: usize = 4096;
+    pub const DEFAULT_MAX_LOCATOR_STEPS: usize = 256;
+    pub const DEFAULT_WINDOW: Duration = Duration::from_secs(60);
+    pub const DEFAULT_MAX_SERVED_BYTES: u64 = 256 * 1024 * 1024;
+    pub const DEFAULT_MAX_SERVED_COST: u64 = 1_000_000;
+    pub const DEFAULT_DISCONNECT_FACTOR: u64 = 4;
+}
+
+impl Default for RequestCostLimits {
+    fn default() -> Self {
+        Self {
+            max_anticone_size: Self::DEFAULT_MAX_ANTICONE_SIZE,
+            max_locator_steps: Self::DEFAULT_MAX_LOCATOR_STEPS,
+            window: Self::DEFAULT_WINDOW,
+            max_served_bytes: Self::DEFAULT_MAX_SERVED_BYTES,
+            max_served_cost: Self::DEFAULT_MAX_SERVED_COST,
+            disconnect_factor: Self::DEFAULT_DISCONNECT_FACTOR,
+        }
+    }
+}
+
+/// The outcome of checking a peer against its serving budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestCostVerdict {
+    /// The peer is within budget and the request can be served right away
+    Allow,
+
+    /// The peer is over budget. The request should be delayed by the given duration, which is the time
+    /// remaining until enough of the window expires for the peer to be back within budget
+    Throttle(Duration),
+
+    /// The peer exceeded its budget by more than the disconnect factor
+    Disconnect,
+}
+
+/// A snapshot of the costs served to a peer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ServedCostCounters {
+    /// Bytes served within the current window
+    pub window_bytes: u64,
+    /// Cost units served within the current window
+    pub window_cost: u64,
+    /// Bytes served since the connection was established
+    pub total_bytes: u64,
+    /// Cost units served since the connection was established
+    pub total_cost: u64,
+}
+
+#[derive(Debug, Default)]
+struct ServedCostState {
+    /// Served entries as (timestamp, bytes, cost), ordered by timestamp
+    entries: VecDeque<(Instant, u64, u64)>,
+    counters: ServedCostCounters,
+}
+
+impl ServedCostState {
+    fn expire(&mut self, now: Instant, window: Duration) {
+        while let Some(&(timestamp, bytes, cost)) = self.entries.front() {
+            if now.saturating_duration_since(timestamp) < window {
+                break;
+            }
+            self.entries.pop_front();
+            self.counters.window_bytes -= bytes;
+            self.counters.window_cost -= cost;
+        }
+    }
+}
+
+/// Tracks the bytes and cost units served to a single peer over a sliding window
+#[derive(Debug, Default)]
+pub struct ServedCostTracker {
+    state: Mutex<ServedCostState>,
+}
+
+impl ServedCostTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a response of `bytes` size which required `cost` units of work to produce
+    pub fn record(&self, now: Instant, window: Duration, bytes: u64, cost: u64) {
+        let mut state = self.state.lock();
+        state.expire(now, window);
+        state.entries.push_back((now, bytes, cost));
+        state.counters.window_bytes += bytes;
+        state.counters.window_cost += cost;
+        state.counters.total_bytes += bytes;
+        state.counters.total_cost += cost;
+    }
+
+    /// Checks the served costs within the current window against `limits`
+    pub fn verdict(&self, now: Instant, limits: &RequestCostLimits) -> RequestCostVerdict {
+        let mut state = self.state.lock();
+        state.expire(now, limits.window);
+        let ServedCostCounters { window_bytes, window_cost, .. } = state.counters;
+        if window_bytes <= limits.max_served_bytes && window_cost <= limits.max_served_cost {
+            return RequestCostVerdict::Allow;
+        }
+        if window_bytes > limits.max_served_bytes.saturating_mul(limits.disconnect_factor)
+            || window_cost > limits.max_served_cost.saturating_mul(limits.disconnect_factor)
+        {
+            return RequestCostVerdict::Disconnect;
+        }
+
+        // Find the earliest point in time at which enough entries expire for the peer to be back within budget
+        let (mut bytes, mut cost) = (window_bytes, window_cost);
+        for &(timestamp, entry_bytes, entry_cost) in state.entries.iter() {
+            bytes -= entry_bytes;
+            cost -= entry_cost;
+            if bytes <= limits.max_served_bytes && cost <= limits.max_served_cost {
+                return RequestCostVerdict::Throttle((timestamp + limits.window).saturating_duration_since(now));
+            }
+        }
+        unreachable!("an empty window is always within budget")
+    }
+
+    /// Returns a snapshot of the served counters as of `now`
+    pub fn counters(&self, now: Instant, window: Duration) -> ServedCostCounters {
+        let mut state = self.state.lock();
+        state.expire(now, window);
+        state.counters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_limits() -> RequestCostLimits {
+        RequestCostLimits {
+            window: Duration::from_secs(10),
+            max_served_bytes: 1000,
+            max_served_cost: 100,
+            disconnect_factor: 4,
+            ..Default::default()
+        }
+    }
+
+    /// Simulates a serving flow: checks the verdict and, if allowed, serves the request (after throttling if needed)
+    fn serve(tracker: &ServedCostTracker, now: &mut Instant, limits: &RequestCostLimits, bytes: u64, cost: u64) -> RequestCostVerdict {
+        let verdict = tracker.verdict(*now, limits);
+        match verdict {
+            RequestCostVerdict::Allow => tracker.record(*now, limits.window, bytes, cost),
+            RequestCostVerdict::Throttle(delay) => {
+                *now += delay;
+                tracker.record(*now, limits.window, bytes, cost)
+            }
+            RequestCostVerdict::Disconnect => {}
+        }
+        verdict
+    }
+
+    #[test]
+    fn test_hostile_peer_throttled_normal_peer_unaffected() {
+        let limits = test_limits();
+        let start = Instant::now();
+
+        // A normal peer issuing modest requests once a second stays within budget indefinitely
+        let normal = ServedCostTracker::new();
+        let mut now = start;
+        for _ in 0..100 {
+            assert_eq!(serve(&normal, &mut now, &limits, 50, 5), RequestCostVerdict::Allow);
+            now += Duration::from_secs(1);
+        }
+
+        // A hostile peer issuing maximal requests in a tight loop gets throttled
+        let hostile = ServedCostTracker::new();
+        let mut now = start;
+        let mut throttled = 0;
+        for _ in 0..100 {
+            if let RequestCostVerdict::Throttle(delay) = serve(&hostile, &mut now, &limits, 400, 40) {
+                assert!(delay <= limits.window);
+                throttled += 1;
+            }
+        }
+        assert!(throttled >= 30, "throttling should engage once the budget is consumed");
+
+        // Throttling bounds the rate the hostile peer is served at to roughly the budget per window
+        let counters = hostile.counters(now, limits.window);
+        assert!(counters.window_bytes <= limits.max_served_bytes + 400);
+        assert!(counters.window_cost <= limits.max_served_cost + 40);
+        assert_eq!(counters.total_bytes, 100 * 400);
+        let windows = (now - start).as_secs() / limits.window.as_secs() + 1;
+        assert!(counters.total_bytes <= windows * (limits.max_served_bytes + 400));
+    }
+
+    #[test]
+    fn test_disconnect_on_excessive_budget() {
+        let limits = test_limits();
+        let tracker = ServedCostTracker::new();
+        let now = Instant::now();
+
+        // A single response exceeding the budget by more than the disconnect factor
+        tracker.record(now, limits.window, 10, limits.max_served_cost * limits.disconnect_factor + 1);
+        assert_eq!(tracker.verdict(now, &limits), RequestCostVerdict::Disconnect);
+
+        // Once the window expires the peer is back within budget
+        assert_eq!(tracker.verdict(now + limits.window, &limits), RequestCostVerdict::Allow);
+        let counters = tracker.counters(now + limits.window, limits.window);
+        assert_eq!((counters.window_bytes, counters.window_cost), (0, 0));
+        assert_eq!(counters.total_bytes, 10);
+    }
+}
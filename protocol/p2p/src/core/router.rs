@@ -6,6 +6,7 @@ use crate::{make_message, Peer};
 use kaspa_core::{debug, error, info, trace, warn};
 use kaspa_utils::networking::PeerId;
 use parking_lot::{Mutex, RwLock};
+use prost::Message;
 use seqlock::SeqLock;
 use std::fmt::{Debug, Display};
 use std::net::SocketAddr;
@@ -19,7 +20,9 @@ use tokio::sync::mpsc::{channel as mpsc_channel, Receiver as MpscReceiver, Sende
 use tokio::sync::oneshot::{channel as oneshot_channel, Sender as OneshotSender};
 use tonic::Streaming;
 
+use super::keepalive::{KeepaliveTracker, RttStats};
 use super::peer::{PeerKey, PeerProperties};
+use super::request_cost::{RequestCostLimits, RequestCostVerdict, ServedCostCounters, ServedCostTracker};
 
 pub struct IncomingRoute {
     rx: MpscReceiver<KaspadMessage>,
@@ -100,8 +103,8 @@ struct RouterMutableState {
     /// Properties of the peer
     properties: Arc<PeerProperties>,
 
-    /// Duration of the last ping to this peer
-    last_ping_duration: u64,
+    /// The limits applied by request-serving flows to this peer
+    request_cost_limits: RequestCostLimits,
 }
 
 impl RouterMutableState {
@@ -139,6 +142,12 @@ pub struct Router {
 
     /// Used for managing router mutable state
     mutable_state: Mutex<RouterMutableState>,
+
+    /// Tracks the costs of responses served to this peer
+    served_costs: ServedCostTracker,
+
+    /// Tracks the incoming activity of this peer and the round-trip times of its pings
+    keepalive: Arc<KeepaliveTracker>,
 }
 
 impl Display for Router {
@@ -161,7 +170,8 @@ impl From<&Router> for Peer {
             router.is_outbound,
             router.connection_started,
             router.properties(),
-            router.last_ping_duration(),
+            router.rtt_stats(),
+            router.served_counters(),
         )
     }
 }
@@ -183,16 +193,19 @@ impl Router {
         let (start_sender, start_receiver) = oneshot_channel();
         let (shutdown_sender, mut shutdown_receiver) = oneshot_channel();
 
+        let connection_started = Instant::now();
         let router = Arc::new(Router {
             identity: Default::default(),
             net_address,
             is_outbound,
-            connection_started: Instant::now(),
+            connection_started,
             routing_map_by_type: RwLock::new(HashMap::new()),
             routing_map_by_id: RwLock::new(HashMap::new()),
             outgoing_route,
             hub_sender,
             mutable_state: Mutex::new(RouterMutableState::new(Some(start_sender), Some(shutdown_sender))),
+            served_costs: ServedCostTracker::new(),
+            keepalive: Arc::new(KeepaliveTracker::new(connection_started)),
         });
 
         let router_clone = router.clone();
@@ -211,6 +224,7 @@ impl Router {
 
                     res = incoming_stream.message() => match res {
                         Ok(Some(msg)) => {
+                            router.keepalive.record_activity();
                             trace!("P2P msg: {:?}, router-id: {}, peer: {}", message_summary(&msg), router.identity(), router);
                             match router.route_to_flow(msg) {
                                 Ok(()) => {},
@@ -285,13 +299,59 @@ impl Router {
         self.mutable_state.lock().properties = properties;
     }
 
-    /// Sets the duration of the last ping
-    pub fn set_last_ping_duration(&self, last_ping_duration: u64) {
-        self.mutable_state.lock().last_ping_duration = last_ping_duration;
+    /// Round-trip time in milliseconds of the last answered ping
+    pub fn last_ping_duration(&self) -> u64 {
+        self.rtt_stats().last.map_or(0, |rtt| rtt.as_millis() as u64)
+    }
+
+    /// Tracks the incoming activity of this peer and the round-trip times of its pings
+    pub fn keepalive(&self) -> &Arc<KeepaliveTracker> {
+        &self.keepalive
     }
 
-    pub fn last_ping_duration(&self) -> u64 {
-        self.mutable_state.lock().last_ping_duration
+    pub fn rtt_stats(&self) -> RttStats {
+        self.keepalive.rtt_stats()
+    }
+
+    /// The limits applied by request-serving flows to this peer
+    pub fn request_cost_limits(&self) -> RequestCostLimits {
+        self.mutable_state.lock().request_cost_limits
+    }
+
+    pub fn set_request_cost_limits(&self, request_cost_limits: RequestCostLimits) {
+        self.mutable_state.lock().request_cost_limits = request_cost_limits;
+    }
+
+    /// Records a response served to this peer along with the `cost` units of work it required
+    pub fn record_served(&self, msg: &KaspadMessage, cost: u64) {
+        self.served_costs.record(Instant::now(), self.request_cost_limits().window, msg.encoded_len() as u64, cost);
+    }
+
+    /// Checks the costs served to this peer within the current window against its budget
+    pub fn served_cost_verdict(&self) -> RequestCostVerdict {
+        self.served_costs.verdict(Instant::now(), &self.request_cost_limits())
+    }
+
+    pub fn served_counters(&self) -> ServedCostCounters {
+        self.served_costs.counters(Instant::now(), self.request_cost_limits().window)
+    }
+
+    /// Should be called by request-serving flows before doing any work on behalf of the peer. Delays the calling flow
+    /// if the peer is over its serving budget, and returns an error if the peer exceeded it by more than the disconnect factor
+    pub async fn apply_request_cost_budget(&self) -> Result<(), ProtocolError> {
+        match self.served_cost_verdict() {
+            RequestCostVerdict::Allow => Ok(()),
+            RequestCostVerdict::Throttle(delay) => {
+                debug!("P2P, throttling requests of peer {} for {:?} since it exceeded its serving budget", self, delay);
+                tokio::time::sleep(delay).await;
+                Ok(())
+            }
+            RequestCostVerdict::Disconnect => Err(ProtocolError::MisbehavingPeer(format!(
+                "peer {} exceeded its serving budget excessively: {:?}",
+                self,
+                self.served_counters()
+            ))),
+        }
     }
 
     pub fn incoming_flow_baseline_channel_size() -> usize {
@@ -418,6 +478,11 @@ impl Router {
         }
     }
 
+    /// Indicates whether the router was closed, i.e., whether the connection to the peer was terminated
+    pub fn is_closed(&self) -> bool {
+        self.mutable_state.lock().shutdown_signal.is_none()
+    }
+
     /// Closes the router, signals exit, and cleans up all resources so that underlying connections will be aborted correctly.
     /// Returns true of this is the first call to close
     pub async fn close(self: &Arc<Router>) -> bool {
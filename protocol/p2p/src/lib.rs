@@ -13,7 +13,10 @@ mod handshake;
 pub use crate::core::adaptor::{Adaptor, ConnectionInitializer};
 pub use crate::core::connection_handler::ConnectionError;
 pub use crate::core::hub::Hub;
+pub use crate::core::keepalive::{run_keepalive, KeepaliveConfig, KeepaliveTracker, PingTransport, RttStats};
 pub use crate::core::payload_type::KaspadMessagePayloadType;
 pub use crate::core::peer::{Peer, PeerKey, PeerProperties};
+pub use crate::core::proxy::ProxyConfig;
+pub use crate::core::request_cost::{RequestCostLimits, RequestCostVerdict, ServedCostCounters, ServedCostTracker};
 pub use crate::core::router::{IncomingRoute, Router, SharedIncomingRoute, BLANK_ROUTE_ID};
 pub use handshake::KaspadHandshake;
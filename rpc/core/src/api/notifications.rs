@@ -6,7 +6,7 @@ use kaspa_notify::{
     notification::{full_featured, Notification as NotificationTrait},
     subscription::{
         context::SubscriptionContext,
-        single::{OverallSubscription, UtxosChangedSubscription, VirtualChainChangedSubscription},
+        single::{OverallSubscription, TransactionConflictSubscription, UtxosChangedSubscription, VirtualChainChangedSubscription},
         Subscription,
     },
 };
@@ -44,6 +44,9 @@ pub enum Notification {
 
     #[display(fmt = "NewBlockTemplate notification")]
     NewBlockTemplate(NewBlockTemplateNotification),
+
+    #[display(fmt = "TransactionConflict notification: transaction {} conflicting with {}", "_0.transaction_id", "_0.conflicting_transaction_id")]
+    TransactionConflict(TransactionConflictNotification),
 }
 }
 
@@ -60,6 +63,7 @@ impl Notification {
             Notification::VirtualDaaScoreChanged(v) => to_value(&v),
             Notification::SinkBlueScoreChanged(v) => to_value(&v),
             Notification::VirtualChainChanged(v) => to_value(&v),
+            Notification::TransactionConflict(v) => to_value(&v),
         }
     }
 }
@@ -85,6 +89,7 @@ impl NotificationTrait for Notification {
                             removed_chain_block_hashes: payload.removed_chain_block_hashes.clone(),
                             added_chain_block_hashes: payload.added_chain_block_hashes.clone(),
                             accepted_transaction_ids: Arc::new(vec![]),
+                            sequence: payload.sequence,
                         }));
                     }
                 }
@@ -108,6 +113,20 @@ impl NotificationTrait for Notification {
         }
     }
 
+    fn apply_transaction_conflict_subscription(
+        &self,
+        subscription: &TransactionConflictSubscription,
+        context: &SubscriptionContext,
+    ) -> Option<Self> {
+        match subscription.active() {
+            true => {
+                let Self::TransactionConflict(notification) = self else { return None };
+                notification.apply_transaction_conflict_subscription(subscription, context).map(Self::TransactionConflict)
+            }
+            false => None,
+        }
+    }
+
     fn event_type(&self) -> EventType {
         self.into()
     }
@@ -116,6 +135,10 @@ impl NotificationTrait for Notification {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::{RpcTransactionOutpoint, RpcUtxoEntry, RpcUtxosByAddressesEntry};
+    use kaspa_addresses::{Address, Prefix, Version};
+    use kaspa_notify::subscription::single::UtxosChangedState;
+    use kaspa_txscript::pay_to_address_script;
 
     #[test]
     fn test_notification_from_bytes() {
@@ -123,4 +146,46 @@ mod test {
         let notification = Notification::try_from_slice(bytes);
         println!("notification: {notification:?}");
     }
+
+    #[test]
+    fn test_utxos_changed_wildcard_subscription() {
+        let context = SubscriptionContext::new();
+        let address = |i: u8| Address::new(Prefix::Mainnet, Version::PubKey, &[i; 32]);
+        let entry = |address: Address| RpcUtxosByAddressesEntry {
+            outpoint: RpcTransactionOutpoint::new(Default::default(), 0),
+            utxo_entry: RpcUtxoEntry::new(1, pay_to_address_script(&address), 0, false),
+            address: Some(address),
+        };
+        let notification = |addresses: &[Address]| {
+            Notification::UtxosChanged(UtxosChangedNotification {
+                added: Arc::new(addresses.iter().cloned().map(entry).collect()),
+                removed: Default::default(),
+                sequence: None,
+            })
+        };
+        let addresses = |notification: Option<Notification>| match notification {
+            Some(Notification::UtxosChanged(notification)) => notification.added.iter().map(|x| x.address.clone().unwrap()).collect(),
+            _ => vec![],
+        };
+
+        let wildcard = UtxosChangedSubscription::new(UtxosChangedState::All, 0);
+        let filtered = UtxosChangedSubscription::new(UtxosChangedState::Selected, 1);
+        filtered.data_mut().register(vec![address(0)], &context).unwrap();
+        let tracked = context.address_tracker.len();
+
+        // The wildcard subscriber sees the changes of addresses never seen before while the filtered one does not
+        let new_addresses = [address(1), address(2)];
+        let applied = notification(&new_addresses).apply_utxos_changed_subscription(&wildcard, &context);
+        assert_eq!(addresses(applied), new_addresses.to_vec());
+        assert!(notification(&new_addresses).apply_utxos_changed_subscription(&filtered, &context).is_none());
+
+        let all_addresses = [address(0), address(3)];
+        let applied = notification(&all_addresses).apply_utxos_changed_subscription(&wildcard, &context);
+        assert_eq!(addresses(applied), all_addresses.to_vec());
+        let applied = notification(&all_addresses).apply_utxos_changed_subscription(&filtered, &context);
+        assert_eq!(addresses(applied), vec![address(0)]);
+
+        // Wildcard notifications skip the address tracker entirely
+        assert_eq!(context.address_tracker.len(), tracked);
+    }
 }
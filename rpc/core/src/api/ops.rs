@@ -72,6 +72,8 @@ pub enum RpcApiOps {
     GetBalancesByAddresses,
     // ?
     GetSinkBlueScore,
+    /// Get the DAA score, sink, sink blue score, parents count and past median time of the virtual block
+    GetVirtualInfo,
     /// Ban a specific peer by it's IP address
     Ban,
     /// Unban a specific peer by it's IP address
@@ -86,6 +88,34 @@ pub enum RpcApiOps {
     GetCoinSupply,
     /// Get DAA Score timestamp estimate
     GetDaaScoreTimestampEstimate,
+    /// Get the selected chain block nearest to a DAA score
+    GetChainBlockByDaaScore,
+    /// Adjust the log filters at runtime
+    SetLogFilters,
+    /// Get the spend status of an outpoint
+    GetOutpointSpendStatus,
+    /// Estimate the confirmation time of a mempool transaction
+    EstimateTransactionConfirmationTime,
+    /// Get a merkle proof of the acceptance of a transaction by a chain block
+    GetAcceptanceProof,
+    /// Create a consistent backup of the consensus databases
+    CreateBackup,
+    /// Get a trace of the virtual parent selection over the current tips
+    GetVirtualParentSelectionTrace,
+    /// Get information about the node host, including the results of the startup preflight checks
+    GetSystemInfo,
+    /// Remove a peer added by AddPeer and disconnect from it
+    RemovePeer,
+    /// Adjust the limit of concurrent block validations at runtime
+    SetProcessingParallelism,
+    /// Whether addresses were ever used, including addresses whose funds were fully spent
+    GetAddressActivity,
+    /// Get the journaled notifications of a scope emitted since a sequence number
+    GetMissedNotifications,
+    /// Get the UTXO entry spent by an input of an accepted transaction and the address it was locked to
+    GetUtxoReturnAddress,
+    /// Validate a block template against the current virtual state without submitting it
+    ValidateBlockTemplate,
 
     // Subscription commands for starting/stopping notifications
     NotifyBlockAdded,
@@ -97,6 +127,7 @@ pub enum RpcApiOps {
     NotifyVirtualDaaScoreChanged,
     NotifyVirtualChainChanged,
     NotifySinkBlueScoreChanged,
+    NotifyTransactionConflict,
 
     // ~
     Subscribe,
@@ -114,6 +145,7 @@ pub enum RpcApiOps {
     VirtualDaaScoreChangedNotification,
     PruningPointUtxoSetOverrideNotification,
     NewBlockTemplateNotification,
+    TransactionConflictNotification,
 }
 
 impl RpcApiOps {
@@ -129,6 +161,7 @@ impl RpcApiOps {
                 | RpcApiOps::NotifyFinalityConflictResolved
                 | RpcApiOps::NotifySinkBlueScoreChanged
                 | RpcApiOps::NotifyVirtualDaaScoreChanged
+                | RpcApiOps::NotifyTransactionConflict
                 | RpcApiOps::Subscribe
                 | RpcApiOps::Unsubscribe
         )
@@ -155,6 +188,7 @@ impl From<EventType> for RpcApiOps {
             EventType::VirtualDaaScoreChanged => RpcApiOps::VirtualDaaScoreChangedNotification,
             EventType::PruningPointUtxoSetOverride => RpcApiOps::PruningPointUtxoSetOverrideNotification,
             EventType::NewBlockTemplate => RpcApiOps::NewBlockTemplateNotification,
+            EventType::TransactionConflict => RpcApiOps::TransactionConflictNotification,
         }
     }
 }
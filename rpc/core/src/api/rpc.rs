@@ -12,6 +12,10 @@ use std::sync::Arc;
 
 pub const MAX_SAFE_WINDOW_SIZE: u32 = 10_000;
 
+/// Number of selected chain blocks searched by `GetOutpointSpendStatus` when no search depth is provided
+pub const DEFAULT_OUTPOINT_SPEND_SEARCH_DEPTH: u64 = 1_000;
+pub const MAX_SAFE_OUTPOINT_SPEND_SEARCH_DEPTH: u64 = 10_000;
+
 /// Client RPC Api
 ///
 /// The [`RpcApi`] trait defines RPC calls taking a request message as unique parameter.
@@ -34,8 +38,16 @@ pub trait RpcApi: Sync + Send + AnySync {
         connection_metrics: bool,
         bandwidth_metrics: bool,
         consensus_metrics: bool,
+        script_metrics: bool,
     ) -> RpcResult<GetMetricsResponse> {
-        self.get_metrics_call(GetMetricsRequest { process_metrics, connection_metrics, bandwidth_metrics, consensus_metrics }).await
+        self.get_metrics_call(GetMetricsRequest {
+            process_metrics,
+            connection_metrics,
+            bandwidth_metrics,
+            consensus_metrics,
+            script_metrics,
+        })
+        .await
     }
     async fn get_metrics_call(&self, request: GetMetricsRequest) -> RpcResult<GetMetricsResponse>;
 
@@ -78,7 +90,7 @@ pub trait RpcApi: Sync + Send + AnySync {
 
     /// Requests the list of known kaspad addresses in the current network (mainnet, testnet, etc.)
     async fn get_peer_addresses(&self) -> RpcResult<GetPeerAddressesResponse> {
-        self.get_peer_addresses_call(GetPeerAddressesRequest {}).await
+        self.get_peer_addresses_call(GetPeerAddressesRequest::default()).await
     }
     async fn get_peer_addresses_call(&self, request: GetPeerAddressesRequest) -> RpcResult<GetPeerAddressesResponse>;
 
@@ -119,7 +131,8 @@ pub trait RpcApi: Sync + Send + AnySync {
 
     /// Adds a peer to the node's outgoing connection list.
     ///
-    /// This will, in most cases, result in the node connecting to said peer.
+    /// This will, in most cases, result in the node connecting to said peer. Permanent peers are persisted
+    /// as manual peers, which the node keeps connected across restarts until removed by [`RpcApi::remove_peer`].
     async fn add_peer(&self, peer_address: RpcContextualPeerAddress, is_permanent: bool) -> RpcResult<()> {
         self.add_peer_call(AddPeerRequest::new(peer_address, is_permanent)).await?;
         Ok(())
@@ -128,7 +141,7 @@ pub trait RpcApi: Sync + Send + AnySync {
 
     /// Submits a transaction to the mempool.
     async fn submit_transaction(&self, transaction: RpcTransaction, allow_orphan: bool) -> RpcResult<RpcTransactionId> {
-        Ok(self.submit_transaction_call(SubmitTransactionRequest { transaction, allow_orphan }).await?.transaction_id)
+        Ok(self.submit_transaction_call(SubmitTransactionRequest::new(transaction, allow_orphan)).await?.transaction_id)
     }
     async fn submit_transaction_call(&self, request: SubmitTransactionRequest) -> RpcResult<SubmitTransactionResponse>;
 
@@ -237,6 +250,13 @@ pub trait RpcApi: Sync + Send + AnySync {
     }
     async fn get_sink_blue_score_call(&self, request: GetSinkBlueScoreRequest) -> RpcResult<GetSinkBlueScoreResponse>;
 
+    /// Requests the DAA score, sink, sink blue score, parents count and past median time of the virtual block
+    /// at once. The call reads the cached virtual state only, hence is cheap enough for frequent polling.
+    async fn get_virtual_info(&self) -> RpcResult<RpcVirtualInfo> {
+        self.get_virtual_info_call(GetVirtualInfoRequest {}).await
+    }
+    async fn get_virtual_info_call(&self, request: GetVirtualInfoRequest) -> RpcResult<GetVirtualInfoResponse>;
+
     /// Bans the given ip.
     async fn ban(&self, ip: RpcIpAddress) -> RpcResult<()> {
         self.ban_call(BanRequest::new(ip)).await?;
@@ -304,6 +324,145 @@ pub trait RpcApi: Sync + Send + AnySync {
         request: GetDaaScoreTimestampEstimateRequest,
     ) -> RpcResult<GetDaaScoreTimestampEstimateResponse>;
 
+    /// Returns the selected chain block whose DAA score is nearest to `daa_score`.
+    async fn get_chain_block_by_daa_score(&self, daa_score: u64) -> RpcResult<GetChainBlockByDaaScoreResponse> {
+        self.get_chain_block_by_daa_score_call(GetChainBlockByDaaScoreRequest { daa_score }).await
+    }
+    async fn get_chain_block_by_daa_score_call(
+        &self,
+        request: GetChainBlockByDaaScoreRequest,
+    ) -> RpcResult<GetChainBlockByDaaScoreResponse>;
+
+    /// Adjusts the log filters of the node at runtime and returns the filters in effect.
+    async fn set_log_filters(&self, filters: String) -> RpcResult<String> {
+        Ok(self.set_log_filters_call(SetLogFiltersRequest { filters }).await?.filters)
+    }
+    async fn set_log_filters_call(&self, request: SetLogFiltersRequest) -> RpcResult<SetLogFiltersResponse>;
+
+    /// Returns the spend status of `outpoint` as seen by the virtual UTXO set, the mempool and the
+    /// `search_depth` most recent selected chain blocks (defaults to a node defined depth).
+    async fn get_outpoint_spend_status(
+        &self,
+        outpoint: RpcTransactionOutpoint,
+        search_depth: Option<u64>,
+    ) -> RpcResult<GetOutpointSpendStatusResponse> {
+        self.get_outpoint_spend_status_call(GetOutpointSpendStatusRequest { outpoint, search_depth }).await
+    }
+    async fn get_outpoint_spend_status_call(
+        &self,
+        request: GetOutpointSpendStatusRequest,
+    ) -> RpcResult<GetOutpointSpendStatusResponse>;
+
+    /// Returns an estimated confirmation time range of the mempool transaction `transaction_id`.
+    /// Fails with [`crate::RpcError::TransactionNotFound`] if the transaction is not in the transaction pool.
+    async fn estimate_transaction_confirmation_time(
+        &self,
+        transaction_id: RpcTransactionId,
+    ) -> RpcResult<EstimateTransactionConfirmationTimeResponse> {
+        self.estimate_transaction_confirmation_time_call(EstimateTransactionConfirmationTimeRequest { transaction_id }).await
+    }
+    async fn estimate_transaction_confirmation_time_call(
+        &self,
+        request: EstimateTransactionConfirmationTimeRequest,
+    ) -> RpcResult<EstimateTransactionConfirmationTimeResponse>;
+
+    /// Returns a merkle proof of the acceptance of `transaction_id` by the selected chain block `accepting_block_hash`,
+    /// verifiable against the accepted id merkle root of the block header.
+    async fn get_acceptance_proof(
+        &self,
+        accepting_block_hash: RpcHash,
+        transaction_id: RpcTransactionId,
+    ) -> RpcResult<GetAcceptanceProofResponse> {
+        self.get_acceptance_proof_call(GetAcceptanceProofRequest { accepting_block_hash, transaction_id }).await
+    }
+    async fn get_acceptance_proof_call(&self, request: GetAcceptanceProofRequest) -> RpcResult<GetAcceptanceProofResponse>;
+
+    /// Requests the node to back up its consensus databases at `output_directory`, an absolute path on the node host.
+    /// Returns the manifest of the backup. Requires the node to run with `--unsaferpc`.
+    async fn create_backup(&self, output_directory: String) -> RpcResult<CreateBackupResponse> {
+        self.create_backup_call(CreateBackupRequest { output_directory }).await
+    }
+    async fn create_backup_call(&self, request: CreateBackupRequest) -> RpcResult<CreateBackupResponse>;
+
+    /// Requests a trace of the virtual parent selection over the current tips, giving the reason of exclusion of each
+    /// tip which is not a virtual parent. Requires the node to run with `--unsaferpc`.
+    async fn get_virtual_parent_selection_trace(&self) -> RpcResult<GetVirtualParentSelectionTraceResponse> {
+        self.get_virtual_parent_selection_trace_call(GetVirtualParentSelectionTraceRequest {}).await
+    }
+    async fn get_virtual_parent_selection_trace_call(
+        &self,
+        request: GetVirtualParentSelectionTraceRequest,
+    ) -> RpcResult<GetVirtualParentSelectionTraceResponse>;
+
+    /// Requests information about the node host, including the results of the startup preflight checks
+    /// (file descriptor limit, free disk space and clock offset against the connected peers).
+    async fn get_system_info(&self) -> RpcResult<GetSystemInfoResponse> {
+        self.get_system_info_call(GetSystemInfoRequest {}).await
+    }
+    async fn get_system_info_call(&self, request: GetSystemInfoRequest) -> RpcResult<GetSystemInfoResponse>;
+
+    /// Removes a peer added by [`RpcApi::add_peer`], whether permanently or not, and disconnects from it.
+    async fn remove_peer(&self, peer_address: RpcContextualPeerAddress) -> RpcResult<()> {
+        self.remove_peer_call(RemovePeerRequest::new(peer_address)).await?;
+        Ok(())
+    }
+    async fn remove_peer_call(&self, request: RemovePeerRequest) -> RpcResult<RemovePeerResponse>;
+
+    /// Sets the maximum number of blocks validated concurrently by the node and returns the previous limit.
+    async fn set_processing_parallelism(&self, limit: u32) -> RpcResult<u32> {
+        Ok(self.set_processing_parallelism_call(SetProcessingParallelismRequest::new(limit)).await?.previous_limit)
+    }
+    async fn set_processing_parallelism_call(
+        &self,
+        request: SetProcessingParallelismRequest,
+    ) -> RpcResult<SetProcessingParallelismResponse>;
+
+    /// Reports for each of `addresses` whether it ever received funds, along with the DAA scores of its first
+    /// and latest activity. Unlike UTXO lookups, addresses whose funds were fully spent are still reported as used.
+    ///
+    /// This call is only available when this node was started with `--utxoindex`.
+    async fn get_address_activity(&self, addresses: Vec<RpcAddress>) -> RpcResult<Vec<RpcAddressActivityEntry>> {
+        Ok(self.get_address_activity_call(GetAddressActivityRequest::new(addresses)).await?.entries)
+    }
+    async fn get_address_activity_call(&self, request: GetAddressActivityRequest) -> RpcResult<GetAddressActivityResponse>;
+
+    /// Requests the notifications of `scope` emitted since `from_sequence`, letting a client which detected a jump in the
+    /// sequence of its notifications backfill the ones it missed. Only the VirtualChainChanged and UtxosChanged scopes
+    /// are journaled.
+    ///
+    /// Fails with [`RpcError::NotificationsTooFarBehind`](crate::RpcError::NotificationsTooFarBehind) if the notifications
+    /// are no longer retained, in which case a full resync is required.
+    ///
+    /// This call is only available when this node was started with `--notification-journal-size`.
+    async fn get_missed_notifications(&self, scope: Scope, from_sequence: u64) -> RpcResult<GetMissedNotificationsResponse> {
+        self.get_missed_notifications_call(GetMissedNotificationsRequest::new(scope, from_sequence)).await
+    }
+    async fn get_missed_notifications_call(&self, request: GetMissedNotificationsRequest)
+        -> RpcResult<GetMissedNotificationsResponse>;
+
+    /// Returns the UTXO entry spent by input `input_index` of `transaction_id`, as accepted by the selected chain block
+    /// `accepting_block_hash`, along with the address it was locked to if its script public key is standard.
+    ///
+    /// This call is only available when this node was started with `--store-spent-utxos`.
+    async fn get_utxo_return_address(
+        &self,
+        accepting_block_hash: RpcHash,
+        transaction_id: RpcTransactionId,
+        input_index: u32,
+    ) -> RpcResult<GetUtxoReturnAddressResponse> {
+        self.get_utxo_return_address_call(GetUtxoReturnAddressRequest::new(accepting_block_hash, transaction_id, input_index)).await
+    }
+    async fn get_utxo_return_address_call(&self, request: GetUtxoReturnAddressRequest) -> RpcResult<GetUtxoReturnAddressResponse>;
+
+    /// Validates a block template against the current virtual state without submitting it, skipping the proof of work check.
+    /// The response reports either the mass and total fees of a valid block or the rule violated by an invalid one.
+    ///
+    /// This call is only available when this node was started with `--unsaferpc`.
+    async fn validate_block_template(&self, block: RpcBlock) -> RpcResult<ValidateBlockTemplateResponse> {
+        self.validate_block_template_call(ValidateBlockTemplateRequest::new(block)).await
+    }
+    async fn validate_block_template_call(&self, request: ValidateBlockTemplateRequest) -> RpcResult<ValidateBlockTemplateResponse>;
+
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Notification API
 
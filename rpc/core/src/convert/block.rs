@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
-use crate::{RpcBlock, RpcError, RpcResult, RpcTransaction};
-use kaspa_consensus_core::block::{Block, MutableBlock};
+use crate::{RpcBlock, RpcBlockTemplateStats, RpcError, RpcResult, RpcTransaction};
+use kaspa_consensus_core::block::{Block, MutableBlock, TemplateSelectionStats};
 
 // ----------------------------------------------------------------------------
 // consensus_core to rpc_core
@@ -28,6 +28,19 @@ impl From<&MutableBlock> for RpcBlock {
     }
 }
 
+impl From<&TemplateSelectionStats> for RpcBlockTemplateStats {
+    fn from(item: &TemplateSelectionStats) -> Self {
+        Self {
+            total_fees: item.total_fees,
+            total_mass: item.total_mass,
+            max_block_mass: item.max_block_mass,
+            considered_count: item.considered_count,
+            selected_count: item.selected_count,
+            min_feerate: item.min_feerate.unwrap_or_default(),
+        }
+    }
+}
+
 // ----------------------------------------------------------------------------
 // rpc_core to consensus_core
 // ----------------------------------------------------------------------------
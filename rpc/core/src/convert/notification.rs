@@ -63,6 +63,7 @@ impl From<&consensus_notify::VirtualChainChangedNotification> for VirtualChainCh
                     })
                     .collect()
             }),
+            sequence: None,
         }
     }
 }
@@ -94,7 +95,7 @@ impl From<&consensus_notify::SinkBlueScoreChangedNotification> for SinkBlueScore
 
 impl From<&consensus_notify::VirtualDaaScoreChangedNotification> for VirtualDaaScoreChangedNotification {
     fn from(item: &consensus_notify::VirtualDaaScoreChangedNotification) -> Self {
-        Self { virtual_daa_score: item.virtual_daa_score }
+        Self { virtual_daa_score: item.virtual_daa_score, virtual_info: Some(item.virtual_info.clone()) }
     }
 }
 
@@ -139,6 +140,10 @@ impl From<&index_notify::UtxosChangedNotification> for UtxosChangedNotification
     // This is not intended to be ever called because no address prefix is available.
     // Use kaspa_rpc_service::converter::index::IndexConverter instead.
     fn from(item: &index_notify::UtxosChangedNotification) -> Self {
-        Self { added: Arc::new(utxo_set_into_rpc(&item.added, None)), removed: Arc::new(utxo_set_into_rpc(&item.removed, None)) }
+        Self {
+            added: Arc::new(utxo_set_into_rpc(&item.added, None)),
+            removed: Arc::new(utxo_set_into_rpc(&item.removed, None)),
+            sequence: None,
+        }
     }
 }
@@ -1,5 +1,6 @@
-use kaspa_consensus_core::{subnets::SubnetworkConversionError, tx::TransactionId};
-use kaspa_utils::networking::IpAddress;
+use kaspa_consensus_core::{subnets::SubnetworkConversionError, tx::TransactionId};
+use kaspa_notify::events::EventType;
+use kaspa_utils::networking::{IpAddress, NetAddress};
 use std::{net::AddrParseError, num::TryFromIntError};
 use thiserror::Error;
 use workflow_core::channel::ChannelError;
@@ -62,21 +63,45 @@ pub enum RpcError {
     #[error("Method unavailable. No connection manager is currently available.")]
     NoConnectionManager,
 
+    #[error("Method unavailable. Run the node with the --notification-journal-size argument.")]
+    NoNotificationJournal,
+
+    #[error("Method unavailable. Run the node with the --store-spent-utxos argument.")]
+    NoSpentUtxoEntries,
+
+    #[error("Notifications of scope {0:?} are not journaled.")]
+    NotificationScopeNotJournaled(EventType),
+
+    #[error("Notifications of scope {0:?} from sequence {1} are no longer available, a full resync is required.")]
+    NotificationsTooFarBehind(EventType, u64),
+
     #[error("Requested window size {0} is larger than max {1} allowed in RPC safe mode.")]
     WindowSizeExceedingMaximum(u32, u32),
 
     #[error("Requested window size {0} is larger than pruning point depth {1}.")]
     WindowSizeExceedingPruningDepth(u32, u64),
 
+    #[error("Requested search depth {0} is larger than max {1} allowed in RPC safe mode.")]
+    SearchDepthExceedingMaximum(u64, u64),
+
     #[error("Method unavailable in safe mode. Run the node with --unsaferpc argument.")]
     UnavailableInSafeMode,
 
+    #[error("Response size of {0} bytes exceeds the maximum message size of {1} bytes. Consider paginating the request.")]
+    ResponseSizeLimitExceeded(usize, usize),
+
     #[error("Cannot ban IP {0} because it has some permanent connection.")]
     IpHasPermanentConnection(IpAddress),
 
     #[error("IP {0} is not registered as banned.")]
     IpIsNotBanned(IpAddress),
 
+    #[error("Cannot add peer {0} permanently since the maximum of {1} manual peers is reached.")]
+    ManualPeersLimitReached(NetAddress, usize),
+
+    #[error("Peer {0} was not added.")]
+    PeerIsNotAdded(NetAddress),
+
     #[error("Block was not submitted: {0}")]
     SubmitBlockError(SubmitBlockRejectReason),
 
@@ -98,6 +123,9 @@ pub enum RpcError {
     #[error(transparent)]
     ConsensusError(#[from] kaspa_consensus_core::errors::consensus::ConsensusError),
 
+    #[error(transparent)]
+    BackupError(#[from] kaspa_consensus_core::errors::backup::BackupError),
+
     #[error(transparent)]
     ScriptClassError(#[from] kaspa_txscript::script_class::Error),
 
@@ -110,15 +138,20 @@ pub enum RpcError {
     #[error("{0}")]
     General(String),
 
+    /// An error reported by the node along with the trace id of the failing call, the error itself being
+    /// reduced to its message
+    #[error("{0} (trace id: {1})")]
+    Traced(String, String),
+
     #[error("RpcCtl dispatch error")]
     RpcCtlDispatchError,
 
     #[error("transaction query must either not filter transactions or include orphans")]
     InconsistentMempoolTxQuery,
 
-    #[error(transparent)]
-    SubnetParsingError(#[from] SubnetworkConversionError),
-
+    #[error(transparent)]
+    SubnetParsingError(#[from] SubnetworkConversionError),
+
     #[error(transparent)]
     WasmError(#[from] workflow_wasm::error::Error),
 
@@ -129,6 +162,24 @@ pub enum RpcError {
     ConsensusClient(#[from] kaspa_consensus_client::error::Error),
 }
 
+impl RpcError {
+    /// Attaches the trace id of the failing call to the error, keeping the trace id already attached if any
+    pub fn with_trace_id(self, trace_id: &str) -> Self {
+        match self {
+            RpcError::Traced(..) => self,
+            _ => RpcError::Traced(self.to_string(), trace_id.to_string()),
+        }
+    }
+
+    /// Returns the trace id of the failing call, if reported by the node
+    pub fn trace_id(&self) -> Option<&str> {
+        match self {
+            RpcError::Traced(_, trace_id) => Some(trace_id),
+            _ => None,
+        }
+    }
+}
+
 impl From<String> for RpcError {
     fn from(value: String) -> Self {
         RpcError::General(value)
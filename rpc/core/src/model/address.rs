@@ -20,5 +20,29 @@ pub struct RpcBalancesByAddressesEntry {
     pub address: RpcAddress,
 
     /// Balance of `address` if available
+    #[serde(default, with = "kaspa_utils::serde_u64_string::option")]
     pub balance: Option<u64>,
 }
+
+/// Represents the activity of an address returned by the `GetAddressActivity` RPC.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAddressActivityEntry {
+    pub address: RpcAddress,
+
+    /// Whether `address` ever received funds. The DAA scores below are zero if not.
+    pub has_received: bool,
+
+    /// DAA score of the first UTXO of `address` seen by the node
+    pub first_seen_daa_score: u64,
+
+    /// DAA score of the latest UTXO of `address` created or spent
+    pub last_activity_daa_score: u64,
+}
+
+impl RpcAddressActivityEntry {
+    /// An entry of an address which never received funds
+    pub fn unused(address: RpcAddress) -> Self {
+        Self { address, has_received: false, first_seen_daa_score: 0, last_activity_daa_score: 0 }
+    }
+}
@@ -1,7 +1,12 @@
-use crate::prelude::{RpcHash, RpcHeader, RpcTransaction};
+use crate::prelude::{RpcBlueWorkType, RpcHash, RpcHeader, RpcTransaction};
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
+pub type RpcDifficultyTarget = kaspa_math::Uint256;
+pub type RpcVirtualParentExclusionReason = kaspa_consensus_core::virtual_parents::VirtualParentExclusionReason;
+pub type RpcExcludedVirtualParent = kaspa_consensus_core::virtual_parents::ExcludedVirtualParent;
+pub type RpcVirtualInfo = kaspa_consensus_core::api::VirtualInfo;
+
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcBlock {
@@ -23,6 +28,44 @@ pub struct RpcBlockVerboseData {
     pub merge_set_blues_hashes: Vec<RpcHash>,
     pub merge_set_reds_hashes: Vec<RpcHash>,
     pub is_chain_block: bool,
+    /// Difficulty target decoded from the header bits
+    pub target: RpcDifficultyTarget,
+    /// Expected number of hashes needed to mine the block, as implied by its target
+    pub work: RpcBlueWorkType,
+    /// Network hashrate estimated over the DAA window of the block, if the window is long enough
+    #[serde(default, with = "kaspa_utils::serde_u64_string::option")]
+    pub network_hashes_per_second: Option<u64>,
+    /// Times at which the node learned of the block, only tracked for recent blocks
+    #[serde(default)]
+    pub propagation: Option<RpcBlockPropagation>,
+}
+
+/// Times (in unix milliseconds of the node clock) at which the node learned of a recent block.
+/// Blocks mined by the node only have a validation time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBlockPropagation {
+    /// The first announcement of the block hash by a peer
+    pub first_inv_time: Option<u64>,
+    /// The arrival of the block header
+    pub header_time: Option<u64>,
+    /// The completion of the block body validation
+    pub validated_time: Option<u64>,
+}
+
+/// Statistics of the transaction selection of a block template, excluding the coinbase transaction
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBlockTemplateStats {
+    #[serde(with = "kaspa_utils::serde_u64_string")]
+    pub total_fees: u64,
+    pub total_mass: u64,
+    pub max_block_mass: u64,
+    /// Number of mempool transactions the selection was made from
+    pub considered_count: u64,
+    pub selected_count: u64,
+    /// Lowest fee per gram of mass of the selected transactions, zero if none was selected
+    pub min_feerate: f64,
 }
 
 cfg_if::cfg_if! {
@@ -58,6 +101,37 @@ cfg_if::cfg_if! {
             mergeSetBluesHashes: HexString[];
             mergeSetRedsHashes: HexString[];
             isChainBlock: boolean;
+            target: HexString;
+            work: HexString;
+            networkHashesPerSecond?: string;
+            propagation?: IBlockPropagation;
+        }
+
+        /**
+         * Times (in unix milliseconds of the node clock) at which the node
+         * learned of a recent block.
+         * 
+         * @category Node RPC
+         */
+        export interface IBlockPropagation {
+            firstInvTime?: number;
+            headerTime?: number;
+            validatedTime?: number;
+        }
+
+        /**
+         * Statistics of the transaction selection of a block template,
+         * excluding the coinbase transaction.
+         * 
+         * @category Node RPC
+         */
+        export interface IBlockTemplateStats {
+            totalFees: string;
+            totalMass: number;
+            maxBlockMass: number;
+            consideredCount: number;
+            selectedCount: number;
+            minFeerate: number;
         }
         "#;
     }
@@ -0,0 +1,116 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use kaspa_consensus_core::multi_consensus::{
+    ConsensusCreationReason, ConsensusEntryInfo, MultiConsensusStatus, RetiredConsensusEntryInfo,
+};
+use serde::{Deserialize, Serialize};
+
+/// Why a consensus entry was created
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcConsensusCreationReason {
+    Genesis,
+    PruningPointSync,
+    /// The entry was created by a node version not recording the reason, or the reason is unknown to this version
+    Unknown,
+}
+
+impl RpcConsensusCreationReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RpcConsensusCreationReason::Genesis => "genesis",
+            RpcConsensusCreationReason::PruningPointSync => "pruning-point-sync",
+            RpcConsensusCreationReason::Unknown => "unknown",
+        }
+    }
+}
+
+impl std::fmt::Display for RpcConsensusCreationReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for RpcConsensusCreationReason {
+    type Err = crate::RpcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "genesis" => Ok(RpcConsensusCreationReason::Genesis),
+            "pruning-point-sync" => Ok(RpcConsensusCreationReason::PruningPointSync),
+            "unknown" => Ok(RpcConsensusCreationReason::Unknown),
+            _ => Err(crate::RpcError::General(format!("unknown consensus creation reason: {s}"))),
+        }
+    }
+}
+
+impl From<ConsensusCreationReason> for RpcConsensusCreationReason {
+    fn from(value: ConsensusCreationReason) -> Self {
+        match value {
+            ConsensusCreationReason::Genesis => RpcConsensusCreationReason::Genesis,
+            ConsensusCreationReason::PruningPointSync => RpcConsensusCreationReason::PruningPointSync,
+            ConsensusCreationReason::Unknown => RpcConsensusCreationReason::Unknown,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcConsensusEntry {
+    pub key: u64,
+    /// Name of the database directory of the entry, relative to the consensus databases directory
+    pub directory_name: String,
+    /// Unix time (in milliseconds) at which the entry was created
+    pub creation_timestamp: u64,
+    pub creation_reason: RpcConsensusCreationReason,
+}
+
+impl From<ConsensusEntryInfo> for RpcConsensusEntry {
+    fn from(value: ConsensusEntryInfo) -> Self {
+        Self {
+            key: value.key,
+            directory_name: value.directory_name,
+            creation_timestamp: value.creation_timestamp,
+            creation_reason: value.creation_reason.into(),
+        }
+    }
+}
+
+/// A consensus entry replaced as active, whose database directory awaits deletion
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcRetiredConsensusEntry {
+    pub entry: RpcConsensusEntry,
+    /// Unix time (in milliseconds) at which the entry was replaced as active, `None` if unknown
+    pub retirement_timestamp: Option<u64>,
+    /// Size in bytes of the database directory of the entry
+    pub disk_usage: u64,
+}
+
+impl From<RetiredConsensusEntryInfo> for RpcRetiredConsensusEntry {
+    fn from(value: RetiredConsensusEntryInfo) -> Self {
+        Self { entry: value.entry.into(), retirement_timestamp: value.retirement_timestamp, disk_usage: value.disk_usage }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcConsensusStatus {
+    /// The active consensus entry
+    pub current: Option<RpcConsensusEntry>,
+    /// The staging consensus entry, existing while syncing from the pruning point of a peer
+    pub staging: Option<RpcConsensusEntry>,
+    /// Whether the staging consensus is sealed and its promotion to active is decided
+    pub is_staging_prepared: bool,
+    pub retired: Vec<RpcRetiredConsensusEntry>,
+}
+
+impl From<MultiConsensusStatus> for RpcConsensusStatus {
+    fn from(value: MultiConsensusStatus) -> Self {
+        Self {
+            current: value.current.map(Into::into),
+            staging: value.staging.map(Into::into),
+            is_staging_prepared: value.is_staging_prepared,
+            retired: value.retired.into_iter().map(Into::into).collect(),
+        }
+    }
+}
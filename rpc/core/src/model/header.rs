@@ -1 +1,25 @@
 pub type RpcHeader = kaspa_consensus_core::header::Header;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::BlueWorkType;
+    use kaspa_hashes::Hash;
+    use serde_json::json;
+
+    #[test]
+    fn test_header_json_is_lossless() {
+        let blue_work = BlueWorkType::from_be_bytes([0xff; 24]);
+        let hash = Hash::from_u64_word;
+        let header =
+            RpcHeader::new_finalized(1, vec![vec![hash(1)]], hash(2), hash(3), hash(4), 5, 6, u64::MAX, 7, blue_work, 8, hash(9));
+        let value = serde_json::to_value(&header).unwrap();
+        assert_eq!(value["nonce"], json!("18446744073709551615"));
+        assert_eq!(value["blueWork"], json!("ffffffffffffffffffffffffffffffffffffffffffffffff"));
+
+        let decoded: RpcHeader = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded.nonce, u64::MAX);
+        assert_eq!(decoded.blue_work, blue_work);
+        assert_eq!(decoded.hash, header.hash);
+    }
+}
@@ -1,4 +1,5 @@
 use kaspa_consensus_core::BlueWorkType;
+use kaspa_math::Uint256;
 use smallvec::{smallvec, SmallVec};
 use std::str;
 
@@ -92,6 +93,24 @@ impl FromRpcHex for BlueWorkType {
     }
 }
 
+/// Big endian format.
+/// Leading '0' are ignored by str parsing and absent of string result.
+/// Odd str lengths are valid.
+impl ToRpcHex for Uint256 {
+    fn to_rpc_hex(&self) -> String {
+        format!("{self:x}")
+    }
+}
+
+/// Big endian format.
+/// Leading '0' are ignored by str parsing and absent of string result.
+/// Odd str lengths are valid.
+impl FromRpcHex for Uint256 {
+    fn from_rpc_hex(hex_str: &str) -> Result<Self, faster_hex::Error> {
+        Uint256::from_hex(hex_str)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
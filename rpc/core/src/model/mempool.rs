@@ -1,21 +1,39 @@
+use super::script_class::RpcScriptClass;
 use super::RpcAddress;
 use super::RpcTransaction;
+use super::RpcTransactionOutpoint;
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct RpcMempoolEntry {
+    #[serde(with = "kaspa_utils::serde_u64_string")]
     pub fee: u64,
     pub transaction: RpcTransaction,
     pub is_orphan: bool,
+    /// The transaction is held by the node until its lock time is reached
+    pub is_held: bool,
 }
 
 impl RpcMempoolEntry {
-    pub fn new(fee: u64, transaction: RpcTransaction, is_orphan: bool) -> Self {
-        Self { fee, transaction, is_orphan }
+    pub fn new(fee: u64, transaction: RpcTransaction, is_orphan: bool, is_held: bool) -> Self {
+        Self { fee, transaction, is_orphan, is_held }
     }
 }
 
+/// Totals of the mempool transactions matching a query, taken under the same lock of the mempool
+/// as the entries returned along. Held transactions are accounted along the transaction pool.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcMempoolSummary {
+    pub transaction_count: u64,
+    pub total_mass: u64,
+    #[serde(with = "kaspa_utils::serde_u64_string")]
+    pub total_fees: u64,
+    /// Unix time in milliseconds of the snapshot
+    pub timestamp: u64,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct RpcMempoolEntryByAddress {
     pub address: RpcAddress,
@@ -29,6 +47,170 @@ impl RpcMempoolEntryByAddress {
     }
 }
 
+/// Minimum value (in sompi) of a relayed transaction output paying to a script class
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcDustThreshold {
+    pub script_class: RpcScriptClass,
+    #[serde(with = "kaspa_utils::serde_u64_string")]
+    pub min_output_value: u64,
+}
+
+impl RpcDustThreshold {
+    pub fn new(script_class: RpcScriptClass, min_output_value: u64) -> Self {
+        Self { script_class, min_output_value }
+    }
+}
+
+/// Estimated confirmation time range of a mempool transaction
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcConfirmationTimeEstimate {
+    /// Feerate of the package formed by the transaction and its in-mempool ancestors
+    pub package_feerate: f64,
+    /// Number of mempool transactions expected to be mined before the transaction
+    pub transactions_ahead: u64,
+    /// Cumulative mass of the mempool transactions expected to be mined before the transaction
+    pub mass_ahead: u64,
+    /// Average ratio of the block mass used by the recent blocks
+    pub block_utilization: f64,
+    /// Average interval between the recent blocks in milliseconds
+    pub block_interval: f64,
+    pub min_blocks: u64,
+    pub max_blocks: u64,
+    /// Lower bound of the estimated confirmation time in milliseconds
+    pub min_time: u64,
+    /// Upper bound of the estimated confirmation time in milliseconds
+    pub max_time: u64,
+}
+
+/// A mempool acceptance rule checked against a submitted transaction
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAcceptanceCheck {
+    /// Name of the rule (e.g. `minimum-fee`)
+    pub rule: String,
+    /// The rule is checked in the context of the UTXO set and the mempool
+    pub contextual: bool,
+    pub outcome: RpcAcceptanceOutcome,
+    /// The rejection reason if the rule failed
+    pub error: Option<String>,
+    /// The inputs the rule was checked against, if relevant
+    pub detail: Option<RpcAcceptanceDetail>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcAcceptanceDetail {
+    /// Transaction mass and the maximum allowed by the rule
+    Mass { mass: u64, limit: u64 },
+    /// Fee required by the rule and the fee provided by the transaction
+    Fee { required: u64, provided: u64 },
+    /// Outpoints found neither in the UTXO set nor in the mempool
+    MissingOutpoints(Vec<RpcTransactionOutpoint>),
+    /// Number of DAA scores left before the coinbase UTXO spent by input `input_index` reaches maturity
+    #[serde(rename_all = "camelCase")]
+    MaturityShortfall { input_index: u32, shortfall: u64 },
+    /// Value of the dust output `output_index` and the minimum value applied to its script class
+    #[serde(rename_all = "camelCase")]
+    Dust { output_index: u32, value: u64, threshold: u64 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcAcceptanceOutcome {
+    Passed,
+    Failed,
+    /// The rule is disabled by the node configuration
+    Skipped,
+    /// An outcome reported by a newer node which is unknown to this version
+    Unknown,
+}
+
+impl RpcAcceptanceOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RpcAcceptanceOutcome::Passed => "passed",
+            RpcAcceptanceOutcome::Failed => "failed",
+            RpcAcceptanceOutcome::Skipped => "skipped",
+            RpcAcceptanceOutcome::Unknown => "unknown",
+        }
+    }
+}
+
+impl std::str::FromStr for RpcAcceptanceOutcome {
+    type Err = crate::RpcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "passed" => Ok(RpcAcceptanceOutcome::Passed),
+            "failed" => Ok(RpcAcceptanceOutcome::Failed),
+            "skipped" => Ok(RpcAcceptanceOutcome::Skipped),
+            "unknown" => Ok(RpcAcceptanceOutcome::Unknown),
+            _ => Err(crate::RpcError::General(format!("unknown acceptance outcome `{s}`"))),
+        }
+    }
+}
+
+/// Outcome of a transaction submission
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcSubmitTransactionStatus {
+    /// The transaction was added to the mempool (or to its orphan or held pools)
+    #[default]
+    Submitted,
+    /// The transaction was already in the mempool, or included in a block not accepted by the selected chain yet
+    AlreadyKnown,
+    /// The transaction was recently accepted by a selected chain block
+    AlreadyAccepted,
+    /// The transaction was rejected while submitted in verbose mode
+    Rejected,
+    /// A status reported by a newer node which is unknown to this version
+    Unknown,
+}
+
+impl RpcSubmitTransactionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RpcSubmitTransactionStatus::Submitted => "submitted",
+            RpcSubmitTransactionStatus::AlreadyKnown => "alreadyKnown",
+            RpcSubmitTransactionStatus::AlreadyAccepted => "alreadyAccepted",
+            RpcSubmitTransactionStatus::Rejected => "rejected",
+            RpcSubmitTransactionStatus::Unknown => "unknown",
+        }
+    }
+}
+
+impl std::fmt::Display for RpcSubmitTransactionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for RpcSubmitTransactionStatus {
+    type Err = crate::RpcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "submitted" => Ok(RpcSubmitTransactionStatus::Submitted),
+            "alreadyKnown" => Ok(RpcSubmitTransactionStatus::AlreadyKnown),
+            "alreadyAccepted" => Ok(RpcSubmitTransactionStatus::AlreadyAccepted),
+            "rejected" => Ok(RpcSubmitTransactionStatus::Rejected),
+            "unknown" => Ok(RpcSubmitTransactionStatus::Unknown),
+            _ => Err(crate::RpcError::General(format!("unknown submit transaction status `{s}`"))),
+        }
+    }
+}
+
+/// The ordered mempool acceptance rules checked against a rejected transaction, up to and including the failing one
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransactionAcceptanceTrace {
+    /// The error the submission was rejected with
+    pub error: String,
+    pub checks: Vec<RpcAcceptanceCheck>,
+}
+
 cfg_if::cfg_if! {
     if #[cfg(feature = "wasm32-sdk")] {
         use wasm_bindgen::prelude::*;
@@ -41,9 +223,79 @@ cfg_if::cfg_if! {
              * @category Node RPC
              */
             export interface IMempoolEntry {
-                fee : bigint;
+                fee : string;
                 transaction : ITransaction;
                 isOrphan : boolean;
+                isHeld : boolean;
+            }
+
+            /**
+             * Totals of the mempool transactions matching a query,
+             * taken under the same lock of the mempool as the returned entries.
+             * 
+             * @category Node RPC
+             */
+            export interface IMempoolSummary {
+                transactionCount : bigint;
+                totalMass : bigint;
+                totalFees : string;
+                timestamp : bigint;
+            }
+
+            /**
+             * Minimum value of a relayed transaction output paying to a script class.
+             * 
+             * @category Node RPC
+             */
+            export interface IDustThreshold {
+                scriptClass : string;
+                minOutputValue : string;
+            }
+
+            /**
+             * Estimated confirmation time range of a mempool transaction.
+             * Times are expressed in milliseconds.
+             * 
+             * @category Node RPC
+             */
+            export interface IConfirmationTimeEstimate {
+                packageFeerate : number;
+                transactionsAhead : bigint;
+                massAhead : bigint;
+                blockUtilization : number;
+                blockInterval : number;
+                minBlocks : bigint;
+                maxBlocks : bigint;
+                minTime : bigint;
+                maxTime : bigint;
+            }
+
+            /**
+             * A mempool acceptance rule checked against a submitted transaction.
+             * 
+             * @category Node RPC
+             */
+            export interface IAcceptanceCheck {
+                rule : string;
+                contextual : boolean;
+                outcome : "passed" | "failed" | "skipped" | "unknown";
+                error? : string;
+                detail? : 
+                    { mass : { mass : bigint, limit : bigint } } |
+                    { fee : { required : bigint, provided : bigint } } |
+                    { missingOutpoints : ITransactionOutpoint[] } |
+                    { maturityShortfall : { inputIndex : number, shortfall : bigint } } |
+                    { dust : { outputIndex : number, value : bigint, threshold : bigint } };
+            }
+
+            /**
+             * The mempool acceptance rules checked against a rejected transaction.
+             * 
+             * @category Node RPC
+             */
+            export interface ITransactionAcceptanceTrace {
+                error : string;
+                checks : IAcceptanceCheck[];
             }
         "#;
     }
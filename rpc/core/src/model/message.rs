@@ -1,8 +1,15 @@
 use crate::model::*;
 use borsh::{BorshDeserialize, BorshSerialize};
-use kaspa_consensus_core::api::stats::BlockCount;
+use kaspa_consensus_core::{api::stats::BlockCount, backup::BackupManifest, virtual_parents::VirtualParentSelectionTrace};
 use kaspa_core::debug;
-use kaspa_notify::subscription::{context::SubscriptionContext, single::UtxosChangedSubscription, Command};
+use kaspa_notify::{
+    scope::Scope,
+    subscription::{
+        context::SubscriptionContext,
+        single::{TransactionConflictSubscription, UtxosChangedSubscription},
+        Command,
+    },
+};
 use serde::{Deserialize, Serialize};
 use std::{
     fmt::{Display, Formatter},
@@ -64,10 +71,46 @@ impl SubmitBlockReport {
     }
 }
 
+/// Classification of a submitted block, done by the node before processing it
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SubmitBlockClassification {
+    /// The block is built on the current virtual parents
+    Fresh = 1,
+    /// The block is built on a template whose parents are no longer the virtual parents.
+    /// Stale blocks are still valid and processed.
+    Stale = 2,
+    /// The block is already known to the node and was not processed again
+    AlreadyProcessed = 3,
+}
+
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SubmitBlockResponse {
     pub report: SubmitBlockReport,
+    /// Classification of the block, or `None` if the submission was rejected before classifying it
+    #[serde(default)]
+    pub submission_classification: Option<SubmitBlockClassification>,
+    /// Whether the node flagged the block transactions as deviating from the canonical block template order.
+    /// Only checked by nodes started with `--check-template-tx-order`; such blocks are still processed.
+    #[serde(default)]
+    pub non_canonical_transaction_order: bool,
+    /// A description of the rule violated by a rejected block, e.g. the expected and actual amounts of a wrong coinbase payment
+    #[serde(default)]
+    pub rejection_detail: Option<String>,
+}
+impl SubmitBlockResponse {
+    pub fn new(report: SubmitBlockReport, submission_classification: Option<SubmitBlockClassification>) -> Self {
+        Self { report, submission_classification, non_canonical_transaction_order: false, rejection_detail: None }
+    }
+
+    pub fn with_non_canonical_transaction_order(self, non_canonical_transaction_order: bool) -> Self {
+        Self { non_canonical_transaction_order, ..self }
+    }
+
+    pub fn with_rejection_detail(self, rejection_detail: impl ToString) -> Self {
+        Self { rejection_detail: Some(rejection_detail.to_string()), ..self }
+    }
 }
 
 /// GetBlockTemplateRequest requests a current block template.
@@ -98,6 +141,16 @@ pub struct GetBlockTemplateResponse {
     /// That is because when kaspad isn't in sync with the rest of the network there's a high
     /// chance the block will never be accepted, thus the solving effort would have been wasted.
     pub is_synced: bool,
+
+    /// Statistics of the transaction selection of the template
+    #[serde(default)]
+    pub selection_stats: Option<RpcBlockTemplateStats>,
+}
+
+impl GetBlockTemplateResponse {
+    pub fn new(block: RpcBlock, is_synced: bool, selection_stats: Option<RpcBlockTemplateStats>) -> Self {
+        Self { block, is_synced, selection_stats }
+    }
 }
 
 /// GetBlockRequest requests information about a specific block
@@ -109,10 +162,19 @@ pub struct GetBlockRequest {
 
     /// Whether to include transaction data in the response
     pub include_transactions: bool,
+
+    /// Transaction fields omitted from the response (applies if `include_transactions` is set)
+    #[serde(default)]
+    pub transaction_field_mask: RpcTransactionFieldMask,
 }
 impl GetBlockRequest {
     pub fn new(hash: RpcHash, include_transactions: bool) -> Self {
-        Self { hash, include_transactions }
+        Self { hash, include_transactions, transaction_field_mask: RpcTransactionFieldMask::NONE }
+    }
+
+    pub fn with_transaction_field_mask(mut self, transaction_field_mask: RpcTransactionFieldMask) -> Self {
+        self.transaction_field_mask = transaction_field_mask;
+        self
     }
 }
 
@@ -137,6 +199,17 @@ pub struct GetInfoResponse {
     pub is_synced: bool,
     pub has_notify_command: bool,
     pub has_message_id: bool,
+    /// Digest of the consensus params the node is running with (see `Params::digest`).
+    /// `None` if reported by a node predating params digests.
+    pub params_digest: Option<RpcHash>,
+    /// Minimum value of the outputs relayed by the node for each standard script class.
+    /// Empty if reported by a node predating dust threshold advertisement.
+    #[serde(default)]
+    pub dust_thresholds: Vec<RpcDustThreshold>,
+    /// The active, staging and retired consensus entries of the node.
+    /// `None` if reported by a node predating consensus status reporting.
+    #[serde(default)]
+    pub consensus_status: Option<RpcConsensusStatus>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
@@ -155,19 +228,33 @@ impl GetCurrentNetworkResponse {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+/// Filters apply to the known addresses only, banned addresses are always returned in full
+#[derive(Clone, Debug, Default, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct GetPeerAddressesRequest {}
+pub struct GetPeerAddressesRequest {
+    /// Only return addresses to which a connection ever succeeded
+    #[serde(default)]
+    pub only_tried: bool,
+    /// Only return addresses to which a connection never succeeded
+    #[serde(default)]
+    pub only_new: bool,
+    /// Only return addresses whose network group starts with this prefix
+    #[serde(default)]
+    pub group_prefix: Option<String>,
+    /// Maximum number of known addresses to return, the most reliable ones first
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetPeerAddressesResponse {
-    pub known_addresses: Vec<RpcPeerAddress>,
+    pub known_addresses: Vec<RpcKnownPeerAddress>,
     pub banned_addresses: Vec<RpcIpAddress>,
 }
 
 impl GetPeerAddressesResponse {
-    pub fn new(known_addresses: Vec<RpcPeerAddress>, banned_addresses: Vec<RpcIpAddress>) -> Self {
+    pub fn new(known_addresses: Vec<RpcKnownPeerAddress>, banned_addresses: Vec<RpcIpAddress>) -> Self {
         Self { known_addresses, banned_addresses }
     }
 }
@@ -221,11 +308,19 @@ pub struct GetMempoolEntriesRequest {
     pub include_orphan_pool: bool,
     // TODO: replace with `include_transaction_pool`
     pub filter_transaction_pool: bool,
+    /// Read all the entries under a single lock of the mempool, so that they match the summary of the response.
+    /// Otherwise large transaction pools are read by chunks and entries removed meanwhile are omitted.
+    #[serde(default)]
+    pub consistent_snapshot: bool,
 }
 
 impl GetMempoolEntriesRequest {
     pub fn new(include_orphan_pool: bool, filter_transaction_pool: bool) -> Self {
-        Self { include_orphan_pool, filter_transaction_pool }
+        Self { include_orphan_pool, filter_transaction_pool, consistent_snapshot: false }
+    }
+
+    pub fn with_consistent_snapshot(self, consistent_snapshot: bool) -> Self {
+        Self { consistent_snapshot, ..self }
     }
 }
 
@@ -233,11 +328,14 @@ impl GetMempoolEntriesRequest {
 #[serde(rename_all = "camelCase")]
 pub struct GetMempoolEntriesResponse {
     pub mempool_entries: Vec<RpcMempoolEntry>,
+    /// Totals of the queried pools at the time the entries were read
+    #[serde(default)]
+    pub summary: RpcMempoolSummary,
 }
 
 impl GetMempoolEntriesResponse {
-    pub fn new(mempool_entries: Vec<RpcMempoolEntry>) -> Self {
-        Self { mempool_entries }
+    pub fn new(mempool_entries: Vec<RpcMempoolEntry>, summary: RpcMempoolSummary) -> Self {
+        Self { mempool_entries, summary }
     }
 }
 
@@ -279,11 +377,42 @@ pub struct AddPeerResponse {}
 pub struct SubmitTransactionRequest {
     pub transaction: RpcTransaction,
     pub allow_orphan: bool,
+    /// Allow the node to hold the transaction until its lock time is reached instead of rejecting it
+    /// (requires the node to run with `--hold-time-locked-txs`)
+    #[serde(default)]
+    pub allow_not_yet_valid: bool,
+    /// On rejection, respond with the trace of the mempool acceptance rules checked against the
+    /// transaction instead of an error (rate limited per connection)
+    #[serde(default)]
+    pub verbose: bool,
+    /// Client-provided key echoed back in the response, correlating retried submissions
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 impl SubmitTransactionRequest {
+    /// Max number of verbose requests processed for a single connection within a sliding [`Self::REJECTION_TRACE_WINDOW`].
+    /// Verbose requests above this limit are processed as non-verbose ones.
+    pub const MAX_REJECTION_TRACES_PER_WINDOW: u64 = 10;
+    pub const REJECTION_TRACE_WINDOW: std::time::Duration = std::time::Duration::from_secs(60);
+
     pub fn new(transaction: RpcTransaction, allow_orphan: bool) -> Self {
-        Self { transaction, allow_orphan }
+        Self { transaction, allow_orphan, allow_not_yet_valid: false, verbose: false, idempotency_key: None }
+    }
+
+    pub fn with_allow_not_yet_valid(mut self, allow_not_yet_valid: bool) -> Self {
+        self.allow_not_yet_valid = allow_not_yet_valid;
+        self
+    }
+
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    pub fn with_idempotency_key(mut self, idempotency_key: Option<String>) -> Self {
+        self.idempotency_key = idempotency_key;
+        self
     }
 }
 
@@ -291,11 +420,55 @@ impl SubmitTransactionRequest {
 #[serde(rename_all = "camelCase")]
 pub struct SubmitTransactionResponse {
     pub transaction_id: RpcTransactionId,
+    /// Set if the transaction was rejected while submitted in verbose mode
+    #[serde(default)]
+    pub rejection_trace: Option<RpcTransactionAcceptanceTrace>,
+    /// Resubmissions of known transactions succeed with an `AlreadyKnown` or `AlreadyAccepted` status,
+    /// making retries safe
+    #[serde(default)]
+    pub status: RpcSubmitTransactionStatus,
+    /// The selected chain block which accepted the transaction, set if the status is `AlreadyAccepted`
+    #[serde(default)]
+    pub accepting_block_hash: Option<RpcHash>,
+    /// The idempotency key of the request
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 impl SubmitTransactionResponse {
     pub fn new(transaction_id: RpcTransactionId) -> Self {
-        Self { transaction_id }
+        Self {
+            transaction_id,
+            rejection_trace: None,
+            status: RpcSubmitTransactionStatus::Submitted,
+            accepting_block_hash: None,
+            idempotency_key: None,
+        }
+    }
+
+    pub fn rejected(transaction_id: RpcTransactionId, rejection_trace: RpcTransactionAcceptanceTrace) -> Self {
+        Self { rejection_trace: Some(rejection_trace), status: RpcSubmitTransactionStatus::Rejected, ..Self::new(transaction_id) }
+    }
+
+    pub fn already_known(transaction_id: RpcTransactionId) -> Self {
+        Self { status: RpcSubmitTransactionStatus::AlreadyKnown, ..Self::new(transaction_id) }
+    }
+
+    pub fn already_accepted(transaction_id: RpcTransactionId, accepting_block_hash: RpcHash) -> Self {
+        Self {
+            status: RpcSubmitTransactionStatus::AlreadyAccepted,
+            accepting_block_hash: Some(accepting_block_hash),
+            ..Self::new(transaction_id)
+        }
+    }
+
+    pub fn with_idempotency_key(mut self, idempotency_key: Option<String>) -> Self {
+        self.idempotency_key = idempotency_key;
+        self
+    }
+
+    pub fn is_rejected(&self) -> bool {
+        self.rejection_trace.is_some()
     }
 }
 
@@ -314,6 +487,7 @@ impl GetSubnetworkRequest {
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetSubnetworkResponse {
+    #[serde(with = "kaspa_utils::serde_u64_string")]
     pub gas_limit: u64,
 }
 
@@ -360,11 +534,19 @@ pub struct GetBlocksRequest {
     pub low_hash: Option<RpcHash>,
     pub include_blocks: bool,
     pub include_transactions: bool,
+    /// Transaction fields omitted from the response (applies if `include_transactions` is set)
+    #[serde(default)]
+    pub transaction_field_mask: RpcTransactionFieldMask,
 }
 
 impl GetBlocksRequest {
     pub fn new(low_hash: Option<RpcHash>, include_blocks: bool, include_transactions: bool) -> Self {
-        Self { low_hash, include_blocks, include_transactions }
+        Self { low_hash, include_blocks, include_transactions, transaction_field_mask: RpcTransactionFieldMask::NONE }
+    }
+
+    pub fn with_transaction_field_mask(mut self, transaction_field_mask: RpcTransactionFieldMask) -> Self {
+        self.transaction_field_mask = transaction_field_mask;
+        self
     }
 }
 
@@ -499,6 +681,7 @@ impl GetBalanceByAddressRequest {
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetBalanceByAddressResponse {
+    #[serde(with = "kaspa_utils::serde_u64_string")]
     pub balance: u64,
 }
 
@@ -548,6 +731,15 @@ impl GetSinkBlueScoreResponse {
     }
 }
 
+/// GetVirtualInfoRequest requests the DAA score, sink, sink blue score, parents count and past median time
+/// of the virtual block at once. These are read from the cached virtual state, making the call cheap enough
+/// for frequent polling.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetVirtualInfoRequest {}
+
+pub type GetVirtualInfoResponse = RpcVirtualInfo;
+
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetUtxosByAddressesRequest {
@@ -620,6 +812,7 @@ impl EstimateNetworkHashesPerSecondRequest {
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EstimateNetworkHashesPerSecondResponse {
+    #[serde(with = "kaspa_utils::serde_u64_string")]
     pub network_hashes_per_second: u64,
 }
 
@@ -663,13 +856,21 @@ pub struct GetCoinSupplyRequest {}
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetCoinSupplyResponse {
+    #[serde(with = "kaspa_utils::serde_u64_string")]
     pub max_sompi: u64,
+    #[serde(with = "kaspa_utils::serde_u64_string")]
     pub circulating_sompi: u64,
+    /// Circulating supply estimated from the emission schedule at the current virtual DAA score
+    #[serde(with = "kaspa_utils::serde_u64_string")]
+    pub estimated_circulating_sompi: u64,
+    /// Subsidy paid to a block mined at the current virtual DAA score
+    #[serde(with = "kaspa_utils::serde_u64_string")]
+    pub block_reward_sompi: u64,
 }
 
 impl GetCoinSupplyResponse {
-    pub fn new(max_sompi: u64, circulating_sompi: u64) -> Self {
-        Self { max_sompi, circulating_sompi }
+    pub fn new(max_sompi: u64, circulating_sompi: u64, estimated_circulating_sompi: u64, block_reward_sompi: u64) -> Self {
+        Self { max_sompi, circulating_sompi, estimated_circulating_sompi, block_reward_sompi }
     }
 }
 
@@ -690,6 +891,8 @@ pub struct GetMetricsRequest {
     pub connection_metrics: bool,
     pub bandwidth_metrics: bool,
     pub consensus_metrics: bool,
+    #[serde(default)]
+    pub script_metrics: bool,
 }
 
 #[derive(Default, Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
@@ -752,6 +955,54 @@ pub struct ConsensusMetrics {
     pub network_past_median_time: u64,
     pub network_virtual_parent_hashes_count: u32,
     pub network_virtual_daa_score: u64,
+
+    /// Number of recent relay blocks sampled for the propagation delay percentiles below
+    pub node_block_propagation_sample_count: u64,
+    /// Percentiles of the delay (in milliseconds) between the first announcement of a relay block and its validation
+    pub node_block_propagation_p50_millis: u64,
+    pub node_block_propagation_p90_millis: u64,
+    pub node_block_propagation_p99_millis: u64,
+
+    /// Maximum number of blocks validated concurrently, see [`SetProcessingParallelismRequest`]
+    pub node_processing_parallelism_limit: u32,
+    /// Number of block validations running, the utilization being this count relative to the limit
+    pub node_processing_running_count: u32,
+    /// Number of block validations waiting for the limit to allow them to run
+    pub node_processing_queued_count: u32,
+}
+
+/// Number of transactions rejected by script verification for a given reason
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcScriptRejectionCount {
+    /// Name of the script engine error variant, e.g. `EvalFalse` or `NullFail`
+    pub reason: String,
+    pub count: u64,
+}
+
+/// Number of executions of an opcode in the sampled transactions
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcOpcodeCount {
+    pub opcode: u8,
+    pub count: u64,
+}
+
+/// Node-wide statistics of transaction script verification since the node started. The counts are aggregated
+/// per validation thread and merged periodically, so the most recent verifications might not be included yet.
+#[derive(Default, Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptMetrics {
+    /// Number of transactions whose scripts were verified
+    pub checked_transactions_count: u64,
+    /// Number of rejected transactions per reason, omitting reasons never encountered
+    pub rejection_counts: Vec<RpcScriptRejectionCount>,
+    /// Executed opcodes are counted for 1 in this many verified transactions, 0 meaning they are not counted
+    pub opcode_sample_rate: u64,
+    /// Number of verified transactions whose executed opcodes were counted
+    pub sampled_transactions_count: u64,
+    /// Number of executions per opcode in the sampled transactions, omitting opcodes never executed
+    pub opcode_counts: Vec<RpcOpcodeCount>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
@@ -762,6 +1013,7 @@ pub struct GetMetricsResponse {
     pub connection_metrics: Option<ConnectionMetrics>,
     pub bandwidth_metrics: Option<BandwidthMetrics>,
     pub consensus_metrics: Option<ConsensusMetrics>,
+    pub script_metrics: Option<ScriptMetrics>,
 }
 
 impl GetMetricsResponse {
@@ -771,8 +1023,9 @@ impl GetMetricsResponse {
         connection_metrics: Option<ConnectionMetrics>,
         bandwidth_metrics: Option<BandwidthMetrics>,
         consensus_metrics: Option<ConsensusMetrics>,
+        script_metrics: Option<ScriptMetrics>,
     ) -> Self {
-        Self { process_metrics, connection_metrics, bandwidth_metrics, consensus_metrics, server_time }
+        Self { process_metrics, connection_metrics, bandwidth_metrics, consensus_metrics, script_metrics, server_time }
     }
 }
 
@@ -825,6 +1078,439 @@ impl GetDaaScoreTimestampEstimateResponse {
     }
 }
 
+/// GetChainBlockByDaaScoreRequest requests the selected chain block whose DAA score is nearest
+/// to `daa_score`. Scores above the sink resolve to the sink, while scores below the pruning
+/// point are rejected.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetChainBlockByDaaScoreRequest {
+    pub daa_score: u64,
+}
+
+impl GetChainBlockByDaaScoreRequest {
+    pub fn new(daa_score: u64) -> Self {
+        Self { daa_score }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetChainBlockByDaaScoreResponse {
+    pub hash: RpcHash,
+    pub daa_score: u64,
+    pub timestamp: u64,
+}
+
+impl GetChainBlockByDaaScoreResponse {
+    pub fn new(hash: RpcHash, daa_score: u64, timestamp: u64) -> Self {
+        Self { hash, daa_score, timestamp }
+    }
+}
+
+/// SetLogFiltersRequest adjusts the log filters of the node at runtime.
+///
+/// `filters` follows the syntax of the `--loglevel` argument, e.g. `kaspa_p2p_flows=debug`,
+/// and is applied on top of the current filters. An empty value leaves them unchanged.
+/// This call is only available when the node runs with unsafe RPC enabled.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetLogFiltersRequest {
+    pub filters: String,
+}
+
+impl SetLogFiltersRequest {
+    pub fn new(filters: String) -> Self {
+        Self { filters }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetLogFiltersResponse {
+    /// The log filters in effect after the update
+    pub filters: String,
+}
+
+impl SetLogFiltersResponse {
+    pub fn new(filters: String) -> Self {
+        Self { filters }
+    }
+}
+
+/// GetOutpointSpendStatusRequest requests the spend status of an outpoint as seen by the virtual UTXO set,
+/// the mempool and the acceptance data of the `search_depth` most recent selected chain blocks.
+///
+/// When `search_depth` is not provided, the node applies a default depth. The depth is further capped
+/// by the node and the search never goes below the pruning point.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetOutpointSpendStatusRequest {
+    pub outpoint: RpcTransactionOutpoint,
+    pub search_depth: Option<u64>,
+}
+
+impl GetOutpointSpendStatusRequest {
+    pub fn new(outpoint: RpcTransactionOutpoint, search_depth: Option<u64>) -> Self {
+        Self { outpoint, search_depth }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetOutpointSpendStatusResponse {
+    /// The UTXO entry of the outpoint if it is still unspent by the virtual UTXO set
+    pub utxo_entry: Option<RpcUtxoEntry>,
+    /// The mempool transaction spending the outpoint, if any
+    pub mempool_spender: Option<RpcTransactionId>,
+    /// The transaction spending the outpoint as accepted by the selected chain, if found within the search depth
+    pub accepted_spender: Option<RpcOutpointSpender>,
+    /// Other transactions spending the outpoint which were merged by the selected chain but rejected as double spends
+    pub conflicting_spenders: Vec<RpcOutpointSpender>,
+}
+
+impl GetOutpointSpendStatusResponse {
+    pub fn new(
+        utxo_entry: Option<RpcUtxoEntry>,
+        mempool_spender: Option<RpcTransactionId>,
+        accepted_spender: Option<RpcOutpointSpender>,
+        conflicting_spenders: Vec<RpcOutpointSpender>,
+    ) -> Self {
+        Self { utxo_entry, mempool_spender, accepted_spender, conflicting_spenders }
+    }
+}
+
+/// EstimateTransactionConfirmationTimeRequest requests an estimated confirmation time range of a transaction
+/// of the mempool, based on its position in package-feerate order, the recent block mass utilization and
+/// the observed block rate.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EstimateTransactionConfirmationTimeRequest {
+    pub transaction_id: RpcTransactionId,
+}
+
+impl EstimateTransactionConfirmationTimeRequest {
+    pub fn new(transaction_id: RpcTransactionId) -> Self {
+        Self { transaction_id }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EstimateTransactionConfirmationTimeResponse {
+    pub estimate: RpcConfirmationTimeEstimate,
+}
+
+impl EstimateTransactionConfirmationTimeResponse {
+    pub fn new(estimate: RpcConfirmationTimeEstimate) -> Self {
+        Self { estimate }
+    }
+}
+
+/// GetAcceptanceProofRequest requests a merkle proof of the acceptance of a transaction by a selected chain block,
+/// authenticating the transaction id against the `accepted_id_merkle_root` of the block header.
+///
+/// The proof is built from the acceptance data of the block, hence is unavailable for blocks below the pruning point.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetAcceptanceProofRequest {
+    pub accepting_block_hash: RpcHash,
+    pub transaction_id: RpcTransactionId,
+}
+
+impl GetAcceptanceProofRequest {
+    pub fn new(accepting_block_hash: RpcHash, transaction_id: RpcTransactionId) -> Self {
+        Self { accepting_block_hash, transaction_id }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetAcceptanceProofResponse {
+    /// The accepted id merkle root of the accepting block header the proof authenticates against
+    pub accepted_id_merkle_root: RpcHash,
+    /// The index of the transaction id within the sorted accepted transaction ids of the accepting block
+    pub leaf_index: u32,
+    /// The sibling hashes from the leaf level up to the root
+    pub path: Vec<RpcHash>,
+}
+
+impl GetAcceptanceProofResponse {
+    pub fn new(accepted_id_merkle_root: RpcHash, leaf_index: u32, path: Vec<RpcHash>) -> Self {
+        Self { accepted_id_merkle_root, leaf_index, path }
+    }
+}
+
+/// CreateBackupRequest requests the node to write a consistent point-in-time copy of its consensus databases,
+/// along with a manifest describing the backed up state, without stopping block processing.
+///
+/// The backup directory mirrors the layout of the node data directory. Restricted to nodes running with `--unsaferpc`.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBackupRequest {
+    /// Absolute path, on the node host, of the directory to create the backup in. The directory must not exist.
+    pub output_directory: String,
+}
+
+impl CreateBackupRequest {
+    pub fn new(output_directory: String) -> Self {
+        Self { output_directory }
+    }
+}
+
+/// The manifest of the created backup
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBackupResponse {
+    pub node_version: String,
+    pub network_id: RpcNetworkId,
+    pub params_digest: RpcHash,
+    pub pruning_point: RpcHash,
+    pub sink: RpcHash,
+    /// Unix time (in milliseconds) at which the backup was taken
+    pub timestamp: u64,
+}
+
+impl From<BackupManifest> for CreateBackupResponse {
+    fn from(manifest: BackupManifest) -> Self {
+        Self {
+            node_version: manifest.node_version,
+            network_id: manifest.network_id,
+            params_digest: manifest.params_digest,
+            pruning_point: manifest.pruning_point,
+            sink: manifest.sink,
+            timestamp: manifest.timestamp,
+        }
+    }
+}
+
+/// GetVirtualParentSelectionTraceRequest requests a trace of the virtual parent selection over the current tips,
+/// explaining why each tip which was not selected as a virtual parent was excluded.
+///
+/// Restricted to nodes running with `--unsaferpc`.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetVirtualParentSelectionTraceRequest {}
+
+pub type GetVirtualParentSelectionTraceResponse = VirtualParentSelectionTrace;
+
+/// GetSystemInfoRequest requests information about the node host, including the results of the startup preflight checks
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSystemInfoRequest {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSystemInfoResponse {
+    pub version: String,
+    /// The soft file descriptor limit of the node process
+    pub fd_limit: u32,
+    pub preflight_checks: Vec<RpcPreflightCheck>,
+}
+
+/// RemovePeerRequest removes a peer added by AddPeer, whether permanently or not, and disconnects from it
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovePeerRequest {
+    pub peer_address: RpcContextualPeerAddress,
+}
+
+impl RemovePeerRequest {
+    pub fn new(peer_address: RpcContextualPeerAddress) -> Self {
+        Self { peer_address }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovePeerResponse {}
+
+/// SetProcessingParallelismRequest sets the maximum number of blocks validated concurrently by the header
+/// and body processors. Raising the limit starts waiting validations right away, while lowering it takes
+/// effect as running validations complete. The limit must be positive.
+/// This call is only available when the node runs with unsafe RPC enabled.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetProcessingParallelismRequest {
+    pub limit: u32,
+}
+
+impl SetProcessingParallelismRequest {
+    pub fn new(limit: u32) -> Self {
+        Self { limit }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetProcessingParallelismResponse {
+    /// The limit in effect before the update
+    pub previous_limit: u32,
+}
+
+impl SetProcessingParallelismResponse {
+    pub fn new(previous_limit: u32) -> Self {
+        Self { previous_limit }
+    }
+}
+
+/// GetAddressActivityRequest requests whether the given addresses were ever used, that is whether they
+/// ever received funds, even if these were spent since. Activity is tracked by the UTXO index from the
+/// time it was first synced.
+/// This call is only available when the node runs with `--utxoindex`.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetAddressActivityRequest {
+    pub addresses: Vec<RpcAddress>,
+}
+
+impl GetAddressActivityRequest {
+    pub fn new(addresses: Vec<RpcAddress>) -> Self {
+        Self { addresses }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetAddressActivityResponse {
+    /// One entry per requested address, in request order
+    pub entries: Vec<RpcAddressActivityEntry>,
+}
+
+impl GetAddressActivityResponse {
+    pub fn new(entries: Vec<RpcAddressActivityEntry>) -> Self {
+        Self { entries }
+    }
+}
+
+/// GetMissedNotificationsRequest requests the notifications of a journaled scope emitted since a sequence
+/// number, letting a client which detected a gap in the sequence of its notifications backfill it.
+///
+/// Only the VirtualChainChanged and UtxosChanged scopes are journaled. The notifications are filtered
+/// by `scope` the same way they would be for a listener subscribed to it.
+///
+/// This call is only available when the node runs with `--notification-journal-size`.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetMissedNotificationsRequest {
+    pub scope: Scope,
+    /// The sequence of the first notification requested, i.e. the last sequence received plus one
+    pub from_sequence: u64,
+}
+
+impl GetMissedNotificationsRequest {
+    pub fn new(scope: Scope, from_sequence: u64) -> Self {
+        Self { scope, from_sequence }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetMissedNotificationsResponse {
+    /// The matching notifications in sequence order, filled for a VirtualChainChanged scope
+    pub virtual_chain_changed: Vec<VirtualChainChangedNotification>,
+    /// The matching notifications in sequence order, filled for an UtxosChanged scope
+    pub utxos_changed: Vec<UtxosChangedNotification>,
+    /// The sequence of the last notification emitted in the scope, whether matching the request scope or not.
+    /// The client is up to date up to this sequence.
+    pub last_sequence: u64,
+}
+
+impl GetMissedNotificationsResponse {
+    pub fn new(
+        virtual_chain_changed: Vec<VirtualChainChangedNotification>,
+        utxos_changed: Vec<UtxosChangedNotification>,
+        last_sequence: u64,
+    ) -> Self {
+        Self { virtual_chain_changed, utxos_changed, last_sequence }
+    }
+}
+
+/// GetUtxoReturnAddressRequest requests the UTXO entry spent by an input of a transaction accepted by a selected
+/// chain block, along with the address it was locked to, e.g. for refunding the sender of a payment.
+///
+/// This call is only available when the node runs with `--store-spent-utxos`, and only for chain blocks processed
+/// since. The spent entries share the lifecycle of the acceptance data of the block, so they are unavailable for
+/// blocks below the pruning point and for blocks no longer on the selected chain.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUtxoReturnAddressRequest {
+    pub accepting_block_hash: RpcHash,
+    pub transaction_id: RpcTransactionId,
+    pub input_index: u32,
+}
+
+impl GetUtxoReturnAddressRequest {
+    pub fn new(accepting_block_hash: RpcHash, transaction_id: RpcTransactionId, input_index: u32) -> Self {
+        Self { accepting_block_hash, transaction_id, input_index }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUtxoReturnAddressResponse {
+    /// The address the spent UTXO was locked to, `None` if its script public key is non-standard
+    pub address: Option<RpcAddress>,
+    /// The spent UTXO entry
+    pub utxo_entry: RpcUtxoEntry,
+}
+
+impl GetUtxoReturnAddressResponse {
+    pub fn new(address: Option<RpcAddress>, utxo_entry: RpcUtxoEntry) -> Self {
+        Self { address, utxo_entry }
+    }
+}
+
+/// ValidateBlockTemplateRequest requests to validate a block template against the current virtual state without
+/// submitting it, e.g. after a pool reordered or injected transactions into a template fetched with getBlockTemplate.
+///
+/// The block is validated as a submission of it would be, except for its proof of work which is not checked, so
+/// that a template can be validated before being mined. It is expected to build on the current virtual parents.
+///
+/// See: [`GetBlockTemplateRequest`]
+#[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidateBlockTemplateRequest {
+    pub block: RpcBlock,
+}
+
+impl ValidateBlockTemplateRequest {
+    pub fn new(block: RpcBlock) -> Self {
+        Self { block }
+    }
+}
+
+/// A transaction of a block template found invalid in the UTXO context of the current virtual state
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcInvalidTemplateTransaction {
+    pub transaction_id: RpcTransactionId,
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidateBlockTemplateResponse {
+    pub is_valid: bool,
+    /// Mass of a valid block
+    pub mass: u64,
+    /// Total fees of the non-coinbase transactions of a valid block
+    pub total_fees: u64,
+    /// A description of the rule violated by an invalid block, as reported as rejection detail by a submission of it
+    pub rejection_detail: Option<String>,
+    /// The invalid transactions of an invalid block, if invalid because of some of its non-coinbase transactions
+    pub invalid_transactions: Vec<RpcInvalidTemplateTransaction>,
+}
+
+impl ValidateBlockTemplateResponse {
+    pub fn valid(mass: u64, total_fees: u64) -> Self {
+        Self { is_valid: true, mass, total_fees, rejection_detail: None, invalid_transactions: vec![] }
+    }
+
+    pub fn invalid(rejection_detail: impl ToString, invalid_transactions: Vec<RpcInvalidTemplateTransaction>) -> Self {
+        Self { is_valid: false, mass: 0, total_fees: 0, rejection_detail: Some(rejection_detail.to_string()), invalid_transactions }
+    }
+}
+
 // ----------------------------------------------------------------------------
 // Subscriptions & notifications
 // ----------------------------------------------------------------------------
@@ -894,6 +1580,10 @@ pub struct VirtualChainChangedNotification {
     pub removed_chain_block_hashes: Arc<Vec<RpcHash>>,
     pub added_chain_block_hashes: Arc<Vec<RpcHash>>,
     pub accepted_transaction_ids: Arc<Vec<RpcAcceptedTransactionIds>>,
+    /// Position of the notification in the sequence of the scope, set when the node journals its notifications.
+    /// A jump in the sequence reveals missed notifications, which can be backfilled with GetMissedNotifications.
+    #[serde(default)]
+    pub sequence: Option<u64>,
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -953,7 +1643,9 @@ pub struct FinalityConflictResolvedNotification {
 // for the given addresses. Depending on the provided `command`, notifications will
 // start or stop for the provided `addresses`.
 //
-// If `addresses` is empty, the notifications will start or stop for all addresses.
+// If `addresses` is empty, the notifications will start or stop for all addresses. Starting
+// such a wildcard subscription is only available when this kaspad was started with `--unsaferpc`
+// since it yields the UTXO changes of every block. Stopping it cancels the whole subscription.
 //
 // This call is only available when this kaspad was started with `--utxoindex`
 //
@@ -983,6 +1675,13 @@ pub struct NotifyUtxosChangedResponse {}
 pub struct UtxosChangedNotification {
     pub added: Arc<Vec<RpcUtxosByAddressesEntry>>,
     pub removed: Arc<Vec<RpcUtxosByAddressesEntry>>,
+    /// Position of the notification in the sequence of the scope, set when the node journals its notifications.
+    ///
+    /// The sequence is shared by all the addresses, so a listener subscribed to some addresses only skips the
+    /// sequence numbers of the notifications not involving them. A jump then only tells some notifications may
+    /// have been missed, which GetMissedNotifications resolves.
+    #[serde(default)]
+    pub sequence: Option<u64>,
 }
 
 impl UtxosChangedNotification {
@@ -1000,7 +1699,7 @@ impl UtxosChangedNotification {
                 None
             } else {
                 debug!("CRPC, Creating UtxosChanged notifications with {} added and {} removed utxos", added.len(), removed.len());
-                Some(Self { added: Arc::new(added), removed: Arc::new(removed) })
+                Some(Self { added: Arc::new(added), removed: Arc::new(removed), sequence: self.sequence })
             }
         }
     }
@@ -1079,6 +1778,10 @@ pub struct NotifyVirtualDaaScoreChangedResponse {}
 #[serde(rename_all = "camelCase")]
 pub struct VirtualDaaScoreChangedNotification {
     pub virtual_daa_score: u64,
+    /// The virtual state tracked along with the DAA score, always set by this node but missing
+    /// from the notifications of older nodes
+    #[serde(default)]
+    pub virtual_info: Option<RpcVirtualInfo>,
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -1133,6 +1836,70 @@ pub struct NotifyNewBlockTemplateResponse {}
 #[serde(rename_all = "camelCase")]
 pub struct NewBlockTemplateNotification {}
 
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TransactionConflictNotification
+
+/// NotifyTransactionConflictRequest registers this connection for transactionConflict notifications
+/// for the given addresses. Depending on the provided `command`, notifications will start or stop
+/// for the provided `addresses`.
+///
+/// If `addresses` is empty, the notifications will start or stop for all addresses.
+///
+/// See: TransactionConflictNotification
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifyTransactionConflictRequest {
+    pub addresses: Vec<RpcAddress>,
+    pub command: Command,
+}
+
+impl NotifyTransactionConflictRequest {
+    pub fn new(addresses: Vec<RpcAddress>, command: Command) -> Self {
+        Self { addresses, command }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifyTransactionConflictResponse {}
+
+/// TransactionConflictNotification is sent whenever the mempool detects two transactions spending
+/// some same outpoints, either when rejecting a transaction double spending a mempool transaction or
+/// when a block transaction double spends a mempool or recently rejected transaction.
+///
+/// See: NotifyTransactionConflictRequest
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionConflictNotification {
+    /// The transaction losing the conflict
+    pub transaction_id: RpcTransactionId,
+    /// The transaction winning the conflict, either in the mempool or in a block
+    pub conflicting_transaction_id: RpcTransactionId,
+    /// The outpoints spent by both transactions
+    pub outpoints: Vec<RpcTransactionOutpoint>,
+    /// Whether the conflicting transaction is in a block rather than in the mempool
+    pub is_conflicting_in_block: bool,
+    /// The addresses paid to by both transactions along with the ones of the spent UTXOs known to the mempool
+    pub addresses: Vec<RpcAddress>,
+}
+
+impl TransactionConflictNotification {
+    pub(crate) fn apply_transaction_conflict_subscription(
+        &self,
+        subscription: &TransactionConflictSubscription,
+        context: &SubscriptionContext,
+    ) -> Option<Self> {
+        if subscription.to_all() {
+            return Some(self.clone());
+        }
+        let subscription_data = subscription.data();
+        match self.addresses.iter().any(|address| subscription_data.contains_address(address, context)) {
+            true => Some(self.clone()),
+            false => None,
+        }
+    }
+}
+
 ///
 ///  wRPC response for RpcApiOps::Subscribe request
 ///
@@ -1,6 +1,7 @@
 pub mod address;
 pub mod block;
 pub mod blue_work;
+pub mod consensus;
 pub mod hash;
 pub mod header;
 pub mod hex_cnv;
@@ -10,11 +11,13 @@ pub mod network;
 pub mod peer;
 pub mod script_class;
 pub mod subnets;
+pub mod system;
 pub mod tx;
 
 pub use address::*;
 pub use block::*;
 pub use blue_work::*;
+pub use consensus::*;
 pub use hash::*;
 pub use header::*;
 pub use hex_cnv::*;
@@ -23,4 +26,5 @@ pub use message::*;
 pub use network::*;
 pub use peer::*;
 pub use subnets::*;
+pub use system::*;
 pub use tx::*;
@@ -20,4 +20,103 @@ pub struct RpcPeerInfo {
     pub advertised_protocol_version: u32,
     pub time_connected: u64, // NOTE: i64 in gRPC protowire
     pub is_ibd_peer: bool,
+
+    /// Bytes served to this peer by request-serving flows within the current accounting window
+    pub served_bytes: u64,
+    /// Cost units (a CPU proxy) served to this peer by request-serving flows within the current accounting window
+    pub served_cost: u64,
+
+    /// Whether the peer was permanently added by the AddPeer call, and is thus connected again after a restart
+    #[serde(default)]
+    pub is_manual: bool,
+}
+
+/// Origin of a known peer address
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcPeerAddressSource {
+    /// The address was restored from the node database, which does not record its origin
+    Unknown,
+    DnsSeed,
+    PeerGossip,
+    /// The address of an outbound peer connected on request
+    Manual,
+}
+
+impl RpcPeerAddressSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RpcPeerAddressSource::Unknown => "unknown",
+            RpcPeerAddressSource::DnsSeed => "dnsSeed",
+            RpcPeerAddressSource::PeerGossip => "peerGossip",
+            RpcPeerAddressSource::Manual => "manual",
+        }
+    }
+}
+
+impl std::str::FromStr for RpcPeerAddressSource {
+    type Err = crate::RpcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "unknown" => Ok(RpcPeerAddressSource::Unknown),
+            "dnsSeed" => Ok(RpcPeerAddressSource::DnsSeed),
+            "peerGossip" => Ok(RpcPeerAddressSource::PeerGossip),
+            "manual" => Ok(RpcPeerAddressSource::Manual),
+            _ => Err(crate::RpcError::General(format!("unknown peer address source `{s}`"))),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcConnectionOutcome {
+    Success,
+    Failure,
+    /// An outcome reported by a newer node which is unknown to this version
+    Unknown,
+}
+
+impl RpcConnectionOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RpcConnectionOutcome::Success => "success",
+            RpcConnectionOutcome::Failure => "failure",
+            RpcConnectionOutcome::Unknown => "unknown",
+        }
+    }
+}
+
+impl std::str::FromStr for RpcConnectionOutcome {
+    type Err = crate::RpcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "success" => Ok(RpcConnectionOutcome::Success),
+            "failure" => Ok(RpcConnectionOutcome::Failure),
+            "unknown" => Ok(RpcConnectionOutcome::Unknown),
+            _ => Err(crate::RpcError::General(format!("unknown connection outcome `{s}`"))),
+        }
+    }
+}
+
+/// A known peer address along with the quality data the address manager holds about it.
+///
+/// Only the connection failure counter is persisted by the node, so the remaining data of
+/// addresses restored from its database is unknown until they are seen or dialed again.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcKnownPeerAddress {
+    pub address: RpcPeerAddress,
+    pub source: RpcPeerAddressSource,
+    /// The `/16` prefix of IPv4 addresses (`a.b`) or the `/64` prefix of IPv6 ones (`a:b:c:d`)
+    pub network_group: String,
+    pub connection_failed_count: u64,
+    /// Whether a connection to the address ever succeeded, as opposed to a new address
+    pub is_tried: bool,
+    /// Unix time (in milliseconds) at which the address was last added or advertised
+    pub last_seen: Option<u64>,
+    /// Unix time (in milliseconds) of the last connection attempt
+    pub last_attempt_time: Option<u64>,
+    pub last_attempt_outcome: Option<RpcConnectionOutcome>,
 }
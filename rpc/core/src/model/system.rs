@@ -0,0 +1,95 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+
+/// Outcome of a node startup preflight check
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcPreflightCheckStatus {
+    Passed,
+    Warning,
+    /// The check failed but was overridden through its command line flag
+    Overridden,
+    /// The check failed, the node refusing to run
+    Failed,
+    /// The check is waiting for its inputs, e.g. for enough peers to estimate the clock offset
+    Pending,
+    /// A status reported by a newer node which is unknown to this version
+    Unknown,
+}
+
+impl RpcPreflightCheckStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RpcPreflightCheckStatus::Passed => "passed",
+            RpcPreflightCheckStatus::Warning => "warning",
+            RpcPreflightCheckStatus::Overridden => "overridden",
+            RpcPreflightCheckStatus::Failed => "failed",
+            RpcPreflightCheckStatus::Pending => "pending",
+            RpcPreflightCheckStatus::Unknown => "unknown",
+        }
+    }
+}
+
+impl std::fmt::Display for RpcPreflightCheckStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for RpcPreflightCheckStatus {
+    type Err = crate::RpcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "passed" => Ok(RpcPreflightCheckStatus::Passed),
+            "warning" => Ok(RpcPreflightCheckStatus::Warning),
+            "overridden" => Ok(RpcPreflightCheckStatus::Overridden),
+            "failed" => Ok(RpcPreflightCheckStatus::Failed),
+            "pending" => Ok(RpcPreflightCheckStatus::Pending),
+            "unknown" => Ok(RpcPreflightCheckStatus::Unknown),
+            _ => Err(crate::RpcError::General(format!("unknown preflight check status: {s}"))),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPreflightCheck {
+    /// The name of the check, e.g. `disk-space`
+    pub name: String,
+    pub status: RpcPreflightCheckStatus,
+    pub message: String,
+}
+
+impl RpcPreflightCheck {
+    pub fn new(name: &str, status: RpcPreflightCheckStatus, message: String) -> Self {
+        Self { name: name.to_string(), status, message }
+    }
+}
+
+/// The preflight check results of a running node, shared between the checks and the RPC service.
+/// Checks deferred past startup update their entry once concluded.
+#[derive(Clone, Debug, Default)]
+pub struct RpcPreflightReport {
+    checks: Arc<RwLock<Vec<RpcPreflightCheck>>>,
+}
+
+impl RpcPreflightReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records `check`, replacing any previous result of the check with the same name
+    pub fn update(&self, check: RpcPreflightCheck) {
+        let mut checks = self.checks.write().unwrap();
+        match checks.iter_mut().find(|x| x.name == check.name) {
+            Some(entry) => *entry = check,
+            None => checks.push(check),
+        }
+    }
+
+    pub fn checks(&self) -> Vec<RpcPreflightCheck> {
+        self.checks.read().unwrap().clone()
+    }
+}
@@ -24,6 +24,7 @@ pub struct RpcTransactionInput {
     pub previous_outpoint: RpcTransactionOutpoint,
     #[serde(with = "hex::serde")]
     pub signature_script: Vec<u8>,
+    #[serde(with = "kaspa_utils::serde_u64_string")]
     pub sequence: u64,
     pub sig_op_count: u8,
     pub verbose_data: Option<RpcTransactionInputVerboseData>,
@@ -56,6 +57,7 @@ pub struct RpcTransactionInputVerboseData {}
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcTransactionOutput {
+    #[serde(with = "kaspa_utils::serde_u64_string")]
     pub value: u64,
     pub script_public_key: RpcScriptPublicKey,
     pub verbose_data: Option<RpcTransactionOutputVerboseData>,
@@ -88,8 +90,10 @@ pub struct RpcTransaction {
     pub version: u16,
     pub inputs: Vec<RpcTransactionInput>,
     pub outputs: Vec<RpcTransactionOutput>,
+    #[serde(with = "kaspa_utils::serde_u64_string")]
     pub lock_time: u64,
     pub subnetwork_id: RpcSubnetworkId,
+    #[serde(with = "kaspa_utils::serde_u64_string")]
     pub gas: u64,
     #[serde(with = "hex::serde")]
     pub payload: Vec<u8>,
@@ -108,6 +112,84 @@ pub struct RpcTransactionVerboseData {
     pub block_time: u64,
 }
 
+/// Set of transaction fields omitted when blocks are returned along with their transactions
+/// (see [`GetBlockRequest`](crate::GetBlockRequest) and [`GetBlocksRequest`](crate::GetBlocksRequest)).
+///
+/// Flags are combined using `|`. Unknown flags are ignored.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(transparent)]
+pub struct RpcTransactionFieldMask(u32);
+
+impl RpcTransactionFieldMask {
+    /// Transactions are returned in full
+    pub const NONE: Self = Self(0);
+    /// Omit the signature scripts of the transaction inputs
+    pub const OMIT_SIGNATURE_SCRIPTS: Self = Self(1 << 0);
+    /// Omit the transaction payloads
+    pub const OMIT_PAYLOADS: Self = Self(1 << 1);
+    /// Omit the verbose data of the transactions and of their inputs and outputs
+    pub const OMIT_VERBOSE_DATA: Self = Self(1 << 2);
+    /// Omit the transaction inputs and payloads, keeping the outputs (and the transaction
+    /// verbose data, unless [`Self::OMIT_VERBOSE_DATA`] is set)
+    pub const OUTPUTS_ONLY: Self = Self(1 << 3);
+
+    const ALL: u32 = 0b1111;
+
+    /// Creates a mask from its bit representation, dropping unknown flags
+    pub const fn from_bits_truncate(bits: u32) -> Self {
+        Self(bits & Self::ALL)
+    }
+
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.0 & Self::ALL == 0
+    }
+
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Clears the fields of `transaction` covered by the mask
+    pub fn apply(&self, transaction: &mut RpcTransaction) {
+        if self.contains(Self::OUTPUTS_ONLY) {
+            transaction.inputs.clear();
+        }
+        if self.contains(Self::OUTPUTS_ONLY) || self.contains(Self::OMIT_PAYLOADS) {
+            transaction.payload.clear();
+        }
+        if self.contains(Self::OMIT_SIGNATURE_SCRIPTS) {
+            transaction.inputs.iter_mut().for_each(|input| input.signature_script.clear());
+        }
+        if self.contains(Self::OMIT_VERBOSE_DATA) {
+            transaction.verbose_data = None;
+            transaction.inputs.iter_mut().for_each(|input| input.verbose_data = None);
+            transaction.outputs.iter_mut().for_each(|output| output.verbose_data = None);
+        }
+    }
+}
+
+impl From<u32> for RpcTransactionFieldMask {
+    fn from(bits: u32) -> Self {
+        Self::from_bits_truncate(bits)
+    }
+}
+
+impl std::ops::BitOr for RpcTransactionFieldMask {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for RpcTransactionFieldMask {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
 /// Represents accepted transaction ids
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
@@ -115,3 +197,139 @@ pub struct RpcAcceptedTransactionIds {
     pub accepting_block_hash: RpcHash,
     pub accepted_transaction_ids: Vec<RpcTransactionId>,
 }
+
+/// Represents a transaction spending a given outpoint along with the block containing it
+/// and the selected chain block merging that block
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcOutpointSpender {
+    pub transaction_id: RpcTransactionId,
+    pub block_hash: RpcHash,
+    pub merging_block_hash: RpcHash,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::{subnets::SUBNETWORK_ID_NATIVE, tx::Transaction};
+    use serde_json::{json, Value};
+
+    fn extreme_transaction() -> RpcTransaction {
+        let outpoint = RpcTransactionOutpoint::new(RpcTransactionId::from_u64_word(1), 0);
+        RpcTransaction {
+            version: 0,
+            inputs: vec![RpcTransactionInput {
+                previous_outpoint: outpoint,
+                signature_script: vec![1, 2, 3],
+                sequence: u64::MAX,
+                sig_op_count: 1,
+                verbose_data: None,
+            }],
+            outputs: vec![RpcTransactionOutput {
+                value: u64::MAX - 1,
+                script_public_key: RpcScriptPublicKey::from_vec(0, vec![0x51]),
+                verbose_data: None,
+            }],
+            lock_time: (1 << 53) + 1,
+            subnetwork_id: SUBNETWORK_ID_NATIVE,
+            gas: u64::MAX,
+            payload: vec![],
+            mass: 1_000,
+            verbose_data: None,
+        }
+    }
+
+    #[test]
+    fn test_transaction_json_is_lossless() {
+        let transaction = extreme_transaction();
+        let value = serde_json::to_value(&transaction).unwrap();
+        assert_eq!(value["inputs"][0]["sequence"], json!("18446744073709551615"));
+        assert_eq!(value["outputs"][0]["value"], json!("18446744073709551614"));
+        assert_eq!(value["lockTime"], json!("9007199254740993"));
+        assert_eq!(value["gas"], json!("18446744073709551615"));
+        // Values which never exceed the JavaScript safe integer range stay numbers
+        assert_eq!(value["mass"], json!(1_000));
+
+        let decoded: RpcTransaction = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded.inputs[0].sequence, u64::MAX);
+        assert_eq!(decoded.outputs[0].value, u64::MAX - 1);
+        assert_eq!(decoded.lock_time, (1 << 53) + 1);
+        assert_eq!(decoded.gas, u64::MAX);
+
+        // Borsh encodings are unaffected
+        assert_eq!(transaction.outputs[0].try_to_vec().unwrap()[..8], (u64::MAX - 1).to_le_bytes());
+    }
+
+    #[test]
+    fn test_numeric_json_compatibility() {
+        // Clients encoding amounts as numbers keep being understood
+        let mut value = serde_json::to_value(extreme_transaction()).unwrap();
+        value["outputs"][0]["value"] = json!(u64::MAX);
+        value["gas"] = json!(0);
+        let decoded: RpcTransaction = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded.outputs[0].value, u64::MAX);
+        assert_eq!(decoded.gas, 0);
+
+        let entry = RpcUtxoEntry::new(u64::MAX, RpcScriptPublicKey::from_vec(0, vec![0x51]), 100, false);
+        let value = serde_json::to_value(&entry).unwrap();
+        assert_eq!(value["amount"], json!("18446744073709551615"));
+        assert_eq!(value["blockDaaScore"], json!(100));
+        assert_eq!(serde_json::from_value::<RpcUtxoEntry>(value).unwrap(), entry);
+        let value: Value = json!({ "amount": 5, "scriptPublicKey": "000051", "blockDaaScore": 100, "isCoinbase": false });
+        assert_eq!(serde_json::from_value::<RpcUtxoEntry>(value).unwrap().amount, 5);
+    }
+
+    fn verbose_transaction() -> RpcTransaction {
+        let mut transaction = extreme_transaction();
+        transaction.inputs[0].signature_script = vec![0xab; 500];
+        transaction.inputs[0].verbose_data = Some(RpcTransactionInputVerboseData {});
+        transaction.payload = vec![0xcd; 250];
+        let id = Transaction::try_from(&transaction).unwrap().id();
+        transaction.verbose_data = Some(RpcTransactionVerboseData {
+            transaction_id: id,
+            hash: id,
+            mass: 1_000,
+            block_hash: RpcHash::default(),
+            block_time: 0,
+        });
+        transaction
+    }
+
+    #[test]
+    fn test_transaction_field_mask() {
+        let full = verbose_transaction();
+        let full_size = serde_json::to_vec(&full).unwrap().len();
+
+        let mut transaction = full.clone();
+        RpcTransactionFieldMask::NONE.apply(&mut transaction);
+        assert_eq!(serde_json::to_vec(&transaction).unwrap().len(), full_size);
+
+        let mut transaction = full.clone();
+        (RpcTransactionFieldMask::OMIT_SIGNATURE_SCRIPTS | RpcTransactionFieldMask::OMIT_PAYLOADS).apply(&mut transaction);
+        assert!(transaction.inputs[0].signature_script.is_empty());
+        assert!(transaction.payload.is_empty());
+        assert_eq!(transaction.inputs[0].previous_outpoint, full.inputs[0].previous_outpoint);
+        assert!(transaction.inputs[0].verbose_data.is_some() && transaction.verbose_data.is_some());
+        assert!(serde_json::to_vec(&transaction).unwrap().len() * 2 < full_size);
+        // Signature scripts are not committed to by the transaction id
+        let mut unsigned = full.clone();
+        RpcTransactionFieldMask::OMIT_SIGNATURE_SCRIPTS.apply(&mut unsigned);
+        assert_eq!(Transaction::try_from(&unsigned).unwrap().id(), full.verbose_data.as_ref().unwrap().transaction_id);
+
+        let mut transaction = full.clone();
+        RpcTransactionFieldMask::OMIT_VERBOSE_DATA.apply(&mut transaction);
+        assert!(transaction.verbose_data.is_none() && transaction.inputs[0].verbose_data.is_none());
+        assert_eq!(transaction.payload, full.payload);
+
+        let mut transaction = full.clone();
+        RpcTransactionFieldMask::OUTPUTS_ONLY.apply(&mut transaction);
+        assert!(transaction.inputs.is_empty() && transaction.payload.is_empty());
+        assert_eq!(transaction.outputs[0].value, full.outputs[0].value);
+        assert_eq!(transaction.verbose_data.unwrap().transaction_id, full.verbose_data.unwrap().transaction_id);
+
+        // Unknown flags are dropped
+        assert_eq!(RpcTransactionFieldMask::from(u32::MAX).bits(), 0b1111);
+        assert!(RpcTransactionFieldMask::from(1 << 10).is_empty());
+        assert_eq!(serde_json::to_value(RpcTransactionFieldMask::OMIT_PAYLOADS).unwrap(), json!(2));
+    }
+}
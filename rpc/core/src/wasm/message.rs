@@ -169,8 +169,16 @@ declare! {
      * @category Node RPC
      */
     export interface IGetCoinSupplyResponse {
-        maxSompi: bigint;
-        circulatingSompi: bigint;
+        maxSompi: string;
+        circulatingSompi: string;
+        /**
+         * Circulating supply estimated from the emission schedule at the current virtual DAA score.
+         */
+        estimatedCirculatingSompi: string;
+        /**
+         * Subsidy paid to a block mined at the current virtual DAA score.
+         */
+        blockRewardSompi: string;
     }
     "#,
 }
@@ -243,6 +251,50 @@ declare! {
         hasNotifyCommand : boolean;
         /** GRPC ONLY */
         hasMessageId : boolean;
+        /** Digest of the consensus params the node is running with */
+        paramsDigest? : HexString;
+        /** Minimum value of the outputs relayed by the node for each standard script class */
+        dustThresholds : IDustThreshold[];
+        /** The active, staging and retired consensus entries of the node */
+        consensusStatus? : IConsensusStatus;
+    }
+    "#,
+}
+
+declare! {
+    IConsensusStatus,
+    r#"
+    /**
+     * @category Node RPC
+     */
+    export interface IConsensusEntry {
+        key : bigint;
+        directoryName : string;
+        creationTimestamp : bigint;
+        creationReason : "genesis" | "pruningPointSync" | "unknown";
+    }
+
+    /**
+     * @category Node RPC
+     */
+    export interface IRetiredConsensusEntry {
+        entry : IConsensusEntry;
+        retirementTimestamp? : bigint;
+        /** Size in bytes of the database directory of the entry */
+        diskUsage : bigint;
+    }
+
+    /**
+     * @category Node RPC
+     */
+    export interface IConsensusStatus {
+        current? : IConsensusEntry;
+        /** Exists while syncing from the pruning point of a peer */
+        staging? : IConsensusEntry;
+        /** Whether the staging consensus is sealed and its promotion to active is decided */
+        isStagingPrepared : boolean;
+        /** Entries replaced as active, whose database directories await deletion */
+        retired : IRetiredConsensusEntry[];
     }
     "#,
 }
@@ -259,7 +311,25 @@ declare! {
     /**
      * @category Node RPC
      */
-    export interface IGetPeerAddressesRequest { }
+    export interface IGetPeerAddressesRequest {
+        /**
+         * Only return addresses to which a connection ever succeeded.
+         */
+        onlyTried? : boolean,
+        /**
+         * Only return addresses to which a connection never succeeded.
+         */
+        onlyNew? : boolean,
+        /**
+         * Only return addresses whose network group (the `a.b` prefix of IPv4
+         * addresses or the `a:b:c:d` prefix of IPv6 ones) starts with this prefix.
+         */
+        groupPrefix? : string,
+        /**
+         * Maximum number of known addresses to return, the most reliable ones first.
+         */
+        limit? : number
+    }
     "#,
 }
 
@@ -381,6 +451,47 @@ try_from! ( args: GetSinkBlueScoreResponse, IGetSinkBlueScoreResponse, {
 
 // ---
 
+declare! {
+    IGetVirtualInfoRequest,
+    r#"
+    /**
+     * Request the DAA score, sink, sink blue score, parents count and
+     * past median time of the virtual block at once.
+     *
+     * @category Node RPC
+     */
+    export interface IGetVirtualInfoRequest { }
+    "#,
+}
+
+try_from! ( args: IGetVirtualInfoRequest, GetVirtualInfoRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    IGetVirtualInfoResponse,
+    r#"
+    /**
+     * @category Node RPC
+     */
+    export interface IGetVirtualInfoResponse {
+        daaScore : bigint;
+        /** The selected parent of the virtual block */
+        sink : HexString;
+        sinkBlueScore : bigint;
+        /** Number of direct parents of the virtual block */
+        parentsCount : number;
+        pastMedianTime : bigint;
+    }
+    "#,
+}
+
+try_from! ( args: GetVirtualInfoResponse, IGetVirtualInfoResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
 declare! {
     IShutdownRequest,
     r#"
@@ -581,7 +692,7 @@ declare! {
      * @category Node RPC
      */
     export interface IEstimateNetworkHashesPerSecondResponse {
-        networkHashesPerSecond : bigint;
+        networkHashesPerSecond : string;
     }
     "#,
 }
@@ -624,7 +735,7 @@ declare! {
      * @category Node RPC
      */
     export interface IGetBalanceByAddressResponse {
-        balance : bigint;
+        balance : string;
     }
     "#,
 }
@@ -670,7 +781,7 @@ declare! {
      */
     export interface IBalancesByAddressesEntry {
         address : Address;
-        balance : bigint;
+        balance : string;
     }
     /**
      * 
@@ -700,6 +811,13 @@ declare! {
     export interface IGetBlockRequest {
         hash : HexString;
         includeTransactions : boolean;
+        /**
+         * Transaction fields omitted from the response when transactions
+         * are included, as a combination of the following flags:
+         * `1` - input signature scripts, `2` - payloads, `4` - verbose data,
+         * `8` - everything but the outputs (and the transaction verbose data).
+         */
+        transactionFieldMask? : number;
     }
     "#,
 }
@@ -740,6 +858,11 @@ declare! {
         lowHash? : HexString;
         includeBlocks : boolean;
         includeTransactions : boolean;
+        /**
+         * Transaction fields omitted from the response when transactions
+         * are included (see {@link IGetBlockRequest.transactionFieldMask}).
+         */
+        transactionFieldMask? : number;
     }
     "#,
 }
@@ -814,6 +937,11 @@ declare! {
      */
     export interface IGetBlockTemplateResponse {
         block : IBlock;
+        isSynced : boolean;
+        /**
+         * Statistics of the transaction selection of the template.
+         */
+        selectionStats? : IBlockTemplateStats;
     }
     "#,
 }
@@ -862,6 +990,637 @@ try_from! ( args: GetDaaScoreTimestampEstimateResponse, IGetDaaScoreTimestampEst
 
 // ---
 
+declare! {
+    IGetChainBlockByDaaScoreRequest,
+    r#"
+    /**
+     * Requests the selected chain block whose DAA score is nearest to `daaScore`.
+     * Scores above the sink resolve to the sink, while scores below the pruning point are rejected.
+     *
+     * @category Node RPC
+     */
+    export interface IGetChainBlockByDaaScoreRequest {
+        daaScore : bigint;
+    }
+    "#,
+}
+
+try_from! ( args: IGetChainBlockByDaaScoreRequest, GetChainBlockByDaaScoreRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    IGetChainBlockByDaaScoreResponse,
+    r#"
+    /**
+     * 
+     * 
+     * @category Node RPC
+     */
+    export interface IGetChainBlockByDaaScoreResponse {
+        hash : HexString;
+        daaScore : bigint;
+        timestamp : bigint;
+    }
+    "#,
+}
+
+try_from! ( args: GetChainBlockByDaaScoreResponse, IGetChainBlockByDaaScoreResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
+declare! {
+    ISetLogFiltersRequest,
+    r#"
+    /**
+     * Adjusts the log filters of the node at runtime, e.g. `kaspa_p2p_flows=debug`.
+     * Requires the node to run with unsafe RPC enabled.
+     *
+     * @category Node RPC
+     */
+    export interface ISetLogFiltersRequest {
+        filters : string;
+    }
+    "#,
+}
+
+try_from! ( args: ISetLogFiltersRequest, SetLogFiltersRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    ISetLogFiltersResponse,
+    r#"
+    /**
+     * 
+     * 
+     * @category Node RPC
+     */
+    export interface ISetLogFiltersResponse {
+        filters : string;
+    }
+    "#,
+}
+
+try_from! ( args: SetLogFiltersResponse, ISetLogFiltersResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
+declare! {
+    IGetOutpointSpendStatusRequest,
+    r#"
+    /**
+     * Requests the spend status of an outpoint as seen by the UTXO set, the mempool
+     * and the `searchDepth` most recent selected chain blocks (defaults to a node defined depth).
+     *
+     * @category Node RPC
+     */
+    export interface IGetOutpointSpendStatusRequest {
+        outpoint : ITransactionOutpoint;
+        searchDepth? : bigint;
+    }
+    "#,
+}
+
+try_from! ( args: IGetOutpointSpendStatusRequest, GetOutpointSpendStatusRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    IGetOutpointSpendStatusResponse,
+    r#"
+    /**
+     * A transaction spending an outpoint, along with the block containing it
+     * and the selected chain block merging that block.
+     *
+     * @category Node RPC
+     */
+    export interface IOutpointSpender {
+        transactionId : HexString;
+        blockHash : HexString;
+        mergingBlockHash : HexString;
+    }
+
+    /**
+     * 
+     * 
+     * @category Node RPC
+     */
+    export interface IGetOutpointSpendStatusResponse {
+        utxoEntry? : {
+            amount : string;
+            scriptPublicKey : HexString;
+            blockDaaScore : bigint;
+            isCoinbase : boolean;
+        };
+        mempoolSpender? : HexString;
+        acceptedSpender? : IOutpointSpender;
+        conflictingSpenders : IOutpointSpender[];
+    }
+    "#,
+}
+
+try_from! ( args: GetOutpointSpendStatusResponse, IGetOutpointSpendStatusResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
+declare! {
+    IEstimateTransactionConfirmationTimeRequest,
+    r#"
+    /**
+     * Requests an estimated confirmation time range of a mempool transaction.
+     *
+     * @category Node RPC
+     */
+    export interface IEstimateTransactionConfirmationTimeRequest {
+        transactionId : HexString;
+    }
+    "#,
+}
+
+try_from! ( args: IEstimateTransactionConfirmationTimeRequest, EstimateTransactionConfirmationTimeRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    IEstimateTransactionConfirmationTimeResponse,
+    r#"
+    /**
+     * 
+     * 
+     * @category Node RPC
+     */
+    export interface IEstimateTransactionConfirmationTimeResponse {
+        estimate : IConfirmationTimeEstimate;
+    }
+    "#,
+}
+
+try_from! ( args: EstimateTransactionConfirmationTimeResponse, IEstimateTransactionConfirmationTimeResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
+declare! {
+    IGetAcceptanceProofRequest,
+    r#"
+    /**
+     * Requests a merkle proof of the acceptance of a transaction by a selected chain block.
+     *
+     * @category Node RPC
+     */
+    export interface IGetAcceptanceProofRequest {
+        acceptingBlockHash : HexString;
+        transactionId : HexString;
+    }
+    "#,
+}
+
+try_from! ( args: IGetAcceptanceProofRequest, GetAcceptanceProofRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    IGetAcceptanceProofResponse,
+    r#"
+    /**
+     * A merkle proof of the acceptance of a transaction, verifiable against the
+     * accepted id merkle root of the accepting block header with `verifyAcceptanceProof`.
+     *
+     * @category Node RPC
+     */
+    export interface IGetAcceptanceProofResponse {
+        acceptedIdMerkleRoot : HexString;
+        leafIndex : number;
+        path : HexString[];
+    }
+    "#,
+}
+
+try_from! ( args: GetAcceptanceProofResponse, IGetAcceptanceProofResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
+declare! {
+    ICreateBackupRequest,
+    r#"
+    /**
+     * Requests the node to back up its consensus databases. Requires the node to run with `--unsaferpc`.
+     *
+     * @category Node RPC
+     */
+    export interface ICreateBackupRequest {
+        /**
+         * Absolute path, on the node host, of the directory to create the backup in.
+         * The directory must not exist.
+         */
+        outputDirectory : string;
+    }
+    "#,
+}
+
+try_from! ( args: ICreateBackupRequest, CreateBackupRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    ICreateBackupResponse,
+    r#"
+    /**
+     * The manifest of a backup of the node consensus databases.
+     *
+     * @category Node RPC
+     */
+    export interface ICreateBackupResponse {
+        nodeVersion : string;
+        networkId : string;
+        paramsDigest : HexString;
+        pruningPoint : HexString;
+        sink : HexString;
+        /** Unix time (in milliseconds) at which the backup was taken */
+        timestamp : bigint;
+    }
+    "#,
+}
+
+try_from! ( args: CreateBackupResponse, ICreateBackupResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
+declare! {
+    IGetVirtualParentSelectionTraceRequest,
+    r#"
+    /**
+     * Requests a trace of the virtual parent selection. Requires the node to run with `--unsaferpc`.
+     *
+     * @category Node RPC
+     */
+    export interface IGetVirtualParentSelectionTraceRequest { }
+    "#,
+}
+
+try_from! ( args: IGetVirtualParentSelectionTraceRequest, GetVirtualParentSelectionTraceRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    IGetVirtualParentSelectionTraceResponse,
+    r#"
+    /**
+     * A trace of the virtual parent selection over the current tips.
+     *
+     * @category Node RPC
+     */
+    export interface IGetVirtualParentSelectionTraceResponse {
+        tips : HexString[];
+        selectedParent : HexString;
+        /** May include blocks in the past of tips excluded due to the mergeset size limit */
+        virtualParents : HexString[];
+        /** The tips which are neither the selected parent nor a virtual parent */
+        excludedTips : {
+            hash : HexString;
+            reason : "mergeset-size-limit" | "merge-depth-violation" | "disqualified" | "parent-limit";
+        }[];
+    }
+    "#,
+}
+
+try_from! ( args: GetVirtualParentSelectionTraceResponse, IGetVirtualParentSelectionTraceResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
+declare! {
+    IGetSystemInfoRequest,
+    r#"
+    /**
+     * Requests information about the node host, including the results of the startup preflight checks.
+     *
+     * @category Node RPC
+     */
+    export interface IGetSystemInfoRequest { }
+    "#,
+}
+
+try_from! ( args: IGetSystemInfoRequest, GetSystemInfoRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    IGetSystemInfoResponse,
+    r#"
+    /**
+     * Information about the node host.
+     *
+     * @category Node RPC
+     */
+    export interface IGetSystemInfoResponse {
+        version : string;
+        /** The soft file descriptor limit of the node process */
+        fdLimit : number;
+        preflightChecks : {
+            /** One of fd-limit, disk-space or clock-skew */
+            name : string;
+            status : "passed" | "warning" | "overridden" | "failed" | "pending" | "unknown";
+            message : string;
+        }[];
+    }
+    "#,
+}
+
+try_from! ( args: GetSystemInfoResponse, IGetSystemInfoResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
+declare! {
+    IRemovePeerRequest,
+    r#"
+    /**
+     * Remove a peer added by {@link RpcClient.addPeer}, whether permanently
+     * or not, and disconnect from it.
+     *
+     * @category Node RPC
+     */
+    export interface IRemovePeerRequest {
+        peerAddress : INetworkAddress;
+    }
+    "#,
+}
+
+try_from! ( args: IRemovePeerRequest, RemovePeerRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    IRemovePeerResponse,
+    r#"
+    /**
+     * 
+     * 
+     * @category Node RPC
+     */
+    export interface IRemovePeerResponse { }
+    "#,
+}
+
+try_from! ( args: RemovePeerResponse, IRemovePeerResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
+declare! {
+    ISetProcessingParallelismRequest,
+    r#"
+    /**
+     * Set the maximum number of blocks validated concurrently by the node.
+     * Requires the node to run with unsafe RPC enabled.
+     *
+     * @category Node RPC
+     */
+    export interface ISetProcessingParallelismRequest {
+        limit : number;
+    }
+    "#,
+}
+
+try_from! ( args: ISetProcessingParallelismRequest, SetProcessingParallelismRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    ISetProcessingParallelismResponse,
+    r#"
+    /**
+     * 
+     * 
+     * @category Node RPC
+     */
+    export interface ISetProcessingParallelismResponse {
+        previousLimit : number;
+    }
+    "#,
+}
+
+try_from! ( args: SetProcessingParallelismResponse, ISetProcessingParallelismResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
+declare! {
+    IGetAddressActivityRequest,
+    "IGetAddressActivityRequest | Address[] | string[]",
+    r#"
+    /**
+     * Check whether addresses were ever used, including addresses whose funds were fully spent.
+     * Requires the node to run with `--utxoindex`.
+     *
+     * @category Node RPC
+     */
+    export interface IGetAddressActivityRequest {
+        addresses : Address[] | string[];
+    }
+    "#,
+}
+
+try_from! ( args: IGetAddressActivityRequest, GetAddressActivityRequest, {
+    let js_value = JsValue::from(args);
+    let request = if let Ok(addresses) = Vec::<Address>::try_from(AddressOrStringArrayT::from(js_value.clone())) {
+        GetAddressActivityRequest { addresses }
+    } else {
+        from_value::<GetAddressActivityRequest>(js_value)?
+    };
+    Ok(request)
+});
+
+declare! {
+    IGetAddressActivityResponse,
+    r#"
+    /**
+     * 
+     * 
+     * @category Node RPC
+     */
+    export interface IAddressActivityEntry {
+        address : Address;
+        hasReceived : boolean;
+        firstSeenDaaScore : bigint;
+        lastActivityDaaScore : bigint;
+    }
+    /**
+     * 
+     * 
+     * @category Node RPC
+     */
+    export interface IGetAddressActivityResponse {
+        entries : IAddressActivityEntry[];
+    }
+    "#,
+}
+
+try_from! ( args: GetAddressActivityResponse, IGetAddressActivityResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+declare! {
+    IGetMissedNotificationsRequest,
+    r#"
+    /**
+     * Get the journaled notifications of a scope emitted since a sequence number,
+     * backfilling the notifications missed by a client. Only the VirtualChainChanged
+     * and UtxosChanged scopes are journaled.
+     * Requires the node to run with `--notification-journal-size`.
+     *
+     * @category Node RPC
+     */
+    export interface IGetMissedNotificationsRequest {
+        scope : { VirtualChainChanged : { include_accepted_transaction_ids : boolean } } | { UtxosChanged : { addresses : Address[] | string[] } };
+        /**
+         * The sequence of the first notification requested, i.e. the last sequence received plus one
+         */
+        fromSequence : bigint;
+    }
+    "#,
+}
+
+try_from! ( args: IGetMissedNotificationsRequest, GetMissedNotificationsRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    IGetMissedNotificationsResponse,
+    r#"
+    /**
+     * 
+     * 
+     * @category Node RPC
+     */
+    export interface IGetMissedNotificationsResponse {
+        virtualChainChanged : IVirtualChainChanged[];
+        utxosChanged : IUtxosChanged[];
+        /**
+         * The sequence of the last notification emitted in the scope
+         */
+        lastSequence : bigint;
+    }
+    "#,
+}
+
+try_from! ( args: GetMissedNotificationsResponse, IGetMissedNotificationsResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
+declare! {
+    IGetUtxoReturnAddressRequest,
+    r#"
+    /**
+     * Requests the UTXO entry spent by an input of a transaction accepted by a selected chain block.
+     *
+     * @category Node RPC
+     */
+    export interface IGetUtxoReturnAddressRequest {
+        acceptingBlockHash : HexString;
+        transactionId : HexString;
+        inputIndex : number;
+    }
+    "#,
+}
+
+try_from! ( args: IGetUtxoReturnAddressRequest, GetUtxoReturnAddressRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    IGetUtxoReturnAddressResponse,
+    r#"
+    /**
+     * The spent UTXO entry along with the address it was locked to,
+     * absent if its script public key is non-standard.
+     *
+     * @category Node RPC
+     */
+    export interface IGetUtxoReturnAddressResponse {
+        address? : Address;
+        utxoEntry : {
+            amount : string;
+            scriptPublicKey : HexString;
+            blockDaaScore : bigint;
+            isCoinbase : boolean;
+        };
+    }
+    "#,
+}
+
+try_from! ( args: GetUtxoReturnAddressResponse, IGetUtxoReturnAddressResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
+declare! {
+    IValidateBlockTemplateRequest,
+    r#"
+    /**
+     * Requests to validate a block template against the current virtual state
+     * without submitting it. The proof of work is not checked.
+     *
+     * @category Node RPC
+     */
+    export interface IValidateBlockTemplateRequest {
+        block : IBlock;
+    }
+    "#,
+}
+
+try_from! ( args: IValidateBlockTemplateRequest, ValidateBlockTemplateRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    IValidateBlockTemplateResponse,
+    r#"
+    /**
+     * The mass and total fees of a valid block template, or the rule
+     * violated by an invalid one.
+     *
+     * @category Node RPC
+     */
+    export interface IValidateBlockTemplateResponse {
+        isValid : boolean;
+        mass : bigint;
+        totalFees : bigint;
+        rejectionDetail? : string;
+        invalidTransactions : {
+            transactionId : HexString;
+            reason : string;
+        }[];
+    }
+    "#,
+}
+
+try_from! ( args: ValidateBlockTemplateResponse, IValidateBlockTemplateResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
 declare! {
     IGetCurrentNetworkRequest,
     r#"
@@ -949,6 +1708,11 @@ declare! {
     export interface IGetMempoolEntriesRequest {
         includeOrphanPool? : boolean;
         filterTransactionPool? : boolean;
+        /**
+         * Read all the entries under a single lock of the mempool,
+         * so that they match the summary of the response.
+         */
+        consistentSnapshot? : boolean;
     }
     "#,
 }
@@ -967,6 +1731,10 @@ declare! {
      */
     export interface IGetMempoolEntriesResponse {
         mempoolEntries : IMempoolEntry[];
+        /**
+         * Totals of the queried pools at the time the entries were read.
+         */
+        summary : IMempoolSummary;
     }
     "#,
 }
@@ -1084,7 +1852,7 @@ declare! {
      * @category Node RPC
      */
     export interface IGetSubnetworkResponse {
-        gasLimit : bigint;
+        gasLimit : string;
     }
     "#,
 }
@@ -1270,6 +2038,25 @@ const TS_SUBMIT_BLOCK_REPORT: &'static str = r#"
         type : "success" | "reject";
         reason? : SubmitBlockRejectReason;
     }
+
+    /**
+     * 
+     * @category Node RPC
+     */
+    export enum SubmitBlockClassification {
+        /**
+         * The block is built on the current virtual parents.
+         */
+        Fresh = "fresh",
+        /**
+         * The block is built on an outdated template. Stale blocks are still valid and processed.
+         */
+        Stale = "stale",
+        /**
+         * The block is already known to the node and was not processed again.
+         */
+        AlreadyProcessed = "alreadyProcessed",
+    }
 "#;
 
 declare! {
@@ -1282,6 +2069,17 @@ declare! {
      */
     export interface ISubmitBlockResponse {
         report : ISubmitBlockReport;
+        submissionClassification? : SubmitBlockClassification;
+        /**
+         * Set if the node (running with `--check-template-tx-order`) flagged the block
+         * transactions as deviating from the canonical block template order.
+         */
+        nonCanonicalTransactionOrder : boolean;
+        /**
+         * Describes the rule violated by a rejected block, e.g. the expected
+         * and actual amounts of a wrong coinbase payment.
+         */
+        rejectionDetail? : string;
     }
     "#,
 }
@@ -1303,23 +2101,43 @@ declare! {
      */
     export interface ISubmitTransactionRequest {
         transaction : Transaction,
-        allowOrphan? : boolean
+        allowOrphan? : boolean,
+        /**
+         * Hold the transaction until its lock time is reached instead of rejecting it
+         * (requires the node to run with `--hold-time-locked-txs`).
+         */
+        allowNotYetValid? : boolean,
+        /**
+         * On rejection, respond with the trace of the mempool acceptance rules checked
+         * against the transaction instead of throwing (rate limited by the node).
+         */
+        verbose? : boolean,
+        /**
+         * Client-provided key echoed back in the response, correlating retried submissions.
+         */
+        idempotencyKey? : string
     }
     "#,
 }
 
 try_from! ( args: ISubmitTransactionRequest, SubmitTransactionRequest, {
-    let (transaction, allow_orphan) = if let Some(transaction) = args.try_get_value("transaction")? {
+    let (transaction, allow_orphan, allow_not_yet_valid, verbose, idempotency_key) = if let Some(transaction) = args.try_get_value("transaction")? {
         let allow_orphan = args.try_get_bool("allowOrphan")?.unwrap_or(false);
-        (transaction, allow_orphan)
+        let allow_not_yet_valid = args.try_get_bool("allowNotYetValid")?.unwrap_or(false);
+        let verbose = args.try_get_bool("verbose")?.unwrap_or(false);
+        let idempotency_key = args.try_get_string("idempotencyKey")?;
+        (transaction, allow_orphan, allow_not_yet_valid, verbose, idempotency_key)
     } else {
-        (args.into(), false)
+        (args.into(), false, false, false, None)
     };
 
     let request = if let Ok(transaction) = Transaction::try_owned_from(&transaction) {
         SubmitTransactionRequest {
             transaction : transaction.into(),
             allow_orphan,
+            allow_not_yet_valid,
+            verbose,
+            idempotency_key,
         }
     } else {
         from_value(transaction)?
@@ -1337,6 +2155,20 @@ declare! {
      */
     export interface ISubmitTransactionResponse {
         transactionId : HexString;
+        /**
+         * Set if the transaction was rejected while submitted in verbose mode.
+         */
+        rejectionTrace? : ITransactionAcceptanceTrace;
+        /**
+         * Resubmissions of a transaction already in the mempool, or recently accepted
+         * by a selected chain block, succeed with the `alreadyKnown` or `alreadyAccepted` status.
+         */
+        status : "submitted" | "alreadyKnown" | "alreadyAccepted" | "rejected" | "unknown";
+        /**
+         * The selected chain block which accepted the transaction, set if the status is `alreadyAccepted`.
+         */
+        acceptingBlockHash? : HexString;
+        idempotencyKey? : string;
     }
     "#,
 }
@@ -1,5 +1,6 @@
 use kaspa_notify::error::Error as NotifyError;
-use kaspa_rpc_core::RpcError;
+use kaspa_rpc_core::{RpcError, RpcTransactionId, SubmitBlockRejectReason};
+use std::str::FromStr;
 use thiserror::Error;
 
 pub type BoxedStdError = Box<(dyn std::error::Error + Sync + std::marker::Send + 'static)>;
@@ -97,3 +98,171 @@ impl From<Error> for NotifyError {
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+/// Typed error of an RPC call, obtained from the [`RpcError`] returned by a [`GrpcClient`](crate::GrpcClient)
+/// or a [`GrpcPool`](crate::pool::GrpcPool).
+///
+/// The errors raised by a node cross the gRPC wire as messages only, so they are classified by matching
+/// the messages of the [`RpcError`] variants they originate from. The consensus and mempool rule errors
+/// do not have stable codes yet, so the reason of a rejected transaction is the message of the rule error.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum CallError {
+    /// The node could not be reached or the connection was lost before a response was received
+    #[error("Node unavailable: {0}")]
+    Unavailable(String),
+
+    /// No response was received before the request timeout
+    #[error("RPC request timeout")]
+    Timeout,
+
+    #[error("Rejected transaction {0}: {1}")]
+    RejectedTransaction(RpcTransactionId, String),
+
+    #[error("Block was not submitted: {0}")]
+    RejectedBlock(SubmitBlockRejectReason),
+
+    #[error("Method unavailable. Run the node with the --utxoindex argument.")]
+    NoUtxoIndex,
+
+    #[error("Method unavailable in safe mode. Run the node with --unsaferpc argument.")]
+    UnavailableInSafeMode,
+
+    /// The method is not implemented or not supported by the node
+    #[error("Feature not supported")]
+    Unsupported,
+
+    /// The request could not be issued by the client
+    #[error("Client error: {0}")]
+    Client(String),
+
+    /// Any other error returned by the node
+    #[error("{0}")]
+    Rpc(String),
+}
+
+impl CallError {
+    /// `true` if the call failed because the node was unavailable, in which case
+    /// the call may succeed if issued again, possibly to another node
+    pub fn is_transient(&self) -> bool {
+        matches!(self, CallError::Unavailable(_) | CallError::Timeout)
+    }
+
+    fn from_message(message: &str) -> Self {
+        if let Some(rejection) = message.strip_prefix("Rejected transaction ") {
+            if let Some((id, reason)) = rejection.split_once(": ") {
+                if let Ok(id) = RpcTransactionId::from_str(id) {
+                    return CallError::RejectedTransaction(id, reason.to_string());
+                }
+            }
+        }
+        if let Some(reason) = message.strip_prefix("Block was not submitted: ") {
+            let reason =
+                [SubmitBlockRejectReason::BlockInvalid, SubmitBlockRejectReason::IsInIBD, SubmitBlockRejectReason::RouteIsFull]
+                    .into_iter()
+                    .find(|known| known.to_string() == reason);
+            if let Some(reason) = reason {
+                return CallError::RejectedBlock(reason);
+            }
+        }
+        // Messages of the client errors converted into an RpcError (see `From<Error> for RpcError`)
+        // and of the RpcError variants with a unit type
+        let client_errors = [Error::NotConnected, Error::ChannelRecvError, Error::ChannelSendError];
+        if client_errors.iter().any(|err| err.to_string() == message)
+            || message.starts_with("GRPC client error ")
+            || message.starts_with("Endpoint connection error: ")
+        {
+            return CallError::Unavailable(message.to_string());
+        }
+        if message == Error::Timeout.to_string() {
+            CallError::Timeout
+        } else if message == RpcError::NoUtxoIndex.to_string() {
+            CallError::NoUtxoIndex
+        } else if message == RpcError::UnavailableInSafeMode.to_string() {
+            CallError::UnavailableInSafeMode
+        } else if message == RpcError::UnsupportedFeature.to_string() || message == RpcError::NotImplemented.to_string() {
+            CallError::Unsupported
+        } else {
+            CallError::Rpc(message.to_string())
+        }
+    }
+}
+
+impl From<&Error> for CallError {
+    fn from(err: &Error) -> Self {
+        match err {
+            Error::NotConnected | Error::ChannelRecvError | Error::ChannelSendError | Error::EndpointConnectionError(_) => {
+                CallError::Unavailable(err.to_string())
+            }
+            Error::Timeout => CallError::Timeout,
+            Error::TonicStatus(status) => match status.code() {
+                tonic::Code::DeadlineExceeded => CallError::Timeout,
+                tonic::Code::Unavailable | tonic::Code::Cancelled | tonic::Code::Aborted => CallError::Unavailable(err.to_string()),
+                tonic::Code::Unimplemented => CallError::Unsupported,
+                _ => CallError::Rpc(status.message().to_string()),
+            },
+            _ => CallError::Client(err.to_string()),
+        }
+    }
+}
+
+impl From<Error> for CallError {
+    fn from(err: Error) -> Self {
+        (&err).into()
+    }
+}
+
+impl From<&RpcError> for CallError {
+    fn from(err: &RpcError) -> Self {
+        match err {
+            RpcError::RejectedTransaction(id, reason) => CallError::RejectedTransaction(*id, reason.clone()),
+            RpcError::SubmitBlockError(reason) => CallError::RejectedBlock(*reason),
+            RpcError::NoUtxoIndex => CallError::NoUtxoIndex,
+            RpcError::UnavailableInSafeMode => CallError::UnavailableInSafeMode,
+            RpcError::UnsupportedFeature | RpcError::NotImplemented => CallError::Unsupported,
+            RpcError::General(message) | RpcError::RpcSubsystem(message) | RpcError::Traced(message, _) => {
+                CallError::from_message(message)
+            }
+            _ => CallError::Rpc(err.to_string()),
+        }
+    }
+}
+
+impl From<RpcError> for CallError {
+    fn from(err: RpcError) -> Self {
+        (&err).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_rpc_core::RpcHash;
+
+    #[test]
+    fn test_call_error_classification() {
+        let id = RpcHash::from_u64_word(7);
+        let reason = "transaction has 10 fees which is under the required amount of 20".to_string();
+
+        // Node errors only cross the wire as messages
+        let wire = |err: RpcError| CallError::from(RpcError::General(err.to_string()));
+        let tests = [
+            (RpcError::RejectedTransaction(id, reason.clone()), CallError::RejectedTransaction(id, reason)),
+            (RpcError::SubmitBlockError(SubmitBlockRejectReason::IsInIBD), CallError::RejectedBlock(SubmitBlockRejectReason::IsInIBD)),
+            (RpcError::NoUtxoIndex, CallError::NoUtxoIndex),
+            (RpcError::UnavailableInSafeMode, CallError::UnavailableInSafeMode),
+            (RpcError::UnsupportedFeature, CallError::Unsupported),
+            (Error::Timeout.into(), CallError::Timeout),
+            (Error::NotConnected.into(), CallError::Unavailable(Error::NotConnected.to_string())),
+            (RpcError::General("some error".to_string()), CallError::Rpc("some error".to_string())),
+        ];
+        for (err, expected) in tests {
+            assert_eq!(CallError::from(&err), expected, "direct classification of {err}");
+            assert_eq!(wire(err), expected);
+        }
+
+        assert!(CallError::from(Error::ChannelRecvError).is_transient());
+        assert!(CallError::from(Error::TonicStatus(tonic::Status::unavailable("down"))).is_transient());
+        assert!(!CallError::from(Error::MissingResponsePayload).is_transient());
+        assert!(!CallError::from(RpcError::NoUtxoIndex).is_transient());
+    }
+}
@@ -7,7 +7,7 @@ use async_trait::async_trait;
 pub use client_pool::ClientPool;
 use connection_event::ConnectionEvent;
 use futures::{future::FutureExt, pin_mut, select};
-use kaspa_core::{debug, error, trace};
+use kaspa_core::{debug, error, log::trace_id, trace};
 use kaspa_grpc_core::{
     channel::NotificationChannel,
     ops::KaspadPayloadOps,
@@ -60,6 +60,8 @@ mod resolver;
 mod route;
 
 mod client_pool;
+pub mod pool;
+pub mod retry;
 
 pub type GrpcClientCollector = CollectorFrom<RpcCoreConverter>;
 pub type GrpcClientNotify = DynNotify<Notification>;
@@ -271,6 +273,21 @@ impl RpcApi for GrpcClient {
     route!(get_mempool_entries_by_addresses_call, GetMempoolEntriesByAddresses);
     route!(get_coin_supply_call, GetCoinSupply);
     route!(get_daa_score_timestamp_estimate_call, GetDaaScoreTimestampEstimate);
+    route!(get_chain_block_by_daa_score_call, GetChainBlockByDaaScore);
+    route!(set_log_filters_call, SetLogFilters);
+    route!(get_outpoint_spend_status_call, GetOutpointSpendStatus);
+    route!(estimate_transaction_confirmation_time_call, EstimateTransactionConfirmationTime);
+    route!(get_acceptance_proof_call, GetAcceptanceProof);
+    route!(create_backup_call, CreateBackup);
+    route!(get_virtual_parent_selection_trace_call, GetVirtualParentSelectionTrace);
+    route!(get_system_info_call, GetSystemInfo);
+    route!(remove_peer_call, RemovePeer);
+    route!(set_processing_parallelism_call, SetProcessingParallelism);
+    route!(get_address_activity_call, GetAddressActivity);
+    route!(get_missed_notifications_call, GetMissedNotifications);
+    route!(get_utxo_return_address_call, GetUtxoReturnAddress);
+    route!(validate_block_template_call, ValidateBlockTemplate);
+    route!(get_virtual_info_call, GetVirtualInfo);
 
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Notification API
@@ -683,6 +700,10 @@ impl Inner {
             let id = u64::from_le_bytes(rand::random::<[u8; 8]>());
             let mut request: KaspadRequest = request.into();
             request.id = id;
+            // Calls made within a trace id scope are traced by the server under that id
+            if let Some(trace_id) = trace_id::current() {
+                request.trace_id = trace_id.to_string();
+            }
 
             trace!("GRPC client: resolver call: {:?}", request);
             if request.payload.is_some() {
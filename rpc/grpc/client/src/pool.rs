@@ -0,0 +1,600 @@
+//!
+//! A resilient gRPC client maintaining connections to a set of kaspad nodes.
+//!
+//! The [`GrpcPool`] routes the RPC calls to an active endpoint, checks the health of
+//! all endpoints periodically and fails over to the next healthy endpoint when the
+//! active one goes down, retrying the calls of read-only methods on the way
+//! (see [`RetryPolicy`] and [`is_retry_safe`](crate::retry::is_retry_safe)).
+//!
+//! Subscriptions are retained by the pool and replayed on the endpoint it fails over
+//! to. As with the reconnections of the wRPC client, notifications emitted while no
+//! endpoint was active are not replayed: a [`PoolEvent::Activated`] event signals
+//! consumers that they should resync any state derived from notifications, like the
+//! UTXO set of the addresses they track.
+//!
+
+use crate::{
+    error::{CallError, Error, Result},
+    retry::RetryPolicy,
+    GrpcClient, REQUEST_TIMEOUT_DURATION,
+};
+use async_channel::Receiver;
+use async_trait::async_trait;
+use futures::{future::FutureExt, pin_mut, select};
+use kaspa_core::{debug, trace, warn};
+use kaspa_grpc_core::{
+    channel::NotificationChannel,
+    ops::KaspadPayloadOps,
+    protowire::{KaspadRequest, KaspadResponse},
+};
+use kaspa_notify::{
+    events::{EventArray, EVENT_TYPE_ARRAY},
+    listener::ListenerId,
+    scope::Scope,
+    subscription::{
+        array::ArrayBuilder, context::SubscriptionContext, Command, DynSubscription, MutateSingle, Mutation, MutationPolicies,
+        UtxosChangedMutationPolicy,
+    },
+};
+use kaspa_rpc_core::{
+    api::rpc::RpcApi,
+    error::RpcResult,
+    model::message::*,
+    notify::{connection::ChannelConnection, mode::NotificationMode},
+    Notification,
+};
+use kaspa_utils::{channel::Channel, triggers::DuplexTrigger};
+use parking_lot::Mutex;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use tokio::time::{sleep, timeout};
+
+/// Options of a [`GrpcPool`]
+#[derive(Debug, Clone)]
+pub struct PoolOptions {
+    pub retry_policy: RetryPolicy,
+
+    /// Interval between two health checks of the endpoints
+    pub health_check_interval: Duration,
+
+    /// Time waited for the connection to an endpoint and for the `Ping` call of a health check
+    pub health_check_timeout: Duration,
+
+    /// Time waited for the response of a call attempt. A call attempt timing out on the
+    /// active endpoint fails the pool over to another endpoint.
+    pub request_timeout: Duration,
+
+    /// It is advised to provide a clone of the same instance if multiple clients dealing with
+    /// `UtxosChangedNotifications` are connected concurrently in order to optimize the memory footprint.
+    pub subscription_context: Option<SubscriptionContext>,
+}
+
+impl Default for PoolOptions {
+    fn default() -> Self {
+        Self {
+            retry_policy: RetryPolicy::default(),
+            health_check_interval: Duration::from_secs(5),
+            health_check_timeout: Duration::from_secs(2),
+            request_timeout: Duration::from_millis(REQUEST_TIMEOUT_DURATION),
+            subscription_context: None,
+        }
+    }
+}
+
+/// Health of an endpoint as observed by the health checks and calls of the pool
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EndpointHealth {
+    /// Round-trip time of the `Ping` call of the last successful health check
+    pub latency: Option<Duration>,
+    /// Number of consecutive failed health checks and calls
+    pub failures: u32,
+}
+
+impl EndpointHealth {
+    /// `true` if the last health check of the endpoint succeeded
+    pub fn is_healthy(&self) -> bool {
+        self.latency.is_some() && self.failures == 0
+    }
+
+    /// `true` if the last health check or call of the endpoint failed
+    pub fn is_failed(&self) -> bool {
+        self.failures > 0
+    }
+
+    fn record(&mut self, latency: Option<Duration>) {
+        match latency {
+            Some(latency) => {
+                self.latency = Some(latency);
+                self.failures = 0;
+            }
+            None => {
+                self.latency = None;
+                self.failures += 1;
+            }
+        }
+    }
+}
+
+/// Connection events of a [`GrpcPool`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PoolEvent {
+    /// The calls are routed to the endpoint at `url`, on which the subscriptions
+    /// have been replayed. `failover` is `false` on the first activation only.
+    Activated { url: String, failover: bool },
+
+    /// No endpoint could be activated
+    Unavailable,
+}
+
+struct Endpoint {
+    url: String,
+    client: Mutex<Option<Arc<GrpcClient>>>,
+    health: Mutex<EndpointHealth>,
+}
+
+struct Inner {
+    endpoints: Vec<Endpoint>,
+    options: PoolOptions,
+
+    /// Index of the endpoint the calls are routed to
+    active: Mutex<Option<usize>>,
+    was_active: AtomicBool,
+    failover_lock: tokio::sync::Mutex<()>,
+
+    // Subscriptions retained across failovers
+    subscriptions: tokio::sync::Mutex<EventArray<DynSubscription>>,
+    subscription_context: SubscriptionContext,
+    policies: MutationPolicies,
+
+    // Notifications of the active endpoint
+    notification_channel: NotificationChannel,
+    events: Channel<PoolEvent>,
+
+    // Health monitor
+    monitor_is_running: AtomicBool,
+    monitor_shutdown: DuplexTrigger,
+}
+
+impl Inner {
+    fn client(&self, index: usize) -> Option<Arc<GrpcClient>> {
+        self.endpoints[index].client.lock().clone()
+    }
+
+    fn active_client(&self) -> Option<(usize, Arc<GrpcClient>)> {
+        let index = (*self.active.lock())?;
+        self.client(index).map(|client| (index, client))
+    }
+
+    fn health(&self, index: usize) -> EndpointHealth {
+        *self.endpoints[index].health.lock()
+    }
+
+    fn record_health(&self, index: usize, latency: Option<Duration>) {
+        self.endpoints[index].health.lock().record(latency);
+    }
+
+    fn send_event(&self, event: PoolEvent) {
+        if let Err(err) = self.events.try_send(event) {
+            debug!("GRPC pool: send event error: {err}");
+        }
+    }
+
+    /// Returns the client of the endpoint, connecting to it if needed. The notifications
+    /// received by a new client are forwarded to the notification channel of the pool.
+    async fn connected_client(&self, index: usize) -> Option<Arc<GrpcClient>> {
+        if let Some(client) = self.client(index).filter(|client| client.is_connected()) {
+            return Some(client);
+        }
+        self.release(index).await;
+
+        let endpoint = &self.endpoints[index];
+        let connect = GrpcClient::connect_with_args(
+            NotificationMode::Direct,
+            endpoint.url.clone(),
+            Some(self.subscription_context.clone()),
+            false,
+            None,
+            false,
+            Some(self.options.request_timeout.as_millis() as u64),
+            Default::default(),
+        );
+        let client = match timeout(self.options.health_check_timeout, connect).await {
+            Ok(Ok(client)) => Arc::new(client),
+            Ok(Err(err)) => {
+                trace!("GRPC pool: connection to {} failed with error {err}", endpoint.url);
+                return None;
+            }
+            Err(_) => {
+                trace!("GRPC pool: connection to {} timed out", endpoint.url);
+                return None;
+            }
+        };
+
+        let receiver = client.notification_channel_receiver();
+        let sender = self.notification_channel.sender();
+        tokio::spawn(async move {
+            // The receiver gets closed when the client disconnects
+            while let Ok(notification) = receiver.recv().await {
+                if sender.send(notification).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        endpoint.client.lock().replace(client.clone());
+        Some(client)
+    }
+
+    /// Disconnects the client of the endpoint, dropping the subscriptions it holds on the node
+    async fn release(&self, index: usize) {
+        let client = self.endpoints[index].client.lock().take();
+        if let Some(client) = client {
+            client.disconnect().await.unwrap_or_else(|err| trace!("GRPC pool: disconnection error {err}"));
+        }
+    }
+
+    /// Checks the health of the endpoint with a `Ping` call. Standby endpoints are
+    /// connected to if needed, while the active one is only checked.
+    async fn check(&self, index: usize) -> EndpointHealth {
+        let is_active = *self.active.lock() == Some(index);
+        let client = match is_active {
+            true => self.client(index).filter(|client| client.is_connected()),
+            false => self.connected_client(index).await,
+        };
+        let latency = match client {
+            Some(client) => {
+                let start = Instant::now();
+                match timeout(self.options.health_check_timeout, client.ping()).await {
+                    Ok(Ok(_)) => Some(start.elapsed()),
+                    _ => None,
+                }
+            }
+            None => None,
+        };
+        self.record_health(index, latency);
+        self.health(index)
+    }
+
+    async fn check_all(&self) {
+        for index in 0..self.endpoints.len() {
+            self.check(index).await;
+        }
+    }
+
+    /// Routes the calls to another endpoint if the active endpoint is still `from`, replaying
+    /// the subscriptions on it. Endpoints are tried healthy ones first, then by their order,
+    /// `from` being tried last. Returns `true` if an endpoint is active on return.
+    async fn failover(&self, from: Option<usize>) -> bool {
+        let _guard = self.failover_lock.lock().await;
+        let active = *self.active.lock();
+        if active != from {
+            // Another call or the health monitor already failed over
+            return active.is_some();
+        }
+        if let Some(from) = from {
+            warn!("GRPC pool: endpoint {} is unavailable, failing over", self.endpoints[from].url);
+            *self.active.lock() = None;
+            self.release(from).await;
+        }
+
+        let mut candidates = (0..self.endpoints.len()).collect::<Vec<_>>();
+        candidates.sort_by_key(|&index| {
+            let health = self.health(index);
+            (Some(index) == from, health.is_failed(), !health.is_healthy(), index)
+        });
+        for index in candidates {
+            let Some(client) = self.connected_client(index).await else {
+                self.record_health(index, None);
+                continue;
+            };
+            match self.replay_subscriptions(&client).await {
+                Ok(()) => {
+                    *self.active.lock() = Some(index);
+                    let failover = self.was_active.swap(true, Ordering::SeqCst);
+                    debug!("GRPC pool: calls are routed to {}", self.endpoints[index].url);
+                    self.send_event(PoolEvent::Activated { url: self.endpoints[index].url.clone(), failover });
+                    return true;
+                }
+                Err(err) => {
+                    debug!("GRPC pool: replaying the subscriptions on {} failed with error {err}", self.endpoints[index].url);
+                    self.record_health(index, None);
+                    self.release(index).await;
+                }
+            }
+        }
+
+        self.send_event(PoolEvent::Unavailable);
+        false
+    }
+
+    async fn replay_subscriptions(&self, client: &GrpcClient) -> RpcResult<()> {
+        let scopes = {
+            let subscriptions = self.subscriptions.lock().await;
+            EVENT_TYPE_ARRAY
+                .into_iter()
+                .filter(|&event| subscriptions[event].active())
+                .map(|event| subscriptions[event].scope(&self.subscription_context))
+                .collect::<Vec<_>>()
+        };
+        for scope in scopes {
+            client.start_notify(GrpcClient::DIRECT_MODE_LISTENER_ID, scope).await?;
+        }
+        Ok(())
+    }
+
+    /// Issues the request to the active endpoint, failing over to another endpoint if the active
+    /// one is unavailable and retrying the request according to the retry policy of the pool.
+    async fn call(&self, op: KaspadPayloadOps, request: impl Into<KaspadRequest>) -> Result<KaspadResponse> {
+        let request: KaspadRequest = request.into();
+        let mut attempt = 0;
+        loop {
+            let (index, err) = match self.active_client() {
+                Some((index, client)) => match timeout(self.options.request_timeout, client.inner.call(op, request.clone())).await {
+                    Ok(Ok(response)) => return Ok(response),
+                    Ok(Err(err)) => (Some(index), err),
+                    Err(_) => (Some(index), Error::Timeout),
+                },
+                None => (None, Error::NotConnected),
+            };
+            if !CallError::from(&err).is_transient() {
+                return Err(err);
+            }
+
+            if let Some(index) = index {
+                self.record_health(index, None);
+            }
+            self.failover(index).await;
+            if !self.options.retry_policy.should_retry(op, attempt) {
+                return Err(err);
+            }
+            trace!("GRPC pool: retrying {op:?} after error {err}");
+            sleep(self.options.retry_policy.backoff(attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    async fn mutate_subscription(&self, command: Command, scope: &Scope) -> RpcResult<()> {
+        self.subscriptions.lock().await[scope.event_type()].mutate(
+            Mutation::new(command, scope.clone()),
+            self.policies,
+            &self.subscription_context,
+        )?;
+        Ok(())
+    }
+
+    /// Launch a task that periodically checks the health of the endpoints and
+    /// fails over to another endpoint if the active one is unhealthy.
+    fn spawn_health_monitor(self: Arc<Self>) {
+        // The task can only be spawned once
+        if self.monitor_is_running.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+            trace!("GRPC pool: health monitor task - spawn ignored since already spawned");
+            return;
+        }
+
+        tokio::spawn(async move {
+            trace!("GRPC pool: health monitor task - started");
+            let shutdown = self.monitor_shutdown.request.listener.clone().fuse();
+            pin_mut!(shutdown);
+            loop {
+                let delay = sleep(self.options.health_check_interval).fuse();
+                pin_mut!(delay);
+                select! {
+                    _ = shutdown => { break; },
+                    _ = delay => {
+                        self.check_all().await;
+                        let active = *self.active.lock();
+                        if active.map_or(true, |index| self.health(index).is_failed()) {
+                            self.failover(active).await;
+                        }
+                    },
+                }
+            }
+            self.monitor_is_running.store(false, Ordering::SeqCst);
+            self.monitor_shutdown.response.trigger.trigger();
+            trace!("GRPC pool: health monitor task - terminated");
+        });
+    }
+
+    async fn stop_health_monitor(&self) {
+        if self.monitor_is_running.load(Ordering::SeqCst) {
+            self.monitor_shutdown.request.trigger.trigger();
+            self.monitor_shutdown.response.listener.clone().await;
+        }
+    }
+}
+
+/// A gRPC client routing its calls to a pool of kaspad nodes, see the [module documentation](self).
+///
+/// The notifications are delivered in direct mode, via the channel returned by
+/// [`notification_channel_receiver`](Self::notification_channel_receiver).
+#[derive(Clone)]
+pub struct GrpcPool {
+    inner: Arc<Inner>,
+}
+
+impl GrpcPool {
+    /// Connects to the endpoints at `urls`, given by order of preference, and activates the first
+    /// reachable one. Fails if none of the endpoints is reachable.
+    pub async fn connect(urls: Vec<String>, options: PoolOptions) -> Result<Self> {
+        if urls.is_empty() {
+            return Err(Error::String("a GRPC pool requires at least one endpoint".to_string()));
+        }
+        if let Some(url) = urls.iter().find(|url| !url.starts_with("grpc://")) {
+            return Err(Error::GrpcAddressSchema(url.clone()));
+        }
+
+        let endpoints = urls.into_iter().map(|url| Endpoint { url, client: Default::default(), health: Default::default() }).collect();
+        let subscription_context = options.subscription_context.clone().unwrap_or_default();
+        let inner = Arc::new(Inner {
+            endpoints,
+            options,
+            active: Default::default(),
+            was_active: AtomicBool::new(false),
+            failover_lock: Default::default(),
+            subscriptions: tokio::sync::Mutex::new(ArrayBuilder::single(GrpcClient::DIRECT_MODE_LISTENER_ID, None)),
+            subscription_context,
+            policies: MutationPolicies::new(UtxosChangedMutationPolicy::AddressSet),
+            notification_channel: Channel::default(),
+            events: Channel::default(),
+            monitor_is_running: AtomicBool::new(false),
+            monitor_shutdown: DuplexTrigger::new(),
+        });
+
+        inner.check_all().await;
+        if !inner.failover(None).await {
+            for index in 0..inner.endpoints.len() {
+                inner.release(index).await;
+            }
+            return Err(Error::NotConnected);
+        }
+        inner.clone().spawn_health_monitor();
+
+        Ok(Self { inner })
+    }
+
+    /// Urls of the endpoints, by order of preference
+    pub fn urls(&self) -> Vec<String> {
+        self.inner.endpoints.iter().map(|endpoint| endpoint.url.clone()).collect()
+    }
+
+    /// Url of the endpoint the calls are routed to
+    pub fn active_url(&self) -> Option<String> {
+        (*self.inner.active.lock()).map(|index| self.inner.endpoints[index].url.clone())
+    }
+
+    /// The client of the endpoint the calls are routed to
+    pub fn active_client(&self) -> Option<Arc<GrpcClient>> {
+        self.inner.active_client().map(|(_, client)| client)
+    }
+
+    pub fn health(&self, url: &str) -> Option<EndpointHealth> {
+        self.inner.endpoints.iter().position(|endpoint| endpoint.url == url).map(|index| self.inner.health(index))
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.active_client().is_some_and(|client| client.is_connected())
+    }
+
+    /// Notifications of the subscriptions, received from the active endpoint
+    pub fn notification_channel_receiver(&self) -> Receiver<Notification> {
+        self.inner.notification_channel.receiver()
+    }
+
+    /// Connection events of the pool
+    pub fn event_receiver(&self) -> Receiver<PoolEvent> {
+        self.inner.events.receiver()
+    }
+
+    /// Stops the health monitor and disconnects from all endpoints
+    pub async fn disconnect(&self) -> Result<()> {
+        self.inner.stop_health_monitor().await;
+        *self.inner.active.lock() = None;
+        for index in 0..self.inner.endpoints.len() {
+            self.inner.release(index).await;
+        }
+        self.inner.notification_channel.close();
+        self.inner.events.close();
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RpcApi for GrpcPool {
+    route!(ping_call, Ping);
+    route!(get_sync_status_call, GetSyncStatus);
+    route!(get_server_info_call, GetServerInfo);
+    route!(get_metrics_call, GetMetrics);
+    route!(submit_block_call, SubmitBlock);
+    route!(get_block_template_call, GetBlockTemplate);
+    route!(get_block_call, GetBlock);
+    route!(get_info_call, GetInfo);
+    route!(get_current_network_call, GetCurrentNetwork);
+    route!(get_peer_addresses_call, GetPeerAddresses);
+    route!(get_sink_call, GetSink);
+    route!(get_mempool_entry_call, GetMempoolEntry);
+    route!(get_mempool_entries_call, GetMempoolEntries);
+    route!(get_connected_peer_info_call, GetConnectedPeerInfo);
+    route!(add_peer_call, AddPeer);
+    route!(submit_transaction_call, SubmitTransaction);
+    route!(get_subnetwork_call, GetSubnetwork);
+    route!(get_virtual_chain_from_block_call, GetVirtualChainFromBlock);
+    route!(get_blocks_call, GetBlocks);
+    route!(get_block_count_call, GetBlockCount);
+    route!(get_block_dag_info_call, GetBlockDagInfo);
+    route!(resolve_finality_conflict_call, ResolveFinalityConflict);
+    route!(shutdown_call, Shutdown);
+    route!(get_headers_call, GetHeaders);
+    route!(get_utxos_by_addresses_call, GetUtxosByAddresses);
+    route!(get_balance_by_address_call, GetBalanceByAddress);
+    route!(get_balances_by_addresses_call, GetBalancesByAddresses);
+    route!(get_sink_blue_score_call, GetSinkBlueScore);
+    route!(ban_call, Ban);
+    route!(unban_call, Unban);
+    route!(estimate_network_hashes_per_second_call, EstimateNetworkHashesPerSecond);
+    route!(get_mempool_entries_by_addresses_call, GetMempoolEntriesByAddresses);
+    route!(get_coin_supply_call, GetCoinSupply);
+    route!(get_daa_score_timestamp_estimate_call, GetDaaScoreTimestampEstimate);
+    route!(get_chain_block_by_daa_score_call, GetChainBlockByDaaScore);
+    route!(set_log_filters_call, SetLogFilters);
+    route!(get_outpoint_spend_status_call, GetOutpointSpendStatus);
+    route!(estimate_transaction_confirmation_time_call, EstimateTransactionConfirmationTime);
+    route!(get_acceptance_proof_call, GetAcceptanceProof);
+    route!(create_backup_call, CreateBackup);
+    route!(get_virtual_parent_selection_trace_call, GetVirtualParentSelectionTrace);
+    route!(get_system_info_call, GetSystemInfo);
+    route!(remove_peer_call, RemovePeer);
+    route!(set_processing_parallelism_call, SetProcessingParallelism);
+    route!(get_address_activity_call, GetAddressActivity);
+    route!(get_missed_notifications_call, GetMissedNotifications);
+    route!(get_utxo_return_address_call, GetUtxoReturnAddress);
+    route!(validate_block_template_call, ValidateBlockTemplate);
+    route!(get_virtual_info_call, GetVirtualInfo);
+
+    // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Notification API
+
+    /// Listener registration is ignored, the pool having a single listener (see [`GrpcPool`])
+    fn register_new_listener(&self, _: ChannelConnection) -> ListenerId {
+        GrpcClient::DIRECT_MODE_LISTENER_ID
+    }
+
+    async fn unregister_listener(&self, _: ListenerId) -> RpcResult<()> {
+        Ok(())
+    }
+
+    /// Start sending notifications of some type to the listener. The subscription is retained
+    /// by the pool, so a subscription failing to reach an unavailable endpoint is replayed on
+    /// the endpoint the pool fails over to.
+    async fn start_notify(&self, id: ListenerId, scope: Scope) -> RpcResult<()> {
+        self.inner.mutate_subscription(Command::Start, &scope).await?;
+        if let Some((index, client)) = self.inner.active_client() {
+            if let Err(err) = client.start_notify(id, scope).await {
+                if !CallError::from(&err).is_transient() {
+                    return Err(err);
+                }
+                self.inner.record_health(index, None);
+                self.inner.failover(Some(index)).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop sending notifications of some type to the listener
+    async fn stop_notify(&self, id: ListenerId, scope: Scope) -> RpcResult<()> {
+        self.inner.mutate_subscription(Command::Stop, &scope).await?;
+        if let Some((_, client)) = self.inner.active_client() {
+            if let Err(err) = client.stop_notify(id, scope).await {
+                // The subscription is dropped anyway with the connection to an unavailable endpoint
+                if !CallError::from(&err).is_transient() {
+                    return Err(err);
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+use kaspa_grpc_core::ops::KaspadPayloadOps;
+use std::time::Duration;
+
+/// Retry policy of the calls issued through a [`GrpcPool`](crate::pool::GrpcPool).
+///
+/// Only the calls of [retry safe](is_retry_safe) methods are retried, and only when
+/// they fail because the node is unavailable (not connected, transport error or timeout).
+/// Errors returned by the node itself are never retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts of a call, including the first one
+    pub max_attempts: u32,
+
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+
+    /// Upper bound of the delay between two attempts
+    pub max_backoff: Duration,
+
+    /// Factor applied to the delay after each retry
+    pub multiplier: u32,
+}
+
+impl RetryPolicy {
+    pub const DEFAULT_MAX_ATTEMPTS: u32 = 4;
+    pub const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+    pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(2);
+    pub const DEFAULT_MULTIPLIER: u32 = 2;
+
+    /// A policy issuing every call exactly once
+    pub fn no_retry() -> Self {
+        Self { max_attempts: 1, ..Default::default() }
+    }
+
+    /// The delay before the attempt following attempt number `attempt` (starting at 0)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = self.multiplier.max(1).saturating_pow(attempt);
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+
+    /// `true` if a call of `op` that failed at attempt number `attempt` (starting at 0)
+    /// because of a transient failure should be attempted again
+    pub fn should_retry(&self, op: KaspadPayloadOps, attempt: u32) -> bool {
+        is_retry_safe(op) && attempt + 1 < self.max_attempts
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: Self::DEFAULT_MAX_ATTEMPTS,
+            initial_backoff: Self::DEFAULT_INITIAL_BACKOFF,
+            max_backoff: Self::DEFAULT_MAX_BACKOFF,
+            multiplier: Self::DEFAULT_MULTIPLIER,
+        }
+    }
+}
+
+/// Returns `true` if a call of `op` can be issued again after a transient failure,
+/// possibly to another node of the pool.
+///
+/// Retry safe methods are the read-only ones, having no effect on the node. Note that a
+/// retried call may be served by another node, whose view of the DAG can slightly differ.
+///
+/// The following methods are never retried:
+///
+/// - `SubmitBlock` and `SubmitTransaction`: a timed out submission may have reached the node.
+///   `SubmitTransaction` reports resubmissions of known transactions with an `AlreadyKnown`
+///   or `AlreadyAccepted` status, so an application can safely resubmit on its own.
+/// - `GetBlockTemplate`: a template must be requested afresh by the miner.
+/// - `AddPeer`, `RemovePeer`, `Ban`, `Unban`, `ResolveFinalityConflict`, `SetLogFilters`,
+///   `SetProcessingParallelism`, `CreateBackup` and `Shutdown`: they change the state of the node.
+/// - Subscription commands: subscriptions are replayed by the pool on failover instead.
+pub fn is_retry_safe(op: KaspadPayloadOps) -> bool {
+    use KaspadPayloadOps::*;
+    match op {
+        Ping
+        | GetSyncStatus
+        | GetServerInfo
+        | GetMetrics
+        | GetCurrentNetwork
+        | GetBlock
+        | GetBlocks
+        | GetInfo
+        | GetPeerAddresses
+        | GetSink
+        | GetMempoolEntry
+        | GetMempoolEntries
+        | GetConnectedPeerInfo
+        | GetSubnetwork
+        | GetVirtualChainFromBlock
+        | GetBlockCount
+        | GetBlockDagInfo
+        | GetHeaders
+        | GetUtxosByAddresses
+        | GetBalanceByAddress
+        | GetBalancesByAddresses
+        | GetSinkBlueScore
+        | EstimateNetworkHashesPerSecond
+        | GetMempoolEntriesByAddresses
+        | GetCoinSupply
+        | GetDaaScoreTimestampEstimate
+        | GetChainBlockByDaaScore
+        | GetOutpointSpendStatus
+        | EstimateTransactionConfirmationTime
+        | GetAcceptanceProof
+        | GetVirtualParentSelectionTrace
+        | GetSystemInfo
+        | GetVirtualInfo
+        | GetAddressActivity
+        | GetMissedNotifications
+        | GetUtxoReturnAddress
+        | ValidateBlockTemplate => true,
+
+        SubmitBlock
+        | SubmitTransaction
+        | GetBlockTemplate
+        | AddPeer
+        | RemovePeer
+        | Ban
+        | Unban
+        | ResolveFinalityConflict
+        | SetLogFilters
+        | SetProcessingParallelism
+        | CreateBackup
+        | Shutdown => false,
+
+        NotifyBlockAdded
+        | NotifyNewBlockTemplate
+        | NotifyFinalityConflict
+        | NotifyUtxosChanged
+        | NotifySinkBlueScoreChanged
+        | NotifyPruningPointUtxoSetOverride
+        | NotifyVirtualDaaScoreChanged
+        | NotifyVirtualChainChanged
+        | NotifyTransactionConflict
+        | StopNotifyingUtxosChanged
+        | StopNotifyingPruningPointUtxoSetOverride => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_policy_backoff() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(800));
+        assert_eq!(policy.backoff(5), policy.max_backoff);
+        assert_eq!(policy.backoff(u32::MAX), policy.max_backoff);
+
+        assert!(policy.should_retry(KaspadPayloadOps::GetInfo, 0));
+        assert!(policy.should_retry(KaspadPayloadOps::GetInfo, 2));
+        assert!(!policy.should_retry(KaspadPayloadOps::GetInfo, 3));
+        assert!(!policy.should_retry(KaspadPayloadOps::SubmitTransaction, 0));
+        assert!(!RetryPolicy::no_retry().should_retry(KaspadPayloadOps::GetInfo, 0));
+    }
+}
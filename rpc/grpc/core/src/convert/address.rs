@@ -10,6 +10,15 @@ from!(item: &kaspa_rpc_core::RpcBalancesByAddressesEntry, protowire::RpcBalances
     Self { address: (&item.address).into(), balance: item.balance.unwrap_or_default(), error: None }
 });
 
+from!(item: &kaspa_rpc_core::RpcAddressActivityEntry, protowire::RpcAddressActivityEntry, {
+    Self {
+        address: (&item.address).into(),
+        has_received: item.has_received,
+        first_seen_daa_score: item.first_seen_daa_score,
+        last_activity_daa_score: item.last_activity_daa_score,
+    }
+});
+
 // ----------------------------------------------------------------------------
 // protowire to rpc_core
 // ----------------------------------------------------------------------------
@@ -18,3 +27,12 @@ try_from!(item: &protowire::RpcBalancesByAddressesEntry, kaspa_rpc_core::RpcBala
     let balance = if item.error.is_some() { None } else { Some(item.balance) };
     Self { address: item.address.as_str().try_into()?, balance }
 });
+
+try_from!(item: &protowire::RpcAddressActivityEntry, kaspa_rpc_core::RpcAddressActivityEntry, {
+    Self {
+        address: item.address.as_str().try_into()?,
+        has_received: item.has_received,
+        first_seen_daa_score: item.first_seen_daa_score,
+        last_activity_daa_score: item.last_activity_daa_score,
+    }
+});
@@ -1,6 +1,6 @@
 use crate::protowire;
 use crate::{from, try_from};
-use kaspa_rpc_core::{RpcError, RpcHash};
+use kaspa_rpc_core::{FromRpcHex, RpcBlueWorkType, RpcDifficultyTarget, RpcError, RpcHash, ToRpcHex};
 use std::str::FromStr;
 
 // ----------------------------------------------------------------------------
@@ -27,6 +27,43 @@ from!(item: &kaspa_rpc_core::RpcBlockVerboseData, protowire::RpcBlockVerboseData
         merge_set_blues_hashes: item.merge_set_blues_hashes.iter().map(|x| x.to_string()).collect(),
         merge_set_reds_hashes: item.merge_set_reds_hashes.iter().map(|x| x.to_string()).collect(),
         is_chain_block: item.is_chain_block,
+        target: item.target.to_rpc_hex(),
+        work: item.work.to_rpc_hex(),
+        network_hashes_per_second: item.network_hashes_per_second.unwrap_or_default(),
+        propagation: item.propagation.as_ref().map(|x| x.into()),
+    }
+});
+
+from!(item: &kaspa_rpc_core::RpcBlockPropagation, protowire::RpcBlockPropagation, {
+    Self {
+        first_inv_time: item.first_inv_time.unwrap_or_default(),
+        header_time: item.header_time.unwrap_or_default(),
+        validated_time: item.validated_time.unwrap_or_default(),
+    }
+});
+
+from!(item: &kaspa_rpc_core::RpcBlockTemplateStats, protowire::RpcBlockTemplateStats, {
+    Self {
+        total_fees: item.total_fees,
+        total_mass: item.total_mass,
+        max_block_mass: item.max_block_mass,
+        considered_count: item.considered_count,
+        selected_count: item.selected_count,
+        min_feerate: item.min_feerate,
+    }
+});
+
+from!(item: &kaspa_rpc_core::RpcExcludedVirtualParent, protowire::RpcExcludedVirtualParent, {
+    Self { hash: item.hash.to_string(), reason: item.reason.to_string() }
+});
+
+from!(item: &kaspa_rpc_core::RpcVirtualInfo, protowire::RpcVirtualInfo, {
+    Self {
+        daa_score: item.daa_score,
+        sink: item.sink.to_string(),
+        sink_blue_score: item.sink_blue_score,
+        parents_count: item.parents_count,
+        past_median_time: item.past_median_time,
     }
 });
 
@@ -74,5 +111,45 @@ try_from!(item: &protowire::RpcBlockVerboseData, kaspa_rpc_core::RpcBlockVerbose
             .map(|x| RpcHash::from_str(x))
             .collect::<Result<Vec<kaspa_rpc_core::RpcHash>, faster_hex::Error>>()?,
         is_chain_block: item.is_chain_block,
+        target: RpcDifficultyTarget::from_rpc_hex(&item.target)?,
+        work: RpcBlueWorkType::from_rpc_hex(&item.work)?,
+        network_hashes_per_second: (item.network_hashes_per_second > 0).then_some(item.network_hashes_per_second),
+        propagation: item.propagation.as_ref().map(kaspa_rpc_core::RpcBlockPropagation::from),
+    }
+});
+
+from!(item: &protowire::RpcBlockPropagation, kaspa_rpc_core::RpcBlockPropagation, {
+    Self {
+        first_inv_time: (item.first_inv_time > 0).then_some(item.first_inv_time),
+        header_time: (item.header_time > 0).then_some(item.header_time),
+        validated_time: (item.validated_time > 0).then_some(item.validated_time),
+    }
+});
+
+from!(item: &protowire::RpcBlockTemplateStats, kaspa_rpc_core::RpcBlockTemplateStats, {
+    Self {
+        total_fees: item.total_fees,
+        total_mass: item.total_mass,
+        max_block_mass: item.max_block_mass,
+        considered_count: item.considered_count,
+        selected_count: item.selected_count,
+        min_feerate: item.min_feerate,
+    }
+});
+
+try_from!(item: &protowire::RpcExcludedVirtualParent, kaspa_rpc_core::RpcExcludedVirtualParent, {
+    Self {
+        hash: RpcHash::from_str(&item.hash)?,
+        reason: kaspa_rpc_core::RpcVirtualParentExclusionReason::from_str(&item.reason).map_err(RpcError::General)?,
+    }
+});
+
+try_from!(item: &protowire::RpcVirtualInfo, kaspa_rpc_core::RpcVirtualInfo, {
+    Self {
+        daa_score: item.daa_score,
+        sink: RpcHash::from_str(&item.sink)?,
+        sink_blue_score: item.sink_blue_score,
+        parents_count: item.parents_count,
+        past_median_time: item.past_median_time,
     }
 });
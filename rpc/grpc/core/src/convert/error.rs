@@ -1,15 +1,27 @@
 use crate::from;
 use crate::protowire;
+use kaspa_core::log::trace_id;
 
 // ----------------------------------------------------------------------------
 // rpc_core to protowire
 // ----------------------------------------------------------------------------
 
-from!(item: kaspa_rpc_core::RpcError, protowire::RpcError, { Self { message: item.to_string() } });
-from!(item: &kaspa_rpc_core::RpcError, protowire::RpcError, { Self { message: item.to_string() } });
+from!(item: kaspa_rpc_core::RpcError, protowire::RpcError, { (&item).into() });
+// Errors converted within the scope of an RPC call get the trace id of the call attached
+from!(item: &kaspa_rpc_core::RpcError, protowire::RpcError, {
+    match item {
+        kaspa_rpc_core::RpcError::Traced(message, trace_id) => Self { message: message.clone(), trace_id: trace_id.clone() },
+        _ => Self { message: item.to_string(), trace_id: trace_id::current().map(|x| x.to_string()).unwrap_or_default() },
+    }
+});
 
 // ----------------------------------------------------------------------------
 // protowire to rpc_core
 // ----------------------------------------------------------------------------
 
-from!(item: &protowire::RpcError, kaspa_rpc_core::RpcError, { kaspa_rpc_core::RpcError::from(item.message.to_string()) });
+from!(item: &protowire::RpcError, kaspa_rpc_core::RpcError, {
+    match item.trace_id.is_empty() {
+        true => kaspa_rpc_core::RpcError::from(item.message.to_string()),
+        false => kaspa_rpc_core::RpcError::Traced(item.message.clone(), item.trace_id.clone()),
+    }
+});
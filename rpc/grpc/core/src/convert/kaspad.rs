@@ -2,7 +2,7 @@ use crate::protowire::{kaspad_request, KaspadRequest, KaspadResponse};
 
 impl From<kaspad_request::Payload> for KaspadRequest {
     fn from(item: kaspad_request::Payload) -> Self {
-        KaspadRequest { id: 0, payload: Some(item) }
+        KaspadRequest { id: 0, trace_id: Default::default(), payload: Some(item) }
     }
 }
 
@@ -57,6 +57,21 @@ pub mod kaspad_request_convert {
     impl_into_kaspad_request!(GetServerInfo);
     impl_into_kaspad_request!(GetSyncStatus);
     impl_into_kaspad_request!(GetDaaScoreTimestampEstimate);
+    impl_into_kaspad_request!(GetChainBlockByDaaScore);
+    impl_into_kaspad_request!(SetLogFilters);
+    impl_into_kaspad_request!(GetOutpointSpendStatus);
+    impl_into_kaspad_request!(EstimateTransactionConfirmationTime);
+    impl_into_kaspad_request!(GetAcceptanceProof);
+    impl_into_kaspad_request!(CreateBackup);
+    impl_into_kaspad_request!(GetVirtualParentSelectionTrace);
+    impl_into_kaspad_request!(GetSystemInfo);
+    impl_into_kaspad_request!(RemovePeer);
+    impl_into_kaspad_request!(GetVirtualInfo);
+    impl_into_kaspad_request!(SetProcessingParallelism);
+    impl_into_kaspad_request!(GetAddressActivity);
+    impl_into_kaspad_request!(GetMissedNotifications);
+    impl_into_kaspad_request!(GetUtxoReturnAddress);
+    impl_into_kaspad_request!(ValidateBlockTemplate);
 
     impl_into_kaspad_request!(NotifyBlockAdded);
     impl_into_kaspad_request!(NotifyNewBlockTemplate);
@@ -66,6 +81,7 @@ pub mod kaspad_request_convert {
     impl_into_kaspad_request!(NotifyVirtualDaaScoreChanged);
     impl_into_kaspad_request!(NotifyVirtualChainChanged);
     impl_into_kaspad_request!(NotifySinkBlueScoreChanged);
+    impl_into_kaspad_request!(NotifyTransactionConflict);
 
     macro_rules! impl_into_kaspad_request {
         ($name:tt) => {
@@ -188,6 +204,21 @@ pub mod kaspad_response_convert {
     impl_into_kaspad_response!(GetServerInfo);
     impl_into_kaspad_response!(GetSyncStatus);
     impl_into_kaspad_response!(GetDaaScoreTimestampEstimate);
+    impl_into_kaspad_response!(GetChainBlockByDaaScore);
+    impl_into_kaspad_response!(SetLogFilters);
+    impl_into_kaspad_response!(GetOutpointSpendStatus);
+    impl_into_kaspad_response!(EstimateTransactionConfirmationTime);
+    impl_into_kaspad_response!(GetAcceptanceProof);
+    impl_into_kaspad_response!(CreateBackup);
+    impl_into_kaspad_response!(GetVirtualParentSelectionTrace);
+    impl_into_kaspad_response!(GetSystemInfo);
+    impl_into_kaspad_response!(RemovePeer);
+    impl_into_kaspad_response!(GetVirtualInfo);
+    impl_into_kaspad_response!(SetProcessingParallelism);
+    impl_into_kaspad_response!(GetAddressActivity);
+    impl_into_kaspad_response!(GetMissedNotifications);
+    impl_into_kaspad_response!(GetUtxoReturnAddress);
+    impl_into_kaspad_response!(ValidateBlockTemplate);
 
     impl_into_kaspad_notify_response!(NotifyBlockAdded);
     impl_into_kaspad_notify_response!(NotifyNewBlockTemplate);
@@ -197,6 +228,7 @@ pub mod kaspad_response_convert {
     impl_into_kaspad_notify_response!(NotifyVirtualDaaScoreChanged);
     impl_into_kaspad_notify_response!(NotifyVirtualChainChanged);
     impl_into_kaspad_notify_response!(NotifySinkBlueScoreChanged);
+    impl_into_kaspad_notify_response!(NotifyTransactionConflict);
 
     impl_into_kaspad_notify_response!(NotifyUtxosChanged, StopNotifyingUtxosChanged);
     impl_into_kaspad_notify_response!(NotifyPruningPointUtxoSetOverride, StopNotifyingPruningPointUtxoSetOverride);
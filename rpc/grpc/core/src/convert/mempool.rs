@@ -1,13 +1,23 @@
-use crate::protowire;
+use crate::protowire::{self, rpc_acceptance_check::Detail};
 use crate::{from, try_from};
-use kaspa_rpc_core::RpcError;
+use kaspa_rpc_core::{RpcAcceptanceDetail, RpcError};
+use std::str::FromStr;
 
 // ----------------------------------------------------------------------------
 // rpc_core to protowire
 // ----------------------------------------------------------------------------
 
 from!(item: &kaspa_rpc_core::RpcMempoolEntry, protowire::RpcMempoolEntry, {
-    Self { fee: item.fee, transaction: Some((&item.transaction).into()), is_orphan: item.is_orphan }
+    Self { fee: item.fee, transaction: Some((&item.transaction).into()), is_orphan: item.is_orphan, is_held: item.is_held }
+});
+
+from!(item: &kaspa_rpc_core::RpcMempoolSummary, protowire::RpcMempoolSummary, {
+    Self {
+        transaction_count: item.transaction_count,
+        total_mass: item.total_mass,
+        total_fees: item.total_fees,
+        timestamp: item.timestamp,
+    }
 });
 
 from!(item: &kaspa_rpc_core::RpcMempoolEntryByAddress, protowire::RpcMempoolEntryByAddress, {
@@ -18,6 +28,53 @@ from!(item: &kaspa_rpc_core::RpcMempoolEntryByAddress, protowire::RpcMempoolEntr
     }
 });
 
+from!(item: &kaspa_rpc_core::RpcConfirmationTimeEstimate, protowire::RpcConfirmationTimeEstimate, {
+    Self {
+        package_feerate: item.package_feerate,
+        transactions_ahead: item.transactions_ahead,
+        mass_ahead: item.mass_ahead,
+        block_utilization: item.block_utilization,
+        block_interval: item.block_interval,
+        min_blocks: item.min_blocks,
+        max_blocks: item.max_blocks,
+        min_time: item.min_time,
+        max_time: item.max_time,
+    }
+});
+
+from!(item: &kaspa_rpc_core::RpcDustThreshold, protowire::RpcDustThreshold, {
+    Self { script_class: item.script_class.to_string(), min_output_value: item.min_output_value }
+});
+
+from!(item: &kaspa_rpc_core::RpcAcceptanceCheck, protowire::RpcAcceptanceCheck, {
+    let detail = item.detail.as_ref().map(|detail| match detail {
+        RpcAcceptanceDetail::Mass { mass, limit } => Detail::Mass(protowire::RpcAcceptanceMassDetail { mass: *mass, limit: *limit }),
+        RpcAcceptanceDetail::Fee { required, provided } => {
+            Detail::Fee(protowire::RpcAcceptanceFeeDetail { required: *required, provided: *provided })
+        }
+        RpcAcceptanceDetail::MissingOutpoints(outpoints) => Detail::MissingOutpoints(protowire::RpcAcceptanceMissingOutpointsDetail {
+            outpoints: outpoints.iter().map(|x| x.into()).collect(),
+        }),
+        RpcAcceptanceDetail::MaturityShortfall { input_index, shortfall } => Detail::MaturityShortfall(
+            protowire::RpcAcceptanceMaturityShortfallDetail { input_index: *input_index, shortfall: *shortfall },
+        ),
+        RpcAcceptanceDetail::Dust { output_index, value, threshold } => {
+            Detail::Dust(protowire::RpcAcceptanceDustDetail { output_index: *output_index, value: *value, threshold: *threshold })
+        }
+    });
+    Self {
+        rule: item.rule.clone(),
+        contextual: item.contextual,
+        outcome: item.outcome.as_str().to_string(),
+        error: item.error.clone().unwrap_or_default(),
+        detail,
+    }
+});
+
+from!(item: &kaspa_rpc_core::RpcTransactionAcceptanceTrace, protowire::RpcTransactionAcceptanceTrace, {
+    Self { error: item.error.clone(), checks: item.checks.iter().map(|x| x.into()).collect() }
+});
+
 // ----------------------------------------------------------------------------
 // protowire to rpc_core
 // ----------------------------------------------------------------------------
@@ -30,9 +87,19 @@ try_from!(item: &protowire::RpcMempoolEntry, kaspa_rpc_core::RpcMempoolEntry, {
             .ok_or_else(|| RpcError::MissingRpcFieldError("RpcMempoolEntry".to_string(), "transaction".to_string()))?
             .try_into()?,
         item.is_orphan,
+        item.is_held,
     )
 });
 
+try_from!(item: &protowire::RpcMempoolSummary, kaspa_rpc_core::RpcMempoolSummary, {
+    Self {
+        transaction_count: item.transaction_count,
+        total_mass: item.total_mass,
+        total_fees: item.total_fees,
+        timestamp: item.timestamp,
+    }
+});
+
 try_from!(item: &protowire::RpcMempoolEntryByAddress, kaspa_rpc_core::RpcMempoolEntryByAddress, {
     Self::new(
         item.address.as_str().try_into()?,
@@ -40,3 +107,50 @@ try_from!(item: &protowire::RpcMempoolEntryByAddress, kaspa_rpc_core::RpcMempool
         item.receiving.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?,
     )
 });
+
+try_from!(item: &protowire::RpcConfirmationTimeEstimate, kaspa_rpc_core::RpcConfirmationTimeEstimate, {
+    Self {
+        package_feerate: item.package_feerate,
+        transactions_ahead: item.transactions_ahead,
+        mass_ahead: item.mass_ahead,
+        block_utilization: item.block_utilization,
+        block_interval: item.block_interval,
+        min_blocks: item.min_blocks,
+        max_blocks: item.max_blocks,
+        min_time: item.min_time,
+        max_time: item.max_time,
+    }
+});
+
+try_from!(item: &protowire::RpcDustThreshold, kaspa_rpc_core::RpcDustThreshold, {
+    Self { script_class: item.script_class.as_str().try_into()?, min_output_value: item.min_output_value }
+});
+
+try_from!(item: &protowire::RpcAcceptanceCheck, kaspa_rpc_core::RpcAcceptanceCheck, {
+    let detail = match item.detail.as_ref() {
+        Some(Detail::Mass(detail)) => Some(RpcAcceptanceDetail::Mass { mass: detail.mass, limit: detail.limit }),
+        Some(Detail::Fee(detail)) => Some(RpcAcceptanceDetail::Fee { required: detail.required, provided: detail.provided }),
+        Some(Detail::MissingOutpoints(detail)) => Some(RpcAcceptanceDetail::MissingOutpoints(
+            detail.outpoints.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?,
+        )),
+        Some(Detail::MaturityShortfall(detail)) => {
+            Some(RpcAcceptanceDetail::MaturityShortfall { input_index: detail.input_index, shortfall: detail.shortfall })
+        }
+        Some(Detail::Dust(detail)) => {
+            Some(RpcAcceptanceDetail::Dust { output_index: detail.output_index, value: detail.value, threshold: detail.threshold })
+        }
+        None => None,
+    };
+    Self {
+        rule: item.rule.clone(),
+        contextual: item.contextual,
+        // Outcomes unknown to this version, as reported by newer nodes, are mapped to `Unknown`
+        outcome: kaspa_rpc_core::RpcAcceptanceOutcome::from_str(&item.outcome).unwrap_or(kaspa_rpc_core::RpcAcceptanceOutcome::Unknown),
+        error: if item.error.is_empty() { None } else { Some(item.error.clone()) },
+        detail,
+    }
+});
+
+try_from!(item: &protowire::RpcTransactionAcceptanceTrace, kaspa_rpc_core::RpcTransactionAcceptanceTrace, {
+    Self { error: item.error.clone(), checks: item.checks.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()? }
+});
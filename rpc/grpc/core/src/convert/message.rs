@@ -18,12 +18,19 @@
 //!
 //! The SubmitBlockResponse is a notable exception to this general rule.
 
-use crate::protowire::{self, submit_block_response_message::RejectReason};
+use crate::protowire::{
+    self,
+    get_missed_notifications_request_message::Scope as MissedNotificationsScope,
+    submit_block_response_message::{RejectReason, SubmissionClassification},
+};
 use kaspa_consensus_core::network::NetworkId;
 use kaspa_core::debug;
-use kaspa_notify::subscription::Command;
+use kaspa_notify::{
+    scope::{Scope, UtxosChangedScope, VirtualChainChangedScope},
+    subscription::Command,
+};
 use kaspa_rpc_core::{
-    RpcContextualPeerAddress, RpcError, RpcExtraData, RpcHash, RpcIpAddress, RpcNetworkType, RpcPeerAddress, RpcResult,
+    RpcContextualPeerAddress, RpcError, RpcExtraData, RpcHash, RpcIpAddress, RpcKnownPeerAddress, RpcNetworkType, RpcResult,
     SubmitBlockRejectReason, SubmitBlockReport,
 };
 use std::str::FromStr;
@@ -136,6 +143,15 @@ from!(item: &kaspa_rpc_core::SubmitBlockReport, RejectReason, {
     }
 });
 
+from!(item: Option<kaspa_rpc_core::SubmitBlockClassification>, SubmissionClassification, {
+    match item {
+        None => SubmissionClassification::Unclassified,
+        Some(kaspa_rpc_core::SubmitBlockClassification::Fresh) => SubmissionClassification::Fresh,
+        Some(kaspa_rpc_core::SubmitBlockClassification::Stale) => SubmissionClassification::Stale,
+        Some(kaspa_rpc_core::SubmitBlockClassification::AlreadyProcessed) => SubmissionClassification::AlreadyProcessed,
+    }
+});
+
 from!(item: &kaspa_rpc_core::SubmitBlockRequest, protowire::SubmitBlockRequestMessage, {
     Self { block: Some((&item.block).into()), allow_non_daa_blocks: item.allow_non_daa_blocks }
 });
@@ -148,7 +164,13 @@ from!(item: RpcResult<&kaspa_rpc_core::SubmitBlockResponse>, protowire::SubmitBl
         kaspa_rpc_core::SubmitBlockReport::Success => None,
         kaspa_rpc_core::SubmitBlockReport::Reject(reason) => Some(RpcError::SubmitBlockError(reason).into())
     };
-    Self { reject_reason: RejectReason::from(&item.report) as i32, error }
+    Self {
+        reject_reason: RejectReason::from(&item.report) as i32,
+        submission_classification: SubmissionClassification::from(item.submission_classification) as i32,
+        non_canonical_transaction_order: item.non_canonical_transaction_order,
+        rejection_detail: item.rejection_detail.clone().unwrap_or_default(),
+        error,
+    }
 });
 
 from!(item: &kaspa_rpc_core::GetBlockTemplateRequest, protowire::GetBlockTemplateRequestMessage, {
@@ -158,11 +180,20 @@ from!(item: &kaspa_rpc_core::GetBlockTemplateRequest, protowire::GetBlockTemplat
     }
 });
 from!(item: RpcResult<&kaspa_rpc_core::GetBlockTemplateResponse>, protowire::GetBlockTemplateResponseMessage, {
-    Self { block: Some((&item.block).into()), is_synced: item.is_synced, error: None }
+    Self {
+        block: Some((&item.block).into()),
+        is_synced: item.is_synced,
+        selection_stats: item.selection_stats.as_ref().map(|x| x.into()),
+        error: None,
+    }
 });
 
 from!(item: &kaspa_rpc_core::GetBlockRequest, protowire::GetBlockRequestMessage, {
-    Self { hash: item.hash.to_string(), include_transactions: item.include_transactions }
+    Self {
+        hash: item.hash.to_string(),
+        include_transactions: item.include_transactions,
+        transaction_field_mask: item.transaction_field_mask.bits(),
+    }
 });
 from!(item: RpcResult<&kaspa_rpc_core::GetBlockResponse>, protowire::GetBlockResponseMessage, {
     Self { block: Some((&item.block).into()), error: None }
@@ -183,15 +214,49 @@ from!(item: RpcResult<&kaspa_rpc_core::GetInfoResponse>, protowire::GetInfoRespo
         is_synced: item.is_synced,
         has_notify_command: item.has_notify_command,
         has_message_id: item.has_message_id,
+        params_digest: item.params_digest.map(|x| x.to_string()).unwrap_or_default(),
+        dust_thresholds: item.dust_thresholds.iter().map(|x| x.into()).collect(),
+        consensus_status: item.consensus_status.as_ref().map(|x| x.into()),
         error: None,
     }
 });
 
+from!(item: &kaspa_rpc_core::RpcConsensusEntry, protowire::RpcConsensusEntry, {
+    Self {
+        key: item.key,
+        directory_name: item.directory_name.clone(),
+        creation_timestamp: item.creation_timestamp,
+        creation_reason: item.creation_reason.to_string(),
+    }
+});
+
+from!(item: &kaspa_rpc_core::RpcRetiredConsensusEntry, protowire::RpcRetiredConsensusEntry, {
+    Self {
+        entry: Some((&item.entry).into()),
+        retirement_timestamp: item.retirement_timestamp.unwrap_or_default(),
+        disk_usage: item.disk_usage,
+    }
+});
+
+from!(item: &kaspa_rpc_core::RpcConsensusStatus, protowire::RpcConsensusStatus, {
+    Self {
+        current: item.current.as_ref().map(|x| x.into()),
+        staging: item.staging.as_ref().map(|x| x.into()),
+        is_staging_prepared: item.is_staging_prepared,
+        retired: item.retired.iter().map(|x| x.into()).collect(),
+    }
+});
+
 from!(item: &kaspa_rpc_core::NotifyNewBlockTemplateRequest, protowire::NotifyNewBlockTemplateRequestMessage, {
     Self { command: item.command.into() }
 });
 from!(RpcResult<&kaspa_rpc_core::NotifyNewBlockTemplateResponse>, protowire::NotifyNewBlockTemplateResponseMessage);
 
+from!(item: &kaspa_rpc_core::NotifyTransactionConflictRequest, protowire::NotifyTransactionConflictRequestMessage, {
+    Self { addresses: item.addresses.iter().map(|x| x.into()).collect(), command: item.command.into() }
+});
+from!(RpcResult<&kaspa_rpc_core::NotifyTransactionConflictResponse>, protowire::NotifyTransactionConflictResponseMessage);
+
 // ~~~
 
 from!(&kaspa_rpc_core::GetCurrentNetworkRequest, protowire::GetCurrentNetworkRequestMessage);
@@ -199,7 +264,14 @@ from!(item: RpcResult<&kaspa_rpc_core::GetCurrentNetworkResponse>, protowire::Ge
     Self { current_network: item.network.to_string(), error: None }
 });
 
-from!(&kaspa_rpc_core::GetPeerAddressesRequest, protowire::GetPeerAddressesRequestMessage);
+from!(item: &kaspa_rpc_core::GetPeerAddressesRequest, protowire::GetPeerAddressesRequestMessage, {
+    Self {
+        only_tried: item.only_tried,
+        only_new: item.only_new,
+        group_prefix: item.group_prefix.clone().unwrap_or_default(),
+        limit: item.limit.unwrap_or_default(),
+    }
+});
 from!(item: RpcResult<&kaspa_rpc_core::GetPeerAddressesResponse>, protowire::GetPeerAddressesResponseMessage, {
     Self {
         addresses: item.known_addresses.iter().map(|x| x.into()).collect(),
@@ -225,10 +297,14 @@ from!(item: RpcResult<&kaspa_rpc_core::GetMempoolEntryResponse>, protowire::GetM
 });
 
 from!(item: &kaspa_rpc_core::GetMempoolEntriesRequest, protowire::GetMempoolEntriesRequestMessage, {
-    Self { include_orphan_pool: item.include_orphan_pool, filter_transaction_pool: item.filter_transaction_pool }
+    Self {
+        include_orphan_pool: item.include_orphan_pool,
+        filter_transaction_pool: item.filter_transaction_pool,
+        consistent_snapshot: item.consistent_snapshot,
+    }
 });
 from!(item: RpcResult<&kaspa_rpc_core::GetMempoolEntriesResponse>, protowire::GetMempoolEntriesResponseMessage, {
-    Self { entries: item.mempool_entries.iter().map(|x| x.into()).collect(), error: None }
+    Self { entries: item.mempool_entries.iter().map(|x| x.into()).collect(), summary: Some((&item.summary).into()), error: None }
 });
 
 from!(&kaspa_rpc_core::GetConnectedPeerInfoRequest, protowire::GetConnectedPeerInfoRequestMessage);
@@ -241,11 +317,97 @@ from!(item: &kaspa_rpc_core::AddPeerRequest, protowire::AddPeerRequestMessage, {
 });
 from!(RpcResult<&kaspa_rpc_core::AddPeerResponse>, protowire::AddPeerResponseMessage);
 
+from!(item: &kaspa_rpc_core::RemovePeerRequest, protowire::RemovePeerRequestMessage, {
+    Self { address: item.peer_address.to_string() }
+});
+from!(RpcResult<&kaspa_rpc_core::RemovePeerResponse>, protowire::RemovePeerResponseMessage);
+
+from!(item: &kaspa_rpc_core::SetProcessingParallelismRequest, protowire::SetProcessingParallelismRequestMessage, {
+    Self { limit: item.limit }
+});
+from!(item: RpcResult<&kaspa_rpc_core::SetProcessingParallelismResponse>, protowire::SetProcessingParallelismResponseMessage, {
+    Self { previous_limit: item.previous_limit, error: None }
+});
+
+from!(item: &kaspa_rpc_core::GetAddressActivityRequest, protowire::GetAddressActivityRequestMessage, {
+    Self { addresses: item.addresses.iter().map(|x| x.into()).collect() }
+});
+from!(item: RpcResult<&kaspa_rpc_core::GetAddressActivityResponse>, protowire::GetAddressActivityResponseMessage, {
+    Self { entries: item.entries.iter().map(|x| x.into()).collect(), error: None }
+});
+
+from!(item: &kaspa_rpc_core::GetMissedNotificationsRequest, protowire::GetMissedNotificationsRequestMessage, {
+    let scope = match item.scope {
+        Scope::VirtualChainChanged(ref scope) => Some(MissedNotificationsScope::VirtualChainChanged(
+            protowire::VirtualChainChangedScopeMessage { include_accepted_transaction_ids: scope.include_accepted_transaction_ids },
+        )),
+        Scope::UtxosChanged(ref scope) => Some(MissedNotificationsScope::UtxosChanged(protowire::UtxosChangedScopeMessage {
+            addresses: scope.addresses.iter().map(|x| x.into()).collect(),
+        })),
+        // Other scopes are not journaled, so the request is left without scope and rejected
+        _ => None,
+    };
+    Self { scope, from_sequence: item.from_sequence }
+});
+from!(item: RpcResult<&kaspa_rpc_core::GetMissedNotificationsResponse>, protowire::GetMissedNotificationsResponseMessage, {
+    Self {
+        virtual_chain_changed: item.virtual_chain_changed.iter().map(|x| x.into()).collect(),
+        utxos_changed: item.utxos_changed.iter().map(|x| x.into()).collect(),
+        last_sequence: item.last_sequence,
+        error: None,
+    }
+});
+
+from!(item: &kaspa_rpc_core::GetUtxoReturnAddressRequest, protowire::GetUtxoReturnAddressRequestMessage, {
+    Self {
+        accepting_block_hash: item.accepting_block_hash.to_string(),
+        transaction_id: item.transaction_id.to_string(),
+        input_index: item.input_index,
+    }
+});
+from!(item: RpcResult<&kaspa_rpc_core::GetUtxoReturnAddressResponse>, protowire::GetUtxoReturnAddressResponseMessage, {
+    Self {
+        address: item.address.as_ref().map_or("".to_string(), |x| x.into()),
+        utxo_entry: Some((&item.utxo_entry).into()),
+        error: None,
+    }
+});
+
+from!(item: &kaspa_rpc_core::ValidateBlockTemplateRequest, protowire::ValidateBlockTemplateRequestMessage, {
+    Self { block: Some((&item.block).into()) }
+});
+from!(item: &kaspa_rpc_core::RpcInvalidTemplateTransaction, protowire::RpcInvalidTemplateTransaction, {
+    Self { transaction_id: item.transaction_id.to_string(), reason: item.reason.clone() }
+});
+from!(item: RpcResult<&kaspa_rpc_core::ValidateBlockTemplateResponse>, protowire::ValidateBlockTemplateResponseMessage, {
+    Self {
+        is_valid: item.is_valid,
+        mass: item.mass,
+        total_fees: item.total_fees,
+        rejection_detail: item.rejection_detail.clone().unwrap_or_default(),
+        invalid_transactions: item.invalid_transactions.iter().map(|x| x.into()).collect(),
+        error: None,
+    }
+});
+
 from!(item: &kaspa_rpc_core::SubmitTransactionRequest, protowire::SubmitTransactionRequestMessage, {
-    Self { transaction: Some((&item.transaction).into()), allow_orphan: item.allow_orphan }
+    Self {
+        transaction: Some((&item.transaction).into()),
+        allow_orphan: item.allow_orphan,
+        allow_not_yet_valid: item.allow_not_yet_valid,
+        verbose: item.verbose,
+        idempotency_key: item.idempotency_key.clone().unwrap_or_default(),
+    }
 });
 from!(item: RpcResult<&kaspa_rpc_core::SubmitTransactionResponse>, protowire::SubmitTransactionResponseMessage, {
-    Self { transaction_id: item.transaction_id.to_string(), error: None }
+    Self {
+        transaction_id: item.transaction_id.to_string(),
+        rejection_trace: item.rejection_trace.as_ref().map(|x| x.into()),
+        status: item.status.to_string(),
+        accepting_block_hash: item.accepting_block_hash.map(|x| x.to_string()).unwrap_or_default(),
+        idempotency_key: item.idempotency_key.clone().unwrap_or_default(),
+        error: None,
+    }
 });
 
 from!(item: &kaspa_rpc_core::GetSubnetworkRequest, protowire::GetSubnetworkRequestMessage, {
@@ -274,6 +436,7 @@ from!(item: &kaspa_rpc_core::GetBlocksRequest, protowire::GetBlocksRequestMessag
         low_hash: item.low_hash.map_or(Default::default(), |x| x.to_string()),
         include_blocks: item.include_blocks,
         include_transactions: item.include_transactions,
+        transaction_field_mask: item.transaction_field_mask.bits(),
     }
 });
 from!(item: RpcResult<&kaspa_rpc_core::GetBlocksResponse>, protowire::GetBlocksResponseMessage, {
@@ -352,6 +515,18 @@ from!(item: RpcResult<&kaspa_rpc_core::GetSinkBlueScoreResponse>, protowire::Get
     Self { blue_score: item.blue_score, error: None }
 });
 
+from!(&kaspa_rpc_core::GetVirtualInfoRequest, protowire::GetVirtualInfoRequestMessage);
+from!(item: RpcResult<&kaspa_rpc_core::GetVirtualInfoResponse>, protowire::GetVirtualInfoResponseMessage, {
+    Self {
+        daa_score: item.daa_score,
+        sink: item.sink.to_string(),
+        sink_blue_score: item.sink_blue_score,
+        parents_count: item.parents_count,
+        past_median_time: item.past_median_time,
+        error: None,
+    }
+});
+
 from!(item: &kaspa_rpc_core::BanRequest, protowire::BanRequestMessage, { Self { ip: item.ip.to_string() } });
 from!(_item: RpcResult<&kaspa_rpc_core::BanResponse>, protowire::BanResponseMessage, { Self { error: None } });
 
@@ -382,7 +557,13 @@ from!(
 
 from!(&kaspa_rpc_core::GetCoinSupplyRequest, protowire::GetCoinSupplyRequestMessage);
 from!(item: RpcResult<&kaspa_rpc_core::GetCoinSupplyResponse>, protowire::GetCoinSupplyResponseMessage, {
-    Self { max_sompi: item.max_sompi, circulating_sompi: item.circulating_sompi, error: None }
+    Self {
+        max_sompi: item.max_sompi,
+        circulating_sompi: item.circulating_sompi,
+        estimated_circulating_sompi: item.estimated_circulating_sompi,
+        block_reward_sompi: item.block_reward_sompi,
+        error: None,
+    }
 });
 
 from!(item: &kaspa_rpc_core::GetDaaScoreTimestampEstimateRequest, protowire::GetDaaScoreTimestampEstimateRequestMessage, {
@@ -394,6 +575,92 @@ from!(item: RpcResult<&kaspa_rpc_core::GetDaaScoreTimestampEstimateResponse>, pr
     Self { timestamps: item.timestamps.clone(), error: None }
 });
 
+from!(item: &kaspa_rpc_core::GetChainBlockByDaaScoreRequest, protowire::GetChainBlockByDaaScoreRequestMessage, {
+    Self { daa_score: item.daa_score }
+});
+from!(item: RpcResult<&kaspa_rpc_core::GetChainBlockByDaaScoreResponse>, protowire::GetChainBlockByDaaScoreResponseMessage, {
+    Self { hash: item.hash.to_string(), daa_score: item.daa_score, timestamp: item.timestamp, error: None }
+});
+
+from!(item: &kaspa_rpc_core::SetLogFiltersRequest, protowire::SetLogFiltersRequestMessage, {
+    Self { filters: item.filters.clone() }
+});
+from!(item: RpcResult<&kaspa_rpc_core::SetLogFiltersResponse>, protowire::SetLogFiltersResponseMessage, {
+    Self { filters: item.filters.clone(), error: None }
+});
+
+from!(item: &kaspa_rpc_core::GetOutpointSpendStatusRequest, protowire::GetOutpointSpendStatusRequestMessage, {
+    Self { outpoint: Some((&item.outpoint).into()), search_depth: item.search_depth.unwrap_or_default() }
+});
+from!(item: RpcResult<&kaspa_rpc_core::GetOutpointSpendStatusResponse>, protowire::GetOutpointSpendStatusResponseMessage, {
+    Self {
+        utxo_entry: item.utxo_entry.as_ref().map(|x| x.into()),
+        mempool_spender: item.mempool_spender.map(|x| x.to_string()).unwrap_or_default(),
+        accepted_spender: item.accepted_spender.as_ref().map(|x| x.into()),
+        conflicting_spenders: item.conflicting_spenders.iter().map(|x| x.into()).collect(),
+        error: None,
+    }
+});
+
+from!(item: &kaspa_rpc_core::EstimateTransactionConfirmationTimeRequest, protowire::EstimateTransactionConfirmationTimeRequestMessage, {
+    Self { transaction_id: item.transaction_id.to_string() }
+});
+from!(item: RpcResult<&kaspa_rpc_core::EstimateTransactionConfirmationTimeResponse>, protowire::EstimateTransactionConfirmationTimeResponseMessage, {
+    Self { estimate: Some((&item.estimate).into()), error: None }
+});
+
+from!(item: &kaspa_rpc_core::GetAcceptanceProofRequest, protowire::GetAcceptanceProofRequestMessage, {
+    Self { accepting_block_hash: item.accepting_block_hash.to_string(), transaction_id: item.transaction_id.to_string() }
+});
+from!(item: RpcResult<&kaspa_rpc_core::GetAcceptanceProofResponse>, protowire::GetAcceptanceProofResponseMessage, {
+    Self {
+        accepted_id_merkle_root: item.accepted_id_merkle_root.to_string(),
+        leaf_index: item.leaf_index,
+        path: item.path.iter().map(|x| x.to_string()).collect(),
+        error: None,
+    }
+});
+
+from!(item: &kaspa_rpc_core::CreateBackupRequest, protowire::CreateBackupRequestMessage, {
+    Self { output_directory: item.output_directory.clone() }
+});
+from!(item: RpcResult<&kaspa_rpc_core::CreateBackupResponse>, protowire::CreateBackupResponseMessage, {
+    Self {
+        node_version: item.node_version.clone(),
+        network_id: item.network_id.to_string(),
+        params_digest: item.params_digest.to_string(),
+        pruning_point: item.pruning_point.to_string(),
+        sink: item.sink.to_string(),
+        timestamp: item.timestamp,
+        error: None,
+    }
+});
+
+from!(&kaspa_rpc_core::GetVirtualParentSelectionTraceRequest, protowire::GetVirtualParentSelectionTraceRequestMessage);
+from!(item: RpcResult<&kaspa_rpc_core::GetVirtualParentSelectionTraceResponse>, protowire::GetVirtualParentSelectionTraceResponseMessage, {
+    Self {
+        tips: item.tips.iter().map(|x| x.to_string()).collect(),
+        selected_parent: item.selected_parent.to_string(),
+        virtual_parents: item.virtual_parents.iter().map(|x| x.to_string()).collect(),
+        excluded_tips: item.excluded_tips.iter().map(|x| x.into()).collect(),
+        error: None,
+    }
+});
+
+from!(item: &kaspa_rpc_core::RpcPreflightCheck, protowire::RpcPreflightCheck, {
+    Self { name: item.name.clone(), status: item.status.to_string(), message: item.message.clone() }
+});
+
+from!(&kaspa_rpc_core::GetSystemInfoRequest, protowire::GetSystemInfoRequestMessage);
+from!(item: RpcResult<&kaspa_rpc_core::GetSystemInfoResponse>, protowire::GetSystemInfoResponseMessage, {
+    Self {
+        version: item.version.clone(),
+        fd_limit: item.fd_limit,
+        preflight_checks: item.preflight_checks.iter().map(|x| x.into()).collect(),
+        error: None,
+    }
+});
+
 from!(&kaspa_rpc_core::PingRequest, protowire::PingRequestMessage);
 from!(RpcResult<&kaspa_rpc_core::PingResponse>, protowire::PingResponseMessage);
 
@@ -403,6 +670,7 @@ from!(item: &kaspa_rpc_core::GetMetricsRequest, protowire::GetMetricsRequestMess
         connection_metrics: item.connection_metrics,
         bandwidth_metrics: item.bandwidth_metrics,
         consensus_metrics: item.consensus_metrics,
+        script_metrics: item.script_metrics,
     }
 });
 from!(item: RpcResult<&kaspa_rpc_core::GetMetricsResponse>, protowire::GetMetricsResponseMessage, {
@@ -412,6 +680,7 @@ from!(item: RpcResult<&kaspa_rpc_core::GetMetricsResponse>, protowire::GetMetric
         connection_metrics: item.connection_metrics.as_ref().map(|x| x.into()),
         bandwidth_metrics: item.bandwidth_metrics.as_ref().map(|x| x.into()),
         consensus_metrics: item.consensus_metrics.as_ref().map(|x| x.into()),
+        script_metrics: item.script_metrics.as_ref().map(|x| x.into()),
         error: None,
     }
 });
@@ -490,6 +759,15 @@ from!(item: RejectReason, kaspa_rpc_core::SubmitBlockReport, {
     }
 });
 
+from!(item: SubmissionClassification, Option<kaspa_rpc_core::SubmitBlockClassification>, {
+    match item {
+        SubmissionClassification::Unclassified => None,
+        SubmissionClassification::Fresh => Some(kaspa_rpc_core::SubmitBlockClassification::Fresh),
+        SubmissionClassification::Stale => Some(kaspa_rpc_core::SubmitBlockClassification::Stale),
+        SubmissionClassification::AlreadyProcessed => Some(kaspa_rpc_core::SubmitBlockClassification::AlreadyProcessed),
+    }
+});
+
 try_from!(item: &protowire::SubmitBlockRequestMessage, kaspa_rpc_core::SubmitBlockRequest, {
     Self {
         block: item
@@ -509,20 +787,25 @@ impl TryFrom<&protowire::SubmitBlockResponseMessage> for kaspa_rpc_core::SubmitB
     fn try_from(item: &protowire::SubmitBlockResponseMessage) -> RpcResult<Self> {
         let report: SubmitBlockReport =
             RejectReason::try_from(item.reject_reason).map_err(|_| RpcError::PrimitiveToEnumConversionError)?.into();
-        if let Some(ref err) = item.error {
+        // Classifications unknown to this version are ignored, since they are informative only
+        let submission_classification: Option<kaspa_rpc_core::SubmitBlockClassification> =
+            SubmissionClassification::try_from(item.submission_classification).map(Into::into).unwrap_or_default();
+        let response = if let Some(ref err) = item.error {
             match report {
                 SubmitBlockReport::Success => {
                     if err.message == RpcError::SubmitBlockError(SubmitBlockRejectReason::RouteIsFull).to_string() {
-                        Ok(Self { report: SubmitBlockReport::Reject(SubmitBlockRejectReason::RouteIsFull) })
+                        Self::new(SubmitBlockReport::Reject(SubmitBlockRejectReason::RouteIsFull), submission_classification)
                     } else {
-                        Err(err.into())
+                        return Err(err.into());
                     }
                 }
-                SubmitBlockReport::Reject(_) => Ok(Self { report }),
+                SubmitBlockReport::Reject(_) => Self::new(report, submission_classification),
             }
         } else {
-            Ok(Self { report })
-        }
+            Self::new(report, submission_classification)
+        };
+        let response = response.with_non_canonical_transaction_order(item.non_canonical_transaction_order);
+        Ok(if item.rejection_detail.is_empty() { response } else { response.with_rejection_detail(&item.rejection_detail) })
     }
 }
 
@@ -537,11 +820,16 @@ try_from!(item: &protowire::GetBlockTemplateResponseMessage, RpcResult<kaspa_rpc
             .ok_or_else(|| RpcError::MissingRpcFieldError("GetBlockTemplateResponseMessage".to_string(), "block".to_string()))?
             .try_into()?,
         is_synced: item.is_synced,
+        selection_stats: item.selection_stats.as_ref().map(kaspa_rpc_core::RpcBlockTemplateStats::from),
     }
 });
 
 try_from!(item: &protowire::GetBlockRequestMessage, kaspa_rpc_core::GetBlockRequest, {
-    Self { hash: RpcHash::from_str(&item.hash)?, include_transactions: item.include_transactions }
+    Self {
+        hash: RpcHash::from_str(&item.hash)?,
+        include_transactions: item.include_transactions,
+        transaction_field_mask: item.transaction_field_mask.into(),
+    }
 });
 try_from!(item: &protowire::GetBlockResponseMessage, RpcResult<kaspa_rpc_core::GetBlockResponse>, {
     Self {
@@ -568,6 +856,38 @@ try_from!(item: &protowire::GetInfoResponseMessage, RpcResult<kaspa_rpc_core::Ge
         is_synced: item.is_synced,
         has_notify_command: item.has_notify_command,
         has_message_id: item.has_message_id,
+        params_digest: if item.params_digest.is_empty() { None } else { Some(RpcHash::from_str(&item.params_digest)?) },
+        // The thresholds of script classes unknown to this version, as reported by newer nodes, are ignored
+        dust_thresholds: item.dust_thresholds.iter().filter_map(|x| x.try_into().ok()).collect(),
+        consensus_status: item.consensus_status.as_ref().map(|x| x.try_into()).transpose()?,
+    }
+});
+
+try_from!(item: &protowire::RpcConsensusEntry, kaspa_rpc_core::RpcConsensusEntry, {
+    // Reasons unknown to this version, as reported by newer nodes, are mapped to `Unknown`
+    let creation_reason = kaspa_rpc_core::RpcConsensusCreationReason::from_str(&item.creation_reason)
+        .unwrap_or(kaspa_rpc_core::RpcConsensusCreationReason::Unknown);
+    Self { key: item.key, directory_name: item.directory_name.clone(), creation_timestamp: item.creation_timestamp, creation_reason }
+});
+
+try_from!(item: &protowire::RpcRetiredConsensusEntry, kaspa_rpc_core::RpcRetiredConsensusEntry, {
+    Self {
+        entry: item
+            .entry
+            .as_ref()
+            .ok_or_else(|| RpcError::MissingRpcFieldError("RpcRetiredConsensusEntry".to_string(), "entry".to_string()))?
+            .try_into()?,
+        retirement_timestamp: (item.retirement_timestamp != 0).then_some(item.retirement_timestamp),
+        disk_usage: item.disk_usage,
+    }
+});
+
+try_from!(item: &protowire::RpcConsensusStatus, kaspa_rpc_core::RpcConsensusStatus, {
+    Self {
+        current: item.current.as_ref().map(|x| x.try_into()).transpose()?,
+        staging: item.staging.as_ref().map(|x| x.try_into()).transpose()?,
+        is_staging_prepared: item.is_staging_prepared,
+        retired: item.retired.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?,
     }
 });
 
@@ -576,6 +896,14 @@ try_from!(item: &protowire::NotifyNewBlockTemplateRequestMessage, kaspa_rpc_core
 });
 try_from!(&protowire::NotifyNewBlockTemplateResponseMessage, RpcResult<kaspa_rpc_core::NotifyNewBlockTemplateResponse>);
 
+try_from!(item: &protowire::NotifyTransactionConflictRequestMessage, kaspa_rpc_core::NotifyTransactionConflictRequest, {
+    Self {
+        addresses: item.addresses.iter().map(|x| x.as_str().try_into()).collect::<Result<Vec<_>, _>>()?,
+        command: item.command.into(),
+    }
+});
+try_from!(&protowire::NotifyTransactionConflictResponseMessage, RpcResult<kaspa_rpc_core::NotifyTransactionConflictResponse>);
+
 // ~~~
 
 try_from!(&protowire::GetCurrentNetworkRequestMessage, kaspa_rpc_core::GetCurrentNetworkRequest);
@@ -586,10 +914,17 @@ try_from!(item: &protowire::GetCurrentNetworkResponseMessage, RpcResult<kaspa_rp
     Self { network: RpcNetworkType::from_str(&item.current_network.to_lowercase())? }
 });
 
-try_from!(&protowire::GetPeerAddressesRequestMessage, kaspa_rpc_core::GetPeerAddressesRequest);
+try_from!(item: &protowire::GetPeerAddressesRequestMessage, kaspa_rpc_core::GetPeerAddressesRequest, {
+    Self {
+        only_tried: item.only_tried,
+        only_new: item.only_new,
+        group_prefix: (!item.group_prefix.is_empty()).then(|| item.group_prefix.clone()),
+        limit: (item.limit > 0).then_some(item.limit),
+    }
+});
 try_from!(item: &protowire::GetPeerAddressesResponseMessage, RpcResult<kaspa_rpc_core::GetPeerAddressesResponse>, {
     Self {
-        known_addresses: item.addresses.iter().map(RpcPeerAddress::try_from).collect::<Result<Vec<_>, _>>()?,
+        known_addresses: item.addresses.iter().map(RpcKnownPeerAddress::try_from).collect::<Result<Vec<_>, _>>()?,
         banned_addresses: item.banned_addresses.iter().map(RpcIpAddress::try_from).collect::<Result<Vec<_>, _>>()?,
     }
 });
@@ -617,10 +952,18 @@ try_from!(item: &protowire::GetMempoolEntryResponseMessage, RpcResult<kaspa_rpc_
 });
 
 try_from!(item: &protowire::GetMempoolEntriesRequestMessage, kaspa_rpc_core::GetMempoolEntriesRequest, {
-    Self { include_orphan_pool: item.include_orphan_pool, filter_transaction_pool: item.filter_transaction_pool }
+    Self {
+        include_orphan_pool: item.include_orphan_pool,
+        filter_transaction_pool: item.filter_transaction_pool,
+        consistent_snapshot: item.consistent_snapshot,
+    }
 });
 try_from!(item: &protowire::GetMempoolEntriesResponseMessage, RpcResult<kaspa_rpc_core::GetMempoolEntriesResponse>, {
-    Self { mempool_entries: item.entries.iter().map(kaspa_rpc_core::RpcMempoolEntry::try_from).collect::<Result<Vec<_>, _>>()? }
+    Self {
+        mempool_entries: item.entries.iter().map(kaspa_rpc_core::RpcMempoolEntry::try_from).collect::<Result<Vec<_>, _>>()?,
+        // Nodes predating the summary do not send it
+        summary: item.summary.as_ref().map(kaspa_rpc_core::RpcMempoolSummary::try_from).transpose()?.unwrap_or_default(),
+    }
 });
 
 try_from!(&protowire::GetConnectedPeerInfoRequestMessage, kaspa_rpc_core::GetConnectedPeerInfoRequest);
@@ -633,6 +976,88 @@ try_from!(item: &protowire::AddPeerRequestMessage, kaspa_rpc_core::AddPeerReques
 });
 try_from!(&protowire::AddPeerResponseMessage, RpcResult<kaspa_rpc_core::AddPeerResponse>);
 
+try_from!(item: &protowire::RemovePeerRequestMessage, kaspa_rpc_core::RemovePeerRequest, {
+    Self { peer_address: RpcContextualPeerAddress::from_str(&item.address)? }
+});
+try_from!(&protowire::RemovePeerResponseMessage, RpcResult<kaspa_rpc_core::RemovePeerResponse>);
+
+try_from!(item: &protowire::SetProcessingParallelismRequestMessage, kaspa_rpc_core::SetProcessingParallelismRequest, {
+    Self { limit: item.limit }
+});
+try_from!(item: &protowire::SetProcessingParallelismResponseMessage, RpcResult<kaspa_rpc_core::SetProcessingParallelismResponse>, {
+    Self { previous_limit: item.previous_limit }
+});
+
+try_from!(item: &protowire::GetAddressActivityRequestMessage, kaspa_rpc_core::GetAddressActivityRequest, {
+    Self { addresses: item.addresses.iter().map(|x| x.as_str().try_into()).collect::<Result<Vec<_>, _>>()? }
+});
+try_from!(item: &protowire::GetAddressActivityResponseMessage, RpcResult<kaspa_rpc_core::GetAddressActivityResponse>, {
+    Self { entries: item.entries.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()? }
+});
+
+try_from!(item: &protowire::GetMissedNotificationsRequestMessage, kaspa_rpc_core::GetMissedNotificationsRequest, {
+    let scope = match item.scope {
+        Some(MissedNotificationsScope::VirtualChainChanged(ref scope)) => {
+            VirtualChainChangedScope::new(scope.include_accepted_transaction_ids).into()
+        }
+        Some(MissedNotificationsScope::UtxosChanged(ref scope)) => {
+            UtxosChangedScope::new(scope.addresses.iter().map(|x| x.as_str().try_into()).collect::<Result<Vec<_>, _>>()?).into()
+        }
+        None => {
+            return Err(RpcError::MissingRpcFieldError("GetMissedNotificationsRequestMessage".to_string(), "scope".to_string()))
+        }
+    };
+    Self { scope, from_sequence: item.from_sequence }
+});
+try_from!(item: &protowire::GetMissedNotificationsResponseMessage, RpcResult<kaspa_rpc_core::GetMissedNotificationsResponse>, {
+    Self {
+        virtual_chain_changed: item.virtual_chain_changed.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?,
+        utxos_changed: item.utxos_changed.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?,
+        last_sequence: item.last_sequence,
+    }
+});
+
+try_from!(item: &protowire::GetUtxoReturnAddressRequestMessage, kaspa_rpc_core::GetUtxoReturnAddressRequest, {
+    Self {
+        accepting_block_hash: RpcHash::from_str(&item.accepting_block_hash)?,
+        transaction_id: RpcHash::from_str(&item.transaction_id)?,
+        input_index: item.input_index,
+    }
+});
+try_from!(item: &protowire::GetUtxoReturnAddressResponseMessage, RpcResult<kaspa_rpc_core::GetUtxoReturnAddressResponse>, {
+    let address = if item.address.is_empty() { None } else { Some(item.address.as_str().try_into()?) };
+    Self {
+        address,
+        utxo_entry: item
+            .utxo_entry
+            .as_ref()
+            .ok_or_else(|| RpcError::MissingRpcFieldError("GetUtxoReturnAddressResponseMessage".to_string(), "utxo_entry".to_string()))?
+            .try_into()?,
+    }
+});
+
+try_from!(item: &protowire::ValidateBlockTemplateRequestMessage, kaspa_rpc_core::ValidateBlockTemplateRequest, {
+    Self {
+        block: item
+            .block
+            .as_ref()
+            .ok_or_else(|| RpcError::MissingRpcFieldError("ValidateBlockTemplateRequestMessage".to_string(), "block".to_string()))?
+            .try_into()?,
+    }
+});
+try_from!(item: &protowire::RpcInvalidTemplateTransaction, kaspa_rpc_core::RpcInvalidTemplateTransaction, {
+    Self { transaction_id: RpcHash::from_str(&item.transaction_id)?, reason: item.reason.clone() }
+});
+try_from!(item: &protowire::ValidateBlockTemplateResponseMessage, RpcResult<kaspa_rpc_core::ValidateBlockTemplateResponse>, {
+    Self {
+        is_valid: item.is_valid,
+        mass: item.mass,
+        total_fees: item.total_fees,
+        rejection_detail: if item.rejection_detail.is_empty() { None } else { Some(item.rejection_detail.clone()) },
+        invalid_transactions: item.invalid_transactions.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?,
+    }
+});
+
 try_from!(item: &protowire::SubmitTransactionRequestMessage, kaspa_rpc_core::SubmitTransactionRequest, {
     Self {
         transaction: item
@@ -641,10 +1066,24 @@ try_from!(item: &protowire::SubmitTransactionRequestMessage, kaspa_rpc_core::Sub
             .ok_or_else(|| RpcError::MissingRpcFieldError("SubmitTransactionRequestMessage".to_string(), "transaction".to_string()))?
             .try_into()?,
         allow_orphan: item.allow_orphan,
+        allow_not_yet_valid: item.allow_not_yet_valid,
+        verbose: item.verbose,
+        idempotency_key: (!item.idempotency_key.is_empty()).then(|| item.idempotency_key.clone()),
     }
 });
 try_from!(item: &protowire::SubmitTransactionResponseMessage, RpcResult<kaspa_rpc_core::SubmitTransactionResponse>, {
-    Self { transaction_id: RpcHash::from_str(&item.transaction_id)? }
+    // Statuses unknown to this version, as reported by newer nodes, are mapped to `Unknown`, while older nodes report none
+    let status = match item.status.as_str() {
+        "" => kaspa_rpc_core::RpcSubmitTransactionStatus::default(),
+        status => kaspa_rpc_core::RpcSubmitTransactionStatus::from_str(status).unwrap_or(kaspa_rpc_core::RpcSubmitTransactionStatus::Unknown),
+    };
+    Self {
+        transaction_id: RpcHash::from_str(&item.transaction_id)?,
+        rejection_trace: item.rejection_trace.as_ref().map(|x| x.try_into()).transpose()?,
+        status,
+        accepting_block_hash: if item.accepting_block_hash.is_empty() { None } else { Some(RpcHash::from_str(&item.accepting_block_hash)?) },
+        idempotency_key: (!item.idempotency_key.is_empty()).then(|| item.idempotency_key.clone()),
+    }
 });
 
 try_from!(item: &protowire::GetSubnetworkRequestMessage, kaspa_rpc_core::GetSubnetworkRequest, {
@@ -674,6 +1113,7 @@ try_from!(item: &protowire::GetBlocksRequestMessage, kaspa_rpc_core::GetBlocksRe
         low_hash: if item.low_hash.is_empty() { None } else { Some(RpcHash::from_str(&item.low_hash)?) },
         include_blocks: item.include_blocks,
         include_transactions: item.include_transactions,
+        transaction_field_mask: item.transaction_field_mask.into(),
     }
 });
 try_from!(item: &protowire::GetBlocksResponseMessage, RpcResult<kaspa_rpc_core::GetBlocksResponse>, {
@@ -746,6 +1186,17 @@ try_from!(item: &protowire::GetSinkBlueScoreResponseMessage, RpcResult<kaspa_rpc
     Self { blue_score: item.blue_score }
 });
 
+try_from!(&protowire::GetVirtualInfoRequestMessage, kaspa_rpc_core::GetVirtualInfoRequest);
+try_from!(item: &protowire::GetVirtualInfoResponseMessage, RpcResult<kaspa_rpc_core::GetVirtualInfoResponse>, {
+    Self {
+        daa_score: item.daa_score,
+        sink: RpcHash::from_str(&item.sink)?,
+        sink_blue_score: item.sink_blue_score,
+        parents_count: item.parents_count,
+        past_median_time: item.past_median_time,
+    }
+});
+
 try_from!(item: &protowire::BanRequestMessage, kaspa_rpc_core::BanRequest, { Self { ip: RpcIpAddress::from_str(&item.ip)? } });
 try_from!(&protowire::BanResponseMessage, RpcResult<kaspa_rpc_core::BanResponse>);
 
@@ -779,7 +1230,12 @@ try_from!(
 
 try_from!(&protowire::GetCoinSupplyRequestMessage, kaspa_rpc_core::GetCoinSupplyRequest);
 try_from!(item: &protowire::GetCoinSupplyResponseMessage, RpcResult<kaspa_rpc_core::GetCoinSupplyResponse>, {
-    Self { max_sompi: item.max_sompi, circulating_sompi: item.circulating_sompi }
+    Self {
+        max_sompi: item.max_sompi,
+        circulating_sompi: item.circulating_sompi,
+        estimated_circulating_sompi: item.estimated_circulating_sompi,
+        block_reward_sompi: item.block_reward_sompi,
+    }
 });
 
 try_from!(item: &protowire::GetDaaScoreTimestampEstimateRequestMessage, kaspa_rpc_core::GetDaaScoreTimestampEstimateRequest , {
@@ -791,11 +1247,115 @@ try_from!(item: &protowire::GetDaaScoreTimestampEstimateResponseMessage, RpcResu
     Self { timestamps: item.timestamps.clone() }
 });
 
+try_from!(item: &protowire::GetChainBlockByDaaScoreRequestMessage, kaspa_rpc_core::GetChainBlockByDaaScoreRequest, {
+    Self { daa_score: item.daa_score }
+});
+try_from!(item: &protowire::GetChainBlockByDaaScoreResponseMessage, RpcResult<kaspa_rpc_core::GetChainBlockByDaaScoreResponse>, {
+    Self { hash: RpcHash::from_str(&item.hash)?, daa_score: item.daa_score, timestamp: item.timestamp }
+});
+
+try_from!(item: &protowire::SetLogFiltersRequestMessage, kaspa_rpc_core::SetLogFiltersRequest, {
+    Self { filters: item.filters.clone() }
+});
+try_from!(item: &protowire::SetLogFiltersResponseMessage, RpcResult<kaspa_rpc_core::SetLogFiltersResponse>, {
+    Self { filters: item.filters.clone() }
+});
+
+try_from!(item: &protowire::GetOutpointSpendStatusRequestMessage, kaspa_rpc_core::GetOutpointSpendStatusRequest, {
+    Self {
+        outpoint: item
+            .outpoint
+            .as_ref()
+            .ok_or_else(|| RpcError::MissingRpcFieldError("GetOutpointSpendStatusRequestMessage".to_string(), "outpoint".to_string()))?
+            .try_into()?,
+        search_depth: if item.search_depth == 0 { None } else { Some(item.search_depth) },
+    }
+});
+try_from!(item: &protowire::GetOutpointSpendStatusResponseMessage, RpcResult<kaspa_rpc_core::GetOutpointSpendStatusResponse>, {
+    Self {
+        utxo_entry: item.utxo_entry.as_ref().map(kaspa_rpc_core::RpcUtxoEntry::try_from).transpose()?,
+        mempool_spender: if item.mempool_spender.is_empty() { None } else { Some(RpcHash::from_str(&item.mempool_spender)?) },
+        accepted_spender: item.accepted_spender.as_ref().map(kaspa_rpc_core::RpcOutpointSpender::try_from).transpose()?,
+        conflicting_spenders: item.conflicting_spenders.iter().map(kaspa_rpc_core::RpcOutpointSpender::try_from).collect::<Result<Vec<_>, _>>()?,
+    }
+});
+
+try_from!(item: &protowire::EstimateTransactionConfirmationTimeRequestMessage, kaspa_rpc_core::EstimateTransactionConfirmationTimeRequest, {
+    Self { transaction_id: RpcHash::from_str(&item.transaction_id)? }
+});
+try_from!(item: &protowire::EstimateTransactionConfirmationTimeResponseMessage, RpcResult<kaspa_rpc_core::EstimateTransactionConfirmationTimeResponse>, {
+    Self {
+        estimate: item
+            .estimate
+            .as_ref()
+            .ok_or_else(|| {
+                RpcError::MissingRpcFieldError("EstimateTransactionConfirmationTimeResponseMessage".to_string(), "estimate".to_string())
+            })?
+            .try_into()?,
+    }
+});
+
+try_from!(item: &protowire::GetAcceptanceProofRequestMessage, kaspa_rpc_core::GetAcceptanceProofRequest, {
+    Self { accepting_block_hash: RpcHash::from_str(&item.accepting_block_hash)?, transaction_id: RpcHash::from_str(&item.transaction_id)? }
+});
+try_from!(item: &protowire::GetAcceptanceProofResponseMessage, RpcResult<kaspa_rpc_core::GetAcceptanceProofResponse>, {
+    Self {
+        accepted_id_merkle_root: RpcHash::from_str(&item.accepted_id_merkle_root)?,
+        leaf_index: item.leaf_index,
+        path: item.path.iter().map(|x| RpcHash::from_str(x)).collect::<Result<Vec<_>, _>>()?,
+    }
+});
+
+try_from!(item: &protowire::CreateBackupRequestMessage, kaspa_rpc_core::CreateBackupRequest, {
+    Self { output_directory: item.output_directory.clone() }
+});
+try_from!(item: &protowire::CreateBackupResponseMessage, RpcResult<kaspa_rpc_core::CreateBackupResponse>, {
+    Self {
+        node_version: item.node_version.clone(),
+        network_id: NetworkId::from_str(&item.network_id)?,
+        params_digest: RpcHash::from_str(&item.params_digest)?,
+        pruning_point: RpcHash::from_str(&item.pruning_point)?,
+        sink: RpcHash::from_str(&item.sink)?,
+        timestamp: item.timestamp,
+    }
+});
+
+try_from!(&protowire::GetVirtualParentSelectionTraceRequestMessage, kaspa_rpc_core::GetVirtualParentSelectionTraceRequest);
+try_from!(item: &protowire::GetVirtualParentSelectionTraceResponseMessage, RpcResult<kaspa_rpc_core::GetVirtualParentSelectionTraceResponse>, {
+    Self {
+        tips: item.tips.iter().map(|x| RpcHash::from_str(x)).collect::<Result<Vec<_>, _>>()?,
+        selected_parent: RpcHash::from_str(&item.selected_parent)?,
+        virtual_parents: item.virtual_parents.iter().map(|x| RpcHash::from_str(x)).collect::<Result<Vec<_>, _>>()?,
+        excluded_tips: item.excluded_tips.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?,
+    }
+});
+
+try_from!(item: &protowire::RpcPreflightCheck, kaspa_rpc_core::RpcPreflightCheck, {
+    // Statuses unknown to this version, as reported by newer nodes, are mapped to `Unknown`
+    let status = kaspa_rpc_core::RpcPreflightCheckStatus::from_str(&item.status).unwrap_or(kaspa_rpc_core::RpcPreflightCheckStatus::Unknown);
+    Self { name: item.name.clone(), status, message: item.message.clone() }
+});
+
+try_from!(&protowire::GetSystemInfoRequestMessage, kaspa_rpc_core::GetSystemInfoRequest);
+try_from!(item: &protowire::GetSystemInfoResponseMessage, RpcResult<kaspa_rpc_core::GetSystemInfoResponse>, {
+    Self {
+        version: item.version.clone(),
+        fd_limit: item.fd_limit,
+        preflight_checks: item.preflight_checks.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?,
+    }
+});
+
 try_from!(&protowire::PingRequestMessage, kaspa_rpc_core::PingRequest);
 try_from!(&protowire::PingResponseMessage, RpcResult<kaspa_rpc_core::PingResponse>);
 
 try_from!(item: &protowire::GetMetricsRequestMessage, kaspa_rpc_core::GetMetricsRequest, {
-    Self { process_metrics: item.process_metrics, connection_metrics: item.connection_metrics, bandwidth_metrics:item.bandwidth_metrics, consensus_metrics: item.consensus_metrics }
+    Self {
+        process_metrics: item.process_metrics,
+        connection_metrics: item.connection_metrics,
+        bandwidth_metrics: item.bandwidth_metrics,
+        consensus_metrics: item.consensus_metrics,
+        script_metrics: item.script_metrics,
+    }
 });
 try_from!(item: &protowire::GetMetricsResponseMessage, RpcResult<kaspa_rpc_core::GetMetricsResponse>, {
     Self {
@@ -804,6 +1364,7 @@ try_from!(item: &protowire::GetMetricsResponseMessage, RpcResult<kaspa_rpc_core:
         connection_metrics: item.connection_metrics.as_ref().map(|x| x.try_into()).transpose()?,
         bandwidth_metrics: item.bandwidth_metrics.as_ref().map(|x| x.try_into()).transpose()?,
         consensus_metrics: item.consensus_metrics.as_ref().map(|x| x.try_into()).transpose()?,
+        script_metrics: item.script_metrics.as_ref().map(|x| x.try_into()).transpose()?,
     }
 });
 
@@ -888,9 +1449,15 @@ try_from!(&protowire::NotifySinkBlueScoreChangedResponseMessage, RpcResult<kaspa
 
 #[cfg(test)]
 mod tests {
-    use kaspa_rpc_core::{RpcError, RpcResult, SubmitBlockRejectReason, SubmitBlockReport, SubmitBlockResponse};
+    use kaspa_rpc_core::{
+        RpcError, RpcResult, SubmitBlockClassification, SubmitBlockRejectReason, SubmitBlockReport, SubmitBlockResponse,
+    };
 
-    use crate::protowire::{self, submit_block_response_message::RejectReason, SubmitBlockResponseMessage};
+    use crate::protowire::{
+        self,
+        submit_block_response_message::{RejectReason, SubmissionClassification},
+        SubmitBlockResponseMessage,
+    };
 
     #[test]
     fn test_submit_block_response() {
@@ -908,33 +1475,75 @@ mod tests {
         }
         let tests = vec![
             Test::new(
-                Ok(SubmitBlockResponse { report: SubmitBlockReport::Success }),
-                SubmitBlockResponseMessage { reject_reason: RejectReason::None as i32, error: None },
+                Ok(SubmitBlockResponse::new(SubmitBlockReport::Success, None)),
+                SubmitBlockResponseMessage {
+                    reject_reason: RejectReason::None as i32,
+                    submission_classification: SubmissionClassification::Unclassified as i32,
+                    non_canonical_transaction_order: false,
+                    error: None,
+                },
             ),
             Test::new(
-                Ok(SubmitBlockResponse { report: SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid) }),
+                Ok(SubmitBlockResponse::new(SubmitBlockReport::Success, Some(SubmitBlockClassification::Stale))),
                 SubmitBlockResponseMessage {
+                    reject_reason: RejectReason::None as i32,
+                    submission_classification: SubmissionClassification::Stale as i32,
+                    non_canonical_transaction_order: false,
+                    error: None,
+                },
+            ),
+            Test::new(
+                Ok(SubmitBlockResponse::new(SubmitBlockReport::Success, Some(SubmitBlockClassification::AlreadyProcessed))),
+                SubmitBlockResponseMessage {
+                    reject_reason: RejectReason::None as i32,
+                    submission_classification: SubmissionClassification::AlreadyProcessed as i32,
+                    non_canonical_transaction_order: false,
+                    error: None,
+                },
+            ),
+            Test::new(
+                Ok(SubmitBlockResponse::new(SubmitBlockReport::Success, Some(SubmitBlockClassification::Fresh))
+                    .with_non_canonical_transaction_order(true)),
+                SubmitBlockResponseMessage {
+                    reject_reason: RejectReason::None as i32,
+                    submission_classification: SubmissionClassification::Fresh as i32,
+                    non_canonical_transaction_order: true,
+                    error: None,
+                },
+            ),
+            Test::new(
+                Ok(SubmitBlockResponse::new(SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid), None)),
+                SubmitBlockResponseMessage {
+                    submission_classification: SubmissionClassification::Unclassified as i32,
+                    non_canonical_transaction_order: false,
                     reject_reason: RejectReason::BlockInvalid as i32,
                     error: Some(protowire::RpcError {
                         message: RpcError::SubmitBlockError(SubmitBlockRejectReason::BlockInvalid).to_string(),
+                        trace_id: Default::default(),
                     }),
                 },
             ),
             Test::new(
-                Ok(SubmitBlockResponse { report: SubmitBlockReport::Reject(SubmitBlockRejectReason::IsInIBD) }),
+                Ok(SubmitBlockResponse::new(SubmitBlockReport::Reject(SubmitBlockRejectReason::IsInIBD), None)),
                 SubmitBlockResponseMessage {
+                    submission_classification: SubmissionClassification::Unclassified as i32,
+                    non_canonical_transaction_order: false,
                     reject_reason: RejectReason::IsInIbd as i32,
                     error: Some(protowire::RpcError {
                         message: RpcError::SubmitBlockError(SubmitBlockRejectReason::IsInIBD).to_string(),
+                        trace_id: Default::default(),
                     }),
                 },
             ),
             Test::new(
-                Ok(SubmitBlockResponse { report: SubmitBlockReport::Reject(SubmitBlockRejectReason::RouteIsFull) }),
+                Ok(SubmitBlockResponse::new(SubmitBlockReport::Reject(SubmitBlockRejectReason::RouteIsFull), None)),
                 SubmitBlockResponseMessage {
+                    submission_classification: SubmissionClassification::Unclassified as i32,
+                    non_canonical_transaction_order: false,
                     reject_reason: RejectReason::None as i32, // This rpc core reject reason has no matching protowire variant
                     error: Some(protowire::RpcError {
                         message: RpcError::SubmitBlockError(SubmitBlockRejectReason::RouteIsFull).to_string(),
+                        trace_id: Default::default(),
                     }),
                 },
             ),
@@ -943,6 +1552,8 @@ mod tests {
         for test in tests {
             let cnv_protowire: SubmitBlockResponseMessage = test.rpc_core.as_ref().map_err(|x| x.clone()).into();
             assert_eq!(cnv_protowire.reject_reason, test.protowire.reject_reason);
+            assert_eq!(cnv_protowire.submission_classification, test.protowire.submission_classification);
+            assert_eq!(cnv_protowire.non_canonical_transaction_order, test.protowire.non_canonical_transaction_order);
             assert_eq!(cnv_protowire.error.is_some(), test.protowire.error.is_some());
             assert_eq!(cnv_protowire.error, test.protowire.error);
 
@@ -952,6 +1563,8 @@ mod tests {
                 Ok(ref cnv_response) => {
                     let Ok(ref response) = test.rpc_core else { panic!() };
                     assert_eq!(cnv_response.report, response.report);
+                    assert_eq!(cnv_response.submission_classification, response.submission_classification);
+                    assert_eq!(cnv_response.non_canonical_transaction_order, response.non_canonical_transaction_order);
                 }
                 Err(ref cnv_err) => {
                     let Err(ref err) = test.rpc_core else { panic!() };
@@ -960,4 +1573,54 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_get_peer_addresses_roundtrip() {
+        use kaspa_rpc_core::{
+            GetPeerAddressesRequest, GetPeerAddressesResponse, RpcConnectionOutcome, RpcIpAddress, RpcKnownPeerAddress,
+            RpcPeerAddress, RpcPeerAddressSource,
+        };
+        use std::str::FromStr;
+
+        let request =
+            GetPeerAddressesRequest { only_tried: true, only_new: false, group_prefix: Some("10.0".to_string()), limit: Some(5) };
+        let message: protowire::GetPeerAddressesRequestMessage = (&request).into();
+        let cnv_request: GetPeerAddressesRequest = (&message).try_into().unwrap();
+        assert_eq!((cnv_request.only_tried, cnv_request.only_new), (true, false));
+        assert_eq!((cnv_request.group_prefix.as_deref(), cnv_request.limit), (Some("10.0"), Some(5)));
+        let message: protowire::GetPeerAddressesRequestMessage = (&GetPeerAddressesRequest::default()).into();
+        let cnv_request: GetPeerAddressesRequest = (&message).try_into().unwrap();
+        assert_eq!((cnv_request.group_prefix, cnv_request.limit), (None, None));
+
+        let tried = RpcKnownPeerAddress {
+            address: RpcPeerAddress::from_str("10.0.0.1:16111").unwrap(),
+            source: RpcPeerAddressSource::DnsSeed,
+            network_group: "10.0".to_string(),
+            connection_failed_count: 0,
+            is_tried: true,
+            last_seen: Some(1_700_000_000_000),
+            last_attempt_time: Some(1_700_000_001_000),
+            last_attempt_outcome: Some(RpcConnectionOutcome::Success),
+        };
+        let restored = RpcKnownPeerAddress {
+            address: RpcPeerAddress::from_str("[2001:db8::1]:16111").unwrap(),
+            source: RpcPeerAddressSource::Unknown,
+            network_group: "2001:db8:0:0".to_string(),
+            connection_failed_count: 2,
+            is_tried: false,
+            last_seen: None,
+            last_attempt_time: None,
+            last_attempt_outcome: None,
+        };
+        let banned = RpcIpAddress::from_str("5.6.7.8").unwrap();
+        let response = GetPeerAddressesResponse::new(vec![tried.clone(), restored.clone()], vec![banned]);
+        let result: RpcResult<&GetPeerAddressesResponse> = Ok(&response);
+        let message: protowire::GetPeerAddressesResponseMessage = result.into();
+        assert_eq!(message.addresses[0].source, "dnsSeed");
+        assert_eq!(message.addresses[1].last_attempt_outcome, "");
+        let cnv_response: RpcResult<GetPeerAddressesResponse> = (&message).try_into();
+        let cnv_response = cnv_response.unwrap();
+        assert_eq!(cnv_response.known_addresses, vec![tried, restored]);
+        assert_eq!(cnv_response.banned_addresses, vec![banned]);
+    }
 }
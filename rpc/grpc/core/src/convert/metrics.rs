@@ -63,6 +63,33 @@ from!(item: &kaspa_rpc_core::ConsensusMetrics, protowire::ConsensusMetrics, {
         past_median_time: item.network_past_median_time,
         virtual_parent_hashes_count: item.network_virtual_parent_hashes_count,
         virtual_daa_score: item.network_virtual_daa_score,
+
+        block_propagation_sample_count: item.node_block_propagation_sample_count,
+        block_propagation_p50_millis: item.node_block_propagation_p50_millis,
+        block_propagation_p90_millis: item.node_block_propagation_p90_millis,
+        block_propagation_p99_millis: item.node_block_propagation_p99_millis,
+
+        processing_parallelism_limit: item.node_processing_parallelism_limit,
+        processing_running_count: item.node_processing_running_count,
+        processing_queued_count: item.node_processing_queued_count,
+    }
+});
+
+from!(item: &kaspa_rpc_core::RpcScriptRejectionCount, protowire::ScriptRejectionCount, {
+    Self { reason: item.reason.clone(), count: item.count }
+});
+
+from!(item: &kaspa_rpc_core::RpcOpcodeCount, protowire::OpcodeCount, {
+    Self { opcode: item.opcode as u32, count: item.count }
+});
+
+from!(item: &kaspa_rpc_core::ScriptMetrics, protowire::ScriptMetrics, {
+    Self {
+        checked_transactions_count: item.checked_transactions_count,
+        rejection_counts: item.rejection_counts.iter().map(|x| x.into()).collect(),
+        opcode_sample_rate: item.opcode_sample_rate,
+        sampled_transactions_count: item.sampled_transactions_count,
+        opcode_counts: item.opcode_counts.iter().map(|x| x.into()).collect(),
     }
 });
 
@@ -127,5 +154,35 @@ try_from!(item: &protowire::ConsensusMetrics, kaspa_rpc_core::ConsensusMetrics,
         network_past_median_time: item.past_median_time,
         network_virtual_parent_hashes_count: item.virtual_parent_hashes_count,
         network_virtual_daa_score: item.virtual_daa_score,
+
+        node_block_propagation_sample_count: item.block_propagation_sample_count,
+        node_block_propagation_p50_millis: item.block_propagation_p50_millis,
+        node_block_propagation_p90_millis: item.block_propagation_p90_millis,
+        node_block_propagation_p99_millis: item.block_propagation_p99_millis,
+
+        node_processing_parallelism_limit: item.processing_parallelism_limit,
+        node_processing_running_count: item.processing_running_count,
+        node_processing_queued_count: item.processing_queued_count,
+    }
+});
+
+try_from!(item: &protowire::ScriptRejectionCount, kaspa_rpc_core::RpcScriptRejectionCount, {
+    Self { reason: item.reason.clone(), count: item.count }
+});
+
+try_from!(item: &protowire::OpcodeCount, kaspa_rpc_core::RpcOpcodeCount, {
+    Self {
+        opcode: item.opcode.try_into().map_err(|_| RpcError::General(format!("invalid opcode value {}", item.opcode)))?,
+        count: item.count,
+    }
+});
+
+try_from!(item: &protowire::ScriptMetrics, kaspa_rpc_core::ScriptMetrics, {
+    Self {
+        checked_transactions_count: item.checked_transactions_count,
+        rejection_counts: item.rejection_counts.iter().map(|x| x.try_into()).collect::<Result<_, _>>()?,
+        opcode_sample_rate: item.opcode_sample_rate,
+        sampled_transactions_count: item.sampled_transactions_count,
+        opcode_counts: item.opcode_counts.iter().map(|x| x.try_into()).collect::<Result<_, _>>()?,
     }
 });
@@ -9,3 +9,6 @@ pub mod metrics;
 pub mod notification;
 pub mod peer;
 pub mod tx;
+
+#[cfg(test)]
+mod tests;
@@ -6,8 +6,8 @@ use crate::protowire::{
     NotifyPruningPointUtxoSetOverrideResponseMessage, NotifyUtxosChangedRequestMessage, NotifyUtxosChangedResponseMessage,
     PruningPointUtxoSetOverrideNotificationMessage, SinkBlueScoreChangedNotificationMessage,
     StopNotifyingPruningPointUtxoSetOverrideRequestMessage, StopNotifyingPruningPointUtxoSetOverrideResponseMessage,
-    StopNotifyingUtxosChangedRequestMessage, StopNotifyingUtxosChangedResponseMessage, UtxosChangedNotificationMessage,
-    VirtualChainChangedNotificationMessage, VirtualDaaScoreChangedNotificationMessage,
+    StopNotifyingUtxosChangedRequestMessage, StopNotifyingUtxosChangedResponseMessage, TransactionConflictNotificationMessage,
+    UtxosChangedNotificationMessage, VirtualChainChangedNotificationMessage, VirtualDaaScoreChangedNotificationMessage,
 };
 use crate::{from, try_from};
 use kaspa_notify::subscription::Command;
@@ -34,6 +34,7 @@ from!(item: &kaspa_rpc_core::Notification, Payload, {
         Notification::PruningPointUtxoSetOverride(ref notification) => {
             Payload::PruningPointUtxoSetOverrideNotification(notification.into())
         }
+        Notification::TransactionConflict(ref notification) => Payload::TransactionConflictNotification(notification.into()),
     }
 });
 
@@ -46,6 +47,7 @@ from!(item: &kaspa_rpc_core::VirtualChainChangedNotification, VirtualChainChange
         removed_chain_block_hashes: item.removed_chain_block_hashes.iter().map(|x| x.to_string()).collect(),
         added_chain_block_hashes: item.added_chain_block_hashes.iter().map(|x| x.to_string()).collect(),
         accepted_transaction_ids: item.accepted_transaction_ids.iter().map(|x| x.into()).collect(),
+        sequence: item.sequence.unwrap_or_default(),
     }
 });
 
@@ -61,6 +63,7 @@ from!(item: &kaspa_rpc_core::UtxosChangedNotification, UtxosChangedNotificationM
     Self {
         added: item.added.iter().map(|x| x.into()).collect::<Vec<_>>(),
         removed: item.removed.iter().map(|x| x.into()).collect::<Vec<_>>(),
+        sequence: item.sequence.unwrap_or_default(),
     }
 });
 
@@ -69,11 +72,21 @@ from!(item: &kaspa_rpc_core::SinkBlueScoreChangedNotification, SinkBlueScoreChan
 });
 
 from!(item: &kaspa_rpc_core::VirtualDaaScoreChangedNotification, VirtualDaaScoreChangedNotificationMessage, {
-    Self { virtual_daa_score: item.virtual_daa_score }
+    Self { virtual_daa_score: item.virtual_daa_score, virtual_info: item.virtual_info.as_ref().map(|x| x.into()) }
 });
 
 from!(&kaspa_rpc_core::PruningPointUtxoSetOverrideNotification, PruningPointUtxoSetOverrideNotificationMessage);
 
+from!(item: &kaspa_rpc_core::TransactionConflictNotification, TransactionConflictNotificationMessage, {
+    Self {
+        transaction_id: item.transaction_id.to_string(),
+        conflicting_transaction_id: item.conflicting_transaction_id.to_string(),
+        outpoints: item.outpoints.iter().map(|x| x.into()).collect(),
+        is_conflicting_in_block: item.is_conflicting_in_block,
+        addresses: item.addresses.iter().map(|x| x.into()).collect(),
+    }
+});
+
 from!(item: Command, RpcNotifyCommand, {
     match item {
         Command::Start => RpcNotifyCommand::NotifyStart,
@@ -117,6 +130,7 @@ try_from!(item: &Payload, kaspa_rpc_core::Notification, {
         Payload::PruningPointUtxoSetOverrideNotification(ref notification) => {
             Notification::PruningPointUtxoSetOverride(notification.try_into()?)
         }
+        Payload::TransactionConflictNotification(ref notification) => Notification::TransactionConflict(notification.try_into()?),
         _ => Err(RpcError::UnsupportedFeature)?,
     }
 });
@@ -143,6 +157,8 @@ try_from!(item: &VirtualChainChangedNotificationMessage, kaspa_rpc_core::Virtual
             item.added_chain_block_hashes.iter().map(|x| RpcHash::from_str(x)).collect::<Result<Vec<_>, _>>()?,
         ),
         accepted_transaction_ids: Arc::new(item.accepted_transaction_ids.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?),
+        // Sequences start at 1, zero standing for a node not journaling its notifications
+        sequence: (item.sequence > 0).then_some(item.sequence),
     }
 });
 
@@ -158,6 +174,7 @@ try_from!(item: &UtxosChangedNotificationMessage, kaspa_rpc_core::UtxosChangedNo
     Self {
         added: Arc::new(item.added.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?),
         removed: Arc::new(item.removed.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?),
+        sequence: (item.sequence > 0).then_some(item.sequence),
     }
 });
 
@@ -166,11 +183,21 @@ try_from!(item: &SinkBlueScoreChangedNotificationMessage, kaspa_rpc_core::SinkBl
 });
 
 try_from!(item: &VirtualDaaScoreChangedNotificationMessage, kaspa_rpc_core::VirtualDaaScoreChangedNotification, {
-    Self { virtual_daa_score: item.virtual_daa_score }
+    Self { virtual_daa_score: item.virtual_daa_score, virtual_info: item.virtual_info.as_ref().map(|x| x.try_into()).transpose()? }
 });
 
 try_from!(&PruningPointUtxoSetOverrideNotificationMessage, kaspa_rpc_core::PruningPointUtxoSetOverrideNotification);
 
+try_from!(item: &TransactionConflictNotificationMessage, kaspa_rpc_core::TransactionConflictNotification, {
+    Self {
+        transaction_id: RpcHash::from_str(&item.transaction_id)?,
+        conflicting_transaction_id: RpcHash::from_str(&item.conflicting_transaction_id)?,
+        outpoints: item.outpoints.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?,
+        is_conflicting_in_block: item.is_conflicting_in_block,
+        addresses: item.addresses.iter().map(|x| x.as_str().try_into()).collect::<Result<Vec<_>, _>>()?,
+    }
+});
+
 from!(item: RpcNotifyCommand, Command, {
     match item {
         RpcNotifyCommand::NotifyStart => Command::Start,
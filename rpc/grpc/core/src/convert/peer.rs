@@ -2,7 +2,7 @@ use std::str::FromStr;
 
 use crate::protowire;
 use crate::{from, try_from};
-use kaspa_rpc_core::{RpcError, RpcNodeId, RpcPeerAddress};
+use kaspa_rpc_core::{RpcConnectionOutcome, RpcError, RpcNodeId, RpcPeerAddress, RpcPeerAddressSource};
 
 // ----------------------------------------------------------------------------
 // rpc_core to protowire
@@ -19,11 +19,27 @@ from!(item: &kaspa_rpc_core::RpcPeerInfo, protowire::GetConnectedPeerInfoMessage
         advertised_protocol_version: item.advertised_protocol_version,
         time_connected: item.time_connected as i64,
         is_ibd_peer: item.is_ibd_peer,
+        served_bytes: item.served_bytes,
+        served_cost: item.served_cost,
+        is_manual: item.is_manual,
     }
 });
 
-from!(item: &kaspa_rpc_core::RpcPeerAddress, protowire::GetPeerAddressesKnownAddressMessage, { Self { addr: item.to_string() } });
-from!(item: &kaspa_rpc_core::RpcIpAddress, protowire::GetPeerAddressesKnownAddressMessage, { Self { addr: item.to_string() } });
+from!(item: &kaspa_rpc_core::RpcKnownPeerAddress, protowire::GetPeerAddressesKnownAddressMessage, {
+    Self {
+        addr: item.address.to_string(),
+        source: item.source.as_str().to_string(),
+        network_group: item.network_group.clone(),
+        connection_failed_count: item.connection_failed_count,
+        is_tried: item.is_tried,
+        last_seen: item.last_seen.unwrap_or_default(),
+        last_attempt_time: item.last_attempt_time.unwrap_or_default(),
+        last_attempt_outcome: item.last_attempt_outcome.map(|outcome| outcome.as_str().to_string()).unwrap_or_default(),
+    }
+});
+from!(item: &kaspa_rpc_core::RpcIpAddress, protowire::GetPeerAddressesKnownAddressMessage, {
+    Self { addr: item.to_string(), ..Default::default() }
+});
 
 // ----------------------------------------------------------------------------
 // protowire to rpc_core
@@ -40,8 +56,27 @@ try_from!(item: &protowire::GetConnectedPeerInfoMessage, kaspa_rpc_core::RpcPeer
         advertised_protocol_version: item.advertised_protocol_version,
         time_connected: item.time_connected as u64,
         is_ibd_peer: item.is_ibd_peer,
+        served_bytes: item.served_bytes,
+        served_cost: item.served_cost,
+        is_manual: item.is_manual,
     }
 });
 
-try_from!(item: &protowire::GetPeerAddressesKnownAddressMessage, kaspa_rpc_core::RpcPeerAddress, { Self::from_str(&item.addr)? });
+try_from!(item: &protowire::GetPeerAddressesKnownAddressMessage, kaspa_rpc_core::RpcKnownPeerAddress, {
+    Self {
+        address: RpcPeerAddress::from_str(&item.addr)?,
+        // Sources and outcomes unknown to this version, as reported by newer nodes, are mapped to `Unknown`
+        source: RpcPeerAddressSource::from_str(&item.source).unwrap_or(RpcPeerAddressSource::Unknown),
+        network_group: item.network_group.clone(),
+        connection_failed_count: item.connection_failed_count,
+        is_tried: item.is_tried,
+        last_seen: (item.last_seen > 0).then_some(item.last_seen),
+        last_attempt_time: (item.last_attempt_time > 0).then_some(item.last_attempt_time),
+        last_attempt_outcome: if item.last_attempt_outcome.is_empty() {
+            None
+        } else {
+            Some(RpcConnectionOutcome::from_str(&item.last_attempt_outcome).unwrap_or(RpcConnectionOutcome::Unknown))
+        },
+    }
+});
 try_from!(item: &protowire::GetPeerAddressesKnownAddressMessage, kaspa_rpc_core::RpcIpAddress, { Self::from_str(&item.addr)? });
@@ -0,0 +1,766 @@
+//!
+//! Round-trip coverage of the protowire conversions of all the RPC requests and responses.
+//!
+//! Every value is converted to a [`KaspadRequest`] or [`KaspadResponse`] and back, and the result
+//! is compared to the original through its JSON representation (most rpc-core types do not implement
+//! `PartialEq`). Converting the decoded value again must yield the very same protowire message.
+//!
+//! Note that optional values are encoded by protowire as the default value of their type, hence
+//! `Some(0)` or `Some("")` are decoded as `None`. The samples below never use these values.
+//!
+
+use crate::protowire::{self, KaspadRequest, KaspadResponse};
+use kaspa_consensus_core::{
+    arbitrary::{Generator, Limits},
+    config::params::MAINNET_PARAMS,
+    network::{NetworkId, NetworkType},
+    virtual_parents::VirtualParentExclusionReason,
+};
+use kaspa_notify::{
+    scope::{UtxosChangedScope, VirtualChainChangedScope},
+    subscription::Command,
+};
+use kaspa_rpc_core::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::Serialize;
+use std::{any::type_name, str::FromStr};
+
+const ADDRESS: &str = "kaspa:qp0yeh6savdtyglh9ete3qpshtdgmv2j2yaw70suhthh9aklhg227erlpvdrc";
+
+fn assert_request_roundtrip<T>(request: T)
+where
+    T: Serialize + for<'a> TryFrom<&'a KaspadRequest, Error = RpcError>,
+    for<'a> KaspadRequest: From<&'a T>,
+{
+    let message = KaspadRequest::from(&request);
+    let decoded = T::try_from(&message).unwrap_or_else(|err| panic!("{} failed to decode: {err}", type_name::<T>()));
+    assert_eq!(serde_json::to_value(&decoded).unwrap(), serde_json::to_value(&request).unwrap(), "{}", type_name::<T>());
+    assert_eq!(KaspadRequest::from(&decoded), message, "{}", type_name::<T>());
+}
+
+fn assert_response_roundtrip<T>(response: T)
+where
+    T: Serialize + for<'a> TryFrom<&'a KaspadResponse, Error = RpcError>,
+    for<'a> KaspadResponse: From<RpcResult<&'a T>>,
+{
+    let message = KaspadResponse::from(Ok::<&T, RpcError>(&response));
+    let decoded = T::try_from(&message).unwrap_or_else(|err| panic!("{} failed to decode: {err}", type_name::<T>()));
+    assert_eq!(serde_json::to_value(&decoded).unwrap(), serde_json::to_value(&response).unwrap(), "{}", type_name::<T>());
+    assert_eq!(KaspadResponse::from(Ok::<&T, RpcError>(&decoded)), message, "{}", type_name::<T>());
+
+    let error = RpcError::General("sample error".to_string());
+    let message = KaspadResponse::from(Err::<&T, _>(error.clone()));
+    match T::try_from(&message) {
+        Ok(_) => panic!("{} error response decoded as a success", type_name::<T>()),
+        Err(err) => assert_eq!(err.to_string(), error.to_string(), "{}", type_name::<T>()),
+    }
+}
+
+/// Generates sample values covering all the fields of the RPC types
+struct Samples {
+    generator: Generator<StdRng>,
+}
+
+impl Samples {
+    fn new() -> Self {
+        Self { generator: Generator::new(StdRng::seed_from_u64(42), Limits::from(&MAINNET_PARAMS)) }
+    }
+
+    /// A non-zero random value, so that it survives the protowire encoding of optional fields
+    fn non_zero(&mut self) -> u64 {
+        self.generator.rng().gen_range(1..=u32::MAX as u64)
+    }
+
+    fn hash(&mut self) -> RpcHash {
+        self.generator.hash()
+    }
+
+    fn hashes(&mut self, count: usize) -> Vec<RpcHash> {
+        (0..count).map(|_| self.hash()).collect()
+    }
+
+    fn address(&self) -> RpcAddress {
+        RpcAddress::try_from(ADDRESS).unwrap()
+    }
+
+    fn outpoint(&mut self) -> RpcTransactionOutpoint {
+        self.generator.outpoint()
+    }
+
+    fn utxo_entry(&mut self) -> RpcUtxoEntry {
+        let script_public_key = self.generator.script_public_key(MAINNET_PARAMS.max_script_public_key_len);
+        RpcUtxoEntry::new(self.non_zero(), script_public_key, self.non_zero(), true)
+    }
+
+    fn peer_address(&self) -> RpcContextualPeerAddress {
+        RpcContextualPeerAddress::from_str("10.0.0.1:16111").unwrap()
+    }
+
+    fn transaction(&mut self) -> RpcTransaction {
+        let mut transaction = RpcTransaction::from(&self.generator.transaction());
+        for input in transaction.inputs.iter_mut() {
+            input.verbose_data = Some(RpcTransactionInputVerboseData {});
+        }
+        for output in transaction.outputs.iter_mut() {
+            output.verbose_data = Some(RpcTransactionOutputVerboseData {
+                script_public_key_type: RpcScriptClass::PubKey,
+                script_public_key_address: self.address(),
+            });
+        }
+        transaction.payload = vec![1, 2, 3];
+        transaction.verbose_data = Some(RpcTransactionVerboseData {
+            transaction_id: self.hash(),
+            hash: self.hash(),
+            mass: self.non_zero(),
+            block_hash: self.hash(),
+            block_time: self.non_zero(),
+        });
+        transaction
+    }
+
+    fn header(&mut self) -> RpcHeader {
+        let hash_merkle_root = self.hash();
+        let mut header = self.generator.header(hash_merkle_root);
+        // Protowire carries the timestamp as an i64
+        header.timestamp >>= 1;
+        header.finalize();
+        header
+    }
+
+    fn block(&mut self) -> RpcBlock {
+        let header = self.header();
+        let transactions = (0..2).map(|_| self.transaction()).collect::<Vec<_>>();
+        let verbose_data = RpcBlockVerboseData {
+            hash: header.hash,
+            difficulty: 1234.5,
+            selected_parent_hash: self.hash(),
+            transaction_ids: transactions.iter().map(|tx| tx.verbose_data.as_ref().unwrap().transaction_id).collect(),
+            is_header_only: false,
+            blue_score: self.non_zero(),
+            children_hashes: self.hashes(2),
+            merge_set_blues_hashes: self.hashes(3),
+            merge_set_reds_hashes: self.hashes(1),
+            is_chain_block: true,
+            target: RpcDifficultyTarget::from_u64(self.non_zero()),
+            work: RpcBlueWorkType::from_u64(self.non_zero()),
+            network_hashes_per_second: Some(self.non_zero()),
+            propagation: Some(RpcBlockPropagation {
+                first_inv_time: Some(self.non_zero()),
+                header_time: Some(self.non_zero()),
+                validated_time: Some(self.non_zero()),
+            }),
+        };
+        RpcBlock { header, transactions, verbose_data: Some(verbose_data) }
+    }
+
+    fn mempool_entry(&mut self) -> RpcMempoolEntry {
+        RpcMempoolEntry::new(self.non_zero(), self.transaction(), true, true)
+    }
+
+    fn utxos_by_addresses_entry(&mut self, address: Option<RpcAddress>) -> RpcUtxosByAddressesEntry {
+        RpcUtxosByAddressesEntry { address, outpoint: self.outpoint(), utxo_entry: self.utxo_entry() }
+    }
+
+    fn outpoint_spender(&mut self) -> RpcOutpointSpender {
+        RpcOutpointSpender { transaction_id: self.hash(), block_hash: self.hash(), merging_block_hash: self.hash() }
+    }
+
+    fn acceptance_trace(&mut self) -> RpcTransactionAcceptanceTrace {
+        let details = vec![
+            None,
+            Some(RpcAcceptanceDetail::Mass { mass: self.non_zero(), limit: self.non_zero() }),
+            Some(RpcAcceptanceDetail::Fee { required: self.non_zero(), provided: self.non_zero() }),
+            Some(RpcAcceptanceDetail::MissingOutpoints(vec![self.outpoint(), self.outpoint()])),
+            Some(RpcAcceptanceDetail::MaturityShortfall { input_index: 1, shortfall: self.non_zero() }),
+            Some(RpcAcceptanceDetail::Dust { output_index: 2, value: self.non_zero(), threshold: self.non_zero() }),
+        ];
+        let outcomes = [RpcAcceptanceOutcome::Passed, RpcAcceptanceOutcome::Failed, RpcAcceptanceOutcome::Skipped];
+        let checks = details
+            .into_iter()
+            .enumerate()
+            .map(|(i, detail)| RpcAcceptanceCheck {
+                rule: format!("rule-{i}"),
+                contextual: i % 2 == 0,
+                outcome: outcomes[i % outcomes.len()],
+                error: (i % 3 == 1).then(|| format!("error-{i}")),
+                detail,
+            })
+            .collect();
+        RpcTransactionAcceptanceTrace { error: "transaction rejected".to_string(), checks }
+    }
+}
+
+#[test]
+fn test_request_roundtrips() {
+    let mut s = Samples::new();
+
+    assert_request_roundtrip(ShutdownRequest {});
+    assert_request_roundtrip(SubmitBlockRequest::new(s.block(), true));
+    assert_request_roundtrip(GetBlockTemplateRequest::new(s.address(), b"kaspa miner/0.1".to_vec()));
+    assert_request_roundtrip(GetBlockRequest::new(s.hash(), true));
+    assert_request_roundtrip(
+        GetBlockRequest::new(s.hash(), true)
+            .with_transaction_field_mask(RpcTransactionFieldMask::OMIT_SIGNATURE_SCRIPTS | RpcTransactionFieldMask::OMIT_PAYLOADS),
+    );
+    assert_request_roundtrip(GetInfoRequest {});
+    assert_request_roundtrip(GetCurrentNetworkRequest {});
+    assert_request_roundtrip(GetPeerAddressesRequest::default());
+    assert_request_roundtrip(GetPeerAddressesRequest {
+        only_tried: true,
+        only_new: true,
+        group_prefix: Some("10.0".to_string()),
+        limit: Some(7),
+    });
+    assert_request_roundtrip(GetSinkRequest {});
+    assert_request_roundtrip(GetMempoolEntryRequest::new(s.hash(), true, true));
+    assert_request_roundtrip(GetMempoolEntriesRequest::new(true, true));
+    assert_request_roundtrip(GetMempoolEntriesRequest::new(false, false).with_consistent_snapshot(true));
+    assert_request_roundtrip(GetConnectedPeerInfoRequest {});
+    assert_request_roundtrip(AddPeerRequest::new(s.peer_address(), true));
+    let transaction = s.transaction();
+    assert_request_roundtrip(
+        SubmitTransactionRequest::new(transaction, true)
+            .with_allow_not_yet_valid(true)
+            .with_verbose(true)
+            .with_idempotency_key(Some("payout-42".to_string())),
+    );
+    assert_request_roundtrip(GetSubnetworkRequest::new(RpcSubnetworkId::from_byte(3)));
+    assert_request_roundtrip(GetVirtualChainFromBlockRequest::new(s.hash(), true));
+    assert_request_roundtrip(GetBlocksRequest::new(None, true, true));
+    assert_request_roundtrip(GetBlocksRequest::new(Some(s.hash()), true, false));
+    assert_request_roundtrip(
+        GetBlocksRequest::new(None, true, true).with_transaction_field_mask(RpcTransactionFieldMask::OUTPUTS_ONLY),
+    );
+    assert_request_roundtrip(GetBlockCountRequest {});
+    assert_request_roundtrip(GetBlockDagInfoRequest {});
+    assert_request_roundtrip(ResolveFinalityConflictRequest::new(s.hash()));
+    assert_request_roundtrip(GetHeadersRequest::new(s.hash(), 11, true));
+    assert_request_roundtrip(GetUtxosByAddressesRequest { addresses: vec![s.address(), s.address()] });
+    assert_request_roundtrip(GetBalanceByAddressRequest::new(s.address()));
+    assert_request_roundtrip(GetBalancesByAddressesRequest::new(vec![s.address()]));
+    assert_request_roundtrip(GetSinkBlueScoreRequest {});
+    assert_request_roundtrip(GetVirtualInfoRequest {});
+    assert_request_roundtrip(BanRequest { ip: RpcIpAddress::from_str("1.2.3.4").unwrap() });
+    assert_request_roundtrip(UnbanRequest { ip: RpcIpAddress::from_str("2001:db8::1").unwrap() });
+    assert_request_roundtrip(EstimateNetworkHashesPerSecondRequest { window_size: 1000, start_hash: None });
+    assert_request_roundtrip(EstimateNetworkHashesPerSecondRequest { window_size: 1000, start_hash: Some(s.hash()) });
+    assert_request_roundtrip(GetMempoolEntriesByAddressesRequest {
+        addresses: vec![s.address()],
+        include_orphan_pool: true,
+        filter_transaction_pool: true,
+    });
+    assert_request_roundtrip(GetCoinSupplyRequest {});
+    assert_request_roundtrip(PingRequest {});
+    assert_request_roundtrip(GetMetricsRequest {
+        process_metrics: true,
+        connection_metrics: true,
+        bandwidth_metrics: true,
+        consensus_metrics: true,
+        script_metrics: true,
+    });
+    assert_request_roundtrip(GetServerInfoRequest {});
+    assert_request_roundtrip(GetSyncStatusRequest {});
+    assert_request_roundtrip(GetDaaScoreTimestampEstimateRequest { daa_scores: vec![0, 1, u64::MAX] });
+    assert_request_roundtrip(GetChainBlockByDaaScoreRequest { daa_score: s.non_zero() });
+    assert_request_roundtrip(SetLogFiltersRequest { filters: "debug,kaspa_rpc_service=trace".to_string() });
+    assert_request_roundtrip(GetOutpointSpendStatusRequest { outpoint: s.outpoint(), search_depth: None });
+    assert_request_roundtrip(GetOutpointSpendStatusRequest { outpoint: s.outpoint(), search_depth: Some(s.non_zero()) });
+    assert_request_roundtrip(EstimateTransactionConfirmationTimeRequest { transaction_id: s.hash() });
+    assert_request_roundtrip(GetAcceptanceProofRequest { accepting_block_hash: s.hash(), transaction_id: s.hash() });
+    assert_request_roundtrip(CreateBackupRequest { output_directory: "/tmp/kaspa-backup".to_string() });
+    assert_request_roundtrip(GetVirtualParentSelectionTraceRequest {});
+    assert_request_roundtrip(GetSystemInfoRequest {});
+    assert_request_roundtrip(RemovePeerRequest { peer_address: s.peer_address() });
+    assert_request_roundtrip(SetProcessingParallelismRequest { limit: 3 });
+    assert_request_roundtrip(GetAddressActivityRequest::new(vec![s.address(), s.address()]));
+    assert_request_roundtrip(GetMissedNotificationsRequest::new(VirtualChainChangedScope::new(true).into(), s.non_zero()));
+    assert_request_roundtrip(GetMissedNotificationsRequest::new(UtxosChangedScope::new(vec![s.address()]).into(), s.non_zero()));
+    assert_request_roundtrip(GetUtxoReturnAddressRequest::new(s.hash(), s.hash(), 2));
+    assert_request_roundtrip(ValidateBlockTemplateRequest::new(s.block()));
+
+    for command in [Command::Start, Command::Stop] {
+        assert_request_roundtrip(NotifyBlockAddedRequest { command });
+        assert_request_roundtrip(NotifyNewBlockTemplateRequest { command });
+        assert_request_roundtrip(NotifyUtxosChangedRequest { addresses: vec![s.address()], command });
+        assert_request_roundtrip(NotifyPruningPointUtxoSetOverrideRequest { command });
+        assert_request_roundtrip(NotifyFinalityConflictRequest { command });
+        assert_request_roundtrip(NotifyVirtualDaaScoreChangedRequest { command });
+        assert_request_roundtrip(NotifyVirtualChainChangedRequest { include_accepted_transaction_ids: true, command });
+        assert_request_roundtrip(NotifySinkBlueScoreChangedRequest { command });
+        assert_request_roundtrip(NotifyTransactionConflictRequest { addresses: vec![s.address()], command });
+    }
+}
+
+#[test]
+fn test_response_roundtrips() {
+    let mut s = Samples::new();
+
+    assert_response_roundtrip(ShutdownResponse {});
+    assert_response_roundtrip(SubmitBlockResponse::new(SubmitBlockReport::Success, Some(SubmitBlockClassification::Fresh)));
+    assert_response_roundtrip(
+        SubmitBlockResponse::new(SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid), None)
+            .with_non_canonical_transaction_order(true),
+    );
+    assert_response_roundtrip(SubmitBlockResponse::new(
+        SubmitBlockReport::Reject(SubmitBlockRejectReason::RouteIsFull),
+        Some(SubmitBlockClassification::AlreadyProcessed),
+    ));
+    assert_response_roundtrip(
+        SubmitBlockResponse::new(
+            SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid),
+            Some(SubmitBlockClassification::Fresh),
+        )
+        .with_rejection_detail("coinbase transaction pays a total of 2 sompi but is expected to pay 1 sompi (subsidy 1 + fees 0)"),
+    );
+    assert_response_roundtrip(GetBlockTemplateResponse::new(s.block(), true, None));
+    let selection_stats = RpcBlockTemplateStats {
+        total_fees: 123_456_789,
+        total_mass: 250_000,
+        max_block_mass: 500_000,
+        considered_count: 1_200,
+        selected_count: 87,
+        min_feerate: 1.5,
+    };
+    assert_response_roundtrip(GetBlockTemplateResponse::new(s.block(), false, Some(selection_stats)));
+    assert_response_roundtrip(GetBlockResponse { block: s.block() });
+    assert_response_roundtrip(GetInfoResponse {
+        p2p_id: "f0e1d2c3-b4a5-4697-8899-aabbccddeeff".to_string(),
+        mempool_size: s.non_zero(),
+        server_version: "0.14.1".to_string(),
+        is_utxo_indexed: true,
+        is_synced: true,
+        has_notify_command: true,
+        has_message_id: true,
+        params_digest: Some(s.hash()),
+        dust_thresholds: vec![
+            RpcDustThreshold::new(RpcScriptClass::PubKey, s.non_zero()),
+            RpcDustThreshold::new(RpcScriptClass::ScriptHash, s.non_zero()),
+        ],
+        consensus_status: Some(RpcConsensusStatus {
+            current: Some(RpcConsensusEntry {
+                key: s.non_zero(),
+                directory_name: "consensus-003".to_string(),
+                creation_timestamp: s.non_zero(),
+                creation_reason: RpcConsensusCreationReason::PruningPointSync,
+            }),
+            staging: None,
+            is_staging_prepared: false,
+            retired: vec![
+                RpcRetiredConsensusEntry {
+                    entry: RpcConsensusEntry {
+                        key: s.non_zero(),
+                        directory_name: "consensus-001".to_string(),
+                        creation_timestamp: s.non_zero(),
+                        creation_reason: RpcConsensusCreationReason::Genesis,
+                    },
+                    retirement_timestamp: Some(s.non_zero()),
+                    disk_usage: s.non_zero(),
+                },
+                RpcRetiredConsensusEntry {
+                    entry: RpcConsensusEntry {
+                        key: s.non_zero(),
+                        directory_name: "consensus-002".to_string(),
+                        creation_timestamp: s.non_zero(),
+                        creation_reason: RpcConsensusCreationReason::Unknown,
+                    },
+                    retirement_timestamp: None,
+                    disk_usage: 0,
+                },
+            ],
+        }),
+    });
+    assert_response_roundtrip(GetInfoResponse {
+        p2p_id: "f0e1d2c3-b4a5-4697-8899-aabbccddeeff".to_string(),
+        mempool_size: s.non_zero(),
+        server_version: "0.14.1".to_string(),
+        is_utxo_indexed: true,
+        is_synced: true,
+        has_notify_command: true,
+        has_message_id: true,
+        params_digest: Some(s.hash()),
+        dust_thresholds: vec![],
+        consensus_status: Some(RpcConsensusStatus {
+            current: Some(RpcConsensusEntry {
+                key: s.non_zero(),
+                directory_name: "consensus-004".to_string(),
+                creation_timestamp: s.non_zero(),
+                creation_reason: RpcConsensusCreationReason::Genesis,
+            }),
+            staging: Some(RpcConsensusEntry {
+                key: s.non_zero(),
+                directory_name: "consensus-005".to_string(),
+                creation_timestamp: s.non_zero(),
+                creation_reason: RpcConsensusCreationReason::PruningPointSync,
+            }),
+            is_staging_prepared: true,
+            retired: vec![],
+        }),
+    });
+    assert_response_roundtrip(GetCurrentNetworkResponse::new(NetworkType::Testnet));
+    assert_response_roundtrip(GetPeerAddressesResponse::new(
+        vec![RpcKnownPeerAddress {
+            address: RpcPeerAddress::from_str("[2001:db8::1]:16111").unwrap(),
+            source: RpcPeerAddressSource::PeerGossip,
+            network_group: "2001:db8:0:0".to_string(),
+            connection_failed_count: s.non_zero(),
+            is_tried: true,
+            last_seen: Some(s.non_zero()),
+            last_attempt_time: Some(s.non_zero()),
+            last_attempt_outcome: Some(RpcConnectionOutcome::Failure),
+        }],
+        vec![RpcIpAddress::from_str("5.6.7.8").unwrap()],
+    ));
+    assert_response_roundtrip(GetSinkResponse::new(s.hash()));
+    assert_response_roundtrip(GetMempoolEntryResponse::new(s.mempool_entry()));
+    let entries = (0..2).map(|_| s.mempool_entry()).collect();
+    let summary = RpcMempoolSummary {
+        transaction_count: s.non_zero(),
+        total_mass: s.non_zero(),
+        total_fees: s.non_zero(),
+        timestamp: s.non_zero(),
+    };
+    assert_response_roundtrip(GetMempoolEntriesResponse::new(entries, summary));
+    assert_response_roundtrip(GetConnectedPeerInfoResponse::new(vec![RpcPeerInfo {
+        id: RpcNodeId::from_str("f0e1d2c3-b4a5-4697-8899-aabbccddeeff").unwrap(),
+        address: RpcPeerAddress::from_str("10.0.0.1:16111").unwrap(),
+        last_ping_duration: s.non_zero(),
+        is_outbound: true,
+        time_offset: -1500,
+        user_agent: "/kaspad:0.14.1/".to_string(),
+        advertised_protocol_version: 6,
+        time_connected: s.non_zero(),
+        is_ibd_peer: true,
+        served_bytes: s.non_zero(),
+        served_cost: s.non_zero(),
+        is_manual: true,
+    }]));
+    assert_response_roundtrip(AddPeerResponse {});
+    assert_response_roundtrip(SubmitTransactionResponse::new(s.hash()));
+    let trace = s.acceptance_trace();
+    assert_response_roundtrip(SubmitTransactionResponse::rejected(s.hash(), trace));
+    assert_response_roundtrip(SubmitTransactionResponse::already_known(s.hash()).with_idempotency_key(Some("payout-42".to_string())));
+    assert_response_roundtrip(SubmitTransactionResponse::already_accepted(s.hash(), s.hash()));
+    assert_response_roundtrip(GetSubnetworkResponse::new(s.non_zero()));
+    let accepted_transaction_ids =
+        vec![RpcAcceptedTransactionIds { accepting_block_hash: s.hash(), accepted_transaction_ids: s.hashes(3) }];
+    assert_response_roundtrip(GetVirtualChainFromBlockResponse::new(s.hashes(1), s.hashes(2), accepted_transaction_ids));
+    let blocks = vec![s.block(), s.block()];
+    assert_response_roundtrip(GetBlocksResponse::new(blocks.iter().map(|block| block.header.hash).collect(), blocks));
+    assert_response_roundtrip(GetBlockCountResponse::new(s.non_zero(), s.non_zero()));
+    assert_response_roundtrip(GetBlockDagInfoResponse::new(
+        NetworkId::with_suffix(NetworkType::Testnet, 11),
+        s.non_zero(),
+        s.non_zero(),
+        s.hashes(2),
+        98765.25,
+        s.non_zero(),
+        s.hashes(3),
+        s.hash(),
+        s.non_zero(),
+        s.hash(),
+    ));
+    assert_response_roundtrip(ResolveFinalityConflictResponse {});
+    // GetHeadersResponse is not covered since protowire only carries the header hashes
+    let address = s.address();
+    let entries = vec![s.utxos_by_addresses_entry(Some(address)), s.utxos_by_addresses_entry(None)];
+    assert_response_roundtrip(GetUtxosByAddressesResponse { entries });
+    assert_response_roundtrip(GetBalanceByAddressResponse::new(s.non_zero()));
+    assert_response_roundtrip(GetBalancesByAddressesResponse::new(vec![
+        RpcBalancesByAddressesEntry { address: s.address(), balance: Some(s.non_zero()) },
+        RpcBalancesByAddressesEntry { address: s.address(), balance: None },
+    ]));
+    assert_response_roundtrip(GetSinkBlueScoreResponse::new(s.non_zero()));
+    assert_response_roundtrip(GetVirtualInfoResponse {
+        daa_score: s.non_zero(),
+        sink: s.hash(),
+        sink_blue_score: s.non_zero(),
+        parents_count: 3,
+        past_median_time: s.non_zero(),
+    });
+    assert_response_roundtrip(BanResponse {});
+    assert_response_roundtrip(UnbanResponse {});
+    assert_response_roundtrip(EstimateNetworkHashesPerSecondResponse { network_hashes_per_second: s.non_zero() });
+    let (sending, receiving) = (vec![s.mempool_entry()], vec![s.mempool_entry(), s.mempool_entry()]);
+    assert_response_roundtrip(GetMempoolEntriesByAddressesResponse::new(vec![RpcMempoolEntryByAddress::new(
+        s.address(),
+        sending,
+        receiving,
+    )]));
+    assert_response_roundtrip(GetCoinSupplyResponse::new(s.non_zero(), s.non_zero(), s.non_zero(), s.non_zero()));
+    assert_response_roundtrip(PingResponse {});
+    assert_response_roundtrip(GetMetricsResponse {
+        server_time: s.non_zero(),
+        process_metrics: None,
+        connection_metrics: None,
+        bandwidth_metrics: None,
+        consensus_metrics: None,
+        script_metrics: None,
+    });
+    assert_response_roundtrip(GetMetricsResponse {
+        server_time: s.non_zero(),
+        process_metrics: Some(ProcessMetrics {
+            resident_set_size: s.non_zero(),
+            virtual_memory_size: s.non_zero(),
+            core_num: 8,
+            cpu_usage: 0.25,
+            fd_num: 120,
+            disk_io_read_bytes: s.non_zero(),
+            disk_io_write_bytes: s.non_zero(),
+            disk_io_read_per_sec: 1.5,
+            disk_io_write_per_sec: 2.5,
+        }),
+        connection_metrics: Some(ConnectionMetrics {
+            borsh_live_connections: 1,
+            borsh_connection_attempts: s.non_zero(),
+            borsh_handshake_failures: s.non_zero(),
+            json_live_connections: 2,
+            json_connection_attempts: s.non_zero(),
+            json_handshake_failures: s.non_zero(),
+            active_peers: 3,
+        }),
+        bandwidth_metrics: Some(BandwidthMetrics {
+            borsh_bytes_tx: s.non_zero(),
+            borsh_bytes_rx: s.non_zero(),
+            json_bytes_tx: s.non_zero(),
+            json_bytes_rx: s.non_zero(),
+            p2p_bytes_tx: s.non_zero(),
+            p2p_bytes_rx: s.non_zero(),
+            grpc_bytes_tx: s.non_zero(),
+            grpc_bytes_rx: s.non_zero(),
+        }),
+        consensus_metrics: Some(ConsensusMetrics {
+            node_blocks_submitted_count: s.non_zero(),
+            node_headers_processed_count: s.non_zero(),
+            node_dependencies_processed_count: s.non_zero(),
+            node_bodies_processed_count: s.non_zero(),
+            node_transactions_processed_count: s.non_zero(),
+            node_chain_blocks_processed_count: s.non_zero(),
+            node_mass_processed_count: s.non_zero(),
+            node_database_blocks_count: s.non_zero(),
+            node_database_headers_count: s.non_zero(),
+            network_mempool_size: s.non_zero(),
+            network_tip_hashes_count: 4,
+            network_difficulty: 4321.75,
+            network_past_median_time: s.non_zero(),
+            network_virtual_parent_hashes_count: 5,
+            network_virtual_daa_score: s.non_zero(),
+            node_block_propagation_sample_count: s.non_zero(),
+            node_block_propagation_p50_millis: s.non_zero(),
+            node_block_propagation_p90_millis: s.non_zero(),
+            node_block_propagation_p99_millis: s.non_zero(),
+            node_processing_parallelism_limit: 6,
+            node_processing_running_count: 3,
+            node_processing_queued_count: 7,
+        }),
+        script_metrics: Some(ScriptMetrics {
+            checked_transactions_count: s.non_zero(),
+            rejection_counts: vec![
+                RpcScriptRejectionCount { reason: "EvalFalse".to_string(), count: s.non_zero() },
+                RpcScriptRejectionCount { reason: "NullFail".to_string(), count: s.non_zero() },
+            ],
+            opcode_sample_rate: 100,
+            sampled_transactions_count: s.non_zero(),
+            opcode_counts: vec![
+                RpcOpcodeCount { opcode: 0x41, count: s.non_zero() },
+                RpcOpcodeCount { opcode: 0xac, count: s.non_zero() },
+            ],
+        }),
+    });
+    assert_response_roundtrip(GetServerInfoResponse {
+        rpc_api_version: [0, 1, 2, 3],
+        server_version: "0.14.1".to_string(),
+        network_id: NetworkId::new(NetworkType::Mainnet),
+        has_utxo_index: true,
+        is_synced: true,
+        virtual_daa_score: s.non_zero(),
+    });
+    assert_response_roundtrip(GetSyncStatusResponse { is_synced: true });
+    assert_response_roundtrip(GetDaaScoreTimestampEstimateResponse { timestamps: vec![s.non_zero(), s.non_zero()] });
+    assert_response_roundtrip(GetChainBlockByDaaScoreResponse { hash: s.hash(), daa_score: s.non_zero(), timestamp: s.non_zero() });
+    assert_response_roundtrip(SetLogFiltersResponse { filters: "info".to_string() });
+    assert_response_roundtrip(GetOutpointSpendStatusResponse::new(None, None, None, vec![]));
+    let conflicting_spenders = vec![s.outpoint_spender(), s.outpoint_spender()];
+    assert_response_roundtrip(GetOutpointSpendStatusResponse::new(
+        Some(s.utxo_entry()),
+        Some(s.hash()),
+        Some(s.outpoint_spender()),
+        conflicting_spenders,
+    ));
+    assert_response_roundtrip(EstimateTransactionConfirmationTimeResponse {
+        estimate: RpcConfirmationTimeEstimate {
+            package_feerate: 1.25,
+            transactions_ahead: s.non_zero(),
+            mass_ahead: s.non_zero(),
+            block_utilization: 0.75,
+            block_interval: 1000.5,
+            min_blocks: s.non_zero(),
+            max_blocks: s.non_zero(),
+            min_time: s.non_zero(),
+            max_time: s.non_zero(),
+        },
+    });
+    assert_response_roundtrip(GetAcceptanceProofResponse { accepted_id_merkle_root: s.hash(), leaf_index: 9, path: s.hashes(4) });
+    assert_response_roundtrip(CreateBackupResponse {
+        node_version: "0.14.1".to_string(),
+        network_id: NetworkId::with_suffix(NetworkType::Testnet, 10),
+        params_digest: s.hash(),
+        pruning_point: s.hash(),
+        sink: s.hash(),
+        timestamp: s.non_zero(),
+    });
+    let reasons = [
+        VirtualParentExclusionReason::MergesetSizeLimit,
+        VirtualParentExclusionReason::MergeDepthViolation,
+        VirtualParentExclusionReason::Disqualified,
+        VirtualParentExclusionReason::ParentLimit,
+    ];
+    assert_response_roundtrip(GetVirtualParentSelectionTraceResponse {
+        tips: s.hashes(6),
+        selected_parent: s.hash(),
+        virtual_parents: s.hashes(2),
+        excluded_tips: reasons.into_iter().map(|reason| RpcExcludedVirtualParent::new(s.hash(), reason)).collect(),
+    });
+    let statuses = [
+        RpcPreflightCheckStatus::Passed,
+        RpcPreflightCheckStatus::Warning,
+        RpcPreflightCheckStatus::Overridden,
+        RpcPreflightCheckStatus::Failed,
+        RpcPreflightCheckStatus::Pending,
+    ];
+    assert_response_roundtrip(GetSystemInfoResponse {
+        version: "0.14.1".to_string(),
+        fd_limit: 4096,
+        preflight_checks: statuses.into_iter().map(|status| RpcPreflightCheck::new("disk", status, "message".to_string())).collect(),
+    });
+    assert_response_roundtrip(RemovePeerResponse {});
+    assert_response_roundtrip(SetProcessingParallelismResponse { previous_limit: 8 });
+    assert_response_roundtrip(GetAddressActivityResponse::new(vec![
+        RpcAddressActivityEntry {
+            address: s.address(),
+            has_received: true,
+            first_seen_daa_score: s.non_zero(),
+            last_activity_daa_score: s.non_zero(),
+        },
+        RpcAddressActivityEntry::unused(s.address()),
+    ]));
+    let virtual_chain_changed = VirtualChainChangedNotification {
+        removed_chain_block_hashes: s.hashes(1).into(),
+        added_chain_block_hashes: s.hashes(2).into(),
+        accepted_transaction_ids: vec![RpcAcceptedTransactionIds {
+            accepting_block_hash: s.hash(),
+            accepted_transaction_ids: s.hashes(2),
+        }]
+        .into(),
+        sequence: Some(s.non_zero()),
+    };
+    assert_response_roundtrip(GetMissedNotificationsResponse::new(vec![virtual_chain_changed], vec![], s.non_zero()));
+    let utxos_changed = UtxosChangedNotification {
+        added: vec![s.utxos_by_addresses_entry(Some(s.address()))].into(),
+        removed: vec![s.utxos_by_addresses_entry(Some(s.address()))].into(),
+        sequence: Some(s.non_zero()),
+    };
+    assert_response_roundtrip(GetMissedNotificationsResponse::new(vec![], vec![utxos_changed], s.non_zero()));
+    assert_response_roundtrip(GetUtxoReturnAddressResponse::new(Some(s.address()), s.utxo_entry()));
+    assert_response_roundtrip(GetUtxoReturnAddressResponse::new(None, s.utxo_entry()));
+    assert_response_roundtrip(ValidateBlockTemplateResponse::valid(s.non_zero(), s.non_zero()));
+    assert_response_roundtrip(ValidateBlockTemplateResponse::invalid(
+        "invalid transactions in new block template",
+        vec![RpcInvalidTemplateTransaction { transaction_id: s.hash(), reason: "transaction input #0 is missing".to_string() }],
+    ));
+
+    assert_response_roundtrip(NotifyBlockAddedResponse {});
+    assert_response_roundtrip(NotifyNewBlockTemplateResponse {});
+    assert_response_roundtrip(NotifyUtxosChangedResponse {});
+    assert_response_roundtrip(NotifyPruningPointUtxoSetOverrideResponse {});
+    assert_response_roundtrip(NotifyFinalityConflictResponse {});
+    assert_response_roundtrip(NotifyVirtualDaaScoreChangedResponse {});
+    assert_response_roundtrip(NotifyVirtualChainChangedResponse {});
+    assert_response_roundtrip(NotifySinkBlueScoreChangedResponse {});
+    assert_response_roundtrip(NotifyTransactionConflictResponse {});
+}
+
+#[test]
+fn test_unknown_enum_values() {
+    // Values added by newer nodes must not fail the decoding of the whole message
+    let message = protowire::GetPeerAddressesKnownAddressMessage {
+        addr: "10.0.0.1:16111".to_string(),
+        source: "futureSource".to_string(),
+        last_attempt_outcome: "futureOutcome".to_string(),
+        ..Default::default()
+    };
+    let address = RpcKnownPeerAddress::try_from(&message).unwrap();
+    assert_eq!(address.source, RpcPeerAddressSource::Unknown);
+    assert_eq!(address.last_attempt_outcome, Some(RpcConnectionOutcome::Unknown));
+
+    let message =
+        protowire::RpcAcceptanceCheck { rule: "rule".to_string(), outcome: "futureOutcome".to_string(), ..Default::default() };
+    assert_eq!(RpcAcceptanceCheck::try_from(&message).unwrap().outcome, RpcAcceptanceOutcome::Unknown);
+
+    let message =
+        protowire::RpcPreflightCheck { name: "check".to_string(), status: "futureStatus".to_string(), message: String::new() };
+    assert_eq!(RpcPreflightCheck::try_from(&message).unwrap().status, RpcPreflightCheckStatus::Unknown);
+
+    let mut message = protowire::SubmitTransactionResponseMessage {
+        transaction_id: RpcHash::from_u64_word(1).to_string(),
+        status: "futureStatus".to_string(),
+        ..Default::default()
+    };
+    let response: RpcResult<SubmitTransactionResponse> = (&message).try_into();
+    assert_eq!(response.unwrap().status, RpcSubmitTransactionStatus::Unknown);
+    // Older nodes report no status, their successful submissions being new ones
+    message.status = String::new();
+    let response: RpcResult<SubmitTransactionResponse> = (&message).try_into();
+    assert_eq!(response.unwrap().status, RpcSubmitTransactionStatus::Submitted);
+
+    let message = protowire::RpcTransactionOutputVerboseData {
+        script_public_key_type: "futureClass".to_string(),
+        script_public_key_address: ADDRESS.to_string(),
+    };
+    assert_eq!(RpcTransactionOutputVerboseData::try_from(&message).unwrap().script_public_key_type, RpcScriptClass::NonStandard);
+
+    let message = protowire::GetInfoResponseMessage {
+        dust_thresholds: vec![
+            protowire::RpcDustThreshold { script_class: "futureClass".to_string(), min_output_value: 1 },
+            protowire::RpcDustThreshold { script_class: RpcScriptClass::PubKey.to_string(), min_output_value: 2 },
+        ],
+        ..Default::default()
+    };
+    let response: RpcResult<GetInfoResponse> = (&message).try_into();
+    let thresholds = response.unwrap().dust_thresholds;
+    assert_eq!(thresholds.iter().map(|x| (x.script_class, x.min_output_value)).collect::<Vec<_>>(), vec![(RpcScriptClass::PubKey, 2)]);
+
+    // Unknown values remain encodable, so that they can be relayed as is
+    assert_eq!(RpcConnectionOutcome::from_str(RpcConnectionOutcome::Unknown.as_str()).unwrap(), RpcConnectionOutcome::Unknown);
+    assert_eq!(RpcAcceptanceOutcome::from_str(RpcAcceptanceOutcome::Unknown.as_str()).unwrap(), RpcAcceptanceOutcome::Unknown);
+    assert_eq!(
+        RpcPreflightCheckStatus::from_str(RpcPreflightCheckStatus::Unknown.as_str()).unwrap(),
+        RpcPreflightCheckStatus::Unknown
+    );
+    assert_eq!(
+        RpcSubmitTransactionStatus::from_str(RpcSubmitTransactionStatus::Unknown.as_str()).unwrap(),
+        RpcSubmitTransactionStatus::Unknown
+    );
+}
+
+#[tokio::test]
+async fn test_error_trace_ids() {
+    use kaspa_core::log::trace_id::{self, TraceId};
+
+    // Errors converted outside of any call carry no trace id
+    let message = protowire::RpcError::from(&RpcError::NotImplemented);
+    assert!(message.trace_id.is_empty());
+    assert!(matches!(RpcError::from(&message), RpcError::General(_)));
+
+    // Errors converted while serving a call carry its trace id
+    let trace_id = TraceId::from_client("call-1").unwrap();
+    let message = trace_id::scope(trace_id, async { protowire::RpcError::from(&RpcError::NotImplemented) }).await;
+    assert_eq!((message.message.as_str(), message.trace_id.as_str()), (RpcError::NotImplemented.to_string().as_str(), "call-1"));
+    let err = RpcError::from(&message);
+    assert_eq!(err.trace_id(), Some("call-1"));
+    assert_eq!(err.to_string(), format!("{} (trace id: call-1)", RpcError::NotImplemented));
+
+    // The trace id reported by the node is kept when relaying the error
+    let relayed = trace_id::scope(TraceId::generate(), async { protowire::RpcError::from(&err) }).await;
+    assert_eq!(relayed, message);
+    assert_eq!(err.clone().with_trace_id("other").trace_id(), Some("call-1"));
+}
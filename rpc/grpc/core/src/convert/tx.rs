@@ -82,6 +82,14 @@ from!(item: &kaspa_rpc_core::RpcAcceptedTransactionIds, protowire::RpcAcceptedTr
     }
 });
 
+from!(item: &kaspa_rpc_core::RpcOutpointSpender, protowire::RpcOutpointSpender, {
+    Self {
+        transaction_id: item.transaction_id.to_string(),
+        block_hash: item.block_hash.to_string(),
+        merging_block_hash: item.merging_block_hash.to_string(),
+    }
+});
+
 from!(item: &kaspa_rpc_core::RpcUtxosByAddressesEntry, protowire::RpcUtxosByAddressesEntry, {
     Self {
         address: item.address.as_ref().map_or("".to_string(), |x| x.into()),
@@ -177,7 +185,8 @@ try_from!(&protowire::RpcTransactionInputVerboseData, kaspa_rpc_core::RpcTransac
 
 try_from!(item: &protowire::RpcTransactionOutputVerboseData, kaspa_rpc_core::RpcTransactionOutputVerboseData, {
     Self {
-        script_public_key_type: item.script_public_key_type.as_str().try_into()?,
+        // Script classes unknown to this version, as reported by newer nodes, are non standard to it
+        script_public_key_type: item.script_public_key_type.as_str().try_into().unwrap_or(kaspa_rpc_core::RpcScriptClass::NonStandard),
         script_public_key_address: item.script_public_key_address.as_str().try_into()?,
     }
 });
@@ -189,6 +198,14 @@ try_from!(item: &protowire::RpcAcceptedTransactionIds, kaspa_rpc_core::RpcAccept
     }
 });
 
+try_from!(item: &protowire::RpcOutpointSpender, kaspa_rpc_core::RpcOutpointSpender, {
+    Self {
+        transaction_id: RpcHash::from_str(&item.transaction_id)?,
+        block_hash: RpcHash::from_str(&item.block_hash)?,
+        merging_block_hash: RpcHash::from_str(&item.merging_block_hash)?,
+    }
+});
+
 try_from!(item: &protowire::RpcUtxosByAddressesEntry, kaspa_rpc_core::RpcUtxosByAddressesEntry, {
     let address = if item.address.is_empty() { None } else { Some(item.address.as_str().try_into()?) };
     Self {
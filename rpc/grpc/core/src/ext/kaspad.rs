@@ -3,13 +3,17 @@ use kaspa_notify::{scope::Scope, subscription::Command};
 use crate::protowire::{
     kaspad_request, kaspad_response, KaspadRequest, KaspadResponse, NotifyBlockAddedRequestMessage,
     NotifyFinalityConflictRequestMessage, NotifyNewBlockTemplateRequestMessage, NotifyPruningPointUtxoSetOverrideRequestMessage,
-    NotifySinkBlueScoreChangedRequestMessage, NotifyUtxosChangedRequestMessage, NotifyVirtualChainChangedRequestMessage,
-    NotifyVirtualDaaScoreChangedRequestMessage,
+    NotifySinkBlueScoreChangedRequestMessage, NotifyTransactionConflictRequestMessage, NotifyUtxosChangedRequestMessage,
+    NotifyVirtualChainChangedRequestMessage, NotifyVirtualDaaScoreChangedRequestMessage,
 };
 
 impl KaspadRequest {
     pub fn from_notification_type(scope: &Scope, command: Command) -> Self {
-        KaspadRequest { id: 0, payload: Some(kaspad_request::Payload::from_notification_type(scope, command)) }
+        KaspadRequest {
+            id: 0,
+            trace_id: Default::default(),
+            payload: Some(kaspad_request::Payload::from_notification_type(scope, command)),
+        }
     }
 
     pub fn is_subscription(&self) -> bool {
@@ -64,6 +68,12 @@ impl kaspad_request::Payload {
                     command: command.into(),
                 })
             }
+            Scope::TransactionConflict(ref scope) => {
+                kaspad_request::Payload::NotifyTransactionConflictRequest(NotifyTransactionConflictRequestMessage {
+                    addresses: scope.addresses.iter().map(|x| x.into()).collect::<Vec<String>>(),
+                    command: command.into(),
+                })
+            }
         }
     }
 
@@ -79,6 +89,7 @@ impl kaspad_request::Payload {
                 | Payload::NotifyVirtualDaaScoreChangedRequest(_)
                 | Payload::NotifyPruningPointUtxoSetOverrideRequest(_)
                 | Payload::NotifyNewBlockTemplateRequest(_)
+                | Payload::NotifyTransactionConflictRequest(_)
                 | Payload::StopNotifyingUtxosChangedRequest(_)
                 | Payload::StopNotifyingPruningPointUtxoSetOverrideRequest(_)
         )
@@ -108,6 +119,7 @@ impl kaspad_response::Payload {
             Payload::VirtualDaaScoreChangedNotification(_) => true,
             Payload::PruningPointUtxoSetOverrideNotification(_) => true,
             Payload::NewBlockTemplateNotification(_) => true,
+            Payload::TransactionConflictNotification(_) => true,
             _ => false,
         }
     }
@@ -81,6 +81,21 @@ pub enum KaspadPayloadOps {
     GetServerInfo,
     GetSyncStatus,
     GetDaaScoreTimestampEstimate,
+    GetChainBlockByDaaScore,
+    SetLogFilters,
+    GetOutpointSpendStatus,
+    EstimateTransactionConfirmationTime,
+    GetAcceptanceProof,
+    CreateBackup,
+    GetVirtualParentSelectionTrace,
+    GetSystemInfo,
+    RemovePeer,
+    GetVirtualInfo,
+    SetProcessingParallelism,
+    GetAddressActivity,
+    GetMissedNotifications,
+    GetUtxoReturnAddress,
+    ValidateBlockTemplate,
 
     // Subscription commands for starting/stopping notifications
     NotifyBlockAdded,
@@ -91,6 +106,7 @@ pub enum KaspadPayloadOps {
     NotifyPruningPointUtxoSetOverride,
     NotifyVirtualDaaScoreChanged,
     NotifyVirtualChainChanged,
+    NotifyTransactionConflict,
 
     // Legacy stop subscription commands
     StopNotifyingUtxosChanged,
@@ -21,7 +21,8 @@ use kaspa_notify::{
     listener::{ListenerId, ListenerLifespan},
     notifier::Notifier,
 };
-use kaspa_rpc_core::Notification;
+use kaspa_rpc_core::{Notification, SubmitTransactionRequest};
+use kaspa_utils::rate_limit::SlidingWindowCounter;
 use parking_lot::Mutex;
 use std::{
     collections::{hash_map::Entry, HashMap},
@@ -84,6 +85,9 @@ struct Inner {
 
     /// When true, stops sending messages to the outgoing route
     is_closed: AtomicBool,
+
+    /// Bounds the rate of the verbose transaction submissions, tracing the acceptance of rejected transactions
+    rejection_trace_limiter: SlidingWindowCounter,
 }
 
 impl Drop for Inner {
@@ -232,6 +236,10 @@ impl Connection {
                 server_context,
                 mutable_state: Mutex::new(InnerMutableState::new(Some(shutdown_sender))),
                 is_closed: AtomicBool::new(false),
+                rejection_trace_limiter: SlidingWindowCounter::new(
+                    SubmitTransactionRequest::MAX_REJECTION_TRACES_PER_WINDOW,
+                    SubmitTransactionRequest::REJECTION_TRACE_WINDOW,
+                ),
             }),
         };
         let connection_clone = connection.clone();
@@ -314,6 +322,11 @@ impl Connection {
         self.inner.connection_id
     }
 
+    /// Returns `true` if a verbose transaction submission can be processed as such
+    pub fn try_acquire_rejection_trace(&self) -> bool {
+        self.inner.rejection_trace_limiter.try_acquire()
+    }
+
     pub fn notifier(&self) -> Arc<GrpcNotifier> {
         self.inner.server_context.notifier.clone()
     }
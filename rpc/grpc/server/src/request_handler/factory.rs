@@ -11,11 +11,20 @@ use crate::{
     connection_handler::ServerContext,
     error::GrpcServerError,
 };
+use kaspa_core::debug;
 use kaspa_grpc_core::protowire::{kaspad_request::Payload, *};
-use kaspa_grpc_core::{ops::KaspadPayloadOps, protowire::NotifyFinalityConflictResponseMessage};
-use kaspa_notify::{scope::FinalityConflictResolvedScope, subscriber::SubscriptionManager};
-use kaspa_rpc_core::{SubmitBlockRejectReason, SubmitBlockReport, SubmitBlockResponse};
+use kaspa_grpc_core::{
+    ops::KaspadPayloadOps,
+    protowire::{NotifyFinalityConflictResponseMessage, NotifyUtxosChangedResponseMessage, SubmitTransactionResponseMessage},
+};
+use kaspa_notify::{
+    scope::{FinalityConflictResolvedScope, Scope},
+    subscriber::SubscriptionManager,
+    subscription::Command,
+};
+use kaspa_rpc_core::{RpcError, SubmitBlockRejectReason, SubmitBlockReport, SubmitBlockResponse};
 use kaspa_rpc_macros::build_grpc_server_interface;
+use kaspa_rpc_service::service::check_start_notify_permission;
 
 pub struct Factory {}
 
@@ -75,6 +84,21 @@ impl Factory {
                 GetServerInfo,
                 GetSyncStatus,
                 GetDaaScoreTimestampEstimate,
+                GetChainBlockByDaaScore,
+                SetLogFilters,
+                GetOutpointSpendStatus,
+                EstimateTransactionConfirmationTime,
+                GetAcceptanceProof,
+                CreateBackup,
+                GetVirtualParentSelectionTrace,
+                GetSystemInfo,
+                RemovePeer,
+                GetVirtualInfo,
+                SetProcessingParallelism,
+                GetAddressActivity,
+                GetMissedNotifications,
+                GetUtxoReturnAddress,
+                ValidateBlockTemplate,
                 NotifyBlockAdded,
                 NotifyNewBlockTemplate,
                 NotifyFinalityConflict,
@@ -83,6 +107,7 @@ impl Factory {
                 NotifyPruningPointUtxoSetOverride,
                 NotifyVirtualDaaScoreChanged,
                 NotifyVirtualChainChanged,
+                NotifyTransactionConflict,
                 StopNotifyingUtxosChanged,
                 StopNotifyingPruningPointUtxoSetOverride,
             ]
@@ -129,6 +154,72 @@ impl Factory {
         });
         interface.replace_method(KaspadPayloadOps::NotifyFinalityConflict, method);
 
+        // Manually reimplementing the NotifyUtxosChangedRequest method so wildcard subscriptions get
+        // restricted to unsafe RPC mode, since the connections subscribe to the core notifier directly.
+        let method: KaspadMethod = Method::new(|server_ctx: ServerContext, connection: Connection, request: KaspadRequest| {
+            Box::pin(async move {
+                let mut response: KaspadResponse = match request.payload {
+                    Some(Payload::NotifyUtxosChangedRequest(ref request)) => {
+                        match kaspa_rpc_core::NotifyUtxosChangedRequest::try_from(request) {
+                            Ok(request) => {
+                                let listener_id = connection.get_or_register_listener_id()?;
+                                let command = request.command;
+                                let scope: Scope = request.into();
+                                let result = match command {
+                                    Command::Start => check_start_notify_permission(server_ctx.unsafe_rpc, &scope),
+                                    Command::Stop => Ok(()),
+                                };
+                                let result = match result {
+                                    Ok(()) => server_ctx
+                                        .notifier
+                                        .clone()
+                                        .execute_subscribe_command(listener_id, scope, command)
+                                        .await
+                                        .map_err(RpcError::from),
+                                    Err(err) => Err(err),
+                                };
+                                NotifyUtxosChangedResponseMessage::from(result).into()
+                            }
+                            Err(err) => NotifyUtxosChangedResponseMessage::from(err).into(),
+                        }
+                    }
+                    _ => {
+                        return Err(GrpcServerError::InvalidRequestPayload);
+                    }
+                };
+                response.id = request.id;
+                Ok(response)
+            })
+        });
+        interface.replace_method(KaspadPayloadOps::NotifyUtxosChanged, method);
+
+        // Manually reimplementing the SubmitTransactionRequest method so verbose submissions
+        // get rate limited per connection.
+        let method: KaspadMethod = Method::new(|server_ctx: ServerContext, connection: Connection, request: KaspadRequest| {
+            Box::pin(async move {
+                let mut response: KaspadResponse = match request.payload {
+                    Some(Payload::SubmitTransactionRequest(ref request)) => {
+                        match kaspa_rpc_core::SubmitTransactionRequest::try_from(request) {
+                            Ok(mut request) => {
+                                if request.verbose && !connection.try_acquire_rejection_trace() {
+                                    debug!("GRPC, verbose SubmitTransaction rate limit reached for client {}", connection);
+                                    request.verbose = false;
+                                }
+                                server_ctx.core_service.submit_transaction_call(request).await.into()
+                            }
+                            Err(err) => SubmitTransactionResponseMessage::from(err).into(),
+                        }
+                    }
+                    _ => {
+                        return Err(GrpcServerError::InvalidRequestPayload);
+                    }
+                };
+                response.id = request.id;
+                Ok(response)
+            })
+        });
+        interface.replace_method(KaspadPayloadOps::SubmitTransaction, method);
+
         // Methods with special properties
         let network_bps = network_bps as usize;
         interface.set_method_properties(
@@ -136,7 +227,7 @@ impl Factory {
             network_bps,
             10.max(network_bps * 2),
             KaspadRoutingPolicy::DropIfFull(Arc::new(Box::new(|_: &KaspadRequest| {
-                Ok(Ok(SubmitBlockResponse { report: SubmitBlockReport::Reject(SubmitBlockRejectReason::RouteIsFull) }).into())
+                Ok(Ok(SubmitBlockResponse::new(SubmitBlockReport::Reject(SubmitBlockRejectReason::RouteIsFull), None)).into())
             }))),
         );
 
@@ -7,7 +7,10 @@ use crate::{
     connection_handler::ServerContext,
     error::GrpcServerResult,
 };
-use kaspa_core::debug;
+use kaspa_core::{
+    debug,
+    log::trace_id::{self, TraceId},
+};
 use kaspa_grpc_core::{
     ops::KaspadPayloadOps,
     protowire::{KaspadRequest, KaspadResponse},
@@ -33,9 +36,12 @@ impl RequestHandler {
         Self { rpc_op, incoming_route, server_ctx: server_context, method, connection }
     }
 
+    /// Handles the request within the scope of its trace id, either provided by the client or generated
     pub async fn handle_request(&self, request: KaspadRequest) -> GrpcServerResult<KaspadResponse> {
         let id = request.id;
-        let mut response = self.method.call(self.server_ctx.clone(), self.connection.clone(), request).await?;
+        let trace_id = TraceId::from_client_or_generate(&request.trace_id);
+        let call = self.method.call(self.server_ctx.clone(), self.connection.clone(), request);
+        let mut response = trace_id::timed_scope(format!("{:?}", self.rpc_op), trace_id, call).await?;
         response.id = id;
         Ok(response)
     }
@@ -1,9 +1,12 @@
 use super::rpc_core_mock::RpcCoreMock;
 use crate::{adaptor::Adaptor, manager::Manager};
-use kaspa_core::info;
+use kaspa_core::{
+    info,
+    log::trace_id::{self, TraceId},
+};
 use kaspa_grpc_client::GrpcClient;
 use kaspa_notify::scope::{NewBlockTemplateScope, Scope};
-use kaspa_rpc_core::api::rpc::RpcApi;
+use kaspa_rpc_core::{api::rpc::RpcApi, RpcError};
 use kaspa_utils::networking::{ContextualNetAddress, NetAddress};
 use std::sync::Arc;
 
@@ -188,6 +191,41 @@ async fn test_client_server_notifications() {
     tokio::time::sleep(std::time::Duration::from_millis(50)).await;
 }
 
+#[tokio::test]
+async fn test_client_server_trace_ids() {
+    kaspa_core::log::try_init_logger("info, kaspa_grpc_core=trace, kaspa_grpc_server=trace, kaspa_grpc_client=trace");
+
+    // Create and start a fake core service, failing the ping calls
+    let rpc_core_service = Arc::new(RpcCoreMock::new());
+    rpc_core_service.start();
+
+    // Create and start the server
+    let server = create_server(rpc_core_service.clone());
+    let client = create_client(server.serve_address()).await;
+
+    // A call made within a trace id scope reports the error under that trace id
+    let err = trace_id::scope(TraceId::from_client("wallet-1:ping").unwrap(), client.ping()).await.unwrap_err();
+    assert_eq!(err.trace_id(), Some("wallet-1:ping"));
+    assert!(matches!(&err, RpcError::Traced(message, _) if message == &RpcError::NotImplemented.to_string()), "{err}");
+    assert!(err.to_string().contains("wallet-1:ping"), "{err}");
+
+    // Otherwise the server generates a trace id
+    let err = client.ping().await.unwrap_err();
+    assert_eq!(err.trace_id().map(str::len), Some(16), "{err}");
+
+    // Stop the fake service
+    rpc_core_service.join().await;
+
+    // Stop the server
+    assert!(server.stop().await.is_ok(), "error stopping the server");
+
+    assert!(client.disconnect().await.is_ok(), "client failed to disconnect");
+    drop(client);
+
+    drop(server);
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+}
+
 fn create_server(core_service: Arc<RpcCoreMock>) -> Arc<Adaptor> {
     let manager = Manager::new(128);
     Adaptor::server(
@@ -199,6 +237,7 @@ fn create_server(core_service: Arc<RpcCoreMock>) -> Arc<Adaptor> {
         core_service.subscription_context(),
         3,
         Default::default(),
+        false,
     )
 }
 
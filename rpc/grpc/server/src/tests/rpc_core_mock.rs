@@ -75,6 +75,9 @@ impl RpcApi for RpcCoreMock {
             is_synced: false,
             has_notify_command: true,
             has_message_id: true,
+            params_digest: None,
+            dust_thresholds: vec![],
+            consensus_status: None,
         })
     }
 
@@ -195,6 +198,10 @@ impl RpcApi for RpcCoreMock {
         Err(RpcError::NotImplemented)
     }
 
+    async fn get_virtual_info_call(&self, _request: GetVirtualInfoRequest) -> RpcResult<GetVirtualInfoResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
     async fn ban_call(&self, _request: BanRequest) -> RpcResult<BanResponse> {
         Err(RpcError::NotImplemented)
     }
@@ -228,6 +235,80 @@ impl RpcApi for RpcCoreMock {
         Err(RpcError::NotImplemented)
     }
 
+    async fn get_chain_block_by_daa_score_call(
+        &self,
+        _request: GetChainBlockByDaaScoreRequest,
+    ) -> RpcResult<GetChainBlockByDaaScoreResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn set_log_filters_call(&self, _request: SetLogFiltersRequest) -> RpcResult<SetLogFiltersResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_outpoint_spend_status_call(
+        &self,
+        _request: GetOutpointSpendStatusRequest,
+    ) -> RpcResult<GetOutpointSpendStatusResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn estimate_transaction_confirmation_time_call(
+        &self,
+        _request: EstimateTransactionConfirmationTimeRequest,
+    ) -> RpcResult<EstimateTransactionConfirmationTimeResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_acceptance_proof_call(&self, _request: GetAcceptanceProofRequest) -> RpcResult<GetAcceptanceProofResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn create_backup_call(&self, _request: CreateBackupRequest) -> RpcResult<CreateBackupResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_virtual_parent_selection_trace_call(
+        &self,
+        _request: GetVirtualParentSelectionTraceRequest,
+    ) -> RpcResult<GetVirtualParentSelectionTraceResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_system_info_call(&self, _request: GetSystemInfoRequest) -> RpcResult<GetSystemInfoResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn remove_peer_call(&self, _request: RemovePeerRequest) -> RpcResult<RemovePeerResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn set_processing_parallelism_call(
+        &self,
+        _request: SetProcessingParallelismRequest,
+    ) -> RpcResult<SetProcessingParallelismResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_address_activity_call(&self, _request: GetAddressActivityRequest) -> RpcResult<GetAddressActivityResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_missed_notifications_call(
+        &self,
+        _request: GetMissedNotificationsRequest,
+    ) -> RpcResult<GetMissedNotificationsResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_utxo_return_address_call(&self, _request: GetUtxoReturnAddressRequest) -> RpcResult<GetUtxoReturnAddressResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn validate_block_template_call(&self, _request: ValidateBlockTemplateRequest) -> RpcResult<ValidateBlockTemplateResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Notification API
 
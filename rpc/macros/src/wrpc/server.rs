@@ -53,8 +53,12 @@ impl ToTokens for RpcTable {
                     interface.method(#rpc_api_ops::#handler, method!(|server_ctx: #server_ctx_type, connection_ctx: #connection_ctx_type, request: #request_type| async move {
                         let verbose = server_ctx.verbose();
                         if verbose { workflow_log::log_info!("request: {:?}",request); }
-                        let response: #response_type = server_ctx.rpc_service(&connection_ctx).#fn_call(request).await
-                            .map_err(|e|ServerError::Text(e.to_string()))?;
+                        let trace_id = kaspa_core::log::trace_id::TraceId::generate();
+                        let rpc_service = server_ctx.rpc_service(&connection_ctx);
+                        let call = rpc_service.#fn_call(request);
+                        let response: #response_type = kaspa_core::log::trace_id::timed_scope(stringify!(#handler), trace_id.clone(), call).await
+                            .map_err(|e|ServerError::Text(e.with_trace_id(trace_id.as_str()).to_string()))?;
+                        server_ctx.check_response_size(&response).map_err(|e|ServerError::Text(e.to_string()))?;
                         if verbose { workflow_log::log_info!("response: {:?}",response); }
                         Ok(response)
                     }));
@@ -1,3 +1,4 @@
+use crate::journal::NotificationJournal;
 use async_trait::async_trait;
 use kaspa_addresses::Address;
 use kaspa_consensus_core::{
@@ -11,25 +12,42 @@ use kaspa_consensus_core::{
 use kaspa_consensus_notify::notification::{self as consensus_notify, Notification as ConsensusNotification};
 use kaspa_consensusmanager::{ConsensusManager, ConsensusProxy};
 use kaspa_math::Uint256;
-use kaspa_mining::model::{owner_txs::OwnerTransactions, TransactionIdSet};
+use kaspa_mining::model::{
+    acceptance_trace::{AcceptanceDetail, AcceptanceOutcome, TransactionAcceptanceTrace},
+    mempool_entries::MempoolSummary,
+    owner_txs::OwnerTransactions,
+    TransactionIdSet,
+};
 use kaspa_notify::converter::Converter;
+use kaspa_p2p_flows::flowcontext::propagation::BlockPropagationTracker;
 use kaspa_rpc_core::{
-    BlockAddedNotification, Notification, RpcAcceptedTransactionIds, RpcBlock, RpcBlockVerboseData, RpcHash, RpcMempoolEntry,
-    RpcMempoolEntryByAddress, RpcResult, RpcTransaction, RpcTransactionInput, RpcTransactionOutput, RpcTransactionOutputVerboseData,
-    RpcTransactionVerboseData,
+    BlockAddedNotification, Notification, RpcAcceptanceCheck, RpcAcceptanceDetail, RpcAcceptanceOutcome, RpcAcceptedTransactionIds,
+    RpcBlock, RpcBlockPropagation, RpcBlockVerboseData, RpcHash, RpcMempoolEntry, RpcMempoolEntryByAddress, RpcMempoolSummary,
+    RpcResult, RpcTransaction, RpcTransactionAcceptanceTrace, RpcTransactionFieldMask, RpcTransactionInput, RpcTransactionOutput,
+    RpcTransactionOutputVerboseData, RpcTransactionVerboseData,
 };
 use kaspa_txscript::{extract_script_pub_key_address, script_class::ScriptClass};
 use std::{collections::HashMap, fmt::Debug, sync::Arc};
 
+/// Number of chain blocks whose acceptance data is read from consensus at once
+pub const ACCEPTANCE_DATA_BATCH_SIZE: usize = 1024;
+
 /// Conversion of consensus_core to rpc_core structures
 pub struct ConsensusConverter {
     consensus_manager: Arc<ConsensusManager>,
     config: Arc<Config>,
+    block_propagation: Arc<BlockPropagationTracker>,
+    journal: Option<Arc<NotificationJournal>>,
 }
 
 impl ConsensusConverter {
-    pub fn new(consensus_manager: Arc<ConsensusManager>, config: Arc<Config>) -> Self {
-        Self { consensus_manager, config }
+    pub fn new(
+        consensus_manager: Arc<ConsensusManager>,
+        config: Arc<Config>,
+        block_propagation: Arc<BlockPropagationTracker>,
+        journal: Option<Arc<NotificationJournal>>,
+    ) -> Self {
+        Self { consensus_manager, config, block_propagation, journal }
     }
 
     /// Returns the proof-of-work difficulty as a multiple of the minimum difficulty using
@@ -44,6 +62,7 @@ impl ConsensusConverter {
     }
 
     /// Converts a consensus [`Block`] into an [`RpcBlock`], optionally including transaction verbose data.
+    /// The fields covered by `transaction_field_mask` are omitted from the included transactions.
     ///
     /// _GO-KASPAD: PopulateBlockWithVerboseData_
     pub async fn get_block(
@@ -52,12 +71,14 @@ impl ConsensusConverter {
         block: &Block,
         include_transactions: bool,
         include_transaction_verbose_data: bool,
+        transaction_field_mask: RpcTransactionFieldMask,
     ) -> RpcResult<RpcBlock> {
         let hash = block.hash();
         let ghostdag_data = consensus.async_get_ghostdag_data(hash).await?;
         let block_status = consensus.async_get_block_status(hash).await.unwrap();
         let children = consensus.async_get_block_children(hash).await.unwrap_or_default();
         let is_chain_block = consensus.async_is_chain_block(hash).await?;
+        let difficulty_context = consensus.async_get_block_difficulty_context(hash).await?;
         let verbose_data = Some(RpcBlockVerboseData {
             hash,
             difficulty: self.get_difficulty_ratio(block.header.bits),
@@ -69,13 +90,27 @@ impl ConsensusConverter {
             merge_set_blues_hashes: ghostdag_data.mergeset_blues,
             merge_set_reds_hashes: ghostdag_data.mergeset_reds,
             is_chain_block,
+            target: difficulty_context.target,
+            work: difficulty_context.work,
+            network_hashes_per_second: difficulty_context.network_hashes_per_second,
+            propagation: self.block_propagation.get(hash).map(|times| RpcBlockPropagation {
+                first_inv_time: times.inv,
+                header_time: times.header,
+                validated_time: times.validated,
+            }),
         });
 
         let transactions = if include_transactions {
+            let include_transaction_verbose_data =
+                include_transaction_verbose_data && !transaction_field_mask.contains(RpcTransactionFieldMask::OMIT_VERBOSE_DATA);
             block
                 .transactions
                 .iter()
-                .map(|x| self.get_transaction(consensus, x, Some(&block.header), include_transaction_verbose_data))
+                .map(|x| {
+                    let mut transaction = self.get_transaction(consensus, x, Some(&block.header), include_transaction_verbose_data);
+                    transaction_field_mask.apply(&mut transaction);
+                    transaction
+                })
                 .collect::<Vec<_>>()
         } else {
             vec![]
@@ -87,7 +122,22 @@ impl ConsensusConverter {
     pub fn get_mempool_entry(&self, consensus: &ConsensusProxy, transaction: &MutableTransaction) -> RpcMempoolEntry {
         let is_orphan = !transaction.is_fully_populated();
         let rpc_transaction = self.get_transaction(consensus, &transaction.tx, None, true);
-        RpcMempoolEntry::new(transaction.calculated_fee.unwrap_or_default(), rpc_transaction, is_orphan)
+        RpcMempoolEntry::new(transaction.calculated_fee.unwrap_or_default(), rpc_transaction, is_orphan, false)
+    }
+
+    /// Converts a transaction held by the mempool until its lock time is reached into an [`RpcMempoolEntry`].
+    pub fn get_held_mempool_entry(&self, consensus: &ConsensusProxy, transaction: &MutableTransaction) -> RpcMempoolEntry {
+        let rpc_transaction = self.get_transaction(consensus, &transaction.tx, None, true);
+        RpcMempoolEntry::new(transaction.calculated_fee.unwrap_or_default(), rpc_transaction, false, true)
+    }
+
+    pub fn get_mempool_summary(&self, summary: &MempoolSummary) -> RpcMempoolSummary {
+        RpcMempoolSummary {
+            transaction_count: summary.transaction_count,
+            total_mass: summary.total_mass,
+            total_fees: summary.total_fees,
+            timestamp: summary.timestamp,
+        }
     }
 
     pub fn get_mempool_entries_by_address(
@@ -111,6 +161,40 @@ impl ConsensusConverter {
         transaction_ids.iter().map(|x| self.get_mempool_entry(consensus, transactions.get(x).expect("transaction exists"))).collect()
     }
 
+    /// Converts the mempool acceptance trace of a transaction rejected with `error` into an [`RpcTransactionAcceptanceTrace`].
+    pub fn get_transaction_acceptance_trace(
+        &self,
+        error: String,
+        trace: &TransactionAcceptanceTrace,
+    ) -> RpcTransactionAcceptanceTrace {
+        let checks = trace
+            .checks
+            .iter()
+            .map(|check| {
+                let (outcome, error) = match &check.outcome {
+                    AcceptanceOutcome::Passed => (RpcAcceptanceOutcome::Passed, None),
+                    AcceptanceOutcome::Failed(err) => (RpcAcceptanceOutcome::Failed, Some(err.to_string())),
+                    AcceptanceOutcome::Skipped => (RpcAcceptanceOutcome::Skipped, None),
+                };
+                let detail = check.detail.as_ref().map(|detail| match detail {
+                    AcceptanceDetail::Mass { mass, limit } => RpcAcceptanceDetail::Mass { mass: *mass, limit: *limit },
+                    AcceptanceDetail::Fee { required, provided } => {
+                        RpcAcceptanceDetail::Fee { required: *required, provided: *provided }
+                    }
+                    AcceptanceDetail::MissingOutpoints(outpoints) => RpcAcceptanceDetail::MissingOutpoints(outpoints.clone()),
+                    AcceptanceDetail::MaturityShortfall { input_index, shortfall } => {
+                        RpcAcceptanceDetail::MaturityShortfall { input_index: *input_index as u32, shortfall: *shortfall }
+                    }
+                    AcceptanceDetail::Dust { output_index, value, threshold } => {
+                        RpcAcceptanceDetail::Dust { output_index: *output_index as u32, value: *value, threshold: *threshold }
+                    }
+                });
+                RpcAcceptanceCheck { rule: check.rule.to_string(), contextual: check.rule.is_contextual(), outcome, error, detail }
+            })
+            .collect();
+        RpcTransactionAcceptanceTrace { error, checks }
+    }
+
     /// Converts a consensus [`Transaction`] into an [`RpcTransaction`], optionally including verbose data.
     ///
     /// _GO-KASPAD: PopulateTransactionWithVerboseData
@@ -158,24 +242,23 @@ impl ConsensusConverter {
         RpcTransactionOutput { value: output.value, script_public_key: output.script_public_key.clone(), verbose_data }
     }
 
+    /// Returns the ids of the transactions accepted by the chain blocks added by `chain_path`. The acceptance data
+    /// is streamed from consensus in batches of [`ACCEPTANCE_DATA_BATCH_SIZE`] blocks, so deep chain paths do not
+    /// require loading the acceptance data of all the chain blocks at once.
     pub async fn get_virtual_chain_accepted_transaction_ids(
         &self,
         consensus: &ConsensusProxy,
         chain_path: &ChainPath,
     ) -> RpcResult<Vec<RpcAcceptedTransactionIds>> {
-        let acceptance_data = consensus.async_get_blocks_acceptance_data(chain_path.added.clone()).await.unwrap();
-        Ok(chain_path
-            .added
-            .iter()
-            .zip(acceptance_data.iter())
-            .map(|(hash, block_data)| RpcAcceptedTransactionIds {
+        let mut accepted_transaction_ids = Vec::with_capacity(chain_path.added.len());
+        for hashes in chain_path.added.chunks(ACCEPTANCE_DATA_BATCH_SIZE) {
+            let batch = consensus.async_get_blocks_accepted_transaction_ids(hashes.to_vec()).await?;
+            accepted_transaction_ids.extend(hashes.iter().zip(batch).map(|(hash, ids)| RpcAcceptedTransactionIds {
                 accepting_block_hash: hash.to_owned(),
-                accepted_transaction_ids: block_data
-                    .iter()
-                    .flat_map(|x| x.accepted_transactions.iter().map(|tx| tx.transaction_id))
-                    .collect(),
-            })
-            .collect())
+                accepted_transaction_ids: ids,
+            }));
+        }
+        Ok(accepted_transaction_ids)
     }
 }
 
@@ -185,14 +268,22 @@ impl Converter for ConsensusConverter {
     type Outgoing = Notification;
 
     async fn convert(&self, incoming: ConsensusNotification) -> Notification {
-        match incoming {
+        let notification = match incoming {
             consensus_notify::Notification::BlockAdded(msg) => {
                 let session = self.consensus_manager.consensus().unguarded_session();
                 // If get_block fails, rely on the infallible From implementation which will lack verbose data
-                let block = Arc::new(self.get_block(&session, &msg.block, true, true).await.unwrap_or_else(|_| (&msg.block).into()));
+                let block = Arc::new(
+                    self.get_block(&session, &msg.block, true, true, RpcTransactionFieldMask::NONE)
+                        .await
+                        .unwrap_or_else(|_| (&msg.block).into()),
+                );
                 Notification::BlockAdded(BlockAddedNotification { block })
             }
             _ => (&incoming).into(),
+        };
+        match self.journal {
+            Some(ref journal) => journal.record(notification),
+            None => notification,
         }
     }
 }
@@ -1,3 +1,4 @@
+use crate::journal::NotificationJournal;
 use async_trait::async_trait;
 use kaspa_consensus_core::config::Config;
 use kaspa_index_core::indexed_utxos::UtxoSetByScriptPublicKey;
@@ -10,17 +11,19 @@ use std::sync::Arc;
 #[derive(Debug)]
 pub struct IndexConverter {
     config: Arc<Config>,
+    journal: Option<Arc<NotificationJournal>>,
 }
 
 impl IndexConverter {
-    pub fn new(config: Arc<Config>) -> Self {
-        Self { config }
+    pub fn new(config: Arc<Config>, journal: Option<Arc<NotificationJournal>>) -> Self {
+        Self { config, journal }
     }
 
     pub fn get_utxo_changed_notification(&self, utxo_changed: index_notify::UtxosChangedNotification) -> UtxosChangedNotification {
         UtxosChangedNotification {
             added: Arc::new(self.get_utxos_by_addresses_entries(&utxo_changed.added)),
             removed: Arc::new(self.get_utxos_by_addresses_entries(&utxo_changed.removed)),
+            sequence: None,
         }
     }
 
@@ -35,9 +38,13 @@ impl Converter for IndexConverter {
     type Outgoing = Notification;
 
     async fn convert(&self, incoming: IndexNotification) -> Notification {
-        match incoming {
+        let notification = match incoming {
             index_notify::Notification::UtxosChanged(msg) => Notification::UtxosChanged(self.get_utxo_changed_notification(msg)),
             _ => (&incoming).into(),
+        };
+        match self.journal {
+            Some(ref journal) => journal.record(notification),
+            None => notification,
         }
     }
 }
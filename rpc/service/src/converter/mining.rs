@@ -0,0 +1,46 @@
+use async_trait::async_trait;
+use kaspa_consensus_core::config::Config;
+use kaspa_mining::model::tx_conflict::{TransactionConflict, TransactionConflictSource};
+use kaspa_notify::converter::Converter;
+use kaspa_rpc_core::{Notification, TransactionConflictNotification};
+use kaspa_txscript::extract_script_pub_key_address;
+use std::sync::Arc;
+
+/// Conversion of mining structures to rpc_core structures
+#[derive(Debug)]
+pub struct MiningConverter {
+    config: Arc<Config>,
+}
+
+impl MiningConverter {
+    pub fn new(config: Arc<Config>) -> Self {
+        Self { config }
+    }
+
+    pub fn get_transaction_conflict_notification(&self, conflict: TransactionConflict) -> TransactionConflictNotification {
+        // Script public keys of a non-standard form have no address and cannot be subscribed to
+        let mut addresses = conflict
+            .script_public_keys
+            .iter()
+            .filter_map(|spk| extract_script_pub_key_address(spk, self.config.prefix()).ok())
+            .collect::<Vec<_>>();
+        addresses.sort_unstable();
+        TransactionConflictNotification {
+            transaction_id: conflict.transaction_id,
+            conflicting_transaction_id: conflict.conflicting_transaction_id,
+            outpoints: conflict.outpoints,
+            is_conflicting_in_block: conflict.source == TransactionConflictSource::Block,
+            addresses,
+        }
+    }
+}
+
+#[async_trait]
+impl Converter for MiningConverter {
+    type Incoming = TransactionConflict;
+    type Outgoing = Notification;
+
+    async fn convert(&self, incoming: TransactionConflict) -> Notification {
+        Notification::TransactionConflict(self.get_transaction_conflict_notification(incoming))
+    }
+}
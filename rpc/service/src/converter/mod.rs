@@ -1,3 +1,4 @@
 pub mod consensus;
 pub mod index;
+pub mod mining;
 pub mod protocol;
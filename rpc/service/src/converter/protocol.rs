@@ -1,8 +1,9 @@
 use std::sync::Arc;
 
+use kaspa_addressmanager::{AddressFilter, AddressInfo, AddressManager, AddressSource, ConnectionOutcome};
 use kaspa_p2p_flows::flow_context::FlowContext;
 use kaspa_p2p_lib::{Peer, PeerKey};
-use kaspa_rpc_core::RpcPeerInfo;
+use kaspa_rpc_core::{GetPeerAddressesRequest, RpcConnectionOutcome, RpcKnownPeerAddress, RpcPeerAddressSource, RpcPeerInfo};
 
 pub struct ProtocolConverter {
     flow_context: Arc<FlowContext>,
@@ -13,8 +14,9 @@ impl ProtocolConverter {
         Self { flow_context }
     }
 
-    fn get_peer_info(&self, peer: &Peer, ibd_peer_key: &Option<PeerKey>) -> RpcPeerInfo {
+    fn get_peer_info(&self, peer: &Peer, ibd_peer_key: &Option<PeerKey>, address_manager: &AddressManager) -> RpcPeerInfo {
         let properties = peer.properties();
+        let served_counters = peer.served_counters();
         RpcPeerInfo {
             id: peer.identity(),
             address: peer.net_address().into(),
@@ -25,11 +27,46 @@ impl ProtocolConverter {
             user_agent: properties.user_agent.clone(),
             advertised_protocol_version: properties.advertised_protocol_version,
             time_connected: peer.time_connected(),
+            served_bytes: served_counters.window_bytes,
+            served_cost: served_counters.window_cost,
+            is_manual: address_manager.is_manual_peer(peer.net_address().into()),
         }
     }
 
     pub fn get_peers_info(&self, peers: &[Peer]) -> Vec<RpcPeerInfo> {
         let ibd_peer_key = self.flow_context.ibd_peer_key();
-        peers.iter().map(|x| self.get_peer_info(x, &ibd_peer_key)).collect()
+        let address_manager = self.flow_context.address_manager.lock();
+        peers.iter().map(|x| self.get_peer_info(x, &ibd_peer_key, &address_manager)).collect()
+    }
+
+    fn get_known_address(&self, info: &AddressInfo) -> RpcKnownPeerAddress {
+        RpcKnownPeerAddress {
+            address: info.address,
+            source: match info.source {
+                AddressSource::Unknown => RpcPeerAddressSource::Unknown,
+                AddressSource::DnsSeed => RpcPeerAddressSource::DnsSeed,
+                AddressSource::PeerGossip => RpcPeerAddressSource::PeerGossip,
+                AddressSource::Manual => RpcPeerAddressSource::Manual,
+            },
+            network_group: info.network_group(),
+            connection_failed_count: info.connection_failed_count,
+            is_tried: info.is_tried,
+            last_seen: info.last_seen,
+            last_attempt_time: info.last_attempt.map(|(time, _)| time),
+            last_attempt_outcome: info.last_attempt.map(|(_, outcome)| match outcome {
+                ConnectionOutcome::Success => RpcConnectionOutcome::Success,
+                ConnectionOutcome::Failure => RpcConnectionOutcome::Failure,
+            }),
+        }
+    }
+
+    pub fn get_known_addresses(&self, request: &GetPeerAddressesRequest) -> Vec<RpcKnownPeerAddress> {
+        let filter = AddressFilter {
+            only_tried: request.only_tried,
+            only_new: request.only_new,
+            group_prefix: request.group_prefix.clone(),
+            limit: request.limit.map(|limit| limit as usize),
+        };
+        self.flow_context.address_manager.lock().get_address_infos(&filter).iter().map(|x| self.get_known_address(x)).collect()
     }
 }
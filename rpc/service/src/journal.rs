@@ -0,0 +1,127 @@
+//! Journal of the notifications of the critical scopes.
+//!
+//! The journal stamps the VirtualChainChanged and UtxosChanged notifications with per-scope monotonically
+//! increasing sequence numbers before they get broadcast to the listeners, and retains the most recent ones
+//! in a bounded ring buffer per scope. A client detecting a jump in the sequence of its notifications, for
+//! instance after a reconnection, can then backfill them deterministically with GetMissedNotifications.
+
+use kaspa_addresses::Address;
+use kaspa_notify::{events::EventType, notification::Notification as NotificationTrait, scope::Scope};
+use kaspa_rpc_core::{
+    GetMissedNotificationsResponse, Notification, RpcError, RpcResult, UtxosChangedNotification, VirtualChainChangedNotification,
+};
+use parking_lot::Mutex;
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::Arc,
+};
+
+/// The retained notifications of a scope
+#[derive(Debug, Default)]
+struct ScopeJournal {
+    /// Sequence of the last notification of the scope, 0 if none was ever emitted
+    last_sequence: u64,
+    entries: VecDeque<Notification>,
+}
+
+impl ScopeJournal {
+    /// The sequence of the oldest retained notification
+    fn first_sequence(&self) -> u64 {
+        self.last_sequence + 1 - self.entries.len() as u64
+    }
+}
+
+#[derive(Debug)]
+pub struct NotificationJournal {
+    /// Max number of notifications retained per scope
+    size: usize,
+    virtual_chain_changed: Mutex<ScopeJournal>,
+    utxos_changed: Mutex<ScopeJournal>,
+}
+
+impl NotificationJournal {
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0, "the journal must retain at least one notification per scope");
+        Self { size, virtual_chain_changed: Default::default(), utxos_changed: Default::default() }
+    }
+
+    fn scope_journal(&self, event: EventType) -> Option<&Mutex<ScopeJournal>> {
+        match event {
+            EventType::VirtualChainChanged => Some(&self.virtual_chain_changed),
+            EventType::UtxosChanged => Some(&self.utxos_changed),
+            _ => None,
+        }
+    }
+
+    /// Stamps a notification of a journaled scope with the next sequence of the scope and retains it.
+    /// Notifications of other scopes are returned unchanged.
+    ///
+    /// The caller must record the notifications of a scope in emission order.
+    pub fn record(&self, notification: Notification) -> Notification {
+        let Some(journal) = self.scope_journal(notification.event_type()) else { return notification };
+        let mut journal = journal.lock();
+        let sequence = Some(journal.last_sequence + 1);
+        let notification = match notification {
+            Notification::VirtualChainChanged(payload) => {
+                Notification::VirtualChainChanged(VirtualChainChangedNotification { sequence, ..payload })
+            }
+            Notification::UtxosChanged(payload) => Notification::UtxosChanged(UtxosChangedNotification { sequence, ..payload }),
+            _ => unreachable!(),
+        };
+        journal.last_sequence += 1;
+        if journal.entries.len() == self.size {
+            journal.entries.pop_front();
+        }
+        journal.entries.push_back(notification.clone());
+        notification
+    }
+
+    /// Returns the retained notifications of `scope` starting at `from_sequence`, filtered as they would be for
+    /// a listener subscribed to `scope`.
+    ///
+    /// Fails with [`RpcError::NotificationsTooFarBehind`] if some notifications since `from_sequence` are no
+    /// longer retained or if `from_sequence` was never emitted, e.g. because the node restarted in between.
+    pub fn get_missed_notifications(&self, scope: &Scope, from_sequence: u64) -> RpcResult<GetMissedNotificationsResponse> {
+        let event = scope.event_type();
+        let journal = self.scope_journal(event).ok_or(RpcError::NotificationScopeNotJournaled(event))?.lock();
+        if from_sequence < journal.first_sequence() || from_sequence > journal.last_sequence + 1 {
+            return Err(RpcError::NotificationsTooFarBehind(event, from_sequence));
+        }
+        let entries = journal.entries.iter().skip((from_sequence - journal.first_sequence()) as usize);
+        let mut response = GetMissedNotificationsResponse::new(vec![], vec![], journal.last_sequence);
+        match scope {
+            Scope::VirtualChainChanged(scope) => {
+                response.virtual_chain_changed = entries
+                    .map(|notification| {
+                        let Notification::VirtualChainChanged(payload) = notification else { unreachable!() };
+                        match scope.include_accepted_transaction_ids {
+                            true => payload.clone(),
+                            false => VirtualChainChangedNotification { accepted_transaction_ids: Arc::new(vec![]), ..payload.clone() },
+                        }
+                    })
+                    .collect();
+            }
+            Scope::UtxosChanged(scope) => {
+                let addresses = scope.addresses.iter().collect::<HashSet<_>>();
+                let is_selected = |address: &Option<Address>| address.as_ref().is_some_and(|address| addresses.contains(address));
+                response.utxos_changed = entries
+                    .filter_map(|notification| {
+                        let Notification::UtxosChanged(payload) = notification else { unreachable!() };
+                        if scope.is_wildcard() {
+                            return Some(payload.clone());
+                        }
+                        let added = payload.added.iter().filter(|x| is_selected(&x.address)).cloned().collect::<Vec<_>>();
+                        let removed = payload.removed.iter().filter(|x| is_selected(&x.address)).cloned().collect::<Vec<_>>();
+                        (!added.is_empty() || !removed.is_empty()).then(|| UtxosChangedNotification {
+                            added: Arc::new(added),
+                            removed: Arc::new(removed),
+                            sequence: payload.sequence,
+                        })
+                    })
+                    .collect();
+            }
+            _ => unreachable!(),
+        }
+        Ok(response)
+    }
+}
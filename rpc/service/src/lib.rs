@@ -1,3 +1,4 @@
 pub mod collector;
 pub mod converter;
+pub mod journal;
 pub mod service;
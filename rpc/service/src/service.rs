@@ -1,16 +1,23 @@
 //! Core server implementation for ClientAPI
 
-use super::collector::{CollectorFromConsensus, CollectorFromIndex};
-use crate::converter::{consensus::ConsensusConverter, index::IndexConverter, protocol::ProtocolConverter};
+use super::collector::{CollectorFromConsensus, CollectorFromIndex, CollectorFromMining};
+use crate::converter::{consensus::ConsensusConverter, index::IndexConverter, mining::MiningConverter, protocol::ProtocolConverter};
+use crate::journal::NotificationJournal;
 use crate::service::NetworkType::{Mainnet, Testnet};
 use async_trait::async_trait;
+use kaspa_addressmanager::MAX_MANUAL_PEERS;
+use kaspa_consensus_core::acceptance_data::OutpointSpender;
 use kaspa_consensus_core::api::counters::ProcessingCounters;
+use kaspa_consensus_core::api::parallelism::ProcessingParallelism;
 use kaspa_consensus_core::errors::block::RuleError;
+use kaspa_consensus_core::errors::consensus::ConsensusError;
 use kaspa_consensus_core::{
     block::Block,
+    blockstatus::BlockStatus,
     coinbase::MinerData,
     config::Config,
     constants::MAX_SOMPI,
+    emission,
     network::NetworkType,
     tx::{Transaction, COINBASE_TRANSACTION_INDEX},
 };
@@ -18,7 +25,7 @@ use kaspa_consensus_notify::{
     notifier::ConsensusNotifier,
     {connection::ConsensusChannelConnection, notification::Notification as ConsensusNotification},
 };
-use kaspa_consensusmanager::ConsensusManager;
+use kaspa_consensusmanager::{spawn_blocking, ConsensusManager};
 use kaspa_core::time::unix_now;
 use kaspa_core::{
     core::Core,
@@ -34,8 +41,19 @@ use kaspa_index_core::{
     connection::IndexChannelConnection, indexed_utxos::UtxoSetByScriptPublicKey, notification::Notification as IndexNotification,
     notifier::IndexNotifier,
 };
-use kaspa_mining::model::tx_query::TransactionQuery;
-use kaspa_mining::{manager::MiningManagerProxy, mempool::tx::Orphan};
+use kaspa_mining::model::{
+    block_submission::{BlockSubmissionClassification, SubmittedTransactionOrder},
+    tx_conflict::TransactionConflict,
+    tx_query::TransactionQuery,
+};
+use kaspa_mining::{
+    errors::MiningManagerError,
+    manager::MiningManagerProxy,
+    mempool::{
+        errors::RuleError as MempoolRuleError,
+        tx::{Hold, Orphan},
+    },
+};
 use kaspa_notify::listener::ListenerLifespan;
 use kaspa_notify::subscription::context::SubscriptionContext;
 use kaspa_notify::subscription::{MutationPolicies, UtxosChangedMutationPolicy};
@@ -45,7 +63,7 @@ use kaspa_notify::{
     events::{EventSwitches, EventType, EVENT_TYPE_ARRAY},
     listener::ListenerId,
     notifier::Notifier,
-    scope::Scope,
+    scope::{Scope, UtxosChangedScope, VirtualChainChangedScope},
     subscriber::{Subscriber, SubscriptionManager},
 };
 use kaspa_p2p_flows::flow_context::FlowContext;
@@ -54,19 +72,20 @@ use kaspa_perf_monitor::{counters::CountersSnapshot, Monitor as PerfMonitor};
 use kaspa_rpc_core::{
     api::{
         ops::RPC_API_VERSION,
-        rpc::{RpcApi, MAX_SAFE_WINDOW_SIZE},
+        rpc::{RpcApi, DEFAULT_OUTPOINT_SPEND_SEARCH_DEPTH, MAX_SAFE_OUTPOINT_SPEND_SEARCH_DEPTH, MAX_SAFE_WINDOW_SIZE},
     },
     model::*,
     notify::connection::ChannelConnection,
     Notification, RpcError, RpcResult,
 };
-use kaspa_txscript::{extract_script_pub_key_address, pay_to_address_script};
-use kaspa_utils::{channel::Channel, triggers::SingleTrigger};
+use kaspa_txscript::{extract_script_pub_key_address, metrics::ScriptMetrics as ScriptVerificationMetrics, pay_to_address_script};
+use kaspa_utils::{channel::Channel, fd_budget, triggers::SingleTrigger};
 use kaspa_utils_tower::counters::TowerConnectionCounters;
 use kaspa_utxoindex::api::UtxoIndexProxy;
 use std::{
     collections::HashMap,
     iter::once,
+    path::PathBuf,
     sync::{atomic::Ordering, Arc},
     vec,
 };
@@ -100,8 +119,12 @@ pub struct RpcCoreService {
     consensus_converter: Arc<ConsensusConverter>,
     index_converter: Arc<IndexConverter>,
     protocol_converter: Arc<ProtocolConverter>,
+    notification_journal: Option<Arc<NotificationJournal>>,
+    transaction_conflict_channel: Channel<TransactionConflict>,
     core: Arc<Core>,
     processing_counters: Arc<ProcessingCounters>,
+    processing_parallelism: Arc<ProcessingParallelism>,
+    script_metrics: Arc<ScriptVerificationMetrics>,
     wrpc_borsh_counters: Arc<WrpcServerCounters>,
     wrpc_json_counters: Arc<WrpcServerCounters>,
     shutdown: SingleTrigger,
@@ -109,9 +132,15 @@ pub struct RpcCoreService {
     perf_monitor: Arc<PerfMonitor<Arc<TickService>>>,
     p2p_tower_counters: Arc<TowerConnectionCounters>,
     grpc_tower_counters: Arc<TowerConnectionCounters>,
+    preflight_report: RpcPreflightReport,
 }
 
 const RPC_CORE: &str = "rpc-core";
+const NOTIFICATION_JOURNAL: &str = "rpc-core notification journal";
+
+/// Capacity of the channel relaying the transaction conflicts detected by the mempool, the conflicts
+/// exceeding it being dropped
+const TRANSACTION_CONFLICT_CHANNEL_CAPACITY: usize = 1_000;
 
 impl RpcCoreService {
     pub const IDENT: &'static str = "rpc-core-service";
@@ -128,11 +157,14 @@ impl RpcCoreService {
         config: Arc<Config>,
         core: Arc<Core>,
         processing_counters: Arc<ProcessingCounters>,
+        processing_parallelism: Arc<ProcessingParallelism>,
+        script_metrics: Arc<ScriptVerificationMetrics>,
         wrpc_borsh_counters: Arc<WrpcServerCounters>,
         wrpc_json_counters: Arc<WrpcServerCounters>,
         perf_monitor: Arc<PerfMonitor<Arc<TickService>>>,
         p2p_tower_counters: Arc<TowerConnectionCounters>,
         grpc_tower_counters: Arc<TowerConnectionCounters>,
+        preflight_report: RpcPreflightReport,
     ) -> Self {
         // This notifier UTXOs subscription granularity to index-processor or consensus notifier
         let policies = match index_notifier {
@@ -151,7 +183,15 @@ impl RpcCoreService {
         let mut consensus_events: EventSwitches = EVENT_TYPE_ARRAY[..].into();
         consensus_events[EventType::UtxosChanged] = false;
         consensus_events[EventType::PruningPointUtxoSetOverride] = index_notifier.is_none();
-        let consensus_converter = Arc::new(ConsensusConverter::new(consensus_manager.clone(), config.clone()));
+        consensus_events[EventType::TransactionConflict] = false;
+        let notification_journal =
+            (config.notification_journal_size > 0).then(|| Arc::new(NotificationJournal::new(config.notification_journal_size)));
+        let consensus_converter = Arc::new(ConsensusConverter::new(
+            consensus_manager.clone(),
+            config.clone(),
+            flow_context.block_propagation().clone(),
+            notification_journal.clone(),
+        ));
         let consensus_collector = Arc::new(CollectorFromConsensus::new(
             "rpc-core <= consensus",
             consensus_notify_channel.receiver(),
@@ -164,7 +204,7 @@ impl RpcCoreService {
         let mut subscribers = vec![consensus_subscriber];
 
         // Prepare index-processor objects if an IndexService is provided
-        let index_converter = Arc::new(IndexConverter::new(config.clone()));
+        let index_converter = Arc::new(IndexConverter::new(config.clone(), notification_journal.clone()));
         if let Some(ref index_notifier) = index_notifier {
             let index_notify_channel = Channel::<IndexNotification>::default();
             let index_notify_listener_id = index_notifier.clone().register_new_listener(
@@ -182,6 +222,16 @@ impl RpcCoreService {
             subscribers.push(index_subscriber);
         }
 
+        // Prepare the mining objects, the transaction conflicts being sent by the mining manager whatever the subscriptions
+        let transaction_conflict_channel = Channel::<TransactionConflict>::bounded(TRANSACTION_CONFLICT_CHANNEL_CAPACITY);
+        mining_manager.set_transaction_conflict_sender(transaction_conflict_channel.sender());
+        let mining_collector = Arc::new(CollectorFromMining::new(
+            "rpc-core <= mining",
+            transaction_conflict_channel.receiver(),
+            Arc::new(MiningConverter::new(config.clone())),
+        ));
+        collectors.push(mining_collector);
+
         // Protocol converter
         let protocol_converter = Arc::new(ProtocolConverter::new(flow_context.clone()));
 
@@ -199,8 +249,12 @@ impl RpcCoreService {
             consensus_converter,
             index_converter,
             protocol_converter,
+            notification_journal,
+            transaction_conflict_channel,
             core,
             processing_counters,
+            processing_parallelism,
+            script_metrics,
             wrpc_borsh_counters,
             wrpc_json_counters,
             shutdown: SingleTrigger::default(),
@@ -208,15 +262,42 @@ impl RpcCoreService {
             perf_monitor,
             p2p_tower_counters,
             grpc_tower_counters,
+            preflight_report,
         }
     }
 
     pub fn start_impl(&self) {
         self.notifier().start();
+        if self.notification_journal.is_some() {
+            self.subscribe_notification_journal();
+        }
+    }
+
+    /// Subscribes an internal listener to the journaled scopes, so that their notifications get emitted and journaled
+    /// in full whatever the subscriptions of the actual listeners are. A reconnecting client can thus backfill the
+    /// notifications emitted while it had no subscription at all.
+    fn subscribe_notification_journal(&self) {
+        let channel = Channel::<Notification>::default();
+        let connection = ChannelConnection::new(NOTIFICATION_JOURNAL, channel.sender(), ChannelType::Closable);
+        let listener_id = self.notifier.register_new_listener(connection, ListenerLifespan::Dynamic);
+        let mut scopes = vec![Scope::VirtualChainChanged(VirtualChainChangedScope::new(true))];
+        if self.utxoindex.is_some() {
+            scopes.push(Scope::UtxosChanged(UtxosChangedScope::default()));
+        }
+        for scope in scopes {
+            if let Err(err) = self.notifier.try_start_notify(listener_id, scope) {
+                warn!("{} failed subscribing the notification journal: {}", Self::IDENT, err);
+            }
+        }
+        // The notifications get journaled upon emission, the listener only has to discard them
+        let receiver = channel.receiver();
+        tokio::spawn(async move { while receiver.recv().await.is_ok() {} });
     }
 
     pub async fn join(&self) -> RpcResult<()> {
         trace!("{} joining notifier", Self::IDENT);
+        // The mining collector only ends once its channel is closed
+        self.transaction_conflict_channel.close();
         self.notifier().join().await?;
         Ok(())
     }
@@ -231,6 +312,13 @@ impl RpcCoreService {
         self.notifier.subscription_context().clone()
     }
 
+    /// Checks that a listener is allowed to start notifications of `scope` in the RPC mode of the node.
+    ///
+    /// RPC servers subscribe their listeners to the core notifier directly, so they must run this check as well.
+    pub fn check_start_notify(&self, scope: &Scope) -> RpcResult<()> {
+        check_start_notify_permission(self.config.unsafe_rpc, scope)
+    }
+
     pub fn core_shutdown_request_listener(&self) -> triggered::Listener {
         self.core_shutdown_request.listener.clone()
     }
@@ -278,23 +366,32 @@ impl RpcApi for RpcCoreService {
     async fn submit_block_call(&self, request: SubmitBlockRequest) -> RpcResult<SubmitBlockResponse> {
         let session = self.consensus_manager.consensus().unguarded_session();
 
-        // TODO: consider adding an error field to SubmitBlockReport to document both the report and error fields
         let is_synced: bool = self.has_sufficient_peer_connectivity() && session.async_is_nearly_synced().await;
 
         if !self.config.enable_unsynced_mining && !is_synced {
-            // error = "Block not submitted - node is not synced"
-            return Ok(SubmitBlockResponse { report: SubmitBlockReport::Reject(SubmitBlockRejectReason::IsInIBD) });
+            return Ok(SubmitBlockResponse::new(SubmitBlockReport::Reject(SubmitBlockRejectReason::IsInIBD), None)
+                .with_rejection_detail("Block not submitted - node is not synced"));
         }
 
         let try_block: RpcResult<Block> = (&request.block).try_into();
         if let Err(err) = &try_block {
             trace!("incoming SubmitBlockRequest with block conversion error: {}", err);
-            // error = format!("Could not parse block: {0}", err)
-            return Ok(SubmitBlockResponse { report: SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid) });
+            return Ok(SubmitBlockResponse::new(SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid), None)
+                .with_rejection_detail(format!("Could not parse block: {err}")));
         }
         let block = try_block?;
         let hash = block.hash();
 
+        let classification = match self.mining_manager.clone().classify_submitted_block(&session, block.clone()).await {
+            BlockSubmissionClassification::Fresh => SubmitBlockClassification::Fresh,
+            BlockSubmissionClassification::Stale => SubmitBlockClassification::Stale,
+            BlockSubmissionClassification::AlreadyProcessed => {
+                // A resubmitted block has nothing new to validate nor to relay
+                trace!("incoming SubmitBlockRequest for already processed block {}", hash);
+                return Ok(SubmitBlockResponse::new(SubmitBlockReport::Success, Some(SubmitBlockClassification::AlreadyProcessed)));
+            }
+        };
+
         if !request.allow_non_daa_blocks {
             let virtual_daa_score = session.get_virtual_daa_score();
 
@@ -303,14 +400,49 @@ impl RpcApi for RpcCoreService {
             let daa_window_block_duration = self.config.daa_window_duration_in_blocks(virtual_daa_score);
             if virtual_daa_score > daa_window_block_duration && block.header.daa_score < virtual_daa_score - daa_window_block_duration
             {
-                // error = format!("Block rejected. Reason: block DAA score {0} is too far behind virtual's DAA score {1}", block.header.daa_score, virtual_daa_score)
-                return Ok(SubmitBlockResponse { report: SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid) });
+                return Ok(SubmitBlockResponse::new(
+                    SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid),
+                    Some(classification),
+                )
+                .with_rejection_detail(format!(
+                    "Block rejected. Reason: block DAA score {} is too far behind virtual's DAA score {}",
+                    block.header.daa_score, virtual_daa_score
+                )));
             }
         }
 
-        trace!("incoming SubmitBlockRequest for block {}", hash);
-        match self.flow_context.submit_rpc_block(&session, block.clone()).await {
-            Ok(_) => Ok(SubmitBlockResponse { report: SubmitBlockReport::Success }),
+        // The order has to be checked before submitting, since submission evicts the block transactions from the mempool
+        let non_canonical_transaction_order = matches!(
+            self.mining_manager.clone().check_submitted_transaction_order(block.clone()).await,
+            Some(SubmittedTransactionOrder::NonCanonical)
+        );
+        if non_canonical_transaction_order {
+            debug!("incoming SubmitBlockRequest for block {} with transactions out of the canonical template order", hash);
+        }
+
+        trace!("incoming SubmitBlockRequest for block {} ({:?})", hash, classification);
+        let response = match self.flow_context.submit_rpc_block(&session, block.clone()).await {
+            // Submission returns once the virtual processed the block, so a block found invalid in UTXO context,
+            // e.g. because of a wrong coinbase payment, is known to be disqualified from the selected chain by now
+            Ok(_) => match session.async_get_block_status(hash).await {
+                Some(BlockStatus::StatusDisqualifiedFromChain) => {
+                    let reason = session.async_get_disqualification_reason(hash).await;
+                    warn!(
+                        "The RPC submitted block {} was disqualified from the selected chain: {}",
+                        hash,
+                        reason.as_ref().map_or("unknown reason".to_string(), |err| err.to_string())
+                    );
+                    let response = SubmitBlockResponse::new(
+                        SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid),
+                        Some(classification),
+                    );
+                    match reason {
+                        Some(err) => response.with_rejection_detail(format!("block is disqualified from the selected chain: {err}")),
+                        None => response.with_rejection_detail("block is disqualified from the selected chain"),
+                    }
+                }
+                _ => SubmitBlockResponse::new(SubmitBlockReport::Success, Some(classification)),
+            },
             Err(ProtocolError::RuleError(RuleError::BadMerkleRoot(h1, h2))) => {
                 warn!(
                     "The RPC submitted block triggered a {} error: {}. 
@@ -321,16 +453,19 @@ NOTE: This error usually indicates an RPC conversion error between the node and
                 if self.config.net.is_mainnet() {
                     warn!("Printing the full block for debug purposes:\n{:?}", block);
                 }
-                Ok(SubmitBlockResponse { report: SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid) })
+                SubmitBlockResponse::new(SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid), Some(classification))
+                    .with_rejection_detail(RuleError::BadMerkleRoot(h1, h2))
             }
             Err(err) => {
                 warn!(
                     "The RPC submitted block triggered an error: {}\nPrinting the full header for debug purposes:\n{:?}",
                     err, block
                 );
-                Ok(SubmitBlockResponse { report: SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid) })
+                SubmitBlockResponse::new(SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid), Some(classification))
+                    .with_rejection_detail(err)
             }
-        }
+        };
+        Ok(response.with_non_canonical_transaction_order(non_canonical_transaction_order))
     }
 
     async fn get_block_template_call(&self, request: GetBlockTemplateRequest) -> RpcResult<GetBlockTemplateResponse> {
@@ -359,10 +494,11 @@ NOTE: This error usually indicates an RPC conversion error between the node and
 
         let is_nearly_synced =
             self.config.is_nearly_synced(block_template.selected_parent_timestamp, block_template.selected_parent_daa_score);
-        Ok(GetBlockTemplateResponse {
-            block: (&block_template.block).into(),
-            is_synced: self.has_sufficient_peer_connectivity() && is_nearly_synced,
-        })
+        Ok(GetBlockTemplateResponse::new(
+            (&block_template.block).into(),
+            self.has_sufficient_peer_connectivity() && is_nearly_synced,
+            block_template.selection_stats.as_ref().map(RpcBlockTemplateStats::from),
+        ))
     }
 
     async fn get_block_call(&self, request: GetBlockRequest) -> RpcResult<GetBlockResponse> {
@@ -372,7 +508,13 @@ NOTE: This error usually indicates an RPC conversion error between the node and
         Ok(GetBlockResponse {
             block: self
                 .consensus_converter
-                .get_block(&session, &block, request.include_transactions, request.include_transactions)
+                .get_block(
+                    &session,
+                    &block,
+                    request.include_transactions,
+                    request.include_transactions,
+                    request.transaction_field_mask,
+                )
                 .await?,
         })
     }
@@ -415,7 +557,13 @@ NOTE: This error usually indicates an RPC conversion error between the node and
                 let block = session.async_get_block_even_if_header_only(hash).await?;
                 let rpc_block = self
                     .consensus_converter
-                    .get_block(&session, &block, request.include_transactions, request.include_transactions)
+                    .get_block(
+                        &session,
+                        &block,
+                        request.include_transactions,
+                        request.include_transactions,
+                        request.transaction_field_mask,
+                    )
                     .await?;
                 blocks.push(rpc_block)
             }
@@ -428,6 +576,8 @@ NOTE: This error usually indicates an RPC conversion error between the node and
 
     async fn get_info_call(&self, _request: GetInfoRequest) -> RpcResult<GetInfoResponse> {
         let is_nearly_synced = self.consensus_manager.consensus().unguarded_session().async_is_nearly_synced().await;
+        let consensus_manager = self.consensus_manager.clone();
+        let consensus_status = spawn_blocking(move || consensus_manager.consensus_status()).await.unwrap();
         Ok(GetInfoResponse {
             p2p_id: self.flow_context.node_id.to_string(),
             mempool_size: self.mining_manager.transaction_count_sample(TransactionQuery::TransactionsOnly),
@@ -436,28 +586,47 @@ NOTE: This error usually indicates an RPC conversion error between the node and
             is_synced: self.has_sufficient_peer_connectivity() && is_nearly_synced,
             has_notify_command: true,
             has_message_id: true,
+            params_digest: Some(self.config.params.digest()),
+            dust_thresholds: (self.mining_manager.clone().standard_dust_thresholds().await)
+                .into_iter()
+                .map(|(script_class, min_output_value)| RpcDustThreshold::new(script_class, min_output_value))
+                .collect(),
+            consensus_status: Some(consensus_status.into()),
         })
     }
 
     async fn get_mempool_entry_call(&self, request: GetMempoolEntryRequest) -> RpcResult<GetMempoolEntryResponse> {
         let query = self.extract_tx_query(request.filter_transaction_pool, request.include_orphan_pool)?;
-        let Some(transaction) = self.mining_manager.clone().get_transaction(request.transaction_id, query).await else {
-            return Err(RpcError::TransactionNotFound(request.transaction_id));
-        };
         let session = self.consensus_manager.consensus().unguarded_session();
-        Ok(GetMempoolEntryResponse::new(self.consensus_converter.get_mempool_entry(&session, &transaction)))
+        if let Some(transaction) = self.mining_manager.clone().get_transaction(request.transaction_id, query).await {
+            return Ok(GetMempoolEntryResponse::new(self.consensus_converter.get_mempool_entry(&session, &transaction)));
+        }
+        if query.include_transaction_pool() {
+            if let Some(transaction) = self.mining_manager.clone().get_held_transaction(request.transaction_id).await {
+                return Ok(GetMempoolEntryResponse::new(self.consensus_converter.get_held_mempool_entry(&session, &transaction)));
+            }
+        }
+        Err(RpcError::TransactionNotFound(request.transaction_id))
     }
 
     async fn get_mempool_entries_call(&self, request: GetMempoolEntriesRequest) -> RpcResult<GetMempoolEntriesResponse> {
         let query = self.extract_tx_query(request.filter_transaction_pool, request.include_orphan_pool)?;
         let session = self.consensus_manager.consensus().unguarded_session();
-        let (transactions, orphans) = self.mining_manager.clone().get_all_transactions(query).await;
-        let mempool_entries = transactions
+        // Transactions held until their lock time is reached are reported along the transaction pool
+        let entries = self.mining_manager.clone().get_mempool_entries(query, request.consistent_snapshot).await;
+        let mempool_entries = entries
+            .transactions
             .iter()
-            .chain(orphans.iter())
+            .chain(entries.orphans.iter())
             .map(|transaction| self.consensus_converter.get_mempool_entry(&session, transaction))
+            .chain(
+                entries
+                    .held_transactions
+                    .iter()
+                    .map(|transaction| self.consensus_converter.get_held_mempool_entry(&session, transaction)),
+            )
             .collect();
-        Ok(GetMempoolEntriesResponse::new(mempool_entries))
+        Ok(GetMempoolEntriesResponse::new(mempool_entries, self.consensus_converter.get_mempool_summary(&entries.summary)))
     }
 
     async fn get_mempool_entries_by_addresses_call(
@@ -490,6 +659,9 @@ NOTE: This error usually indicates an RPC conversion error between the node and
         if !self.config.unsafe_rpc && request.allow_orphan {
             warn!("SubmitTransaction RPC command called with AllowOrphan enabled while node in safe RPC mode -- switching to ForbidOrphan.");
         }
+        if !self.config.hold_time_locked_txs && request.allow_not_yet_valid {
+            warn!("SubmitTransaction RPC command called with AllowNotYetValid enabled while node is not holding time-locked transactions -- switching to ForbidNotYetValid.");
+        }
 
         let transaction: Transaction = (&request.transaction).try_into()?;
         let transaction_id = transaction.id();
@@ -498,12 +670,37 @@ NOTE: This error usually indicates an RPC conversion error between the node and
             true => Orphan::Allowed,
             false => Orphan::Forbidden,
         };
-        self.flow_context.submit_rpc_transaction(&session, transaction, orphan).await.map_err(|err| {
-            let err = RpcError::RejectedTransaction(transaction_id, err.to_string());
-            debug!("{err}");
-            err
-        })?;
-        Ok(SubmitTransactionResponse::new(transaction_id))
+        let hold = match self.config.hold_time_locked_txs && request.allow_not_yet_valid {
+            true => Hold::Allowed,
+            false => Hold::Forbidden,
+        };
+        // Keep a copy of the transaction for tracing its acceptance only if requested
+        let traced_transaction = request.verbose.then(|| transaction.clone());
+        let response = match self.flow_context.submit_rpc_transaction(&session, transaction, orphan, hold).await {
+            Ok(()) => SubmitTransactionResponse::new(transaction_id),
+            // Resubmissions of a known transaction succeed, so that clients can safely retry a submission
+            Err(ProtocolError::MiningManagerError(MiningManagerError::MempoolError(
+                MempoolRuleError::RejectDuplicate(_) | MempoolRuleError::RejectDuplicateOrphan(_),
+            ))) => SubmitTransactionResponse::already_known(transaction_id),
+            Err(ProtocolError::MiningManagerError(MiningManagerError::MempoolError(MempoolRuleError::RejectAlreadyAccepted(_)))) => {
+                // A transaction included by a block not yet accepted by the selected chain is reported as known
+                match self.mining_manager.clone().accepting_block(transaction_id).await {
+                    Some(accepting_block_hash) => SubmitTransactionResponse::already_accepted(transaction_id, accepting_block_hash),
+                    None => SubmitTransactionResponse::already_known(transaction_id),
+                }
+            }
+            Err(err) => {
+                let err = RpcError::RejectedTransaction(transaction_id, err.to_string());
+                debug!("{err}");
+                let Some(transaction) = traced_transaction else {
+                    return Err(err);
+                };
+                let trace = self.mining_manager.clone().trace_transaction_acceptance(&session, transaction).await;
+                let trace = self.consensus_converter.get_transaction_acceptance_trace(err.to_string(), &trace);
+                SubmitTransactionResponse::rejected(transaction_id, trace)
+            }
+        };
+        Ok(response.with_idempotency_key(request.idempotency_key))
     }
 
     async fn get_current_network_call(&self, _: GetCurrentNetworkRequest) -> RpcResult<GetCurrentNetworkResponse> {
@@ -523,6 +720,11 @@ NOTE: This error usually indicates an RPC conversion error between the node and
         Ok(GetSinkBlueScoreResponse::new(session.async_get_ghostdag_data(session.async_get_sink().await).await?.blue_score))
     }
 
+    async fn get_virtual_info_call(&self, _: GetVirtualInfoRequest) -> RpcResult<GetVirtualInfoResponse> {
+        // Read from the cached virtual state, hence neither waiting for the session lock nor reaching the stores
+        Ok(self.consensus_manager.consensus().unguarded_session().get_virtual_info())
+    }
+
     async fn get_virtual_chain_from_block_call(
         &self,
         request: GetVirtualChainFromBlockRequest,
@@ -580,13 +782,94 @@ NOTE: This error usually indicates an RPC conversion error between the node and
         Ok(GetBalancesByAddressesResponse::new(entries))
     }
 
+    async fn get_address_activity_call(&self, request: GetAddressActivityRequest) -> RpcResult<GetAddressActivityResponse> {
+        if !self.config.utxoindex {
+            return Err(RpcError::NoUtxoIndex);
+        }
+        let activity_map = self
+            .utxoindex
+            .clone()
+            .unwrap()
+            .get_activity_by_script_public_keys(request.addresses.iter().map(pay_to_address_script).collect())
+            .await
+            .map_err(|err| RpcError::General(err.to_string()))?;
+        let entries = request
+            .addresses
+            .into_iter()
+            .map(|address| match activity_map.get(&pay_to_address_script(&address)) {
+                Some(activity) => RpcAddressActivityEntry {
+                    address,
+                    has_received: true,
+                    first_seen_daa_score: activity.first_seen_daa_score,
+                    last_activity_daa_score: activity.last_activity_daa_score,
+                },
+                None => RpcAddressActivityEntry::unused(address),
+            })
+            .collect();
+        Ok(GetAddressActivityResponse::new(entries))
+    }
+
+    async fn get_missed_notifications_call(
+        &self,
+        request: GetMissedNotificationsRequest,
+    ) -> RpcResult<GetMissedNotificationsResponse> {
+        let Some(ref journal) = self.notification_journal else { return Err(RpcError::NoNotificationJournal) };
+        if matches!(request.scope, Scope::UtxosChanged(_)) && !self.config.utxoindex {
+            return Err(RpcError::NoUtxoIndex);
+        }
+        // Backfilling wildcard UtxosChanged notifications is as resource intensive as subscribing to them
+        self.check_start_notify(&request.scope)?;
+        journal.get_missed_notifications(&request.scope, request.from_sequence)
+    }
+
+    async fn get_utxo_return_address_call(&self, request: GetUtxoReturnAddressRequest) -> RpcResult<GetUtxoReturnAddressResponse> {
+        if !self.config.store_spent_utxos {
+            return Err(RpcError::NoSpentUtxoEntries);
+        }
+        let session = self.consensus_manager.consensus().session().await;
+        let utxo_entry =
+            session.async_get_spent_utxo_entry(request.accepting_block_hash, request.transaction_id, request.input_index).await?;
+        let address = extract_script_pub_key_address(&utxo_entry.script_public_key, self.config.prefix()).ok();
+        Ok(GetUtxoReturnAddressResponse::new(address, utxo_entry))
+    }
+
+    async fn validate_block_template_call(&self, request: ValidateBlockTemplateRequest) -> RpcResult<ValidateBlockTemplateResponse> {
+        if !self.config.unsafe_rpc {
+            warn!("ValidateBlockTemplate RPC command called while node in safe RPC mode -- ignoring.");
+            return Err(RpcError::UnavailableInSafeMode);
+        }
+        let block: Block = match (&request.block).try_into() {
+            Ok(block) => block,
+            Err(err) => return Ok(ValidateBlockTemplateResponse::invalid(format!("Could not parse block: {err}"), vec![])),
+        };
+        // Same session as block submission, since the template is validated as it would be submitted
+        let session = self.consensus_manager.consensus().unguarded_session();
+        let response = match session.async_validate_block_template(block).await {
+            Ok(validation) => ValidateBlockTemplateResponse::valid(validation.mass, validation.total_fees),
+            Err(err) => {
+                let invalid_transactions = match &err {
+                    RuleError::InvalidTransactionsInNewBlock(invalid_transactions) => invalid_transactions
+                        .iter()
+                        .map(|(&transaction_id, err)| RpcInvalidTemplateTransaction { transaction_id, reason: err.to_string() })
+                        .collect(),
+                    _ => vec![],
+                };
+                ValidateBlockTemplateResponse::invalid(err, invalid_transactions)
+            }
+        };
+        Ok(response)
+    }
+
     async fn get_coin_supply_call(&self, _: GetCoinSupplyRequest) -> RpcResult<GetCoinSupplyResponse> {
         if !self.config.utxoindex {
             return Err(RpcError::NoUtxoIndex);
         }
         let circulating_sompi =
             self.utxoindex.clone().unwrap().get_circulating_supply().await.map_err(|e| RpcError::General(e.to_string()))?;
-        Ok(GetCoinSupplyResponse::new(MAX_SOMPI, circulating_sompi))
+        let virtual_daa_score = self.consensus_manager.consensus().unguarded_session().get_virtual_daa_score();
+        let estimated_circulating_sompi = emission::estimated_circulating_supply(&self.config.params, virtual_daa_score);
+        let block_reward_sompi = emission::subsidy_at_daa_score(&self.config.params, virtual_daa_score);
+        Ok(GetCoinSupplyResponse::new(MAX_SOMPI, circulating_sompi, estimated_circulating_sompi, block_reward_sompi))
     }
 
     async fn get_daa_score_timestamp_estimate_call(
@@ -594,10 +877,41 @@ NOTE: This error usually indicates an RPC conversion error between the node and
         request: GetDaaScoreTimestampEstimateRequest,
     ) -> RpcResult<GetDaaScoreTimestampEstimateResponse> {
         let session = self.consensus_manager.consensus().session().await;
-        // TODO: cache samples based on sufficient recency of the data and append sink data
-        let mut headers = session.async_get_chain_block_samples().await;
-        let mut requested_daa_scores = request.daa_scores.clone();
         let mut daa_score_timestamp_map = HashMap::<u64, u64>::new();
+        let mut requested_daa_scores = Vec::new();
+
+        // DAA scores above the pruning point are resolved using the nearest selected chain block, which is
+        // exact for scores of chain blocks. Scores below the pruning point fall back to interpolating samples.
+        for &daa_score in request.daa_scores.iter() {
+            if daa_score_timestamp_map.contains_key(&daa_score) {
+                continue;
+            }
+            match session.async_get_chain_block_by_daa_score(daa_score).await {
+                Ok(block) => {
+                    // Adjust the timestamp in milliseconds based on the difference (daa_score * target_time_per_block)
+                    let timestamp = if daa_score >= block.daa_score {
+                        let time_adjustment =
+                            (daa_score - block.daa_score).checked_mul(self.config.target_time_per_block).unwrap_or(u64::MAX);
+                        block.timestamp.checked_add(time_adjustment).unwrap_or(u64::MAX)
+                    } else {
+                        let time_adjustment =
+                            (block.daa_score - daa_score).checked_mul(self.config.target_time_per_block).unwrap_or(u64::MAX);
+                        block.timestamp.saturating_sub(time_adjustment)
+                    };
+                    daa_score_timestamp_map.insert(daa_score, timestamp);
+                }
+                Err(ConsensusError::DaaScoreBelowPruningPoint(_, _)) => requested_daa_scores.push(daa_score),
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        if requested_daa_scores.is_empty() {
+            let timestamps = request.daa_scores.iter().map(|curr_daa_score| daa_score_timestamp_map[curr_daa_score]).collect();
+            return Ok(GetDaaScoreTimestampEstimateResponse::new(timestamps));
+        }
+
+        // TODO: cache samples based on sufficient recency of the data
+        let mut headers = session.async_get_chain_block_samples().await;
 
         headers.reverse();
         requested_daa_scores.sort_by(|a, b| b.cmp(a));
@@ -610,7 +924,7 @@ NOTE: This error usually indicates an RPC conversion error between the node and
         // will cause every remaining requested daa_score to be "found in range"
         //
         // TODO: optimize using binary search over the samples to obtain O(m log n) complexity (which is an improvement assuming m << n)
-        while header_idx < headers.len() && req_idx < request.daa_scores.len() {
+        while header_idx < headers.len() && req_idx < requested_daa_scores.len() {
             let header = headers.get(header_idx).unwrap();
             let curr_daa_score = requested_daa_scores[req_idx];
 
@@ -647,6 +961,127 @@ NOTE: This error usually indicates an RPC conversion error between the node and
         Ok(GetDaaScoreTimestampEstimateResponse::new(timestamps))
     }
 
+    async fn get_chain_block_by_daa_score_call(
+        &self,
+        request: GetChainBlockByDaaScoreRequest,
+    ) -> RpcResult<GetChainBlockByDaaScoreResponse> {
+        let session = self.consensus_manager.consensus().session().await;
+        let block = session.async_get_chain_block_by_daa_score(request.daa_score).await?;
+        Ok(GetChainBlockByDaaScoreResponse::new(block.hash, block.daa_score, block.timestamp))
+    }
+
+    async fn set_log_filters_call(&self, request: SetLogFiltersRequest) -> RpcResult<SetLogFiltersResponse> {
+        if !self.config.unsafe_rpc {
+            warn!("SetLogFilters RPC command called while node in safe RPC mode -- ignoring.");
+            return Err(RpcError::UnavailableInSafeMode);
+        }
+        let filters = if request.filters.trim().is_empty() {
+            kaspa_core::log::log_filters()
+        } else {
+            let filters = kaspa_core::log::set_log_filters(&request.filters).map_err(|err| RpcError::General(err.to_string()))?;
+            info!("Log filters were set to `{}` via RPC", filters);
+            Some(filters)
+        };
+        Ok(SetLogFiltersResponse::new(filters.unwrap_or_default()))
+    }
+
+    async fn get_outpoint_spend_status_call(
+        &self,
+        request: GetOutpointSpendStatusRequest,
+    ) -> RpcResult<GetOutpointSpendStatusResponse> {
+        let search_depth = request.search_depth.unwrap_or(DEFAULT_OUTPOINT_SPEND_SEARCH_DEPTH);
+        if !self.config.unsafe_rpc && search_depth > MAX_SAFE_OUTPOINT_SPEND_SEARCH_DEPTH {
+            return Err(RpcError::SearchDepthExceedingMaximum(search_depth, MAX_SAFE_OUTPOINT_SPEND_SEARCH_DEPTH));
+        }
+        let session = self.consensus_manager.consensus().session().await;
+        let status = session.async_get_outpoint_spend_status(request.outpoint, search_depth).await?;
+        let mempool_spender = self.mining_manager.clone().get_outpoint_spender(request.outpoint).await;
+
+        // Spenders are ordered from the sink downwards, so the first accepted one is the most recent. Other blocks
+        // including the very same transaction are not conflicting, only the rejected double spends are.
+        let accepted_spender = status.spenders.iter().find(|spender| spender.is_accepted).copied();
+        let into_rpc = |spender: &OutpointSpender| RpcOutpointSpender {
+            transaction_id: spender.transaction_id,
+            block_hash: spender.block_hash,
+            merging_block_hash: spender.merging_block_hash,
+        };
+        let conflicting_spenders = status
+            .spenders
+            .iter()
+            .filter(|spender| {
+                !spender.is_accepted && accepted_spender.map_or(true, |accepted| accepted.transaction_id != spender.transaction_id)
+            })
+            .map(into_rpc)
+            .collect();
+
+        Ok(GetOutpointSpendStatusResponse::new(
+            status.utxo_entry,
+            mempool_spender,
+            accepted_spender.as_ref().map(into_rpc),
+            conflicting_spenders,
+        ))
+    }
+
+    async fn estimate_transaction_confirmation_time_call(
+        &self,
+        request: EstimateTransactionConfirmationTimeRequest,
+    ) -> RpcResult<EstimateTransactionConfirmationTimeResponse> {
+        let Some(estimate) = self.mining_manager.clone().estimate_transaction_confirmation_time(request.transaction_id).await else {
+            return Err(RpcError::TransactionNotFound(request.transaction_id));
+        };
+        Ok(EstimateTransactionConfirmationTimeResponse::new(RpcConfirmationTimeEstimate {
+            package_feerate: estimate.package_feerate,
+            transactions_ahead: estimate.transactions_ahead,
+            mass_ahead: estimate.mass_ahead,
+            block_utilization: estimate.block_utilization,
+            block_interval: estimate.block_interval,
+            min_blocks: estimate.min_blocks,
+            max_blocks: estimate.max_blocks,
+            min_time: estimate.min_time,
+            max_time: estimate.max_time,
+        }))
+    }
+
+    async fn get_acceptance_proof_call(&self, request: GetAcceptanceProofRequest) -> RpcResult<GetAcceptanceProofResponse> {
+        let session = self.consensus_manager.consensus().session().await;
+        let proof = session.async_get_acceptance_proof(request.accepting_block_hash, request.transaction_id).await?;
+        let header = session.async_get_header(request.accepting_block_hash).await?;
+        Ok(GetAcceptanceProofResponse::new(header.accepted_id_merkle_root, proof.leaf_index, proof.path))
+    }
+
+    async fn create_backup_call(&self, request: CreateBackupRequest) -> RpcResult<CreateBackupResponse> {
+        if !self.config.unsafe_rpc {
+            warn!("CreateBackup RPC command called while node in safe RPC mode -- ignoring.");
+            return Err(RpcError::UnavailableInSafeMode);
+        }
+        let output_dir = PathBuf::from(request.output_directory);
+        if !output_dir.is_absolute() {
+            return Err(RpcError::General("the backup output directory must be an absolute path".to_string()));
+        }
+        let consensus_manager = self.consensus_manager.clone();
+        let manifest = spawn_blocking(move || consensus_manager.create_backup(&output_dir)).await.unwrap()?;
+        Ok(manifest.into())
+    }
+
+    async fn get_virtual_parent_selection_trace_call(
+        &self,
+        _: GetVirtualParentSelectionTraceRequest,
+    ) -> RpcResult<GetVirtualParentSelectionTraceResponse> {
+        if !self.config.unsafe_rpc {
+            warn!("GetVirtualParentSelectionTrace RPC command called while node in safe RPC mode -- ignoring.");
+            return Err(RpcError::UnavailableInSafeMode);
+        }
+        Ok(self.consensus_manager.consensus().session().await.async_get_virtual_parent_selection_trace().await)
+    }
+
+    async fn get_system_info_call(&self, _: GetSystemInfoRequest) -> RpcResult<GetSystemInfoResponse> {
+        Ok(GetSystemInfoResponse {
+            version: version().to_string(),
+            fd_limit: fd_budget::limit() as u32,
+            preflight_checks: self.preflight_report.checks(),
+        })
+    }
+
     async fn ping_call(&self, _: PingRequest) -> RpcResult<PingResponse> {
         Ok(PingResponse {})
     }
@@ -710,17 +1145,52 @@ NOTE: This error usually indicates an RPC conversion error between the node and
             return Err(RpcError::UnavailableInSafeMode);
         }
         let peer_address = request.peer_address.normalize(self.config.net.default_p2p_port());
-        if let Some(connection_manager) = self.flow_context.connection_manager() {
-            connection_manager.add_connection_request(peer_address.into(), request.is_permanent).await;
-        } else {
+        let Some(connection_manager) = self.flow_context.connection_manager() else {
             return Err(RpcError::NoConnectionManager);
+        };
+        if !request.is_permanent {
+            connection_manager.add_connection_request(peer_address.into(), false).await;
+        } else if !connection_manager.add_manual_peer(peer_address.into()).await {
+            return Err(RpcError::ManualPeersLimitReached(peer_address, MAX_MANUAL_PEERS));
         }
         Ok(AddPeerResponse {})
     }
 
-    async fn get_peer_addresses_call(&self, _: GetPeerAddressesRequest) -> RpcResult<GetPeerAddressesResponse> {
-        let address_manager = self.flow_context.address_manager.lock();
-        Ok(GetPeerAddressesResponse::new(address_manager.get_all_addresses(), address_manager.get_all_banned_addresses()))
+    async fn remove_peer_call(&self, request: RemovePeerRequest) -> RpcResult<RemovePeerResponse> {
+        if !self.config.unsafe_rpc {
+            warn!("RemovePeer RPC command called while node in safe RPC mode -- ignoring.");
+            return Err(RpcError::UnavailableInSafeMode);
+        }
+        let peer_address = request.peer_address.normalize(self.config.net.default_p2p_port());
+        let Some(connection_manager) = self.flow_context.connection_manager() else {
+            return Err(RpcError::NoConnectionManager);
+        };
+        if !connection_manager.remove_peer(peer_address.into()).await {
+            return Err(RpcError::PeerIsNotAdded(peer_address));
+        }
+        Ok(RemovePeerResponse {})
+    }
+
+    async fn set_processing_parallelism_call(
+        &self,
+        request: SetProcessingParallelismRequest,
+    ) -> RpcResult<SetProcessingParallelismResponse> {
+        if !self.config.unsafe_rpc {
+            warn!("SetProcessingParallelism RPC command called while node in safe RPC mode -- ignoring.");
+            return Err(RpcError::UnavailableInSafeMode);
+        }
+        if request.limit == 0 {
+            return Err(RpcError::General("the processing parallelism limit must be positive".to_string()));
+        }
+        let previous_limit = self.processing_parallelism.set_limit(request.limit as usize);
+        info!("Processing parallelism was set from {} to {} via RPC", previous_limit, request.limit);
+        Ok(SetProcessingParallelismResponse::new(previous_limit.try_into().unwrap_or(u32::MAX)))
+    }
+
+    async fn get_peer_addresses_call(&self, request: GetPeerAddressesRequest) -> RpcResult<GetPeerAddressesResponse> {
+        let known_addresses = self.protocol_converter.get_known_addresses(&request);
+        let banned_addresses = self.flow_context.address_manager.lock().get_all_banned_addresses();
+        Ok(GetPeerAddressesResponse::new(known_addresses, banned_addresses))
     }
 
     async fn ban_call(&self, request: BanRequest) -> RpcResult<BanResponse> {
@@ -842,6 +1312,8 @@ NOTE: This error usually indicates an RPC conversion error between the node and
         let consensus_metrics = if req.consensus_metrics {
             let consensus_stats = self.consensus_manager.consensus().unguarded_session().async_get_stats().await;
             let processing_counters = self.processing_counters.snapshot();
+            let parallelism = self.processing_parallelism.snapshot();
+            let propagation = self.flow_context.block_propagation().percentiles();
 
             Some(ConsensusMetrics {
                 node_blocks_submitted_count: processing_counters.blocks_submitted,
@@ -861,14 +1333,45 @@ NOTE: This error usually indicates an RPC conversion error between the node and
                 network_past_median_time: consensus_stats.virtual_stats.past_median_time,
                 network_virtual_parent_hashes_count: consensus_stats.virtual_stats.num_parents,
                 network_virtual_daa_score: consensus_stats.virtual_stats.daa_score,
+                // ---
+                node_block_propagation_sample_count: propagation.samples as u64,
+                node_block_propagation_p50_millis: propagation.p50,
+                node_block_propagation_p90_millis: propagation.p90,
+                node_block_propagation_p99_millis: propagation.p99,
+                // ---
+                node_processing_parallelism_limit: parallelism.limit.try_into().unwrap_or(u32::MAX),
+                node_processing_running_count: parallelism.running.try_into().unwrap_or(u32::MAX),
+                node_processing_queued_count: parallelism.queued.try_into().unwrap_or(u32::MAX),
             })
         } else {
             None
         };
 
+        let script_metrics = req.script_metrics.then(|| {
+            let snapshot = self.script_metrics.snapshot();
+            ScriptMetrics {
+                checked_transactions_count: snapshot.checked_counts,
+                rejection_counts: snapshot
+                    .rejection_counts
+                    .into_iter()
+                    .map(|(reason, count)| RpcScriptRejectionCount { reason: reason.to_string(), count })
+                    .collect(),
+                opcode_sample_rate: snapshot.opcode_sample_rate,
+                sampled_transactions_count: snapshot.sampled_counts,
+                opcode_counts: snapshot.opcode_counts.into_iter().map(|(opcode, count)| RpcOpcodeCount { opcode, count }).collect(),
+            }
+        });
+
         let server_time = unix_now();
 
-        let response = GetMetricsResponse { server_time, process_metrics, connection_metrics, bandwidth_metrics, consensus_metrics };
+        let response = GetMetricsResponse {
+            server_time,
+            process_metrics,
+            connection_metrics,
+            bandwidth_metrics,
+            consensus_metrics,
+            script_metrics,
+        };
 
         Ok(response)
     }
@@ -912,22 +1415,9 @@ NOTE: This error usually indicates an RPC conversion error between the node and
 
     /// Start sending notifications of some type to a listener.
     async fn start_notify(&self, id: ListenerId, scope: Scope) -> RpcResult<()> {
-        match scope {
-            Scope::UtxosChanged(ref utxos_changed_scope) if !self.config.unsafe_rpc && utxos_changed_scope.addresses.is_empty() => {
-                // The subscription to blanket UtxosChanged notifications is restricted to unsafe mode only
-                // since the notifications yielded are highly resource intensive.
-                //
-                // Please note that unsubscribing to blanket UtxosChanged is always allowed and cancels
-                // the whole subscription no matter if blanket or targeting specified addresses.
-
-                warn!("RPC subscription to blanket UtxosChanged called while node in safe RPC mode -- ignoring.");
-                Err(RpcError::UnavailableInSafeMode)
-            }
-            _ => {
-                self.notifier.clone().start_notify(id, scope).await?;
-                Ok(())
-            }
-        }
+        self.check_start_notify(&scope)?;
+        self.notifier.clone().start_notify(id, scope).await?;
+        Ok(())
     }
 
     /// Stop sending notifications of some type to a listener.
@@ -937,6 +1427,23 @@ NOTE: This error usually indicates an RPC conversion error between the node and
     }
 }
 
+/// Checks that a listener is allowed to start notifications of `scope`, given whether the node runs in unsafe RPC mode.
+///
+/// The subscription to wildcard UtxosChanged notifications is restricted to unsafe mode only since the notifications
+/// yielded are highly resource intensive.
+///
+/// Please note that unsubscribing from wildcard UtxosChanged is always allowed and cancels the whole subscription no
+/// matter if wildcard or targeting specified addresses.
+pub fn check_start_notify_permission(unsafe_rpc: bool, scope: &Scope) -> RpcResult<()> {
+    match scope {
+        Scope::UtxosChanged(utxos_changed_scope) if !unsafe_rpc && utxos_changed_scope.is_wildcard() => {
+            warn!("RPC subscription to wildcard UtxosChanged called while node in safe RPC mode -- ignoring.");
+            Err(RpcError::UnavailableInSafeMode)
+        }
+        _ => Ok(()),
+    }
+}
+
 // It might be necessary to opt this out in the context of wasm32
 
 impl AsyncService for RpcCoreService {
@@ -1,7 +1,7 @@
 use crate::imports::*;
 use crate::parse::parse_host;
-use crate::{error::Error, node::NodeDescriptor};
-use kaspa_consensus_core::network::NetworkType;
+use crate::{error::Error, node::NodeDescriptor, resolver::NodeFailover};
+use kaspa_consensus_core::{config::params::Params, network::NetworkType};
 use kaspa_notify::{
     listener::ListenerLifespan,
     subscription::{context::SubscriptionContext, MutationPolicies, UtxosChangedMutationPolicy},
@@ -39,6 +39,7 @@ struct Inner {
     resolver: Mutex<Option<Resolver>>,
     network_id: Mutex<Option<NetworkId>>,
     node_descriptor: Mutex<Option<Arc<NodeDescriptor>>>,
+    failover: Mutex<NodeFailover>,
 }
 
 impl Inner {
@@ -67,6 +68,7 @@ impl Inner {
             RpcApiOps::VirtualDaaScoreChangedNotification,
             RpcApiOps::PruningPointUtxoSetOverrideNotification,
             RpcApiOps::NewBlockTemplateNotification,
+            RpcApiOps::TransactionConflictNotification,
         ]
         .into_iter()
         .for_each(|notification_op| {
@@ -109,6 +111,7 @@ impl Inner {
             resolver: Mutex::new(resolver),
             network_id: Mutex::new(network_id),
             node_descriptor: Mutex::new(None),
+            failover: Mutex::new(NodeFailover::default()),
         };
         Ok(client)
     }
@@ -217,7 +220,18 @@ impl RpcResolver for Inner {
             url
         } else if let Some(resolver) = self.resolver().as_ref() {
             let network_id = self.network_id().expect("Resolver requires network id in RPC client configuration");
-            let node = resolver.get_node(self.encoding, network_id).await.map_err(WebSocketError::custom)?;
+            // Retry the current node a few times before failing over to the next
+            // candidate, and resolve new candidates once all of them failed
+            let next = self.failover.lock().unwrap().next_node();
+            let node = match next {
+                Some(node) => node,
+                None => {
+                    let candidates = resolver.resolve(self.encoding, network_id).await.map_err(WebSocketError::custom)?;
+                    let mut failover = self.failover.lock().unwrap();
+                    failover.reset(candidates);
+                    failover.next_node().ok_or_else(|| WebSocketError::custom("Resolver returned no node"))?
+                }
+            };
             let url = node.url.clone();
             self.node_descriptor.lock().unwrap().replace(Arc::new(node));
             url
@@ -347,11 +361,13 @@ impl KaspaRpcClient {
 
     pub fn set_resolver(&self, resolver: Resolver) -> Result<()> {
         self.inner.resolver.lock().unwrap().replace(resolver);
+        self.inner.failover.lock().unwrap().reset(vec![]);
         Ok(())
     }
 
     pub fn set_network_id(&self, network_id: &NetworkId) -> Result<()> {
         self.inner.network_id.lock().unwrap().replace(*network_id);
+        self.inner.failover.lock().unwrap().reset(vec![]);
         Ok(())
     }
 
@@ -539,6 +555,7 @@ impl KaspaRpcClient {
                         if let Ok(msg) = msg {
                             match msg {
                                 WrpcCtl::Connect => {
+                                    inner.failover.lock().unwrap().on_connected();
                                     inner.rpc_ctl.signal_open().await.expect("(KaspaRpcClient) rpc_ctl.signal_open() error");
                                 }
                                 WrpcCtl::Disconnect => {
@@ -568,6 +585,25 @@ impl KaspaRpcClient {
     pub fn trigger_abort(&self) -> Result<()> {
         Ok(self.inner.rpc_client.trigger_abort()?)
     }
+
+    /// Warns if a transaction without a mass commitment is submitted to a network
+    /// where storage mass (KIP-9) is activated. Such transactions are rejected by the node.
+    fn warn_on_missing_mass_commitment(&self, transaction: &RpcTransaction) {
+        if transaction.mass != 0 {
+            return;
+        }
+        let Some(network_id) = self.inner.network_id() else { return };
+        // avoid panicking on unknown testnet suffixes while resolving consensus params
+        if network_id.network_type == NetworkType::Testnet && !matches!(network_id.suffix, Some(10) | Some(11)) {
+            return;
+        }
+        if Params::from(network_id).storage_mass_activation_daa_score != u64::MAX {
+            log_warn!(
+                "WARNING: submitting transaction with zero mass to {network_id}; \
+                the transaction mass must be committed (see `finalize_mass()`) or the transaction will be rejected"
+            );
+        }
+    }
 }
 
 #[async_trait]
@@ -597,6 +633,21 @@ impl RpcApi for KaspaRpcClient {
             GetCoinSupply,
             GetConnectedPeerInfo,
             GetDaaScoreTimestampEstimate,
+            GetChainBlockByDaaScore,
+            SetLogFilters,
+            GetOutpointSpendStatus,
+            EstimateTransactionConfirmationTime,
+            GetAcceptanceProof,
+            CreateBackup,
+            GetVirtualParentSelectionTrace,
+            GetSystemInfo,
+            RemovePeer,
+            GetVirtualInfo,
+            SetProcessingParallelism,
+            GetAddressActivity,
+            GetMissedNotifications,
+            GetUtxoReturnAddress,
+            ValidateBlockTemplate,
             GetServerInfo,
             GetCurrentNetwork,
             GetHeaders,
@@ -616,11 +667,17 @@ impl RpcApi for KaspaRpcClient {
             ResolveFinalityConflict,
             Shutdown,
             SubmitBlock,
-            SubmitTransaction,
             Unban,
         ]
     );
 
+    async fn submit_transaction_call(&self, request: SubmitTransactionRequest) -> RpcResult<SubmitTransactionResponse> {
+        self.warn_on_missing_mass_commitment(&request.transaction);
+        let response: ClientResult<SubmitTransactionResponse> =
+            self.inner.rpc_client.call(RpcApiOps::SubmitTransaction, request).await;
+        Ok(response.map_err(|e| kaspa_rpc_core::error::RpcError::RpcSubsystem(e.to_string()))?)
+    }
+
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Notification API
 
@@ -61,6 +61,9 @@ pub enum Error {
 
     #[error(transparent)]
     NetworkId(#[from] kaspa_consensus_core::network::NetworkIdError),
+
+    #[error("Resolver node list is not signed by a trusted key")]
+    InvalidNodeListSignature,
 }
 
 impl Error {
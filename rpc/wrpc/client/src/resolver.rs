@@ -4,10 +4,23 @@ use crate::node::NodeDescriptor;
 pub use futures::future::join_all;
 use rand::seq::SliceRandom;
 use rand::thread_rng;
+use secp256k1::{schnorr::Signature, Keypair, Message, XOnlyPublicKey, SECP256K1};
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+use std::time::Duration;
+use workflow_core::task::sleep;
+use workflow_core::time::{unixtime_as_millis_u64, Instant};
 use workflow_http::get_json;
 
 const DEFAULT_VERSION: usize = 1;
 
+/// Maximum age of a signed node list, older lists are rejected as replayed
+pub const MAX_NODE_LIST_AGE: Duration = Duration::from_secs(60 * 60);
+/// Maximum number of candidates of a node list probed on resolution
+pub const PROBE_CANDIDATES: usize = 5;
+/// Timeout of the connection and of the `GetInfo` call of a candidate probe
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResolverRecord {
     pub url: String,
@@ -16,31 +29,171 @@ pub struct ResolverRecord {
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ResolverConfig {
+    /// Hex encoded public keys trusted for signing node lists
+    #[serde(default, rename = "public-keys")]
+    public_keys: Vec<String>,
     resolver: Vec<ResolverRecord>,
 }
 
-fn try_parse_resolvers(toml: &str) -> Result<Vec<Arc<String>>> {
-    Ok(toml::from_str::<ResolverConfig>(toml)?
+fn try_parse_resolvers(toml: &str) -> Result<(Vec<Arc<String>>, Vec<XOnlyPublicKey>)> {
+    let config = toml::from_str::<ResolverConfig>(toml)?;
+    let urls = config
         .resolver
         .into_iter()
         .filter_map(|resolver| resolver.enable.unwrap_or(true).then_some(Arc::new(resolver.url)))
-        .collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let public_keys = config.public_keys.iter().map(|key| parse_public_key(key)).collect::<Result<Vec<_>>>()?;
+    Ok((urls, public_keys))
+}
+
+/// Parses a hex encoded x-only public key trusted for signing node lists
+pub fn parse_public_key(key: &str) -> Result<XOnlyPublicKey> {
+    XOnlyPublicKey::from_str(key).map_err(|err| Error::custom(format!("Invalid resolver public key `{key}`: {err}")))
+}
+
+///
+/// List of the nodes serving the given encoding and network, as elected by a resolver.
+///
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeList {
+    pub encoding: Encoding,
+    pub network_id: NetworkId,
+    /// Unix time (in milliseconds) at which the list was signed
+    pub timestamp: u64,
+    pub nodes: Vec<NodeDescriptor>,
+}
+
+///
+/// A [`NodeList`] signed by a resolver, which prevents a spoofed resolver from
+/// steering clients to nodes of its choice.
+///
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignedNodeList {
+    /// JSON serialized [`NodeList`], as signed
+    pub payload: String,
+    /// Hex encoded Schnorr signature of the SHA-256 digest of the payload
+    pub signature: String,
+}
+
+impl SignedNodeList {
+    fn message(payload: &str) -> Message {
+        Message::from_digest_slice(Sha256::digest(payload.as_bytes()).as_slice()).expect("SHA-256 digest is 32 bytes long")
+    }
+
+    pub fn try_sign(list: &NodeList, keypair: &Keypair) -> Result<Self> {
+        let payload = serde_json::to_string(list).map_err(Error::custom)?;
+        let signature = SECP256K1.sign_schnorr(&Self::message(&payload), keypair).to_string();
+        Ok(Self { payload, signature })
+    }
+
+    /// Returns the signed node list if signed by one of the `public_keys`, was elected for the
+    /// given encoding and network, and is not older than [`MAX_NODE_LIST_AGE`].
+    pub fn verify(&self, public_keys: &[XOnlyPublicKey], encoding: Encoding, network_id: NetworkId) -> Result<NodeList> {
+        let signature = Signature::from_str(&self.signature).map_err(|_| Error::InvalidNodeListSignature)?;
+        let message = Self::message(&self.payload);
+        if !public_keys.iter().any(|key| SECP256K1.verify_schnorr(&signature, &message, key).is_ok()) {
+            return Err(Error::InvalidNodeListSignature);
+        }
+        let list = serde_json::from_str::<NodeList>(&self.payload).map_err(Error::custom)?;
+        if list.encoding != encoding || list.network_id != network_id {
+            return Err(Error::custom(format!(
+                "Resolver node list is for {} {} while {encoding} {network_id} was requested",
+                list.encoding, list.network_id
+            )));
+        }
+        if list.timestamp.saturating_add(MAX_NODE_LIST_AGE.as_millis() as u64) < unixtime_as_millis_u64() {
+            return Err(Error::custom("Resolver node list is outdated"));
+        }
+        Ok(list)
+    }
+}
+
+///
+/// Outcome of the probe of a node list candidate.
+///
+#[derive(Clone, Debug)]
+pub struct NodeProbe {
+    pub node: NodeDescriptor,
+    /// Round-trip time of the `GetInfo` call, `None` if the node could not be reached
+    pub latency: Option<Duration>,
+    pub is_synced: bool,
+    pub is_utxo_indexed: bool,
+}
+
+impl NodeProbe {
+    /// `true` if the node is reachable, synced and serves UTXO index queries
+    pub fn is_eligible(&self) -> bool {
+        self.latency.is_some() && self.is_synced && self.is_utxo_indexed
+    }
+}
+
+/// Ranks the eligible probed nodes by ascending latency, dropping the others
+pub fn rank_candidates(mut probes: Vec<NodeProbe>) -> Vec<NodeDescriptor> {
+    probes.retain(NodeProbe::is_eligible);
+    probes.sort_by_key(|probe| probe.latency);
+    probes.into_iter().map(|probe| probe.node).collect()
+}
+
+///
+/// Candidates of the last resolution of the [`KaspaRpcClient`](crate::KaspaRpcClient),
+/// failed over to in turn after repeated connection failures. A new resolution is due
+/// once all candidates failed.
+///
+#[derive(Debug, Default)]
+pub struct NodeFailover {
+    candidates: VecDeque<NodeDescriptor>,
+    current: Option<NodeDescriptor>,
+    attempts: usize,
+}
+
+impl NodeFailover {
+    /// Number of consecutive connection attempts to a node before failing over
+    pub const MAX_ATTEMPTS: usize = 3;
+
+    pub fn reset(&mut self, candidates: Vec<NodeDescriptor>) {
+        self.candidates = candidates.into();
+        self.current = None;
+        self.attempts = 0;
+    }
+
+    /// Records a successful connection to the current node
+    pub fn on_connected(&mut self) {
+        self.attempts = 0;
+    }
+
+    /// Returns the node of the next connection attempt, `None` if all candidates failed
+    pub fn next_node(&mut self) -> Option<NodeDescriptor> {
+        if self.current.is_none() || self.attempts >= Self::MAX_ATTEMPTS {
+            self.current = self.candidates.pop_front();
+            self.attempts = 0;
+        }
+        let node = self.current.clone()?;
+        self.attempts += 1;
+        Some(node)
+    }
 }
 
 #[derive(Debug)]
 struct Inner {
     pub urls: Vec<Arc<String>>,
+    pub public_keys: Vec<XOnlyPublicKey>,
 }
 
 impl Inner {
-    pub fn new(urls: Vec<Arc<String>>) -> Self {
-        Self { urls }
+    pub fn new(urls: Vec<Arc<String>>, public_keys: Vec<XOnlyPublicKey>) -> Self {
+        Self { urls, public_keys }
     }
 }
 
 ///
 /// Resolver is a client for obtaining public Kaspa wRPC endpoints.
 ///
+/// Without trusted public keys, the resolver returns the node elected by the first
+/// reachable resolver. With trusted public keys, it fetches the signed list of nodes
+/// of a resolver, probes a subset of them and ranks the reachable and synced ones by
+/// latency, rejecting lists not signed by one of the keys.
+///
 #[derive(Debug, Clone)]
 pub struct Resolver {
     inner: Arc<Inner>,
@@ -49,8 +202,8 @@ pub struct Resolver {
 impl Default for Resolver {
     fn default() -> Self {
         let toml = include_str!("../Resolvers.toml");
-        let urls = try_parse_resolvers(toml).expect("TOML: Unable to parse RPC Resolver list");
-        Self { inner: Arc::new(Inner::new(urls)) }
+        let (urls, public_keys) = try_parse_resolvers(toml).expect("TOML: Unable to parse RPC Resolver list");
+        Self { inner: Arc::new(Inner::new(urls, public_keys)) }
     }
 }
 
@@ -60,13 +213,26 @@ impl Resolver {
             panic!("Resolver: Empty URL list supplied to the constructor.");
         }
 
-        Self { inner: Arc::new(Inner::new(urls)) }
+        Self { inner: Arc::new(Inner::new(urls, vec![])) }
+    }
+
+    /// Creates a resolver only accepting node lists signed by one of the `public_keys`
+    pub fn new_with_public_keys(urls: Vec<Arc<String>>, public_keys: Vec<XOnlyPublicKey>) -> Self {
+        if urls.is_empty() {
+            panic!("Resolver: Empty URL list supplied to the constructor.");
+        }
+
+        Self { inner: Arc::new(Inner::new(urls, public_keys)) }
     }
 
     pub fn urls(&self) -> Vec<Arc<String>> {
         self.inner.urls.clone()
     }
 
+    pub fn public_keys(&self) -> Vec<XOnlyPublicKey> {
+        self.inner.public_keys.clone()
+    }
+
     async fn fetch_node_info(&self, url: &str, encoding: Encoding, network_id: NetworkId) -> Result<NodeDescriptor> {
         let url = format!("{}/v{}/wrpc/{}/{}", url, DEFAULT_VERSION, encoding, network_id);
         let node =
@@ -74,6 +240,86 @@ impl Resolver {
         Ok(node)
     }
 
+    async fn fetch_node_list(&self, url: &str, encoding: Encoding, network_id: NetworkId) -> Result<Vec<NodeDescriptor>> {
+        let url = format!("{}/v{}/wrpc/{}/{}/list", url, DEFAULT_VERSION, encoding, network_id);
+        let signed =
+            get_json::<SignedNodeList>(&url).await.map_err(|error| Error::custom(format!("Unable to connect to {url}: {error}")))?;
+        let list = signed.verify(&self.inner.public_keys, encoding, network_id)?;
+        if list.nodes.is_empty() {
+            return Err(Error::custom(format!("Empty node list received from {url}")));
+        }
+        Ok(list.nodes)
+    }
+
+    /// Fetches the verified node list of the first resolver serving one, trying the resolvers in random order
+    pub async fn fetch_candidates(&self, encoding: Encoding, network_id: NetworkId) -> Result<Vec<NodeDescriptor>> {
+        let mut urls = self.inner.urls.clone();
+        urls.shuffle(&mut thread_rng());
+
+        let mut errors = Vec::default();
+        for url in urls {
+            match self.fetch_node_list(&url, encoding, network_id).await {
+                Ok(nodes) => return Ok(nodes),
+                Err(error) => {
+                    log_warn!("Resolver {url}: {error}");
+                    errors.push(error);
+                }
+            }
+        }
+        Err(Error::Custom(format!("Failed to fetch a node list: {:?}", errors)))
+    }
+
+    /// Connects to the node and measures the round-trip time of a `GetInfo` call
+    pub async fn probe(&self, node: NodeDescriptor, encoding: Encoding, network_id: NetworkId) -> NodeProbe {
+        let mut probe = NodeProbe { node, latency: None, is_synced: false, is_utxo_indexed: false };
+        let Ok(client) = KaspaRpcClient::new(encoding, Some(&probe.node.url), None, Some(network_id), None) else {
+            return probe;
+        };
+        let options = ConnectOptions {
+            block_async_connect: true,
+            strategy: ConnectStrategy::Fallback,
+            connect_timeout: Some(PROBE_TIMEOUT),
+            ..Default::default()
+        };
+        if client.connect(Some(options)).await.is_ok() {
+            let start = Instant::now();
+            select! {
+                info = client.get_info().fuse() => {
+                    if let Ok(info) = info {
+                        probe.latency = Some(start.elapsed());
+                        probe.is_synced = info.is_synced;
+                        probe.is_utxo_indexed = info.is_utxo_indexed;
+                    }
+                },
+                _ = sleep(PROBE_TIMEOUT).fuse() => {},
+            }
+            client.disconnect().await.ok();
+        }
+        probe
+    }
+
+    /// Resolves the candidate nodes for the given encoding and network, best first.
+    ///
+    /// With trusted public keys, up to [`PROBE_CANDIDATES`] nodes of a verified node
+    /// list are probed and ranked with [`rank_candidates`]. The node elected by a
+    /// resolver is returned otherwise.
+    pub async fn resolve(&self, encoding: Encoding, network_id: NetworkId) -> Result<Vec<NodeDescriptor>> {
+        if self.inner.public_keys.is_empty() {
+            return Ok(vec![self.fetch(encoding, network_id).await?]);
+        }
+
+        let mut candidates = self.fetch_candidates(encoding, network_id).await?;
+        candidates.shuffle(&mut thread_rng());
+        candidates.truncate(PROBE_CANDIDATES);
+        let probes = join_all(candidates.into_iter().map(|node| self.probe(node, encoding, network_id))).await;
+        let ranked = rank_candidates(probes);
+        if ranked.is_empty() {
+            Err(Error::custom(format!("No reachable and synced node found for {encoding} {network_id}")))
+        } else {
+            Ok(ranked)
+        }
+    }
+
     pub async fn fetch(&self, encoding: Encoding, network_id: NetworkId) -> Result<NodeDescriptor> {
         let mut urls = self.inner.urls.clone();
         urls.shuffle(&mut thread_rng());
@@ -110,11 +356,148 @@ impl Resolver {
     }
 
     pub async fn get_node(&self, encoding: Encoding, network_id: NetworkId) -> Result<NodeDescriptor> {
-        self.fetch(encoding, network_id).await
+        Ok(self.resolve(encoding, network_id).await?.remove(0))
     }
 
     pub async fn get_url(&self, encoding: Encoding, network_id: NetworkId) -> Result<String> {
-        let nodes = self.fetch(encoding, network_id).await?;
-        Ok(nodes.url.clone())
+        let node = self.get_node(encoding, network_id).await?;
+        Ok(node.url.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::network::NetworkType;
+    use secp256k1::rand::thread_rng as secp_rng;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn node(id: &str) -> NodeDescriptor {
+        NodeDescriptor { id: id.to_string(), url: format!("ws://{id}"), provider_name: None, provider_url: None }
+    }
+
+    fn node_list(encoding: Encoding, network_id: NetworkId, nodes: Vec<NodeDescriptor>) -> NodeList {
+        NodeList { encoding, network_id, timestamp: unixtime_as_millis_u64(), nodes }
+    }
+
+    fn probe(id: &str, latency: Option<u64>, is_synced: bool, is_utxo_indexed: bool) -> NodeProbe {
+        NodeProbe { node: node(id), latency: latency.map(Duration::from_millis), is_synced, is_utxo_indexed }
+    }
+
+    /// Serves `body` as a JSON response to any request, acting as a resolver
+    async fn mock_resolver(body: String) -> Arc<String> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = [0u8; 4096];
+                if stream.read(&mut request).await.unwrap_or(0) == 0 {
+                    continue;
+                }
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).await.ok();
+            }
+        });
+        Arc::new(url)
+    }
+
+    #[test]
+    fn test_signed_node_list() {
+        let (encoding, network_id) = (Encoding::Borsh, NetworkId::new(NetworkType::Mainnet));
+        let keypair = Keypair::new(SECP256K1, &mut secp_rng());
+        let public_key = keypair.x_only_public_key().0;
+        let other_key = Keypair::new(SECP256K1, &mut secp_rng()).x_only_public_key().0;
+
+        let list = node_list(encoding, network_id, vec![node("a"), node("b")]);
+        let signed = SignedNodeList::try_sign(&list, &keypair).unwrap();
+        assert_eq!(signed.verify(&[other_key, public_key], encoding, network_id).unwrap().nodes, list.nodes);
+
+        // A list signed by an untrusted key is rejected
+        assert!(matches!(signed.verify(&[other_key], encoding, network_id), Err(Error::InvalidNodeListSignature)));
+
+        // A tampered list is rejected
+        let mut tampered = signed.clone();
+        tampered.payload = tampered.payload.replace("ws://b", "ws://evil");
+        assert!(matches!(tampered.verify(&[public_key], encoding, network_id), Err(Error::InvalidNodeListSignature)));
+        let mut malformed = signed.clone();
+        malformed.signature = "00".to_string();
+        assert!(matches!(malformed.verify(&[public_key], encoding, network_id), Err(Error::InvalidNodeListSignature)));
+
+        // A genuine list for another encoding or network is rejected
+        assert!(signed.verify(&[public_key], Encoding::SerdeJson, network_id).is_err());
+        assert!(signed.verify(&[public_key], encoding, NetworkId::with_suffix(NetworkType::Testnet, 11)).is_err());
+
+        // An outdated list is rejected
+        let mut outdated = list.clone();
+        outdated.timestamp -= MAX_NODE_LIST_AGE.as_millis() as u64 + 1;
+        let signed = SignedNodeList::try_sign(&outdated, &keypair).unwrap();
+        assert!(signed.verify(&[public_key], encoding, network_id).is_err());
+    }
+
+    #[test]
+    fn test_rank_candidates() {
+        let probes = vec![
+            probe("slow", Some(300), true, true),
+            probe("unreachable", None, false, false),
+            probe("syncing", Some(10), false, true),
+            probe("fast", Some(20), true, true),
+            probe("unindexed", Some(5), true, false),
+            probe("average", Some(100), true, true),
+        ];
+        assert_eq!(rank_candidates(probes), vec![node("fast"), node("average"), node("slow")]);
+        assert!(rank_candidates(vec![probe("unreachable", None, false, false)]).is_empty());
+    }
+
+    #[test]
+    fn test_node_failover() {
+        let mut failover = NodeFailover::default();
+        assert_eq!(failover.next_node(), None);
+
+        failover.reset(vec![node("a"), node("b")]);
+        for _ in 0..NodeFailover::MAX_ATTEMPTS {
+            assert_eq!(failover.next_node(), Some(node("a")));
+        }
+        // A successful connection grants the node new attempts
+        failover.on_connected();
+        for _ in 0..NodeFailover::MAX_ATTEMPTS {
+            assert_eq!(failover.next_node(), Some(node("a")));
+        }
+        // Repeated failures fail over to the next candidate, then call for a new resolution
+        for _ in 0..NodeFailover::MAX_ATTEMPTS {
+            assert_eq!(failover.next_node(), Some(node("b")));
+        }
+        assert_eq!(failover.next_node(), None);
+    }
+
+    #[tokio::test]
+    async fn test_resolver_node_list() {
+        let (encoding, network_id) = (Encoding::Borsh, NetworkId::new(NetworkType::Mainnet));
+        let keypair = Keypair::new(SECP256K1, &mut secp_rng());
+        let spoofing_keypair = Keypair::new(SECP256K1, &mut secp_rng());
+        // Candidates of the genuine list are not running nodes, failing their probes
+        let genuine = node_list(encoding, network_id, vec![node("127.0.0.1:1"), node("127.0.0.1:2")]);
+        let spoofed = node_list(encoding, network_id, vec![node("evil")]);
+
+        let genuine_url = mock_resolver(serde_json::to_string(&SignedNodeList::try_sign(&genuine, &keypair).unwrap()).unwrap()).await;
+        let spoofed_url =
+            mock_resolver(serde_json::to_string(&SignedNodeList::try_sign(&spoofed, &spoofing_keypair).unwrap()).unwrap()).await;
+        let public_keys = vec![keypair.x_only_public_key().0];
+
+        // The spoofed list is rejected whatever the order the resolvers are tried in
+        let resolver = Resolver::new_with_public_keys(vec![spoofed_url.clone(), genuine_url.clone()], public_keys.clone());
+        for _ in 0..4 {
+            assert_eq!(resolver.fetch_candidates(encoding, network_id).await.unwrap(), genuine.nodes);
+        }
+
+        let resolver = Resolver::new_with_public_keys(vec![spoofed_url], public_keys.clone());
+        assert!(resolver.fetch_candidates(encoding, network_id).await.is_err());
+        assert!(resolver.resolve(encoding, network_id).await.is_err());
+
+        // Unreachable candidates are not selected
+        let resolver = Resolver::new_with_public_keys(vec![genuine_url], public_keys);
+        assert!(resolver.resolve(encoding, network_id).await.is_err());
     }
 }
@@ -23,6 +23,11 @@ pub struct Args {
     /// Enable resolver status access via `/status`
     #[arg(long, default_value = "false")]
     pub status: bool,
+
+    /// Path to a file containing the hex encoded secret key signing the node lists served via
+    /// `/v1/wrpc/:encoding/:network/list`. The route is disabled if not supplied.
+    #[arg(long = "key-file", value_name = "PATH")]
+    pub key_file: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -187,7 +187,7 @@ impl Connection {
                 self.is_synced.store(is_synced, Ordering::Relaxed);
 
                 if is_synced {
-                    match self.client.get_metrics(false, true, false, false).await {
+                    match self.client.get_metrics(false, true, false, false, false).await {
                         Ok(metrics) => {
                             if let Some(connection_metrics) = metrics.connection_metrics {
                                 // update
@@ -260,3 +260,15 @@ impl<'a> From<&'a Arc<Connection>> for Output<'a> {
         Self { id, url, provider_name, provider_url }
     }
 }
+
+impl From<&Arc<Connection>> for NodeDescriptor {
+    fn from(connection: &Arc<Connection>) -> Self {
+        let output = Output::from(connection);
+        Self {
+            id: output.id.to_string(),
+            url: output.url.to_string(),
+            provider_name: output.provider_name.map(String::from),
+            provider_url: output.provider_url.map(String::from),
+        }
+    }
+}
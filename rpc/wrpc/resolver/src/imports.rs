@@ -14,8 +14,11 @@ pub use kaspa_rpc_core::api::rpc::RpcApi;
 pub use kaspa_utils::hashmap::GroupExtension;
 pub use kaspa_wrpc_client::{
     client::{ConnectOptions, ConnectStrategy},
+    node::NodeDescriptor,
+    resolver::{NodeList, SignedNodeList},
     KaspaRpcClient, WrpcEncoding,
 };
+pub use secp256k1::Keypair;
 pub use serde::{de::DeserializeOwned, Deserialize, Serialize};
 pub use std::collections::HashMap;
 pub use std::fmt;
@@ -26,3 +29,4 @@ pub use std::sync::{Arc, Mutex, OnceLock, RwLock};
 pub use std::time::Duration;
 pub use workflow_core::channel::*;
 pub use workflow_core::task::spawn;
+pub use workflow_core::time::unixtime_as_millis_u64;
@@ -208,6 +208,25 @@ impl Monitor {
     pub fn get_json(&self, params: &PathParams) -> Option<String> {
         self.descriptors.read().unwrap().get(params).cloned().map(|descriptor| descriptor.json)
     }
+
+    /// Get the list of online nodes, by ascending score, as a JSON string signed with `keypair`
+    /// (available via `/v1/wrpc/:encoding/:network/list` if a key file is supplied).
+    pub fn get_signed_node_list_json(&self, params: &PathParams, keypair: &Keypair) -> Result<Option<String>> {
+        let mut connections = self
+            .connections()
+            .get(params)
+            .map(|connections| connections.iter().filter(|connection| connection.online()).cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+        if connections.is_empty() {
+            return Ok(None);
+        }
+        connections.sort_by_key(|connection| connection.score());
+
+        let nodes = connections.iter().map(NodeDescriptor::from).collect();
+        let list = NodeList { encoding: params.encoding, network_id: params.network, timestamp: unixtime_as_millis_u64(), nodes };
+        let signed = SignedNodeList::try_sign(&list, keypair)?;
+        Ok(Some(serde_json::to_string(&signed)?))
+    }
 }
 
 #[derive(Serialize)]
@@ -22,6 +22,17 @@ pub async fn server(args: &Args) -> Result<(TcpListener, Router)> {
 
     let app = Router::new().route("/v1/wrpc/:encoding/:network", get(get_elected_node));
 
+    let app = if let Some(key_file) = args.key_file.as_ref() {
+        let secret_key = std::fs::read_to_string(key_file)?;
+        let keypair = Keypair::from_seckey_str(secp256k1::SECP256K1, secret_key.trim())
+            .map_err(|err| Error::custom(format!("Invalid secret key in {key_file}: {err}")))?;
+        log_success!("Routes", "Enabling `/v1/wrpc/:encoding/:network/list` route, public key: {}", keypair.x_only_public_key().0);
+        app.route("/v1/wrpc/:encoding/:network/list", get(move |path: Path<PathParams>| get_signed_node_list(path, keypair)))
+    } else {
+        log_warn!("Routes", "Disabling `/v1/wrpc/:encoding/:network/list` route, no key file supplied");
+        app
+    };
+
     let app = if args.status {
         log_warn!("Routes", "Enabling `/status` route");
         app.route("/status", get(get_status_all_nodes))
@@ -75,6 +86,25 @@ async fn get_elected_node(Query(_query): Query<QueryParams>, Path(params): Path<
     }
 }
 
+// respond with a JSON object containing the signed list of online nodes
+async fn get_signed_node_list(Path(params): Path<PathParams>, keypair: Keypair) -> impl IntoResponse {
+    match monitor().get_signed_node_list_json(&params, &keypair) {
+        Ok(Some(json)) => ([(header::CONTENT_TYPE, HeaderValue::from_static(mime::APPLICATION_JSON.as_ref()))], json).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            [(header::CONTENT_TYPE, HeaderValue::from_static(mime::TEXT_PLAIN_UTF_8.as_ref()))],
+            "NOT FOUND".to_string(),
+        )
+            .into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            [(header::CONTENT_TYPE, HeaderValue::from_static(mime::TEXT_PLAIN_UTF_8.as_ref()))],
+            err.to_string(),
+        )
+            .into_response(),
+    }
+}
+
 // We define our own `Path` extractor that customizes the error from `axum::extract::Path`
 struct Path<T>(T);
 
@@ -6,7 +6,8 @@ use kaspa_notify::{
     notification::Notification as NotificationT,
     notifier::Notify,
 };
-use kaspa_rpc_core::{api::ops::RpcApiOps, notify::mode::NotificationMode, Notification};
+use kaspa_rpc_core::{api::ops::RpcApiOps, notify::mode::NotificationMode, Notification, SubmitTransactionRequest};
+use kaspa_utils::rate_limit::SlidingWindowCounter;
 use std::{
     fmt::{Debug, Display},
     sync::{Arc, Mutex},
@@ -50,6 +51,7 @@ struct ConnectionInner {
     pub grpc_client: Option<Arc<GrpcClient>>,
     // not using an atomic in case an Id will change type in the future...
     pub listener_id: Mutex<Option<ListenerId>>,
+    pub rejection_trace_limiter: SlidingWindowCounter,
 }
 
 impl ConnectionInner {
@@ -87,7 +89,13 @@ impl Connection {
         assert!(grpc_client.is_none() || grpc_client.as_ref().unwrap().notification_mode() == NotificationMode::Direct);
         // Should a gRPC client be provided, no listener_id is required for subscriptions so the listener id is set to default
         let listener_id = Mutex::new(grpc_client.clone().map(|_| ListenerId::default()));
-        Connection { inner: Arc::new(ConnectionInner { id, peer: *peer, messenger, grpc_client, listener_id }) }
+        let rejection_trace_limiter = SlidingWindowCounter::new(
+            SubmitTransactionRequest::MAX_REJECTION_TRACES_PER_WINDOW,
+            SubmitTransactionRequest::REJECTION_TRACE_WINDOW,
+        );
+        Connection {
+            inner: Arc::new(ConnectionInner { id, peer: *peer, messenger, grpc_client, listener_id, rejection_trace_limiter }),
+        }
     }
 
     /// Obtain the connection id
@@ -120,6 +128,11 @@ impl Connection {
         self.inner.listener_id.lock().unwrap().replace(listener_id);
     }
 
+    /// Returns `true` if a verbose transaction submission can be processed as such
+    pub fn try_acquire_rejection_trace(&self) -> bool {
+        self.inner.rejection_trace_limiter.try_acquire()
+    }
+
     pub fn peer(&self) -> &SocketAddr {
         &self.inner.peer
     }
@@ -1,4 +1,5 @@
 use crate::{connection::*, server::*};
+use kaspa_core::log::trace_id::{self, TraceId};
 use kaspa_notify::scope::Scope;
 use kaspa_rpc_core::{api::ops::RpcApiOps, prelude::*};
 use kaspa_rpc_macros::build_wrpc_server_interface;
@@ -45,6 +46,21 @@ impl Router {
                 GetCoinSupply,
                 GetConnectedPeerInfo,
                 GetDaaScoreTimestampEstimate,
+                GetChainBlockByDaaScore,
+                SetLogFilters,
+                GetOutpointSpendStatus,
+                EstimateTransactionConfirmationTime,
+                GetAcceptanceProof,
+                CreateBackup,
+                GetVirtualParentSelectionTrace,
+                GetSystemInfo,
+                RemovePeer,
+                GetVirtualInfo,
+                SetProcessingParallelism,
+                GetAddressActivity,
+                GetMissedNotifications,
+                GetUtxoReturnAddress,
+                ValidateBlockTemplate,
                 GetServerInfo,
                 GetCurrentNetwork,
                 GetHeaders,
@@ -65,11 +81,40 @@ impl Router {
                 ResolveFinalityConflict,
                 Shutdown,
                 SubmitBlock,
-                SubmitTransaction,
                 Unban,
             ]
         );
 
+        // Verbose transaction submissions are rate limited per connection
+        interface.method(
+            RpcApiOps::SubmitTransaction,
+            workflow_rpc::server::Method::new(
+                move |server_ctx: Server, connection: Connection, mut request: SubmitTransactionRequest| {
+                    Box::pin(async move {
+                        let verbose = server_ctx.verbose();
+                        if verbose {
+                            workflow_log::log_info!("request: {:?}", request);
+                        }
+                        if request.verbose && !connection.try_acquire_rejection_trace() {
+                            workflow_log::log_trace!("wRPC server -> verbose SubmitTransaction rate limit reached for {connection}");
+                            request.verbose = false;
+                        }
+                        let trace_id = TraceId::generate();
+                        let rpc_service = server_ctx.rpc_service(&connection);
+                        let call = rpc_service.submit_transaction_call(request);
+                        let response: SubmitTransactionResponse = trace_id::timed_scope("SubmitTransaction", trace_id.clone(), call)
+                            .await
+                            .map_err(|e| ServerError::Text(e.with_trace_id(trace_id.as_str()).to_string()))?;
+                        server_ctx.check_response_size(&response).map_err(|e| ServerError::Text(e.to_string()))?;
+                        if verbose {
+                            workflow_log::log_info!("response: {:?}", response);
+                        }
+                        Ok(response)
+                    })
+                },
+            ),
+        );
+
         interface.method(
             RpcApiOps::Subscribe,
             workflow_rpc::server::Method::new(move |manager: Server, connection: Connection, scope: Scope| {
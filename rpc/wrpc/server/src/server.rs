@@ -4,6 +4,7 @@ use crate::{
     result::Result,
     service::Options,
 };
+use borsh::BorshSerialize;
 use kaspa_grpc_client::GrpcClient;
 use kaspa_notify::{
     connection::ChannelType,
@@ -17,11 +18,13 @@ use kaspa_notify::{
 use kaspa_rpc_core::{
     api::rpc::{DynRpcService, RpcApi},
     notify::{channel::NotificationChannel, connection::ChannelConnection, mode::NotificationMode},
-    Notification, RpcResult,
+    Notification, RpcError, RpcResult,
 };
 use kaspa_rpc_service::service::RpcCoreService;
+use serde::Serialize;
 use std::{
     collections::HashMap,
+    io,
     sync::{
         atomic::{AtomicU64, Ordering},
         Arc, Mutex,
@@ -39,7 +42,7 @@ struct RpcCore {
 
 struct ServerInner {
     pub next_connection_id: AtomicU64,
-    pub _encoding: Encoding,
+    pub encoding: Encoding,
     pub sockets: Mutex<HashMap<u64, Connection>>,
     pub rpc_core: Option<RpcCore>,
     pub options: Arc<Options>,
@@ -94,7 +97,7 @@ impl Server {
         Server {
             inner: Arc::new(ServerInner {
                 next_connection_id: AtomicU64::new(0),
-                _encoding: encoding,
+                encoding,
                 sockets: Mutex::new(HashMap::new()),
                 rpc_core,
                 options,
@@ -191,8 +194,10 @@ impl Server {
         };
         workflow_log::log_trace!("notification subscribe[0x{listener_id:x}] {scope:?}");
         if let Some(rpc_core) = &self.inner.rpc_core {
+            rpc_core.service.check_start_notify(&scope)?;
             rpc_core.wrpc_notifier.clone().try_start_notify(listener_id, scope)?;
         } else {
+            // The gRPC server of the node checks the permission
             connection.grpc_client().start_notify(listener_id, scope).await?;
         }
         Ok(())
@@ -216,6 +221,23 @@ impl Server {
         self.inner.options.verbose
     }
 
+    /// Checks that the serialized `response` fits within the configured maximum outbound message size
+    pub fn check_response_size<T: BorshSerialize + Serialize>(&self, response: &T) -> RpcResult<()> {
+        let Some(max_size) = self.inner.options.max_outbound_message_size else {
+            return Ok(());
+        };
+        // Measure the serialized size without allocating the serialized response
+        let mut counter = ByteCounter::default();
+        match self.inner.encoding {
+            Encoding::Borsh => response.serialize(&mut counter).map_err(|err| RpcError::General(err.to_string()))?,
+            Encoding::SerdeJson => serde_json::to_writer(&mut counter, response).map_err(|err| RpcError::General(err.to_string()))?,
+        }
+        if counter.0 > max_size {
+            return Err(RpcError::ResponseSizeLimitExceeded(counter.0, max_size));
+        }
+        Ok(())
+    }
+
     pub async fn join(&self) -> Result<()> {
         if let Some(rpc_core) = &self.inner.rpc_core {
             // Wait for the internal notifier to stop
@@ -227,3 +249,18 @@ impl Server {
         Ok(())
     }
 }
+
+/// A writer counting the bytes written into it
+#[derive(Default)]
+struct ByteCounter(usize);
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
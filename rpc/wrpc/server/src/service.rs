@@ -13,18 +13,30 @@ use tokio::sync::oneshot::{channel as oneshot_channel, Sender as OneshotSender};
 use workflow_rpc::server::prelude::*;
 pub use workflow_rpc::server::{Encoding as WrpcEncoding, WebSocketConfig, WebSocketCounters};
 
-static MAX_WRPC_MESSAGE_SIZE: usize = 1024 * 1024 * 128; // 128MB
+/// Default maximum size of an inbound message (and frame)
+pub const DEFAULT_MAX_WRPC_MESSAGE_SIZE: usize = 1024 * 1024 * 128; // 128MB
 
 /// Options for configuring the wRPC server
 pub struct Options {
     pub listen_address: String,
     pub grpc_proxy_address: Option<String>,
     pub verbose: bool,
+    /// Maximum size in bytes of an inbound message. A client sending a larger message or frame gets disconnected
+    pub max_inbound_message_size: usize,
+    /// Maximum size in bytes of an outbound response. A request whose response would exceed it fails with
+    /// [`RpcError::ResponseSizeLimitExceeded`](kaspa_rpc_core::RpcError::ResponseSizeLimitExceeded). Unlimited if `None`
+    pub max_outbound_message_size: Option<usize>,
 }
 
 impl Default for Options {
     fn default() -> Self {
-        Options { listen_address: "127.0.0.1:17110".to_owned(), verbose: false, grpc_proxy_address: None }
+        Options {
+            listen_address: "127.0.0.1:17110".to_owned(),
+            verbose: false,
+            grpc_proxy_address: None,
+            max_inbound_message_size: DEFAULT_MAX_WRPC_MESSAGE_SIZE,
+            max_outbound_message_size: None,
+        }
     }
 }
 
@@ -132,6 +144,7 @@ impl WrpcService {
     pub fn serve(self: Arc<Self>) -> OneshotSender<()> {
         let (termination_sender, termination_receiver) = oneshot_channel::<()>();
         let listen_address = self.options.listen_address.clone();
+        let max_inbound_message_size = self.options.max_inbound_message_size;
         self.rpc_handler.server.start();
 
         // Spawn a task stopping the server on termination signal
@@ -145,7 +158,11 @@ impl WrpcService {
         // Spawn a task running the server
         info!("WRPC Server starting on: {}", listen_address);
         tokio::spawn(async move {
-            let config = WebSocketConfig { max_message_size: Some(MAX_WRPC_MESSAGE_SIZE), ..Default::default() };
+            let config = WebSocketConfig {
+                max_message_size: Some(max_inbound_message_size),
+                max_frame_size: Some(max_inbound_message_size),
+                ..Default::default()
+            };
             let serve_result = self.server.listen(&listen_address, Some(config)).await;
             match serve_result {
                 Ok(_) => info!("WRPC Server stopped on: {}", listen_address),
@@ -704,12 +704,15 @@ impl RpcClient {
                                     let notification_event = NotificationEvent::Notification(event_type);
                                     if let Some(handlers) = this.inner.notification_callbacks(notification_event) {
 
-                                        let UtxosChangedNotification { added, removed } = utxos_changed_notification;
+                                        let UtxosChangedNotification { added, removed, sequence } = utxos_changed_notification;
                                         let added = js_sys::Array::from_iter(added.iter().map(UtxoEntryReference::from).map(JsValue::from));
                                         let removed = js_sys::Array::from_iter(removed.iter().map(UtxoEntryReference::from).map(JsValue::from));
                                         let notification = Object::new();
                                         notification.set("added", &added).unwrap();
                                         notification.set("removed", &removed).unwrap();
+                                        if let Some(sequence) = sequence {
+                                            notification.set("sequence", &JsValue::from(*sequence)).unwrap();
+                                        }
 
                                         for handler in handlers.into_iter() {
                                             let event = Object::new();
@@ -820,7 +823,9 @@ impl RpcClient {
     /// UTXOs changed notification event is produced when the set
     /// of unspent transaction outputs (UTXOs) changes in the
     /// Kaspa BlockDAG. The event notification will be scoped to the
-    /// provided list of addresses.
+    /// provided list of addresses. An empty list subscribes to the
+    /// changes of all addresses, which requires the node to run
+    /// with `--unsaferpc`.
     #[wasm_bindgen(js_name = subscribeUtxosChanged)]
     pub async fn subscribe_utxos_changed(&self, addresses: AddressOrStringArrayT) -> Result<()> {
         if let Some(listener_id) = self.listener_id() {
@@ -846,6 +851,36 @@ impl RpcClient {
         Ok(())
     }
 
+    /// Subscribe for a transaction conflict notification event.
+    /// Transaction conflict notification event is produced when the
+    /// mempool detects two transactions spending some same outpoints.
+    /// The event notification will be scoped to the provided list of
+    /// addresses. An empty list subscribes to the conflicts of all
+    /// addresses.
+    #[wasm_bindgen(js_name = subscribeTransactionConflict)]
+    pub async fn subscribe_transaction_conflict(&self, addresses: AddressOrStringArrayT) -> Result<()> {
+        if let Some(listener_id) = self.listener_id() {
+            let addresses: Vec<Address> = addresses.try_into()?;
+            self.inner.client.start_notify(listener_id, Scope::TransactionConflict(TransactionConflictScope { addresses })).await?;
+        } else {
+            log_error!("RPC subscribe on a closed connection");
+        }
+        Ok(())
+    }
+
+    /// Unsubscribe from transaction conflict notification event
+    /// for a specific set of addresses.
+    #[wasm_bindgen(js_name = unsubscribeTransactionConflict)]
+    pub async fn unsubscribe_transaction_conflict(&self, addresses: AddressOrStringArrayT) -> Result<()> {
+        if let Some(listener_id) = self.listener_id() {
+            let addresses: Vec<Address> = addresses.try_into()?;
+            self.inner.client.stop_notify(listener_id, Scope::TransactionConflict(TransactionConflictScope { addresses })).await?;
+        } else {
+            log_error!("RPC unsubscribe on a closed connection");
+        }
+        Ok(())
+    }
+
     // TODO: scope variant with field functions
 
     /// Manage subscription for a virtual chain changed notification event.
@@ -886,6 +921,7 @@ build_wrpc_wasm_bindgen_subscriptions!([
     // Manually implemented subscriptions (above)
     // - VirtualChainChanged, // can't used this here due to non-C-style enum variant
     // - UtxosChanged, // can't used this here due to non-C-style enum variant
+    // - TransactionConflict, // can't used this here due to non-C-style enum variant
     // - VirtualDaaScoreChanged,
     /// Manage subscription for a block added notification event.
     /// Block added notification event is produced when a new
@@ -966,6 +1002,11 @@ build_wrpc_wasm_bindgen_interface!(
         /// leading up to that block.
         /// Returned information: Blue score of the sink block.
         GetSinkBlueScore,
+        /// Retrieves the virtual state commonly tracked by wallets at once,
+        /// read from the cached virtual state of the node.
+        /// Returned information: Virtual DAA score, sink hash, sink blue
+        /// score, virtual parents count, past median time.
+        GetVirtualInfo,
         /// Tests the connection and responsiveness of a Kaspa node.
         /// Returned information: None.
         Ping,
@@ -985,6 +1026,37 @@ build_wrpc_wasm_bindgen_interface!(
         /// Adds a peer to the Kaspa node's list of known peers.
         /// Returned information: None.
         AddPeer,
+        /// Removes a peer added by {@link RpcClient.addPeer}, whether permanently or not,
+        /// and disconnects from it.
+        /// Returned information: None.
+        RemovePeer,
+        /// Sets the maximum number of blocks validated concurrently by the node
+        /// (requires the node to run with unsafe RPC enabled).
+        /// Returned information: The previous limit.
+        SetProcessingParallelism,
+        /// Checks whether addresses were ever used, including addresses whose
+        /// funds were fully spent (requires the node to run with `--utxoindex`).
+        /// Returned information: For each address, whether it received funds and
+        /// the DAA scores of its first and latest activity.
+        GetAddressActivity,
+        /// Gets the VirtualChainChanged or UtxosChanged notifications emitted
+        /// since a sequence number, backfilling the notifications missed by a
+        /// client (requires the node to run with `--notification-journal-size`).
+        /// Returned information: The matching notifications and the last sequence
+        /// of the scope.
+        GetMissedNotifications,
+        /// Gets the UTXO entry spent by an input of a transaction accepted by a
+        /// chain block, e.g. for refunding its sender (requires the node to run
+        /// with `--store-spent-utxos`).
+        /// Returned information: The spent UTXO entry and the address it was
+        /// locked to, if standard.
+        GetUtxoReturnAddress,
+        /// Validates a block template against the current virtual state without
+        /// submitting it, skipping the proof of work check (requires the node to
+        /// run with `--unsaferpc`).
+        /// Returned information: The mass and total fees of a valid block, or the
+        /// rule violated by an invalid one.
+        ValidateBlockTemplate,
         /// Bans a peer from connecting to the Kaspa node for a specified duration.
         /// Returned information: None.
         Ban,
@@ -1010,6 +1082,38 @@ build_wrpc_wasm_bindgen_interface!(
         /// score timestamp estimate.
         /// Returned information: DAA score timestamp estimate.
         GetDaaScoreTimestampEstimate,
+        /// Retrieves the selected chain block whose DAA score is nearest
+        /// to the given DAA score.
+        /// Returned information: Chain block hash, DAA score and timestamp.
+        GetChainBlockByDaaScore,
+        /// Adjusts the log filters of the node at runtime
+        /// (requires the node to run with unsafe RPC enabled).
+        /// Returned information: The log filters in effect.
+        SetLogFilters,
+        /// Retrieves the spend status of an outpoint from the UTXO set,
+        /// the mempool and the recent selected chain blocks.
+        /// Returned information: UTXO entry, mempool, accepted and conflicting spenders.
+        GetOutpointSpendStatus,
+        /// Estimates the confirmation time range of a mempool transaction from its
+        /// position in package-feerate order and the recent network conditions.
+        /// Returned information: Confirmation time estimate.
+        EstimateTransactionConfirmationTime,
+        /// Retrieves a merkle proof of the acceptance of a transaction by a selected
+        /// chain block, verifiable against the block header with `verifyAcceptanceProof`.
+        /// Returned information: Accepted id merkle root, leaf index and authentication path.
+        GetAcceptanceProof,
+        /// Requests the node to back up its consensus databases in a directory of the
+        /// node host, without stopping block processing. Requires `--unsaferpc`.
+        /// Returned information: Manifest of the backup.
+        CreateBackup,
+        /// Traces the virtual parent selection over the current tips, giving the
+        /// reason of exclusion of each tip which is not a virtual parent. Requires `--unsaferpc`.
+        /// Returned information: Tips, selected parent, virtual parents and excluded tips.
+        GetVirtualParentSelectionTrace,
+        /// Retrieves information about the node host, including the results of the
+        /// startup preflight checks (file descriptor limit, free disk space and clock offset).
+        /// Returned information: Node version, file descriptor limit and preflight checks.
+        GetSystemInfo,
         /// Retrieves the current network configuration.
         /// Returned information: Current network configuration.
         GetCurrentNetwork,
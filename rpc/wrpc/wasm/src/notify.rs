@@ -21,6 +21,7 @@ export enum RpcEventType {
     VirtualDaaScoreChanged = "virtual-daa-score-changed",
     PruningPointUtxoSetOverride = "pruning-point-utxo-set-override",
     NewBlockTemplate = "new-block-template",
+    TransactionConflict = "transaction-conflict",
 }
 
 /**
@@ -36,7 +37,8 @@ export type RpcEventData = IBlockAdded
     | ISinkBlueScoreChanged 
     | IVirtualDaaScoreChanged 
     | IPruningPointUtxoSetOverride 
-    | INewBlockTemplate;
+    | INewBlockTemplate 
+    | ITransactionConflict;
 
 /**
  * RPC notification event data map.
@@ -55,6 +57,7 @@ export type RpcEventMap = {
     "virtual-daa-score-changed" : IVirtualDaaScoreChanged,
     "pruning-point-utxo-set-override" : IPruningPointUtxoSetOverride,
     "new-block-template" : INewBlockTemplate,
+    "transaction-conflict" : ITransactionConflict,
 }
 
 /**
@@ -80,6 +83,7 @@ export type RpcEvent = {
  * {@link RpcClient.subscribeSinkBlueScoreChanged},
  * {@link RpcClient.subscribePruningPointUtxoSetOverride},
  * {@link RpcClient.subscribeNewBlockTemplate},
+ * {@link RpcClient.subscribeTransactionConflict},
  * 
  * @category Node RPC
  */
@@ -237,3 +241,23 @@ declare! {
     }
     "#,
 }
+
+declare! {
+    ITransactionConflict,
+    r#"
+    /**
+     * Transaction conflict notification event is produced when the mempool
+     * detects two transactions spending some same outpoints, the losing
+     * transaction being rejected or removed from the mempool.
+     * 
+     * @category Node RPC
+     */
+    export interface ITransactionConflict {
+        transactionId : HexString;
+        conflictingTransactionId : HexString;
+        outpoints : ITransactionOutpoint[];
+        isConflictingInBlock : boolean;
+        addresses : Address[];
+    }
+    "#,
+}
@@ -3,6 +3,7 @@ use crate::imports::*;
 use js_sys::Array;
 pub use kaspa_rpc_macros::declare_typescript_wasm_interface as declare;
 use kaspa_wrpc_client::node::NodeDescriptor;
+use kaspa_wrpc_client::resolver::parse_public_key;
 use kaspa_wrpc_client::Resolver as NativeResolver;
 use serde::ser;
 use workflow_wasm::extensions::ObjectExtension;
@@ -21,6 +22,13 @@ declare! {
          * Optional URLs for one or multiple resolvers.
          */
         urls?: string[];
+        /**
+         * Optional hex encoded public keys trusted for signing the node
+         * lists of the resolvers. When supplied, a node is selected by
+         * latency from the signed node list of a resolver and lists not
+         * signed by one of the keys are rejected.
+         */
+        publicKeys?: string[];
     }
     "#,
 }
@@ -77,8 +85,13 @@ extern "C" {
 /// wRPC endpoints for the given encoding, network identifier and other
 /// parameters. It then provides this information to the {@link RpcClient}.
 ///
-/// Each time {@link RpcClient} disconnects, it will query the resolver
-/// to fetch a new wRPC URL.
+/// When {@link RpcClient} repeatedly fails to connect to a node, it fails
+/// over to the next candidate of the last resolution and queries the resolver
+/// again once all candidates failed.
+///
+/// If public keys are supplied, the resolver fetches a signed list of nodes,
+/// probes a subset of them and selects the synced node with the lowest latency.
+/// Node lists not signed by one of the keys are rejected.
 ///
 /// ```javascript
 /// // using integrated public URLs
@@ -92,6 +105,12 @@ extern "C" {
 ///     resolver: new Resolver({urls: ["<resolver-url>",...]}),
 ///     networkId : "mainnet"
 /// });
+///
+/// // accepting only node lists signed by a resolver key
+/// let rpc = RpcClient({
+///     resolver: new Resolver({publicKeys: ["<public-key>",...]}),
+///     networkId : "mainnet"
+/// });
 /// ```
 ///
 /// @see {@link IResolverConfig}, {@link IResolverConnect}, {@link RpcClient}
@@ -134,6 +153,12 @@ impl Resolver {
         Array::from_iter(self.resolver.urls().iter().map(|v| JsValue::from(v.as_str()))).unchecked_into()
     }
 
+    /// Public keys trusted for signing the node lists of the resolvers.
+    #[wasm_bindgen(getter, js_name = publicKeys)]
+    pub fn public_keys(&self) -> ResolverArrayT {
+        Array::from_iter(self.resolver.public_keys().iter().map(|v| JsValue::from(v.to_string()))).unchecked_into()
+    }
+
     /// Fetches a public Kaspa wRPC endpoint for the given encoding and network identifier.
     /// @see {@link Encoding}, {@link NetworkId}, {@link Node}
     #[wasm_bindgen(js_name = getNode)]
@@ -163,14 +188,31 @@ impl Resolver {
 impl TryFrom<IResolverConfig> for NativeResolver {
     type Error = Error;
     fn try_from(config: IResolverConfig) -> Result<Self> {
-        let resolver = config
-            .get_vec("urls")
-            .map(|urls| urls.into_iter().map(|v| v.as_string()).collect::<Option<Vec<_>>>())
-            .or_else(|_| config.dyn_into::<Array>().map(|urls| urls.into_iter().map(|v| v.as_string()).collect::<Option<Vec<_>>>()))
-            .map_err(|_| Error::custom("Invalid or missing resolver URL"))?
-            .map(|urls| NativeResolver::new(urls.into_iter().map(Arc::new).collect()));
-
-        Ok(resolver.unwrap_or_default())
+        let public_keys = config
+            .get_vec("publicKeys")
+            .ok()
+            .map(|keys| {
+                keys.into_iter()
+                    .map(|key| parse_public_key(&key.as_string().ok_or_else(|| Error::custom("Invalid resolver public key"))?))
+                    .collect::<Result<Vec<_>>>()
+            })
+            .transpose()?;
+
+        let urls =
+            config.get_vec("urls").map(|urls| urls.into_iter().map(|v| v.as_string()).collect::<Option<Vec<_>>>()).or_else(|_| {
+                config.dyn_ref::<Array>().map(|urls| urls.iter().map(|v| v.as_string()).collect::<Option<Vec<_>>>()).ok_or(())
+            });
+
+        let resolver = match (urls, public_keys) {
+            (Err(_), None) => return Err(Error::custom("Invalid or missing resolver URL")),
+            (Ok(Some(urls)), public_keys) => {
+                NativeResolver::new_with_public_keys(urls.into_iter().map(Arc::new).collect(), public_keys.unwrap_or_default())
+            }
+            (_, Some(public_keys)) => NativeResolver::new_with_public_keys(NativeResolver::default().urls(), public_keys),
+            (Ok(None), None) => NativeResolver::default(),
+        };
+
+        Ok(resolver)
     }
 }
 
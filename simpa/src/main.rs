@@ -220,6 +220,8 @@ fn main_impl(mut args: Args) {
             notification_root,
             Default::default(),
             Default::default(),
+            Default::default(),
+            Default::default(),
             unix_now(),
         ));
         (consensus, lifetime)
@@ -256,6 +258,8 @@ fn main_impl(mut args: Args) {
         notification_root,
         Default::default(),
         Default::default(),
+        Default::default(),
+        Default::default(),
         unix_now(),
     ));
     let handles2 = consensus2.run_processors();
@@ -108,12 +108,7 @@ impl Miner {
             target_txs_per_block,
             target_blocks,
             max_cached_outpoints: 10_000,
-            mass_calculator: MassCalculator::new(
-                params.mass_per_tx_byte,
-                params.mass_per_script_pub_key_byte,
-                params.mass_per_sig_op,
-                params.storage_mass_parameter,
-            ),
+            mass_calculator: MassCalculator::new(params),
         }
     }
 
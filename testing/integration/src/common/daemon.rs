@@ -7,7 +7,10 @@ use kaspa_notify::subscription::context::SubscriptionContext;
 use kaspa_rpc_core::notify::mode::NotificationMode;
 use kaspa_rpc_service::service::RpcCoreService;
 use kaspa_utils::triggers::Listener;
-use kaspad_lib::{args::Args, daemon::create_core_with_runtime};
+use kaspad_lib::{
+    args::Args,
+    daemon::{create_core_with_runtime, Runtime},
+};
 use parking_lot::RwLock;
 use std::{ops::Deref, sync::Arc, time::Duration};
 use tempfile::TempDir;
@@ -137,13 +140,20 @@ impl Daemon {
     pub fn with_manager(client_manager: Arc<ClientManager>, fd_total_budget: i32) -> Daemon {
         let appdir_tempdir = get_kaspa_tempdir();
         client_manager.args.write().appdir = Some(appdir_tempdir.path().to_str().unwrap().to_owned());
-        let (core, _) = create_core_with_runtime(&Default::default(), &client_manager.args.read(), fd_total_budget);
+        let (core, grpc_server_started, shutdown_requested) = Self::create_core(&client_manager, fd_total_budget);
+        Daemon { client_manager, core, grpc_server_started, shutdown_requested, workers: None, _appdir_tempdir: appdir_tempdir }
+    }
+
+    fn create_core(client_manager: &ClientManager, fd_total_budget: i32) -> (Arc<Core>, Listener, Listener) {
+        // Namespaced by the RPC port, identifying the services of each of the daemons running in the test process
+        let runtime = Runtime::default().namespaced(format!("daemon-{}", client_manager.rpc_port));
+        let (core, _) = create_core_with_runtime(&runtime, &client_manager.args.read(), fd_total_budget);
         let async_service = &Arc::downcast::<AsyncRuntime>(core.find(AsyncRuntime::IDENT).unwrap().arc_any()).unwrap();
         let rpc_core_service = &Arc::downcast::<RpcCoreService>(async_service.find(RpcCoreService::IDENT).unwrap().arc_any()).unwrap();
         let shutdown_requested = rpc_core_service.core_shutdown_request_listener();
         let grpc_server = &Arc::downcast::<GrpcService>(async_service.find(GrpcService::IDENT).unwrap().arc_any()).unwrap();
         let grpc_server_started = grpc_server.started();
-        Daemon { client_manager, core, grpc_server_started, shutdown_requested, workers: None, _appdir_tempdir: appdir_tempdir }
+        (core, grpc_server_started, shutdown_requested)
     }
 
     pub fn client_manager(&self) -> Arc<ClientManager> {
@@ -179,6 +189,13 @@ impl Daemon {
         self.core.shutdown();
         self.join();
     }
+
+    /// Shuts the node down and starts it again with the same app dir and ports, returning a client of the restarted node
+    pub async fn restart(&mut self, fd_total_budget: i32) -> GrpcClient {
+        self.shutdown();
+        (self.core, self.grpc_server_started, self.shutdown_requested) = Self::create_core(&self.client_manager, fd_total_budget);
+        self.start().await
+    }
 }
 
 impl Deref for Daemon {
@@ -208,8 +208,9 @@ pub async fn mine_block(pay_address: Address, submitting_client: &GrpcClient, li
             .unwrap()
             .unwrap()
         {
-            Notification::VirtualDaaScoreChanged(VirtualDaaScoreChangedNotification { virtual_daa_score }) => {
+            Notification::VirtualDaaScoreChanged(VirtualDaaScoreChangedNotification { virtual_daa_score, virtual_info }) => {
                 assert_eq!(virtual_daa_score, block_daa_score + 1);
+                assert_eq!(virtual_info.expect("the node populates the virtual info").daa_score, virtual_daa_score);
             }
             _ => panic!("wrong notification type"),
         }
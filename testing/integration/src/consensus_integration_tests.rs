@@ -8,36 +8,51 @@ use kaspa_consensus::config::genesis::GENESIS;
 use kaspa_consensus::config::{Config, ConfigBuilder};
 use kaspa_consensus::consensus::factory::Factory as ConsensusFactory;
 use kaspa_consensus::consensus::test_consensus::{TestConsensus, TestConsensusFactory};
+use kaspa_consensus::header_verifier::{HeaderChainVerifier, HeaderParentDataProvider};
+use kaspa_consensus::model::services::reachability::ReachabilityService;
 use kaspa_consensus::model::stores::block_transactions::{
     BlockTransactionsStore, BlockTransactionsStoreReader, DbBlockTransactionsStore,
 };
+use kaspa_consensus::model::stores::block_window_cache::{BlockWindowCacheStore, BlockWindowHeap};
 use kaspa_consensus::model::stores::ghostdag::{GhostdagStoreReader, KType as GhostdagKType};
 use kaspa_consensus::model::stores::headers::HeaderStoreReader;
 use kaspa_consensus::model::stores::reachability::DbReachabilityStore;
 use kaspa_consensus::model::stores::relations::DbRelationsStore;
 use kaspa_consensus::model::stores::selected_chain::SelectedChainStoreReader;
-use kaspa_consensus::params::{Params, DEVNET_PARAMS, MAINNET_PARAMS, MAX_DIFFICULTY_TARGET, MAX_DIFFICULTY_TARGET_AS_F64};
+use kaspa_consensus::params::{
+    Params, DEVNET_PARAMS, MAINNET_PARAMS, MAX_DIFFICULTY_TARGET, MAX_DIFFICULTY_TARGET_AS_F64, SIMNET_PARAMS,
+};
 use kaspa_consensus::pipeline::monitor::ConsensusMonitor;
 use kaspa_consensus::pipeline::ProcessingCounters;
 use kaspa_consensus::processes::reachability::tests::{DagBlock, DagBuilder, StoreValidationExtensions};
-use kaspa_consensus::processes::window::{WindowManager, WindowType};
-use kaspa_consensus_core::api::{BlockValidationFutures, ConsensusApi};
+use kaspa_consensus::processes::window::{DualWindowManager, WindowManager, WindowType};
+use kaspa_consensus_core::acceptance_data::{verify_acceptance_proof, OutpointSpender};
+use kaspa_consensus_core::api::{BlockValidationFutures, ConsensusApi, TrustedBlocksSummary};
 use kaspa_consensus_core::block::Block;
 use kaspa_consensus_core::blockhash::new_unique;
 use kaspa_consensus_core::blockstatus::BlockStatus;
-use kaspa_consensus_core::constants::{BLOCK_VERSION, STORAGE_MASS_PARAMETER};
+use kaspa_consensus_core::coinbase::{CoinbaseAmounts, MinerData};
+use kaspa_consensus_core::config::checkpoints::{validate_pruning_points_against_checkpoints, Checkpoint};
+use kaspa_consensus_core::config::header_version::{HeaderVersionActivation, HeaderVersionPolicy};
+use kaspa_consensus_core::constants::{BLOCK_VERSION, MAX_TX_IN_SEQUENCE_NUM, STORAGE_MASS_PARAMETER, TX_VERSION};
+use kaspa_consensus_core::daa_score_timestamp::ChainBlockAtDaaScore;
 use kaspa_consensus_core::errors::block::{BlockProcessResult, RuleError};
+use kaspa_consensus_core::errors::consensus::ConsensusError;
+use kaspa_consensus_core::errors::pruning::PruningImportError;
 use kaspa_consensus_core::header::Header;
+use kaspa_consensus_core::merkle::calc_hash_merkle_root;
 use kaspa_consensus_core::network::{NetworkId, NetworkType::Mainnet};
-use kaspa_consensus_core::subnets::SubnetworkId;
+use kaspa_consensus_core::pruning::PruningPointProof;
+use kaspa_consensus_core::subnets::{SubnetworkId, SUBNETWORK_ID_NATIVE};
 use kaspa_consensus_core::trusted::{ExternalGhostdagData, TrustedBlock};
 use kaspa_consensus_core::tx::{ScriptPublicKey, Transaction, TransactionInput, TransactionOutpoint, TransactionOutput, UtxoEntry};
-use kaspa_consensus_core::{blockhash, hashing, BlockHashMap, BlueWorkType};
+use kaspa_consensus_core::{blockhash, hashing, BlockHashMap, BlockHashSet, BlockLevel, BlueWorkType};
+use kaspa_consensus_notify::notification::Notification as ConsensusNotification;
 use kaspa_consensus_notify::root::ConsensusNotificationRoot;
 use kaspa_consensus_notify::service::NotifyService;
 use kaspa_consensusmanager::ConsensusManager;
 use kaspa_core::task::tick::TickService;
-use kaspa_core::time::unix_now;
+use kaspa_core::time::SimulatedClock;
 use kaspa_database::utils::get_kaspa_tempdir;
 use kaspa_hashes::Hash;
 
@@ -49,25 +64,34 @@ use kaspa_core::signals::Shutdown;
 use kaspa_core::task::runtime::AsyncRuntime;
 use kaspa_core::{assert_match, info};
 use kaspa_database::create_temp_db;
-use kaspa_database::prelude::{CachePolicy, ConnBuilder};
+use kaspa_database::prelude::{CachePolicy, ConnBuilder, DbKey, DB};
+use kaspa_database::registry::DatabaseStorePrefixes;
 use kaspa_index_processor::service::IndexService;
-use kaspa_math::Uint256;
+use kaspa_math::{Uint256, Uint320};
 use kaspa_muhash::MuHash;
+use kaspa_notify::listener::ListenerId;
+use kaspa_notify::scope::BlockAddedScope;
+use kaspa_notify::subscriber::SubscriptionManager;
 use kaspa_notify::subscription::context::SubscriptionContext;
 use kaspa_txscript::caches::TxScriptCacheCounters;
+use kaspa_txscript::{extract_script_pub_key_address, pay_to_script_hash_signature_script, test_helpers::op_true_script};
 use kaspa_utxoindex::api::{UtxoIndexApi, UtxoIndexProxy};
 use kaspa_utxoindex::UtxoIndex;
 use serde::{Deserialize, Serialize};
 use std::cmp::{max, Ordering};
 use std::collections::HashSet;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{
+    atomic::{self, AtomicBool},
+    Arc,
+};
 use std::{
     collections::HashMap,
     fs::File,
     future::Future,
     io::{BufRead, BufReader},
     str::{from_utf8, FromStr},
+    time::{Duration, Instant},
 };
 
 use crate::common;
@@ -393,7 +417,8 @@ async fn block_window_test() {
 #[tokio::test]
 async fn header_in_isolation_validation_test() {
     init_allocator_with_default_settings();
-    let config = Config::new(MAINNET_PARAMS);
+    let clock = SimulatedClock::from_system_time();
+    let config = ConfigBuilder::new(MAINNET_PARAMS).set_clock(clock.as_dyn()).build();
     let consensus = TestConsensus::new(&config);
     let wait_handles = consensus.init();
     let block = consensus.build_block_with_parents(1.into(), vec![config.genesis.hash]);
@@ -403,8 +428,9 @@ async fn header_in_isolation_validation_test() {
         let block_version = BLOCK_VERSION - 1;
         block.header.version = block_version;
         match consensus.validate_and_insert_block(block.to_immutable()).virtual_state_task.await {
-            Err(RuleError::WrongBlockVersion(wrong_version)) => {
-                assert_eq!(wrong_version, block_version)
+            Err(RuleError::UnacceptedBlockVersion(wrong_version, accepted_versions, _)) => {
+                assert_eq!(wrong_version, block_version);
+                assert_eq!(accepted_versions.0, vec![BLOCK_VERSION]);
             }
             res => {
                 panic!("Unexpected result: {res:?}")
@@ -416,12 +442,14 @@ async fn header_in_isolation_validation_test() {
         let mut block = block.clone();
         block.header.hash = 2.into();
 
-        let now = unix_now();
-        let block_ts = now + config.legacy_timestamp_deviation_tolerance * config.target_time_per_block + 2000;
+        // The clock is simulated so the block is exactly one millisecond past the maximum allowed time
+        let max_block_time = clock.unix_now() + config.timestamp_deviation_tolerance(0) * 1000;
+        let block_ts = max_block_time + 1;
         block.header.timestamp = block_ts;
         match consensus.validate_and_insert_block(block.to_immutable()).virtual_state_task.await {
-            Err(RuleError::TimeTooFarIntoTheFuture(ts, _)) => {
-                assert_eq!(ts, block_ts)
+            Err(RuleError::TimeTooFarIntoTheFuture(ts, max)) => {
+                assert_eq!(ts, block_ts);
+                assert_eq!(max, max_block_time);
             }
             res => {
                 panic!("Unexpected result: {res:?}")
@@ -459,6 +487,341 @@ async fn header_in_isolation_validation_test() {
     consensus.shutdown(wait_handles);
 }
 
+#[tokio::test]
+async fn header_version_activation_test() {
+    init_allocator_with_default_settings();
+    // A chain block at height h above genesis has DAA score `GENESIS.daa_score + h`
+    const ACTIVATION_DAA_SCORE: u64 = GENESIS.daa_score + 4;
+    const TOLERANCE_DAA_SCORE: u64 = ACTIVATION_DAA_SCORE + 1;
+    const VERSIONS: &[HeaderVersionActivation] =
+        &[HeaderVersionActivation::new(BLOCK_VERSION, 0), HeaderVersionActivation::new(BLOCK_VERSION + 1, ACTIVATION_DAA_SCORE)];
+    let config = ConfigBuilder::new(MAINNET_PARAMS)
+        .skip_proof_of_work()
+        .edit_consensus_params(|p| {
+            p.min_difficulty_window_len = p.legacy_difficulty_window_size;
+            p.header_version_policy =
+                HeaderVersionPolicy::new(VERSIONS).with_unknown_version_tolerance(BLOCK_VERSION + 1, TOLERANCE_DAA_SCORE);
+        })
+        .build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+    let miner_data = MinerData::new(ScriptPublicKey::from_vec(0, vec![]), vec![]);
+
+    consensus.add_utxo_valid_block_with_parents(1.into(), vec![config.genesis.hash], vec![]).await.unwrap();
+    consensus.add_utxo_valid_block_with_parents(2.into(), vec![1.into()], vec![]).await.unwrap();
+
+    let build_block = |hash: u64, parent: u64, version: Option<u16>| {
+        let mut block = consensus.build_utxo_valid_block_with_parents(hash.into(), vec![parent.into()], miner_data.clone(), vec![]);
+        if let Some(version) = version {
+            block.header.version = version;
+        }
+        block
+    };
+
+    // Right below the activation score, the template carries the current version and the new version is rejected
+    let block = build_block(100, 2, None);
+    assert_eq!(block.header.daa_score, ACTIVATION_DAA_SCORE - 1);
+    assert_eq!(block.header.version, BLOCK_VERSION);
+    match consensus.validate_and_insert_block(build_block(101, 2, Some(BLOCK_VERSION + 1)).to_immutable()).virtual_state_task.await {
+        Err(RuleError::UnacceptedBlockVersion(version, accepted_versions, daa_score)) => {
+            assert_eq!(version, BLOCK_VERSION + 1);
+            assert_eq!(accepted_versions.0, vec![BLOCK_VERSION]);
+            assert_eq!(daa_score, ACTIVATION_DAA_SCORE - 1);
+        }
+        res => panic!("Unexpected result: {res:?}"),
+    }
+    consensus.validate_and_insert_block(block.to_immutable()).virtual_state_task.await.unwrap();
+
+    // From the activation score on, both versions are accepted and the template carries the new version
+    let block = build_block(3, 100, None);
+    assert_eq!(block.header.daa_score, ACTIVATION_DAA_SCORE);
+    assert_eq!(block.header.version, BLOCK_VERSION + 1);
+    consensus.validate_and_insert_block(block.to_immutable()).virtual_state_task.await.unwrap();
+    consensus.validate_and_insert_block(build_block(102, 100, Some(BLOCK_VERSION)).to_immutable()).virtual_state_task.await.unwrap();
+
+    // Unknown versions are rejected until the tolerance is active
+    match consensus.validate_and_insert_block(build_block(103, 100, Some(BLOCK_VERSION + 2)).to_immutable()).virtual_state_task.await {
+        Err(RuleError::UnacceptedBlockVersion(version, accepted_versions, daa_score)) => {
+            assert_eq!(version, BLOCK_VERSION + 2);
+            assert_eq!(accepted_versions.0, vec![BLOCK_VERSION, BLOCK_VERSION + 1]);
+            assert_eq!(daa_score, ACTIVATION_DAA_SCORE);
+        }
+        res => panic!("Unexpected result: {res:?}"),
+    }
+    let block = build_block(104, 3, Some(BLOCK_VERSION + 2));
+    assert_eq!(block.header.daa_score, TOLERANCE_DAA_SCORE);
+    consensus.validate_and_insert_block(block.to_immutable()).virtual_state_task.await.unwrap();
+    assert_eq!(build_block(105, 3, None).header.version, BLOCK_VERSION + 1, "unknown versions are never built");
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn bad_coinbase_payment_test() {
+    init_allocator_with_default_settings();
+    // The subsidy committed to by every block before the deflationary phase
+    const SUBSIDY: u64 = 50_000_000_000;
+    let config = ConfigBuilder::new(MAINNET_PARAMS)
+        .skip_proof_of_work()
+        .edit_consensus_params(|p| p.min_difficulty_window_len = p.legacy_difficulty_window_size)
+        .build();
+    assert_eq!(config.pre_deflationary_phase_base_subsidy, SUBSIDY);
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+    let miner_data = MinerData::new(ScriptPublicKey::from_vec(0, vec![1; 32]), vec![]);
+
+    consensus.add_utxo_valid_block_with_parents(1.into(), vec![config.genesis.hash], vec![]).await.unwrap();
+    consensus.add_utxo_valid_block_with_parents(2.into(), vec![1.into()], vec![]).await.unwrap();
+
+    // The coinbase of a chain block pays the rewards of its mergeset, here the subsidy of its selected parent
+    assert_eq!(consensus.expected_coinbase_amount(2.into()).unwrap(), CoinbaseAmounts::new(SUBSIDY, 0));
+    assert_match!(consensus.expected_coinbase_amount(100.into()), Err(ConsensusError::HeaderNotFound(_)));
+
+    // A block built from a fresh template is expected to pay the subsidy of the sink
+    let expected = consensus.expected_virtual_coinbase_amount();
+    assert_eq!(expected, CoinbaseAmounts::new(SUBSIDY, 0));
+    let mut block = consensus.build_utxo_valid_block_with_parents(3.into(), vec![2.into()], miner_data.clone(), vec![]);
+    assert_eq!(block.transactions[0].outputs.iter().map(|output| output.value).sum::<u64>(), expected.total());
+
+    // Overpay by 1 sompi
+    block.transactions[0].outputs[0].value += 1;
+    block.transactions[0].finalize();
+    block.header.hash_merkle_root = calc_hash_merkle_root(block.transactions.iter());
+    let status = consensus.validate_and_insert_block(block.to_immutable()).virtual_state_task.await.unwrap();
+    assert_eq!(status, BlockStatus::StatusDisqualifiedFromChain);
+    match consensus.get_disqualification_reason(3.into()) {
+        Some(RuleError::BadCoinbasePayment(expected_amounts, actual_total)) => {
+            assert_eq!(expected_amounts, CoinbaseAmounts::new(SUBSIDY, 0));
+            assert_eq!(expected_amounts.total(), 50_000_000_000);
+            assert_eq!(actual_total, 50_000_000_001);
+        }
+        reason => panic!("Unexpected disqualification reason: {reason:?}"),
+    }
+    assert_eq!(
+        consensus.get_disqualification_reason(3.into()).unwrap().to_string(),
+        "coinbase transaction pays a total of 50000000001 sompi but is expected to pay 50000000000 sompi (subsidy 50000000000 + fees 0)"
+    );
+    assert!(consensus.get_disqualification_reason(2.into()).is_none());
+
+    // The sibling paying the expected amount is valid
+    consensus.add_utxo_valid_block_with_parents(4.into(), vec![2.into()], vec![]).await.unwrap();
+    assert_eq!(consensus.get_block_status(4.into()), Some(BlockStatus::StatusUTXOValid));
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn body_in_isolation_full_validation_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(MAINNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+    let body_processor = consensus.block_body_processor();
+
+    // A transaction weighing more than half of the block mass limit
+    let outpoint = TransactionOutpoint::new(1.into(), 0);
+    let tx = Transaction::new(
+        TX_VERSION,
+        vec![TransactionInput::new(outpoint, vec![], MAX_TX_IN_SEQUENCE_NUM, 255)],
+        vec![TransactionOutput::new(1_000, ScriptPublicKey::from_vec(0, vec![1; 32]))],
+        0,
+        SUBNETWORK_ID_NATIVE,
+        0,
+        vec![],
+    );
+
+    // A valid body reports the mass computed by the fail-fast validation
+    let block =
+        consensus.build_block_with_parents_and_transactions(1.into(), vec![config.genesis.hash], vec![tx.clone()]).to_immutable();
+    let mass = body_processor.validate_body_in_isolation(&block).unwrap();
+    let report = consensus.validate_body_in_isolation_full(&block);
+    assert!(report.is_valid(), "{report:?}");
+    assert_eq!(report.mass, Some(mass));
+
+    // A body violating several rules at once, of which the fail-fast validation only reports the first one
+    let mut block =
+        consensus.build_block_with_parents_and_transactions(2.into(), vec![config.genesis.hash], vec![tx.clone(), tx.clone()]);
+    block.header.hash_merkle_root = 0.into();
+    let block = block.to_immutable();
+    assert_match!(body_processor.validate_body_in_isolation(&block), Err(RuleError::BadMerkleRoot(_, _)));
+    let report = consensus.validate_body_in_isolation_full(&block);
+    assert_match!(
+        report.errors.as_slice(),
+        [
+            RuleError::BadMerkleRoot(_, _),
+            RuleError::ExceedsMassLimit(_),
+            RuleError::DuplicateTransactions(tx_id),
+            RuleError::DoubleSpendInSameBlock(spent_outpoint),
+        ] if *tx_id == tx.id() && *spent_outpoint == outpoint
+    );
+    assert_eq!(report.mass, None);
+
+    // The other rules are not checked for a body without transactions
+    let block = consensus.build_block_with_parents(3.into(), vec![config.genesis.hash]).to_immutable();
+    let report = consensus.validate_body_in_isolation_full(&block);
+    assert_match!(report.errors.as_slice(), [RuleError::NoTransactions]);
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn block_difficulty_context_test() {
+    init_allocator_with_default_settings();
+    const WINDOW_SIZE: usize = 20;
+    const MIN_WINDOW_LEN: usize = 10;
+
+    // Computes the work from the bits independently of the consensus implementation, as 2^256 / (target + 1)
+    fn expected_work(bits: u32) -> BlueWorkType {
+        let target = Uint320::from(Uint256::from_compact_target_bits(bits));
+        let work = (Uint320::from_u64(1) << 256) / (target + Uint320::from_u64(1));
+        Uint256::try_from(work).unwrap().try_into().unwrap()
+    }
+
+    for params in [SIMNET_PARAMS, MAINNET_PARAMS] {
+        let config = ConfigBuilder::new(params)
+            .skip_proof_of_work()
+            .edit_consensus_params(|p| {
+                p.sampling_activation_daa_score = u64::MAX;
+                p.legacy_difficulty_window_size = WINDOW_SIZE;
+                p.min_difficulty_window_len = MIN_WINDOW_LEN;
+            })
+            .build();
+        let consensus = TestConsensus::new(&config);
+        let wait_handles = consensus.init();
+
+        let genesis_context = consensus.get_block_difficulty_context(config.genesis.hash).unwrap();
+        assert_eq!(genesis_context.work, expected_work(config.genesis.bits));
+        assert_eq!(genesis_context.network_hashes_per_second, None);
+
+        let mut headers = vec![];
+        let mut selected_parent = config.genesis.hash;
+        for _ in 0..2 * WINDOW_SIZE {
+            let mut header = consensus.build_header_with_parents(new_unique(), vec![selected_parent]);
+            header.timestamp = consensus.headers_store().get_timestamp(selected_parent).unwrap() + config.target_time_per_block(0);
+            consensus.validate_and_insert_block(Block::new(header.clone(), vec![])).virtual_state_task.await.unwrap();
+            selected_parent = header.hash;
+            headers.push(header);
+        }
+
+        for header in headers.iter() {
+            let context = consensus.get_block_difficulty_context(header.hash).unwrap();
+            assert_eq!(context.target, Uint256::from_compact_target_bits(header.bits), "{}", config.params.net);
+            assert_eq!(context.work, expected_work(header.bits), "{}", config.params.net);
+
+            let ghostdag_data = consensus.ghostdag_store().get_data(header.hash).unwrap();
+            let window = consensus.window_manager().block_daa_window(&ghostdag_data).unwrap().window;
+            if window.len() < MIN_WINDOW_LEN {
+                assert_eq!(context.network_hashes_per_second, None, "{}", config.params.net);
+                continue;
+            }
+            // The hashrate is the blue work accumulated over the time span of the window
+            let timestamps = window.iter().map(|x| consensus.headers_store().get_timestamp(x.0.hash).unwrap());
+            let (min_ts, max_ts) = timestamps.minmax().into_option().unwrap();
+            let (min_blue_work, max_blue_work) = window.iter().map(|x| x.0.blue_work).minmax().into_option().unwrap();
+            let expected = ((max_blue_work - min_blue_work) / ((max_ts - min_ts) / 1000)).as_u64();
+            assert_eq!(context.network_hashes_per_second, Some(expected), "{}", config.params.net);
+            // Cached contexts are reported again as is
+            assert_eq!(consensus.get_block_difficulty_context(header.hash).unwrap(), context);
+        }
+
+        assert!(matches!(consensus.get_block_difficulty_context(new_unique()), Err(ConsensusError::HeaderNotFound(_))));
+        consensus.shutdown(wait_handles);
+    }
+}
+/// A header chain recorded from a consensus instance along with the windows of its headers
+#[derive(Default, Clone)]
+struct RecordedHeaderChain {
+    headers: BlockHashMap<Arc<Header>>,
+    difficulty_windows: BlockHashMap<Vec<Hash>>,
+    past_median_time_windows: BlockHashMap<Vec<Hash>>,
+}
+
+impl RecordedHeaderChain {
+    fn window(&self, windows: &BlockHashMap<Vec<Hash>>, header: &Header) -> BlockProcessResult<Vec<Arc<Header>>> {
+        let window = windows.get(&header.hash).ok_or_else(|| RuleError::MissingParents(header.direct_parents().to_vec()))?;
+        Ok(window.iter().map(|hash| self.headers[hash].clone()).collect())
+    }
+}
+
+impl HeaderParentDataProvider for RecordedHeaderChain {
+    fn get_header(&self, hash: Hash) -> Option<Arc<Header>> {
+        self.headers.get(&hash).cloned()
+    }
+
+    fn difficulty_window(&self, header: &Header) -> BlockProcessResult<Vec<Arc<Header>>> {
+        self.window(&self.difficulty_windows, header)
+    }
+
+    fn past_median_time_window(&self, header: &Header) -> BlockProcessResult<Vec<Arc<Header>>> {
+        self.window(&self.past_median_time_windows, header)
+    }
+}
+
+#[tokio::test]
+async fn header_chain_verifier_test() {
+    init_allocator_with_default_settings();
+    const WINDOW_SIZE: usize = 20;
+    const MIN_WINDOW_LEN: usize = 10;
+
+    let config = ConfigBuilder::new(SIMNET_PARAMS)
+        .edit_consensus_params(|p| {
+            p.sampled_difficulty_window_size = WINDOW_SIZE;
+            p.min_difficulty_window_len = MIN_WINDOW_LEN;
+            p.difficulty_sample_rate = 2;
+            p.past_median_time_sampled_window_size = 11;
+            p.past_median_time_sample_rate = 2;
+        })
+        .build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    // Record a chain of headers mined faster than the target time per block, so the difficulty adjusts
+    let mut recording = RecordedHeaderChain::default();
+    let mut headers = vec![];
+    let mut selected_parent = config.genesis.hash;
+    recording.headers.insert(selected_parent, consensus.headers_store().get_header(selected_parent).unwrap());
+    for _ in 0..3 * WINDOW_SIZE {
+        let mut header = consensus.build_header_with_parents(new_unique(), vec![selected_parent]);
+        header.timestamp = consensus.headers_store().get_timestamp(selected_parent).unwrap() + config.target_time_per_block(0) / 2;
+        consensus.validate_and_insert_block(Block::new(header.clone(), vec![])).virtual_state_task.await.unwrap();
+
+        let ghostdag_data = consensus.ghostdag_store().get_data(header.hash).unwrap();
+        let windows = consensus.window_manager().block_windows(&ghostdag_data).unwrap();
+        let (_, past_median_time_window) = windows.past_median_time.unwrap();
+        recording.difficulty_windows.insert(header.hash, windows.daa_window.window.iter().map(|x| x.0.hash).collect());
+        recording.past_median_time_windows.insert(header.hash, past_median_time_window.iter().map(|x| x.0.hash).collect());
+
+        let header = Arc::new(header);
+        recording.headers.insert(header.hash, header.clone());
+        selected_parent = header.hash;
+        headers.push(header);
+    }
+    consensus.shutdown(wait_handles);
+    assert!(headers.iter().any(|header| header.bits != config.genesis.bits), "the difficulty is expected to adjust");
+
+    // The recorded chain is valid
+    let verifier = HeaderChainVerifier::new(&config.params, recording.clone());
+    verifier.verify_batch(&headers).unwrap();
+
+    // A corrupted difficulty is detected at the exact header carrying it
+    const CORRUPTED_INDEX: usize = 2 * WINDOW_SIZE;
+    let mut corrupted = (*headers[CORRUPTED_INDEX]).clone();
+    let expected_bits = corrupted.bits;
+    corrupted.bits += 1;
+    let corrupted = Arc::new(corrupted);
+    recording.headers.insert(corrupted.hash, corrupted.clone());
+    headers[CORRUPTED_INDEX] = corrupted;
+    let verifier = HeaderChainVerifier::new(&config.params, recording);
+    match verifier.verify_batch(&headers) {
+        Err((index, RuleError::UnexpectedDifficulty(bits, expected))) => {
+            assert_eq!(index, CORRUPTED_INDEX);
+            assert_eq!(bits, expected_bits + 1);
+            assert_eq!(expected, expected_bits);
+        }
+        res => panic!("Unexpected result: {res:?}"),
+    }
+}
+
 #[tokio::test]
 async fn incest_test() {
     init_allocator_with_default_settings();
@@ -829,6 +1192,7 @@ impl KaspadGoParams {
             mass_per_script_pub_key_byte: self.MassPerScriptPubKeyByte,
             mass_per_sig_op: self.MassPerSigOp,
             max_block_mass: self.MaxBlockMass,
+            max_block_transactions: MAINNET_PARAMS.max_block_transactions,
             storage_mass_parameter: STORAGE_MASS_PARAMETER,
             storage_mass_activation_daa_score: u64::MAX,
             deflationary_phase_daa_score: self.DeflationaryPhaseDaaScore,
@@ -837,6 +1201,7 @@ impl KaspadGoParams {
             skip_proof_of_work: self.SkipProofOfWork,
             max_block_level: self.MaxBlockLevel,
             pruning_proof_m: self.PruningProofM,
+            header_version_policy: MAINNET_PARAMS.header_version_policy,
         }
     }
 }
@@ -901,6 +1266,122 @@ async fn goref_mainnet_concurrent_test() {
     json_test("testdata/dags_for_json_tests/goref-mainnet", true).await
 }
 
+/// Replays the headers of a recorded DAG and checks that the DAA and past median time windows built together by
+/// [`WindowManager::block_windows`] are identical to the windows built separately, while requiring fewer store reads.
+/// The windows are compared as full windows, as sampled windows and across a sampling activation within the DAG.
+#[tokio::test]
+async fn goref_notx_shared_windows_test() {
+    init_allocator_with_default_settings();
+    let mut lines = gzip_file_lines(Path::new("testdata/dags_for_json_tests/goref-notx-5000-blocks/blocks.json.gz"));
+    let go_params: KaspadGoParams = serde_json::from_str(&lines.next().unwrap()).unwrap();
+    let mut params = go_params.into_params();
+    let genesis_block = json_line_to_block(lines.next().unwrap());
+    params.genesis = (genesis_block.header.as_ref(), DEVNET_PARAMS.genesis.coinbase_payload).into();
+    params.min_difficulty_window_len = params.legacy_difficulty_window_size;
+    let config = Config::new(params);
+
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+    let mut hashes = Vec::new();
+    for chunk in lines.chunks(1000).into_iter() {
+        let blocks = chunk.map(json_line_to_block).collect_vec();
+        hashes.extend(blocks.iter().map(|block| block.hash()));
+        try_join_all(
+            blocks
+                .into_iter()
+                .map(|block| consensus.validate_and_insert_block(Block::from_header_arc(block.header)).virtual_state_task),
+        )
+        .await
+        .unwrap();
+    }
+
+    let middle_daa_score = consensus.headers_store().get_daa_score(hashes[hashes.len() / 2]).unwrap();
+    for sampling_activation_daa_score in [config.sampling_activation_daa_score, middle_daa_score, 0] {
+        let separate = cached_window_manager(&consensus, sampling_activation_daa_score);
+        let shared = cached_window_manager(&consensus, sampling_activation_daa_score);
+        for &hash in hashes.iter() {
+            let ghostdag_data = consensus.ghostdag_store().get_data(hash).unwrap();
+            let daa_window = separate.window_manager.block_daa_window(&ghostdag_data).unwrap();
+            let (past_median_time, past_median_time_window) = separate.window_manager.calc_past_median_time(&ghostdag_data).unwrap();
+            let windows = shared.window_manager.block_windows(&ghostdag_data).unwrap();
+            let (shared_past_median_time, shared_past_median_time_window) = windows.past_median_time.unwrap();
+
+            assert_eq!(window_blocks(&daa_window.window), window_blocks(&windows.daa_window.window), "DAA window of {hash}");
+            assert_eq!(daa_window.daa_score, windows.daa_window.daa_score, "DAA score of {hash}");
+            assert_eq!(daa_window.mergeset_non_daa, windows.daa_window.mergeset_non_daa, "mergeset non-DAA blocks of {hash}");
+            assert_eq!(
+                separate.window_manager.calculate_difficulty_bits(&ghostdag_data, &daa_window),
+                shared.window_manager.calculate_difficulty_bits(&ghostdag_data, &windows.daa_window),
+                "difficulty bits of {hash}"
+            );
+            assert_eq!(
+                window_blocks(&past_median_time_window),
+                window_blocks(&shared_past_median_time_window),
+                "past median time window of {hash}"
+            );
+            assert_eq!(past_median_time, shared_past_median_time, "past median time of {hash}");
+
+            separate.cache(hash, daa_window.window, past_median_time_window);
+            shared.cache(hash, windows.daa_window.window, shared_past_median_time_window);
+        }
+
+        let separate_reads = separate.counters.snapshot().window_store_reads;
+        let shared_reads = shared.counters.snapshot().window_store_reads;
+        info!(
+            "Sampling activation DAA score {}: {} window store reads when built separately, {} when shared",
+            sampling_activation_daa_score, separate_reads, shared_reads
+        );
+        assert!(shared_reads < separate_reads);
+    }
+    consensus.shutdown(wait_handles);
+}
+
+/// A window manager over the stores of a test consensus with caches of its own, filled as done by the header processor
+struct CachedWindowManager<T: WindowManager> {
+    window_manager: T,
+    block_window_cache_for_difficulty: Arc<BlockWindowCacheStore>,
+    block_window_cache_for_past_median_time: Arc<BlockWindowCacheStore>,
+    counters: Arc<ProcessingCounters>,
+}
+
+fn cached_window_manager(consensus: &TestConsensus, sampling_activation_daa_score: u64) -> CachedWindowManager<impl WindowManager> {
+    let params = consensus.params();
+    let block_window_cache_for_difficulty = Arc::new(BlockWindowCacheStore::new(CachePolicy::Count(2000)));
+    let block_window_cache_for_past_median_time = Arc::new(BlockWindowCacheStore::new(CachePolicy::Count(2000)));
+    let counters = Arc::new(ProcessingCounters::default());
+    let window_manager = DualWindowManager::new(
+        &params.genesis,
+        consensus.ghostdag_store().clone(),
+        consensus.headers_store(),
+        consensus.daa_excluded_store(),
+        block_window_cache_for_difficulty.clone(),
+        block_window_cache_for_past_median_time.clone(),
+        params.max_difficulty_target,
+        params.target_time_per_block,
+        sampling_activation_daa_score,
+        params.legacy_difficulty_window_size,
+        params.sampled_difficulty_window_size,
+        params.min_difficulty_window_len,
+        params.difficulty_sample_rate,
+        params.legacy_past_median_time_window_size(),
+        params.sampled_past_median_time_window_size(),
+        params.past_median_time_sample_rate,
+        counters.clone(),
+    );
+    CachedWindowManager { window_manager, block_window_cache_for_difficulty, block_window_cache_for_past_median_time, counters }
+}
+
+impl<T: WindowManager> CachedWindowManager<T> {
+    fn cache(&self, hash: Hash, difficulty_window: Arc<BlockWindowHeap>, past_median_time_window: Arc<BlockWindowHeap>) {
+        self.block_window_cache_for_difficulty.insert(hash, difficulty_window);
+        self.block_window_cache_for_past_median_time.insert(hash, past_median_time_window);
+    }
+}
+
+fn window_blocks(window: &BlockWindowHeap) -> Vec<(Hash, BlueWorkType)> {
+    window.iter().map(|block| (block.0.hash, block.0.blue_work)).sorted().collect()
+}
+
 fn gzip_file_lines(path: &Path) -> impl Iterator<Item = String> {
     let file = common::open_file(path);
     let decoder = GzDecoder::new(file);
@@ -1701,6 +2182,406 @@ async fn selected_chain_test() {
     consensus.shutdown(wait_handles);
 }
 
+#[tokio::test]
+async fn chain_block_by_daa_score_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(MAINNET_PARAMS)
+        .skip_proof_of_work()
+        .edit_consensus_params(|p| {
+            p.min_difficulty_window_len = p.legacy_difficulty_window_size;
+        })
+        .build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    // Build a chain where one chain block merges a wide anticone, creating a gap in DAA scores over the selected chain
+    consensus.add_utxo_valid_block_with_parents(1.into(), vec![config.genesis.hash], vec![]).await.unwrap();
+    for i in 2..6 {
+        consensus.add_utxo_valid_block_with_parents(i.into(), vec![(i - 1).into()], vec![]).await.unwrap();
+    }
+    for i in 6..16 {
+        consensus.add_utxo_valid_block_with_parents(i.into(), vec![5.into()], vec![]).await.unwrap();
+    }
+    consensus.add_utxo_valid_block_with_parents(16.into(), (6..16).map(|i| i.into()).collect_vec(), vec![]).await.unwrap();
+    consensus.add_utxo_valid_block_with_parents(17.into(), vec![16.into()], vec![]).await.unwrap();
+
+    let sink = consensus.get_sink();
+    assert_eq!(sink, 17.into());
+    let chain = (0..=consensus.selected_chain_store.read().get_tip().unwrap().0)
+        .map(|index| {
+            let hash = consensus.selected_chain_store.read().get_by_index(index).unwrap();
+            let header = consensus.headers_store().get_header(hash).unwrap();
+            ChainBlockAtDaaScore { hash, daa_score: header.daa_score, timestamp: header.timestamp }
+        })
+        .collect_vec();
+
+    // Exactness at the DAA scores of chain blocks
+    for block in chain.iter() {
+        assert_eq!(consensus.get_chain_block_by_daa_score(block.daa_score).unwrap(), *block);
+    }
+
+    // Nearest neighbor between chain blocks, preferring the earlier block on ties
+    let mut found_gap = false;
+    for (low, high) in chain.iter().tuple_windows() {
+        assert!(low.daa_score < high.daa_score);
+        for daa_score in low.daa_score..=high.daa_score {
+            let expected = if daa_score - low.daa_score <= high.daa_score - daa_score { low } else { high };
+            assert_eq!(consensus.get_chain_block_by_daa_score(daa_score).unwrap(), *expected);
+        }
+        found_gap |= high.daa_score - low.daa_score > 2;
+    }
+    assert!(found_gap, "expected the merging chain block to create a gap in DAA scores");
+
+    // Scores above the sink resolve to the sink
+    let last = chain.last().unwrap();
+    assert_eq!(last.hash, sink);
+    for daa_score in [last.daa_score + 1, last.daa_score + 1000, u64::MAX] {
+        assert_eq!(consensus.get_chain_block_by_daa_score(daa_score).unwrap(), *last);
+    }
+
+    // Scores below the pruning point are rejected
+    let pruning_point_daa_score = chain.first().unwrap().daa_score;
+    assert_eq!(chain.first().unwrap().hash, consensus.pruning_point());
+    assert!(pruning_point_daa_score > 0, "mainnet genesis is expected to carry the checkpoint DAA score");
+    for daa_score in [0, pruning_point_daa_score - 1] {
+        assert!(matches!(
+            consensus.get_chain_block_by_daa_score(daa_score),
+            Err(ConsensusError::DaaScoreBelowPruningPoint(score, pp_score)) if score == daa_score && pp_score == pruning_point_daa_score
+        ));
+    }
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn outpoint_spend_status_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(MAINNET_PARAMS)
+        .skip_proof_of_work()
+        .edit_consensus_params(|p| {
+            p.min_difficulty_window_len = p.legacy_difficulty_window_size;
+            p.coinbase_maturity = 0;
+        })
+        .build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    // All blocks reward an anyone-can-spend script so that their coinbase outputs can be easily spent
+    let (script_public_key, redeem_script) = op_true_script();
+    let miner_data = MinerData::new(script_public_key.clone(), vec![]);
+    let add_block = |hash: u64, parents: Vec<Hash>, txs: Vec<Transaction>| {
+        let block = consensus.build_utxo_valid_block_with_parents(hash.into(), parents, miner_data.clone(), txs);
+        consensus.validate_and_insert_block(block.to_immutable()).virtual_state_task
+    };
+
+    add_block(1, vec![config.genesis.hash], vec![]).await.unwrap();
+    for i in 2..=10 {
+        add_block(i, vec![(i - 1).into()], vec![]).await.unwrap();
+    }
+
+    let (outpoint, entry) = consensus
+        .get_virtual_utxos(None, usize::MAX, false)
+        .into_iter()
+        .find(|(_, entry)| entry.script_public_key == script_public_key)
+        .expect("the chain should hold spendable coinbase outputs");
+
+    // Unspent
+    let status = consensus.get_outpoint_spend_status(outpoint, 100).unwrap();
+    assert_eq!(status.utxo_entry, Some(entry.clone()));
+    assert!(status.spenders.is_empty());
+
+    let signature_script = pay_to_script_hash_signature_script(redeem_script, vec![]).unwrap();
+    let spend = |fee: u64| {
+        let input = TransactionInput::new(outpoint, signature_script.clone(), MAX_TX_IN_SEQUENCE_NUM, 0);
+        let output = TransactionOutput::new(entry.amount - fee, script_public_key.clone());
+        Transaction::new(TX_VERSION, vec![input], vec![output], 0, SUBNETWORK_ID_NATIVE, 0, vec![])
+    };
+    let (tx_a, tx_b) = (spend(1000), spend(2000));
+    let spender = |tx: &Transaction, block_hash: u64, merging_block_hash: u64, is_accepted: bool| OutpointSpender {
+        transaction_id: tx.id(),
+        block_hash: block_hash.into(),
+        merging_block_hash: merging_block_hash.into(),
+        is_accepted,
+    };
+
+    // Two conflicting blocks spending the outpoint, where 13 accepts tx_a and 14 merges the double spend tx_b
+    //
+    //   10 <- 11 (tx_a) <- 13 <- 14
+    //     \                    /
+    //      <- 12 (tx_b) <-------
+    add_block(11, vec![10.into()], vec![tx_a.clone()]).await.unwrap();
+    add_block(12, vec![10.into()], vec![tx_b.clone()]).await.unwrap();
+    add_block(13, vec![11.into()], vec![]).await.unwrap();
+    add_block(14, vec![13.into(), 12.into()], vec![]).await.unwrap();
+    assert_eq!(consensus.get_sink(), 14.into());
+
+    // Chain-accepted
+    let status = consensus.get_outpoint_spend_status(outpoint, 100).unwrap();
+    assert_eq!(status.utxo_entry, None);
+    assert_eq!(status.spenders, vec![spender(&tx_b, 12, 14, false), spender(&tx_a, 11, 13, true)]);
+
+    // The search is bounded by the requested depth
+    let status = consensus.get_outpoint_spend_status(outpoint, 1).unwrap();
+    assert_eq!(status.spenders, vec![spender(&tx_b, 12, 14, false)]);
+    let status = consensus.get_outpoint_spend_status(outpoint, 0).unwrap();
+    assert_eq!(status.utxo_entry, None);
+    assert!(status.spenders.is_empty());
+
+    // Reorg to a heavier chain through 12, which flips the accepted spender to tx_b
+    //
+    //   10 <- 11 (tx_a) <- 13 <- 14
+    //     \       \
+    //      \       <------------------- 18
+    //       \                          /
+    //        <- 12 (tx_b) <- 15 <- 16 <- 17
+    add_block(15, vec![12.into()], vec![]).await.unwrap();
+    add_block(16, vec![15.into()], vec![]).await.unwrap();
+    add_block(17, vec![16.into()], vec![]).await.unwrap();
+    add_block(18, vec![17.into(), 11.into()], vec![]).await.unwrap();
+    assert_eq!(consensus.get_sink(), 18.into());
+
+    let status = consensus.get_outpoint_spend_status(outpoint, 100).unwrap();
+    assert_eq!(status.utxo_entry, None);
+    assert_eq!(status.spenders, vec![spender(&tx_a, 11, 18, false), spender(&tx_b, 12, 15, true)]);
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn acceptance_proof_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(SIMNET_PARAMS)
+        .skip_proof_of_work()
+        .edit_consensus_params(|p| {
+            p.min_difficulty_window_len = p.legacy_difficulty_window_size;
+            p.coinbase_maturity = 0;
+        })
+        .build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    let (script_public_key, redeem_script) = op_true_script();
+    let miner_data = MinerData::new(script_public_key.clone(), vec![]);
+    let add_block = |hash: u64, parents: Vec<Hash>, txs: Vec<Transaction>| {
+        let block = consensus.build_utxo_valid_block_with_parents(hash.into(), parents, miner_data.clone(), txs);
+        consensus.validate_and_insert_block(block.to_immutable()).virtual_state_task
+    };
+
+    add_block(1, vec![config.genesis.hash], vec![]).await.unwrap();
+    for i in 2..=10 {
+        add_block(i, vec![(i - 1).into()], vec![]).await.unwrap();
+    }
+
+    // Spend a few coinbase outputs in block 11, which get accepted by the chain block 12
+    let signature_script = pay_to_script_hash_signature_script(redeem_script, vec![]).unwrap();
+    let txs = consensus
+        .get_virtual_utxos(None, usize::MAX, false)
+        .into_iter()
+        .filter(|(_, entry)| entry.script_public_key == script_public_key)
+        .take(3)
+        .map(|(outpoint, entry)| {
+            let input = TransactionInput::new(outpoint, signature_script.clone(), MAX_TX_IN_SEQUENCE_NUM, 0);
+            let output = TransactionOutput::new(entry.amount - 1000, script_public_key.clone());
+            Transaction::new(TX_VERSION, vec![input], vec![output], 0, SUBNETWORK_ID_NATIVE, 0, vec![])
+        })
+        .collect_vec();
+    assert_eq!(txs.len(), 3);
+    add_block(11, vec![10.into()], txs.clone()).await.unwrap();
+    add_block(12, vec![11.into()], vec![]).await.unwrap();
+    assert_eq!(consensus.get_sink(), 12.into());
+
+    let root = consensus.get_header(12.into()).unwrap().accepted_id_merkle_root;
+    let coinbase_id = consensus.get_block(11.into()).unwrap().transactions[0].id();
+    let accepted_ids = txs.iter().map(|tx| tx.id()).chain(std::iter::once(coinbase_id)).collect_vec();
+    let mut proofs = Vec::new();
+    for &transaction_id in accepted_ids.iter() {
+        let proof = consensus.get_acceptance_proof(12.into(), transaction_id).unwrap();
+        assert!(verify_acceptance_proof(root, transaction_id, &proof));
+        proofs.push(proof);
+    }
+    let leaf_indices = proofs.iter().map(|proof| proof.leaf_index).sorted().collect_vec();
+    assert_eq!(leaf_indices, vec![0, 1, 2, 3]);
+
+    // Tampered proofs are rejected
+    let (transaction_id, proof) = (accepted_ids[0], &proofs[0]);
+    assert!(!verify_acceptance_proof(root, accepted_ids[1], proof));
+    assert!(!verify_acceptance_proof(consensus.get_header(11.into()).unwrap().accepted_id_merkle_root, transaction_id, proof));
+    let mut tampered = proof.clone();
+    tampered.leaf_index ^= 1;
+    assert!(!verify_acceptance_proof(root, transaction_id, &tampered));
+    let mut tampered = proof.clone();
+    tampered.path[0] = Hash::from_u64_word(1);
+    assert!(!verify_acceptance_proof(root, transaction_id, &tampered));
+    let mut tampered = proof.clone();
+    tampered.path.pop();
+    assert!(!verify_acceptance_proof(root, transaction_id, &tampered));
+
+    // A transaction accepted by another chain block
+    assert!(matches!(
+        consensus.get_acceptance_proof(11.into(), txs[0].id()),
+        Err(ConsensusError::TransactionNotAccepted(id, block)) if id == txs[0].id() && block == 11.into()
+    ));
+
+    // A block off the selected chain
+    add_block(13, vec![11.into()], vec![]).await.unwrap();
+    add_block(14, vec![12.into()], vec![]).await.unwrap();
+    assert_eq!(consensus.get_sink(), 14.into());
+    assert!(
+        matches!(consensus.get_acceptance_proof(13.into(), txs[0].id()), Err(ConsensusError::NotChainBlock(block)) if block == 13.into())
+    );
+
+    // Unknown blocks
+    assert!(matches!(consensus.get_acceptance_proof(100.into(), txs[0].id()), Err(ConsensusError::HeaderNotFound(_))));
+
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn spent_utxo_entries_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(SIMNET_PARAMS)
+        .skip_proof_of_work()
+        .edit_consensus_params(|p| {
+            p.min_difficulty_window_len = p.legacy_difficulty_window_size;
+            p.coinbase_maturity = 0;
+        })
+        .apply_args(|config| config.store_spent_utxos = true)
+        .build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    let (script_public_key, redeem_script) = op_true_script();
+    let miner_data = MinerData::new(script_public_key.clone(), vec![]);
+    let add_block = |hash: u64, parents: Vec<Hash>, txs: Vec<Transaction>| {
+        let block = consensus.build_utxo_valid_block_with_parents(hash.into(), parents, miner_data.clone(), txs);
+        consensus.validate_and_insert_block(block.to_immutable()).virtual_state_task
+    };
+
+    add_block(1, vec![config.genesis.hash], vec![]).await.unwrap();
+    for i in 2..=10 {
+        add_block(i, vec![(i - 1).into()], vec![]).await.unwrap();
+    }
+
+    // Spend a known coinbase output in block 11, which gets accepted by the chain block 12
+    let signature_script = pay_to_script_hash_signature_script(redeem_script, vec![]).unwrap();
+    let (outpoint, spent_entry) = consensus
+        .get_virtual_utxos(None, usize::MAX, false)
+        .into_iter()
+        .find(|(_, entry)| entry.script_public_key == script_public_key)
+        .unwrap();
+    let input = TransactionInput::new(outpoint, signature_script, MAX_TX_IN_SEQUENCE_NUM, 0);
+    let output = TransactionOutput::new(spent_entry.amount - 1000, script_public_key.clone());
+    let tx = Transaction::new(TX_VERSION, vec![input], vec![output], 0, SUBNETWORK_ID_NATIVE, 0, vec![]);
+    add_block(11, vec![10.into()], vec![tx.clone()]).await.unwrap();
+    add_block(12, vec![11.into()], vec![]).await.unwrap();
+    assert_eq!(consensus.get_sink(), 12.into());
+
+    let entry = consensus.get_spent_utxo_entry(12.into(), tx.id(), 0).unwrap();
+    assert_eq!(entry, spent_entry);
+    let address = extract_script_pub_key_address(&entry.script_public_key, config.prefix()).unwrap();
+    assert_eq!(address, extract_script_pub_key_address(&script_public_key, config.prefix()).unwrap());
+
+    // Missing inputs and transactions accepted by other chain blocks
+    assert!(matches!(
+        consensus.get_spent_utxo_entry(12.into(), tx.id(), 1),
+        Err(ConsensusError::InputIndexOutOfRange(id, 1)) if id == tx.id()
+    ));
+    assert!(matches!(consensus.get_spent_utxo_entry(11.into(), tx.id(), 0), Err(ConsensusError::TransactionNotAccepted(_, _))));
+
+    // Reorg the accepting block out of the selected chain, the transaction now being accepted by block 13
+    add_block(13, vec![11.into()], vec![]).await.unwrap();
+    add_block(14, vec![13.into()], vec![]).await.unwrap();
+    assert_eq!(consensus.get_sink(), 14.into());
+    assert!(
+        matches!(consensus.get_spent_utxo_entry(12.into(), tx.id(), 0), Err(ConsensusError::NotChainBlock(block)) if block == 12.into())
+    );
+    assert_eq!(consensus.get_spent_utxo_entry(13.into(), tx.id(), 0).unwrap(), spent_entry);
+
+    consensus.shutdown(wait_handles);
+
+    // Spent entries are not recorded unless requested
+    let config = ConfigBuilder::new(SIMNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+    consensus.add_utxo_valid_block_with_parents(1.into(), vec![config.genesis.hash], vec![]).await.unwrap();
+    assert!(matches!(
+        consensus.get_spent_utxo_entry(1.into(), tx.id(), 0),
+        Err(ConsensusError::SpentUtxoEntriesNotStored(block)) if block == 1.into()
+    ));
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn reachability_chain_of_forks_stress_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(DEVNET_PARAMS).skip_proof_of_work().build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+
+    // Build a selected chain where every few blocks a long fork chain is mined from a block which
+    // is deep below the reindex root. Each fork exhausts the (halving) interval allocation of its
+    // base and forces reindexing of intervals earlier than the reindex root.
+    let chain_len = 400;
+    let fork_every = 20;
+    let fork_depth = 150;
+    let fork_len = 80;
+
+    let mut next_hash = 1u64;
+    let mut new_hash = || {
+        next_hash += 1;
+        Hash::from_u64_word(next_hash)
+    };
+    let mut chain = vec![config.genesis.hash];
+    let mut forks = Vec::new();
+    for i in 1..=chain_len {
+        let hash = new_hash();
+        consensus.add_block_with_parents(hash, vec![*chain.last().unwrap()]).await.unwrap();
+        chain.push(hash);
+
+        if i % fork_every == 0 && i > fork_depth {
+            let base = chain[i - fork_depth];
+            let mut fork = vec![base];
+            for _ in 0..fork_len {
+                let hash = new_hash();
+                consensus.add_block_with_parents(hash, vec![*fork.last().unwrap()]).await.unwrap();
+                fork.push(hash);
+            }
+            forks.push(fork);
+        }
+    }
+
+    // The node survived and deep reindexing indeed took place
+    let counters = consensus.processing_counters().snapshot();
+    assert!(counters.reindex_counts > 0);
+    assert!(counters.reindexed_blocks_counts > 0);
+    consensus.reachability_store().read().validate_intervals(blockhash::ORIGIN).unwrap();
+    assert_eq!(consensus.get_sink(), *chain.last().unwrap());
+
+    // Assert reachability answers
+    let reachability = consensus.reachability_service();
+    for window in chain.windows(2) {
+        assert!(reachability.is_chain_ancestor_of(window[0], window[1]));
+        assert!(!reachability.is_dag_ancestor_of(window[1], window[0]));
+    }
+    let tip = *chain.last().unwrap();
+    for fork in forks.iter() {
+        let (base, fork_tip) = (fork[0], *fork.last().unwrap());
+        assert!(reachability.is_chain_ancestor_of(base, fork_tip));
+        assert!(reachability.is_chain_ancestor_of(config.genesis.hash, fork_tip));
+        for &block in fork.iter().skip(1) {
+            assert!(reachability.is_dag_ancestor_of(base, block));
+            assert!(!reachability.is_dag_ancestor_of(block, tip));
+            assert!(!reachability.is_dag_ancestor_of(tip, block));
+        }
+    }
+    for (a, b) in forks.iter().tuple_windows() {
+        assert!(!reachability.is_dag_ancestor_of(*a.last().unwrap(), *b.last().unwrap()));
+        assert!(!reachability.is_dag_ancestor_of(*b.last().unwrap(), *a.last().unwrap()));
+    }
+
+    consensus.shutdown(wait_handles);
+}
+
 fn assert_selected_chain_store_matches_virtual_chain(consensus: &TestConsensus) {
     let pruning_point = consensus.pruning_point();
     let iter1 = selected_chain_store_iterator(consensus, pruning_point);
@@ -1738,9 +2619,12 @@ async fn staging_consensus_test() {
         &config,
         consensus_db_dir,
         4,
+        Default::default(),
         notification_root,
         counters,
+        Default::default(),
         tx_script_cache_counters,
+        Default::default(),
         200,
     ));
     let consensus_manager = Arc::new(ConsensusManager::new(consensus_factory));
@@ -1755,3 +2639,422 @@ async fn staging_consensus_test() {
     core.shutdown();
     core.join(joins);
 }
+
+#[tokio::test]
+async fn pruning_proof_validation_test() {
+    init_allocator_with_default_settings();
+    let main_path = Path::new("testdata/dags_for_json_tests/goref_custom_pruning_depth");
+    let mut lines = gzip_file_lines(&main_path.join("blocks.json.gz"));
+    let go_params: KaspadGoParams = serde_json::from_str(&lines.next().unwrap()).unwrap();
+    let mut params = go_params.into_params();
+    let genesis_block = json_line_to_block(lines.next().unwrap());
+    params.genesis = (genesis_block.header.as_ref(), DEVNET_PARAMS.genesis.coinbase_payload).into();
+    params.min_difficulty_window_len = params.legacy_difficulty_window_size;
+    let config = Config::new(params);
+
+    // Sync the DAG on a first consensus so that its pruning point advances and it can build a proof
+    let syncer = TestConsensus::new(&config);
+    let wait_handles = syncer.init();
+    for line in lines {
+        let block = json_line_to_block(line);
+        let hash = block.hash();
+        syncer.validate_and_insert_block(block).virtual_state_task.await.unwrap_or_else(|e| panic!("block {hash} failed: {e}"));
+    }
+    // Shutting down lets the pruning processor complete all pending pruning point advancements
+    syncer.shutdown(wait_handles);
+    assert_ne!(syncer.pruning_point(), config.genesis.hash);
+    let proof = syncer.get_pruning_point_proof();
+
+    let syncee = TestConsensus::new(&config);
+    let wait_handles = syncee.init();
+
+    // Cancel the validation as soon as the first progress report arrives
+    let cancel = AtomicBool::new(false);
+    let reports = parking_lot::Mutex::new(Vec::new());
+    let result = syncee.validate_pruning_proof_with_progress(
+        &proof,
+        &|progress| {
+            reports.lock().push(progress);
+            cancel.store(true, atomic::Ordering::Relaxed);
+        },
+        &cancel,
+    );
+    assert_match!(result, Err(PruningImportError::PruningValidationCancelled));
+    let first_report = reports.into_inner()[0];
+    assert!(first_report.processed_headers < first_report.total_headers);
+    assert!(first_report.processed_levels < first_report.total_levels);
+
+    // The cancelled validation must not leave any partial state behind
+    assert_eq!(syncee.pruning_point(), config.genesis.hash);
+    assert_eq!(syncee.get_headers_selected_tip(), config.genesis.hash);
+    assert!(proof
+        .iter()
+        .flatten()
+        .filter(|header| header.hash != config.genesis.hash)
+        .all(|header| syncee.get_header(header.hash).is_err()));
+
+    // Validation on the global pool must be identical to validating the levels one after the other on a single thread
+    let serial_pool = rayon::ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+    let reports = parking_lot::Mutex::new(Vec::new());
+    syncee.validate_pruning_proof_with_progress(&proof, &|progress| reports.lock().push(progress), &AtomicBool::new(false)).unwrap();
+    serial_pool.install(|| syncee.validate_pruning_proof(&proof)).unwrap();
+    let last_report = reports.into_inner().into_iter().max_by_key(|progress| progress.processed_levels).unwrap();
+    assert_eq!(last_report.processed_headers, last_report.total_headers);
+    assert_eq!(last_report.processed_levels, last_report.total_levels);
+
+    // Duplicate a header at every level holding enough headers. The failure of the highest such level is expected in both cases
+    let mut invalid_proof = (*proof).clone();
+    let mut highest_invalid_level = None;
+    for (level, headers) in invalid_proof.iter_mut().enumerate() {
+        if headers.len() > 2 {
+            headers.insert(2, headers[1].clone());
+            highest_invalid_level = Some(level as BlockLevel);
+        }
+    }
+    let parallel_err = syncee.validate_pruning_proof(&invalid_proof).unwrap_err();
+    let serial_err = serial_pool.install(|| syncee.validate_pruning_proof(&invalid_proof)).unwrap_err();
+    let PruningImportError::PruningProofDuplicateHeaderAtLevel(_, level) = parallel_err else {
+        panic!("unexpected error: {parallel_err}");
+    };
+    assert_eq!(Some(level), highest_invalid_level);
+    assert_eq!(format!("{parallel_err:?}"), format!("{serial_err:?}"));
+
+    syncee.shutdown(wait_handles);
+}
+
+/// Builds the trusted set of the current pruning point of `consensus` as done by the IBD flow of a syncee: the pruning point
+/// anticone blocks followed by the remaining DAA window blocks as header-only, sorted by blue work
+fn build_trusted_set(consensus: &TestConsensus) -> Vec<TrustedBlock> {
+    let trusted_data = consensus.get_pruning_point_anticone_and_trusted_data().unwrap();
+    let ghostdag_data = trusted_data
+        .ghostdag_blocks
+        .iter()
+        .map(|data| (data.hash, data.ghostdag.clone()))
+        .chain(trusted_data.daa_window_blocks.iter().map(|data| (data.header.hash, data.ghostdag.clone())))
+        .collect::<BlockHashMap<_>>();
+    let mut set = BlockHashSet::new();
+    let mut trusted_set = Vec::new();
+    for &hash in trusted_data.anticone.iter() {
+        if set.insert(hash) {
+            trusted_set.push(TrustedBlock::new(consensus.get_block(hash).unwrap(), ghostdag_data[&hash].clone()));
+        }
+    }
+    for data in trusted_data.daa_window_blocks.iter() {
+        if set.insert(data.header.hash) {
+            trusted_set.push(TrustedBlock::new(Block::from_header_arc(data.header.clone()), data.ghostdag.clone()));
+        }
+    }
+    for tb in trusted_set.iter_mut() {
+        tb.ghostdag.mergeset_blues.retain(|h| set.contains(h));
+        tb.ghostdag.mergeset_reds.retain(|h| set.contains(h));
+        tb.ghostdag.blues_anticone_sizes.retain(|k, _| set.contains(k));
+        if !set.contains(&tb.ghostdag.selected_parent) {
+            tb.ghostdag.selected_parent = blockhash::ORIGIN;
+        }
+    }
+    trusted_set.sort_by(|a, b| a.block.header.blue_work.cmp(&b.block.header.blue_work));
+    trusted_set
+}
+
+/// Imports `proof` and `trusted_set` on a new test consensus, either one trusted block after the other or in batches of
+/// `batch_size` blocks. Returns the consensus along with the number of BlockAdded notifications it sent and the time
+/// taken by the processing of the trusted blocks.
+async fn import_trusted_set(
+    config: &Config,
+    proof: &PruningPointProof,
+    pruning_points: &[Arc<Header>],
+    trusted_set: &[TrustedBlock],
+    batch_size: Option<usize>,
+) -> (TestConsensus, usize, Duration) {
+    let (notification_sender, notification_receiver) = unbounded();
+    let consensus = TestConsensus::with_notifier(config, notification_sender, SubscriptionContext::new());
+    let wait_handles = consensus.init();
+    consensus.notification_root().start_notify(ListenerId::default(), BlockAddedScope {}.into()).await.unwrap();
+    consensus.apply_pruning_proof(proof.clone(), trusted_set).unwrap();
+    consensus.import_pruning_points(pruning_points.to_vec());
+    let start = Instant::now();
+    match batch_size {
+        Some(batch_size) => {
+            let mut summary = TrustedBlocksSummary::default();
+            for batch in trusted_set.chunks(batch_size) {
+                summary += consensus.validate_and_insert_trusted_blocks(batch.to_vec()).await.unwrap();
+            }
+            let bodies = trusted_set.iter().filter(|tb| !tb.block.is_header_only()).count();
+            assert_eq!(summary, TrustedBlocksSummary { blocks: trusted_set.len(), bodies });
+        }
+        None => {
+            for tb in trusted_set.iter().cloned() {
+                consensus.validate_and_insert_trusted_block(tb).virtual_state_task.await.unwrap();
+            }
+        }
+    }
+    let elapsed = start.elapsed();
+    consensus.shutdown(wait_handles);
+    let block_added = std::iter::from_fn(|| notification_receiver.try_recv().ok())
+        .filter(|notification| matches!(notification, ConsensusNotification::BlockAdded(_)))
+        .count();
+    (consensus, block_added, elapsed)
+}
+
+/// Syncs the `goref_custom_pruning_depth` DAG on a new test consensus, whose pruning point advances so that it can serve
+/// a pruning point proof and trusted data. Returns the config of the DAG along with the synced consensus.
+async fn sync_custom_pruning_depth_dag() -> (Config, TestConsensus) {
+    let main_path = Path::new("testdata/dags_for_json_tests/goref_custom_pruning_depth");
+    let mut lines = gzip_file_lines(&main_path.join("blocks.json.gz"));
+    let go_params: KaspadGoParams = serde_json::from_str(&lines.next().unwrap()).unwrap();
+    let mut params = go_params.into_params();
+    let genesis_block = json_line_to_block(lines.next().unwrap());
+    params.genesis = (genesis_block.header.as_ref(), DEVNET_PARAMS.genesis.coinbase_payload).into();
+    params.min_difficulty_window_len = params.legacy_difficulty_window_size;
+    let config = Config::new(params);
+
+    let syncer = TestConsensus::new(&config);
+    let wait_handles = syncer.init();
+    for line in lines {
+        let block = json_line_to_block(line);
+        let hash = block.hash();
+        syncer.validate_and_insert_block(block).virtual_state_task.await.unwrap_or_else(|e| panic!("block {hash} failed: {e}"));
+    }
+    syncer.shutdown(wait_handles);
+    assert_ne!(syncer.pruning_point(), config.genesis.hash);
+    (config, syncer)
+}
+
+#[tokio::test]
+async fn trusted_blocks_batch_test() {
+    init_allocator_with_default_settings();
+    let (config, syncer) = sync_custom_pruning_depth_dag().await;
+    let proof = syncer.get_pruning_point_proof();
+    let pruning_points = syncer.pruning_point_headers();
+    let trusted_set = build_trusted_set(&syncer);
+    let bodies = trusted_set.iter().filter(|tb| !tb.block.is_header_only()).count();
+    assert!(bodies > 0 && bodies < trusted_set.len());
+
+    // Batches smaller than the trusted set make sure that dependencies across batches are handled as well
+    let (one_by_one, one_by_one_block_added, _) = import_trusted_set(&config, &proof, &pruning_points, &trusted_set, None).await;
+    let (batched, batched_block_added, _) = import_trusted_set(&config, &proof, &pruning_points, &trusted_set, Some(100)).await;
+
+    // Per-block notifications are suppressed for batches
+    assert_eq!(one_by_one_block_added, bodies);
+    assert_eq!(batched_block_added, 0);
+
+    // Both paths lead to the same store state
+    for tb in trusted_set.iter() {
+        let hash = tb.block.hash();
+        assert_eq!(one_by_one.get_block_status(hash), batched.get_block_status(hash));
+        assert_eq!(
+            one_by_one.get_block_even_if_header_only(hash).unwrap().transactions.len(),
+            batched.get_block_even_if_header_only(hash).unwrap().transactions.len()
+        );
+        assert_eq!(one_by_one.get_block_parents(hash), batched.get_block_parents(hash));
+        let sorted_children =
+            |consensus: &TestConsensus| consensus.get_block_children(hash).map(|children| children.into_iter().sorted().collect_vec());
+        assert_eq!(sorted_children(&one_by_one), sorted_children(&batched));
+        let (expected, actual) = (one_by_one.get_ghostdag_data(hash).unwrap(), batched.get_ghostdag_data(hash).unwrap());
+        assert_eq!(expected.blue_score, actual.blue_score);
+        assert_eq!(expected.blue_work, actual.blue_work);
+        assert_eq!(expected.selected_parent, actual.selected_parent);
+        assert_eq!(expected.mergeset_blues, actual.mergeset_blues);
+        assert_eq!(expected.mergeset_reds, actual.mergeset_reds);
+        assert_eq!(expected.blues_anticone_sizes, actual.blues_anticone_sizes);
+    }
+    assert_eq!(one_by_one.get_headers_selected_tip(), batched.get_headers_selected_tip());
+    assert_eq!(one_by_one.body_tips(), batched.body_tips());
+    assert_eq!(one_by_one.get_tips().into_iter().sorted().collect_vec(), batched.get_tips().into_iter().sorted().collect_vec());
+}
+
+/// Compares the throughput of the trusted blocks phase of IBD when processing the trusted set one block after the other
+/// and in batches. Run this benchmark with the following command line:
+/// `cargo test --release --package kaspa-testing-integration --lib -- consensus_integration_tests::bench_trusted_blocks_import --exact --nocapture --ignored`
+#[tokio::test]
+#[ignore = "bmk"]
+async fn bench_trusted_blocks_import() {
+    init_allocator_with_default_settings();
+    kaspa_core::log::try_init_logger("info");
+    const ROUNDS: usize = 5;
+    const BATCH_SIZE: usize = 1000;
+
+    let (config, syncer) = sync_custom_pruning_depth_dag().await;
+    let proof = syncer.get_pruning_point_proof();
+    let pruning_points = syncer.pruning_point_headers();
+    let trusted_set = build_trusted_set(&syncer);
+
+    let mut one_by_one_elapsed = Duration::ZERO;
+    let mut batched_elapsed = Duration::ZERO;
+    for _ in 0..ROUNDS {
+        one_by_one_elapsed += import_trusted_set(&config, &proof, &pruning_points, &trusted_set, None).await.2;
+        batched_elapsed += import_trusted_set(&config, &proof, &pruning_points, &trusted_set, Some(BATCH_SIZE)).await.2;
+    }
+    let blocks = (ROUNDS * trusted_set.len()) as f64;
+    info!(
+        "Processed {} trusted blocks: {:.0} blocks/s one by one, {:.0} blocks/s in batches of {} (speedup {:.2})",
+        trusted_set.len(),
+        blocks / one_by_one_elapsed.as_secs_f64(),
+        blocks / batched_elapsed.as_secs_f64(),
+        BATCH_SIZE,
+        one_by_one_elapsed.as_secs_f64() / batched_elapsed.as_secs_f64()
+    );
+}
+
+/// Syncs `blocks` on a new test consensus with the given checkpoints, shutting it down so that all pending pruning point advancements complete
+async fn sync_with_checkpoints(params: &Params, checkpoints: Vec<Checkpoint>, blocks: &[Block]) -> TestConsensus {
+    let mut config = Config::new(params.clone());
+    config.checkpoints = checkpoints;
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+    for block in blocks.iter().cloned() {
+        let hash = block.hash();
+        consensus.validate_and_insert_block(block).virtual_state_task.await.unwrap_or_else(|e| panic!("block {hash} failed: {e}"));
+    }
+    consensus.shutdown(wait_handles);
+    consensus
+}
+
+#[tokio::test]
+async fn pruning_points_checkpoints_test() {
+    init_allocator_with_default_settings();
+    let main_path = Path::new("testdata/dags_for_json_tests/goref_custom_pruning_depth");
+    let mut lines = gzip_file_lines(&main_path.join("blocks.json.gz"));
+    let go_params: KaspadGoParams = serde_json::from_str(&lines.next().unwrap()).unwrap();
+    let mut params = go_params.into_params();
+    let genesis_block = json_line_to_block(lines.next().unwrap());
+    params.genesis = (genesis_block.header.as_ref(), DEVNET_PARAMS.genesis.coinbase_payload).into();
+    params.min_difficulty_window_len = params.legacy_difficulty_window_size;
+    let blocks = lines.map(json_line_to_block).collect_vec();
+
+    let honest = sync_with_checkpoints(&params, vec![], &blocks).await;
+    let pruning_points = honest.pruning_point_headers();
+    assert!(pruning_points.len() > 2);
+    let checkpoint_index = pruning_points.len() / 2;
+    let checkpoint = Checkpoint::new(pruning_points[checkpoint_index].daa_score, pruning_points[checkpoint_index].hash);
+
+    assert!(validate_pruning_points_against_checkpoints(&[checkpoint], &pruning_points).is_ok());
+    assert!(honest.validate_pruning_points().is_ok());
+
+    // A fake chain going through another pruning point at the DAA score of the checkpoint
+    let mut fake_header = (*pruning_points[checkpoint_index]).clone();
+    fake_header.nonce += 1;
+    fake_header.finalize();
+    let fake_checkpoint = Checkpoint::new(fake_header.daa_score, fake_header.hash);
+    let mut fake_pruning_points = pruning_points.clone();
+    fake_pruning_points[checkpoint_index] = Arc::new(fake_header);
+    assert_match!(
+        validate_pruning_points_against_checkpoints(&[checkpoint], &fake_pruning_points),
+        Err(PruningImportError::PruningPointsCheckpointMismatch(_, _))
+    );
+
+    // From the POV of a node whose checkpoint pins the fake pruning point, the honest chain is the conflicting one
+    let conflicting = sync_with_checkpoints(&params, vec![fake_checkpoint], &blocks).await;
+    assert_match!(conflicting.validate_pruning_points(), Err(ConsensusError::General(_)));
+}
+
+/// Overwrites the DB record at `key` with bytes which fail deserialization, returning the original record
+fn corrupt_db_record(db: &DB, key: &DbKey) -> Vec<u8> {
+    let original = db.get(key).unwrap().expect("the record is expected to exist");
+    db.put(key, [0xff; 3]).unwrap();
+    original
+}
+
+/// Reopens the consensus over `db` with fresh caches, as on a node restart
+fn reopen_consensus(db: &Arc<DB>, config: &Config) -> TestConsensus {
+    let mut config = config.clone();
+    config.process_genesis = false;
+    TestConsensus::with_db(db.clone(), &config, unbounded().0)
+}
+
+#[tokio::test]
+async fn store_failure_propagation_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(MAINNET_PARAMS)
+        .skip_proof_of_work()
+        .edit_consensus_params(|p| {
+            p.min_difficulty_window_len = p.legacy_difficulty_window_size;
+        })
+        .build();
+    let (_db_lifetime, db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+
+    let consensus = TestConsensus::with_db(db.clone(), &config, unbounded().0);
+    let wait_handles = consensus.init();
+    for i in 1..=3u64 {
+        let parent = if i == 1 { config.genesis.hash } else { (i - 1).into() };
+        consensus.add_utxo_valid_block_with_parents(i.into(), vec![parent], vec![]).await.unwrap();
+    }
+    // Siblings submitted after the store failures below, built now since building reads the stores as well
+    let miner_data = MinerData::new(ScriptPublicKey::from_vec(0, vec![]), vec![]);
+    let [block4, block5, block6] = [4u64, 5, 6]
+        .map(|i| consensus.build_utxo_valid_block_with_parents(i.into(), vec![3.into()], miner_data.clone(), vec![]).to_immutable());
+    consensus.shutdown(wait_handles);
+
+    // A corrupted status of the parent fails the header of the child without marking it invalid
+    let statuses_key = DbKey::new(&[DatabaseStorePrefixes::Statuses.into()], Hash::from(3u64));
+    let original_status = corrupt_db_record(&db, &statuses_key);
+    let consensus = reopen_consensus(&db, &config);
+    let wait_handles = consensus.init();
+    let BlockValidationFutures { block_task, virtual_state_task } = consensus.validate_and_insert_block(block4.clone());
+    assert_match!(block_task.await, Err(RuleError::StoreFailure(..)));
+    assert_match!(virtual_state_task.await, Err(RuleError::StoreFailure(..)));
+    assert_eq!(consensus.get_block_status(block4.hash()), None);
+
+    // Once the record is repaired the same block is processed as usual
+    db.put(&statuses_key, original_status).unwrap();
+    assert_eq!(consensus.validate_and_insert_block(block4.clone()).virtual_state_task.await.unwrap(), BlockStatus::StatusUTXOValid);
+    assert_eq!(consensus.get_sink(), block4.hash());
+    consensus.shutdown(wait_handles);
+
+    // A corrupted virtual state fails virtual resolving while the block itself passes header and body validation
+    let virtual_state_key = DbKey::prefix_only(&[DatabaseStorePrefixes::VirtualState.into()]);
+    let original_virtual_state = corrupt_db_record(&db, &virtual_state_key);
+    let consensus = reopen_consensus(&db, &config);
+    let wait_handles = consensus.init();
+    let BlockValidationFutures { block_task, virtual_state_task } = consensus.validate_and_insert_block(block5.clone());
+    assert_eq!(block_task.await.unwrap(), BlockStatus::StatusUTXOPendingVerification);
+    assert_match!(virtual_state_task.await, Err(RuleError::StoreFailure(..)));
+    assert_eq!(consensus.get_block_status(block5.hash()), Some(BlockStatus::StatusUTXOPendingVerification));
+
+    // Virtual resolving is retried with the next block once the record is repaired, picking up the pending block as well
+    db.put(&virtual_state_key, original_virtual_state).unwrap();
+    consensus.validate_and_insert_block(block6.clone()).virtual_state_task.await.unwrap();
+    assert_eq!(consensus.get_virtual_parents(), BlockHashSet::from_iter([block4.hash(), block5.hash(), block6.hash()]));
+    consensus.shutdown(wait_handles);
+}
+
+#[tokio::test]
+async fn processing_parallelism_test() {
+    init_allocator_with_default_settings();
+    let config = ConfigBuilder::new(MAINNET_PARAMS)
+        .skip_proof_of_work()
+        .edit_consensus_params(|p| {
+            p.min_difficulty_window_len = p.legacy_difficulty_window_size;
+        })
+        .build();
+    let consensus = TestConsensus::new(&config);
+    let wait_handles = consensus.init();
+    let parallelism = consensus.processing_parallelism().clone();
+
+    // Layers of sibling blocks are submitted at once, each layer pointing at the previous one
+    let mut parents = vec![config.genesis.hash];
+    let mut next_hash = 0u64;
+    for limit in [1, 3] {
+        parallelism.set_limit(limit);
+        parallelism.take_peak_running();
+        for _ in 0..3 {
+            let hashes = (0..8)
+                .map(|_| {
+                    next_hash += 1;
+                    Hash::from(next_hash)
+                })
+                .collect_vec();
+            try_join_all(hashes.iter().map(|&hash| consensus.add_block_with_parents(hash, parents.clone()))).await.unwrap();
+            parents = hashes;
+        }
+        let peak = parallelism.take_peak_running();
+        if limit == 1 {
+            // Header and body validations are serialized
+            assert_eq!(peak, 1);
+        } else {
+            assert!((1..=limit).contains(&peak), "{peak} validations ran concurrently with a limit of {limit}");
+        }
+    }
+    assert_eq!(consensus.get_virtual_parents(), BlockHashSet::from_iter(parents));
+    consensus.shutdown(wait_handles);
+}
@@ -6,16 +6,56 @@ use crate::common::{
 };
 use kaspa_addresses::Address;
 use kaspa_alloc::init_allocator_with_default_settings;
-use kaspa_consensus::params::SIMNET_PARAMS;
+use kaspa_consensus::{
+    consensus::backup::verify_backup,
+    model::stores::headers::DbHeadersStore,
+    params::{SIMNET_GENESIS, SIMNET_PARAMS},
+};
+use kaspa_consensus_core::{
+    config::Config, errors::tx::CoinbaseMaturityShortfall, subnets::SUBNETWORK_ID_NATIVE, trusted::TrustedBlock, tx::Transaction,
+    BlockHashMap,
+};
 use kaspa_consensusmanager::ConsensusManager;
-use kaspa_core::{task::runtime::AsyncRuntime, trace};
-use kaspa_grpc_client::GrpcClient;
-use kaspa_notify::scope::{BlockAddedScope, UtxosChangedScope, VirtualDaaScoreChangedScope};
-use kaspa_rpc_core::{api::rpc::RpcApi, Notification, RpcTransactionId};
+use kaspa_core::{info, task::runtime::AsyncRuntime, trace};
+use kaspa_database::prelude::{CachePolicy, ConnBuilder};
+use kaspa_grpc_client::{
+    error::CallError,
+    pool::{GrpcPool, PoolEvent, PoolOptions},
+    retry::RetryPolicy,
+    GrpcClient,
+};
+use kaspa_muhash::MuHash;
+use kaspa_notify::scope::{BlockAddedScope, Scope, UtxosChangedScope, VirtualChainChangedScope, VirtualDaaScoreChangedScope};
+use kaspa_rpc_core::{
+    api::rpc::RpcApi, Notification, RpcSubmitTransactionStatus, RpcTransaction, RpcTransactionId, SubmitTransactionRequest,
+    UtxosChangedNotification, VirtualChainChangedNotification,
+};
 use kaspa_txscript::pay_to_address_script;
-use kaspad_lib::args::Args;
+use kaspa_utils::networking::ContextualNetAddress;
+use kaspa_wallet_core::{
+    endpoints::{Endpoint, EndpointMonitor},
+    error::Error as WalletError,
+    prelude::{
+        Account, AccountCreateArgs, AccountDiscoveryArgs, EncryptionKind, Events, Fees, Language, Mnemonic, PaymentOutputs,
+        PrvKeyDataCreateArgs, Secret, UtxoSelection, Wallet, WalletCreateArgs, WordCount,
+    },
+    rpc::Rpc,
+    utxo::{UtxoContext, UtxoContextBinding, UtxoProcessor},
+};
+use kaspa_wrpc_client::prelude::{ConnectOptions, ConnectStrategy, KaspaRpcClient, WrpcEncoding};
+use kaspad_lib::{
+    args::{Args, DevClusterCommand},
+    devcluster::{DevCluster, Topology},
+};
 use rand::thread_rng;
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use workflow_core::abortable::Abortable;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn daemon_sanity_test() {
@@ -42,6 +82,57 @@ async fn daemon_sanity_test() {
     kaspad2.shutdown();
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn daemon_manual_peers_test() {
+    init_allocator_with_default_settings();
+    kaspa_core::log::try_init_logger("INFO");
+
+    let args = Args {
+        devnet: true,
+        unsafe_rpc: true,
+        disable_upnp: true, // UPnP registration might take some time and is not needed for this test
+        ..Default::default()
+    };
+    let total_fd_limit = 10;
+    let mut kaspad1 = Daemon::new_random_with_args(args.clone(), total_fd_limit);
+    let mut kaspad2 = Daemon::new_random_with_args(args, total_fd_limit);
+    let rpc_client1 = kaspad1.start().await;
+    let rpc_client2 = kaspad2.start().await;
+
+    let peer_address: ContextualNetAddress = format!("127.0.0.1:{}", kaspad1.p2p_port).try_into().unwrap();
+    let peer_count = |client: &GrpcClient| {
+        let client = client.clone();
+        async move { client.get_connected_peer_info().await.unwrap().peer_info.len() }
+    };
+    rpc_client2.add_peer(peer_address, true).await.unwrap();
+    wait_for(100, 50, || async { peer_count(&rpc_client2).await == 1 }, "the manual peer was not connected").await;
+    assert!(rpc_client2.get_connected_peer_info().await.unwrap().peer_info[0].is_manual);
+
+    // The manual peer is dialed again after a restart
+    rpc_client2.disconnect().await.unwrap();
+    drop(rpc_client2);
+    let rpc_client2 = kaspad2.restart(total_fd_limit).await;
+    wait_for(100, 50, || async { peer_count(&rpc_client2).await == 1 }, "the manual peer was not redialed after restart").await;
+    let peer_info = rpc_client2.get_connected_peer_info().await.unwrap().peer_info;
+    assert!(peer_info[0].is_manual && peer_info[0].is_outbound);
+
+    // Once removed, the peer is disconnected and not dialed anymore
+    rpc_client2.remove_peer(peer_address).await.unwrap();
+    wait_for(100, 50, || async { peer_count(&rpc_client1).await == 0 }, "the removed peer was not disconnected").await;
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert_eq!(peer_count(&rpc_client2).await, 0);
+    assert_eq!(peer_count(&rpc_client1).await, 0);
+    assert!(rpc_client2.remove_peer(peer_address).await.is_err());
+
+    rpc_client1.disconnect().await.unwrap();
+    drop(rpc_client1);
+    kaspad1.shutdown();
+
+    rpc_client2.disconnect().await.unwrap();
+    drop(rpc_client2);
+    kaspad2.shutdown();
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn daemon_mining_test() {
     init_allocator_with_default_settings();
@@ -113,6 +204,318 @@ async fn daemon_mining_test() {
     }
 }
 
+/// Reserves `count` successive free local ports, released when the returned listeners are dropped
+fn reserve_port_range(count: u16) -> Vec<std::net::TcpListener> {
+    loop {
+        let first = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = first.local_addr().unwrap().port();
+        let Some(last) = base.checked_add(count - 1) else { continue };
+        let others = (base + 1..=last).map_while(|port| std::net::TcpListener::bind(("127.0.0.1", port)).ok()).collect::<Vec<_>>();
+        if others.len() == count as usize - 1 {
+            return std::iter::once(first).chain(others).collect();
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn daemon_devcluster_test() {
+    init_allocator_with_default_settings();
+    kaspa_core::log::try_init_logger("INFO");
+
+    const NODES: usize = 3;
+    let rpc_ports = reserve_port_range(NODES as u16);
+    let p2p_ports = reserve_port_range(NODES as u16);
+    let base_port = |ports: &[std::net::TcpListener]| ports[0].local_addr().unwrap().port();
+    let args = Args {
+        simnet: true,
+        enable_unsynced_mining: true,
+        rpclisten: Some(format!("127.0.0.1:{}", base_port(&rpc_ports)).parse().unwrap()),
+        listen: Some(format!("127.0.0.1:{}", base_port(&p2p_ports)).parse().unwrap()),
+        ..Default::default()
+    };
+    drop((rpc_ports, p2p_ports));
+    let command = DevClusterCommand { nodes: NODES, topology: Topology::Chain };
+    let cluster = DevCluster::new(&Default::default(), &args, &command, 30).unwrap();
+    cluster.start();
+    // Wait for the nodes to initialize before connecting to RPC
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    let mut clients = vec![];
+    for node in 0..NODES {
+        let port = cluster.args(node).rpclisten.unwrap().normalize(0).port;
+        clients.push(GrpcClient::connect(format!("grpc://localhost:{port}")).await.unwrap());
+    }
+    // In a chain, the nodes at both ends have a single peer
+    let expected_peers = [1, 2, 1];
+    for (client, expected) in clients.iter().cloned().zip(expected_peers) {
+        wait_for(
+            100,
+            50,
+            move || {
+                let client = client.clone();
+                async move { client.get_connected_peer_info().await.unwrap().peer_info.len() == expected }
+            },
+            "the cluster nodes did not connect",
+        )
+        .await;
+    }
+
+    // Mine on node 0 and expect the blocks to be relayed through node 1 to node 2
+    let address = Address::new(kaspa_addresses::Prefix::Simnet, kaspa_addresses::Version::PubKey, &[0; 32]);
+    for _ in 0..10 {
+        mine_block(address.clone(), &clients[0], &[]).await;
+    }
+    let sink = clients[0].get_sink().await.unwrap().sink;
+    for client in clients[1..].iter().cloned() {
+        wait_for(
+            100,
+            100,
+            move || {
+                let client = client.clone();
+                async move { client.get_sink().await.unwrap().sink == sink }
+            },
+            "the cluster nodes did not converge to the same sink",
+        )
+        .await;
+    }
+
+    for client in clients {
+        client.disconnect().await.unwrap();
+    }
+    // A node can be stopped alone, the others being stopped when the cluster is dropped
+    cluster.shutdown_node(NODES - 1);
+    let root = cluster.root().to_path_buf();
+    drop(cluster);
+    assert!(!root.exists());
+}
+
+#[cfg(feature = "stratum")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn daemon_stratum_mining_test() {
+    use serde_json::{json, Value};
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines},
+        net::tcp::OwnedReadHalf,
+    };
+
+    async fn next_message(lines: &mut Lines<BufReader<OwnedReadHalf>>) -> Value {
+        let line = tokio::time::timeout(Duration::from_secs(5), lines.next_line()).await.unwrap().unwrap().unwrap();
+        serde_json::from_str(&line).unwrap()
+    }
+
+    init_allocator_with_default_settings();
+    kaspa_core::log::try_init_logger("INFO");
+
+    let stratum_port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let args = Args {
+        simnet: true,
+        unsafe_rpc: true,
+        enable_unsynced_mining: true,
+        disable_upnp: true, // UPnP registration might take some time and is not needed for this test
+        stratum_listen: Some(format!("127.0.0.1:{stratum_port}").parse().unwrap()),
+        ..Default::default()
+    };
+    let mut kaspad = Daemon::new_random_with_args(args, 10);
+    let rpc_client = kaspad.start().await;
+
+    let stream = tokio::net::TcpStream::connect(("127.0.0.1", stratum_port)).await.unwrap();
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    let address = Address::new(kaspad.network.into(), kaspa_addresses::Version::PubKey, &[0; 32]);
+
+    let subscribe = json!({ "id": 1, "method": "mining.subscribe", "params": ["test-miner"] });
+    let authorize = json!({ "id": 2, "method": "mining.authorize", "params": [format!("{address}.worker"), "x"] });
+    writer.write_all(format!("{subscribe}\n{authorize}\n").as_bytes()).await.unwrap();
+
+    // Wait for the first job, then submit nonces until one solves the block, which with the simnet target takes a few attempts
+    let job_id = loop {
+        let message = next_message(&mut lines).await;
+        if message["method"] == "mining.notify" {
+            break message["params"][0].as_str().unwrap().to_string();
+        }
+        assert!(message["error"].is_null(), "unexpected error {message}");
+    };
+    let mut solved = false;
+    for nonce in 0..64u64 {
+        let id = 100 + nonce;
+        let request = json!({ "id": id, "method": "mining.submit", "params": ["worker", job_id, format!("{nonce:012x}")] });
+        writer.write_all(format!("{request}\n").as_bytes()).await.unwrap();
+        let response = loop {
+            let message = next_message(&mut lines).await;
+            if message["id"] == id {
+                break message;
+            }
+        };
+        if response["result"] == true {
+            solved = true;
+            break;
+        }
+        assert_eq!(response["error"][0], 23, "only low difficulty shares are expected to be rejected");
+    }
+    assert!(solved, "expected a nonce solving the block");
+
+    // Expect the solved block to be added to the DAG
+    wait_for(
+        100,
+        50,
+        || async { rpc_client.get_block_dag_info().await.unwrap().block_count == 1 },
+        "the block mined through the stratum server was not added to the DAG",
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn daemon_virtual_info_test() {
+    init_allocator_with_default_settings();
+    kaspa_core::log::try_init_logger("INFO");
+
+    let args = Args {
+        simnet: true,
+        unsafe_rpc: true,
+        enable_unsynced_mining: true,
+        disable_upnp: true, // UPnP registration might take some time and is not needed for this test
+        ..Default::default()
+    };
+    let total_fd_limit = 10;
+    let mut kaspad1 = Daemon::new_random_with_args(args, total_fd_limit);
+    let consensus_manager = Arc::downcast::<ConsensusManager>(kaspad1.core.find(ConsensusManager::IDENT).unwrap().arc_any()).unwrap();
+    let rpc_client1 = kaspad1.start().await;
+
+    let (sender, event_receiver) = async_channel::unbounded();
+    rpc_client1.start(Some(Arc::new(ChannelNotify::new(sender)))).await;
+    rpc_client1.start_notify(Default::default(), VirtualDaaScoreChangedScope {}.into()).await.unwrap();
+
+    // Mine a few blocks, the DAA score notifications carrying the virtual info
+    let pay_address = Address::new(kaspad1.network.into(), kaspa_addresses::Version::PubKey, &[0; 32]);
+    for i in 0..5 {
+        let template = rpc_client1.get_block_template(pay_address.clone(), vec![]).await.unwrap();
+        rpc_client1.submit_block(template.block, false).await.unwrap();
+        loop {
+            let notification = match tokio::time::timeout(Duration::from_secs(1), event_receiver.recv()).await {
+                Ok(res) => res.unwrap(),
+                Err(elapsed) => panic!("expected virtual event before {}", elapsed),
+            };
+            let Notification::VirtualDaaScoreChanged(msg) = notification else { panic!("expected only DAA score notifications") };
+            assert_eq!(msg.virtual_info.expect("the node populates the virtual info").daa_score, msg.virtual_daa_score);
+            if msg.virtual_daa_score == i + 1 {
+                break;
+            }
+        }
+    }
+
+    // The virtual info matches the values of the heavier calls
+    let virtual_info = rpc_client1.get_virtual_info().await.unwrap();
+    let dag_info = rpc_client1.get_block_dag_info().await.unwrap();
+    assert_eq!(virtual_info.daa_score, dag_info.virtual_daa_score);
+    assert_eq!(virtual_info.sink, dag_info.sink);
+    assert_eq!(virtual_info.parents_count as usize, dag_info.virtual_parent_hashes.len());
+    assert_eq!(virtual_info.past_median_time, dag_info.past_median_time);
+    assert_eq!(virtual_info.sink_blue_score, rpc_client1.get_sink_blue_score().await.unwrap());
+
+    // Hold the session write lock, as data pruning does, until released
+    let session_lock = consensus_manager.consensus().session_lock();
+    let (locked_sender, locked_receiver) = tokio::sync::oneshot::channel();
+    let (release_sender, release_receiver) = std::sync::mpsc::channel::<()>();
+    let lock_holder = std::thread::spawn(move || {
+        let _write_guard = session_lock.blocking_write();
+        locked_sender.send(()).unwrap();
+        release_receiver.recv().ok();
+    });
+    locked_receiver.await.unwrap();
+
+    // A guarded call waits for the lock...
+    let guarded_call = tokio::spawn({
+        let client = rpc_client1.clone();
+        async move { client.get_virtual_chain_from_block(SIMNET_GENESIS.hash, false).await }
+    });
+
+    // ...while GetVirtualInfo keeps being served
+    const CALLS: u32 = 100;
+    let start = Instant::now();
+    for _ in 0..CALLS {
+        let info = match tokio::time::timeout(Duration::from_secs(1), rpc_client1.get_virtual_info()).await {
+            Ok(res) => res.unwrap(),
+            Err(_) => panic!("GetVirtualInfo waited for the session lock"),
+        };
+        assert_eq!(info, virtual_info);
+    }
+    info!("GetVirtualInfo average latency with the session lock held: {:?}", start.elapsed() / CALLS);
+    assert!(!guarded_call.is_finished(), "the guarded call should wait for the session lock");
+
+    release_sender.send(()).unwrap();
+    lock_holder.join().unwrap();
+    let virtual_chain = tokio::time::timeout(Duration::from_secs(1), guarded_call).await.unwrap().unwrap().unwrap();
+    assert_eq!(virtual_chain.added_chain_block_hashes.len(), 5);
+
+    rpc_client1.disconnect().await.unwrap();
+    drop(rpc_client1);
+    kaspad1.shutdown();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn daemon_backup_test() {
+    init_allocator_with_default_settings();
+    kaspa_core::log::try_init_logger("INFO");
+
+    let args = Args { simnet: true, unsafe_rpc: true, enable_unsynced_mining: true, disable_upnp: true, ..Default::default() };
+    let total_fd_limit = 10;
+    let mut kaspad = Daemon::new_random_with_args(args, total_fd_limit);
+    let rpc_client = kaspad.start().await;
+    let pay_address = Address::new(kaspad.network.into(), kaspa_addresses::Version::PubKey, &[0; 32]);
+
+    // Keep mining during the backup
+    let stop_mining = Arc::new(AtomicBool::new(false));
+    let miner = {
+        let (rpc_client, stop_mining) = (rpc_client.clone(), stop_mining.clone());
+        tokio::spawn(async move {
+            let mut mined = 0;
+            while !stop_mining.load(Ordering::Relaxed) {
+                let template = rpc_client.get_block_template(pay_address.clone(), vec![]).await.unwrap();
+                rpc_client.submit_block(template.block, false).await.unwrap();
+                mined += 1;
+            }
+            mined
+        })
+    };
+    let client = rpc_client.clone();
+    wait_for(50, 100, move || has_block_count(client.clone(), 10), "the miner failed to mine 10 blocks").await;
+
+    let backup_root = tempfile::tempdir().unwrap();
+    let backup_dir = backup_root.path().join("backup");
+    let backup = rpc_client.create_backup(backup_dir.to_str().unwrap().to_string()).await.unwrap();
+    let client = rpc_client.clone();
+    let target_block_count = rpc_client.get_block_dag_info().await.unwrap().block_count + 10;
+    wait_for(50, 100, move || has_block_count(client.clone(), target_block_count), "the miner failed to keep mining").await;
+    stop_mining.store(true, Ordering::Relaxed);
+    assert!(miner.await.unwrap() >= 20);
+
+    // The backup directory cannot be overwritten, and relative paths are rejected
+    assert!(rpc_client.create_backup(backup_dir.to_str().unwrap().to_string()).await.is_err());
+    assert!(rpc_client.create_backup("backup".to_string()).await.is_err());
+
+    assert_ne!(backup.sink, SIMNET_GENESIS.hash);
+    assert!(rpc_client.get_block(backup.sink, false).await.is_ok(), "the backed up sink is a block of the node");
+    assert_ne!(rpc_client.get_block_dag_info().await.unwrap().sink, backup.sink, "blocks mined after the backup are not part of it");
+
+    // The backup opens on its own and passes the consistency checker
+    let config = Arc::new(Config::new(SIMNET_PARAMS));
+    let manifest = verify_backup(&backup_dir, config.clone()).unwrap();
+    assert_eq!((manifest.sink, manifest.pruning_point, manifest.timestamp), (backup.sink, backup.pruning_point, backup.timestamp));
+    assert_eq!(manifest.params_digest, config.params.digest());
+    let consensus_db =
+        ConnBuilder::default().with_db_path(backup_dir.join(&manifest.consensus_dir)).with_files_limit(10).build_read_only().unwrap();
+    let headers_store = DbHeadersStore::new(consensus_db, CachePolicy::Empty, CachePolicy::Empty);
+    assert!(headers_store.has(manifest.sink).unwrap(), "the backup holds the manifest sink");
+
+    rpc_client.disconnect().await.unwrap();
+    drop(rpc_client);
+    kaspad.shutdown();
+}
+
+async fn has_block_count(client: GrpcClient, block_count: u64) -> bool {
+    client.get_block_dag_info().await.unwrap().block_count >= block_count
+}
+
 /// `cargo test --release --package kaspa-testing-integration --lib -- daemon_integration_tests::daemon_utxos_propagation_test`
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn daemon_utxos_propagation_test() {
@@ -261,6 +664,28 @@ async fn daemon_utxos_propagation_test() {
         assert_eq!(utxo.1.script_public_key, miner_spk);
     }
 
+    // Spending a coinbase UTXO one block before its maturity is rejected with the exact DAA scores
+    let virtual_daa_score = rpc_client1.get_server_info().await.unwrap().virtual_daa_score;
+    let immature_utxo = rpc_client1
+        .get_utxos_by_addresses(vec![miner_address.clone()])
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|entry| entry.utxo_entry.block_daa_score + coinbase_maturity == virtual_daa_score + 1)
+        .map(|entry| (entry.outpoint, entry.utxo_entry))
+        .unwrap();
+    let immature_transaction = generate_tx(
+        miner_schnorr_key,
+        &[immature_utxo.clone()],
+        SIMNET_PARAMS.pre_deflationary_phase_base_subsidy / 2,
+        1,
+        &user_address,
+    );
+    let err = rpc_client1.submit_transaction((&immature_transaction).into(), false).await.unwrap_err();
+    let shortfall = CoinbaseMaturityShortfall::from_error_message(&err.to_string()).unwrap();
+    assert_eq!(shortfall, CoinbaseMaturityShortfall::new(immature_utxo.1.block_daa_score, virtual_daa_score, coinbase_maturity));
+    assert_eq!(shortfall.remaining(), 1);
+
     // Drain UTXOs and Virtual DAA score changed notification channels
     clients.iter().for_each(|x| x.utxos_changed_listener().unwrap().drain());
     clients.iter().for_each(|x| x.virtual_daa_score_changed_listener().unwrap().drain());
@@ -288,6 +713,14 @@ async fn daemon_utxos_propagation_test() {
     )
     .await;
 
+    // Resubmitting a transaction of the mempool succeeds, echoing the idempotency key
+    let idempotency_key = Some("payout-1".to_string());
+    let resubmission = SubmitTransactionRequest::new((&transaction).into(), false).with_idempotency_key(idempotency_key.clone());
+    let response = rpc_client1.submit_transaction_call(resubmission.clone()).await.unwrap();
+    assert_eq!(response.transaction_id, transaction_id);
+    assert_eq!(response.status, RpcSubmitTransactionStatus::AlreadyKnown);
+    assert_eq!(response.idempotency_key, idempotency_key);
+
     mine_block(blank_address.clone(), &rpc_client1, &clients).await;
 
     // Check UTXOs changed notifications
@@ -315,6 +748,27 @@ async fn daemon_utxos_propagation_test() {
         assert_eq!(user_balance, TX_AMOUNT);
     }
 
+    // The transaction gets accepted by the selected chain block merging the block including it
+    mine_block(blank_address.clone(), &rpc_client1, &clients).await;
+    let accepting_block_hash = rpc_client1.get_block_dag_info().await.unwrap().sink;
+    let check_client = rpc_client1.clone();
+    wait_for(
+        50,
+        20,
+        move || {
+            async fn transaction_accepted(client: GrpcClient, request: SubmitTransactionRequest) -> bool {
+                let response = client.submit_transaction_call(request).await.unwrap();
+                response.status == RpcSubmitTransactionStatus::AlreadyAccepted
+            }
+            Box::pin(transaction_accepted(check_client.clone(), resubmission.clone()))
+        },
+        "the transaction was not reported as accepted",
+    )
+    .await;
+    let response = rpc_client1.submit_transaction_call(resubmission).await.unwrap();
+    assert_eq!(response.accepting_block_hash, Some(accepting_block_hash));
+    assert_eq!(response.idempotency_key, idempotency_key);
+
     // Terminate multi-listener clients
     for x in clients.iter() {
         x.disconnect().await.unwrap();
@@ -351,3 +805,990 @@ async fn daemon_cleaning_test() {
     assert_eq!(async_runtime.strong_count(), 0);
     assert_eq!(core.strong_count(), 0);
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn daemon_wrpc_message_size_limits_test() {
+    init_allocator_with_default_settings();
+    kaspa_core::log::try_init_logger("INFO");
+
+    let args = Args {
+        simnet: true,
+        unsafe_rpc: true,
+        enable_unsynced_mining: true,
+        disable_upnp: true, // UPnP registration might take some time and is not needed for this test
+        wrpc_max_inbound_message_size: 64 * 1024,
+        wrpc_max_outbound_message_size: Some(4 * 1024),
+        ..Default::default()
+    };
+    let total_fd_limit = 10;
+    let mut kaspad = Daemon::new_random_with_args(args, total_fd_limit);
+    let grpc_client = kaspad.start().await;
+
+    // Mine enough blocks for a full GetBlocks response to exceed the outbound limit
+    let miner_address = Address::new(kaspad.network.into(), kaspa_addresses::Version::PubKey, &[0; 32]);
+    for _ in 0..20 {
+        let template = grpc_client.get_block_template(miner_address.clone(), vec![]).await.unwrap();
+        grpc_client.submit_block(template.block, false).await.unwrap();
+    }
+
+    let wrpc_port = kaspad
+        .args
+        .read()
+        .rpclisten_borsh
+        .as_ref()
+        .unwrap()
+        .to_address(&kaspad.network.network_type, &WrpcEncoding::Borsh)
+        .normalize(0)
+        .port;
+    let wrpc_client =
+        KaspaRpcClient::new(WrpcEncoding::Borsh, Some(&format!("ws://127.0.0.1:{wrpc_port}")), None, None, None).unwrap();
+    let options = ConnectOptions { block_async_connect: true, strategy: ConnectStrategy::Fallback, ..Default::default() };
+    wrpc_client.connect(Some(options)).await.unwrap();
+
+    // Responses within the limit are served
+    wrpc_client.get_server_info().await.unwrap();
+
+    // A response exceeding the outbound limit is replaced by an error and the connection is kept
+    let err = wrpc_client.get_blocks(None, true, true).await.unwrap_err();
+    assert!(err.to_string().contains("exceeds the maximum message size"), "unexpected error: {err}");
+    assert!(wrpc_client.get_blocks(None, false, false).await.is_ok());
+
+    // An inbound message exceeding the inbound limit closes the connection
+    let oversized_tx = Transaction::new(0, vec![], vec![], 0, SUBNETWORK_ID_NATIVE, 0, vec![0; 128 * 1024]);
+    let result =
+        tokio::time::timeout(Duration::from_secs(10), wrpc_client.submit_transaction(RpcTransaction::from(&oversized_tx), false))
+            .await;
+    assert!(!matches!(result, Ok(Ok(_))), "an oversized message must not be processed");
+    wait_for(50, 100, || async { !wrpc_client.is_connected() }, "the oversized message did not close the connection").await;
+    wrpc_client.disconnect().await.unwrap();
+
+    // The node keeps serving other clients
+    grpc_client.get_server_info().await.unwrap();
+    grpc_client.disconnect().await.unwrap();
+    drop(grpc_client);
+    kaspad.shutdown();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn daemon_wallet_account_discovery_test() {
+    init_allocator_with_default_settings();
+    kaspa_core::log::try_init_logger("INFO");
+
+    let args = Args {
+        simnet: true,
+        unsafe_rpc: true,
+        enable_unsynced_mining: true,
+        disable_upnp: true, // UPnP registration might take some time and is not needed for this test
+        utxoindex: true,
+        ..Default::default()
+    };
+    let total_fd_limit = 10;
+    let mut kaspad = Daemon::new_random_with_args(args, total_fd_limit);
+    let grpc_client = kaspad.start().await;
+    let network_id = kaspad.network;
+
+    let wallet_secret = Secret::from("secret");
+    let mnemonic = Mnemonic::random(WordCount::Words24, Language::English).unwrap();
+
+    // Derive the accounts to be funded using an offline wallet holding the same mnemonic
+    let funded_account_indexes = [0, 2, 5];
+    let funding_wallet = Arc::new(Wallet::try_with_rpc(None, Wallet::resident_store().unwrap(), Some(network_id)).unwrap());
+    funding_wallet
+        .create_wallet(&wallet_secret, WalletCreateArgs::new(None, None, EncryptionKind::XChaCha20Poly1305, None, false))
+        .await
+        .unwrap();
+    let prv_key_data_id = funding_wallet
+        .create_prv_key_data(&wallet_secret, PrvKeyDataCreateArgs::new(None, None, Secret::from(mnemonic.phrase_string())))
+        .await
+        .unwrap();
+    let mut funded_accounts = vec![];
+    for account_index in funded_account_indexes {
+        let account_args = AccountCreateArgs::new_bip32(prv_key_data_id, None, None, Some(account_index));
+        funded_accounts.push(funding_wallet.create_account(&wallet_secret, account_args, false).await.unwrap());
+    }
+
+    // Fund the accounts by mining a block to the first receive address of each of them,
+    // followed by a few blocks paying out the rewards of the former
+    let miner_addresses = funded_accounts
+        .iter()
+        .map(|account| account.receive_address().unwrap())
+        .chain(std::iter::repeat(Address::new(network_id.into(), kaspa_addresses::Version::PubKey, &[0; 32])).take(3))
+        .collect::<Vec<_>>();
+    for miner_address in miner_addresses {
+        let template = grpc_client.get_block_template(miner_address, vec![]).await.unwrap();
+        grpc_client.submit_block(template.block, false).await.unwrap();
+    }
+    let funded_addresses = funded_accounts.iter().map(|account| account.receive_address().unwrap()).collect::<Vec<_>>();
+    wait_for(
+        50,
+        100,
+        || async {
+            let utxos = grpc_client.get_utxos_by_addresses(funded_addresses.clone()).await.unwrap();
+            funded_addresses.iter().all(|address| utxos.iter().any(|entry| entry.address.as_ref() == Some(address)))
+        },
+        "the funded accounts did not receive their rewards",
+    )
+    .await;
+
+    // Connect a new wallet to the node and import the mnemonic
+    let wallet = Arc::new(Wallet::try_new(Wallet::resident_store().unwrap(), None, Some(network_id)).unwrap());
+    wallet
+        .create_wallet(&wallet_secret, WalletCreateArgs::new(None, None, EncryptionKind::XChaCha20Poly1305, None, false))
+        .await
+        .unwrap();
+    wallet.start().await.unwrap();
+    let wrpc_port = kaspad
+        .args
+        .read()
+        .rpclisten_borsh
+        .as_ref()
+        .unwrap()
+        .to_address(&kaspad.network.network_type, &WrpcEncoding::Borsh)
+        .normalize(0)
+        .port;
+    let options = ConnectOptions {
+        block_async_connect: true,
+        strategy: ConnectStrategy::Fallback,
+        url: Some(format!("ws://127.0.0.1:{wrpc_port}")),
+        ..Default::default()
+    };
+    wallet.try_wrpc_client().unwrap().connect(Some(options)).await.unwrap();
+    wait_for(50, 100, || async { wallet.is_connected() }, "the wallet did not connect to the node").await;
+
+    let channel = wallet.multiplexer().channel();
+    let discovery_args = AccountDiscoveryArgs::new(20, 3);
+    let accounts = wallet.import_with_mnemonic_and_discovery(&wallet_secret, None, mnemonic, discovery_args).await.unwrap();
+
+    // Exactly the funded accounts are created
+    let account_ids = accounts.iter().map(|account| *account.id()).collect::<Vec<_>>();
+    let funded_account_ids = funded_accounts.iter().map(|account| *account.id()).collect::<Vec<_>>();
+    assert_eq!(account_ids, funded_account_ids);
+
+    // The discovery stops after 3 consecutive accounts without activity following account #5
+    let mut scanned_accounts = vec![];
+    while let Ok(event) = channel.receiver.try_recv() {
+        if let Events::AccountDiscovery { account_index, is_active } = *event {
+            scanned_accounts.push((account_index, is_active));
+        }
+    }
+    let expected_scanned_accounts = (0..=8).map(|index| (index, funded_account_indexes.contains(&index))).collect::<Vec<_>>();
+    assert_eq!(scanned_accounts, expected_scanned_accounts);
+
+    wallet.stop().await.unwrap();
+    wallet.try_wrpc_client().unwrap().disconnect().await.unwrap();
+    grpc_client.disconnect().await.unwrap();
+    drop(grpc_client);
+    kaspad.shutdown();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn daemon_utxo_processor_reconnection_test() {
+    init_allocator_with_default_settings();
+    kaspa_core::log::try_init_logger("INFO");
+
+    let args = Args {
+        simnet: true,
+        unsafe_rpc: true,
+        enable_unsynced_mining: true,
+        disable_upnp: true, // UPnP registration might take some time and is not needed for this test
+        utxoindex: true,
+        ..Default::default()
+    };
+    let total_fd_limit = 10;
+    let mut kaspad = Daemon::new_random_with_args(args, total_fd_limit);
+    let client_manager = kaspad.client_manager();
+    let grpc_client = kaspad.start().await;
+    let network_id = kaspad.network;
+
+    // Connect a standalone UtxoProcessor to the node, letting the wRPC client reconnect on its own
+    let wrpc_port = kaspad
+        .args
+        .read()
+        .rpclisten_borsh
+        .as_ref()
+        .unwrap()
+        .to_address(&kaspad.network.network_type, &WrpcEncoding::Borsh)
+        .normalize(0)
+        .port;
+    let url = format!("ws://127.0.0.1:{wrpc_port}");
+    let wrpc_client = Arc::new(KaspaRpcClient::new(WrpcEncoding::Borsh, Some(&url), None, None, None).unwrap());
+    let rpc = Rpc::new(wrpc_client.clone(), wrpc_client.rpc_ctl().clone());
+    let processor = UtxoProcessor::new(Some(rpc), Some(network_id), None, None);
+    let channel = processor.multiplexer().channel();
+    processor.start().await.unwrap();
+    let options = ConnectOptions { block_async_connect: true, strategy: ConnectStrategy::Retry, url: Some(url), ..Default::default() };
+    wrpc_client.connect(Some(options)).await.unwrap();
+    wait_for(50, 100, || async { processor.is_connected() }, "the utxo processor did not connect to the node").await;
+
+    let address = Address::new(network_id.into(), kaspa_addresses::Version::PubKey, &[1; 32]);
+    let context = UtxoContext::new(&processor, UtxoContextBinding::default());
+    context.scan_and_register_addresses(vec![address.clone()], None).await.unwrap();
+    let utxo_count = |context: &UtxoContext| {
+        context
+            .balance()
+            .map(|balance| balance.mature_utxo_count + balance.pending_utxo_count + balance.stasis_utxo_count)
+            .unwrap_or(0)
+    };
+
+    // Mine a block to the tracked address, followed by a few blocks paying out its reward
+    let mine_reward = |grpc_client: &GrpcClient| {
+        let grpc_client = grpc_client.clone();
+        let address = address.clone();
+        async move {
+            mine_block(address, &grpc_client, &[]).await;
+            for _ in 0..3 {
+                mine_block(Address::new(network_id.into(), kaspa_addresses::Version::PubKey, &[0; 32]), &grpc_client, &[]).await;
+            }
+        }
+    };
+    let wait_for_event = |predicate: fn(&Events) -> bool| {
+        let receiver = channel.receiver.clone();
+        async move {
+            loop {
+                let event = tokio::time::timeout(Duration::from_secs(30), receiver.recv()).await.unwrap().unwrap();
+                if predicate(&event) {
+                    break;
+                }
+            }
+        }
+    };
+    mine_reward(&grpc_client).await;
+    wait_for(50, 100, || async { utxo_count(&context) > 0 }, "the utxo context did not receive the reward").await;
+
+    // Stop the node: the registered contexts are retained for a resync
+    grpc_client.disconnect().await.unwrap();
+    drop(grpc_client);
+    kaspad.shutdown();
+    wait_for_event(|event| matches!(event, Events::Disconnect { resynced: true, .. })).await;
+    assert!(!processor.is_connected());
+    drop(kaspad);
+
+    // Restart a node on the same ports. It runs a fresh DAG, so the resync removes the previously received reward.
+    let mut kaspad = Daemon::with_manager(client_manager, total_fd_limit);
+    let grpc_client = kaspad.start().await;
+    wait_for_event(|event| matches!(event, Events::Connect { resynced: true, .. })).await;
+    assert_eq!(utxo_count(&context), 0);
+
+    // The context keeps tracking its address without being registered again
+    mine_reward(&grpc_client).await;
+    wait_for(50, 100, || async { utxo_count(&context) > 0 }, "the utxo context did not receive the reward after reconnecting").await;
+
+    processor.stop().await.unwrap();
+    wrpc_client.disconnect().await.unwrap();
+    grpc_client.disconnect().await.unwrap();
+    drop(grpc_client);
+    kaspad.shutdown();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn daemon_endpoint_failover_test() {
+    init_allocator_with_default_settings();
+    kaspa_core::log::try_init_logger("INFO");
+
+    let args = Args {
+        simnet: true,
+        unsafe_rpc: true,
+        enable_unsynced_mining: true,
+        disable_upnp: true, // UPnP registration might take some time and is not needed for this test
+        utxoindex: true,
+        ..Default::default()
+    };
+    let total_fd_limit = 10;
+    let mut kaspad1 = Daemon::new_random_with_args(args.clone(), total_fd_limit);
+    let mut kaspad2 = Daemon::new_random_with_args(args, total_fd_limit);
+    let grpc_client1 = kaspad1.start().await;
+    let grpc_client2 = kaspad2.start().await;
+    let network_id = kaspad1.network;
+
+    let wrpc_url = |kaspad: &Daemon| {
+        let port = kaspad
+            .args
+            .read()
+            .rpclisten_borsh
+            .as_ref()
+            .unwrap()
+            .to_address(&kaspad.network.network_type, &WrpcEncoding::Borsh)
+            .normalize(0)
+            .port;
+        format!("ws://127.0.0.1:{port}")
+    };
+    let first = Endpoint::new("first", &wrpc_url(&kaspad1), 0);
+    let second = Endpoint::new("second", &wrpc_url(&kaspad2), 1);
+
+    // Point a standalone UtxoProcessor to the first endpoint, probing the endpoints every half second
+    let wrpc_client = Arc::new(KaspaRpcClient::new(WrpcEncoding::Borsh, None, None, None, None).unwrap());
+    let rpc = Rpc::new(wrpc_client.clone(), wrpc_client.rpc_ctl().clone());
+    let processor = UtxoProcessor::new(Some(rpc), Some(network_id), None, None);
+    let channel = processor.multiplexer().channel();
+    processor.start().await.unwrap();
+    let monitor = EndpointMonitor::new(wrpc_client.clone(), &processor, Duration::from_millis(500), Duration::from_secs(2));
+    monitor.set_endpoints(vec![second.clone(), first.clone()]);
+    monitor.start().await.unwrap();
+    monitor.select(&first.name).await.unwrap();
+    let options = ConnectOptions { block_async_connect: true, strategy: ConnectStrategy::Retry, ..Default::default() };
+    wrpc_client.connect(Some(options)).await.unwrap();
+    wait_for(50, 100, || async { processor.is_connected() }, "the utxo processor did not connect to the first node").await;
+    assert_eq!(wrpc_client.url(), Some(first.url.clone()));
+
+    let address = Address::new(network_id.into(), kaspa_addresses::Version::PubKey, &[1; 32]);
+    let context = UtxoContext::new(&processor, UtxoContextBinding::default());
+    context.scan_and_register_addresses(vec![address.clone()], None).await.unwrap();
+    let utxo_count = |context: &UtxoContext| {
+        context
+            .balance()
+            .map(|balance| balance.mature_utxo_count + balance.pending_utxo_count + balance.stasis_utxo_count)
+            .unwrap_or(0)
+    };
+
+    // Mine a block to the tracked address, followed by a few blocks paying out its reward
+    let mine_reward = |grpc_client: &GrpcClient| {
+        let grpc_client = grpc_client.clone();
+        let address = address.clone();
+        async move {
+            mine_block(address, &grpc_client, &[]).await;
+            for _ in 0..3 {
+                mine_block(Address::new(network_id.into(), kaspa_addresses::Version::PubKey, &[0; 32]), &grpc_client, &[]).await;
+            }
+        }
+    };
+    mine_reward(&grpc_client1).await;
+    wait_for(50, 100, || async { utxo_count(&context) > 0 }, "the utxo context did not receive the reward").await;
+    wait_for(50, 100, || async { monitor.health(&second).is_healthy() }, "the second endpoint was not probed").await;
+    assert!(monitor.health(&first).is_healthy());
+
+    // Kill the active node: the monitor fails over to the second endpoint and the client reconnects to it
+    grpc_client1.disconnect().await.unwrap();
+    drop(grpc_client1);
+    kaspad1.shutdown();
+    loop {
+        let event = tokio::time::timeout(Duration::from_secs(30), channel.receiver.recv()).await.unwrap().unwrap();
+        if let Events::NodeEndpoint { name, url, failover, .. } = *event {
+            assert!(failover);
+            assert_eq!((name, url), (second.name.clone(), second.url.clone()));
+            break;
+        }
+    }
+    assert_eq!(monitor.active(), Some(second.clone()));
+    assert!(monitor.health(&first).is_failed());
+    wait_for(
+        50,
+        200,
+        || async { processor.is_connected() && wrpc_client.url() == Some(second.url.clone()) },
+        "the utxo processor did not reconnect to the second node",
+    )
+    .await;
+
+    // The second node runs its own DAG, so the resync removes the reward received from the first one
+    // while the context keeps tracking its address without being registered again
+    wait_for(50, 100, || async { utxo_count(&context) == 0 }, "the utxo context was not resynced").await;
+    mine_reward(&grpc_client2).await;
+    wait_for(50, 100, || async { utxo_count(&context) > 0 }, "the utxo context did not receive the reward after the failover").await;
+
+    monitor.stop().await.unwrap();
+    processor.stop().await.unwrap();
+    wrpc_client.disconnect().await.unwrap();
+    grpc_client2.disconnect().await.unwrap();
+    drop(grpc_client2);
+    kaspad2.shutdown();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn daemon_grpc_pool_failover_test() {
+    init_allocator_with_default_settings();
+    kaspa_core::log::try_init_logger("INFO");
+
+    let args = Args {
+        simnet: true,
+        unsafe_rpc: true,
+        enable_unsynced_mining: true,
+        disable_upnp: true, // UPnP registration might take some time and is not needed for this test
+        ..Default::default()
+    };
+    let total_fd_limit = 10;
+    let mut kaspad1 = Daemon::new_random_with_args(args.clone(), total_fd_limit);
+    let mut kaspad2 = Daemon::new_random_with_args(args, total_fd_limit);
+    let grpc_client1 = kaspad1.start().await;
+    let grpc_client2 = kaspad2.start().await;
+    let network_id = kaspad1.network;
+    let url1 = format!("grpc://localhost:{}", kaspad1.rpc_port);
+    let url2 = format!("grpc://localhost:{}", kaspad2.rpc_port);
+
+    let options = PoolOptions {
+        health_check_interval: Duration::from_millis(500),
+        health_check_timeout: Duration::from_secs(1),
+        ..Default::default()
+    };
+    let pool = GrpcPool::connect(vec![url1.clone(), url2.clone()], options).await.unwrap();
+    let events = pool.event_receiver();
+    assert_eq!(events.recv().await.unwrap(), PoolEvent::Activated { url: url1.clone(), failover: false });
+    assert_eq!(pool.active_url(), Some(url1.clone()));
+
+    // Subscribe to the blocks added to the DAG of the active node
+    let notifications = pool.notification_channel_receiver();
+    pool.start_notify(GrpcClient::DIRECT_MODE_LISTENER_ID, BlockAddedScope {}.into()).await.unwrap();
+    let next_block_added = || {
+        let notifications = notifications.clone();
+        async move {
+            loop {
+                let notification = tokio::time::timeout(Duration::from_secs(10), notifications.recv()).await.unwrap().unwrap();
+                if let Notification::BlockAdded(notification) = notification {
+                    break notification.block.header.hash;
+                }
+            }
+        }
+    };
+    let pay_address = Address::new(network_id.into(), kaspa_addresses::Version::PubKey, &[0; 32]);
+    for _ in 0..3 {
+        mine_block(pay_address.clone(), &grpc_client1, &[]).await;
+        next_block_added().await;
+    }
+    assert_eq!(pool.get_sink().await.unwrap().sink, grpc_client1.get_sink().await.unwrap().sink);
+    wait_for(50, 100, || async { pool.health(&url2).unwrap().is_healthy() }, "the standby endpoint was not checked").await;
+
+    // Kill the active node while the notifications stream: the pool fails over to the second node
+    // and replays the subscription on it
+    grpc_client1.disconnect().await.unwrap();
+    drop(grpc_client1);
+    kaspad1.shutdown();
+    let event = tokio::time::timeout(Duration::from_secs(30), events.recv()).await.unwrap().unwrap();
+    assert_eq!(event, PoolEvent::Activated { url: url2.clone(), failover: true });
+    assert_eq!(pool.active_url(), Some(url2.clone()));
+    assert!(pool.health(&url1).unwrap().is_failed());
+
+    // The second node runs its own DAG
+    assert_eq!(pool.get_sink().await.unwrap().sink, grpc_client2.get_sink().await.unwrap().sink);
+    let template = grpc_client2.get_block_template(pay_address.clone(), vec![]).await.unwrap();
+    let block_hash = template.block.header.hash;
+    grpc_client2.submit_block(template.block, false).await.unwrap();
+    assert_eq!(next_block_added().await, block_hash);
+    assert_eq!(pool.get_sink().await.unwrap().sink, block_hash);
+
+    pool.disconnect().await.unwrap();
+    grpc_client2.disconnect().await.unwrap();
+    drop(grpc_client2);
+    kaspad2.shutdown();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn daemon_grpc_pool_retry_test() {
+    init_allocator_with_default_settings();
+    kaspa_core::log::try_init_logger("INFO");
+
+    let args = Args {
+        simnet: true,
+        unsafe_rpc: true,
+        enable_unsynced_mining: true,
+        disable_upnp: true, // UPnP registration might take some time and is not needed for this test
+        ..Default::default()
+    };
+    let total_fd_limit = 10;
+    let mut kaspad = Daemon::new_random_with_args(args, total_fd_limit);
+    let client_manager = kaspad.client_manager();
+    let grpc_client = kaspad.start().await;
+    let url = format!("grpc://localhost:{}", kaspad.rpc_port);
+
+    // Retry for up to about 10 seconds, covering the restart of the node
+    let retry_policy = RetryPolicy {
+        max_attempts: 20,
+        initial_backoff: Duration::from_millis(200),
+        max_backoff: Duration::from_secs(1),
+        multiplier: 2,
+    };
+    let options = PoolOptions { retry_policy, health_check_interval: Duration::from_millis(500), ..Default::default() };
+    let pool = GrpcPool::connect(vec![url.clone()], options).await.unwrap();
+    pool.get_info().await.unwrap();
+
+    // Stop the node: calls of methods unsafe to retry fail immediately with a transient error
+    grpc_client.disconnect().await.unwrap();
+    drop(grpc_client);
+    kaspad.shutdown();
+    drop(kaspad);
+    let start = Instant::now();
+    let transaction = RpcTransaction::from(&Transaction::new(0, vec![], vec![], 0, SUBNETWORK_ID_NATIVE, 0, vec![]));
+    let err = pool.submit_transaction(transaction, false).await.unwrap_err();
+    assert!(CallError::from(&err).is_transient(), "unexpected error {err}");
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    // A read issued while the node is down is retried until the node is back
+    let call = tokio::spawn({
+        let pool = pool.clone();
+        async move { pool.get_block_dag_info().await }
+    });
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(!call.is_finished());
+    let mut kaspad = Daemon::with_manager(client_manager, total_fd_limit);
+    let grpc_client = kaspad.start().await;
+    let dag_info = call.await.unwrap().unwrap();
+    assert_eq!(dag_info.network, kaspad.network);
+    assert_eq!(pool.active_url(), Some(url));
+
+    // Errors returned by the node are not retried, and have a typed classification
+    let start = Instant::now();
+    let err = pool.get_utxos_by_addresses(vec![]).await.unwrap_err();
+    assert_eq!(CallError::from(&err), CallError::NoUtxoIndex);
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    pool.disconnect().await.unwrap();
+    grpc_client.disconnect().await.unwrap();
+    drop(grpc_client);
+    kaspad.shutdown();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn daemon_utxo_set_override_test() {
+    init_allocator_with_default_settings();
+    kaspa_core::log::try_init_logger("INFO");
+
+    let args = Args {
+        simnet: true,
+        unsafe_rpc: true,
+        enable_unsynced_mining: true,
+        disable_upnp: true, // UPnP registration might take some time and is not needed for this test
+        utxoindex: true,
+        ..Default::default()
+    };
+    let total_fd_limit = 10;
+    let mut kaspad = Daemon::new_random_with_args(args, total_fd_limit);
+    let consensus_manager = Arc::downcast::<ConsensusManager>(kaspad.core.find(ConsensusManager::IDENT).unwrap().arc_any()).unwrap();
+    let grpc_client = kaspad.start().await;
+    let network_id = kaspad.network;
+
+    // Connect a standalone UtxoProcessor to the node
+    let wrpc_port = kaspad
+        .args
+        .read()
+        .rpclisten_borsh
+        .as_ref()
+        .unwrap()
+        .to_address(&kaspad.network.network_type, &WrpcEncoding::Borsh)
+        .normalize(0)
+        .port;
+    let url = format!("ws://127.0.0.1:{wrpc_port}");
+    let wrpc_client = Arc::new(KaspaRpcClient::new(WrpcEncoding::Borsh, Some(&url), None, None, None).unwrap());
+    let rpc = Rpc::new(wrpc_client.clone(), wrpc_client.rpc_ctl().clone());
+    let processor = UtxoProcessor::new(Some(rpc), Some(network_id), None, None);
+    let channel = processor.multiplexer().channel();
+    processor.start().await.unwrap();
+    let options = ConnectOptions { block_async_connect: true, strategy: ConnectStrategy::Retry, url: Some(url), ..Default::default() };
+    wrpc_client.connect(Some(options)).await.unwrap();
+    wait_for(50, 100, || async { processor.is_connected() }, "the utxo processor did not connect to the node").await;
+
+    let address = Address::new(network_id.into(), kaspa_addresses::Version::PubKey, &[1; 32]);
+    let context = UtxoContext::new(&processor, UtxoContextBinding::default());
+    context.scan_and_register_addresses(vec![address.clone()], None).await.unwrap();
+    let utxo_count = |context: &UtxoContext| {
+        context
+            .balance()
+            .map(|balance| balance.mature_utxo_count + balance.pending_utxo_count + balance.stasis_utxo_count)
+            .unwrap_or(0)
+    };
+    let index_utxo_count = |grpc_client: &GrpcClient| {
+        let grpc_client = grpc_client.clone();
+        let address = address.clone();
+        async move { grpc_client.get_utxos_by_addresses(vec![address]).await.unwrap().len() }
+    };
+
+    // Mine a block to the tracked address, followed by a few blocks paying out its reward
+    let mine_reward = |grpc_client: &GrpcClient| {
+        let grpc_client = grpc_client.clone();
+        let address = address.clone();
+        async move {
+            mine_block(address, &grpc_client, &[]).await;
+            for _ in 0..3 {
+                mine_block(Address::new(network_id.into(), kaspa_addresses::Version::PubKey, &[0; 32]), &grpc_client, &[]).await;
+            }
+        }
+    };
+    mine_reward(&grpc_client).await;
+    wait_for(50, 100, || async { utxo_count(&context) > 0 }, "the utxo context did not receive the reward").await;
+    assert_eq!(index_utxo_count(&grpc_client).await, utxo_count(&context));
+
+    // Override the pruning point UTXO set through the staging consensus import path of an IBD with headers proof,
+    // using the active consensus as the syncer. Its pruning point is genesis, whose UTXO set is empty.
+    let session = consensus_manager.consensus().session().await;
+    let proof = session.async_get_pruning_point_proof().await;
+    let pruning_points = session.async_pruning_point_headers().await;
+    let trusted_data = session.async_get_pruning_point_anticone_and_trusted_data().await.unwrap();
+    let ghostdag_data = trusted_data
+        .ghostdag_blocks
+        .iter()
+        .map(|data| (data.hash, data.ghostdag.clone()))
+        .chain(trusted_data.daa_window_blocks.iter().map(|data| (data.header.hash, data.ghostdag.clone())))
+        .collect::<BlockHashMap<_>>();
+    let mut trusted_set = Vec::with_capacity(trusted_data.anticone.len());
+    for &hash in trusted_data.anticone.iter() {
+        trusted_set.push(TrustedBlock::new(session.async_get_block(hash).await.unwrap(), ghostdag_data[&hash].clone()));
+    }
+    assert_eq!(session.async_pruning_point().await, SIMNET_GENESIS.hash);
+    drop(session);
+
+    let staging = consensus_manager.new_staging_consensus();
+    let staging_session = staging.session().await;
+    let trusted_set = staging_session
+        .clone()
+        .spawn_blocking(move |c| {
+            c.apply_pruning_proof((*proof).clone(), &trusted_set).unwrap();
+            c.import_pruning_points(pruning_points);
+            trusted_set
+        })
+        .await;
+    for tb in trusted_set {
+        staging_session.validate_and_insert_trusted_block(tb).virtual_state_task.await.unwrap();
+    }
+    staging_session.clone().spawn_blocking(|c| c.import_pruning_point_utxo_set(SIMNET_GENESIS.hash, MuHash::new())).await.unwrap();
+    drop(staging_session);
+    tokio::task::spawn_blocking(move || staging.commit()).await.unwrap();
+
+    // Both the index and the context converge to the overridden (empty) UTXO set, without restarting
+    let wait_for_event = |predicate: fn(&Events) -> bool| {
+        let receiver = channel.receiver.clone();
+        async move {
+            loop {
+                let event = tokio::time::timeout(Duration::from_secs(30), receiver.recv()).await.unwrap().unwrap();
+                if predicate(&event) {
+                    break;
+                }
+            }
+        }
+    };
+    wait_for_event(|event| matches!(event, Events::ResyncRequired)).await;
+    wait_for_event(|event| matches!(event, Events::ResyncComplete)).await;
+    assert_eq!(utxo_count(&context), 0);
+    assert_eq!(index_utxo_count(&grpc_client).await, 0);
+    assert_eq!(grpc_client.get_balance_by_address(address.clone()).await.unwrap(), 0);
+
+    // The context keeps tracking its address on the new consensus
+    mine_reward(&grpc_client).await;
+    wait_for(50, 100, || async { utxo_count(&context) > 0 }, "the utxo context did not receive the reward after the override").await;
+    assert_eq!(index_utxo_count(&grpc_client).await, utxo_count(&context));
+
+    processor.stop().await.unwrap();
+    wrpc_client.disconnect().await.unwrap();
+    grpc_client.disconnect().await.unwrap();
+    drop(grpc_client);
+    kaspad.shutdown();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn daemon_wallet_bump_fee_test() {
+    init_allocator_with_default_settings();
+    kaspa_core::log::try_init_logger("INFO");
+
+    let args = Args {
+        simnet: true,
+        unsafe_rpc: true,
+        enable_unsynced_mining: true,
+        disable_upnp: true, // UPnP registration might take some time and is not needed for this test
+        utxoindex: true,
+        ..Default::default()
+    };
+    let total_fd_limit = 10;
+    let mut kaspad = Daemon::new_random_with_args(args, total_fd_limit);
+    let grpc_client = kaspad.start().await;
+    let network_id = kaspad.network;
+
+    // Connect a wallet holding a single account to the node
+    let wallet_secret = Secret::from("secret");
+    let mnemonic = Mnemonic::random(WordCount::Words24, Language::English).unwrap();
+    let wallet = Arc::new(Wallet::try_new(Wallet::resident_store().unwrap(), None, Some(network_id)).unwrap());
+    wallet
+        .create_wallet(&wallet_secret, WalletCreateArgs::new(None, None, EncryptionKind::XChaCha20Poly1305, None, false))
+        .await
+        .unwrap();
+    wallet.start().await.unwrap();
+    let wrpc_port = kaspad
+        .args
+        .read()
+        .rpclisten_borsh
+        .as_ref()
+        .unwrap()
+        .to_address(&kaspad.network.network_type, &WrpcEncoding::Borsh)
+        .normalize(0)
+        .port;
+    let options = ConnectOptions {
+        block_async_connect: true,
+        strategy: ConnectStrategy::Fallback,
+        url: Some(format!("ws://127.0.0.1:{wrpc_port}")),
+        ..Default::default()
+    };
+    wallet.try_wrpc_client().unwrap().connect(Some(options)).await.unwrap();
+    wait_for(50, 100, || async { wallet.is_connected() }, "the wallet did not connect to the node").await;
+    let prv_key_data_id = wallet
+        .create_prv_key_data(&wallet_secret, PrvKeyDataCreateArgs::new(None, None, Secret::from(mnemonic.phrase_string())))
+        .await
+        .unwrap();
+    let account =
+        wallet.create_account(&wallet_secret, AccountCreateArgs::new_bip32(prv_key_data_id, None, None, None), false).await.unwrap();
+    account.clone().start().await.unwrap();
+
+    // Fund the account with a coinbase reward and let it reach maturity
+    let blank_address = Address::new(network_id.into(), kaspa_addresses::Version::PubKey, &[0; 32]);
+    mine_block(account.receive_address().unwrap(), &grpc_client, &[]).await;
+    for _ in 0..SIMNET_PARAMS.coinbase_maturity + 5 {
+        mine_block(blank_address.clone(), &grpc_client, &[]).await;
+    }
+    wait_for(
+        50,
+        100,
+        || async { account.balance().is_some_and(|balance| balance.mature > 0) },
+        "the account reward did not reach maturity",
+    )
+    .await;
+
+    // Send a payment paying the minimum fee
+    let recipient = Address::new(network_id.into(), kaspa_addresses::Version::PubKey, &[1; 32]);
+    let amount = SIMNET_PARAMS.pre_deflationary_phase_base_subsidy / 2;
+    let abortable = Abortable::default();
+    let (_, ids) = account
+        .clone()
+        .send(
+            PaymentOutputs::from((recipient.clone(), amount)).into(),
+            Fees::SenderPays(0),
+            None,
+            UtxoSelection::Automatic,
+            wallet_secret.clone(),
+            None,
+            &abortable,
+            None,
+        )
+        .await
+        .unwrap();
+    let parent_id = *ids.last().unwrap();
+    let parent_feerate = grpc_client.estimate_transaction_confirmation_time(parent_id).await.unwrap().estimate.package_feerate;
+
+    // Bump the package of the payment to ten times its feerate
+    let target_feerate = parent_feerate * 10.0;
+    let (_, ids) = account.clone().bump_fee(parent_id, target_feerate, wallet_secret.clone(), None, &abortable, None).await.unwrap();
+    assert_eq!(ids.len(), 2);
+    assert_eq!(ids[0], parent_id);
+    let child_id = ids[1];
+    let child_estimate = grpc_client.estimate_transaction_confirmation_time(child_id).await.unwrap().estimate;
+    assert!(
+        child_estimate.package_feerate >= target_feerate,
+        "package feerate {} below {target_feerate}",
+        child_estimate.package_feerate
+    );
+    assert_eq!(grpc_client.estimate_transaction_confirmation_time(parent_id).await.unwrap().estimate.package_feerate, parent_feerate);
+
+    // The change of the parent is spent by the child, so a second bump is refused
+    let err =
+        account.clone().bump_fee(parent_id, target_feerate * 2.0, wallet_secret.clone(), None, &abortable, None).await.unwrap_err();
+    assert!(matches!(err, WalletError::BumpFeeChangeAlreadySpent(id) if id == parent_id), "unexpected error {err}");
+
+    // The parent is mined first and the child, spending its change, right after
+    for _ in 0..3 {
+        mine_block(blank_address.clone(), &grpc_client, &[]).await;
+    }
+    let change_address = account.change_address().unwrap();
+    wait_for(
+        50,
+        100,
+        || async {
+            let utxos = grpc_client.get_utxos_by_addresses(vec![recipient.clone(), change_address.clone()]).await.unwrap();
+            let is_confirmed = |id| utxos.iter().any(|entry| entry.outpoint.transaction_id == id);
+            is_confirmed(parent_id) && is_confirmed(child_id)
+        },
+        "the parent and child transactions were not confirmed",
+    )
+    .await;
+    assert!(grpc_client.get_mempool_entry(parent_id, false, false).await.is_err());
+    assert!(grpc_client.get_mempool_entry(child_id, false, false).await.is_err());
+
+    wallet.stop().await.unwrap();
+    wallet.try_wrpc_client().unwrap().disconnect().await.unwrap();
+    grpc_client.disconnect().await.unwrap();
+    drop(grpc_client);
+    kaspad.shutdown();
+}
+
+/// The journaled notifications received by a gRPC client
+struct JournalSubscriber {
+    client: GrpcClient,
+    receiver: async_channel::Receiver<Notification>,
+    virtual_chain_changed: Vec<VirtualChainChangedNotification>,
+    utxos_changed: Vec<UtxosChangedNotification>,
+}
+
+impl JournalSubscriber {
+    async fn subscribe(kaspad: &Daemon, scopes: &[Scope]) -> Self {
+        let client = kaspad.new_client().await;
+        let (sender, receiver) = async_channel::unbounded();
+        client.start(Some(Arc::new(ChannelNotify::new(sender)))).await;
+        for scope in scopes {
+            client.start_notify(Default::default(), scope.clone()).await.unwrap();
+        }
+        Self { client, receiver, virtual_chain_changed: vec![], utxos_changed: vec![] }
+    }
+
+    /// Subscribes again through a new client, keeping the notifications received so far
+    async fn reconnect(self, kaspad: &Daemon, scopes: &[Scope]) -> Self {
+        let Self { virtual_chain_changed, utxos_changed, .. } = self;
+        Self { virtual_chain_changed, utxos_changed, ..Self::subscribe(kaspad, scopes).await }
+    }
+
+    fn push(&mut self, notification: Notification) {
+        match notification {
+            Notification::VirtualChainChanged(msg) => self.virtual_chain_changed.push(msg),
+            Notification::UtxosChanged(msg) => self.utxos_changed.push(msg),
+            _ => panic!("unexpected notification {notification}"),
+        }
+    }
+
+    fn collect(&mut self) {
+        while let Ok(notification) = self.receiver.try_recv() {
+            self.push(notification);
+        }
+    }
+
+    fn sequences(&self) -> Sequences {
+        Sequences::new(&self.virtual_chain_changed, &self.utxos_changed)
+    }
+
+    /// Waits for the received notifications to match the journaled ones for a while
+    async fn sync(&mut self, scopes: &[Scope]) {
+        let mut matches = 0;
+        for _ in 0..100 {
+            self.collect();
+            let mut virtual_chain_changed = vec![];
+            let mut utxos_changed = vec![];
+            for scope in scopes {
+                let response = self.client.get_missed_notifications(scope.clone(), 1).await.unwrap();
+                virtual_chain_changed.extend(response.virtual_chain_changed);
+                utxos_changed.extend(response.utxos_changed);
+            }
+            matches = if Sequences::new(&virtual_chain_changed, &utxos_changed) == self.sequences() { matches + 1 } else { 0 };
+            if matches == 3 {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        panic!("the journaled notifications were not all received");
+    }
+
+    /// Backfills the notifications emitted since the last ones received
+    async fn backfill(&mut self, scopes: &[Scope]) {
+        self.collect();
+        let sequences = self.sequences();
+        for scope in scopes {
+            let last_sequence = match scope {
+                Scope::VirtualChainChanged(_) => sequences.virtual_chain_changed.last(),
+                Scope::UtxosChanged(_) => sequences.utxos_changed.last(),
+                _ => unreachable!(),
+            };
+            let response = self.client.get_missed_notifications(scope.clone(), last_sequence.unwrap_or(&0) + 1).await.unwrap();
+            self.virtual_chain_changed.extend(response.virtual_chain_changed);
+            self.utxos_changed.extend(response.utxos_changed);
+        }
+        // Drop the duplicates of the notifications received since reconnecting
+        self.virtual_chain_changed.sort_by_key(|x| x.sequence);
+        self.virtual_chain_changed.dedup_by_key(|x| x.sequence);
+        self.utxos_changed.sort_by_key(|x| x.sequence);
+        self.utxos_changed.dedup_by_key(|x| x.sequence);
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Sequences {
+    virtual_chain_changed: Vec<u64>,
+    utxos_changed: Vec<u64>,
+}
+
+impl Sequences {
+    fn new(virtual_chain_changed: &[VirtualChainChangedNotification], utxos_changed: &[UtxosChangedNotification]) -> Self {
+        Self {
+            virtual_chain_changed: virtual_chain_changed
+                .iter()
+                .map(|x| x.sequence.expect("the node journals its notifications"))
+                .collect(),
+            utxos_changed: utxos_changed.iter().map(|x| x.sequence.expect("the node journals its notifications")).collect(),
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn daemon_missed_notifications_test() {
+    init_allocator_with_default_settings();
+    kaspa_core::log::try_init_logger("INFO");
+
+    const JOURNAL_SIZE: usize = 16;
+    let args = Args {
+        simnet: true,
+        unsafe_rpc: true,
+        enable_unsynced_mining: true,
+        disable_upnp: true, // UPnP registration might take some time and is not needed for this test
+        utxoindex: true,
+        notification_journal_size: JOURNAL_SIZE,
+        ..Default::default()
+    };
+    let total_fd_limit = 10;
+    let mut kaspad = Daemon::new_random_with_args(args, total_fd_limit);
+    let miner = kaspad.start().await;
+    let address = Address::new(kaspad.network.into(), kaspa_addresses::Version::PubKey, &[1; 32]);
+    let scopes: [Scope; 2] = [VirtualChainChangedScope::new(true).into(), UtxosChangedScope::new(vec![address.clone()]).into()];
+    let mine_blocks = |count: usize| {
+        let miner = miner.clone();
+        let address = address.clone();
+        async move {
+            for _ in 0..count {
+                mine_block(address.clone(), &miner, &[]).await;
+            }
+        }
+    };
+
+    // A subscriber staying connected all along and one disconnecting for a while
+    let mut connected = JournalSubscriber::subscribe(&kaspad, &scopes).await;
+    let mut reconnecting = JournalSubscriber::subscribe(&kaspad, &scopes).await;
+    mine_blocks(3).await;
+    connected.sync(&scopes).await;
+    reconnecting.sync(&scopes).await;
+    assert_eq!(reconnecting.sequences().virtual_chain_changed, vec![1, 2, 3]);
+
+    reconnecting.client.disconnect().await.unwrap();
+    mine_blocks(4).await;
+    let mut reconnecting = reconnecting.reconnect(&kaspad, &scopes).await;
+    mine_blocks(1).await;
+    connected.sync(&scopes).await;
+    assert_eq!(connected.sequences().virtual_chain_changed, (1..=8).collect::<Vec<_>>());
+
+    // The reconnected subscriber detects the gap through the sequence of its next notification...
+    while reconnecting.virtual_chain_changed.len() < 4 {
+        let notification = tokio::time::timeout(Duration::from_secs(5), reconnecting.receiver.recv()).await.unwrap().unwrap();
+        reconnecting.push(notification);
+    }
+    assert_eq!(reconnecting.sequences().virtual_chain_changed, vec![1, 2, 3, 8]);
+
+    // ...and backfills it to exact parity with the connected subscriber
+    reconnecting.backfill(&scopes).await;
+    assert_eq!(reconnecting.sequences(), connected.sequences());
+    assert_eq!(format!("{:?}", reconnecting.virtual_chain_changed), format!("{:?}", connected.virtual_chain_changed));
+    assert_eq!(format!("{:?}", reconnecting.utxos_changed), format!("{:?}", connected.utxos_changed));
+    assert!(connected.virtual_chain_changed.iter().all(|x| !x.accepted_transaction_ids.is_empty()));
+    assert!(!connected.utxos_changed.is_empty());
+    assert!(connected.utxos_changed.iter().flat_map(|x| x.added.iter()).all(|entry| entry.address.as_ref() == Some(&address)));
+
+    // Once the journal overflows, a full resync is required
+    mine_blocks(JOURNAL_SIZE).await;
+    let client = miner.clone();
+    let scope = scopes[0].clone();
+    wait_for(
+        50,
+        100,
+        move || {
+            let client = client.clone();
+            let scope = scope.clone();
+            async move {
+                match client.get_missed_notifications(scope, 1).await {
+                    Ok(_) => false,
+                    Err(err) => err.to_string().contains("full resync"),
+                }
+            }
+        },
+        "the journal did not overflow",
+    )
+    .await;
+
+    for client in [connected.client, reconnecting.client, miner] {
+        client.disconnect().await.unwrap();
+    }
+    kaspad.shutdown();
+}
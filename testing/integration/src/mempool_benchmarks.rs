@@ -134,7 +134,7 @@ async fn bench_bbt_latency() {
     let submit_tx_pool_tasks = submit_tx_pool.start(|c, (i, tx)| async move {
         match c.submit_transaction(tx.as_ref().into(), false).await {
             Ok(_) => {}
-            Err(RpcError::General(msg)) if msg.contains("orphan") => {
+            Err(RpcError::General(msg) | RpcError::Traced(msg, _)) if msg.contains("orphan") => {
                 kaspa_core::warn!("\n\n\n{msg}\n\n");
                 kaspa_core::warn!("Submitted {} transactions, exiting tx submit loop", i);
                 return true;
@@ -4,7 +4,9 @@ use crate::common::{client_notify::ChannelNotify, daemon::Daemon};
 use futures_util::future::try_join_all;
 use kaspa_addresses::{Address, Prefix, Version};
 use kaspa_consensus::params::SIMNET_GENESIS;
-use kaspa_consensus_core::{constants::MAX_SOMPI, subnets::SubnetworkId, tx::Transaction};
+use kaspa_consensus_core::{
+    config::params::Params, constants::MAX_SOMPI, emission, merkle::calc_hash_merkle_root, subnets::SubnetworkId, tx::Transaction,
+};
 use kaspa_core::info;
 use kaspa_grpc_core::ops::KaspadPayloadOps;
 use kaspa_hashes::Hash;
@@ -12,7 +14,7 @@ use kaspa_notify::{
     connection::{ChannelConnection, ChannelType},
     scope::{
         BlockAddedScope, FinalityConflictScope, NewBlockTemplateScope, PruningPointUtxoSetOverrideScope, Scope,
-        SinkBlueScoreChangedScope, UtxosChangedScope, VirtualChainChangedScope, VirtualDaaScoreChangedScope,
+        SinkBlueScoreChangedScope, TransactionConflictScope, UtxosChangedScope, VirtualChainChangedScope, VirtualDaaScoreChangedScope,
     },
 };
 use kaspa_rpc_core::{api::rpc::RpcApi, model::*, Notification};
@@ -49,7 +51,9 @@ async fn sanity_test() {
         enable_unsynced_mining: true,
         block_template_cache_lifetime: Some(0),
         utxoindex: true,
+        store_spent_utxos: true,
         unsafe_rpc: true,
+        notification_journal_size: 16,
         ..Default::default()
     };
 
@@ -100,7 +104,7 @@ async fn sanity_test() {
                     assert!(response.removed_chain_block_hashes.is_empty());
 
                     // Get a block template
-                    let GetBlockTemplateResponse { block, is_synced } = rpc_client
+                    let GetBlockTemplateResponse { block, is_synced, selection_stats } = rpc_client
                         .get_block_template_call(GetBlockTemplateRequest {
                             pay_address: Address::new(Prefix::Simnet, Version::PubKey, &[0u8; 32]),
                             extra_data: Vec::new(),
@@ -108,6 +112,21 @@ async fn sanity_test() {
                         .await
                         .unwrap();
                     assert!(!is_synced);
+                    // The mempool is empty
+                    let selection_stats = selection_stats.expect("the mining manager selector provides stats");
+                    assert_eq!(block.transactions.len(), 1);
+                    assert_eq!(
+                        (selection_stats.considered_count, selection_stats.selected_count, selection_stats.total_fees),
+                        (0, 0, 0)
+                    );
+                    assert_eq!(selection_stats.total_mass, 0);
+                    assert!(selection_stats.max_block_mass > 0);
+
+                    // The template validates as is, its only transaction being the coinbase
+                    let response = rpc_client.validate_block_template(block.clone()).await.unwrap();
+                    assert!(response.is_valid, "{:?}", response.rejection_detail);
+                    assert_eq!(response.total_fees, 0);
+                    assert!(response.mass > 0);
 
                     // Submit the template (no mining, in simnet PoW is skipped)
                     let response = rpc_client.submit_block(block.clone(), false).await.unwrap();
@@ -155,6 +174,10 @@ async fn sanity_test() {
                 tst!(op, "see SubmitBlock")
             }
 
+            KaspadPayloadOps::ValidateBlockTemplate => {
+                tst!(op, "see SubmitBlock")
+            }
+
             KaspadPayloadOps::GetCurrentNetwork => {
                 let rpc_client = client.clone();
                 tst!(op, {
@@ -166,27 +189,51 @@ async fn sanity_test() {
             KaspadPayloadOps::GetBlock => {
                 let rpc_client = client.clone();
                 tst!(op, {
-                    let result = rpc_client.get_block_call(GetBlockRequest { hash: 0.into(), include_transactions: false }).await;
+                    let result = rpc_client.get_block_call(GetBlockRequest::new(0.into(), false)).await;
                     assert!(result.is_err());
 
+                    let response = rpc_client.get_block_call(GetBlockRequest::new(SIMNET_GENESIS.hash, false)).await.unwrap();
+                    assert_eq!(response.block.header.hash, SIMNET_GENESIS.hash);
+
                     let response = rpc_client
-                        .get_block_call(GetBlockRequest { hash: SIMNET_GENESIS.hash, include_transactions: false })
+                        .get_block_call(
+                            GetBlockRequest::new(SIMNET_GENESIS.hash, true)
+                                .with_transaction_field_mask(RpcTransactionFieldMask::OMIT_VERBOSE_DATA),
+                        )
                         .await
                         .unwrap();
-                    assert_eq!(response.block.header.hash, SIMNET_GENESIS.hash);
+                    assert!(!response.block.transactions.is_empty());
+                    assert!(response.block.transactions.iter().all(|tx| tx.verbose_data.is_none()));
                 })
             }
 
             KaspadPayloadOps::GetBlocks => {
                 let rpc_client = client.clone();
                 tst!(op, {
-                    let response = rpc_client
-                        .get_blocks_call(GetBlocksRequest { include_blocks: true, include_transactions: false, low_hash: None })
-                        .await
-                        .unwrap();
+                    let response = rpc_client.get_blocks_call(GetBlocksRequest::new(None, true, false)).await.unwrap();
                     assert_eq!(response.blocks.len(), 1, "genesis block should be returned");
                     assert_eq!(response.blocks[0].header.hash, SIMNET_GENESIS.hash);
                     assert_eq!(response.block_hashes[0], SIMNET_GENESIS.hash);
+
+                    // Masked transactions omit the requested fields while their ids
+                    // still match the full transactions committed to by the header
+                    let full = rpc_client.get_blocks_call(GetBlocksRequest::new(None, true, true)).await.unwrap();
+                    let mask = RpcTransactionFieldMask::OMIT_SIGNATURE_SCRIPTS | RpcTransactionFieldMask::OMIT_PAYLOADS;
+                    let masked = rpc_client
+                        .get_blocks_call(GetBlocksRequest::new(None, true, true).with_transaction_field_mask(mask))
+                        .await
+                        .unwrap();
+                    assert!(serde_json::to_vec(&masked).unwrap().len() < serde_json::to_vec(&full).unwrap().len());
+
+                    let transactions =
+                        full.blocks[0].transactions.iter().map(Transaction::try_from).collect::<Result<Vec<_>, _>>().unwrap();
+                    assert_eq!(calc_hash_merkle_root(transactions.iter()), full.blocks[0].header.hash_merkle_root);
+                    assert_eq!(masked.blocks[0].transactions.len(), transactions.len());
+                    for (masked, transaction) in masked.blocks[0].transactions.iter().zip(transactions.iter()) {
+                        assert!(masked.payload.is_empty());
+                        assert!(masked.inputs.iter().all(|input| input.signature_script.is_empty()));
+                        assert_eq!(masked.verbose_data.as_ref().unwrap().transaction_id, transaction.id());
+                    }
                 })
             }
 
@@ -239,10 +286,12 @@ async fn sanity_test() {
                         .get_mempool_entries_call(GetMempoolEntriesRequest {
                             include_orphan_pool: true,
                             filter_transaction_pool: false,
+                            consistent_snapshot: true,
                         })
                         .await
                         .unwrap();
                     assert!(response.mempool_entries.is_empty());
+                    assert_eq!(response.summary.transaction_count, 0);
                 })
             }
 
@@ -263,11 +312,34 @@ async fn sanity_test() {
                     // Add peer only adds the IP to a connection request. It will only be added to known_addresses if it
                     // actually can be connected to. So in this test we can't expect it to be added unless we set up an
                     // actual peer.
-                    let response = rpc_client.get_peer_addresses_call(GetPeerAddressesRequest {}).await.unwrap();
+                    let response = rpc_client.get_peer_addresses_call(GetPeerAddressesRequest::default()).await.unwrap();
                     assert!(response.known_addresses.is_empty());
                 })
             }
 
+            KaspadPayloadOps::RemovePeer => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let peer_address = ContextualNetAddress::from_str("1.2.3.5").unwrap();
+                    assert!(rpc_client.remove_peer(peer_address).await.is_err(), "a peer which was not added cannot be removed");
+                    rpc_client.add_peer(peer_address, true).await.unwrap();
+                    rpc_client.remove_peer(peer_address).await.unwrap();
+                    assert!(rpc_client.remove_peer(peer_address).await.is_err());
+                })
+            }
+
+            KaspadPayloadOps::SetProcessingParallelism => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    assert!(rpc_client.set_processing_parallelism(0).await.is_err(), "a zero limit is rejected");
+                    let initial = rpc_client.set_processing_parallelism(2).await.unwrap();
+                    assert!(initial > 0);
+                    let metrics = rpc_client.get_metrics(false, false, false, true, false).await.unwrap().consensus_metrics.unwrap();
+                    assert_eq!(metrics.node_processing_parallelism_limit, 2);
+                    assert_eq!(rpc_client.set_processing_parallelism(initial).await.unwrap(), 2);
+                })
+            }
+
             KaspadPayloadOps::Ban => {
                 let rpc_client = client.clone();
                 tst!(op, {
@@ -277,11 +349,11 @@ async fn sanity_test() {
                     let _ = rpc_client.add_peer_call(AddPeerRequest { peer_address, is_permanent: false }).await.unwrap();
                     let _ = rpc_client.ban_call(BanRequest { ip }).await.unwrap();
 
-                    let response = rpc_client.get_peer_addresses_call(GetPeerAddressesRequest {}).await.unwrap();
+                    let response = rpc_client.get_peer_addresses_call(GetPeerAddressesRequest::default()).await.unwrap();
                     assert!(response.banned_addresses.contains(&ip));
 
                     let _ = rpc_client.unban_call(UnbanRequest { ip }).await.unwrap();
-                    let response = rpc_client.get_peer_addresses_call(GetPeerAddressesRequest {}).await.unwrap();
+                    let response = rpc_client.get_peer_addresses_call(GetPeerAddressesRequest::default()).await.unwrap();
                     assert!(!response.banned_addresses.contains(&ip));
                 })
             }
@@ -298,6 +370,17 @@ async fn sanity_test() {
                     let result = rpc_client.submit_transaction((&transaction).into(), false).await;
                     // ...that gets rejected by the consensus
                     assert!(result.is_err());
+
+                    // In verbose mode, the rejection is reported along with the trace of the checked acceptance rules
+                    let request = SubmitTransactionRequest::new((&transaction).into(), false).with_verbose(true);
+                    let response = rpc_client.submit_transaction_call(request).await.unwrap();
+                    assert_eq!(response.transaction_id, transaction.id());
+                    let trace = response.rejection_trace.expect("a rejected verbose submission carries a trace");
+                    assert!(!trace.error.is_empty());
+                    let last_check = trace.checks.last().expect("the trace should not be empty");
+                    assert_eq!(last_check.outcome, RpcAcceptanceOutcome::Failed);
+                    assert!(last_check.error.is_some());
+                    assert!(trace.checks.iter().rev().skip(1).all(|check| check.outcome != RpcAcceptanceOutcome::Failed));
                 })
             }
 
@@ -394,6 +477,42 @@ async fn sanity_test() {
                 })
             }
 
+            KaspadPayloadOps::GetAddressActivity => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let addresses = vec![Address::new(Prefix::Simnet, Version::PubKey, &[2u8; 32])];
+                    let entries = rpc_client.get_address_activity(addresses.clone()).await.unwrap();
+                    assert_eq!(entries.len(), 1);
+                    assert_eq!(entries[0].address, addresses[0]);
+                    assert!(!entries[0].has_received);
+                    assert_eq!(entries[0].first_seen_daa_score, 0);
+
+                    assert!(rpc_client.get_address_activity(vec![]).await.unwrap().is_empty());
+                })
+            }
+
+            KaspadPayloadOps::GetMissedNotifications => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    // Concurrent tests may emit notifications, so only the sequences never emitted are checked
+                    let address = Address::new(Prefix::Simnet, Version::PubKey, &[3u8; 32]);
+                    for scope in [Scope::from(VirtualChainChangedScope::new(true)), UtxosChangedScope::new(vec![address]).into()] {
+                        let err = rpc_client.get_missed_notifications(scope, u64::MAX).await.unwrap_err();
+                        assert!(err.to_string().contains("full resync"), "unexpected error: {err}");
+                    }
+                    assert!(rpc_client.get_missed_notifications(BlockAddedScope {}.into(), 1).await.is_err());
+                })
+            }
+
+            KaspadPayloadOps::GetUtxoReturnAddress => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    // An unknown accepting block has no spent entries
+                    let unknown = Hash::from_u64_word(u64::MAX);
+                    assert!(rpc_client.get_utxo_return_address(unknown, unknown, 0).await.is_err());
+                })
+            }
+
             KaspadPayloadOps::GetSinkBlueScore => {
                 let rpc_client = client.clone();
                 tst!(op, {
@@ -403,6 +522,16 @@ async fn sanity_test() {
                 })
             }
 
+            KaspadPayloadOps::GetVirtualInfo => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let response = rpc_client.get_virtual_info_call(GetVirtualInfoRequest {}).await.unwrap();
+                    // A concurrent test may have added a single block on top of genesis
+                    assert!(response.sink_blue_score < 2);
+                    assert!(response.parents_count >= 1);
+                })
+            }
+
             KaspadPayloadOps::EstimateNetworkHashesPerSecond => {
                 let rpc_client = client.clone();
                 tst!(op, {
@@ -442,6 +571,9 @@ async fn sanity_test() {
                     let response = rpc_client.get_coin_supply_call(GetCoinSupplyRequest {}).await.unwrap();
                     assert_eq!(response.circulating_sompi, 0);
                     assert_eq!(response.max_sompi, MAX_SOMPI);
+                    let params = Params::from(network_id);
+                    assert_eq!(response.block_reward_sompi, params.pre_deflationary_phase_base_subsidy);
+                    assert!(response.estimated_circulating_sompi <= emission::max_emission(&params));
                 })
             }
 
@@ -461,11 +593,13 @@ async fn sanity_test() {
                             connection_metrics: true,
                             bandwidth_metrics: true,
                             process_metrics: true,
+                            script_metrics: true,
                         })
                         .await
                         .unwrap();
                     assert!(get_metrics_call_response.process_metrics.is_some());
                     assert!(get_metrics_call_response.consensus_metrics.is_some());
+                    assert!(get_metrics_call_response.script_metrics.is_some());
 
                     let get_metrics_call_response = rpc_client
                         .get_metrics_call(GetMetricsRequest {
@@ -473,11 +607,13 @@ async fn sanity_test() {
                             connection_metrics: true,
                             bandwidth_metrics: true,
                             process_metrics: true,
+                            script_metrics: false,
                         })
                         .await
                         .unwrap();
                     assert!(get_metrics_call_response.process_metrics.is_some());
                     assert!(get_metrics_call_response.consensus_metrics.is_none());
+                    assert!(get_metrics_call_response.script_metrics.is_none());
 
                     let get_metrics_call_response = rpc_client
                         .get_metrics_call(GetMetricsRequest {
@@ -485,11 +621,13 @@ async fn sanity_test() {
                             connection_metrics: true,
                             bandwidth_metrics: false,
                             process_metrics: false,
+                            script_metrics: true,
                         })
                         .await
                         .unwrap();
                     assert!(get_metrics_call_response.process_metrics.is_none());
                     assert!(get_metrics_call_response.consensus_metrics.is_some());
+                    assert!(get_metrics_call_response.script_metrics.is_some());
 
                     let get_metrics_call_response = rpc_client
                         .get_metrics_call(GetMetricsRequest {
@@ -497,11 +635,13 @@ async fn sanity_test() {
                             connection_metrics: true,
                             bandwidth_metrics: false,
                             process_metrics: false,
+                            script_metrics: false,
                         })
                         .await
                         .unwrap();
                     assert!(get_metrics_call_response.process_metrics.is_none());
                     assert!(get_metrics_call_response.consensus_metrics.is_none());
+                    assert!(get_metrics_call_response.script_metrics.is_none());
                 })
             }
 
@@ -546,6 +686,104 @@ async fn sanity_test() {
                 })
             }
 
+            KaspadPayloadOps::GetChainBlockByDaaScore => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let dag_info = rpc_client.get_block_dag_info().await.unwrap();
+
+                    // A DAA score above the sink resolves to the sink
+                    let sink = rpc_client.get_chain_block_by_daa_score(u64::MAX).await.unwrap();
+                    assert_eq!(sink.hash, dag_info.sink);
+
+                    // The DAA score of a chain block resolves to the block itself
+                    let result = rpc_client.get_chain_block_by_daa_score(sink.daa_score).await.unwrap();
+                    assert_eq!(result.hash, sink.hash);
+                    assert_eq!(result.daa_score, sink.daa_score);
+                    assert_eq!(result.timestamp, sink.timestamp);
+                })
+            }
+
+            KaspadPayloadOps::SetLogFilters => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    // An empty value returns the current filters
+                    let filters = rpc_client.set_log_filters(String::new()).await.unwrap();
+                    assert!(!filters.is_empty());
+
+                    let filters = rpc_client.set_log_filters("kaspa_rpc_core=debug".to_string()).await.unwrap();
+                    assert!(filters.split(',').any(|x| x == "kaspa_rpc_core=debug"), "unexpected filters {filters}");
+                    assert_eq!(rpc_client.set_log_filters(String::new()).await.unwrap(), filters);
+
+                    // Invalid filters are rejected
+                    assert!(rpc_client.set_log_filters("kaspa_rpc_core=loud".to_string()).await.is_err());
+
+                    let filters = rpc_client.set_log_filters("kaspa_rpc_core=info".to_string()).await.unwrap();
+                    assert!(filters.split(',').any(|x| x == "kaspa_rpc_core=info"), "unexpected filters {filters}");
+                })
+            }
+
+            KaspadPayloadOps::GetOutpointSpendStatus => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    // An unknown outpoint is neither unspent nor spent
+                    let outpoint = RpcTransactionOutpoint::new(Hash::from_u64_word(u64::MAX), 0);
+                    let status = rpc_client.get_outpoint_spend_status(outpoint, None).await.unwrap();
+                    assert!(status.utxo_entry.is_none());
+                    assert!(status.mempool_spender.is_none());
+                    assert!(status.accepted_spender.is_none());
+                    assert!(status.conflicting_spenders.is_empty());
+
+                    let status = rpc_client.get_outpoint_spend_status(outpoint, Some(1)).await.unwrap();
+                    assert!(status.accepted_spender.is_none());
+                })
+            }
+
+            KaspadPayloadOps::EstimateTransactionConfirmationTime => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    // A transaction unknown to the mempool has no estimate
+                    let result = rpc_client.estimate_transaction_confirmation_time(Hash::from_u64_word(u64::MAX)).await;
+                    assert!(result.is_err());
+                })
+            }
+
+            KaspadPayloadOps::GetAcceptanceProof => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    // An unknown block or a transaction not accepted by the sink has no proof
+                    let unknown = Hash::from_u64_word(u64::MAX);
+                    assert!(rpc_client.get_acceptance_proof(unknown, unknown).await.is_err());
+                    let dag_info = rpc_client.get_block_dag_info().await.unwrap();
+                    assert!(rpc_client.get_acceptance_proof(dag_info.sink, unknown).await.is_err());
+                })
+            }
+
+            KaspadPayloadOps::CreateBackup => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    // See daemon_backup_test for actual backups
+                    assert!(rpc_client.create_backup("relative/backup".to_string()).await.is_err());
+                })
+            }
+
+            KaspadPayloadOps::GetVirtualParentSelectionTrace => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let trace = rpc_client.get_virtual_parent_selection_trace().await.unwrap();
+                    assert_eq!(trace.virtual_parents.first(), Some(&trace.selected_parent));
+                    assert!(trace.excluded_tips.iter().all(|excluded| trace.tips.contains(&excluded.hash)));
+                })
+            }
+
+            KaspadPayloadOps::GetSystemInfo => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let info = rpc_client.get_system_info().await.unwrap();
+                    assert!(info.fd_limit > 0);
+                    assert!(info.preflight_checks.iter().any(|check| check.name == "disk-space"));
+                })
+            }
+
             KaspadPayloadOps::NotifyBlockAdded => {
                 let rpc_client = client.clone();
                 let id = listener_id;
@@ -607,6 +845,13 @@ async fn sanity_test() {
                         .unwrap();
                 })
             }
+            KaspadPayloadOps::NotifyTransactionConflict => {
+                let rpc_client = client.clone();
+                let id = listener_id;
+                tst!(op, {
+                    rpc_client.start_notify(id, TransactionConflictScope::wildcard().into()).await.unwrap();
+                })
+            }
             KaspadPayloadOps::StopNotifyingUtxosChanged => {
                 let rpc_client = client.clone();
                 let id = listener_id;
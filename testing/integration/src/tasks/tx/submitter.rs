@@ -54,7 +54,7 @@ impl Task for TransactionSubmitterTask {
                         Ok(_) => {
                             return false;
                         }
-                        Err(RpcError::General(msg)) if msg.contains("orphan") => {
+                        Err(RpcError::General(msg) | RpcError::Traced(msg, _)) if msg.contains("orphan") => {
                             error!("Transaction {i}: submit attempt #{attempt} failed");
                             error!("\n\n\n{msg}\n\n");
                             sleep(Duration::from_millis(50)).await;
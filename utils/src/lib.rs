@@ -8,6 +8,7 @@ pub mod iter;
 pub mod mem_size;
 pub mod networking;
 pub mod option;
+pub mod rate_limit;
 pub mod refs;
 
 pub mod as_slice;
@@ -186,6 +187,7 @@ pub mod serde_bytes_fixed;
 /// assert_eq!(test_struct, from_json);
 /// ```
 pub mod serde_bytes_fixed_ref;
+pub mod serde_u64_string;
 pub mod sim;
 pub mod sync;
 pub mod triggers;
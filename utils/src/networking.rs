@@ -8,6 +8,7 @@ use std::{
     ops::Deref,
     str::FromStr,
 };
+use thiserror::Error;
 use uuid::Uuid;
 use wasm_bindgen::prelude::*;
 
@@ -55,6 +56,11 @@ impl From<&IpAddress> for PrefixBucket {
                 if let Some(ipv4) = ipv6.to_ipv4() {
                     let prefix_bytes = ipv4.octets();
                     Self(u64::from_be_bytes([0u8, 0u8, 0u8, 0u8, 0u8, 0u8, prefix_bytes[0], prefix_bytes[1]]))
+                } else if ip_address.is_onion() {
+                    // Onion addresses are all reached through the proxy, so they form a single bucket
+                    let mut prefix_bytes = [0u8; 8];
+                    prefix_bytes[..ONION_IPV6_PREFIX.len()].copy_from_slice(&ONION_IPV6_PREFIX);
+                    Self(u64::from_be_bytes(prefix_bytes))
                 } else {
                     // Else use first 8 bytes (routing prefix + subnetwork id) of ipv6
                     Self(u64::from_be_bytes(ipv6.octets().as_slice()[..8].try_into().expect("Slice with incorrect length")))
@@ -128,6 +134,17 @@ impl IpAddress {
     pub fn prefix_bucket(&self) -> PrefixBucket {
         PrefixBucket::from(self)
     }
+
+    /// Whether this is the IPv6 encoding of an onion address, see [`OnionAddress::ipv6_encoding`]
+    pub fn is_onion(&self) -> bool {
+        matches!(self.0, IpAddr::V6(ip) if ip.octets().starts_with(&ONION_IPV6_PREFIX))
+    }
+
+    /// Whether this is a link-local IPv6 address (`fe80::/10`). Such addresses are only reachable through
+    /// the network interface designated by their scope id, which is not part of the address.
+    pub fn is_ipv6_link_local(&self) -> bool {
+        matches!(self.0, IpAddr::V6(ip) if ip.segments()[0] & 0xffc0 == 0xfe80)
+    }
 }
 
 impl From<IpAddr> for IpAddress {
@@ -218,7 +235,128 @@ impl BorshDeserialize for IpAddress {
     }
 }
 
+/// The OnionCat IPv6 range (`fd87:d87e:eb43::/48`) in which onion addresses are encoded
+const ONION_IPV6_PREFIX: [u8; 6] = [0xfd, 0x87, 0xd8, 0x7e, 0xeb, 0x43];
+
+/// Length of a version 3 onion address: a 32 bytes ed25519 public key, a 2 bytes checksum and a version byte
+pub const ONION_ADDRESS_LEN: usize = 35;
+
+const ONION_ADDRESS_VERSION: u8 = 3;
+const ONION_SUFFIX: &str = ".onion";
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum OnionAddressError {
+    #[error("the onion address is missing the `.onion` suffix")]
+    MissingSuffix,
+
+    #[error("invalid onion address length {0}")]
+    InvalidLength(usize),
+
+    #[error("invalid base32 character {0:?} in onion address")]
+    InvalidCharacter(char),
+
+    #[error("unsupported onion address version {0}")]
+    UnsupportedVersion(u8),
+}
+
+/// A Tor version 3 onion service address, written as the base32 encoding of its bytes followed by `.onion`.
+///
+/// The checksum part of the address is not verified, the Tor proxy rejecting addresses with a wrong checksum.
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+pub struct OnionAddress([u8; ONION_ADDRESS_LEN]);
+
+impl OnionAddress {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, OnionAddressError> {
+        let bytes: [u8; ONION_ADDRESS_LEN] = bytes.try_into().map_err(|_| OnionAddressError::InvalidLength(bytes.len()))?;
+        match bytes[ONION_ADDRESS_LEN - 1] {
+            ONION_ADDRESS_VERSION => Ok(Self(bytes)),
+            version => Err(OnionAddressError::UnsupportedVersion(version)),
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Whether `host` is an onion host name
+    pub fn is_onion_host(host: &str) -> bool {
+        host.to_ascii_lowercase().ends_with(ONION_SUFFIX)
+    }
+
+    /// Encodes the address in the OnionCat IPv6 range, keeping the first 80 bits of its public key.
+    ///
+    /// The encoding is not reversible and only gives onion peers an IP based identity within the node,
+    /// for instance as address manager keys. Since the range is not publicly routable, encoded addresses
+    /// are never mistaken for reachable IPs.
+    pub fn ipv6_encoding(&self) -> Ipv6Addr {
+        let mut octets = [0u8; 16];
+        octets[..ONION_IPV6_PREFIX.len()].copy_from_slice(&ONION_IPV6_PREFIX);
+        octets[ONION_IPV6_PREFIX.len()..].copy_from_slice(&self.0[..16 - ONION_IPV6_PREFIX.len()]);
+        Ipv6Addr::from(octets)
+    }
+}
+
+impl FromStr for OnionAddress {
+    type Err = OnionAddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_ascii_lowercase();
+        let encoded = s.strip_suffix(ONION_SUFFIX).ok_or(OnionAddressError::MissingSuffix)?;
+        if encoded.len() != ONION_ADDRESS_LEN * 8 / 5 {
+            return Err(OnionAddressError::InvalidLength(encoded.len()));
+        }
+        let mut bytes = Vec::with_capacity(ONION_ADDRESS_LEN);
+        let (mut buffer, mut bits) = (0u16, 0);
+        for c in encoded.chars() {
+            let value =
+                BASE32_ALPHABET.iter().position(|&symbol| symbol as char == c).ok_or(OnionAddressError::InvalidCharacter(c))?;
+            buffer = (buffer << 5) | value as u16;
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                bytes.push((buffer >> bits) as u8);
+                buffer &= (1 << bits) - 1;
+            }
+        }
+        Self::from_bytes(&bytes)
+    }
+}
+
+impl Display for OnionAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut encoded = String::with_capacity(ONION_ADDRESS_LEN * 8 / 5 + ONION_SUFFIX.len());
+        let (mut buffer, mut bits) = (0u16, 0);
+        for &byte in self.0.iter() {
+            buffer = (buffer << 8) | byte as u16;
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                encoded.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+            }
+            buffer &= (1 << bits) - 1;
+        }
+        encoded.push_str(ONION_SUFFIX);
+        f.write_str(&encoded)
+    }
+}
+
+impl Serialize for OnionAddress {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for OnionAddress {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        OnionAddress::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
 /// A network address, equivalent of a [SocketAddr].
+///
+/// The scope id of IPv6 socket addresses is not kept, see [`IpAddress::is_ipv6_link_local`].
 #[derive(PartialEq, Eq, Hash, Copy, Clone, Serialize, Deserialize, Debug, BorshSerialize, BorshDeserialize)]
 pub struct NetAddress {
     pub ip: IpAddress,
@@ -308,7 +446,8 @@ impl FromStr for ContextualNetAddress {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match SocketAddr::from_str(s) {
             Ok(socket) => Ok(Self::new(socket.ip().into(), Some(socket.port()))),
-            Err(_) => Ok(Self::new(IpAddress::from_str(s)?, None)),
+            // IPv6 addresses without port may be bracketed, as when written with one
+            Err(_) => Ok(Self::new(IpAddress::from_str(s.strip_prefix('[').and_then(|s| s.strip_suffix(']')).unwrap_or(s))?, None)),
         }
     }
 }
@@ -450,6 +589,59 @@ mod tests {
         assert!(addr.prefix_bucket() == PrefixBucket(u16::from_be_bytes(prefix_bytes) as u64));
     }
 
+    #[test]
+    fn test_contextual_address_from_str() {
+        let addr = ContextualNetAddress::from_str("[2a01:4f8:191:1143::2]").unwrap();
+        assert_eq!(addr, ContextualNetAddress::from_str("2a01:4f8:191:1143::2").unwrap());
+        assert!(addr.port_not_specified());
+        assert_eq!(ContextualNetAddress::from_str("[2a01:4f8:191:1143::2]:5678").unwrap().port, Some(5678));
+        assert!(ContextualNetAddress::from_str("[1.2.3.4]:").is_err());
+    }
+
+    #[test]
+    fn test_onion_address() {
+        let host = "2gzyxa5ihm7nsggfxnu52rck2vv4rvmdlkiu3zzui5du4xyclen53wid.onion";
+        let onion = OnionAddress::from_str(host).unwrap();
+        assert_eq!(onion.to_string(), host);
+        assert_eq!(OnionAddress::from_str(&host.to_uppercase()).unwrap(), onion);
+        assert_eq!(OnionAddress::from_bytes(onion.as_bytes()).unwrap(), onion);
+        assert_eq!(serde_json::from_str::<OnionAddress>(&serde_json::to_string(&onion).unwrap()).unwrap(), onion);
+
+        assert_eq!(
+            OnionAddress::from_str("2gzyxa5ihm7nsggfxnu52rck2vv4rvmdlkiu3zzui5du4xyclen53wid"),
+            Err(OnionAddressError::MissingSuffix)
+        );
+        assert_eq!(OnionAddress::from_str("expyuzz4wqqyqhjn.onion"), Err(OnionAddressError::InvalidLength(16)));
+        assert_eq!(
+            OnionAddress::from_str("2gzyxa5ihm7nsggfxnu52rck2vv4rvmdlkiu3zzui5du4xyclen53wi1.onion"),
+            Err(OnionAddressError::InvalidCharacter('1'))
+        );
+        assert_eq!(
+            OnionAddress::from_str("2gzyxa5ihm7nsggfxnu52rck2vv4rvmdlkiu3zzui5du4xyclen53wia.onion"),
+            Err(OnionAddressError::UnsupportedVersion(0))
+        );
+
+        // Onion addresses are encoded in a single non routable bucket
+        let ip = IpAddress::from(onion.ipv6_encoding());
+        assert_eq!(ip.to_string(), "fd87:d87e:eb43:d1b3:8b83:a83b:3ed9:18c5");
+        assert!(ip.is_onion() && !ip.is_publicly_routable());
+        let other: IpAddress = Ipv6Addr::from_str("fd87:d87e:eb43:ffff::1").unwrap().into();
+        assert_eq!(ip.prefix_bucket(), other.prefix_bucket());
+        assert!(!IpAddress::from_str("2a01:4f8:191:1143::2").unwrap().is_onion());
+    }
+
+    #[test]
+    fn test_ipv6_link_local() {
+        assert!(IpAddress::from_str("fe80::1").unwrap().is_ipv6_link_local());
+        assert!(IpAddress::from_str("febf::1").unwrap().is_ipv6_link_local());
+        assert!(!IpAddress::from_str("fec0::1").unwrap().is_ipv6_link_local());
+        assert!(!IpAddress::from_str("169.254.0.1").unwrap().is_ipv6_link_local());
+
+        // The scope id of a socket address is not kept
+        let addr = NetAddress::from_str("[fe80::1%2]:5678").unwrap();
+        assert_eq!(addr.to_string(), "[fe80::1]:5678");
+    }
+
     #[test]
     fn test_contextual_address_ser() {
         let addr = IpAddress::from_str("127.0.0.1").unwrap();
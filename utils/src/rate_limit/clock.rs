@@ -0,0 +1,76 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// A monotonic source of time
+pub trait Clock: Send + Sync {
+    /// Returns the time elapsed since an arbitrary but fixed origin
+    fn now(&self) -> Duration;
+}
+
+/// A [`Clock`] based on [`std::time::Instant`]
+#[derive(Clone, Copy, Debug)]
+pub struct StdClock {
+    origin: Instant,
+}
+
+impl Default for StdClock {
+    fn default() -> Self {
+        Self { origin: Instant::now() }
+    }
+}
+
+impl Clock for StdClock {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+}
+
+/// A [`Clock`] based on [`tokio::time::Instant`], hence following the tokio clock when it is paused
+/// and advanced by tests. Waiting in `acquire` relies on tokio timers, so this is the clock to use
+/// for testing async acquisitions in virtual time.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Copy, Debug)]
+pub struct TokioClock {
+    origin: tokio::time::Instant,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for TokioClock {
+    fn default() -> Self {
+        Self { origin: tokio::time::Instant::now() }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Clock for TokioClock {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+}
+
+/// A [`Clock`] only moving when explicitly advanced. Clones share the same time.
+#[derive(Clone, Debug, Default)]
+pub struct VirtualClock {
+    nanos: Arc<AtomicU64>,
+}
+
+impl VirtualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.nanos.fetch_add(duration.as_nanos() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::SeqCst))
+    }
+}
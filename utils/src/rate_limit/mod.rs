@@ -0,0 +1,62 @@
+//! Rate limiting primitives shared by the node subsystems.
+//!
+//! [`TokenBucket`] grants permits at a steady rate while allowing bursts up to its capacity, and
+//! [`SlidingWindowCounter`] bounds the number of permits granted within a sliding window of a given duration.
+//! Both are generic over a [`Clock`], so tests can drive them with a [`VirtualClock`].
+
+mod clock;
+mod sliding_window;
+mod token_bucket;
+
+pub use clock::{Clock, StdClock, VirtualClock};
+pub use sliding_window::SlidingWindowCounter;
+pub use token_bucket::TokenBucket;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use clock::TokioClock;
+
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RateLimitError {
+    #[error("cannot acquire {0} permits from a limiter granting at most {1} at once")]
+    ExceedsCapacity(u64, u64),
+
+    #[error("the limiter has a zero refill rate and cannot grant {0} permits anymore")]
+    Exhausted(u64),
+}
+
+pub type RateLimitResult<T> = std::result::Result<T, RateLimitError>;
+
+#[inline]
+fn nanos(duration: Duration) -> u128 {
+    duration.as_nanos()
+}
+
+/// Converts nanoseconds to a duration, saturating at [`Duration::MAX`]
+#[inline]
+fn from_nanos(nanos: u128) -> Duration {
+    match u64::try_from(nanos / 1_000_000_000) {
+        Ok(secs) => Duration::new(secs, (nanos % 1_000_000_000) as u32),
+        Err(_) => Duration::MAX,
+    }
+}
+
+/// Waits until `n` permits are granted by `try_acquire`, sleeping on the tokio timer for the durations
+/// reported by `time_until_available`.
+#[cfg(not(target_arch = "wasm32"))]
+async fn acquire_with(
+    n: u64,
+    try_acquire: impl Fn(u64) -> bool,
+    time_until_available: impl Fn(u64) -> RateLimitResult<Duration>,
+) -> RateLimitResult<()> {
+    loop {
+        if try_acquire(n) {
+            return Ok(());
+        }
+        // Sleep at least a nanosecond so rounding never makes this loop spin
+        let wait = time_until_available(n)?.max(Duration::from_nanos(1));
+        tokio::time::sleep(wait).await;
+    }
+}
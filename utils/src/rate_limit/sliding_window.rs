@@ -0,0 +1,248 @@
+use super::{from_nanos, nanos, Clock, RateLimitError, RateLimitResult, StdClock};
+use parking_lot::Mutex;
+use std::time::Duration;
+
+#[derive(Debug, Default)]
+struct State {
+    /// Index of the current window, counted in windows since the clock origin
+    index: u128,
+    /// Permits granted within the previous and the current windows
+    previous: u64,
+    current: u64,
+}
+
+/// Limits the number of permits granted within a sliding window of a given duration.
+///
+/// Time is split into consecutive windows and the permits granted in the sliding window are estimated
+/// by weighting the count of the previous window by its overlap with the sliding window, assuming they
+/// were granted uniformly. This keeps a constant state while, unlike fixed windows, not letting a client
+/// double its rate around window boundaries. The bound is exact for the current window: no more than
+/// `max_permits` are ever granted within a single window.
+#[derive(Debug)]
+pub struct SlidingWindowCounter<C: Clock = StdClock> {
+    max_permits: u64,
+    window: Duration,
+    clock: C,
+    state: Mutex<State>,
+}
+
+impl SlidingWindowCounter {
+    pub fn new(max_permits: u64, window: Duration) -> Self {
+        Self::with_clock(max_permits, window, StdClock::default())
+    }
+}
+
+impl<C: Clock> SlidingWindowCounter<C> {
+    pub fn with_clock(max_permits: u64, window: Duration, clock: C) -> Self {
+        assert!(!window.is_zero(), "the window duration must be positive");
+        let index = nanos(clock.now()) / nanos(window);
+        Self { max_permits, window, clock, state: Mutex::new(State { index, ..Default::default() }) }
+    }
+
+    pub fn max_permits(&self) -> u64 {
+        self.max_permits
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Returns the estimated number of permits granted within the sliding window ending now
+    pub fn count(&self) -> u64 {
+        let mut state = self.state.lock();
+        let elapsed = self.roll(&mut state);
+        self.estimate(&state, elapsed)
+    }
+
+    pub fn try_acquire(&self) -> bool {
+        self.try_acquire_n(1)
+    }
+
+    /// Acquires `n` permits if granting them keeps the sliding window within bounds, returning `true` if it did
+    pub fn try_acquire_n(&self, n: u64) -> bool {
+        let mut state = self.state.lock();
+        let elapsed = self.roll(&mut state);
+        if self.estimate(&state, elapsed).checked_add(n).is_some_and(|count| count <= self.max_permits) {
+            state.current += n;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the time left until `n` permits can be acquired, or an error if they never can be
+    pub fn time_until_available(&self, n: u64) -> RateLimitResult<Duration> {
+        if n > self.max_permits {
+            return Err(RateLimitError::ExceedsCapacity(n, self.max_permits));
+        }
+        let mut state = self.state.lock();
+        let elapsed = self.roll(&mut state);
+        let window = nanos(self.window);
+        let wait = if state.current.checked_add(n).is_some_and(|count| count <= self.max_permits) {
+            // The previous window weight has to decrease enough to make room within the current window
+            self.fitting_offset(state.previous, self.max_permits - state.current - n).saturating_sub(elapsed)
+        } else {
+            // The current window is full, so wait until its weight in the next window decreases enough
+            window - elapsed + self.fitting_offset(state.current, self.max_permits - n)
+        };
+        Ok(from_nanos(wait))
+    }
+
+    /// Waits until a permit is acquired
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn acquire(&self) -> RateLimitResult<()> {
+        self.acquire_n(1).await
+    }
+
+    /// Waits until `n` permits are acquired. Fails right away if the permits can never be granted.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn acquire_n(&self, n: u64) -> RateLimitResult<()> {
+        super::acquire_with(n, |n| self.try_acquire_n(n), |n| self.time_until_available(n)).await
+    }
+
+    /// Moves the state to the window containing the current time and returns the time elapsed within it
+    fn roll(&self, state: &mut State) -> u128 {
+        let now = nanos(self.clock.now());
+        let window = nanos(self.window);
+        let index = now / window;
+        if index == state.index + 1 {
+            state.previous = state.current;
+            state.current = 0;
+        } else if index > state.index {
+            state.previous = 0;
+            state.current = 0;
+        }
+        state.index = state.index.max(index);
+        now.saturating_sub(state.index * window)
+    }
+
+    fn estimate(&self, state: &State, elapsed: u128) -> u64 {
+        let window = nanos(self.window);
+        let previous = (state.previous as u128 * (window - elapsed.min(window))).div_ceil(window);
+        (previous as u64).saturating_add(state.current)
+    }
+
+    /// Returns the earliest offset within a window from which the weight of a previous window holding
+    /// `count` permits is at most `room`
+    fn fitting_offset(&self, count: u64, room: u64) -> u128 {
+        let window = nanos(self.window);
+        if count == 0 {
+            return 0;
+        }
+        window.saturating_sub(room as u128 * window / count as u128)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rate_limit::VirtualClock;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    const WINDOW: Duration = Duration::from_secs(10);
+
+    #[test]
+    fn test_sliding_window_counter() {
+        let clock = VirtualClock::new();
+        let counter = SlidingWindowCounter::with_clock(4, WINDOW, clock.clone());
+        assert!(counter.try_acquire_n(4));
+        assert!(!counter.try_acquire());
+        // The weight of the current window has to drop to 2 within the next window
+        assert_eq!(counter.time_until_available(2), Ok(Duration::from_secs(15)));
+
+        // Halfway through the next window, half the previous window is still accounted for
+        clock.advance(Duration::from_secs(15));
+        assert_eq!(counter.count(), 2);
+        assert!(counter.try_acquire_n(2));
+        assert!(!counter.try_acquire());
+        // The previous window weight needs to drop to 1 to fit a permit next to the 2 just granted
+        assert_eq!(counter.time_until_available(1), Ok(Duration::from_millis(2_500)));
+        clock.advance(Duration::from_millis(2_499));
+        assert!(!counter.try_acquire());
+        clock.advance(Duration::from_millis(1));
+        assert!(counter.try_acquire());
+        assert_eq!(counter.count(), 4);
+
+        // Windows older than the previous one are forgotten
+        clock.advance(Duration::from_secs(20));
+        assert_eq!(counter.count(), 0);
+        assert!(counter.try_acquire_n(4));
+    }
+
+    #[test]
+    fn test_sliding_window_zero_capacity() {
+        let clock = VirtualClock::new();
+        let counter = SlidingWindowCounter::with_clock(0, WINDOW, clock.clone());
+        assert!(!counter.try_acquire());
+        assert!(counter.try_acquire_n(0));
+        clock.advance(WINDOW * 100);
+        assert!(!counter.try_acquire());
+        assert_eq!(counter.time_until_available(1), Err(RateLimitError::ExceedsCapacity(1, 0)));
+        assert_eq!(counter.time_until_available(0), Ok(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_sliding_window_overflow() {
+        let clock = VirtualClock::new();
+        let counter = SlidingWindowCounter::with_clock(u64::MAX, Duration::from_nanos(1), clock.clone());
+        assert!(counter.try_acquire_n(u64::MAX));
+        assert!(!counter.try_acquire());
+        // With a single nanosecond per window, the previous window is always fully accounted for
+        clock.advance(Duration::from_nanos(1));
+        assert!(!counter.try_acquire());
+        clock.advance(Duration::from_nanos(1));
+        assert!(counter.try_acquire_n(u64::MAX));
+        clock.advance(Duration::from_secs(u32::MAX as u64));
+        assert_eq!(counter.count(), 0);
+
+        let counter = SlidingWindowCounter::with_clock(1, Duration::from_secs(u64::MAX), clock.clone());
+        assert!(counter.try_acquire());
+        assert_eq!(counter.time_until_available(1), Ok(Duration::MAX));
+    }
+
+    /// Under random bursty demand polled often, every window grants at most `max_permits`, and nearly
+    /// as many once the whole window is covered by the limiter
+    #[test]
+    fn test_sliding_window_long_run_rate() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..50 {
+            let max_permits = rng.gen_range(1..1000);
+            let window = Duration::from_millis(rng.gen_range(1..2000));
+            let clock = VirtualClock::new();
+            let counter = SlidingWindowCounter::with_clock(max_permits, window, clock.clone());
+            let mut granted = vec![0u64];
+            for _ in 0..5000 {
+                let index = (nanos(clock.now()) / nanos(window)) as usize;
+                granted.resize(index + 1, 0);
+                for _ in 0..rng.gen_range(0..4) {
+                    let n = rng.gen_range(1..=max_permits);
+                    if counter.try_acquire_n(n) {
+                        granted[index] += n;
+                    }
+                }
+                while counter.try_acquire() {
+                    granted[index] += 1;
+                }
+                // Polling at least 20 times per window
+                clock.advance(Duration::from_nanos(rng.gen_range(0..nanos(window) as u64 / 20)));
+            }
+            for (index, &count) in granted.iter().enumerate() {
+                assert!(count <= max_permits, "window {index} granted {count} permits out of {max_permits}");
+            }
+            // The last window may be partial
+            for (index, &count) in granted.iter().enumerate().take(granted.len() - 1) {
+                assert!(count + max_permits / 20 + 1 >= max_permits, "window {index} granted {count} permits out of {max_permits}");
+            }
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sliding_window_acquire() {
+        let counter = SlidingWindowCounter::with_clock(2, WINDOW, crate::rate_limit::TokioClock::default());
+        let start = tokio::time::Instant::now();
+        counter.acquire_n(2).await.unwrap();
+        counter.acquire().await.unwrap();
+        assert_eq!(start.elapsed(), Duration::from_secs(15));
+        assert_eq!(counter.acquire_n(3).await, Err(RateLimitError::ExceedsCapacity(3, 2)));
+    }
+}
@@ -0,0 +1,252 @@
+use super::{from_nanos, nanos, Clock, RateLimitError, RateLimitResult, StdClock};
+use parking_lot::Mutex;
+use std::time::Duration;
+
+#[derive(Debug)]
+struct State {
+    /// Permits granted per `period`
+    permits: u64,
+    period: Duration,
+    available: u64,
+    /// Time up to which the refill has been accounted for
+    last_refill: Duration,
+}
+
+/// A token bucket granting `permits` per `period` at a steady rate, and accumulating up to `capacity`
+/// unused permits which can then be acquired in a burst.
+///
+/// The bucket starts full. Refills are computed with integer arithmetic and the fractional progress
+/// towards the next permit is retained, so the long-run rate is exact.
+#[derive(Debug)]
+pub struct TokenBucket<C: Clock = StdClock> {
+    capacity: u64,
+    clock: C,
+    state: Mutex<State>,
+}
+
+impl TokenBucket {
+    pub fn new(permits: u64, period: Duration, capacity: u64) -> Self {
+        Self::with_clock(permits, period, capacity, StdClock::default())
+    }
+}
+
+impl<C: Clock> TokenBucket<C> {
+    pub fn with_clock(permits: u64, period: Duration, capacity: u64, clock: C) -> Self {
+        assert!(!period.is_zero(), "the refill period must be positive");
+        let last_refill = clock.now();
+        Self { capacity, clock, state: Mutex::new(State { permits, period, available: capacity, last_refill }) }
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    /// Changes the refill rate, preserving the permits accumulated so far
+    pub fn set_rate(&self, permits: u64, period: Duration) {
+        assert!(!period.is_zero(), "the refill period must be positive");
+        let mut state = self.state.lock();
+        self.refill(&mut state);
+        if state.permits != permits || state.period != period {
+            // The fractional progress towards the next permit is specific to the previous rate
+            state.last_refill = self.clock.now();
+            state.permits = permits;
+            state.period = period;
+        }
+    }
+
+    /// Returns the number of permits which can be acquired right away
+    pub fn available(&self) -> u64 {
+        let mut state = self.state.lock();
+        self.refill(&mut state);
+        state.available
+    }
+
+    pub fn try_acquire(&self) -> bool {
+        self.try_acquire_n(1)
+    }
+
+    /// Acquires `n` permits if they are all available right away, returning `true` if it did
+    pub fn try_acquire_n(&self, n: u64) -> bool {
+        let mut state = self.state.lock();
+        self.refill(&mut state);
+        if state.available >= n {
+            state.available -= n;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the time left until `n` permits are available, or an error if they never will be
+    pub fn time_until_available(&self, n: u64) -> RateLimitResult<Duration> {
+        if n > self.capacity {
+            return Err(RateLimitError::ExceedsCapacity(n, self.capacity));
+        }
+        let mut state = self.state.lock();
+        self.refill(&mut state);
+        if state.available >= n {
+            return Ok(Duration::ZERO);
+        }
+        if state.permits == 0 {
+            return Err(RateLimitError::Exhausted(n));
+        }
+        let missing = (n - state.available) as u128;
+        let ready_at = nanos(state.last_refill) + (missing * nanos(state.period)).div_ceil(state.permits as u128);
+        Ok(from_nanos(ready_at.saturating_sub(nanos(self.clock.now()))))
+    }
+
+    /// Waits until a permit is acquired
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn acquire(&self) -> RateLimitResult<()> {
+        self.acquire_n(1).await
+    }
+
+    /// Waits until `n` permits are acquired. Fails right away if the permits can never be granted.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn acquire_n(&self, n: u64) -> RateLimitResult<()> {
+        super::acquire_with(n, |n| self.try_acquire_n(n), |n| self.time_until_available(n)).await
+    }
+
+    fn refill(&self, state: &mut State) {
+        let now = self.clock.now();
+        if state.available >= self.capacity || state.permits == 0 {
+            state.last_refill = now;
+            return;
+        }
+        let elapsed = nanos(now.saturating_sub(state.last_refill));
+        let period = nanos(state.period);
+        let refilled = elapsed * state.permits as u128 / period;
+        if refilled == 0 {
+            return;
+        }
+        let available = (state.available as u128 + refilled).min(self.capacity as u128) as u64;
+        if available == self.capacity {
+            state.last_refill = now;
+        } else {
+            // Only account for the time needed to produce the refilled permits, keeping the remainder
+            state.last_refill += from_nanos((refilled * period).div_ceil(state.permits as u128));
+        }
+        state.available = available;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rate_limit::VirtualClock;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn test_token_bucket_burst_and_refill() {
+        let clock = VirtualClock::new();
+        let bucket = TokenBucket::with_clock(10, SECOND, 5, clock.clone());
+        assert!(bucket.try_acquire_n(5), "the bucket starts full");
+        assert!(!bucket.try_acquire());
+        assert_eq!(bucket.time_until_available(1), Ok(Duration::from_millis(100)));
+        assert_eq!(bucket.time_until_available(3), Ok(Duration::from_millis(300)));
+
+        clock.advance(Duration::from_millis(150));
+        assert_eq!(bucket.available(), 1);
+        // The fractional progress towards the next permit is kept
+        assert_eq!(bucket.time_until_available(2), Ok(Duration::from_millis(50)));
+        clock.advance(Duration::from_millis(50));
+        assert!(bucket.try_acquire_n(2));
+
+        // Refilling never exceeds the capacity
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(bucket.available(), 5);
+        assert!(bucket.try_acquire_n(5));
+        assert!(!bucket.try_acquire());
+    }
+
+    #[test]
+    fn test_token_bucket_zero_capacity() {
+        let clock = VirtualClock::new();
+        let bucket = TokenBucket::with_clock(10, SECOND, 0, clock.clone());
+        assert!(!bucket.try_acquire());
+        assert!(bucket.try_acquire_n(0));
+        clock.advance(Duration::from_secs(100));
+        assert_eq!(bucket.available(), 0);
+        assert_eq!(bucket.time_until_available(1), Err(RateLimitError::ExceedsCapacity(1, 0)));
+    }
+
+    #[test]
+    fn test_token_bucket_zero_rate() {
+        let clock = VirtualClock::new();
+        let bucket = TokenBucket::with_clock(0, SECOND, 2, clock.clone());
+        assert!(bucket.try_acquire_n(2));
+        clock.advance(Duration::from_secs(100));
+        assert!(!bucket.try_acquire());
+        assert_eq!(bucket.time_until_available(1), Err(RateLimitError::Exhausted(1)));
+
+        // Raising the rate resumes the refill from now on
+        bucket.set_rate(1, SECOND);
+        assert_eq!(bucket.time_until_available(1), Ok(SECOND));
+        clock.advance(SECOND);
+        assert!(bucket.try_acquire());
+    }
+
+    #[test]
+    fn test_token_bucket_refill_overflow() {
+        let clock = VirtualClock::new();
+        let bucket = TokenBucket::with_clock(u64::MAX, Duration::from_nanos(1), u64::MAX, clock.clone());
+        assert!(bucket.try_acquire_n(u64::MAX));
+        clock.advance(Duration::from_secs(u32::MAX as u64));
+        assert_eq!(bucket.available(), u64::MAX);
+
+        let bucket = TokenBucket::with_clock(1, Duration::from_secs(u64::MAX), 1, clock.clone());
+        assert!(bucket.try_acquire());
+        assert_eq!(bucket.time_until_available(1), Ok(Duration::from_secs(u64::MAX)));
+    }
+
+    /// Under random bursty demand, the number of permits granted over a long run never exceeds the
+    /// capacity plus the steady rate, and matches it when demand always exceeds the rate
+    #[test]
+    fn test_token_bucket_long_run_rate() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..50 {
+            let permits = rng.gen_range(1..1000);
+            let period = Duration::from_millis(rng.gen_range(1..2000));
+            let capacity = rng.gen_range(0..5000);
+            let clock = VirtualClock::new();
+            let bucket = TokenBucket::with_clock(permits, period, capacity, clock.clone());
+            let mut granted = 0u128;
+            for _ in 0..2000 {
+                // Bursts of requests far above the steady rate
+                for _ in 0..rng.gen_range(0..4) {
+                    let n = rng.gen_range(1..=permits.max(capacity));
+                    if bucket.try_acquire_n(n) {
+                        granted += n as u128;
+                    }
+                }
+                while bucket.try_acquire() {
+                    granted += 1;
+                }
+                clock.advance(Duration::from_micros(rng.gen_range(0..2 * period.as_micros() as u64)));
+            }
+            while bucket.try_acquire() {
+                granted += 1;
+            }
+            let elapsed = nanos(clock.now());
+            let max_granted = capacity as u128 + elapsed * permits as u128 / nanos(period);
+            assert!(granted <= max_granted, "granted {granted} permits while at most {max_granted} are allowed");
+            // The bucket is drained at every step, so unless it can fill up between two steps no refill is ever lost
+            if capacity > 2 * permits {
+                assert!(granted + 1 >= max_granted, "granted {granted} permits while {max_granted} are due");
+            }
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_token_bucket_acquire() {
+        let bucket = TokenBucket::with_clock(2, SECOND, 1, crate::rate_limit::TokioClock::default());
+        let start = tokio::time::Instant::now();
+        for _ in 0..5 {
+            bucket.acquire().await.unwrap();
+        }
+        assert_eq!(start.elapsed(), Duration::from_secs(2));
+        assert_eq!(bucket.acquire_n(2).await, Err(RateLimitError::ExceedsCapacity(2, 1)));
+    }
+}
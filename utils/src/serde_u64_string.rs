@@ -0,0 +1,153 @@
+//!
+//! Lossless serde encoding of `u64` values, which JavaScript numbers cannot hold above 2^53 - 1.
+//!
+//! Human-readable serializers (eg. JSON) receive the value as a decimal string, while binary
+//! serializers (eg. bincode) still receive a plain `u64`. Deserialization from a human-readable
+//! format accepts both a number (or a BigInt) and a decimal or `0x`-prefixed hex string, so
+//! payloads produced before the switch to strings remain readable.
+//!
+
+use serde::{de, Deserializer, Serializer};
+use std::fmt;
+
+pub fn serialize<S>(value: &u64, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if serializer.is_human_readable() {
+        serializer.collect_str(value)
+    } else {
+        serializer.serialize_u64(*value)
+    }
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(U64Visitor)
+    } else {
+        deserializer.deserialize_u64(U64Visitor)
+    }
+}
+
+/// The largest integer a JavaScript number holds exactly
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+struct U64Visitor;
+
+impl<'de> de::Visitor<'de> for U64Visitor {
+    type Value = u64;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an unsigned 64-bit integer as a number or a string")
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        u64::try_from(v).map_err(|_| E::invalid_value(de::Unexpected::Signed(v), &self))
+    }
+
+    fn visit_u128<E: de::Error>(self, v: u128) -> Result<Self::Value, E> {
+        u64::try_from(v).map_err(|_| E::invalid_value(de::Unexpected::Other("128-bit integer"), &self))
+    }
+
+    fn visit_i128<E: de::Error>(self, v: i128) -> Result<Self::Value, E> {
+        u64::try_from(v).map_err(|_| E::invalid_value(de::Unexpected::Other("128-bit integer"), &self))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        // Only integral numbers in the exact range are accepted, larger ones were possibly rounded
+        if v.fract() == 0.0 && (0.0..=MAX_SAFE_INTEGER).contains(&v) {
+            Ok(v as u64)
+        } else {
+            Err(E::invalid_value(de::Unexpected::Float(v), &self))
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        let parsed = match v.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => v.parse::<u64>(),
+        };
+        parsed.map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+}
+
+/// Lossless serde encoding of `Option<u64>` values, see [`crate::serde_u64_string`]
+pub mod option {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(transparent)]
+    struct Wrapper(#[serde(with = "crate::serde_u64_string")] u64);
+
+    pub fn serialize<S>(value: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        value.map(Wrapper).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|Wrapper(value)| value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh::{BorshDeserialize, BorshSerialize};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Entry {
+        #[serde(with = "crate::serde_u64_string")]
+        amount: u64,
+        #[serde(with = "crate::serde_u64_string::option")]
+        balance: Option<u64>,
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        for value in [0, 1 << 53, (1 << 53) + 1, u64::MAX] {
+            let entry = Entry { amount: value, balance: Some(value) };
+            let json = serde_json::to_string(&entry).unwrap();
+            assert_eq!(json, format!(r#"{{"amount":"{value}","balance":"{value}"}}"#));
+            assert_eq!(serde_json::from_str::<Entry>(&json).unwrap(), entry);
+        }
+        let entry = Entry { amount: 7, balance: None };
+        assert_eq!(serde_json::from_str::<Entry>(&serde_json::to_string(&entry).unwrap()).unwrap(), entry);
+    }
+
+    #[test]
+    fn test_json_compatibility() {
+        let entry = serde_json::from_str::<Entry>(r#"{"amount":18446744073709551615,"balance":null}"#).unwrap();
+        assert_eq!(entry, Entry { amount: u64::MAX, balance: None });
+        let entry = serde_json::from_str::<Entry>(r#"{"amount":"0xff","balance":255.0}"#).unwrap();
+        assert_eq!(entry, Entry { amount: 255, balance: Some(255) });
+
+        // Negative, fractional or possibly rounded numbers are refused
+        assert!(serde_json::from_str::<Entry>(r#"{"amount":-1,"balance":null}"#).is_err());
+        assert!(serde_json::from_str::<Entry>(r#"{"amount":1.5,"balance":null}"#).is_err());
+        assert!(serde_json::from_str::<Entry>(r#"{"amount":1e17,"balance":null}"#).is_err());
+        assert!(serde_json::from_str::<Entry>(r#"{"amount":"18446744073709551616","balance":null}"#).is_err());
+    }
+
+    #[test]
+    fn test_binary_encodings() {
+        let entry = Entry { amount: u64::MAX, balance: Some(1) };
+        let encoded = bincode::serialize(&entry).unwrap();
+        assert_eq!(encoded[..8], u64::MAX.to_le_bytes());
+        assert_eq!(bincode::deserialize::<Entry>(&encoded).unwrap(), entry);
+        // Borsh ignores the serde attributes
+        assert_eq!(entry.try_to_vec().unwrap(), (u64::MAX, Some(1u64)).try_to_vec().unwrap());
+    }
+}
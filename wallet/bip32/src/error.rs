@@ -47,6 +47,18 @@ pub enum Error {
     #[error("Bip39 error")]
     Bip39,
 
+    /// BIP39 mnemonic word which is not part of the wordlist
+    #[error("BIP39: the word at index {0} is not part of the wordlist")]
+    Bip39Word(usize),
+
+    /// BIP39 checksum mismatch, reported against the last word which holds the checksum bits
+    #[error("BIP39: invalid checksum, the word at index {0} does not match the preceding words")]
+    Bip39Checksum(usize),
+
+    /// BIP39 entropy of unsupported length
+    #[error("BIP39: entropy length should be 16 or 32 bytes ({0} bytes supplied)")]
+    Bip39EntropyLength(usize),
+
     /// Hmac-related errors.
     #[error("HMAC -> {0}")]
     Hmac(hmac::digest::InvalidLength),
@@ -12,6 +12,7 @@ use rand_core::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use wasm_bindgen::prelude::*;
+use workflow_wasm::prelude::*;
 use zeroize::{Zeroize, Zeroizing};
 use {super::seed::Seed, hmac::Hmac, sha2::Sha512};
 
@@ -84,10 +85,18 @@ impl Mnemonic {
         self.entropy = vec;
     }
 
+    /// Create a random mnemonic of 12 or 24 (default) words.
     #[wasm_bindgen(js_name = random)]
-    pub fn create_random_js(word_count: JsValue) -> Result<Mnemonic> {
-        let word_count = word_count.as_f64().unwrap_or(24.0) as usize;
-        Mnemonic::random(word_count.try_into()?, Default::default())
+    pub fn create_random_js(word_count: Option<u32>, language: Option<Language>) -> Result<Mnemonic> {
+        let word_count = word_count.unwrap_or(24) as usize;
+        Mnemonic::random(word_count.try_into()?, language.unwrap_or_default())
+    }
+
+    /// Create a mnemonic from externally sourced entropy (e.g. dice rolls), supplied as
+    /// a `HexString` or a `Uint8Array` of 16 bytes (12 words) or 32 bytes (24 words).
+    #[wasm_bindgen(js_name = fromEntropy)]
+    pub fn from_entropy_js(entropy: JsValue, language: Option<Language>) -> Result<Mnemonic> {
+        Mnemonic::from_entropy(entropy.try_as_vec_u8()?, language.unwrap_or_default())
     }
 
     #[wasm_bindgen(getter, js_name = phrase)]
@@ -131,7 +140,7 @@ impl Mnemonic {
     /// Create a new BIP39 mnemonic phrase from the given entropy
     pub fn from_entropy(entropy: Vec<u8>, language: Language) -> Result<Self> {
         if entropy.len() != 16 && entropy.len() != 32 {
-            return Err(Error::String("Entropy length should be 16 or 32.".to_string()));
+            return Err(Error::Bip39EntropyLength(entropy.len()));
         }
 
         let wordlist = language.wordlist();
@@ -155,7 +164,8 @@ impl Mnemonic {
     /// Create a new BIP39 mnemonic phrase from the given string.
     ///
     /// The phrase supplied will be checked for word length and validated
-    /// according to the checksum specified in BIP0039.
+    /// according to the checksum specified in BIP0039. Unknown words and
+    /// checksum mismatches are reported along with the index of the failing word.
     ///
     /// To use the default language, English, (the only one supported by this
     /// library and also the only one standardized for BIP39) you can supply
@@ -170,8 +180,10 @@ impl Mnemonic {
         // Preallocate enough space for the longest possible word list
         let mut bits = BitWriter::with_capacity(264);
 
-        for word in phrase.split(' ') {
-            bits.push(wordmap.get_bits(word).ok_or(Error::Bip39)?);
+        let words = phrase.split(' ').collect::<Vec<_>>();
+        WordCount::try_from(words.len())?;
+        for (index, word) in words.iter().enumerate() {
+            bits.push(wordmap.get_bits(word).ok_or(Error::Bip39Word(index))?);
         }
 
         let mut entropy = Zeroizing::new(bits.into_bytes());
@@ -192,7 +204,8 @@ impl Mnemonic {
         let expected_checksum = Self::build_checksum(&entropy)?;
 
         if actual_checksum != expected_checksum {
-            return Err(Error::String(format!("BIP39: actual checksum({actual_checksum}) != expected checksum({expected_checksum})")));
+            // The checksum bits are all held by the last word
+            return Err(Error::Bip39Checksum(words.len() - 1));
         }
 
         Self::from_entropy(entropy.to_vec(), language)
@@ -248,11 +261,13 @@ impl Drop for Mnemonic {
 
 #[cfg(test)]
 mod tests {
-    use super::Mnemonic;
+    use super::{Mnemonic, WordCount};
+    use crate::Error;
     use crate::ExtendedPrivateKey;
     use crate::Language;
     use crate::Prefix;
     use crate::SecretKey;
+    use kaspa_utils::hex::*;
 
     #[test]
     pub fn tests() {
@@ -330,4 +345,102 @@ mod tests {
             assert_eq!(&xprv.to_string(prefix).to_string(), xprv_str, "xprv is not valid");
         }
     }
+
+    #[test]
+    fn test_bip39_reference_vectors() {
+        // Official BIP39 test vectors (https://github.com/trezor/python-mnemonic/blob/master/vectors.json)
+        // with the `TREZOR` password, limited to the supported 16 and 32 bytes of entropy
+        let vectors = [
+            [
+                "00000000000000000000000000000000",
+                "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+                "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+                "xprv9s21ZrQH143K3h3fDYiay8mocZ3afhfULfb5GX8kCBdno77K4HiA15Tg23wpbeF1pLfs1c5SPmYHrEpTuuRhxMwvKDwqdKiGJS9XFKzUsAF",
+            ],
+            [
+                "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+                "legal winner thank year wave sausage worth useful legal winner thank yellow",
+                "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607",
+                "xprv9s21ZrQH143K2gA81bYFHqU68xz1cX2APaSq5tt6MFSLeXnCKV1RVUJt9FWNTbrrryem4ZckN8k4Ls1H6nwdvDTvnV7zEXs2HgPezuVccsq",
+            ],
+            [
+                "80808080808080808080808080808080",
+                "letter advice cage absurd amount doctor acoustic avoid letter advice cage above",
+                "d71de856f81a8acc65e6fc851a38d4d7ec216fd0796d0a6827a3ad6ed5511a30fa280f12eb2e47ed2ac03b5c462a0358d18d69fe4f985ec81778c1b370b652a8",
+                "xprv9s21ZrQH143K2shfP28KM3nr5Ap1SXjz8gc2rAqqMEynmjt6o1qboCDpxckqXavCwdnYds6yBHZGKHv7ef2eTXy461PXUjBFQg6PrwY4Gzq",
+            ],
+            [
+                "ffffffffffffffffffffffffffffffff",
+                "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong",
+                "ac27495480225222079d7be181583751e86f571027b0497b5b5d11218e0a8a13332572917f0f8e5a589620c6f15b11c61dee327651a14c34e18231052e48c069",
+                "xprv9s21ZrQH143K2V4oox4M8Zmhi2Fjx5XK4Lf7GKRvPSgydU3mjZuKGCTg7UPiBUD7ydVPvSLtg9hjp7MQTYsW67rZHAXeccqYqrsx8LcXnyd",
+            ],
+            [
+                "9e885d952ad362caeb4efe34a8e91bd2",
+                "ozone drill grab fiber curtain grace pudding thank cruise elder eight picnic",
+                "274ddc525802f7c828d8ef7ddbcdc5304e87ac3535913611fbbfa986d0c9e5476c91689f9c8a54fd55bd38606aa6a8595ad213d4c9c9f9aca3fb217069a41028",
+                "xprv9s21ZrQH143K2oZ9stBYpoaZ2ktHj7jLz7iMqpgg1En8kKFTXJHsjxry1JbKH19YrDTicVwKPehFKTbmaxgVEc5TpHdS1aYhB2s9aFJBeJH",
+            ],
+            [
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art",
+                "bda85446c68413707090a52022edd26a1c9462295029f2e60cd7c4f2bbd3097170af7a4d73245cafa9c3cca8d561a7c3de6f5d4a10be8ed2a5e608d68f92fcc8",
+                "xprv9s21ZrQH143K32qBagUJAMU2LsHg3ka7jqMcV98Y7gVeVyNStwYS3U7yVVoDZ4btbRNf4h6ibWpY22iRmXq35qgLs79f312g2kj5539ebPM",
+            ],
+            [
+                "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+                "legal winner thank year wave sausage worth useful legal winner thank year wave sausage worth useful legal winner thank year wave sausage worth title",
+                "bc09fca1804f7e69da93c2f2028eb238c227f2e9dda30cd63699232578480a4021b146ad717fbb7e451ce9eb835f43620bf5c514db0f8add49f5d121449d3e87",
+                "xprv9s21ZrQH143K3Y1sd2XVu9wtqxJRvybCfAetjUrMMco6r3v9qZTBeXiBZkS8JxWbcGJZyio8TrZtm6pkbzG8SYt1sxwNLh3Wx7to5pgiVFU",
+            ],
+            [
+                "8080808080808080808080808080808080808080808080808080808080808080",
+                "letter advice cage absurd amount doctor acoustic avoid letter advice cage absurd amount doctor acoustic avoid letter advice cage absurd amount doctor acoustic bless",
+                "c0c519bd0e91a2ed54357d9d1ebef6f5af218a153624cf4f2da911a0ed8f7a09e2ef61af0aca007096df430022f7a2b6fb91661a9589097069720d015e4e982f",
+                "xprv9s21ZrQH143K3CSnQNYC3MqAAqHwxeTLhDbhF43A4ss4ciWNmCY9zQGvAKUSqVUf2vPHBTSE1rB2pg4avopqSiLVzXEU8KziNnVPauTqLRo",
+            ],
+            [
+                "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote",
+                "dd48c104698c30cfe2b6142103248622fb7bb0ff692eebb00089b32d22484e1613912f0a5b694407be899ffd31ed3992c456cdf60f5d4564b8ba3f05a69890ad",
+                "xprv9s21ZrQH143K2WFF16X85T2QCpndrGwx6GueB72Zf3AHwHJaknRXNF37ZmDrtHrrLSHvbuRejXcnYxoZKvRquTPyp2JiNG3XcjQyzSEgqCB",
+            ],
+            [
+                "68a79eaca2324873eacc50cb9c6eca8cc68ea5d936f98787c60c7ebc74e6ce7c",
+                "hamster diagram private dutch cause delay private meat slide toddler razor book happy fancy gospel tennis maple dilemma loan word shrug inflict delay length",
+                "64c87cde7e12ecf6704ab95bb1408bef047c22db4cc7491c4271d170a1b213d20b385bc1588d9c7b38f1b39d415665b8a9030c9ec653d75e65f847d8fc1fc440",
+                "xprv9s21ZrQH143K2XTAhys3pMNcGn261Fi5Ta2Pw8PwaVPhg3D8DWkzWQwjTJfskj8ofb81i9NP2cUNKxwjueJHHMQAnxtivTA75uUFqPFeWzk",
+            ],
+        ];
+
+        for [entropy, phrase, seed, xprv] in vectors {
+            let mnemonic = Mnemonic::from_entropy(Vec::<u8>::from_hex(entropy).unwrap(), Language::English).unwrap();
+            assert_eq!(mnemonic.phrase(), phrase);
+            let parsed = Mnemonic::new(phrase, Language::English).unwrap();
+            assert_eq!(parsed.entropy().to_hex(), entropy);
+
+            let seed_bytes = mnemonic.to_seed("TREZOR");
+            assert_eq!(seed_bytes.as_bytes().to_vec().to_hex(), seed);
+            let key = ExtendedPrivateKey::<SecretKey>::new(seed_bytes).unwrap();
+            assert_eq!(key.to_string(Prefix::XPRV).to_string(), xprv);
+        }
+    }
+
+    #[test]
+    fn test_mnemonic_validation_errors() {
+        let words = ["abandon"; 11].join(" ");
+        assert!(matches!(Mnemonic::new(format!("{words} about"), Language::English), Ok(_)));
+        assert!(matches!(Mnemonic::new(format!("{words} zoo"), Language::English), Err(Error::Bip39Checksum(11))));
+        assert!(matches!(Mnemonic::new(format!("{words} kaspa"), Language::English), Err(Error::Bip39Word(11))));
+        assert!(matches!(Mnemonic::new(format!("abandon abandon abandonn {words}"), Language::English), Err(Error::WordCount(14))));
+        let words = ["abandon"; 10].join(" ");
+        assert!(matches!(Mnemonic::new(format!("abandon abandonn {words}"), Language::English), Err(Error::Bip39Word(1))));
+        assert!(matches!(Mnemonic::from_entropy(vec![0; 20], Language::English), Err(Error::Bip39EntropyLength(20))));
+
+        for (word_count, len) in [(WordCount::Words12, 12), (WordCount::Words24, 24)] {
+            let mnemonic = Mnemonic::random(word_count, Language::English).unwrap();
+            assert_eq!(mnemonic.phrase().split(' ').count(), len);
+            assert!(Mnemonic::validate(mnemonic.phrase(), None));
+        }
+    }
 }
@@ -49,6 +49,7 @@ pub enum AccountDescriptorProperty {
     XpubKeys,
     Ecdsa,
     DerivationMeta,
+    ChangeAddressPolicy,
     Other(String),
 }
 
@@ -59,6 +60,7 @@ impl std::fmt::Display for AccountDescriptorProperty {
             AccountDescriptorProperty::XpubKeys => write!(f, "Xpub Keys"),
             AccountDescriptorProperty::Ecdsa => write!(f, "ECDSA"),
             AccountDescriptorProperty::DerivationMeta => write!(f, "Derivation Indexes"),
+            AccountDescriptorProperty::ChangeAddressPolicy => write!(f, "Change Address Policy"),
             AccountDescriptorProperty::Other(other) => write!(f, "{}", other),
         }
     }
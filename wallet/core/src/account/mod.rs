@@ -12,17 +12,23 @@ pub use variants::*;
 use crate::derivation::build_derivate_paths;
 use crate::derivation::AddressDerivationManagerTrait;
 use crate::imports::*;
-use crate::storage::account::AccountSettings;
+use crate::policy::{request_approval, ApprovalRequest, SpendingLedger, SpendingPolicy, SPENDING_WINDOW_MSEC};
+use crate::storage::account::{AccountSettings, ChangeAddressPolicy};
 use crate::storage::AccountMetadata;
 use crate::storage::{PrvKeyData, PrvKeyDataId};
-use crate::tx::PaymentOutput;
-use crate::tx::{Fees, Generator, GeneratorSettings, GeneratorSummary, PaymentDestination, PendingTransaction, Signer};
+use crate::tx::{
+    ChangeAddressProvider, Fees, Generator, GeneratorSettings, GeneratorSummary, PaymentDestination, PaymentOutput, PaymentOutputs,
+    PendingTransaction, Signer, UtxoSelection,
+};
 use crate::utxo::balance::{AtomicBalance, BalanceStrings};
 use crate::utxo::UtxoContextBinding;
+use kaspa_addresses::Version;
 use kaspa_bip32::{ChildNumber, ExtendedPrivateKey, PrivateKey};
 use kaspa_consensus_client::UtxoEntryReference;
+use kaspa_consensus_core::message::{sign_message, Error as MessageSigningError, MessageSignature};
 use kaspa_wallet_keys::derivation::gen0::WalletDerivationManagerV0;
 use workflow_core::abortable::Abortable;
+use workflow_core::time::unixtime_as_millis_u64;
 
 /// Notification callback type used by [`Account::sweep`] and [`Account::send`].
 /// Allows tracking in-flight transactions during transaction generation.
@@ -153,6 +159,152 @@ pub trait Account: AnySync + Send + Sync + 'static {
         Ok(())
     }
 
+    fn change_address_policy(&self) -> ChangeAddressPolicy {
+        self.context().settings.change_address_policy
+    }
+
+    async fn set_change_address_policy(&self, wallet_secret: &Secret, change_address_policy: ChangeAddressPolicy) -> Result<()> {
+        {
+            let mut context = self.context();
+            context.settings.change_address_policy = change_address_policy;
+        }
+
+        let account = self.to_storage()?;
+        self.wallet().store().as_account_store()?.store_single(&account, None).await?;
+
+        self.wallet().store().commit(wallet_secret).await?;
+        Ok(())
+    }
+
+    fn spending_policy(&self) -> SpendingPolicy {
+        self.context().settings.spending_policy.clone()
+    }
+
+    async fn set_spending_policy(&self, wallet_secret: &Secret, spending_policy: SpendingPolicy) -> Result<()> {
+        {
+            let mut context = self.context();
+            context.settings.spending_policy = spending_policy;
+        }
+
+        let account = self.to_storage()?;
+        self.wallet().store().as_account_store()?.store_single(&account, None).await?;
+
+        self.wallet().store().commit(wallet_secret).await?;
+        Ok(())
+    }
+
+    /// Returns the payments of the account made since `unixtime_msec`, loaded from its outgoing
+    /// transaction records along with its outgoing transactions not recorded yet.
+    async fn spending_ledger(&self, unixtime_msec: u64) -> Result<SpendingLedger> {
+        let mut ledger = SpendingLedger::default();
+        let binding = Binding::from(self.utxo_context().binding());
+        let network_id = self.wallet().network_id()?;
+        let filter = TransactionRecordFilter {
+            kinds: Some(vec![TransactionKind::Outgoing, TransactionKind::TransferOutgoing]),
+            from_unixtime_msec: Some(unixtime_msec),
+            ..Default::default()
+        };
+        let store = self.wallet().store().as_transaction_record_store()?;
+        let mut cursor = None;
+        loop {
+            let page = store.transactions_iter(&binding, &network_id, &filter, cursor, 100).await?;
+            page.transactions.iter().for_each(|record| ledger.insert_record(record));
+            match page.next_cursor {
+                Some(next_cursor) => cursor = Some(next_cursor),
+                None => break,
+            }
+        }
+
+        // records of submitted transactions are stored asynchronously
+        let now = unixtime_as_millis_u64();
+        for outgoing in self.utxo_context().context().outgoing.values() {
+            if let Some(payment_value) = outgoing.payment_value().filter(|_| !outgoing.is_batch()) {
+                ledger.insert(outgoing.id(), now, payment_value);
+            }
+        }
+
+        Ok(ledger)
+    }
+
+    /// Checks a payment to `outputs` against the [`SpendingPolicy`] of the account.
+    async fn check_spending_policy(&self, outputs: &PaymentOutputs) -> Result<()> {
+        let policy = self.spending_policy();
+        if policy.is_unrestricted() {
+            return Ok(());
+        }
+
+        let now = unixtime_as_millis_u64();
+        let ledger = match policy.daily_limit_sompi {
+            Some(_) => self.spending_ledger(now.saturating_sub(SPENDING_WINDOW_MSEC)).await?,
+            None => SpendingLedger::default(),
+        };
+        Ok(policy.check(&outputs.outputs, &ledger, now)?)
+    }
+
+    /// Requests the approval of the payment to `outputs` from the approval hook of the wallet if required by
+    /// the [`SpendingPolicy`] of the account. Only the final transaction of the payment is submitted for approval,
+    /// the batch transactions preceding it sending funds back to the account.
+    async fn approve_payment(&self, outputs: &PaymentOutputs, transaction: &PendingTransaction) -> Result<()> {
+        let amount = outputs.amount();
+        if transaction.is_batch() || !self.spending_policy().requires_approval(amount) {
+            return Ok(());
+        }
+
+        let request = ApprovalRequest {
+            account_id: *self.id(),
+            network_id: self.wallet().network_id()?,
+            transaction_id: transaction.id(),
+            outputs: outputs.outputs.clone(),
+            amount,
+            fees: transaction.fees(),
+            unixtime_msec: unixtime_as_millis_u64(),
+        };
+        Ok(request_approval(self.wallet().approval_hook(), &request).await?)
+    }
+
+    /// Returns the [`ChangeAddressProvider`] deriving the change addresses of transactions sent to `destination`
+    /// according to the [`ChangeAddressPolicy`] of the account, `None` if the change address is reused.
+    fn change_address_provider(self: Arc<Self>, destination: &PaymentDestination) -> Option<ChangeAddressProvider> {
+        let policy = self.change_address_policy();
+        if !policy.is_rotating() {
+            return None;
+        }
+
+        let change_address_manager = self.as_derivation_capable().ok()?.derivation().change_address_manager();
+        let provider: ChangeAddressProvider = match (policy, destination) {
+            (ChangeAddressPolicy::MatchDestinationBranch, PaymentDestination::Change) => {
+                // all transactions of the sweep share a single fresh change address
+                let sweep_address = Mutex::new(None);
+                Arc::new(move || {
+                    let mut sweep_address = sweep_address.lock().unwrap();
+                    match sweep_address.as_ref() {
+                        Some(address) => Ok(address.clone()),
+                        None => Ok(sweep_address.insert(change_address_manager.new_address()?).clone()),
+                    }
+                })
+            }
+            _ => Arc::new(move || change_address_manager.new_address()),
+        };
+
+        Some(provider)
+    }
+
+    /// Persists the address derivation metadata once `transaction` advanced the change branch by
+    /// sending its change to a rotated change address, so that a restart does not reuse the index.
+    async fn store_change_address_rotation(&self, transaction: &PendingTransaction) -> Result<()> {
+        if !transaction.generator().rotates_change_address() {
+            return Ok(());
+        }
+
+        if let Some(metadata) = self.metadata()? {
+            let store = self.wallet().store().as_account_store()?;
+            store.update_metadata(vec![metadata]).await?;
+            self.wallet().notify(Events::AccountUpdate { account_descriptor: self.descriptor()? }).await?;
+        }
+
+        Ok(())
+    }
+
     fn get_list_string(&self) -> Result<String> {
         let name = style(self.name_with_id()).blue();
         let balance = self.balance_as_strings(None)?;
@@ -218,11 +370,12 @@ pub trait Account: AnySync + Send + Sync + 'static {
                         window_size,
                         Some(extent),
                     ),
+                    // rotating change addresses advance the change branch by one address per transaction
                     Scan::new_with_address_manager(
                         derivation.change_address_manager(),
                         &balance,
                         current_daa_score,
-                        window_size,
+                        self.change_address_policy().change_scan_window_size(window_size),
                         Some(extent),
                     ),
                 ];
@@ -303,6 +456,7 @@ pub trait Account: AnySync + Send + Sync + 'static {
         let mut ids = vec![];
         while let Some(transaction) = stream.try_next().await? {
             transaction.try_sign()?;
+            self.store_change_address_rotation(&transaction).await?;
             ids.push(transaction.try_submit(&self.wallet().rpc_api()).await?);
 
             if let Some(notifier) = notifier.as_ref() {
@@ -316,27 +470,43 @@ pub trait Account: AnySync + Send + Sync + 'static {
 
     /// Send funds to a [`PaymentDestination`] comprised of one or multiple [`PaymentOutputs`](crate::tx::PaymentOutputs)
     /// or [`PaymentDestination::Change`] variant that will forward funds to the change address.
+    /// The transaction is funded by the account UTXOs according to `utxo_selection`.
     async fn send(
         self: Arc<Self>,
         destination: PaymentDestination,
         priority_fee_sompi: Fees,
         payload: Option<Vec<u8>>,
+        utxo_selection: UtxoSelection,
         wallet_secret: Secret,
         payment_secret: Option<Secret>,
         abortable: &Abortable,
         notifier: Option<GenerationNotifier>,
     ) -> Result<(GeneratorSummary, Vec<kaspa_hashes::Hash>)> {
+        // payments to the change address move funds within the account
+        let outputs = match &destination {
+            PaymentDestination::PaymentOutputs(outputs) => Some(outputs.clone()),
+            PaymentDestination::Change => None,
+        };
+        if let Some(outputs) = outputs.as_ref() {
+            self.check_spending_policy(outputs).await?;
+        }
+
         let keydata = self.prv_key_data(wallet_secret).await?;
         let signer = Arc::new(Signer::new(self.clone().as_dyn_arc(), keydata, payment_secret));
 
-        let settings = GeneratorSettings::try_new_with_account(self.clone().as_dyn_arc(), destination, priority_fee_sompi, payload)?;
+        let settings = GeneratorSettings::try_new_with_account(self.clone().as_dyn_arc(), destination, priority_fee_sompi, payload)?
+            .with_utxo_selection(utxo_selection);
 
         let generator = Generator::try_new(settings, Some(signer), Some(abortable))?;
 
         let mut stream = generator.stream();
         let mut ids = vec![];
         while let Some(transaction) = stream.try_next().await? {
+            if let Some(outputs) = outputs.as_ref() {
+                self.approve_payment(outputs, &transaction).await?;
+            }
             transaction.try_sign()?;
+            self.store_change_address_rotation(&transaction).await?;
             ids.push(transaction.try_submit(&self.wallet().rpc_api()).await?);
 
             if let Some(notifier) = notifier.as_ref() {
@@ -359,17 +529,21 @@ pub trait Account: AnySync + Send + Sync + 'static {
         abortable: &Abortable,
         notifier: Option<GenerationNotifier>,
     ) -> Result<(GeneratorSummary, Vec<kaspa_hashes::Hash>)> {
-        let keydata = self.prv_key_data(wallet_secret).await?;
-        let signer = Arc::new(Signer::new(self.clone().as_dyn_arc(), keydata, payment_secret));
-
         let destination_account = self
             .wallet()
             .get_account_by_id(&destination_account_id)
             .await?
             .ok_or_else(|| Error::AccountNotFound(destination_account_id))?;
 
+        // transfers are subject to the policy as payments to the receive address of the destination account
         let destination_address = destination_account.receive_address()?;
-        let final_transaction_destination = PaymentDestination::from(PaymentOutput::new(destination_address, transfer_amount_sompi));
+        let outputs = PaymentOutputs::from((destination_address, transfer_amount_sompi));
+        self.check_spending_policy(&outputs).await?;
+
+        let keydata = self.prv_key_data(wallet_secret).await?;
+        let signer = Arc::new(Signer::new(self.clone().as_dyn_arc(), keydata, payment_secret));
+
+        let final_transaction_destination = PaymentDestination::from(outputs.clone());
         let final_transaction_payload = None;
 
         let settings = GeneratorSettings::try_new_with_account(
@@ -385,7 +559,9 @@ pub trait Account: AnySync + Send + Sync + 'static {
         let mut stream = generator.stream();
         let mut ids = vec![];
         while let Some(transaction) = stream.try_next().await? {
+            self.approve_payment(&outputs, &transaction).await?;
             transaction.try_sign()?;
+            self.store_change_address_rotation(&transaction).await?;
             ids.push(transaction.try_submit(&self.wallet().rpc_api()).await?);
 
             if let Some(notifier) = notifier.as_ref() {
@@ -397,6 +573,99 @@ pub trait Account: AnySync + Send + Sync + 'static {
         Ok((generator.summary(), ids))
     }
 
+    /// Accelerate the outgoing transaction `transaction_id` stuck in the mempool with a child paying for its parent.
+    /// The child sends the change output of the transaction back to the change address, paying the fees required
+    /// for the package formed by the child and its unaccepted ancestors to reach `target_feerate` (in SOMPI per gram),
+    /// which is how the mempool ranks transactions spending unaccepted outputs. The parent is resubmitted first if
+    /// the node no longer holds it. Returns the ids of the parent and of the child.
+    async fn bump_fee(
+        self: Arc<Self>,
+        transaction_id: TransactionId,
+        target_feerate: f64,
+        wallet_secret: Secret,
+        payment_secret: Option<Secret>,
+        abortable: &Abortable,
+        notifier: Option<GenerationNotifier>,
+    ) -> Result<(GeneratorSummary, Vec<kaspa_hashes::Hash>)> {
+        let utxo_context = self.utxo_context().clone();
+        let parent = utxo_context
+            .context()
+            .outgoing
+            .get(&transaction_id)
+            .filter(|outgoing| !outgoing.is_accepted())
+            .cloned()
+            .ok_or(Error::BumpFeeTransactionNotFound(transaction_id))?;
+        let change = parent.change_utxo_entry().ok_or(Error::BumpFeeNoChangeOutput(transaction_id))?;
+
+        let outgoing = utxo_context.processor().outgoing();
+        if outgoing.iter().any(|outgoing| outgoing.utxo_entries().contains_key(change.id_as_ref())) {
+            return Err(Error::BumpFeeChangeAlreadySpent(transaction_id));
+        }
+
+        // the parent is mined along with its unaccepted ancestors, batch transactions of the same send included
+        let mut package = AHashSet::from([transaction_id]);
+        let mut stack = vec![parent.clone()];
+        let (mut package_fees, mut package_mass) = (0, 0);
+        while let Some(transaction) = stack.pop() {
+            package_fees += transaction.fees();
+            package_mass += transaction.pending_transaction().transaction().mass();
+            for entry in transaction.utxo_entries().values() {
+                if let Some(ancestor) = outgoing.get(entry.transaction_id_as_ref()).filter(|ancestor| !ancestor.is_accepted()) {
+                    if package.insert(ancestor.id()) {
+                        stack.push(ancestor.clone());
+                    }
+                }
+            }
+        }
+        if package_fees as f64 >= target_feerate * package_mass as f64 {
+            return Err(Error::BumpFeeTargetAlreadyReached(transaction_id));
+        }
+
+        let keydata = self.prv_key_data(wallet_secret).await?;
+        let signer = Arc::new(Signer::new(self.clone().as_dyn_arc(), keydata, payment_secret));
+        let change_address = self.change_address()?;
+
+        // the priority fee does not affect the mass of the child, so the
+        // second attempt reaches the target unless the change is too small
+        let mut priority_fee = 0;
+        for _ in 0..3 {
+            let destination = PaymentDestination::from(PaymentOutput::new(change_address.clone(), change.amount()));
+            let mut settings = GeneratorSettings::try_new_with_account(
+                self.clone().as_dyn_arc(),
+                destination,
+                Fees::ReceiverPays(priority_fee),
+                None,
+            )?;
+            settings.utxo_iterator = Box::new(std::iter::once(change.clone()));
+            let generator = Generator::try_new(settings, Some(signer.clone()), Some(abortable))?;
+
+            let child = generator.generate_transaction()?.ok_or(Error::BumpFeeNoChangeOutput(transaction_id))?;
+            child.try_sign()?;
+
+            let required_fees = (target_feerate * (package_mass + child.transaction().mass()) as f64).ceil() as u64;
+            let fees = package_fees + child.fees();
+            if fees < required_fees {
+                priority_fee += required_fees - fees;
+                continue;
+            }
+
+            let rpc = self.wallet().rpc_api();
+            if rpc.get_mempool_entry(transaction_id, false, false).await.is_err() {
+                rpc.submit_transaction(parent.pending_transaction().rpc_transaction(), false).await?;
+            }
+            self.store_change_address_rotation(&child).await?;
+            let child_id = child.try_submit(&rpc).await?;
+
+            if let Some(notifier) = notifier.as_ref() {
+                notifier(&child);
+            }
+
+            return Ok((generator.summary(), vec![transaction_id, child_id]));
+        }
+
+        Err(Error::BumpFeeTargetNotReached(transaction_id))
+    }
+
     async fn estimate(
         self: Arc<Self>,
         destination: PaymentDestination,
@@ -406,16 +675,47 @@ pub trait Account: AnySync + Send + Sync + 'static {
     ) -> Result<GeneratorSummary> {
         let settings = GeneratorSettings::try_new_with_account(self.as_dyn_arc(), destination, priority_fee_sompi, payload)?;
 
-        let generator = Generator::try_new(settings, None, Some(abortable))?;
+        let generator = Generator::try_new_estimate(settings, Some(abortable))?;
 
-        let mut stream = generator.stream();
-        while let Some(_transaction) = stream.try_next().await? {
+        while generator.estimate_transaction()? {
             yield_executor().await;
         }
 
         Ok(generator.summary())
     }
 
+    /// Sign `message` with the private key of `address`, proving the ownership of the address.
+    /// The signature can be verified by anyone with [`kaspa_consensus_core::message::verify_message`].
+    async fn sign_message(
+        self: Arc<Self>,
+        address: &Address,
+        message: &[u8],
+        wallet_secret: Secret,
+        payment_secret: Option<Secret>,
+    ) -> Result<MessageSignature> {
+        if address.version == Version::ScriptHash {
+            return Err(MessageSigningError::UnsupportedAddress(address.version).into());
+        }
+
+        let keydata = self.prv_key_data(wallet_secret).await?;
+        let private_key = if let Ok(account) = self.clone().as_derivation_capable() {
+            let (receive, change) = account.derivation().addresses_indexes(&[address])?;
+            account
+                .create_private_keys(&keydata, &payment_secret, &receive, &change)?
+                .into_iter()
+                .find_map(|(key_address, private_key)| (key_address == address).then_some(private_key))
+        } else if self.receive_address()? == *address {
+            keydata.payload.decrypt(payment_secret.as_ref())?.as_secret_key()?
+        } else {
+            None
+        };
+
+        let mut private_key = private_key.ok_or_else(|| Error::AddressNotInAccount(address.clone()))?.secret_bytes();
+        let signature = sign_message(message, &private_key, address.version);
+        private_key.zeroize();
+        Ok(signature?)
+    }
+
     fn as_derivation_capable(self: Arc<Self>) -> Result<Arc<dyn DerivationCapableAccount>> {
         Err(Error::AccountAddressDerivationCaps)
     }
@@ -196,7 +196,8 @@ impl Account for Legacy {
             self.receive_address().ok(),
             self.change_address().ok(),
         )
-        .with_property(AccountDescriptorProperty::DerivationMeta, self.derivation.address_derivation_meta().into());
+        .with_property(AccountDescriptorProperty::DerivationMeta, self.derivation.address_derivation_meta().into())
+        .with_property(AccountDescriptorProperty::ChangeAddressPolicy, self.change_address_policy().to_string().into());
 
         Ok(descriptor)
     }
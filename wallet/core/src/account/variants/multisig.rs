@@ -228,7 +228,8 @@ impl Account for MultiSig {
         )
         .with_property(AccountDescriptorProperty::XpubKeys, self.xpub_keys.clone().into())
         .with_property(AccountDescriptorProperty::Ecdsa, self.ecdsa.into())
-        .with_property(AccountDescriptorProperty::DerivationMeta, self.derivation.address_derivation_meta().into());
+        .with_property(AccountDescriptorProperty::DerivationMeta, self.derivation.address_derivation_meta().into())
+        .with_property(AccountDescriptorProperty::ChangeAddressPolicy, self.change_address_policy().to_string().into());
 
         Ok(descriptor)
     }
@@ -6,7 +6,9 @@
 //!
 
 use crate::imports::*;
+use crate::policy::SpendingPolicy;
 use crate::tx::{Fees, GeneratorSummary, PaymentDestination};
+use crate::utxo::UtxoSnapshotImportSummary;
 use kaspa_addresses::Address;
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
@@ -221,6 +223,32 @@ pub struct WalletImportResponse {
     pub wallet_descriptor: WalletDescriptor,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletExportUtxoSnapshotRequest {
+    pub snapshot_secret: Secret,
+    pub account_ids: Option<Vec<AccountId>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletExportUtxoSnapshotResponse {
+    pub snapshot_data: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletImportUtxoSnapshotRequest {
+    pub snapshot_secret: Secret,
+    pub snapshot_data: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletImportUtxoSnapshotResponse {
+    pub accounts: Vec<UtxoSnapshotImportSummary>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PrvKeyDataEnumerateRequest {}
@@ -291,6 +319,30 @@ pub struct AccountsRenameRequest {
 #[serde(rename_all = "camelCase")]
 pub struct AccountsRenameResponse {}
 
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountsSetChangeAddressPolicyRequest {
+    pub account_id: AccountId,
+    pub change_address_policy: ChangeAddressPolicy,
+    pub wallet_secret: Secret,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountsSetChangeAddressPolicyResponse {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountsSetSpendingPolicyRequest {
+    pub account_id: AccountId,
+    pub spending_policy: SpendingPolicy,
+    pub wallet_secret: Secret,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountsSetSpendingPolicyResponse {}
+
 /// @category Wallet API
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, CastFromJs)]
 #[serde(rename_all = "camelCase")]
@@ -354,14 +406,21 @@ pub struct AccountsEnsureDefaultResponse {
     pub account_descriptor: AccountDescriptor,
 }
 
-// TODO
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct AccountsImportRequest {}
+pub struct AccountsImportRequest {
+    pub wallet_secret: Secret,
+    pub payment_secret: Option<Secret>,
+    pub mnemonic_phrase: Secret,
+    /// Perform a BIP44 account discovery; if `None`, only account #0 is imported
+    pub discovery_args: Option<AccountDiscoveryArgs>,
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct AccountsImportResponse {}
+pub struct AccountsImportResponse {
+    pub account_descriptors: Vec<AccountDescriptor>,
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
@@ -479,6 +538,24 @@ pub struct AccountsTransferResponse {
     pub transaction_ids: Vec<TransactionId>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountsBumpFeeRequest {
+    pub account_id: AccountId,
+    pub transaction_id: TransactionId,
+    pub wallet_secret: Secret,
+    pub payment_secret: Option<Secret>,
+    /// Feerate in SOMPI per gram to be reached by the package of the transaction and its child
+    pub target_feerate: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountsBumpFeeResponse {
+    pub generator_summary: GeneratorSummary,
+    pub transaction_ids: Vec<TransactionId>,
+}
+
 // TODO: Use Generator Summary from WASM module...
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
@@ -521,6 +598,32 @@ pub struct TransactionsDataGetResponse {
     pub total: u64,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionsRequest {
+    pub account_id: AccountId,
+    pub network_id: NetworkId,
+    #[serde(default)]
+    pub filter: TransactionRecordFilter,
+    /// Cursor returned with the previous page, `None` for the first page
+    pub cursor: Option<TransactionCursor>,
+    pub limit: u64,
+}
+
+impl TransactionsRequest {
+    /// Maximum number of records of a page
+    pub const MAX_LIMIT: u64 = 1000;
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionsResponse {
+    pub account_id: AccountId,
+    pub transactions: Vec<Arc<TransactionRecord>>,
+    /// Cursor of the next page, `None` if there are no further records
+    pub next_cursor: Option<TransactionCursor>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionsReplaceNoteRequest {
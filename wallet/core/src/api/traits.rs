@@ -9,8 +9,10 @@
 
 use crate::api::message::*;
 use crate::imports::*;
+use crate::policy::SpendingPolicy;
 use crate::storage::{PrvKeyData, PrvKeyDataId, PrvKeyDataInfo, WalletDescriptor};
 use crate::tx::GeneratorSummary;
+use crate::utxo::UtxoSnapshotImportSummary;
 use workflow_core::channel::Receiver;
 
 ///
@@ -105,6 +107,8 @@ pub trait WalletApi: Send + Sync + AnySync {
 
     /// Enumerates all wallets available in the storage. Returns `Vec<WalletDescriptor>`
     /// that can be subsequently used to perform wallet operations such as open the wallet.
+    /// Descriptors carry the network and the time each wallet was last opened, as recorded
+    /// in the wallet registry, and are ordered from the most recently opened.
     /// See [`wallet_enumerate()`](Self::wallet_enumerate) for a convenience wrapper
     /// around this call.
     async fn wallet_enumerate_call(self: Arc<Self>, request: WalletEnumerateRequest) -> Result<WalletEnumerateResponse>;
@@ -141,6 +145,8 @@ pub trait WalletApi: Send + Sync + AnySync {
     /// Opens a wallet. A wallet is opened by it's `filename`, which is available
     /// as a part of the `WalletDescriptor` struct returned during the `wallet_enumerate_call()` call.
     /// If the `filename` is `None`, the wallet opens the default wallet named `kaspa`.
+    /// Opening a wallet which is currently open in another wallet instance fails
+    /// with [`Error::WalletLocked`](crate::error::Error::WalletLocked).
     ///
     /// If `account_descriptors` is true, this call will return `Some(Vec<AccountDescriptor>)`
     /// for all accounts in the wallet.
@@ -198,6 +204,50 @@ pub trait WalletApi: Send + Sync + AnySync {
     /// subsystem uses a custom storage backend.
     async fn wallet_import_call(self: Arc<Self>, request: WalletImportRequest) -> Result<WalletImportResponse>;
 
+    /// Wrapper around [`wallet_export_utxo_snapshot_call()`](Self::wallet_export_utxo_snapshot_call)
+    async fn wallet_export_utxo_snapshot(
+        self: Arc<Self>,
+        snapshot_secret: Secret,
+        account_ids: Option<Vec<AccountId>>,
+    ) -> Result<Vec<u8>> {
+        Ok(self
+            .wallet_export_utxo_snapshot_call(WalletExportUtxoSnapshotRequest { snapshot_secret, account_ids })
+            .await?
+            .snapshot_data)
+    }
+    /// Export the UTXO sets of the active accounts, or of the given active accounts, as a
+    /// [`UtxoSnapshot`](crate::utxo::UtxoSnapshot) encrypted with `snapshot_secret`. Importing the
+    /// snapshot with [`wallet_import_utxo_snapshot_call()`](Self::wallet_import_utxo_snapshot_call)
+    /// restores the accounts without a full address rescan.
+    ///
+    /// See [`wallet_export_utxo_snapshot`](Self::wallet_export_utxo_snapshot) for a convenience
+    /// wrapper around this call.
+    async fn wallet_export_utxo_snapshot_call(
+        self: Arc<Self>,
+        request: WalletExportUtxoSnapshotRequest,
+    ) -> Result<WalletExportUtxoSnapshotResponse>;
+
+    /// Wrapper around [`wallet_import_utxo_snapshot_call()`](Self::wallet_import_utxo_snapshot_call)
+    async fn wallet_import_utxo_snapshot(
+        self: Arc<Self>,
+        snapshot_secret: Secret,
+        snapshot_data: Vec<u8>,
+    ) -> Result<Vec<UtxoSnapshotImportSummary>> {
+        Ok(self.wallet_import_utxo_snapshot_call(WalletImportUtxoSnapshotRequest { snapshot_secret, snapshot_data }).await?.accounts)
+    }
+    /// Activate the accounts of a UTXO snapshot exported by
+    /// [`wallet_export_utxo_snapshot_call()`](Self::wallet_export_utxo_snapshot_call), restoring their
+    /// UTXO sets from the snapshot instead of scanning their addresses. The UTXO entries of the snapshot
+    /// are validated against the node and only the addresses whose UTXO set changed since the export are
+    /// rescanned. Accounts of the snapshot unknown to the wallet are skipped.
+    ///
+    /// See [`wallet_import_utxo_snapshot`](Self::wallet_import_utxo_snapshot) for a convenience
+    /// wrapper around this call.
+    async fn wallet_import_utxo_snapshot_call(
+        self: Arc<Self>,
+        request: WalletImportUtxoSnapshotRequest,
+    ) -> Result<WalletImportUtxoSnapshotResponse>;
+
     /// Wrapper around [`wallet_change_secret_call()`](Self::wallet_change_secret_call)
     async fn wallet_change_secret(self: Arc<Self>, old_wallet_secret: Secret, new_wallet_secret: Secret) -> Result<()> {
         let request = WalletChangeSecretRequest { old_wallet_secret, new_wallet_secret };
@@ -264,6 +314,53 @@ pub trait WalletApi: Send + Sync + AnySync {
     /// around this call.
     async fn accounts_rename_call(self: Arc<Self>, request: AccountsRenameRequest) -> Result<AccountsRenameResponse>;
 
+    /// Wrapper around [`accounts_set_change_address_policy_call()`](Self::accounts_set_change_address_policy_call)
+    async fn accounts_set_change_address_policy(
+        self: Arc<Self>,
+        account_id: AccountId,
+        change_address_policy: ChangeAddressPolicy,
+        wallet_secret: Secret,
+    ) -> Result<()> {
+        self.accounts_set_change_address_policy_call(AccountsSetChangeAddressPolicyRequest {
+            account_id,
+            change_address_policy,
+            wallet_secret,
+        })
+        .await?;
+        Ok(())
+    }
+    /// Change the [`ChangeAddressPolicy`] selecting the change addresses of the transactions sent by the account.
+    ///
+    /// See [`accounts_set_change_address_policy`](Self::accounts_set_change_address_policy) for a convenience
+    /// wrapper around this call.
+    async fn accounts_set_change_address_policy_call(
+        self: Arc<Self>,
+        request: AccountsSetChangeAddressPolicyRequest,
+    ) -> Result<AccountsSetChangeAddressPolicyResponse>;
+
+    /// Wrapper around [`accounts_set_spending_policy_call()`](Self::accounts_set_spending_policy_call)
+    async fn accounts_set_spending_policy(
+        self: Arc<Self>,
+        account_id: AccountId,
+        spending_policy: SpendingPolicy,
+        wallet_secret: Secret,
+    ) -> Result<()> {
+        self.accounts_set_spending_policy_call(AccountsSetSpendingPolicyRequest { account_id, spending_policy, wallet_secret })
+            .await?;
+        Ok(())
+    }
+    /// Change the [`SpendingPolicy`] limiting the payments of the account. Payments violating the policy are
+    /// denied by [`accounts_send_call()`](Self::accounts_send_call) and
+    /// [`accounts_transfer_call()`](Self::accounts_transfer_call) with an
+    /// [`Error::SpendingPolicy`](crate::error::Error::SpendingPolicy) error.
+    ///
+    /// See [`accounts_set_spending_policy`](Self::accounts_set_spending_policy) for a convenience
+    /// wrapper around this call.
+    async fn accounts_set_spending_policy_call(
+        self: Arc<Self>,
+        request: AccountsSetSpendingPolicyRequest,
+    ) -> Result<AccountsSetSpendingPolicyResponse>;
+
     async fn accounts_select(self: Arc<Self>, account_id: Option<AccountId>) -> Result<()> {
         self.accounts_select_call(AccountsSelectRequest { account_id }).await?;
         Ok(())
@@ -351,7 +448,24 @@ pub trait WalletApi: Send + Sync + AnySync {
         request: AccountsEnsureDefaultRequest,
     ) -> Result<AccountsEnsureDefaultResponse>;
 
-    // TODO
+    /// Wrapper around [`accounts_import_call()`](Self::accounts_import_call)
+    async fn accounts_import(
+        self: Arc<Self>,
+        wallet_secret: Secret,
+        payment_secret: Option<Secret>,
+        mnemonic_phrase: Secret,
+        discovery_args: Option<AccountDiscoveryArgs>,
+    ) -> Result<Vec<AccountDescriptor>> {
+        let request = AccountsImportRequest { wallet_secret, payment_secret, mnemonic_phrase, discovery_args };
+        Ok(self.accounts_import_call(request).await?.account_descriptors)
+    }
+
+    /// Import a BIP32 private key from a mnemonic phrase (and an optional BIP39
+    /// passphrase supplied as `payment_secret`). If `discovery_args` are supplied,
+    /// a BIP44 account discovery is performed and an account is created for every
+    /// account index with on-chain activity, otherwise only account #0 is created.
+    /// The discovery progress is posted as [`Events::AccountDiscovery`](crate::events::Events::AccountDiscovery)
+    /// events. Returns a list of [`AccountDescriptor`] structs for the created accounts.
     async fn accounts_import_call(self: Arc<Self>, request: AccountsImportRequest) -> Result<AccountsImportResponse>;
 
     /// Get an [`AccountDescriptor`] for a specific account id.
@@ -390,6 +504,12 @@ pub trait WalletApi: Send + Sync + AnySync {
     /// available immediately upon transaction acceptance.
     async fn accounts_transfer_call(self: Arc<Self>, request: AccountsTransferRequest) -> Result<AccountsTransferResponse>;
 
+    /// Accelerate a pending outgoing transaction of the account with a child transaction
+    /// spending its change output (child pays for parent). Returns an [`AccountsBumpFeeResponse`]
+    /// struct that contains a [`GeneratorSummary`] of the child as well as `transaction_ids`
+    /// containing the ids of the parent and of the child.
+    async fn accounts_bump_fee_call(self: Arc<Self>, request: AccountsBumpFeeRequest) -> Result<AccountsBumpFeeResponse>;
+
     /// Performs a transaction estimate, returning [`AccountsEstimateResponse`]
     /// that contains [`GeneratorSummary`]. This call will estimate the total
     /// amount of fees that will be required by the transaction as well as
@@ -410,6 +530,12 @@ pub trait WalletApi: Send + Sync + AnySync {
     }
 
     async fn transactions_data_get_call(self: Arc<Self>, request: TransactionsDataGetRequest) -> Result<TransactionsDataGetResponse>;
+
+    /// Get a page of the transaction history of a specific account id, newest first,
+    /// optionally filtered by record kind, date range and address. Returns a
+    /// [`TransactionsResponse`] holding at most `limit` records and the cursor
+    /// to be supplied in the request of the next page.
+    async fn transactions_call(self: Arc<Self>, request: TransactionsRequest) -> Result<TransactionsResponse>;
     // async fn transaction_get_call(self: Arc<Self>, request: TransactionGetRequest) -> Result<TransactionGetResponse>;
 
     /// Replaces the note of a transaction with a new note. Note is meant
@@ -82,11 +82,15 @@ impl WalletApi for WalletClient {
         WalletChangeSecret,
         WalletExport,
         WalletImport,
+        WalletExportUtxoSnapshot,
+        WalletImportUtxoSnapshot,
         PrvKeyDataEnumerate,
         PrvKeyDataCreate,
         PrvKeyDataRemove,
         PrvKeyDataGet,
         AccountsRename,
+        AccountsSetChangeAddressPolicy,
+        AccountsSetSpendingPolicy,
         AccountsSelect,
         AccountsEnumerate,
         AccountsDiscovery,
@@ -100,7 +104,9 @@ impl WalletApi for WalletClient {
         AccountsSend,
         AccountsTransfer,
         AccountsEstimate,
+        AccountsBumpFee,
         TransactionsDataGet,
+        Transactions,
         TransactionsReplaceNote,
         TransactionsReplaceMetadata,
         AddressBookEnumerate,
@@ -158,11 +164,15 @@ impl WalletServer {
         WalletChangeSecret,
         WalletExport,
         WalletImport,
+        WalletExportUtxoSnapshot,
+        WalletImportUtxoSnapshot,
         PrvKeyDataEnumerate,
         PrvKeyDataCreate,
         PrvKeyDataRemove,
         PrvKeyDataGet,
         AccountsRename,
+        AccountsSetChangeAddressPolicy,
+        AccountsSetSpendingPolicy,
         AccountsSelect,
         AccountsEnumerate,
         AccountsDiscovery,
@@ -176,7 +186,9 @@ impl WalletServer {
         AccountsSend,
         AccountsTransfer,
         AccountsEstimate,
+        AccountsBumpFee,
         TransactionsDataGet,
+        Transactions,
         TransactionsReplaceNote,
         TransactionsReplaceMetadata,
         AddressBookEnumerate,
@@ -0,0 +1,403 @@
+//!
+//! Node endpoint profiles and health-based failover of the wRPC client.
+//!
+//! Endpoint profiles are named node urls with priorities, stored per network
+//! in the wallet settings. The [`EndpointMonitor`] periodically probes the
+//! endpoints of the current network with a `GetInfo` call, tracking their
+//! latency. When the connection to the active endpoint is lost and the endpoint
+//! is unreachable, the monitor points the wRPC client to the next healthy
+//! endpoint. The client then reconnects on its own and the [`UtxoProcessor`]
+//! resubscribes its retained [`UtxoContext`]s against the new node.
+//!
+
+use crate::imports::*;
+use crate::rpc::{ConnectOptions, ConnectStrategy, RpcApi};
+use futures::pin_mut;
+use kaspa_rpc_core::api::ctl::RpcState;
+use kaspa_wrpc_client::KaspaRpcClient;
+use std::collections::BTreeMap;
+use workflow_core::task::sleep;
+
+/// A named node endpoint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Endpoint {
+    pub name: String,
+    /// Node wRPC url
+    pub url: String,
+    /// Endpoints with a lower priority value are preferred
+    pub priority: u32,
+}
+
+impl Endpoint {
+    pub fn new(name: &str, url: &str, priority: u32) -> Self {
+        Self { name: name.to_string(), url: url.to_string(), priority }
+    }
+}
+
+/// Endpoint profiles of all networks, keyed by network id, as stored
+/// under [`WalletSettings::Endpoints`](crate::settings::WalletSettings::Endpoints)
+pub type EndpointProfiles = BTreeMap<String, Vec<Endpoint>>;
+
+/// Health of an endpoint as observed by the probes of the [`EndpointMonitor`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EndpointHealth {
+    /// Round-trip time of the `GetInfo` call of the last successful probe
+    pub latency: Option<Duration>,
+    /// Number of consecutive failed probes
+    pub failures: u32,
+}
+
+impl EndpointHealth {
+    /// `true` if the last probe of the endpoint succeeded
+    pub fn is_healthy(&self) -> bool {
+        self.latency.is_some() && self.failures == 0
+    }
+
+    /// `true` if the last probe of the endpoint failed
+    pub fn is_failed(&self) -> bool {
+        self.failures > 0
+    }
+
+    fn record(&mut self, latency: Option<Duration>) {
+        match latency {
+            Some(latency) => {
+                self.latency = Some(latency);
+                self.failures = 0;
+            }
+            None => {
+                self.latency = None;
+                self.failures += 1;
+            }
+        }
+    }
+}
+
+struct Inner {
+    wrpc_client: Arc<KaspaRpcClient>,
+    processor: UtxoProcessor,
+    probe_interval: Duration,
+    probe_timeout: Duration,
+    endpoints: Mutex<Vec<Endpoint>>,
+    health: Mutex<HashMap<String, EndpointHealth>>,
+    active: Mutex<Option<Endpoint>>,
+    task_ctl: DuplexChannel,
+    running: AtomicBool,
+}
+
+/// Probes the endpoint profiles of the current network and fails the wRPC
+/// client over to the next healthy endpoint when the active one goes down.
+///
+/// The monitor only manages the client once an endpoint has been
+/// [`selected`](Self::select) or failed over to.
+#[derive(Clone)]
+pub struct EndpointMonitor {
+    inner: Arc<Inner>,
+}
+
+impl EndpointMonitor {
+    pub const DEFAULT_PROBE_INTERVAL: Duration = Duration::from_secs(30);
+    pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+    pub fn new(
+        wrpc_client: Arc<KaspaRpcClient>,
+        processor: &UtxoProcessor,
+        probe_interval: Duration,
+        probe_timeout: Duration,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                wrpc_client,
+                processor: processor.clone(),
+                probe_interval,
+                probe_timeout,
+                endpoints: Mutex::new(Vec::new()),
+                health: Mutex::new(HashMap::new()),
+                active: Mutex::new(None),
+                task_ctl: DuplexChannel::oneshot(),
+                running: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.inner.running.load(Ordering::SeqCst)
+    }
+
+    /// Endpoints of the current network, by ascending priority
+    pub fn endpoints(&self) -> Vec<Endpoint> {
+        self.inner.endpoints.lock().unwrap().clone()
+    }
+
+    /// Replaces the endpoints of the current network. The health of the retained
+    /// endpoints is kept, and the active endpoint is released if it was removed.
+    pub fn set_endpoints(&self, mut endpoints: Vec<Endpoint>) {
+        endpoints.sort_by_key(|endpoint| endpoint.priority);
+        self.inner.health.lock().unwrap().retain(|url, _| endpoints.iter().any(|endpoint| &endpoint.url == url));
+        let mut active = self.inner.active.lock().unwrap();
+        if let Some(endpoint) = active.as_ref() {
+            *active = endpoints.iter().find(|e| e.name == endpoint.name && e.url == endpoint.url).cloned();
+        }
+        *self.inner.endpoints.lock().unwrap() = endpoints;
+    }
+
+    pub fn health(&self, endpoint: &Endpoint) -> EndpointHealth {
+        self.inner.health.lock().unwrap().get(&endpoint.url).copied().unwrap_or_default()
+    }
+
+    /// The endpoint the wRPC client is pointed to
+    pub fn active(&self) -> Option<Endpoint> {
+        self.inner.active.lock().unwrap().clone()
+    }
+
+    /// Points the wRPC client to the endpoint named `name`. The client
+    /// connects to it on its next connection attempt.
+    pub async fn select(&self, name: &str) -> Result<Endpoint> {
+        let endpoint = self
+            .endpoints()
+            .into_iter()
+            .find(|endpoint| endpoint.name == name)
+            .ok_or_else(|| Error::custom(format!("Unknown node endpoint `{name}`")))?;
+        self.activate(endpoint.clone(), false).await?;
+        Ok(endpoint)
+    }
+
+    /// Releases the wRPC client from the management of the monitor
+    pub fn release(&self) {
+        self.inner.active.lock().unwrap().take();
+    }
+
+    async fn activate(&self, endpoint: Endpoint, failover: bool) -> Result<()> {
+        self.inner.wrpc_client.set_url(Some(endpoint.url.as_str()))?;
+        self.inner.active.lock().unwrap().replace(endpoint.clone());
+        self.inner
+            .processor
+            .notify(Events::NodeEndpoint {
+                network_id: self.inner.processor.network_id()?,
+                name: endpoint.name,
+                url: endpoint.url,
+                failover,
+            })
+            .await
+    }
+
+    /// Endpoints eligible for a failover from the active one: healthy
+    /// endpoints first, then the ones not probed yet, each by ascending
+    /// priority and latency. Endpoints whose last probe failed are excluded.
+    pub fn failover_candidates(&self) -> Vec<Endpoint> {
+        let active = self.active();
+        let health = self.inner.health.lock().unwrap();
+        let mut candidates = self
+            .endpoints()
+            .into_iter()
+            .filter(|endpoint| active.as_ref().map(|active| active.url != endpoint.url).unwrap_or(true))
+            .map(|endpoint| {
+                let health = health.get(&endpoint.url).copied().unwrap_or_default();
+                (endpoint, health)
+            })
+            .filter(|(_, health)| !health.is_failed())
+            .collect::<Vec<_>>();
+        candidates.sort_by(|(a, a_health), (b, b_health)| {
+            b_health
+                .is_healthy()
+                .cmp(&a_health.is_healthy())
+                .then(a.priority.cmp(&b.priority))
+                .then(a_health.latency.cmp(&b_health.latency))
+        });
+        candidates.into_iter().map(|(endpoint, _)| endpoint).collect()
+    }
+
+    async fn failover(&self) -> Result<bool> {
+        if let Some(endpoint) = self.failover_candidates().into_iter().next() {
+            log_info!(
+                "Node endpoint `{}` is unreachable, failing over to `{}` at {}",
+                self.active().map(|e| e.name).unwrap_or_default(),
+                endpoint.name,
+                endpoint.url
+            );
+            self.activate(endpoint, true).await?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Issues a `GetInfo` call, returning its round-trip time or `None`
+    /// if it failed or did not complete within the probe timeout
+    async fn timed_get_info(&self, client: &KaspaRpcClient) -> Option<Duration> {
+        let start = Instant::now();
+        select! {
+            info = client.get_info().fuse() => info.ok().map(|_| start.elapsed()),
+            _ = sleep(self.inner.probe_timeout).fuse() => None,
+        }
+    }
+
+    /// Probes the endpoint, recording its health. The active endpoint is probed
+    /// through the wRPC client while connected, others through a temporary client.
+    pub async fn probe(&self, endpoint: &Endpoint) -> EndpointHealth {
+        let wrpc_client = &self.inner.wrpc_client;
+        let is_active = self.active().map(|active| active.url == endpoint.url).unwrap_or(false);
+        let latency = if is_active && wrpc_client.is_connected() {
+            self.timed_get_info(wrpc_client).await
+        } else {
+            match KaspaRpcClient::new(wrpc_client.encoding(), Some(endpoint.url.as_str()), None, None, None) {
+                Ok(client) => {
+                    let options = ConnectOptions {
+                        block_async_connect: true,
+                        strategy: ConnectStrategy::Fallback,
+                        connect_timeout: Some(self.inner.probe_timeout),
+                        ..Default::default()
+                    };
+                    if client.connect(Some(options)).await.is_ok() {
+                        let latency = self.timed_get_info(&client).await;
+                        client.disconnect().await.ok();
+                        latency
+                    } else {
+                        None
+                    }
+                }
+                Err(err) => {
+                    log_error!("Unable to create an RPC client for node endpoint `{}`: {err}", endpoint.name);
+                    None
+                }
+            }
+        };
+
+        let mut health_map = self.inner.health.lock().unwrap();
+        let health = health_map.entry(endpoint.url.clone()).or_default();
+        health.record(latency);
+        *health
+    }
+
+    /// Probes all endpoints of the current network
+    pub async fn probe_all(&self) {
+        for endpoint in self.endpoints() {
+            self.probe(&endpoint).await;
+        }
+    }
+
+    async fn handle_disconnect(&self) -> Result<()> {
+        let Some(active) = self.active() else {
+            return Ok(());
+        };
+        // The client reconnects on its own to a node that is still reachable,
+        // which is also the case of a disconnection requested by the user
+        if !self.probe(&active).await.is_healthy() {
+            self.failover().await?;
+        }
+        Ok(())
+    }
+
+    async fn handle_probe_interval(&self) -> Result<()> {
+        self.probe_all().await;
+        // Move on if the endpoint failed over to is unreachable as well
+        if let Some(active) = self.active() {
+            if !self.inner.wrpc_client.is_connected() && self.health(&active).is_failed() {
+                self.failover().await?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn start(&self) -> Result<()> {
+        if self.is_running() {
+            return Err(Error::custom("EndpointMonitor::start() called while already running"));
+        }
+
+        let this = self.clone();
+        this.inner.running.store(true, Ordering::SeqCst);
+        let task_ctl_receiver = self.inner.task_ctl.request.receiver.clone();
+        let task_ctl_sender = self.inner.task_ctl.response.sender.clone();
+        let rpc_ctl_channel = self.inner.wrpc_client.ctl().multiplexer().channel();
+
+        spawn(async move {
+            let interval = interval(this.inner.probe_interval);
+            pin_mut!(interval);
+
+            loop {
+                select! {
+                    _ = task_ctl_receiver.recv().fuse() => {
+                        break;
+                    },
+
+                    _ = interval.next().fuse() => {
+                        if !this.endpoints().is_empty() {
+                            this.handle_probe_interval().await.unwrap_or_else(|err| log_error!("EndpointMonitor error: {err}"));
+                        }
+                    },
+
+                    msg = rpc_ctl_channel.receiver.recv().fuse() => {
+                        match msg {
+                            Ok(RpcState::Disconnected) => {
+                                this.handle_disconnect().await.unwrap_or_else(|err| log_error!("EndpointMonitor error: {err}"));
+                            }
+                            Ok(RpcState::Connected) => {}
+                            Err(err) => {
+                                log_error!("EndpointMonitor: error while receiving rpc_ctl_channel message: {err}");
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+
+            this.inner.running.store(false, Ordering::SeqCst);
+            task_ctl_sender.send(()).await.unwrap();
+        });
+        Ok(())
+    }
+
+    pub async fn stop(&self) -> Result<()> {
+        if self.is_running() {
+            self.inner.task_ctl.signal(()).await.expect("EndpointMonitor::stop() `signal` error");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::WrpcEncoding;
+
+    fn monitor() -> EndpointMonitor {
+        let wrpc_client = Arc::new(KaspaRpcClient::new(WrpcEncoding::Borsh, None, None, None, None).unwrap());
+        let processor = UtxoProcessor::new(None, Some(NetworkId::new(NetworkType::Simnet)), None, None);
+        EndpointMonitor::new(wrpc_client, &processor, EndpointMonitor::DEFAULT_PROBE_INTERVAL, EndpointMonitor::DEFAULT_PROBE_TIMEOUT)
+    }
+
+    #[test]
+    fn test_failover_candidates() {
+        let monitor = monitor();
+        let a = Endpoint::new("a", "ws://127.0.0.1:1", 0);
+        let b = Endpoint::new("b", "ws://127.0.0.1:2", 1);
+        let c = Endpoint::new("c", "ws://127.0.0.1:3", 2);
+        let d = Endpoint::new("d", "ws://127.0.0.1:4", 3);
+        monitor.set_endpoints(vec![d.clone(), c.clone(), b.clone(), a.clone()]);
+        assert_eq!(monitor.endpoints(), vec![a.clone(), b.clone(), c.clone(), d.clone()]);
+
+        // Unprobed endpoints are ranked by priority
+        assert_eq!(monitor.failover_candidates(), vec![a.clone(), b.clone(), c.clone(), d.clone()]);
+
+        // Healthy endpoints come first, failed ones are excluded
+        monitor.inner.active.lock().unwrap().replace(a.clone());
+        {
+            let mut health = monitor.inner.health.lock().unwrap();
+            health.entry(a.url.clone()).or_default().record(None);
+            health.entry(b.url.clone()).or_default().record(None);
+            health.entry(c.url.clone()).or_default().record(Some(Duration::from_millis(30)));
+            health.entry(d.url.clone()).or_default().record(Some(Duration::from_millis(10)));
+        }
+        assert_eq!(monitor.failover_candidates(), vec![c.clone(), d.clone()]);
+
+        // A recovered endpoint is eligible again
+        monitor.inner.health.lock().unwrap().get_mut(&b.url).unwrap().record(Some(Duration::from_millis(50)));
+        assert_eq!(monitor.failover_candidates(), vec![b.clone(), c.clone(), d.clone()]);
+
+        // Removing the active endpoint releases it along with the health of the removed endpoints
+        monitor.set_endpoints(vec![b.clone(), c.clone()]);
+        assert!(monitor.active().is_none());
+        assert_eq!(monitor.health(&a), EndpointHealth::default());
+        assert!(monitor.health(&b).is_healthy());
+    }
+}
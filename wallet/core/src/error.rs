@@ -5,8 +5,12 @@
 use crate::imports::{AccountId, AccountKind, AssocPrvKeyDataIds, PrvKeyDataId};
 use base64::DecodeError;
 use downcast::DowncastError;
+use kaspa_addresses::Prefix;
 use kaspa_bip32::Error as BIP32Error;
+use kaspa_consensus_core::errors::tx::CoinbaseMaturityShortfall;
+use kaspa_consensus_core::message::Error as MessageSigningError;
 use kaspa_consensus_core::sign::Error as CoreSignError;
+use kaspa_consensus_core::tx::TransactionId;
 use kaspa_rpc_core::RpcError as KaspaRpcError;
 use kaspa_wrpc_client::error::Error as KaspaWorkflowRpcError;
 use std::sync::PoisonError;
@@ -57,6 +61,9 @@ pub enum Error {
     #[error("(consensus core sign()) {0}")]
     CoreSignError(#[from] CoreSignError),
 
+    #[error("Message signing -> {0}")]
+    MessageSigningError(#[from] MessageSigningError),
+
     #[error("SerdeJson -> {0}")]
     SerdeJson(#[from] serde_json::Error),
 
@@ -72,6 +79,9 @@ pub enum Error {
     #[error("Wallet is not open")]
     WalletNotOpen,
 
+    #[error("Wallet '{0}' is already open by another wallet instance ({1})")]
+    WalletLocked(String, String),
+
     #[error("Wallet is not connected")]
     NotConnected,
 
@@ -87,6 +97,9 @@ pub enum Error {
     #[error("Invalid network type - expected: {0} connected to: {1}")]
     InvalidNetworkType(String, String),
 
+    #[error("Consensus params digest mismatch for network {0} - expected: {1} node reports: {2}")]
+    ParamsDigestMismatch(String, String, String),
+
     #[error("Invalid network suffix '{0}'")]
     InvalidNetworkSuffix(String),
 
@@ -237,6 +250,9 @@ pub enum Error {
     #[error("Address derivation processing is not supported by this account type")]
     AccountAddressDerivationCaps,
 
+    #[error("Address {0} does not belong to the account")]
+    AddressNotInAccount(Address),
+
     #[error("{0}")]
     DowncastError(String),
 
@@ -270,12 +286,54 @@ pub enum Error {
     #[error("Transaction exceeds the maximum allowed mass")]
     GeneratorTransactionIsTooHeavy,
 
+    #[error("No UTXOs selected for the transaction")]
+    GeneratorUtxoSelectionEmpty,
+
+    #[error("Selected UTXO {0} is not an unspent UTXO of the account")]
+    GeneratorUtxoSelectionNotFound(String),
+
+    #[error("Selected UTXO {0} is not mature")]
+    GeneratorUtxoSelectionImmature(String),
+
+    #[error("UTXO {0} is selected more than once")]
+    GeneratorUtxoSelectionDuplicate(String),
+
+    #[error("Selected UTXOs are insufficient, an additional {additional_needed} SOMPI are needed")]
+    GeneratorUtxoSelectionInsufficientFunds { additional_needed: u64 },
+
+    #[error("Selected UTXOs do not fit in a single transaction")]
+    GeneratorUtxoSelectionExceedsSingleTransaction,
+
+    #[error("Transactions can not be generated by a generator running in estimate mode")]
+    GeneratorEstimateOnly,
+
+    #[error("Transaction {0} is not an unaccepted outgoing transaction of the account")]
+    BumpFeeTransactionNotFound(TransactionId),
+
+    #[error("Transaction {0} has no change output")]
+    BumpFeeNoChangeOutput(TransactionId),
+
+    #[error("The change output of transaction {0} is already spent by an outgoing transaction")]
+    BumpFeeChangeAlreadySpent(TransactionId),
+
+    #[error("Transaction {0} already reaches the target feerate")]
+    BumpFeeTargetAlreadyReached(TransactionId),
+
+    #[error("Unable to reach the target feerate when spending the change output of transaction {0}")]
+    BumpFeeTargetNotReached(TransactionId),
+
+    #[error(transparent)]
+    SpendingPolicy(#[from] crate::policy::SpendingPolicyViolation),
+
     #[error("Storage mass exceeds maximum")]
     StorageMassExceedsMaximumTransactionMass { storage_mass: u64 },
 
     #[error("Invalid range {0}..{1}")]
     InvalidRange(u64, u64),
 
+    #[error("Invalid page limit {0}, expected 1..={1}")]
+    InvalidPageLimit(u64, u64),
+
     #[error(transparent)]
     MultisigCreateError(#[from] kaspa_txscript::MultisigCreateError),
 
@@ -324,6 +382,24 @@ pub enum Error {
     #[error("Invalid public key length")]
     InvalidPublicKeyLength,
 
+    #[error("Invalid payment URI: {0}")]
+    InvalidPaymentUri(String),
+
+    #[error("Payment URI network mismatch - expected: {expected} found: {found}")]
+    PaymentUriNetworkMismatch { expected: Prefix, found: Prefix },
+
+    #[error("Payment URI amount '{0}' has more than 8 decimal places")]
+    PaymentUriAmountPrecision(String),
+
+    #[error("Unable to generate QR code: {0}")]
+    QrCode(String),
+
+    #[error("UTXO snapshot does not match account {0}")]
+    UtxoSnapshotMismatch(AccountId),
+
+    #[error("UTXO snapshot network mismatch - expected: {expected} found: {found}")]
+    UtxoSnapshotNetworkMismatch { expected: NetworkId, found: NetworkId },
+
     #[error(transparent)]
     Metrics(#[from] kaspa_metrics_core::error::Error),
 }
@@ -338,6 +414,18 @@ impl Error {
     pub fn custom<T: Into<String>>(msg: T) -> Self {
         Error::Custom(msg.into())
     }
+
+    /// Returns the maturity shortfall reported by the node if this error is the rejection of a
+    /// transaction spending an immature coinbase UTXO, so its submission can be retried once
+    /// the virtual DAA score reaches [`CoinbaseMaturityShortfall::mature_daa_score`].
+    pub fn coinbase_maturity_shortfall(&self) -> Option<CoinbaseMaturityShortfall> {
+        match self {
+            Error::KaspaRpcClientResult(_) | Error::RpcError(_) | Error::KaspaWorkflowRpcError(_) => {
+                CoinbaseMaturityShortfall::from_error_message(&self.to_string())
+            }
+            _ => None,
+        }
+    }
 }
 
 impl From<chacha20poly1305::Error> for Error {
@@ -7,6 +7,8 @@
 use crate::imports::*;
 use crate::storage::{Hint, PrvKeyDataInfo, StorageDescriptor, TransactionRecord, WalletDescriptor};
 use crate::utxo::context::UtxoContextId;
+use kaspa_consensus_core::tx::TransactionOutpoint;
+use kaspa_hashes::Hash;
 use transaction::TransactionRecordNotification;
 
 /// Sync state of the kaspad node
@@ -61,12 +63,20 @@ pub enum Events {
         /// Node RPC url on which connection
         /// has been established
         url: Option<String>,
+        /// `true` if the UtxoContexts retained from a
+        /// previous connection have been re-subscribed
+        /// and refreshed against the node
+        resynced: bool,
     },
     /// RPC disconnection
     Disconnect {
         #[serde(rename = "networkId")]
         network_id: NetworkId,
         url: Option<String>,
+        /// `true` if registered UtxoContexts are retained
+        /// and will be resynced once the connection is
+        /// re-established
+        resynced: bool,
     },
     /// A special event emitted if the connected node
     /// does not have UTXO index enabled
@@ -75,6 +85,33 @@ pub enum Events {
         /// has been established
         url: Option<String>,
     },
+    /// The RPC client has been pointed to a node endpoint profile,
+    /// either on a user request or by a failover
+    NodeEndpoint {
+        #[serde(rename = "networkId")]
+        network_id: NetworkId,
+        /// Endpoint profile name
+        name: String,
+        /// Node RPC url of the endpoint
+        url: String,
+        /// `true` if the endpoint has been selected automatically
+        /// because the previously active endpoint became unreachable
+        failover: bool,
+    },
+    /// A special event emitted if the consensus params digest
+    /// reported by the connected node does not match the digest
+    /// of the network the wallet is configured for
+    ParamsDigestMismatch {
+        #[serde(rename = "networkId")]
+        network_id: NetworkId,
+        /// Params digest of the configured network
+        expected: Hash,
+        /// Params digest reported by the connected node
+        received: Hash,
+        /// Node RPC url on which connection
+        /// has been established
+        url: Option<String>,
+    },
     /// [`SyncState`] notification posted
     /// when the node sync state changes
     SyncState {
@@ -131,6 +168,16 @@ pub enum Events {
     AccountUpdate {
         account_descriptor: AccountDescriptor,
     },
+    /// Emitted for each account index scanned during
+    /// the account discovery performed on mnemonic import
+    AccountDiscovery {
+        #[serde(rename = "accountIndex")]
+        account_index: u64,
+        /// `true` if UTXOs have been found within the
+        /// scanned addresses of the account
+        #[serde(rename = "isActive")]
+        is_active: bool,
+    },
     /// Emitted after successful RPC connection
     /// after the initial state negotiation.
     ServerStatus {
@@ -159,6 +206,15 @@ pub enum Events {
     UtxoProcError {
         message: String,
     },
+    /// The UTXO set of the node has been overridden (e.g. following
+    /// an IBD from a pruning point proof), so the UTXO sets tracked
+    /// by the UtxoContexts may be stale. Followed by
+    /// [`Events::ResyncComplete`] once the UtxoContexts have been
+    /// resynced against the node.
+    ResyncRequired,
+    /// The UtxoContexts have been resynced against the node
+    /// following [`Events::ResyncRequired`].
+    ResyncComplete,
     /// DAA score change
     DaaScoreChange {
         #[serde(rename = "currentDaaScore")]
@@ -181,6 +237,22 @@ pub enum Events {
     Maturity {
         record: TransactionRecord,
     },
+    /// The node detected a double spend involving the monitored
+    /// addresses: the transaction `transaction_id` is rejected or
+    /// removed from the mempool because the transaction
+    /// `conflicting_transaction_id` spends some same outpoints.
+    TransactionConflict {
+        #[serde(rename = "transactionId")]
+        transaction_id: TransactionId,
+        #[serde(rename = "conflictingTransactionId")]
+        conflicting_transaction_id: TransactionId,
+        /// The outpoints spent by both transactions
+        outpoints: Vec<TransactionOutpoint>,
+        /// `true` if the conflicting transaction is in a block
+        /// rather than in the mempool
+        #[serde(rename = "isConflictingInBlock")]
+        is_conflicting_in_block: bool,
+    },
     /// Emitted when a transaction has been discovered
     /// during the UTXO scan. This event is generated
     /// when a runtime [`Account`]
@@ -252,6 +324,8 @@ pub enum EventKind {
     Connect,
     Disconnect,
     UtxoIndexNotEnabled,
+    NodeEndpoint,
+    ParamsDigestMismatch,
     SyncState,
     WalletStart,
     WalletHint,
@@ -266,15 +340,19 @@ pub enum EventKind {
     AccountSelection,
     AccountCreate,
     AccountUpdate,
+    AccountDiscovery,
     ServerStatus,
     UtxoProcStart,
     UtxoProcStop,
     UtxoProcError,
+    ResyncRequired,
+    ResyncComplete,
     DaaScoreChange,
     Pending,
     Reorg,
     Stasis,
     Maturity,
+    TransactionConflict,
     Discovery,
     Balance,
     Metrics,
@@ -289,6 +367,8 @@ impl From<&Events> for EventKind {
             Events::Connect { .. } => EventKind::Connect,
             Events::Disconnect { .. } => EventKind::Disconnect,
             Events::UtxoIndexNotEnabled { .. } => EventKind::UtxoIndexNotEnabled,
+            Events::NodeEndpoint { .. } => EventKind::NodeEndpoint,
+            Events::ParamsDigestMismatch { .. } => EventKind::ParamsDigestMismatch,
             Events::SyncState { .. } => EventKind::SyncState,
             Events::WalletHint { .. } => EventKind::WalletHint,
             Events::WalletOpen { .. } => EventKind::WalletOpen,
@@ -302,15 +382,19 @@ impl From<&Events> for EventKind {
             Events::AccountSelection { .. } => EventKind::AccountSelection,
             Events::AccountCreate { .. } => EventKind::AccountCreate,
             Events::AccountUpdate { .. } => EventKind::AccountUpdate,
+            Events::AccountDiscovery { .. } => EventKind::AccountDiscovery,
             Events::ServerStatus { .. } => EventKind::ServerStatus,
             Events::UtxoProcStart => EventKind::UtxoProcStart,
             Events::UtxoProcStop => EventKind::UtxoProcStop,
             Events::UtxoProcError { .. } => EventKind::UtxoProcError,
+            Events::ResyncRequired => EventKind::ResyncRequired,
+            Events::ResyncComplete => EventKind::ResyncComplete,
             Events::DaaScoreChange { .. } => EventKind::DaaScoreChange,
             Events::Pending { .. } => EventKind::Pending,
             Events::Reorg { .. } => EventKind::Reorg,
             Events::Stasis { .. } => EventKind::Stasis,
             Events::Maturity { .. } => EventKind::Maturity,
+            Events::TransactionConflict { .. } => EventKind::TransactionConflict,
             Events::Discovery { .. } => EventKind::Discovery,
             Events::Balance { .. } => EventKind::Balance,
             Events::Metrics { .. } => EventKind::Metrics,
@@ -327,6 +411,8 @@ impl FromStr for EventKind {
             "connect" => Ok(EventKind::Connect),
             "disconnect" => Ok(EventKind::Disconnect),
             "utxo-index-not-enabled" => Ok(EventKind::UtxoIndexNotEnabled),
+            "node-endpoint" => Ok(EventKind::NodeEndpoint),
+            "params-digest-mismatch" => Ok(EventKind::ParamsDigestMismatch),
             "sync-state" => Ok(EventKind::SyncState),
             "wallet-start" => Ok(EventKind::WalletStart),
             "wallet-hint" => Ok(EventKind::WalletHint),
@@ -341,15 +427,19 @@ impl FromStr for EventKind {
             "account-selection" => Ok(EventKind::AccountSelection),
             "account-create" => Ok(EventKind::AccountCreate),
             "account-update" => Ok(EventKind::AccountUpdate),
+            "account-discovery" => Ok(EventKind::AccountDiscovery),
             "server-status" => Ok(EventKind::ServerStatus),
             "utxo-proc-start" => Ok(EventKind::UtxoProcStart),
             "utxo-proc-stop" => Ok(EventKind::UtxoProcStop),
             "utxo-proc-error" => Ok(EventKind::UtxoProcError),
+            "resync-required" => Ok(EventKind::ResyncRequired),
+            "resync-complete" => Ok(EventKind::ResyncComplete),
             "daa-score-change" => Ok(EventKind::DaaScoreChange),
             "pending" => Ok(EventKind::Pending),
             "reorg" => Ok(EventKind::Reorg),
             "stasis" => Ok(EventKind::Stasis),
             "maturity" => Ok(EventKind::Maturity),
+            "transaction-conflict" => Ok(EventKind::TransactionConflict),
             "discovery" => Ok(EventKind::Discovery),
             "balance" => Ok(EventKind::Balance),
             "metrics" => Ok(EventKind::Metrics),
@@ -375,6 +465,8 @@ impl std::fmt::Display for EventKind {
             EventKind::Connect => "connect",
             EventKind::Disconnect => "disconnect",
             EventKind::UtxoIndexNotEnabled => "utxo-index-not-enabled",
+            EventKind::NodeEndpoint => "node-endpoint",
+            EventKind::ParamsDigestMismatch => "params-digest-mismatch",
             EventKind::SyncState => "sync-state",
             EventKind::WalletHint => "wallet-hint",
             EventKind::WalletOpen => "wallet-open",
@@ -388,15 +480,19 @@ impl std::fmt::Display for EventKind {
             EventKind::AccountSelection => "account-selection",
             EventKind::AccountCreate => "account-create",
             EventKind::AccountUpdate => "account-update",
+            EventKind::AccountDiscovery => "account-discovery",
             EventKind::ServerStatus => "server-status",
             EventKind::UtxoProcStart => "utxo-proc-start",
             EventKind::UtxoProcStop => "utxo-proc-stop",
             EventKind::UtxoProcError => "utxo-proc-error",
+            EventKind::ResyncRequired => "resync-required",
+            EventKind::ResyncComplete => "resync-complete",
             EventKind::DaaScoreChange => "daa-score-change",
             EventKind::Pending => "pending",
             EventKind::Reorg => "reorg",
             EventKind::Stasis => "stasis",
             EventKind::Maturity => "maturity",
+            EventKind::TransactionConflict => "transaction-conflict",
             EventKind::Discovery => "discovery",
             EventKind::Balance => "balance",
             EventKind::Metrics => "metrics",
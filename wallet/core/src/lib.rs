@@ -91,22 +91,28 @@ pub mod cryptobox;
 pub mod derivation;
 pub mod deterministic;
 pub mod encryption;
+pub mod endpoints;
 pub mod error;
 pub mod events;
 pub mod factory;
 mod imports;
 pub mod message;
 pub mod metrics;
+pub mod policy;
 pub mod prelude;
 pub mod result;
 pub mod rpc;
 pub mod serializer;
 pub mod settings;
 pub mod storage;
+pub mod subscriptions;
 pub mod tx;
+pub mod uri;
 pub mod utils;
 pub mod utxo;
 pub mod wallet;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod webhooks;
 //     }
 
 // }
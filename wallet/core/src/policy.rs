@@ -0,0 +1,494 @@
+//!
+//! Spending policies of the wallet accounts.
+//!
+//! A [`SpendingPolicy`] is persisted in the settings of an account and enforced
+//! whenever the account pays to external destinations, i.e. by
+//! [`Account::send`](crate::account::Account::send) and
+//! [`Account::transfer`](crate::account::Account::transfer). The policy bounds
+//! the amount of a single payment, the amount paid over a rolling 24 hour window
+//! and the destinations of the payments. Payments reaching the approval threshold
+//! must be approved by the [`ApprovalHook`] of the wallet before their final
+//! transaction is signed.
+//!
+//! Sweeps, fee bumps and derivation scans only send funds back to the account
+//! and are not subject to the policy.
+//!
+
+use crate::imports::*;
+use crate::tx::PaymentOutput;
+use thiserror::Error;
+
+/// Length of the rolling window of the daily limit
+pub const SPENDING_WINDOW_MSEC: u64 = 24 * 60 * 60 * 1000;
+
+/// Limits applying to the payments of an account. All limits are optional,
+/// the default policy being unrestricted.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpendingPolicy {
+    /// Maximum amount of a single payment (in SOMPI), fees excluded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_payment_sompi: Option<u64>,
+    /// Maximum amount paid over the last 24 hours (in SOMPI), fees excluded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_limit_sompi: Option<u64>,
+    /// Destinations the account can pay to, `None` allowing any destination
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_destinations: Option<Vec<Address>>,
+    /// Payments of at least this amount (in SOMPI) require an approval of the [`ApprovalHook`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval_threshold_sompi: Option<u64>,
+}
+
+impl SpendingPolicy {
+    pub fn is_unrestricted(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// `true` if a payment of `amount` requires an approval
+    pub fn requires_approval(&self, amount: u64) -> bool {
+        self.approval_threshold_sompi.is_some_and(|threshold| amount >= threshold)
+    }
+
+    /// Checks a payment to `outputs` made at `now` (unix time in milliseconds) against the
+    /// policy, `ledger` holding the previous payments of the account.
+    pub fn check(
+        &self,
+        outputs: &[PaymentOutput],
+        ledger: &SpendingLedger,
+        now: u64,
+    ) -> std::result::Result<(), SpendingPolicyViolation> {
+        if let Some(allowed_destinations) = self.allowed_destinations.as_ref() {
+            if let Some(output) = outputs.iter().find(|output| !allowed_destinations.contains(&output.address)) {
+                return Err(SpendingPolicyViolation::DestinationNotAllowed(output.address.clone()));
+            }
+        }
+
+        let amount = outputs.iter().map(|output| output.amount).sum::<u64>();
+        if let Some(limit) = self.max_payment_sompi {
+            if amount > limit {
+                return Err(SpendingPolicyViolation::PaymentLimitExceeded { amount, limit });
+            }
+        }
+        if let Some(limit) = self.daily_limit_sompi {
+            let spent = ledger.spent_since(now.saturating_sub(SPENDING_WINDOW_MSEC));
+            if spent.saturating_add(amount) > limit {
+                return Err(SpendingPolicyViolation::DailyLimitExceeded { amount, spent, limit });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Payments of an account, keyed by transaction id, the daily limit applying to their
+/// sum over the spending window
+#[derive(Debug, Clone, Default)]
+pub struct SpendingLedger {
+    payments: AHashMap<TransactionId, (u64, u64)>,
+}
+
+impl SpendingLedger {
+    /// Records a payment of `amount` made at `unixtime_msec`, a payment already recorded
+    /// under the same transaction id being kept
+    pub fn insert(&mut self, id: TransactionId, unixtime_msec: u64, amount: u64) {
+        self.payments.entry(id).or_insert((unixtime_msec, amount));
+    }
+
+    /// Records the payment of an outgoing (or outgoing transfer) transaction record, other
+    /// records holding no payment of the account
+    pub fn insert_record(&mut self, record: &TransactionRecord) {
+        let payment_value = match record.transaction_data() {
+            TransactionData::Outgoing { payment_value, .. } | TransactionData::TransferOutgoing { payment_value, .. } => {
+                *payment_value
+            }
+            _ => None,
+        };
+        if let (Some(amount), Some(unixtime_msec)) = (payment_value, record.unixtime_msec()) {
+            self.insert(*record.id(), unixtime_msec, amount);
+        }
+    }
+
+    /// Sum of the payments made at or after `unixtime_msec`
+    pub fn spent_since(&self, unixtime_msec: u64) -> u64 {
+        self.payments.values().filter(|(time, _)| *time >= unixtime_msec).map(|(_, amount)| amount).sum()
+    }
+}
+
+/// Payments denied by the [`SpendingPolicy`] of an account
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SpendingPolicyViolation {
+    #[error("Payment of {amount} SOMPI exceeds the per-transaction limit of {limit} SOMPI")]
+    PaymentLimitExceeded { amount: u64, limit: u64 },
+
+    #[error("Payment of {amount} SOMPI exceeds the daily limit of {limit} SOMPI ({spent} SOMPI spent over the last 24 hours)")]
+    DailyLimitExceeded { amount: u64, spent: u64, limit: u64 },
+
+    #[error("Destination {0} is not allowed by the spending policy")]
+    DestinationNotAllowed(Address),
+
+    #[error("Transaction {0} requires an approval but no approver could be reached: {1}")]
+    ApprovalUnavailable(TransactionId, String),
+
+    #[error("Transaction {0} was denied by the approver: {1}")]
+    ApprovalDenied(TransactionId, String),
+
+    #[error("Invalid approval of transaction {0}: {1}")]
+    InvalidApproval(TransactionId, String),
+}
+
+/// Description of a pending transaction submitted to the [`ApprovalHook`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalRequest {
+    pub account_id: AccountId,
+    pub network_id: NetworkId,
+    /// Id of the final transaction of the payment, which the approval must refer to
+    pub transaction_id: TransactionId,
+    pub outputs: Vec<PaymentOutput>,
+    /// Sum of `outputs` (in SOMPI)
+    pub amount: u64,
+    /// Fees of the transaction (in SOMPI)
+    pub fees: u64,
+    pub unixtime_msec: u64,
+}
+
+/// Decision of the approver on an [`ApprovalRequest`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalResponse {
+    pub transaction_id: TransactionId,
+    pub approved: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Second-factor confirmation of the payments reaching the approval threshold of a [`SpendingPolicy`].
+/// Failing to obtain a decision must return an error, which denies the payment.
+#[async_trait]
+pub trait ApprovalHook: Send + Sync {
+    async fn approve(&self, request: &ApprovalRequest) -> Result<ApprovalResponse>;
+}
+
+/// Requests the approval of `request` from `hook`, denying the payment if no hook is configured,
+/// if the hook fails or if its response does not approve the transaction of the request
+pub async fn request_approval(
+    hook: Option<Arc<dyn ApprovalHook>>,
+    request: &ApprovalRequest,
+) -> std::result::Result<(), SpendingPolicyViolation> {
+    let id = request.transaction_id;
+    let Some(hook) = hook else {
+        return Err(SpendingPolicyViolation::ApprovalUnavailable(id, "no approval hook is configured".to_string()));
+    };
+    let response = match hook.approve(request).await {
+        Ok(response) => response,
+        Err(Error::SpendingPolicy(violation)) => return Err(violation),
+        Err(err) => return Err(SpendingPolicyViolation::ApprovalUnavailable(id, err.to_string())),
+    };
+    if response.transaction_id != id {
+        return Err(SpendingPolicyViolation::InvalidApproval(
+            id,
+            format!("the response refers to transaction {}", response.transaction_id),
+        ));
+    }
+    if !response.approved {
+        return Err(SpendingPolicyViolation::ApprovalDenied(id, response.reason.unwrap_or_else(|| "no reason given".to_string())));
+    }
+    Ok(())
+}
+
+cfg_if! {
+    if #[cfg(not(target_arch = "wasm32"))] {
+        use crate::webhooks::{http_post, sign, verify, SIGNATURE_HEADER};
+        use std::time::Duration;
+
+        /// [`ApprovalHook`] POSTing the [`ApprovalRequest`] as JSON to an `http://` approver.
+        ///
+        /// The request is signed with the shared secret in the [`SIGNATURE_HEADER`] (see [`sign`]).
+        /// The approver must answer with a `2xx` status and an [`ApprovalResponse`] body signed
+        /// with the same secret in the same header, unsigned or badly signed responses being
+        /// rejected as invalid approvals.
+        #[derive(Debug, Clone)]
+        pub struct HttpApprovalHook {
+            url: String,
+            secret: String,
+            timeout: Duration,
+        }
+
+        impl HttpApprovalHook {
+            /// Approvers may require a human confirmation, hence the long default timeout
+            pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+
+            pub fn new(url: &str, secret: &str) -> Self {
+                Self { url: url.to_string(), secret: secret.to_string(), timeout: Self::DEFAULT_TIMEOUT }
+            }
+
+            pub fn with_timeout(mut self, timeout: Duration) -> Self {
+                self.timeout = timeout;
+                self
+            }
+        }
+
+        #[async_trait]
+        impl ApprovalHook for HttpApprovalHook {
+            async fn approve(&self, request: &ApprovalRequest) -> Result<ApprovalResponse> {
+                let body = serde_json::to_string(request)?;
+                let headers = [(SIGNATURE_HEADER, sign(self.secret.as_bytes(), body.as_bytes()))];
+                let response = async_std::future::timeout(self.timeout, http_post(&self.url, &headers, &body))
+                    .await
+                    .map_err(|_| Error::custom(format!("approval request to {} timed out", self.url)))??;
+                if !(200..300).contains(&response.status) {
+                    return Err(Error::custom(format!("approver responded with status {}", response.status)));
+                }
+
+                let invalid = |reason: &str| SpendingPolicyViolation::InvalidApproval(request.transaction_id, reason.to_string());
+                let signature = response.header(SIGNATURE_HEADER).ok_or_else(|| invalid("the response is not signed"))?;
+                if !verify(self.secret.as_bytes(), &response.body, signature) {
+                    return Err(invalid("the signature of the response is invalid").into());
+                }
+                serde_json::from_slice(&response.body).map_err(|err| invalid(&format!("malformed response: {err}")).into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_addresses::Version;
+    use kaspa_consensus_core::subnets::SUBNETWORK_ID_NATIVE;
+    use kaspa_consensus_core::tx::Transaction;
+    use kaspa_hashes::Hash;
+
+    const NOW: u64 = 1_700_000_000_000;
+    const HOUR: u64 = 60 * 60 * 1000;
+
+    fn address(n: u8) -> Address {
+        Address::new(Prefix::Testnet, Version::PubKey, &[n; 32])
+    }
+
+    fn outgoing_record(id: u64, unixtime_msec: u64, payment_value: u64, transfer: bool) -> TransactionRecord {
+        let (fees, aggregate_input_value) = (1, payment_value + 1);
+        let transaction = Transaction::new(0, vec![], vec![], id, SUBNETWORK_ID_NATIVE, 0, vec![]);
+        let transaction_data = match transfer {
+            false => TransactionData::Outgoing {
+                fees,
+                aggregate_input_value,
+                aggregate_output_value: payment_value,
+                transaction,
+                payment_value: Some(payment_value),
+                change_value: 0,
+                accepted_daa_score: None,
+                utxo_entries: vec![],
+            },
+            true => TransactionData::TransferOutgoing {
+                fees,
+                aggregate_input_value,
+                aggregate_output_value: payment_value,
+                transaction,
+                payment_value: Some(payment_value),
+                change_value: 0,
+                accepted_daa_score: None,
+                utxo_entries: vec![],
+            },
+        };
+        TransactionRecord {
+            id: TransactionId::from_u64_word(id),
+            unixtime_msec: Some(unixtime_msec),
+            value: payment_value,
+            binding: Binding::Account(AccountId(Hash::from_u64_word(1))),
+            block_daa_score: 0,
+            network_id: NetworkId::with_suffix(NetworkType::Testnet, 11),
+            transaction_data,
+            note: None,
+            metadata: None,
+            confirmation_estimate: None,
+        }
+    }
+
+    fn request(id: u64, amount: u64) -> ApprovalRequest {
+        ApprovalRequest {
+            account_id: AccountId(Hash::from_u64_word(1)),
+            network_id: NetworkId::with_suffix(NetworkType::Testnet, 11),
+            transaction_id: TransactionId::from_u64_word(id),
+            outputs: vec![PaymentOutput::new(address(1), amount)],
+            amount,
+            fees: 1000,
+            unixtime_msec: NOW,
+        }
+    }
+
+    #[test]
+    fn test_spending_policy_daily_limit_accounting() {
+        let policy = SpendingPolicy { daily_limit_sompi: Some(1000), max_payment_sompi: Some(600), ..Default::default() };
+        let payment = |amount: u64| vec![PaymentOutput::new(address(1), amount)];
+
+        // recorded payments: one outside of the window, one transfer, and a
+        // payment also pending as an outgoing transaction not yet recorded
+        let mut ledger = SpendingLedger::default();
+        ledger.insert_record(&outgoing_record(1, NOW - 25 * HOUR, 500, false));
+        ledger.insert_record(&outgoing_record(2, NOW - 23 * HOUR, 200, true));
+        ledger.insert_record(&outgoing_record(3, NOW - HOUR, 100, false));
+        ledger.insert(TransactionId::from_u64_word(3), NOW, 100);
+        assert_eq!(ledger.spent_since(NOW - SPENDING_WINDOW_MSEC), 300);
+
+        // a series of sends, each of them recorded once submitted
+        let mut id = 10;
+        for amount in [300, 250, 150] {
+            policy.check(&payment(amount), &ledger, NOW).unwrap();
+            ledger.insert(TransactionId::from_u64_word(id), NOW, amount);
+            id += 1;
+        }
+        assert_eq!(ledger.spent_since(NOW - SPENDING_WINDOW_MSEC), 1000);
+        assert_eq!(
+            policy.check(&payment(1), &ledger, NOW),
+            Err(SpendingPolicyViolation::DailyLimitExceeded { amount: 1, spent: 1000, limit: 1000 })
+        );
+        assert_eq!(
+            policy.check(&payment(601), &SpendingLedger::default(), NOW),
+            Err(SpendingPolicyViolation::PaymentLimitExceeded { amount: 601, limit: 600 })
+        );
+
+        // the window rolls past the transfer 2 hours later
+        policy.check(&payment(200), &ledger, NOW + 2 * HOUR).unwrap();
+        assert!(policy.check(&payment(201), &ledger, NOW + 2 * HOUR).is_err());
+    }
+
+    #[test]
+    fn test_spending_policy_allowed_destinations() {
+        let policy = SpendingPolicy { allowed_destinations: Some(vec![address(1), address(2)]), ..Default::default() };
+        let ledger = SpendingLedger::default();
+        policy.check(&[PaymentOutput::new(address(1), 10), PaymentOutput::new(address(2), 10)], &ledger, NOW).unwrap();
+
+        // a disallowed destination can not be slipped among allowed ones
+        let outputs = [PaymentOutput::new(address(1), 10), PaymentOutput::new(address(3), 10), PaymentOutput::new(address(2), 10)];
+        assert_eq!(policy.check(&outputs, &ledger, NOW), Err(SpendingPolicyViolation::DestinationNotAllowed(address(3))));
+
+        // transfers are checked against the receive address of the destination account
+        // and the same public key on another network is a different destination
+        let other_network = Address::new(Prefix::Mainnet, Version::PubKey, &[1; 32]);
+        assert_eq!(
+            policy.check(&[PaymentOutput::new(other_network.clone(), 10)], &ledger, NOW),
+            Err(SpendingPolicyViolation::DestinationNotAllowed(other_network))
+        );
+
+        let policy = SpendingPolicy { allowed_destinations: Some(vec![]), ..Default::default() };
+        assert!(policy.check(&[PaymentOutput::new(address(1), 10)], &ledger, NOW).is_err());
+        assert!(SpendingPolicy::default().check(&outputs, &ledger, NOW).is_ok());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    mod approval {
+        use super::*;
+        use std::io::{BufRead, BufReader, Read, Write};
+        use std::net::TcpListener;
+
+        /// What the [`MockApprover`] answers to the requests it receives
+        #[derive(Clone, Copy)]
+        enum Answer {
+            Approve,
+            Deny,
+            /// Approves another transaction than the requested one
+            ApproveOther,
+            /// Approves with a response signed with another secret
+            BadSignature,
+        }
+
+        /// A local HTTP approver verifying the signature of the requests
+        struct MockApprover {
+            url: String,
+            requests: Arc<Mutex<Vec<ApprovalRequest>>>,
+        }
+
+        impl MockApprover {
+            fn start(secret: &'static str, answers: Vec<Answer>) -> Self {
+                let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+                let url = format!("http://{}/approve", listener.local_addr().unwrap());
+                let requests = Arc::new(Mutex::new(Vec::new()));
+                let approver_requests = requests.clone();
+                std::thread::spawn(move || {
+                    for (stream, answer) in listener.incoming().zip(answers) {
+                        let mut stream = stream.unwrap();
+                        let mut reader = BufReader::new(stream.try_clone().unwrap());
+                        let mut headers = HashMap::new();
+                        let mut line = String::new();
+                        reader.read_line(&mut line).unwrap();
+                        assert!(line.starts_with("POST /approve HTTP/1.1"));
+                        loop {
+                            line.clear();
+                            reader.read_line(&mut line).unwrap();
+                            let Some((name, value)) = line.trim_end().split_once(": ") else {
+                                break;
+                            };
+                            headers.insert(name.to_lowercase(), value.to_string());
+                        }
+                        let mut body = vec![0u8; headers["content-length"].parse::<usize>().unwrap()];
+                        reader.read_exact(&mut body).unwrap();
+                        assert!(verify(secret.as_bytes(), &body, &headers[&SIGNATURE_HEADER.to_lowercase()]));
+                        let request: ApprovalRequest = serde_json::from_slice(&body).unwrap();
+                        approver_requests.lock().unwrap().push(request.clone());
+
+                        let (transaction_id, approved, secret) = match answer {
+                            Answer::Approve => (request.transaction_id, true, secret),
+                            Answer::Deny => (request.transaction_id, false, secret),
+                            Answer::ApproveOther => (TransactionId::from_u64_word(u64::MAX), true, secret),
+                            Answer::BadSignature => (request.transaction_id, true, "other secret"),
+                        };
+                        let reason = (!approved).then(|| "above the budget of the day".to_string());
+                        let response = serde_json::to_string(&ApprovalResponse { transaction_id, approved, reason }).unwrap();
+                        let signature = sign(secret.as_bytes(), response.as_bytes());
+                        write!(
+                            stream,
+                            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n{SIGNATURE_HEADER}: {signature}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                            response.len()
+                        )
+                        .unwrap();
+                    }
+                });
+                Self { url, requests }
+            }
+        }
+
+        #[tokio::test]
+        async fn test_http_approval_round_trip() {
+            let approver =
+                MockApprover::start("secret", vec![Answer::Approve, Answer::Deny, Answer::ApproveOther, Answer::BadSignature]);
+            let hook: Arc<dyn ApprovalHook> = Arc::new(HttpApprovalHook::new(&approver.url, "secret"));
+
+            request_approval(Some(hook.clone()), &request(1, 5000)).await.unwrap();
+            let received = approver.requests.lock().unwrap()[0].clone();
+            assert_eq!((received.transaction_id, received.amount), (TransactionId::from_u64_word(1), 5000));
+            assert_eq!(received.outputs[0].address, address(1));
+
+            let id = TransactionId::from_u64_word(2);
+            assert_eq!(
+                request_approval(Some(hook.clone()), &request(2, 5000)).await,
+                Err(SpendingPolicyViolation::ApprovalDenied(id, "above the budget of the day".to_string()))
+            );
+
+            let id = TransactionId::from_u64_word(3);
+            assert!(matches!(
+                request_approval(Some(hook.clone()), &request(3, 5000)).await,
+                Err(SpendingPolicyViolation::InvalidApproval(denied, _)) if denied == id
+            ));
+
+            let id = TransactionId::from_u64_word(4);
+            assert!(matches!(
+                request_approval(Some(hook.clone()), &request(4, 5000)).await,
+                Err(SpendingPolicyViolation::InvalidApproval(denied, _)) if denied == id
+            ));
+            assert_eq!(approver.requests.lock().unwrap().len(), 4);
+
+            // an unreachable approver or a missing hook denies the payment
+            let id = TransactionId::from_u64_word(5);
+            assert!(matches!(
+                request_approval(Some(hook), &request(5, 5000)).await,
+                Err(SpendingPolicyViolation::ApprovalUnavailable(denied, _)) if denied == id
+            ));
+            assert!(matches!(
+                request_approval(None, &request(5, 5000)).await,
+                Err(SpendingPolicyViolation::ApprovalUnavailable(denied, _)) if denied == id
+            ));
+        }
+    }
+}
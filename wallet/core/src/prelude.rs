@@ -10,10 +10,15 @@ pub use crate::deterministic::{AccountId, AccountStorageKey};
 pub use crate::encryption::EncryptionKind;
 pub use crate::events::{Events, SyncState};
 pub use crate::metrics::{MetricsUpdate, MetricsUpdateKind};
+pub use crate::policy::{ApprovalHook, SpendingPolicy};
 pub use crate::rpc::{ConnectOptions, ConnectStrategy, DynRpcApi};
 pub use crate::settings::WalletSettings;
-pub use crate::storage::{IdT, Interface, PrvKeyDataId, PrvKeyDataInfo, TransactionId, TransactionRecord, WalletDescriptor};
-pub use crate::tx::{Fees, PaymentDestination, PaymentOutput, PaymentOutputs};
+pub use crate::storage::{
+    ChangeAddressPolicy, IdT, Interface, PrvKeyDataId, PrvKeyDataInfo, TransactionId, TransactionRecord, WalletDescriptor,
+};
+pub use crate::subscriptions::{BalanceEvent, EventKindSet, EventSubscription, LagPolicy};
+pub use crate::tx::{Fees, PaymentDestination, PaymentOutput, PaymentOutputs, UtxoSelection};
+pub use crate::uri::PaymentUri;
 pub use crate::utxo::balance::{Balance, BalanceStrings};
 pub use crate::wallet::args::*;
 pub use crate::wallet::Wallet;
@@ -22,6 +22,8 @@ pub enum WalletSettings {
     Server,
     #[describe("Wallet storage or file name (default 'kaspa')")]
     Wallet,
+    #[describe("Node endpoint profiles per network")]
+    Endpoints,
 }
 
 #[async_trait]
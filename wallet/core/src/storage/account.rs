@@ -3,8 +3,72 @@
 //!
 
 use crate::imports::*;
+use crate::policy::SpendingPolicy;
+use crate::utxo::scan::DEFAULT_WINDOW_SIZE;
 
-const ACCOUNT_SETTINGS_VERSION: u32 = 0;
+const ACCOUNT_SETTINGS_VERSION: u32 = 2;
+
+/// Selection of the change addresses of the transactions sent by an account.
+/// Rotation applies only to accounts capable of address derivation, other
+/// accounts always reuse their single change address.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChangeAddressPolicy {
+    /// All transactions send their change to the current change address.
+    #[default]
+    Reuse,
+    /// Every generated transaction sends its change to a freshly derived change
+    /// address, advancing the change branch, so that transactions can not be
+    /// linked through their change outputs.
+    RotatePerTransaction,
+    /// Payments rotate change addresses as [`ChangeAddressPolicy::RotatePerTransaction`],
+    /// while each sweep (compounding the UTXOs of the account) sends all of its
+    /// transactions to a single fresh change address, the destination of a sweep
+    /// being the change branch itself.
+    MatchDestinationBranch,
+}
+
+impl ChangeAddressPolicy {
+    /// Factor widening the window of empty addresses after which the scan of the change branch
+    /// stops. Change outputs are usually spent by the following transactions, so the change
+    /// branch of a rotating account accumulates long runs of addresses holding no UTXOs.
+    pub const ROTATION_SCAN_WINDOW_FACTOR: usize = 4;
+
+    pub fn is_rotating(&self) -> bool {
+        !matches!(self, ChangeAddressPolicy::Reuse)
+    }
+
+    /// Returns the window of the change branch scan for the window of the receive branch scan.
+    pub fn change_scan_window_size(&self, window_size: Option<usize>) -> Option<usize> {
+        if self.is_rotating() {
+            Some(window_size.unwrap_or(DEFAULT_WINDOW_SIZE) * Self::ROTATION_SCAN_WINDOW_FACTOR)
+        } else {
+            window_size
+        }
+    }
+}
+
+impl std::fmt::Display for ChangeAddressPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangeAddressPolicy::Reuse => write!(f, "reuse"),
+            ChangeAddressPolicy::RotatePerTransaction => write!(f, "rotate-per-transaction"),
+            ChangeAddressPolicy::MatchDestinationBranch => write!(f, "match-destination-branch"),
+        }
+    }
+}
+
+impl FromStr for ChangeAddressPolicy {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "reuse" => Ok(ChangeAddressPolicy::Reuse),
+            "rotate-per-transaction" | "rotate" => Ok(ChangeAddressPolicy::RotatePerTransaction),
+            "match-destination-branch" | "match" => Ok(ChangeAddressPolicy::MatchDestinationBranch),
+            _ => Err(Error::custom(format!("invalid change address policy: '{s}'"))),
+        }
+    }
+}
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -13,6 +77,10 @@ pub struct AccountSettings {
     pub name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub meta: Option<Vec<u8>>,
+    #[serde(default)]
+    pub change_address_policy: ChangeAddressPolicy,
+    #[serde(default)]
+    pub spending_policy: SpendingPolicy,
 }
 
 impl BorshSerialize for AccountSettings {
@@ -20,6 +88,8 @@ impl BorshSerialize for AccountSettings {
         BorshSerialize::serialize(&ACCOUNT_SETTINGS_VERSION, writer)?;
         BorshSerialize::serialize(&self.name, writer)?;
         BorshSerialize::serialize(&self.meta, writer)?;
+        BorshSerialize::serialize(&self.change_address_policy, writer)?;
+        BorshSerialize::serialize(&self.spending_policy, writer)?;
 
         Ok(())
     }
@@ -27,11 +97,13 @@ impl BorshSerialize for AccountSettings {
 
 impl BorshDeserialize for AccountSettings {
     fn deserialize(buf: &mut &[u8]) -> IoResult<Self> {
-        let _version: u32 = BorshDeserialize::deserialize(buf)?;
+        let version: u32 = BorshDeserialize::deserialize(buf)?;
         let name = BorshDeserialize::deserialize(buf)?;
         let meta = BorshDeserialize::deserialize(buf)?;
+        let change_address_policy = if version >= 1 { BorshDeserialize::deserialize(buf)? } else { Default::default() };
+        let spending_policy = if version >= 2 { BorshDeserialize::deserialize(buf)? } else { Default::default() };
 
-        Ok(Self { name, meta })
+        Ok(Self { name, meta, change_address_policy, spending_policy })
     }
 }
 
@@ -150,4 +222,62 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_storage_account_settings_change_address_policy() -> Result<()> {
+        let settings = AccountSettings {
+            name: Some("rotating".to_string()),
+            meta: None,
+            change_address_policy: ChangeAddressPolicy::RotatePerTransaction,
+            spending_policy: Default::default(),
+        };
+        let settings_out = AccountSettings::try_from_slice(&settings.try_to_vec()?)?;
+        assert_eq!(settings_out.name, settings.name);
+        assert_eq!(settings_out.change_address_policy, ChangeAddressPolicy::RotatePerTransaction);
+
+        // settings stored before the change address policy was introduced reuse the change address
+        let mut data = vec![];
+        BorshSerialize::serialize(&0u32, &mut data)?;
+        BorshSerialize::serialize(&Some("legacy".to_string()), &mut data)?;
+        BorshSerialize::serialize(&None::<Vec<u8>>, &mut data)?;
+        let settings_out = AccountSettings::try_from_slice(&data)?;
+        assert_eq!(settings_out.name.as_deref(), Some("legacy"));
+        assert_eq!(settings_out.change_address_policy, ChangeAddressPolicy::Reuse);
+
+        for policy in
+            [ChangeAddressPolicy::Reuse, ChangeAddressPolicy::RotatePerTransaction, ChangeAddressPolicy::MatchDestinationBranch]
+        {
+            assert_eq!(policy.to_string().parse::<ChangeAddressPolicy>()?, policy);
+        }
+        assert_eq!(ChangeAddressPolicy::Reuse.change_scan_window_size(None), None);
+        assert_eq!(ChangeAddressPolicy::RotatePerTransaction.change_scan_window_size(Some(10)), Some(40));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_storage_account_settings_spending_policy() -> Result<()> {
+        let address = Address::try_from("kaspatest:qqnapngv3zxp305qf06w6hpzmyxtx2r99jjhs04lu980xdyd2ulwwmx9evrfz")?;
+        let spending_policy = SpendingPolicy {
+            max_payment_sompi: Some(1_000),
+            daily_limit_sompi: Some(10_000),
+            allowed_destinations: Some(vec![address]),
+            approval_threshold_sompi: None,
+        };
+        let settings = AccountSettings { spending_policy: spending_policy.clone(), ..Default::default() };
+        let settings_out = AccountSettings::try_from_slice(&settings.try_to_vec()?)?;
+        assert_eq!(settings_out.spending_policy, spending_policy);
+
+        // settings stored before the spending policy was introduced are unrestricted
+        let mut data = vec![];
+        BorshSerialize::serialize(&1u32, &mut data)?;
+        BorshSerialize::serialize(&None::<String>, &mut data)?;
+        BorshSerialize::serialize(&None::<Vec<u8>>, &mut data)?;
+        BorshSerialize::serialize(&ChangeAddressPolicy::RotatePerTransaction, &mut data)?;
+        let settings_out = AccountSettings::try_from_slice(&data)?;
+        assert_eq!(settings_out.change_address_policy, ChangeAddressPolicy::RotatePerTransaction);
+        assert!(settings_out.spending_policy.is_unrestricted());
+
+        Ok(())
+    }
 }
@@ -5,6 +5,7 @@
 use crate::imports::*;
 use async_trait::async_trait;
 use downcast::{downcast_sync, AnySync};
+use workflow_core::time::unixtime_to_locale_string;
 
 #[derive(Debug, Clone)]
 pub struct WalletExportOptions {
@@ -21,22 +22,49 @@ const TS_WALLET_DESCRIPTOR: &'static str = r#"
 export interface IWalletDescriptor {
     title?: string;
     filename: string;
+    networkId?: string;
+    lastOpened?: number;
 }
 "#;
 
 /// @category Wallet API
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
 #[wasm_bindgen(inspectable)]
 pub struct WalletDescriptor {
     #[wasm_bindgen(getter_with_clone)]
     pub title: Option<String>,
     #[wasm_bindgen(getter_with_clone)]
     pub filename: String,
+    /// Network the wallet was last opened on, if known
+    #[serde(default)]
+    #[wasm_bindgen(js_name = networkId)]
+    pub network_id: Option<NetworkId>,
+    /// Unix time (in milliseconds) at which the wallet was last opened, if known
+    #[serde(default)]
+    #[wasm_bindgen(js_name = lastOpened)]
+    pub last_opened: Option<u64>,
 }
 
 impl WalletDescriptor {
     pub fn new(title: Option<String>, filename: String) -> Self {
-        Self { title, filename }
+        Self { title, filename, network_id: None, last_opened: None }
+    }
+}
+
+impl std::fmt::Display for WalletDescriptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.filename)?;
+        if let Some(title) = &self.title {
+            write!(f, ": {title}")?;
+        }
+        let last_opened = self.last_opened.map(|last_opened| format!("last opened {}", unixtime_to_locale_string(last_opened)));
+        match (self.network_id, last_opened) {
+            (Some(network_id), Some(last_opened)) => write!(f, " ({network_id}, {last_opened})"),
+            (Some(network_id), None) => write!(f, " ({network_id})"),
+            (None, Some(last_opened)) => write!(f, " ({last_opened})"),
+            (None, None) => Ok(()),
+        }
     }
 }
 
@@ -128,6 +156,19 @@ pub trait TransactionRecordStore: Send + Sync {
         range: std::ops::Range<usize>,
     ) -> Result<TransactionRangeResult>;
 
+    /// Loads a page of at most `limit` transaction records matching `filter`, newest first,
+    /// starting after the record at `cursor` or at the newest record if no cursor is given.
+    /// The records are located through the timestamp ordered index of the store, so only
+    /// the records of the page (and, when filtering by address, the skipped ones) are loaded.
+    async fn transactions_iter(
+        &self,
+        binding: &Binding,
+        network_id: &NetworkId,
+        filter: &TransactionRecordFilter,
+        cursor: Option<TransactionCursor>,
+        limit: usize,
+    ) -> Result<TransactionPage>;
+
     async fn load_single(&self, binding: &Binding, network_id: &NetworkId, id: &TransactionId) -> Result<Arc<TransactionRecord>>;
     async fn load_multiple(
         &self,
@@ -162,6 +203,8 @@ pub struct CreateArgs {
     pub encryption_kind: EncryptionKind,
     pub user_hint: Option<Hint>,
     pub overwrite_wallet: bool,
+    /// Network the wallet is created on, recorded in the wallet registry
+    pub network_id: Option<NetworkId>,
 }
 
 impl CreateArgs {
@@ -172,24 +215,34 @@ impl CreateArgs {
         user_hint: Option<Hint>,
         overwrite_wallet: bool,
     ) -> Self {
-        Self { title, filename, encryption_kind, user_hint, overwrite_wallet }
+        Self { title, filename, encryption_kind, user_hint, overwrite_wallet, network_id: None }
+    }
+
+    pub fn with_network_id(self, network_id: Option<NetworkId>) -> Self {
+        Self { network_id, ..self }
     }
 }
 
 #[derive(Debug)]
 pub struct OpenArgs {
     pub filename: Option<String>,
+    /// Network the wallet is opened on, recorded in the wallet registry
+    pub network_id: Option<NetworkId>,
 }
 
 impl OpenArgs {
     pub fn new(filename: Option<String>) -> Self {
-        Self { filename }
+        Self { filename, network_id: None }
+    }
+
+    pub fn with_network_id(self, network_id: Option<NetworkId>) -> Self {
+        Self { network_id, ..self }
     }
 }
 
 #[async_trait]
 pub trait Interface: Send + Sync + AnySync {
-    /// enumerate all wallets available in the storage
+    /// enumerate all wallets available in the storage, most recently opened first
     async fn wallet_list(&self) -> Result<Vec<WalletDescriptor>>;
 
     /// check if a wallet is currently open
@@ -204,7 +257,7 @@ pub trait Interface: Send + Sync + AnySync {
     /// encryption used by the currently open wallet
     fn encryption_kind(&self) -> Result<EncryptionKind>;
 
-    /// rename the currently open wallet (title or the filename)
+    /// rename the currently open wallet (title or the filename), failing if the target filename exists
     async fn rename(&self, wallet_secret: &Secret, title: Option<&str>, filename: Option<&str>) -> Result<()>;
 
     /// change the secret of the currently open wallet
@@ -216,7 +269,8 @@ pub trait Interface: Send + Sync + AnySync {
     /// initialize wallet storage
     async fn create(&self, wallet_secret: &Secret, args: CreateArgs) -> Result<WalletDescriptor>;
 
-    /// establish an open state (load wallet data cache, connect to the database etc.)
+    /// establish an open state (load wallet data cache, connect to the database etc.),
+    /// failing with [`Error::WalletLocked`] if the wallet is open by another wallet instance
     async fn open(&self, wallet_secret: &Secret, args: OpenArgs) -> Result<()>;
 
     /// suspend commit operations until flush() is called
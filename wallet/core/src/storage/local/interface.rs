@@ -9,13 +9,15 @@ use crate::storage::interface::{
     AddressBookStore, CreateArgs, OpenArgs, StorageDescriptor, StorageStream, WalletDescriptor, WalletExportOptions,
 };
 use crate::storage::local::cache::*;
+use crate::storage::local::lock::FileLock;
+use crate::storage::local::registry::WalletRegistry;
 use crate::storage::local::streams::*;
 use crate::storage::local::transaction::*;
 use crate::storage::local::wallet::WalletStorage;
 use crate::storage::local::Payload;
 use crate::storage::local::Storage;
 use slugify_rs::slugify;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use workflow_core::runtime::is_web;
@@ -55,12 +57,14 @@ pub(crate) struct LocalStoreInner {
     pub store: RwLock<Arc<Store>>,
     pub transactions: Arc<dyn TransactionRecordStore>,
     pub is_modified: AtomicBool,
+    /// Lock of the wallet file, held until the wallet is closed
+    lock: Mutex<Option<Arc<FileLock>>>,
 }
 
 impl LocalStoreInner {
     async fn try_create(wallet_secret: &Secret, folder: &str, args: CreateArgs, is_resident: bool) -> Result<Self> {
-        let (store, wallet_title, filename) = if is_resident {
-            (Store::Resident, Some("Resident Wallet".to_string()), "resident".to_string())
+        let (store, wallet_title, filename, lock) = if is_resident {
+            (Store::Resident, Some("Resident Wallet".to_string()), "resident".to_string(), None)
         } else {
             // log_info!("LocalStoreInner::try_create: folder: {}, args: {:?}, is_resident: {}", folder, args, is_resident);
 
@@ -68,10 +72,12 @@ impl LocalStoreInner {
             let filename = make_filename(&title, &args.filename);
 
             let storage = Storage::try_new_with_folder(folder, &format!("{filename}.wallet"))?;
+            // Locking before checking for existence, so concurrent creations of the same wallet can not both succeed
+            let lock = Arc::new(FileLock::try_acquire(storage.filename())?);
             if storage.exists().await? && !args.overwrite_wallet {
                 return Err(Error::WalletAlreadyExists);
             }
-            (Store::Storage(storage), title, filename)
+            (Store::Storage(storage), title, filename, Some(lock))
         };
 
         let payload = Payload::default();
@@ -84,13 +90,18 @@ impl LocalStoreInner {
             Arc::new(indexdb::TransactionStore::new(&filename))
         };
 
-        Ok(Self { cache, store: RwLock::new(Arc::new(store)), is_modified, transactions })
+        Ok(Self { cache, store: RwLock::new(Arc::new(store)), is_modified, transactions, lock: Mutex::new(lock) })
     }
 
-    async fn try_load(wallet_secret: &Secret, folder: &str, args: OpenArgs) -> Result<Self> {
+    /// Loads a wallet, reusing the lock of the `current` wallet if it is the same wallet being reopened
+    async fn try_load(wallet_secret: &Secret, folder: &str, args: &OpenArgs, current: Option<&LocalStoreInner>) -> Result<Self> {
         let filename = make_filename(&None, &args.filename);
         let storage = Storage::try_new_with_folder(folder, &format!("{filename}.wallet"))?;
 
+        let lock = match current.and_then(|current| current.lock_of(storage.filename())) {
+            Some(lock) => lock,
+            None => Arc::new(FileLock::try_acquire(storage.filename())?),
+        };
         let wallet = WalletStorage::try_load(&storage).await?;
         let cache = Arc::new(RwLock::new(Cache::from_wallet(wallet, wallet_secret)?));
         let is_modified = AtomicBool::new(false);
@@ -101,7 +112,13 @@ impl LocalStoreInner {
             Arc::new(indexdb::TransactionStore::new(&filename))
         };
 
-        Ok(Self { cache, store: RwLock::new(Arc::new(Store::Storage(storage))), is_modified, transactions })
+        Ok(Self {
+            cache,
+            store: RwLock::new(Arc::new(Store::Storage(storage))),
+            is_modified,
+            transactions,
+            lock: Mutex::new(Some(lock)),
+        })
     }
 
     async fn try_import(wallet_secret: &Secret, folder: &str, serialized_wallet_storage: &[u8]) -> Result<Self> {
@@ -113,6 +130,7 @@ impl LocalStoreInner {
 
         let filename = make_filename(&wallet.title, &None);
         let storage = Storage::try_new_with_folder(folder, &format!("{filename}.wallet"))?;
+        let lock = Arc::new(FileLock::try_acquire(storage.filename())?);
         if storage.exists_sync()? {
             return Err(Error::WalletAlreadyExists);
         }
@@ -126,7 +144,13 @@ impl LocalStoreInner {
             Arc::new(indexdb::TransactionStore::new(&filename))
         };
 
-        Ok(Self { cache, store: RwLock::new(Arc::new(Store::Storage(storage))), is_modified, transactions })
+        Ok(Self {
+            cache,
+            store: RwLock::new(Arc::new(Store::Storage(storage))),
+            is_modified,
+            transactions,
+            lock: Mutex::new(Some(lock)),
+        })
     }
 
     async fn try_export(&self, wallet_secret: &Secret, _options: WalletExportOptions) -> Result<Vec<u8>> {
@@ -138,14 +162,32 @@ impl LocalStoreInner {
         self.store.read().unwrap().clone()
     }
 
+    /// Returns the lock of this wallet if it is stored in `filename`
+    fn lock_of(&self, filename: &Path) -> Option<Arc<FileLock>> {
+        match &*self.storage() {
+            Store::Storage(storage) if storage.filename() == filename => self.lock.lock().unwrap().clone(),
+            _ => None,
+        }
+    }
+
     fn rename(&self, filename: &str) -> Result<()> {
         let store = (**self.store.read().unwrap()).clone();
-        let filename = make_filename(&None, &Some(filename.to_string()));
+        let filename = format!("{}.wallet", make_filename(&None, &Some(filename.to_string())));
         match store {
             Store::Resident => Err(Error::ResidentWallet),
             Store::Storage(mut storage) => {
+                let target = storage.filename().with_file_name(&filename);
+                if target == *storage.filename() {
+                    return Ok(());
+                }
+                // The target is locked first so that no other instance can create or open it meanwhile
+                let lock = Arc::new(FileLock::try_acquire(&target)?);
+                if fs::exists_sync(&target)? {
+                    return Err(Error::WalletAlreadyExists);
+                }
                 storage.rename_sync(filename.as_str())?;
                 *self.store.write().unwrap() = Arc::new(Store::Storage(storage));
+                self.lock.lock().unwrap().replace(lock);
                 Ok(())
             }
         }
@@ -235,16 +277,23 @@ impl LocalStoreInner {
     }
 
     async fn close(&self) -> Result<()> {
+        self.release_lock();
         Ok(())
     }
 
+    /// Releases the wallet file lock. The inner store may remain referenced for
+    /// a while once closed or replaced, so the lock is released explicitly.
+    fn release_lock(&self) {
+        self.lock.lock().unwrap().take();
+    }
+
     fn descriptor(&self) -> WalletDescriptor {
         let filename = self
             .storage()
             .filename()
             .and_then(|f| PathBuf::from(f).file_stem().and_then(|f| f.to_str().map(String::from)))
             .unwrap_or_else(|| "resident".to_string());
-        WalletDescriptor { title: self.cache.read().unwrap().wallet_title.clone(), filename }
+        WalletDescriptor::new(self.cache.read().unwrap().wallet_title.clone(), filename)
     }
 
     fn location(&self) -> Result<StorageDescriptor> {
@@ -306,6 +355,21 @@ impl LocalStore {
         self.location.lock().unwrap().clone()
     }
 
+    /// Updates the wallet registry. The registry only holds informative metadata,
+    /// so failing to update it does not fail the wallet operation.
+    async fn update_registry<F>(&self, update: F)
+    where
+        F: FnOnce(&mut WalletRegistry) + Send,
+    {
+        if self.is_resident {
+            return;
+        }
+        let location = self.location().expect("initialized wallet storage location");
+        if let Err(err) = WalletRegistry::update(&location.folder, update).await {
+            log_warn!("Unable to update the wallet registry: {err}");
+        }
+    }
+
     #[allow(dead_code)]
     async fn wallet_export_impl(&self, wallet_secret: &Secret, _options: WalletExportOptions) -> Result<Vec<u8>> {
         self.inner()?.try_export(wallet_secret, _options).await
@@ -351,10 +415,15 @@ impl Interface for LocalStore {
         if let Some(title) = title {
             inner.cache.write().unwrap().wallet_title = Some(title.to_string());
             self.commit(wallet_secret).await?;
+            let filename = inner.descriptor().filename;
+            self.update_registry(|registry| registry.set_title(&filename, Some(title.to_string()))).await;
         }
 
         if let Some(filename) = filename {
+            let previous = inner.descriptor().filename;
             inner.rename(filename)?;
+            let filename = inner.descriptor().filename;
+            self.update_registry(|registry| registry.rename(&previous, &filename)).await;
         }
         Ok(())
     }
@@ -376,10 +445,14 @@ impl Interface for LocalStore {
     async fn create(&self, wallet_secret: &Secret, args: CreateArgs) -> Result<WalletDescriptor> {
         let location = self.location().expect("initialized wallet storage location");
 
+        let network_id = args.network_id;
         let inner = Arc::new(LocalStoreInner::try_create(wallet_secret, &location.folder, args, self.is_resident).await?);
         let descriptor = inner.descriptor();
         self.inner.lock().unwrap().replace(inner);
 
+        let WalletDescriptor { title, filename, .. } = descriptor.clone();
+        self.update_registry(|registry| registry.record_open(&filename, title, network_id)).await;
+
         Ok(descriptor)
     }
 
@@ -391,8 +464,14 @@ impl Interface for LocalStore {
         }
 
         let location = self.location.lock().unwrap().clone().unwrap();
-        let inner = Arc::new(LocalStoreInner::try_load(wallet_secret, &location.folder, args).await?);
-        self.inner.lock().unwrap().replace(inner);
+        let current = self.inner.lock().unwrap().clone();
+        let inner = Arc::new(LocalStoreInner::try_load(wallet_secret, &location.folder, &args, current.as_deref()).await?);
+        let WalletDescriptor { title, filename, .. } = inner.descriptor();
+        if let Some(previous) = self.inner.lock().unwrap().replace(inner) {
+            previous.release_lock();
+        }
+
+        self.update_registry(|registry| registry.record_open(&filename, title, args.network_id)).await;
         Ok(())
     }
 
@@ -409,6 +488,7 @@ impl Interface for LocalStore {
             })
             .collect::<Vec<_>>();
 
+        let registry = WalletRegistry::load(&location.folder).await?;
         let mut descriptors = vec![];
         for filename in wallets.into_iter() {
             let path = folder.join(format!("{}.wallet", filename));
@@ -416,8 +496,10 @@ impl Interface for LocalStore {
             let wallet_data = fs::read(&path).await;
             let title =
                 wallet_data.ok().and_then(|data| WalletStorage::try_from_slice(data.as_slice()).ok()).and_then(|wallet| wallet.title);
-            descriptors.push(WalletDescriptor { title, filename });
+            let entry = registry.get(&filename).cloned().unwrap_or_default();
+            descriptors.push(WalletDescriptor { title, filename, network_id: entry.network_id, last_opened: entry.last_opened });
         }
+        descriptors.sort_by(|a, b| b.last_opened.cmp(&a.last_opened).then_with(|| a.filename.cmp(&b.filename)));
 
         Ok(descriptors)
     }
@@ -626,3 +708,109 @@ impl AddressBookStore for LocalStoreInner {
         Ok(matches)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::Barrier;
+
+    const OPENERS: usize = 8;
+
+    fn local_store(folder: &str) -> LocalStore {
+        let store = LocalStore::try_new(false).unwrap();
+        store.location.lock().unwrap().replace(Arc::new(Location::new(folder)));
+        store
+    }
+
+    /// Runs `OPENERS` concurrent operations on distinct stores of the same folder, returning the stores with their results
+    async fn race<F, Fut>(folder: &str, op: F) -> Vec<(LocalStore, Result<()>)>
+    where
+        F: Fn(LocalStore) -> Fut,
+        Fut: std::future::Future<Output = (LocalStore, Result<()>)> + Send + 'static,
+    {
+        let barrier = Arc::new(Barrier::new(OPENERS));
+        let tasks = (0..OPENERS).map(|_| {
+            let barrier = barrier.clone();
+            let op = op(local_store(folder));
+            tokio::spawn(async move {
+                barrier.wait().await;
+                op.await
+            })
+        });
+        join_all(tasks).await.into_iter().map(|result| result.unwrap()).collect()
+    }
+
+    fn split_winner(results: Vec<(LocalStore, Result<()>)>) -> LocalStore {
+        let (winners, losers): (Vec<_>, Vec<_>) = results.into_iter().partition(|(_, result)| result.is_ok());
+        assert_eq!(winners.len(), 1, "exactly one instance acquires the wallet");
+        for (_, result) in losers {
+            assert!(matches!(result, Err(Error::WalletLocked(ref name, _)) if name == "racing"), "unexpected result {result:?}");
+        }
+        winners.into_iter().next().unwrap().0
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_local_store_racing_openers() -> Result<()> {
+        let folder = tempfile::tempdir()?;
+        let folder = folder.path().to_str().unwrap().to_string();
+        let wallet_secret = Secret::from("racing-secret");
+
+        // Concurrent creations of the same wallet
+        let results = race(&folder, |store| {
+            let wallet_secret = wallet_secret.clone();
+            async move {
+                let args = CreateArgs::new(
+                    Some("Racing".to_string()),
+                    Some("racing".to_string()),
+                    EncryptionKind::XChaCha20Poly1305,
+                    None,
+                    false,
+                );
+                let result = store.create(&wallet_secret, args).await.map(|_| ());
+                (store, result)
+            }
+        })
+        .await;
+        let winner = split_winner(results);
+        winner.commit(&wallet_secret).await?;
+        winner.close().await?;
+        let args = CreateArgs::new(None, Some("racing".to_string()), EncryptionKind::XChaCha20Poly1305, None, false);
+        assert!(matches!(local_store(&folder).create(&wallet_secret, args).await, Err(Error::WalletAlreadyExists)));
+
+        // Concurrent openings of the wallet, the winner modifying it while the others are refused
+        let results = race(&folder, |store| {
+            let wallet_secret = wallet_secret.clone();
+            async move {
+                let result = store.open(&wallet_secret, OpenArgs::new(Some("racing".to_string()))).await;
+                (store, result)
+            }
+        })
+        .await;
+        let winner = split_winner(results);
+        winner.rename(&wallet_secret, Some("Winner"), None).await?;
+        // Reopening the wallet from the instance holding it reuses its lock
+        winner.open(&wallet_secret, OpenArgs::new(Some("racing".to_string()))).await?;
+        let other = local_store(&folder);
+        assert!(matches!(other.open(&wallet_secret, OpenArgs::new(Some("racing".to_string()))).await, Err(Error::WalletLocked(..))));
+        winner.close().await?;
+
+        // Once closed, the wallet can be opened elsewhere and holds the changes of the winner
+        other.open(&wallet_secret, OpenArgs::new(Some("racing".to_string()))).await?;
+        assert_eq!(other.descriptor().unwrap().title.as_deref(), Some("Winner"));
+        let wallets = other.wallet_list().await?;
+        assert_eq!(wallets.len(), 1);
+        assert_eq!(wallets[0].title.as_deref(), Some("Winner"));
+        assert!(wallets[0].last_opened.is_some());
+
+        // Renaming moves the lock along with the wallet file
+        other.rename(&wallet_secret, None, Some("renamed")).await?;
+        assert_eq!(other.wallet_list().await?.into_iter().map(|wallet| wallet.filename).collect::<Vec<_>>(), vec!["renamed"]);
+        let third = local_store(&folder);
+        assert!(matches!(third.open(&wallet_secret, OpenArgs::new(Some("renamed".to_string()))).await, Err(Error::WalletLocked(..))));
+        other.close().await?;
+        third.open(&wallet_secret, OpenArgs::new(Some("renamed".to_string()))).await?;
+        third.close().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+//!
+//! Advisory locking of wallet storage files, preventing multiple
+//! wallet instances (within one or several processes) from opening
+//! and writing the same wallet file concurrently. Locking is
+//! not available in web environments, where it is a no-op.
+//!
+
+use crate::imports::*;
+
+cfg_if! {
+    if #[cfg(not(target_arch = "wasm32"))] {
+        use fs2::FileExt;
+        use std::fs::{File, OpenOptions};
+        use std::io::{Read, Seek, SeekFrom, Write};
+        use std::path::{Path, PathBuf};
+        use std::time::Duration;
+        use workflow_core::time::unixtime_as_millis_u64;
+
+        /// Delay between attempts of [`FileLock::acquire`]
+        const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
+        /// Information about the lock owner, recorded in the lock file
+        #[derive(Debug, Clone, Serialize, Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        pub struct LockOwner {
+            pub pid: u32,
+            /// Unix time (in milliseconds) at which the lock was acquired
+            pub acquired: u64,
+        }
+
+        impl LockOwner {
+            fn current() -> Self {
+                Self { pid: std::process::id(), acquired: unixtime_as_millis_u64() }
+            }
+        }
+
+        impl std::fmt::Display for LockOwner {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "held by process {} since {}", self.pid, workflow_core::time::unixtime_to_locale_string(self.acquired))
+            }
+        }
+
+        /// An exclusive lock on a `<file>.lock` file located next to the locked file,
+        /// released when this value is dropped.
+        ///
+        /// The lock is an OS level `flock`-style advisory lock, so the OS releases it
+        /// if its owner dies. Lock files are never removed, as a concurrent opener
+        /// could otherwise end up locking an unlinked file; instead they are truncated
+        /// when released. A lock file found non-empty once locked has thus been left
+        /// by an owner which exited without releasing it, and is taken over.
+        pub struct FileLock {
+            file: File,
+            path: PathBuf,
+        }
+
+        impl FileLock {
+            /// Acquires the lock of `path`, failing with [`Error::WalletLocked`] if it is already held
+            pub fn try_acquire(path: &Path) -> Result<Self> {
+                let lock_path = lock_path(path);
+                if let Some(folder) = lock_path.parent() {
+                    std::fs::create_dir_all(folder)?;
+                }
+                let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(&lock_path)?;
+
+                if let Err(err) = file.try_lock_exclusive() {
+                    if err.raw_os_error() == fs2::lock_contended_error().raw_os_error() {
+                        let owner = read_owner(&mut file).map(|owner| owner.to_string());
+                        let owner = owner.unwrap_or_else(|| "unknown owner".to_string());
+                        return Err(Error::WalletLocked(display_name(path), owner));
+                    }
+                    return Err(err.into());
+                }
+
+                let mut previous = vec![];
+                file.read_to_end(&mut previous)?;
+                if !previous.is_empty() {
+                    let owner = serde_json::from_slice::<LockOwner>(&previous)
+                        .map(|owner| format!("a lock {owner}"))
+                        .unwrap_or_else(|_| "an unreadable lock".to_string());
+                    log_warn!("Taking over {owner} on `{}`, which was not released properly", display_name(path));
+                }
+
+                let owner = serde_json::to_vec(&LockOwner::current())?;
+                file.set_len(0)?;
+                file.seek(SeekFrom::Start(0))?;
+                file.write_all(&owner)?;
+                file.sync_all()?;
+
+                Ok(Self { file, path: lock_path })
+            }
+
+            /// Acquires the lock of `path`, waiting for its current owner to release it.
+            /// Meant for short critical sections only.
+            pub async fn acquire(path: &Path) -> Result<Self> {
+                loop {
+                    match Self::try_acquire(path) {
+                        Err(Error::WalletLocked(..)) => workflow_core::task::sleep(LOCK_RETRY_INTERVAL).await,
+                        result => return result,
+                    }
+                }
+            }
+
+            pub fn path(&self) -> &Path {
+                &self.path
+            }
+        }
+
+        impl Drop for FileLock {
+            fn drop(&mut self) {
+                // Truncating first, so a successor finding content knows the lock was not released
+                if let Err(err) = self.file.set_len(0).and_then(|_| self.file.unlock()) {
+                    log_error!("Unable to release the lock `{}`: {err}", self.path.display());
+                }
+            }
+        }
+
+        fn lock_path(path: &Path) -> PathBuf {
+            let mut lock_path = path.as_os_str().to_owned();
+            lock_path.push(".lock");
+            PathBuf::from(lock_path)
+        }
+
+        fn display_name(path: &Path) -> String {
+            path.file_stem().and_then(|name| name.to_str()).unwrap_or_default().to_string()
+        }
+
+        fn read_owner(file: &mut File) -> Option<LockOwner> {
+            let mut content = vec![];
+            file.seek(SeekFrom::Start(0)).ok()?;
+            file.read_to_end(&mut content).ok()?;
+            serde_json::from_slice(&content).ok()
+        }
+    } else {
+        use std::path::Path;
+
+        /// Wallet files are not locked in web environments
+        pub struct FileLock;
+
+        impl FileLock {
+            pub fn try_acquire(_path: &Path) -> Result<Self> {
+                Ok(Self)
+            }
+
+            pub async fn acquire(_path: &Path) -> Result<Self> {
+                Ok(Self)
+            }
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_lock_exclusive() -> Result<()> {
+        let folder = tempfile::tempdir()?;
+        let path = folder.path().join("test.wallet");
+
+        let lock = FileLock::try_acquire(&path)?;
+        assert!(matches!(FileLock::try_acquire(&path), Err(Error::WalletLocked(name, _)) if name == "test"));
+        drop(lock);
+
+        // Once released, the lock file is kept but empty
+        let lock = FileLock::try_acquire(&path)?;
+        assert!(std::fs::metadata(lock.path())?.len() > 0);
+        let lock_path = lock.path().to_path_buf();
+        drop(lock);
+        assert_eq!(std::fs::metadata(lock_path)?.len(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_lock_stale_takeover() -> Result<()> {
+        let folder = tempfile::tempdir()?;
+        let path = folder.path().join("test.wallet");
+
+        // A lock file left behind by an owner which exited without releasing it
+        let stale = LockOwner { pid: u32::MAX, acquired: 0 };
+        std::fs::write(lock_path(&path), serde_json::to_vec(&stale)?)?;
+
+        let mut lock = FileLock::try_acquire(&path)?;
+        let owner = read_owner(&mut lock.file).unwrap();
+        assert_eq!(owner.pid, std::process::id());
+        Ok(())
+    }
+}
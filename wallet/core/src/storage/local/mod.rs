@@ -13,7 +13,9 @@
 pub mod cache;
 pub mod collection;
 pub mod interface;
+pub mod lock;
 pub mod payload;
+pub mod registry;
 pub mod storage;
 pub mod streams;
 pub mod transaction;
@@ -0,0 +1,119 @@
+//!
+//! Registry of the wallets kept in a storage folder, recording
+//! metadata that is not part of the wallet files themselves.
+//!
+
+use crate::imports::*;
+use crate::storage::local::lock::FileLock;
+use crate::storage::local::Storage;
+use std::collections::BTreeMap;
+use workflow_core::time::unixtime_as_millis_u64;
+use workflow_store::fs;
+
+/// Name of the registry file within the storage folder
+pub const REGISTRY_FILE: &str = "wallets.registry";
+
+/// Registry metadata of a single wallet
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletRegistryEntry {
+    pub title: Option<String>,
+    /// Network the wallet was last opened on
+    pub network_id: Option<NetworkId>,
+    /// Unix time (in milliseconds) at which the wallet was last opened
+    pub last_opened: Option<u64>,
+}
+
+/// Wallet registry, keyed by wallet filename (without the `.wallet` extension).
+///
+/// The registry only holds informative metadata and wallet files remain the
+/// source of truth: entries of missing wallet files are ignored and wallet
+/// files missing from the registry simply lack the metadata. An unreadable
+/// registry is thus discarded rather than reported.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct WalletRegistry {
+    wallets: BTreeMap<String, WalletRegistryEntry>,
+}
+
+impl WalletRegistry {
+    pub async fn load(folder: &str) -> Result<Self> {
+        Ok(Self::load_from(&Storage::try_new_with_folder(folder, REGISTRY_FILE)?).await)
+    }
+
+    /// Loads the registry of `folder`, applies `update` to it and stores it back.
+    /// Concurrent updates from other wallet instances are serialized by locking
+    /// the registry file on native platforms.
+    pub async fn update<F>(folder: &str, update: F) -> Result<()>
+    where
+        F: FnOnce(&mut Self),
+    {
+        let storage = Storage::try_new_with_folder(folder, REGISTRY_FILE)?;
+        let _lock = FileLock::acquire(storage.filename()).await?;
+        let mut registry = Self::load_from(&storage).await;
+        update(&mut registry);
+        storage.ensure_dir().await?;
+        fs::write_json(storage.filename(), &registry).await?;
+        Ok(())
+    }
+
+    async fn load_from(storage: &Storage) -> Self {
+        if !storage.exists().await.unwrap_or_default() {
+            return Self::default();
+        }
+        let registry: std::result::Result<Self, _> = fs::read_json(storage.filename()).await;
+        registry.unwrap_or_else(|err| {
+            log_warn!("Discarding the unreadable wallet registry `{}`: {err}", storage.filename().display());
+            Self::default()
+        })
+    }
+
+    pub fn get(&self, filename: &str) -> Option<&WalletRegistryEntry> {
+        self.wallets.get(filename)
+    }
+
+    /// Records the opening of a wallet, keeping the previous network if `network_id` is `None`
+    pub fn record_open(&mut self, filename: &str, title: Option<String>, network_id: Option<NetworkId>) {
+        let entry = self.wallets.entry(filename.to_string()).or_default();
+        entry.title = title;
+        entry.network_id = network_id.or(entry.network_id);
+        entry.last_opened = Some(unixtime_as_millis_u64());
+    }
+
+    pub fn set_title(&mut self, filename: &str, title: Option<String>) {
+        self.wallets.entry(filename.to_string()).or_default().title = title;
+    }
+
+    pub fn rename(&mut self, filename: &str, new_filename: &str) {
+        let entry = self.wallets.remove(filename).unwrap_or_default();
+        self.wallets.insert(new_filename.to_string(), entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_wallet_registry_update() -> Result<()> {
+        let folder = tempfile::tempdir()?;
+        let folder = folder.path().to_str().unwrap();
+        assert!(WalletRegistry::load(folder).await?.get("a").is_none());
+
+        let network_id = NetworkId::with_suffix(NetworkType::Testnet, 11);
+        WalletRegistry::update(folder, |registry| registry.record_open("a", Some("A".to_string()), Some(network_id))).await?;
+        WalletRegistry::update(folder, |registry| registry.record_open("a", Some("A".to_string()), None)).await?;
+        let entry = WalletRegistry::load(folder).await?.get("a").cloned().unwrap();
+        assert_eq!(entry.network_id, Some(network_id), "the network is kept when unknown");
+        assert!(entry.last_opened.is_some());
+
+        WalletRegistry::update(folder, |registry| registry.rename("a", "b")).await?;
+        let registry = WalletRegistry::load(folder).await?;
+        assert!(registry.get("a").is_none());
+        assert_eq!(registry.get("b"), Some(&entry));
+
+        // An unreadable registry is discarded
+        std::fs::write(std::path::Path::new(folder).join(REGISTRY_FILE), "not json")?;
+        assert!(WalletRegistry::load(folder).await?.get("b").is_none());
+        Ok(())
+    }
+}
@@ -55,8 +55,9 @@ impl Storage {
         Ok(Storage { filename })
     }
 
+    /// Renames the storage file, keeping it in the same folder
     pub fn rename_sync(&mut self, filename: &str) -> Result<()> {
-        let target_filename = Path::new(filename).to_path_buf();
+        let target_filename = self.filename.with_file_name(filename);
         workflow_store::fs::rename_sync(self.filename(), &target_filename)?;
         self.filename = target_filename;
         Ok(())
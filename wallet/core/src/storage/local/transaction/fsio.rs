@@ -2,6 +2,7 @@
 //! Local file system transaction storage (native+NodeJS fs IO).
 //!
 
+use super::index::TransactionIndex;
 use crate::encryption::*;
 use crate::imports::*;
 use crate::storage::interface::{StorageStream, TransactionRangeResult};
@@ -16,6 +17,8 @@ use workflow_store::fs;
 
 pub struct Inner {
     known_folders: HashSet<String>,
+    /// Timestamp ordered indexes of the folders, loaded on first use
+    indexes: HashMap<String, TransactionIndex>,
 }
 
 pub struct TransactionStore {
@@ -27,7 +30,7 @@ pub struct TransactionStore {
 impl TransactionStore {
     pub fn new<P: AsRef<Path>>(folder: P, name: &str) -> TransactionStore {
         TransactionStore {
-            inner: Arc::new(Mutex::new(Inner { known_folders: HashSet::default() })),
+            inner: Arc::new(Mutex::new(Inner { known_folders: HashSet::default(), indexes: HashMap::default() })),
             folder: fs::resolve_path(folder.as_ref().to_str().unwrap()).expect("transaction store folder is invalid"),
             name: name.to_string(),
         }
@@ -49,6 +52,11 @@ impl TransactionStore {
         self.folder.join(self.make_subfolder(binding, network_id))
     }
 
+    /// The index is stored next to the folder of the records so that it is not enumerated as one
+    fn make_index_path(&self, subfolder: &str) -> PathBuf {
+        self.folder.join(format!("{subfolder}.index"))
+    }
+
     async fn ensure_folder(&self, binding: &Binding, network_id: &NetworkId) -> Result<PathBuf> {
         let subfolder = self.make_subfolder(binding, network_id);
         let folder = self.folder.join(&subfolder);
@@ -87,6 +95,64 @@ impl TransactionStore {
             }
         }
     }
+
+    /// Loads the index of the folder if it is not loaded yet, returning the subfolder it is registered
+    /// under. The stored index is used if it covers exactly the records of the folder, otherwise
+    /// (e.g. if the folder was modified by a version of the wallet not maintaining an index)
+    /// the index is rebuilt by reading all of the records.
+    async fn ensure_index(&self, binding: &Binding, network_id: &NetworkId) -> Result<String> {
+        let subfolder = self.make_subfolder(binding, network_id);
+        if self.inner().indexes.contains_key(&subfolder) {
+            return Ok(subfolder);
+        }
+
+        let folder = self.ensure_folder(binding, network_id).await?;
+        let ids = self.enumerate(binding, network_id).await?;
+        let index_path = self.make_index_path(&subfolder);
+        let stored = if fs::exists(&index_path).await? {
+            fs::read(&index_path).await.ok().and_then(|bytes| TransactionIndex::try_from_slice(bytes.as_slice()).ok())
+        } else {
+            None
+        };
+
+        let index = match stored {
+            Some(index) if index.len() == ids.len() && ids.iter().all(|id| index.contains(id)) => index,
+            _ => {
+                log_info!("TransactionStore: building the transaction index of {subfolder}");
+                let mut index = TransactionIndex::default();
+                for id in ids {
+                    match read(&folder.join(id.to_hex()), None).await {
+                        Ok(tx) => {
+                            index.insert(&tx);
+                        }
+                        Err(err) => {
+                            log_error!("Error loading transaction {id}: {:?}", err);
+                        }
+                    }
+                }
+                fs::write(&index_path, &index.try_to_vec()?).await?;
+                index
+            }
+        };
+
+        self.inner().indexes.entry(subfolder.clone()).or_insert(index);
+        Ok(subfolder)
+    }
+
+    /// Applies `f` to the loaded index of `subfolder`, storing the index if `f` changed it
+    async fn update_index<F>(&self, subfolder: &str, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut TransactionIndex) -> bool,
+    {
+        let data = match self.inner().indexes.get_mut(subfolder) {
+            Some(index) if f(index) => Some(index.try_to_vec()?),
+            _ => None,
+        };
+        if let Some(data) = data {
+            fs::write(&self.make_index_path(subfolder), &data).await?;
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -184,22 +250,77 @@ impl TransactionRecordStore for TransactionStore {
         Ok(TransactionRangeResult { transactions, total: total as u64 })
     }
 
+    async fn transactions_iter(
+        &self,
+        binding: &Binding,
+        network_id: &NetworkId,
+        filter: &TransactionRecordFilter,
+        cursor: Option<TransactionCursor>,
+        limit: usize,
+    ) -> Result<TransactionPage> {
+        let subfolder = self.ensure_index(binding, network_id).await?;
+        let folder = self.make_folder(binding, network_id);
+        let mut transactions = Vec::with_capacity(limit);
+        let mut position = cursor;
+
+        // Candidates are taken from the index in batches since the address
+        // filter can only be evaluated once the records are loaded
+        while transactions.len() < limit {
+            let candidates = match self.inner().indexes.get(&subfolder) {
+                Some(index) => index.iter(filter, position).take(limit - transactions.len()).copied().collect::<Vec<_>>(),
+                None => vec![],
+            };
+            let Some(last) = candidates.last().copied() else {
+                break;
+            };
+
+            for candidate in candidates {
+                match read(&folder.join(candidate.id.to_hex()), None).await {
+                    Ok(tx) => {
+                        if filter.matches(&tx) {
+                            transactions.push(Arc::new(tx));
+                        }
+                    }
+                    Err(err) => {
+                        log_error!("Error loading transaction {}: {:?}", candidate.id, err);
+                    }
+                }
+            }
+            position = Some(last);
+        }
+
+        let next_cursor = if transactions.len() == limit { position } else { None };
+        Ok(TransactionPage { transactions, next_cursor })
+    }
+
     async fn store(&self, transaction_records: &[&TransactionRecord]) -> Result<()> {
+        let mut subfolders = HashMap::<String, Vec<&TransactionRecord>>::default();
         for tx in transaction_records {
-            let folder = self.ensure_folder(tx.binding(), tx.network_id()).await?;
-            let filename = folder.join(tx.id().to_hex());
+            // The index is loaded before the record is written so a stored index is still found valid
+            let subfolder = self.ensure_index(tx.binding(), tx.network_id()).await?;
+            let filename = self.folder.join(&subfolder).join(tx.id().to_hex());
             write(&filename, tx, None, EncryptionKind::XChaCha20Poly1305).await?;
+            subfolders.entry(subfolder).or_default().push(*tx);
+        }
+
+        for (subfolder, transaction_records) in subfolders {
+            self.update_index(&subfolder, |index| {
+                transaction_records.into_iter().fold(false, |changed, tx| index.insert(tx) | changed)
+            })
+            .await?;
         }
 
         Ok(())
     }
 
     async fn remove(&self, binding: &Binding, network_id: &NetworkId, ids: &[&TransactionId]) -> Result<()> {
+        let subfolder = self.ensure_index(binding, network_id).await?;
         let folder = self.ensure_folder(binding, network_id).await?;
         for id in ids {
             let filename = folder.join(id.to_hex());
             fs::remove(&filename).await?;
         }
+        self.update_index(&subfolder, |index| ids.iter().fold(false, |changed, id| index.remove(id) | changed)).await?;
 
         Ok(())
     }
@@ -318,3 +439,173 @@ async fn write(path: &Path, record: &TransactionRecord, secret: Option<&Secret>,
     fs::write(path, &data.try_to_vec()?).await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::transaction::UtxoRecord;
+    use kaspa_addresses::Version;
+    use kaspa_consensus_core::subnets::SUBNETWORK_ID_NATIVE;
+    use kaspa_consensus_core::tx::Transaction;
+    use kaspa_hashes::Hash;
+
+    const RECORDS: u64 = 5000;
+    const BASE_UNIXTIME_MSEC: u64 = 1_700_000_000_000;
+
+    /// Records of varying kinds and addresses, three records sharing each timestamp
+    fn make_record(binding: &Binding, network_id: NetworkId, addresses: &[Address], i: u64) -> TransactionRecord {
+        let aggregate_input_value = 1000 + i;
+        let utxo_entries = vec![UtxoRecord {
+            address: Some(addresses[i as usize % addresses.len()].clone()),
+            index: 0,
+            amount: aggregate_input_value,
+            script_public_key: ScriptPublicKey::from_vec(0, vec![]),
+            is_coinbase: i % 10 == 0,
+        }];
+        let transaction = || Transaction::new(0, vec![], vec![], i, SUBNETWORK_ID_NATIVE, 0, vec![]);
+        let transaction_data = match i % 4 {
+            0 | 1 => TransactionData::Incoming { utxo_entries, aggregate_input_value },
+            2 => TransactionData::Outgoing {
+                fees: 1,
+                aggregate_input_value,
+                aggregate_output_value: aggregate_input_value - 1,
+                transaction: transaction(),
+                payment_value: Some(aggregate_input_value - 1),
+                change_value: 0,
+                accepted_daa_score: None,
+                utxo_entries,
+            },
+            _ => TransactionData::Batch {
+                fees: 1,
+                aggregate_input_value,
+                aggregate_output_value: aggregate_input_value - 1,
+                transaction: transaction(),
+                payment_value: None,
+                change_value: aggregate_input_value - 1,
+                accepted_daa_score: None,
+                utxo_entries,
+            },
+        };
+
+        TransactionRecord {
+            id: TransactionId::from_u64_word(i + 1),
+            unixtime_msec: Some(BASE_UNIXTIME_MSEC + i / 3),
+            value: aggregate_input_value,
+            binding: binding.clone(),
+            block_daa_score: i,
+            network_id,
+            transaction_data,
+            note: None,
+            metadata: None,
+            confirmation_estimate: None,
+        }
+    }
+
+    /// Pages through the history of `binding`, asserting that no page exceeds `limit`
+    async fn collect_pages(
+        store: &TransactionStore,
+        binding: &Binding,
+        network_id: &NetworkId,
+        filter: &TransactionRecordFilter,
+        limit: usize,
+    ) -> Result<Vec<TransactionId>> {
+        let mut ids = vec![];
+        let mut cursor = None;
+        loop {
+            let TransactionPage { transactions, next_cursor } =
+                store.transactions_iter(binding, network_id, filter, cursor, limit).await?;
+            assert!(transactions.len() <= limit, "a page of {} records exceeds the limit of {limit}", transactions.len());
+            ids.extend(transactions.iter().map(|tx| tx.id));
+            match next_cursor {
+                Some(next_cursor) => cursor = Some(next_cursor),
+                None => break,
+            }
+        }
+        Ok(ids)
+    }
+
+    #[tokio::test]
+    async fn test_transaction_store_pagination() -> Result<()> {
+        let folder = tempfile::tempdir()?;
+        let folder = folder.path().to_str().unwrap();
+        let binding = Binding::Account(AccountId(Hash::from_u64_word(1)));
+        let network_id = NetworkId::with_suffix(NetworkType::Testnet, 10);
+        let addresses = (0..4u8).map(|i| Address::new(Prefix::Testnet, Version::PubKey, &[i; 32])).collect::<Vec<_>>();
+
+        let records = (0..RECORDS).map(|i| make_record(&binding, network_id, &addresses, i)).collect::<Vec<_>>();
+        let store = TransactionStore::new(folder, "pagination");
+        store.store(&records.iter().collect::<Vec<_>>()).await?;
+
+        // Newest first, ties broken by the highest id
+        let mut expected = records.iter().map(|tx| (TransactionCursor::from(tx), tx)).collect::<Vec<_>>();
+        expected.sort_by_key(|(cursor, _)| std::cmp::Reverse(*cursor));
+
+        // Unfiltered history
+        let unfiltered = TransactionRecordFilter::default();
+        let ids = collect_pages(&store, &binding, &network_id, &unfiltered, 100).await?;
+        assert_eq!(ids, expected.iter().map(|(_, tx)| tx.id).collect::<Vec<_>>());
+
+        // Filtered histories
+        let from = BASE_UNIXTIME_MSEC + 200;
+        let to = BASE_UNIXTIME_MSEC + 1200;
+        let filters = [
+            TransactionRecordFilter { kinds: Some(vec![TransactionKind::Outgoing]), ..Default::default() },
+            TransactionRecordFilter { kinds: Some(vec![TransactionKind::Batch]), ..Default::default() },
+            TransactionRecordFilter { coinbase: Some(true), ..Default::default() },
+            TransactionRecordFilter { from_unixtime_msec: Some(from), to_unixtime_msec: Some(to), ..Default::default() },
+            TransactionRecordFilter { address: Some(addresses[1].clone()), ..Default::default() },
+            TransactionRecordFilter {
+                kinds: Some(vec![TransactionKind::Incoming, TransactionKind::Batch]),
+                coinbase: Some(false),
+                from_unixtime_msec: Some(from),
+                to_unixtime_msec: None,
+                address: Some(addresses[3].clone()),
+            },
+        ];
+        for filter in filters.iter() {
+            let expected_ids = expected
+                .iter()
+                .filter(|(cursor, tx)| {
+                    filter.kinds.as_ref().map_or(true, |kinds| kinds.contains(&tx.kind()))
+                        && filter.coinbase.map_or(true, |coinbase| tx.is_coinbase() == coinbase)
+                        && filter.from_unixtime_msec.map_or(true, |from| cursor.unixtime_msec >= from)
+                        && filter.to_unixtime_msec.map_or(true, |to| cursor.unixtime_msec < to)
+                        && filter.address.as_ref().map_or(true, |address| tx.transaction_data().has_address(address))
+                })
+                .map(|(_, tx)| tx.id)
+                .collect::<Vec<_>>();
+            assert!(!expected_ids.is_empty());
+            let ids = collect_pages(&store, &binding, &network_id, filter, 37).await?;
+            assert_eq!(ids, expected_ids, "filter {filter:?}");
+        }
+
+        // The pages following a cursor are not affected by newer records
+        let TransactionPage { transactions: first, next_cursor } =
+            store.transactions_iter(&binding, &network_id, &unfiltered, None, 250).await?;
+        assert_eq!(first.len(), 250);
+        let newer = (RECORDS..RECORDS + 10).map(|i| make_record(&binding, network_id, &addresses, i)).collect::<Vec<_>>();
+        store.store(&newer.iter().collect::<Vec<_>>()).await?;
+        let TransactionPage { transactions: second, .. } =
+            store.transactions_iter(&binding, &network_id, &unfiltered, next_cursor, 250).await?;
+        assert_eq!(
+            second.iter().map(|tx| tx.id).collect::<Vec<_>>(),
+            expected[250..500].iter().map(|(_, tx)| tx.id).collect::<Vec<_>>()
+        );
+
+        // A reopened store uses the stored index, which reflects the updates and removals
+        let removed = expected[..5].iter().map(|(_, tx)| tx.id).collect::<Vec<_>>();
+        store.remove(&binding, &network_id, &removed.iter().collect::<Vec<_>>()).await?;
+        let mut updated = records[0].clone();
+        updated.unixtime_msec = Some(BASE_UNIXTIME_MSEC + RECORDS);
+        store.store(&[&updated]).await?;
+
+        let reopened = TransactionStore::new(folder, "pagination");
+        let ids = collect_pages(&reopened, &binding, &network_id, &unfiltered, 1000).await?;
+        assert_eq!(ids.len() as u64, RECORDS + 10 - 5);
+        assert_eq!(ids.iter().position(|id| *id == updated.id), Some(0));
+        assert!(removed.iter().all(|id| !ids.contains(id)));
+        assert_eq!(reopened.inner().indexes.values().next().map(|index| index.len()), Some(ids.len()));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+//!
+//! Secondary index of the local transaction storage, ordering the
+//! transaction records by their unix time.
+//!
+
+use crate::imports::*;
+use crate::storage::transaction::TransactionIndexEntry;
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
+#[derive(Default, Clone)]
+pub struct TransactionIndex {
+    entries: BTreeMap<TransactionCursor, TransactionIndexEntry>,
+    cursors: HashMap<TransactionId, TransactionCursor>,
+}
+
+impl TransactionIndex {
+    const STORAGE_MAGIC: u32 = 0x58444954;
+    const STORAGE_VERSION: u32 = 0;
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, id: &TransactionId) -> bool {
+        self.cursors.contains_key(id)
+    }
+
+    /// Inserts or updates the entry of the record, returning `true` if the index changed
+    pub fn insert(&mut self, record: &TransactionRecord) -> bool {
+        let cursor = TransactionCursor::from(record);
+        let entry = TransactionIndexEntry::from(record);
+        if let Some(previous) = self.cursors.insert(record.id, cursor) {
+            if previous == cursor && self.entries.get(&cursor) == Some(&entry) {
+                return false;
+            }
+            self.entries.remove(&previous);
+        }
+        self.entries.insert(cursor, entry);
+        true
+    }
+
+    /// Removes the entry of the record, returning `true` if the index changed
+    pub fn remove(&mut self, id: &TransactionId) -> bool {
+        if let Some(cursor) = self.cursors.remove(id) {
+            self.entries.remove(&cursor);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Iterates newest first over the cursors of the records following `cursor` whose
+    /// index entries match `filter`. The date range of the filter bounds the iteration
+    /// so records outside of it are never visited.
+    pub fn iter<'a>(
+        &'a self,
+        filter: &'a TransactionRecordFilter,
+        cursor: Option<TransactionCursor>,
+    ) -> impl Iterator<Item = &'a TransactionCursor> + 'a {
+        // The first possible cursor of the given unix time
+        let first = |unixtime_msec| TransactionCursor { unixtime_msec, id: TransactionId::default() };
+        let lower = match filter.from_unixtime_msec {
+            Some(from) => Bound::Included(first(from)),
+            None => Bound::Unbounded,
+        };
+        let upper = match (cursor, filter.to_unixtime_msec) {
+            (Some(cursor), Some(to)) if cursor.unixtime_msec >= to => Bound::Excluded(first(to)),
+            (Some(cursor), _) => Bound::Excluded(cursor),
+            (None, Some(to)) => Bound::Excluded(first(to)),
+            (None, None) => Bound::Unbounded,
+        };
+        // An empty range would make `BTreeMap::range()` panic
+        let empty = match (&lower, &upper) {
+            (Bound::Included(lower), Bound::Excluded(upper)) => lower >= upper,
+            _ => false,
+        };
+        (!empty)
+            .then(|| self.entries.range((lower, upper)).rev())
+            .into_iter()
+            .flatten()
+            .filter(move |(cursor, entry)| filter.matches_entry(cursor, entry))
+            .map(|(cursor, _)| cursor)
+    }
+
+    pub fn try_to_vec(&self) -> Result<Vec<u8>> {
+        let mut data = vec![];
+        StorageHeader::new(Self::STORAGE_MAGIC, Self::STORAGE_VERSION).serialize(&mut data)?;
+        BorshSerialize::serialize(&(self.entries.len() as u32), &mut data)?;
+        for (cursor, entry) in self.entries.iter() {
+            BorshSerialize::serialize(cursor, &mut data)?;
+            BorshSerialize::serialize(entry, &mut data)?;
+        }
+        Ok(data)
+    }
+
+    pub fn try_from_slice(mut buf: &[u8]) -> Result<Self> {
+        let buf = &mut buf;
+        StorageHeader::deserialize(buf)?.try_magic(Self::STORAGE_MAGIC)?.try_version(Self::STORAGE_VERSION)?;
+        let len: u32 = BorshDeserialize::deserialize(buf)?;
+        let mut index = Self::default();
+        for _ in 0..len {
+            let cursor: TransactionCursor = BorshDeserialize::deserialize(buf)?;
+            let entry = BorshDeserialize::deserialize(buf)?;
+            index.cursors.insert(cursor.id, cursor);
+            index.entries.insert(cursor, entry);
+        }
+        Ok(index)
+    }
+}
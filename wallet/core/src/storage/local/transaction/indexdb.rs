@@ -10,12 +10,17 @@ use crate::storage::{Binding, TransactionKind, TransactionRecordStore};
 use indexed_db_futures::prelude::*;
 use itertools::Itertools;
 use js_sys::{Date, Uint8Array};
+use web_sys::{IdbCursorDirection, IdbKeyRange};
 use workflow_core::task::call_async_no_send;
 
 const TRANSACTIONS_STORE_NAME: &str = "transactions";
 const TRANSACTIONS_STORE_ID_INDEX: &str = "id";
 const TRANSACTIONS_STORE_TIMESTAMP_INDEX: &str = "timestamp";
 const TRANSACTIONS_STORE_DATA_INDEX: &str = "data";
+/// History order of the records, see [`order_key()`]
+const TRANSACTIONS_STORE_ORDER_INDEX: &str = "order";
+
+const DATABASE_VERSION: u32 = 2;
 
 const ENCRYPTION_KIND: EncryptionKind = EncryptionKind::XChaCha20Poly1305;
 
@@ -26,7 +31,7 @@ pub struct Inner {
 impl Inner {
     async fn open_db(&self, db_name: String) -> Result<IdbDatabase> {
         call_async_no_send!(async move {
-            let mut db_req: OpenDbRequest = IdbDatabase::open_u32(&db_name, 1)
+            let mut db_req: OpenDbRequest = IdbDatabase::open_u32(&db_name, DATABASE_VERSION)
                 .map_err(|err| Error::Custom(format!("Failed to open indexdb database {:?}", err)))?;
 
             fn on_upgrade_needed(evt: &IdbVersionChangeEvent) -> Result<(), JsValue> {
@@ -48,6 +53,19 @@ impl Inner {
                         &IdbKeyPath::str(TRANSACTIONS_STORE_DATA_INDEX),
                         IdbIndexParameters::new().unique(false),
                     )?;
+                    object_store.create_index_with_params(
+                        TRANSACTIONS_STORE_ORDER_INDEX,
+                        &IdbKeyPath::str(TRANSACTIONS_STORE_ORDER_INDEX),
+                        IdbIndexParameters::new().unique(true),
+                    )?;
+                } else if evt.old_version() < 2.0 {
+                    // The records of the existing store are added to the order index by `backfill_order_index()`
+                    let object_store = evt.transaction().object_store(TRANSACTIONS_STORE_NAME)?;
+                    object_store.create_index_with_params(
+                        TRANSACTIONS_STORE_ORDER_INDEX,
+                        &IdbKeyPath::str(TRANSACTIONS_STORE_ORDER_INDEX),
+                        IdbIndexParameters::new().unique(true),
+                    )?;
                 }
                 Ok(())
             }
@@ -261,6 +279,76 @@ impl TransactionRecordStore for TransactionStore {
         })
     }
 
+    async fn transactions_iter(
+        &self,
+        binding: &Binding,
+        network_id: &NetworkId,
+        filter: &TransactionRecordFilter,
+        cursor: Option<TransactionCursor>,
+        limit: usize,
+    ) -> Result<TransactionPage> {
+        let binding_str = binding.to_hex();
+        let network_id_str = network_id.to_string();
+        let db_name = self.make_db_name(&binding_str, &network_id_str);
+        let filter = filter.clone();
+
+        let inner = self.inner().clone();
+
+        call_async_no_send!(async move {
+            let db = inner.open_db(db_name).await?;
+            backfill_order_index(&db).await?;
+
+            let mut transactions = Vec::with_capacity(limit);
+            let mut position = None;
+
+            let range = if limit > 0 { order_key_range(&filter, cursor)? } else { None };
+            if let Some(range) = range {
+                let idb_tx = db
+                    .transaction_on_one_with_mode(TRANSACTIONS_STORE_NAME, IdbTransactionMode::Readonly)
+                    .map_err(|err| Error::Custom(format!("Failed to open indexdb transaction for reading {:?}", err)))?;
+                let store = idb_tx
+                    .object_store(TRANSACTIONS_STORE_NAME)
+                    .map_err(|err| Error::Custom(format!("Failed to open indexdb object store for reading {:?}", err)))?;
+                let index = store
+                    .index(TRANSACTIONS_STORE_ORDER_INDEX)
+                    .map_err(|err| Error::Custom(format!("Failed to open indexdb index for reading {:?}", err)))?;
+
+                let idb_cursor = index
+                    .open_cursor_with_range_and_direction(&range, IdbCursorDirection::Prev)
+                    .map_err(|err| Error::Custom(format!("Failed to open indexdb cursor {:?}", err)))?
+                    .await
+                    .map_err(|err| Error::Custom(format!("Failed to open indexdb cursor {:?}", err)))?;
+
+                if let Some(idb_cursor) = idb_cursor {
+                    loop {
+                        let transaction_record = transaction_record_from_js_value(&idb_cursor.value(), None).map_err(|err| {
+                            Error::Custom(format!("Failed to deserialize transaction record from indexdb {:?}", err))
+                        })?;
+                        position = Some(TransactionCursor::from(&transaction_record));
+                        if filter.matches(&transaction_record) {
+                            transactions.push(Arc::new(transaction_record));
+                            if transactions.len() == limit {
+                                break;
+                            }
+                        }
+
+                        let advanced = idb_cursor
+                            .continue_cursor()
+                            .map_err(|err| Error::Custom(format!("Failed to advance indexdb cursor {:?}", err)))?
+                            .await
+                            .map_err(|err| Error::Custom(format!("Failed to advance indexdb cursor {:?}", err)))?;
+                        if !advanced {
+                            break;
+                        }
+                    }
+                }
+            }
+
+            let next_cursor = if transactions.len() == limit { position } else { None };
+            Ok(TransactionPage { transactions, next_cursor })
+        })
+    }
+
     async fn store(&self, transaction_records: &[&TransactionRecord]) -> Result<()> {
         struct StorableItem {
             db_name: String,
@@ -502,11 +590,86 @@ fn transaction_record_to_js_value(
     obj.set("id", &id_js_value)?;
     obj.set("timestamp", &timestamp_js_value)?;
     obj.set("data", &borsh_data_js_value)?;
+    obj.set("order", &JsValue::from_str(&order_key(&TransactionCursor::from(transaction_record))))?;
 
     let value = JsValue::from(obj);
     Ok(value)
 }
 
+/// Key of the order index. Its lexicographic order is the order of [`TransactionCursor`],
+/// so iterating the index backwards yields the history newest first.
+fn order_key(cursor: &TransactionCursor) -> String {
+    format!("{:016x}{}", cursor.unixtime_msec, cursor.id)
+}
+
+/// The range of the order index holding the records of the `filter` date range following
+/// `cursor`, or `None` if the range is empty
+fn order_key_range(filter: &TransactionRecordFilter, cursor: Option<TransactionCursor>) -> Result<Option<IdbKeyRange>> {
+    let first = |unixtime_msec| TransactionCursor { unixtime_msec, id: TransactionId::default() };
+    let lower = filter.from_unixtime_msec.map(first);
+    let upper = match (cursor, filter.to_unixtime_msec) {
+        (Some(cursor), Some(to)) => Some(cursor.min(first(to))),
+        (Some(cursor), None) => Some(cursor),
+        (None, to) => to.map(first),
+    };
+
+    let range = match (lower, upper) {
+        (Some(lower), Some(upper)) if lower >= upper => return Ok(None),
+        (Some(lower), Some(upper)) => {
+            IdbKeyRange::bound_with_lower_open_and_upper_open(&order_key(&lower).into(), &order_key(&upper).into(), false, true)
+        }
+        (Some(lower), None) => IdbKeyRange::lower_bound(&order_key(&lower).into()),
+        (None, Some(upper)) => IdbKeyRange::upper_bound_with_open(&order_key(&upper).into(), true),
+        (None, None) => IdbKeyRange::lower_bound(&JsValue::from_str("")),
+    };
+    range.map(Some).map_err(|err| Error::Custom(format!("Invalid indexdb key range {:?}", err)))
+}
+
+/// Re-stores the records missing from the order index, i.e. the records stored
+/// before the index was introduced, so that they are located by the history queries.
+async fn backfill_order_index(db: &IdbDatabase) -> Result<()> {
+    let idb_tx = db
+        .transaction_on_one_with_mode(TRANSACTIONS_STORE_NAME, IdbTransactionMode::Readwrite)
+        .map_err(|err| Error::Custom(format!("Failed to open indexdb transaction for writing {:?}", err)))?;
+    let store = idb_tx
+        .object_store(TRANSACTIONS_STORE_NAME)
+        .map_err(|err| Error::Custom(format!("Failed to open indexdb object store for writing {:?}", err)))?;
+    let index = store
+        .index(TRANSACTIONS_STORE_ORDER_INDEX)
+        .map_err(|err| Error::Custom(format!("Failed to open indexdb index for reading {:?}", err)))?;
+
+    let total = store
+        .count()
+        .map_err(|err| Error::Custom(format!("Failed to count indexdb records {:?}", err)))?
+        .await
+        .map_err(|err| Error::Custom(format!("Failed to count indexdb records {:?}", err)))?;
+    let indexed = index
+        .count()
+        .map_err(|err| Error::Custom(format!("Failed to count indexdb records {:?}", err)))?
+        .await
+        .map_err(|err| Error::Custom(format!("Failed to count indexdb records {:?}", err)))?;
+    if indexed >= total {
+        return Ok(());
+    }
+
+    log_info!("Adding {} transaction records to the indexdb order index", total - indexed);
+    let array = store
+        .get_all()
+        .map_err(|err| Error::Custom(format!("Failed to get transaction record from indexdb {:?}", err)))?
+        .await
+        .map_err(|err| Error::Custom(format!("Failed to get transaction record from indexdb {:?}", err)))?;
+    for js_value in array.iter() {
+        let transaction_record = transaction_record_from_js_value(&js_value, None)
+            .map_err(|err| Error::Custom(format!("Failed to deserialize transaction record from indexdb {:?}", err)))?;
+        let js_value = transaction_record_to_js_value(&transaction_record, None, ENCRYPTION_KIND)?;
+        store
+            .put_key_val_owned(transaction_record.id.to_string().as_str(), &js_value)
+            .map_err(|_err| Error::Custom("Failed to put transaction record in indexdb object store".to_string()))?;
+    }
+
+    Ok(())
+}
+
 fn transaction_record_from_js_value(js_value: &JsValue, secret: Option<&Secret>) -> Result<TransactionRecord, Error> {
     if let Some(object) = Object::try_from(js_value) {
         let borsh_data_jsv = object.get_value("data")?;
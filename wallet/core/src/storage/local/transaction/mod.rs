@@ -3,4 +3,5 @@
 //!
 
 pub mod fsio;
+pub mod index;
 pub mod indexdb;
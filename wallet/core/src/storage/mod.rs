@@ -16,7 +16,7 @@ pub mod metadata;
 pub mod storable;
 pub mod transaction;
 
-pub use account::{AccountSettings, AccountStorable, AccountStorage};
+pub use account::{AccountSettings, AccountStorable, AccountStorage, ChangeAddressPolicy};
 pub use address::AddressBookEntry;
 pub use binding::Binding;
 pub use hint::Hint;
@@ -28,7 +28,9 @@ pub use keydata::{AssocPrvKeyDataIds, PrvKeyData, PrvKeyDataId, PrvKeyDataInfo,
 pub use local::interface::make_filename;
 pub use metadata::AccountMetadata;
 pub use storable::Storable;
-pub use transaction::{TransactionData, TransactionId, TransactionKind, TransactionRecord};
+pub use transaction::{
+    TransactionCursor, TransactionData, TransactionId, TransactionKind, TransactionPage, TransactionRecord, TransactionRecordFilter,
+};
 
 #[cfg(test)]
 mod tests {
@@ -4,10 +4,12 @@
 
 pub mod data;
 pub mod kind;
+pub mod query;
 pub mod record;
 pub mod utxo;
 
 pub use data::*;
 pub use kind::*;
+pub use query::*;
 pub use record::*;
 pub use utxo::*;
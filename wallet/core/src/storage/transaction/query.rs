@@ -0,0 +1,79 @@
+//!
+//! Paginated transaction history queries.
+//!
+
+use crate::imports::*;
+
+/// Filter of the transaction history queries. A record matches the filter
+/// if it satisfies all of the specified conditions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionRecordFilter {
+    /// Kinds of records to include. Sweep transactions compounding the
+    /// UTXOs of the account are [`TransactionKind::Batch`] records.
+    pub kinds: Option<Vec<TransactionKind>>,
+    /// Include only (`true`) or exclude (`false`) incoming coinbase records.
+    pub coinbase: Option<bool>,
+    /// Inclusive lower bound of the record unix time in milliseconds.
+    pub from_unixtime_msec: Option<u64>,
+    /// Exclusive upper bound of the record unix time in milliseconds.
+    pub to_unixtime_msec: Option<u64>,
+    /// Include only records with a UTXO entry of this address.
+    pub address: Option<Address>,
+}
+
+impl TransactionRecordFilter {
+    /// Evaluates the conditions of the filter that do not depend on the record contents
+    pub fn matches_entry(&self, key: &TransactionCursor, entry: &TransactionIndexEntry) -> bool {
+        self.kinds.as_ref().map_or(true, |kinds| kinds.contains(&entry.kind))
+            && self.coinbase.map_or(true, |coinbase| coinbase == entry.is_coinbase)
+            && self.from_unixtime_msec.map_or(true, |from| key.unixtime_msec >= from)
+            && self.to_unixtime_msec.map_or(true, |to| key.unixtime_msec < to)
+    }
+
+    pub fn matches(&self, record: &TransactionRecord) -> bool {
+        self.matches_entry(&record.into(), &record.into())
+            && self.address.as_ref().map_or(true, |address| record.transaction_data().has_address(address))
+    }
+}
+
+/// Position of a record in the transaction history. The history is ordered
+/// newest first by the record unix time, with ties broken by the transaction id.
+/// Records without a unix time are ordered as the oldest ones.
+///
+/// A cursor returned with a [`TransactionPage`] points at the last record of the page
+/// and remains valid as new records are stored, so consecutive pages never overlap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionCursor {
+    pub unixtime_msec: u64,
+    pub id: TransactionId,
+}
+
+impl From<&TransactionRecord> for TransactionCursor {
+    fn from(record: &TransactionRecord) -> Self {
+        Self { unixtime_msec: record.unixtime_msec.unwrap_or_default(), id: record.id }
+    }
+}
+
+/// Record properties kept by the secondary (timestamp ordered) index
+/// of the transaction stores, allowing to filter records without loading them.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct TransactionIndexEntry {
+    pub kind: TransactionKind,
+    pub is_coinbase: bool,
+}
+
+impl From<&TransactionRecord> for TransactionIndexEntry {
+    fn from(record: &TransactionRecord) -> Self {
+        Self { kind: record.kind(), is_coinbase: record.is_coinbase() }
+    }
+}
+
+/// A page of the transaction history.
+pub struct TransactionPage {
+    pub transactions: Vec<Arc<TransactionRecord>>,
+    /// Cursor of the next page, `None` if the history is exhausted. A full page
+    /// is always followed by a cursor, even if no further records match the filter.
+    pub next_cursor: Option<TransactionCursor>,
+}
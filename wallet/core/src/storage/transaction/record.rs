@@ -10,6 +10,7 @@ use workflow_core::time::{unixtime_as_millis_u64, unixtime_to_locale_string};
 use workflow_wasm::utils::try_get_js_value_prop;
 
 pub use kaspa_consensus_core::tx::TransactionId;
+use kaspa_rpc_core::RpcConfirmationTimeEstimate;
 use zeroize::Zeroize;
 
 #[wasm_bindgen(typescript_custom_section)]
@@ -277,6 +278,11 @@ export interface ITransactionRecord {
      * and store its own metadata into the value of this key.
      */
     metadata?: string;
+    /**
+     * Estimated confirmation time of a pending transaction as reported
+     * by the node at the time of the record creation. Not persisted.
+     */
+    confirmationEstimate?: IConfirmationTimeEstimate;
 
     /**
      * Transaction data type.
@@ -336,6 +342,12 @@ pub struct TransactionRecord {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[wasm_bindgen(getter_with_clone)]
     pub metadata: Option<String>,
+    /// Estimated confirmation time of a pending transaction as reported by
+    /// the node at the time of the record creation. Not persisted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "confirmationEstimate")]
+    #[wasm_bindgen(skip)]
+    pub confirmation_estimate: Option<RpcConfirmationTimeEstimate>,
 }
 
 impl TransactionRecord {
@@ -460,6 +472,18 @@ impl TransactionRecord {
     pub fn value(&self) -> u64 {
         self.value
     }
+
+    pub fn confirmation_estimate(&self) -> Option<&RpcConfirmationTimeEstimate> {
+        self.confirmation_estimate.as_ref()
+    }
+
+    /// Queries the node for the estimated confirmation time of this transaction.
+    /// Fails if the transaction is not in the mempool of the node.
+    pub async fn update_confirmation_estimate(&mut self, rpc: &DynRpcApi) -> Result<()> {
+        let response = rpc.estimate_transaction_confirmation_time(self.id).await?;
+        self.confirmation_estimate = Some(response.estimate);
+        Ok(())
+    }
 }
 
 impl TransactionRecord {
@@ -505,6 +529,7 @@ impl TransactionRecord {
             network_id: utxo_context.processor().network_id().expect("network expected for transaction record generation"),
             metadata: None,
             note: None,
+            confirmation_estimate: None,
         }
     }
 
@@ -530,6 +555,7 @@ impl TransactionRecord {
             network_id: utxo_context.processor().network_id().expect("network expected for transaction record generation"),
             metadata: None,
             note: None,
+            confirmation_estimate: None,
         }
     }
 
@@ -580,6 +606,7 @@ impl TransactionRecord {
             network_id: utxo_context.processor().network_id().expect("network expected for transaction record generation"),
             metadata: None,
             note: None,
+            confirmation_estimate: None,
         })
     }
 
@@ -626,6 +653,7 @@ impl TransactionRecord {
             network_id: utxo_context.processor().network_id().expect("network expected for transaction record generation"),
             metadata: None,
             note: None,
+            confirmation_estimate: None,
         })
     }
 
@@ -678,6 +706,7 @@ impl TransactionRecord {
             network_id: utxo_context.processor().network_id().expect("network expected for transaction record generation"),
             metadata: None,
             note: None,
+            confirmation_estimate: None,
         })
     }
 
@@ -730,6 +759,7 @@ impl TransactionRecord {
             network_id: utxo_context.processor().network_id().expect("network expected for transaction record generation"),
             metadata: None,
             note: None,
+            confirmation_estimate: None,
         })
     }
 
@@ -778,6 +808,7 @@ impl TransactionRecord {
             network_id: utxo_context.processor().network_id().expect("network expected for transaction record generation"),
             metadata: None,
             note: None,
+            confirmation_estimate: None,
         })
     }
 }
@@ -851,7 +882,18 @@ impl BorshDeserialize for TransactionRecord {
         let note = BorshDeserialize::deserialize(buf)?;
         let metadata = BorshDeserialize::deserialize(buf)?;
 
-        Ok(Self { id, unixtime_msec: unixtime, value, binding, block_daa_score, network_id, transaction_data, note, metadata })
+        Ok(Self {
+            id,
+            unixtime_msec: unixtime,
+            value,
+            binding,
+            block_daa_score,
+            network_id,
+            transaction_data,
+            note,
+            metadata,
+            confirmation_estimate: None,
+        })
     }
 }
 
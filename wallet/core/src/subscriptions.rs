@@ -0,0 +1,345 @@
+//!
+//! Filtered subscriptions to the wallet framework events. Unlike the event multiplexer
+//! channels, which receive every event, a subscriber only receives (and is only woken up by)
+//! the event kinds it subscribed to, through its own channel with a configurable lag policy.
+//!
+
+use crate::imports::*;
+use crate::utxo::context::UtxoContextId;
+use async_channel::{bounded, unbounded, Receiver, Sender, TrySendError};
+use futures::stream::BoxStream;
+use std::ops::{BitOr, BitOrAssign};
+
+/// Default capacity of the channels of the typed subscriptions
+pub const DEFAULT_SUBSCRIPTION_CAPACITY: usize = 1024;
+
+/// A set of [`EventKind`]s, one bit per kind. [`EventKind::All`] stands for every kind.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct EventKindSet(u64);
+
+impl EventKindSet {
+    pub const fn empty() -> Self {
+        EventKindSet(0)
+    }
+
+    pub const fn all() -> Self {
+        EventKindSet(u64::MAX)
+    }
+
+    fn bits(kind: EventKind) -> u64 {
+        match kind {
+            EventKind::All => u64::MAX,
+            // EventKind holds less than 64 variants
+            kind => 1 << kind as u64,
+        }
+    }
+
+    pub fn with(mut self, kind: EventKind) -> Self {
+        self.insert(kind);
+        self
+    }
+
+    pub fn insert(&mut self, kind: EventKind) {
+        self.0 |= Self::bits(kind);
+    }
+
+    pub fn remove(&mut self, kind: EventKind) {
+        self.0 &= !Self::bits(kind);
+    }
+
+    pub fn contains(&self, kind: EventKind) -> bool {
+        let bits = Self::bits(kind);
+        self.0 & bits == bits
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl From<EventKind> for EventKindSet {
+    fn from(kind: EventKind) -> Self {
+        EventKindSet::empty().with(kind)
+    }
+}
+
+impl FromIterator<EventKind> for EventKindSet {
+    fn from_iter<I: IntoIterator<Item = EventKind>>(iter: I) -> Self {
+        iter.into_iter().fold(EventKindSet::empty(), EventKindSet::with)
+    }
+}
+
+impl BitOr for EventKindSet {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        EventKindSet(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for EventKindSet {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+/// Handling of the events of a subscriber whose bounded channel is full
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LagPolicy {
+    /// Drop the incoming event, keeping the queued ones
+    #[default]
+    DropNewest,
+    /// Drop the oldest queued event to make room for the incoming one
+    DropOldest,
+}
+
+struct Subscriber {
+    filter: Arc<AtomicU64>,
+    sender: Sender<Box<Events>>,
+    /// Used for evicting the oldest queued event under [`LagPolicy::DropOldest`]
+    receiver: Receiver<Box<Events>>,
+    policy: LagPolicy,
+    lagged: Arc<AtomicU64>,
+}
+
+impl Subscriber {
+    fn try_send(&self, event: &Events) {
+        match self.sender.try_send(Box::new(event.clone())) {
+            Ok(()) | Err(TrySendError::Closed(_)) => {}
+            Err(TrySendError::Full(event)) => {
+                self.lagged.fetch_add(1, Ordering::Relaxed);
+                if self.policy == LagPolicy::DropOldest {
+                    self.receiver.try_recv().ok();
+                    self.sender.try_send(event).ok();
+                }
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct Inner {
+    subscribers: Mutex<AHashMap<u64, Subscriber>>,
+    next_id: AtomicU64,
+}
+
+/// Registry of the filtered event subscriptions, to which the wallet framework dispatches
+/// every event it broadcasts on its event multiplexer.
+#[derive(Clone, Default)]
+pub struct EventSubscriptions {
+    inner: Arc<Inner>,
+}
+
+impl EventSubscriptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Subscribes to the event kinds of `filter`. A `capacity` of `None` makes the subscriber channel
+    /// unbounded, otherwise events received while the channel is full are handled according to `policy`.
+    pub fn subscribe(&self, filter: EventKindSet, capacity: Option<usize>, policy: LagPolicy) -> EventSubscription {
+        let (sender, receiver) = match capacity {
+            Some(capacity) => bounded(capacity),
+            None => unbounded(),
+        };
+        let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+        let filter = Arc::new(AtomicU64::new(filter.0));
+        let lagged = Arc::new(AtomicU64::new(0));
+        let subscriber = Subscriber { filter: filter.clone(), sender, receiver: receiver.clone(), policy, lagged: lagged.clone() };
+        self.inner.subscribers.lock().unwrap().insert(id, subscriber);
+        EventSubscription { id, filter, receiver, lagged, subscriptions: self.clone() }
+    }
+
+    /// Subscribes to the balance updates of all [`UtxoContext`] instances. Only the most recent
+    /// updates are retained when the subscriber lags behind.
+    pub fn subscribe_balance_events(&self) -> BoxStream<'static, BalanceEvent> {
+        self.subscribe(EventKind::Balance.into(), Some(DEFAULT_SUBSCRIPTION_CAPACITY), LagPolicy::DropOldest)
+            .into_stream()
+            .filter_map(|event| async move {
+                match *event {
+                    Events::Balance { balance, id } => Some(BalanceEvent { id, balance }),
+                    _ => None,
+                }
+            })
+            .boxed()
+    }
+
+    /// Subscribes to the sync state updates of the connected node. Only the most recent updates
+    /// are retained when the subscriber lags behind.
+    pub fn subscribe_sync_state_events(&self) -> BoxStream<'static, SyncState> {
+        self.subscribe(EventKind::SyncState.into(), Some(DEFAULT_SUBSCRIPTION_CAPACITY), LagPolicy::DropOldest)
+            .into_stream()
+            .filter_map(|event| async move {
+                match *event {
+                    Events::SyncState { sync_state } => Some(sync_state),
+                    _ => None,
+                }
+            })
+            .boxed()
+    }
+
+    /// Dispatches `event` to the subscribers whose filter includes its kind
+    pub fn dispatch(&self, event: &Events) {
+        let kind = EventKind::from(event);
+        let subscribers = self.inner.subscribers.lock().unwrap();
+        for subscriber in subscribers.values() {
+            if EventKindSet(subscriber.filter.load(Ordering::Relaxed)).contains(kind) {
+                subscriber.try_send(event);
+            }
+        }
+    }
+
+    fn unsubscribe(&self, id: u64) {
+        self.inner.subscribers.lock().unwrap().remove(&id);
+    }
+}
+
+/// A filtered subscription to the wallet framework events, unsubscribed when dropped
+pub struct EventSubscription {
+    id: u64,
+    filter: Arc<AtomicU64>,
+    receiver: Receiver<Box<Events>>,
+    lagged: Arc<AtomicU64>,
+    subscriptions: EventSubscriptions,
+}
+
+impl EventSubscription {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn filter(&self) -> EventKindSet {
+        EventKindSet(self.filter.load(Ordering::Relaxed))
+    }
+
+    /// Replaces the subscribed event kinds. Events already queued are retained.
+    pub fn set_filter(&self, filter: EventKindSet) {
+        self.filter.store(filter.0, Ordering::Relaxed);
+    }
+
+    pub fn receiver(&self) -> &Receiver<Box<Events>> {
+        &self.receiver
+    }
+
+    pub async fn recv(&self) -> Option<Box<Events>> {
+        self.receiver.recv().await.ok()
+    }
+
+    pub fn try_recv(&self) -> Option<Box<Events>> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// The number of events dropped so far due to the subscriber lagging behind
+    pub fn lagged(&self) -> u64 {
+        self.lagged.load(Ordering::Relaxed)
+    }
+
+    pub fn into_stream(self) -> BoxStream<'static, Box<Events>> {
+        futures::stream::unfold(self, |subscription| async move {
+            let event = subscription.recv().await?;
+            Some((event, subscription))
+        })
+        .boxed()
+    }
+}
+
+impl Drop for EventSubscription {
+    fn drop(&mut self) {
+        self.subscriptions.unsubscribe(self.id);
+    }
+}
+
+/// A balance update of a [`UtxoContext`]
+#[derive(Clone, Debug)]
+pub struct BalanceEvent {
+    /// The account id if the UtxoContext is bound to an account, otherwise its developer-assigned id
+    pub id: UtxoContextId,
+    pub balance: Option<Balance>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn balance_event(mature: u64) -> Events {
+        Events::Balance { balance: Some(Balance::new(mature, 0, 0, 0, 0, 0)), id: UtxoContextId::default() }
+    }
+
+    fn mature_balance(event: &Events) -> u64 {
+        match event {
+            Events::Balance { balance: Some(balance), .. } => balance.mature,
+            _ => panic!("unexpected event {event:?}"),
+        }
+    }
+
+    #[test]
+    fn test_event_kind_set() {
+        let set = EventKindSet::from(EventKind::Balance).with(EventKind::Maturity);
+        assert!(set.contains(EventKind::Balance) && set.contains(EventKind::Maturity));
+        assert!(!set.contains(EventKind::DaaScoreChange) && !set.contains(EventKind::All));
+        assert!(EventKindSet::from(EventKind::All).contains(EventKind::DaaScoreChange));
+        assert_eq!([EventKind::Balance, EventKind::Maturity].into_iter().collect::<EventKindSet>(), set);
+
+        let mut set = set;
+        set.remove(EventKind::Balance);
+        assert_eq!(set, EventKindSet::from(EventKind::Maturity));
+        set.remove(EventKind::All);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_filtered_subscription() {
+        let subscriptions = EventSubscriptions::new();
+        let balance = subscriptions.subscribe(EventKind::Balance.into(), Some(16), LagPolicy::DropNewest);
+        let all = subscriptions.subscribe(EventKindSet::all(), None, LagPolicy::DropNewest);
+
+        for current_daa_score in 0..100 {
+            subscriptions.dispatch(&Events::DaaScoreChange { current_daa_score });
+        }
+        // The balance subscriber is never woken up by DAA score ticks
+        assert!(balance.receiver().is_empty());
+        assert_eq!(all.receiver().len(), 100);
+
+        subscriptions.dispatch(&balance_event(7));
+        assert_eq!(mature_balance(&balance.try_recv().unwrap()), 7);
+        assert!(balance.try_recv().is_none());
+        assert_eq!(balance.lagged(), 0);
+
+        balance.set_filter(EventKind::DaaScoreChange.into());
+        subscriptions.dispatch(&balance_event(8));
+        subscriptions.dispatch(&Events::DaaScoreChange { current_daa_score: 100 });
+        assert!(matches!(*balance.try_recv().unwrap(), Events::DaaScoreChange { current_daa_score: 100 }));
+
+        drop(balance);
+        assert_eq!(subscriptions.inner.subscribers.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_lag_policies() {
+        let subscriptions = EventSubscriptions::new();
+        let newest = subscriptions.subscribe(EventKind::Balance.into(), Some(2), LagPolicy::DropNewest);
+        let oldest = subscriptions.subscribe(EventKind::Balance.into(), Some(2), LagPolicy::DropOldest);
+
+        for mature in 0..5 {
+            subscriptions.dispatch(&balance_event(mature));
+        }
+
+        assert_eq!(newest.lagged(), 3);
+        assert_eq!(oldest.lagged(), 3);
+        let newest = std::iter::from_fn(|| newest.try_recv()).map(|event| mature_balance(&event)).collect::<Vec<_>>();
+        let oldest = std::iter::from_fn(|| oldest.try_recv()).map(|event| mature_balance(&event)).collect::<Vec<_>>();
+        assert_eq!(newest, vec![0, 1]);
+        assert_eq!(oldest, vec![3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_balance_events() {
+        let subscriptions = EventSubscriptions::new();
+        let mut balance_events = subscriptions.subscribe_balance_events();
+
+        subscriptions.dispatch(&Events::DaaScoreChange { current_daa_score: 1 });
+        subscriptions.dispatch(&balance_event(5));
+        let event = balance_events.next().await.unwrap();
+        assert_eq!(event.balance.unwrap().mature, 5);
+    }
+}
@@ -2,6 +2,8 @@ use crate::imports::*;
 
 use async_channel::{unbounded, Receiver};
 use async_trait::async_trait;
+use kaspa_consensus_core::config::params::Params;
+use kaspa_consensus_core::tx::Transaction;
 use kaspa_notify::events::EVENT_TYPE_ARRAY;
 use kaspa_notify::listener::{ListenerId, ListenerLifespan};
 use kaspa_notify::notifier::{Notifier, Notify};
@@ -25,6 +27,14 @@ pub struct RpcCoreMock {
     ctl: RpcCtl,
     core_notifier: Arc<RpcCoreNotifier>,
     _sync_receiver: Receiver<()>,
+    /// Network the mocked node reports running on (server info and params digest)
+    network_id: Option<NetworkId>,
+    /// Dust thresholds the mocked node advertises
+    dust_thresholds: Vec<RpcDustThreshold>,
+    /// UTXO entries the mocked node serves by address
+    utxos: Mutex<Vec<RpcUtxosByAddressesEntry>>,
+    /// Addresses of the `get_utxos_by_addresses()` requests received so far
+    utxo_queries: Mutex<Vec<Vec<RpcAddress>>>,
 }
 
 impl RpcCoreMock {
@@ -41,7 +51,34 @@ impl RpcCoreMock {
             policies,
             Some(sync_sender),
         ));
-        Self { core_notifier, _sync_receiver: sync_receiver, ctl: RpcCtl::new() }
+        Self {
+            core_notifier,
+            _sync_receiver: sync_receiver,
+            ctl: RpcCtl::new(),
+            network_id: None,
+            dust_thresholds: vec![],
+            utxos: Default::default(),
+            utxo_queries: Default::default(),
+        }
+    }
+
+    pub fn with_network_id(network_id: NetworkId) -> Self {
+        Self { network_id: Some(network_id), ..Self::new() }
+    }
+
+    pub fn with_dust_thresholds(mut self, dust_thresholds: Vec<RpcDustThreshold>) -> Self {
+        self.dust_thresholds = dust_thresholds;
+        self
+    }
+
+    /// Replaces the UTXO entries served by `get_utxos_by_addresses()`
+    pub fn set_utxos(&self, utxos: Vec<RpcUtxosByAddressesEntry>) {
+        *self.utxos.lock().unwrap() = utxos;
+    }
+
+    /// Returns and clears the addresses of the `get_utxos_by_addresses()` requests received so far
+    pub fn take_utxo_queries(&self) -> Vec<Vec<RpcAddress>> {
+        std::mem::take(&mut *self.utxo_queries.lock().unwrap())
     }
 
     pub fn core_notifier(&self) -> Arc<RpcCoreNotifier> {
@@ -92,6 +129,9 @@ impl RpcApi for RpcCoreMock {
             is_synced: false,
             has_notify_command: false,
             has_message_id: false,
+            params_digest: self.network_id.map(|network_id| Params::from(network_id).digest()),
+            dust_thresholds: self.dust_thresholds.clone(),
+            consensus_status: None,
         })
     }
 
@@ -104,7 +144,15 @@ impl RpcApi for RpcCoreMock {
     }
 
     async fn get_server_info_call(&self, _request: GetServerInfoRequest) -> RpcResult<GetServerInfoResponse> {
-        Err(RpcError::NotImplemented)
+        let network_id = self.network_id.ok_or(RpcError::NotImplemented)?;
+        Ok(GetServerInfoResponse {
+            rpc_api_version: [0; 4],
+            server_version: "mock".to_string(),
+            network_id,
+            has_utxo_index: true,
+            is_synced: true,
+            virtual_daa_score: 0,
+        })
     }
 
     async fn get_sync_status_call(&self, _request: GetSyncStatusRequest) -> RpcResult<GetSyncStatusResponse> {
@@ -147,8 +195,10 @@ impl RpcApi for RpcCoreMock {
         Err(RpcError::NotImplemented)
     }
 
-    async fn submit_transaction_call(&self, _request: SubmitTransactionRequest) -> RpcResult<SubmitTransactionResponse> {
-        Err(RpcError::NotImplemented)
+    async fn submit_transaction_call(&self, request: SubmitTransactionRequest) -> RpcResult<SubmitTransactionResponse> {
+        // accept any transaction
+        let transaction = Transaction::try_from(&request.transaction)?;
+        Ok(SubmitTransactionResponse::new(transaction.id()))
     }
 
     async fn get_block_call(&self, _request: GetBlockRequest) -> RpcResult<GetBlockResponse> {
@@ -204,14 +254,28 @@ impl RpcApi for RpcCoreMock {
         Err(RpcError::NotImplemented)
     }
 
-    async fn get_utxos_by_addresses_call(&self, _request: GetUtxosByAddressesRequest) -> RpcResult<GetUtxosByAddressesResponse> {
-        Err(RpcError::NotImplemented)
+    async fn get_utxos_by_addresses_call(&self, request: GetUtxosByAddressesRequest) -> RpcResult<GetUtxosByAddressesResponse> {
+        self.utxo_queries.lock().unwrap().push(request.addresses.clone());
+        let addresses = request.addresses.into_iter().collect::<HashSet<_>>();
+        let entries = self
+            .utxos
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|entry| entry.address.as_ref().is_some_and(|address| addresses.contains(address)))
+            .cloned()
+            .collect();
+        Ok(GetUtxosByAddressesResponse::new(entries))
     }
 
     async fn get_sink_blue_score_call(&self, _request: GetSinkBlueScoreRequest) -> RpcResult<GetSinkBlueScoreResponse> {
         Err(RpcError::NotImplemented)
     }
 
+    async fn get_virtual_info_call(&self, _request: GetVirtualInfoRequest) -> RpcResult<GetVirtualInfoResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
     async fn ban_call(&self, _request: BanRequest) -> RpcResult<BanResponse> {
         Err(RpcError::NotImplemented)
     }
@@ -245,6 +309,80 @@ impl RpcApi for RpcCoreMock {
         Err(RpcError::NotImplemented)
     }
 
+    async fn get_chain_block_by_daa_score_call(
+        &self,
+        _request: GetChainBlockByDaaScoreRequest,
+    ) -> RpcResult<GetChainBlockByDaaScoreResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn set_log_filters_call(&self, _request: SetLogFiltersRequest) -> RpcResult<SetLogFiltersResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_outpoint_spend_status_call(
+        &self,
+        _request: GetOutpointSpendStatusRequest,
+    ) -> RpcResult<GetOutpointSpendStatusResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn estimate_transaction_confirmation_time_call(
+        &self,
+        _request: EstimateTransactionConfirmationTimeRequest,
+    ) -> RpcResult<EstimateTransactionConfirmationTimeResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_acceptance_proof_call(&self, _request: GetAcceptanceProofRequest) -> RpcResult<GetAcceptanceProofResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn create_backup_call(&self, _request: CreateBackupRequest) -> RpcResult<CreateBackupResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_virtual_parent_selection_trace_call(
+        &self,
+        _request: GetVirtualParentSelectionTraceRequest,
+    ) -> RpcResult<GetVirtualParentSelectionTraceResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_system_info_call(&self, _request: GetSystemInfoRequest) -> RpcResult<GetSystemInfoResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn remove_peer_call(&self, _request: RemovePeerRequest) -> RpcResult<RemovePeerResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn set_processing_parallelism_call(
+        &self,
+        _request: SetProcessingParallelismRequest,
+    ) -> RpcResult<SetProcessingParallelismResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_address_activity_call(&self, _request: GetAddressActivityRequest) -> RpcResult<GetAddressActivityResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_missed_notifications_call(
+        &self,
+        _request: GetMissedNotificationsRequest,
+    ) -> RpcResult<GetMissedNotificationsResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_utxo_return_address_call(&self, _request: GetUtxoReturnAddressRequest) -> RpcResult<GetUtxoReturnAddressResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn validate_block_template_call(&self, _request: ValidateBlockTemplateRequest) -> RpcResult<ValidateBlockTemplateResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Notification API
 
@@ -55,6 +55,12 @@
 //!   context of WASM32 SDK, not doing that while working with large
 //!   UTXO sets will result in a browser UI freezing.
 //!
+//! A [`Generator`] created with [`Generator::try_new_estimate`] runs in
+//! an estimate mode: it performs the UTXO selection and the mass and fee
+//! computation without producing [`PendingTransaction`] instances nor
+//! reserving UTXO entries in the source [`UtxoContext`], producing only
+//! the [`GeneratorSummary`] via [`Generator::estimate`].
+//!
 
 use crate::imports::*;
 use crate::result::Result;
@@ -62,7 +68,7 @@ use crate::tx::{
     mass::*, Fees, GeneratorSettings, GeneratorSummary, PaymentDestination, PendingTransaction, PendingTransactionIterator,
     PendingTransactionStream,
 };
-use crate::utxo::{NetworkParams, UtxoContext, UtxoEntryReference};
+use crate::utxo::{NetworkParams, UtxoContext, UtxoEntryId, UtxoEntryReference};
 use kaspa_consensus_client::UtxoEntry;
 use kaspa_consensus_core::constants::UNACCEPTED_DAA_SCORE;
 use kaspa_consensus_core::subnets::SUBNETWORK_ID_NATIVE;
@@ -72,6 +78,8 @@ use std::collections::VecDeque;
 
 use super::SignerT;
 
+static GENERATOR_ID_SEQUENCER: AtomicU64 = AtomicU64::new(0);
+
 // fee reduction - when a transactions has some storage mass
 // and the total mass is below this threshold (as well as
 // other conditions), we attempt to accumulate additional
@@ -92,6 +100,11 @@ struct Context {
     /// total fees of all transactions issued by
     /// the single generator instance
     aggregate_fees: u64,
+    /// total mass of all transactions issued by
+    /// the single generator instance
+    aggregate_mass: u64,
+    /// change output value of the final transaction
+    final_transaction_change: Option<u64>,
     /// number of generated transactions
     number_of_transactions: usize,
     /// current tree stage
@@ -101,6 +114,9 @@ struct Context {
     /// iterator has been consumed but was rejected due to mass constraints or
     /// other conditions.
     utxo_stash: VecDeque<UtxoEntryReference>,
+    /// UTXO entries reserved by this generator in the source [`UtxoContext`],
+    /// released when the generator (and all its pending transactions) is dropped
+    reserved_utxos: Vec<UtxoEntryId>,
     /// final transaction id
     final_transaction_id: Option<TransactionId>,
     /// signifies that the generator is finished
@@ -223,6 +239,20 @@ impl Data {
     }
 }
 
+/// Transaction produced by a single generation step
+struct GeneratedTransaction {
+    transaction: Transaction,
+    utxo_entry_references: Vec<UtxoEntryReference>,
+    addresses: Vec<Address>,
+    change_address: Option<Address>,
+    change_output_value: u64,
+    aggregate_input_value: u64,
+    aggregate_output_value: u64,
+    mass: u64,
+    fees: u64,
+    kind: DataKind,
+}
+
 /// Helper struct for passing around transaction value
 #[derive(Debug)]
 struct FinalTransaction {
@@ -251,8 +281,12 @@ struct MassDisposition {
 ///  Internal Generator settings and references
 ///
 struct Inner {
+    // Generator id used as the owner of the UTXO reservations in the source [`UtxoContext`]
+    id: u64,
     // Atomic abortable trigger that will cause the processing to halt with `Error::Aborted`
     abortable: Option<Abortable>,
+    // Estimate mode: no pending transactions are produced and no UTXO entries are reserved
+    estimate_only: bool,
     // Optional signer that is passed on to the [`PendingTransaction`] allowing [`PendingTransaction`] to expose signing functions for convenience.
     signer: Option<Arc<dyn SignerT>>,
     // Internal mass calculator (pre-configured with network params)
@@ -275,6 +309,12 @@ struct Inner {
     minimum_signatures: u16,
     // change address
     change_address: Address,
+    // provider of a change address per generated transaction
+    change_address_provider: Option<ChangeAddressProvider>,
+    // minimum change output value relayed by the node (if advertised by the node)
+    change_dust_threshold: Option<u64>,
+    // the transaction spends exactly the manually selected UTXO entries
+    manual_utxo_selection: bool,
     // change_output: TransactionOutput,
     standard_change_output_compute_mass: u64,
     // signature mass per input
@@ -298,9 +338,20 @@ struct Inner {
     context: Mutex<Context>,
 }
 
+impl Drop for Inner {
+    fn drop(&mut self) {
+        // release UTXO entries that have not been consumed by a submitted transaction
+        if let (Some(utxo_context), Ok(context)) = (self.source_utxo_context.as_ref(), self.context.get_mut()) {
+            utxo_context.release_utxos(context.reserved_utxos.iter(), self.id);
+        }
+    }
+}
+
 impl std::fmt::Debug for Inner {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Inner")
+            .field("id", &self.id)
+            .field("estimate_only", &self.estimate_only)
             .field("network_id", &self.network_id)
             .field("network_params", &self.network_params)
             // .field("source_utxo_context", &self.source_utxo_context)
@@ -309,6 +360,9 @@ impl std::fmt::Debug for Inner {
             .field("sig_op_count", &self.sig_op_count)
             .field("minimum_signatures", &self.minimum_signatures)
             .field("change_address", &self.change_address)
+            .field("rotates_change_address", &self.change_address_provider.is_some())
+            .field("change_dust_threshold", &self.change_dust_threshold)
+            .field("manual_utxo_selection", &self.manual_utxo_selection)
             .field("standard_change_output_compute_mass", &self.standard_change_output_compute_mass)
             .field("signature_mass_per_input", &self.signature_mass_per_input)
             // .field("final_transaction", &self.final_transaction)
@@ -334,6 +388,22 @@ pub struct Generator {
 impl Generator {
     /// Create a new [`Generator`] instance using [`GeneratorSettings`].
     pub fn try_new(settings: GeneratorSettings, signer: Option<Arc<dyn SignerT>>, abortable: Option<&Abortable>) -> Result<Self> {
+        Self::try_new_with_mode(settings, signer, abortable, false)
+    }
+
+    /// Create a new [`Generator`] instance running in estimate mode using [`GeneratorSettings`].
+    /// The generator produces only the [`GeneratorSummary`] via [`Generator::estimate`] and
+    /// leaves the source [`UtxoContext`] untouched.
+    pub fn try_new_estimate(settings: GeneratorSettings, abortable: Option<&Abortable>) -> Result<Self> {
+        Self::try_new_with_mode(settings, None, abortable, true)
+    }
+
+    fn try_new_with_mode(
+        settings: GeneratorSettings,
+        signer: Option<Arc<dyn SignerT>>,
+        abortable: Option<&Abortable>,
+        estimate_only: bool,
+    ) -> Result<Self> {
         let GeneratorSettings {
             network_id,
             multiplexer,
@@ -342,10 +412,13 @@ impl Generator {
             sig_op_count,
             minimum_signatures,
             change_address,
+            change_address_provider,
             final_transaction_priority_fee,
             final_transaction_destination,
             final_transaction_payload,
             destination_utxo_context,
+            change_dust_threshold,
+            utxo_selection,
         } = settings;
 
         let network_type = NetworkType::from(network_id);
@@ -414,29 +487,40 @@ impl Generator {
             return Err(Error::GeneratorTransactionOutputsAreTooHeavy { mass: mass_sanity_check, kind: "compute mass" });
         }
 
+        let manual_utxo_selection = utxo_selection.is_manual();
+        let utxo_iterator = utxo_selection.resolve(utxo_iterator, utxo_context.as_ref())?;
+
         let context = Mutex::new(Context {
             utxo_source_iterator: utxo_iterator,
             number_of_transactions: 0,
             aggregated_utxos: 0,
             aggregate_fees: 0,
+            aggregate_mass: 0,
+            final_transaction_change: None,
             stage: Some(Box::default()),
             utxo_stash: VecDeque::default(),
+            reserved_utxos: vec![],
             final_transaction_id: None,
             is_done: false,
         });
 
         let inner = Inner {
+            id: GENERATOR_ID_SEQUENCER.fetch_add(1, Ordering::SeqCst),
             network_id,
             network_params,
             multiplexer,
             context,
             signer,
             abortable: abortable.cloned(),
+            estimate_only,
             mass_calculator,
             source_utxo_context: utxo_context,
             sig_op_count,
             minimum_signatures,
             change_address,
+            change_address_provider,
+            change_dust_threshold,
+            manual_utxo_selection,
             standard_change_output_compute_mass: standard_change_output_mass,
             signature_mass_per_input,
             final_transaction,
@@ -467,11 +551,44 @@ impl Generator {
         &self.inner.network_params
     }
 
+    /// Signifies that the generator runs in estimate mode
+    pub fn is_estimate_only(&self) -> bool {
+        self.inner.estimate_only
+    }
+
     /// The underlying [`UtxoContext`] (if available).
     pub fn source_utxo_context(&self) -> &Option<UtxoContext> {
         &self.inner.source_utxo_context
     }
 
+    /// The address receiving the change outputs of the generated transactions, unless the generator
+    /// rotates change addresses (see [`PendingTransaction::change_address`] for the change address
+    /// of a particular transaction)
+    pub fn change_address(&self) -> &Address {
+        &self.inner.change_address
+    }
+
+    /// Signifies that each generated transaction sends its change to a change address
+    /// obtained from the [`ChangeAddressProvider`] of the settings
+    pub fn rotates_change_address(&self) -> bool {
+        self.inner.change_address_provider.is_some() && !self.inner.estimate_only
+    }
+
+    /// The change address of the next generated transaction
+    fn next_change_address(&self) -> Result<Address> {
+        match self.inner.change_address_provider.as_ref() {
+            Some(provider) if !self.inner.estimate_only => {
+                let change_address = provider()?;
+                // sanity check
+                if change_address.prefix != self.inner.change_address.prefix {
+                    return Err(Error::GeneratorChangeAddressNetworkTypeMismatch);
+                }
+                Ok(change_address)
+            }
+            _ => Ok(self.inner.change_address.clone()),
+        }
+    }
+
     /// Signifies that the transaction is a transfer between accounts
     pub fn destination_utxo_context(&self) -> &Option<UtxoContext> {
         &self.inner.destination_utxo_context
@@ -535,7 +652,41 @@ impl Generator {
             .utxo_stash
             .pop_front()
             .or_else(|| stage.utxo_iterator.as_mut().and_then(|utxo_stage_iterator| utxo_stage_iterator.next()))
-            .or_else(|| context.utxo_source_iterator.next())
+            .or_else(|| self.get_source_utxo_entry(context))
+    }
+
+    /// Get next UTXO entry from the UTXO source iterator. If the generator is running against a [`UtxoContext`],
+    /// the entry is reserved in the context and entries reserved by concurrently running generators are skipped
+    /// to prevent different transactions from spending the same UTXOs. In estimate mode, reserved entries are
+    /// skipped without reserving the selected ones.
+    fn get_source_utxo_entry(&self, context: &mut Context) -> Option<UtxoEntryReference> {
+        let Some(utxo_context) = self.inner.source_utxo_context.as_ref() else {
+            return context.utxo_source_iterator.next();
+        };
+
+        loop {
+            let utxo_entry = context.utxo_source_iterator.next()?;
+            if self.inner.estimate_only {
+                if self.inner.manual_utxo_selection || !utxo_context.is_reserved_utxo(utxo_entry.id_as_ref()) {
+                    return Some(utxo_entry);
+                }
+            } else if utxo_context.try_reserve_utxo(utxo_entry.id_as_ref(), self.inner.id) {
+                context.reserved_utxos.push(utxo_entry.id());
+                return Some(utxo_entry);
+            } else if self.inner.manual_utxo_selection {
+                // manually selected UTXOs are spent as requested
+                return Some(utxo_entry);
+            }
+        }
+    }
+
+    /// Release reservations of the UTXO entries left unused in the UTXO stash once the generator is done.
+    fn release_utxo_stash(&self, context: &mut Context) {
+        if let Some(utxo_context) = self.inner.source_utxo_context.as_ref() {
+            let unused = context.utxo_stash.drain(..).map(|utxo_entry| utxo_entry.id()).collect::<AHashSet<_>>();
+            context.reserved_utxos.retain(|id| !unused.contains(id));
+            utxo_context.release_utxos(unused.iter(), self.inner.id);
+        }
     }
 
     /// Calculate relay transaction mass for the current transaction `data`
@@ -595,12 +746,18 @@ impl Generator {
             if let Some(final_transaction) = &self.inner.final_transaction {
                 // try finish a stage or produce a final transaction with target value
                 // use basic condition checks to avoid unnecessary processing
-                if data.aggregate_mass > TRANSACTION_MASS_BOUNDARY_FOR_STAGE_INPUT_ACCUMULATION
-                    || (self.inner.final_transaction_priority_fee.sender_pays()
-                        && stage.aggregate_input_value >= final_transaction.value_with_priority_fee)
-                    || (self.inner.final_transaction_priority_fee.receiver_pays()
-                        && stage.aggregate_input_value >= final_transaction.value_no_fees.saturating_sub(context.aggregate_fees))
-                {
+                // (manually selected UTXOs are all consumed before finishing)
+                let try_finish = if self.inner.manual_utxo_selection {
+                    !self.has_utxo_entries(context, stage)
+                } else {
+                    data.aggregate_mass > TRANSACTION_MASS_BOUNDARY_FOR_STAGE_INPUT_ACCUMULATION
+                        || (self.inner.final_transaction_priority_fee.sender_pays()
+                            && stage.aggregate_input_value >= final_transaction.value_with_priority_fee)
+                        || (self.inner.final_transaction_priority_fee.receiver_pays()
+                            && stage.aggregate_input_value >= final_transaction.value_no_fees.saturating_sub(context.aggregate_fees))
+                };
+
+                if try_finish {
                     if let Some(kind) = self.try_finish_standard_stage_processing(context, stage, &mut data, final_transaction)? {
                         return Ok((kind, data));
                     }
@@ -682,7 +839,7 @@ impl Generator {
         } else {
             let change_output_value = data.aggregate_input_value - data.transaction_fees;
 
-            if self.inner.mass_calculator.is_dust(change_output_value) {
+            if self.is_dust_change(change_output_value) {
                 // sweep transaction resulting in dust output
                 Ok((DataKind::NoOp, data))
             } else {
@@ -693,6 +850,13 @@ impl Generator {
         }
     }
 
+    /// Test if the change output value is dust, either by the standard dust rule
+    /// or because it is below the minimum output value relayed by the node.
+    fn is_dust_change(&self, change_value: u64) -> bool {
+        self.inner.mass_calculator.is_dust(change_value)
+            || self.inner.change_dust_threshold.is_some_and(|threshold| change_value < threshold)
+    }
+
     /// Calculate storage mass using inputs from `Data`
     /// and `output_harmonics` supplied by the user
     fn calc_storage_mass(&self, data: &Data, output_harmonics: u64) -> u64 {
@@ -728,7 +892,14 @@ impl Generator {
             Fees::None => unreachable!("Fees::None can not occur for final transaction"),
         };
 
-        if reject {
+        if reject && self.inner.manual_utxo_selection {
+            // all manually selected UTXOs are consumed
+            let available_value = match self.inner.final_transaction_priority_fee {
+                Fees::ReceiverPays(_) => stage.aggregate_input_value + context.aggregate_fees,
+                _ => stage.aggregate_input_value,
+            };
+            Err(Error::GeneratorUtxoSelectionInsufficientFunds { additional_needed: total_stage_value_needed - available_value })
+        } else if reject {
             // need more value, reject finalization (try adding more inputs)
             Ok(None)
         } else if transaction_mass > MAXIMUM_STANDARD_TRANSACTION_MASS || stage.number_of_transactions > 0 {
@@ -828,7 +999,7 @@ impl Generator {
             // calculate for final transaction boundaries
             let change_value = data.aggregate_input_value - transaction_target_value;
 
-            if self.inner.mass_calculator.is_dust(change_value) {
+            if self.is_dust_change(change_value) {
                 absorb_change_to_fees = true;
                 self.calc_storage_mass(data, self.inner.final_transaction_outputs_harmonic)
             } else {
@@ -916,7 +1087,61 @@ impl Generator {
     /// an intermediate "batch" transaction sending funds to the change address
     /// or creating a final transaction with the requested set of outputs and the
     /// payload.
+    ///
+    /// Generators running in estimate mode produce no transactions, see [`Generator::estimate`].
     pub fn generate_transaction(&self) -> Result<Option<PendingTransaction>> {
+        if self.inner.estimate_only {
+            return Err(Error::GeneratorEstimateOnly);
+        }
+
+        let Some(generated) = self.generate()? else {
+            return Ok(None);
+        };
+
+        let GeneratedTransaction {
+            transaction,
+            utxo_entry_references,
+            addresses,
+            change_address,
+            change_output_value,
+            aggregate_input_value,
+            aggregate_output_value,
+            mass,
+            fees,
+            kind,
+        } = generated;
+
+        Ok(Some(PendingTransaction::try_new(
+            self,
+            transaction,
+            utxo_entry_references,
+            addresses,
+            self.final_transaction_value_no_fees(),
+            change_address,
+            change_output_value,
+            aggregate_input_value,
+            aggregate_output_value,
+            mass,
+            fees,
+            kind,
+        )?))
+    }
+
+    /// Performs a single step of the transaction generation process, updating the state
+    /// accounted for in the [`GeneratorSummary`] without producing a [`PendingTransaction`].
+    /// Returns `false` once the generator is done.
+    pub fn estimate_transaction(&self) -> Result<bool> {
+        Ok(self.generate()?.is_some())
+    }
+
+    /// Runs the transaction generation process to completion without producing
+    /// [`PendingTransaction`] instances and returns the resulting [`GeneratorSummary`].
+    pub fn estimate(&self) -> Result<GeneratorSummary> {
+        while self.estimate_transaction()? {}
+        Ok(self.summary())
+    }
+
+    fn generate(&self) -> Result<Option<GeneratedTransaction>> {
         let mut context = self.context();
 
         if context.is_done {
@@ -927,15 +1152,21 @@ impl Generator {
         let (kind, data) = self.generate_transaction_data(&mut context, &mut stage)?;
         context.stage.replace(stage);
 
+        if self.inner.manual_utxo_selection && (kind.is_stage_node() || kind.is_stage_edge()) {
+            return Err(Error::GeneratorUtxoSelectionExceedsSingleTransaction);
+        }
+
         match (kind, data) {
             (DataKind::NoOp, _) => {
                 context.is_done = true;
                 context.stage.take();
+                self.release_utxo_stash(&mut context);
                 Ok(None)
             }
             (DataKind::Final, data) => {
                 context.is_done = true;
                 context.stage.take();
+                self.release_utxo_stash(&mut context);
 
                 let Data {
                     inputs,
@@ -962,10 +1193,14 @@ impl Generator {
                     }
                 }
 
-                if change_output_value > 0 {
-                    let output = TransactionOutput::new(change_output_value, pay_to_address_script(&self.inner.change_address));
+                let change_address = if change_output_value > 0 {
+                    let change_address = self.next_change_address()?;
+                    let output = TransactionOutput::new(change_output_value, pay_to_address_script(&change_address));
                     final_outputs.push(output);
-                }
+                    Some(change_address)
+                } else {
+                    None
+                };
 
                 let aggregate_output_value = final_outputs.iter().map(|output| output.value).sum::<u64>();
                 // TODO - validate that this is still correct
@@ -988,21 +1223,22 @@ impl Generator {
                 );
 
                 context.final_transaction_id = Some(tx.id());
+                context.final_transaction_change = Some(change_output_value);
+                context.aggregate_mass += aggregate_mass;
                 context.number_of_transactions += 1;
 
-                Ok(Some(PendingTransaction::try_new(
-                    self,
-                    tx,
+                Ok(Some(GeneratedTransaction {
+                    transaction: tx,
                     utxo_entry_references,
-                    addresses.into_iter().collect(),
-                    self.final_transaction_value_no_fees(),
+                    addresses: addresses.into_iter().collect(),
+                    change_address,
                     change_output_value,
                     aggregate_input_value,
                     aggregate_output_value,
-                    aggregate_mass,
-                    transaction_fees,
+                    mass: aggregate_mass,
+                    fees: transaction_fees,
                     kind,
-                )?))
+                }))
             }
             (kind, data) => {
                 let Data {
@@ -1019,13 +1255,15 @@ impl Generator {
                 assert_eq!(change_output_value, None);
 
                 let output_value = aggregate_input_value - transaction_fees;
-                let script_public_key = pay_to_address_script(&self.inner.change_address);
+                let change_address = self.next_change_address()?;
+                let script_public_key = pay_to_address_script(&change_address);
                 let output = TransactionOutput::new(output_value, script_public_key.clone());
                 let tx = Transaction::new(0, inputs, vec![output], 0, SUBNETWORK_ID_NATIVE, 0, vec![]);
+                context.aggregate_mass += aggregate_mass;
                 context.number_of_transactions += 1;
 
                 let utxo_entry_reference =
-                    Self::create_batch_utxo_entry_reference(tx.id(), output_value, script_public_key, &self.inner.change_address);
+                    Self::create_batch_utxo_entry_reference(tx.id(), output_value, script_public_key, &change_address);
 
                 match kind {
                     DataKind::Node => {
@@ -1044,19 +1282,18 @@ impl Generator {
                     _ => unreachable!(),
                 }
 
-                Ok(Some(PendingTransaction::try_new(
-                    self,
-                    tx,
+                Ok(Some(GeneratedTransaction {
+                    transaction: tx,
                     utxo_entry_references,
-                    addresses.into_iter().collect(),
-                    self.final_transaction_value_no_fees(),
-                    output_value,
+                    addresses: addresses.into_iter().collect(),
+                    change_address: Some(change_address),
+                    change_output_value: output_value,
                     aggregate_input_value,
-                    output_value,
-                    aggregate_mass,
-                    transaction_fees,
+                    aggregate_output_value: output_value,
+                    mass: aggregate_mass,
+                    fees: transaction_fees,
                     kind,
-                )?))
+                }))
             }
         }
     }
@@ -1088,8 +1325,10 @@ impl Generator {
             network_id: self.inner.network_id,
             aggregated_utxos: context.aggregated_utxos,
             aggregated_fees: context.aggregate_fees,
+            aggregated_mass: context.aggregate_mass,
             final_transaction_amount: self.final_transaction_value_no_fees(),
             final_transaction_id: context.final_transaction_id,
+            final_transaction_change: context.final_transaction_change,
             number_of_generated_transactions: context.number_of_transactions,
         }
     }
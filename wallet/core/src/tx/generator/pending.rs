@@ -6,8 +6,9 @@
 use crate::imports::*;
 use crate::result::Result;
 use crate::rpc::DynRpcApi;
-use crate::tx::{DataKind, Generator};
+use crate::tx::{finalize_mass, DataKind, Generator};
 use crate::utxo::{UtxoContext, UtxoEntryId, UtxoEntryReference};
+use kaspa_consensus_core::config::params::Params;
 use kaspa_consensus_core::sign::sign_with_multiple_v2;
 use kaspa_consensus_core::tx::{SignableTransaction, Transaction, TransactionId};
 use kaspa_rpc_core::{RpcTransaction, RpcTransactionId};
@@ -27,6 +28,8 @@ pub(crate) struct PendingTransactionInner {
     pub(crate) is_submitted: AtomicBool,
     /// Payment value of the transaction (transaction destination amount)
     pub(crate) payment_value: Option<u64>,
+    /// Address receiving the change output of the transaction (if any)
+    pub(crate) change_address: Option<Address>,
     /// Change value of the transaction (transaction change amount)
     pub(crate) change_output_value: u64,
     /// Total aggregate value of all inputs
@@ -48,6 +51,7 @@ impl std::fmt::Debug for PendingTransaction {
             .field("utxo_entries", &self.inner.utxo_entries)
             .field("addresses", &self.inner.addresses)
             .field("payment_value", &self.inner.payment_value)
+            .field("change_address", &self.inner.change_address)
             .field("change_output_value", &self.inner.change_output_value)
             .field("aggregate_input_value", &self.inner.aggregate_input_value)
             .field("mass", &self.inner.mass)
@@ -74,6 +78,7 @@ impl PendingTransaction {
         utxo_entries: Vec<UtxoEntryReference>,
         addresses: Vec<Address>,
         payment_value: Option<u64>,
+        change_address: Option<Address>,
         change_output_value: u64,
         aggregate_input_value: u64,
         aggregate_output_value: u64,
@@ -94,6 +99,7 @@ impl PendingTransaction {
                 addresses,
                 is_submitted: AtomicBool::new(false),
                 payment_value,
+                change_address,
                 change_output_value,
                 aggregate_input_value,
                 aggregate_output_value,
@@ -150,6 +156,11 @@ impl PendingTransaction {
         self.inner.change_output_value
     }
 
+    /// Address receiving the change output of the transaction, `None` if the transaction has no change output
+    pub fn change_address(&self) -> Option<&Address> {
+        self.inner.change_address.as_ref()
+    }
+
     pub fn is_final(&self) -> bool {
         self.inner.kind.is_final()
     }
@@ -182,10 +193,21 @@ impl PendingTransaction {
         });
         self.inner.is_submitted.store(true, Ordering::SeqCst);
 
+        // commit the transaction mass if the transaction was signed externally
+        if self.transaction().mass() == 0 {
+            self.finalize_mass()?;
+        }
+
         let rpc_transaction: RpcTransaction = self.rpc_transaction();
 
         // if we are running under UtxoProcessor
         if let Some(utxo_context) = self.inner.generator.source_utxo_context() {
+            // the change of a generator rotating change addresses is sent to an address that the
+            // UtxoContext is not yet tracking, register it to receive the change UTXO notifications
+            if let Some(change_address) = self.change_address().filter(|_| self.inner.generator.rotates_change_address()) {
+                utxo_context.register_addresses(&[change_address.clone()]).await?;
+            }
+
             // lock UtxoProcessor notification ingest
             let _lock = utxo_context.processor().notification_lock().await;
 
@@ -220,6 +242,7 @@ impl PendingTransaction {
         let signer = self.inner.generator.signer().as_ref().expect("no signer in tx generator");
         let signed_tx = signer.try_sign(self.inner.signable_tx.lock()?.clone(), self.addresses())?;
         *self.inner.signable_tx.lock().unwrap() = signed_tx;
+        self.finalize_mass()?;
         Ok(())
     }
 
@@ -227,6 +250,33 @@ impl PendingTransaction {
         let mutable_tx = self.inner.signable_tx.lock()?.clone();
         let signed_tx = sign_with_multiple_v2(mutable_tx, privkeys).fully_signed()?;
         *self.inner.signable_tx.lock().unwrap() = signed_tx;
+        self.finalize_mass()?;
+        Ok(())
+    }
+
+    /// Replaces the signature scripts of the transaction inputs, e.g. with the ones of
+    /// a copy of the transaction signed in another thread, and commits the mass of the
+    /// signed transaction.
+    pub fn try_fill_signature_scripts(&self, signature_scripts: Vec<Vec<u8>>) -> Result<()> {
+        {
+            let mut signable_tx = self.inner.signable_tx.lock()?;
+            if signature_scripts.len() != signable_tx.tx.inputs.len() {
+                return Err(Error::custom("the count of signature scripts does not match the count of inputs"));
+            }
+            for (input, signature_script) in signable_tx.tx.inputs.iter_mut().zip(signature_scripts) {
+                input.signature_script = signature_script;
+            }
+        }
+        self.finalize_mass()?;
         Ok(())
     }
+
+    /// Compute the full contextual mass (compute + storage) of the signed
+    /// transaction and commit it to the transaction mass field.
+    pub fn finalize_mass(&self) -> Result<u64> {
+        let consensus_params = Params::from(self.inner.generator.network_id());
+        let utxo_entries = self.inner.utxo_entries.values().cloned().collect::<Vec<_>>();
+        let signable_tx = self.inner.signable_tx.lock()?;
+        finalize_mass(&signable_tx.tx, &utxo_entries, &consensus_params)
+    }
 }
@@ -7,10 +7,17 @@ use crate::events::Events;
 use crate::imports::*;
 use crate::result::Result;
 use crate::tx::{Fees, PaymentDestination};
-use crate::utxo::{UtxoContext, UtxoEntryReference, UtxoIterator};
+use crate::utxo::{UtxoContext, UtxoEntryId, UtxoEntryReference, UtxoIterator};
 use kaspa_addresses::Address;
+use kaspa_consensus_core::tx::TransactionOutpoint;
+use kaspa_txscript::script_class::ScriptClass;
 use workflow_core::channel::Multiplexer;
 
+/// Provider of the change addresses of the transactions created by the [`Generator`](crate::tx::Generator),
+/// invoked once for every generated transaction that has a change output (see
+/// [`GeneratorSettings::with_change_address_provider`]).
+pub type ChangeAddressProvider = Arc<dyn Fn() -> Result<Address> + Send + Sync>;
+
 pub struct GeneratorSettings {
     // Network type
     pub network_id: NetworkId,
@@ -26,6 +33,8 @@ pub struct GeneratorSettings {
     pub minimum_signatures: u16,
     // change address
     pub change_address: Address,
+    // provider of a change address per generated transaction (the change address is used if absent)
+    pub change_address_provider: Option<ChangeAddressProvider>,
     // applies only to the final transaction
     pub final_transaction_priority_fee: Fees,
     // final transaction outputs
@@ -34,6 +43,73 @@ pub struct GeneratorSettings {
     pub final_transaction_payload: Option<Vec<u8>>,
     // transaction is a transfer between accounts
     pub destination_utxo_context: Option<UtxoContext>,
+    // minimum change output value relayed by the node (if advertised by the node)
+    pub change_dust_threshold: Option<u64>,
+    // UTXO entries funding the transaction
+    pub utxo_selection: UtxoSelection,
+}
+
+/// Selection of the UTXO entries funding the transactions of a [`Generator`](crate::tx::Generator).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum UtxoSelection {
+    /// The generator consumes the UTXO entries of its source as needed,
+    /// chaining transactions if they do not fit in a single one.
+    #[default]
+    Automatic,
+    /// Coin control: the final transaction spends exactly the UTXO entries referenced
+    /// by these outpoints. The entries must fit in a single transaction.
+    Manual(Vec<TransactionOutpoint>),
+}
+
+impl UtxoSelection {
+    pub fn is_manual(&self) -> bool {
+        matches!(self, UtxoSelection::Manual(_))
+    }
+
+    /// Resolves the selected outpoints to the UTXO entries funding the transaction.
+    /// If a source [`UtxoContext`] is available, the entries must be mature UTXOs of
+    /// the context, otherwise they are looked up in the supplied `utxo_iterator`.
+    pub(crate) fn resolve(
+        self,
+        utxo_iterator: Box<dyn Iterator<Item = UtxoEntryReference> + Send + Sync + 'static>,
+        utxo_context: Option<&UtxoContext>,
+    ) -> Result<Box<dyn Iterator<Item = UtxoEntryReference> + Send + Sync + 'static>> {
+        let UtxoSelection::Manual(outpoints) = self else {
+            return Ok(utxo_iterator);
+        };
+
+        if outpoints.is_empty() {
+            return Err(Error::GeneratorUtxoSelectionEmpty);
+        }
+
+        let (mut available, immature) = match utxo_context {
+            Some(utxo_context) => {
+                let context = utxo_context.context();
+                let available = context.mature.iter().map(|entry| (entry.id(), entry.clone())).collect::<AHashMap<_, _>>();
+                let immature = context.pending.keys().chain(context.stasis.keys()).cloned().collect::<AHashSet<_>>();
+                (available, immature)
+            }
+            None => (utxo_iterator.map(|entry| (entry.id(), entry)).collect(), AHashSet::default()),
+        };
+
+        let mut selected = Vec::with_capacity(outpoints.len());
+        let mut selected_ids = AHashSet::with_capacity(outpoints.len());
+        for outpoint in outpoints {
+            let id = UtxoEntryId::from(outpoint);
+            if let Some(entry) = available.remove(&id) {
+                selected_ids.insert(id);
+                selected.push(entry);
+            } else if selected_ids.contains(&id) {
+                return Err(Error::GeneratorUtxoSelectionDuplicate(id.to_string()));
+            } else if immature.contains(&id) {
+                return Err(Error::GeneratorUtxoSelectionImmature(id.to_string()));
+            } else {
+                return Err(Error::GeneratorUtxoSelectionNotFound(id.to_string()));
+            }
+        }
+
+        Ok(Box::new(selected.into_iter()))
+    }
 }
 
 // impl std::fmt::Debug for GeneratorSettings {
@@ -63,11 +139,13 @@ impl GeneratorSettings {
     ) -> Result<Self> {
         let network_id = account.utxo_context().processor().network_id()?;
         let change_address = account.change_address()?;
+        let change_address_provider = account.clone().change_address_provider(&final_transaction_destination);
         let multiplexer = account.wallet().multiplexer().clone();
         let sig_op_count = account.sig_op_count();
         let minimum_signatures = account.minimum_signatures();
 
         let utxo_iterator = UtxoIterator::new(account.utxo_context());
+        let change_dust_threshold = account.utxo_context().processor().dust_threshold(ScriptClass::from(change_address.version));
 
         let settings = GeneratorSettings {
             network_id,
@@ -75,6 +153,7 @@ impl GeneratorSettings {
             sig_op_count,
             minimum_signatures,
             change_address,
+            change_address_provider,
             utxo_iterator: Box::new(utxo_iterator),
             source_utxo_context: Some(account.utxo_context().clone()),
 
@@ -82,6 +161,8 @@ impl GeneratorSettings {
             final_transaction_destination,
             final_transaction_payload,
             destination_utxo_context: None,
+            change_dust_threshold,
+            utxo_selection: UtxoSelection::Automatic,
         };
 
         Ok(settings)
@@ -99,6 +180,7 @@ impl GeneratorSettings {
     ) -> Result<Self> {
         let network_id = utxo_context.processor().network_id()?;
         let utxo_iterator = UtxoIterator::new(&utxo_context);
+        let change_dust_threshold = utxo_context.processor().dust_threshold(ScriptClass::from(change_address.version));
 
         let settings = GeneratorSettings {
             network_id,
@@ -106,6 +188,7 @@ impl GeneratorSettings {
             sig_op_count,
             minimum_signatures,
             change_address,
+            change_address_provider: None,
             utxo_iterator: Box::new(utxo_iterator),
             source_utxo_context: Some(utxo_context),
 
@@ -113,6 +196,8 @@ impl GeneratorSettings {
             final_transaction_destination,
             final_transaction_payload,
             destination_utxo_context: None,
+            change_dust_threshold,
+            utxo_selection: UtxoSelection::Automatic,
         };
 
         Ok(settings)
@@ -135,6 +220,7 @@ impl GeneratorSettings {
             sig_op_count,
             minimum_signatures,
             change_address,
+            change_address_provider: None,
             utxo_iterator: Box::new(utxo_iterator),
             source_utxo_context: None,
 
@@ -142,6 +228,8 @@ impl GeneratorSettings {
             final_transaction_destination,
             final_transaction_payload,
             destination_utxo_context: None,
+            change_dust_threshold: None,
+            utxo_selection: UtxoSelection::Automatic,
         };
 
         Ok(settings)
@@ -151,4 +239,27 @@ impl GeneratorSettings {
         self.destination_utxo_context = Some(destination_utxo_context.clone());
         self
     }
+
+    /// Sets the minimum change output value relayed by the node, change below it being absorbed to fees.
+    /// Set by default from the node the [`UtxoContext`] is connected to, if any.
+    pub fn with_change_dust_threshold(mut self, change_dust_threshold: Option<u64>) -> Self {
+        self.change_dust_threshold = change_dust_threshold;
+        self
+    }
+
+    /// Sets the provider of the change addresses of the generated transactions, each transaction
+    /// having a change output receiving it on a change address obtained from the provider.
+    /// The provider must yield addresses of the same kind as the change address, which
+    /// remains the one accounted for in the transaction mass estimations. The provider is
+    /// not invoked by a generator running in estimate mode.
+    pub fn with_change_address_provider(mut self, change_address_provider: Option<ChangeAddressProvider>) -> Self {
+        self.change_address_provider = change_address_provider;
+        self
+    }
+
+    /// Sets the UTXO entries funding the transaction, see [`UtxoSelection`].
+    pub fn with_utxo_selection(mut self, utxo_selection: UtxoSelection) -> Self {
+        self.utxo_selection = utxo_selection;
+        self
+    }
 }
@@ -17,8 +17,12 @@ pub struct GeneratorSummary {
     pub network_id: NetworkId,
     pub aggregated_utxos: usize,
     pub aggregated_fees: u64,
+    #[serde(default)]
+    pub aggregated_mass: u64,
     pub number_of_generated_transactions: usize,
     pub final_transaction_amount: Option<u64>,
+    #[serde(default)]
+    pub final_transaction_change: Option<u64>,
     pub final_transaction_id: Option<TransactionId>,
 }
 
@@ -39,6 +43,10 @@ impl GeneratorSummary {
         self.aggregated_fees
     }
 
+    pub fn aggregated_mass(&self) -> u64 {
+        self.aggregated_mass
+    }
+
     pub fn number_of_generated_transactions(&self) -> usize {
         self.number_of_generated_transactions
     }
@@ -47,6 +55,10 @@ impl GeneratorSummary {
         self.final_transaction_amount
     }
 
+    pub fn final_transaction_change(&self) -> Option<u64> {
+        self.final_transaction_change
+    }
+
     pub fn final_transaction_id(&self) -> Option<TransactionId> {
         self.final_transaction_id
     }
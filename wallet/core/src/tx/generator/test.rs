@@ -2,10 +2,11 @@
 
 use crate::error::Error;
 use crate::result::Result;
-use crate::tx::{Fees, MassCalculator, PaymentDestination};
+use crate::tx::{Fees, MassCalculator, PaymentDestination, SIGNATURE_SIZE};
 use crate::utxo::UtxoEntryReference;
 use crate::{tx::PaymentOutputs, utils::kaspa_to_sompi};
 use kaspa_addresses::Address;
+use kaspa_consensus_core::config::params::Params;
 use kaspa_consensus_core::network::{NetworkId, NetworkType};
 use kaspa_consensus_core::tx::Transaction;
 use rand::prelude::*;
@@ -381,6 +382,21 @@ pub(crate) fn make_generator<F>(
     change_address: F,
     final_transaction_destination: PaymentDestination,
 ) -> Result<Generator>
+where
+    F: FnOnce(NetworkType) -> Address,
+{
+    let settings = make_generator_settings(network_id, head, tail, fees, change_address, final_transaction_destination);
+    Generator::try_new(settings, None, None)
+}
+
+pub(crate) fn make_generator_settings<F>(
+    network_id: NetworkId,
+    head: &[f64],
+    tail: &[f64],
+    fees: Fees,
+    change_address: F,
+    final_transaction_destination: PaymentDestination,
+) -> GeneratorSettings
 where
     F: FnOnce(NetworkType) -> Address,
 {
@@ -398,7 +414,7 @@ where
     let final_transaction_payload = None;
     let change_address = change_address(network_id.into());
 
-    let settings = GeneratorSettings {
+    GeneratorSettings {
         network_id,
         multiplexer,
         sig_op_count,
@@ -410,9 +426,9 @@ where
         final_transaction_priority_fee: final_priority_fee,
         final_transaction_destination,
         final_transaction_payload,
-    };
-
-    Generator::try_new(settings, None, None)
+        change_dust_threshold: None,
+        utxo_selection: UtxoSelection::Automatic,
+    }
 }
 
 pub(crate) fn change_address(network_type: NetworkType) -> Address {
@@ -536,6 +552,108 @@ fn test_generator_dust_1_1() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_generator_change_dust_threshold() -> Result<()> {
+    let network_id = test_network_id();
+    let payment = || PaymentOutputs::from([(output_address(network_id.into()), kaspa_to_sompi(9.0))].as_slice()).into();
+
+    // A change of about 1 KAS is created by default
+    let generator = make_generator(network_id, &[10.0], &[], Fees::sender(Kaspa(0.0)), change_address, payment())?;
+    let pt = generator.generate_transaction()?.expect("final transaction");
+    assert_eq!(pt.transaction().outputs.len(), 2, "expected a change output");
+
+    // The change is absorbed to fees if the node does not relay it
+    let settings = make_generator_settings(network_id, &[10.0], &[], Fees::sender(Kaspa(0.0)), change_address, payment())
+        .with_change_dust_threshold(Some(kaspa_to_sompi(2.0)));
+    let pt = Generator::try_new(settings, None, None)?.generate_transaction()?.expect("final transaction");
+    assert_eq!(pt.transaction().outputs.len(), 1, "expected the change to be absorbed to fees");
+    assert_eq!(pt.fees(), kaspa_to_sompi(1.0));
+
+    // A threshold below the change value keeps it
+    let settings = make_generator_settings(network_id, &[10.0], &[], Fees::sender(Kaspa(0.0)), change_address, payment())
+        .with_change_dust_threshold(Some(kaspa_to_sompi(0.5)));
+    let pt = Generator::try_new(settings, None, None)?.generate_transaction()?.expect("final transaction");
+    assert_eq!(pt.transaction().outputs.len(), 2, "expected a change output");
+
+    Ok(())
+}
+
+fn make_manual_selection_generator(entries: &[UtxoEntryReference], selected: &[usize], fees: Fees, amount: f64) -> Result<Generator> {
+    let network_id = test_network_id();
+    let outputs = PaymentOutputs::from([(output_address(network_id.into()), kaspa_to_sompi(amount))].as_slice());
+    let outpoints = selected.iter().map(|&i| entries[i].utxo.outpoint.clone().into()).collect();
+    let settings = GeneratorSettings::try_new_with_iterator(
+        network_id,
+        Box::new(entries.to_vec().into_iter()),
+        change_address(network_id.into()),
+        1,
+        1,
+        outputs.into(),
+        fees,
+        None,
+        None,
+    )?
+    .with_utxo_selection(UtxoSelection::Manual(outpoints));
+    Generator::try_new(settings, None, None)
+}
+
+#[test]
+fn test_generator_manual_utxo_selection() -> Result<()> {
+    let entries = [10.0, 20.0, 30.0, 40.0].map(|v| UtxoEntryReference::simulated(kaspa_to_sompi(v)));
+
+    // the selected UTXOs are all spent, even if fewer would cover the amount
+    let generator = make_manual_selection_generator(&entries, &[3, 0], Fees::sender(Kaspa(0.0)), 5.0)?;
+    let pt = generator.generate_transaction()?.expect("final transaction");
+    let tx = pt.transaction();
+    let spent = tx.inputs.iter().map(|input| input.previous_outpoint).collect::<Vec<_>>();
+    assert_eq!(spent, [3, 0].map(|i| kaspa_consensus_core::tx::TransactionOutpoint::from(entries[i].utxo.outpoint.clone())));
+    assert_eq!(pt.aggregate_input_value(), kaspa_to_sompi(50.0));
+    assert!(generator.generate_transaction()?.is_none());
+
+    // the shortfall includes the transaction fees
+    let generator = make_manual_selection_generator(&entries, &[0, 1], Fees::sender(Kaspa(0.0)), 35.0)?;
+    match generator.generate_transaction() {
+        Err(Error::GeneratorUtxoSelectionInsufficientFunds { additional_needed }) => {
+            assert!(additional_needed > kaspa_to_sompi(5.0) && additional_needed < kaspa_to_sompi(5.01), "{additional_needed}")
+        }
+        result => panic!("expected insufficient funds in the selected UTXOs, received: {:?}", result.map(|_| ())),
+    }
+
+    // outpoints missing from the source are rejected
+    let unknown = [UtxoEntryReference::simulated(kaspa_to_sompi(1.0))];
+    let settings = GeneratorSettings::try_new_with_iterator(
+        test_network_id(),
+        Box::new(entries.to_vec().into_iter()),
+        change_address(test_network_id().into()),
+        1,
+        1,
+        PaymentDestination::Change,
+        Fees::None,
+        None,
+        None,
+    )?
+    .with_utxo_selection(UtxoSelection::Manual(vec![unknown[0].utxo.outpoint.clone().into()]));
+    assert!(matches!(Generator::try_new(settings, None, None), Err(Error::GeneratorUtxoSelectionNotFound(_))));
+    assert!(matches!(
+        make_manual_selection_generator(&entries, &[1, 1], Fees::sender(Kaspa(0.0)), 5.0),
+        Err(Error::GeneratorUtxoSelectionDuplicate(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_generator_manual_utxo_selection_single_transaction() -> Result<()> {
+    let entries = (0..1000).map(|_| UtxoEntryReference::simulated(kaspa_to_sompi(1.0))).collect::<Vec<_>>();
+    let selected = (0..entries.len()).collect::<Vec<_>>();
+
+    // automatic selection would chain transactions, manual selection can not
+    let generator = make_manual_selection_generator(&entries, &selected, Fees::sender(Kaspa(0.0)), 900.0)?;
+    assert!(matches!(generator.generate_transaction(), Err(Error::GeneratorUtxoSelectionExceedsSingleTransaction)));
+
+    Ok(())
+}
+
 #[test]
 fn test_generator_inputs_2_outputs_2_fees_exclude() -> Result<()> {
     generator(
@@ -680,3 +798,135 @@ fn test_generator_inputs_903_outputs_2_fees_exclude() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_generator_finalize_mass_commitment() -> Result<()> {
+    let generator = make_generator(test_network_id(), &[10.0, 10.0], &[], Fees::None, change_address, PaymentDestination::Change)
+        .expect("merge 2 UTXOs without fees: generator");
+    let pt = generator.generate_transaction()?.expect("merge 2 UTXOs without fees: transaction");
+    let unsigned_tx = pt.transaction();
+    assert_eq!(unsigned_tx.mass(), 0, "unsigned transaction must not carry a mass commitment");
+
+    // emulate signing by populating standard size signature scripts
+    pt.inner
+        .signable_tx
+        .lock()
+        .unwrap()
+        .tx
+        .inputs
+        .iter_mut()
+        .for_each(|input| input.signature_script = vec![0; SIGNATURE_SIZE as usize]);
+
+    let mass = pt.finalize_mass()?;
+    let tx = pt.transaction();
+    assert_eq!(tx.mass(), mass, "committed mass does not match finalized mass");
+    assert_eq!(pt.rpc_transaction().mass, mass, "committed mass is not propagated to the rpc transaction");
+
+    // the node expects the committed mass to be the compute mass of the signed transaction combined with its storage mass
+    let calc = MassCalculator::new(&Params::from(pt.generator().network_id()), pt.generator().network_params());
+    let utxo_entries = pt.utxo_entries().values().cloned().collect::<Vec<_>>();
+    let storage_mass = calc.calc_storage_mass_for_transaction(false, &utxo_entries, &tx.outputs).unwrap();
+    let compute_mass = calc.calc_mass_for_signed_transaction(&unsigned_tx, 1);
+    assert_eq!(calc.calc_mass_for_transaction(&tx), compute_mass);
+    assert_eq!(mass, calc.combine_mass(compute_mass, storage_mass), "committed mass does not match the contextual mass");
+
+    Ok(())
+}
+
+fn assert_estimate_matches_generation(head: &[f64], fees: Fees, outputs: &[(Address, u64)]) -> Result<()> {
+    let destination = || if outputs.is_empty() { PaymentDestination::Change } else { PaymentOutputs::from(outputs).into() };
+    let settings = || make_generator_settings(test_network_id(), head, &[], fees.clone(), change_address, destination());
+
+    let generator = Generator::try_new(settings(), None, None)?;
+    let transactions = generator.iter().collect::<Result<Vec<_>>>()?;
+    let summary = generator.summary();
+
+    let estimator = Generator::try_new_estimate(settings(), None)?;
+    assert!(estimator.is_estimate_only());
+    let estimate = estimator.estimate()?;
+
+    assert_eq!(estimate.number_of_generated_transactions, transactions.len(), "number of transactions");
+    assert_eq!(estimate.aggregated_utxos, summary.aggregated_utxos, "aggregated UTXOs");
+    assert_eq!(estimate.aggregated_fees, transactions.iter().map(|pt| pt.fees()).sum::<u64>(), "aggregated fees");
+    assert_eq!(estimate.aggregated_mass, transactions.iter().map(|pt| pt.inner.mass).sum::<u64>(), "aggregated mass");
+    assert_eq!(estimate.final_transaction_amount, summary.final_transaction_amount, "final transaction amount");
+    assert_eq!(estimate.final_transaction_change, transactions.last().map(|pt| pt.change_value()), "final transaction change");
+    assert_eq!(estimate.final_transaction_id, transactions.last().map(|pt| pt.id()), "final transaction id");
+
+    // an estimate-only generator produces no pending transactions
+    let estimator = Generator::try_new_estimate(settings(), None)?;
+    assert!(matches!(estimator.generate_transaction(), Err(Error::GeneratorEstimateOnly)));
+
+    Ok(())
+}
+
+#[test]
+fn test_generator_estimate_matches_generation() -> Result<()> {
+    let output = |amount: f64| vec![(output_address(test_network_id().into()), kaspa_to_sompi(amount))];
+
+    assert_estimate_matches_generation(&[10.0, 10.0], Fees::None, &[])?;
+    assert_estimate_matches_generation(&[10.0; 200], Fees::None, &[])?;
+    assert_estimate_matches_generation(&[10.0; 100], Fees::sender(Kaspa(5.0)), &output(990.0))?;
+    assert_estimate_matches_generation(&[10.0; 100], Fees::receiver(Kaspa(5.0)), &output(990.0))?;
+    assert_estimate_matches_generation(&[10.0; 1_000], Fees::sender(Kaspa(5.0)), &output(9_000.0))?;
+
+    // insufficient funds are reported by the estimate as well
+    let settings = make_generator_settings(
+        test_network_id(),
+        &[10.0; 10],
+        &[],
+        Fees::sender(Kaspa(5.0)),
+        change_address,
+        PaymentOutputs::from(output(990.0).as_slice()).into(),
+    );
+    let estimate = Generator::try_new_estimate(settings, None)?.estimate();
+    assert!(matches!(estimate, Err(Error::InsufficientFunds { .. })), "expecting insufficient funds error, received: {:?}", estimate);
+
+    Ok(())
+}
+
+#[test]
+fn test_generator_change_address_rotation() -> Result<()> {
+    use kaspa_addresses::{Prefix, Version};
+    use kaspa_txscript::pay_to_address_script;
+    use std::collections::HashSet;
+    use std::sync::atomic::{AtomicU8, Ordering};
+    use std::sync::Arc;
+
+    let provided = Arc::new(AtomicU8::new(0));
+    let provider: ChangeAddressProvider = {
+        let provided = provided.clone();
+        Arc::new(move || Ok(Address::new(Prefix::Testnet, Version::PubKey, &[provided.fetch_add(1, Ordering::SeqCst) + 1; 32])))
+    };
+    let destination = || PaymentOutputs::from([(output_address(test_network_id().into()), kaspa_to_sompi(9_000.0))].as_slice()).into();
+    let settings =
+        || make_generator_settings(test_network_id(), &[10.0; 1_000], &[], Fees::sender(Kaspa(5.0)), change_address, destination());
+
+    // estimations do not consume change addresses
+    let settings_with_provider = settings().with_change_address_provider(Some(provider.clone()));
+    let estimate = Generator::try_new_estimate(settings_with_provider, None)?.estimate()?;
+    assert_eq!(provided.load(Ordering::SeqCst), 0);
+
+    let generator = Generator::try_new(settings().with_change_address_provider(Some(provider)), None, None)?;
+    assert!(generator.rotates_change_address());
+    let transactions = generator.iter().collect::<Result<Vec<_>>>()?;
+    assert_eq!(transactions.len(), estimate.number_of_generated_transactions());
+    assert!(transactions.len() > 1, "the payment is expected to require batch transactions");
+
+    // every transaction sends its change to a fresh change address
+    let change_addresses = transactions.iter().map(|tx| tx.change_address().cloned().expect("change output")).collect::<Vec<_>>();
+    assert_eq!(provided.load(Ordering::SeqCst) as usize, transactions.len());
+    assert_eq!(change_addresses.iter().collect::<HashSet<_>>().len(), transactions.len());
+    assert!(!change_addresses.contains(&change_address(test_network_id().into())));
+    for (tx, change_address) in transactions.iter().zip(change_addresses.iter()) {
+        let script_public_key = pay_to_address_script(change_address);
+        assert_eq!(tx.transaction().outputs.last().unwrap().script_public_key, script_public_key);
+    }
+
+    // the batch outputs spent by the final transaction belong to the rotated change addresses
+    let final_transaction = transactions.last().unwrap();
+    assert!(final_transaction.is_final());
+    assert!(final_transaction.addresses().iter().all(|address| change_addresses.contains(address)));
+
+    Ok(())
+}
@@ -2,6 +2,8 @@
 //! Transaction mass calculator.
 //!
 
+use crate::error::Error;
+use crate::result::Result;
 use crate::utxo::NetworkParams;
 use kaspa_consensus_client::UtxoEntryReference;
 use kaspa_consensus_core::tx::{Transaction, TransactionInput, TransactionOutput, SCRIPT_VECTOR_SIZE};
@@ -217,6 +219,19 @@ pub const fn transaction_standard_output_serialized_byte_size() -> u64 {
     size
 }
 
+/// Computes the full contextual mass (compute + storage) of a signed transaction and
+/// sets it as the transaction mass commitment. Returns the committed mass.
+///
+/// `utxo_entries` must contain the UTXO entries spent by the transaction inputs.
+pub fn finalize_mass(tx: &Transaction, utxo_entries: &[UtxoEntryReference], consensus_params: &Params) -> Result<u64> {
+    let network_params = NetworkParams::from(consensus_params.net);
+    let mass = MassCalculator::new(consensus_params, &network_params)
+        .calc_contextual_mass_for_transaction(tx, utxo_entries)
+        .ok_or(Error::MassCalculationError)?;
+    tx.set_mass(mass);
+    Ok(mass)
+}
+
 pub struct MassCalculator {
     mass_per_tx_byte: u64,
     mass_per_script_pub_key_byte: u64,
@@ -356,6 +371,18 @@ impl MassCalculator {
         Some(harmonic_outs.saturating_sub(arithmetic_ins)) // max( 0 , C·( |O|/H(O) - |I|/A(I) ) )
     }
 
+    /// Calculates the full contextual mass of a transaction, i.e. the compute mass combined with
+    /// the storage mass as per the network mass combination strategy. This is the mass the node
+    /// expects to find committed to in the transaction mass field. The transaction is expected
+    /// to be signed, as signature scripts contribute to the compute mass.
+    ///
+    /// Returns `None` if the storage mass is incomputable.
+    pub fn calc_contextual_mass_for_transaction(&self, tx: &Transaction, utxo_entries: &[UtxoEntryReference]) -> Option<u64> {
+        let compute_mass = self.calc_mass_for_transaction(tx);
+        let storage_mass = self.calc_storage_mass_for_transaction(tx.is_coinbase(), utxo_entries, &tx.outputs)?;
+        Some(self.combine_mass(compute_mass, storage_mass))
+    }
+
     pub fn calc_storage_mass_output_harmonic(&self, outputs: &[TransactionOutput]) -> Option<u64> {
         outputs
             .iter()
@@ -0,0 +1,296 @@
+//!
+//! `kaspa:` payment URIs and their QR code rendering.
+//!
+//! A payment URI is a Kaspa address followed by optional query parameters:
+//! `kaspa:qr0...?amount=1.5&label=Coffee%20Shop&message=Order%2042`.
+//! The address prefix doubles as the URI scheme, so testnet URIs start with
+//! `kaspatest:`. The `amount` is expressed in KAS with up to 8 decimal places,
+//! `label` and `message` are percent-encoded UTF-8 strings. Unknown parameters
+//! are ignored unless they are prefixed with `req-`, in which case the URI is
+//! rejected since the requirement it expresses can not be honored.
+//!
+
+use crate::error::Error;
+use crate::result::Result;
+use kaspa_addresses::{Address, Prefix};
+use kaspa_consensus_core::constants::SOMPI_PER_KASPA;
+use qrcode::render::{svg, unicode};
+use qrcode::QrCode;
+use std::fmt;
+
+/// Number of decimal places of a KAS amount expressed in sompi
+const KASPA_DECIMALS: usize = 8;
+
+/// A payment request expressed as a `kaspa:` URI
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PaymentUri {
+    pub address: Address,
+    /// Requested amount in sompi
+    pub amount: Option<u64>,
+    /// Name of the payment recipient
+    pub label: Option<String>,
+    /// Description of the payment
+    pub message: Option<String>,
+}
+
+impl PaymentUri {
+    pub fn new(address: Address) -> Self {
+        Self { address, amount: None, label: None, message: None }
+    }
+
+    pub fn with_amount(mut self, amount: u64) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    pub fn with_label<S: Into<String>>(mut self, label: S) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn with_message<S: Into<String>>(mut self, message: S) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Parses a payment URI. If `prefix` is supplied, the URI address must belong to
+    /// the corresponding network.
+    pub fn parse(uri: &str, prefix: Option<Prefix>) -> Result<Self> {
+        let uri = uri.trim();
+        let (address, query) = match uri.split_once('?') {
+            Some((address, query)) => (address, Some(query)),
+            None => (uri, None),
+        };
+
+        let address = Address::try_from(address).map_err(|err| Error::InvalidPaymentUri(format!("{err}")))?;
+        if let Some(prefix) = prefix {
+            if address.prefix != prefix {
+                return Err(Error::PaymentUriNetworkMismatch { expected: prefix, found: address.prefix });
+            }
+        }
+
+        let mut payment_uri = Self::new(address);
+        for param in query.into_iter().flat_map(|query| query.split('&')) {
+            let (key, value) =
+                param.split_once('=').ok_or_else(|| Error::InvalidPaymentUri(format!("malformed parameter '{param}'")))?;
+            let field = match key {
+                "amount" => {
+                    let amount = try_parse_amount(value)?;
+                    payment_uri.amount.replace(amount).is_some()
+                }
+                "label" => payment_uri.label.replace(percent_decode(value)?).is_some(),
+                "message" => payment_uri.message.replace(percent_decode(value)?).is_some(),
+                key if key.starts_with("req-") => {
+                    return Err(Error::InvalidPaymentUri(format!("unsupported required parameter '{key}'")));
+                }
+                _ => false,
+            };
+            if field {
+                return Err(Error::InvalidPaymentUri(format!("duplicate parameter '{key}'")));
+            }
+        }
+
+        Ok(payment_uri)
+    }
+
+    /// Renders the URI as a QR code for display in a terminal, using unicode half blocks
+    pub fn to_qr_terminal(&self) -> Result<String> {
+        render_qr_terminal(&self.to_string())
+    }
+
+    /// Renders the URI as an SVG QR code image
+    pub fn to_qr_svg(&self) -> Result<String> {
+        render_qr_svg(&self.to_string())
+    }
+}
+
+impl fmt::Display for PaymentUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.address)?;
+        let params = [
+            self.amount.map(|amount| format!("amount={}", format_amount(amount))),
+            self.label.as_deref().map(|label| format!("label={}", percent_encode(label))),
+            self.message.as_deref().map(|message| format!("message={}", percent_encode(message))),
+        ];
+        for (i, param) in params.into_iter().flatten().enumerate() {
+            write!(f, "{}{param}", if i == 0 { '?' } else { '&' })?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for PaymentUri {
+    type Err = Error;
+    fn from_str(uri: &str) -> Result<Self> {
+        Self::parse(uri, None)
+    }
+}
+
+/// Renders `data` as a QR code for display in a terminal, using unicode half blocks
+/// (two modules per character cell)
+pub fn render_qr_terminal(data: &str) -> Result<String> {
+    let code = QrCode::new(data.as_bytes()).map_err(|err| Error::QrCode(err.to_string()))?;
+    // terminals commonly draw light text on a dark background, so the modules are inverted
+    Ok(code.render::<unicode::Dense1x2>().dark_color(unicode::Dense1x2::Light).light_color(unicode::Dense1x2::Dark).build())
+}
+
+/// Renders `data` as an SVG QR code image
+pub fn render_qr_svg(data: &str) -> Result<String> {
+    let code = QrCode::new(data.as_bytes()).map_err(|err| Error::QrCode(err.to_string()))?;
+    Ok(code.render::<svg::Color>().min_dimensions(200, 200).build())
+}
+
+/// Parses a KAS amount into sompi without any loss of precision.
+/// Amounts with more than 8 decimal places are rejected.
+pub fn try_parse_amount(amount: &str) -> Result<u64> {
+    let invalid = || Error::InvalidPaymentUri(format!("invalid amount '{amount}'"));
+    let (integer, fraction) = amount.split_once('.').unwrap_or((amount, "0"));
+    if integer.is_empty() || fraction.is_empty() || !integer.bytes().chain(fraction.bytes()).all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    if fraction.len() > KASPA_DECIMALS {
+        return Err(Error::PaymentUriAmountPrecision(amount.to_string()));
+    }
+    let integer = integer.parse::<u64>().map_err(|_| invalid())?;
+    let fraction = format!("{fraction:0<KASPA_DECIMALS$}").parse::<u64>().map_err(|_| invalid())?;
+    integer.checked_mul(SOMPI_PER_KASPA).and_then(|sompi| sompi.checked_add(fraction)).ok_or_else(invalid)
+}
+
+/// Formats a sompi amount in KAS, omitting trailing zeros of the fractional part
+fn format_amount(sompi: u64) -> String {
+    let (integer, fraction) = (sompi / SOMPI_PER_KASPA, sompi % SOMPI_PER_KASPA);
+    if fraction == 0 {
+        integer.to_string()
+    } else {
+        let fraction = format!("{fraction:0>KASPA_DECIMALS$}");
+        format!("{integer}.{}", fraction.trim_end_matches('0'))
+    }
+}
+
+/// Percent-encodes all the bytes of `value` but the RFC 3986 unreserved characters
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+fn percent_decode(value: &str) -> Result<String> {
+    let invalid = || Error::InvalidPaymentUri(format!("invalid percent-encoding '{value}'"));
+    let mut decoded = Vec::with_capacity(value.len());
+    let mut bytes = value.bytes();
+    while let Some(byte) = bytes.next() {
+        if byte == b'%' {
+            let mut hex_digit = || bytes.next().and_then(|byte| (byte as char).to_digit(16)).ok_or_else(invalid);
+            decoded.push((hex_digit()? * 16 + hex_digit()?) as u8);
+        } else {
+            decoded.push(byte);
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAINNET_ADDRESS: &str = "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j";
+    const TESTNET_ADDRESS: &str = "kaspatest:qz7ulu4c25dh7fzec9zjyrmlhnkzrg4wmf89q7gzr3gfrsj3uz6xjceef60sd";
+
+    #[test]
+    fn test_payment_uri_round_trip() {
+        let address = Address::try_from(MAINNET_ADDRESS).unwrap();
+        let cases = [
+            (PaymentUri::new(address.clone()), MAINNET_ADDRESS.to_string()),
+            (PaymentUri::new(address.clone()).with_amount(150_000_000), format!("{MAINNET_ADDRESS}?amount=1.5")),
+            (PaymentUri::new(address.clone()).with_amount(1), format!("{MAINNET_ADDRESS}?amount=0.00000001")),
+            (PaymentUri::new(address.clone()).with_amount(u64::MAX), format!("{MAINNET_ADDRESS}?amount=184467440737.09551615")),
+            (
+                PaymentUri::new(address.clone())
+                    .with_amount(42 * SOMPI_PER_KASPA)
+                    .with_label("Coffee Shop")
+                    .with_message("Order #42 & tip"),
+                format!("{MAINNET_ADDRESS}?amount=42&label=Coffee%20Shop&message=Order%20%2342%20%26%20tip"),
+            ),
+            (
+                PaymentUri::new(address.clone()).with_message("ünïcödé ✓"),
+                format!("{MAINNET_ADDRESS}?message=%C3%BCn%C3%AFc%C3%B6d%C3%A9%20%E2%9C%93"),
+            ),
+        ];
+        for (payment_uri, expected) in cases {
+            let uri = payment_uri.to_string();
+            assert_eq!(uri, expected);
+            assert_eq!(PaymentUri::parse(&uri, Some(Prefix::Mainnet)).unwrap(), payment_uri);
+        }
+
+        // Unknown optional parameters are ignored and the parameters order is irrelevant
+        let payment_uri = PaymentUri::parse(&format!("{MAINNET_ADDRESS}?label=a+b&foo=bar&amount=0.1"), None).unwrap();
+        assert_eq!(payment_uri, PaymentUri::new(address).with_amount(10_000_000).with_label("a+b"));
+    }
+
+    #[test]
+    fn test_payment_uri_malformed_corpus() {
+        let corpus = [
+            "",
+            "kaspa:",
+            "kaspa:?amount=1",
+            "bitcoin:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j",
+            "qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j",
+            // bad checksum
+            "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5k",
+            "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j?amount",
+            "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j?amount=",
+            "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j?amount=-1",
+            "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j?amount=+1",
+            "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j?amount=1e8",
+            "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j?amount=.5",
+            "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j?amount=5.",
+            "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j?amount=1.2.3",
+            "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j?amount=1,5",
+            "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j?amount=%31",
+            "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j?amount=184467440737.09551616",
+            "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j?amount=1&amount=2",
+            "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j?label=a&label=b",
+            "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j?label=%",
+            "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j?label=%4",
+            "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j?label=%zz",
+            "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j?label=%+1",
+            "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j?message=%C3",
+            "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j?req-expires=1700000000",
+            "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j?amount=1&&label=a",
+        ];
+        for uri in corpus {
+            assert!(matches!(PaymentUri::parse(uri, None), Err(Error::InvalidPaymentUri(_))), "'{uri}' should be rejected");
+        }
+
+        let uri = format!("{MAINNET_ADDRESS}?amount=0.123456789");
+        assert!(matches!(PaymentUri::parse(&uri, None), Err(Error::PaymentUriAmountPrecision(_))));
+    }
+
+    #[test]
+    fn test_payment_uri_network_prefix() {
+        let uri = format!("{TESTNET_ADDRESS}?amount=10");
+        let payment_uri = PaymentUri::parse(&uri, Some(Prefix::Testnet)).unwrap();
+        assert_eq!(payment_uri.amount, Some(10 * SOMPI_PER_KASPA));
+        assert!(matches!(
+            PaymentUri::parse(&uri, Some(Prefix::Mainnet)),
+            Err(Error::PaymentUriNetworkMismatch { expected: Prefix::Mainnet, found: Prefix::Testnet })
+        ));
+        assert!(PaymentUri::parse(MAINNET_ADDRESS, Some(Prefix::Testnet)).is_err());
+    }
+
+    #[test]
+    fn test_payment_uri_qr_rendering() {
+        let payment_uri = PaymentUri::parse(&format!("{MAINNET_ADDRESS}?amount=1.5&label=Shop"), None).unwrap();
+        let svg = payment_uri.to_qr_svg().unwrap();
+        assert!(svg.starts_with("<?xml") && svg.contains("<svg"));
+        let terminal = payment_uri.to_qr_terminal().unwrap();
+        let lines = terminal.lines().collect::<Vec<_>>();
+        assert!(lines.len() > 10 && lines.iter().all(|line| line.chars().count() == lines[0].chars().count()));
+    }
+}
@@ -66,6 +66,12 @@ export interface IBalance {
      * but has not yet been accepted by the network.
      */
     outgoing: bigint;
+    /**
+     * Portion of the mature amount (in SOMPI) held
+     * by UTXOs currently selected by transactions
+     * that are being generated or submitted.
+     */
+    reserved: bigint;
     /**
      * Number of UTXOs available for spending.
      */
@@ -97,6 +103,8 @@ pub struct Balance {
     pub mature: u64,
     pub pending: u64,
     pub outgoing: u64,
+    #[serde(default)]
+    pub reserved: u64,
     pub mature_utxo_count: usize,
     pub pending_utxo_count: usize,
     pub stasis_utxo_count: usize,
@@ -119,6 +127,7 @@ impl Balance {
             mature,
             pending,
             outgoing,
+            reserved: 0,
             mature_delta: Delta::default(),
             pending_delta: Delta::default(),
             mature_utxo_count,
@@ -127,6 +136,12 @@ impl Balance {
         }
     }
 
+    /// Sets the portion of the mature amount reserved by in-flight transaction generation
+    pub fn with_reserved(mut self, reserved: u64) -> Self {
+        self.reserved = reserved;
+        self
+    }
+
     pub fn is_empty(&self) -> bool {
         self.mature == 0 && self.pending == 0
     }
@@ -171,6 +186,7 @@ impl From<AtomicBalance> for Balance {
             mature: atomic_balance.mature.load(Ordering::SeqCst),
             pending: atomic_balance.pending.load(Ordering::SeqCst),
             outgoing: 0,
+            reserved: 0,
             mature_utxo_count: atomic_balance.mature_utxos.load(Ordering::SeqCst),
             pending_utxo_count: atomic_balance.pending_utxos.load(Ordering::SeqCst),
             stasis_utxo_count: atomic_balance.stasis_utxos.load(Ordering::SeqCst),
@@ -14,8 +14,11 @@ use crate::utxo::{
     Maturity, NetworkParams, OutgoingTransaction, PendingUtxoEntryReference, UtxoContextBinding, UtxoEntryId, UtxoEntryReference,
     UtxoEntryReferenceExtension, UtxoProcessor,
 };
+use kaspa_consensus_core::constants::UNACCEPTED_DAA_SCORE;
 use kaspa_hashes::Hash;
 use sorted_insert::SortedInsertBinaryByKey;
+use std::time::Duration;
+use workflow_core::time::unixtime_as_millis_u64;
 
 static UTXO_CONTEXT_ID_SEQUENCER: AtomicU64 = AtomicU64::new(0);
 fn next_utxo_context_id() -> Hash {
@@ -73,6 +76,25 @@ impl std::fmt::Display for UtxoContextId {
     }
 }
 
+/// Duration after which a UTXO reservation held by a transaction [`Generator`](crate::tx::Generator)
+/// lapses if it has not been released (e.g. if the generator has been abandoned without being dropped).
+pub const UTXO_RESERVATION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Reservation of a mature UTXO entry by a transaction generator
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct UtxoReservation {
+    /// id of the generator holding the reservation
+    owner: u64,
+    /// unixtime (msec) at which the reservation lapses
+    expires: u64,
+}
+
+impl UtxoReservation {
+    fn is_live(&self, now: u64) -> bool {
+        self.expires > now
+    }
+}
+
 pub enum UtxoEntryVariant {
     Mature(UtxoEntryReference),
     Pending(UtxoEntryReference),
@@ -92,6 +114,10 @@ pub struct Context {
     /// Confirmation occurs when the transaction UTXOs are
     /// removed from the context by the UTXO change notification.
     pub(crate) outgoing: AHashMap<TransactionId, OutgoingTransaction>,
+    /// Mature UTXOs reserved by transaction generators that are
+    /// in the process of creating or submitting transactions
+    /// spending them. Reserved UTXOs are skipped by other generators.
+    pub(crate) reserved: AHashMap<UtxoEntryId, UtxoReservation>,
     /// Total balance of all UTXOs in this context (mature, pending)
     balance: Option<Balance>,
     /// Addresses monitored by this UTXO context
@@ -106,6 +132,7 @@ impl Default for Context {
             stasis: AHashMap::default(),
             map: AHashMap::default(),
             outgoing: AHashMap::default(),
+            reserved: AHashMap::default(),
             balance: None,
             addresses: Arc::new(DashSet::new()),
         }
@@ -123,6 +150,7 @@ impl Context {
         self.stasis.clear();
         self.pending.clear();
         self.outgoing.clear();
+        self.reserved.clear();
         self.addresses.clear();
         self.balance = None;
     }
@@ -241,6 +269,36 @@ impl UtxoContext {
         Ok(balance)
     }
 
+    /// Reserves the mature UTXO entry `id` on behalf of the transaction generator `owner` for
+    /// [`UTXO_RESERVATION_TIMEOUT`]. Returns `false` if the entry is held by a live reservation
+    /// of a different generator.
+    pub(crate) fn try_reserve_utxo(&self, id: &UtxoEntryId, owner: u64) -> bool {
+        let now = unixtime_as_millis_u64();
+        let mut context = self.context();
+        if context.reserved.get(id).is_some_and(|reservation| reservation.owner != owner && reservation.is_live(now)) {
+            return false;
+        }
+        let expires = now + UTXO_RESERVATION_TIMEOUT.as_millis() as u64;
+        context.reserved.insert(id.clone(), UtxoReservation { owner, expires });
+        true
+    }
+
+    /// Returns `true` if the mature UTXO entry `id` is held by a live reservation of a transaction generator.
+    pub(crate) fn is_reserved_utxo(&self, id: &UtxoEntryId) -> bool {
+        let now = unixtime_as_millis_u64();
+        self.context().reserved.get(id).is_some_and(|reservation| reservation.is_live(now))
+    }
+
+    /// Releases the reservations held by the transaction generator `owner` on the UTXO entries `ids`.
+    pub(crate) fn release_utxos<'a>(&self, ids: impl IntoIterator<Item = &'a UtxoEntryId>, owner: u64) {
+        let mut context = self.context();
+        for id in ids {
+            if context.reserved.get(id).is_some_and(|reservation| reservation.owner == owner) {
+                context.reserved.remove(id);
+            }
+        }
+    }
+
     /// Process pending transaction. Remove mature UTXO entries and add them to the consumed set.
     /// Produces a notification on the even multiplexer.
     pub(crate) async fn register_outgoing_transaction(&self, pending_tx: &PendingTransaction) -> Result<()> {
@@ -251,6 +309,10 @@ impl UtxoContext {
             let mut context = self.context();
             let pending_utxo_entries = pending_tx.utxo_entries();
             context.mature.retain(|entry| !pending_utxo_entries.contains_key(&entry.id()));
+            // the entries are no longer available for selection, their reservations are no longer needed
+            pending_utxo_entries.keys().for_each(|id| {
+                context.reserved.remove(id);
+            });
 
             let outgoing_transaction = OutgoingTransaction::new(current_daa_score, self.clone(), pending_tx.clone());
             self.processor().register_outgoing_transaction(outgoing_transaction.clone());
@@ -263,13 +325,16 @@ impl UtxoContext {
     pub(crate) async fn notify_outgoing_transaction(&self, pending_tx: &PendingTransaction) -> Result<()> {
         let outgoing_tx = self.processor().outgoing().get(&pending_tx.id()).expect("outgoing transaction for notification");
 
-        if pending_tx.is_batch() {
-            let record = TransactionRecord::new_batch(self, &outgoing_tx, None)?;
-            self.processor().notify(Events::Pending { record }).await?;
+        let mut record = if pending_tx.is_batch() {
+            TransactionRecord::new_batch(self, &outgoing_tx, None)?
         } else {
-            let record = TransactionRecord::new_outgoing(self, &outgoing_tx, None)?;
-            self.processor().notify(Events::Pending { record }).await?;
+            TransactionRecord::new_outgoing(self, &outgoing_tx, None)?
+        };
+        // The estimate is informational only, so the record is still notified if the node cannot provide one
+        if let Err(err) = record.update_confirmation_estimate(&self.processor().rpc_api()).await {
+            log_warn!("Unable to estimate the confirmation time of transaction {}: {err}", record.id());
         }
+        self.processor().notify(Events::Pending { record }).await?;
         self.update_balance().await?;
         Ok(())
     }
@@ -283,7 +348,10 @@ impl UtxoContext {
 
         let outgoing_transaction = context.outgoing.remove(&pending_tx.id()).expect("outgoing transaction");
         outgoing_transaction.utxo_entries().iter().for_each(|(_, entry)| {
-            context.mature.push(entry.clone());
+            // outputs of unaccepted transactions (spent by a fee-bumping child) are not part of the mature pool
+            if entry.block_daa_score() != UNACCEPTED_DAA_SCORE {
+                context.mature.push(entry.clone());
+            }
         });
 
         Ok(())
@@ -291,10 +359,16 @@ impl UtxoContext {
 
     /// Insert `utxo_entry` into the `UtxoSet`.
     /// NOTE: The insert will be ignored if already present in the inner map.
+    /// An entry already spent by an outgoing transaction (the change of a transaction
+    /// bumped by a child) is tracked but not made available for selection.
     pub async fn insert(&self, utxo_entry: UtxoEntryReference, current_daa_score: u64, force_maturity: bool) -> Result<()> {
         let mut context = self.context();
+        let is_spent = context.outgoing.values().any(|tx| tx.utxo_entries().contains_key(utxo_entry.id_as_ref()));
         if let std::collections::hash_map::Entry::Vacant(e) = context.map.entry(utxo_entry.id().clone()) {
             e.insert(utxo_entry.clone());
+            if is_spent {
+                return Ok(());
+            }
             if force_maturity {
                 context.mature.sorted_insert_binary_asc_by_key(utxo_entry.clone(), |entry| entry.amount_as_ref());
             } else {
@@ -344,6 +418,7 @@ impl UtxoContext {
                         log_error!("Error: unable to remove utxo entry from global pending (with context)");
                     }
                 } else {
+                    context.reserved.remove(&id);
                     remove_mature_ids.push(id);
                 }
             } else {
@@ -416,6 +491,19 @@ impl UtxoContext {
         context.outgoing.remove(txid)
     }
 
+    /// Removes an outgoing transaction which is no longer expected to be accepted,
+    /// returning its UTXO entries to the mature pool.
+    pub(crate) fn revert_outgoing_transaction(&self, txid: &TransactionId) {
+        let mut context = self.context();
+        if let Some(outgoing_transaction) = context.outgoing.remove(txid) {
+            outgoing_transaction.utxo_entries().iter().for_each(|(_, entry)| {
+                if entry.block_daa_score() != UNACCEPTED_DAA_SCORE {
+                    context.mature.push(entry.clone());
+                }
+            });
+        }
+    }
+
     pub async fn extend_from_scan(&self, utxo_entries: Vec<UtxoEntryReference>, current_daa_score: u64) -> Result<()> {
         let (pending, mature) = {
             let mut context = self.context();
@@ -474,8 +562,15 @@ impl UtxoContext {
     }
 
     pub async fn calculate_balance(&self) -> Balance {
-        let context = self.context();
+        let mut context = self.context();
         let mature: u64 = context.mature.iter().map(|e| e.as_ref().amount).sum();
+
+        // drop lapsed reservations and account for mature
+        // UTXOs currently selected by transaction generators
+        let now = unixtime_as_millis_u64();
+        context.reserved.retain(|_, reservation| reservation.is_live(now));
+        let reserved: u64 =
+            context.mature.iter().filter(|e| context.reserved.contains_key(e.id_as_ref())).map(|e| e.as_ref().amount).sum();
         let pending: u64 = context.pending.values().map(|e| e.as_ref().amount).sum();
 
         // this will aggregate only transactions containing
@@ -509,6 +604,7 @@ impl UtxoContext {
         let mature = (mature + consumed).saturating_sub(outgoing);
 
         Balance::new(mature, pending, outgoing, context.mature.len(), context.pending.len(), context.stasis.len())
+            .with_reserved(reserved)
     }
 
     pub(crate) async fn handle_utxo_added(&self, utxos: Vec<UtxoEntryReference>, current_daa_score: u64) -> Result<()> {
@@ -638,6 +734,31 @@ impl UtxoContext {
         Ok(())
     }
 
+    /// Reconciles the UTXO set of this context with `utxo_entries`, the current UTXO set
+    /// of the context addresses as reported by the node on reconnection. Entries added or
+    /// removed while disconnected are processed as if received via UTXO change notifications.
+    pub(crate) async fn resync(&self, utxo_entries: Vec<UtxoEntryReference>, current_daa_score: u64) -> Result<()> {
+        let (added, removed) = {
+            let context = self.context();
+            let current = utxo_entries.iter().map(|utxo| utxo.id()).collect::<AHashSet<_>>();
+            let removed = context.map.values().filter(|utxo| !current.contains(&utxo.id())).cloned().collect::<Vec<_>>();
+            let added = utxo_entries.into_iter().filter(|utxo| !context.map.contains_key(&utxo.id())).collect::<Vec<_>>();
+            (added, removed)
+        };
+
+        if !removed.is_empty() {
+            self.handle_utxo_removed(removed, current_daa_score).await?;
+        }
+
+        if !added.is_empty() {
+            self.handle_utxo_added(added, current_daa_score).await?;
+        }
+
+        self.update_balance().await?;
+
+        Ok(())
+    }
+
     pub async fn register_addresses(&self, addresses: &[Address]) -> Result<()> {
         if addresses.is_empty() {
             log_error!("utxo processor: register for an empty address set");
@@ -12,6 +12,7 @@ pub mod processor;
 pub mod reference;
 pub mod scan;
 pub mod settings;
+pub mod snapshot;
 pub mod stream;
 pub mod sync;
 
@@ -26,6 +27,7 @@ pub use processor::UtxoProcessor;
 pub use reference::{Maturity, TryIntoUtxoEntryReferences, UtxoEntryReference, UtxoEntryReferenceExtension};
 pub use scan::{Scan, ScanExtent};
 pub use settings::*;
+pub use snapshot::{AccountUtxoSnapshot, UtxoSnapshot, UtxoSnapshotImportSummary};
 pub use stream::UtxoStream;
 pub use sync::SyncMonitor;
 
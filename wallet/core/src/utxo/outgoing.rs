@@ -7,6 +7,10 @@
 use crate::imports::*;
 use crate::tx::PendingTransaction;
 use crate::utxo::{UtxoContext, UtxoEntryId, UtxoEntryReference};
+use kaspa_consensus_client::UtxoEntry;
+use kaspa_consensus_core::constants::UNACCEPTED_DAA_SCORE;
+use kaspa_consensus_core::tx::TransactionOutpoint;
+use kaspa_txscript::pay_to_address_script;
 
 struct Inner {
     pub id: TransactionId,
@@ -85,6 +89,26 @@ impl OutgoingTransaction {
         self.inner.pending_transaction.utxo_entries()
     }
 
+    /// The change output of the transaction as a UTXO entry that can be spent
+    /// before the transaction is accepted (e.g. by a fee-bumping child).
+    pub fn change_utxo_entry(&self) -> Option<UtxoEntryReference> {
+        let pending_transaction = &self.inner.pending_transaction;
+        let change_address = pending_transaction.change_address()?;
+        let script_public_key = pay_to_address_script(change_address);
+        let transaction = pending_transaction.transaction();
+        // the change output is the last output of the transaction
+        let index = transaction.outputs.iter().rposition(|output| output.script_public_key == script_public_key)?;
+        let utxo = UtxoEntry {
+            address: Some(change_address.clone()),
+            outpoint: TransactionOutpoint::new(self.id(), index as TransactionIndexType).into(),
+            amount: transaction.outputs[index].value,
+            script_public_key,
+            block_daa_score: UNACCEPTED_DAA_SCORE,
+            is_coinbase: false,
+        };
+        Some(UtxoEntryReference { utxo: Arc::new(utxo) })
+    }
+
     pub fn originating_context(&self) -> &UtxoContext {
         &self.inner.originating_context
     }
@@ -7,26 +7,31 @@
 
 use crate::imports::*;
 // use futures::pin_mut;
+use futures::stream::BoxStream;
+use kaspa_consensus_core::config::params::Params;
 use kaspa_notify::{
     listener::ListenerId,
-    scope::{Scope, UtxosChangedScope, VirtualDaaScoreChangedScope},
+    scope::{PruningPointUtxoSetOverrideScope, Scope, TransactionConflictScope, UtxosChangedScope, VirtualDaaScoreChangedScope},
+    subscription::Command,
 };
 use kaspa_rpc_core::{
     api::{
         ctl::{RpcCtl, RpcState},
         ops::RPC_API_VERSION,
     },
-    message::UtxosChangedNotification,
-    GetServerInfoResponse,
+    message::{TransactionConflictNotification, UtxosChangedNotification},
+    GetInfoResponse, GetServerInfoResponse, RpcDustThreshold, RpcHash, RpcVirtualInfo,
 };
+use kaspa_txscript::script_class::ScriptClass;
 use kaspa_wrpc_client::KaspaRpcClient;
 use workflow_core::channel::{Channel, DuplexChannel};
 use workflow_core::task::spawn;
 
 use crate::events::Events;
 use crate::result::Result;
+use crate::subscriptions::BalanceEvent;
 use crate::utxo::{
-    Maturity, OutgoingTransaction, PendingUtxoEntryReference, SyncMonitor, UtxoContext, UtxoEntryId, UtxoEntryReference,
+    Maturity, OutgoingTransaction, PendingUtxoEntryReference, SyncMonitor, UtxoContext, UtxoContextId, UtxoEntryId, UtxoEntryReference,
 };
 use crate::wallet::WalletBusMessage;
 use kaspa_rpc_core::{
@@ -48,6 +53,8 @@ pub struct Inner {
     address_to_utxo_context_map: DashMap<Arc<Address>, UtxoContext>,
     // ---
     current_daa_score: Arc<AtomicU64>,
+    /// Virtual state of the connected node, missing if the node predates the virtual info
+    virtual_info: Mutex<Option<RpcVirtualInfo>>,
     network_id: Arc<Mutex<Option<NetworkId>>>,
     rpc: Mutex<Option<Rpc>>,
     is_connected: AtomicBool,
@@ -57,11 +64,14 @@ pub struct Inner {
     notification_channel: Channel<Notification>,
     sync_proc: SyncMonitor,
     multiplexer: Multiplexer<Box<Events>>,
+    subscriptions: EventSubscriptions,
     wallet_bus: Option<Channel<WalletBusMessage>>,
     notification_guard: AsyncMutex<()>,
     connect_disconnect_guard: AsyncMutex<()>,
     metrics: Arc<Metrics>,
     metrics_kinds: Mutex<Vec<MetricsUpdateKind>>,
+    /// Minimum output values advertised by the connected node
+    dust_thresholds: Mutex<Vec<RpcDustThreshold>>,
 }
 
 impl Inner {
@@ -71,12 +81,14 @@ impl Inner {
         multiplexer: Multiplexer<Box<Events>>,
         wallet_bus: Option<Channel<WalletBusMessage>>,
     ) -> Self {
+        let subscriptions = EventSubscriptions::new();
         Self {
             stasis: DashMap::new(),
             pending: DashMap::new(),
             outgoing: DashMap::new(),
             address_to_utxo_context_map: DashMap::new(),
             current_daa_score: Arc::new(AtomicU64::new(0)),
+            virtual_info: Mutex::new(None),
             network_id: Arc::new(Mutex::new(network_id)),
             rpc: Mutex::new(rpc.clone()),
             is_connected: AtomicBool::new(false),
@@ -84,13 +96,15 @@ impl Inner {
             task_ctl: DuplexChannel::oneshot(),
             task_is_running: AtomicBool::new(false),
             notification_channel: Channel::<Notification>::unbounded(),
-            sync_proc: SyncMonitor::new(rpc.clone(), &multiplexer),
+            sync_proc: SyncMonitor::new(rpc.clone(), &multiplexer, &subscriptions),
             multiplexer,
+            subscriptions,
             wallet_bus,
             notification_guard: Default::default(),
             connect_disconnect_guard: Default::default(),
             metrics: Arc::new(Metrics::default()),
             metrics_kinds: Mutex::new(vec![]),
+            dust_thresholds: Mutex::new(vec![]),
         }
     }
 }
@@ -159,6 +173,21 @@ impl UtxoProcessor {
         &self.inner.multiplexer
     }
 
+    /// Filtered subscriptions to the events broadcast on the [`multiplexer`](Self::multiplexer)
+    pub fn subscriptions(&self) -> &EventSubscriptions {
+        &self.inner.subscriptions
+    }
+
+    /// Subscribes to the event kinds of `filter`, see [`EventSubscriptions::subscribe`]
+    pub fn subscribe(&self, filter: EventKindSet, capacity: Option<usize>, policy: LagPolicy) -> EventSubscription {
+        self.inner.subscriptions.subscribe(filter, capacity, policy)
+    }
+
+    /// Returns a stream of the balance updates of all UtxoContext instances
+    pub fn subscribe_balance_events(&self) -> BoxStream<'static, BalanceEvent> {
+        self.inner.subscriptions.subscribe_balance_events()
+    }
+
     pub async fn notification_lock(&self) -> AsyncMutexGuard<()> {
         self.inner.notification_guard.lock().await
     }
@@ -172,7 +201,12 @@ impl UtxoProcessor {
     }
 
     pub fn set_network_id(&self, network_id: &NetworkId) {
-        self.inner.network_id.lock().unwrap().replace(*network_id);
+        let previous = self.inner.network_id.lock().unwrap().replace(*network_id);
+        // contexts retained across connections monitor addresses
+        // of the previous network and can not be resynced
+        if previous.is_some_and(|previous| previous != *network_id) {
+            self.clear();
+        }
     }
 
     pub fn network_id(&self) -> Result<NetworkId> {
@@ -200,6 +234,12 @@ impl UtxoProcessor {
         self.is_connected().then_some(self.inner.current_daa_score.load(Ordering::SeqCst))
     }
 
+    /// The virtual DAA score, sink, sink blue score, parents count and past median time of the
+    /// connected node, as of its last virtual DAA score change
+    pub fn virtual_info(&self) -> Option<RpcVirtualInfo> {
+        self.is_connected().then(|| self.inner.virtual_info.lock().unwrap().clone()).flatten()
+    }
+
     pub fn address_to_utxo_context_map(&self) -> &DashMap<Arc<Address>, UtxoContext> {
         &self.inner.address_to_utxo_context_map
     }
@@ -216,8 +256,9 @@ impl UtxoProcessor {
         if self.is_connected() {
             if !addresses.is_empty() {
                 let addresses = addresses.into_iter().map(|address| (*address).clone()).collect::<Vec<_>>();
-                let utxos_changed_scope = UtxosChangedScope::new(addresses);
+                let utxos_changed_scope = UtxosChangedScope::new(addresses.clone());
                 self.rpc_api().start_notify(self.listener_id()?, utxos_changed_scope.into()).await?;
+                self.notify_transaction_conflicts(Command::Start, addresses).await?;
             } else {
                 log_error!("registering an empty address list!");
             }
@@ -233,8 +274,9 @@ impl UtxoProcessor {
         if self.is_connected() {
             if !addresses.is_empty() {
                 let addresses = addresses.into_iter().map(|address| (*address).clone()).collect::<Vec<_>>();
-                let utxos_changed_scope = UtxosChangedScope::new(addresses);
+                let utxos_changed_scope = UtxosChangedScope::new(addresses.clone());
                 self.rpc_api().stop_notify(self.listener_id()?, utxos_changed_scope.into()).await?;
+                self.notify_transaction_conflicts(Command::Stop, addresses).await?;
             } else {
                 log_error!("unregistering empty address list!");
             }
@@ -242,7 +284,24 @@ impl UtxoProcessor {
         Ok(())
     }
 
+    /// Starts or stops the transaction conflict notifications of `addresses`.
+    ///
+    /// Nodes predating these notifications reject the subscription, in which
+    /// case double spends are simply not reported.
+    async fn notify_transaction_conflicts(&self, command: Command, addresses: Vec<Address>) -> Result<()> {
+        let scope = TransactionConflictScope::new(addresses).into();
+        let result = match command {
+            Command::Start => self.rpc_api().start_notify(self.listener_id()?, scope).await,
+            Command::Stop => self.rpc_api().stop_notify(self.listener_id()?, scope).await,
+        };
+        if let Err(err) = result {
+            log_warn!("UtxoProcessor: transaction conflict notifications are unavailable: {err}");
+        }
+        Ok(())
+    }
+
     pub async fn notify(&self, event: Events) -> Result<()> {
+        self.inner.subscriptions.dispatch(&event);
         self.multiplexer()
             .try_broadcast(Box::new(event))
             .map_err(|_| Error::Custom("multiplexer channel error during notify".to_string()))?;
@@ -250,6 +309,7 @@ impl UtxoProcessor {
     }
 
     pub fn try_notify(&self, event: Events) -> Result<()> {
+        self.inner.subscriptions.dispatch(&event);
         self.multiplexer()
             .try_broadcast(Box::new(event))
             .map_err(|_| Error::Custom("multiplexer channel error during try_notify".to_string()))?;
@@ -451,6 +511,10 @@ impl UtxoProcessor {
         }
 
         let network_id = self.network_id()?;
+        let GetInfoResponse { params_digest, dust_thresholds, .. } = self.rpc_api().get_info().await?;
+        self.verify_params_digest(&network_id, params_digest).await?;
+        *self.inner.dust_thresholds.lock().unwrap() = dust_thresholds;
+
         if network_id != server_network_id {
             return Err(Error::InvalidNetworkType(network_id.to_string(), server_network_id.to_string()));
         }
@@ -462,6 +526,8 @@ impl UtxoProcessor {
         }
 
         self.inner.current_daa_score.store(virtual_daa_score, Ordering::SeqCst);
+        // Nodes predating GetVirtualInfo fail the call, the virtual info is then left unknown
+        *self.inner.virtual_info.lock().unwrap() = self.rpc_api().get_virtual_info().await.ok();
 
         log_trace!("Connected to kaspad: '{server_version}' on '{server_network_id}';  SYNC: {is_synced}  DAA: {virtual_daa_score}");
         self.notify(Events::ServerStatus { server_version, is_synced, network_id, url: self.rpc_url() }).await?;
@@ -469,10 +535,37 @@ impl UtxoProcessor {
         Ok(is_synced)
     }
 
-    pub async fn handle_connect_impl(&self) -> Result<()> {
+    /// Verifies that the consensus params digest reported by the node matches
+    /// the digest of the configured network. Nodes predating params digests
+    /// are not verified.
+    async fn verify_params_digest(&self, network_id: &NetworkId, params_digest: Option<RpcHash>) -> Result<()> {
+        let Some(received) = params_digest else {
+            return Ok(());
+        };
+
+        let expected = Params::from(*network_id).digest();
+        if expected != received {
+            self.notify(Events::ParamsDigestMismatch { network_id: *network_id, expected, received, url: self.rpc_url() }).await?;
+            return Err(Error::ParamsDigestMismatch(network_id.to_string(), expected.to_string(), received.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the minimum value of the outputs of `script_class` relayed by the
+    /// connected node, or `None` if the node does not advertise it.
+    pub fn dust_threshold(&self, script_class: ScriptClass) -> Option<u64> {
+        let dust_thresholds = self.inner.dust_thresholds.lock().unwrap();
+        dust_thresholds.iter().find(|threshold| threshold.script_class == script_class).map(|threshold| threshold.min_output_value)
+    }
+
+    /// Returns `true` if UtxoContexts retained from a previous
+    /// connection have been resynced.
+    pub async fn handle_connect_impl(&self) -> Result<bool> {
         let is_synced = self.init_state_from_server().await?;
         self.inner.is_connected.store(true, Ordering::SeqCst);
         self.register_notification_listener().await?;
+        let resynced = self.resync_contexts().await?;
         self.notify(Events::UtxoProcStart).await?;
         self.sync_proc().track(is_synced).await?;
 
@@ -484,10 +577,10 @@ impl UtxoProcessor {
             None
         })));
 
-        Ok(())
+        Ok(resynced)
     }
 
-    pub async fn handle_connect(&self) -> Result<()> {
+    pub async fn handle_connect(&self) -> Result<bool> {
         let _ = self.inner.connect_disconnect_guard.lock().await;
 
         match self.handle_connect_impl().await {
@@ -501,7 +594,7 @@ impl UtxoProcessor {
                 }
                 Err(err)
             }
-            Ok(_) => Ok(()),
+            Ok(resynced) => Ok(resynced),
         }
     }
 
@@ -515,19 +608,99 @@ impl UtxoProcessor {
 
         self.unregister_notification_listener().await?;
         self.notify(Events::UtxoProcStop).await?;
-        self.cleanup().await?;
+        // registered UtxoContexts as well as the pending and outgoing
+        // state are retained so that they can be resynced on reconnection
 
         Ok(())
     }
 
     pub async fn cleanup(&self) -> Result<()> {
+        self.clear();
+        Ok(())
+    }
+
+    fn clear(&self) {
         self.inner.pending.clear();
         self.inner.stasis.clear();
         self.inner.outgoing.clear();
         self.inner.address_to_utxo_context_map.clear();
+    }
+
+    /// Re-subscribes the addresses of the UtxoContexts retained across
+    /// connections and refreshes their UTXO sets against the node, emitting
+    /// events for UTXO entries added or removed while disconnected.
+    /// Returns `true` if any UtxoContexts have been resynced.
+    async fn resync_contexts(&self) -> Result<bool> {
+        let contexts = self.registered_contexts();
+        if contexts.is_empty() {
+            return Ok(false);
+        }
+
+        let addresses = contexts.values().flat_map(|(_, addresses)| addresses.iter().cloned()).collect::<Vec<_>>();
+        let utxos_changed_scope = UtxosChangedScope::new(addresses.clone());
+        self.rpc_api().start_notify(self.listener_id()?, utxos_changed_scope.into()).await?;
+        self.notify_transaction_conflicts(Command::Start, addresses).await?;
+
+        self.refresh_contexts(contexts).await?;
+        Ok(true)
+    }
+
+    /// Returns the registered UtxoContexts along with their addresses
+    fn registered_contexts(&self) -> HashMap<UtxoContextId, (UtxoContext, Vec<Address>)> {
+        let mut contexts: HashMap<UtxoContextId, (UtxoContext, Vec<Address>)> = HashMap::new();
+        for entry in self.inner.address_to_utxo_context_map.iter() {
+            let (_, addresses) = contexts.entry(entry.value().id()).or_insert_with(|| (entry.value().clone(), vec![]));
+            addresses.push((**entry.key()).clone());
+        }
+        contexts
+    }
+
+    /// Refreshes the UTXO sets of `contexts` against the node
+    async fn refresh_contexts(&self, contexts: HashMap<UtxoContextId, (UtxoContext, Vec<Address>)>) -> Result<()> {
+        let current_daa_score = self.inner.current_daa_score.load(Ordering::SeqCst);
+        for (utxo_context, addresses) in contexts.into_values() {
+            let utxos = self.rpc_api().get_utxos_by_addresses(addresses).await?;
+            let utxo_entries = utxos.into_iter().map(UtxoEntryReference::from).collect::<Vec<_>>();
+            utxo_context.resync(utxo_entries, current_daa_score).await?;
+        }
         Ok(())
     }
 
+    /// Handles the override of the pruning point UTXO set of the node (e.g. following
+    /// an IBD from a pruning point proof), after which the UTXO sets of the UtxoContexts
+    /// may be stale. Outgoing transactions not yet accepted are dropped, returning their
+    /// UTXO entries to their UtxoContexts, and the UTXO sets of the UtxoContexts are then
+    /// refreshed against the node, emitting events for the UTXO entries added or removed
+    /// by the override between [`Events::ResyncRequired`] and [`Events::ResyncComplete`].
+    pub async fn handle_utxo_set_override(&self) -> Result<()> {
+        log_info!("UtxoProcessor: the node UTXO set has been overridden, resyncing...");
+        self.notify(Events::ResyncRequired).await?;
+
+        self.inner.outgoing.retain(|_, outgoing| {
+            if outgoing.is_accepted() {
+                true
+            } else {
+                outgoing.originating_context().revert_outgoing_transaction(&outgoing.id());
+                false
+            }
+        });
+        self.refresh_contexts(self.registered_contexts()).await?;
+
+        self.notify(Events::ResyncComplete).await?;
+        Ok(())
+    }
+
+    /// Relays a double spend involving the addresses of the UtxoContexts as
+    /// [`Events::TransactionConflict`]. The UTXO sets of the UtxoContexts are
+    /// left to the UtxosChanged notifications of the winning transaction.
+    async fn handle_transaction_conflict(&self, notification: TransactionConflictNotification) -> Result<()> {
+        let TransactionConflictNotification { transaction_id, conflicting_transaction_id, outpoints, is_conflicting_in_block, .. } =
+            notification;
+        log_info!("UtxoProcessor: transaction {transaction_id} conflicts with transaction {conflicting_transaction_id}");
+        self.notify(Events::TransactionConflict { transaction_id, conflicting_transaction_id, outpoints, is_conflicting_in_block })
+            .await
+    }
+
     async fn register_notification_listener(&self) -> Result<()> {
         let listener_id = self.rpc_api().register_new_listener(ChannelConnection::new(
             "utxo processor",
@@ -536,6 +709,7 @@ impl UtxoProcessor {
         ));
         *self.inner.listener_id.lock().unwrap() = Some(listener_id);
         self.rpc_api().start_notify(listener_id, Scope::VirtualDaaScoreChanged(VirtualDaaScoreChangedScope {})).await?;
+        self.rpc_api().start_notify(listener_id, Scope::PruningPointUtxoSetOverride(PruningPointUtxoSetOverrideScope {})).await?;
         Ok(())
     }
 
@@ -553,6 +727,9 @@ impl UtxoProcessor {
 
         match notification {
             Notification::VirtualDaaScoreChanged(virtual_daa_score_changed_notification) => {
+                if let Some(virtual_info) = virtual_daa_score_changed_notification.virtual_info {
+                    *self.inner.virtual_info.lock().unwrap() = Some(virtual_info);
+                }
                 self.handle_daa_score_change(virtual_daa_score_changed_notification.virtual_daa_score).await?;
             }
 
@@ -564,6 +741,14 @@ impl UtxoProcessor {
                 self.handle_utxo_changed(utxos_changed_notification).await?;
             }
 
+            Notification::PruningPointUtxoSetOverride(_) => {
+                self.handle_utxo_set_override().await?;
+            }
+
+            Notification::TransactionConflict(transaction_conflict_notification) => {
+                self.handle_transaction_conflict(transaction_conflict_notification).await?;
+            }
+
             _ => {
                 log_warn!("unknown notification: {:?}", notification);
             }
@@ -632,22 +817,27 @@ impl UtxoProcessor {
                                 match msg {
                                     RpcState::Connected => {
                                         if !this.is_connected() {
-                                            if let Err(err) = this.handle_connect().await {
-                                                log_error!("UtxoProcessor error: {err}");
-                                            } else {
-                                                this.inner.multiplexer.try_broadcast(Box::new(Events::Connect {
-                                                    network_id : this.network_id().expect("network id expected during connection"),
-                                                    url : this.rpc_url()
-                                                })).unwrap_or_else(|err| log_error!("{err}"));
+                                            match this.handle_connect().await {
+                                                Ok(resynced) => {
+                                                    this.try_notify(Events::Connect {
+                                                        network_id : this.network_id().expect("network id expected during connection"),
+                                                        url : this.rpc_url(),
+                                                        resynced,
+                                                    }).unwrap_or_else(|err| log_error!("{err}"));
+                                                }
+                                                Err(err) => {
+                                                    log_error!("UtxoProcessor error: {err}");
+                                                }
                                             }
                                         }
                                     },
                                     RpcState::Disconnected => {
                                         if this.is_connected() {
-                                            this.inner.multiplexer.try_broadcast(Box::new(Events::Disconnect {
+                                            this.try_notify(Events::Disconnect {
                                                 network_id : this.network_id().expect("network id expected during connection"),
-                                                url : this.rpc_url()
-                                            })).unwrap_or_else(|err| log_error!("{err}"));
+                                                url : this.rpc_url(),
+                                                resynced : !this.inner.address_to_utxo_context_map.is_empty(),
+                                            }).unwrap_or_else(|err| log_error!("{err}"));
                                             this.handle_disconnect().await.unwrap_or_else(|err| log_error!("{err}"));
                                         }
                                     }
@@ -719,6 +909,14 @@ pub(crate) mod mock {
             self.inner.is_connected.store(connected, Ordering::SeqCst);
         }
 
+        /// Marks the processor as connected to its RPC with a notification listener registered, without
+        /// the server handshake of [`handle_connect()`](UtxoProcessor::handle_connect)
+        pub async fn mock_connect(&self, current_daa_score: u64) -> Result<()> {
+            self.mock_set_connected(true);
+            self.register_notification_listener().await?;
+            self.handle_daa_score_change(current_daa_score).await
+        }
+
         // pub fn mock_set_daa_score(&self, connected : bool) {
         //     self.inner.is_connected.store(connected, Ordering::SeqCst);
         // }
@@ -32,6 +32,7 @@ pub struct Scan {
     extent: Option<ScanExtent>,
     balance: Arc<AtomicBalance>,
     current_daa_score: u64,
+    start: u32,
 }
 
 impl Scan {
@@ -42,7 +43,14 @@ impl Scan {
         window_size: Option<usize>,
         extent: Option<ScanExtent>,
     ) -> Scan {
-        Scan { provider: Provider::AddressManager(address_manager), window_size, extent, balance: balance.clone(), current_daa_score }
+        Scan {
+            provider: Provider::AddressManager(address_manager),
+            window_size,
+            extent,
+            balance: balance.clone(),
+            current_daa_score,
+            start: 0,
+        }
     }
     pub fn new_with_address_set(addresses: HashSet<Address>, balance: &Arc<AtomicBalance>, current_daa_score: u64) -> Scan {
         Scan {
@@ -51,9 +59,17 @@ impl Scan {
             extent: None,
             balance: balance.clone(),
             current_daa_score,
+            start: 0,
         }
     }
 
+    /// Starts the scan of an address manager at the derivation index `start` instead of the first address,
+    /// the addresses below `start` being known to the caller
+    pub fn with_start(mut self, start: u32) -> Self {
+        self.start = start;
+        self
+    }
+
     pub async fn scan(&self, utxo_context: &UtxoContext) -> Result<()> {
         match &self.provider {
             Provider::AddressManager(address_manager) => self.scan_with_address_manager(address_manager, utxo_context).await,
@@ -67,13 +83,13 @@ impl Scan {
         let window_size = self.window_size.unwrap_or(DEFAULT_WINDOW_SIZE) as u32;
         let extent = self.extent.expect("address manager requires an extent");
 
-        let mut cursor: u32 = 0;
+        let mut cursor: u32 = self.start;
         let mut last_address_index = address_manager.index();
 
         'scan: loop {
             // scan first up to address index, then in window chunks
             let first = cursor;
-            let last = if cursor == 0 { max(last_address_index + 1, window_size) } else { cursor + window_size };
+            let last = if cursor == self.start { max(last_address_index + 1, cursor + window_size) } else { cursor + window_size };
             cursor = last;
 
             // generate address derivations
@@ -84,6 +100,11 @@ impl Scan {
             // addresses used before we start interacting with them.
             utxo_context.register_addresses(&addresses).await?;
 
+            // addresses whose funds were fully spent hold no UTXOs but still count as used for the gap limit
+            if let Some(used_address_index) = Self::last_used_address_index(address_manager, utxo_context, &addresses).await {
+                last_address_index = max(last_address_index, used_address_index);
+            }
+
             let ts = Instant::now();
             let resp = utxo_context.processor().rpc_api().get_utxos_by_addresses(addresses).await?;
             let elapsed_msec = ts.elapsed().as_secs_f32();
@@ -142,6 +163,23 @@ impl Scan {
         Ok(())
     }
 
+    /// Returns the highest derivation index among `addresses` reported as used by the node,
+    /// or `None` if none was used or the node does not support address activity queries
+    /// (e.g. when not running with `--utxoindex`), in which case the scan relies on UTXOs only.
+    async fn last_used_address_index(
+        address_manager: &Arc<AddressManager>,
+        utxo_context: &UtxoContext,
+        addresses: &[Address],
+    ) -> Option<u32> {
+        let entries = utxo_context.processor().rpc_api().get_address_activity(addresses.to_vec()).await.ok()?;
+        let inner = address_manager.inner();
+        entries
+            .iter()
+            .filter(|entry| entry.has_received)
+            .filter_map(|entry| inner.address_to_index_map.get(&entry.address).copied())
+            .max()
+    }
+
     pub async fn scan_with_address_set(&self, address_set: &HashSet<Address>, utxo_context: &UtxoContext) -> Result<()> {
         let params = utxo_context.processor().network_params()?;
         let address_vec = address_set.iter().cloned().collect::<Vec<_>>();
@@ -4,6 +4,7 @@
 //!
 
 use crate::imports::*;
+use kaspa_consensus_core::config::params::{Params, DEVNET_PARAMS, MAINNET_PARAMS, SIMNET_PARAMS, TESTNET11_PARAMS, TESTNET_PARAMS};
 
 #[derive(Debug)]
 pub struct NetworkParams {
@@ -14,45 +15,36 @@ pub struct NetworkParams {
     pub additional_compound_transaction_mass: u64,
 }
 
-pub const MAINNET_NETWORK_PARAMS: NetworkParams = NetworkParams {
-    coinbase_transaction_maturity_period_daa: 100,
-    coinbase_transaction_stasis_period_daa: 50,
-    user_transaction_maturity_period_daa: 10,
-    mass_combination_strategy: MassCombinationStrategy::Add,
-    additional_compound_transaction_mass: 0,
-};
+impl NetworkParams {
+    /// Derives the maturity periods from the coinbase maturity of the consensus `params`
+    /// so that the wallet and the consensus cannot diverge.
+    const fn with_consensus_params(
+        params: &Params,
+        mass_combination_strategy: MassCombinationStrategy,
+        additional_compound_transaction_mass: u64,
+    ) -> Self {
+        Self {
+            coinbase_transaction_maturity_period_daa: params.coinbase_maturity,
+            coinbase_transaction_stasis_period_daa: params.coinbase_maturity / 2,
+            user_transaction_maturity_period_daa: params.coinbase_maturity / 10,
+            mass_combination_strategy,
+            additional_compound_transaction_mass,
+        }
+    }
+}
+
+pub const MAINNET_NETWORK_PARAMS: NetworkParams =
+    NetworkParams::with_consensus_params(&MAINNET_PARAMS, MassCombinationStrategy::Add, 0);
 
-pub const TESTNET10_NETWORK_PARAMS: NetworkParams = NetworkParams {
-    coinbase_transaction_maturity_period_daa: 100,
-    coinbase_transaction_stasis_period_daa: 50,
-    user_transaction_maturity_period_daa: 10,
-    mass_combination_strategy: MassCombinationStrategy::Add,
-    additional_compound_transaction_mass: 100,
-};
+pub const TESTNET10_NETWORK_PARAMS: NetworkParams =
+    NetworkParams::with_consensus_params(&TESTNET_PARAMS, MassCombinationStrategy::Add, 100);
 
-pub const TESTNET11_NETWORK_PARAMS: NetworkParams = NetworkParams {
-    coinbase_transaction_maturity_period_daa: 1_000,
-    coinbase_transaction_stasis_period_daa: 500,
-    user_transaction_maturity_period_daa: 100,
-    mass_combination_strategy: MassCombinationStrategy::Add,
-    additional_compound_transaction_mass: 100,
-};
+pub const TESTNET11_NETWORK_PARAMS: NetworkParams =
+    NetworkParams::with_consensus_params(&TESTNET11_PARAMS, MassCombinationStrategy::Add, 100);
 
-pub const DEVNET_NETWORK_PARAMS: NetworkParams = NetworkParams {
-    coinbase_transaction_maturity_period_daa: 100,
-    coinbase_transaction_stasis_period_daa: 50,
-    user_transaction_maturity_period_daa: 10,
-    mass_combination_strategy: MassCombinationStrategy::Add,
-    additional_compound_transaction_mass: 0,
-};
+pub const DEVNET_NETWORK_PARAMS: NetworkParams = NetworkParams::with_consensus_params(&DEVNET_PARAMS, MassCombinationStrategy::Add, 0);
 
-pub const SIMNET_NETWORK_PARAMS: NetworkParams = NetworkParams {
-    coinbase_transaction_maturity_period_daa: 100,
-    coinbase_transaction_stasis_period_daa: 50,
-    user_transaction_maturity_period_daa: 10,
-    mass_combination_strategy: MassCombinationStrategy::Add,
-    additional_compound_transaction_mass: 0,
-};
+pub const SIMNET_NETWORK_PARAMS: NetworkParams = NetworkParams::with_consensus_params(&SIMNET_PARAMS, MassCombinationStrategy::Add, 0);
 
 impl From<NetworkId> for &'static NetworkParams {
     fn from(value: NetworkId) -> Self {
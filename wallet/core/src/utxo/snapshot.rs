@@ -0,0 +1,285 @@
+//!
+//! UTXO snapshots restoring the UTXO set of accounts without a full address rescan.
+//!
+//! A [`UtxoSnapshot`] holds the UTXO entries tracked by the [`UtxoContext`] of accounts along with the
+//! derivation of their addresses, the address derivation indexes of the accounts and the virtual DAA
+//! score at the time of the export. It is stored encrypted with a secret chosen by the user.
+//!
+//! On import, the UTXO entries of the snapshot are validated against the node with batched
+//! `get_utxos_by_addresses()` requests over the addresses holding them. The entries of an address whose
+//! UTXO set is unchanged are imported as is, while an address whose UTXO set changed since the export is
+//! rescanned. The addresses derived past the derivation indexes of the snapshot are then scanned until an
+//! empty window is found, discovering the addresses used since the export. The restore time is therefore
+//! a function of the changes since the export rather than of the account history.
+//!
+
+use crate::derivation::{AddressDerivationMeta, AddressManager};
+use crate::encryption::{Decrypted, Encrypted};
+use crate::imports::*;
+use crate::utxo::balance::AtomicBalance;
+use kaspa_consensus_core::constants::UNACCEPTED_DAA_SCORE;
+use kaspa_consensus_core::tx::{TransactionOutpoint, UtxoEntry};
+use kaspa_rpc_core::RpcUtxosByAddressesEntry;
+use std::cmp::max;
+
+/// Maximum number of addresses validated by a single `get_utxos_by_addresses()` request
+pub const UTXO_SNAPSHOT_VALIDATION_BATCH_SIZE: usize = 256;
+
+/// Derivation of an address of a derivation capable account
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UtxoSnapshotAddressDerivation {
+    /// `true` if the address belongs to the change branch
+    pub change: bool,
+    pub index: u32,
+}
+
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct UtxoSnapshotEntry {
+    pub address: Address,
+    /// Derivation of `address`, `None` for accounts which are not derivation capable
+    pub derivation: Option<UtxoSnapshotAddressDerivation>,
+    pub outpoint: TransactionOutpoint,
+    pub utxo_entry: UtxoEntry,
+}
+
+impl From<&UtxoSnapshotEntry> for UtxoEntryReference {
+    fn from(entry: &UtxoSnapshotEntry) -> Self {
+        RpcUtxosByAddressesEntry {
+            address: Some(entry.address.clone()),
+            outpoint: entry.outpoint,
+            utxo_entry: entry.utxo_entry.clone(),
+        }
+        .into()
+    }
+}
+
+/// UTXO set of a single account
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct AccountUtxoSnapshot {
+    pub account_id: AccountId,
+    /// Address derivation indexes of derivation capable accounts
+    pub indexes: Option<AddressDerivationMeta>,
+    pub balance: Option<Balance>,
+    pub utxos: Vec<UtxoSnapshotEntry>,
+}
+
+impl AccountUtxoSnapshot {
+    /// Takes a snapshot of the UTXO entries tracked by the UTXO context of an active account. The entries
+    /// created by outgoing transactions which are not accepted yet are unknown to the node and left out.
+    pub fn try_from_account(account: &Arc<dyn Account>) -> Result<Self> {
+        let utxo_context = account.utxo_context();
+        let entries = utxo_context
+            .context()
+            .map
+            .values()
+            .filter(|utxo| utxo.block_daa_score() != UNACCEPTED_DAA_SCORE)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let address_managers = address_managers(account);
+        let utxos = entries
+            .iter()
+            .filter_map(|utxo| {
+                let address = utxo.utxo.address.clone()?;
+                let derivation = address_managers.as_ref().and_then(|managers| {
+                    managers.iter().enumerate().find_map(|(branch, manager)| {
+                        manager
+                            .inner()
+                            .address_to_index_map
+                            .get(&address)
+                            .map(|index| UtxoSnapshotAddressDerivation { change: branch == 1, index: *index })
+                    })
+                });
+                Some(UtxoSnapshotEntry { address, derivation, outpoint: utxo.utxo.outpoint.clone().into(), utxo_entry: utxo.into() })
+            })
+            .collect();
+
+        Ok(Self {
+            account_id: *account.id(),
+            indexes: account.metadata()?.and_then(|metadata| metadata.address_derivation_indexes()),
+            balance: utxo_context.balance(),
+            utxos,
+        })
+    }
+
+    /// Restores the UTXO set of `account` from this snapshot, validating the entries against the node and
+    /// rescanning the addresses that changed since the snapshot was taken. Replaces the UTXO set currently
+    /// tracked by the account, if any, and activates the account without scanning it.
+    pub async fn import(&self, account: &Arc<dyn Account>) -> Result<UtxoSnapshotImportSummary> {
+        if self.account_id != *account.id() {
+            return Err(Error::UtxoSnapshotMismatch(*account.id()));
+        }
+
+        let wallet = account.wallet();
+        let current_daa_score = wallet.current_daa_score().ok_or(Error::NotConnected)?;
+        let utxo_context = account.utxo_context();
+
+        // derive the known addresses of the account, making sure the snapshot was taken from the same account
+        let address_managers = address_managers(account);
+        let indexes = self.indexes.clone().unwrap_or_default();
+        let known_addresses = match address_managers.as_ref() {
+            Some(managers) => {
+                let mut addresses = vec![];
+                for (manager, index) in managers.iter().zip([indexes.receive(), indexes.change()]) {
+                    addresses.extend(manager.get_range(0..max(manager.index(), index) + 1)?);
+                }
+                let is_derived = |entry: &UtxoSnapshotEntry| {
+                    entry.derivation.is_some_and(|derivation| {
+                        let manager = &managers[derivation.change as usize];
+                        manager.inner().address_to_index_map.get(&entry.address) == Some(&derivation.index)
+                    })
+                };
+                if !self.utxos.iter().all(is_derived) {
+                    return Err(Error::UtxoSnapshotMismatch(*account.id()));
+                }
+                for (manager, index) in managers.iter().zip([indexes.receive(), indexes.change()]) {
+                    manager.set_index(max(manager.index(), index))?;
+                }
+                addresses
+            }
+            None => {
+                let addresses = vec![account.receive_address()?, account.change_address()?];
+                if !self.utxos.iter().all(|entry| addresses.contains(&entry.address)) {
+                    return Err(Error::UtxoSnapshotMismatch(*account.id()));
+                }
+                addresses
+            }
+        };
+
+        utxo_context.clear().await?;
+        wallet.active_accounts().insert(account.clone());
+        utxo_context.register_addresses(&known_addresses).await?;
+
+        // validate the snapshot entries of each address against its current UTXO set
+        let snapshot_entries = HashMap::group_from(self.utxos.iter().map(|entry| (entry.address.clone(), entry)));
+        let addresses = snapshot_entries.keys().cloned().collect::<Vec<_>>();
+        let mut imported = vec![];
+        let mut rescanned_addresses = vec![];
+        for batch in addresses.chunks(UTXO_SNAPSHOT_VALIDATION_BATCH_SIZE) {
+            let current = utxo_context.processor().rpc_api().get_utxos_by_addresses(batch.to_vec()).await?;
+            let mut current_outpoints = HashMap::<Address, HashSet<TransactionOutpoint>>::new();
+            for entry in current.into_iter() {
+                if let Some(address) = entry.address {
+                    current_outpoints.entry(address).or_default().insert(entry.outpoint);
+                }
+            }
+
+            for address in batch.iter() {
+                let entries = &snapshot_entries[address];
+                let outpoints = current_outpoints.remove(address).unwrap_or_default();
+                if outpoints.len() == entries.len() && entries.iter().all(|entry| outpoints.contains(&entry.outpoint)) {
+                    imported.extend(entries.iter().map(|entry| UtxoEntryReference::from(*entry)));
+                } else {
+                    rescanned_addresses.push(address.clone());
+                }
+            }
+            yield_executor().await;
+        }
+
+        let imported_utxo_count = imported.len();
+        utxo_context.extend_from_scan(imported, current_daa_score).await?;
+
+        let balance = Arc::new(AtomicBalance::default());
+        if !rescanned_addresses.is_empty() {
+            let scan = Scan::new_with_address_set(rescanned_addresses.iter().cloned().collect(), &balance, current_daa_score);
+            scan.scan(utxo_context).await?;
+        }
+
+        // discover the addresses derived since the snapshot was taken
+        if let Some(managers) = address_managers.as_ref() {
+            let window_sizes = [None, account.change_address_policy().change_scan_window_size(None)];
+            for ((manager, index), window_size) in managers.iter().zip([indexes.receive(), indexes.change()]).zip(window_sizes) {
+                let scan = Scan::new_with_address_manager(
+                    manager.clone(),
+                    &balance,
+                    current_daa_score,
+                    window_size,
+                    Some(ScanExtent::EmptyWindow),
+                )
+                .with_start(index + 1);
+                scan.scan(utxo_context).await?;
+            }
+
+            if let Some(metadata) = account.metadata()? {
+                wallet.store().as_account_store()?.update_metadata(vec![metadata]).await?;
+            }
+        }
+
+        utxo_context.update_balance().await?;
+
+        Ok(UtxoSnapshotImportSummary {
+            account_id: *account.id(),
+            validated_address_count: addresses.len(),
+            imported_utxo_count,
+            rescanned_addresses,
+        })
+    }
+}
+
+/// Returns the receive and change address managers of derivation capable accounts
+fn address_managers(account: &Arc<dyn Account>) -> Option<[Arc<AddressManager>; 2]> {
+    let derivation = account.clone().as_derivation_capable().ok()?.derivation();
+    Some([derivation.receive_address_manager(), derivation.change_address_manager()])
+}
+
+/// UTXO sets of a set of accounts of a wallet
+#[derive(Clone, Debug)]
+pub struct UtxoSnapshot {
+    pub network_id: NetworkId,
+    /// Virtual DAA score at the time of the export
+    pub daa_score: u64,
+    pub accounts: Vec<AccountUtxoSnapshot>,
+}
+
+impl UtxoSnapshot {
+    const STORAGE_MAGIC: u32 = 0x50414e53;
+    const STORAGE_VERSION: u32 = 0;
+
+    /// Serializes the snapshot encrypted with `secret`
+    pub fn try_to_encrypted_vec(&self, secret: &Secret) -> Result<Vec<u8>> {
+        Ok(Decrypted::new(self.clone()).encrypt(secret, EncryptionKind::XChaCha20Poly1305)?.try_to_vec()?)
+    }
+
+    /// Deserializes a snapshot encrypted with `secret`
+    pub fn try_from_encrypted_slice(data: &[u8], secret: &Secret) -> Result<Self> {
+        Ok(Encrypted::try_from_slice(data)?.decrypt::<Self>(secret)?.unwrap())
+    }
+}
+
+impl BorshSerialize for UtxoSnapshot {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        StorageHeader::new(Self::STORAGE_MAGIC, Self::STORAGE_VERSION).serialize(writer)?;
+        BorshSerialize::serialize(&self.network_id, writer)?;
+        BorshSerialize::serialize(&self.daa_score, writer)?;
+        BorshSerialize::serialize(&self.accounts, writer)?;
+
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for UtxoSnapshot {
+    fn deserialize(buf: &mut &[u8]) -> IoResult<Self> {
+        let StorageHeader { version: _, .. } =
+            StorageHeader::deserialize(buf)?.try_magic(Self::STORAGE_MAGIC)?.try_version(Self::STORAGE_VERSION)?;
+
+        let network_id = BorshDeserialize::deserialize(buf)?;
+        let daa_score = BorshDeserialize::deserialize(buf)?;
+        let accounts = BorshDeserialize::deserialize(buf)?;
+
+        Ok(Self { network_id, daa_score, accounts })
+    }
+}
+
+/// Outcome of the import of the UTXO snapshot of an account
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UtxoSnapshotImportSummary {
+    pub account_id: AccountId,
+    /// Number of addresses of the snapshot validated against the node
+    pub validated_address_count: usize,
+    /// Number of UTXO entries imported from the snapshot without rescan
+    pub imported_utxo_count: usize,
+    /// Addresses whose UTXO set changed since the snapshot was taken and which were rescanned
+    pub rescanned_addresses: Vec<Address>,
+}
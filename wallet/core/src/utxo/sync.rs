@@ -12,6 +12,7 @@ struct Inner {
     task_ctl: DuplexChannel,
     rpc: Mutex<Option<Rpc>>,
     multiplexer: Multiplexer<Box<Events>>,
+    subscriptions: EventSubscriptions,
     running: AtomicBool,
     is_synced: AtomicBool,
     state_observer: StateObserver,
@@ -23,11 +24,12 @@ pub struct SyncMonitor {
 }
 
 impl SyncMonitor {
-    pub fn new(rpc: Option<Rpc>, multiplexer: &Multiplexer<Box<Events>>) -> Self {
+    pub fn new(rpc: Option<Rpc>, multiplexer: &Multiplexer<Box<Events>>, subscriptions: &EventSubscriptions) -> Self {
         Self {
             inner: Arc::new(Inner {
                 rpc: Mutex::new(rpc.clone()),
                 multiplexer: multiplexer.clone(),
+                subscriptions: subscriptions.clone(),
                 task_ctl: DuplexChannel::oneshot(),
                 running: AtomicBool::new(false),
                 is_synced: AtomicBool::new(false),
@@ -90,6 +92,7 @@ impl SyncMonitor {
     }
 
     pub async fn notify(&self, event: Events) -> Result<()> {
+        self.inner.subscriptions.dispatch(&event);
         self.multiplexer()
             .try_broadcast(Box::new(event))
             .map_err(|_| Error::Custom("multiplexer channel error during update_balance".to_string()))?;
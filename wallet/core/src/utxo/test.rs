@@ -5,6 +5,9 @@ use crate::tx::generator::test::*;
 use crate::tx::*;
 use crate::utils::*;
 use crate::utxo::*;
+use kaspa_consensus_core::config::params::Params;
+use kaspa_rpc_core::RpcDustThreshold;
+use kaspa_txscript::script_class::ScriptClass;
 
 #[tokio::test]
 async fn test_utxo_subsystem_bootstrap() -> Result<()> {
@@ -20,6 +23,43 @@ async fn test_utxo_subsystem_bootstrap() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_utxo_processor_params_digest_mismatch() -> Result<()> {
+    let network_id = NetworkId::with_suffix(NetworkType::Testnet, 10);
+    let rpc_api_mock = Arc::new(RpcCoreMock::with_network_id(NetworkId::new(NetworkType::Simnet)));
+    let processor = UtxoProcessor::new(Some(rpc_api_mock.clone().into()), Some(network_id), None, None);
+    let channel = processor.multiplexer().channel();
+
+    let err = processor.init_state_from_server().await.expect_err("connecting to a node of a different network must fail");
+    assert!(matches!(err, Error::ParamsDigestMismatch(..)), "expecting params digest mismatch error, received: {err:?}");
+
+    match *channel.receiver.try_recv().expect("expecting a params digest mismatch event") {
+        Events::ParamsDigestMismatch { network_id: event_network_id, expected, received, .. } => {
+            assert_eq!(event_network_id, network_id);
+            assert_eq!(expected, Params::from(network_id).digest());
+            assert_eq!(received, Params::from(NetworkId::new(NetworkType::Simnet)).digest());
+        }
+        event => panic!("expecting params digest mismatch event, received: {event:?}"),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_utxo_processor_dust_thresholds() -> Result<()> {
+    let network_id = NetworkId::with_suffix(NetworkType::Testnet, 10);
+    let dust_thresholds = vec![RpcDustThreshold::new(ScriptClass::PubKey, 10_000)];
+    let rpc_api_mock = Arc::new(RpcCoreMock::with_network_id(network_id).with_dust_thresholds(dust_thresholds));
+    let processor = UtxoProcessor::new(Some(rpc_api_mock.clone().into()), Some(network_id), None, None);
+    assert_eq!(processor.dust_threshold(ScriptClass::PubKey), None);
+
+    processor.init_state_from_server().await?;
+    assert_eq!(processor.dust_threshold(ScriptClass::PubKey), Some(10_000));
+    assert_eq!(processor.dust_threshold(ScriptClass::ScriptHash), None, "classes which are not advertised have no threshold");
+
+    Ok(())
+}
+
 #[test]
 fn test_utxo_generator_empty_utxo_noop() -> Result<()> {
     let network_id = NetworkId::with_suffix(NetworkType::Testnet, 11);
@@ -32,3 +72,264 @@ fn test_utxo_generator_empty_utxo_noop() -> Result<()> {
     // assert!(tx.is_none());
     Ok(())
 }
+
+#[tokio::test]
+async fn test_utxo_context_manual_utxo_selection() -> Result<()> {
+    let network_id = NetworkId::with_suffix(NetworkType::Testnet, 11);
+    let rpc_api_mock = Arc::new(RpcCoreMock::new());
+    let processor = UtxoProcessor::new(Some(rpc_api_mock.clone().into()), Some(network_id), None, None);
+    let utxo_context = UtxoContext::new(&processor, UtxoContextBinding::default());
+
+    let mature = UtxoEntryReference::simulated(kaspa_to_sompi(10.0));
+    let immature = UtxoEntryReference::simulated(kaspa_to_sompi(10.0));
+    utxo_context.insert(mature.clone(), 0, true).await?;
+    // a freshly minted coinbase UTXO is in stasis
+    utxo_context.insert(immature.clone(), 0, false).await?;
+
+    let generator = |selected: &[&UtxoEntryReference]| {
+        let outpoints = selected.iter().map(|entry| entry.utxo.outpoint.clone().into()).collect();
+        let settings = GeneratorSettings::try_new_with_context(
+            utxo_context.clone(),
+            change_address(network_id.into()),
+            1,
+            1,
+            PaymentOutput::new(output_address(network_id.into()), kaspa_to_sompi(2.0)).into(),
+            Fees::SenderPays(0),
+            None,
+            None,
+        )?
+        .with_utxo_selection(UtxoSelection::Manual(outpoints));
+        Generator::try_new(settings, None, None)
+    };
+
+    let pt = generator(&[&mature])?.generate_transaction()?.expect("final transaction");
+    assert_eq!(pt.utxo_entries().len(), 1);
+    assert!(pt.utxo_entries().contains_key(mature.id_as_ref()));
+
+    let err = generator(&[&mature, &immature]).expect_err("immature UTXOs can not be selected");
+    assert!(matches!(err, Error::GeneratorUtxoSelectionImmature(ref id) if *id == immature.id().to_string()), "{err:?}");
+
+    let foreign = UtxoEntryReference::simulated(kaspa_to_sompi(10.0));
+    let err = generator(&[&foreign]).expect_err("UTXOs of other accounts can not be selected");
+    assert!(matches!(err, Error::GeneratorUtxoSelectionNotFound(_)), "{err:?}");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_utxo_context_concurrent_generators() -> Result<()> {
+    let network_id = NetworkId::with_suffix(NetworkType::Testnet, 11);
+    let rpc_api_mock = Arc::new(RpcCoreMock::new());
+    let processor = UtxoProcessor::new(Some(rpc_api_mock.clone().into()), Some(network_id), None, None);
+    let utxo_context = UtxoContext::new(&processor, UtxoContextBinding::default());
+
+    processor.mock_set_connected(true);
+    processor.handle_daa_score_change(1).await?;
+
+    for _ in 0..4 {
+        utxo_context.insert(UtxoEntryReference::simulated(kaspa_to_sompi(10.0)), 0, true).await?;
+    }
+
+    // each payment requires two of the four UTXOs, so naive selections from the same
+    // set of mature UTXOs would both consume the first two entries
+    let generator = || {
+        let settings = GeneratorSettings::try_new_with_context(
+            utxo_context.clone(),
+            change_address(network_id.into()),
+            1,
+            1,
+            PaymentOutput::new(output_address(network_id.into()), kaspa_to_sompi(15.0)).into(),
+            Fees::SenderPays(0),
+            None,
+            None,
+        )?;
+        Generator::try_new(settings, None, None)
+    };
+
+    let first = generator()?.generate_transaction()?.expect("final transaction");
+    let second = generator()?.generate_transaction()?.expect("final transaction");
+
+    let first_inputs = first.utxo_entries().keys().cloned().collect::<AHashSet<_>>();
+    let second_inputs = second.utxo_entries().keys().cloned().collect::<AHashSet<_>>();
+    assert_eq!(first_inputs.len(), 2);
+    assert_eq!(second_inputs.len(), 2);
+    assert!(first_inputs.is_disjoint(&second_inputs), "concurrent generators must select disjoint UTXOs");
+    assert_eq!(utxo_context.calculate_balance().await.reserved, kaspa_to_sompi(40.0));
+
+    let rpc = processor.rpc_api();
+    let (first_id, second_id) = futures::join!(first.try_submit(&rpc), second.try_submit(&rpc));
+    assert_eq!(first_id?, first.id());
+    assert_eq!(second_id?, second.id());
+
+    let balance = utxo_context.calculate_balance().await;
+    assert_eq!(balance.mature_utxo_count, 0);
+    assert_eq!(balance.reserved, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_utxo_scan_rotated_change_addresses() -> Result<()> {
+    use crate::utxo::balance::AtomicBalance;
+    use kaspa_bip32::WordCount;
+    use kaspa_consensus_core::tx::{TransactionOutpoint, UtxoEntry};
+    use kaspa_rpc_core::RpcUtxosByAddressesEntry;
+    use kaspa_txscript::pay_to_address_script;
+
+    let network_id = NetworkId::with_suffix(NetworkType::Testnet, 11);
+    let rpc_api_mock = Arc::new(RpcCoreMock::with_network_id(network_id));
+    let wallet = Arc::new(Wallet::try_with_rpc(Some(rpc_api_mock.clone().into()), Wallet::resident_store()?, Some(network_id))?);
+    let wallet_secret = Secret::new(b"secret".to_vec());
+    let wallet_args = WalletCreateArgs::new(None, None, EncryptionKind::XChaCha20Poly1305, None, false);
+    let (_, _, _, account) =
+        wallet.create_wallet_with_accounts(&wallet_secret, wallet_args, None, None, WordCount::Words12, None).await?;
+
+    let payment = PaymentDestination::from(PaymentOutput::new(output_address(network_id.into()), 1));
+    assert!(account.clone().change_address_provider(&payment).is_none(), "change addresses are reused by default");
+    account.set_change_address_policy(&wallet_secret, ChangeAddressPolicy::RotatePerTransaction).await?;
+
+    // a series of payments, each of them spending the change of the previous one
+    let provider = account.clone().change_address_provider(&payment).expect("rotating change address provider");
+    let change_addresses = (0..20).map(|_| provider()).collect::<Result<Vec<_>>>()?;
+    assert_eq!(change_addresses.iter().collect::<HashSet<_>>().len(), change_addresses.len());
+
+    let change_address_manager = account.clone().as_derivation_capable()?.derivation().change_address_manager();
+    assert_eq!(change_address_manager.index(), 20);
+    assert_eq!(account.metadata()?.unwrap().address_derivation_indexes().unwrap().change(), 20);
+
+    // only the change of the last payment is left unspent
+    let last_change_address = change_addresses.last().unwrap().clone();
+    rpc_api_mock.set_utxos(vec![RpcUtxosByAddressesEntry {
+        address: Some(last_change_address.clone()),
+        outpoint: TransactionOutpoint::new(TransactionId::from_u64_word(1), 0),
+        utxo_entry: UtxoEntry::new(kaspa_to_sompi(10.0), pay_to_address_script(&last_change_address), 0, false),
+    }]);
+
+    // rescan of a wallet restored without its derivation metadata
+    let rescan = |window_size: Option<usize>| {
+        let change_address_manager = change_address_manager.clone();
+        let utxo_context = account.utxo_context().clone();
+        async move {
+            change_address_manager.set_index(0)?;
+            let balance = Arc::new(AtomicBalance::default());
+            let scan = Scan::new_with_address_manager(
+                change_address_manager.clone(),
+                &balance,
+                1,
+                window_size,
+                Some(ScanExtent::EmptyWindow),
+            );
+            scan.scan(&utxo_context).await?;
+            let amount = balance.mature.load(Ordering::SeqCst) + balance.pending.load(Ordering::SeqCst);
+            Result::<_>::Ok((change_address_manager.index(), amount))
+        }
+    };
+
+    // the default window stops the scan of the change branch before the last change address
+    let (index, amount) = rescan(ChangeAddressPolicy::Reuse.change_scan_window_size(None)).await?;
+    assert_eq!((index, amount), (0, 0));
+
+    let (index, amount) = rescan(account.change_address_policy().change_scan_window_size(None)).await?;
+    assert_eq!(index, 20, "the scan must restore the change branch cursor");
+    assert_eq!(amount, kaspa_to_sompi(10.0));
+    assert_eq!(change_address_manager.current_address()?, last_change_address);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_utxo_snapshot_restore() -> Result<()> {
+    use kaspa_bip32::WordCount;
+    use kaspa_consensus_core::tx::{TransactionOutpoint, UtxoEntry};
+    use kaspa_rpc_core::RpcUtxosByAddressesEntry;
+    use kaspa_txscript::pay_to_address_script;
+
+    let network_id = NetworkId::with_suffix(NetworkType::Testnet, 11);
+    let current_daa_score = 100_000;
+    // the node shared by the wallets which the snapshots are exported from and imported into
+    let rpc_api_mock = Arc::new(RpcCoreMock::with_network_id(network_id));
+    let utxo = |address: &Address, id: u64, amount: f64| RpcUtxosByAddressesEntry {
+        address: Some(address.clone()),
+        outpoint: TransactionOutpoint::new(TransactionId::from_u64_word(id), 0),
+        utxo_entry: UtxoEntry::new(kaspa_to_sompi(amount), pay_to_address_script(address), 0, false),
+    };
+    let wallet_secret = Secret::new(b"secret".to_vec());
+    let snapshot_secret = Secret::new(b"snapshot".to_vec());
+
+    let wallet = Arc::new(Wallet::try_with_rpc(Some(rpc_api_mock.clone().into()), Wallet::resident_store()?, Some(network_id))?);
+    let wallet_args = WalletCreateArgs::new(None, None, EncryptionKind::XChaCha20Poly1305, None, false);
+    let (_, _, mnemonic, account) =
+        wallet.create_wallet_with_accounts(&wallet_secret, wallet_args, None, None, WordCount::Words12, None).await?;
+    let derivation = account.clone().as_derivation_capable()?.derivation();
+    let receive = derivation.receive_address_manager().get_range(0..4)?;
+    let change = derivation.change_address_manager().get_range(0..3)?;
+
+    rpc_api_mock.set_utxos(vec![
+        utxo(&receive[0], 1, 10.0),
+        utxo(&receive[1], 2, 5.0),
+        utxo(&receive[2], 3, 3.0),
+        utxo(&change[1], 4, 2.0),
+    ]);
+    wallet.utxo_processor().mock_connect(current_daa_score).await?;
+    account.clone().start().await?;
+    assert_eq!(account.balance().unwrap().mature, kaspa_to_sompi(20.0));
+
+    let snapshot = wallet.utxo_snapshot(None)?;
+    assert_eq!(snapshot.daa_score, current_daa_score);
+    assert_eq!(snapshot.accounts.len(), 1);
+    assert_eq!(snapshot.accounts[0].utxos.len(), 4);
+    let snapshot_data = snapshot.try_to_encrypted_vec(&snapshot_secret)?;
+    assert!(UtxoSnapshot::try_from_encrypted_slice(&snapshot_data, &Secret::new(b"wrong".to_vec())).is_err());
+    let snapshot = UtxoSnapshot::try_from_encrypted_slice(&snapshot_data, &snapshot_secret)?;
+
+    // spends since the export: the funds of the second receive address are sent with the change
+    // going to a new change address and the third receive address receives another payment
+    rpc_api_mock.set_utxos(vec![
+        utxo(&receive[0], 1, 10.0),
+        utxo(&receive[2], 3, 3.0),
+        utxo(&receive[2], 5, 1.0),
+        utxo(&change[1], 4, 2.0),
+        utxo(&change[2], 6, 4.0),
+    ]);
+
+    // the snapshot is imported into another wallet restored from the same mnemonic
+    let restored = Arc::new(Wallet::try_with_rpc(Some(rpc_api_mock.clone().into()), Wallet::resident_store()?, Some(network_id))?);
+    let wallet_args = WalletCreateArgs::new(None, None, EncryptionKind::XChaCha20Poly1305, None, false);
+    restored.create_wallet(&wallet_secret, wallet_args).await?;
+    let restored_account = restored.import_with_mnemonic(&wallet_secret, None, mnemonic, BIP32_ACCOUNT_KIND.into()).await?;
+    assert_eq!(restored_account.id(), account.id());
+    restored.utxo_processor().mock_connect(current_daa_score).await?;
+    rpc_api_mock.take_utxo_queries();
+
+    let summaries = restored.import_utxo_snapshot(&snapshot).await?;
+    assert_eq!(summaries.len(), 1);
+    let summary = &summaries[0];
+    assert_eq!(summary.validated_address_count, 4);
+    assert_eq!(summary.imported_utxo_count, 2);
+    let changed = HashSet::from([receive[1].clone(), receive[2].clone()]);
+    assert_eq!(summary.rescanned_addresses.iter().cloned().collect::<HashSet<_>>(), changed);
+
+    // the addresses of the snapshot are validated at once, then only the changed addresses are
+    // rescanned along with the addresses derived past the snapshot derivation indexes
+    let queries = rpc_api_mock.take_utxo_queries().into_iter().map(HashSet::<Address>::from_iter).collect::<Vec<_>>();
+    assert_eq!(queries[0], HashSet::from([receive[0].clone(), receive[1].clone(), receive[2].clone(), change[1].clone()]));
+    assert_eq!(queries[1], changed);
+    let scanned_past_snapshot = queries[2..].iter().flatten().collect::<HashSet<_>>();
+    assert!(scanned_past_snapshot.contains(&receive[3]) && scanned_past_snapshot.contains(&change[2]));
+    for address in [&receive[0], &receive[1], &receive[2], &change[0], &change[1]] {
+        assert!(!scanned_past_snapshot.contains(address), "address {address} scanned again");
+    }
+
+    // the restored account converges with a full rescan of the original account
+    account.clone().scan(None, None).await?;
+    let outpoints = |account: &Arc<dyn Account>| account.utxo_context().context().map.keys().cloned().collect::<HashSet<_>>();
+    assert_eq!(outpoints(&restored_account), outpoints(&account));
+    let (balance, restored_balance) = (account.balance().unwrap(), restored_account.balance().unwrap());
+    assert_eq!(restored_balance.mature, kaspa_to_sompi(20.0));
+    assert_eq!((restored_balance.mature, restored_balance.pending), (balance.mature, balance.pending));
+    let indexes = |account: &Arc<dyn Account>| account.metadata().unwrap().unwrap().address_derivation_indexes().unwrap();
+    assert_eq!((indexes(&restored_account).receive(), indexes(&restored_account).change()), (2, 2));
+    assert_eq!((indexes(&account).receive(), indexes(&account).change()), (2, 2));
+
+    Ok(())
+}
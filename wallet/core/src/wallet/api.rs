@@ -7,7 +7,9 @@ use crate::imports::*;
 use crate::result::Result;
 use crate::storage::interface::TransactionRangeResult;
 use crate::storage::Binding;
-use crate::tx::Fees;
+use crate::tx::{Fees, UtxoSelection};
+use crate::utxo::UtxoSnapshot;
+use kaspa_bip32::{Language, Mnemonic};
 use workflow_core::channel::Receiver;
 
 #[async_trait]
@@ -192,6 +194,29 @@ impl WalletApi for super::Wallet {
         Ok(WalletImportResponse { wallet_descriptor })
     }
 
+    async fn wallet_export_utxo_snapshot_call(
+        self: Arc<Self>,
+        request: WalletExportUtxoSnapshotRequest,
+    ) -> Result<WalletExportUtxoSnapshotResponse> {
+        let WalletExportUtxoSnapshotRequest { snapshot_secret, account_ids } = request;
+
+        let snapshot_data = self.utxo_snapshot(account_ids.as_deref())?.try_to_encrypted_vec(&snapshot_secret)?;
+
+        Ok(WalletExportUtxoSnapshotResponse { snapshot_data })
+    }
+
+    async fn wallet_import_utxo_snapshot_call(
+        self: Arc<Self>,
+        request: WalletImportUtxoSnapshotRequest,
+    ) -> Result<WalletImportUtxoSnapshotResponse> {
+        let WalletImportUtxoSnapshotRequest { snapshot_secret, snapshot_data } = request;
+
+        let snapshot = UtxoSnapshot::try_from_encrypted_slice(&snapshot_data, &snapshot_secret)?;
+        let accounts = self.import_utxo_snapshot(&snapshot).await?;
+
+        Ok(WalletImportUtxoSnapshotResponse { accounts })
+    }
+
     async fn prv_key_data_enumerate_call(
         self: Arc<Self>,
         _request: PrvKeyDataEnumerateRequest,
@@ -228,6 +253,30 @@ impl WalletApi for super::Wallet {
         Ok(AccountsRenameResponse {})
     }
 
+    async fn accounts_set_change_address_policy_call(
+        self: Arc<Self>,
+        request: AccountsSetChangeAddressPolicyRequest,
+    ) -> Result<AccountsSetChangeAddressPolicyResponse> {
+        let AccountsSetChangeAddressPolicyRequest { account_id, change_address_policy, wallet_secret } = request;
+
+        let account = self.get_account_by_id(&account_id).await?.ok_or(Error::AccountNotFound(account_id))?;
+        account.set_change_address_policy(&wallet_secret, change_address_policy).await?;
+
+        Ok(AccountsSetChangeAddressPolicyResponse {})
+    }
+
+    async fn accounts_set_spending_policy_call(
+        self: Arc<Self>,
+        request: AccountsSetSpendingPolicyRequest,
+    ) -> Result<AccountsSetSpendingPolicyResponse> {
+        let AccountsSetSpendingPolicyRequest { account_id, spending_policy, wallet_secret } = request;
+
+        let account = self.get_account_by_id(&account_id).await?.ok_or(Error::AccountNotFound(account_id))?;
+        account.set_spending_policy(&wallet_secret, spending_policy).await?;
+
+        Ok(AccountsSetSpendingPolicyResponse {})
+    }
+
     async fn accounts_select_call(self: Arc<Self>, request: AccountsSelectRequest) -> Result<AccountsSelectResponse> {
         let AccountsSelectRequest { account_id } = request;
 
@@ -314,9 +363,20 @@ impl WalletApi for super::Wallet {
         Ok(AccountsEnsureDefaultResponse { account_descriptor })
     }
 
-    async fn accounts_import_call(self: Arc<Self>, _request: AccountsImportRequest) -> Result<AccountsImportResponse> {
-        // TODO handle account imports
-        return Err(Error::NotImplemented);
+    async fn accounts_import_call(self: Arc<Self>, request: AccountsImportRequest) -> Result<AccountsImportResponse> {
+        let AccountsImportRequest { wallet_secret, payment_secret, mnemonic_phrase, discovery_args } = request;
+
+        let mnemonic_phrase = std::str::from_utf8(mnemonic_phrase.as_ref()).map_err(|_| Error::InvalidMnemonicPhrase)?;
+        let mnemonic = Mnemonic::new(mnemonic_phrase.trim(), Language::English)?;
+
+        let accounts = if let Some(discovery_args) = discovery_args {
+            self.import_with_mnemonic_and_discovery(&wallet_secret, payment_secret.as_ref(), mnemonic, discovery_args).await?
+        } else {
+            vec![self.import_with_mnemonic(&wallet_secret, payment_secret.as_ref(), mnemonic, BIP32_ACCOUNT_KIND.into()).await?]
+        };
+        let account_descriptors = accounts.iter().map(|account| account.descriptor()).collect::<Result<Vec<_>>>()?;
+
+        Ok(AccountsImportResponse { account_descriptors })
     }
 
     async fn accounts_get_call(self: Arc<Self>, request: AccountsGetRequest) -> Result<AccountsGetResponse> {
@@ -348,8 +408,9 @@ impl WalletApi for super::Wallet {
         let account = self.get_account_by_id(&account_id).await?.ok_or(Error::AccountNotFound(account_id))?;
 
         let abortable = Abortable::new();
-        let (generator_summary, transaction_ids) =
-            account.send(destination, priority_fee_sompi, payload, wallet_secret, payment_secret, &abortable, None).await?;
+        let (generator_summary, transaction_ids) = account
+            .send(destination, priority_fee_sompi, payload, UtxoSelection::Automatic, wallet_secret, payment_secret, &abortable, None)
+            .await?;
 
         Ok(AccountsSendResponse { generator_summary, transaction_ids })
     }
@@ -382,6 +443,18 @@ impl WalletApi for super::Wallet {
         Ok(AccountsTransferResponse { generator_summary, transaction_ids })
     }
 
+    async fn accounts_bump_fee_call(self: Arc<Self>, request: AccountsBumpFeeRequest) -> Result<AccountsBumpFeeResponse> {
+        let AccountsBumpFeeRequest { account_id, transaction_id, wallet_secret, payment_secret, target_feerate } = request;
+
+        let account = self.get_account_by_id(&account_id).await?.ok_or(Error::AccountNotFound(account_id))?;
+
+        let abortable = Abortable::new();
+        let (generator_summary, transaction_ids) =
+            account.bump_fee(transaction_id, target_feerate, wallet_secret, payment_secret, &abortable, None).await?;
+
+        Ok(AccountsBumpFeeResponse { generator_summary, transaction_ids })
+    }
+
     async fn accounts_estimate_call(self: Arc<Self>, request: AccountsEstimateRequest) -> Result<AccountsEstimateResponse> {
         let AccountsEstimateRequest { account_id, destination, priority_fee_sompi, payload } = request;
 
@@ -421,6 +494,21 @@ impl WalletApi for super::Wallet {
         Ok(TransactionsDataGetResponse { transactions, total, account_id, start })
     }
 
+    async fn transactions_call(self: Arc<Self>, request: TransactionsRequest) -> Result<TransactionsResponse> {
+        let TransactionsRequest { account_id, network_id, filter, cursor, limit } = request;
+
+        if limit == 0 || limit > TransactionsRequest::MAX_LIMIT {
+            return Err(Error::InvalidPageLimit(limit, TransactionsRequest::MAX_LIMIT));
+        }
+
+        let binding = Binding::Account(account_id);
+        let store = self.store().as_transaction_record_store()?;
+        let TransactionPage { transactions, next_cursor } =
+            store.transactions_iter(&binding, &network_id, &filter, cursor, limit as usize).await?;
+
+        Ok(TransactionsResponse { account_id, transactions, next_cursor })
+    }
+
     async fn transactions_replace_note_call(
         self: Arc<Self>,
         request: TransactionsReplaceNoteRequest,
@@ -169,3 +169,33 @@ impl AccountCreateArgs {
         AccountCreateArgs::Multisig { prv_key_data_args, additional_xpub_keys, name, minimum_signatures }
     }
 }
+
+/// Default number of addresses scanned on each of the receive and change branches of an account during discovery
+pub const DEFAULT_ACCOUNT_DISCOVERY_ADDRESS_SCAN_EXTENT: u32 = 20;
+/// Default number of consecutive accounts without activity after which the account discovery stops
+pub const DEFAULT_ACCOUNT_DISCOVERY_ACCOUNT_SCAN_EXTENT: u32 = 5;
+
+/// Options of the BIP44 account discovery performed when importing a mnemonic.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountDiscoveryArgs {
+    /// Number of addresses (gap limit) scanned on each of the receive and change branches of an account
+    pub address_scan_extent: u32,
+    /// Number of consecutive accounts without activity after which the discovery stops
+    pub account_scan_extent: u32,
+}
+
+impl AccountDiscoveryArgs {
+    pub fn new(address_scan_extent: u32, account_scan_extent: u32) -> Self {
+        Self { address_scan_extent, account_scan_extent }
+    }
+}
+
+impl Default for AccountDiscoveryArgs {
+    fn default() -> Self {
+        Self {
+            address_scan_extent: DEFAULT_ACCOUNT_DISCOVERY_ADDRESS_SCAN_EXTENT,
+            account_scan_extent: DEFAULT_ACCOUNT_DISCOVERY_ACCOUNT_SCAN_EXTENT,
+        }
+    }
+}
@@ -8,13 +8,16 @@ pub use args::*;
 
 use crate::account::ScanNotifier;
 use crate::compat::gen1::decrypt_mnemonic;
+use crate::endpoints::{Endpoint, EndpointMonitor, EndpointProfiles};
 use crate::error::Error::Custom;
 use crate::factory::try_load_account;
 use crate::imports::*;
+use crate::policy::ApprovalHook;
 use crate::settings::{SettingsStore, WalletSettings};
-use crate::storage::interface::{OpenArgs, StorageDescriptor};
+use crate::storage::interface::{CreateArgs, OpenArgs, StorageDescriptor};
 use crate::storage::local::interface::LocalStore;
 use crate::storage::local::Storage;
+use crate::utxo::{AccountUtxoSnapshot, UtxoSnapshot, UtxoSnapshotImportSummary};
 use crate::wallet::maps::ActiveAccountMap;
 use kaspa_bip32::{ExtendedKey, Language, Mnemonic, Prefix as KeyPrefix, WordCount};
 use kaspa_notify::{
@@ -86,10 +89,12 @@ pub struct Inner {
     store: Arc<dyn Interface>,
     settings: SettingsStore<WalletSettings>,
     utxo_processor: Arc<UtxoProcessor>,
+    endpoint_monitor: Option<EndpointMonitor>,
     multiplexer: Multiplexer<Box<Events>>,
     wallet_bus: Channel<WalletBusMessage>,
     estimation_abortables: Mutex<HashMap<AccountId, Abortable>>,
     retained_contexts: Mutex<HashMap<String, Arc<Vec<u8>>>>,
+    approval_hook: Mutex<Option<Arc<dyn ApprovalHook>>>,
 }
 
 ///
@@ -140,6 +145,12 @@ impl Wallet {
         let wallet_bus = Channel::unbounded();
         let utxo_processor =
             Arc::new(UtxoProcessor::new(rpc.clone(), network_id, Some(multiplexer.clone()), Some(wallet_bus.clone())));
+        // node endpoint profiles are only supported by the wRPC client
+        let wrpc_client = rpc.as_ref().and_then(|rpc| rpc.rpc_api().clone().downcast_arc::<KaspaRpcClient>().ok());
+        let endpoint_monitor = wrpc_client.map(|wrpc_client| {
+            let (interval, timeout) = (EndpointMonitor::DEFAULT_PROBE_INTERVAL, EndpointMonitor::DEFAULT_PROBE_TIMEOUT);
+            EndpointMonitor::new(wrpc_client, &utxo_processor, interval, timeout)
+        });
 
         let wallet = Wallet {
             inner: Arc::new(Inner {
@@ -152,9 +163,11 @@ impl Wallet {
                 selected_account: Mutex::new(None),
                 settings: SettingsStore::new_with_storage(Storage::default_settings_store()),
                 utxo_processor: utxo_processor.clone(),
+                endpoint_monitor,
                 wallet_bus,
                 estimation_abortables: Mutex::new(HashMap::new()),
                 retained_contexts: Mutex::new(HashMap::new()),
+                approval_hook: Mutex::new(None),
             }),
         };
 
@@ -173,6 +186,22 @@ impl Wallet {
         &self.inner.utxo_processor
     }
 
+    /// Sets the [`ApprovalHook`] confirming the payments of the accounts whose
+    /// [`SpendingPolicy`](crate::policy::SpendingPolicy) requires an approval.
+    /// Such payments are denied while no hook is set.
+    pub fn set_approval_hook(&self, approval_hook: Option<Arc<dyn ApprovalHook>>) {
+        *self.inner.approval_hook.lock().unwrap() = approval_hook;
+    }
+
+    pub fn approval_hook(&self) -> Option<Arc<dyn ApprovalHook>> {
+        self.inner.approval_hook.lock().unwrap().clone()
+    }
+
+    /// Monitor of the node endpoint profiles, available with a wRPC client
+    pub fn endpoint_monitor(&self) -> Option<&EndpointMonitor> {
+        self.inner.endpoint_monitor.as_ref()
+    }
+
     pub fn descriptor(&self) -> Option<WalletDescriptor> {
         self.store().descriptor()
     }
@@ -298,7 +327,7 @@ impl Wallet {
 
         let was_open = self.is_open();
 
-        self.store().open(wallet_secret, OpenArgs::new(filename)).await?;
+        self.store().open(wallet_secret, OpenArgs::new(filename).with_network_id(self.network_id().ok())).await?;
         let wallet_name = self.store().descriptor();
 
         if was_open {
@@ -424,6 +453,44 @@ impl Wallet {
         Ok(())
     }
 
+    /// Takes a [`UtxoSnapshot`] of the given active accounts, or of all active accounts if `None`
+    pub fn utxo_snapshot(self: &Arc<Self>, account_ids: Option<&[AccountId]>) -> Result<UtxoSnapshot> {
+        let accounts = if let Some(ids) = account_ids {
+            ids.iter().map(|id| self.active_accounts().get(id).ok_or(Error::AccountNotActive(*id))).collect::<Result<Vec<_>>>()?
+        } else {
+            self.active_accounts().collect()
+        };
+
+        Ok(UtxoSnapshot {
+            network_id: self.network_id()?,
+            daa_score: self.current_daa_score().ok_or(Error::NotConnected)?,
+            accounts: accounts.iter().map(AccountUtxoSnapshot::try_from_account).collect::<Result<Vec<_>>>()?,
+        })
+    }
+
+    /// Activates the accounts of a [`UtxoSnapshot`], restoring their UTXO sets from the snapshot instead of
+    /// scanning their addresses. Accounts of the snapshot unknown to this wallet are skipped.
+    pub async fn import_utxo_snapshot(self: &Arc<Self>, snapshot: &UtxoSnapshot) -> Result<Vec<UtxoSnapshotImportSummary>> {
+        let network_id = self.network_id()?;
+        if snapshot.network_id != network_id {
+            return Err(Error::UtxoSnapshotNetworkMismatch { expected: network_id, found: snapshot.network_id });
+        }
+
+        let mut summaries = vec![];
+        for account_snapshot in snapshot.accounts.iter() {
+            if let Some(account) = self.get_account_by_id(&account_snapshot.account_id).await? {
+                summaries.push(account_snapshot.import(&account).await?);
+            } else {
+                log_warn!("UTXO snapshot: skipping unknown account {}", account_snapshot.account_id);
+            }
+        }
+
+        let ids = summaries.iter().map(|summary| summary.account_id).collect::<Vec<_>>();
+        self.notify(Events::AccountActivation { ids }).await?;
+
+        Ok(summaries)
+    }
+
     pub async fn account_descriptors(self: Arc<Self>) -> Result<Vec<AccountDescriptor>> {
         let iter = self.inner.store.as_account_store().unwrap().iter(None).await.unwrap();
         let wallet = self.clone();
@@ -491,6 +558,11 @@ impl Wallet {
         &self.inner.multiplexer
     }
 
+    /// Filtered subscriptions to the events broadcast on the [`multiplexer`](Self::multiplexer)
+    pub fn subscriptions(&self) -> &EventSubscriptions {
+        self.utxo_processor().subscriptions()
+    }
+
     pub(crate) fn wallet_bus(&self) -> &Channel<WalletBusMessage> {
         &self.inner.wallet_bus
     }
@@ -512,15 +584,79 @@ impl Wallet {
             self.set_network_id(&network_id).unwrap_or_else(|_| log_error!("Unable to select network type: `{}`", network_id));
         }
 
-        if let Some(url) = settings.get::<String>(WalletSettings::Server) {
+        let server = settings.get::<String>(WalletSettings::Server);
+        if let Some(url) = server.as_ref() {
             if let Some(wrpc_client) = self.try_wrpc_client() {
                 wrpc_client.set_url(Some(url.as_str())).unwrap_or_else(|_| log_error!("Unable to set rpc url: `{}`", url));
             }
         }
 
+        self.update_endpoint_monitor();
+
+        // resume the failover of a server selected among the node endpoint profiles
+        if let (Some(endpoint_monitor), Some(url)) = (self.endpoint_monitor(), server) {
+            if let Some(endpoint) = endpoint_monitor.endpoints().into_iter().find(|endpoint| endpoint.url == url) {
+                endpoint_monitor.select(&endpoint.name).await?;
+            }
+        }
+
         Ok(())
     }
 
+    /// Node endpoint profiles of the current network, by ascending priority
+    pub fn endpoints(&self) -> Result<Vec<Endpoint>> {
+        let network_id = self.network_id()?;
+        let mut endpoints = self
+            .settings()
+            .get::<EndpointProfiles>(WalletSettings::Endpoints)
+            .and_then(|mut profiles| profiles.remove(&network_id.to_string()))
+            .unwrap_or_default();
+        endpoints.sort_by_key(|endpoint| endpoint.priority);
+        Ok(endpoints)
+    }
+
+    async fn store_endpoints(&self, endpoints: Vec<Endpoint>) -> Result<()> {
+        let network_id = self.network_id()?;
+        let mut profiles = self.settings().get::<EndpointProfiles>(WalletSettings::Endpoints).unwrap_or_default();
+        if endpoints.is_empty() {
+            profiles.remove(&network_id.to_string());
+        } else {
+            profiles.insert(network_id.to_string(), endpoints);
+        }
+        self.settings().set(WalletSettings::Endpoints, profiles).await?;
+        self.update_endpoint_monitor();
+        Ok(())
+    }
+
+    /// Adds a node endpoint profile to the current network, replacing
+    /// the profile of the same name if any
+    pub async fn add_endpoint(&self, endpoint: Endpoint) -> Result<()> {
+        let mut endpoints = self.endpoints()?;
+        endpoints.retain(|existing| existing.name != endpoint.name);
+        endpoints.push(endpoint);
+        self.store_endpoints(endpoints).await
+    }
+
+    /// Removes the node endpoint profile named `name` from the current
+    /// network, returning `false` if there was no such profile
+    pub async fn remove_endpoint(&self, name: &str) -> Result<bool> {
+        let mut endpoints = self.endpoints()?;
+        let len = endpoints.len();
+        endpoints.retain(|endpoint| endpoint.name != name);
+        if endpoints.len() == len {
+            return Ok(false);
+        }
+        self.store_endpoints(endpoints).await?;
+        Ok(true)
+    }
+
+    fn update_endpoint_monitor(&self) {
+        // no endpoints are monitored until a network is selected
+        if let Some(endpoint_monitor) = self.endpoint_monitor() {
+            endpoint_monitor.set_endpoints(self.endpoints().unwrap_or_default());
+        }
+    }
+
     // intended for starting async management tasks
     pub async fn start(self: &Arc<Self>) -> Result<()> {
         // self.load_settings().await.unwrap_or_else(|_| log_error!("Unable to load settings, discarding..."));
@@ -532,12 +668,18 @@ impl Wallet {
         if let Some(rpc_client) = self.try_wrpc_client() {
             rpc_client.start().await?;
         }
+        if let Some(endpoint_monitor) = self.endpoint_monitor() {
+            endpoint_monitor.start().await?;
+        }
 
         Ok(())
     }
 
     // intended for stopping async management task
     pub async fn stop(&self) -> Result<()> {
+        if let Some(endpoint_monitor) = self.endpoint_monitor() {
+            endpoint_monitor.stop().await?;
+        }
         self.utxo_processor().stop().await?;
         self.stop_task().await?;
         Ok(())
@@ -575,6 +717,12 @@ impl Wallet {
         if let Some(wrpc_client) = self.try_wrpc_client() {
             wrpc_client.set_network_id(network_id)?;
         }
+
+        // the endpoint profiles of the previous network no longer apply
+        if let Some(endpoint_monitor) = self.endpoint_monitor() {
+            endpoint_monitor.release();
+        }
+        self.update_endpoint_monitor();
         Ok(())
     }
 
@@ -778,7 +926,8 @@ impl Wallet {
     ) -> Result<(WalletDescriptor, StorageDescriptor)> {
         self.close().await?;
 
-        let wallet_descriptor = self.inner.store.create(wallet_secret, args.into()).await?;
+        let args = CreateArgs::from(args).with_network_id(self.network_id().ok());
+        let wallet_descriptor = self.inner.store.create(wallet_secret, args).await?;
         let storage_descriptor = self.inner.store.location()?;
         self.inner.store.commit(wallet_secret).await?;
 
@@ -825,7 +974,8 @@ impl Wallet {
         self.close().await?;
 
         let encryption_kind = wallet_args.encryption_kind;
-        let wallet_descriptor = self.inner.store.create(wallet_secret, wallet_args.into()).await?;
+        let wallet_args = CreateArgs::from(wallet_args).with_network_id(self.network_id().ok());
+        let wallet_descriptor = self.inner.store.create(wallet_secret, wallet_args).await?;
         let storage_descriptor = self.inner.store.location()?;
         let mnemonic = Mnemonic::random(mnemonic_phrase_word_count, Default::default())?;
         let account_index = 0;
@@ -863,6 +1013,7 @@ impl Wallet {
     }
 
     pub async fn notify(&self, event: Events) -> Result<()> {
+        self.utxo_processor().subscriptions().dispatch(&event);
         self.multiplexer()
             .try_broadcast(Box::new(event))
             .map_err(|_| Error::Custom("multiplexer channel error during update_balance".to_string()))?;
@@ -1408,6 +1559,7 @@ impl Wallet {
 
         let account_store = self.inner.store.as_account_store()?;
         self.inner.store.batch().await?;
+        prv_key_data_store.store(wallet_secret, prv_key_data).await?;
         account_store.store_single(&account.to_storage()?, None).await?;
         self.inner.store.flush(wallet_secret).await?;
 
@@ -1437,9 +1589,86 @@ impl Wallet {
         Ok(account)
     }
 
+    /// Import a mnemonic as a BIP32 private key while performing a BIP44 account discovery.
+    /// Successive account indexes are derived and the first `address_scan_extent` addresses
+    /// of both the receive and the change branches of each account are checked for UTXOs.
+    /// A wallet account is created for every account index with on-chain activity (account
+    /// #0 is always created) and the discovery stops once `account_scan_extent` consecutive
+    /// accounts without activity have been scanned. An [`Events::AccountDiscovery`] event
+    /// is posted for every scanned account index.
+    pub async fn import_with_mnemonic_and_discovery(
+        self: &Arc<Wallet>,
+        wallet_secret: &Secret,
+        payment_secret: Option<&Secret>,
+        mnemonic: Mnemonic,
+        discovery_args: AccountDiscoveryArgs,
+    ) -> Result<Vec<Arc<dyn Account>>> {
+        if !self.is_connected() {
+            return Err(Error::NotConnected);
+        }
+
+        let AccountDiscoveryArgs { address_scan_extent, account_scan_extent } = discovery_args;
+
+        let prv_key_data = storage::PrvKeyData::try_new_from_mnemonic(mnemonic, payment_secret, self.store().encryption_kind()?)?;
+        let prv_key_data_store = self.store().as_prv_key_data_store()?;
+        if prv_key_data_store.load_key_data(wallet_secret, &prv_key_data.id).await?.is_some() {
+            return Err(Error::PrivateKeyAlreadyExists(prv_key_data.id));
+        }
+
+        let mut accounts: Vec<Arc<dyn Account>> = vec![];
+        let mut account_index = 0;
+        let mut inactive_accounts = 0;
+        loop {
+            let xpub_key = prv_key_data.create_xpub(payment_secret, BIP32_ACCOUNT_KIND.into(), account_index).await?;
+            let xpub_keys = Arc::new(vec![xpub_key]);
+            let account = bip32::Bip32::try_new(self, None, prv_key_data.id, account_index, xpub_keys, false).await?;
+
+            let addresses = account.get_address_range_for_scan(0..address_scan_extent)?;
+            let is_active = self.is_any_address_used(addresses).await?;
+            self.notify(Events::AccountDiscovery { account_index, is_active }).await?;
+
+            if is_active || account_index == 0 {
+                accounts.push(Arc::new(account));
+            }
+
+            inactive_accounts = if is_active { 0 } else { inactive_accounts + 1 };
+            if inactive_accounts >= account_scan_extent {
+                break;
+            }
+            account_index += 1;
+        }
+
+        let prv_key_data_info = PrvKeyDataInfo::from(&prv_key_data);
+        let account_store = self.inner.store.as_account_store()?;
+        self.inner.store.batch().await?;
+        prv_key_data_store.store(wallet_secret, prv_key_data).await?;
+        for account in accounts.iter() {
+            account_store.store_single(&account.to_storage()?, None).await?;
+        }
+        self.inner.store.flush(wallet_secret).await?;
+
+        self.notify(Events::PrvKeyDataCreate { prv_key_data_info }).await?;
+        for account in accounts.iter() {
+            self.notify(Events::AccountCreate { account_descriptor: account.descriptor()? }).await?;
+            account.clone().start().await?;
+        }
+
+        Ok(accounts)
+    }
+
+    /// Returns `true` if any of `addresses` was ever used. The address activity RPC
+    /// detects the addresses whose funds were fully spent, the UTXO lookup covering
+    /// nodes which do not support it (e.g. when not running with `--utxoindex`).
+    async fn is_any_address_used(&self, addresses: Vec<Address>) -> Result<bool> {
+        match self.rpc_api().get_address_activity(addresses.clone()).await {
+            Ok(entries) if entries.iter().any(|entry| entry.has_received) => Ok(true),
+            _ => Ok(self.rpc_api().get_utxos_by_addresses(addresses).await?.is_not_empty()),
+        }
+    }
+
     /// Perform a "2d" scan of account derivations while scanning addresses
     /// in each account (UTXOs up to `address_scan_extent` address derivation).
-    /// Report back the last account index that has UTXOs. The scan is performed
+    /// Report back the last account index that was used. The scan is performed
     /// until we have encountered at least `account_scan_extent` of empty
     /// accounts.
     pub async fn scan_bip44_accounts(
@@ -1469,7 +1698,7 @@ impl Wallet {
             let addresses = bip32::Bip32::try_new(self, None, prv_key_data.id, account_index as u64, xpub_keys, ecdsa)
                 .await?
                 .get_address_range_for_scan(0..address_scan_extent)?;
-            if self.rpc_api().get_utxos_by_addresses(addresses).await?.is_not_empty() {
+            if self.is_any_address_used(addresses).await? {
                 last_account_index = account_index;
             }
             account_index += 1;
@@ -1654,8 +1883,7 @@ mod test {
 
         let wrpc_client = wallet.wrpc_client().expect("Unable to obtain wRPC client");
 
-        let info = rpc_api.get_block_dag_info().await?;
-        let current_daa_score = info.virtual_daa_score;
+        let current_daa_score = rpc_api.get_virtual_info().await?.daa_score;
 
         let _connect_result = wrpc_client.connect(ConnectOptions::fallback()).await;
         //println!("connect_result: {_connect_result:?}");
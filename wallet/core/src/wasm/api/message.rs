@@ -4,6 +4,7 @@ use super::extensions::*;
 use crate::account::descriptor::IAccountDescriptor;
 use crate::api::message::*;
 use crate::imports::*;
+use crate::policy::SpendingPolicy;
 use crate::tx::{Fees, PaymentDestination, PaymentOutputs};
 use crate::wasm::tx::fees::IFees;
 use crate::wasm::tx::GeneratorSummary;
@@ -679,6 +680,101 @@ try_from! ( _args: WalletImportResponse, IWalletImportResponse, {
 
 // ---
 
+declare! {
+    IWalletExportUtxoSnapshotRequest,
+    r#"
+    /**
+     * 
+     * Export the UTXO sets of the active accounts, or of the given active accounts,
+     * encrypted with `snapshotSecret`.
+     * 
+     * @category Wallet API
+     */
+    export interface IWalletExportUtxoSnapshotRequest {
+        snapshotSecret: string;
+        accountIds?: HexString[];
+    }
+    "#,
+}
+
+try_from! ( args: IWalletExportUtxoSnapshotRequest, WalletExportUtxoSnapshotRequest, {
+    let snapshot_secret = args.get_secret("snapshotSecret")?;
+    let account_ids = args.try_get_value("accountIds")?.map(from_value::<Vec<AccountId>>).transpose()?;
+    Ok(WalletExportUtxoSnapshotRequest { snapshot_secret, account_ids })
+});
+
+declare! {
+    IWalletExportUtxoSnapshotResponse,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface IWalletExportUtxoSnapshotResponse {
+        snapshotData: HexString;
+    }
+    "#,
+}
+
+try_from! ( args: WalletExportUtxoSnapshotResponse, IWalletExportUtxoSnapshotResponse, {
+    let response = IWalletExportUtxoSnapshotResponse::default();
+    response.set("snapshotData", &JsValue::from_str(&args.snapshot_data.to_hex()))?;
+    Ok(response)
+});
+
+// ---
+
+declare! {
+    IWalletImportUtxoSnapshotRequest,
+    r#"
+    /**
+     * 
+     * Activate the accounts of a UTXO snapshot, restoring their UTXO sets
+     * without a full address rescan.
+     * 
+     * @category Wallet API
+     */
+    export interface IWalletImportUtxoSnapshotRequest {
+        snapshotSecret: string;
+        snapshotData: HexString | Uint8Array;
+    }
+    "#,
+}
+
+try_from! ( args: IWalletImportUtxoSnapshotRequest, WalletImportUtxoSnapshotRequest, {
+    let snapshot_secret = args.get_secret("snapshotSecret")?;
+    let snapshot_data = args.get_vec_u8("snapshotData").map_err(|err|Error::custom(format!("snapshotData: {err}")))?;
+    Ok(WalletImportUtxoSnapshotRequest { snapshot_secret, snapshot_data })
+});
+
+declare! {
+    IWalletImportUtxoSnapshotResponse,
+    r#"
+    /**
+     * 
+     * Response listing the imported accounts along with the addresses
+     * rescanned because their UTXO set changed since the export.
+     * 
+     * @category Wallet API
+     */
+    export interface IWalletImportUtxoSnapshotResponse {
+        accounts: {
+            accountId: HexString;
+            validatedAddressCount: number;
+            importedUtxoCount: number;
+            rescannedAddresses: string[];
+        }[];
+    }
+    "#,
+}
+
+try_from! ( args: WalletImportUtxoSnapshotResponse, IWalletImportUtxoSnapshotResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
 declare! {
     IPrvKeyDataEnumerateRequest,
     r#"
@@ -945,6 +1041,109 @@ try_from! ( _args: AccountsRenameResponse, IAccountsRenameResponse, {
 
 // ---
 
+declare! {
+    IAccountsSetChangeAddressPolicyRequest,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface IAccountsSetChangeAddressPolicyRequest {
+        accountId: string;
+        /**
+         * Change addresses of the transactions sent by the account:
+         * `reuse` (default) sends the change to the current change address,
+         * `rotate-per-transaction` derives a fresh change address for every transaction,
+         * `match-destination-branch` rotates the change addresses of payments
+         * and sends each sweep to a single fresh change address.
+         */
+        changeAddressPolicy: "reuse" | "rotate-per-transaction" | "match-destination-branch";
+        walletSecret: string;
+    }
+    "#,
+}
+
+try_from! ( args: IAccountsSetChangeAddressPolicyRequest, AccountsSetChangeAddressPolicyRequest, {
+    let account_id = args.get_account_id("accountId")?;
+    let change_address_policy = args.get_string("changeAddressPolicy")?.parse::<ChangeAddressPolicy>()?;
+    let wallet_secret = args.get_secret("walletSecret")?;
+    Ok(AccountsSetChangeAddressPolicyRequest { account_id, change_address_policy, wallet_secret })
+});
+
+declare! {
+    IAccountsSetChangeAddressPolicyResponse,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface IAccountsSetChangeAddressPolicyResponse { }
+    "#,
+}
+
+try_from! ( _args: AccountsSetChangeAddressPolicyResponse, IAccountsSetChangeAddressPolicyResponse, {
+    Ok(IAccountsSetChangeAddressPolicyResponse::default())
+});
+
+// ---
+
+declare! {
+    IAccountsSetSpendingPolicyRequest,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface IAccountsSetSpendingPolicyRequest {
+        accountId: string;
+        /**
+         * Limits of the payments of the account, each of them being optional.
+         * Payments violating the policy are denied by `accountsSend()` and
+         * `accountsTransfer()`, payments of at least `approvalThresholdSompi`
+         * requiring the approval of the approval hook of the wallet.
+         */
+        spendingPolicy: {
+            /** Maximum amount of a single payment, fees excluded */
+            maxPaymentSompi?: bigint;
+            /** Maximum amount paid over the last 24 hours, fees excluded */
+            dailyLimitSompi?: bigint;
+            /** Destinations the account can pay to, any destination if not set */
+            allowedDestinations?: string[];
+            approvalThresholdSompi?: bigint;
+        };
+        walletSecret: string;
+    }
+    "#,
+}
+
+try_from! ( args: IAccountsSetSpendingPolicyRequest, AccountsSetSpendingPolicyRequest, {
+    let account_id = args.get_account_id("accountId")?;
+    let spending_policy = from_value::<SpendingPolicy>(args.get_value("spendingPolicy")?)?;
+    let wallet_secret = args.get_secret("walletSecret")?;
+    Ok(AccountsSetSpendingPolicyRequest { account_id, spending_policy, wallet_secret })
+});
+
+declare! {
+    IAccountsSetSpendingPolicyResponse,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface IAccountsSetSpendingPolicyResponse { }
+    "#,
+}
+
+try_from! ( _args: AccountsSetSpendingPolicyResponse, IAccountsSetSpendingPolicyResponse, {
+    Ok(IAccountsSetSpendingPolicyResponse::default())
+});
+
+// ---
+
 // TODO
 declare! {
     IAccountsDiscoveryRequest,
@@ -1142,20 +1341,55 @@ declare! {
     IAccountsImportRequest,
     r#"
     /**
+     * Account discovery options used by {@link IAccountsImportRequest}.
+     * `addressScanExtent` (default: 20) is the number of addresses scanned
+     * on each of the receive and change branches of an account and
+     * `accountScanExtent` (default: 5) is the number of consecutive accounts
+     * without activity after which the discovery stops.
+     * 
+     * @category Wallet API
+     */
+    export interface IAccountDiscoveryArgs {
+        addressScanExtent? : number;
+        accountScanExtent? : number;
+    }
+
+    /**
+     * Import a BIP32 private key from a mnemonic. If `discovery` is supplied,
+     * an account is created for every BIP44 account index with on-chain activity
+     * (emitting `account-discovery` events while scanning), otherwise only
+     * the account #0 is created.
      * 
-     *  
      * @category Wallet API
      */
     export interface IAccountsImportRequest {
         walletSecret: string;
-        // TODO
+        paymentSecret?: string;
+        mnemonic: string;
+        discovery?: IAccountDiscoveryArgs;
     }
     "#,
 }
 
-try_from! ( _args: IAccountsImportRequest, AccountsImportRequest, {
-    unimplemented!();
-    // Ok(AccountsImportRequest { })
+try_from! ( args: IAccountsImportRequest, AccountsImportRequest, {
+    let wallet_secret = args.get_secret("walletSecret")?;
+    let payment_secret = args.try_get_secret("paymentSecret")?;
+    let mnemonic_phrase = args.get_secret("mnemonic")?;
+    let discovery_args = if let Some(discovery) = args.try_get_value("discovery")? {
+        let discovery = Object::from(discovery);
+        let mut discovery_args = AccountDiscoveryArgs::default();
+        if discovery.try_get_value("addressScanExtent")?.is_some() {
+            discovery_args.address_scan_extent = discovery.get_u32("addressScanExtent")?;
+        }
+        if discovery.try_get_value("accountScanExtent")?.is_some() {
+            discovery_args.account_scan_extent = discovery.get_u32("accountScanExtent")?;
+        }
+        Some(discovery_args)
+    } else {
+        None
+    };
+
+    Ok(AccountsImportRequest { wallet_secret, payment_secret, mnemonic_phrase, discovery_args })
 });
 
 declare! {
@@ -1167,15 +1401,16 @@ declare! {
      * @category Wallet API
      */
     export interface IAccountsImportResponse {
-        // TODO
+        accountDescriptors : IAccountDescriptor[];
     }
     "#,
 }
 
-try_from! ( _args: AccountsImportResponse, IAccountsImportResponse, {
-    unimplemented!();
-    // let response = IAccountsImportResponse::default();
-    // Ok(response)
+try_from! ( args: AccountsImportResponse, IAccountsImportResponse, {
+    let response = IAccountsImportResponse::default();
+    let account_descriptors = args.account_descriptors.into_iter().map(IAccountDescriptor::try_from).collect::<Result<Vec<IAccountDescriptor>>>()?;
+    response.set("accountDescriptors", &Array::from_iter(account_descriptors.into_iter()))?;
+    Ok(response)
 });
 
 // ---
@@ -1483,6 +1718,59 @@ try_from! ( args: AccountsTransferResponse, IAccountsTransferResponse, {
 
 // ---
 
+declare! {
+    IAccountsBumpFeeRequest,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface IAccountsBumpFeeRequest {
+        accountId : HexString;
+        transactionId : HexString;
+        walletSecret : string;
+        paymentSecret? : string;
+        /** Feerate in SOMPI per gram to be reached by the package of the transaction and its child */
+        targetFeerate : number;
+    }
+    "#,
+}
+
+try_from! ( args: IAccountsBumpFeeRequest, AccountsBumpFeeRequest, {
+    let account_id = args.get_account_id("accountId")?;
+    let transaction_id = args.get_transaction_id("transactionId")?;
+    let wallet_secret = args.get_secret("walletSecret")?;
+    let payment_secret = args.try_get_secret("paymentSecret")?;
+    let target_feerate = args.get_value("targetFeerate")?.as_f64().ok_or(Error::custom("targetFeerate must be a number"))?;
+
+    Ok(AccountsBumpFeeRequest { account_id, transaction_id, wallet_secret, payment_secret, target_feerate })
+});
+
+declare! {
+    IAccountsBumpFeeResponse,
+    r#"
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface IAccountsBumpFeeResponse {
+        generatorSummary : GeneratorSummary;
+        transactionIds : HexString[];
+    }
+    "#,
+}
+
+try_from! ( args: AccountsBumpFeeResponse, IAccountsBumpFeeResponse, {
+    let response = IAccountsBumpFeeResponse::default();
+    response.set("generatorSummary", &GeneratorSummary::from(args.generator_summary).into())?;
+    response.set("transactionIds", &to_value(&args.transaction_ids)?)?;
+    Ok(response)
+});
+
+// ---
+
 declare! {
     IAccountsEstimateRequest,
     r#"
@@ -1594,6 +1882,88 @@ try_from! ( args: TransactionsDataGetResponse, ITransactionsDataGetResponse, {
 
 // ---
 
+declare! {
+    ITransactionsRequest,
+    r#"
+    /**
+     * Transaction history filter. A record is included if it satisfies
+     * all of the specified conditions.
+     * 
+     * @category Wallet API
+     */
+    export interface ITransactionsFilter {
+        /** Record kinds to include, sweep transactions are `batch` records */
+        kinds? : TransactionKind[];
+        /** Include only (`true`) or exclude (`false`) coinbase records */
+        coinbase? : boolean;
+        /** Inclusive lower bound of the record unix time in milliseconds */
+        fromUnixtimeMsec? : bigint | number;
+        /** Exclusive upper bound of the record unix time in milliseconds */
+        toUnixtimeMsec? : bigint | number;
+        /** Include only records involving this address */
+        address? : Address | string;
+    }
+
+    /**
+     * Position of a record in the transaction history.
+     * 
+     * @category Wallet API
+     */
+    export interface ITransactionsCursor {
+        unixtimeMsec : bigint;
+        id : HexString;
+    }
+
+    /**
+     * 
+     *  
+     * @category Wallet API
+     */
+    export interface ITransactionsRequest {
+        accountId : HexString;
+        networkId : NetworkId | string;
+        filter? : ITransactionsFilter;
+        /** The `nextCursor` of the previous page, omitted for the first page */
+        cursor? : ITransactionsCursor;
+        /** Maximum number of records of the page (at most 1000) */
+        limit : number;
+    }
+    "#,
+}
+
+try_from! ( args: ITransactionsRequest, TransactionsRequest, {
+    let account_id = args.get_account_id("accountId")?;
+    let network_id = args.get_network_id("networkId")?;
+    let filter = args.try_get_value("filter")?.map(from_value::<TransactionRecordFilter>).transpose()?.unwrap_or_default();
+    let cursor = args.try_get_value("cursor")?.map(from_value::<TransactionCursor>).transpose()?;
+    let limit = args.get_u64("limit")?;
+
+    Ok(TransactionsRequest { account_id, network_id, filter, cursor, limit })
+});
+
+declare! {
+    ITransactionsResponse,
+    r#"
+    /**
+     * 
+     * 
+     * @category Wallet API
+     */
+    export interface ITransactionsResponse {
+        accountId : HexString;
+        transactions : ITransactionRecord[];
+        /** Cursor of the next page, absent once there are no further records */
+        nextCursor? : ITransactionsCursor;
+    }
+    "#,
+}
+
+try_from! ( args: TransactionsResponse, ITransactionsResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
 declare! {
     ITransactionsReplaceNoteRequest,
     r#"
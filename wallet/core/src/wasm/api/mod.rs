@@ -30,12 +30,16 @@ declare_wasm_handlers!([
     WalletChangeSecret,
     WalletExport,
     WalletImport,
+    WalletExportUtxoSnapshot,
+    WalletImportUtxoSnapshot,
     PrvKeyDataEnumerate,
     PrvKeyDataCreate,
     PrvKeyDataRemove,
     PrvKeyDataGet,
     AccountsEnumerate,
     AccountsRename,
+    AccountsSetChangeAddressPolicy,
+    AccountsSetSpendingPolicy,
     AccountsDiscovery,
     AccountsCreate,
     AccountsEnsureDefault,
@@ -48,7 +52,9 @@ declare_wasm_handlers!([
     AccountsSend,
     AccountsTransfer,
     AccountsEstimate,
+    AccountsBumpFee,
     TransactionsDataGet,
+    Transactions,
     TransactionsReplaceNote,
     TransactionsReplaceMetadata,
     AddressBookEnumerate,
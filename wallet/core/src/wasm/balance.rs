@@ -35,6 +35,12 @@ impl Balance {
         self.inner.outgoing.into()
     }
 
+    /// Portion of the mature funds selected by transactions that are being generated or submitted.
+    #[wasm_bindgen(getter)]
+    pub fn reserved(&self) -> BigInt {
+        self.inner.reserved.into()
+    }
+
     #[wasm_bindgen(js_name = "toBalanceStrings")]
     pub fn to_balance_strings(&self, network_type: &NetworkTypeT) -> Result<BalanceStrings> {
         let network_type = NetworkType::try_from(network_type)?;
@@ -1,5 +1,6 @@
 use crate::imports::*;
 use crate::message::*;
+use kaspa_consensus_core::message::{self as signed_message, MessageSignature};
 use kaspa_wallet_keys::privatekey::PrivateKey;
 use kaspa_wallet_keys::publickey::PublicKey;
 use kaspa_wasm_core::types::HexString;
@@ -79,3 +80,96 @@ pub fn js_verify_message(value: IVerifyMessage) -> Result<bool, Error> {
         Err(Error::custom("Failed to parse input"))
     }
 }
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_MESSAGE_TYPES: &'static str = r#"
+/**
+ * Interface declaration for {@link signAddressMessage} function arguments.
+ * 
+ * @category Message Signing
+ */
+export interface ISignAddressMessage {
+    message: string;
+    privateKey: PrivateKey | string;
+    address: Address | string;
+}
+
+/**
+ * Interface declaration for {@link verifyAddressMessage} function arguments.
+ * 
+ * @category Message Signing
+ */
+export interface IVerifyAddressMessage {
+    message: string;
+    signature: HexString;
+    address: Address | string;
+}
+
+/**
+ * Interface declaration for {@link recoverMessageAddress} function arguments.
+ * 
+ * @category Message Signing
+ */
+export interface IRecoverMessageAddress {
+    message: string;
+    signature: HexString;
+    networkId: NetworkId | string;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends = js_sys::Object, typescript_type = "ISignAddressMessage")]
+    pub type ISignAddressMessage;
+    #[wasm_bindgen(extends = js_sys::Object, typescript_type = "IVerifyAddressMessage")]
+    pub type IVerifyAddressMessage;
+    #[wasm_bindgen(extends = js_sys::Object, typescript_type = "IRecoverMessageAddress")]
+    pub type IRecoverMessageAddress;
+}
+
+/// Signs a message with the private key of the given address, producing an encoded signature
+/// that embeds the signature scheme of the address (schnorr or ECDSA)
+/// @category Message Signing
+#[wasm_bindgen(js_name = signAddressMessage)]
+pub fn js_sign_address_message(value: ISignAddressMessage) -> Result<HexString, Error> {
+    if let Some(object) = Object::try_from(&value) {
+        let private_key = object.get_cast::<PrivateKey>("privateKey")?;
+        let address = object.get_cast::<Address>("address")?;
+        let raw_msg = object.get_string("message")?;
+        let mut privkey_bytes = private_key.as_ref().secret_bytes();
+        let signature = signed_message::sign_message(raw_msg.as_bytes(), &privkey_bytes, address.version);
+        privkey_bytes.zeroize();
+        Ok(signature?.to_string().into())
+    } else {
+        Err(Error::custom("Failed to parse input"))
+    }
+}
+
+/// Verifies an encoded signature of the given message against an address
+/// @category Message Signing
+#[wasm_bindgen(js_name = verifyAddressMessage, skip_jsdoc)]
+pub fn js_verify_address_message(value: IVerifyAddressMessage) -> Result<bool, Error> {
+    if let Some(object) = Object::try_from(&value) {
+        let address = object.get_cast::<Address>("address")?;
+        let raw_msg = object.get_string("message")?;
+        let signature = MessageSignature::from_str(&object.get_string("signature")?)?;
+        Ok(signed_message::verify_message(&address, raw_msg.as_bytes(), &signature).is_ok())
+    } else {
+        Err(Error::custom("Failed to parse input"))
+    }
+}
+
+/// Recovers the address that produced an encoded ECDSA signature of the given message.
+/// Schnorr signatures do not allow recovering the address and produce an error.
+/// @category Message Signing
+#[wasm_bindgen(js_name = recoverMessageAddress)]
+pub fn js_recover_message_address(value: IRecoverMessageAddress) -> Result<Address, Error> {
+    if let Some(object) = Object::try_from(&value) {
+        let network_id = object.get_cast::<NetworkId>("networkId")?.into_owned();
+        let raw_msg = object.get_string("message")?;
+        let signature = MessageSignature::from_str(&object.get_string("signature")?)?;
+        Ok(signed_message::recover_address(network_id.into(), raw_msg.as_bytes(), &signature)?)
+    } else {
+        Err(Error::custom("Failed to parse input"))
+    }
+}
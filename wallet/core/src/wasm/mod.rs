@@ -11,6 +11,7 @@ cfg_if! {
         pub mod notify;
         pub mod signer;
         pub mod tx;
+        pub mod uri;
         pub mod utils;
         pub mod utxo;
         pub mod encryption;
@@ -21,6 +22,7 @@ cfg_if! {
         pub use self::notify::*;
         pub use self::signer::*;
         pub use self::tx::*;
+        pub use self::uri::*;
         pub use self::utils::*;
         pub use self::utxo::*;
         pub use self::encryption::*;
@@ -17,15 +17,19 @@ cfg_if! {
             Connect = "connect",
             Disconnect = "disconnect",
             UtxoIndexNotEnabled = "utxo-index-not-enabled",
+            ParamsDigestMismatch = "params-digest-mismatch",
             SyncState = "sync-state",
             UtxoProcStart = "utxo-proc-start",
             UtxoProcStop = "utxo-proc-stop",
             UtxoProcError = "utxo-proc-error",
+            ResyncRequired = "resync-required",
+            ResyncComplete = "resync-complete",
             DaaScoreChange = "daa-score-change",
             Pending = "pending",
             Reorg = "reorg",
             Stasis = "stasis",
             Maturity = "maturity",
+            TransactionConflict = "transaction-conflict",
             Discovery = "discovery",
             Balance = "balance",
             Error = "error",
@@ -38,6 +42,7 @@ cfg_if! {
         export type UtxoProcessorEventData = IConnectEvent
             | IDisconnectEvent
             | IUtxoIndexNotEnabledEvent
+            | IParamsDigestMismatchEvent
             | ISyncStateEvent
             | IServerStatusEvent
             | IUtxoProcErrorEvent
@@ -46,6 +51,7 @@ cfg_if! {
             | IReorgEvent
             | IStasisEvent
             | IMaturityEvent
+            | ITransactionConflictEvent
             | IDiscoveryEvent
             | IBalanceEvent
             | IErrorEvent
@@ -61,16 +67,20 @@ cfg_if! {
             "connect":IConnectEvent,
             "disconnect": IDisconnectEvent,
             "utxo-index-not-enabled": IUtxoIndexNotEnabledEvent,
+            "params-digest-mismatch": IParamsDigestMismatchEvent,
             "sync-state": ISyncStateEvent,
             "server-status": IServerStatusEvent,
             "utxo-proc-start": undefined,
             "utxo-proc-stop": undefined,
             "utxo-proc-error": IUtxoProcErrorEvent,
+            "resync-required": undefined,
+            "resync-complete": undefined,
             "daa-score-change": IDaaScoreChangeEvent,
             "pending": IPendingEvent,
             "reorg": IReorgEvent,
             "stasis": IStasisEvent,
             "maturity": IMaturityEvent,
+            "transaction-conflict": ITransactionConflictEvent,
             "discovery": IDiscoveryEvent,
             "balance": IBalanceEvent,
             "error": IErrorEvent
@@ -123,6 +133,8 @@ cfg_if! {
             Connect = "connect",
             Disconnect = "disconnect",
             UtxoIndexNotEnabled = "utxo-index-not-enabled",
+            NodeEndpoint = "node-endpoint",
+            ParamsDigestMismatch = "params-digest-mismatch",
             SyncState = "sync-state",
             WalletHint = "wallet-hint",
             WalletOpen = "wallet-open",
@@ -136,15 +148,19 @@ cfg_if! {
             AccountSelection = "account-selection",
             AccountCreate = "account-create",
             AccountUpdate = "account-update",
+            AccountDiscovery = "account-discovery",
             ServerStatus = "server-status",
             UtxoProcStart = "utxo-proc-start",
             UtxoProcStop = "utxo-proc-stop",
             UtxoProcError = "utxo-proc-error",
+            ResyncRequired = "resync-required",
+            ResyncComplete = "resync-complete",
             DaaScoreChange = "daa-score-change",
             Pending = "pending",
             Reorg = "reorg",
             Stasis = "stasis",
             Maturity = "maturity",
+            TransactionConflict = "transaction-conflict",
             Discovery = "discovery",
             Balance = "balance",
             Error = "error",
@@ -158,6 +174,8 @@ cfg_if! {
         export type WalletEventData = IConnectEvent
             | IDisconnectEvent
             | IUtxoIndexNotEnabledEvent
+            | INodeEndpointEvent
+            | IParamsDigestMismatchEvent
             | ISyncStateEvent
             | IWalletHintEvent
             | IWalletOpenEvent
@@ -171,6 +189,7 @@ cfg_if! {
             | IAccountSelectionEvent
             | IAccountCreateEvent
         | IAccountUpdateEvent
+            | IAccountDiscoveryEvent
             | IServerStatusEvent
             // | IUtxoProcStartEvent
             // | IUtxoProcStopEvent
@@ -180,6 +199,7 @@ cfg_if! {
             | IReorgEvent
             | IStasisEvent
             | IMaturityEvent
+            | ITransactionConflictEvent
             | IDiscoveryEvent
             | IBalanceEvent
             | IErrorEvent
@@ -195,6 +215,8 @@ cfg_if! {
              "connect": IConnectEvent,
              "disconnect": IDisconnectEvent,
              "utxo-index-not-enabled": IUtxoIndexNotEnabledEvent,
+             "node-endpoint": INodeEndpointEvent,
+             "params-digest-mismatch": IParamsDigestMismatchEvent,
              "sync-state": ISyncStateEvent,
              "wallet-hint": IWalletHintEvent,
              "wallet-open": IWalletOpenEvent,
@@ -208,15 +230,19 @@ cfg_if! {
              "account-selection": IAccountSelectionEvent,
              "account-create": IAccountCreateEvent,
              "account-update": IAccountUpdateEvent,
+             "account-discovery": IAccountDiscoveryEvent,
              "server-status": IServerStatusEvent,
              "utxo-proc-start": undefined,
              "utxo-proc-stop": undefined,
              "utxo-proc-error": IUtxoProcErrorEvent,
+             "resync-required": undefined,
+             "resync-complete": undefined,
              "daa-score-change": IDaaScoreChangeEvent,
              "pending": IPendingEvent,
              "reorg": IReorgEvent,
              "stasis": IStasisEvent,
              "maturity": IMaturityEvent,
+             "transaction-conflict": ITransactionConflictEvent,
              "discovery": IDiscoveryEvent,
              "balance": IBalanceEvent,
              "error": IErrorEvent,
@@ -266,6 +292,11 @@ declare! {
     export interface IConnectEvent {
         networkId : string;
         url? : string;
+        /**
+         * `true` if the {@link UtxoContext} instances registered during a previous
+         * connection have been re-subscribed and refreshed against the node.
+         */
+        resynced : boolean;
     }
     "#,
 }
@@ -281,6 +312,11 @@ declare! {
     export interface IDisconnectEvent {
         networkId : string;
         url? : string;
+        /**
+         * `true` if the registered {@link UtxoContext} instances are retained
+         * and will be resynced once the connection is re-established.
+         */
+        resynced : boolean;
     }
     "#,
 }
@@ -299,6 +335,45 @@ declare! {
     "#,
 }
 
+declare! {
+    INodeEndpointEvent,
+    r#"
+    /**
+     * Emitted by {@link Wallet} when the RPC client has been pointed to a node endpoint profile.
+     * 
+     * @category Wallet Events
+     */
+    export interface INodeEndpointEvent {
+        networkId : string;
+        name : string;
+        url : string;
+        /**
+         * `true` if the endpoint has been selected automatically
+         * because the previously active endpoint became unreachable.
+         */
+        failover : boolean;
+    }
+    "#,
+}
+
+declare! {
+    IParamsDigestMismatchEvent,
+    r#"
+    /**
+     * Emitted by {@link UtxoProcessor} when it detects that the consensus params digest
+     * reported by the connected node does not match the digest of the configured network.
+     * 
+     * @category Wallet Events
+     */
+    export interface IParamsDigestMismatchEvent {
+        networkId : string;
+        expected : HexString;
+        received : HexString;
+        url? : string;
+    }
+    "#,
+}
+
 declare! {
     ISyncStateEvent,
     r#"
@@ -494,6 +569,25 @@ declare! {
     "#,
 }
 
+#[cfg(feature = "wasm32-sdk")]
+declare! {
+    IAccountDiscoveryEvent,
+    r#"
+    /**
+     * Emitted by {@link Wallet} for each account index scanned during
+     * the account discovery performed when importing a mnemonic.
+     * `isActive` is `true` if UTXOs have been found within the
+     * scanned addresses of the account.
+     * 
+     * @category Wallet Events
+     */
+    export interface IAccountDiscoveryEvent {
+        accountIndex : number;
+        isActive : boolean;
+    }
+    "#,
+}
+
 declare! {
     IServerStatusEvent,
     r#"
@@ -601,6 +695,26 @@ declare! {
     "#,
 }
 
+declare! {
+    ITransactionConflictEvent,
+    r#"
+    /**
+     * Emitted by {@link UtxoProcessor} when the node detects a double spend
+     * involving the monitored addresses. The transaction `transactionId` is
+     * rejected or removed from the mempool because the transaction
+     * `conflictingTransactionId` spends some same outpoints.
+     * 
+     * @category Wallet Events
+     */
+    export interface ITransactionConflictEvent {
+        transactionId : HexString;
+        conflictingTransactionId : HexString;
+        outpoints : ITransactionOutpoint[];
+        isConflictingInBlock : boolean;
+    }
+    "#,
+}
+
 declare! {
     IDiscoveryEvent,
     r#"
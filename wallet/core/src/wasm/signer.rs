@@ -1,11 +1,13 @@
 use crate::imports::*;
 use crate::result::Result;
+use crate::tx::{finalize_mass, get_consensus_params_by_address};
 use js_sys::Array;
-use kaspa_consensus_client::{sign_with_multiple_v3, Transaction};
-use kaspa_consensus_core::tx::PopulatedTransaction;
+use kaspa_consensus_client::{sign_input, sign_with_multiple_v3, SighashType, Signed, Transaction};
+use kaspa_consensus_core::tx::{self as cctx, PopulatedTransaction};
 use kaspa_consensus_core::{hashing::sighash_type::SIG_HASH_ALL, sign::verify};
 use kaspa_hashes::Hash;
 use kaspa_wallet_keys::privatekey::PrivateKey;
+use kaspa_wasm_core::types::HexString;
 use serde_wasm_bindgen::from_value;
 
 #[wasm_bindgen]
@@ -48,7 +50,13 @@ pub fn js_sign_transaction(tx: Transaction, signer: PrivateKeyArrayT, verify_sig
 }
 
 pub fn sign_transaction(tx: Transaction, private_keys: &[[u8; 32]], verify_sig: bool) -> Result<Transaction> {
-    let tx = sign(tx, private_keys)?;
+    let tx = match sign_with_multiple_v3(tx, private_keys)? {
+        Signed::Fully(tx) => {
+            finalize_transaction_mass(&tx)?;
+            tx
+        }
+        Signed::Partially(tx) => tx,
+    };
     if verify_sig {
         let (cctx, utxos) = tx.tx_and_utxos();
         let populated_transaction = PopulatedTransaction::new(&cctx, utxos);
@@ -57,6 +65,55 @@ pub fn sign_transaction(tx: Transaction, private_keys: &[[u8; 32]], verify_sig:
     Ok(tx)
 }
 
+/// Commit the full contextual mass (compute + storage) to a fully signed transaction.
+/// Consensus parameters are resolved from the addresses of the UTXO entries spent by
+/// the transaction; if no UTXO entry carries an address, the mass is left unchanged.
+pub fn finalize_transaction_mass(tx: &Transaction) -> Result<()> {
+    let utxo_entries = tx.inner().inputs.iter().filter_map(|input| input.utxo()).collect::<Vec<_>>();
+    if let Some(address) = utxo_entries.iter().find_map(|entry| entry.utxo.address.clone()) {
+        let consensus_params = get_consensus_params_by_address(&address);
+        let mass = finalize_mass(&cctx::Transaction::from(tx), &utxo_entries, &consensus_params)?;
+        tx.set_mass(mass);
+    }
+    Ok(())
+}
+
+/// `createInputSignature()` is a helper function to sign a single transaction input using a private key
+/// and the given sighash type (defaults to {@link SighashType.All}). Returns the signature script push
+/// `OP_DATA_65 <SIGNATURE+SIGHASH_TYPE>`, which can be used as is for P2PK inputs or assembled into a
+/// larger script (e.g. for multisig inputs) and applied using {@link Transaction.fillInput}.
+/// The transaction inputs must carry their UTXO entries.
+/// @see {@link signTransactionInput}
+/// @category Wallet SDK
+#[wasm_bindgen(js_name = "createInputSignature")]
+pub fn create_input_signature(
+    tx: &Transaction,
+    input_index: usize,
+    private_key: &PrivateKey,
+    sighash_type: Option<SighashType>,
+) -> Result<HexString> {
+    let signature = sign_input(tx, input_index, &private_key.secret_bytes(), sighash_type.unwrap_or_default().into())?;
+    Ok(signature.to_hex().into())
+}
+
+/// `signTransactionInput()` is a helper function to sign a single transaction input using a private key
+/// and the given sighash type (defaults to {@link SighashType.All}), setting the resulting signature as
+/// the signature script of the input. Other inputs are left untouched, so a transaction can be signed
+/// input by input by different parties.
+/// @see {@link createInputSignature}
+/// @category Wallet SDK
+#[wasm_bindgen(js_name = "signTransactionInput")]
+pub fn sign_transaction_input(
+    tx: &Transaction,
+    input_index: usize,
+    private_key: &PrivateKey,
+    sighash_type: Option<SighashType>,
+) -> Result<()> {
+    let signature = sign_input(tx, input_index, &private_key.secret_bytes(), sighash_type.unwrap_or_default().into())?;
+    tx.set_signature_script(input_index, signature)?;
+    Ok(())
+}
+
 /// Sign a transaction using schnorr, returns a new transaction with the signatures added.
 /// The resulting transaction may be partially signed if the supplied keys are not sufficient
 /// to sign all of its inputs.
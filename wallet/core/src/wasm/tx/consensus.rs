@@ -1,7 +1,14 @@
-use crate::tx::consensus as core;
+use crate::error::Error;
+use crate::result::Result;
+use crate::tx::{consensus as core, mass};
+use crate::utxo::{settings as native, UtxoEntryReference, UtxoEntryReferenceExtension};
 use kaspa_addresses::Address;
-use kaspa_consensus_core::{config::params::Params, network::NetworkType};
+use kaspa_consensus_core::{
+    config::params::Params,
+    network::{NetworkId, NetworkIdT, NetworkType},
+};
 use wasm_bindgen::prelude::*;
+use workflow_wasm::prelude::*;
 
 /// @category Wallet SDK
 #[wasm_bindgen]
@@ -34,3 +41,128 @@ pub fn get_consensus_params_by_address(address: &Address) -> ConsensusParams {
 pub fn get_consensus_params_by_network(network: NetworkType) -> ConsensusParams {
     core::get_consensus_params_by_network(&network).into()
 }
+
+///
+/// Parameters of a network derived from the consensus parameters of its {@link NetworkId},
+/// allowing applications to obtain network constants instead of hardcoding them.
+///
+/// @see {@link NetworkId}, {@link UtxoProcessor}
+/// @category Wallet SDK
+///
+#[wasm_bindgen]
+pub struct NetworkParams {
+    network_id: NetworkId,
+    params: Params,
+    wallet_params: &'static native::NetworkParams,
+}
+
+impl NetworkParams {
+    pub fn try_new(network_id: NetworkId) -> Result<Self> {
+        if !NetworkId::iter().any(|supported| supported == network_id) {
+            return Err(Error::custom(format!("network id {network_id} is not supported")));
+        }
+        Ok(Self { network_id, params: Params::from(network_id), wallet_params: network_id.into() })
+    }
+}
+
+#[wasm_bindgen]
+impl NetworkParams {
+    #[wasm_bindgen(constructor)]
+    pub fn ctor(network_id: &NetworkIdT) -> Result<NetworkParams> {
+        Self::try_new(NetworkId::try_cast_from(network_id)?.into_owned())
+    }
+
+    #[wasm_bindgen(getter, js_name = "networkId")]
+    pub fn network_id(&self) -> String {
+        self.network_id.to_string()
+    }
+
+    /// The address prefix of the network (e.g. `kaspa` or `kaspatest`)
+    #[wasm_bindgen(getter, js_name = "addressPrefix")]
+    pub fn address_prefix(&self) -> String {
+        self.params.prefix().to_string()
+    }
+
+    /// Target time per block (in milliseconds)
+    #[wasm_bindgen(getter, js_name = "targetTimePerBlock")]
+    pub fn target_time_per_block(&self) -> u64 {
+        self.params.target_time_per_block
+    }
+
+    /// The number of DAA score units a coinbase UTXO must wait before being spendable
+    #[wasm_bindgen(getter, js_name = "coinbaseMaturity")]
+    pub fn coinbase_maturity(&self) -> u64 {
+        self.params.coinbase_maturity
+    }
+
+    /// The finality duration (in milliseconds)
+    #[wasm_bindgen(getter, js_name = "finalityDuration")]
+    pub fn finality_duration(&self) -> u64 {
+        self.params.finality_duration()
+    }
+
+    #[wasm_bindgen(getter, js_name = "maxBlockMass")]
+    pub fn max_block_mass(&self) -> u64 {
+        self.params.max_block_mass
+    }
+
+    /// The maximum mass of a standard transaction accepted by the mempool
+    #[wasm_bindgen(getter, js_name = "maxStandardTransactionMass")]
+    pub fn max_standard_transaction_mass(&self) -> u64 {
+        mass::MAXIMUM_STANDARD_TRANSACTION_MASS
+    }
+
+    /// DAA score after which the pre-deflationary period switches to the deflationary period
+    #[wasm_bindgen(getter, js_name = "deflationaryPhaseDaaScore")]
+    pub fn deflationary_phase_daa_score(&self) -> u64 {
+        self.params.deflationary_phase_daa_score
+    }
+
+    /// The number of DAA score units after which a coinbase UTXO leaves the stasis period
+    #[wasm_bindgen(getter, js_name = "coinbaseTransactionStasisPeriodDaa")]
+    pub fn coinbase_transaction_stasis_period_daa(&self) -> u64 {
+        self.wallet_params.coinbase_transaction_stasis_period_daa
+    }
+
+    /// The number of DAA score units after which a user transaction UTXO is considered confirmed
+    #[wasm_bindgen(getter, js_name = "userTransactionMaturityPeriodDaa")]
+    pub fn user_transaction_maturity_period_daa(&self) -> u64 {
+        self.wallet_params.user_transaction_maturity_period_daa
+    }
+
+    /// Returns the maturity of the UTXO entry at the given DAA score: `stasis`
+    /// (coinbase UTXOs only), `pending` or `confirmed`.
+    pub fn maturity(&self, entry: &UtxoEntryReference, current_daa_score: u64) -> String {
+        entry.maturity(self.wallet_params, current_daa_score).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_params() {
+        for network_id in NetworkId::iter() {
+            let network_params = NetworkParams::try_new(network_id).unwrap();
+            let (address_prefix, target_time_per_block, coinbase_maturity, finality_duration) = match network_id.to_string().as_str() {
+                "mainnet" => ("kaspa", 1000, 100, 86_400_000),
+                "testnet-10" => ("kaspatest", 1000, 100, 86_400_000),
+                "testnet-11" => ("kaspatest", 100, 1000, 43_200_000),
+                "devnet" => ("kaspadev", 1000, 100, 86_400_000),
+                "simnet" => ("kaspasim", 100, 1000, 43_200_000),
+                network_id => panic!("unexpected network id {network_id}"),
+            };
+            assert_eq!(network_params.network_id(), network_id.to_string());
+            assert_eq!(network_params.address_prefix(), address_prefix, "{network_id}");
+            assert_eq!(network_params.target_time_per_block(), target_time_per_block, "{network_id}");
+            assert_eq!(network_params.coinbase_maturity(), coinbase_maturity, "{network_id}");
+            assert_eq!(network_params.coinbase_transaction_stasis_period_daa(), coinbase_maturity / 2, "{network_id}");
+            assert_eq!(network_params.user_transaction_maturity_period_daa(), coinbase_maturity / 10, "{network_id}");
+            assert_eq!(network_params.max_block_mass(), 500_000, "{network_id}");
+            assert_eq!(network_params.finality_duration(), finality_duration, "{network_id}");
+            assert_eq!(network_params.max_standard_transaction_mass(), 100_000, "{network_id}");
+        }
+        assert!(NetworkParams::try_new(NetworkId::with_suffix(NetworkType::Testnet, 12)).is_err());
+    }
+}
@@ -64,6 +64,15 @@ interface IGeneratorSettingsObject {
      * interface, or a {@link UtxoContext} instance.
      */
     entries: IUtxoEntry[] | UtxoEntryReference[] | UtxoContext;
+    /**
+     * Optional coin control: outpoints (as `"<transactionId>-<index>"` strings or
+     * {@link ITransactionOutpoint} objects) of the UTXO entries that must fund the
+     * transaction. The referenced entries must be available in `entries` (and be
+     * mature if `entries` is a {@link UtxoContext}); they are all spent by a single
+     * transaction, the generator failing if they do not fit in one or if their
+     * value is insufficient.
+     */
+    utxoSelection?: (string | ITransactionOutpoint)[];
     /**
      * Optional number of signature operations in the transaction.
      */
@@ -142,6 +151,36 @@ pub struct Generator {
 impl Generator {
     #[wasm_bindgen(constructor)]
     pub fn ctor(args: IGeneratorSettingsObject) -> Result<Generator> {
+        Self::try_new(args, false)
+    }
+
+    /// Generate next transaction
+    pub async fn next(&self) -> Result<JsValue> {
+        if let Some(transaction) = self.inner.generate_transaction().transpose() {
+            let transaction = PendingTransaction::from(transaction?);
+            Ok(transaction.into())
+        } else {
+            Ok(JsValue::NULL)
+        }
+    }
+
+    pub async fn estimate(&self) -> Result<GeneratorSummary> {
+        while self.inner.estimate_transaction()? {}
+        Ok(self.summary())
+    }
+
+    pub fn summary(&self) -> GeneratorSummary {
+        self.inner.summary().into()
+    }
+}
+
+impl Generator {
+    /// Creates a generator running in estimate mode, see [`native::Generator::try_new_estimate`]
+    pub fn try_new_estimate(args: IGeneratorSettingsObject) -> Result<Generator> {
+        Self::try_new(args, true)
+    }
+
+    fn try_new(args: IGeneratorSettingsObject, estimate_only: bool) -> Result<Generator> {
         let settings = GeneratorSettings::try_from(args)?;
 
         let GeneratorSettings {
@@ -154,6 +193,7 @@ impl Generator {
             sig_op_count,
             minimum_signatures,
             payload,
+            utxo_selection,
         } = settings;
 
         let settings = match source {
@@ -175,6 +215,7 @@ impl Generator {
                     payload,
                     multiplexer,
                 )?
+                .with_utxo_selection(utxo_selection)
             }
             GeneratorSource::UtxoContext(utxo_context) => {
                 let change_address = change_address
@@ -190,6 +231,7 @@ impl Generator {
                     payload,
                     multiplexer,
                 )?
+                .with_utxo_selection(utxo_selection)
             } // GeneratorSource::Account(account) => {
               //     let account: Arc<dyn crate::account::Account> = account.into();
               //     native::GeneratorSettings::try_new_with_account(account, final_transaction_destination, final_priority_fee, None)?
@@ -197,33 +239,19 @@ impl Generator {
         };
 
         let abortable = Abortable::default();
-        let generator = native::Generator::try_new(settings, None, Some(&abortable))?;
+        let generator = match estimate_only {
+            true => native::Generator::try_new_estimate(settings, Some(&abortable))?,
+            false => native::Generator::try_new(settings, None, Some(&abortable))?,
+        };
 
         Ok(Self { inner: Arc::new(generator) })
     }
 
-    /// Generate next transaction
-    pub async fn next(&self) -> Result<JsValue> {
-        if let Some(transaction) = self.inner.generate_transaction().transpose() {
-            let transaction = PendingTransaction::from(transaction?);
-            Ok(transaction.into())
-        } else {
-            Ok(JsValue::NULL)
-        }
-    }
-
-    pub async fn estimate(&self) -> Result<GeneratorSummary> {
-        let mut stream = self.inner.stream();
-        while stream.try_next().await?.is_some() {}
-        Ok(self.summary())
-    }
-
-    pub fn summary(&self) -> GeneratorSummary {
-        self.inner.summary().into()
+    /// Performs a single estimation step, returning `false` once the generator is done
+    pub fn estimate_transaction(&self) -> Result<bool> {
+        self.inner.estimate_transaction()
     }
-}
 
-impl Generator {
     pub fn iter(&self) -> impl Iterator<Item = Result<native::PendingTransaction>> {
         self.inner.iter()
     }
@@ -251,6 +279,7 @@ struct GeneratorSettings {
     pub sig_op_count: u8,
     pub minimum_signatures: u16,
     pub payload: Option<Vec<u8>>,
+    pub utxo_selection: native::UtxoSelection,
 }
 
 impl TryFrom<IGeneratorSettingsObject> for GeneratorSettings {
@@ -288,6 +317,19 @@ impl TryFrom<IGeneratorSettingsObject> for GeneratorSettings {
 
         let payload = args.get_vec_u8("payload").ok();
 
+        let utxo_selection = args.get_value("utxoSelection")?;
+        let utxo_selection = if !utxo_selection.is_undefined() {
+            let outpoints = utxo_selection
+                .dyn_into::<Array>()
+                .map_err(|_| Error::custom("utxoSelection must be an array of transaction outpoints"))?
+                .iter()
+                .map(|outpoint| kaspa_consensus_client::TransactionOutpoint::try_from(&outpoint).map(Into::into))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            native::UtxoSelection::Manual(outpoints)
+        } else {
+            native::UtxoSelection::Automatic
+        };
+
         let settings = GeneratorSettings {
             network_id,
             source: generator_source,
@@ -298,6 +340,7 @@ impl TryFrom<IGeneratorSettingsObject> for GeneratorSettings {
             sig_op_count,
             minimum_signatures,
             payload,
+            utxo_selection,
         };
 
         Ok(settings)
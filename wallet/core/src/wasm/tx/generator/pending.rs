@@ -134,6 +134,28 @@ impl PendingTransaction {
         Ok(string::SerializableTransaction::from_cctx_transaction(&self.inner.transaction(), self.inner.utxo_entries())?
             .serialize_to_json()?)
     }
+
+    /// Serializes the transaction, along with the UTXO entries of its inputs, to a compact binary
+    /// buffer which can be posted to a Web Worker as a transferable. No private keys are included.
+    /// The worker deserializes it using {@link Transaction.fromBytes}, signs it, and posts back
+    /// the result of {@link Transaction.toBytes}, which is applied using {@link PendingTransaction.fillFromBytes}
+    /// before submitting the transaction from the main thread.
+    #[wasm_bindgen(js_name = "toBytes")]
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(self.transaction()?.to_bytes())
+    }
+
+    /// Applies the signatures of a copy of this transaction signed externally (e.g. in a Web Worker)
+    /// and serialized using {@link Transaction.toBytes}.
+    #[wasm_bindgen(js_name = "fillFromBytes")]
+    pub fn fill_from_bytes(&self, bytes: &[u8]) -> Result<()> {
+        let signed = Transaction::from_bytes(bytes)?;
+        if signed.id() != self.inner.id() {
+            return Err(Error::custom("the signed transaction does not match the pending transaction"));
+        }
+        let signature_scripts = signed.inner().inputs.iter().map(|input| input.inner().signature_script.clone()).collect();
+        self.inner.try_fill_signature_scripts(signature_scripts)
+    }
 }
 
 impl From<native::PendingTransaction> for PendingTransaction {
@@ -4,10 +4,10 @@ use crate::tx::generator as core;
 ///
 /// A class containing a summary produced by transaction {@link Generator}.
 /// This class contains the number of transactions, the aggregated fees,
-/// the aggregated UTXOs and the final transaction amount that includes
-/// both network and QoS (priority) fees.
+/// mass and UTXOs, the final transaction amount that includes
+/// both network and QoS (priority) fees and the final transaction change.
 ///
-/// @see {@link createTransactions}, {@link IGeneratorSettingsObject}, {@link Generator}
+/// @see {@link createTransactions}, {@link estimateTransactions}, {@link IGeneratorSettingsObject}, {@link Generator}
 /// @category Wallet SDK
 ///
 #[wasm_bindgen(inspectable)]
@@ -32,6 +32,11 @@ impl GeneratorSummary {
         BigInt::from(self.inner.aggregated_fees())
     }
 
+    #[wasm_bindgen(getter, js_name = mass)]
+    pub fn aggregated_mass(&self) -> BigInt {
+        BigInt::from(self.inner.aggregated_mass())
+    }
+
     #[wasm_bindgen(getter, js_name = transactions)]
     pub fn number_of_generated_transactions(&self) -> usize {
         self.inner.number_of_generated_transactions()
@@ -42,6 +47,11 @@ impl GeneratorSummary {
         self.inner.final_transaction_amount().map(BigInt::from)
     }
 
+    #[wasm_bindgen(getter, js_name = finalChange)]
+    pub fn final_transaction_change(&self) -> Option<BigInt> {
+        self.inner.final_transaction_change().map(BigInt::from)
+    }
+
     #[wasm_bindgen(getter, js_name = finalTransactionId)]
     pub fn final_transaction_id(&self) -> Option<String> {
         self.inner.final_transaction_id().map(|id| id.to_string())
@@ -0,0 +1,68 @@
+use crate::imports::*;
+use crate::result::Result;
+use kaspa_consensus_client::*;
+use kaspa_consensus_core::locktime::{self, SequenceLock};
+use kaspa_consensus_core::tx as cctx;
+use kaspa_wallet_macros::declare_typescript_wasm_interface as declare;
+
+declare! {
+    ISequenceLock,
+    r#"
+    /**
+     * The earliest DAA score and past median time at which a transaction
+     * satisfies its lock time and the relative locks of its inputs.
+     *
+     * @see {@link calcSequenceLock}, {@link isTransactionFinal}
+     * @category Wallet SDK
+     */
+    export interface ISequenceLock {
+        /**
+         * The DAA score required for the transaction to be accepted.
+         */
+        daaScore : bigint;
+        /**
+         * The past median time (in milliseconds) required for the
+         * transaction to be accepted.
+         */
+        pastMedianTime : bigint;
+    }
+    "#,
+}
+
+impl TryFrom<SequenceLock> for ISequenceLock {
+    type Error = Error;
+    fn try_from(lock: SequenceLock) -> std::result::Result<Self, Self::Error> {
+        let object = ISequenceLock::default();
+        object.set("daaScore", &BigInt::from(lock.daa_score).into())?;
+        object.set("pastMedianTime", &BigInt::from(lock.median_time).into())?;
+        Ok(object)
+    }
+}
+
+/// Returns whether the lock time of the transaction is reached (or ignored since the sequence
+/// numbers of all its inputs are maxed out) at the given DAA score and past median time, as
+/// checked by the consensus and the mempool.
+/// @see {@link calcSequenceLock}
+/// @category Wallet SDK
+#[wasm_bindgen(js_name = "isTransactionFinal")]
+pub fn js_is_transaction_final(tx: &JsValue, daa_score: u64, past_median_time: u64) -> Result<bool> {
+    let tx = Transaction::try_cast_from(tx)?;
+    let tx = cctx::Transaction::from(tx.as_ref());
+    Ok(locktime::is_final(&tx, daa_score, past_median_time))
+}
+
+/// Calculates the earliest DAA score and past median time, at or after the current ones, at which
+/// the transaction can be accepted given its lock time and the relative locks of its inputs. All
+/// the transaction inputs must carry their UTXO entries.
+/// @see {@link ISequenceLock}, {@link isTransactionFinal}
+/// @category Wallet SDK
+#[wasm_bindgen(js_name = "calcSequenceLock")]
+pub fn js_calc_sequence_lock(tx: &JsValue, current_daa_score: u64, current_past_median_time: u64) -> Result<ISequenceLock> {
+    let tx = Transaction::try_cast_from(tx)?;
+    if tx.as_ref().inner().inputs.iter().any(|input| input.get_utxo().is_none()) {
+        return Err(Error::custom("calcSequenceLock() requires the UTXO entries of all transaction inputs"));
+    }
+    let (tx, utxos) = tx.as_ref().tx_and_utxos();
+    let utxo_daa_scores = utxos.iter().map(|utxo| utxo.block_daa_score).collect::<Vec<_>>();
+    locktime::calc_sequence_lock(&tx, &utxo_daa_scores, current_daa_score, current_past_median_time).try_into()
+}
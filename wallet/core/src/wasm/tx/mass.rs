@@ -1,10 +1,13 @@
+use crate::error::Error;
 use crate::imports::NetworkParams;
 use crate::result::Result;
-use crate::tx::mass;
+use crate::tx::mass::{self, MassCombinationStrategy};
 use crate::wasm::tx::*;
 use kaspa_consensus_client::*;
 use kaspa_consensus_core::config::params::Params;
+use kaspa_consensus_core::mass::{Kip9Version, MassCalculator as ConsensusMassCalculator};
 use kaspa_consensus_core::tx as cctx;
+use kaspa_consensus_core::tx::MutableTransaction;
 use std::sync::Arc;
 use wasm_bindgen::prelude::*;
 use workflow_wasm::convert::*;
@@ -13,6 +16,8 @@ use workflow_wasm::convert::*;
 #[wasm_bindgen]
 pub struct MassCalculator {
     mc: Arc<mass::MassCalculator>,
+    consensus: Arc<ConsensusMassCalculator>,
+    kip9_version: Kip9Version,
 }
 
 #[wasm_bindgen]
@@ -21,7 +26,60 @@ impl MassCalculator {
     pub fn new(cp: ConsensusParams) -> Self {
         let consensus_params = Params::from(cp);
         let network_params = NetworkParams::from(consensus_params.net);
-        Self { mc: Arc::new(mass::MassCalculator::new(&consensus_params, &network_params)) }
+        Self {
+            mc: Arc::new(mass::MassCalculator::new(&consensus_params, &network_params)),
+            consensus: Arc::new(ConsensusMassCalculator::new(&consensus_params)),
+            kip9_version: match network_params.mass_combination_strategy {
+                MassCombinationStrategy::Add => Kip9Version::Alpha,
+                MassCombinationStrategy::Max => Kip9Version::Beta,
+            },
+        }
+    }
+
+    /// The compute mass charged per byte of the estimated serialized transaction size
+    #[wasm_bindgen(getter, js_name=massPerTxByte)]
+    pub fn mass_per_tx_byte(&self) -> u64 {
+        self.consensus.mass_per_tx_byte()
+    }
+
+    /// The compute mass charged per byte of the output script public keys
+    #[wasm_bindgen(getter, js_name=massPerScriptPubKeyByte)]
+    pub fn mass_per_script_pub_key_byte(&self) -> u64 {
+        self.consensus.mass_per_script_pub_key_byte()
+    }
+
+    /// The compute mass charged per input signature operation
+    #[wasm_bindgen(getter, js_name=massPerSigOp)]
+    pub fn mass_per_sig_op(&self) -> u64 {
+        self.consensus.mass_per_sig_op()
+    }
+
+    /// The KIP-0009 storage mass parameter
+    #[wasm_bindgen(getter, js_name=storageMassParameter)]
+    pub fn storage_mass_parameter(&self) -> u64 {
+        self.consensus.storage_mass_parameter()
+    }
+
+    /// `calcComputeMass()` returns the compute mass of a (signed) transaction,
+    /// as calculated by the consensus.
+    #[wasm_bindgen(js_name=calcComputeMass)]
+    pub fn calc_compute_mass(&self, tx: &JsValue) -> Result<u64> {
+        let tx = Transaction::try_cast_from(tx)?;
+        let tx = cctx::Transaction::from(tx.as_ref());
+        Ok(self.consensus.calc_tx_compute_mass(&tx))
+    }
+
+    /// `calcStorageMass()` returns the KIP-0009 storage mass of a transaction whose inputs
+    /// all carry their UTXO entries, as calculated by the consensus.
+    #[wasm_bindgen(js_name=calcStorageMass)]
+    pub fn calc_storage_mass(&self, tx: &JsValue) -> Result<u64> {
+        let tx = Transaction::try_cast_from(tx)?;
+        if tx.as_ref().inner().inputs.iter().any(|input| input.get_utxo().is_none()) {
+            return Err(Error::custom("calcStorageMass() requires the UTXO entries of all transaction inputs"));
+        }
+        let (tx, utxos) = tx.as_ref().tx_and_utxos();
+        let tx = MutableTransaction::with_entries(tx, utxos);
+        self.consensus.calc_tx_storage_mass(&tx.as_verifiable(), self.kip9_version).ok_or(Error::MassCalculationError)
     }
 
     #[wasm_bindgen(js_name=isDust)]
@@ -1,11 +1,13 @@
 pub mod consensus;
 pub mod fees;
 pub mod generator;
+pub mod locktime;
 pub mod mass;
 pub mod utils;
 
 pub use self::consensus::*;
 pub use self::fees::*;
 pub use self::generator::*;
+pub use self::locktime::*;
 pub use self::mass::*;
 pub use self::utils::*;
@@ -67,7 +67,7 @@ pub fn create_transaction_js(
     // TODO - Calculate mass and fees
 
     let outputs: Vec<TransactionOutput> = outputs.into();
-    let transaction = Transaction::new(None, 0, inputs, outputs, 0, SUBNETWORK_ID_NATIVE, 0, payload)?;
+    let transaction = Transaction::new(None, 0, inputs, outputs, 0, SUBNETWORK_ID_NATIVE, 0, payload, 0)?;
     let _fee = mc.calc_minimum_transaction_relay_fee(&transaction, minimum_signatures);
     //let mtx = SignableTransaction::new(transaction, entries.into());
 
@@ -131,22 +131,25 @@ pub async fn create_transactions_js(settings: IGeneratorSettingsObject) -> Resul
 }
 
 /// Helper function that creates an estimate using the transaction {@link Generator}
-/// by producing only the {@link GeneratorSummary} containing the estimate.
+/// by producing only the {@link GeneratorSummary} containing the estimate
+/// (number of transactions, aggregated mass, fees and UTXOs, final change).
+///
+/// The generator runs in an estimate mode: the UTXO selection and the mass and
+/// fee computation are performed without creating pending transactions nor
+/// reserving the UTXO entries of the source {@link UtxoContext}, making this
+/// function suitable for updating an estimate as the user types an amount.
 /// @see {@link IGeneratorSettingsObject}, {@link Generator}, {@link createTransactions}
 /// @category Wallet SDK
 #[wasm_bindgen(js_name=estimateTransactions)]
 pub async fn estimate_transactions_js(settings: IGeneratorSettingsObject) -> Result<GeneratorSummary> {
-    let generator = Generator::ctor(settings)?;
+    let generator = Generator::try_new_estimate(settings)?;
     if is_web() {
-        // yield after each generated transaction if operating in the browser
-        let mut stream = generator.stream();
-        while stream.try_next().await?.is_some() {
+        // yield after each estimated transaction if operating in the browser
+        while generator.estimate_transaction()? {
             yield_executor().await;
         }
-        Ok(generator.summary())
     } else {
-        // use iterator to aggregate all transactions
-        generator.iter().collect::<Result<Vec<_>>>()?;
-        Ok(generator.summary())
+        while generator.estimate_transaction()? {}
     }
+    Ok(generator.summary())
 }
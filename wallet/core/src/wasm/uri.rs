@@ -0,0 +1,91 @@
+use crate::imports::*;
+use crate::uri::{self, PaymentUri};
+use kaspa_consensus_core::network::NetworkTypeT;
+use kaspa_wallet_macros::declare_typescript_wasm_interface as declare;
+
+declare! {
+    IPaymentUri,
+    r#"
+    /**
+     * Payment request encoded as a `kaspa:` URI.
+     *
+     * @see {@link buildPaymentUri}, {@link parsePaymentUri}
+     * @category Wallet SDK
+     */
+    export interface IPaymentUri {
+        /**
+         * Address of the payment recipient. The address prefix
+         * is used as the URI scheme.
+         */
+        address : Address | string;
+        /**
+         * Requested amount in Sompi.
+         */
+        amount? : bigint;
+        /**
+         * Name of the payment recipient.
+         */
+        label? : string;
+        /**
+         * Description of the payment.
+         */
+        message? : string;
+    }
+    "#,
+}
+
+impl TryFrom<IPaymentUri> for PaymentUri {
+    type Error = Error;
+    fn try_from(args: IPaymentUri) -> std::result::Result<Self, Self::Error> {
+        let address = args.get_cast::<Address>("address")?.into_owned();
+        let amount = args.try_get_value("amount")?.map(|amount| amount.try_as_u64()).transpose()?;
+        let label = args.try_get_string("label")?;
+        let message = args.try_get_string("message")?;
+        Ok(PaymentUri { address, amount, label, message })
+    }
+}
+
+impl TryFrom<PaymentUri> for IPaymentUri {
+    type Error = Error;
+    fn try_from(payment_uri: PaymentUri) -> std::result::Result<Self, Self::Error> {
+        let object = IPaymentUri::default();
+        object.set("address", &payment_uri.address.into())?;
+        if let Some(amount) = payment_uri.amount {
+            object.set("amount", &BigInt::from(amount).into())?;
+        }
+        if let Some(label) = payment_uri.label {
+            object.set("label", &label.into())?;
+        }
+        if let Some(message) = payment_uri.message {
+            object.set("message", &message.into())?;
+        }
+        Ok(object)
+    }
+}
+
+/// Creates a `kaspa:` payment URI from the payment request properties.
+/// @see {@link IPaymentUri}, {@link parsePaymentUri}
+/// @category Wallet SDK
+#[wasm_bindgen(js_name = "buildPaymentUri")]
+pub fn js_build_payment_uri(args: IPaymentUri) -> Result<String> {
+    Ok(PaymentUri::try_from(args)?.to_string())
+}
+
+/// Parses a `kaspa:` payment URI. If `network` is supplied, the URI is rejected
+/// unless its address belongs to the given network. Amounts with more than 8
+/// decimal places are rejected.
+/// @see {@link IPaymentUri}, {@link buildPaymentUri}
+/// @category Wallet SDK
+#[wasm_bindgen(js_name = "parsePaymentUri")]
+pub fn js_parse_payment_uri(uri: String, network: Option<NetworkTypeT>) -> Result<IPaymentUri> {
+    let prefix = network.as_ref().map(Prefix::try_from).transpose()?;
+    PaymentUri::parse(&uri, prefix)?.try_into()
+}
+
+/// Renders the supplied data (typically a payment URI or an address) as
+/// an SVG QR code image.
+/// @category Wallet SDK
+#[wasm_bindgen(js_name = "createQrCodeSvg")]
+pub fn js_create_qr_code_svg(data: String) -> Result<String> {
+    uri::render_qr_svg(&data)
+}
@@ -1,6 +1,8 @@
 use crate::result::Result;
 use js_sys::BigInt;
-use kaspa_consensus_core::network::{NetworkType, NetworkTypeT};
+use kaspa_consensus_core::config::params::Params;
+use kaspa_consensus_core::emission;
+use kaspa_consensus_core::network::{NetworkId, NetworkIdT, NetworkType, NetworkTypeT};
 use wasm_bindgen::prelude::*;
 use workflow_wasm::prelude::*;
 
@@ -44,3 +46,51 @@ pub fn sompi_to_kaspa_string_with_suffix(sompi: ISompiToKaspa, network: &Network
     let network_type = NetworkType::try_from(network)?;
     Ok(crate::utils::sompi_to_kaspa_string_with_suffix(sompi, &network_type))
 }
+
+///
+/// Returns the subsidy (in Sompi) paid to a block with the given DAA score
+/// on the specified network.
+///
+/// @category Wallet SDK
+///
+#[wasm_bindgen(js_name = "subsidyAtDaaScore")]
+pub fn subsidy_at_daa_score(daa_score: u64, network_id: &NetworkIdT) -> Result<u64> {
+    let params = Params::from(NetworkId::try_cast_from(network_id)?.into_owned());
+    Ok(emission::subsidy_at_daa_score(&params, daa_score))
+}
+
+///
+/// Returns the overall subsidy (in Sompi) paid to all the blocks mined up to
+/// and including the given DAA score on the specified network.
+///
+/// @category Wallet SDK
+///
+#[wasm_bindgen(js_name = "cumulativeEmissionAt")]
+pub fn cumulative_emission_at(daa_score: u64, network_id: &NetworkIdT) -> Result<u64> {
+    let params = Params::from(NetworkId::try_cast_from(network_id)?.into_owned());
+    Ok(emission::cumulative_emission_at(&params, daa_score))
+}
+
+///
+/// Returns an estimation of the circulating supply (in Sompi) of the specified
+/// network given the virtual DAA score, as derived from the emission schedule.
+///
+/// @category Wallet SDK
+///
+#[wasm_bindgen(js_name = "estimatedCirculatingSupply")]
+pub fn estimated_circulating_supply(virtual_daa_score: u64, network_id: &NetworkIdT) -> Result<u64> {
+    let params = Params::from(NetworkId::try_cast_from(network_id)?.into_owned());
+    Ok(emission::estimated_circulating_supply(&params, virtual_daa_score))
+}
+
+///
+/// Returns the overall subsidy (in Sompi) paid over the full emission schedule
+/// of the specified network.
+///
+/// @category Wallet SDK
+///
+#[wasm_bindgen(js_name = "maxEmission")]
+pub fn max_emission(network_id: &NetworkIdT) -> Result<u64> {
+    let params = Params::from(NetworkId::try_cast_from(network_id)?.into_owned());
+    Ok(emission::max_emission(&params))
+}
@@ -141,8 +141,9 @@ impl UtxoContext {
     }
 
     /// Clear the UtxoContext.  Unregister all addresses and clear all UTXO entries.
-    /// IMPORTANT: This function must be manually called when disconnecting or re-connecting to the node
-    /// (followed by address re-registration).  
+    /// NOTE: Registered addresses are retained across RPC reconnections, during which
+    /// the UtxoProcessor re-subscribes and refreshes them (see {@link IConnectEvent}),
+    /// so this function is only required to stop tracking the addresses.
     pub async fn clear(&self) -> Result<()> {
         self.inner().clear().await
     }
@@ -36,6 +36,7 @@ pub struct Inner {
     callbacks: Mutex<AHashMap<EventKind, Vec<Sink>>>,
     task_running: AtomicBool,
     task_ctl: DuplexChannel,
+    subscription: Mutex<Option<Arc<EventSubscription>>>,
 }
 
 impl Inner {
@@ -53,6 +54,19 @@ impl Inner {
             (None, None) => None,
         }
     }
+
+    /// The event kinds having at least one registered callback
+    fn event_filter(&self) -> EventKindSet {
+        let callbacks = self.callbacks.lock().unwrap();
+        callbacks.iter().filter(|(_, handlers)| !handlers.is_empty()).map(|(event, _)| *event).collect()
+    }
+
+    /// Narrows the notification task subscription down to the event kinds having a registered callback
+    fn update_event_filter(&self) {
+        if let Some(subscription) = self.subscription.lock().unwrap().as_ref() {
+            subscription.set_filter(self.event_filter());
+        }
+    }
 }
 
 cfg_if! {
@@ -81,6 +95,12 @@ cfg_if! {
 /// between multiple UtxoContext instances. It acts as a bridge between the
 /// Kaspa node RPC connection, address subscriptions and UtxoContext instances.
 ///
+/// UtxoContext instances and their address sets are retained when the RPC
+/// connection drops. Once the connection is re-established, their addresses
+/// are re-subscribed and their UTXO sets are refreshed against the node, emitting
+/// events for UTXO entries added or removed while disconnected, followed by a
+/// `connect` event with `resynced` set to `true`.
+///
 /// @see {@link IUtxoProcessorArgs},
 /// {@link UtxoContext},
 /// {@link RpcClient},
@@ -117,6 +137,7 @@ impl UtxoProcessor {
                 callbacks: Mutex::new(AHashMap::new()),
                 task_running: AtomicBool::new(false),
                 task_ctl: DuplexChannel::oneshot(),
+                subscription: Mutex::new(None),
             }),
         };
 
@@ -125,7 +146,7 @@ impl UtxoProcessor {
 
     /// Starts the UtxoProcessor and begins processing UTXO and other notifications.
     pub async fn start(&self) -> Result<()> {
-        self.start_notification_task(self.inner.processor.multiplexer()).await?;
+        self.start_notification_task(self.inner.processor.subscriptions()).await?;
         self.inner.processor.start().await?;
         Ok(())
     }
@@ -147,6 +168,14 @@ impl UtxoProcessor {
         self.inner.processor.network_id().ok().map(|network_id| network_id.to_string())
     }
 
+    /// The parameters of the network the UtxoProcessor is configured for,
+    /// used to determine the maturity of the UTXO entries it tracks.
+    /// @see {@link NetworkParams}
+    #[wasm_bindgen(getter, js_name = "networkParams")]
+    pub fn network_params(&self) -> Option<crate::wasm::tx::NetworkParams> {
+        self.inner.processor.network_id().ok().and_then(|network_id| crate::wasm::tx::NetworkParams::try_new(network_id).ok())
+    }
+
     #[wasm_bindgen(js_name = "setNetworkId")]
     pub fn set_network_id(&self, network_id: &NetworkIdT) -> Result<()> {
         let network_id = NetworkId::try_cast_from(network_id)?;
@@ -190,7 +219,7 @@ impl UtxoProcessor {
         &self.inner.processor
     }
 
-    pub async fn start_notification_task(&self, multiplexer: &Multiplexer<Box<Events>>) -> Result<()> {
+    pub async fn start_notification_task(&self, subscriptions: &EventSubscriptions) -> Result<()> {
         let inner = self.inner.clone();
 
         if inner.task_running.load(Ordering::SeqCst) {
@@ -202,7 +231,9 @@ impl UtxoProcessor {
 
         let ctl_receiver = inner.task_ctl.request.receiver.clone();
         let ctl_sender = inner.task_ctl.response.sender.clone();
-        let channel = multiplexer.channel();
+        // Only the event kinds having a registered callback are received by the task
+        let subscription = Arc::new(subscriptions.subscribe(inner.event_filter(), None, LagPolicy::DropNewest));
+        inner.subscription.lock().unwrap().replace(subscription.clone());
 
         spawn(async move {
             loop {
@@ -210,7 +241,7 @@ impl UtxoProcessor {
                     _ = ctl_receiver.recv().fuse() => {
                         break;
                     },
-                    msg = channel.receiver.recv().fuse() => {
+                    msg = subscription.receiver().recv().fuse() => {
                         if let Ok(notification) = &msg {
                             let event_type = EventKind::from(notification.as_ref());
                             let callbacks = inner.callbacks(event_type);
@@ -227,7 +258,8 @@ impl UtxoProcessor {
                 }
             }
 
-            channel.close();
+            drop(subscription);
+            inner.subscription.lock().unwrap().take();
             inner.task_running.store(false, Ordering::SeqCst);
             ctl_sender.send(()).await.ok();
         });
@@ -255,12 +287,14 @@ impl UtxoProcessor {
         if let Ok(sink) = Sink::try_from(&event) {
             let event = EventKind::All;
             self.inner.callbacks.lock().unwrap().entry(event).or_default().push(sink);
+            self.inner.update_event_filter();
             Ok(())
         } else if let Some(Ok(sink)) = callback.map(Sink::try_from) {
             let targets: Vec<EventKind> = get_event_targets(event)?;
             for event in targets {
                 self.inner.callbacks.lock().unwrap().entry(event).or_default().push(sink.clone());
             }
+            self.inner.update_event_filter();
             Ok(())
         } else {
             Err(Error::custom("Invalid event listener callback"))
@@ -294,6 +328,8 @@ impl UtxoProcessor {
                 callbacks.remove(&event);
             }
         }
+        drop(callbacks);
+        self.inner.update_event_filter();
         Ok(())
     }
 }
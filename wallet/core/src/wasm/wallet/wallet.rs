@@ -60,6 +60,7 @@ struct Inner {
     callbacks: Mutex<AHashMap<EventKind, Vec<Sink>>>,
     task_running: AtomicBool,
     task_ctl: DuplexChannel,
+    subscription: Mutex<Option<Arc<EventSubscription>>>,
 }
 
 impl Inner {
@@ -77,6 +78,19 @@ impl Inner {
             (None, None) => None,
         }
     }
+
+    /// The event kinds having at least one registered callback
+    fn event_filter(&self) -> EventKindSet {
+        let callbacks = self.callbacks.lock().unwrap();
+        callbacks.iter().filter(|(_, handlers)| !handlers.is_empty()).map(|(event, _)| *event).collect()
+    }
+
+    /// Narrows the notification task subscription down to the event kinds having a registered callback
+    fn update_event_filter(&self) {
+        if let Some(subscription) = self.subscription.lock().unwrap().as_ref() {
+            subscription.set_filter(self.event_filter());
+        }
+    }
 }
 
 ///
@@ -163,6 +177,7 @@ impl Wallet {
                 callbacks: Mutex::new(AHashMap::new()),
                 task_running: AtomicBool::new(false),
                 task_ctl: DuplexChannel::oneshot(),
+                subscription: Mutex::new(None),
             }),
         })
     }
@@ -197,7 +212,7 @@ impl Wallet {
     }
 
     pub async fn start(&self) -> Result<()> {
-        self.start_notification_task(self.wallet().multiplexer()).await?;
+        self.start_notification_task(self.wallet().subscriptions()).await?;
         self.wallet().start().await?;
         Ok(())
     }
@@ -227,12 +242,14 @@ impl Wallet {
         if let Ok(sink) = Sink::try_from(&event) {
             let event = EventKind::All;
             self.inner.callbacks.lock().unwrap().entry(event).or_default().push(sink);
+            self.inner.update_event_filter();
             Ok(())
         } else if let Some(Ok(sink)) = callback.map(Sink::try_from) {
             let targets: Vec<EventKind> = get_event_targets(event)?;
             for event in targets {
                 self.inner.callbacks.lock().unwrap().entry(event).or_default().push(sink.clone());
             }
+            self.inner.update_event_filter();
             Ok(())
         } else {
             Err(Error::custom("Invalid event listener callback"))
@@ -262,6 +279,8 @@ impl Wallet {
                 callbacks.remove(&event);
             }
         }
+        drop(callbacks);
+        self.inner.update_event_filter();
         Ok(())
     }
 }
@@ -271,7 +290,7 @@ impl Wallet {
         &self.inner.wallet
     }
 
-    pub async fn start_notification_task(&self, multiplexer: &Multiplexer<Box<Events>>) -> Result<()> {
+    pub async fn start_notification_task(&self, subscriptions: &EventSubscriptions) -> Result<()> {
         let inner = self.inner.clone();
 
         if inner.task_running.load(Ordering::SeqCst) {
@@ -283,7 +302,9 @@ impl Wallet {
         let ctl_receiver = inner.task_ctl.request.receiver.clone();
         let ctl_sender = inner.task_ctl.response.sender.clone();
 
-        let channel = multiplexer.channel();
+        // Only the event kinds having a registered callback are received by the task
+        let subscription = Arc::new(subscriptions.subscribe(inner.event_filter(), None, LagPolicy::DropNewest));
+        inner.subscription.lock().unwrap().replace(subscription.clone());
 
         spawn(async move {
             loop {
@@ -291,7 +312,7 @@ impl Wallet {
                     _ = ctl_receiver.recv().fuse() => {
                         break;
                     },
-                    msg = channel.receiver.recv().fuse() => {
+                    msg = subscription.receiver().recv().fuse() => {
                         if let Ok(notification) = &msg {
                             let event_type = EventKind::from(notification.as_ref());
                             let callbacks = inner.callbacks(event_type);
@@ -308,7 +329,8 @@ impl Wallet {
                 }
             }
 
-            channel.close();
+            drop(subscription);
+            inner.subscription.lock().unwrap().take();
             ctl_sender.send(()).await.ok();
         });
 
@@ -0,0 +1,723 @@
+//!
+//! Webhook notifications of the payment related wallet events.
+//!
+//! The [`WebhookDispatcher`] subscribes to the balance, maturity and transaction
+//! record events of a [`UtxoProcessor`] and POSTs each of them as a JSON payload
+//! to the configured [`WebhookEndpoint`]s whose filter includes the event. Payloads
+//! are signed with the secret of the endpoint (see [`sign`]), failed deliveries are
+//! retried with an exponential backoff and end up in a dead-letter log once the
+//! retries are exhausted. Recently dispatched payloads are retained so that a range
+//! of them can be [replayed](WebhookDispatcher::replay) by id.
+//!
+
+use crate::imports::*;
+use async_std::net::TcpStream;
+use futures::{AsyncReadExt, AsyncWriteExt};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::time::Duration;
+use workflow_core::task::sleep;
+use workflow_core::time::unixtime_as_millis_u64;
+
+/// Header holding the signature of the payload, formatted as `sha256=<hex encoded HMAC-SHA256>`
+pub const SIGNATURE_HEADER: &str = "X-Kaspa-Signature";
+/// Header holding the [`WebhookEvent`] of the payload
+pub const EVENT_HEADER: &str = "X-Kaspa-Event";
+/// Header holding the id of the payload
+pub const DELIVERY_HEADER: &str = "X-Kaspa-Delivery";
+
+/// Number of dispatched payloads retained for [replay](WebhookDispatcher::replay)
+pub const DEFAULT_HISTORY_CAPACITY: usize = 10_000;
+/// Number of dead letters retained in memory, the dead-letter log file retaining all of them
+pub const DEFAULT_DEAD_LETTER_CAPACITY: usize = 1_000;
+
+/// Events a webhook endpoint can subscribe to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WebhookEvent {
+    /// Balance update of an account ([`Events::Balance`])
+    Balance,
+    /// Confirmation of a transaction ([`Events::Maturity`])
+    Maturity,
+    /// Creation of a transaction record, either for a new incoming transaction
+    /// ([`Events::Pending`]) or for one discovered during a UTXO scan ([`Events::Discovery`])
+    TransactionRecord,
+}
+
+impl WebhookEvent {
+    pub const ALL: [WebhookEvent; 3] = [WebhookEvent::Balance, WebhookEvent::Maturity, WebhookEvent::TransactionRecord];
+
+    pub fn from_event(event: &Events) -> Option<Self> {
+        match event {
+            Events::Balance { .. } => Some(WebhookEvent::Balance),
+            Events::Maturity { .. } => Some(WebhookEvent::Maturity),
+            Events::Pending { .. } | Events::Discovery { .. } => Some(WebhookEvent::TransactionRecord),
+            _ => None,
+        }
+    }
+
+    /// The wallet event kinds mapping to this event
+    pub fn event_kinds(&self) -> EventKindSet {
+        match self {
+            WebhookEvent::Balance => EventKind::Balance.into(),
+            WebhookEvent::Maturity => EventKind::Maturity.into(),
+            WebhookEvent::TransactionRecord => EventKind::Pending.into() | EventKind::Discovery.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for WebhookEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            WebhookEvent::Balance => "balance",
+            WebhookEvent::Maturity => "maturity",
+            WebhookEvent::TransactionRecord => "transaction-record",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl FromStr for WebhookEvent {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "balance" => Ok(WebhookEvent::Balance),
+            "maturity" => Ok(WebhookEvent::Maturity),
+            "transaction-record" => Ok(WebhookEvent::TransactionRecord),
+            _ => Err(Error::custom(format!("invalid webhook event: {s}"))),
+        }
+    }
+}
+
+/// An HTTP endpoint receiving the webhook payloads of the events it subscribed to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookEndpoint {
+    pub name: String,
+    pub url: String,
+    /// Secret the payloads delivered to this endpoint are signed with
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+}
+
+impl WebhookEndpoint {
+    pub fn new(name: &str, url: &str, secret: &str, events: &[WebhookEvent]) -> Self {
+        Self { name: name.to_string(), url: url.to_string(), secret: secret.to_string(), events: events.to_vec() }
+    }
+
+    pub fn accepts(&self, event: WebhookEvent) -> bool {
+        self.events.contains(&event)
+    }
+}
+
+/// Returns the signature of `body` with `secret`, as sent in the [`SIGNATURE_HEADER`]
+pub fn sign(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    format!("sha256={}", mac.finalize().into_bytes().as_slice().to_hex())
+}
+
+/// `true` if `signature` is the signature of `body` with `secret`
+pub fn verify(secret: &[u8], body: &[u8], signature: &str) -> bool {
+    let Some(signature) = signature.strip_prefix("sha256=").and_then(|hex| Vec::<u8>::from_hex(hex).ok()) else {
+        return false;
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// Retry policy of the webhook deliveries.
+///
+/// Deliveries failing with a transport error or with a `408`, `429` or `5xx` response are retried,
+/// other responses are final.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WebhookRetryPolicy {
+    /// Maximum number of attempts of a delivery, including the first one
+    pub max_attempts: u32,
+
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+
+    /// Upper bound of the delay between two attempts
+    pub max_backoff: Duration,
+
+    /// Factor applied to the delay after each retry
+    pub multiplier: u32,
+}
+
+impl WebhookRetryPolicy {
+    pub const DEFAULT_MAX_ATTEMPTS: u32 = 6;
+    pub const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+    pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(60);
+    pub const DEFAULT_MULTIPLIER: u32 = 2;
+
+    /// The delay before the attempt following attempt number `attempt` (starting at 0)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = self.multiplier.max(1).saturating_pow(attempt);
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+
+    fn is_retryable_status(status: u16) -> bool {
+        status == 408 || status == 429 || (500..600).contains(&status)
+    }
+}
+
+impl Default for WebhookRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: Self::DEFAULT_MAX_ATTEMPTS,
+            initial_backoff: Self::DEFAULT_INITIAL_BACKOFF,
+            max_backoff: Self::DEFAULT_MAX_BACKOFF,
+            multiplier: Self::DEFAULT_MULTIPLIER,
+        }
+    }
+}
+
+/// Transport POSTing the webhook payloads, returning the HTTP status of the response
+#[async_trait]
+pub trait WebhookTransport: Send + Sync {
+    async fn post(&self, url: &str, headers: &[(&str, String)], body: &str) -> Result<u16>;
+}
+
+/// Minimal HTTP/1.1 transport, supporting `http://` urls only. Endpoints served over
+/// TLS require a custom [`WebhookTransport`] (or a local TLS terminating proxy).
+#[derive(Debug, Clone)]
+pub struct HttpTransport {
+    timeout: Duration,
+}
+
+impl HttpTransport {
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+}
+
+impl Default for HttpTransport {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TIMEOUT)
+    }
+}
+
+#[async_trait]
+impl WebhookTransport for HttpTransport {
+    async fn post(&self, url: &str, headers: &[(&str, String)], body: &str) -> Result<u16> {
+        let response = async_std::future::timeout(self.timeout, http_post(url, headers, body))
+            .await
+            .map_err(|_| Error::custom(format!("webhook request to {url} timed out")))??;
+        Ok(response.status)
+    }
+}
+
+/// Response of an [`http_post`] request
+#[derive(Debug, Clone)]
+pub(crate) struct HttpResponse {
+    pub status: u16,
+    /// Headers of the response, keyed by lowercase name
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(&name.to_lowercase()).map(String::as_str)
+    }
+}
+
+/// POSTs the JSON `body` to the `http://` `url` and reads the response, the body of the response
+/// extending up to its `Content-Length` or, if missing, up to the closing of the connection
+pub(crate) async fn http_post(url: &str, headers: &[(&str, String)], body: &str) -> Result<HttpResponse> {
+    let Some(location) = url.strip_prefix("http://") else {
+        return Err(Error::custom(format!("unsupported url {url}, only http:// urls are supported")));
+    };
+    let (authority, path) = match location.find('/') {
+        Some(index) => location.split_at(index),
+        None => (location, "/"),
+    };
+    let address = if authority.contains(':') { authority.to_string() } else { format!("{authority}:80") };
+
+    let mut request = format!(
+        "POST {path} HTTP/1.1\r\nHost: {authority}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        body.len()
+    );
+    for (name, value) in headers {
+        request.push_str(&format!("{name}: {value}\r\n"));
+    }
+    request.push_str("\r\n");
+    request.push_str(body);
+
+    let mut stream = TcpStream::connect(&address).await?;
+    stream.write_all(request.as_bytes()).await?;
+    let mut response = Vec::new();
+    let mut buffer = [0u8; 4096];
+    let mut head: Option<(usize, Option<usize>)> = None;
+    loop {
+        if head.is_none() {
+            if let Some(index) = response.windows(4).position(|window| window == b"\r\n\r\n") {
+                let content_length = String::from_utf8_lossy(&response[..index]).lines().skip(1).find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    match name.trim().eq_ignore_ascii_case("content-length") {
+                        true => value.trim().parse::<usize>().ok(),
+                        false => None,
+                    }
+                });
+                head = Some((index + 4, content_length));
+            }
+        }
+        if let Some((body_start, Some(content_length))) = head {
+            if response.len() >= body_start + content_length {
+                response.truncate(body_start + content_length);
+                break;
+            }
+        }
+        let len = stream.read(&mut buffer).await?;
+        if len == 0 {
+            break;
+        }
+        response.extend_from_slice(&buffer[..len]);
+    }
+
+    let body_start = head.map(|(body_start, _)| body_start).unwrap_or(response.len());
+    let head = String::from_utf8_lossy(&response[..body_start]).to_string();
+    let mut lines = head.lines();
+    let status_line = lines.next().unwrap_or_default();
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse::<u16>().ok())
+        .ok_or_else(|| Error::custom(format!("invalid HTTP response status line `{status_line}` from {url}")))?;
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect::<HashMap<_, _>>();
+    Ok(HttpResponse { status, headers, body: response[body_start..].to_vec() })
+}
+
+/// A delivery that failed after exhausting its retries, as appended to the dead-letter log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetter {
+    /// Id of the payload
+    pub id: u64,
+    pub endpoint: String,
+    pub url: String,
+    pub event: WebhookEvent,
+    pub attempts: u32,
+    /// Failure of the last attempt
+    pub error: String,
+    pub timestamp: u64,
+    pub body: String,
+}
+
+/// A dispatched payload
+#[derive(Debug, Clone)]
+struct WebhookRecord {
+    id: u64,
+    event: WebhookEvent,
+    body: Arc<String>,
+}
+
+struct Inner {
+    processor: UtxoProcessor,
+    transport: Arc<dyn WebhookTransport>,
+    retry_policy: WebhookRetryPolicy,
+    dead_letter_log: Option<PathBuf>,
+    endpoints: Mutex<Vec<WebhookEndpoint>>,
+    history: Mutex<VecDeque<WebhookRecord>>,
+    dead_letters: Mutex<VecDeque<DeadLetter>>,
+    next_id: AtomicU64,
+    task_ctl: DuplexChannel,
+    running: AtomicBool,
+}
+
+/// Delivers the payment related events of a [`UtxoProcessor`] to webhook endpoints.
+///
+/// Each dispatched event gets a payload id, increasing by one from the first event, which
+/// lets a receiver detect missed payloads and [replay](Self::replay) them. Deliveries run
+/// concurrently, so a payload retried by an endpoint can be received after the next ones.
+/// The payload is a JSON object holding the `id`, the `event`, a `timestamp` in milliseconds
+/// and the wallet `data` of the event, along with the transaction `recordId` for the
+/// maturity and transaction record events.
+#[derive(Clone)]
+pub struct WebhookDispatcher {
+    inner: Arc<Inner>,
+}
+
+impl WebhookDispatcher {
+    /// Creates a dispatcher delivering the payloads through `transport`, appending its dead letters
+    /// to the `dead_letter_log` file as JSON lines if provided
+    pub fn new(
+        processor: &UtxoProcessor,
+        transport: Arc<dyn WebhookTransport>,
+        retry_policy: WebhookRetryPolicy,
+        dead_letter_log: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                processor: processor.clone(),
+                transport,
+                retry_policy,
+                dead_letter_log,
+                endpoints: Mutex::new(Vec::new()),
+                history: Mutex::new(VecDeque::new()),
+                dead_letters: Mutex::new(VecDeque::new()),
+                next_id: AtomicU64::new(1),
+                task_ctl: DuplexChannel::oneshot(),
+                running: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.inner.running.load(Ordering::SeqCst)
+    }
+
+    pub fn endpoints(&self) -> Vec<WebhookEndpoint> {
+        self.inner.endpoints.lock().unwrap().clone()
+    }
+
+    /// Adds `endpoint`, replacing any endpoint of the same name
+    pub fn add_endpoint(&self, endpoint: WebhookEndpoint) {
+        let mut endpoints = self.inner.endpoints.lock().unwrap();
+        endpoints.retain(|existing| existing.name != endpoint.name);
+        endpoints.push(endpoint);
+    }
+
+    /// Removes the endpoint named `name`, returning it if found
+    pub fn remove_endpoint(&self, name: &str) -> Option<WebhookEndpoint> {
+        let mut endpoints = self.inner.endpoints.lock().unwrap();
+        let index = endpoints.iter().position(|endpoint| endpoint.name == name)?;
+        Some(endpoints.remove(index))
+    }
+
+    /// The most recent dead letters, oldest first
+    pub fn dead_letters(&self) -> Vec<DeadLetter> {
+        self.inner.dead_letters.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Dispatches `event` to the endpoints subscribed to it and waits for the deliveries to complete,
+    /// including their retries. Returns the id of the payload, or `None` if `event` is not a webhook event.
+    pub async fn dispatch(&self, event: &Events) -> Option<u64> {
+        let webhook_event = WebhookEvent::from_event(event)?;
+        let id = self.inner.next_id.fetch_add(1, Ordering::SeqCst);
+        let record_id = match event {
+            Events::Maturity { record } | Events::Pending { record } | Events::Discovery { record } => Some(*record.id()),
+            _ => None,
+        };
+        let payload = serde_json::json!({
+            "id": id,
+            "event": webhook_event,
+            "timestamp": unixtime_as_millis_u64(),
+            "recordId": record_id,
+            "data": event,
+        });
+        let record = WebhookRecord { id, event: webhook_event, body: Arc::new(payload.to_string()) };
+        {
+            let mut history = self.inner.history.lock().unwrap();
+            history.push_back(record.clone());
+            while history.len() > DEFAULT_HISTORY_CAPACITY {
+                history.pop_front();
+            }
+        }
+        self.deliver_to_all(&record).await;
+        Some(id)
+    }
+
+    /// Delivers again the retained payloads whose id is within `ids` to the endpoints currently subscribed
+    /// to them, waiting for the deliveries to complete. Returns the number of replayed payloads.
+    ///
+    /// Fails if the payloads of `ids` were not all dispatched yet or some were evicted from the history.
+    pub async fn replay(&self, ids: RangeInclusive<u64>) -> Result<usize> {
+        let records = {
+            let history = self.inner.history.lock().unwrap();
+            let first = history.front().map(|record| record.id).unwrap_or(self.inner.next_id.load(Ordering::SeqCst));
+            let last = self.inner.next_id.load(Ordering::SeqCst) - 1;
+            if *ids.start() < first || *ids.end() > last {
+                return Err(Error::custom(format!(
+                    "webhook payloads {}..={} are not available for replay, retained payloads are {first}..={last}",
+                    ids.start(),
+                    ids.end()
+                )));
+            }
+            history.iter().filter(|record| ids.contains(&record.id)).cloned().collect::<Vec<_>>()
+        };
+        for record in records.iter() {
+            self.deliver_to_all(record).await;
+        }
+        Ok(records.len())
+    }
+
+    async fn deliver_to_all(&self, record: &WebhookRecord) {
+        let endpoints = self.endpoints().into_iter().filter(|endpoint| endpoint.accepts(record.event)).collect::<Vec<_>>();
+        join_all(endpoints.iter().map(|endpoint| self.deliver(endpoint, record))).await;
+    }
+
+    /// Delivers `record` to `endpoint`, retrying according to the retry policy. Returns `true` on success,
+    /// the failed delivery being appended to the dead-letter log otherwise.
+    async fn deliver(&self, endpoint: &WebhookEndpoint, record: &WebhookRecord) -> bool {
+        let headers = [
+            (SIGNATURE_HEADER, sign(endpoint.secret.as_bytes(), record.body.as_bytes())),
+            (EVENT_HEADER, record.event.to_string()),
+            (DELIVERY_HEADER, record.id.to_string()),
+        ];
+        let policy = self.inner.retry_policy;
+        let mut attempt = 0;
+        loop {
+            let (error, retryable) = match self.inner.transport.post(&endpoint.url, &headers, &record.body).await {
+                Ok(status) if (200..300).contains(&status) => return true,
+                Ok(status) => (format!("endpoint responded with status {status}"), WebhookRetryPolicy::is_retryable_status(status)),
+                Err(err) => (err.to_string(), true),
+            };
+            if !retryable || attempt + 1 >= policy.max_attempts {
+                self.dead_letter(endpoint, record, attempt + 1, error);
+                return false;
+            }
+            sleep(policy.backoff(attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    fn dead_letter(&self, endpoint: &WebhookEndpoint, record: &WebhookRecord, attempts: u32, error: String) {
+        log_warn!(
+            "Webhook payload {} could not be delivered to {} after {} attempt(s): {}",
+            record.id,
+            endpoint.name,
+            attempts,
+            error
+        );
+        let dead_letter = DeadLetter {
+            id: record.id,
+            endpoint: endpoint.name.clone(),
+            url: endpoint.url.clone(),
+            event: record.event,
+            attempts,
+            error,
+            timestamp: unixtime_as_millis_u64(),
+            body: record.body.to_string(),
+        };
+        if let Some(path) = self.inner.dead_letter_log.as_ref() {
+            let line = serde_json::to_string(&dead_letter).expect("dead letters are serializable");
+            let written = OpenOptions::new().create(true).append(true).open(path).and_then(|mut file| writeln!(file, "{line}"));
+            if let Err(err) = written {
+                log_error!("Unable to append to the webhook dead-letter log {}: {}", path.display(), err);
+            }
+        }
+        let mut dead_letters = self.inner.dead_letters.lock().unwrap();
+        dead_letters.push_back(dead_letter);
+        while dead_letters.len() > DEFAULT_DEAD_LETTER_CAPACITY {
+            dead_letters.pop_front();
+        }
+    }
+
+    pub async fn start(&self) -> Result<()> {
+        if self.is_running() {
+            return Err(Error::custom("WebhookDispatcher::start() called while already running"));
+        }
+
+        let this = self.clone();
+        this.inner.running.store(true, Ordering::SeqCst);
+        let task_ctl_receiver = self.inner.task_ctl.request.receiver.clone();
+        let task_ctl_sender = self.inner.task_ctl.response.sender.clone();
+        let filter = WebhookEvent::ALL.iter().fold(EventKindSet::empty(), |filter, event| filter | event.event_kinds());
+        // Payment notifications must not be dropped, hence the unbounded channel
+        let subscription = self.inner.processor.subscribe(filter, None, LagPolicy::DropNewest);
+
+        spawn(async move {
+            loop {
+                select! {
+                    _ = task_ctl_receiver.recv().fuse() => {
+                        break;
+                    },
+
+                    event = subscription.recv().fuse() => {
+                        let Some(event) = event else {
+                            break;
+                        };
+                        let this = this.clone();
+                        spawn(async move {
+                            this.dispatch(&event).await;
+                        });
+                    }
+                }
+            }
+
+            this.inner.running.store(false, Ordering::SeqCst);
+            task_ctl_sender.send(()).await.unwrap();
+        });
+        Ok(())
+    }
+
+    pub async fn stop(&self) -> Result<()> {
+        if self.is_running() {
+            self.inner.task_ctl.signal(()).await.expect("WebhookDispatcher::stop() `signal` error");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utxo::context::UtxoContextId;
+    use std::io::{BufRead, BufReader, Read};
+    use std::net::TcpListener;
+
+    /// A delivery received by the [`HttpSink`]
+    struct Received {
+        headers: HashMap<String, String>,
+        body: String,
+    }
+
+    /// A local HTTP server answering the requests it receives with the statuses of `responses`,
+    /// then with `200` once exhausted
+    struct HttpSink {
+        url: String,
+        received: Arc<Mutex<Vec<Received>>>,
+    }
+
+    impl HttpSink {
+        fn start(responses: Vec<u16>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+            let received = Arc::new(Mutex::new(Vec::new()));
+            let sink_received = received.clone();
+            std::thread::spawn(move || {
+                let mut responses = VecDeque::from(responses);
+                for stream in listener.incoming() {
+                    let mut stream = stream.unwrap();
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut headers = HashMap::new();
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    assert!(line.starts_with("POST /hooks HTTP/1.1"));
+                    loop {
+                        line.clear();
+                        reader.read_line(&mut line).unwrap();
+                        let Some((name, value)) = line.trim_end().split_once(": ") else {
+                            break;
+                        };
+                        headers.insert(name.to_lowercase(), value.to_string());
+                    }
+                    let len = headers["content-length"].parse::<usize>().unwrap();
+                    let mut body = vec![0u8; len];
+                    reader.read_exact(&mut body).unwrap();
+                    sink_received.lock().unwrap().push(Received { headers, body: String::from_utf8(body).unwrap() });
+
+                    let status = responses.pop_front().unwrap_or(200);
+                    write!(stream, "HTTP/1.1 {status} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").unwrap();
+                }
+            });
+            Self { url, received }
+        }
+
+        fn received(&self) -> Vec<Received> {
+            std::mem::take(&mut *self.received.lock().unwrap())
+        }
+    }
+
+    fn dispatcher(processor: &UtxoProcessor, max_attempts: u32, dead_letter_log: Option<PathBuf>) -> WebhookDispatcher {
+        let retry_policy = WebhookRetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(40),
+            ..Default::default()
+        };
+        WebhookDispatcher::new(processor, Arc::new(HttpTransport::default()), retry_policy, dead_letter_log)
+    }
+
+    fn balance_event(mature: u64) -> Events {
+        Events::Balance { balance: Some(Balance::new(mature, 0, 0, 1, 0, 0)), id: UtxoContextId::default() }
+    }
+
+    fn assert_signed_delivery(received: &Received, secret: &str, id: u64) {
+        assert!(verify(secret.as_bytes(), received.body.as_bytes(), &received.headers[&SIGNATURE_HEADER.to_lowercase()]));
+        assert!(!verify(b"other secret", received.body.as_bytes(), &received.headers[&SIGNATURE_HEADER.to_lowercase()]));
+        assert_eq!(received.headers[&DELIVERY_HEADER.to_lowercase()], id.to_string());
+        let payload: serde_json::Value = serde_json::from_str(&received.body).unwrap();
+        assert_eq!(payload["id"], id);
+        assert_eq!(payload["event"], "balance");
+        assert_eq!(payload["data"]["type"], "balance");
+    }
+
+    #[test]
+    fn test_webhook_retry_policy_backoff() {
+        let policy = WebhookRetryPolicy::default();
+        assert_eq!(policy.backoff(0), Duration::from_secs(1));
+        assert_eq!(policy.backoff(1), Duration::from_secs(2));
+        assert_eq!(policy.backoff(5), Duration::from_secs(32));
+        assert_eq!(policy.backoff(6), policy.max_backoff);
+        assert_eq!(policy.backoff(u32::MAX), policy.max_backoff);
+        assert!(WebhookRetryPolicy::is_retryable_status(503));
+        assert!(WebhookRetryPolicy::is_retryable_status(429));
+        assert!(!WebhookRetryPolicy::is_retryable_status(400));
+    }
+
+    #[tokio::test]
+    async fn test_webhook_delivery_with_retries() {
+        let processor = UtxoProcessor::new(None, Some(NetworkId::new(NetworkType::Simnet)), None, None);
+        let dir = tempfile::tempdir().unwrap();
+        let dead_letter_log = dir.path().join("dead-letters.jsonl");
+        let dispatcher = dispatcher(&processor, 3, Some(dead_letter_log.clone()));
+
+        // The sink fails the first two attempts
+        let sink = HttpSink::start(vec![500, 503]);
+        dispatcher.add_endpoint(WebhookEndpoint::new("merchant", &sink.url, "secret", &[WebhookEvent::Balance]));
+        let maturity_sink = HttpSink::start(vec![]);
+        dispatcher.add_endpoint(WebhookEndpoint::new("maturity", &maturity_sink.url, "other", &[WebhookEvent::Maturity]));
+
+        // Events are consumed from the processor through the typed subscriptions
+        dispatcher.start().await.unwrap();
+        processor.notify(balance_event(100)).await.unwrap();
+        processor.notify(Events::DaaScoreChange { current_daa_score: 1 }).await.unwrap();
+        for _ in 0..100 {
+            if sink.received.lock().unwrap().len() == 3 {
+                break;
+            }
+            sleep(Duration::from_millis(20)).await;
+        }
+        dispatcher.stop().await.unwrap();
+
+        let received = sink.received();
+        assert_eq!(received.len(), 3);
+        received.iter().for_each(|received| assert_signed_delivery(received, "secret", 1));
+        assert!(maturity_sink.received().is_empty());
+        assert!(dispatcher.dead_letters().is_empty());
+
+        // Deliveries failing all their attempts end up in the dead-letter log
+        let sink = HttpSink::start(vec![500, 500, 500]);
+        dispatcher.add_endpoint(WebhookEndpoint::new("merchant", &sink.url, "secret", &[WebhookEvent::Balance]));
+        assert_eq!(dispatcher.dispatch(&balance_event(200)).await, Some(2));
+        assert_eq!(sink.received().len(), 3);
+        let dead_letters = dispatcher.dead_letters();
+        assert_eq!(dead_letters.len(), 1);
+        assert_eq!((dead_letters[0].id, dead_letters[0].attempts), (2, 3));
+        assert_eq!(dead_letters[0].endpoint, "merchant");
+        let logged = std::fs::read_to_string(&dead_letter_log).unwrap();
+        assert_eq!(serde_json::from_str::<DeadLetter>(logged.trim_end()).unwrap(), dead_letters[0]);
+
+        // Non retryable responses are not retried
+        let sink = HttpSink::start(vec![400]);
+        dispatcher.add_endpoint(WebhookEndpoint::new("merchant", &sink.url, "secret", &[WebhookEvent::Balance]));
+        assert_eq!(dispatcher.dispatch(&balance_event(300)).await, Some(3));
+        assert_eq!(sink.received().len(), 1);
+        assert_eq!(dispatcher.dead_letters().len(), 2);
+
+        // Missed payloads are replayed by id
+        let sink = HttpSink::start(vec![]);
+        dispatcher.add_endpoint(WebhookEndpoint::new("merchant", &sink.url, "secret", &[WebhookEvent::Balance]));
+        assert_eq!(dispatcher.replay(2..=3).await.unwrap(), 2);
+        let received = sink.received();
+        assert_eq!(received.len(), 2);
+        assert_signed_delivery(&received[0], "secret", 2);
+        assert_signed_delivery(&received[1], "secret", 3);
+        assert!(dispatcher.replay(3..=4).await.is_err());
+        assert!(dispatcher.replay(0..=1).await.is_err());
+    }
+}
@@ -543,6 +543,26 @@ mod tests {
         println!("Extended kpub: {}\n", xpub_str);
     }
 
+    #[tokio::test]
+    async fn address_from_12_and_24_word_mnemonics() {
+        let cases = [
+            (vec![0u8; 16], 12, "kaspa:qqd6e65yefepe9wk0m9vuxdufxd80sphy67gwwd0vdaumzdt4tc9s3qt0lqeh"),
+            (vec![0u8; 32], 24, "kaspa:qz20g2ugrrm629fnnvmfjpsjlx9zu2f2zj828wwpv9gvxcnajl0qs36s8hfkl"),
+        ];
+        for (entropy, word_count, expected) in cases {
+            let mnemonic = kaspa_bip32::Mnemonic::from_entropy(entropy, kaspa_bip32::Language::English).unwrap();
+            assert_eq!(mnemonic.phrase().split(' ').count(), word_count);
+            let xprv = kaspa_bip32::ExtendedPrivateKey::<kaspa_bip32::SecretKey>::new(mnemonic.to_seed("")).unwrap();
+            let xprv_str = xprv.to_string(kaspa_bip32::Prefix::KPRV).to_string();
+
+            // Both phrase lengths derive along the same m/44'/111111'/0'/0/0 path
+            let wallet = WalletDerivationManager::from_master_xprv(&xprv_str, false, 0, None).unwrap();
+            let pubkey = wallet.derive_receive_pubkey(0).unwrap();
+            let address: String = PubkeyDerivationManager::create_address(&pubkey, Prefix::Mainnet, false).unwrap().into();
+            assert_eq!(address, expected, "{word_count} word mnemonic address not matched");
+        }
+    }
+
     #[tokio::test]
     async fn address_test_by_ktrv() {
         let mnemonic = "hunt bitter praise lift buyer topic crane leopard uniform network inquiry over grain pass match crush marine strike doll relax fortune trumpet sunny silk";
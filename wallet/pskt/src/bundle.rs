@@ -0,0 +1,413 @@
+//!
+//! Bundles of PSKTs, for workflows producing several transactions at once such as chained sweeps.
+//!
+
+use crate::{CombineError, ExtractError, Finalizer, Inner, SignInputOk, Signature, Signer, PSKT};
+use kaspa_consensus_core::{
+    constants::UNACCEPTED_DAA_SCORE,
+    hashing::{
+        sighash::{calc_schnorr_signature_hash, SigHashReusedValues},
+        sighash_type::SigHashType,
+    },
+    tx::{SignableTransaction, Transaction, TransactionId, UtxoEntry},
+};
+use secp256k1::Keypair;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// An input of a bundled PSKT which spends an output of a PSKT preceding it in the bundle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Dependency {
+    /// Index of the spending PSKT in the bundle
+    pub pskt_index: usize,
+    pub input_index: usize,
+    /// Index of the spent PSKT in the bundle
+    pub spent_pskt_index: usize,
+    pub output_index: u32,
+}
+
+/// An ordered list of PSKTs, each of which may spend outputs of the PSKTs preceding it.
+///
+/// Transaction ids do not commit to signature scripts, so the outpoints spent by dependent
+/// inputs are known before signing and remain valid once the bundle is signed. Dependent inputs
+/// are nonetheless recorded, letting signers know which inputs spend outputs that are not in the
+/// UTXO set yet, and their missing UTXO entries are filled from the spent outputs.
+///
+/// Dependencies are verified again when a bundle is deserialized or combined.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", try_from = "BundleData")]
+pub struct Bundle {
+    pskts: Vec<Inner>,
+    dependencies: Vec<Dependency>,
+    #[serde(skip)]
+    ids: Vec<TransactionId>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleData {
+    pskts: Vec<Inner>,
+    dependencies: Vec<Dependency>,
+}
+
+impl TryFrom<BundleData> for Bundle {
+    type Error = BundleError;
+
+    fn try_from(data: BundleData) -> Result<Self, Self::Error> {
+        Self::rebuild(data.pskts, &data.dependencies)
+    }
+}
+
+impl Bundle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a bundle of `pskts`, ordered so each only spends outputs of the ones preceding it
+    pub fn from_pskts<R>(pskts: impl IntoIterator<Item = PSKT<R>>) -> Result<Self, BundleError> {
+        let mut bundle = Self::new();
+        pskts.into_iter().try_for_each(|pskt| bundle.push(pskt))?;
+        Ok(bundle)
+    }
+
+    /// Appends `pskt` to the bundle, recording its inputs which spend outputs of the PSKTs already bundled
+    pub fn push<R>(&mut self, pskt: PSKT<R>) -> Result<(), BundleError> {
+        let pskt_index = self.pskts.len();
+        let transaction_id = pskt.calculate_id_internal();
+        if let Some(spending) = self
+            .pskts
+            .iter()
+            .position(|inner| inner.inputs.iter().any(|input| input.previous_outpoint.transaction_id == transaction_id))
+        {
+            return Err(BundleError::WrongOrder { pskt_index: spending, spent_pskt_index: pskt_index });
+        }
+
+        let mut inner = pskt.inner_pskt;
+        let mut dependencies = vec![];
+        for (input_index, input) in inner.inputs.iter_mut().enumerate() {
+            let Some(spent_pskt_index) = self.ids.iter().position(|id| *id == input.previous_outpoint.transaction_id) else {
+                continue;
+            };
+            let dependency = Dependency { pskt_index, input_index, spent_pskt_index, output_index: input.previous_outpoint.index };
+            let output = self.pskts[spent_pskt_index].outputs.get(dependency.output_index as usize);
+            let output = output.ok_or(BundleError::MissingOutput(dependency))?;
+            match input.utxo_entry.as_ref() {
+                None => {
+                    input.utxo_entry =
+                        Some(UtxoEntry::new(output.amount, output.script_public_key.clone(), UNACCEPTED_DAA_SCORE, false))
+                }
+                Some(entry) if entry.amount != output.amount || entry.script_public_key != output.script_public_key => {
+                    return Err(BundleError::UtxoEntryMismatch(dependency))
+                }
+                Some(_) => {}
+            }
+            dependencies.push(dependency);
+        }
+
+        self.pskts.push(inner);
+        self.ids.push(transaction_id);
+        self.dependencies.extend(dependencies);
+        Ok(())
+    }
+
+    fn rebuild(pskts: Vec<Inner>, dependencies: &[Dependency]) -> Result<Self, BundleError> {
+        let bundle = Self::from_pskts(pskts.into_iter().map(|inner| PSKT::<Signer> { inner_pskt: inner, role: Default::default() }))?;
+        if bundle.dependencies != dependencies {
+            return Err(BundleError::DependenciesMismatch);
+        }
+        Ok(bundle)
+    }
+
+    pub fn len(&self) -> usize {
+        self.pskts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pskts.is_empty()
+    }
+
+    pub fn pskts(&self) -> &[Inner] {
+        &self.pskts
+    }
+
+    /// The ids of the bundled transactions, which do not change once signed
+    pub fn ids(&self) -> &[TransactionId] {
+        &self.ids
+    }
+
+    pub fn dependencies(&self) -> &[Dependency] {
+        &self.dependencies
+    }
+
+    /// Returns the dependency of input `input_index` of the PSKT at `pskt_index`, if it spends a bundled output
+    pub fn dependency(&self, pskt_index: usize, input_index: usize) -> Option<&Dependency> {
+        self.dependencies.iter().find(|dependency| dependency.pskt_index == pskt_index && dependency.input_index == input_index)
+    }
+
+    /// Combines the bundle with the bundle of another cosigner holding the same transactions
+    pub fn combine(self, other: Bundle) -> Result<Self, BundleError> {
+        if self.ids != other.ids {
+            return Err(BundleError::TransactionsMismatch);
+        }
+        let pskts = self
+            .pskts
+            .into_iter()
+            .zip(other.pskts)
+            .map(|(lhs, rhs)| {
+                let lhs = PSKT::<crate::Combiner> { inner_pskt: lhs, role: Default::default() };
+                let rhs = PSKT::<crate::Combiner> { inner_pskt: rhs, role: Default::default() };
+                Ok((lhs + rhs)?.inner_pskt)
+            })
+            .collect::<Result<Vec<_>, BundleError>>()?;
+        // Combining may alter the transactions, in which case their dependencies break
+        Self::rebuild(pskts, &self.dependencies)
+    }
+
+    /// Passes the signatures of all bundled PSKTs, see [`PSKT::pass_signature_sync`].
+    /// The signing function receives the index of the PSKT it signs.
+    pub fn pass_signature_sync<SignFn, E>(self, mut sign_fn: SignFn) -> Result<Self, BundleError>
+    where
+        E: Display,
+        SignFn: FnMut(usize, SignableTransaction, Vec<SigHashType>) -> Result<Vec<SignInputOk>, E>,
+    {
+        (0..self.len())
+            .try_fold(self, |bundle, index| bundle.pass_signature_sync_at(index, |tx, sighashes| sign_fn(index, tx, sighashes)))
+    }
+
+    /// Passes the signatures of the PSKT at `index`, see [`PSKT::pass_signature_sync`]
+    pub fn pass_signature_sync_at<SignFn, E>(mut self, index: usize, sign_fn: SignFn) -> Result<Self, BundleError>
+    where
+        E: Display,
+        SignFn: FnOnce(SignableTransaction, Vec<SigHashType>) -> Result<Vec<SignInputOk>, E>,
+    {
+        let inner = self.pskts.get_mut(index).ok_or(BundleError::OutOfBounds(index))?;
+        if let Some(input_index) = inner.inputs.iter().position(|input| input.utxo_entry.is_none()) {
+            return Err(BundleError::MissingUtxoEntry { pskt_index: index, input_index });
+        }
+        let signer = PSKT::<Signer> { inner_pskt: std::mem::take(inner), role: Default::default() };
+        *inner = signer.pass_signature_sync(sign_fn).map_err(|err| BundleError::Sign(index, err.to_string()))?.inner_pskt;
+        Ok(self)
+    }
+
+    /// Signs all inputs of all bundled PSKTs with `keypair` using Schnorr signatures
+    pub fn sign_schnorr(self, keypair: &Keypair) -> Result<Self, BundleError> {
+        self.pass_signature_sync(|_, tx, sighashes| sign_schnorr(keypair, tx, sighashes))
+    }
+
+    /// Signs all inputs of the PSKT at `index` with `keypair` using Schnorr signatures
+    pub fn sign_schnorr_at(self, index: usize, keypair: &Keypair) -> Result<Self, BundleError> {
+        self.pass_signature_sync_at(index, |tx, sighashes| sign_schnorr(keypair, tx, sighashes))
+    }
+
+    /// Finalizes all bundled PSKTs, see [`PSKT::finalize_sync`].
+    /// The finalizing function receives the index of the PSKT it finalizes.
+    pub fn finalize_sync<E: Display>(
+        mut self,
+        mut final_sig_fn: impl FnMut(usize, &Inner) -> Result<Vec<Vec<u8>>, E>,
+    ) -> Result<Self, BundleError> {
+        for (index, inner) in self.pskts.iter_mut().enumerate() {
+            let finalizer = PSKT::<Finalizer> { inner_pskt: std::mem::take(inner), role: Default::default() };
+            let finalized = finalizer.finalize_sync(|inner| final_sig_fn(index, inner));
+            *inner = finalized.map_err(|err| BundleError::Finalize(index, err.to_string()))?.inner_pskt;
+        }
+        Ok(self)
+    }
+
+    /// Extracts the transactions of a finalized bundle in dependency order, ready to be broadcast in sequence.
+    /// The signature scripts of all inputs are verified and the transaction masses are left unset.
+    pub fn extract_transactions(self) -> Result<Vec<(Transaction, Vec<Option<UtxoEntry>>)>, BundleError> {
+        self.pskts
+            .into_iter()
+            .enumerate()
+            .map(|(index, inner)| {
+                let finalizer = PSKT::<Finalizer> { inner_pskt: inner, role: Default::default() };
+                let extractor = finalizer.extractor().map_err(|err| BundleError::Extract(index, err.into()))?;
+                Ok(extractor.extract_tx().map_err(|err| BundleError::Extract(index, err))?(0))
+            })
+            .collect()
+    }
+}
+
+fn sign_schnorr(keypair: &Keypair, tx: SignableTransaction, sighashes: Vec<SigHashType>) -> Result<Vec<SignInputOk>, BundleError> {
+    let mut reused_values = SigHashReusedValues::new();
+    let tx = tx.as_verifiable();
+    sighashes
+        .into_iter()
+        .enumerate()
+        .map(|(input_index, sighash_type)| {
+            let hash = calc_schnorr_signature_hash(&tx, input_index, sighash_type, &mut reused_values);
+            let msg = secp256k1::Message::from_digest_slice(hash.as_bytes().as_slice()).map_err(BundleError::Secp256k1)?;
+            Ok(SignInputOk {
+                signature: Signature::Schnorr(keypair.sign_schnorr(msg)),
+                pub_key: keypair.public_key(),
+                key_source: None,
+            })
+        })
+        .collect()
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum BundleError {
+    #[error("PSKT {0} is out of the bundle bounds")]
+    OutOfBounds(usize),
+    #[error("PSKT {pskt_index} spends an output of PSKT {spent_pskt_index}, which must thus precede it in the bundle")]
+    WrongOrder { pskt_index: usize, spent_pskt_index: usize },
+    #[error("Input {} of PSKT {} spends output {} of PSKT {}, which does not exist", .0.input_index, .0.pskt_index, .0.output_index, .0.spent_pskt_index)]
+    MissingOutput(Dependency),
+    #[error("Input {} of PSKT {} has a UTXO entry not matching the spent output {} of PSKT {}", .0.input_index, .0.pskt_index, .0.output_index, .0.spent_pskt_index)]
+    UtxoEntryMismatch(Dependency),
+    #[error("Input {input_index} of PSKT {pskt_index} is missing its UTXO entry")]
+    MissingUtxoEntry { pskt_index: usize, input_index: usize },
+    #[error("The bundle dependencies do not match the bundled transactions")]
+    DependenciesMismatch,
+    #[error("The combined bundles do not hold the same transactions")]
+    TransactionsMismatch,
+    #[error(transparent)]
+    Combine(#[from] CombineError),
+    #[error(transparent)]
+    Secp256k1(secp256k1::Error),
+    #[error("Signing PSKT {0} failed: {1}")]
+    Sign(usize, String),
+    #[error("Finalizing PSKT {0} failed: {1}")]
+    Finalize(usize, String),
+    #[error("Extracting the transaction of PSKT {0} failed: {1}")]
+    Extract(usize, ExtractError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InputBuilder, OutputBuilder};
+    use kaspa_consensus_core::tx::{ScriptPublicKey, TransactionOutpoint};
+    use kaspa_txscript::{opcodes::codes::OpCheckSig, opcodes::codes::OpData65, script_builder::ScriptBuilder};
+    use std::iter;
+
+    const FEE: u64 = 10_000;
+
+    fn keypair(seed: u8) -> Keypair {
+        Keypair::from_seckey_slice(secp256k1::SECP256K1, &[seed; 32]).unwrap()
+    }
+
+    fn p2pk(keypair: &Keypair) -> ScriptPublicKey {
+        let script =
+            ScriptBuilder::new().add_data(&keypair.x_only_public_key().0.serialize()).unwrap().add_op(OpCheckSig).unwrap().drain();
+        ScriptPublicKey::new(0, script.into())
+    }
+
+    fn pskt(inputs: Vec<(TransactionOutpoint, Option<UtxoEntry>)>, amount: u64, script_public_key: ScriptPublicKey) -> PSKT<Signer> {
+        let pskt = inputs.into_iter().fold(PSKT::<crate::Creator>::default().constructor(), |pskt, (outpoint, entry)| {
+            let mut input = InputBuilder::default();
+            input.previous_outpoint(outpoint).sig_op_count(1);
+            if let Some(entry) = entry {
+                input.utxo_entry(entry);
+            }
+            pskt.input(input.build().unwrap())
+        });
+        pskt.output(OutputBuilder::default().amount(amount).script_public_key(script_public_key).build().unwrap()).signer()
+    }
+
+    /// A sweep of two UTXOs owned by two cosigners into a chain of three transactions
+    fn sweep_bundle(alice: &Keypair, bob: &Keypair, destination: ScriptPublicKey) -> Bundle {
+        let utxo = |seed: u8, keypair: &Keypair| {
+            let outpoint = TransactionOutpoint::new(TransactionId::from_bytes([seed; 32]), 0);
+            (outpoint, Some(UtxoEntry::new(1_000_000, p2pk(keypair), 100, false)))
+        };
+        let first = pskt(vec![utxo(1, alice), utxo(2, bob)], 2_000_000 - FEE, p2pk(alice));
+        let first_id = first.calculate_id();
+        let second = pskt(vec![(TransactionOutpoint::new(first_id, 0), None)], 2_000_000 - 2 * FEE, p2pk(alice));
+        let second_id = second.calculate_id();
+        let third = pskt(vec![(TransactionOutpoint::new(second_id, 0), None)], 2_000_000 - 3 * FEE, destination);
+        Bundle::from_pskts([first, second, third]).unwrap()
+    }
+
+    /// Builds the signature scripts of P2PK inputs from the partial signature of the key of the spent output
+    fn finalize_p2pk(_: usize, inner: &Inner) -> Result<Vec<Vec<u8>>, String> {
+        inner
+            .inputs
+            .iter()
+            .map(|input| {
+                let script = input.utxo_entry.as_ref().ok_or("missing UTXO entry")?.script_public_key.script();
+                let (_, signature) = input
+                    .partial_sigs
+                    .iter()
+                    .find(|(pub_key, _)| pub_key.x_only_public_key().0.serialize() == script[1..33])
+                    .ok_or("missing signature")?;
+                Ok(iter::once(OpData65).chain(signature.into_bytes()).chain([input.sighash_type.to_u8()]).collect())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_bundle_dependencies() {
+        let (alice, bob) = (keypair(1), keypair(2));
+        let bundle = sweep_bundle(&alice, &bob, p2pk(&bob));
+        assert_eq!(
+            bundle.dependencies(),
+            &[
+                Dependency { pskt_index: 1, input_index: 0, spent_pskt_index: 0, output_index: 0 },
+                Dependency { pskt_index: 2, input_index: 0, spent_pskt_index: 1, output_index: 0 },
+            ]
+        );
+        assert!(bundle.dependency(0, 1).is_none());
+        // The UTXO entries of dependent inputs are filled from the spent outputs
+        let entry = bundle.pskts()[2].inputs[0].utxo_entry.clone().unwrap();
+        assert_eq!((entry.amount, entry.block_daa_score), (2_000_000 - 2 * FEE, UNACCEPTED_DAA_SCORE));
+
+        // A PSKT must be bundled after the ones it spends
+        let pskts = bundle.pskts().iter().map(|inner| PSKT::<Signer> { inner_pskt: inner.clone(), role: Default::default() });
+        let mut reversed = pskts.collect::<Vec<_>>();
+        reversed.reverse();
+        assert_eq!(Bundle::from_pskts(reversed).unwrap_err(), BundleError::WrongOrder { pskt_index: 0, spent_pskt_index: 1 });
+
+        // Tampered dependencies are detected on deserialization
+        let mut json: serde_json::Value = serde_json::to_value(&bundle).unwrap();
+        json["dependencies"].as_array_mut().unwrap().pop();
+        assert!(serde_json::from_value::<Bundle>(json).is_err());
+        let deserialized: Bundle = serde_json::from_str(&serde_json::to_string(&bundle).unwrap()).unwrap();
+        assert_eq!((deserialized.ids(), deserialized.dependencies()), (bundle.ids(), bundle.dependencies()));
+    }
+
+    #[test]
+    fn test_bundle_offline_signing() {
+        let (alice, bob) = (keypair(1), keypair(2));
+        let destination = p2pk(&keypair(3));
+        let bundle = sweep_bundle(&alice, &bob, destination.clone());
+        let serialized = serde_json::to_string(&bundle).unwrap();
+
+        // Each cosigner signs its own copy, Bob only owning an input of the first transaction
+        let signed_by_alice = serde_json::from_str::<Bundle>(&serialized).unwrap().sign_schnorr(&alice).unwrap();
+        let signed_by_bob = serde_json::from_str::<Bundle>(&serialized).unwrap().sign_schnorr_at(0, &bob).unwrap();
+        assert!(matches!(bundle.clone().sign_schnorr_at(3, &bob), Err(BundleError::OutOfBounds(3))));
+
+        // The bundle of Alice alone can not be finalized
+        assert!(matches!(signed_by_alice.clone().finalize_sync(finalize_p2pk), Err(BundleError::Finalize(0, _))));
+
+        let signed = serde_json::to_string(&signed_by_bob).unwrap();
+        let combined = signed_by_alice.combine(serde_json::from_str(&signed).unwrap()).unwrap();
+        assert_eq!(combined.ids(), bundle.ids(), "signing does not alter the transaction ids");
+        let transactions = combined.finalize_sync(finalize_p2pk).unwrap().extract_transactions().unwrap();
+
+        assert_eq!(transactions.len(), 3);
+        for (index, (tx, _)) in transactions.iter().enumerate() {
+            assert_eq!(tx.id(), bundle.ids()[index]);
+            assert!(tx.inputs.iter().all(|input| !input.signature_script.is_empty()));
+        }
+        assert_eq!(transactions[1].0.inputs[0].previous_outpoint, TransactionOutpoint::new(transactions[0].0.id(), 0));
+        assert_eq!(transactions[2].0.inputs[0].previous_outpoint, TransactionOutpoint::new(transactions[1].0.id(), 0));
+        assert_eq!(transactions[2].0.outputs[0].script_public_key, destination);
+    }
+
+    #[test]
+    fn test_bundle_combine_mismatch() {
+        let (alice, bob) = (keypair(1), keypair(2));
+        let bundle = sweep_bundle(&alice, &bob, p2pk(&bob));
+        let other = sweep_bundle(&alice, &bob, p2pk(&alice));
+        assert_eq!(bundle.clone().combine(other).unwrap_err(), BundleError::TransactionsMismatch);
+
+        let mut missing_entry = PSKT::<crate::Creator>::default().constructor();
+        missing_entry = missing_entry.input(InputBuilder::default().previous_outpoint(Default::default()).build().unwrap());
+        let bundle = Bundle::from_pskts([missing_entry.signer()]).unwrap();
+        assert_eq!(bundle.sign_schnorr(&alice).unwrap_err(), BundleError::MissingUtxoEntry { pskt_index: 0, input_index: 0 });
+    }
+}
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use std::{collections::BTreeMap, fmt::Display, fmt::Formatter, future::Future, marker::PhantomData, ops::Deref};
 
+mod bundle;
 mod error;
 mod global;
 mod input;
@@ -12,6 +13,7 @@ mod output;
 mod role;
 mod utils;
 
+pub use bundle::{Bundle, BundleError, Dependency};
 pub use error::Error;
 pub use global::{Global, GlobalBuilder};
 pub use input::{Input, InputBuilder};
@@ -30,4 +30,21 @@ kaspa.initConsolePanicHook();
     const seed3 = mnemonic1.toSeed();
     console.log("seed3 (no recovery password):", seed3);
 
+    // create a 12-word mnemonic
+    const mnemonic3 = Mnemonic.random(12);
+    console.log("mnemonic3 (12 words):", mnemonic3.phrase);
+
+    // create a mnemonic from externally sourced entropy (16 or 32 bytes)
+    const mnemonic4 = Mnemonic.fromEntropy(mnemonic1.entropy);
+    if (mnemonic4.phrase !== mnemonic1.phrase) {
+        throw Error("mnemonic entropy import failure");
+    }
+
+    // invalid phrases report the index of the failing word
+    try {
+        new Mnemonic(mnemonic3.phrase.split(" ").reverse().join(" "));
+    } catch (err) {
+        console.log("invalid mnemonic:", err);
+    }
+
 })();